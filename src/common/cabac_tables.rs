@@ -11,7 +11,7 @@ pub const CONTEXT_MODEL_COUNT: usize = 1053;
 /// ctxIdx can be from 0 to 1024. There are (m,n) values for
 /// each ctxIdx. It is also parameterized by cabac_init_idc and
 /// the slice_type (either I/SI or B/P/SP).
-pub const CABAC_INIT_CONSTANTS: [[[i32; 2]; 4]; CONTEXT_MODEL_COUNT] = [
+pub static CABAC_INIT_CONSTANTS: [[[i32; 2]; 4]; CONTEXT_MODEL_COUNT] = [
     // Table 9-12 -- 0-10
     [[20, -15], [20, -15], [20, -15], [20, -15]],
     [[2, 54], [2, 54], [2, 54], [2, 54]],
@@ -1069,11 +1069,11 @@ pub const CABAC_INIT_CONSTANTS: [[[i32; 2]; 4]; CONTEXT_MODEL_COUNT] = [
     [[-14, 138], [0, 75], [0, 75], [0, 75]],
     [[-22, 140], [2, 65], [2, 65], [2, 65]],
     [[-11, 99], [2, 59], [2, 59], [2, 59]],
-    // Table G-13 -- SVC 1027, 1028, 1029, 1030
-    [[-6, 67], [-6, 67], [-6, 67], [CTX_NA, CTX_NA]],
-    [[-6, 67], [-6, 67], [-6, 67], [CTX_NA, CTX_NA]],
-    [[-23, 104], [-23, 104], [-23, 104], [CTX_NA, CTX_NA]],
-    [[-35, 106], [-35, 106], [-35, 106], [CTX_NA, CTX_NA]],
+    // Table G-13 -- SVC 1027, 1028, 1029, 1030 (only used in EP and EB slices)
+    [[CTX_NA, CTX_NA], [-6, 67], [-6, 67], [-6, 67]],
+    [[CTX_NA, CTX_NA], [-6, 67], [-6, 67], [-6, 67]],
+    [[CTX_NA, CTX_NA], [-23, 104], [-23, 104], [-23, 104]],
+    [[CTX_NA, CTX_NA], [-35, 106], [-35, 106], [-35, 106]],
     // Table J-6 -- 3D-AVC 1031-1039
     [[23, 33], [22, 25], [29, 16], [CTX_NA, CTX_NA]],
     [[23, 2], [34, 0], [25, 0], [CTX_NA, CTX_NA]],
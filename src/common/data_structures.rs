@@ -4,6 +4,7 @@ use crate::common::helper::decoder_formatted_print;
 use crate::common::helper::encoder_formatted_print;
 use crate::common::helper::formatted_print;
 use crate::common::helper::inverse_raster_scan;
+use crate::decoder::error::DecodeError;
 use log::debug;
 use serde::{Deserialize, Serialize};
 use std::cmp;
@@ -21,6 +22,16 @@ pub struct H264DecodedStream {
     pub slices: Vec<Slice>,
     pub seis: Vec<SEINalu>,
     pub auds: Vec<AccessUnitDelim>,
+    #[serde(default)]
+    pub filler_data: Vec<FillerData>,
+    #[serde(default)]
+    pub dpses: Vec<DepthParameterSet>,
+    // indices of NALUs that failed to decode and are re-encoded from their raw bytes
+    #[serde(default)]
+    pub undecoded_nalus: Vec<usize>,
+    // one entry per slice data partition NALU (types 2, 3, and 4), in bitstream order
+    #[serde(default)]
+    pub slice_partitions: Vec<SliceDataPartition>,
 }
 
 impl H264DecodedStream {
@@ -36,6 +47,10 @@ impl H264DecodedStream {
             slices: Vec::new(),
             seis: Vec::new(),
             auds: Vec::new(),
+            filler_data: Vec::new(),
+            dpses: Vec::new(),
+            undecoded_nalus: Vec::new(),
+            slice_partitions: Vec::new(),
         }
     }
 
@@ -51,6 +66,10 @@ impl H264DecodedStream {
             slices: self.slices.clone(),
             seis: self.seis.clone(),
             auds: self.auds.clone(),
+            filler_data: self.filler_data.clone(),
+            dpses: self.dpses.clone(),
+            undecoded_nalus: self.undecoded_nalus.clone(),
+            slice_partitions: self.slice_partitions.clone(),
         }
     }
 }
@@ -187,6 +206,16 @@ impl NALUheader {
             mvc_extension: NALUHeaderMVCExtension::new(),
         }
     }
+
+    /// Equation 7-1; for SVC NALUs idr_flag takes this role (G.7.4.1.1),
+    /// and for 3D-AVC NALUs non_idr_flag does (J.7.4.1.1)
+    pub fn idr_pic_flag(&self) -> bool {
+        self.nal_unit_type == 5
+            || (self.nal_unit_type == 20 && self.svc_extension_flag && self.svc_extension.idr_flag)
+            || (self.nal_unit_type == 21
+                && self.avc_3d_extension_flag
+                && !self.avc_3d_extension.non_idr_flag)
+    }
 }
 
 impl Default for NALUheader {
@@ -303,7 +332,7 @@ pub struct VideoParameters {
     pub mb_width_c: u32,    // 6-1
     pub mb_height_c: u32,   // 6-2
     pub idr_pic_flag: bool, // 7-1 - used to determine whether the current slice is of NALU
-    // pub depth_flag: bool,      // 7-2 - whether the 3d extension is enabled
+    pub depth_flag: bool,   // 7-2 - whether the current slice belongs to a depth view component
     pub chroma_array_type: u8, // defined in separate_colour_plane_flag section on page 74 (pg 96 of PDF)
     pub bit_depth_y: u8,       // 7-3 - range of [8, 14]
     pub qp_bd_offset_y: i32,   // 7-4 - range of [0, 36] multiples of 6
@@ -405,10 +434,11 @@ impl VideoParameters {
         // section 6.4 is used for neighbor calculation
 
         // equation 7-1
-        let idr_pic_flag: bool = nh.nal_unit_type == 5;
+        let idr_pic_flag: bool = nh.idr_pic_flag();
 
-        // TODO: equation 7-2 which is the 3d extension
-        // depth_flag = match (nh.nal_unit_type != 21) {false => match nh.avc_3d_extension_flag { true => nh.depth-flag, _ => true}, _ => false};
+        // equation 7-2
+        let depth_flag: bool = nh.nal_unit_type == 21
+            && (!nh.avc_3d_extension_flag || nh.avc_3d_extension.depth_flag);
 
         // page 74/ separate_colour_plane_flag section
         let chroma_array_type: u8 = if !s.separate_colour_plane_flag {
@@ -509,6 +539,7 @@ impl VideoParameters {
             mb_width_c: mb_width_c,
             mb_height_c: mb_height_c,
             idr_pic_flag: idr_pic_flag,
+            depth_flag,
             chroma_array_type: chroma_array_type,
             bit_depth_y: bit_depth_y,
             qp_bd_offset_y: qp_bd_offset_y,
@@ -524,6 +555,40 @@ impl VideoParameters {
             entropy_coding_mode_flag: entropy_coding_mode_flag,
         }
     }
+
+    /// Auxiliary coded pictures (NALU type 19) only contain luma samples, which have the
+    /// bit depth of the SPS extension linked to the active SPS (7.4.2.1.2)
+    pub fn set_auxiliary_picture(
+        &mut self,
+        sps_extensions: &[SPSExtension],
+        seq_parameter_set_id: u32,
+    ) -> Result<(), DecodeError> {
+        let ext = match sps_extensions
+            .iter()
+            .rev()
+            .find(|x| x.seq_parameter_set_id == seq_parameter_set_id)
+        {
+            Some(x) => x,
+            None => {
+                return Err(DecodeError::new(format!(
+                    "set_auxiliary_picture - SPS extension with id {} not found",
+                    seq_parameter_set_id
+                )))
+            }
+        };
+        if ext.aux_format_idc == 0 {
+            println!("\t [WARNING] Auxiliary coded picture with aux_format_idc equal to 0");
+        }
+
+        self.sub_width_c = 0;
+        self.sub_height_c = 0;
+        self.mb_width_c = 0;
+        self.mb_height_c = 0;
+        self.chroma_array_type = 0;
+        self.bit_depth_y = 8 + (ext.bit_depth_aux_minus8 as u8);
+        self.qp_bd_offset_y = 6 * (ext.bit_depth_aux_minus8 as i32);
+        Ok(())
+    }
 }
 
 /// Macroblock Types
@@ -645,7 +710,7 @@ impl Default for CoeffToken {
 }
 
 /// CAVLC residual mode
-#[derive(PartialEq)]
+#[derive(PartialEq, Clone, Copy)]
 pub enum ResidualMode {
     ChromaDCLevel,
     Intra16x16DCLevel,
@@ -786,6 +851,16 @@ pub struct MacroBlock {
     // sub_mb_pred
     pub sub_mb_type: [SubMbType; 4],
 
+    // macroblock_layer_in_scalable_extension - Annex G
+    #[serde(default)]
+    pub base_mode_flag: bool,
+    #[serde(default)]
+    pub motion_prediction_flag_l0: [bool; 4],
+    #[serde(default)]
+    pub motion_prediction_flag_l1: [bool; 4],
+    #[serde(default)]
+    pub residual_prediction_flag: bool,
+
     // residual_block_cabac or residual_block_cavlc
     pub intra_16x16_dc_level_transform_blocks: TransformBlock,
     pub intra_16x16_ac_level_transform_blocks: Vec<TransformBlock>,
@@ -861,6 +936,11 @@ impl MacroBlock {
 
             sub_mb_type: [SubMbType::NA; 4],
 
+            base_mode_flag: false,
+            motion_prediction_flag_l0: [false; 4],
+            motion_prediction_flag_l1: [false; 4],
+            residual_prediction_flag: false,
+
             intra_16x16_dc_level_transform_blocks: TransformBlock::new(),
             cb_intra_16x16_dc_level_transform_blocks: TransformBlock::new(),
             cr_intra_16x16_dc_level_transform_blocks: TransformBlock::new(),
@@ -1372,6 +1452,30 @@ impl MacroBlock {
 
         true
     }
+
+    /// Used in CAVLC decoding - returns the 4x4 transform block at blk_idx of the Luma (0), Cb (1),
+    /// or Cr (2) colour component. Cb and Cr are only coded like Luma when ChromaArrayType is 3
+    pub fn get_4x4_transform_block(&self, colour_component: u8, blk_idx: usize) -> TransformBlock {
+        // the 8x8 block containing blk_idx must have residue, see CodedBlockPatternLuma
+        if (self.coded_block_pattern_luma >> (blk_idx >> 2)) & 1 == 0 {
+            return TransformBlock::new();
+        }
+
+        let intra_16x16 = self.mb_part_pred_mode(0) == MbPartPredMode::Intra16x16;
+        let blocks = match (colour_component, intra_16x16) {
+            (1, true) => &self.cb_intra_16x16_ac_level_transform_blocks,
+            (1, false) => &self.cb_level_4x4_transform_blocks,
+            (2, true) => &self.cr_intra_16x16_ac_level_transform_blocks,
+            (2, false) => &self.cr_level_4x4_transform_blocks,
+            (_, true) => &self.intra_16x16_ac_level_transform_blocks,
+            (_, false) => &self.luma_level_4x4_transform_blocks,
+        };
+
+        match blocks.get(blk_idx) {
+            Some(b) => b.clone(),
+            None => TransformBlock::new(),
+        }
+    }
 }
 
 impl Default for MacroBlock {
@@ -1380,6 +1484,155 @@ impl Default for MacroBlock {
     }
 }
 
+/// Slice header syntax elements that only appear in slice_header_in_scalable_extension() -- G.7.3.3.4
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SliceHeaderSVCExtension {
+    pub base_pred_weight_table_flag: bool,
+    pub store_ref_base_pic_flag: bool,
+    // dec_ref_base_pic_marking() - G.7.3.3.5
+    pub adaptive_ref_base_pic_marking_mode_flag: bool,
+    pub memory_management_base_control_operation: Vec<u32>, // array of ue(v)
+    pub difference_of_base_pic_nums_minus1: Vec<u32>,       // array of ue(v)
+    pub long_term_base_pic_num: Vec<u32>,                   // array of ue(v)
+    //
+    pub ref_layer_dq_id: u32,                           // ue(v)
+    pub disable_inter_layer_deblocking_filter_idc: u32, // ue(v)
+    pub inter_layer_slice_alpha_c0_offset_div2: i32,    // se(v)
+    pub inter_layer_slice_beta_offset_div2: i32,        // se(v)
+    pub constrained_intra_resampling_flag: bool,
+    pub ref_layer_chroma_phase_x_plus1_flag: bool,
+    pub ref_layer_chroma_phase_y_plus1: u8,  // u(2)
+    pub scaled_ref_layer_left_offset: i32,   // se(v)
+    pub scaled_ref_layer_top_offset: i32,    // se(v)
+    pub scaled_ref_layer_right_offset: i32,  // se(v)
+    pub scaled_ref_layer_bottom_offset: i32, // se(v)
+    pub slice_skip_flag: bool,
+    pub num_mbs_in_slice_minus1: u32, // ue(v)
+    pub adaptive_base_mode_flag: bool,
+    pub default_base_mode_flag: bool,
+    pub adaptive_motion_prediction_flag: bool,
+    pub default_motion_prediction_flag: bool,
+    pub adaptive_residual_prediction_flag: bool,
+    pub default_residual_prediction_flag: bool,
+    pub tcoeff_level_prediction_flag: bool,
+    pub scan_idx_start: u8, // u(4)
+    pub scan_idx_end: u8,   // u(4)
+
+    // The following values are calculated from the stream information
+    pub no_inter_layer_pred_flag: bool, // copied from the NALU header SVC extension
+    pub quality_id: u8,                 // copied from the NALU header SVC extension
+    pub scaled_ref_layer_offsets: [i32; 4], // ScaledRefLayer{Left,Top,Right,Bottom}Offset in luma samples, G.7.4.3.4
+}
+
+impl SliceHeaderSVCExtension {
+    pub fn new() -> SliceHeaderSVCExtension {
+        SliceHeaderSVCExtension {
+            base_pred_weight_table_flag: false,
+            store_ref_base_pic_flag: false,
+            adaptive_ref_base_pic_marking_mode_flag: false,
+            memory_management_base_control_operation: Vec::new(),
+            difference_of_base_pic_nums_minus1: Vec::new(),
+            long_term_base_pic_num: Vec::new(),
+            ref_layer_dq_id: 0,
+            disable_inter_layer_deblocking_filter_idc: 0,
+            inter_layer_slice_alpha_c0_offset_div2: 0,
+            inter_layer_slice_beta_offset_div2: 0,
+            constrained_intra_resampling_flag: false,
+            ref_layer_chroma_phase_x_plus1_flag: false,
+            ref_layer_chroma_phase_y_plus1: 0,
+            scaled_ref_layer_left_offset: 0,
+            scaled_ref_layer_top_offset: 0,
+            scaled_ref_layer_right_offset: 0,
+            scaled_ref_layer_bottom_offset: 0,
+            slice_skip_flag: false,
+            num_mbs_in_slice_minus1: 0,
+            adaptive_base_mode_flag: false,
+            default_base_mode_flag: false,
+            adaptive_motion_prediction_flag: false,
+            default_motion_prediction_flag: false,
+            adaptive_residual_prediction_flag: false,
+            default_residual_prediction_flag: false,
+            tcoeff_level_prediction_flag: false,
+            scan_idx_start: 0,
+            scan_idx_end: 15,
+            no_inter_layer_pred_flag: false,
+            quality_id: 0,
+            scaled_ref_layer_offsets: [0; 4],
+        }
+    }
+
+    /// Derives ScaledRefLayer{Left,Top,Right,Bottom}Offset from the slice or sequence values, per G.7.4.3.4
+    pub fn set_scaled_ref_layer_offsets(&mut self, s: &SubsetSPS) {
+        let (left, top, right, bottom) = match s.sps_svc.extended_spatial_scalability_idc {
+            0 => (0, 0, 0, 0),
+            1 => (
+                s.sps_svc.seq_scaled_ref_layer_left_offset,
+                s.sps_svc.seq_scaled_ref_layer_top_offset,
+                s.sps_svc.seq_scaled_ref_layer_right_offset,
+                s.sps_svc.seq_scaled_ref_layer_bottom_offset,
+            ),
+            _ => (
+                self.scaled_ref_layer_left_offset,
+                self.scaled_ref_layer_top_offset,
+                self.scaled_ref_layer_right_offset,
+                self.scaled_ref_layer_bottom_offset,
+            ),
+        };
+        let frame_mbs_only_int = match s.sps.frame_mbs_only_flag {
+            true => 1,
+            false => 0,
+        };
+        self.scaled_ref_layer_offsets = [
+            2 * left,
+            2 * (2 - frame_mbs_only_int) * top,
+            2 * right,
+            2 * (2 - frame_mbs_only_int) * bottom,
+        ];
+    }
+}
+
+impl Default for SliceHeaderSVCExtension {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Slice header syntax elements that only appear in slice_header_in_3davc_extension() -- J.7.3.3.4
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SliceHeader3DAVCExtension {
+    // only present when slice_header_prediction_flag is set in the 3D-AVC SPS extension
+    pub pre_slice_header_src: u8,        // u(2)
+    pub pre_ref_lists_src: u8,           // u(2)
+    pub pre_pred_weight_table_src: u8,   // u(2)
+    pub pre_dec_ref_pic_marking_src: u8, // u(2)
+    //
+    pub depth_weighted_pred_flag: bool,
+    pub dmvp_flag: bool,
+    pub slice_vsp_flag: bool,
+    pub dps_id: u32, // ue(v)
+}
+
+impl SliceHeader3DAVCExtension {
+    pub fn new() -> SliceHeader3DAVCExtension {
+        SliceHeader3DAVCExtension {
+            pre_slice_header_src: 0,
+            pre_ref_lists_src: 0,
+            pre_pred_weight_table_src: 0,
+            pre_dec_ref_pic_marking_src: 0,
+            depth_weighted_pred_flag: false,
+            dmvp_flag: false,
+            slice_vsp_flag: false,
+            dps_id: 0,
+        }
+    }
+}
+
+impl Default for SliceHeader3DAVCExtension {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Slice Header syntax elements
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SliceHeader {
@@ -1463,6 +1716,18 @@ pub struct SliceHeader {
     // Annex H addendum
     pub abs_diff_view_idx_minus1_l0: Vec<u32>,
     pub abs_diff_view_idx_minus1_l1: Vec<u32>,
+
+    // Annex G addendum -- set when decoded from slice_header_in_scalable_extension()
+    #[serde(default)]
+    pub svc_extension_flag: bool,
+    #[serde(default)]
+    pub svc_extension: SliceHeaderSVCExtension,
+
+    // Annex J addendum -- set when decoded from slice_header_in_3davc_extension()
+    #[serde(default)]
+    pub avc_3d_extension_flag: bool,
+    #[serde(default)]
+    pub avc_3d_extension: SliceHeader3DAVCExtension,
 }
 
 impl SliceHeader {
@@ -1537,6 +1802,10 @@ impl SliceHeader {
             filter_offset_b: 0,
             abs_diff_view_idx_minus1_l0: Vec::new(),
             abs_diff_view_idx_minus1_l1: Vec::new(),
+            svc_extension_flag: false,
+            svc_extension: SliceHeaderSVCExtension::new(),
+            avc_3d_extension_flag: false,
+            avc_3d_extension: SliceHeader3DAVCExtension::new(),
         }
     }
 
@@ -1772,25 +2041,114 @@ impl SliceHeader {
             filter_offset_b: self.filter_offset_b,
             abs_diff_view_idx_minus1_l0: self.abs_diff_view_idx_minus1_l0.clone(),
             abs_diff_view_idx_minus1_l1: self.abs_diff_view_idx_minus1_l1.clone(),
+            svc_extension_flag: self.svc_extension_flag,
+            svc_extension: self.svc_extension.clone(),
+            avc_3d_extension_flag: self.avc_3d_extension_flag,
+            avc_3d_extension: self.avc_3d_extension.clone(),
+        }
+    }
+
+    /// Returns whether mb_addr lies inside the scaled reference layer window, i.e. InCropWindow() in G.7.4.6.
+    /// Macroblocks outside of the window can not use inter-layer prediction.
+    pub fn in_crop_window(&self, mb_addr: usize, vp: &VideoParameters) -> bool {
+        if !self.svc_extension_flag || self.svc_extension.no_inter_layer_pred_flag {
+            return false;
         }
+
+        let pic_width_in_mbs = vp.pic_width_in_mbs as i32;
+        let field_int = match self.field_pic_flag {
+            true => 1,
+            false => 0,
+        };
+        let mbaff_int = match self.mbaff_frame_flag {
+            true => 1,
+            false => 0,
+        };
+        let mb_addr = mb_addr as i32;
+
+        // G-3 and G-4
+        let mb_x = if self.mbaff_frame_flag {
+            (mb_addr / 2) % pic_width_in_mbs
+        } else {
+            mb_addr % pic_width_in_mbs
+        };
+        let mb_y0 = if self.mbaff_frame_flag {
+            2 * ((mb_addr / 2) / pic_width_in_mbs)
+        } else {
+            mb_addr / pic_width_in_mbs
+        };
+
+        let [left, top, right, bottom] = self.svc_extension.scaled_ref_layer_offsets;
+        let scaled_ref_layer_pic_width_in_samples_l =
+            (vp.pic_width_in_mbs * 16) as i32 - left - right;
+        // PicHeightInMbs * (1 + field_pic_flag) is FrameHeightInMbs
+        let scaled_ref_layer_pic_height_in_samples_l =
+            (vp.frame_height_in_mbs * 16) as i32 - top - bottom;
+
+        // offsets may be negative so use floor division like the spec
+        let mb_x_ok = mb_x >= (left + 15).div_euclid(16)
+            && mb_x < (left + scaled_ref_layer_pic_width_in_samples_l).div_euclid(16);
+        let y_scale = 16 * (1 + field_int);
+        let mb_y_ok = mb_y0 >= (top + y_scale - 1).div_euclid(y_scale)
+            && mb_y0 + mbaff_int
+                < (top + scaled_ref_layer_pic_height_in_samples_l).div_euclid(y_scale);
+
+        mb_x_ok && mb_y_ok
     }
 
     /// Produce the slice group map that will be used by next_mb_addr to handle FMO.
-    /// Follows the process described in section 8.2.2
+    /// Follows the process described in section 8.2.2. Returns an error when the PPS
+    /// slice group parameters do not fit in the picture size of the SPS.
     pub fn generate_slice_group_map(
         &self,
         s: &SeqParameterSet,
         p: &PicParameterSet,
         vp: &VideoParameters,
-    ) -> Vec<u32> {
-        let slice_group_change_rate = p.slice_group_change_rate_minus1 + 1;
+    ) -> Result<Vec<u32>, DecodeError> {
+        let slice_group_change_rate = p.slice_group_change_rate_minus1.saturating_add(1);
 
         // Equation 7-34
         let map_units_in_slice_group0 = cmp::min(
-            self.slice_group_change_cycle * slice_group_change_rate,
+            self.slice_group_change_cycle.saturating_mul(slice_group_change_rate),
             vp.pic_size_in_map_units,
         );
 
+        if p.num_slice_groups_minus1 > 0 {
+            match p.slice_group_map_type {
+                0 if p.run_length_minus1.len() <= p.num_slice_groups_minus1 as usize => {
+                    return Err(DecodeError::new(format!(
+                        "generate_slice_group_map - {} run lengths for {} slice groups",
+                        p.run_length_minus1.len(),
+                        p.num_slice_groups_minus1 + 1
+                    )));
+                }
+                2 => {
+                    // 7.4.2.2: top_left[ i ] <= bottom_right[ i ] < PicSizeInMapUnits
+                    for i_group in 0..p.num_slice_groups_minus1 as usize {
+                        match (p.top_left.get(i_group), p.bottom_right.get(i_group)) {
+                            (Some(&top_left), Some(&bottom_right))
+                                if top_left <= bottom_right
+                                    && bottom_right < vp.pic_size_in_map_units => {}
+                            _ => {
+                                return Err(DecodeError::new(format!(
+                                    "generate_slice_group_map - slice group {} rectangle is outside of the {} map units",
+                                    i_group, vp.pic_size_in_map_units
+                                )))
+                            }
+                        }
+                    }
+                }
+                6 if p.slice_group_id.len() != vp.pic_size_in_map_units as usize => {
+                    return Err(DecodeError::new(format!(
+                        "generate_slice_group_map - {} slice_group_id values for {} map units",
+                        p.slice_group_id.len(),
+                        vp.pic_size_in_map_units
+                    )));
+                }
+                _ => (),
+            }
+        }
+
         // Start of 8.2.2
         let size_of_upper_left_group = if p.num_slice_groups_minus1 == 1
             && (p.slice_group_map_type == 4 || p.slice_group_map_type == 5)
@@ -1819,12 +2177,13 @@ impl SliceHeader {
 
                         while i < vp.pic_size_in_map_units {
                             for i_group in 0..=p.num_slice_groups_minus1 {
-                                for j in 0..=p.run_length_minus1[i_group as usize] {
-                                    if i + j < vp.pic_size_in_map_units {
-                                        res[(i + j) as usize] = i_group;
-                                    }
+                                let run_end = i
+                                    .saturating_add(p.run_length_minus1[i_group as usize])
+                                    .saturating_add(1);
+                                for j in i..cmp::min(run_end, vp.pic_size_in_map_units) {
+                                    res[j as usize] = i_group;
                                 }
-                                i += p.run_length_minus1[i_group as usize] + 1;
+                                i = run_end;
                             }
                         }
                         res
@@ -1899,7 +2258,7 @@ impl SliceHeader {
                             }
 
                             if x_dir == -1 && x == left_bound {
-                                left_bound = cmp::max(left_bound - 1, 0);
+                                left_bound = left_bound.saturating_sub(1);
                                 x = left_bound;
 
                                 x_dir = 0;
@@ -1911,7 +2270,7 @@ impl SliceHeader {
                                 x_dir = 0;
                                 y_dir = 1 - 2 * slice_group_change_direction_int;
                             } else if y_dir == -1 && y == top_bound {
-                                top_bound = cmp::max(top_bound - 1, 0);
+                                top_bound = top_bound.saturating_sub(1);
                                 y = top_bound;
 
                                 x_dir = 1 - 2 * slice_group_change_direction_int;
@@ -1983,10 +2342,10 @@ impl SliceHeader {
                         res
                     }
                     _ => {
-                        panic!(
-                            "Out-of-bounds num_slice_groups_minus1: {}",
-                            p.num_slice_groups_minus1
-                        )
+                        return Err(DecodeError::new(format!(
+                            "generate_slice_group_map - Out-of-bounds slice_group_map_type: {}",
+                            p.slice_group_map_type
+                        )))
                     }
                 }
             }
@@ -2014,7 +2373,7 @@ impl SliceHeader {
             res
         };
 
-        sgm
+        Ok(sgm)
     }
 }
 
@@ -2072,6 +2431,16 @@ impl SliceData {
         }
     }
 
+    /// Returns the index of the previously decoded macroblock at address `mb_addr`,
+    /// or None if it is not in this slice. With FMO the macroblocks of a slice are
+    /// not contiguous in address, so neighbors are found by address rather than by
+    /// an index offset.
+    fn get_mb_idx_from_addr(&self, curr_mb_idx: usize, mb_addr: usize) -> Option<usize> {
+        self.macroblock_vec[..curr_mb_idx]
+            .iter()
+            .rposition(|mb| mb.mb_addr == mb_addr)
+    }
+
     /// Returns neighbor macroblocks and availability per Section 6.4.9
     pub fn get_neighbor_macroblock(
         &self,
@@ -2086,77 +2455,47 @@ impl SliceData {
         }
 
         // Follow Figure 6-12 for neighboring macroblocks
-        match neighbor_type {
+        let curr_mb_addr = self.macroblock_vec[curr_mb_idx].mb_addr;
+        let pic_width_in_mbs = vp.pic_width_in_mbs as usize;
+        let neighbor_addr = match neighbor_type {
             NeighborMB::MbAddrA => {
-                if self.macroblock_vec[curr_mb_idx].mb_addr % (vp.pic_width_in_mbs as usize) == 0
-                    || curr_mb_idx == 0
-                {
-                    result_mb = MacroBlock::new();
+                if curr_mb_addr.is_multiple_of(pic_width_in_mbs) {
+                    None
                 } else {
-                    // check if mb_a is in this slice or not
-                    let mb_a_idx = curr_mb_idx - 1;
-                    let mb_a_addr = self.macroblock_vec[curr_mb_idx].mb_addr - 1;
-                    if mb_a_addr == self.macroblock_vec[mb_a_idx].mb_addr {
-                        result_mb = self.macroblock_vec[mb_a_idx].clone();
-                    } else {
-                        result_mb = MacroBlock::new();
-                    }
+                    Some(curr_mb_addr - 1)
                 }
             }
             NeighborMB::MbAddrB => {
-                if self.macroblock_vec[curr_mb_idx].mb_addr < (vp.pic_width_in_mbs as usize)
-                    || curr_mb_idx < (vp.pic_width_in_mbs as usize)
-                {
-                    result_mb = MacroBlock::new();
+                if curr_mb_addr < pic_width_in_mbs {
+                    None
                 } else {
-                    let mb_b_idx = curr_mb_idx - (vp.pic_width_in_mbs as usize);
-                    let mb_b_addr =
-                        self.macroblock_vec[curr_mb_idx].mb_addr - (vp.pic_width_in_mbs as usize);
-                    if mb_b_addr == self.macroblock_vec[mb_b_idx].mb_addr {
-                        result_mb = self.macroblock_vec[mb_b_idx].clone();
-                    } else {
-                        result_mb = MacroBlock::new();
-                    }
+                    Some(curr_mb_addr - pic_width_in_mbs)
                 }
             }
             NeighborMB::MbAddrC => {
-                if self.macroblock_vec[curr_mb_idx].mb_addr < (vp.pic_width_in_mbs as usize)
-                    || (self.macroblock_vec[curr_mb_idx].mb_addr + 1)
-                        % (vp.pic_width_in_mbs as usize)
-                        == 0
-                    || curr_mb_idx < (vp.pic_width_in_mbs as usize) - 1
+                if curr_mb_addr < pic_width_in_mbs
+                    || (curr_mb_addr + 1).is_multiple_of(pic_width_in_mbs)
                 {
-                    result_mb = MacroBlock::new();
+                    None
                 } else {
-                    let mb_c_idx = (curr_mb_idx + 1) - (vp.pic_width_in_mbs as usize);
-                    let mb_c_addr = self.macroblock_vec[curr_mb_idx].mb_addr
-                        - (vp.pic_width_in_mbs as usize)
-                        + 1;
-                    if mb_c_addr == self.macroblock_vec[mb_c_idx].mb_addr {
-                        result_mb = self.macroblock_vec[mb_c_idx].clone();
-                    } else {
-                        result_mb = MacroBlock::new();
-                    }
+                    Some(curr_mb_addr - pic_width_in_mbs + 1)
                 }
             }
             NeighborMB::MbAddrD => {
-                if self.macroblock_vec[curr_mb_idx].mb_addr % (vp.pic_width_in_mbs as usize) == 0
-                    || self.macroblock_vec[curr_mb_idx].mb_addr < (vp.pic_width_in_mbs as usize + 1)
-                    || curr_mb_idx < (vp.pic_width_in_mbs as usize) + 1
+                if curr_mb_addr.is_multiple_of(pic_width_in_mbs)
+                    || curr_mb_addr < pic_width_in_mbs + 1
                 {
-                    result_mb = MacroBlock::new();
+                    None
                 } else {
-                    let mb_d_idx = curr_mb_idx - (vp.pic_width_in_mbs as usize) - 1;
-                    let mb_d_addr = self.macroblock_vec[curr_mb_idx].mb_addr
-                        - (vp.pic_width_in_mbs as usize)
-                        - 1;
-                    if mb_d_addr == self.macroblock_vec[mb_d_idx].mb_addr {
-                        result_mb = self.macroblock_vec[mb_d_idx].clone();
-                    } else {
-                        result_mb = MacroBlock::new();
-                    }
+                    Some(curr_mb_addr - pic_width_in_mbs - 1)
                 }
             }
+        };
+
+        // check if the neighbor is in this slice or not
+        match neighbor_addr.and_then(|addr| self.get_mb_idx_from_addr(curr_mb_idx, addr)) {
+            Some(idx) => result_mb = self.macroblock_vec[idx].clone(),
+            None => result_mb = MacroBlock::new(),
         }
 
         result_mb
@@ -2176,85 +2515,49 @@ impl SliceData {
         }
 
         // Follow Figure 6-13 for neighboring macroblocks
-        match neighbor_type {
+        let curr_mb_pair_addr = self.macroblock_vec[curr_mb_idx].mb_addr / 2;
+        let pic_width_in_mbs = vp.pic_width_in_mbs as usize;
+        let neighbor_pair_addr = match neighbor_type {
             NeighborMB::MbAddrA => {
-                if self.macroblock_vec[curr_mb_idx].mb_addr / 2 % (vp.pic_width_in_mbs as usize)
-                    == 0
-                    || curr_mb_idx < 2
-                {
-                    // the last check is to make sure mb_a_addr doesn't equal 0
-                    result_mb = MacroBlock::new();
+                if curr_mb_pair_addr.is_multiple_of(pic_width_in_mbs) {
+                    None
                 } else {
-                    // check if mb_a is in this slice or not
-                    let mb_a_idx = 2 * (curr_mb_idx / 2 - 1);
-                    let mb_a_addr = 2 * (self.macroblock_vec[curr_mb_idx].mb_addr / 2 - 1);
-                    if mb_a_addr == self.macroblock_vec[mb_a_idx].mb_addr {
-                        result_mb = self.macroblock_vec[mb_a_idx].clone();
-                    } else {
-                        result_mb = MacroBlock::new();
-                    }
+                    Some(curr_mb_pair_addr - 1)
                 }
             }
             NeighborMB::MbAddrB => {
-                if self.macroblock_vec[curr_mb_idx].mb_addr / 2 < (vp.pic_width_in_mbs as usize)
-                    || curr_mb_idx < 2 * (vp.pic_width_in_mbs as usize)
-                {
-                    result_mb = MacroBlock::new();
+                if curr_mb_pair_addr < pic_width_in_mbs {
+                    None
                 } else {
-                    let mb_b_idx = 2 * (curr_mb_idx / 2 - (vp.pic_width_in_mbs as usize));
-                    let mb_b_addr = 2
-                        * (self.macroblock_vec[curr_mb_idx].mb_addr / 2
-                            - (vp.pic_width_in_mbs as usize));
-                    if mb_b_addr == self.macroblock_vec[mb_b_idx].mb_addr {
-                        result_mb = self.macroblock_vec[mb_b_idx].clone();
-                    } else {
-                        result_mb = MacroBlock::new();
-                    }
+                    Some(curr_mb_pair_addr - pic_width_in_mbs)
                 }
             }
             NeighborMB::MbAddrC => {
-                // if on the right-most edge, top-most row, or the index is on the top-most row
-                // calculate right-most edge to avoid mod 0
-                if self.macroblock_vec[curr_mb_idx].mb_addr / 2 % (vp.pic_width_in_mbs as usize)
-                    == (vp.pic_width_in_mbs as usize - 1)
-                    || self.macroblock_vec[curr_mb_idx].mb_addr / 2 < (vp.pic_width_in_mbs as usize)
-                    || curr_mb_idx / 2 < (vp.pic_width_in_mbs as usize) - 1
+                // if on the right-most edge or top-most row
+                if curr_mb_pair_addr % pic_width_in_mbs == pic_width_in_mbs - 1
+                    || curr_mb_pair_addr < pic_width_in_mbs
                 {
-                    result_mb = MacroBlock::new();
+                    None
                 } else {
-                    let mb_c_idx = 2 * (curr_mb_idx / 2 + 1 - (vp.pic_width_in_mbs as usize));
-                    let mb_c_addr = 2
-                        * (self.macroblock_vec[curr_mb_idx].mb_addr / 2
-                            - (vp.pic_width_in_mbs as usize)
-                            + 1);
-                    if mb_c_addr == self.macroblock_vec[mb_c_idx].mb_addr {
-                        result_mb = self.macroblock_vec[mb_c_idx].clone();
-                    } else {
-                        result_mb = MacroBlock::new();
-                    }
+                    Some(curr_mb_pair_addr - pic_width_in_mbs + 1)
                 }
             }
             NeighborMB::MbAddrD => {
-                // if on the left-most edge, top-most row, or the index is on the top-most row
-                if self.macroblock_vec[curr_mb_idx].mb_addr / 2 % (vp.pic_width_in_mbs as usize)
-                    == 0
-                    || self.macroblock_vec[curr_mb_idx].mb_addr / 2 < (vp.pic_width_in_mbs as usize)
-                    || curr_mb_idx / 2 < (vp.pic_width_in_mbs as usize) + 1
+                // if on the left-most edge or top-most row
+                if curr_mb_pair_addr.is_multiple_of(pic_width_in_mbs)
+                    || curr_mb_pair_addr < pic_width_in_mbs
                 {
-                    result_mb = MacroBlock::new();
+                    None
                 } else {
-                    let mb_d_idx = 2 * (curr_mb_idx / 2 - (vp.pic_width_in_mbs as usize) - 1);
-                    let mb_d_addr = 2
-                        * (self.macroblock_vec[curr_mb_idx].mb_addr / 2
-                            - (vp.pic_width_in_mbs as usize)
-                            - 1);
-                    if mb_d_addr == self.macroblock_vec[mb_d_idx].mb_addr {
-                        result_mb = self.macroblock_vec[mb_d_idx].clone();
-                    } else {
-                        result_mb = MacroBlock::new();
-                    }
+                    Some(curr_mb_pair_addr - pic_width_in_mbs - 1)
                 }
             }
+        };
+
+        // check if the top macroblock of the neighboring pair is in this slice or not
+        match neighbor_pair_addr.and_then(|addr| self.get_mb_idx_from_addr(curr_mb_idx, 2 * addr)) {
+            Some(idx) => result_mb = self.macroblock_vec[idx].clone(),
+            None => result_mb = MacroBlock::new(),
         }
 
         if NEIGHBOR_DEBUG {
@@ -3164,6 +3467,31 @@ impl Default for Slice {
     }
 }
 
+/// NALU Types 2, 3, and 4 -- Slice data partition A, B, and C syntax elements
+/// that are not part of the slice header or slice data (Spec 7.3.2.9)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SliceDataPartition {
+    pub slice_id: u32,          // ue(v)
+    pub colour_plane_id: u8,    // u(2) -- only in partitions B and C
+    pub redundant_pic_cnt: u32, // ue(v) -- only in partitions B and C
+}
+
+impl SliceDataPartition {
+    pub fn new() -> SliceDataPartition {
+        SliceDataPartition {
+            slice_id: 0,
+            colour_plane_id: 0,
+            redundant_pic_cnt: 0,
+        }
+    }
+}
+
+impl Default for SliceDataPartition {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// NALU Type 8 -- Picture Parameter Set
 #[derive(Serialize, Deserialize, Clone)]
 pub struct PicParameterSet {
@@ -4817,101 +5145,424 @@ impl MVCDSPSExtension {
     }
 }
 
+impl MVCDSPSExtension {
+    /// AllViewsPairedFlag as derived in J.7.4.2.1.5; when every view has both a texture
+    /// and a depth component, the 3D-AVC SPS extension does not repeat the texture view dependencies
+    pub fn all_views_paired_flag(&self) -> bool {
+        (1..=self.num_views_minus1 as usize).all(|i| {
+            *self.depth_view_present_flag.get(i).unwrap_or(&false)
+                && *self.texture_view_present_flag.get(i).unwrap_or(&false)
+        })
+    }
+}
+
 impl Default for MVCDSPSExtension {
     fn default() -> Self {
         Self::new()
     }
 }
 
-/// AVC-3D SPS Parameters -- part of Subset SPS
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct AVC3DSPSExtension {
-    // TODO: AVC 3D SPS Extension
+/// 3DV acquisition element -- J.7.3.2.5.2
+///
+/// Each value is indexed by view; syntax elements that are not present are stored as 0
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcquisitionElement3DV {
+    pub element_equal_flag: bool,
+    pub mantissa_len_minus1: u8, // u(5)
+    pub sign0: Vec<bool>,
+    pub exponent0: Vec<u32>, // u(v)
+    pub mantissa0: Vec<u32>, // u(v)
+    pub skip_flag: Vec<bool>,
+    pub sign1: Vec<bool>,
+    pub exponent_skip_flag: Vec<bool>,
+    pub exponent1: Vec<u32>,     // u(v)
+    pub mantissa_diff: Vec<i32>, // se(v)
 }
 
-impl AVC3DSPSExtension {
-    pub fn new() -> AVC3DSPSExtension {
-        AVC3DSPSExtension {}
+impl AcquisitionElement3DV {
+    pub fn new() -> AcquisitionElement3DV {
+        AcquisitionElement3DV {
+            element_equal_flag: false,
+            mantissa_len_minus1: 0,
+            sign0: Vec::new(),
+            exponent0: Vec::new(),
+            mantissa0: Vec::new(),
+            skip_flag: Vec::new(),
+            sign1: Vec::new(),
+            exponent_skip_flag: Vec::new(),
+            exponent1: Vec::new(),
+            mantissa_diff: Vec::new(),
+        }
     }
 
-    pub fn encoder_pretty_print(&self) {}
+    /// Number of coded values, as derived in J.7.3.2.5.2
+    pub fn num_values(&self, num_views: u32) -> u32 {
+        if num_views > 1 && self.element_equal_flag {
+            1
+        } else {
+            num_views
+        }
+    }
 }
 
-impl Default for AVC3DSPSExtension {
+impl Default for AcquisitionElement3DV {
     fn default() -> Self {
         Self::new()
     }
 }
 
-/// NALU Type 7 -- Sequence Parameter Set
-#[derive(Serialize, Deserialize, Clone)]
-pub struct SeqParameterSet {
-    pub available: bool, // used to determine if the SPS has been set or not
-
-    pub profile_idc: u8,
-    pub constraint_set0_flag: bool,
-    pub constraint_set1_flag: bool,
-    pub constraint_set2_flag: bool,
-    pub constraint_set3_flag: bool,
-    pub constraint_set4_flag: bool,
-    pub constraint_set5_flag: bool,
-    pub reserved_zero_2bits: u8, //u(2)
-    pub level_idc: u8,
-    pub seq_parameter_set_id: u32, //ue(v)
-
-    pub chroma_format_idc: u8, //ue(v)
-    pub separate_colour_plane_flag: bool,
-    pub bit_depth_luma_minus8: u8,   //ue(v)
-    pub bit_depth_chroma_minus8: u8, //ue(v)
-    pub qpprime_y_zero_transform_bypass_flag: bool,
-
-    // seq_scaling
-    pub seq_scaling_matrix_present_flag: bool,
-    pub seq_scaling_list_present_flag: Vec<bool>, //u(1)[8 or 12]
-    pub delta_scale_4x4: Vec<Vec<i32>>, // used to calculate scaling list, but store for ease in encoding
-    pub scaling_list_4x4: Vec<Vec<i32>>, // list (len 6) of list (len 16) of se(v)
-    pub delta_scale_8x8: Vec<Vec<i32>>, // used to calculate scaling list, but store for ease in encoding
-    pub scaling_list_8x8: Vec<Vec<i32>>, // list (len 2 or 6) of list (len 64) of se(v)
-    pub use_default_scaling_matrix_4x4: Vec<bool>, // list len 6
-    pub use_default_scaling_matrix_8x8: Vec<bool>, // list len 2 or 6
-
-    pub log2_max_frame_num_minus4: u32, //ue(v)
-
-    pub pic_order_cnt_type: u32,               //ue(v)
-    pub log2_max_pic_order_cnt_lsb_minus4: u8, //ue(v) - the range of this must be between 0 to 12 inclusive
+/// Depth ranges -- J.7.3.2.5.1
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepthRanges {
+    pub z_near_flag: bool,
+    pub z_far_flag: bool,
+    pub z_near: AcquisitionElement3DV,
+    pub z_far: AcquisitionElement3DV,
+}
 
-    pub delta_pic_order_always_zero_flag: bool,
-    pub offset_for_non_ref_pic: i32,                //se(v)
-    pub offset_for_top_to_bottom_field: i32,        //se(v)
-    pub num_ref_frames_in_pic_order_cnt_cycle: u32, //ue(v)
-    pub offset_for_ref_frame: Vec<i32>,             //se(v)[num_ref_frames_in_pic_order_cnt_cycle]
+impl DepthRanges {
+    pub fn new() -> DepthRanges {
+        DepthRanges {
+            z_near_flag: false,
+            z_far_flag: false,
+            z_near: AcquisitionElement3DV::new(),
+            z_far: AcquisitionElement3DV::new(),
+        }
+    }
+}
 
-    pub max_num_ref_frames: u32, //ue(v)
-    pub gaps_in_frame_num_value_allowed_flag: bool,
-    pub pic_width_in_mbs_minus1: u32,        //ue(v)
-    pub pic_height_in_map_units_minus1: u32, //ue(v)
+impl Default for DepthRanges {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    pub frame_mbs_only_flag: bool,
-    pub mb_adaptive_frame_field_flag: bool,
+/// View synthesis prediction parameters -- J.7.3.2.5.3
+///
+/// The disparity values are indexed by [i][j] for every view pair j < i
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VSPParam {
+    pub disparity_diff_wji: Vec<Vec<u32>>, // ue(v)
+    pub disparity_diff_oji: Vec<Vec<u32>>, // ue(v)
+    pub disparity_diff_wij: Vec<Vec<u32>>, // ue(v)
+    pub disparity_diff_oij: Vec<Vec<u32>>, // ue(v)
+}
 
-    pub direct_8x8_inference_flag: bool,
+impl VSPParam {
+    pub fn new() -> VSPParam {
+        VSPParam {
+            disparity_diff_wji: Vec::new(),
+            disparity_diff_oji: Vec::new(),
+            disparity_diff_wij: Vec::new(),
+            disparity_diff_oij: Vec::new(),
+        }
+    }
+}
 
-    pub frame_cropping_flag: bool,
-    pub frame_crop_left_offset: u32,   //ue(v)
-    pub frame_crop_right_offset: u32,  //ue(v)
-    pub frame_crop_top_offset: u32,    //ue(v)
-    pub frame_crop_bottom_offset: u32, //ue(v)
+impl Default for VSPParam {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-    pub vui_parameters_present_flag: bool,
-    pub vui_parameters: VUIParameters,
+/// NALU Type 16 -- Depth Parameter Set
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DepthParameterSet {
+    pub depth_parameter_set_id: u32, // ue(v)
+    pub pred_direction: u32,         // ue(v)
+    pub ref_dps_id0: u32,            // ue(v)
+    pub ref_dps_id1: u32,            // ue(v)
+    pub pred_weight0: u8,            // u(6)
+    pub num_depth_views_minus1: u32, // ue(v)
+    pub depth_ranges: DepthRanges,
+    pub vsp_param_flag: bool,
+    pub vsp_param: VSPParam,
+    pub depth_param_additional_extension_flag: bool,
+    pub nonlinear_depth_representation_num: u32, // ue(v)
+    pub nonlinear_depth_representation_model: Vec<u32>, // ue(v)
+    pub depth_param_additional_extension_data_flag: Vec<bool>,
 }
 
-impl SeqParameterSet {
-    pub fn new() -> SeqParameterSet {
-        SeqParameterSet {
-            available: false,
-            profile_idc: 0,
-            constraint_set0_flag: false,
+impl DepthParameterSet {
+    pub fn new() -> DepthParameterSet {
+        DepthParameterSet {
+            depth_parameter_set_id: 0,
+            pred_direction: 2,
+            ref_dps_id0: 0,
+            ref_dps_id1: 0,
+            pred_weight0: 0,
+            num_depth_views_minus1: 0,
+            depth_ranges: DepthRanges::new(),
+            vsp_param_flag: false,
+            vsp_param: VSPParam::new(),
+            depth_param_additional_extension_flag: false,
+            nonlinear_depth_representation_num: 0,
+            nonlinear_depth_representation_model: Vec::new(),
+            depth_param_additional_extension_data_flag: Vec::new(),
+        }
+    }
+
+    pub fn encoder_pretty_print(&self) {
+        encoder_formatted_print(
+            "DPS: depth_parameter_set_id",
+            self.depth_parameter_set_id,
+            63,
+        );
+        encoder_formatted_print("DPS: pred_direction", self.pred_direction, 63);
+        encoder_formatted_print("DPS: ref_dps_id0", self.ref_dps_id0, 63);
+        encoder_formatted_print("DPS: ref_dps_id1", self.ref_dps_id1, 63);
+        encoder_formatted_print("DPS: pred_weight0", self.pred_weight0, 63);
+        encoder_formatted_print(
+            "DPS: num_depth_views_minus1",
+            self.num_depth_views_minus1,
+            63,
+        );
+        encoder_formatted_print("DPS: depth_ranges", &self.depth_ranges, 63);
+        encoder_formatted_print("DPS: vsp_param_flag", self.vsp_param_flag, 63);
+        encoder_formatted_print("DPS: vsp_param", &self.vsp_param, 63);
+        encoder_formatted_print(
+            "DPS: depth_param_additional_extension_flag",
+            self.depth_param_additional_extension_flag,
+            63,
+        );
+        encoder_formatted_print(
+            "DPS: nonlinear_depth_representation_num",
+            self.nonlinear_depth_representation_num,
+            63,
+        );
+        encoder_formatted_print(
+            "DPS: nonlinear_depth_representation_model",
+            &self.nonlinear_depth_representation_model,
+            63,
+        );
+    }
+}
+
+impl Default for DepthParameterSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// AVC-3D SPS Parameters -- part of Subset SPS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AVC3DSPSExtension {
+    pub three_dv_acquisition_idc: u32, // 3dv_acquisition_idc ue(v)
+    pub view_id_3dv: Vec<u32>,         // ue(v)
+    pub depth_ranges: DepthRanges,
+    pub vsp_param: VSPParam,
+    pub reduced_resolution_flag: bool,
+    pub depth_pic_width_in_mbs_minus1: u32,        // ue(v)
+    pub depth_pic_height_in_map_units_minus1: u32, // ue(v)
+    pub depth_hor_mult_minus1: u32,                // ue(v)
+    pub depth_ver_mult_minus1: u32,                // ue(v)
+    pub depth_hor_rsh: u32,                        // ue(v)
+    pub depth_ver_rsh: u32,                        // ue(v)
+    pub depth_frame_cropping_flag: bool,
+    pub depth_frame_crop_left_offset: u32,   // ue(v)
+    pub depth_frame_crop_right_offset: u32,  // ue(v)
+    pub depth_frame_crop_top_offset: u32,    // ue(v)
+    pub depth_frame_crop_bottom_offset: u32, // ue(v)
+    pub grid_pos_num_views: u32,             // ue(v)
+    pub grid_pos_view_id: Vec<u32>,          // ue(v)
+    pub grid_pos_x: Vec<i32>,                // se(v)
+    pub grid_pos_y: Vec<i32>,                // se(v)
+    pub slice_header_prediction_flag: bool,
+    pub seq_view_synthesis_flag: bool,
+    pub alc_sps_enable_flag: bool,
+    pub enable_rle_skip_flag: bool,
+    // texture view dependencies, only present when AllViewsPairedFlag is 0
+    pub num_anchor_refs_l0: Vec<u32>,     // ue(v)
+    pub anchor_ref_l0: Vec<Vec<u32>>,     // ue(v)
+    pub num_anchor_refs_l1: Vec<u32>,     // ue(v)
+    pub anchor_ref_l1: Vec<Vec<u32>>,     // ue(v)
+    pub num_non_anchor_refs_l0: Vec<u32>, // ue(v)
+    pub non_anchor_ref_l0: Vec<Vec<u32>>, // ue(v)
+    pub num_non_anchor_refs_l1: Vec<u32>, // ue(v)
+    pub non_anchor_ref_l1: Vec<Vec<u32>>, // ue(v)
+}
+
+impl AVC3DSPSExtension {
+    pub fn new() -> AVC3DSPSExtension {
+        AVC3DSPSExtension {
+            three_dv_acquisition_idc: 0,
+            view_id_3dv: Vec::new(),
+            depth_ranges: DepthRanges::new(),
+            vsp_param: VSPParam::new(),
+            reduced_resolution_flag: false,
+            depth_pic_width_in_mbs_minus1: 0,
+            depth_pic_height_in_map_units_minus1: 0,
+            depth_hor_mult_minus1: 0,
+            depth_ver_mult_minus1: 0,
+            depth_hor_rsh: 0,
+            depth_ver_rsh: 0,
+            depth_frame_cropping_flag: false,
+            depth_frame_crop_left_offset: 0,
+            depth_frame_crop_right_offset: 0,
+            depth_frame_crop_top_offset: 0,
+            depth_frame_crop_bottom_offset: 0,
+            grid_pos_num_views: 0,
+            grid_pos_view_id: Vec::new(),
+            grid_pos_x: Vec::new(),
+            grid_pos_y: Vec::new(),
+            slice_header_prediction_flag: false,
+            seq_view_synthesis_flag: false,
+            alc_sps_enable_flag: false,
+            enable_rle_skip_flag: false,
+            num_anchor_refs_l0: Vec::new(),
+            anchor_ref_l0: Vec::new(),
+            num_anchor_refs_l1: Vec::new(),
+            anchor_ref_l1: Vec::new(),
+            num_non_anchor_refs_l0: Vec::new(),
+            non_anchor_ref_l0: Vec::new(),
+            num_non_anchor_refs_l1: Vec::new(),
+            non_anchor_ref_l1: Vec::new(),
+        }
+    }
+
+    pub fn encoder_pretty_print(&self) {
+        encoder_formatted_print(
+            "3D-AVC SPS: 3dv_acquisition_idc",
+            self.three_dv_acquisition_idc,
+            63,
+        );
+        encoder_formatted_print("3D-AVC SPS: view_id_3dv", &self.view_id_3dv, 63);
+        encoder_formatted_print("3D-AVC SPS: depth_ranges", &self.depth_ranges, 63);
+        encoder_formatted_print("3D-AVC SPS: vsp_param", &self.vsp_param, 63);
+        encoder_formatted_print(
+            "3D-AVC SPS: reduced_resolution_flag",
+            self.reduced_resolution_flag,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: depth_frame_cropping_flag",
+            self.depth_frame_cropping_flag,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: grid_pos_num_views",
+            self.grid_pos_num_views,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: slice_header_prediction_flag",
+            self.slice_header_prediction_flag,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: seq_view_synthesis_flag",
+            self.seq_view_synthesis_flag,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: alc_sps_enable_flag",
+            self.alc_sps_enable_flag,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: enable_rle_skip_flag",
+            self.enable_rle_skip_flag,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: num_anchor_refs_l0",
+            &self.num_anchor_refs_l0,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: num_anchor_refs_l1",
+            &self.num_anchor_refs_l1,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: num_non_anchor_refs_l0",
+            &self.num_non_anchor_refs_l0,
+            63,
+        );
+        encoder_formatted_print(
+            "3D-AVC SPS: num_non_anchor_refs_l1",
+            &self.num_non_anchor_refs_l1,
+            63,
+        );
+    }
+}
+
+impl Default for AVC3DSPSExtension {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// NALU Type 7 -- Sequence Parameter Set
+#[derive(Serialize, Deserialize, Clone)]
+pub struct SeqParameterSet {
+    pub available: bool, // used to determine if the SPS has been set or not
+
+    pub profile_idc: u8,
+    pub constraint_set0_flag: bool,
+    pub constraint_set1_flag: bool,
+    pub constraint_set2_flag: bool,
+    pub constraint_set3_flag: bool,
+    pub constraint_set4_flag: bool,
+    pub constraint_set5_flag: bool,
+    pub reserved_zero_2bits: u8, //u(2)
+    pub level_idc: u8,
+    pub seq_parameter_set_id: u32, //ue(v)
+
+    pub chroma_format_idc: u8, //ue(v)
+    pub separate_colour_plane_flag: bool,
+    pub bit_depth_luma_minus8: u8,   //ue(v)
+    pub bit_depth_chroma_minus8: u8, //ue(v)
+    pub qpprime_y_zero_transform_bypass_flag: bool,
+
+    // seq_scaling
+    pub seq_scaling_matrix_present_flag: bool,
+    pub seq_scaling_list_present_flag: Vec<bool>, //u(1)[8 or 12]
+    pub delta_scale_4x4: Vec<Vec<i32>>, // used to calculate scaling list, but store for ease in encoding
+    pub scaling_list_4x4: Vec<Vec<i32>>, // list (len 6) of list (len 16) of se(v)
+    pub delta_scale_8x8: Vec<Vec<i32>>, // used to calculate scaling list, but store for ease in encoding
+    pub scaling_list_8x8: Vec<Vec<i32>>, // list (len 2 or 6) of list (len 64) of se(v)
+    pub use_default_scaling_matrix_4x4: Vec<bool>, // list len 6
+    pub use_default_scaling_matrix_8x8: Vec<bool>, // list len 2 or 6
+
+    pub log2_max_frame_num_minus4: u32, //ue(v)
+
+    pub pic_order_cnt_type: u32,               //ue(v)
+    pub log2_max_pic_order_cnt_lsb_minus4: u8, //ue(v) - the range of this must be between 0 to 12 inclusive
+
+    pub delta_pic_order_always_zero_flag: bool,
+    pub offset_for_non_ref_pic: i32,                //se(v)
+    pub offset_for_top_to_bottom_field: i32,        //se(v)
+    pub num_ref_frames_in_pic_order_cnt_cycle: u32, //ue(v)
+    pub offset_for_ref_frame: Vec<i32>,             //se(v)[num_ref_frames_in_pic_order_cnt_cycle]
+
+    pub max_num_ref_frames: u32, //ue(v)
+    pub gaps_in_frame_num_value_allowed_flag: bool,
+    pub pic_width_in_mbs_minus1: u32,        //ue(v)
+    pub pic_height_in_map_units_minus1: u32, //ue(v)
+
+    pub frame_mbs_only_flag: bool,
+    pub mb_adaptive_frame_field_flag: bool,
+
+    pub direct_8x8_inference_flag: bool,
+
+    pub frame_cropping_flag: bool,
+    pub frame_crop_left_offset: u32,   //ue(v)
+    pub frame_crop_right_offset: u32,  //ue(v)
+    pub frame_crop_top_offset: u32,    //ue(v)
+    pub frame_crop_bottom_offset: u32, //ue(v)
+
+    pub vui_parameters_present_flag: bool,
+    pub vui_parameters: VUIParameters,
+}
+
+impl SeqParameterSet {
+    pub fn new() -> SeqParameterSet {
+        SeqParameterSet {
+            available: false,
+            profile_idc: 0,
+            constraint_set0_flag: false,
             constraint_set1_flag: false,
             constraint_set2_flag: false,
             constraint_set3_flag: false,
@@ -5332,6 +5983,31 @@ impl SubsetSPS {
         }
     }
 
+    /// True for the profiles that carry the MVCD SPS extension (Annex I and J)
+    pub fn is_mvcd(&self) -> bool {
+        self.sps.profile_idc == 135 || self.sps.profile_idc == 138 || self.sps.profile_idc == 139
+    }
+
+    /// The number of inter-view references of each view, indexed by view order
+    /// index, as [anchor l0, anchor l1, non-anchor l0, non-anchor l1]
+    pub fn inter_view_ref_counts(&self) -> [Vec<u32>; 4] {
+        if self.is_mvcd() {
+            [
+                self.sps_mvcd.num_anchor_refs_l0.clone(),
+                self.sps_mvcd.num_anchor_refs_l1.clone(),
+                self.sps_mvcd.num_non_anchor_refs_l0.clone(),
+                self.sps_mvcd.num_non_anchor_refs_l1.clone(),
+            ]
+        } else {
+            [
+                self.sps_mvc.num_anchor_refs_l0.clone(),
+                self.sps_mvc.num_anchor_refs_l1.clone(),
+                self.sps_mvc.num_non_anchor_refs_l0.clone(),
+                self.sps_mvc.num_non_anchor_refs_l1.clone(),
+            ]
+        }
+    }
+
     pub fn encoder_pretty_print(&self) {
         self.sps.encoder_pretty_print();
 
@@ -5527,6 +6203,14 @@ pub const UUID_APPLE3: [u8; 16] = [
     0x23, 0xF2, 0x8D, 0xDC, 0xE2, 0xC3, 0x46, 0x56, 0xBC, 0x51, 0x57, 0xA5, 0x1C, 0xDE, 0x4F, 0xDE,
 ];
 // maybe I have the endian-ness wrong and it's 5646c3e2dc8df223 and de4fde1ca55751bc: 5646c3e2dc8df223de4fde1ca55751bc
+/// x264 encoder version and options string
+pub const UUID_X264: [u8; 16] = [
+    0xDC, 0x45, 0xE9, 0xBD, 0xE6, 0xD9, 0x48, 0xB7, 0x96, 0x2C, 0xD8, 0x20, 0xD9, 0x23, 0xEE, 0xEF,
+];
+/// MISB ST 0604 precision time stamp ("MISPmicrosectime")
+pub const UUID_MISB_MICROSECTIME: [u8; 16] = [
+    0x4D, 0x49, 0x53, 0x50, 0x6D, 0x69, 0x63, 0x72, 0x6F, 0x73, 0x65, 0x63, 0x74, 0x69, 0x6D, 0x65,
+];
 
 /// UUID: 0x0387F44ECD0A4BDCA1943AC3D49B171F (recovered from AppleD5500.kext)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -5587,6 +6271,154 @@ impl Default for SEIUnregisteredDataApple2 {
     }
 }
 
+/// SEI Type 2; Described in Annex D.2.4
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIPanScanRect {
+    pub pan_scan_rect_id: u32, // ue(v)
+    pub pan_scan_rect_cancel_flag: bool,
+    pub pan_scan_cnt_minus1: u32,              // ue(v)
+    pub pan_scan_rect_left_offset: Vec<i32>,   // se(v)
+    pub pan_scan_rect_right_offset: Vec<i32>,  // se(v)
+    pub pan_scan_rect_top_offset: Vec<i32>,    // se(v)
+    pub pan_scan_rect_bottom_offset: Vec<i32>, // se(v)
+    pub pan_scan_rect_repetition_period: u32,  // ue(v)
+}
+
+impl SEIPanScanRect {
+    pub fn new() -> SEIPanScanRect {
+        SEIPanScanRect {
+            pan_scan_rect_id: 0,
+            pan_scan_rect_cancel_flag: false,
+            pan_scan_cnt_minus1: 0,
+            pan_scan_rect_left_offset: Vec::new(),
+            pan_scan_rect_right_offset: Vec::new(),
+            pan_scan_rect_top_offset: Vec::new(),
+            pan_scan_rect_bottom_offset: Vec::new(),
+            pan_scan_rect_repetition_period: 0,
+        }
+    }
+}
+
+impl Default for SEIPanScanRect {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 3; Described in Annex D.2.5
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIFillerPayload {
+    pub ff_byte: Vec<u8>, // f(8), each equal to 0xFF
+}
+
+impl SEIFillerPayload {
+    pub fn new() -> SEIFillerPayload {
+        SEIFillerPayload {
+            ff_byte: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIFillerPayload {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// ITU-T T.35 country code for the United States
+pub const ITU_T_T35_COUNTRY_CODE_US: u8 = 0xB5;
+/// ITU-T T.35 terminal provider code assigned to ATSC
+pub const ITU_T_T35_PROVIDER_CODE_ATSC: u16 = 0x0031;
+/// ATSC A/53 Part 4 user_identifier for caption and bar data ("GA94")
+pub const ATSC_USER_IDENTIFIER_GA94: u32 = 0x4741_3934;
+/// ATSC A/53 Part 4 user_data_type_code for cc_data()
+pub const ATSC_USER_DATA_TYPE_CC_DATA: u8 = 0x03;
+
+/// Closed caption data; Described in ATSC A/53 Part 4 Table 6.9 and CEA-708
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIATSCCCData {
+    pub process_em_data_flag: bool, // u(1)
+    pub process_cc_data_flag: bool, // u(1)
+    pub additional_data_flag: bool, // u(1)
+    pub cc_count: u8,               // u(5)
+    pub em_data: u8,                // u(8)
+    pub cc_marker_bits: Vec<u8>,    // u(5), each equal to 0x1F
+    pub cc_valid: Vec<bool>,        // u(1)
+    pub cc_type: Vec<u8>, // u(2); 0 and 1 are CEA-608 fields, 2 and 3 are CEA-708 DTVCC packet data
+    pub cc_data_1: Vec<u8>, // u(8)
+    pub cc_data_2: Vec<u8>, // u(8)
+    pub marker_bits: u8,  // u(8), equal to 0xFF
+}
+
+impl SEIATSCCCData {
+    pub fn new() -> SEIATSCCCData {
+        SEIATSCCCData {
+            process_em_data_flag: false,
+            process_cc_data_flag: false,
+            additional_data_flag: false,
+            cc_count: 0,
+            em_data: 0,
+            cc_marker_bits: Vec::new(),
+            cc_valid: Vec::new(),
+            cc_type: Vec::new(),
+            cc_data_1: Vec::new(),
+            cc_data_2: Vec::new(),
+            marker_bits: 0,
+        }
+    }
+}
+
+impl Default for SEIATSCCCData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 4; Described in Annex D.2.6
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIUserDataRegisteredITUTT35 {
+    pub itu_t_t35_country_code: u8,                // u(8)
+    pub itu_t_t35_country_code_extension_byte: u8, // u(8); only present if the country code is 0xFF
+    pub itu_t_t35_provider_code: u16,              // u(16)
+    pub atsc_user_identifier: u32,                 // u(32); only present for the ATSC provider code
+    pub atsc_user_data_type_code: u8,              // u(8); only present for the ATSC provider code
+    pub cc_data: SEIATSCCCData,                    // only present for GA94 cc_data()
+    pub itu_t_t35_payload_byte: Vec<u8>,           // b(8); any remaining bytes
+}
+
+impl SEIUserDataRegisteredITUTT35 {
+    pub fn new() -> SEIUserDataRegisteredITUTT35 {
+        SEIUserDataRegisteredITUTT35 {
+            itu_t_t35_country_code: 0,
+            itu_t_t35_country_code_extension_byte: 0,
+            itu_t_t35_provider_code: 0,
+            atsc_user_identifier: 0,
+            atsc_user_data_type_code: 0,
+            cc_data: SEIATSCCCData::new(),
+            itu_t_t35_payload_byte: Vec::new(),
+        }
+    }
+
+    /// True if the payload follows the ATSC A/53 Part 4 layout
+    pub fn is_atsc(&self) -> bool {
+        self.itu_t_t35_country_code == ITU_T_T35_COUNTRY_CODE_US
+            && self.itu_t_t35_provider_code == ITU_T_T35_PROVIDER_CODE_ATSC
+    }
+
+    /// True if the payload carries ATSC cc_data()
+    pub fn has_cc_data(&self) -> bool {
+        self.is_atsc()
+            && self.atsc_user_identifier == ATSC_USER_IDENTIFIER_GA94
+            && self.atsc_user_data_type_code == ATSC_USER_DATA_TYPE_CC_DATA
+    }
+}
+
+impl Default for SEIUserDataRegisteredITUTT35 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// SEI Type 5; Described in Annex D.2.7
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SEIUserDataUnregistered {
@@ -5666,38 +6498,326 @@ impl Default for SEIRecoveryPoint {
     }
 }
 
-/// SEI Type 19; Described in Annex D.2.21
+/// SEI Type 7; Described in Annex D.2.9
+///
+/// The dec_ref_pic_marking() syntax is stored in the same parallel vector layout
+/// as the slice header, with original_idr_flag taking the role of IdrPicFlag
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SEIFilmGrainCharacteristics {
-    pub film_grain_characteristics_cancel_flag: bool,
-    pub film_grain_model_id: u8, // u(2)
-    pub separate_colour_description_present_flag: bool,
-    pub film_grain_bit_depth_luma_minus8: u8,   // u(3)
-    pub film_grain_bit_depth_chroma_minus8: u8, // u(3)
-    pub film_grain_full_range_flag: bool,
-    pub film_grain_colour_primaries: u8,         // u(8)
-    pub film_grain_transfer_characteristics: u8, // u(8)
-    pub film_grain_matrix_coefficients: u8,      // u(8)
-    pub blending_mode_id: u8,                    // u(2)
-    pub log2_scale_factor: u8,                   // u(4)
-    pub comp_model_present_flag: Vec<bool>,
-    pub num_intensity_intervals_minus1: Vec<u8>, // u(8)
-    pub num_model_values_minus1: Vec<u8>,        // u(3)
-    pub intensity_interval_lower_bound: Vec<Vec<u8>>, // u(8)
-    pub intensity_interval_upper_bound: Vec<Vec<u8>>, // u(8)
-    pub comp_model_value: Vec<Vec<Vec<i32>>>,    // se(v)
-    pub film_grain_characteristics_repetition_period: u32, // ue(v)
+pub struct SEIDecRefPicMarkingRepetition {
+    pub original_idr_flag: bool,
+    pub original_frame_num: u32, // ue(v)
+    pub original_field_pic_flag: bool,
+    pub original_bottom_field_flag: bool,
+    // dec_ref_pic_marking()
+    pub no_output_of_prior_pics_flag: bool,
+    pub long_term_reference_flag: bool,
+    pub adaptive_ref_pic_marking_mode_flag: bool,
+    pub memory_management_control_operation: Vec<u32>, // ue(v)
+    pub difference_of_pic_nums_minus1: Vec<u32>,       // ue(v)
+    pub long_term_pic_num: Vec<u32>,                   // ue(v)
+    pub long_term_frame_idx: Vec<u32>,                 // ue(v)
+    pub max_long_term_frame_idx_plus1: Vec<u32>,       // ue(v)
 }
 
-impl SEIFilmGrainCharacteristics {
-    pub fn new() -> SEIFilmGrainCharacteristics {
-        SEIFilmGrainCharacteristics {
-            film_grain_characteristics_cancel_flag: false,
-            film_grain_model_id: 0,
-            separate_colour_description_present_flag: false,
-            film_grain_bit_depth_luma_minus8: 0,
-            film_grain_bit_depth_chroma_minus8: 0,
-            film_grain_full_range_flag: false,
+impl SEIDecRefPicMarkingRepetition {
+    pub fn new() -> SEIDecRefPicMarkingRepetition {
+        SEIDecRefPicMarkingRepetition {
+            original_idr_flag: false,
+            original_frame_num: 0,
+            original_field_pic_flag: false,
+            original_bottom_field_flag: false,
+            no_output_of_prior_pics_flag: false,
+            long_term_reference_flag: false,
+            adaptive_ref_pic_marking_mode_flag: false,
+            memory_management_control_operation: Vec::new(),
+            difference_of_pic_nums_minus1: Vec::new(),
+            long_term_pic_num: Vec::new(),
+            long_term_frame_idx: Vec::new(),
+            max_long_term_frame_idx_plus1: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIDecRefPicMarkingRepetition {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 8; Described in Annex D.2.10
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEISparePic {
+    pub target_frame_num: u32, // ue(v)
+    pub spare_field_flag: bool,
+    pub target_bottom_field_flag: bool,
+    pub num_spare_pics_minus1: u32,      // ue(v)
+    pub delta_spare_frame_num: Vec<u32>, // ue(v)
+    pub spare_bottom_field_flag: Vec<bool>,
+    pub spare_area_idc: Vec<u32>,        // ue(v)
+    pub spare_unit_flag: Vec<Vec<bool>>, // one flag per map unit when spare_area_idc is 1
+    pub zero_run_length: Vec<Vec<u32>>, // ue(v); runs until PicSizeInMapUnits when spare_area_idc is 2
+}
+
+impl SEISparePic {
+    pub fn new() -> SEISparePic {
+        SEISparePic {
+            target_frame_num: 0,
+            spare_field_flag: false,
+            target_bottom_field_flag: false,
+            num_spare_pics_minus1: 0,
+            delta_spare_frame_num: Vec::new(),
+            spare_bottom_field_flag: Vec::new(),
+            spare_area_idc: Vec::new(),
+            spare_unit_flag: Vec::new(),
+            zero_run_length: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEISparePic {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 9; Described in Annex D.2.11
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEISceneInfo {
+    pub scene_info_present_flag: bool,
+    pub scene_id: u32,              // ue(v)
+    pub scene_transition_type: u32, // ue(v)
+    pub second_scene_id: u32,       // ue(v)
+}
+
+impl SEISceneInfo {
+    pub fn new() -> SEISceneInfo {
+        SEISceneInfo {
+            scene_info_present_flag: false,
+            scene_id: 0,
+            scene_transition_type: 0,
+            second_scene_id: 0,
+        }
+    }
+}
+
+impl Default for SEISceneInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 10; Described in Annex D.2.12
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEISubSeqInfo {
+    pub sub_seq_layer_num: u32, // ue(v)
+    pub sub_seq_id: u32,        // ue(v)
+    pub first_ref_pic_flag: bool,
+    pub leading_non_ref_pic_flag: bool,
+    pub last_pic_flag: bool,
+    pub sub_seq_frame_num_flag: bool,
+    pub sub_seq_frame_num: u32, // ue(v)
+}
+
+impl SEISubSeqInfo {
+    pub fn new() -> SEISubSeqInfo {
+        SEISubSeqInfo {
+            sub_seq_layer_num: 0,
+            sub_seq_id: 0,
+            first_ref_pic_flag: false,
+            leading_non_ref_pic_flag: false,
+            last_pic_flag: false,
+            sub_seq_frame_num_flag: false,
+            sub_seq_frame_num: 0,
+        }
+    }
+}
+
+impl Default for SEISubSeqInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 11; Described in Annex D.2.13
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEISubSeqLayerCharacteristics {
+    pub num_sub_seq_layers_minus1: u32, // ue(v)
+    pub accurate_statistics_flag: Vec<bool>,
+    pub average_bit_rate: Vec<u32>,   // u(16)
+    pub average_frame_rate: Vec<u32>, // u(16)
+}
+
+impl SEISubSeqLayerCharacteristics {
+    pub fn new() -> SEISubSeqLayerCharacteristics {
+        SEISubSeqLayerCharacteristics {
+            num_sub_seq_layers_minus1: 0,
+            accurate_statistics_flag: Vec::new(),
+            average_bit_rate: Vec::new(),
+            average_frame_rate: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEISubSeqLayerCharacteristics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 12; Described in Annex D.2.14
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEISubSeqCharacteristics {
+    pub sub_seq_layer_num: u32, // ue(v)
+    pub sub_seq_id: u32,        // ue(v)
+    pub duration_flag: bool,
+    pub sub_seq_duration: u32, // u(32)
+    pub average_rate_flag: bool,
+    pub accurate_statistics_flag: bool,
+    pub average_bit_rate: u32,           // u(16)
+    pub average_frame_rate: u32,         // u(16)
+    pub num_referenced_subseqs: u32,     // ue(v)
+    pub ref_sub_seq_layer_num: Vec<u32>, // ue(v)
+    pub ref_sub_seq_id: Vec<u32>,        // ue(v)
+    pub ref_sub_seq_direction: Vec<bool>,
+}
+
+impl SEISubSeqCharacteristics {
+    pub fn new() -> SEISubSeqCharacteristics {
+        SEISubSeqCharacteristics {
+            sub_seq_layer_num: 0,
+            sub_seq_id: 0,
+            duration_flag: false,
+            sub_seq_duration: 0,
+            average_rate_flag: false,
+            accurate_statistics_flag: false,
+            average_bit_rate: 0,
+            average_frame_rate: 0,
+            num_referenced_subseqs: 0,
+            ref_sub_seq_layer_num: Vec::new(),
+            ref_sub_seq_id: Vec::new(),
+            ref_sub_seq_direction: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEISubSeqCharacteristics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 13; Described in Annex D.2.15
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIFullFrameFreeze {
+    pub full_frame_freeze_repetition_period: u32, // ue(v)
+}
+
+impl SEIFullFrameFreeze {
+    pub fn new() -> SEIFullFrameFreeze {
+        SEIFullFrameFreeze {
+            full_frame_freeze_repetition_period: 0,
+        }
+    }
+}
+
+impl Default for SEIFullFrameFreeze {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 15; Described in Annex D.2.17
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIFullFrameSnapshot {
+    pub snapshot_id: u32, // ue(v)
+}
+
+impl SEIFullFrameSnapshot {
+    pub fn new() -> SEIFullFrameSnapshot {
+        SEIFullFrameSnapshot { snapshot_id: 0 }
+    }
+}
+
+impl Default for SEIFullFrameSnapshot {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 16; Described in Annex D.2.18
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIProgressiveRefinementSegmentStart {
+    pub progressive_refinement_id: u32,   // ue(v)
+    pub num_refinement_steps_minus1: u32, // ue(v)
+}
+
+impl SEIProgressiveRefinementSegmentStart {
+    pub fn new() -> SEIProgressiveRefinementSegmentStart {
+        SEIProgressiveRefinementSegmentStart {
+            progressive_refinement_id: 0,
+            num_refinement_steps_minus1: 0,
+        }
+    }
+}
+
+impl Default for SEIProgressiveRefinementSegmentStart {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 17; Described in Annex D.2.19
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIProgressiveRefinementSegmentEnd {
+    pub progressive_refinement_id: u32, // ue(v)
+}
+
+impl SEIProgressiveRefinementSegmentEnd {
+    pub fn new() -> SEIProgressiveRefinementSegmentEnd {
+        SEIProgressiveRefinementSegmentEnd {
+            progressive_refinement_id: 0,
+        }
+    }
+}
+
+impl Default for SEIProgressiveRefinementSegmentEnd {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 19; Described in Annex D.2.21
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIFilmGrainCharacteristics {
+    pub film_grain_characteristics_cancel_flag: bool,
+    pub film_grain_model_id: u8, // u(2)
+    pub separate_colour_description_present_flag: bool,
+    pub film_grain_bit_depth_luma_minus8: u8,   // u(3)
+    pub film_grain_bit_depth_chroma_minus8: u8, // u(3)
+    pub film_grain_full_range_flag: bool,
+    pub film_grain_colour_primaries: u8,         // u(8)
+    pub film_grain_transfer_characteristics: u8, // u(8)
+    pub film_grain_matrix_coefficients: u8,      // u(8)
+    pub blending_mode_id: u8,                    // u(2)
+    pub log2_scale_factor: u8,                   // u(4)
+    pub comp_model_present_flag: Vec<bool>,
+    pub num_intensity_intervals_minus1: Vec<u8>, // u(8)
+    pub num_model_values_minus1: Vec<u8>,        // u(3)
+    pub intensity_interval_lower_bound: Vec<Vec<u8>>, // u(8)
+    pub intensity_interval_upper_bound: Vec<Vec<u8>>, // u(8)
+    pub comp_model_value: Vec<Vec<Vec<i32>>>,    // se(v)
+    pub film_grain_characteristics_repetition_period: u32, // ue(v)
+}
+
+impl SEIFilmGrainCharacteristics {
+    pub fn new() -> SEIFilmGrainCharacteristics {
+        SEIFilmGrainCharacteristics {
+            film_grain_characteristics_cancel_flag: false,
+            film_grain_model_id: 0,
+            separate_colour_description_present_flag: false,
+            film_grain_bit_depth_luma_minus8: 0,
+            film_grain_bit_depth_chroma_minus8: 0,
+            film_grain_full_range_flag: false,
             film_grain_colour_primaries: 0,
             film_grain_transfer_characteristics: 0,
             film_grain_matrix_coefficients: 0,
@@ -5813,115 +6933,2144 @@ impl Default for SEIFilmGrainCharacteristics {
     }
 }
 
-/// SEI Type 45; Described in Annex D.2.26
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-pub struct SEIFramePacking {}
-
-impl SEIFramePacking {
-    pub fn new() -> SEIFramePacking {
-        SEIFramePacking {}
-    }
-
-    #[allow(dead_code)]
-    pub fn encoder_pretty_print(&self) {}
-}
-
-impl Default for SEIFramePacking {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
-/// SEI Payload
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SEIPayload {
-    pub available: bool,                      // Enabled if the SEI has been parsed
-    pub buffering_period: SEIBufferingPeriod, // SEI type 0
-    pub pic_timing: SEIPicTiming,             // SEI type 1
-    pub unregistered_user_data: SEIUserDataUnregistered, // SEI type 5
-    pub recovery_point: SEIRecoveryPoint,     // SEI type 6
-    pub film_grain_characteristics: SEIFilmGrainCharacteristics, // SEI Type 19
-    pub frame_packing: SEIFramePacking,       // SEI type 45
-}
-
-impl SEIPayload {
-    pub fn new() -> SEIPayload {
-        SEIPayload {
-            available: false,
-            buffering_period: SEIBufferingPeriod::new(),
-            pic_timing: SEIPicTiming::new(),
-            unregistered_user_data: SEIUserDataUnregistered::new(),
-            recovery_point: SEIRecoveryPoint::new(),
-            film_grain_characteristics: SEIFilmGrainCharacteristics::new(),
-            frame_packing: SEIFramePacking::new(),
-        }
-    }
-}
-
-/// NALU Type 6 -- SEI
+/// SEI Type 23; Described in Annex D.2.25
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct SEINalu {
-    // Each SEI can have multiple types so keep one data structure for each NALU SEI
-    pub payload_type: Vec<u32>,
-    pub payload_size: Vec<u32>,
-    // embed specific SEI type into here
-    pub payload: Vec<SEIPayload>,
+pub struct SEIToneMappingInfo {
+    pub tone_map_id: u32, // ue(v)
+    pub tone_map_cancel_flag: bool,
+    pub tone_map_repetition_period: u32, // ue(v)
+    pub coded_data_bit_depth: u8,        // u(8)
+    pub target_bit_depth: u8,            // u(8)
+    pub tone_map_model_id: u32,          // ue(v)
+    // tone_map_model_id == 0
+    pub min_value: u32, // u(32)
+    pub max_value: u32, // u(32)
+    // tone_map_model_id == 1
+    pub sigmoid_midpoint: u32, // u(32)
+    pub sigmoid_width: u32,    // u(32)
+    // tone_map_model_id == 2
+    pub start_of_coded_interval: Vec<u32>, // u(v)
+    // tone_map_model_id == 3
+    pub num_pivots: u32,              // u(16)
+    pub coded_pivot_value: Vec<u32>,  // u(v)
+    pub target_pivot_value: Vec<u32>, // u(v)
+    // tone_map_model_id == 4
+    pub camera_iso_speed_idc: u8,    // u(8)
+    pub camera_iso_speed_value: u32, // u(32)
+    pub exposure_index_idc: u8,      // u(8)
+    pub exposure_index_value: u32,   // u(32)
+    pub exposure_compensation_value_sign_flag: bool,
+    pub exposure_compensation_value_numerator: u32, // u(16)
+    pub exposure_compensation_value_denom_idc: u32, // u(16)
+    pub ref_screen_luminance_white: u32,            // u(32)
+    pub extended_range_white_level: u32,            // u(32)
+    pub nominal_black_level_luma_code_value: u32,   // u(16)
+    pub nominal_white_level_luma_code_value: u32,   // u(16)
+    pub extended_white_level_luma_code_value: u32,  // u(16)
 }
 
-impl SEINalu {
-    pub fn new() -> SEINalu {
-        SEINalu {
-            payload_type: Vec::new(),
-            payload_size: Vec::new(),
-            payload: Vec::new(),
+impl SEIToneMappingInfo {
+    pub fn new() -> SEIToneMappingInfo {
+        SEIToneMappingInfo {
+            tone_map_id: 0,
+            tone_map_cancel_flag: false,
+            tone_map_repetition_period: 0,
+            coded_data_bit_depth: 0,
+            target_bit_depth: 0,
+            tone_map_model_id: 0,
+            min_value: 0,
+            max_value: 0,
+            sigmoid_midpoint: 0,
+            sigmoid_width: 0,
+            start_of_coded_interval: Vec::new(),
+            num_pivots: 0,
+            coded_pivot_value: Vec::new(),
+            target_pivot_value: Vec::new(),
+            camera_iso_speed_idc: 0,
+            camera_iso_speed_value: 0,
+            exposure_index_idc: 0,
+            exposure_index_value: 0,
+            exposure_compensation_value_sign_flag: false,
+            exposure_compensation_value_numerator: 0,
+            exposure_compensation_value_denom_idc: 0,
+            ref_screen_luminance_white: 0,
+            extended_range_white_level: 0,
+            nominal_black_level_luma_code_value: 0,
+            nominal_white_level_luma_code_value: 0,
+            extended_white_level_luma_code_value: 0,
         }
     }
-
-    #[allow(dead_code)]
-    pub fn encoder_pretty_print(&self) {}
 }
 
-impl Default for SEINalu {
+impl Default for SEIToneMappingInfo {
     fn default() -> Self {
         Self::new()
     }
 }
 
-/// NALU Type 9 -- AUD
+/// SEI Type 45; Described in Annex D.2.26
 ///
-/// NALU Type 9 used to indicate the type of slices present in a primary
-/// coded picture and used by decoder to simplify the detection of the
-/// boundary between access units
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct AccessUnitDelim {
-    // Table 7-5 – Meaning of primary_pic_type
-    // primary_pic_type    | slice_type values that may be present in the primary coded picture
-    // ------------------------------------------------------------------------------------------
-    //       0             | 2, 7
-    //       1             | 0, 2, 5, 7
-    //       2             | 0, 1, 2, 5, 6, 7
-    //       3             | 4, 9
-    //       4             | 3, 4, 8, 9
-    //       5             | 2, 4, 7, 9
-    //       6             | 0, 2, 3, 4, 5, 7, 8, 9
-    //       7             | 0, 1, 2, 3, 4, 5, 6, 7, 8, 9
-    pub primary_pic_type: u8, // u(3)
+/// Older JSON dumps stored this as an empty object, hence the struct-level default
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SEIFramePacking {
+    pub frame_packing_arrangement_id: u32, // ue(v)
+    pub frame_packing_arrangement_cancel_flag: bool,
+    pub frame_packing_arrangement_type: u8, // u(7)
+    pub quincunx_sampling_flag: bool,
+    pub content_interpretation_type: u8, // u(6)
+    pub spatial_flipping_flag: bool,
+    pub frame0_flipped_flag: bool,
+    pub field_views_flag: bool,
+    pub current_frame_is_frame0_flag: bool,
+    pub frame0_self_contained_flag: bool,
+    pub frame1_self_contained_flag: bool,
+    pub frame0_grid_position_x: u8,                       // u(4)
+    pub frame0_grid_position_y: u8,                       // u(4)
+    pub frame1_grid_position_x: u8,                       // u(4)
+    pub frame1_grid_position_y: u8,                       // u(4)
+    pub frame_packing_arrangement_reserved_byte: u8,      // u(8)
+    pub frame_packing_arrangement_repetition_period: u32, // ue(v)
+    pub frame_packing_arrangement_extension_flag: bool,
 }
 
-impl AccessUnitDelim {
-    pub fn new() -> AccessUnitDelim {
-        AccessUnitDelim {
-            primary_pic_type: 0,
+impl SEIFramePacking {
+    pub fn new() -> SEIFramePacking {
+        SEIFramePacking {
+            frame_packing_arrangement_id: 0,
+            frame_packing_arrangement_cancel_flag: false,
+            frame_packing_arrangement_type: 0,
+            quincunx_sampling_flag: false,
+            content_interpretation_type: 0,
+            spatial_flipping_flag: false,
+            frame0_flipped_flag: false,
+            field_views_flag: false,
+            current_frame_is_frame0_flag: false,
+            frame0_self_contained_flag: false,
+            frame1_self_contained_flag: false,
+            frame0_grid_position_x: 0,
+            frame0_grid_position_y: 0,
+            frame1_grid_position_x: 0,
+            frame1_grid_position_y: 0,
+            frame_packing_arrangement_reserved_byte: 0,
+            frame_packing_arrangement_repetition_period: 0,
+            frame_packing_arrangement_extension_flag: false,
         }
     }
 
-    pub fn encoder_pretty_print(&self) {
-        encoder_formatted_print("AUD: primary_pic_type", self.primary_pic_type, 63);
+    /// The grid positions are only present when quincunx sampling is off and
+    /// the arrangement is not temporal interleaving (type 5)
+    pub fn grid_positions_present(&self) -> bool {
+        !self.quincunx_sampling_flag && self.frame_packing_arrangement_type != 5
     }
-}
 
-impl Default for AccessUnitDelim {
-    fn default() -> Self {
-        Self::new()
+    pub fn encoder_pretty_print(&self) {
+        encoder_formatted_print(
+            "SEI (Frame Packing): frame_packing_arrangement_id",
+            self.frame_packing_arrangement_id,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Frame Packing): frame_packing_arrangement_cancel_flag",
+            self.frame_packing_arrangement_cancel_flag,
+            63,
+        );
+        if !self.frame_packing_arrangement_cancel_flag {
+            encoder_formatted_print(
+                "SEI (Frame Packing): frame_packing_arrangement_type",
+                self.frame_packing_arrangement_type,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): quincunx_sampling_flag",
+                self.quincunx_sampling_flag,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): content_interpretation_type",
+                self.content_interpretation_type,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): spatial_flipping_flag",
+                self.spatial_flipping_flag,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): frame0_flipped_flag",
+                self.frame0_flipped_flag,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): field_views_flag",
+                self.field_views_flag,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): current_frame_is_frame0_flag",
+                self.current_frame_is_frame0_flag,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): frame0_self_contained_flag",
+                self.frame0_self_contained_flag,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): frame1_self_contained_flag",
+                self.frame1_self_contained_flag,
+                63,
+            );
+            if self.grid_positions_present() {
+                encoder_formatted_print(
+                    "SEI (Frame Packing): frame0_grid_position_x",
+                    self.frame0_grid_position_x,
+                    63,
+                );
+                encoder_formatted_print(
+                    "SEI (Frame Packing): frame0_grid_position_y",
+                    self.frame0_grid_position_y,
+                    63,
+                );
+                encoder_formatted_print(
+                    "SEI (Frame Packing): frame1_grid_position_x",
+                    self.frame1_grid_position_x,
+                    63,
+                );
+                encoder_formatted_print(
+                    "SEI (Frame Packing): frame1_grid_position_y",
+                    self.frame1_grid_position_y,
+                    63,
+                );
+            }
+            encoder_formatted_print(
+                "SEI (Frame Packing): frame_packing_arrangement_reserved_byte",
+                self.frame_packing_arrangement_reserved_byte,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Frame Packing): frame_packing_arrangement_repetition_period",
+                self.frame_packing_arrangement_repetition_period,
+                63,
+            );
+        }
+        encoder_formatted_print(
+            "SEI (Frame Packing): frame_packing_arrangement_extension_flag",
+            self.frame_packing_arrangement_extension_flag,
+            63,
+        );
+    }
+}
+
+impl Default for SEIFramePacking {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 47; Described in Annex D.2.27
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIDisplayOrientation {
+    pub display_orientation_cancel_flag: bool,
+    pub hor_flip: bool,
+    pub ver_flip: bool,
+    pub anticlockwise_rotation: u32, // u(16) ; in units of 2^-16 * 360 degrees
+    pub display_orientation_repetition_period: u32, // ue(v)
+    pub display_orientation_extension_flag: bool,
+}
+
+impl SEIDisplayOrientation {
+    pub fn new() -> SEIDisplayOrientation {
+        SEIDisplayOrientation {
+            display_orientation_cancel_flag: false,
+            hor_flip: false,
+            ver_flip: false,
+            anticlockwise_rotation: 0,
+            display_orientation_repetition_period: 0,
+            display_orientation_extension_flag: false,
+        }
+    }
+
+    pub fn encoder_pretty_print(&self) {
+        encoder_formatted_print(
+            "SEI (Display Orientation): display_orientation_cancel_flag",
+            self.display_orientation_cancel_flag,
+            63,
+        );
+        if !self.display_orientation_cancel_flag {
+            encoder_formatted_print("SEI (Display Orientation): hor_flip", self.hor_flip, 63);
+            encoder_formatted_print("SEI (Display Orientation): ver_flip", self.ver_flip, 63);
+            encoder_formatted_print(
+                "SEI (Display Orientation): anticlockwise_rotation",
+                self.anticlockwise_rotation,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Display Orientation): display_orientation_repetition_period",
+                self.display_orientation_repetition_period,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Display Orientation): display_orientation_extension_flag",
+                self.display_orientation_extension_flag,
+                63,
+            );
+        }
+    }
+}
+
+impl Default for SEIDisplayOrientation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 137; Described in Annex D.2.29
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIMasteringDisplayColourVolume {
+    pub display_primaries_x: [u32; 3],        // u(16)
+    pub display_primaries_y: [u32; 3],        // u(16)
+    pub white_point_x: u32,                   // u(16)
+    pub white_point_y: u32,                   // u(16)
+    pub max_display_mastering_luminance: u32, // u(32)
+    pub min_display_mastering_luminance: u32, // u(32)
+}
+
+impl SEIMasteringDisplayColourVolume {
+    pub fn new() -> SEIMasteringDisplayColourVolume {
+        SEIMasteringDisplayColourVolume {
+            display_primaries_x: [0; 3],
+            display_primaries_y: [0; 3],
+            white_point_x: 0,
+            white_point_y: 0,
+            max_display_mastering_luminance: 0,
+            min_display_mastering_luminance: 0,
+        }
+    }
+}
+
+impl Default for SEIMasteringDisplayColourVolume {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 142; Described in Annex D.2.30
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIColourRemappingInfo {
+    pub colour_remap_id: u32, // ue(v)
+    pub colour_remap_cancel_flag: bool,
+    pub colour_remap_repetition_period: u32, // ue(v)
+    pub colour_remap_video_signal_info_present_flag: bool,
+    pub colour_remap_full_range_flag: bool,
+    pub colour_remap_primaries: u8,           // u(8)
+    pub colour_remap_transfer_function: u8,   // u(8)
+    pub colour_remap_matrix_coefficients: u8, // u(8)
+    pub colour_remap_input_bit_depth: u8,     // u(8)
+    pub colour_remap_output_bit_depth: u8,    // u(8)
+    pub pre_lut_num_val_minus1: [u8; 3],      // u(8)
+    pub pre_lut_coded_value: Vec<Vec<u32>>,   // u(v)
+    pub pre_lut_target_value: Vec<Vec<u32>>,  // u(v)
+    pub colour_remap_matrix_present_flag: bool,
+    pub log2_matrix_denom: u8,                // u(4)
+    pub colour_remap_coeffs: [[i32; 3]; 3],   // se(v)
+    pub post_lut_num_val_minus1: [u8; 3],     // u(8)
+    pub post_lut_coded_value: Vec<Vec<u32>>,  // u(v)
+    pub post_lut_target_value: Vec<Vec<u32>>, // u(v)
+}
+
+impl SEIColourRemappingInfo {
+    pub fn new() -> SEIColourRemappingInfo {
+        SEIColourRemappingInfo {
+            colour_remap_id: 0,
+            colour_remap_cancel_flag: false,
+            colour_remap_repetition_period: 0,
+            colour_remap_video_signal_info_present_flag: false,
+            colour_remap_full_range_flag: false,
+            colour_remap_primaries: 0,
+            colour_remap_transfer_function: 0,
+            colour_remap_matrix_coefficients: 0,
+            colour_remap_input_bit_depth: 0,
+            colour_remap_output_bit_depth: 0,
+            pre_lut_num_val_minus1: [0; 3],
+            pre_lut_coded_value: Vec::new(),
+            pre_lut_target_value: Vec::new(),
+            colour_remap_matrix_present_flag: false,
+            log2_matrix_denom: 0,
+            colour_remap_coeffs: [[0; 3]; 3],
+            post_lut_num_val_minus1: [0; 3],
+            post_lut_coded_value: Vec::new(),
+            post_lut_target_value: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIColourRemappingInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 144; Described in Annex D.2.31
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIContentLightLevelInfo {
+    pub max_content_light_level: u32,     // u(16)
+    pub max_pic_average_light_level: u32, // u(16)
+}
+
+impl SEIContentLightLevelInfo {
+    pub fn new() -> SEIContentLightLevelInfo {
+        SEIContentLightLevelInfo {
+            max_content_light_level: 0,
+            max_pic_average_light_level: 0,
+        }
+    }
+}
+
+impl Default for SEIContentLightLevelInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 147; Described in Annex D.2.32
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIAlternativeTransferCharacteristics {
+    pub preferred_transfer_characteristics: u8, // u(8)
+}
+
+impl SEIAlternativeTransferCharacteristics {
+    pub fn new() -> SEIAlternativeTransferCharacteristics {
+        SEIAlternativeTransferCharacteristics {
+            preferred_transfer_characteristics: 0,
+        }
+    }
+}
+
+impl Default for SEIAlternativeTransferCharacteristics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 149; Described in Annex D.2.33
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIContentColourVolume {
+    pub ccv_cancel_flag: bool,
+    pub ccv_persistence_flag: bool,
+    pub ccv_primaries_present_flag: bool,
+    pub ccv_min_luminance_value_present_flag: bool,
+    pub ccv_max_luminance_value_present_flag: bool,
+    pub ccv_avg_luminance_value_present_flag: bool,
+    pub ccv_reserved_zero_2bits: u8,  // u(2)
+    pub ccv_primaries_x: [i32; 3],    // i(32)
+    pub ccv_primaries_y: [i32; 3],    // i(32)
+    pub ccv_min_luminance_value: u32, // u(32)
+    pub ccv_max_luminance_value: u32, // u(32)
+    pub ccv_avg_luminance_value: u32, // u(32)
+}
+
+impl SEIContentColourVolume {
+    pub fn new() -> SEIContentColourVolume {
+        SEIContentColourVolume {
+            ccv_cancel_flag: false,
+            ccv_persistence_flag: false,
+            ccv_primaries_present_flag: false,
+            ccv_min_luminance_value_present_flag: false,
+            ccv_max_luminance_value_present_flag: false,
+            ccv_avg_luminance_value_present_flag: false,
+            ccv_reserved_zero_2bits: 0,
+            ccv_primaries_x: [0; 3],
+            ccv_primaries_y: [0; 3],
+            ccv_min_luminance_value: 0,
+            ccv_max_luminance_value: 0,
+            ccv_avg_luminance_value: 0,
+        }
+    }
+}
+
+impl Default for SEIContentColourVolume {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 150; Described in Annex D.2.35.1
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIEquirectangularProjection {
+    pub erp_cancel_flag: bool,
+    pub erp_persistence_flag: bool,
+    pub erp_padding_flag: bool,
+    pub erp_reserved_zero_2bits: u8, // u(2)
+    pub gb_erp_type: u8,             // u(3)
+    pub left_gb_erp_width: u8,       // u(8)
+    pub right_gb_erp_width: u8,      // u(8)
+}
+
+impl SEIEquirectangularProjection {
+    pub fn new() -> SEIEquirectangularProjection {
+        SEIEquirectangularProjection {
+            erp_cancel_flag: false,
+            erp_persistence_flag: false,
+            erp_padding_flag: false,
+            erp_reserved_zero_2bits: 0,
+            gb_erp_type: 0,
+            left_gb_erp_width: 0,
+            right_gb_erp_width: 0,
+        }
+    }
+}
+
+impl Default for SEIEquirectangularProjection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 151; Described in Annex D.2.35.2
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEICubemapProjection {
+    pub cmp_cancel_flag: bool,
+    pub cmp_persistence_flag: bool,
+}
+
+impl SEICubemapProjection {
+    pub fn new() -> SEICubemapProjection {
+        SEICubemapProjection {
+            cmp_cancel_flag: false,
+            cmp_persistence_flag: false,
+        }
+    }
+}
+
+impl Default for SEICubemapProjection {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 154; Described in Annex D.2.35.3
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEISphereRotation {
+    pub sphere_rotation_cancel_flag: bool,
+    pub sphere_rotation_persistence_flag: bool,
+    pub sphere_rotation_reserved_zero_6bits: u8, // u(6)
+    pub yaw_rotation: i32,                       // i(32)
+    pub pitch_rotation: i32,                     // i(32)
+    pub roll_rotation: i32,                      // i(32)
+}
+
+impl SEISphereRotation {
+    pub fn new() -> SEISphereRotation {
+        SEISphereRotation {
+            sphere_rotation_cancel_flag: false,
+            sphere_rotation_persistence_flag: false,
+            sphere_rotation_reserved_zero_6bits: 0,
+            yaw_rotation: 0,
+            pitch_rotation: 0,
+            roll_rotation: 0,
+        }
+    }
+}
+
+impl Default for SEISphereRotation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 155; Described in Annex D.2.35.4
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIRegionwisePacking {
+    pub rwp_cancel_flag: bool,
+    pub rwp_persistence_flag: bool,
+    pub constituent_picture_matching_flag: bool,
+    pub rwp_reserved_zero_5bits: u8,      // u(5)
+    pub num_packed_regions: u8,           // u(8)
+    pub proj_picture_width: u32,          // u(32)
+    pub proj_picture_height: u32,         // u(32)
+    pub packed_picture_width: u32,        // u(16)
+    pub packed_picture_height: u32,       // u(16)
+    pub rwp_reserved_zero_4bits: Vec<u8>, // u(4)
+    pub rwp_transform_type: Vec<u8>,      // u(3)
+    pub rwp_guard_band_flag: Vec<bool>,
+    pub proj_region_width: Vec<u32>,    // u(32)
+    pub proj_region_height: Vec<u32>,   // u(32)
+    pub proj_region_top: Vec<u32>,      // u(32)
+    pub proj_region_left: Vec<u32>,     // u(32)
+    pub packed_region_width: Vec<u32>,  // u(16)
+    pub packed_region_height: Vec<u32>, // u(16)
+    pub packed_region_top: Vec<u32>,    // u(16)
+    pub packed_region_left: Vec<u32>,   // u(16)
+    // Only read when rwp_guard_band_flag[i] is set; zeroed otherwise
+    pub rwp_left_guard_band_width: Vec<u8>,    // u(8)
+    pub rwp_right_guard_band_width: Vec<u8>,   // u(8)
+    pub rwp_top_guard_band_height: Vec<u8>,    // u(8)
+    pub rwp_bottom_guard_band_height: Vec<u8>, // u(8)
+    pub rwp_guard_band_not_used_for_pred_flag: Vec<bool>,
+    pub rwp_guard_band_type: Vec<[u8; 4]>,           // u(3)
+    pub rwp_guard_band_reserved_zero_3bits: Vec<u8>, // u(3)
+}
+
+impl SEIRegionwisePacking {
+    pub fn new() -> SEIRegionwisePacking {
+        SEIRegionwisePacking {
+            rwp_cancel_flag: false,
+            rwp_persistence_flag: false,
+            constituent_picture_matching_flag: false,
+            rwp_reserved_zero_5bits: 0,
+            num_packed_regions: 0,
+            proj_picture_width: 0,
+            proj_picture_height: 0,
+            packed_picture_width: 0,
+            packed_picture_height: 0,
+            rwp_reserved_zero_4bits: Vec::new(),
+            rwp_transform_type: Vec::new(),
+            rwp_guard_band_flag: Vec::new(),
+            proj_region_width: Vec::new(),
+            proj_region_height: Vec::new(),
+            proj_region_top: Vec::new(),
+            proj_region_left: Vec::new(),
+            packed_region_width: Vec::new(),
+            packed_region_height: Vec::new(),
+            packed_region_top: Vec::new(),
+            packed_region_left: Vec::new(),
+            rwp_left_guard_band_width: Vec::new(),
+            rwp_right_guard_band_width: Vec::new(),
+            rwp_top_guard_band_height: Vec::new(),
+            rwp_bottom_guard_band_height: Vec::new(),
+            rwp_guard_band_not_used_for_pred_flag: Vec::new(),
+            rwp_guard_band_type: Vec::new(),
+            rwp_guard_band_reserved_zero_3bits: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIRegionwisePacking {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 156; Described in Annex D.2.35.5
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIOmniViewport {
+    pub omni_viewport_id: u32, // u(10)
+    pub omni_viewport_cancel_flag: bool,
+    pub omni_viewport_persistence_flag: bool,
+    pub omni_viewport_cnt_minus1: u8,             // u(4)
+    pub omni_viewport_azimuth_centre: Vec<i32>,   // i(32)
+    pub omni_viewport_elevation_centre: Vec<i32>, // i(32)
+    pub omni_viewport_tilt_centre: Vec<i32>,      // i(32)
+    pub omni_viewport_hor_range: Vec<u32>,        // u(32)
+    pub omni_viewport_ver_range: Vec<u32>,        // u(32)
+}
+
+impl SEIOmniViewport {
+    pub fn new() -> SEIOmniViewport {
+        SEIOmniViewport {
+            omni_viewport_id: 0,
+            omni_viewport_cancel_flag: false,
+            omni_viewport_persistence_flag: false,
+            omni_viewport_cnt_minus1: 0,
+            omni_viewport_azimuth_centre: Vec::new(),
+            omni_viewport_elevation_centre: Vec::new(),
+            omni_viewport_tilt_centre: Vec::new(),
+            omni_viewport_hor_range: Vec::new(),
+            omni_viewport_ver_range: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIOmniViewport {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 181; Described in Annex I.13.2.6
+///
+/// Camera parameters that are not present for a view are stored as default so
+/// the per-view vectors stay aligned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIAlternativeDepthInfo {
+    pub depth_type: u32,                       // ue(v)
+    pub num_constituent_views_gvd_minus1: u32, // ue(v)
+    pub depth_present_gvd_flag: bool,
+    pub z_gvd_flag: bool,
+    pub intrinsic_param_gvd_flag: bool,
+    pub rotation_gvd_flag: bool,
+    pub translation_gvd_flag: bool,
+    pub gvd_z_near: Vec<SEIDepthRepresentationElement>,
+    pub gvd_z_far: Vec<SEIDepthRepresentationElement>,
+    pub prec_gvd_focal_length: u32,      // ue(v)
+    pub prec_gvd_principal_point: u32,   // ue(v)
+    pub prec_gvd_rotation_param: u32,    // ue(v)
+    pub prec_gvd_translation_param: u32, // ue(v)
+    pub gvd_focal_length_x: Vec<SEIFloatValue>,
+    pub gvd_focal_length_y: Vec<SEIFloatValue>,
+    pub gvd_principal_point_x: Vec<SEIFloatValue>,
+    pub gvd_principal_point_y: Vec<SEIFloatValue>,
+    pub gvd_r: Vec<[[SEIFloatValue; 3]; 3]>,
+    pub gvd_t_x: Vec<SEIFloatValue>,
+}
+
+impl SEIAlternativeDepthInfo {
+    pub fn new() -> SEIAlternativeDepthInfo {
+        SEIAlternativeDepthInfo {
+            depth_type: 0,
+            num_constituent_views_gvd_minus1: 0,
+            depth_present_gvd_flag: false,
+            z_gvd_flag: false,
+            intrinsic_param_gvd_flag: false,
+            rotation_gvd_flag: false,
+            translation_gvd_flag: false,
+            gvd_z_near: Vec::new(),
+            gvd_z_far: Vec::new(),
+            prec_gvd_focal_length: 0,
+            prec_gvd_principal_point: 0,
+            prec_gvd_rotation_param: 0,
+            prec_gvd_translation_param: 0,
+            gvd_focal_length_x: Vec::new(),
+            gvd_focal_length_y: Vec::new(),
+            gvd_principal_point_x: Vec::new(),
+            gvd_principal_point_y: Vec::new(),
+            gvd_r: Vec::new(),
+            gvd_t_x: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIAlternativeDepthInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 24; Described in Annex G.13.2.1
+///
+/// Elements that are not present for a layer are stored as 0 (or false) so the
+/// per-layer vectors stay aligned with layer_id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIScalabilityInfo {
+    pub temporal_id_nesting_flag: bool,
+    pub priority_layer_info_present_flag: bool,
+    pub priority_id_setting_flag: bool,
+    pub num_layers_minus1: u32, // ue(v)
+    pub layer_id: Vec<u32>,     // ue(v)
+    pub priority_id: Vec<u8>,   // u(6)
+    pub discardable_flag: Vec<bool>,
+    pub dependency_id: Vec<u8>, // u(3)
+    pub quality_id: Vec<u8>,    // u(4)
+    pub temporal_id: Vec<u8>,   // u(3)
+    pub sub_pic_layer_flag: Vec<bool>,
+    pub sub_region_layer_flag: Vec<bool>,
+    pub iroi_division_info_present_flag: Vec<bool>,
+    pub profile_level_info_present_flag: Vec<bool>,
+    pub bitrate_info_present_flag: Vec<bool>,
+    pub frm_rate_info_present_flag: Vec<bool>,
+    pub frm_size_info_present_flag: Vec<bool>,
+    pub layer_dependency_info_present_flag: Vec<bool>,
+    pub parameter_sets_info_present_flag: Vec<bool>,
+    pub bitstream_restriction_info_present_flag: Vec<bool>,
+    pub exact_inter_layer_pred_flag: Vec<bool>,
+    pub exact_sample_value_match_flag: Vec<bool>,
+    pub layer_conversion_flag: Vec<bool>,
+    pub layer_output_flag: Vec<bool>,
+    pub layer_profile_level_idc: Vec<u32>,          // u(24)
+    pub avg_bitrate: Vec<u32>,                      // u(16)
+    pub max_bitrate_layer: Vec<u32>,                // u(16)
+    pub max_bitrate_layer_representation: Vec<u32>, // u(16)
+    pub max_bitrate_calc_window: Vec<u32>,          // u(16)
+    pub constant_frm_rate_idc: Vec<u8>,             // u(2)
+    pub avg_frm_rate: Vec<u32>,                     // u(16)
+    pub frm_width_in_mbs_minus1: Vec<u32>,          // ue(v)
+    pub frm_height_in_mbs_minus1: Vec<u32>,         // ue(v)
+    pub base_region_layer_id: Vec<u32>,             // ue(v)
+    pub dynamic_rect_flag: Vec<bool>,
+    pub horizontal_offset: Vec<u32>, // u(16)
+    pub vertical_offset: Vec<u32>,   // u(16)
+    pub region_width: Vec<u32>,      // u(16)
+    pub region_height: Vec<u32>,     // u(16)
+    pub roi_id: Vec<u32>,            // ue(v)
+    pub iroi_grid_flag: Vec<bool>,
+    pub grid_width_in_mbs_minus1: Vec<u32>,      // ue(v)
+    pub grid_height_in_mbs_minus1: Vec<u32>,     // ue(v)
+    pub num_rois_minus1: Vec<u32>,               // ue(v)
+    pub first_mb_in_roi: Vec<Vec<u32>>,          // ue(v)
+    pub roi_width_in_mbs_minus1: Vec<Vec<u32>>,  // ue(v)
+    pub roi_height_in_mbs_minus1: Vec<Vec<u32>>, // ue(v)
+    pub num_directly_dependent_layers: Vec<u32>, // ue(v)
+    pub directly_dependent_layer_id_delta_minus1: Vec<Vec<u32>>, // ue(v)
+    pub layer_dependency_info_src_layer_id_delta: Vec<u32>, // ue(v)
+    pub num_seq_parameter_sets: Vec<u32>,        // ue(v)
+    pub seq_parameter_set_id_delta: Vec<Vec<u32>>, // ue(v)
+    pub num_subset_seq_parameter_sets: Vec<u32>, // ue(v)
+    pub subset_seq_parameter_set_id_delta: Vec<Vec<u32>>, // ue(v)
+    pub num_pic_parameter_sets_minus1: Vec<u32>, // ue(v)
+    pub pic_parameter_set_id_delta: Vec<Vec<u32>>, // ue(v)
+    pub parameter_sets_info_src_layer_id_delta: Vec<u32>, // ue(v)
+    pub motion_vectors_over_pic_boundaries_flag: Vec<bool>,
+    pub max_bytes_per_pic_denom: Vec<u32>,       // ue(v)
+    pub max_bits_per_mb_denom: Vec<u32>,         // ue(v)
+    pub log2_max_mv_length_horizontal: Vec<u32>, // ue(v)
+    pub log2_max_mv_length_vertical: Vec<u32>,   // ue(v)
+    pub max_num_reorder_frames: Vec<u32>,        // ue(v)
+    pub max_dec_frame_buffering: Vec<u32>,       // ue(v)
+    pub conversion_type_idc: Vec<u32>,           // ue(v)
+    pub rewriting_info_flag: Vec<[bool; 2]>,
+    pub rewriting_profile_level_idc: Vec<[u32; 2]>, // u(24)
+    pub rewriting_avg_bitrate: Vec<[u32; 2]>,       // u(16)
+    pub rewriting_max_bitrate: Vec<[u32; 2]>,       // u(16)
+    pub pr_num_dids_minus1: u32,                    // ue(v) ; pr_num_dIds_minus1
+    pub pr_dependency_id: Vec<u8>,                  // u(3)
+    pub pr_num_minus1: Vec<u32>,                    // ue(v)
+    pub pr_id: Vec<Vec<u32>>,                       // ue(v)
+    pub pr_profile_level_idc: Vec<Vec<u32>>,        // u(24)
+    pub pr_avg_bitrate: Vec<Vec<u32>>,              // u(16)
+    pub pr_max_bitrate: Vec<Vec<u32>>,              // u(16)
+    pub priority_id_setting_uri: Vec<u8>,           // b(8) ; without the terminating null byte
+}
+
+impl SEIScalabilityInfo {
+    pub fn new() -> SEIScalabilityInfo {
+        SEIScalabilityInfo {
+            temporal_id_nesting_flag: false,
+            priority_layer_info_present_flag: false,
+            priority_id_setting_flag: false,
+            num_layers_minus1: 0,
+            layer_id: Vec::new(),
+            priority_id: Vec::new(),
+            discardable_flag: Vec::new(),
+            dependency_id: Vec::new(),
+            quality_id: Vec::new(),
+            temporal_id: Vec::new(),
+            sub_pic_layer_flag: Vec::new(),
+            sub_region_layer_flag: Vec::new(),
+            iroi_division_info_present_flag: Vec::new(),
+            profile_level_info_present_flag: Vec::new(),
+            bitrate_info_present_flag: Vec::new(),
+            frm_rate_info_present_flag: Vec::new(),
+            frm_size_info_present_flag: Vec::new(),
+            layer_dependency_info_present_flag: Vec::new(),
+            parameter_sets_info_present_flag: Vec::new(),
+            bitstream_restriction_info_present_flag: Vec::new(),
+            exact_inter_layer_pred_flag: Vec::new(),
+            exact_sample_value_match_flag: Vec::new(),
+            layer_conversion_flag: Vec::new(),
+            layer_output_flag: Vec::new(),
+            layer_profile_level_idc: Vec::new(),
+            avg_bitrate: Vec::new(),
+            max_bitrate_layer: Vec::new(),
+            max_bitrate_layer_representation: Vec::new(),
+            max_bitrate_calc_window: Vec::new(),
+            constant_frm_rate_idc: Vec::new(),
+            avg_frm_rate: Vec::new(),
+            frm_width_in_mbs_minus1: Vec::new(),
+            frm_height_in_mbs_minus1: Vec::new(),
+            base_region_layer_id: Vec::new(),
+            dynamic_rect_flag: Vec::new(),
+            horizontal_offset: Vec::new(),
+            vertical_offset: Vec::new(),
+            region_width: Vec::new(),
+            region_height: Vec::new(),
+            roi_id: Vec::new(),
+            iroi_grid_flag: Vec::new(),
+            grid_width_in_mbs_minus1: Vec::new(),
+            grid_height_in_mbs_minus1: Vec::new(),
+            num_rois_minus1: Vec::new(),
+            first_mb_in_roi: Vec::new(),
+            roi_width_in_mbs_minus1: Vec::new(),
+            roi_height_in_mbs_minus1: Vec::new(),
+            num_directly_dependent_layers: Vec::new(),
+            directly_dependent_layer_id_delta_minus1: Vec::new(),
+            layer_dependency_info_src_layer_id_delta: Vec::new(),
+            num_seq_parameter_sets: Vec::new(),
+            seq_parameter_set_id_delta: Vec::new(),
+            num_subset_seq_parameter_sets: Vec::new(),
+            subset_seq_parameter_set_id_delta: Vec::new(),
+            num_pic_parameter_sets_minus1: Vec::new(),
+            pic_parameter_set_id_delta: Vec::new(),
+            parameter_sets_info_src_layer_id_delta: Vec::new(),
+            motion_vectors_over_pic_boundaries_flag: Vec::new(),
+            max_bytes_per_pic_denom: Vec::new(),
+            max_bits_per_mb_denom: Vec::new(),
+            log2_max_mv_length_horizontal: Vec::new(),
+            log2_max_mv_length_vertical: Vec::new(),
+            max_num_reorder_frames: Vec::new(),
+            max_dec_frame_buffering: Vec::new(),
+            conversion_type_idc: Vec::new(),
+            rewriting_info_flag: Vec::new(),
+            rewriting_profile_level_idc: Vec::new(),
+            rewriting_avg_bitrate: Vec::new(),
+            rewriting_max_bitrate: Vec::new(),
+            pr_num_dids_minus1: 0,
+            pr_dependency_id: Vec::new(),
+            pr_num_minus1: Vec::new(),
+            pr_id: Vec::new(),
+            pr_profile_level_idc: Vec::new(),
+            pr_avg_bitrate: Vec::new(),
+            pr_max_bitrate: Vec::new(),
+            priority_id_setting_uri: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIScalabilityInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 25; Described in Annex G.13.2.2
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEISubPicScalableLayer {
+    pub layer_id: u32, // ue(v)
+}
+
+impl SEISubPicScalableLayer {
+    pub fn new() -> SEISubPicScalableLayer {
+        SEISubPicScalableLayer { layer_id: 0 }
+    }
+}
+
+impl Default for SEISubPicScalableLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 26; Described in Annex G.13.2.3
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEINonRequiredLayerRep {
+    pub num_info_entries_minus1: u32,                       // ue(v)
+    pub entry_dependency_id: Vec<u8>,                       // u(3)
+    pub num_non_required_layer_reps_minus1: Vec<u32>,       // ue(v)
+    pub non_required_layer_rep_dependency_id: Vec<Vec<u8>>, // u(3)
+    pub non_required_layer_rep_quality_id: Vec<Vec<u8>>,    // u(4)
+}
+
+impl SEINonRequiredLayerRep {
+    pub fn new() -> SEINonRequiredLayerRep {
+        SEINonRequiredLayerRep {
+            num_info_entries_minus1: 0,
+            entry_dependency_id: Vec::new(),
+            num_non_required_layer_reps_minus1: Vec::new(),
+            non_required_layer_rep_dependency_id: Vec::new(),
+            non_required_layer_rep_quality_id: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEINonRequiredLayerRep {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 27; Described in Annex G.13.2.4
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIPriorityLayerInfo {
+    pub pr_dependency_id: u8,     // u(3)
+    pub num_priority_ids: u8,     // u(4)
+    pub alt_priority_id: Vec<u8>, // u(6)
+}
+
+impl SEIPriorityLayerInfo {
+    pub fn new() -> SEIPriorityLayerInfo {
+        SEIPriorityLayerInfo {
+            pr_dependency_id: 0,
+            num_priority_ids: 0,
+            alt_priority_id: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIPriorityLayerInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 28; Described in Annex G.13.2.5
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEILayersNotPresent {
+    pub num_layers: u32,    // ue(v)
+    pub layer_id: Vec<u32>, // ue(v)
+}
+
+impl SEILayersNotPresent {
+    pub fn new() -> SEILayersNotPresent {
+        SEILayersNotPresent {
+            num_layers: 0,
+            layer_id: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEILayersNotPresent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 29; Described in Annex G.13.2.6
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEILayerDependencyChange {
+    pub num_layers_minus1: u32, // ue(v)
+    pub layer_id: Vec<u32>,     // ue(v)
+    pub layer_dependency_info_present_flag: Vec<bool>,
+    pub num_directly_dependent_layers: Vec<u32>, // ue(v)
+    pub directly_dependent_layer_id_delta_minus1: Vec<Vec<u32>>, // ue(v)
+    pub layer_dependency_info_src_layer_id_delta_minus1: Vec<u32>, // ue(v)
+}
+
+impl SEILayerDependencyChange {
+    pub fn new() -> SEILayerDependencyChange {
+        SEILayerDependencyChange {
+            num_layers_minus1: 0,
+            layer_id: Vec::new(),
+            layer_dependency_info_present_flag: Vec::new(),
+            num_directly_dependent_layers: Vec::new(),
+            directly_dependent_layer_id_delta_minus1: Vec::new(),
+            layer_dependency_info_src_layer_id_delta_minus1: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEILayerDependencyChange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 30; Described in Annex G.13.2.7
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIScalableNesting {
+    pub all_layer_representations_in_au_flag: bool,
+    pub num_layer_representations_minus1: u32, // ue(v)
+    pub sei_dependency_id: Vec<u8>,            // u(3)
+    pub sei_quality_id: Vec<u8>,               // u(4)
+    pub sei_temporal_id: u8,                   // u(3)
+    pub nested_sei: SEINalu,                   // sei_message() until the end of the payload
+}
+
+impl SEIScalableNesting {
+    pub fn new() -> SEIScalableNesting {
+        SEIScalableNesting {
+            all_layer_representations_in_au_flag: false,
+            num_layer_representations_minus1: 0,
+            sei_dependency_id: Vec::new(),
+            sei_quality_id: Vec::new(),
+            sei_temporal_id: 0,
+            nested_sei: SEINalu::new(),
+        }
+    }
+}
+
+impl Default for SEIScalableNesting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Types 31 and 44; Described in Annex G.13.2.8 and H.13.2.9
+///
+/// The base layer (Annex G) and base view (Annex H) temporal HRD messages share
+/// the same syntax, only the element names carry an sei_mvc_ prefix in Annex H
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEITemporalHRD {
+    pub num_of_temporal_layers_minus1: u32, // ue(v)
+    pub sei_temporal_id: Vec<u8>,           // u(3)
+    pub sei_timing_info_present_flag: Vec<bool>,
+    pub sei_num_units_in_tick: Vec<u32>, // u(32)
+    pub sei_time_scale: Vec<u32>,        // u(32)
+    pub sei_fixed_frame_rate_flag: Vec<bool>,
+    pub sei_nal_hrd_parameters_present_flag: Vec<bool>,
+    pub sei_nal_hrd_parameters: Vec<HRDParameters>,
+    pub sei_vcl_hrd_parameters_present_flag: Vec<bool>,
+    pub sei_vcl_hrd_parameters: Vec<HRDParameters>,
+    pub sei_low_delay_hrd_flag: Vec<bool>,
+    pub sei_pic_struct_present_flag: Vec<bool>,
+}
+
+impl SEITemporalHRD {
+    pub fn new() -> SEITemporalHRD {
+        SEITemporalHRD {
+            num_of_temporal_layers_minus1: 0,
+            sei_temporal_id: Vec::new(),
+            sei_timing_info_present_flag: Vec::new(),
+            sei_num_units_in_tick: Vec::new(),
+            sei_time_scale: Vec::new(),
+            sei_fixed_frame_rate_flag: Vec::new(),
+            sei_nal_hrd_parameters_present_flag: Vec::new(),
+            sei_nal_hrd_parameters: Vec::new(),
+            sei_vcl_hrd_parameters_present_flag: Vec::new(),
+            sei_vcl_hrd_parameters: Vec::new(),
+            sei_low_delay_hrd_flag: Vec::new(),
+            sei_pic_struct_present_flag: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEITemporalHRD {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 32; Described in Annex G.13.2.9
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIQualityLayerIntegrityCheck {
+    pub num_info_entries_minus1: u32, // ue(v)
+    pub entry_dependency_id: Vec<u8>, // u(3)
+    pub quality_layer_crc: Vec<u32>,  // u(16)
+}
+
+impl SEIQualityLayerIntegrityCheck {
+    pub fn new() -> SEIQualityLayerIntegrityCheck {
+        SEIQualityLayerIntegrityCheck {
+            num_info_entries_minus1: 0,
+            entry_dependency_id: Vec::new(),
+            quality_layer_crc: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIQualityLayerIntegrityCheck {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 33; Described in Annex G.13.2.10
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIRedundantPicProperty {
+    pub num_dids_minus1: u32,                         // ue(v) ; num_dIds_minus1
+    pub dependency_id: Vec<u8>,                       // u(3)
+    pub num_qids_minus1: Vec<u32>,                    // ue(v) ; num_qIds_minus1
+    pub quality_id: Vec<Vec<u8>>,                     // u(4)
+    pub num_redundant_pics_minus1: Vec<Vec<u32>>,     // ue(v)
+    pub redundant_pic_cnt_minus1: Vec<Vec<Vec<u32>>>, // ue(v)
+    pub pic_match_flag: Vec<Vec<Vec<bool>>>,
+    pub mb_type_match_flag: Vec<Vec<Vec<bool>>>,
+    pub motion_match_flag: Vec<Vec<Vec<bool>>>,
+    pub residual_match_flag: Vec<Vec<Vec<bool>>>,
+    pub intra_samples_match_flag: Vec<Vec<Vec<bool>>>,
+}
+
+impl SEIRedundantPicProperty {
+    pub fn new() -> SEIRedundantPicProperty {
+        SEIRedundantPicProperty {
+            num_dids_minus1: 0,
+            dependency_id: Vec::new(),
+            num_qids_minus1: Vec::new(),
+            quality_id: Vec::new(),
+            num_redundant_pics_minus1: Vec::new(),
+            redundant_pic_cnt_minus1: Vec::new(),
+            pic_match_flag: Vec::new(),
+            mb_type_match_flag: Vec::new(),
+            motion_match_flag: Vec::new(),
+            residual_match_flag: Vec::new(),
+            intra_samples_match_flag: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIRedundantPicProperty {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 34; Described in Annex G.13.2.11
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEITl0DepRepIndex {
+    pub tl0_dep_rep_idx: u32,      // u(8)
+    pub effective_idr_pic_id: u32, // u(16)
+}
+
+impl SEITl0DepRepIndex {
+    pub fn new() -> SEITl0DepRepIndex {
+        SEITl0DepRepIndex {
+            tl0_dep_rep_idx: 0,
+            effective_idr_pic_id: 0,
+        }
+    }
+}
+
+impl Default for SEITl0DepRepIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 35; Described in Annex G.13.2.12
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEITlSwitchingPoint {
+    pub delta_frame_num: i32, // se(v)
+}
+
+impl SEITlSwitchingPoint {
+    pub fn new() -> SEITlSwitchingPoint {
+        SEITlSwitchingPoint { delta_frame_num: 0 }
+    }
+}
+
+impl Default for SEITlSwitchingPoint {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 36; Described in Annex H.13.2.1
+///
+/// The delays are indexed by view order index as in the MVC SPS extension, so
+/// index 0 is left empty. anchor_pic_flag comes from the VCL NAL units of the
+/// access unit; the decoder infers it from the payload length
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIParallelDecodingInfo {
+    pub seq_parameter_set_id: u32, // ue(v)
+    pub anchor_pic_flag: bool,
+    pub pdi_init_delay_anchor_minus2_l0: Vec<Vec<u32>>, // ue(v)
+    pub pdi_init_delay_anchor_minus2_l1: Vec<Vec<u32>>, // ue(v)
+    pub pdi_init_delay_non_anchor_minus2_l0: Vec<Vec<u32>>, // ue(v)
+    pub pdi_init_delay_non_anchor_minus2_l1: Vec<Vec<u32>>, // ue(v)
+}
+
+impl SEIParallelDecodingInfo {
+    pub fn new() -> SEIParallelDecodingInfo {
+        SEIParallelDecodingInfo {
+            seq_parameter_set_id: 0,
+            anchor_pic_flag: false,
+            pdi_init_delay_anchor_minus2_l0: Vec::new(),
+            pdi_init_delay_anchor_minus2_l1: Vec::new(),
+            pdi_init_delay_non_anchor_minus2_l0: Vec::new(),
+            pdi_init_delay_non_anchor_minus2_l1: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIParallelDecodingInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Types 37 and 48; Described in Annex H.13.2.2 and I.13.2.2
+///
+/// The MVCD scalable nesting message adds view applicability and depth/texture
+/// flags to the MVC syntax; those elements are ignored for payload type 37
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIMVCScalableNesting {
+    pub operation_point_flag: bool,
+    pub all_view_components_in_au_flag: bool,
+    pub num_view_components_minus1: u32,        // ue(v)
+    pub sei_view_id: Vec<u32>,                  // u(10)
+    pub sei_view_applicability_flag: Vec<bool>, // MVCD only
+    pub sei_op_texture_only_flag: bool,         // MVCD only
+    pub num_view_components_op_minus1: u32,     // ue(v)
+    pub sei_op_view_id: Vec<u32>,               // u(10)
+    pub sei_op_depth_flag: Vec<bool>,           // MVCD only
+    pub sei_op_texture_flag: Vec<bool>,         // MVCD only
+    pub sei_op_temporal_id: u8,                 // u(3)
+    pub nested_sei: SEINalu,                    // sei_message() until the end of the payload
+}
+
+impl SEIMVCScalableNesting {
+    pub fn new() -> SEIMVCScalableNesting {
+        SEIMVCScalableNesting {
+            operation_point_flag: false,
+            all_view_components_in_au_flag: false,
+            num_view_components_minus1: 0,
+            sei_view_id: Vec::new(),
+            sei_view_applicability_flag: Vec::new(),
+            sei_op_texture_only_flag: false,
+            num_view_components_op_minus1: 0,
+            sei_op_view_id: Vec::new(),
+            sei_op_depth_flag: Vec::new(),
+            sei_op_texture_flag: Vec::new(),
+            sei_op_temporal_id: 0,
+            nested_sei: SEINalu::new(),
+        }
+    }
+}
+
+impl Default for SEIMVCScalableNesting {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Types 38 and 49; Described in Annex H.13.2.3 and I.13.2.1
+///
+/// The MVCD view scalability information message adds mvcd_op_view_info() after
+/// each view_id; those elements are ignored for payload type 38
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIViewScalabilityInfo {
+    pub num_operation_points_minus1: u32,                    // ue(v)
+    pub operation_point_id: Vec<u32>,                        // ue(v)
+    pub priority_id: Vec<u8>,                                // u(5)
+    pub temporal_id: Vec<u8>,                                // u(3)
+    pub num_target_output_views_minus1: Vec<u32>,            // ue(v)
+    pub view_id: Vec<Vec<u32>>,                              // ue(v)
+    pub view_info_depth_view_present_flag: Vec<Vec<bool>>,   // MVCD only
+    pub mvcd_depth_view_flag: Vec<Vec<bool>>,                // MVCD only
+    pub view_info_texture_view_present_flag: Vec<Vec<bool>>, // MVCD only
+    pub mvcd_texture_view_flag: Vec<Vec<bool>>,              // MVCD only
+    pub profile_level_info_present_flag: Vec<bool>,
+    pub bitrate_info_present_flag: Vec<bool>,
+    pub frm_rate_info_present_flag: Vec<bool>,
+    pub view_dependency_info_present_flag: Vec<bool>,
+    pub parameter_sets_info_present_flag: Vec<bool>,
+    pub bitstream_restriction_info_present_flag: Vec<bool>,
+    pub op_profile_level_idc: Vec<u32>,                   // u(24)
+    pub avg_bitrate: Vec<u32>,                            // u(16)
+    pub max_bitrate: Vec<u32>,                            // u(16)
+    pub max_bitrate_calc_window: Vec<u32>,                // u(16)
+    pub constant_frm_rate_idc: Vec<u8>,                   // u(2)
+    pub avg_frm_rate: Vec<u32>,                           // u(16)
+    pub num_directly_dependent_views: Vec<u32>,           // ue(v)
+    pub directly_dependent_view_id: Vec<Vec<u32>>,        // ue(v)
+    pub view_dependency_info_src_op_id: Vec<u32>,         // ue(v)
+    pub num_seq_parameter_sets: Vec<u32>,                 // ue(v)
+    pub seq_parameter_set_id_delta: Vec<Vec<u32>>,        // ue(v)
+    pub num_subset_seq_parameter_sets: Vec<u32>,          // ue(v)
+    pub subset_seq_parameter_set_id_delta: Vec<Vec<u32>>, // ue(v)
+    pub num_pic_parameter_sets_minus1: Vec<u32>,          // ue(v)
+    pub pic_parameter_set_id_delta: Vec<Vec<u32>>,        // ue(v)
+    pub parameter_sets_info_src_op_id: Vec<u32>,          // ue(v)
+    pub motion_vectors_over_pic_boundaries_flag: Vec<bool>,
+    pub max_bytes_per_pic_denom: Vec<u32>,       // ue(v)
+    pub max_bits_per_mb_denom: Vec<u32>,         // ue(v)
+    pub log2_max_mv_length_horizontal: Vec<u32>, // ue(v)
+    pub log2_max_mv_length_vertical: Vec<u32>,   // ue(v)
+    pub max_num_reorder_frames: Vec<u32>,        // ue(v)
+    pub max_dec_frame_buffering: Vec<u32>,       // ue(v)
+}
+
+impl SEIViewScalabilityInfo {
+    pub fn new() -> SEIViewScalabilityInfo {
+        SEIViewScalabilityInfo {
+            num_operation_points_minus1: 0,
+            operation_point_id: Vec::new(),
+            priority_id: Vec::new(),
+            temporal_id: Vec::new(),
+            num_target_output_views_minus1: Vec::new(),
+            view_id: Vec::new(),
+            view_info_depth_view_present_flag: Vec::new(),
+            mvcd_depth_view_flag: Vec::new(),
+            view_info_texture_view_present_flag: Vec::new(),
+            mvcd_texture_view_flag: Vec::new(),
+            profile_level_info_present_flag: Vec::new(),
+            bitrate_info_present_flag: Vec::new(),
+            frm_rate_info_present_flag: Vec::new(),
+            view_dependency_info_present_flag: Vec::new(),
+            parameter_sets_info_present_flag: Vec::new(),
+            bitstream_restriction_info_present_flag: Vec::new(),
+            op_profile_level_idc: Vec::new(),
+            avg_bitrate: Vec::new(),
+            max_bitrate: Vec::new(),
+            max_bitrate_calc_window: Vec::new(),
+            constant_frm_rate_idc: Vec::new(),
+            avg_frm_rate: Vec::new(),
+            num_directly_dependent_views: Vec::new(),
+            directly_dependent_view_id: Vec::new(),
+            view_dependency_info_src_op_id: Vec::new(),
+            num_seq_parameter_sets: Vec::new(),
+            seq_parameter_set_id_delta: Vec::new(),
+            num_subset_seq_parameter_sets: Vec::new(),
+            subset_seq_parameter_set_id_delta: Vec::new(),
+            num_pic_parameter_sets_minus1: Vec::new(),
+            pic_parameter_set_id_delta: Vec::new(),
+            parameter_sets_info_src_op_id: Vec::new(),
+            motion_vectors_over_pic_boundaries_flag: Vec::new(),
+            max_bytes_per_pic_denom: Vec::new(),
+            max_bits_per_mb_denom: Vec::new(),
+            log2_max_mv_length_horizontal: Vec::new(),
+            log2_max_mv_length_vertical: Vec::new(),
+            max_num_reorder_frames: Vec::new(),
+            max_dec_frame_buffering: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIViewScalabilityInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 39; Described in Annex H.13.2.4
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIMultiviewSceneInfo {
+    pub max_disparity: u32, // ue(v)
+}
+
+impl SEIMultiviewSceneInfo {
+    pub fn new() -> SEIMultiviewSceneInfo {
+        SEIMultiviewSceneInfo { max_disparity: 0 }
+    }
+}
+
+impl Default for SEIMultiviewSceneInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sign, exponent and mantissa of a camera or display parameter. The mantissa
+/// length is derived from the exponent and the signalled precision
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SEIFloatValue {
+    pub sign: bool,    // u(1)
+    pub exponent: u8,  // u(6)
+    pub mantissa: u64, // u(v)
+}
+
+/// SEI Type 40; Described in Annex H.13.2.5
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIMultiviewAcquisitionInfo {
+    pub num_views_minus1: u32, // ue(v)
+    pub intrinsic_param_flag: bool,
+    pub extrinsic_param_flag: bool,
+    pub intrinsic_params_equal_flag: bool,
+    pub prec_focal_length: u32,    // ue(v)
+    pub prec_principal_point: u32, // ue(v)
+    pub prec_skew_factor: u32,     // ue(v)
+    pub focal_length_x: Vec<SEIFloatValue>,
+    pub focal_length_y: Vec<SEIFloatValue>,
+    pub principal_point_x: Vec<SEIFloatValue>,
+    pub principal_point_y: Vec<SEIFloatValue>,
+    pub skew_factor: Vec<SEIFloatValue>,
+    pub prec_rotation_param: u32,    // ue(v)
+    pub prec_translation_param: u32, // ue(v)
+    pub r: Vec<[[SEIFloatValue; 3]; 3]>,
+    pub t: Vec<[SEIFloatValue; 3]>,
+}
+
+impl SEIMultiviewAcquisitionInfo {
+    pub fn new() -> SEIMultiviewAcquisitionInfo {
+        SEIMultiviewAcquisitionInfo {
+            num_views_minus1: 0,
+            intrinsic_param_flag: false,
+            extrinsic_param_flag: false,
+            intrinsic_params_equal_flag: false,
+            prec_focal_length: 0,
+            prec_principal_point: 0,
+            prec_skew_factor: 0,
+            focal_length_x: Vec::new(),
+            focal_length_y: Vec::new(),
+            principal_point_x: Vec::new(),
+            principal_point_y: Vec::new(),
+            skew_factor: Vec::new(),
+            prec_rotation_param: 0,
+            prec_translation_param: 0,
+            r: Vec::new(),
+            t: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIMultiviewAcquisitionInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 41; Described in Annex H.13.2.6
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEINonRequiredViewComponent {
+    pub num_info_entries_minus1: u32,                      // ue(v)
+    pub view_order_index: Vec<u32>,                        // ue(v)
+    pub num_non_required_view_components_minus1: Vec<u32>, // ue(v)
+    pub index_delta_minus1: Vec<Vec<u32>>,                 // ue(v)
+}
+
+impl SEINonRequiredViewComponent {
+    pub fn new() -> SEINonRequiredViewComponent {
+        SEINonRequiredViewComponent {
+            num_info_entries_minus1: 0,
+            view_order_index: Vec::new(),
+            num_non_required_view_components_minus1: Vec::new(),
+            index_delta_minus1: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEINonRequiredViewComponent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 42; Described in Annex H.13.2.7
+///
+/// The flags are indexed by view order index as in the MVC SPS extension, so
+/// index 0 is left empty
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIViewDependencyChange {
+    pub seq_parameter_set_id: u32, // ue(v)
+    pub anchor_update_flag: bool,
+    pub non_anchor_update_flag: bool,
+    pub anchor_ref_l0_flag: Vec<Vec<bool>>,
+    pub anchor_ref_l1_flag: Vec<Vec<bool>>,
+    pub non_anchor_ref_l0_flag: Vec<Vec<bool>>,
+    pub non_anchor_ref_l1_flag: Vec<Vec<bool>>,
+}
+
+impl SEIViewDependencyChange {
+    pub fn new() -> SEIViewDependencyChange {
+        SEIViewDependencyChange {
+            seq_parameter_set_id: 0,
+            anchor_update_flag: false,
+            non_anchor_update_flag: false,
+            anchor_ref_l0_flag: Vec::new(),
+            anchor_ref_l1_flag: Vec::new(),
+            non_anchor_ref_l0_flag: Vec::new(),
+            non_anchor_ref_l1_flag: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIViewDependencyChange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 43; Described in Annex H.13.2.8
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIOperationPointsNotPresent {
+    pub num_operation_points: u32,                // ue(v)
+    pub operation_point_not_present_id: Vec<u32>, // ue(v)
+}
+
+impl SEIOperationPointsNotPresent {
+    pub fn new() -> SEIOperationPointsNotPresent {
+        SEIOperationPointsNotPresent {
+            num_operation_points: 0,
+            operation_point_not_present_id: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIOperationPointsNotPresent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 46; Described in Annex H.13.2.10
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIMultiviewViewPosition {
+    pub num_views_minus1: u32,   // ue(v)
+    pub view_position: Vec<u32>, // ue(v)
+    pub multiview_view_position_extension_flag: bool,
+}
+
+impl SEIMultiviewViewPosition {
+    pub fn new() -> SEIMultiviewViewPosition {
+        SEIMultiviewViewPosition {
+            num_views_minus1: 0,
+            view_position: Vec::new(),
+            multiview_view_position_extension_flag: false,
+        }
+    }
+}
+
+impl Default for SEIMultiviewViewPosition {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// depth_representation_sei_element(); Described in Annex I.13.2.3
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SEIDepthRepresentationElement {
+    pub da_sign_flag: bool,
+    pub da_exponent: u8,            // u(7)
+    pub da_mantissa_len_minus1: u8, // u(5)
+    pub da_mantissa: u32,           // u(v)
+}
+
+/// SEI Type 50; Described in Annex I.13.2.3
+///
+/// Elements that are not present for a view are stored as 0 (or default) so the
+/// per-view vectors stay aligned with depth_info_view_id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIDepthRepresentationInfo {
+    pub all_views_equal_flag: bool,
+    pub num_views_minus1: u32, // ue(v)
+    pub z_near_flag: bool,
+    pub z_far_flag: bool,
+    pub z_axis_equal_flag: bool,
+    pub common_z_axis_reference_view: u32, // ue(v)
+    pub d_min_flag: bool,
+    pub d_max_flag: bool,
+    pub depth_representation_type: u32,    // ue(v)
+    pub depth_info_view_id: Vec<u32>,       // ue(v)
+    pub z_axis_reference_view: Vec<u32>,    // ue(v)
+    pub disparity_reference_view: Vec<u32>, // ue(v)
+    pub z_near: Vec<SEIDepthRepresentationElement>,
+    pub z_far: Vec<SEIDepthRepresentationElement>,
+    pub d_min: Vec<SEIDepthRepresentationElement>,
+    pub d_max: Vec<SEIDepthRepresentationElement>,
+    pub depth_nonlinear_representation_num_minus1: u32, // ue(v)
+    pub depth_nonlinear_representation_model: Vec<u32>, // ue(v)
+}
+
+impl SEIDepthRepresentationInfo {
+    pub fn new() -> SEIDepthRepresentationInfo {
+        SEIDepthRepresentationInfo {
+            all_views_equal_flag: false,
+            num_views_minus1: 0,
+            z_near_flag: false,
+            z_far_flag: false,
+            z_axis_equal_flag: false,
+            common_z_axis_reference_view: 0,
+            d_min_flag: false,
+            d_max_flag: false,
+            depth_representation_type: 0,
+            depth_info_view_id: Vec::new(),
+            z_axis_reference_view: Vec::new(),
+            disparity_reference_view: Vec::new(),
+            z_near: Vec::new(),
+            z_far: Vec::new(),
+            d_min: Vec::new(),
+            d_max: Vec::new(),
+            depth_nonlinear_representation_num_minus1: 0,
+            depth_nonlinear_representation_model: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIDepthRepresentationInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 51; Described in Annex I.13.2.4
+///
+/// Viewing distances and sample shifts that are not present for a display are
+/// stored as 0 so the per-display vectors stay aligned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIThreeDimensionalReferenceDisplaysInfo {
+    pub prec_ref_baseline: u32,      // ue(v)
+    pub prec_ref_display_width: u32, // ue(v)
+    pub ref_viewing_distance_flag: bool,
+    pub prec_ref_viewing_dist: u32,                 // ue(v)
+    pub num_ref_displays_minus1: u32,               // ue(v)
+    pub exponent_ref_baseline: Vec<u8>,             // u(6)
+    pub mantissa_ref_baseline: Vec<u64>,            // u(v)
+    pub exponent_ref_display_width: Vec<u8>,        // u(6)
+    pub mantissa_ref_display_width: Vec<u64>,       // u(v)
+    pub exponent_ref_viewing_distance: Vec<u8>,     // u(6)
+    pub mantissa_ref_viewing_distance: Vec<u64>,    // u(v)
+    pub additional_shift_present_flag: Vec<bool>,
+    pub num_sample_shift_plus512: Vec<u32>, // u(10)
+    pub three_dimensional_reference_displays_extension_flag: bool,
+}
+
+impl SEIThreeDimensionalReferenceDisplaysInfo {
+    pub fn new() -> SEIThreeDimensionalReferenceDisplaysInfo {
+        SEIThreeDimensionalReferenceDisplaysInfo {
+            prec_ref_baseline: 0,
+            prec_ref_display_width: 0,
+            ref_viewing_distance_flag: false,
+            prec_ref_viewing_dist: 0,
+            num_ref_displays_minus1: 0,
+            exponent_ref_baseline: Vec::new(),
+            mantissa_ref_baseline: Vec::new(),
+            exponent_ref_display_width: Vec::new(),
+            mantissa_ref_display_width: Vec::new(),
+            exponent_ref_viewing_distance: Vec::new(),
+            mantissa_ref_viewing_distance: Vec::new(),
+            additional_shift_present_flag: Vec::new(),
+            num_sample_shift_plus512: Vec::new(),
+            three_dimensional_reference_displays_extension_flag: false,
+        }
+    }
+}
+
+impl Default for SEIThreeDimensionalReferenceDisplaysInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// depth_timing_offset(); Described in Annex I.13.2.5
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SEIDepthTimingOffset {
+    pub offset_len_minus1: u8,           // u(5)
+    pub depth_disp_delay_offset_fp: u32, // u(v)
+    pub depth_disp_delay_offset_dp: u8,  // u(6)
+}
+
+/// SEI Type 52; Described in Annex I.13.2.5
+///
+/// With per_view_depth_timing_flag there is one offset for each depth view of
+/// the active MVCD SPS, otherwise a single one
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIDepthTiming {
+    pub per_view_depth_timing_flag: bool,
+    pub depth_timing_offset: Vec<SEIDepthTimingOffset>,
+}
+
+impl SEIDepthTiming {
+    pub fn new() -> SEIDepthTiming {
+        SEIDepthTiming {
+            per_view_depth_timing_flag: false,
+            depth_timing_offset: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIDepthTiming {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// depth_grid_position(); Described in Annex I.13.2.7
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SEIDepthGridPosition {
+    pub depth_grid_pos_x_fp: u32, // u(20)
+    pub depth_grid_pos_x_dp: u8,  // u(4)
+    pub depth_grid_pos_x_sign_flag: bool,
+    pub depth_grid_pos_y_fp: u32, // u(20)
+    pub depth_grid_pos_y_dp: u8,  // u(4)
+    pub depth_grid_pos_y_sign_flag: bool,
+}
+
+/// SEI Type 53; Described in Annex I.13.2.7
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIDepthSamplingInfo {
+    pub dttsr_x_mul: u32, // u(16)
+    pub dttsr_x_dp: u8,   // u(4)
+    pub dttsr_y_mul: u32, // u(16)
+    pub dttsr_y_dp: u8,   // u(4)
+    pub per_view_depth_grid_pos_flag: bool,
+    pub num_video_plus_depth_views_minus1: u32, // ue(v)
+    pub depth_grid_view_id: Vec<u32>,           // ue(v)
+    pub depth_grid_position: Vec<SEIDepthGridPosition>,
+}
+
+impl SEIDepthSamplingInfo {
+    pub fn new() -> SEIDepthSamplingInfo {
+        SEIDepthSamplingInfo {
+            dttsr_x_mul: 0,
+            dttsr_x_dp: 0,
+            dttsr_y_mul: 0,
+            dttsr_y_dp: 0,
+            per_view_depth_grid_pos_flag: false,
+            num_video_plus_depth_views_minus1: 0,
+            depth_grid_view_id: Vec::new(),
+            depth_grid_position: Vec::new(),
+        }
+    }
+}
+
+impl Default for SEIDepthSamplingInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 54; Described in Annex J.13.2.1
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SEIConstrainedDepthParameterSetIdentifier {
+    pub max_dps_id: u32,      // ue(v)
+    pub max_dps_id_diff: u32, // ue(v)
+}
+
+impl SEIConstrainedDepthParameterSetIdentifier {
+    pub fn new() -> SEIConstrainedDepthParameterSetIdentifier {
+        SEIConstrainedDepthParameterSetIdentifier {
+            max_dps_id: 0,
+            max_dps_id_diff: 0,
+        }
+    }
+}
+
+impl Default for SEIConstrainedDepthParameterSetIdentifier {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEIPayload {
+    pub available: bool,                      // Enabled if the SEI has been parsed
+    pub buffering_period: SEIBufferingPeriod, // SEI type 0
+    pub pic_timing: SEIPicTiming,             // SEI type 1
+    #[serde(default)]
+    pub pan_scan_rect: SEIPanScanRect, // SEI type 2
+    #[serde(default)]
+    pub filler_payload: SEIFillerPayload, // SEI type 3
+    #[serde(default)]
+    pub registered_user_data: SEIUserDataRegisteredITUTT35, // SEI type 4
+    pub unregistered_user_data: SEIUserDataUnregistered, // SEI type 5
+    pub recovery_point: SEIRecoveryPoint,     // SEI type 6
+    #[serde(default)]
+    pub dec_ref_pic_marking_repetition: SEIDecRefPicMarkingRepetition, // SEI type 7
+    #[serde(default)]
+    pub spare_pic: SEISparePic, // SEI type 8
+    #[serde(default)]
+    pub scene_info: SEISceneInfo, // SEI type 9
+    #[serde(default)]
+    pub sub_seq_info: SEISubSeqInfo, // SEI type 10
+    #[serde(default)]
+    pub sub_seq_layer_characteristics: SEISubSeqLayerCharacteristics, // SEI type 11
+    #[serde(default)]
+    pub sub_seq_characteristics: SEISubSeqCharacteristics, // SEI type 12
+    #[serde(default)]
+    pub full_frame_freeze: SEIFullFrameFreeze, // SEI type 13
+    #[serde(default)]
+    pub full_frame_snapshot: SEIFullFrameSnapshot, // SEI type 15
+    #[serde(default)]
+    pub progressive_refinement_segment_start: SEIProgressiveRefinementSegmentStart, // SEI type 16
+    #[serde(default)]
+    pub progressive_refinement_segment_end: SEIProgressiveRefinementSegmentEnd, // SEI type 17
+    pub film_grain_characteristics: SEIFilmGrainCharacteristics, // SEI Type 19
+    #[serde(default)]
+    pub tone_mapping_info: SEIToneMappingInfo, // SEI type 23
+    #[serde(default)]
+    pub scalability_info: SEIScalabilityInfo, // SEI type 24
+    #[serde(default)]
+    pub sub_pic_scalable_layer: SEISubPicScalableLayer, // SEI type 25
+    #[serde(default)]
+    pub non_required_layer_rep: SEINonRequiredLayerRep, // SEI type 26
+    #[serde(default)]
+    pub priority_layer_info: SEIPriorityLayerInfo, // SEI type 27
+    #[serde(default)]
+    pub layers_not_present: SEILayersNotPresent, // SEI type 28
+    #[serde(default)]
+    pub layer_dependency_change: SEILayerDependencyChange, // SEI type 29
+    #[serde(default)]
+    pub scalable_nesting: SEIScalableNesting, // SEI type 30
+    #[serde(default)]
+    pub base_layer_temporal_hrd: SEITemporalHRD, // SEI type 31
+    #[serde(default)]
+    pub quality_layer_integrity_check: SEIQualityLayerIntegrityCheck, // SEI type 32
+    #[serde(default)]
+    pub redundant_pic_property: SEIRedundantPicProperty, // SEI type 33
+    #[serde(default)]
+    pub tl0_dep_rep_index: SEITl0DepRepIndex, // SEI type 34
+    #[serde(default)]
+    pub tl_switching_point: SEITlSwitchingPoint, // SEI type 35
+    #[serde(default)]
+    pub parallel_decoding_info: SEIParallelDecodingInfo, // SEI type 36
+    #[serde(default)]
+    pub mvc_scalable_nesting: SEIMVCScalableNesting, // SEI type 37
+    #[serde(default)]
+    pub view_scalability_info: SEIViewScalabilityInfo, // SEI type 38
+    #[serde(default)]
+    pub multiview_scene_info: SEIMultiviewSceneInfo, // SEI type 39
+    #[serde(default)]
+    pub multiview_acquisition_info: SEIMultiviewAcquisitionInfo, // SEI type 40
+    #[serde(default)]
+    pub non_required_view_component: SEINonRequiredViewComponent, // SEI type 41
+    #[serde(default)]
+    pub view_dependency_change: SEIViewDependencyChange, // SEI type 42
+    #[serde(default)]
+    pub operation_points_not_present: SEIOperationPointsNotPresent, // SEI type 43
+    #[serde(default)]
+    pub base_view_temporal_hrd: SEITemporalHRD, // SEI type 44
+    pub frame_packing: SEIFramePacking,       // SEI type 45
+    #[serde(default)]
+    pub multiview_view_position: SEIMultiviewViewPosition, // SEI type 46
+    #[serde(default)]
+    pub display_orientation: SEIDisplayOrientation, // SEI type 47
+    #[serde(default)]
+    pub mvcd_scalable_nesting: SEIMVCScalableNesting, // SEI type 48
+    #[serde(default)]
+    pub mvcd_view_scalability_info: SEIViewScalabilityInfo, // SEI type 49
+    #[serde(default)]
+    pub depth_representation_info: SEIDepthRepresentationInfo, // SEI type 50
+    #[serde(default)]
+    pub three_dimensional_reference_displays_info: SEIThreeDimensionalReferenceDisplaysInfo, // SEI type 51
+    #[serde(default)]
+    pub depth_timing: SEIDepthTiming, // SEI type 52
+    #[serde(default)]
+    pub depth_sampling_info: SEIDepthSamplingInfo, // SEI type 53
+    #[serde(default)]
+    pub constrained_depth_parameter_set_identifier: SEIConstrainedDepthParameterSetIdentifier, // SEI type 54
+    #[serde(default)]
+    pub mastering_display_colour_volume: SEIMasteringDisplayColourVolume, // SEI type 137
+    #[serde(default)]
+    pub colour_remapping_info: SEIColourRemappingInfo, // SEI type 142
+    #[serde(default)]
+    pub content_light_level_info: SEIContentLightLevelInfo, // SEI type 144
+    #[serde(default)]
+    pub alternative_transfer_characteristics: SEIAlternativeTransferCharacteristics, // SEI type 147
+    #[serde(default)]
+    pub content_colour_volume: SEIContentColourVolume, // SEI type 149
+    #[serde(default)]
+    pub equirectangular_projection: SEIEquirectangularProjection, // SEI type 150
+    #[serde(default)]
+    pub cubemap_projection: SEICubemapProjection, // SEI type 151
+    #[serde(default)]
+    pub sphere_rotation: SEISphereRotation, // SEI type 154
+    #[serde(default)]
+    pub regionwise_packing: SEIRegionwisePacking, // SEI type 155
+    #[serde(default)]
+    pub omni_viewport: SEIOmniViewport, // SEI type 156
+    #[serde(default)]
+    pub alternative_depth_info: SEIAlternativeDepthInfo, // SEI type 181
+    #[serde(default)]
+    pub raw_payload_byte: Vec<u8>, // payload bytes that were not parsed, re-encoded verbatim after the modelled fields
+    #[serde(default)]
+    pub payload_size_delta: i32, // written payload_size minus the actual payload length, used when payload_size is 0
+}
+
+impl Default for SEIPayload {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SEIPayload {
+    pub fn new() -> SEIPayload {
+        SEIPayload {
+            available: false,
+            buffering_period: SEIBufferingPeriod::new(),
+            pic_timing: SEIPicTiming::new(),
+            pan_scan_rect: SEIPanScanRect::new(),
+            filler_payload: SEIFillerPayload::new(),
+            registered_user_data: SEIUserDataRegisteredITUTT35::new(),
+            unregistered_user_data: SEIUserDataUnregistered::new(),
+            recovery_point: SEIRecoveryPoint::new(),
+            dec_ref_pic_marking_repetition: SEIDecRefPicMarkingRepetition::new(),
+            spare_pic: SEISparePic::new(),
+            scene_info: SEISceneInfo::new(),
+            sub_seq_info: SEISubSeqInfo::new(),
+            sub_seq_layer_characteristics: SEISubSeqLayerCharacteristics::new(),
+            sub_seq_characteristics: SEISubSeqCharacteristics::new(),
+            full_frame_freeze: SEIFullFrameFreeze::new(),
+            full_frame_snapshot: SEIFullFrameSnapshot::new(),
+            progressive_refinement_segment_start: SEIProgressiveRefinementSegmentStart::new(),
+            progressive_refinement_segment_end: SEIProgressiveRefinementSegmentEnd::new(),
+            film_grain_characteristics: SEIFilmGrainCharacteristics::new(),
+            tone_mapping_info: SEIToneMappingInfo::new(),
+            scalability_info: SEIScalabilityInfo::new(),
+            sub_pic_scalable_layer: SEISubPicScalableLayer::new(),
+            non_required_layer_rep: SEINonRequiredLayerRep::new(),
+            priority_layer_info: SEIPriorityLayerInfo::new(),
+            layers_not_present: SEILayersNotPresent::new(),
+            layer_dependency_change: SEILayerDependencyChange::new(),
+            scalable_nesting: SEIScalableNesting::new(),
+            base_layer_temporal_hrd: SEITemporalHRD::new(),
+            quality_layer_integrity_check: SEIQualityLayerIntegrityCheck::new(),
+            redundant_pic_property: SEIRedundantPicProperty::new(),
+            tl0_dep_rep_index: SEITl0DepRepIndex::new(),
+            tl_switching_point: SEITlSwitchingPoint::new(),
+            parallel_decoding_info: SEIParallelDecodingInfo::new(),
+            mvc_scalable_nesting: SEIMVCScalableNesting::new(),
+            view_scalability_info: SEIViewScalabilityInfo::new(),
+            multiview_scene_info: SEIMultiviewSceneInfo::new(),
+            multiview_acquisition_info: SEIMultiviewAcquisitionInfo::new(),
+            non_required_view_component: SEINonRequiredViewComponent::new(),
+            view_dependency_change: SEIViewDependencyChange::new(),
+            operation_points_not_present: SEIOperationPointsNotPresent::new(),
+            base_view_temporal_hrd: SEITemporalHRD::new(),
+            frame_packing: SEIFramePacking::new(),
+            multiview_view_position: SEIMultiviewViewPosition::new(),
+            display_orientation: SEIDisplayOrientation::new(),
+            mvcd_scalable_nesting: SEIMVCScalableNesting::new(),
+            mvcd_view_scalability_info: SEIViewScalabilityInfo::new(),
+            depth_representation_info: SEIDepthRepresentationInfo::new(),
+            three_dimensional_reference_displays_info:
+                SEIThreeDimensionalReferenceDisplaysInfo::new(),
+            depth_timing: SEIDepthTiming::new(),
+            depth_sampling_info: SEIDepthSamplingInfo::new(),
+            constrained_depth_parameter_set_identifier:
+                SEIConstrainedDepthParameterSetIdentifier::new(),
+            mastering_display_colour_volume: SEIMasteringDisplayColourVolume::new(),
+            colour_remapping_info: SEIColourRemappingInfo::new(),
+            content_light_level_info: SEIContentLightLevelInfo::new(),
+            alternative_transfer_characteristics: SEIAlternativeTransferCharacteristics::new(),
+            content_colour_volume: SEIContentColourVolume::new(),
+            equirectangular_projection: SEIEquirectangularProjection::new(),
+            cubemap_projection: SEICubemapProjection::new(),
+            sphere_rotation: SEISphereRotation::new(),
+            regionwise_packing: SEIRegionwisePacking::new(),
+            omni_viewport: SEIOmniViewport::new(),
+            alternative_depth_info: SEIAlternativeDepthInfo::new(),
+            raw_payload_byte: Vec::new(),
+            payload_size_delta: 0,
+        }
+    }
+}
+
+/// NALU Type 6 -- SEI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SEINalu {
+    // Each SEI can have multiple types so keep one data structure for each NALU SEI
+    pub payload_type: Vec<u32>,
+    pub payload_size: Vec<u32>,
+    // embed specific SEI type into here
+    pub payload: Vec<SEIPayload>,
+}
+
+impl SEINalu {
+    pub fn new() -> SEINalu {
+        SEINalu {
+            payload_type: Vec::new(),
+            payload_size: Vec::new(),
+            payload: Vec::new(),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn encoder_pretty_print(&self) {}
+}
+
+impl Default for SEINalu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// NALU Type 9 -- AUD
+///
+/// NALU Type 9 used to indicate the type of slices present in a primary
+/// coded picture and used by decoder to simplify the detection of the
+/// boundary between access units
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccessUnitDelim {
+    // Table 7-5 – Meaning of primary_pic_type
+    // primary_pic_type    | slice_type values that may be present in the primary coded picture
+    // ------------------------------------------------------------------------------------------
+    //       0             | 2, 7
+    //       1             | 0, 2, 5, 7
+    //       2             | 0, 1, 2, 5, 6, 7
+    //       3             | 4, 9
+    //       4             | 3, 4, 8, 9
+    //       5             | 2, 4, 7, 9
+    //       6             | 0, 2, 3, 4, 5, 7, 8, 9
+    //       7             | 0, 1, 2, 3, 4, 5, 6, 7, 8, 9
+    pub primary_pic_type: u8, // u(3)
+}
+
+impl AccessUnitDelim {
+    pub fn new() -> AccessUnitDelim {
+        AccessUnitDelim {
+            primary_pic_type: 0,
+        }
+    }
+
+    pub fn encoder_pretty_print(&self) {
+        encoder_formatted_print("AUD: primary_pic_type", self.primary_pic_type, 63);
+    }
+}
+
+impl Default for AccessUnitDelim {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Filler Data (7.3.2.7)
+///
+/// NALU Type 12 pads the bitstream and is discarded by decoders
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FillerData {
+    pub ff_byte: Vec<u8>, // f(8) -- shall be 0xFF, but other values are kept as is
+    pub rbsp_trailing_bits: bool, // false if the NALU does not end with rbsp_trailing_bits()
+}
+
+impl FillerData {
+    pub fn new() -> FillerData {
+        FillerData {
+            ff_byte: Vec::new(),
+            rbsp_trailing_bits: true,
+        }
+    }
+
+    pub fn encoder_pretty_print(&self) {
+        encoder_formatted_print("Filler data: ff_byte", &self.ff_byte, 63);
+        encoder_formatted_print(
+            "Filler data: rbsp_trailing_bits",
+            self.rbsp_trailing_bits,
+            63,
+        );
+    }
+}
+
+impl Default for FillerData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fmo_neighbors_follow_slice_groups() {
+        let mut s = SeqParameterSet::new();
+        s.pic_width_in_mbs_minus1 = 4;
+        s.pic_height_in_map_units_minus1 = 3;
+        s.frame_mbs_only_flag = true;
+        let width = 5;
+
+        for slice_group_map_type in 0..=6 {
+            let mut p = PicParameterSet::new();
+            p.num_slice_groups_minus1 = 2;
+            p.slice_group_map_type = slice_group_map_type;
+            p.run_length_minus1 = vec![1, 2, 0];
+            p.top_left = vec![1, 6];
+            p.bottom_right = vec![7, 13];
+            p.slice_group_id = (0..20).map(|i| (i * 7 % 3) as u32).collect();
+            if (3..=5).contains(&slice_group_map_type) {
+                p.num_slice_groups_minus1 = 1;
+            }
+            let mut sh = SliceHeader::new();
+            sh.slice_group_change_cycle = 7;
+            let vp = VideoParameters::new(&NALUheader::new(), &p, &s);
+            let sgm = sh.generate_slice_group_map(&s, &p, &vp).unwrap();
+
+            for group in 0..=p.num_slice_groups_minus1 {
+                // one slice per slice group, holding its macroblocks in decoding order
+                let mut sd = SliceData::new();
+                for mb_addr in (0..sgm.len()).filter(|&a| sgm[a] == group) {
+                    let mut mb = MacroBlock::new();
+                    mb.mb_idx = sd.macroblock_vec.len();
+                    mb.mb_addr = mb_addr;
+                    mb.available = true;
+                    sd.macroblock_vec.push(mb);
+                }
+
+                for curr_mb_idx in 0..sd.macroblock_vec.len() {
+                    let mb_addr = sd.macroblock_vec[curr_mb_idx].mb_addr as i32;
+                    let (x, y) = (mb_addr % width, mb_addr / width);
+                    for (neighbor_type, dx, dy) in [
+                        (NeighborMB::MbAddrA, -1, 0),
+                        (NeighborMB::MbAddrB, 0, -1),
+                        (NeighborMB::MbAddrC, 1, -1),
+                        (NeighborMB::MbAddrD, -1, -1),
+                    ] {
+                        let (nx, ny) = (x + dx, y + dy);
+                        let expected = nx >= 0
+                            && nx < width
+                            && ny >= 0
+                            && sgm[(ny * width + nx) as usize] == group;
+                        let n = sd.get_neighbor_macroblock(curr_mb_idx, neighbor_type, &vp);
+                        assert_eq!(
+                            n.available, expected,
+                            "map type {} mb {}",
+                            slice_group_map_type, mb_addr
+                        );
+                        if expected {
+                            assert_eq!(n.mb_addr as i32, ny * width + nx);
+                        }
+                    }
+                }
+            }
+        }
     }
 }
@@ -1,6 +1,8 @@
 //! Helper functions for working with byte streams and printing.
 
+use crate::decoder::error::DecodeError;
 use log::debug;
+use std::cell::RefCell;
 use std::collections::VecDeque;
 use std::fmt::Debug;
 
@@ -46,11 +48,7 @@ impl ByteStream {
         // only 1 byte, need to check byte_offset value is 1 and the rest 0
 
         // get the rest of the data, and see if it's greater than 2^byte_offset
-        let mut new_bs = ByteStream {
-            byte_offset: self.byte_offset,
-            bytestream: self.bytestream.clone(),
-        };
-        let intermediate = new_bs.read_bits(7 - new_bs.byte_offset + 1);
+        let intermediate = self.bytestream[0] as u32 & ((1 << (8 - self.byte_offset)) - 1);
         if intermediate == 0 || intermediate == (1 << (7 - self.byte_offset)) {
             // if we get zero or equal to the max power of two, then no more data
             return false;
@@ -59,17 +57,25 @@ impl ByteStream {
         true
     }
 
-    pub fn read_bits(&mut self, bits_to_read: u8) -> u32 {
+    pub fn read_bits(&mut self, bits_to_read: u8) -> Result<u32, DecodeError> {
         let mut result: u32 = 0;
 
-        // TODO: should this be replaced with an Option?
         if bits_to_read == 0 {
-            return result;
-        } else if self.bytestream.len() == 0 {
-            panic!("read_bits - Bytestream is empty!");
+            return Ok(result);
+        } else if self.bytestream.is_empty() {
+            return Err(DecodeError::new(String::from(
+                "read_bits - Bytestream is empty!",
+            )));
+        } else if bits_to_read > 32 {
+            return Err(DecodeError::new(format!(
+                "read_bits - Cannot read {} bits at once",
+                bits_to_read
+            )));
         } else if (bits_to_read as usize) > (self.bytestream.len() * 8 - self.byte_offset as usize)
         {
-            panic!("read_bits - Trying to read outside bounds!");
+            return Err(DecodeError::new(String::from(
+                "read_bits - Trying to read outside bounds!",
+            )));
         }
 
         for i in (0..bits_to_read).rev() {
@@ -86,20 +92,28 @@ impl ByteStream {
             }
         }
 
-        result
+        Ok(result)
     }
 
-    pub fn read_bits64(&mut self, bits_to_read: u8) -> u64 {
+    pub fn read_bits64(&mut self, bits_to_read: u8) -> Result<u64, DecodeError> {
         let mut result: u64 = 0;
 
-        // TODO: should this be replaced with an Option?
         if bits_to_read == 0 {
-            return result;
-        } else if self.bytestream.len() == 0 {
-            panic!("read_bits - Bytestream is empty!");
+            return Ok(result);
+        } else if self.bytestream.is_empty() {
+            return Err(DecodeError::new(String::from(
+                "read_bits64 - Bytestream is empty!",
+            )));
+        } else if bits_to_read > 64 {
+            return Err(DecodeError::new(format!(
+                "read_bits64 - Cannot read {} bits at once",
+                bits_to_read
+            )));
         } else if (bits_to_read as usize) > (self.bytestream.len() * 8 - self.byte_offset as usize)
         {
-            panic!("read_bits - Trying to read outside bounds!");
+            return Err(DecodeError::new(String::from(
+                "read_bits64 - Trying to read outside bounds!",
+            )));
         }
 
         for i in (0..bits_to_read).rev() {
@@ -116,7 +130,7 @@ impl ByteStream {
             }
         }
 
-        result
+        Ok(result)
     }
 }
 
@@ -150,8 +164,27 @@ pub fn formatted_print<T: Debug>(out: &str, val: T, width: usize) {
     println!("\t{out:<width$} ({:?})", val, out = out, width = width);
 }
 
+thread_local! {
+    static LAST_DECODED_ELEMENT: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+/// Returns the name of the last syntax element passed to `decoder_formatted_print`
+pub fn last_decoded_syntax_element() -> String {
+    LAST_DECODED_ELEMENT.with(|e| e.borrow().clone())
+}
+
+/// Forgets the last decoded syntax element, e.g., when starting a new NALU
+pub fn clear_last_decoded_syntax_element() {
+    LAST_DECODED_ELEMENT.with(|e| e.borrow_mut().clear());
+}
+
 /// Output to decoding debug file a string and its value at a particular width
 pub fn decoder_formatted_print<T: Debug>(out: &str, val: T, width: usize) {
+    LAST_DECODED_ELEMENT.with(|e| {
+        let mut e = e.borrow_mut();
+        e.clear();
+        e.push_str(out);
+    });
     debug!(target: "decode",
         "\t{out:<width$} ({:?})",
         val,
@@ -174,7 +207,8 @@ pub fn encoder_formatted_print<T: Debug>(out: &str, val: T, width: usize) {
 /// # Arguments
 ///
 /// * `slice_type_num` - the decoded slice number
-/// * `slice_letter` - the slice type we're interested in matching
+/// * `slice_letter` - the slice type we're interested in matching; any letter other
+///   than P, B, I, SP or SI never matches
 pub fn is_slice_type(slice_type_num: u8, slice_letter: &str) -> bool {
     let sl = slice_letter.to_uppercase();
     match sl.as_str() {
@@ -183,10 +217,32 @@ pub fn is_slice_type(slice_type_num: u8, slice_letter: &str) -> bool {
         "I" => slice_type_num % 5 == 2,  // 2 or 7
         "SP" => slice_type_num % 5 == 3, // 3 or 8
         "SI" => slice_type_num % 5 == 4, // 4 or 9
-        _ => panic!("Nonexistent slice_letter passed: {:?}", slice_letter),
+        _ => false,
     }
 }
 
+/// Length in bits of the mantissa of an SEI camera or display parameter, as
+/// given in the semantics of the multiview acquisition information SEI (H.13.2.5)
+pub fn sei_mantissa_len(exponent: u8, prec: u32) -> u32 {
+    if exponent == 0 {
+        prec.saturating_sub(30)
+    } else {
+        (exponent as u32).saturating_add(prec).saturating_sub(31)
+    }
+}
+
+/// Length in bits of slice_group_change_cycle, given by equation 7-35 as
+/// Ceil(Log2(PicSizeInMapUnits ÷ SliceGroupChangeRate + 1)), where the division is exact
+pub fn slice_group_change_cycle_len(
+    pic_size_in_map_units: u32,
+    slice_group_change_rate_minus1: u32,
+) -> usize {
+    let slice_group_change_rate = slice_group_change_rate_minus1 as f64 + 1.0;
+    (pic_size_in_map_units as f64 / slice_group_change_rate + 1.0)
+        .log2()
+        .ceil() as usize
+}
+
 /// Convert a bitstream to a bytestream and pad the bitstream with a chosen value
 pub fn bitstream_to_bytestream<BS: AsRef<[u8]>>(bitstream: BS, padding_bit: u8) -> Vec<u8> {
     let bitstream = bitstream.as_ref();
@@ -336,7 +392,7 @@ mod test {
 
             let prev_len = bs.bytestream.len();
 
-            let r = bs.read_bits(test_cases[i].0);
+            let r = bs.read_bits(test_cases[i].0).unwrap();
 
             assert!(results[i].0 == r); // check the results
             assert!(results[i].1 == prev_len - bs.bytestream.len()); // check the consumption
@@ -355,7 +411,7 @@ mod test {
         for i in 0..results.len() {
             let bits_to_read = i as u8;
             let prev_len = bs.bytestream.len();
-            let r = bs.read_bits(bits_to_read);
+            let r = bs.read_bits(bits_to_read).unwrap();
 
             println!(
                 "test_stream: {:?}, bits_to_read: {}, bytes_consumed: {}, byte_offset : {}",
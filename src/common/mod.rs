@@ -4,3 +4,5 @@ pub mod cabac_tables;
 pub mod cavlc_tables;
 pub mod data_structures;
 pub mod helper;
+#[cfg(test)]
+pub mod test_support;
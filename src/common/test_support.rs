@@ -0,0 +1,58 @@
+//! Fixtures shared by the generation and round-trip tests.
+
+use crate::common::data_structures::H264DecodedStream;
+use crate::common::data_structures::NALUheader;
+use crate::common::data_structures::SEINalu;
+use crate::common::data_structures::SEIPayload;
+use crate::common::data_structures::NALU;
+use crate::decoder::decoder::decode_bitstream_from_bytes;
+use crate::encoder::encoder::reencode_syntax_elements;
+use crate::vidgen::generate_configurations::RandomBoolRange;
+use crate::vidgen::generate_configurations::RandomU32Enum;
+use crate::vidgen::generate_configurations::RandomizeConfig;
+
+/// An SPS, a PPS, an IDR slice and a P slice
+pub const SPS_PPS_I_P: &[u8] = include_bytes!("../../input_vids/SPS_PPS_I_P.264");
+
+/// Encode a stream and decode the result, which must succeed
+pub fn round_trip(ds: &mut H264DecodedStream) -> H264DecodedStream {
+    let (encoded, _, _) = reencode_syntax_elements(ds, -1, -1, -1, false, true, false);
+    decode_bitstream_from_bytes(&encoded, false, false, false, false).unwrap()
+}
+
+/// SPS_PPS_I_P with an SEI NALU without messages between the PPS and the IDR slice
+pub fn stream_with_empty_sei() -> H264DecodedStream {
+    let mut ds = decode_bitstream_from_bytes(SPS_PPS_I_P, false, false, false, false).unwrap();
+
+    let mut nh = NALUheader::new();
+    nh.nal_unit_type = 6;
+    ds.nalu_headers.insert(2, nh);
+    ds.nalu_elements.insert(2, NALU::new());
+    ds.seis.push(SEINalu::new());
+    ds
+}
+
+/// Check that every decoded SEI payload was parsed and matches the encoded one
+pub fn assert_sei_payloads_match(decoded: &[SEIPayload], encoded: &[SEIPayload]) {
+    assert_eq!(decoded.len(), encoded.len());
+    for (a, b) in decoded.iter().zip(encoded.iter()) {
+        assert!(a.available);
+        let mut a = a.clone();
+        a.available = b.available;
+        assert_eq!(
+            serde_json::to_string(&a).unwrap(),
+            serde_json::to_string(b).unwrap()
+        );
+    }
+}
+
+/// Ranges for Main profile CAVLC videos without cropping or VUI, which the decoder can
+/// read back
+pub fn main_profile_cavlc_config() -> RandomizeConfig {
+    let mut rconfig = RandomizeConfig::new();
+    rconfig.random_sps_range.profile_idc = RandomU32Enum::new(vec![77]);
+    rconfig.random_sps_range.frame_cropping_flag = RandomBoolRange::never();
+    rconfig.random_sps_range.vui_parameters_present_flag = RandomBoolRange::never();
+    rconfig.random_pps_range.entropy_coding_mode_flag = RandomBoolRange::never();
+    rconfig
+}
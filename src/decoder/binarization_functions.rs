@@ -21,7 +21,7 @@ use crate::decoder::expgolomb::exp_golomb_decode_no_stream;
 /// Reads a unary value with 0 as the ending character
 pub fn read_unary_value(bs: &[u8]) -> Option<i32> {
     if bs.is_empty() {
-        panic!("read_unary_value - empty bitstream value!");
+        return None;
     }
 
     let mut cur_bit = bs[0];
@@ -42,7 +42,7 @@ pub fn read_unary_value(bs: &[u8]) -> Option<i32> {
 /// Reads a unary value up to max_val
 pub fn read_truncated_unary_value(max_val: u32, bs: &[u8]) -> Option<i32> {
     if bs.is_empty() {
-        panic!("read_truncated_unary_value - empty bitstream value!");
+        return None;
     }
 
     let mut cur_bit = bs[0];
@@ -84,7 +84,7 @@ pub fn read_uegk(signed_val_flag: bool, ucoff: u32, k: u8, bs: &[u8]) -> Option<
 /// Specified in section 9.3.2.5
 pub fn read_mb_types(bs: &[u8], slice_type: u8) -> Option<i32> {
     if bs.is_empty() {
-        panic!("read_mb_types - empty bitstream value!");
+        return None;
     }
 
     // Table 9-37
@@ -131,7 +131,7 @@ pub fn read_mb_types(bs: &[u8], slice_type: u8) -> Option<i32> {
                         5 => return Some(8),
                         6 => return Some(9),
                         7 => return Some(10),
-                        _ => panic!("read_mb_types - incorrect pattern for B slice {:?}", bs),
+                        _ => unreachable!("read_mb_types - incorrect pattern for B slice {:?}", bs),
                     }
                 } else {
                     // 111
@@ -211,7 +211,7 @@ pub fn read_mb_types(bs: &[u8], slice_type: u8) -> Option<i32> {
                         5 => {
                             return Some(100); // this signals that we need to decode the prefix for intra mode
                         }
-                        _ => panic!("read_mb_types - incorrect pattern for B slice {:?}", bs),
+                        _ => unreachable!("read_mb_types - incorrect pattern for B slice {:?}", bs),
                     }
                 }
             }
@@ -228,7 +228,7 @@ pub fn read_mb_types(bs: &[u8], slice_type: u8) -> Option<i32> {
                 1 => return Some(3),
                 2 => return Some(2),
                 3 => return Some(1),
-                _ => panic!("read_mb_types - incorrect pattern for P slice {:?}", bs),
+                _ => unreachable!("read_mb_types - incorrect pattern for P slice {:?}", bs),
             }
         }
     }
@@ -348,7 +348,7 @@ pub fn read_mb_types(bs: &[u8], slice_type: u8) -> Option<i32> {
                             return Some(24);
                         }
                     }
-                    _ => panic!("read_mb_types - failure with read_bits, got {}", b25),
+                    _ => unreachable!("read_mb_types - failure with read_bits, got {}", b25),
                 };
             }
         }
@@ -359,7 +359,7 @@ pub fn read_mb_types(bs: &[u8], slice_type: u8) -> Option<i32> {
 /// Specified in section 9.3.2.5 Table 9-38
 pub fn read_sub_mb_types(bs: &[u8], slice_type: u8) -> Option<i32> {
     if bs.is_empty() {
-        panic!("read_sub_mb_types - empty bitstream value!");
+        return None;
     }
 
     if is_slice_type(slice_type, "P") || is_slice_type(slice_type, "SP") {
@@ -445,7 +445,7 @@ pub fn read_sub_mb_types(bs: &[u8], slice_type: u8) -> Option<i32> {
             }
         }
     } else {
-        panic!(
+        unreachable!(
             "read_sub_mb_types - Incorrect slice_type provided: {:?}",
             slice_type
         );
@@ -469,10 +469,7 @@ mod tests {
         for t in test_cases.iter() {
             let res = read_unary_value(&t.1);
             println!("Expected: {}; Got: {:?}", t.0, res);
-            match res {
-                Some(x) => assert!(t.0 == x),
-                _ => panic!("Error in parsing"),
-            }
+            assert_eq!(res, Some(t.0));
         }
     }
 
@@ -490,10 +487,7 @@ mod tests {
         for t in test_cases.iter() {
             let res = read_truncated_unary_value(max_val, &t.1);
             println!("Expected: {}; Got: {:?}", t.0, res);
-            match res {
-                Some(x) => assert!(t.0 == x),
-                _ => panic!("Error in parsing"),
-            }
+            assert_eq!(res, Some(t.0));
         }
     }
 }
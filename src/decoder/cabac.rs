@@ -13,6 +13,7 @@ use crate::common::helper::clip3;
 use crate::common::helper::is_slice_type;
 use crate::common::helper::ByteStream;
 use crate::decoder::binarization_functions::*;
+use crate::decoder::error::DecodeError;
 use log::debug;
 use std::cmp;
 
@@ -62,7 +63,7 @@ impl Default for CABACState {
 ///
 /// Load up all possible ctxIdx values into the state. Similar
 /// process to OpenH264.
-pub fn initialize_state(bs: &mut ByteStream) -> CABACState {
+pub fn initialize_state(bs: &mut ByteStream) -> Result<CABACState, DecodeError> {
     let mut r = CABACState::new();
 
     for i in 0..4 {
@@ -96,9 +97,9 @@ pub fn initialize_state(bs: &mut ByteStream) -> CABACState {
     }
 
     r.cod_i_range = 510;
-    r.cod_i_offset = bs.read_bits(9);
+    r.cod_i_offset = bs.read_bits(9)?;
 
-    r
+    Ok(r)
 }
 
 /// Described in section 9.3.2 of the spec
@@ -111,7 +112,7 @@ fn get_binarization_params(
     sh: &SliceHeader,
     sd: &SliceData,
     curr_mb_idx: usize,
-) -> (u32, u32, bool) {
+) -> Result<(u32, u32, bool), DecodeError> {
     let mut max_bin_idx_ctx: u32 = 0;
     let mut ctx_idx_offset: u32 = 0;
     let mut bypass_flag: bool = true;
@@ -130,7 +131,9 @@ fn get_binarization_params(
                 ctx_idx_offset = 24;
                 bypass_flag = false;
             } else {
-                panic!("MB_SKIP_FLAG DECODING ERROR");
+                return Err(DecodeError::new(String::from(
+                    "MB_SKIP_FLAG DECODING ERROR",
+                )));
             }
         }
         "mb_field_decoding_flag" => {
@@ -287,16 +290,40 @@ fn get_binarization_params(
             ctx_idx_offset = 276;
             bypass_flag = false;
         }
+        // macroblock_layer_in_scalable_extension()
+        "base_mode_flag" => {
+            max_bin_idx_ctx = 0;
+            ctx_idx_offset = 1024;
+            bypass_flag = false;
+        }
+        "motion_prediction_flag_l0" => {
+            max_bin_idx_ctx = 0;
+            ctx_idx_offset = 1027;
+            bypass_flag = false;
+        }
+        "motion_prediction_flag_l1" => {
+            max_bin_idx_ctx = 0;
+            ctx_idx_offset = 1028;
+            bypass_flag = false;
+        }
+        "residual_prediction_flag" => {
+            max_bin_idx_ctx = 0;
+            ctx_idx_offset = 1029;
+            bypass_flag = false;
+        }
         "coeff_sign_flag" => {
             max_bin_idx_ctx = 0;
             bypass_flag = true;
         }
         _ => {
-            panic!("get_binarization_params - {} not found", syntax_element);
+            return Err(DecodeError::new(format!(
+                "get_binarization_params - {} not found",
+                syntax_element
+            )));
         }
     }
 
-    (max_bin_idx_ctx, ctx_idx_offset, bypass_flag)
+    Ok((max_bin_idx_ctx, ctx_idx_offset, bypass_flag))
 }
 
 /// Relies on getting information from the surrounding macroblocks to get
@@ -312,7 +339,7 @@ fn get_ctx_idx(
     ctx_block_cat: u8,
     additional_inputs: &[usize],
     vp: &VideoParameters,
-) -> usize {
+) -> Result<usize, DecodeError> {
     let mut ctx_idx: usize = ctx_idx_offset as usize;
     let mut ctx_idx_inc: usize = 0;
 
@@ -328,10 +355,10 @@ fn get_ctx_idx(
     match ctx_idx_offset {
         0 => {
             if bin_idx > 0 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             // Decode according to 9.3.3.1.1.3
 
@@ -379,7 +406,7 @@ fn get_ctx_idx(
 
                 ctx_idx_inc = cond_term_flag_a + cond_term_flag_b;
             } else if bin_idx == 1 {
-                return 276;
+                return Ok(276);
             } else if bin_idx == 2 {
                 ctx_idx_inc = 3;
             } else if bin_idx == 3 {
@@ -433,10 +460,10 @@ fn get_ctx_idx(
         }
         14 => {
             if bin_idx > 2 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             if CABAC_DEBUG {
                 debug!(target: "decode","get_ctx_idx - mb_type (P, SP slices Prefix)");
@@ -464,7 +491,7 @@ fn get_ctx_idx(
             if bin_idx == 0 {
                 ctx_idx_inc = 0;
             } else if bin_idx == 1 {
-                return 276;
+                return Ok(276);
             } else if bin_idx == 2 {
                 ctx_idx_inc = 1;
             } else if bin_idx == 3 {
@@ -487,10 +514,10 @@ fn get_ctx_idx(
         }
         21 => {
             if bin_idx > 2 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             if bin_idx == 0 {
                 ctx_idx_inc = 0;
@@ -502,10 +529,10 @@ fn get_ctx_idx(
         }
         24 => {
             if bin_idx > 0 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             // Decode according to 9.3.3.1.1.1
             let mut cond_term_flag_a: usize = 1;
@@ -583,7 +610,7 @@ fn get_ctx_idx(
             if bin_idx == 0 {
                 ctx_idx_inc = 0;
             } else if bin_idx == 1 {
-                return 276;
+                return Ok(276);
             } else if bin_idx == 2 {
                 ctx_idx_inc = 1;
             } else if bin_idx == 3 {
@@ -602,10 +629,10 @@ fn get_ctx_idx(
         }
         36 => {
             if bin_idx > 5 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             if bin_idx == 0 {
                 ctx_idx_inc = 0;
@@ -800,10 +827,10 @@ fn get_ctx_idx(
         }
         64 => {
             if bin_idx > 2 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             if bin_idx == 0 {
                 let mut cond_term_flag_a: usize = 1;
@@ -838,28 +865,28 @@ fn get_ctx_idx(
         }
         68 => {
             if bin_idx > 0 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             ctx_idx_inc = 0;
         }
         69 => {
             if bin_idx > 2 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             ctx_idx_inc = 0;
         }
         70 => {
             if bin_idx > 0 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             // Implements clause 9.3.3.1.1.2
             let mut cond_term_flag_a: usize = 1;
@@ -884,10 +911,10 @@ fn get_ctx_idx(
         }
         73 => {
             if bin_idx > 3 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
 
             let mut cond_term_flag_a: usize = 1;
@@ -967,10 +994,10 @@ fn get_ctx_idx(
                 debug!(target: "decode","get_ctx_idx - coded_block_pattern - Suffix - bin_idx {}", bin_idx);
             }
             if bin_idx > 1 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
 
             let mut cond_term_flag_a: usize = 1;
@@ -1028,19 +1055,19 @@ fn get_ctx_idx(
         }
         276 => {
             if bin_idx > 0 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             ctx_idx_inc = 0;
         }
         399 => {
             if bin_idx > 0 {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "GET_CTX_IDX: Incorrect ctx_idx_offset and bin_idx combination: {} and {}",
                     ctx_idx_offset, bin_idx
-                );
+                )));
             }
             // Defined in clause 9.3.3.1.1.10
             let mut cond_term_flag_a: usize = 1;
@@ -1062,6 +1089,37 @@ fn get_ctx_idx(
 
             ctx_idx_inc = cond_term_flag_a + cond_term_flag_b;
         }
+        1024 => {
+            // base_mode_flag ctxIdxInc, defined in clause G.9.3.3.1.1
+            let mut cond_term_flag_a: usize = 1;
+            let mut cond_term_flag_b: usize = 1;
+
+            let res = sd.get_neighbor(curr_mb_idx, false, vp);
+
+            let mb_a: MacroBlock = res.0;
+            let mb_b: MacroBlock = res.1;
+
+            if !mb_a.available || mb_a.base_mode_flag {
+                cond_term_flag_a = 0;
+            }
+
+            if !mb_b.available || mb_b.base_mode_flag {
+                cond_term_flag_b = 0;
+            }
+
+            ctx_idx_inc = cond_term_flag_a + cond_term_flag_b;
+        }
+        1027 | 1028 => {
+            // motion_prediction_flag_l0 and motion_prediction_flag_l1 use a single context
+            ctx_idx_inc = 0;
+        }
+        1029 => {
+            // residual_prediction_flag ctxIdxInc, defined in clause G.9.3.3.1.1
+            ctx_idx_inc = match sd.macroblock_vec[curr_mb_idx].base_mode_flag {
+                true => 0,
+                false => 1,
+            };
+        }
         _ => {
             in_table = false;
         }
@@ -1629,17 +1687,23 @@ fn get_ctx_idx(
                 );
             }
         } else {
-            panic!("get_ctx_idx - Unknown syntax_element being decoded!");
+            return Err(DecodeError::new(String::from(
+                "get_ctx_idx - Unknown syntax_element being decoded!",
+            )));
         }
         ctx_idx += ctx_block_cat_offset + ctx_idx_inc;
     }
 
-    ctx_idx
+    Ok(ctx_idx)
 }
 
 /// Given a bitstream value, produces the decoded syntax elements
 /// by walking the decoding tree.
-fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> Option<i32> {
+fn debinarization(
+    syntax_element: &str,
+    bitstream: &[u8],
+    sh: &SliceHeader,
+) -> Result<Option<i32>, DecodeError> {
     let res: i32;
 
     match syntax_element {
@@ -1651,7 +1715,9 @@ fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> O
             {
                 res = bitstream[0] as i32; // Fixed-length binarization process
             } else {
-                panic!("MB_SKIP_FLAG DECODING ERROR");
+                return Err(DecodeError::new(String::from(
+                    "MB_SKIP_FLAG DECODING ERROR",
+                )));
             }
         }
         "mb_field_decoding_flag" => {
@@ -1666,9 +1732,11 @@ fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> O
             {
                 res = bitstream[0] as i32;
             } else if is_slice_type(sh.slice_type, "I") || is_slice_type(sh.slice_type, "B") {
-                return read_mb_types(&bitstream, sh.slice_type);
+                return Ok(read_mb_types(bitstream, sh.slice_type));
             } else {
-                panic!("MB_TYPE_PREFIX DECODING ERROR");
+                return Err(DecodeError::new(String::from(
+                    "MB_TYPE_PREFIX DECODING ERROR",
+                )));
             }
         }
         "mb_type_suffix" => {
@@ -1676,12 +1744,12 @@ fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> O
             // We're only here because we need to decode I slice types
             if is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "SP") {
                 if bitstream[0] == 1 {
-                    return read_mb_types(&bitstream[1..], 2); // 2 is I slice type
+                    return Ok(read_mb_types(&bitstream[1..], 2)); // 2 is I slice type
                 } else {
-                    return read_mb_types(&bitstream[1..], sh.slice_type);
+                    return Ok(read_mb_types(&bitstream[1..], sh.slice_type));
                 }
             } else {
-                return read_mb_types(&bitstream, 2); // 2 is I slice type
+                return Ok(read_mb_types(bitstream, 2)); // 2 is I slice type
             }
         }
         "transform_size_8x8_flag" => {
@@ -1690,7 +1758,7 @@ fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> O
         "coded_block_pattern_luma" => {
             // Specified in clause 9.3.2.6
             if bitstream.len() != 4 {
-                return None;
+                return Ok(None);
             } else {
                 res = ((bitstream[3] << 3)
                     | (bitstream[2] << 2)
@@ -1700,12 +1768,12 @@ fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> O
         }
         "coded_block_pattern_chroma" => {
             // Specified in clause 9.3.2.6
-            return read_truncated_unary_value(2, &bitstream);
+            return Ok(read_truncated_unary_value(2, bitstream));
         }
         "mb_qp_delta" => {
             // specified in 9.3.2.7
             // Table 9-4
-            return read_unary_value(&bitstream);
+            return Ok(read_unary_value(bitstream));
         }
         // mb_pred()
         "prev_intra4x4_pred_mode_flag" => {
@@ -1713,7 +1781,7 @@ fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> O
         }
         "rem_intra4x4_pred_mode" => {
             if bitstream.len() < 3 {
-                return None;
+                return Ok(None);
             } else {
                 res = ((bitstream[2] << 2) | (bitstream[1] << 1) | bitstream[0]) as i32;
             }
@@ -1723,36 +1791,36 @@ fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> O
         }
         "rem_intra8x8_pred_mode" => {
             if bitstream.len() < 3 {
-                return None;
+                return Ok(None);
             } else {
                 res = ((bitstream[2] << 2) | (bitstream[1] << 1) | bitstream[0]) as i32;
             }
         }
         "intra_chroma_pred_mode" => {
-            return read_truncated_unary_value(3, &bitstream);
+            return Ok(read_truncated_unary_value(3, bitstream));
         }
         // mb_pred() and sub_mb_pred()
         "ref_idx_l0" => {
-            return read_unary_value(&bitstream);
+            return Ok(read_unary_value(bitstream));
         }
         "ref_idx_l1" => {
-            return read_unary_value(&bitstream);
+            return Ok(read_unary_value(bitstream));
         }
         "mvd_l0_0" => {
-            return read_uegk(true, 9, 3, &bitstream);
+            return Ok(read_uegk(true, 9, 3, bitstream));
         }
         "mvd_l1_0" => {
-            return read_uegk(true, 9, 3, &bitstream);
+            return Ok(read_uegk(true, 9, 3, bitstream));
         }
         "mvd_l0_1" => {
-            return read_uegk(true, 9, 3, &bitstream);
+            return Ok(read_uegk(true, 9, 3, bitstream));
         }
         "mvd_l1_1" => {
-            return read_uegk(true, 9, 3, &bitstream);
+            return Ok(read_uegk(true, 9, 3, bitstream));
         }
         // sub_mb_pred()
         "sub_mb_type" => {
-            return read_sub_mb_types(bitstream, sh.slice_type);
+            return Ok(read_sub_mb_types(bitstream, sh.slice_type));
         }
         // residual_block_cabac()
         "coded_block_flag" => {
@@ -1765,16 +1833,32 @@ fn debinarization(syntax_element: &str, bitstream: &[u8], sh: &SliceHeader) -> O
             res = bitstream[0] as i32;
         }
         "coeff_abs_level_minus1" => {
-            return read_uegk(false, 14, 0, &bitstream);
+            return Ok(read_uegk(false, 14, 0, bitstream));
         }
         "end_of_slice_flag" => {
             res = bitstream[0] as i32;
         }
+        // macroblock_layer_in_scalable_extension()
+        "base_mode_flag" => {
+            res = bitstream[0] as i32;
+        }
+        "motion_prediction_flag_l0" => {
+            res = bitstream[0] as i32;
+        }
+        "motion_prediction_flag_l1" => {
+            res = bitstream[0] as i32;
+        }
+        "residual_prediction_flag" => {
+            res = bitstream[0] as i32;
+        }
         _ => {
-            panic!("debinarization - {} not found", syntax_element);
+            return Err(DecodeError::new(format!(
+                "debinarization - {} not found",
+                syntax_element
+            )));
         }
     }
-    Some(res)
+    Ok(Some(res))
 }
 
 /// arith_decode - part of cabac_decode
@@ -1795,11 +1879,24 @@ fn arith_decode(
     idx3: usize,
     bs: &mut ByteStream,
     cs: &mut CABACState,
-) -> u8 {
+) -> Result<u8, DecodeError> {
     // decode_decision()
 
     let bin_val: u8;
-    let cabac_se_state = &mut cs.states[idx1][idx2][idx3];
+    let cabac_se_state = match cs
+        .states
+        .get_mut(idx1)
+        .and_then(|x| x.get_mut(idx2))
+        .and_then(|x| x.get_mut(idx3))
+    {
+        Some(x) => x,
+        None => {
+            return Err(DecodeError::new(format!(
+                "arith_decode - context index [{}][{}][{}] is out of range",
+                idx1, idx2, idx3
+            )))
+        }
+    };
     let cod_i_range_idx: usize = ((cs.cod_i_range >> 6) as usize) & 3;
     let cod_i_range_lps: u32 =
         cabac_tables::RANGE_TAB_LPS[cabac_se_state.p_state_idx as usize][cod_i_range_idx];
@@ -1833,7 +1930,7 @@ fn arith_decode(
     while cs.cod_i_range < 256 {
         cs.cod_i_range <<= 1;
         cs.cod_i_offset <<= 1;
-        cs.cod_i_offset |= bs.read_bits(1);
+        cs.cod_i_offset |= bs.read_bits(1)?;
     }
     if CABAC_DEBUG {
         debug!(target: "decode","after1 - cod_i_range: {}", cs.cod_i_range);
@@ -1841,20 +1938,25 @@ fn arith_decode(
     // the bitstream shall not contain data that result in a value of
     // cod_i_offset being greater than or equal to cod_i_range upon
     // completion of this process
-    assert!(cs.cod_i_offset < cs.cod_i_range);
+    if cs.cod_i_offset >= cs.cod_i_range {
+        return Err(DecodeError::new(format!(
+            "arith_decode - cod_i_offset {} is not less than cod_i_range {}",
+            cs.cod_i_offset, cs.cod_i_range
+        )));
+    }
 
-    bin_val
+    Ok(bin_val)
 }
 
 /// arithmetic decoding with p=0.5
-fn decode_bypass(bs: &mut ByteStream, cs: &mut CABACState) -> u8 {
+fn decode_bypass(bs: &mut ByteStream, cs: &mut CABACState) -> Result<u8, DecodeError> {
     if CABAC_DEBUG {
         debug!(target: "decode","decode_bypass called");
     }
     let bin_val: u8;
 
     cs.cod_i_offset <<= 1;
-    cs.cod_i_offset |= bs.read_bits(1);
+    cs.cod_i_offset |= bs.read_bits(1)?;
 
     if cs.cod_i_offset >= cs.cod_i_range {
         bin_val = 1;
@@ -1862,10 +1964,10 @@ fn decode_bypass(bs: &mut ByteStream, cs: &mut CABACState) -> u8 {
     } else {
         bin_val = 0;
     }
-    bin_val
+    Ok(bin_val)
 }
 
-fn decode_terminate(bs: &mut ByteStream, cs: &mut CABACState) -> u8 {
+fn decode_terminate(bs: &mut ByteStream, cs: &mut CABACState) -> Result<u8, DecodeError> {
     if CABAC_DEBUG {
         debug!(target: "decode","decode_terminate called");
     }
@@ -1882,11 +1984,11 @@ fn decode_terminate(bs: &mut ByteStream, cs: &mut CABACState) -> u8 {
         while cs.cod_i_range < 256 {
             cs.cod_i_range <<= 1;
             cs.cod_i_offset <<= 1;
-            cs.cod_i_offset |= bs.read_bits(1);
+            cs.cod_i_offset |= bs.read_bits(1)?;
         }
     }
 
-    bin_val
+    Ok(bin_val)
 }
 
 /// Because the coded block pattern has its own unique binarization process we split it out
@@ -1899,7 +2001,7 @@ fn cabac_decode_cbp(
     sh: &SliceHeader,
     sd: &mut SliceData,
     vp: &VideoParameters,
-) -> i32 {
+) -> Result<i32, DecodeError> {
     // first decode the prefix
     let mut decoded: Vec<u8> = Vec::new();
     let mut res: i32;
@@ -1922,7 +2024,7 @@ fn cabac_decode_cbp(
             0,
             &additional_inputs,
             vp,
-        );
+        )?;
 
         let mut idx1 = sh.cabac_init_idc as usize;
         let idx2 = sh.slice_qp_y as usize;
@@ -1954,7 +2056,7 @@ fn cabac_decode_cbp(
             debug!(target: "decode","\tctx_idx: {}", ctx_idx);
         }
 
-        let ad = arith_decode(idx1, idx2, ctx_idx, bs, state);
+        let ad = arith_decode(idx1, idx2, ctx_idx, bs, state)?;
         if CABAC_DEBUG {
             debug!(
                 "updated state: {:x}",
@@ -1964,11 +2066,15 @@ fn cabac_decode_cbp(
         decoded.push(ad);
         additional_inputs.push(ad as usize);
     }
-    match debinarization(&(syntax_element.to_owned() + "_luma"), &decoded, sh) {
+    match debinarization(&(syntax_element.to_owned() + "_luma"), &decoded, sh)? {
         Some(x) => {
             res = x;
         }
-        _ => panic!("cabac_decode_cbp - issue debinarization luma"),
+        _ => {
+            return Err(DecodeError::new(String::from(
+                "cabac_decode_cbp - issue debinarization luma",
+            )))
+        }
     }
     if CABAC_DEBUG {
         debug!(target: "decode","yoo cbp_luma: {:?}", decoded);
@@ -1991,7 +2097,7 @@ fn cabac_decode_cbp(
                 0,
                 &[],
                 vp,
-            );
+            )?;
 
             let mut idx1 = sh.cabac_init_idc as usize;
             let idx2 = sh.slice_qp_y as usize;
@@ -2024,7 +2130,7 @@ fn cabac_decode_cbp(
                 debug!(target: "decode","\tctx_idx: {}", ctx_idx);
             }
 
-            let ad = arith_decode(idx1, idx2, ctx_idx, bs, state);
+            let ad = arith_decode(idx1, idx2, ctx_idx, bs, state)?;
             if CABAC_DEBUG {
                 debug!(
                     "updated state: {:x}",
@@ -2033,7 +2139,7 @@ fn cabac_decode_cbp(
             }
 
             decoded.push(ad);
-            if let Some(x) = debinarization(&(syntax_element.to_owned() + "_chroma"), &decoded, sh)
+            if let Some(x) = debinarization(&(syntax_element.to_owned() + "_chroma"), &decoded, sh)?
             {
                 // Table 7-15 - Chroma value can't be equal to 3 so if that's the case then we set it equal to 2
                 if x == 3 {
@@ -2051,7 +2157,7 @@ fn cabac_decode_cbp(
     if CABAC_DEBUG {
         debug!(target: "decode","yoo cbp_total: {:?}", res);
     }
-    res
+    Ok(res)
 }
 
 /// Own function to decode Luma coefficient values
@@ -2065,7 +2171,7 @@ fn cabac_decode_coeff(
     vp: &VideoParameters,
     ctx_block_cat: u8,
     additional_inputs: &[usize],
-) -> i32 {
+) -> Result<i32, DecodeError> {
     // first decode the prefix
     let mut decoded: Vec<u8> = Vec::new();
     let mut res: i32 = 0;
@@ -2107,7 +2213,7 @@ fn cabac_decode_coeff(
             ctx_block_cat,
             &additional_inputs,
             vp,
-        );
+        )?;
 
         let mut idx1 = sh.cabac_init_idc as usize;
         let idx2 = sh.slice_qp_y as usize;
@@ -2122,7 +2228,7 @@ fn cabac_decode_coeff(
             debug!(target: "decode","cod_i_offset: {:x}", state.cod_i_offset << 7);
             debug!(target: "decode","state: {:x}", state.states[idx1][idx2][ctx_idx].p_state_idx);
         }
-        let ad = arith_decode(idx1, idx2, ctx_idx, bs, state);
+        let ad = arith_decode(idx1, idx2, ctx_idx, bs, state)?;
         if CABAC_DEBUG {
             debug!(
                 "updated state: {:x}",
@@ -2130,7 +2236,7 @@ fn cabac_decode_coeff(
             );
         }
         decoded.push(ad);
-        match debinarization(syntax_element, &decoded, sh) {
+        match debinarization(syntax_element, &decoded, sh)? {
             Some(x) => {
                 res = x;
                 done_decoding = true;
@@ -2147,9 +2253,9 @@ fn cabac_decode_coeff(
         let mut suffix: i32 = 0;
 
         while !done_decoding {
-            let ad = decode_bypass(bs, state) as u8;
+            let ad = decode_bypass(bs, state)?;
             decoded.push(ad);
-            if let Some(x) = debinarization(syntax_element, &decoded, sh) {
+            if let Some(x) = debinarization(syntax_element, &decoded, sh)? {
                 suffix = x;
                 done_decoding = true;
             }
@@ -2158,7 +2264,7 @@ fn cabac_decode_coeff(
         res = (res << 4) | suffix;
     }
 
-    res
+    Ok(res)
 }
 
 /// mb_type is broken up into prefix and suffix so we handle those cases here
@@ -2172,7 +2278,7 @@ fn cabac_decode_mbtype(
     vp: &VideoParameters,
     ctx_block_cat: u8,
     mut additional_inputs: Vec<usize>,
-) -> i32 {
+) -> Result<i32, DecodeError> {
     let mut decoded: Vec<u8> = Vec::new();
     let mut res: i32 = 0;
     let mut decode_suffix = true;
@@ -2255,10 +2361,10 @@ fn cabac_decode_mbtype(
             ctx_block_cat,
             &additional_inputs,
             vp,
-        );
+        )?;
         if ctx_idx == 276 {
             // DecodeTerminate
-            let ad = decode_terminate(bs, state);
+            let ad = decode_terminate(bs, state)?;
             decoded.push(ad);
         } else {
             let mut idx1 = sh.cabac_init_idc as usize;
@@ -2288,7 +2394,7 @@ fn cabac_decode_mbtype(
                 }
             }
 
-            let ad = arith_decode(idx1, idx2, ctx_idx, bs, state);
+            let ad = arith_decode(idx1, idx2, ctx_idx, bs, state)?;
             if CABAC_DEBUG {
                 debug!(
                     "updated state: {:x}",
@@ -2298,7 +2404,7 @@ fn cabac_decode_mbtype(
             decoded.push(ad);
         }
 
-        match debinarization(&(syntax_element.to_owned() + "_prefix"), &decoded, sh) {
+        match debinarization(&(syntax_element.to_owned() + "_prefix"), &decoded, sh)? {
             Some(x) => {
                 res = x;
                 still_decoding = false;
@@ -2403,10 +2509,10 @@ fn cabac_decode_mbtype(
                 ctx_block_cat,
                 &additional_inputs,
                 vp,
-            );
+            )?;
             if ctx_idx == 276 {
                 // DecodeTerminate
-                let ad = decode_terminate(bs, state);
+                let ad = decode_terminate(bs, state)?;
                 decoded.push(ad);
             } else {
                 let mut idx1 = sh.cabac_init_idc as usize;
@@ -2440,7 +2546,7 @@ fn cabac_decode_mbtype(
                     debug!(target: "decode","\tctx_idx: {}", ctx_idx);
                 }
 
-                let ad = arith_decode(idx1, idx2, ctx_idx, bs, state);
+                let ad = arith_decode(idx1, idx2, ctx_idx, bs, state)?;
                 if CABAC_DEBUG {
                     debug!(
                         "updated state: {:x}",
@@ -2451,7 +2557,7 @@ fn cabac_decode_mbtype(
                 decoded.push(ad);
             }
 
-            match debinarization(&(syntax_element.to_owned() + "_suffix"), &decoded, sh) {
+            match debinarization(&(syntax_element.to_owned() + "_suffix"), &decoded, sh)? {
                 Some(x) => {
                     res = x + suffix_offset_value;
                     still_decoding = false;
@@ -2461,7 +2567,7 @@ fn cabac_decode_mbtype(
         }
     }
 
-    res
+    Ok(res)
 }
 
 /// Similar to the coeffs above, mvd uses UEGK split between prefix and suffix. We use a separate
@@ -2475,7 +2581,7 @@ fn cabac_decode_mvd(
     sd: &mut SliceData,
     vp: &VideoParameters,
     additional_inputs: &[usize],
-) -> i32 {
+) -> Result<i32, DecodeError> {
     let mut decoded: Vec<u8> = Vec::new();
     let mut res: i32 = 0;
 
@@ -2892,7 +2998,7 @@ fn cabac_decode_mvd(
             debug!(target: "decode","state: {:x}", state.states[idx1][idx2][ctx_idx].p_state_idx);
         }
 
-        let ad = arith_decode(idx1, idx2, ctx_idx, bs, state);
+        let ad = arith_decode(idx1, idx2, ctx_idx, bs, state)?;
         if CABAC_DEBUG {
             debug!(
                 "updated state: {:x}",
@@ -2922,7 +3028,7 @@ fn cabac_decode_mvd(
 
         // there seems to be an extra check at the start
         while {
-            let ad = decode_bypass(bs, state);
+            let ad = decode_bypass(bs, state)?;
             if ad == 1 {
                 symbol += 1 << k;
                 k += 1;
@@ -2931,7 +3037,7 @@ fn cabac_decode_mvd(
         } {}
         k -= 1;
         while k >= 0 {
-            let ad = decode_bypass(bs, state);
+            let ad = decode_bypass(bs, state)?;
             if ad == 1 {
                 binary_symbol |= 1 << k;
             }
@@ -2940,13 +3046,13 @@ fn cabac_decode_mvd(
         res = symbol + binary_symbol + 9; // the 9 is the truncated unary value that we have read
     }
     if res != 0 {
-        let ad = decode_bypass(bs, state);
+        let ad = decode_bypass(bs, state)?;
         if ad == 1 {
             res = -res;
         }
     }
 
-    res
+    Ok(res)
 }
 
 /// CABAC decode a syntax element
@@ -2976,7 +3082,7 @@ pub fn cabac_decode(
     vp: &VideoParameters,
     ctx_block_cat: u8,
     mut additional_inputs: Vec<usize>,
-) -> i32 {
+) -> Result<i32, DecodeError> {
     if CABAC_DEBUG {
         debug!(target: "decode","");
         debug!(target: "decode","");
@@ -3030,7 +3136,7 @@ pub fn cabac_decode(
     let mut res: i32 = 0;
 
     let (max_bin_idx_ctx, ctx_idx_offset, bypass_flag) =
-        get_binarization_params(syntax_element, ctx_block_cat, sh, sd, curr_mb_idx);
+        get_binarization_params(syntax_element, ctx_block_cat, sh, sd, curr_mb_idx)?;
     // if we're not bypassing, then we're arithmetic decoding
     if !bypass_flag {
         let mut bin_idx = 0;
@@ -3056,11 +3162,11 @@ pub fn cabac_decode(
                 ctx_block_cat,
                 &additional_inputs,
                 vp,
-            );
+            )?;
 
             if ctx_idx == 276 {
                 // DecodeTerminate
-                let ad = decode_terminate(bs, state);
+                let ad = decode_terminate(bs, state)?;
                 decoded.push(ad);
             } else {
                 let mut idx1 = sh.cabac_init_idc as usize;
@@ -3093,7 +3199,7 @@ pub fn cabac_decode(
                         debug!(target: "decode","state ctx_idx+3: {:x}", state.states[idx1][idx2][ctx_idx+3].p_state_idx);
                     }
                 }
-                let ad = arith_decode(idx1, idx2, ctx_idx, bs, state);
+                let ad = arith_decode(idx1, idx2, ctx_idx, bs, state)?;
                 if CABAC_DEBUG {
                     debug!(
                         "updated state: {:x}",
@@ -3104,7 +3210,7 @@ pub fn cabac_decode(
                 decoded.push(ad);
             }
 
-            match debinarization(syntax_element, &decoded, sh) {
+            match debinarization(syntax_element, &decoded, sh)? {
                 Some(x) => {
                     res = x;
                     still_decoding = false;
@@ -3114,7 +3220,7 @@ pub fn cabac_decode(
         }
     } else {
         // bypass context model approach
-        res = decode_bypass(bs, state) as i32;
+        res = decode_bypass(bs, state)? as i32;
     }
-    res
+    Ok(res)
 }
@@ -7,13 +7,13 @@ use crate::common::cavlc_tables::MAPPED_EXP_GOLOMB_CAT03;
 use crate::common::cavlc_tables::MAPPED_EXP_GOLOMB_CAT12;
 use crate::common::data_structures::CoeffToken;
 use crate::common::data_structures::MacroBlock;
-use crate::common::data_structures::MbPartPredMode;
 use crate::common::data_structures::MbType;
 use crate::common::data_structures::ResidualMode;
 use crate::common::data_structures::SliceData;
 use crate::common::data_structures::TransformBlock;
 use crate::common::data_structures::VideoParameters;
 use crate::common::helper::ByteStream;
+use crate::decoder::error::DecodeError;
 use crate::decoder::expgolomb::exp_golomb_decode_one_wrapper;
 use log::debug;
 
@@ -26,10 +26,16 @@ pub fn mapped_exp_golomb_decode(
     chroma_array_type: u8,
     intra_mode: usize,
     bs: &mut ByteStream,
-) -> i32 {
+) -> Result<i32, DecodeError> {
     // first recover the codeNum
     let res: i32;
-    let code_num = exp_golomb_decode_one_wrapper(bs, false, 0) as usize;
+    let code_num = exp_golomb_decode_one_wrapper(bs, false, 0)? as usize;
+    if code_num >= MAPPED_EXP_GOLOMB_CAT12.len() {
+        return Err(DecodeError::new(format!(
+            "mapped_exp_golomb_decode - codeNum {} is outside of Table 9-4",
+            code_num
+        )));
+    }
 
     // follow the mapping in 9.1.2
     if chroma_array_type == 1 || chroma_array_type == 2 {
@@ -37,10 +43,13 @@ pub fn mapped_exp_golomb_decode(
     } else if chroma_array_type == 0 || chroma_array_type == 3 {
         res = MAPPED_EXP_GOLOMB_CAT03[code_num][intra_mode];
     } else {
-        panic!("Wrong chroma_array_type: {}", chroma_array_type);
+        return Err(DecodeError::new(format!(
+            "Wrong chroma_array_type: {}",
+            chroma_array_type
+        )));
     }
 
-    res
+    Ok(res)
 }
 
 /// Truncated Exp-Golomb-coded syntax element
@@ -52,13 +61,16 @@ pub fn mapped_exp_golomb_decode(
 /// - if x is equal to 1, then return !read_bits(1)
 ///
 /// Described in clause 9.1
-pub fn truncated_exp_golomb_decode(max_value: u32, bs: &mut ByteStream) -> u32 {
+pub fn truncated_exp_golomb_decode(
+    max_value: u32,
+    bs: &mut ByteStream,
+) -> Result<u32, DecodeError> {
     let res: u32 = if max_value == 1 {
-        1 - bs.read_bits(1)
+        1 - bs.read_bits(1)?
     } else {
-        exp_golomb_decode_one_wrapper(bs, false, 0) as u32
+        exp_golomb_decode_one_wrapper(bs, false, 0)? as u32
     };
-    res
+    Ok(res)
 }
 
 /// CoeffToken decoding
@@ -77,7 +89,7 @@ pub fn cavlc_decode_coeff_token(
     i_cb_cr: usize,
     bs: &mut ByteStream,
     vp: &VideoParameters,
-) -> CoeffToken {
+) -> Result<CoeffToken, DecodeError> {
     // First calculate nC, then return the total_coeff and trailing_ones
     let n_c: i8;
 
@@ -119,55 +131,29 @@ pub fn cavlc_decode_coeff_token(
                 blk_idx_a = res.2;
                 blk_idx_b = res.3;
 
-                if mb_a.available && (mb_a.coded_block_pattern_luma >> (blk_idx_a >> 2)) & 1 != 0 {
-                    if mb_a.mb_part_pred_mode(0) == MbPartPredMode::Intra16x16 {
-                        blk_a = mb_a.intra_16x16_ac_level_transform_blocks[blk_idx_a].clone();
-                    } else {
-                        blk_a = mb_a.luma_level_4x4_transform_blocks[blk_idx_a].clone();
-                    }
-                } else {
-                    blk_a = TransformBlock::new();
-                }
-
-                if mb_b.available && (mb_b.coded_block_pattern_luma >> (blk_idx_b >> 2)) & 1 != 0 {
-                    if mb_b.mb_part_pred_mode(0) == MbPartPredMode::Intra16x16 {
-                        blk_b = mb_b.intra_16x16_ac_level_transform_blocks[blk_idx_b].clone();
-                    } else {
-                        blk_b = mb_b.luma_level_4x4_transform_blocks[blk_idx_b].clone();
-                    }
-                } else {
-                    blk_b = TransformBlock::new();
-                }
-
                 ac_mode = 0;
             }
             ResidualMode::CbIntra16x16DCLevel
             | ResidualMode::CbIntra16x16ACLevel
             | ResidualMode::CbLevel4x4 => {
-                let _res = sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp);
-                //mb_a = res.0;
-                //mb_b = res.1;
-                //blk_idx_a = res.2;
-                //blk_idx_b = res.3;
-
-                // TODO: CAVLC 422/444
-                panic!("sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp); - no TransformBlock yet");
+                let res = sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp);
+                mb_a = res.0;
+                mb_b = res.1;
+                blk_idx_a = res.2;
+                blk_idx_b = res.3;
 
-                //ac_mode = 1;
+                ac_mode = 1;
             }
             ResidualMode::CrIntra16x16DCLevel
             | ResidualMode::CrIntra16x16ACLevel
             | ResidualMode::CrLevel4x4 => {
-                let _res = sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp);
-                //mb_a = res.0;
-                //mb_b = res.1;
-                //blk_idx_a = res.2;
-                //blk_idx_b = res.3;
-
-                // TODO: CAVLC 422/444
-                panic!("sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp); - no TransformBlock yet");
+                let res = sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp);
+                mb_a = res.0;
+                mb_b = res.1;
+                blk_idx_a = res.2;
+                blk_idx_b = res.3;
 
-                //ac_mode = 2;
+                ac_mode = 2;
             }
             ResidualMode::ChromaACLevel => {
                 let res = sd.get_neighbor_4x4_chroma_block(curr_mb_idx, blk_idx, vp);
@@ -192,6 +178,17 @@ pub fn cavlc_decode_coeff_token(
             }
             _ => (),
         }
+
+        // Luma, and Cb and Cr when ChromaArrayType is 3, share the same 4x4 block layout
+        if ac_mode < 3 {
+            if mb_a.available {
+                blk_a = mb_a.get_4x4_transform_block(ac_mode, blk_idx_a);
+            }
+            if mb_b.available {
+                blk_b = mb_b.get_4x4_transform_block(ac_mode, blk_idx_b);
+            }
+        }
+
         debug!(target: "decode","blk_idx_a {}, blk_idx_b {}", blk_idx_a, blk_idx_b);
         // step 5
         let mut available_flag_a: bool = true;
@@ -273,7 +270,7 @@ pub fn cavlc_decode_coeff_token(
     let total_coeff: usize;
     let trailing_ones: usize;
     loop {
-        key += &bs.read_bits(1).to_string();
+        key += &bs.read_bits(1)?.to_string();
         debug!(target: "decode","cavlc_decode_coeff_token - key: {:?}", key);
         if coeff_token_mapping.contains_key(&key) {
             let res = coeff_token_mapping[&key];
@@ -283,34 +280,37 @@ pub fn cavlc_decode_coeff_token(
         }
 
         if key.len() > 16 {
-            panic!("key not found in coeff_token_mapping: {:?}", key);
+            return Err(DecodeError::new(format!(
+                "key not found in coeff_token_mapping: {:?}",
+                key
+            )));
         }
     }
 
     // NOTE: if max_num_coeff == 15 then total_coeff cannot be equal to 16 for bitstream conformance
 
-    CoeffToken {
+    Ok(CoeffToken {
         total_coeff,
         trailing_ones,
         n_c,
-    }
+    })
 }
 
 /// Level prefix decoding
 ///
 /// Decodes level_prefix by counting the number of zeros in the stream
 /// Described in section 9.2.2.1
-pub fn cavlc_decode_level_prefix(bs: &mut ByteStream) -> u32 {
+pub fn cavlc_decode_level_prefix(bs: &mut ByteStream) -> Result<u32, DecodeError> {
     let mut leading_zeros = 0;
 
     // see section 9.2.2.1 and Table 9-6 as an example
-    let mut cur_bit = bs.read_bits(1);
+    let mut cur_bit = bs.read_bits(1)?;
     while cur_bit == 0 {
         leading_zeros += 1;
-        cur_bit = bs.read_bits(1);
+        cur_bit = bs.read_bits(1)?;
     }
 
-    leading_zeros
+    Ok(leading_zeros)
 }
 
 /// Level suffix decoding
@@ -320,7 +320,7 @@ pub fn cavlc_decode_level_suffix(
     suffix_length: u32,
     level_prefix: u32,
     bs: &mut ByteStream,
-) -> u32 {
+) -> Result<u32, DecodeError> {
     let level_suffix_size: u32 = if level_prefix == 14 && suffix_length == 0 {
         4
     } else if level_prefix >= 15 {
@@ -331,12 +331,12 @@ pub fn cavlc_decode_level_suffix(
     debug!(target: "decode","cavlc_decode_level_suffix - level_suffix_size: {}", level_suffix_size);
 
     let level_suffix: u32 = if level_suffix_size > 0 {
-        bs.read_bits(level_suffix_size as u8)
+        bs.read_bits(level_suffix_size as u8)?
     } else {
         0
     };
 
-    level_suffix
+    Ok(level_suffix)
 }
 
 /// Total zeroes decoding
@@ -350,14 +350,14 @@ pub fn cavlc_decode_total_zeros(
     tz_vlc_index: usize,
     max_num_coeff: usize,
     bs: &mut ByteStream,
-) -> usize {
+) -> Result<usize, DecodeError> {
     let total_zeros: usize;
     debug!(target: "decode","cavlc_decode_total_zeros - max_num_coeff: {}; tz_vlc_index : {}", max_num_coeff, tz_vlc_index);
     let total_zeros_mapping = create_total_zeros_mappings(max_num_coeff, tz_vlc_index);
 
     let mut key: String = String::new();
     loop {
-        key += &bs.read_bits(1).to_string();
+        key += &bs.read_bits(1)?.to_string();
         debug!(target: "decode","cavlc_decode_total_zeros - key: {:?}", key);
         if total_zeros_mapping.contains_key(&key) {
             total_zeros = total_zeros_mapping[&key];
@@ -365,20 +365,23 @@ pub fn cavlc_decode_total_zeros(
         }
     }
 
-    total_zeros
+    Ok(total_zeros)
 }
 
 /// Run before decoding
 ///
 /// Described in 9.2.3 and Table 9-10
-pub fn cavlc_decode_run_before(zeros_left: usize, bs: &mut ByteStream) -> usize {
+pub fn cavlc_decode_run_before(
+    zeros_left: usize,
+    bs: &mut ByteStream,
+) -> Result<usize, DecodeError> {
     let run_before: usize;
 
     let run_before_mapping = create_run_before_mappings(zeros_left);
 
     let mut key: String = String::new();
     loop {
-        key += &bs.read_bits(1).to_string();
+        key += &bs.read_bits(1)?.to_string();
         debug!(target: "decode","cavlc_decode_run_before - key: {:?}", key);
         if run_before_mapping.contains_key(&key) {
             run_before = run_before_mapping[&key];
@@ -386,5 +389,5 @@ pub fn cavlc_decode_run_before(zeros_left: usize, bs: &mut ByteStream) -> usize
         }
     }
 
-    run_before
+    Ok(run_before)
 }
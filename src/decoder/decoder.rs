@@ -1,6 +1,8 @@
 //! Decoder entry point.
 
 use crate::common::data_structures::AccessUnitDelim;
+use crate::common::data_structures::DepthParameterSet;
+use crate::common::data_structures::FillerData;
 use crate::common::data_structures::H264DecodedStream;
 use crate::common::data_structures::NALUheader;
 use crate::common::data_structures::PicParameterSet;
@@ -9,67 +11,407 @@ use crate::common::data_structures::SEINalu;
 use crate::common::data_structures::SPSExtension;
 use crate::common::data_structures::SeqParameterSet;
 use crate::common::data_structures::Slice;
+use crate::common::data_structures::SliceDataPartition;
 use crate::common::data_structures::SubsetSPS;
+use crate::common::data_structures::NALU;
+use crate::common::helper::clear_last_decoded_syntax_element;
 use crate::common::helper::ByteStream;
+use crate::decoder::error::DecodeError;
 use crate::decoder::nalu::decode_access_unit_delimiter;
+use crate::decoder::nalu::decode_filler_data;
 use crate::decoder::nalu::decode_nalu_header;
 use crate::decoder::nalu::decode_prefix_nal_unit_svc;
 use crate::decoder::nalu::split_into_nalu;
+use crate::decoder::nalu::split_into_nalu_from_bytes;
+use crate::decoder::nalu::split_into_nalu_from_reader;
+use crate::decoder::nalu::NALUIterator;
+use crate::decoder::parameter_sets::decode_depth_parameter_set;
 use crate::decoder::parameter_sets::decode_pic_parameter_set;
 use crate::decoder::parameter_sets::decode_seq_parameter_set;
 use crate::decoder::parameter_sets::decode_sps_extension;
 use crate::decoder::parameter_sets::decode_subset_sps;
 use crate::decoder::sei::decode_sei_message;
+use crate::decoder::slice::decode_slice_data_partitions;
 use crate::decoder::slice::decode_slice_layer_extension_rbsp;
 use crate::decoder::slice::decode_slice_layer_without_partitioning_rbsp;
+use std::collections::VecDeque;
+use std::io;
+use std::io::Read;
 use std::time::SystemTime;
 
 /// Given the bytestream, it returns the decoded syntax elements
+///
+/// By default the first NALU that fails to decode is returned as a `DecodeError`.
+/// With `decode_lenient`, NALUs that fail to decode are kept as raw bytes in
+/// `nalu_elements` and their index is recorded in `undecoded_nalus`.
 pub fn decode_bitstream(
     filename: &str,
     only_headers: bool,
     perf_output: bool,
     decode_strict_fmo: bool,
-) -> H264DecodedStream {
+    decode_lenient: bool,
+) -> Result<H264DecodedStream, DecodeError> {
     let start_time = SystemTime::now();
-    let nalu_elements = split_into_nalu(filename);
+    let nalu_elements = split_into_nalu(filename)?;
 
     if perf_output {
-        let duration = start_time.elapsed();
-        match duration {
-            Ok(elapsed) => {
-                println!(
-                    "[PERF] decode_bitstream - split_into_nalu - duration: {} ns",
-                    elapsed.as_nanos()
-                );
+        print_perf("split_into_nalu", start_time);
+    }
+
+    decode_nalu_elements(
+        nalu_elements,
+        only_headers,
+        perf_output,
+        decode_strict_fmo,
+        decode_lenient,
+    )
+}
+
+/// Same as `decode_bitstream`, but for a bytestream that is already in memory
+pub fn decode_bitstream_from_bytes(
+    data: &[u8],
+    only_headers: bool,
+    perf_output: bool,
+    decode_strict_fmo: bool,
+    decode_lenient: bool,
+) -> Result<H264DecodedStream, DecodeError> {
+    let start_time = SystemTime::now();
+    let nalu_elements = split_into_nalu_from_bytes(data);
+
+    if perf_output {
+        print_perf("split_into_nalu_from_bytes", start_time);
+    }
+
+    decode_nalu_elements(
+        nalu_elements,
+        only_headers,
+        perf_output,
+        decode_strict_fmo,
+        decode_lenient,
+    )
+}
+
+/// Same as `decode_bitstream`, but reads the bytestream until the end of `reader`
+pub fn decode_bitstream_from_reader<R: Read>(
+    reader: R,
+    only_headers: bool,
+    perf_output: bool,
+    decode_strict_fmo: bool,
+    decode_lenient: bool,
+) -> Result<H264DecodedStream, DecodeError> {
+    let start_time = SystemTime::now();
+    let nalu_elements = split_into_nalu_from_reader(reader)?;
+
+    if perf_output {
+        print_perf("split_into_nalu_from_reader", start_time);
+    }
+
+    decode_nalu_elements(
+        nalu_elements,
+        only_headers,
+        perf_output,
+        decode_strict_fmo,
+        decode_lenient,
+    )
+}
+
+/// Returns an iterator that decodes NALUs from `reader` one at a time, as soon as each
+/// one has fully arrived. Only the parameter sets are kept around between NALUs.
+pub fn decode_nalus<R: Read>(
+    reader: R,
+    only_headers: bool,
+    perf_output: bool,
+    decode_strict_fmo: bool,
+    decode_lenient: bool,
+) -> DecodedNALUIterator<NALUIterator<R>> {
+    DecodedNALUIterator::new(
+        NALUIterator::new(reader),
+        NALUDecoder::new(only_headers, perf_output, decode_strict_fmo, decode_lenient),
+    )
+}
+
+/// Decodes already split NALUs into a H264DecodedStream
+fn decode_nalu_elements(
+    nalu_elements: Vec<NALU>,
+    only_headers: bool,
+    perf_output: bool,
+    decode_strict_fmo: bool,
+    decode_lenient: bool,
+) -> Result<H264DecodedStream, DecodeError> {
+    let decoder = NALUDecoder::new(only_headers, perf_output, decode_strict_fmo, decode_lenient);
+    let mut ds = H264DecodedStream::new();
+
+    println!("\tFound {:?} NALUs", nalu_elements.len());
+
+    for d in DecodedNALUIterator::new(nalu_elements.into_iter().map(Ok), decoder) {
+        d?.append_to(&mut ds);
+    }
+
+    println!(
+        "\t decode_bitstream - Decoded a total of {} slices",
+        ds.slices.len()
+    );
+
+    if !ds.undecoded_nalus.is_empty() {
+        println!(
+            "\t decode_bitstream - Kept {} undecodable NALUs as raw bytes",
+            ds.undecoded_nalus.len()
+        );
+    }
+
+    Ok(ds)
+}
+
+/// The syntax elements recovered from a single NALU
+#[allow(clippy::large_enum_variant)]
+pub enum NALUPayload {
+    Slice(Slice),
+    /// Partition A, which holds the slice data merged from all partitions of the slice
+    SliceDataPartitionA(Slice, SliceDataPartition),
+    /// Partition B or C
+    SliceDataPartitionBC(SliceDataPartition),
+    SEI(SEINalu),
+    SPS(SeqParameterSet),
+    PPS(PicParameterSet),
+    AUD(AccessUnitDelim),
+    FillerData(FillerData),
+    SPSExtension(SPSExtension),
+    PrefixNALU(PrefixNALU),
+    SubsetSPS(SubsetSPS),
+    DPS(DepthParameterSet),
+    /// Nothing to decode, or decoding of this nal_unit_type is not supported
+    Empty,
+    /// Failed to decode in lenient mode, or a slice data partition B or C that does
+    /// not follow its partition A; only the raw bytes are kept
+    Undecoded,
+}
+
+/// A NALU with its decoded header and payload
+pub struct DecodedNALU {
+    pub nalu: NALU,
+    pub header: NALUheader,
+    pub payload: NALUPayload,
+}
+
+impl DecodedNALU {
+    /// Adds the NALU to the end of `ds`
+    pub fn append_to(self, ds: &mut H264DecodedStream) {
+        let nalu_idx = ds.nalu_elements.len();
+        ds.nalu_elements.push(self.nalu);
+        ds.nalu_headers.push(self.header);
+
+        match self.payload {
+            NALUPayload::Slice(x) => ds.slices.push(x),
+            NALUPayload::SliceDataPartitionA(x, y) => {
+                ds.slices.push(x);
+                ds.slice_partitions.push(y);
             }
+            NALUPayload::SliceDataPartitionBC(x) => ds.slice_partitions.push(x),
+            NALUPayload::SEI(x) => ds.seis.push(x),
+            NALUPayload::SPS(x) => ds.spses.push(x),
+            NALUPayload::PPS(x) => ds.ppses.push(x),
+            NALUPayload::AUD(x) => ds.auds.push(x),
+            NALUPayload::FillerData(x) => ds.filler_data.push(x),
+            NALUPayload::SPSExtension(x) => ds.sps_extensions.push(x),
+            NALUPayload::PrefixNALU(x) => ds.prefix_nalus.push(x),
+            NALUPayload::SubsetSPS(x) => ds.subset_spses.push(x),
+            NALUPayload::DPS(x) => ds.dpses.push(x),
+            NALUPayload::Empty => (),
+            NALUPayload::Undecoded => ds.undecoded_nalus.push(nalu_idx),
+        }
+    }
+}
+
+/// Decodes NALUs one at a time, keeping the parameter sets that later NALUs refer to
+pub struct NALUDecoder {
+    pub spses: Vec<SeqParameterSet>,
+    pub sps_extensions: Vec<SPSExtension>,
+    pub subset_spses: Vec<SubsetSPS>,
+    pub ppses: Vec<PicParameterSet>,
+    only_headers: bool,
+    perf_output: bool,
+    decode_strict_fmo: bool,
+    decode_lenient: bool,
+    nalu_idx: usize,
+}
+
+impl NALUDecoder {
+    pub fn new(
+        only_headers: bool,
+        perf_output: bool,
+        decode_strict_fmo: bool,
+        decode_lenient: bool,
+    ) -> NALUDecoder {
+        NALUDecoder {
+            spses: Vec::new(),
+            sps_extensions: Vec::new(),
+            subset_spses: Vec::new(),
+            ppses: Vec::new(),
+            only_headers,
+            perf_output,
+            decode_strict_fmo,
+            decode_lenient,
+            nalu_idx: 0,
+        }
+    }
+
+    /// Decodes the next NALU of the stream
+    pub fn decode(&mut self, nalu: NALU) -> Result<DecodedNALU, DecodeError> {
+        let i = self.nalu_idx;
+        self.nalu_idx += 1;
+
+        let mut nalu_data = ByteStream::new(nalu.content.clone());
+        let mut decoded_header: Option<NALUheader> = None;
+
+        clear_last_decoded_syntax_element();
+        let res = decode_nalu_header(nalu.longstartcode, &mut nalu_data).and_then(|header| {
+            decoded_header = Some(header.clone());
+            self.decode_payload(i, &header, &mut nalu_data)
+        });
+
+        match res {
+            Ok(payload) => Ok(DecodedNALU {
+                header: decoded_header.unwrap(),
+                nalu,
+                payload,
+            }),
             Err(e) => {
-                println!("Error: {:?}", e);
+                let e = e.locate(
+                    i,
+                    nalu.content.len(),
+                    nalu_data.bytestream.len(),
+                    nalu_data.byte_offset,
+                );
+                if !self.decode_lenient {
+                    return Err(e);
+                }
+
+                println!(
+                    "\t decode_bitstream - NALU {} - [WARNING] unable to decode, keeping raw bytes - {}",
+                    i, e
+                );
+                Ok(undecoded_nalu(nalu, decoded_header))
             }
         }
     }
 
-    let mut nalu_headers: Vec<NALUheader> = Vec::new();
-    let mut spses: Vec<SeqParameterSet> = Vec::new();
-    let mut subset_spses: Vec<SubsetSPS> = Vec::new();
-    let mut sps_extensions: Vec<SPSExtension> = Vec::new();
-    let mut ppses: Vec<PicParameterSet> = Vec::new();
-    let mut prefix_nalus: Vec<PrefixNALU> = Vec::new();
-    let mut seis: Vec<SEINalu> = Vec::new();
-    let mut slices: Vec<Slice> = Vec::new();
-    let mut auds: Vec<AccessUnitDelim> = Vec::new();
+    /// Decodes slice data partition A together with the partitions B and C of the
+    /// same slice that follow it. Partitions B and C are needed to decode the residual
+    /// of intra and inter macroblocks respectively, so they are decoded as a group.
+    /// If any partition fails to decode, the error refers to partition A.
+    pub fn decode_partitions(
+        &mut self,
+        nalu_a: NALU,
+        nalu_b: Option<NALU>,
+        nalu_c: Option<NALU>,
+    ) -> Result<Vec<DecodedNALU>, DecodeError> {
+        let i = self.nalu_idx;
+        self.nalu_idx += 1 + nalu_b.is_some() as usize + nalu_c.is_some() as usize;
 
-    println!("\tFound {:?} NALUs", nalu_elements.len());
+        let mut a_data = ByteStream::new(nalu_a.content.clone());
+        let mut b_data = nalu_b.as_ref().map(|n| ByteStream::new(n.content.clone()));
+        let mut c_data = nalu_c.as_ref().map(|n| ByteStream::new(n.content.clone()));
+        let mut header_a: Option<NALUheader> = None;
+        let mut header_b: Option<NALUheader> = None;
+        let mut header_c: Option<NALUheader> = None;
 
-    for (i, n) in nalu_elements.iter().enumerate() {
-        let mut nalu_data = ByteStream::new(n.content.clone());
+        clear_last_decoded_syntax_element();
+        let res = decode_nalu_header(nalu_a.longstartcode, &mut a_data).and_then(|header| {
+            header_a = Some(header.clone());
+            if let (Some(n), Some(bs)) = (&nalu_b, b_data.as_mut()) {
+                header_b = Some(decode_nalu_header(n.longstartcode, bs)?);
+            }
+            if let (Some(n), Some(bs)) = (&nalu_c, c_data.as_mut()) {
+                header_c = Some(decode_nalu_header(n.longstartcode, bs)?);
+            }
 
-        let header = decode_nalu_header(n.longstartcode, &mut nalu_data);
-        nalu_headers.push(header.clone());
+            println!(
+                "\t decode_bitstream - NALU {} - {} - Coded slice data partition A{}{}",
+                i,
+                header.nal_unit_type,
+                if nalu_b.is_some() {
+                    " with partition B"
+                } else {
+                    ""
+                },
+                if nalu_c.is_some() {
+                    " with partition C"
+                } else {
+                    ""
+                },
+            );
+            let start_time = SystemTime::now();
+            let res = decode_slice_data_partitions(
+                &mut a_data,
+                b_data.as_mut(),
+                c_data.as_mut(),
+                &header,
+                &self.spses,
+                &self.ppses,
+                self.only_headers,
+                self.decode_strict_fmo,
+            );
+            if self.perf_output {
+                print_perf("decode_slice_data_partitions", start_time);
+            }
+            res
+        });
 
-        match header.nal_unit_type {
+        match res {
+            Ok((slice, partitions)) => {
+                let mut partitions = partitions.into_iter();
+                let mut res = vec![DecodedNALU {
+                    nalu: nalu_a,
+                    header: header_a.unwrap(),
+                    payload: NALUPayload::SliceDataPartitionA(slice, partitions.next().unwrap()),
+                }];
+                for (nalu, header) in [(nalu_b, header_b), (nalu_c, header_c)] {
+                    if let Some(nalu) = nalu {
+                        res.push(DecodedNALU {
+                            nalu,
+                            header: header.unwrap(),
+                            payload: NALUPayload::SliceDataPartitionBC(partitions.next().unwrap()),
+                        });
+                    }
+                }
+                Ok(res)
+            }
+            Err(e) => {
+                let e = e.locate(
+                    i,
+                    nalu_a.content.len(),
+                    a_data.bytestream.len(),
+                    a_data.byte_offset,
+                );
+                if !self.decode_lenient {
+                    return Err(e);
+                }
+
+                println!(
+                    "\t decode_bitstream - NALU {} - [WARNING] unable to decode slice data partitions, keeping raw bytes - {}",
+                    i, e
+                );
+                let mut res = vec![undecoded_nalu(nalu_a, header_a)];
+                for (nalu, header) in [(nalu_b, header_b), (nalu_c, header_c)] {
+                    if let Some(nalu) = nalu {
+                        res.push(undecoded_nalu(nalu, header));
+                    }
+                }
+                Ok(res)
+            }
+        }
+    }
+
+    fn decode_payload(
+        &mut self,
+        i: usize,
+        header: &NALUheader,
+        nalu_data: &mut ByteStream,
+    ) -> Result<NALUPayload, DecodeError> {
+        Ok(match header.nal_unit_type {
             0 => {
-                println!("\t decode_bitstream - NALU {} - {} - Unknown nal_unit_type of 0 - not affecting decoding process", i,  header.nal_unit_type)
+                println!("\t decode_bitstream - NALU {} - {} - Unknown nal_unit_type of 0 - not affecting decoding process", i,  header.nal_unit_type);
+                NALUPayload::Empty
             }
             1 => {
                 println!(
@@ -78,43 +420,49 @@ pub fn decode_bitstream(
                 );
                 let start_time = SystemTime::now();
                 let slice = decode_slice_layer_without_partitioning_rbsp(
-                    &mut nalu_data,
-                    &header,
-                    &spses,
-                    &ppses,
-                    only_headers,
-                    decode_strict_fmo,
-                );
-                if perf_output {
-                    let duration = start_time.elapsed();
-                    match duration {
-                        Ok(elapsed) => {
-                            println!("[PERF] decode_bitstream - decode_slice_layer_without_partitioning_rbsp - duration: {} ns", elapsed.as_nanos());
-                        }
-                        Err(e) => {
-                            println!("Error: {:?}", e);
-                        }
-                    }
+                    nalu_data,
+                    header,
+                    &self.spses,
+                    &self.sps_extensions,
+                    &self.ppses,
+                    self.only_headers,
+                    self.decode_strict_fmo,
+                )?;
+                if self.perf_output {
+                    print_perf("decode_slice_layer_without_partitioning_rbsp", start_time);
                 }
-                slices.push(slice);
+                NALUPayload::Slice(slice)
             }
             2 => {
+                // partitions B and C are decoded along with A in `decode_partitions`
                 println!(
                     "\t decode_bitstream - NALU {} - {} - Coded slice data partition A",
                     i, header.nal_unit_type
                 );
+                let start_time = SystemTime::now();
+                let (slice, mut partitions) = decode_slice_data_partitions(
+                    nalu_data,
+                    None,
+                    None,
+                    header,
+                    &self.spses,
+                    &self.ppses,
+                    self.only_headers,
+                    self.decode_strict_fmo,
+                )?;
+                if self.perf_output {
+                    print_perf("decode_slice_data_partitions", start_time);
+                }
+                NALUPayload::SliceDataPartitionA(slice, partitions.remove(0))
             }
-            3 => {
-                println!(
-                    "\t decode_bitstream - NALU {} - {} - Coded slice data partition B",
-                    i, header.nal_unit_type
-                );
-            }
-            4 => {
+            3 | 4 => {
                 println!(
-                    "\t decode_bitstream - NALU {} - {} - Coded slice data partition C",
-                    i, header.nal_unit_type
+                    "\t decode_bitstream - NALU {} - {} - [WARNING] Coded slice data partition {} without a preceding partition A, keeping raw bytes",
+                    i,
+                    header.nal_unit_type,
+                    if header.nal_unit_type == 3 { "B" } else { "C" }
                 );
+                NALUPayload::Undecoded
             }
             5 => {
                 println!(
@@ -123,30 +471,27 @@ pub fn decode_bitstream(
                 );
                 let start_time = SystemTime::now();
                 let slice = decode_slice_layer_without_partitioning_rbsp(
-                    &mut nalu_data,
-                    &header,
-                    &spses,
-                    &ppses,
-                    only_headers,
-                    decode_strict_fmo,
-                );
-                if perf_output {
-                    let duration = start_time.elapsed();
-                    match duration {
-                        Ok(elapsed) => {
-                            println!("[PERF] decode_bitstream - decode_slice_layer_without_partitioning_rbsp - duration: {} ns", elapsed.as_nanos());
-                        }
-                        Err(e) => {
-                            println!("Error: {:?}", e);
-                        }
-                    }
+                    nalu_data,
+                    header,
+                    &self.spses,
+                    &self.sps_extensions,
+                    &self.ppses,
+                    self.only_headers,
+                    self.decode_strict_fmo,
+                )?;
+                if self.perf_output {
+                    print_perf("decode_slice_layer_without_partitioning_rbsp", start_time);
                 }
-                slices.push(slice);
+                NALUPayload::Slice(slice)
             }
             6 => {
                 println!("\t decode_bitstream - NALU {} - {} - Supplemental enhancement information (SEI)", i,  header.nal_unit_type);
                 // not all SEI units need SPSes
-                seis.push(decode_sei_message(&spses, &mut nalu_data));
+                NALUPayload::SEI(decode_sei_message(
+                    &self.spses,
+                    &self.subset_spses,
+                    nalu_data,
+                )?)
             }
             7 => {
                 println!(
@@ -154,19 +499,12 @@ pub fn decode_bitstream(
                     i, header.nal_unit_type
                 );
                 let start_time = SystemTime::now();
-                let sps = decode_seq_parameter_set(&mut nalu_data);
-                if perf_output {
-                    let duration = start_time.elapsed();
-                    match duration {
-                        Ok(elapsed) => {
-                            println!("[PERF] decode_bitstream - decode_seq_parameter_set - duration: {} ns", elapsed.as_nanos());
-                        }
-                        Err(e) => {
-                            println!("Error: {:?}", e);
-                        }
-                    }
+                let sps = decode_seq_parameter_set(nalu_data)?;
+                if self.perf_output {
+                    print_perf("decode_seq_parameter_set", start_time);
                 }
-                spses.push(sps);
+                self.spses.push(sps.clone());
+                NALUPayload::SPS(sps)
             }
             8 => {
                 println!(
@@ -174,19 +512,12 @@ pub fn decode_bitstream(
                     i, header.nal_unit_type
                 );
                 let start_time = SystemTime::now();
-                let r = decode_pic_parameter_set(&mut nalu_data, &spses, &subset_spses);
-                if perf_output {
-                    let duration = start_time.elapsed();
-                    match duration {
-                        Ok(elapsed) => {
-                            println!("[PERF] decode_bitstream - decode_pic_parameter_set - duration: {} ns", elapsed.as_nanos());
-                        }
-                        Err(e) => {
-                            println!("Error: {:?}", e);
-                        }
-                    }
+                let pps = decode_pic_parameter_set(nalu_data, &self.spses, &self.subset_spses)?;
+                if self.perf_output {
+                    print_perf("decode_pic_parameter_set", start_time);
                 }
-                ppses.push(r);
+                self.ppses.push(pps.clone());
+                NALUPayload::PPS(pps)
             }
             9 => {
                 println!(
@@ -194,20 +525,11 @@ pub fn decode_bitstream(
                     i, header.nal_unit_type
                 );
                 let start_time = SystemTime::now();
-                let aud = decode_access_unit_delimiter(&mut nalu_data);
-                auds.push(aud);
-
-                if perf_output {
-                    let duration = start_time.elapsed();
-                    match duration {
-                        Ok(elapsed) => {
-                            println!("[PERF] decode_bitstream - decode_pic_parameter_set - duration: {} ns", elapsed.as_nanos());
-                        }
-                        Err(e) => {
-                            println!("Error: {:?}", e);
-                        }
-                    }
+                let aud = decode_access_unit_delimiter(nalu_data)?;
+                if self.perf_output {
+                    print_perf("decode_access_unit_delimiter", start_time);
                 }
+                NALUPayload::AUD(aud)
             }
             10 => {
                 println!(
@@ -216,6 +538,14 @@ pub fn decode_bitstream(
                 );
                 // According to 7.3.2.5 there is nothing to parse
                 // According to 7.4.2.5 this signals that the next NALU shall be an IDR
+                if !nalu_data.bytestream.is_empty() {
+                    println!(
+                        "\t decode_bitstream - NALU {} - [WARNING] End of Sequence carries {} trailing bytes",
+                        i,
+                        nalu_data.bytestream.len()
+                    );
+                }
+                NALUPayload::Empty
             }
             11 => {
                 println!(
@@ -224,6 +554,14 @@ pub fn decode_bitstream(
                 );
                 // According to 7.3.2.6 there is nothing to parse
                 // According to 7.4.2.6 this signals that there is nothing else to decode, so technically the decoder could `break;`
+                if !nalu_data.bytestream.is_empty() {
+                    println!(
+                        "\t decode_bitstream - NALU {} - [WARNING] End of Stream carries {} trailing bytes",
+                        i,
+                        nalu_data.bytestream.len()
+                    );
+                }
+                NALUPayload::Empty
             }
             12 => {
                 println!(
@@ -232,8 +570,7 @@ pub fn decode_bitstream(
                 );
                 // According to 7.3.2.7 and 7.4.2.7 this is, as the name describes, filler data
                 // that should be all 0xff bytes
-                // TODO: implement 7.3.2.7
-                //filler_data_rbsp();
+                NALUPayload::FillerData(decode_filler_data(nalu_data))
             }
             13 => {
                 println!(
@@ -241,22 +578,12 @@ pub fn decode_bitstream(
                     i, header.nal_unit_type
                 );
                 let start_time = SystemTime::now();
-                let sps_ext = decode_sps_extension(&mut nalu_data);
-                if perf_output {
-                    let duration = start_time.elapsed();
-                    match duration {
-                        Ok(elapsed) => {
-                            println!(
-                                "[PERF] decode_bitstream - decode_sps_extension - duration: {} ns",
-                                elapsed.as_nanos()
-                            );
-                        }
-                        Err(e) => {
-                            println!("Error: {:?}", e);
-                        }
-                    }
+                let sps_ext = decode_sps_extension(nalu_data)?;
+                if self.perf_output {
+                    print_perf("decode_sps_extension", start_time);
                 }
-                sps_extensions.push(sps_ext);
+                self.sps_extensions.push(sps_ext);
+                NALUPayload::SPSExtension(sps_ext)
             }
             14 => {
                 println!(
@@ -267,19 +594,13 @@ pub fn decode_bitstream(
                 if header.svc_extension_flag {
                     // described in G.7.3.2.12.1
                     let start_time = SystemTime::now();
-                    let prefix_nal = decode_prefix_nal_unit_svc(header, &mut nalu_data);
-                    if perf_output {
-                        let duration = start_time.elapsed();
-                        match duration {
-                            Ok(elapsed) => {
-                                println!("[PERF] decode_bitstream - decode_prefix_nal_unit_svc - duration: {} ns", elapsed.as_nanos());
-                            }
-                            Err(e) => {
-                                println!("Error: {:?}", e);
-                            }
-                        }
+                    let prefix_nal = decode_prefix_nal_unit_svc(header.clone(), nalu_data)?;
+                    if self.perf_output {
+                        print_perf("decode_prefix_nal_unit_svc", start_time);
                     }
-                    prefix_nalus.push(prefix_nal);
+                    NALUPayload::PrefixNALU(prefix_nal)
+                } else {
+                    NALUPayload::Empty
                 }
             }
             15 => {
@@ -288,76 +609,80 @@ pub fn decode_bitstream(
                     i, header.nal_unit_type
                 );
                 let start_time = SystemTime::now();
-                let sub_sps = decode_subset_sps(&mut nalu_data);
-                if perf_output {
-                    let duration = start_time.elapsed();
-                    match duration {
-                        Ok(elapsed) => {
-                            println!(
-                                "[PERF] decode_bitstream - decode_subset_sps - duration: {} ns",
-                                elapsed.as_nanos()
-                            );
-                        }
-                        Err(e) => {
-                            println!("Error: {:?}", e);
-                        }
-                    }
+                let sub_sps = decode_subset_sps(nalu_data)?;
+                if self.perf_output {
+                    print_perf("decode_subset_sps", start_time);
                 }
-                subset_spses.push(sub_sps);
+                self.subset_spses.push(sub_sps.clone());
+                NALUPayload::SubsetSPS(sub_sps)
             }
             16 => {
                 println!(
                     "\t decode_bitstream - NALU {} - {} -  Depth parameter set",
                     i, header.nal_unit_type
                 );
-                // TODO: Annex J
-                //depth_parameter_set_rbsp();
+                let start_time = SystemTime::now();
+                let dps = decode_depth_parameter_set(nalu_data)?;
+                if self.perf_output {
+                    print_perf("decode_depth_parameter_set", start_time);
+                }
+                NALUPayload::DPS(dps)
+            }
+            17..=18 => {
+                println!(
+                    "\t decode_bitstream - NALU {} - {} - RESERVED nal_unit_type ignoring",
+                    i, header.nal_unit_type
+                );
+                NALUPayload::Empty
             }
-            17..=18 => println!(
-                "\t decode_bitstream - NALU {} - {} - RESERVED nal_unit_type ignoring",
-                i, header.nal_unit_type
-            ),
             19 => {
                 println!("\t decode_bitstream - NALU {} - {} - Coded slice of an auxiliary coded picture without partitioning", i,  header.nal_unit_type);
-                // slice_layer_without_partitioning_rbsp(); // but non-VCL
+                let start_time = SystemTime::now();
+                let slice = decode_slice_layer_without_partitioning_rbsp(
+                    nalu_data,
+                    header,
+                    &self.spses,
+                    &self.sps_extensions,
+                    &self.ppses,
+                    self.only_headers,
+                    self.decode_strict_fmo,
+                )?;
+                if self.perf_output {
+                    print_perf("decode_slice_layer_without_partitioning_rbsp", start_time);
+                }
+                NALUPayload::Slice(slice)
             }
-            20 => {
+            20 | 21 => {
                 // Multiview Coding is specified in Annex H, Scalable Video Coding in Annex G, 3D AVC in Annex J
-                println!(
-                    "\t decode_bitstream - NALU {} - {} - Coded slice extension",
-                    i, header.nal_unit_type
-                );
+                if header.nal_unit_type == 20 {
+                    println!(
+                        "\t decode_bitstream - NALU {} - {} - Coded slice extension",
+                        i, header.nal_unit_type
+                    );
+                } else {
+                    println!("\t decode_bitstream - NALU {} - {} - Coded slice extension for a depth view component or a 3D-AVC texture view component", i,  header.nal_unit_type);
+                }
                 let start_time = SystemTime::now();
                 let slice = decode_slice_layer_extension_rbsp(
-                    &mut nalu_data,
-                    &header,
-                    &subset_spses,
-                    &ppses,
-                    only_headers,
-                    decode_strict_fmo,
-                );
-                if perf_output {
-                    let duration = start_time.elapsed();
-                    match duration {
-                        Ok(elapsed) => {
-                            println!("[PERF] decode_bitstream - decode_slice_layer_extension_rbsp - duration: {} ns", elapsed.as_nanos());
-                        }
-                        Err(e) => {
-                            println!("Error: {:?}", e);
-                        }
-                    }
+                    nalu_data,
+                    header,
+                    &self.subset_spses,
+                    &self.ppses,
+                    self.only_headers,
+                    self.decode_strict_fmo,
+                )?;
+                if self.perf_output {
+                    print_perf("decode_slice_layer_extension_rbsp", start_time);
                 }
-                slices.push(slice);
+                NALUPayload::Slice(slice)
             }
-            21 => {
-                // specified in Annex J
-                println!("\t decode_bitstream - NALU {} - {} - Coded slice extension for a depth view component or a 3D-AVC texture view component", i,  header.nal_unit_type);
-                // slice_layer_extension_rbsp();
+            22..=23 => {
+                println!(
+                    "\t decode_bitstream - NALU {} - {} - RESERVED nal_unit_type ignoring",
+                    i, header.nal_unit_type
+                );
+                NALUPayload::Empty
             }
-            22..=23 => println!(
-                "\t decode_bitstream - NALU {} - {} - RESERVED nal_unit_type ignoring",
-                i, header.nal_unit_type
-            ),
             // The following types are from https://www.ietf.org/rfc/rfc3984.txt and updated in https://datatracker.ietf.org/doc/html/rfc6184
             24 => {
                 // STAP-A    Single-time aggregation packet     5.7.1
@@ -365,6 +690,7 @@ pub fn decode_bitstream(
                     "\t decode_bitstream - NALU {} - {} - RTP STAP-A",
                     i, header.nal_unit_type
                 );
+                NALUPayload::Empty
             }
             25 => {
                 // STAP-B    Single-time aggregation packet     5.7.1
@@ -372,6 +698,7 @@ pub fn decode_bitstream(
                     "\t decode_bitstream - NALU {} - {} - RTP STAP-B",
                     i, header.nal_unit_type
                 );
+                NALUPayload::Empty
             }
             26 => {
                 //MTAP16    Multi-time aggregation packet      5.7.2
@@ -379,6 +706,7 @@ pub fn decode_bitstream(
                     "\t decode_bitstream - NALU {} - {} - RTP MTAP16",
                     i, header.nal_unit_type
                 );
+                NALUPayload::Empty
             }
             27 => {
                 //MTAP24    Multi-time aggregation packet      5.7.2
@@ -386,6 +714,7 @@ pub fn decode_bitstream(
                     "\t decode_bitstream - NALU {} - {} - RTP MTAP24",
                     i, header.nal_unit_type
                 );
+                NALUPayload::Empty
             }
             28 => {
                 //FU-A      Fragmentation unit                 5.8
@@ -393,6 +722,7 @@ pub fn decode_bitstream(
                     "\t decode_bitstream - NALU {} - {} - RTP FU-A",
                     i, header.nal_unit_type
                 );
+                NALUPayload::Empty
             }
             29 => {
                 //FU-B      Fragmentation unit                 5.8
@@ -400,6 +730,7 @@ pub fn decode_bitstream(
                     "\t decode_bitstream - NALU {} - {} - RTP FU-B",
                     i, header.nal_unit_type
                 );
+                NALUPayload::Empty
             }
             // The following types are from SVC RTP https://datatracker.ietf.org/doc/html/rfc6190
             30 => {
@@ -408,6 +739,7 @@ pub fn decode_bitstream(
                     "\t decode_bitstream - NALU {} - {} - RTP SVC PACSI",
                     i, header.nal_unit_type
                 );
+                NALUPayload::Empty
             }
             31 => {
                 // This reads a subtype
@@ -420,29 +752,250 @@ pub fn decode_bitstream(
                     "\t decode_bitstream - NALU {} - {} - RTP SVC NALU",
                     i, header.nal_unit_type
                 );
+                NALUPayload::Empty
+            }
+            _ => {
+                println!(
+                    "\t decode_bitstream - NALU {} - {} - Unknown nal_unit_type ",
+                    i, header.nal_unit_type
+                );
+                NALUPayload::Empty
+            }
+        })
+    }
+}
+
+/// Decodes NALUs as they arrive. Stops after the first error.
+///
+/// A slice data partition A is held back until it is known whether partitions B
+/// and C directly follow it, since they are decoded together.
+pub struct DecodedNALUIterator<I: Iterator<Item = io::Result<NALU>>> {
+    nalus: I,
+    decoder: NALUDecoder,
+    lookahead: Option<io::Result<NALU>>,
+    pending: VecDeque<DecodedNALU>,
+    done: bool,
+}
+
+impl<I: Iterator<Item = io::Result<NALU>>> DecodedNALUIterator<I> {
+    pub fn new(nalus: I, decoder: NALUDecoder) -> DecodedNALUIterator<I> {
+        DecodedNALUIterator {
+            nalus,
+            decoder,
+            lookahead: None,
+            pending: VecDeque::new(),
+            done: false,
+        }
+    }
+
+    /// The decoder state, e.g., to look up the parameter sets a slice refers to
+    pub fn decoder(&self) -> &NALUDecoder {
+        &self.decoder
+    }
+
+    fn next_nalu(&mut self) -> Option<io::Result<NALU>> {
+        match self.lookahead.take() {
+            Some(n) => Some(n),
+            None => self.nalus.next(),
+        }
+    }
+
+    /// Returns the next NALU only if it has type `nal_unit_type`
+    fn next_nalu_if_type(&mut self, nal_unit_type: u8) -> Option<NALU> {
+        if self.lookahead.is_none() {
+            self.lookahead = self.nalus.next();
+        }
+        match &self.lookahead {
+            Some(Ok(n)) if raw_nalu_header(&n.content).nal_unit_type == nal_unit_type => {
+                match self.lookahead.take() {
+                    Some(Ok(n)) => Some(n),
+                    _ => None,
+                }
             }
-            _ => println!(
-                "\t decode_bitstream - NALU {} - {} - Unknown nal_unit_type ",
-                i, header.nal_unit_type
-            ),
+            _ => None,
+        }
+    }
+}
+
+impl<I: Iterator<Item = io::Result<NALU>>> Iterator for DecodedNALUIterator<I> {
+    type Item = Result<DecodedNALU, DecodeError>;
+
+    fn next(&mut self) -> Option<Result<DecodedNALU, DecodeError>> {
+        if let Some(d) = self.pending.pop_front() {
+            return Some(Ok(d));
+        }
+        if self.done {
+            return None;
+        }
+
+        let res = match self.next_nalu()? {
+            Ok(n) if raw_nalu_header(&n.content).nal_unit_type == 2 => {
+                let nalu_b = self.next_nalu_if_type(3);
+                let nalu_c = self.next_nalu_if_type(4);
+                self.decoder
+                    .decode_partitions(n, nalu_b, nalu_c)
+                    .map(|decoded| {
+                        self.pending.extend(decoded);
+                        self.pending.pop_front().unwrap()
+                    })
+            }
+            Ok(n) => self.decoder.decode(n),
+            Err(e) => Err(DecodeError::read_error(self.decoder.nalu_idx, &e)),
         };
+        self.done = res.is_err();
+
+        Some(res)
     }
+}
 
-    println!(
-        "\t decode_bitstream - Decoded a total of {} slices",
-        slices.len()
-    );
+/// Keeps the raw bytes of a NALU that failed to decode
+fn undecoded_nalu(nalu: NALU, header: Option<NALUheader>) -> DecodedNALU {
+    // the NALU header itself may have failed to decode
+    let header = match header {
+        Some(x) => x,
+        None => raw_nalu_header(&nalu.content),
+    };
+    DecodedNALU {
+        nalu,
+        header,
+        payload: NALUPayload::Undecoded,
+    }
+}
 
-    H264DecodedStream {
-        nalu_elements,
-        nalu_headers,
-        spses,
-        subset_spses,
-        sps_extensions,
-        ppses,
-        prefix_nalus,
-        slices,
-        seis,
-        auds,
+/// Recovers what we can of a NALU header from its first byte, without any extensions
+fn raw_nalu_header(content: &[u8]) -> NALUheader {
+    let mut header = NALUheader::new();
+    if let Some(b) = content.first() {
+        header.forbidden_zero_bit = b >> 7;
+        header.nal_ref_idc = (b >> 5) & 0x3;
+        header.nal_unit_type = b & 0x1f;
+    }
+    header
+}
+
+fn print_perf(function_name: &str, start_time: SystemTime) {
+    match start_time.elapsed() {
+        Ok(elapsed) => {
+            println!(
+                "[PERF] decode_bitstream - {} - duration: {} ns",
+                function_name,
+                elapsed.as_nanos()
+            );
+        }
+        Err(e) => {
+            println!("Error: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::SPS_PPS_I_P;
+
+    #[test]
+    fn test_decode_from_bytes_and_reader_match() {
+        let data = SPS_PPS_I_P;
+
+        let from_bytes = decode_bitstream_from_bytes(data, false, false, false, false).unwrap();
+        let from_reader = decode_bitstream_from_reader(data, false, false, false, false).unwrap();
+        let incremental: Vec<DecodedNALU> = decode_nalus(data, false, false, false, false)
+            .collect::<Result<Vec<DecodedNALU>, DecodeError>>()
+            .unwrap();
+
+        assert_eq!(from_bytes.nalu_elements.len(), 4);
+        assert_eq!(from_reader.nalu_elements.len(), 4);
+        assert_eq!(incremental.len(), 4);
+        assert_eq!(from_bytes.slices.len(), 2);
+        for (i, d) in incremental.iter().enumerate() {
+            assert_eq!(d.nalu.content, from_bytes.nalu_elements[i].content);
+            assert_eq!(d.nalu.content, from_reader.nalu_elements[i].content);
+        }
+    }
+
+    #[test]
+    fn test_decode_truncated_nalu() {
+        let data = SPS_PPS_I_P;
+        // cut the IDR slice, the third NALU, short
+        let mut bad = data[..31].to_vec();
+        bad.extend(&data[42..]);
+
+        let Err(e) = decode_bitstream_from_bytes(&bad, false, false, false, false) else {
+            panic!("truncated NALU should not decode");
+        };
+        assert_eq!(e.nalu_idx, 2);
+
+        let ds = decode_bitstream_from_bytes(&bad, false, false, false, true).unwrap();
+        assert_eq!(ds.undecoded_nalus, vec![2]);
+        assert_eq!(ds.nalu_headers[2].nal_unit_type, 5);
+        assert_eq!(ds.slices.len(), 1);
+    }
+
+    #[test]
+    fn test_decode_missing_pps() {
+        let data = SPS_PPS_I_P;
+        // drop the PPS, the second NALU, so the slices refer to a PPS that was never seen
+        let mut bad = data[..14].to_vec();
+        bad.extend(&data[24..]);
+
+        let Err(e) = decode_bitstream_from_bytes(&bad, false, false, false, false) else {
+            panic!("slice without its PPS should not decode");
+        };
+        assert_eq!(e.nalu_idx, 1);
+        assert_eq!(e.syntax_element, "SH: pic_parameter_set_id");
+        assert_eq!(e.message, "decode_slice_header - PPS with id 0 not found");
+    }
+
+    #[test]
+    fn test_decode_missing_file() {
+        let Err(e) = decode_bitstream("input_vids/does_not_exist.264", false, false, false, false)
+        else {
+            panic!("missing file should not decode");
+        };
+        assert_eq!(e.nalu_idx, 0);
+        assert!(e.message.starts_with("unable to read bitstream"));
+    }
+
+    #[test]
+    fn test_decode_mutated_streams() {
+        // malformed input must be reported with an error, never a panic
+        let streams: [&[u8]; 3] = [
+            SPS_PPS_I_P,
+            include_bytes!("../../input_vids/SPS_PPS_SPS_I_P.264"),
+            include_bytes!("../../input_vids/SPS_PPS_I_FMO.264"),
+        ];
+        let mut state: u64 = 1;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for stream in streams {
+            for _ in 0..300 {
+                // flip a bit, overwrite a byte or truncate, past the first start code
+                let mut bytes = stream.to_vec();
+                for _ in 0..1 + next() % 4 {
+                    let i = 5 + (next() as usize % (bytes.len() - 5));
+                    match next() % 3 {
+                        0 => bytes[i] ^= 1 << (next() % 8),
+                        1 => bytes[i] = next() as u8,
+                        _ => bytes.truncate(i.max(6)),
+                    }
+                }
+                for (decode_strict_fmo, decode_lenient) in
+                    [(false, false), (false, true), (true, false)]
+                {
+                    let _ = decode_bitstream_from_bytes(
+                        &bytes,
+                        false,
+                        false,
+                        decode_strict_fmo,
+                        decode_lenient,
+                    );
+                }
+            }
+        }
     }
 }
@@ -0,0 +1,89 @@
+//! Structured decoding errors.
+//!
+//! The syntax element readers return a `DecodeError` on malformed input (out of
+//! bounds reads, unknown VLC codes, missing parameter sets). The error is passed
+//! up to the NALU decoder, which records where in the stream it happened.
+
+use crate::common::helper::last_decoded_syntax_element;
+use std::fmt;
+use std::io;
+
+/// Describes where decoding of a bitstream stopped
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    /// Index of the NALU that failed to decode
+    pub nalu_idx: usize,
+    /// Bit offset into the NALU (header included) at which decoding stopped
+    pub bit_offset: usize,
+    /// Last syntax element successfully decoded before the failure
+    pub syntax_element: String,
+    /// Reason reported by the decoder
+    pub message: String,
+}
+
+impl DecodeError {
+    /// Builds the error for a syntax element reader that failed. The position is
+    /// filled in with `locate` once the error reaches the NALU decoder
+    pub fn new(message: String) -> DecodeError {
+        DecodeError {
+            nalu_idx: 0,
+            bit_offset: 0,
+            syntax_element: last_decoded_syntax_element(),
+            message,
+        }
+    }
+
+    /// Records that the error happened in NALU `nalu_idx` given the unread amount of its content
+    pub fn locate(
+        mut self,
+        nalu_idx: usize,
+        content_len: usize,
+        bytes_left: usize,
+        byte_offset: u8,
+    ) -> DecodeError {
+        self.nalu_idx = nalu_idx;
+        self.bit_offset = (content_len - bytes_left) * 8 + byte_offset as usize;
+        self
+    }
+
+    /// Builds the error for a failure to read the bytestream before NALU `nalu_idx`
+    pub fn read_error(nalu_idx: usize, e: &io::Error) -> DecodeError {
+        DecodeError {
+            nalu_idx,
+            bit_offset: 0,
+            syntax_element: String::new(),
+            message: format!("unable to read bitstream: {}", e),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NALU {} - bit offset {} - after syntax element \"{}\" - {}",
+            self.nalu_idx, self.bit_offset, self.syntax_element, self.message
+        )
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::helper::ByteStream;
+
+    #[test]
+    fn test_read_bits_error_reports_offset() {
+        let content = vec![0x67, 0xff];
+        let mut bs = ByteStream::new(content.clone());
+        bs.read_bits(8).unwrap();
+        bs.read_bits(3).unwrap();
+        let e = bs.read_bits(8).unwrap_err();
+        assert_eq!(e.message, "read_bits - Trying to read outside bounds!");
+
+        let e = e.locate(3, content.len(), bs.bytestream.len(), bs.byte_offset);
+        assert_eq!(e.nalu_idx, 3);
+        assert_eq!(e.bit_offset, 11);
+    }
+}
@@ -1,6 +1,7 @@
 //! Exp-golomb entropy decoding.
 
 use crate::common::helper::ByteStream;
+use crate::decoder::error::DecodeError;
 
 /// Exp-Golomb decode -- returns an option
 pub fn exp_golomb_decode_one(bs: &mut ByteStream, signed: bool, k: u8) -> Option<i32> {
@@ -13,6 +14,10 @@ pub fn exp_golomb_decode_one(bs: &mut ByteStream, signed: bool, k: u8) -> Option
     let mut foundstart = false;
 
     while !foundstart {
+        // out of data, or more leading zeros than a 32-bit value can have
+        if bs.bytestream.is_empty() || zerocount > 32 {
+            return None;
+        }
         let start_offset = bs.byte_offset;
         for i in (0..(8 - start_offset)).rev() {
             if ((bs.bytestream[0] >> i) & 1u8) == 0u8 {
@@ -38,7 +43,7 @@ pub fn exp_golomb_decode_one(bs: &mut ByteStream, signed: bool, k: u8) -> Option
     // add 1 to zerocount and that's how many bits we have to read
     zerocount += 1;
 
-    let mut res: i32 = bs.read_bits(zerocount) as i32; // we got the top most bit now
+    let mut res: i32 = bs.read_bits(zerocount).ok()? as i32; // we got the top most bit now
 
     // res has the raw number - we need to determine signed or not
 
@@ -52,18 +57,23 @@ pub fn exp_golomb_decode_one(bs: &mut ByteStream, signed: bool, k: u8) -> Option
         }
     } else {
         //kth order whenever it's unsigned
-        res = (res << k) + (bs.read_bits(k) as i32);
+        res = (res << k) + (bs.read_bits(k).ok()? as i32);
     }
 
     Some(res)
 }
 
 /// Exp-Golomb decode -- calls `exp_golomb_decode_one`
-pub fn exp_golomb_decode_one_wrapper(bs: &mut ByteStream, signed: bool, k: u8) -> i32 {
-    let r = exp_golomb_decode_one(bs, signed, k);
-    match r {
-        Some(x) => x,
-        _ => panic!("Error with exp_golomb decoding"),
+pub fn exp_golomb_decode_one_wrapper(
+    bs: &mut ByteStream,
+    signed: bool,
+    k: u8,
+) -> Result<i32, DecodeError> {
+    match exp_golomb_decode_one(bs, signed, k) {
+        Some(x) => Ok(x),
+        None => Err(DecodeError::new(String::from(
+            "exp_golomb_decode_one - Error with exp_golomb decoding",
+        ))),
     }
 }
 
@@ -168,10 +178,7 @@ mod tests {
             println!("Expected: {}; Got: {:?}", r.0, result);
             println!("ByteStream after: {:?}", bs);
 
-            match result {
-                Some(x) => assert!(r.0 == x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(r.0));
         }
     }
 
@@ -199,10 +206,7 @@ mod tests {
             let result = exp_golomb_decode_one(&mut bs, false, 0);
             println!("Expected: {}; Got: {:?}", r.0, result);
             println!("ByteStream after: {:?}", bs);
-            match result {
-                Some(x) => assert!(r.0 == x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(r.0));
         }
     }
 
@@ -222,10 +226,7 @@ mod tests {
             println!("Expected: {}; Got: {:?}", r, result);
             println!("ByteStream after: {:?}", bs);
 
-            match result {
-                Some(x) => assert!(r == &x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(*r));
         }
     }
 
@@ -246,10 +247,7 @@ mod tests {
             let result = exp_golomb_decode_one(&mut bs, false, 0);
             println!("Expected: {}; Got: {:?}", r, result);
             println!("ByteStream after: {:?}", bs);
-            match result {
-                Some(x) => assert!(r == &x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(*r));
         }
     }
 
@@ -277,10 +275,7 @@ mod tests {
             println!("Expected: {}; Got: {:?}", r.0, result);
             println!("ByteStream after: {:?}", bs);
 
-            match result {
-                Some(x) => assert!(r.0 == x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(r.0));
         }
     }
 
@@ -306,10 +301,7 @@ mod tests {
             let result = exp_golomb_decode_one(&mut bs, true, 0);
             println!("Expected: {}; Got: {:?}", r.0, result);
             println!("ByteStream after: {:?}", bs);
-            match result {
-                Some(x) => assert!(r.0 == x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(r.0));
         }
     }
 
@@ -335,10 +327,7 @@ mod tests {
             let result = exp_golomb_decode_one(&mut bs, r.2, 0);
             println!("Expected: {}; Got: {:?}", r.0, result);
             println!("ByteStream after: {:?}", bs);
-            match result {
-                Some(x) => assert!(r.0 == x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(r.0));
         }
     }
 
@@ -365,10 +354,7 @@ mod tests {
             println!("Expected: {}; Got: {:?}", r.0, result);
             println!("ByteStream after: {:?}", bs);
 
-            match result {
-                Some(x) => assert!(r.0 == x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(r.0));
         }
     }
 
@@ -399,10 +385,7 @@ mod tests {
             println!("Expected: {}; Got: {:?}", r.0, result);
             println!("ByteStream after: {:?}", bs);
 
-            match result {
-                Some(x) => assert!(r.0 == x),
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(r.0));
         }
     }
 
@@ -426,13 +409,7 @@ mod tests {
 
         for t in test_cases.iter() {
             let result = exp_golomb_decode_no_stream(&t.1, t.2, 0, false);
-            match result {
-                Some(x) => {
-                    println!("Expected: {}, got: {}", t.0, x);
-                    assert!(x == t.0);
-                }
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(t.0));
         }
     }
 
@@ -453,13 +430,7 @@ mod tests {
 
         for t in test_cases.iter() {
             let result = exp_golomb_decode_no_stream(&t.1, false, t.2, false);
-            match result {
-                Some(x) => {
-                    println!("Expected: {}, got: {}", t.0, x);
-                    assert!(x == t.0);
-                }
-                _ => panic!("error in implementation"),
-            }
+            assert_eq!(result, Some(t.0));
         }
     }
 }
@@ -22,6 +22,7 @@ use crate::decoder::cavlc::cavlc_decode_run_before;
 use crate::decoder::cavlc::cavlc_decode_total_zeros;
 use crate::decoder::cavlc::mapped_exp_golomb_decode;
 use crate::decoder::cavlc::truncated_exp_golomb_decode;
+use crate::decoder::error::DecodeError;
 use crate::decoder::expgolomb::exp_golomb_decode_one_wrapper;
 use log::debug;
 use std::cmp;
@@ -108,21 +109,23 @@ fn decode_mb_type(mut res: i32, sh: &SliceHeader) -> MbType {
 
 /// Turns the decoded sub_mb_type number into the actual
 /// Type. Based off of Tables 7-17 to 7-18
-fn decode_sub_mb_type(res: i32, sh: &SliceHeader) -> SubMbType {
+fn decode_sub_mb_type(res: i32, sh: &SliceHeader) -> Result<SubMbType, DecodeError> {
     // value to sub_mb_type matching done in table 9-38
     if is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "SP") {
-        match res {
+        Ok(match res {
             0 => SubMbType::PL08x8,
             1 => SubMbType::PL08x4,
             2 => SubMbType::PL04x8,
             3 => SubMbType::PL04x4,
-            _ => panic!(
-                "decode_sub_mb_type - Incorrect value provided for P slice: {:?}",
-                res
-            ),
-        }
+            _ => {
+                return Err(DecodeError::new(format!(
+                    "decode_sub_mb_type - Incorrect value provided for P slice: {:?}",
+                    res
+                )))
+            }
+        })
     } else if is_slice_type(sh.slice_type, "B") {
-        match res {
+        Ok(match res {
             0 => SubMbType::BDirect8x8,
             1 => SubMbType::BL08x8,
             2 => SubMbType::BL18x8,
@@ -136,20 +139,48 @@ fn decode_sub_mb_type(res: i32, sh: &SliceHeader) -> SubMbType {
             10 => SubMbType::BL04x4,
             11 => SubMbType::BL14x4,
             12 => SubMbType::BBi4x4,
-            _ => panic!(
-                "decode_sub_mb_type - Incorrect value provided for B slice: {:?}",
-                res
-            ),
-        }
+            _ => {
+                return Err(DecodeError::new(format!(
+                    "decode_sub_mb_type - Incorrect value provided for B slice: {:?}",
+                    res
+                )))
+            }
+        })
     } else {
-        panic!(
+        Err(DecodeError::new(format!(
             "decode_sub_mb_type - Incorrect slice_type provided: {:?}",
             sh.slice_type
-        );
+        )))
     }
 }
 
-/// Follows Section 7.3.5
+/// Slice data partitions B and C of a partitioned slice. Category 3 syntax
+/// elements (intra residual and PCM samples) are read from B, and category 4
+/// syntax elements (inter residual) are read from C. See Section 7.3.2.9
+pub struct ResidualPartitions<'a> {
+    pub b: Option<&'a mut ByteStream>,
+    pub c: Option<&'a mut ByteStream>,
+}
+
+impl ResidualPartitions<'_> {
+    /// Returns the partition holding the residual of an intra or inter macroblock
+    fn stream(&mut self, intra: bool) -> Result<&mut ByteStream, DecodeError> {
+        let (partition, name) = if intra {
+            (&mut self.b, "B")
+        } else {
+            (&mut self.c, "C")
+        };
+        match partition {
+            Some(x) => Ok(x),
+            None => Err(DecodeError::new(format!(
+                "decode_macroblock_layer - slice data partition {} is needed but not present",
+                name
+            ))),
+        }
+    }
+}
+
+/// Follows Section 7.3.5, and G.7.3.6 when the slice header is from a scalable extension
 pub fn decode_macroblock_layer(
     curr_mb_idx: usize,
     cabac_state: &mut CABACState,
@@ -159,29 +190,71 @@ pub fn decode_macroblock_layer(
     vp: &VideoParameters,
     s: &SeqParameterSet,
     p: &PicParameterSet,
-) {
-    let res: i32 = if p.entropy_coding_mode_flag {
-        cabac_decode(
-            "mb_type",
-            bs,
-            cabac_state,
-            curr_mb_idx,
-            sh,
-            sd,
-            vp,
-            0,
-            Vec::new(),
-        )
-    } else {
-        exp_golomb_decode_one_wrapper(bs, false, 0)
-    };
+    partitions: Option<&mut ResidualPartitions>,
+) -> Result<(), DecodeError> {
+    let in_crop_window = sh.in_crop_window(sd.macroblock_vec[curr_mb_idx].mb_addr, vp);
 
-    sd.macroblock_vec[curr_mb_idx].mb_type = decode_mb_type(res, sh);
+    if sh.svc_extension_flag {
+        if in_crop_window && sh.svc_extension.adaptive_base_mode_flag {
+            let res: i32 = if p.entropy_coding_mode_flag {
+                cabac_decode(
+                    "base_mode_flag",
+                    bs,
+                    cabac_state,
+                    curr_mb_idx,
+                    sh,
+                    sd,
+                    vp,
+                    0,
+                    Vec::new(),
+                )?
+            } else {
+                bs.read_bits(1)? as i32
+            };
+            sd.macroblock_vec[curr_mb_idx].base_mode_flag = res == 1;
+            decoder_formatted_print(
+                "base_mode_flag",
+                sd.macroblock_vec[curr_mb_idx].base_mode_flag,
+                63,
+            );
+        } else {
+            sd.macroblock_vec[curr_mb_idx].base_mode_flag =
+                in_crop_window && sh.svc_extension.default_base_mode_flag;
+        }
+    }
+    let base_mode_flag = sd.macroblock_vec[curr_mb_idx].base_mode_flag;
 
-    decoder_formatted_print("mb_type", &res, 63);
-    decoder_formatted_print("mb_type(fancy)", sd.macroblock_vec[curr_mb_idx].mb_type, 63);
+    // with base_mode_flag the macroblock type is inferred from the reference layer so it stays INONE
+    if !base_mode_flag {
+        let res: i32 = if p.entropy_coding_mode_flag {
+            cabac_decode(
+                "mb_type",
+                bs,
+                cabac_state,
+                curr_mb_idx,
+                sh,
+                sd,
+                vp,
+                0,
+                Vec::new(),
+            )?
+        } else {
+            exp_golomb_decode_one_wrapper(bs, false, 0)?
+        };
+
+        sd.macroblock_vec[curr_mb_idx].mb_type = decode_mb_type(res, sh);
+
+        decoder_formatted_print("mb_type", res, 63);
+        decoder_formatted_print("mb_type(fancy)", sd.macroblock_vec[curr_mb_idx].mb_type, 63);
+    }
 
     if sd.macroblock_vec[curr_mb_idx].mb_type == MbType::IPCM {
+        // PCM samples are in partition B when the slice is partitioned
+        let bs = match partitions {
+            Some(rp) => rp.stream(true)?,
+            None => bs,
+        };
+
         // zero align
         if bs.byte_offset > 0 {
             bs.byte_offset = 0;
@@ -192,6 +265,12 @@ pub fn decode_macroblock_layer(
 
         // fast-path: we're just reading bytes
         if vp.bit_depth_y == 8 {
+            if bs.bytestream.len() < 256 {
+                return Err(DecodeError::new(format!(
+                    "decode_macroblock_layer - {} bytes left for the 256 PCM luma samples",
+                    bs.bytestream.len()
+                )));
+            }
             for i in 0..256 {
                 sd.macroblock_vec[curr_mb_idx]
                     .pcm_sample_luma
@@ -208,7 +287,7 @@ pub fn decode_macroblock_layer(
             for i in 0..256 {
                 sd.macroblock_vec[curr_mb_idx]
                     .pcm_sample_luma
-                    .push(bs.read_bits(vp.bit_depth_y));
+                    .push(bs.read_bits(vp.bit_depth_y)?);
                 decoder_formatted_print(
                     "pcm_byte luma",
                     sd.macroblock_vec[curr_mb_idx].pcm_sample_luma[i],
@@ -219,6 +298,13 @@ pub fn decode_macroblock_layer(
 
         if vp.bit_depth_c == 8 {
             let max_chroma_params = (2 * vp.mb_width_c * vp.mb_height_c) as usize;
+            if bs.bytestream.len() < max_chroma_params {
+                return Err(DecodeError::new(format!(
+                    "decode_macroblock_layer - {} bytes left for the {} PCM chroma samples",
+                    bs.bytestream.len(),
+                    max_chroma_params
+                )));
+            }
             for i in 0..max_chroma_params {
                 sd.macroblock_vec[curr_mb_idx]
                     .pcm_sample_chroma
@@ -234,7 +320,7 @@ pub fn decode_macroblock_layer(
             for i in 0..2 * vp.mb_width_c * vp.mb_height_c {
                 sd.macroblock_vec[curr_mb_idx]
                     .pcm_sample_chroma
-                    .push(bs.read_bits(vp.bit_depth_c));
+                    .push(bs.read_bits(vp.bit_depth_c)?);
                 decoder_formatted_print(
                     "pcm_byte chroma",
                     sd.macroblock_vec[curr_mb_idx].pcm_sample_chroma[i as usize],
@@ -246,16 +332,18 @@ pub fn decode_macroblock_layer(
         if p.entropy_coding_mode_flag {
             // reset the CABAC state after reading the above bytes
             cabac_state.cod_i_range = 510;
-            cabac_state.cod_i_offset = bs.read_bits(9);
+            cabac_state.cod_i_offset = bs.read_bits(9)?;
         }
     } else {
         sd.macroblock_vec[curr_mb_idx].no_sub_mb_part_size_less_than_8x8_flag = true;
 
-        if sd.macroblock_vec[curr_mb_idx].mb_type != MbType::INxN
+        if base_mode_flag {
+            // prediction data is inferred from the reference layer
+        } else if sd.macroblock_vec[curr_mb_idx].mb_type != MbType::INxN
             && sd.macroblock_vec[curr_mb_idx].mb_part_pred_mode(0) != MbPartPredMode::Intra16x16
             && sd.macroblock_vec[curr_mb_idx].num_mb_part() == 4
         {
-            decode_sub_mb_pred(curr_mb_idx, bs, cabac_state, sh, sd, vp, p);
+            decode_sub_mb_pred(curr_mb_idx, bs, cabac_state, sh, sd, vp, p)?;
 
             for mb_part_idx in 0..4 {
                 if sd.macroblock_vec[curr_mb_idx].sub_mb_type[mb_part_idx] != SubMbType::BDirect8x8
@@ -281,9 +369,9 @@ pub fn decode_macroblock_layer(
                         vp,
                         0,
                         Vec::new(),
-                    )
+                    )?
                 } else {
-                    bs.read_bits(1) as i32
+                    bs.read_bits(1)? as i32
                 };
 
                 sd.macroblock_vec[curr_mb_idx].transform_size_8x8_flag = match res {
@@ -293,12 +381,61 @@ pub fn decode_macroblock_layer(
 
                 decoder_formatted_print("transform_size_8x8_flag", &res, 63);
             }
-            decode_mb_pred(curr_mb_idx, bs, cabac_state, sh, sd, vp, p);
+            decode_mb_pred(curr_mb_idx, bs, cabac_state, sh, sd, vp, p)?;
+        }
+        if sh.svc_extension_flag {
+            if sh.svc_extension.adaptive_residual_prediction_flag
+                && !is_slice_type(sh.slice_type, "I")
+                && in_crop_window
+                && (base_mode_flag || !sd.macroblock_vec[curr_mb_idx].is_intra())
+            {
+                let res: i32 = if p.entropy_coding_mode_flag {
+                    cabac_decode(
+                        "residual_prediction_flag",
+                        bs,
+                        cabac_state,
+                        curr_mb_idx,
+                        sh,
+                        sd,
+                        vp,
+                        0,
+                        Vec::new(),
+                    )?
+                } else {
+                    bs.read_bits(1)? as i32
+                };
+                sd.macroblock_vec[curr_mb_idx].residual_prediction_flag = res == 1;
+                decoder_formatted_print(
+                    "residual_prediction_flag",
+                    sd.macroblock_vec[curr_mb_idx].residual_prediction_flag,
+                    63,
+                );
+            } else {
+                sd.macroblock_vec[curr_mb_idx].residual_prediction_flag = in_crop_window
+                    && !is_slice_type(sh.slice_type, "I")
+                    && sh.svc_extension.default_residual_prediction_flag;
+            }
         }
+
         // equation 7-36
         sd.macroblock_vec[curr_mb_idx].set_cbp_chroma_and_luma();
 
-        if sd.macroblock_vec[curr_mb_idx].mb_part_pred_mode(0) != MbPartPredMode::Intra16x16 {
+        // an empty scan range means no residual syntax elements are present
+        let (scan_idx_start, scan_idx_end) = if sh.svc_extension_flag {
+            (
+                sh.svc_extension.scan_idx_start as usize,
+                sh.svc_extension.scan_idx_end as usize,
+            )
+        } else {
+            (0, 15)
+        };
+        if scan_idx_end < scan_idx_start {
+            return Ok(());
+        }
+
+        if base_mode_flag
+            || sd.macroblock_vec[curr_mb_idx].mb_part_pred_mode(0) != MbPartPredMode::Intra16x16
+        {
             let res: i32 = if p.entropy_coding_mode_flag {
                 cabac_decode(
                     "coded_block_pattern",
@@ -310,13 +447,13 @@ pub fn decode_macroblock_layer(
                     vp,
                     0,
                     Vec::new(),
-                )
+                )?
             } else {
                 let intra_mode = match sd.macroblock_vec[curr_mb_idx].is_intra() {
                     true => 0,
                     _ => 1,
                 };
-                mapped_exp_golomb_decode(vp.chroma_array_type, intra_mode, bs)
+                mapped_exp_golomb_decode(vp.chroma_array_type, intra_mode, bs)?
             };
 
             sd.macroblock_vec[curr_mb_idx].coded_block_pattern = res as u32;
@@ -335,10 +472,11 @@ pub fn decode_macroblock_layer(
 
             if sd.macroblock_vec[curr_mb_idx].coded_block_pattern_luma > 0
                 && p.transform_8x8_mode_flag
-                && sd.macroblock_vec[curr_mb_idx].mb_type != MbType::INxN
-                && sd.macroblock_vec[curr_mb_idx].no_sub_mb_part_size_less_than_8x8_flag
-                && (sd.macroblock_vec[curr_mb_idx].mb_type != MbType::BDirect16x16
-                    || s.direct_8x8_inference_flag)
+                && (base_mode_flag
+                    || (sd.macroblock_vec[curr_mb_idx].mb_type != MbType::INxN
+                        && sd.macroblock_vec[curr_mb_idx].no_sub_mb_part_size_less_than_8x8_flag
+                        && (sd.macroblock_vec[curr_mb_idx].mb_type != MbType::BDirect16x16
+                            || s.direct_8x8_inference_flag)))
             {
                 let res: i32 = if p.entropy_coding_mode_flag {
                     cabac_decode(
@@ -351,9 +489,9 @@ pub fn decode_macroblock_layer(
                         vp,
                         0,
                         Vec::new(),
-                    )
+                    )?
                 } else {
-                    bs.read_bits(1) as i32
+                    bs.read_bits(1)? as i32
                 };
 
                 sd.macroblock_vec[curr_mb_idx].transform_size_8x8_flag = match res {
@@ -381,14 +519,14 @@ pub fn decode_macroblock_layer(
                     vp,
                     0,
                     Vec::new(),
-                );
+                )?;
                 sd.macroblock_vec[curr_mb_idx].mb_qp_delta = ((res + 1) / 2)
                     * match res % 2 {
                         0 => -1i32,
                         _ => 1i32,
                     };
             } else {
-                res = exp_golomb_decode_one_wrapper(bs, true, 0);
+                res = exp_golomb_decode_one_wrapper(bs, true, 0)?;
                 sd.macroblock_vec[curr_mb_idx].mb_qp_delta = res;
             }
 
@@ -416,9 +554,103 @@ pub fn decode_macroblock_layer(
                 sd.macroblock_vec[curr_mb_idx].transform_bypass_mode_flag = true;
             }
 
-            decode_residual(0, 15, bs, cabac_state, curr_mb_idx, sh, sd, vp, p);
+            let bs = match partitions {
+                Some(rp) => rp.stream(sd.macroblock_vec[curr_mb_idx].is_intra())?,
+                None => bs,
+            };
+            decode_residual(
+                scan_idx_start,
+                scan_idx_end,
+                bs,
+                cabac_state,
+                curr_mb_idx,
+                sh,
+                sd,
+                vp,
+                p,
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads or infers motion_prediction_flag_l0 and motion_prediction_flag_l1 following G.7.3.6.1 and G.7.3.6.2
+///
+/// pred_modes holds the prediction mode of each macroblock or sub-macroblock partition
+#[allow(clippy::too_many_arguments)]
+fn decode_motion_prediction_flags(
+    curr_mb_idx: usize,
+    pred_modes: &[MbPartPredMode],
+    bs: &mut ByteStream,
+    cabac_state: &mut CABACState,
+    sh: &SliceHeader,
+    sd: &mut SliceData,
+    vp: &VideoParameters,
+    p: &PicParameterSet,
+) -> Result<(), DecodeError> {
+    let in_crop_window = sh.in_crop_window(sd.macroblock_vec[curr_mb_idx].mb_addr, vp);
+
+    if in_crop_window && sh.svc_extension.adaptive_motion_prediction_flag {
+        for (mb_part_idx, pred_mode) in pred_modes.iter().enumerate() {
+            if *pred_mode != MbPartPredMode::PredL1 && *pred_mode != MbPartPredMode::Direct {
+                let res: i32 = if p.entropy_coding_mode_flag {
+                    cabac_decode(
+                        "motion_prediction_flag_l0",
+                        bs,
+                        cabac_state,
+                        curr_mb_idx,
+                        sh,
+                        sd,
+                        vp,
+                        0,
+                        Vec::new(),
+                    )?
+                } else {
+                    bs.read_bits(1)? as i32
+                };
+                sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l0[mb_part_idx] = res == 1;
+                decoder_formatted_print(
+                    "motion_prediction_flag_l0",
+                    sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l0[mb_part_idx],
+                    63,
+                );
+            }
+        }
+        for (mb_part_idx, pred_mode) in pred_modes.iter().enumerate() {
+            if *pred_mode != MbPartPredMode::PredL0 && *pred_mode != MbPartPredMode::Direct {
+                let res: i32 = if p.entropy_coding_mode_flag {
+                    cabac_decode(
+                        "motion_prediction_flag_l1",
+                        bs,
+                        cabac_state,
+                        curr_mb_idx,
+                        sh,
+                        sd,
+                        vp,
+                        0,
+                        Vec::new(),
+                    )?
+                } else {
+                    bs.read_bits(1)? as i32
+                };
+                sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l1[mb_part_idx] = res == 1;
+                decoder_formatted_print(
+                    "motion_prediction_flag_l1",
+                    sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l1[mb_part_idx],
+                    63,
+                );
+            }
+        }
+    } else {
+        let inferred = in_crop_window && sh.svc_extension.default_motion_prediction_flag;
+        for mb_part_idx in 0..pred_modes.len() {
+            sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l0[mb_part_idx] = inferred;
+            sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l1[mb_part_idx] = inferred;
         }
     }
+
+    Ok(())
 }
 
 /// Follows Section 7.3.5.1
@@ -430,7 +662,7 @@ fn decode_mb_pred(
     sd: &mut SliceData,
     vp: &VideoParameters,
     p: &PicParameterSet,
-) {
+) -> Result<(), DecodeError> {
     let mpp_mode = sd.macroblock_vec[curr_mb_idx].mb_part_pred_mode(0);
 
     if mpp_mode == MbPartPredMode::Intra4x4
@@ -451,9 +683,9 @@ fn decode_mb_pred(
                         vp,
                         0,
                         Vec::new(),
-                    )
+                    )?
                 } else {
-                    bs.read_bits(1) as i32
+                    bs.read_bits(1)? as i32
                 };
 
                 sd.macroblock_vec[curr_mb_idx].prev_intra4x4_pred_mode_flag[luma4x4blkidx] =
@@ -474,9 +706,9 @@ fn decode_mb_pred(
                             vp,
                             0,
                             Vec::new(),
-                        ) as u32
+                        )? as u32
                     } else {
-                        bs.read_bits(3)
+                        bs.read_bits(3)?
                     };
 
                     sd.macroblock_vec[curr_mb_idx].rem_intra4x4_pred_mode[luma4x4blkidx] = res;
@@ -513,9 +745,9 @@ fn decode_mb_pred(
                         vp,
                         0,
                         Vec::new(),
-                    ) as u32
+                    )? as u32
                 } else {
-                    bs.read_bits(1)
+                    bs.read_bits(1)?
                 };
 
                 sd.macroblock_vec[curr_mb_idx].prev_intra8x8_pred_mode_flag[luma8x8blkidx] =
@@ -536,9 +768,9 @@ fn decode_mb_pred(
                             vp,
                             0,
                             Vec::new(),
-                        ) as u32
+                        )? as u32
                     } else {
-                        bs.read_bits(3)
+                        bs.read_bits(3)?
                     };
 
                     sd.macroblock_vec[curr_mb_idx].rem_intra8x8_pred_mode[luma8x8blkidx] = res;
@@ -564,9 +796,9 @@ fn decode_mb_pred(
                     vp,
                     0,
                     Vec::new(),
-                )
+                )?
             } else {
-                exp_golomb_decode_one_wrapper(bs, false, 0)
+                exp_golomb_decode_one_wrapper(bs, false, 0)?
             };
 
             sd.macroblock_vec[curr_mb_idx].intra_chroma_pred_mode = res as u8;
@@ -577,11 +809,27 @@ fn decode_mb_pred(
             );
         }
     } else if mpp_mode != MbPartPredMode::Direct {
+        if sh.svc_extension_flag {
+            let pred_modes: Vec<MbPartPredMode> = (0..sd.macroblock_vec[curr_mb_idx].num_mb_part())
+                .map(|mb_part_idx| sd.macroblock_vec[curr_mb_idx].mb_part_pred_mode(mb_part_idx))
+                .collect();
+            decode_motion_prediction_flags(
+                curr_mb_idx,
+                &pred_modes,
+                bs,
+                cabac_state,
+                sh,
+                sd,
+                vp,
+                p,
+            )?;
+        }
         for mb_part_idx in 0..sd.macroblock_vec[curr_mb_idx].num_mb_part() {
             if (sh.num_ref_idx_l0_active_minus1 > 0
                 || sd.mb_field_decoding_flag[curr_mb_idx] != sh.field_pic_flag)
                 && sd.macroblock_vec[curr_mb_idx].mb_part_pred_mode(mb_part_idx)
                     != MbPartPredMode::PredL1
+                && !sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l0[mb_part_idx]
             {
                 let additional_inputs = vec![mb_part_idx];
                 let res: u32 = if p.entropy_coding_mode_flag {
@@ -595,7 +843,7 @@ fn decode_mb_pred(
                         vp,
                         0,
                         additional_inputs,
-                    ) as u32
+                    )? as u32
                 } else {
                     let max_val: u32 =
                         if !sh.mbaff_frame_flag || !sd.mb_field_decoding_flag[curr_mb_idx] {
@@ -603,7 +851,7 @@ fn decode_mb_pred(
                         } else {
                             2 * sh.num_ref_idx_l0_active_minus1 + 1
                         };
-                    truncated_exp_golomb_decode(max_val, bs) as u32
+                    truncated_exp_golomb_decode(max_val, bs)?
                 };
 
                 sd.macroblock_vec[curr_mb_idx].ref_idx_l0[mb_part_idx] = res;
@@ -619,6 +867,7 @@ fn decode_mb_pred(
                 || sd.mb_field_decoding_flag[curr_mb_idx] != sh.field_pic_flag)
                 && sd.macroblock_vec[curr_mb_idx].mb_part_pred_mode(mb_part_idx)
                     != MbPartPredMode::PredL0
+                && !sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l1[mb_part_idx]
             {
                 let additional_inputs = vec![mb_part_idx];
                 let res: u32 = if p.entropy_coding_mode_flag {
@@ -632,7 +881,7 @@ fn decode_mb_pred(
                         vp,
                         0,
                         additional_inputs,
-                    ) as u32
+                    )? as u32
                 } else {
                     let max_val: u32 =
                         if !sh.mbaff_frame_flag || !sd.mb_field_decoding_flag[curr_mb_idx] {
@@ -641,7 +890,7 @@ fn decode_mb_pred(
                             2 * sh.num_ref_idx_l1_active_minus1 + 1
                         };
 
-                    truncated_exp_golomb_decode(max_val, bs) as u32
+                    truncated_exp_golomb_decode(max_val, bs)?
                 };
 
                 sd.macroblock_vec[curr_mb_idx].ref_idx_l1[mb_part_idx] = res;
@@ -671,9 +920,9 @@ fn decode_mb_pred(
                             vp,
                             0,
                             additional_inputs,
-                        )
+                        )?
                     } else {
-                        exp_golomb_decode_one_wrapper(bs, true, 0)
+                        exp_golomb_decode_one_wrapper(bs, true, 0)?
                     };
 
                     sd.macroblock_vec[curr_mb_idx].mvd_l0[mb_part_idx][0][comp_idx] = res;
@@ -705,9 +954,9 @@ fn decode_mb_pred(
                             vp,
                             0,
                             additional_inputs,
-                        )
+                        )?
                     } else {
-                        exp_golomb_decode_one_wrapper(bs, true, 0)
+                        exp_golomb_decode_one_wrapper(bs, true, 0)?
                     };
 
                     sd.macroblock_vec[curr_mb_idx].mvd_l1[mb_part_idx][0][comp_idx] = res;
@@ -721,6 +970,8 @@ fn decode_mb_pred(
             }
         }
     }
+
+    Ok(())
 }
 
 /// Follows Section 7.3.5.2
@@ -732,7 +983,7 @@ fn decode_sub_mb_pred(
     sd: &mut SliceData,
     vp: &VideoParameters,
     p: &PicParameterSet,
-) {
+) -> Result<(), DecodeError> {
     for mb_part_idx in 0..4 {
         let res: i32 = if p.entropy_coding_mode_flag {
             cabac_decode(
@@ -745,12 +996,12 @@ fn decode_sub_mb_pred(
                 vp,
                 0,
                 Vec::new(),
-            )
+            )?
         } else {
-            exp_golomb_decode_one_wrapper(bs, false, 0)
+            exp_golomb_decode_one_wrapper(bs, false, 0)?
         };
 
-        sd.macroblock_vec[curr_mb_idx].sub_mb_type[mb_part_idx] = decode_sub_mb_type(res, sh);
+        sd.macroblock_vec[curr_mb_idx].sub_mb_type[mb_part_idx] = decode_sub_mb_type(res, sh)?;
 
         decoder_formatted_print("sub_mb_type", &res, 63);
         decoder_formatted_print(
@@ -760,6 +1011,13 @@ fn decode_sub_mb_pred(
         );
     }
 
+    if sh.svc_extension_flag {
+        let pred_modes: Vec<MbPartPredMode> = (0..4)
+            .map(|mb_part_idx| sd.macroblock_vec[curr_mb_idx].sub_mb_part_pred_mode(mb_part_idx))
+            .collect();
+        decode_motion_prediction_flags(curr_mb_idx, &pred_modes, bs, cabac_state, sh, sd, vp, p)?;
+    }
+
     for mb_part_idx in 0..4 {
         if (sh.num_ref_idx_l0_active_minus1 > 0
             || sd.mb_field_decoding_flag[curr_mb_idx] != sh.field_pic_flag)
@@ -767,6 +1025,7 @@ fn decode_sub_mb_pred(
             && sd.macroblock_vec[curr_mb_idx].sub_mb_type[mb_part_idx] != SubMbType::BDirect8x8
             && sd.macroblock_vec[curr_mb_idx].sub_mb_part_pred_mode(mb_part_idx)
                 != MbPartPredMode::PredL1
+            && !sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l0[mb_part_idx]
         {
             let additional_inputs = vec![mb_part_idx];
             let res: u32 = if p.entropy_coding_mode_flag {
@@ -780,7 +1039,7 @@ fn decode_sub_mb_pred(
                     vp,
                     0,
                     additional_inputs,
-                ) as u32
+                )? as u32
             } else {
                 let max_val: u32 =
                     if !sh.mbaff_frame_flag || !sd.mb_field_decoding_flag[curr_mb_idx] {
@@ -789,7 +1048,7 @@ fn decode_sub_mb_pred(
                         2 * sh.num_ref_idx_l0_active_minus1 + 1
                     };
 
-                truncated_exp_golomb_decode(max_val, bs) as u32
+                truncated_exp_golomb_decode(max_val, bs)?
             };
 
             sd.macroblock_vec[curr_mb_idx].ref_idx_l0[mb_part_idx] = res;
@@ -807,6 +1066,7 @@ fn decode_sub_mb_pred(
             && sd.macroblock_vec[curr_mb_idx].sub_mb_type[mb_part_idx] != SubMbType::BDirect8x8
             && sd.macroblock_vec[curr_mb_idx].sub_mb_part_pred_mode(mb_part_idx)
                 != MbPartPredMode::PredL0
+            && !sd.macroblock_vec[curr_mb_idx].motion_prediction_flag_l1[mb_part_idx]
         {
             let additional_inputs = vec![mb_part_idx];
             let res: u32 = if p.entropy_coding_mode_flag {
@@ -820,7 +1080,7 @@ fn decode_sub_mb_pred(
                     vp,
                     0,
                     additional_inputs,
-                ) as u32
+                )? as u32
             } else {
                 let max_val: u32 =
                     if !sh.mbaff_frame_flag || !sd.mb_field_decoding_flag[curr_mb_idx] {
@@ -828,7 +1088,7 @@ fn decode_sub_mb_pred(
                     } else {
                         2 * sh.num_ref_idx_l1_active_minus1 + 1
                     };
-                truncated_exp_golomb_decode(max_val, bs) as u32
+                truncated_exp_golomb_decode(max_val, bs)?
             };
 
             sd.macroblock_vec[curr_mb_idx].ref_idx_l1[mb_part_idx] = res;
@@ -861,9 +1121,9 @@ fn decode_sub_mb_pred(
                             vp,
                             0,
                             additional_inputs,
-                        )
+                        )?
                     } else {
-                        exp_golomb_decode_one_wrapper(bs, true, 0)
+                        exp_golomb_decode_one_wrapper(bs, true, 0)?
                     };
 
                     sd.macroblock_vec[curr_mb_idx].mvd_l0[mb_part_idx][sub_mb_part_idx][comp_idx] =
@@ -900,9 +1160,9 @@ fn decode_sub_mb_pred(
                             vp,
                             0,
                             additional_inputs,
-                        )
+                        )?
                     } else {
-                        exp_golomb_decode_one_wrapper(bs, true, 0)
+                        exp_golomb_decode_one_wrapper(bs, true, 0)?
                     };
 
                     sd.macroblock_vec[curr_mb_idx].mvd_l1[mb_part_idx][sub_mb_part_idx][comp_idx] =
@@ -918,6 +1178,8 @@ fn decode_sub_mb_pred(
             }
         }
     }
+
+    Ok(())
 }
 
 /// Follows Section 7.3.5.3
@@ -931,7 +1193,7 @@ fn decode_residual(
     sd: &mut SliceData,
     vp: &VideoParameters,
     p: &PicParameterSet,
-) {
+) -> Result<(), DecodeError> {
     let mut i16x16dclevel: Vec<i32> = Vec::new();
     let mut i16x16aclevel: Vec<Vec<i32>> = Vec::new();
     let mut level4x4: Vec<Vec<i32>> = Vec::new();
@@ -964,7 +1226,7 @@ fn decode_residual(
         &mut i16x16aclevel_transform_block,
         &mut level4x4_transform_block,
         &mut level8x8_transform_block,
-    );
+    )?;
 
     // these are used in section 8.5 to reconstruct the image
 
@@ -1004,7 +1266,7 @@ fn decode_residual(
                         vp,
                         additional_inputs,
                         &mut res2,
-                    );
+                    )?;
                 } else {
                     decode_residual_block_cavlc(
                         &mut res,
@@ -1019,7 +1281,7 @@ fn decode_residual(
                         vp,
                         &additional_inputs,
                         &mut res2,
-                    );
+                    )?;
                 }
 
                 sd.macroblock_vec[curr_mb_idx].chroma_dc_level[i_cb_cr] = res.clone();
@@ -1074,7 +1336,7 @@ fn decode_residual(
                                     vp,
                                     additional_inputs.clone(),
                                     &mut res2,
-                                );
+                                )?;
                             } else {
                                 decode_residual_block_cavlc(
                                     &mut res,
@@ -1089,7 +1351,7 @@ fn decode_residual(
                                     vp,
                                     &additional_inputs,
                                     &mut res2,
-                                );
+                                )?;
                             }
                         } else if p.entropy_coding_mode_flag {
                             decode_residual_block_cabac(
@@ -1106,7 +1368,7 @@ fn decode_residual(
                                 vp,
                                 additional_inputs.clone(),
                                 &mut res2,
-                            );
+                            )?;
                         } else {
                             decode_residual_block_cavlc(
                                 &mut res,
@@ -1121,7 +1383,7 @@ fn decode_residual(
                                 vp,
                                 &additional_inputs,
                                 &mut res2,
-                            );
+                            )?;
                         }
 
                         sd.macroblock_vec[curr_mb_idx].chroma_ac_level[i_cb_cr]
@@ -1171,7 +1433,7 @@ fn decode_residual(
             &mut i16x16aclevel_transform_block,
             &mut level4x4_transform_block,
             &mut level8x8_transform_block,
-        );
+        )?;
 
         // Now the Cr values
         i16x16dclevel.clear();
@@ -1204,8 +1466,10 @@ fn decode_residual(
             &mut i16x16aclevel_transform_block,
             &mut level4x4_transform_block,
             &mut level8x8_transform_block,
-        );
+        )?;
     }
+
+    Ok(())
 }
 
 /// Follows Section 7.3.5.3.1
@@ -1228,10 +1492,29 @@ fn decode_residual_luma(
     i16x16aclevel_transform_block: &mut Vec<TransformBlock>,
     level4x4_transform_block: &mut Vec<TransformBlock>,
     level8x8_transform_block: &mut Vec<TransformBlock>,
-) {
+) -> Result<(), DecodeError> {
     debug!(target: "decode","Decoding Residual Luma components");
     let mut ctx_block_cat: u8; // Values are derived from Table 9-42
 
+    // Cb and Cr of ChromaArrayType 3 are coded like Luma, but take CAVLC neighbors from their own component
+    let (dc_mode, ac_mode, level4x4_mode) = match ctx_block_cat_offset {
+        6 => (
+            ResidualMode::CbIntra16x16DCLevel,
+            ResidualMode::CbIntra16x16ACLevel,
+            ResidualMode::CbLevel4x4,
+        ),
+        10 => (
+            ResidualMode::CrIntra16x16DCLevel,
+            ResidualMode::CrIntra16x16ACLevel,
+            ResidualMode::CrLevel4x4,
+        ),
+        _ => (
+            ResidualMode::Intra16x16DCLevel,
+            ResidualMode::Intra16x16ACLevel,
+            ResidualMode::LumaLevel4x4,
+        ),
+    };
+
     if start_idx == 0
         && sd.macroblock_vec[curr_mb_idx].mb_part_pred_mode(0) == MbPartPredMode::Intra16x16
     {
@@ -1254,7 +1537,7 @@ fn decode_residual_luma(
                 vp,
                 additional_inputs.clone(),
                 i16x16dclevel_transform_block,
-            );
+            )?;
         } else {
             decode_residual_block_cavlc(
                 i16x16dclevel,
@@ -1262,14 +1545,14 @@ fn decode_residual_luma(
                 15,
                 16,
                 bs,
-                ResidualMode::LumaLevel4x4,
+                dc_mode,
                 curr_mb_idx,
                 sh,
                 sd,
                 vp,
                 &additional_inputs,
                 i16x16dclevel_transform_block,
-            );
+            )?;
         }
 
         if ctx_block_cat_offset == 0 {
@@ -1322,7 +1605,7 @@ fn decode_residual_luma(
                                     vp,
                                     additional_inputs.clone(),
                                     &mut i16x16aclevel_transform_block[(i8x8 * 4 + i4x4) as usize],
-                                );
+                                )?;
                             } else {
                                 decode_residual_block_cavlc(
                                     &mut i16x16aclevel[(i8x8 * 4 + i4x4) as usize],
@@ -1330,14 +1613,14 @@ fn decode_residual_luma(
                                     end_idx - 1,
                                     15,
                                     bs,
-                                    ResidualMode::Intra16x16ACLevel,
+                                    ac_mode,
                                     curr_mb_idx,
                                     sh,
                                     sd,
                                     vp,
                                     &additional_inputs,
                                     &mut i16x16aclevel_transform_block[(i8x8 * 4 + i4x4) as usize],
-                                );
+                                )?;
                             }
                         } else if p.entropy_coding_mode_flag {
                             decode_residual_block_cabac(
@@ -1354,7 +1637,7 @@ fn decode_residual_luma(
                                 vp,
                                 additional_inputs.clone(),
                                 &mut i16x16aclevel_transform_block[(i8x8 * 4 + i4x4) as usize],
-                            );
+                            )?;
                         } else {
                             decode_residual_block_cavlc(
                                 &mut i16x16aclevel[(i8x8 * 4 + i4x4) as usize],
@@ -1362,14 +1645,14 @@ fn decode_residual_luma(
                                 end_idx - 1,
                                 15,
                                 bs,
-                                ResidualMode::Intra16x16ACLevel,
+                                ac_mode,
                                 curr_mb_idx,
                                 sh,
                                 sd,
                                 vp,
                                 &additional_inputs,
                                 &mut i16x16aclevel_transform_block[(i8x8 * 4 + i4x4) as usize],
-                            );
+                            )?;
                         }
                         // To test, copy over the values
                         level4x4_transform_block[(i8x8 * 4 + i4x4) as usize] =
@@ -1392,7 +1675,7 @@ fn decode_residual_luma(
                                 vp,
                                 additional_inputs.clone(),
                                 &mut level4x4_transform_block[(i8x8 * 4 + i4x4) as usize],
-                            );
+                            )?;
                         } else {
                             decode_residual_block_cavlc(
                                 &mut level4x4[(i8x8 * 4 + i4x4) as usize],
@@ -1400,14 +1683,14 @@ fn decode_residual_luma(
                                 end_idx,
                                 16,
                                 bs,
-                                ResidualMode::LumaLevel4x4,
+                                level4x4_mode,
                                 curr_mb_idx,
                                 sh,
                                 sd,
                                 vp,
                                 &additional_inputs,
                                 &mut level4x4_transform_block[(i8x8 * 4 + i4x4) as usize],
-                            );
+                            )?;
                         }
                         // To test, copy over the values
                         i16x16aclevel_transform_block[(i8x8 * 4 + i4x4) as usize] =
@@ -1431,7 +1714,10 @@ fn decode_residual_luma(
                     && !p.entropy_coding_mode_flag
                 {
                     debug!(target: "decode","Luma8x8 - copying");
-                    level8x8[i8x8] = vec![0; 64];
+                    // the four 4x4 blocks interleave into one 8x8 block, so only clear it on the first
+                    if i4x4 == 0 {
+                        level8x8[i8x8] = vec![0; 64];
+                    }
                     for i in 0..16 {
                         level8x8[i8x8][4 * i + i4x4] = level4x4[i8x8 * 4 + i4x4][i];
                     }
@@ -1528,7 +1814,7 @@ fn decode_residual_luma(
                     vp,
                     additional_inputs.clone(),
                     &mut level8x8_transform_block[i8x8],
-                );
+                )?;
             } else {
                 decode_residual_block_cavlc(
                     &mut level8x8[i8x8],
@@ -1536,14 +1822,14 @@ fn decode_residual_luma(
                     4 * end_idx + 3,
                     64,
                     bs,
-                    ResidualMode::LumaLevel4x4,
+                    level4x4_mode,
                     curr_mb_idx,
                     sh,
                     sd,
                     vp,
                     &additional_inputs,
                     &mut level8x8_transform_block[i8x8],
-                );
+                )?;
             }
         } else {
             // insert empty values
@@ -1600,6 +1886,8 @@ fn decode_residual_luma(
                 level8x8_transform_block.clone();
         }
     }
+
+    Ok(())
 }
 
 /// Follows Section 7.3.5.3.2
@@ -1617,7 +1905,7 @@ fn decode_residual_block_cabac(
     vp: &VideoParameters,
     mut additional_inputs: Vec<usize>,
     cur_transform_block: &mut TransformBlock,
-) {
+) -> Result<(), DecodeError> {
     // additional inputs starts off with containing the BlkIdx, which is necessary for coded_block_flag
     if max_num_coeff != 64 || vp.chroma_array_type == 3 {
         let r = cabac_decode(
@@ -1630,7 +1918,7 @@ fn decode_residual_block_cabac(
             vp,
             ctx_block_cat,
             additional_inputs.clone(),
-        );
+        )?;
         cur_transform_block.coded_block_flag = match r {
             1 => true,
             _ => false,
@@ -1666,7 +1954,7 @@ fn decode_residual_block_cabac(
                 vp,
                 ctx_block_cat,
                 additional_inputs.clone(),
-            );
+            )?;
             cur_transform_block.significant_coeff_flag[i] = match res {
                 1 => true,
                 _ => false,
@@ -1684,7 +1972,7 @@ fn decode_residual_block_cabac(
                     vp,
                     ctx_block_cat,
                     additional_inputs.clone(),
-                );
+                )?;
                 cur_transform_block.last_significant_coeff_flag[i] = match res {
                     1 => true,
                     _ => false,
@@ -1717,7 +2005,7 @@ fn decode_residual_block_cabac(
             vp,
             ctx_block_cat,
             additional_inputs.clone(),
-        ) as u32;
+        )? as u32;
 
         // we do +1 because we compare the coeff value, not the coeff value minus one
         if cur_transform_block.coeff_abs_level_minus1[num_coeff - 1] + 1 == 1 {
@@ -1735,7 +2023,7 @@ fn decode_residual_block_cabac(
             vp,
             ctx_block_cat,
             additional_inputs.clone(),
-        );
+        )?;
         cur_transform_block.coeff_sign_flag[num_coeff - 1] = match r {
             1 => true,
             _ => false,
@@ -1764,7 +2052,7 @@ fn decode_residual_block_cabac(
                         vp,
                         ctx_block_cat,
                         additional_inputs.clone(),
-                    ) as u32;
+                    )? as u32;
 
                     // we do +1 because we compare the coeff value, not the coeff value minus one
                     if cur_transform_block.coeff_abs_level_minus1[i] + 1 == 1 {
@@ -1783,7 +2071,7 @@ fn decode_residual_block_cabac(
                         vp,
                         ctx_block_cat,
                         additional_inputs.clone(),
-                    );
+                    )?;
                     cur_transform_block.coeff_sign_flag[i] = match r {
                         1 => true,
                         _ => false,
@@ -1804,6 +2092,8 @@ fn decode_residual_block_cabac(
             }
         }
     }
+
+    Ok(())
 }
 
 /// Follows Section 7.3.5.3.3
@@ -1820,7 +2110,7 @@ fn decode_residual_block_cavlc(
     vp: &VideoParameters,
     additional_inputs: &[usize],
     cur_transform_block: &mut TransformBlock,
-) {
+) -> Result<(), DecodeError> {
     let mut level_val: Vec<i32> = Vec::new();
     let mut run_val: Vec<usize> = Vec::new();
     let mut suffix_length: u32 = 0;
@@ -1846,7 +2136,7 @@ fn decode_residual_block_cavlc(
         i_cb_cr,
         bs,
         vp,
-    );
+    )?;
     cur_transform_block.coeff_token = coeff_token.clone();
 
     decoder_formatted_print("coeff_token.n_c", coeff_token.n_c, 63);
@@ -1867,7 +2157,7 @@ fn decode_residual_block_cavlc(
             cur_transform_block.level_suffix.push(0);
 
             if i < coeff_token.trailing_ones {
-                let trailing_ones_sign_flag: i32 = bs.read_bits(1) as i32;
+                let trailing_ones_sign_flag: i32 = bs.read_bits(1)? as i32;
                 cur_transform_block.trailing_ones_sign_flag[i] = match trailing_ones_sign_flag {
                     0 => false,
                     _ => true,
@@ -1876,13 +2166,13 @@ fn decode_residual_block_cavlc(
                 decoder_formatted_print("trailing_ones_sign_flag", trailing_ones_sign_flag, 63);
                 level_val[i] = 1 - 2 * trailing_ones_sign_flag;
             } else {
-                let level_prefix = cavlc_decode_level_prefix(bs);
+                let level_prefix = cavlc_decode_level_prefix(bs)?;
                 cur_transform_block.level_prefix[i] = level_prefix;
 
                 decoder_formatted_print("level_prefix", level_prefix, 63);
                 level_code = cmp::min(15, level_prefix as i32) << suffix_length;
                 if suffix_length > 0 || level_prefix >= 14 {
-                    let level_suffix = cavlc_decode_level_suffix(suffix_length, level_prefix, bs);
+                    let level_suffix = cavlc_decode_level_suffix(suffix_length, level_prefix, bs)?;
                     cur_transform_block.level_suffix[i] = level_suffix;
 
                     decoder_formatted_print("level_suffix", level_suffix, 63);
@@ -1914,7 +2204,7 @@ fn decode_residual_block_cavlc(
             debug!(target: "decode","------");
         }
         if coeff_token.total_coeff < (end_idx - start_idx + 1) {
-            let total_zeros = cavlc_decode_total_zeros(coeff_token.total_coeff, max_num_coeff, bs);
+            let total_zeros = cavlc_decode_total_zeros(coeff_token.total_coeff, max_num_coeff, bs)?;
             cur_transform_block.total_zeros = total_zeros;
 
             decoder_formatted_print("total_zeros", total_zeros, 63);
@@ -1928,7 +2218,13 @@ fn decode_residual_block_cavlc(
             cur_transform_block.run_before.push(0);
 
             if zeros_left > 0 {
-                let run_before = cavlc_decode_run_before(zeros_left, bs);
+                let run_before = cavlc_decode_run_before(zeros_left, bs)?;
+                if run_before > zeros_left {
+                    return Err(DecodeError::new(format!(
+                        "decode_residual_block_cavlc - run_before {} is greater than zeros_left {}",
+                        run_before, zeros_left
+                    )));
+                }
                 cur_transform_block.run_before[i] = run_before;
 
                 decoder_formatted_print("run_before", run_before, 63);
@@ -1945,6 +2241,13 @@ fn decode_residual_block_cavlc(
                 coeff_num += run_val[i] + 1;
             }
 
+            if start_idx + coeff_num >= coeff_level.len() {
+                return Err(DecodeError::new(format!(
+                    "decode_residual_block_cavlc - coefficient {} is outside of the {} coefficients of the block",
+                    start_idx + coeff_num,
+                    coeff_level.len()
+                )));
+            }
             coeff_level[start_idx + coeff_num] = level_val[i];
             decoder_formatted_print(
                 format!("coeff_level[{}]", start_idx + coeff_num).as_str(),
@@ -1953,4 +2256,6 @@ fn decode_residual_block_cavlc(
             );
         }
     }
+
+    Ok(())
 }
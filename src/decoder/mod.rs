@@ -4,6 +4,7 @@ pub mod binarization_functions;
 pub mod cabac;
 pub mod cavlc;
 pub mod decoder;
+pub mod error;
 pub mod expgolomb;
 pub mod macroblock;
 pub mod nalu;
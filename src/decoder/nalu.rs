@@ -1,6 +1,7 @@
 //! NALU header and extensions syntax element decoding.
 
 use crate::common::data_structures::AccessUnitDelim;
+use crate::common::data_structures::FillerData;
 use crate::common::data_structures::NALUHeader3DAVCExtension;
 use crate::common::data_structures::NALUHeaderMVCExtension;
 use crate::common::data_structures::NALUHeaderSVCExtension;
@@ -9,85 +10,194 @@ use crate::common::data_structures::PrefixNALU;
 use crate::common::data_structures::NALU;
 use crate::common::helper::decoder_formatted_print;
 use crate::common::helper::ByteStream;
+use crate::decoder::error::DecodeError;
 use crate::decoder::expgolomb::exp_golomb_decode_one_wrapper;
 use log::debug;
 use std::fs::File;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
 use std::io::Read;
 
 /// Split a bytestream into NALUs
-pub fn split_into_nalu(filename: &str) -> Vec<NALU> {
-    let f = match File::open(filename) {
-        Err(_) => {
-            println!("ERROR - couldn't open {}", filename);
-            std::process::exit(1);
-        }
-        Ok(file) => file,
-    };
+pub fn split_into_nalu(filename: &str) -> Result<Vec<NALU>, DecodeError> {
+    let f = File::open(filename).map_err(|e| DecodeError::read_error(0, &e))?;
+
+    split_into_nalu_from_reader(f)
+}
 
+/// Split an in-memory bytestream into NALUs
+pub fn split_into_nalu_from_bytes(data: &[u8]) -> Vec<NALU> {
+    let mut splitter = NALUSplitter::new();
+    let mut results: Vec<NALU> = data.iter().filter_map(|b| splitter.push(*b)).collect();
+    results.extend(splitter.finish());
+
+    debug!(target: "decode","Found {} NALUs", results.len());
+    debug!(target: "decode","Done splitting");
+
+    results
+}
+
+/// Split a bytestream coming from any reader into NALUs
+pub fn split_into_nalu_from_reader<R: Read>(reader: R) -> Result<Vec<NALU>, DecodeError> {
     let mut results: Vec<NALU> = Vec::new();
+    for n in NALUIterator::new(reader) {
+        let n = n.map_err(|e| DecodeError::read_error(results.len(), &e))?;
+        results.push(n);
+    }
 
-    // state machine approach to find matching NALU start codes
-    let mut zerocount = 0;
-    let mut longstart = false;
-    let mut curnalu: Vec<u8> = Vec::new();
-    let mut firststore = false;
+    debug!(target: "decode","Found {} NALUs", results.len());
+    debug!(target: "decode","Done splitting");
+
+    Ok(results)
+}
+
+/// Incrementally finds NALUs in an Annex B bytestream, one byte at a time.
+///
+/// A NALU is only complete once the next start code (or the end of the stream) is seen.
+pub struct NALUSplitter {
+    zerocount: usize,
+    longstart: bool,
+    curnalu: Vec<u8>,
+    firststore: bool,
+}
+
+impl NALUSplitter {
+    pub fn new() -> NALUSplitter {
+        NALUSplitter {
+            zerocount: 0,
+            longstart: false,
+            curnalu: Vec::new(),
+            firststore: false,
+        }
+    }
 
-    for (_, byte) in f.bytes().enumerate() {
-        let curbyte = byte.unwrap();
+    /// Consume the next byte of the stream, returning a NALU if it completes one
+    pub fn push(&mut self, curbyte: u8) -> Option<NALU> {
+        let mut res = None;
 
-        if firststore {
-            curnalu.push(curbyte);
+        if self.firststore {
+            self.curnalu.push(curbyte);
         }
 
+        // state machine approach to find matching NALU start codes
         if curbyte == 0 {
-            zerocount += 1;
+            self.zerocount += 1;
         } else if curbyte == 1 {
             // two or more, followed by 0x01, counts as a start code
-            if zerocount > 1 {
+            if self.zerocount > 1 {
                 // if is the first NALU, it's time to start
                 // storing contents
-                if !firststore {
-                    firststore = true;
+                if !self.firststore {
+                    self.firststore = true;
 
                 // if it's not, then let's remove the start codes that were added,
                 // save the curnalu, and reset it
                 } else {
-                    curnalu.pop(); // 1
-                                   // remove zerocount amount
-                    let final_length = curnalu.len().saturating_sub(zerocount);
-                    curnalu.truncate(final_length);
-
-                    let cur: NALU = NALU {
-                        longstartcode: longstart,
-                        content: remove_emulation_prevention_three_byte(&curnalu),
-                    };
-                    results.push(cur);
-                    curnalu.truncate(0); // reset it
+                    self.curnalu.pop(); // 1
+                                        // remove zerocount amount
+                    let final_length = self.curnalu.len().saturating_sub(self.zerocount);
+                    self.curnalu.truncate(final_length);
+
+                    res = Some(NALU {
+                        longstartcode: self.longstart,
+                        content: remove_emulation_prevention_three_byte(&self.curnalu),
+                    });
+                    self.curnalu.truncate(0); // reset it
                 }
 
-                longstart = zerocount == 3;
+                self.longstart = self.zerocount == 3;
             }
 
             // reset the zero count
-            zerocount = 0;
+            self.zerocount = 0;
         } else {
             // go back to the start
-            zerocount = 0;
+            self.zerocount = 0;
         }
+
+        res
     }
 
-    // push the last NALU if start code was found
-    if firststore {
-        let cur: NALU = NALU {
-            longstartcode: longstart,
-            content: remove_emulation_prevention_three_byte(&curnalu),
+    /// Signal the end of the stream, returning the last NALU if a start code was found
+    pub fn finish(&mut self) -> Option<NALU> {
+        if !self.firststore {
+            return None;
+        }
+
+        let cur = NALU {
+            longstartcode: self.longstart,
+            content: remove_emulation_prevention_three_byte(&self.curnalu),
         };
-        results.push(cur);
+        *self = NALUSplitter::new();
+        Some(cur)
     }
-    debug!(target: "decode","Found {} NALUs", results.len());
-    debug!(target: "decode","Done splitting");
+}
 
-    results
+impl Default for NALUSplitter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Yields NALUs from a reader as soon as each one is complete, so the whole
+/// stream never needs to be in memory
+pub struct NALUIterator<R: Read> {
+    reader: BufReader<R>,
+    splitter: NALUSplitter,
+    done: bool,
+}
+
+impl<R: Read> NALUIterator<R> {
+    pub fn new(reader: R) -> NALUIterator<R> {
+        NALUIterator {
+            reader: BufReader::new(reader),
+            splitter: NALUSplitter::new(),
+            done: false,
+        }
+    }
+}
+
+impl<R: Read> Iterator for NALUIterator<R> {
+    type Item = io::Result<NALU>;
+
+    fn next(&mut self) -> Option<io::Result<NALU>> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            let buf = match self.reader.fill_buf() {
+                Ok(buf) => buf,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            };
+
+            if buf.is_empty() {
+                self.done = true;
+                return self.splitter.finish().map(Ok);
+            }
+
+            // only consume up to the end of the NALU we found
+            let mut consumed = 0;
+            let mut found = None;
+            for b in buf {
+                consumed += 1;
+                found = self.splitter.push(*b);
+                if found.is_some() {
+                    break;
+                }
+            }
+            self.reader.consume(consumed);
+
+            if let Some(nalu) = found {
+                return Some(Ok(nalu));
+            }
+        }
+    }
 }
 
 /// The emulation prevention three byte (0x00 0x00 0x03) is inserted into a stream
@@ -135,13 +245,16 @@ fn remove_emulation_prevention_three_byte(stream: &[u8]) -> Vec<u8> {
 }
 
 /// Parse NALU header contents
-pub fn decode_nalu_header(long_start_code: bool, nalu_data: &mut ByteStream) -> NALUheader {
+pub fn decode_nalu_header(
+    long_start_code: bool,
+    nalu_data: &mut ByteStream,
+) -> Result<NALUheader, DecodeError> {
     // each NALU has a 1 byte header; the rest is control information
     // or coded video data
 
-    let forbidden_zero_bit = nalu_data.read_bits(1) as u8; // f(1)
-    let nal_ref_idc = nalu_data.read_bits(2) as u8; // u(2)
-    let nal_unit_type = nalu_data.read_bits(5) as u8; // u(5)
+    let forbidden_zero_bit = nalu_data.read_bits(1)? as u8; // f(1)
+    let nal_ref_idc = nalu_data.read_bits(2)? as u8; // u(2)
+    let nal_unit_type = nalu_data.read_bits(5)? as u8; // u(5)
     debug!(target: "decode","");
     debug!(target: "decode","");
     debug!(target: "decode","Annex B NALU w/ {} startcode, len {}, forbidden_bit {}, nal_reference_idc {}, nal_unit_type {}",
@@ -156,14 +269,14 @@ pub fn decode_nalu_header(long_start_code: bool, nalu_data: &mut ByteStream) ->
 
     if nal_unit_type == 14 || nal_unit_type == 20 || nal_unit_type == 21 {
         if nal_unit_type != 21 {
-            svc_extension_flag = 1 == nalu_data.read_bits(1); // u(1)
+            svc_extension_flag = 1 == nalu_data.read_bits(1)?; // u(1)
             decoder_formatted_print(
                 "NALU Extension: svc_extension_flag",
                 &svc_extension_flag,
                 63,
             );
         } else {
-            avc_3d_extension_flag = 1 == nalu_data.read_bits(1); // u(1)
+            avc_3d_extension_flag = 1 == nalu_data.read_bits(1)?; // u(1)
             decoder_formatted_print(
                 "NALU Extension: avc_3d_extension_flag",
                 &avc_3d_extension_flag,
@@ -173,17 +286,17 @@ pub fn decode_nalu_header(long_start_code: bool, nalu_data: &mut ByteStream) ->
 
         if svc_extension_flag {
             // specified in Annex G
-            svc_extension = decode_nal_unit_header_svc_extension(nalu_data);
+            svc_extension = decode_nal_unit_header_svc_extension(nalu_data)?;
         } else if avc_3d_extension_flag {
             // specified in Annex J
-            avc_3d_extension = decode_nal_unit_header_3davc_extension(nalu_data);
+            avc_3d_extension = decode_nal_unit_header_3davc_extension(nalu_data)?;
         } else {
             // specified in Annex H
-            mvc_extension = decode_nal_unit_header_mvc_extension(nalu_data);
+            mvc_extension = decode_nal_unit_header_mvc_extension(nalu_data)?;
         }
     }
 
-    NALUheader {
+    Ok(NALUheader {
         forbidden_zero_bit,
         nal_ref_idc,
         nal_unit_type,
@@ -192,48 +305,50 @@ pub fn decode_nalu_header(long_start_code: bool, nalu_data: &mut ByteStream) ->
         avc_3d_extension_flag,
         avc_3d_extension,
         mvc_extension,
-    }
+    })
 }
 
-fn decode_nal_unit_header_svc_extension(bs: &mut ByteStream) -> NALUHeaderSVCExtension {
-    let idr_flag = 1 == bs.read_bits(1);
+fn decode_nal_unit_header_svc_extension(
+    bs: &mut ByteStream,
+) -> Result<NALUHeaderSVCExtension, DecodeError> {
+    let idr_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("NALU SVC Extension: idr_flag", &idr_flag, 63);
-    let priority_id = bs.read_bits(6) as u8;
+    let priority_id = bs.read_bits(6)? as u8;
     decoder_formatted_print("NALU SVC Extension: priority_id", &priority_id, 63);
-    let no_inter_layer_pred_flag = 1 == bs.read_bits(1);
+    let no_inter_layer_pred_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "NALU SVC Extension: no_inter_layer_pred_flag",
         &no_inter_layer_pred_flag,
         63,
     );
-    let dependency_id = bs.read_bits(3) as u8;
+    let dependency_id = bs.read_bits(3)? as u8;
     decoder_formatted_print("NALU SVC Extension: dependency_id", &dependency_id, 63);
-    let quality_id = bs.read_bits(4) as u8;
+    let quality_id = bs.read_bits(4)? as u8;
     decoder_formatted_print("NALU SVC Extension: quality_id", &quality_id, 63);
-    let temporal_id = bs.read_bits(3) as u8;
+    let temporal_id = bs.read_bits(3)? as u8;
     decoder_formatted_print("NALU SVC Extension: temporal_id", &temporal_id, 63);
-    let use_ref_base_pic_flag = 1 == bs.read_bits(1);
+    let use_ref_base_pic_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "NALU SVC Extension: use_ref_base_pic_flag",
         &use_ref_base_pic_flag,
         63,
     );
-    let discardable_flag = 1 == bs.read_bits(1);
+    let discardable_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "NALU SVC Extension: discardable_flag",
         &discardable_flag,
         63,
     );
-    let output_flag = 1 == bs.read_bits(1);
+    let output_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("NALU SVC Extension: output_flag", &output_flag, 63);
-    let reserved_three_2bits = bs.read_bits(2) as u8;
+    let reserved_three_2bits = bs.read_bits(2)? as u8;
     decoder_formatted_print(
         "NALU SVC Extension: reserved_three_2bits",
         &reserved_three_2bits,
         63,
     );
 
-    NALUHeaderSVCExtension {
+    Ok(NALUHeaderSVCExtension {
         idr_flag,
         priority_id,
         no_inter_layer_pred_flag,
@@ -244,63 +359,67 @@ fn decode_nal_unit_header_svc_extension(bs: &mut ByteStream) -> NALUHeaderSVCExt
         discardable_flag,
         output_flag,
         reserved_three_2bits,
-    }
+    })
 }
 
-fn decode_nal_unit_header_3davc_extension(bs: &mut ByteStream) -> NALUHeader3DAVCExtension {
-    let view_idx = bs.read_bits(8) as u8;
+fn decode_nal_unit_header_3davc_extension(
+    bs: &mut ByteStream,
+) -> Result<NALUHeader3DAVCExtension, DecodeError> {
+    let view_idx = bs.read_bits(8)? as u8;
     decoder_formatted_print("NALU 3DAVC Extension: view_idx", &view_idx, 63);
-    let depth_flag = 1 == bs.read_bits(1);
+    let depth_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("NALU 3DAVC Extension: depth_flag", &depth_flag, 63);
-    let non_idr_flag = 1 == bs.read_bits(1);
+    let non_idr_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("NALU 3DAVC Extension: non_idr_flag", &non_idr_flag, 63);
-    let temporal_id = bs.read_bits(3) as u8;
+    let temporal_id = bs.read_bits(3)? as u8;
     decoder_formatted_print("NALU 3DAVC Extension: temporal_id", &temporal_id, 63);
-    let anchor_pic_flag = 1 == bs.read_bits(1);
+    let anchor_pic_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "NALU 3DAVC Extension: anchor_pic_flag",
         &anchor_pic_flag,
         63,
     );
-    let inter_view_flag = 1 == bs.read_bits(1);
+    let inter_view_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "NALU 3DAVC Extension: inter_view_flag",
         &inter_view_flag,
         63,
     );
 
-    NALUHeader3DAVCExtension {
+    Ok(NALUHeader3DAVCExtension {
         view_idx,
         depth_flag,
         non_idr_flag,
         temporal_id,
         anchor_pic_flag,
         inter_view_flag,
-    }
+    })
 }
 
 /// Described in H.7.3.1.1 NAL unit header MVC extension syntax
-fn decode_nal_unit_header_mvc_extension(bs: &mut ByteStream) -> NALUHeaderMVCExtension {
-    let non_idr_flag = 1 == bs.read_bits(1);
+fn decode_nal_unit_header_mvc_extension(
+    bs: &mut ByteStream,
+) -> Result<NALUHeaderMVCExtension, DecodeError> {
+    let non_idr_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("NALU MVC Extension: non_idr_flag", &non_idr_flag, 63);
-    let priority_id = bs.read_bits(6) as u8;
+    let priority_id = bs.read_bits(6)? as u8;
     decoder_formatted_print("NALU MVC Extension: priority_id", &priority_id, 63);
-    let view_id = bs.read_bits(10);
+    let view_id = bs.read_bits(10)?;
     decoder_formatted_print("NALU MVC Extension: view_id", &view_id, 63);
-    let temporal_id = bs.read_bits(3) as u8;
+    let temporal_id = bs.read_bits(3)? as u8;
     decoder_formatted_print("NALU MVC Extension: temporal_id", &temporal_id, 63);
-    let anchor_pic_flag = 1 == bs.read_bits(1);
+    let anchor_pic_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("NALU MVC Extension: anchor_pic_flag", &anchor_pic_flag, 63);
-    let inter_view_flag = 1 == bs.read_bits(1);
+    let inter_view_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("NALU MVC Extension: inter_view_flag", &inter_view_flag, 63);
-    let reserved_one_bit = 1 == bs.read_bits(1);
+    let reserved_one_bit = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "NALU MVC Extension: reserved_one_bit",
         &reserved_one_bit,
         63,
     );
 
-    NALUHeaderMVCExtension {
+    Ok(NALUHeaderMVCExtension {
         non_idr_flag,
         priority_id,
         view_id,
@@ -308,15 +427,18 @@ fn decode_nal_unit_header_mvc_extension(bs: &mut ByteStream) -> NALUHeaderMVCExt
         anchor_pic_flag,
         inter_view_flag,
         reserved_one_bit,
-    }
+    })
 }
 
 /// Described in G.7.3.2.12.1 Prefix NAL unit SVC syntax
-pub fn decode_prefix_nal_unit_svc(nh: NALUheader, bs: &mut ByteStream) -> PrefixNALU {
+pub fn decode_prefix_nal_unit_svc(
+    nh: NALUheader,
+    bs: &mut ByteStream,
+) -> Result<PrefixNALU, DecodeError> {
     let mut res = PrefixNALU::new();
 
     if nh.nal_ref_idc != 0 {
-        res.store_ref_base_pic_flag = 1 == bs.read_bits(1);
+        res.store_ref_base_pic_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "Prefix NALU: store_ref_base_pic_flag",
             &res.store_ref_base_pic_flag,
@@ -325,9 +447,9 @@ pub fn decode_prefix_nal_unit_svc(nh: NALUheader, bs: &mut ByteStream) -> Prefix
         if (res.store_ref_base_pic_flag || nh.svc_extension.use_ref_base_pic_flag)
             && !nh.svc_extension.idr_flag
         {
-            dec_ref_base_pic_marking(&mut res, bs);
+            dec_ref_base_pic_marking(&mut res, bs)?;
         }
-        res.additional_prefix_nal_unit_extension_flag = 1 == bs.read_bits(1);
+        res.additional_prefix_nal_unit_extension_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "Prefix NALU: additional_prefix_nal_unit_extension_flag",
             &res.additional_prefix_nal_unit_extension_flag,
@@ -337,7 +459,7 @@ pub fn decode_prefix_nal_unit_svc(nh: NALUheader, bs: &mut ByteStream) -> Prefix
             let i = 0;
             while bs.more_data() {
                 res.additional_prefix_nal_unit_extension_data_flag
-                    .push(1 == bs.read_bits(1));
+                    .push(1 == bs.read_bits(1)?);
                 decoder_formatted_print(
                     "Prefix NALU: additional_prefix_nal_unit_extension_data_flag",
                     &res.additional_prefix_nal_unit_extension_data_flag[i],
@@ -349,7 +471,7 @@ pub fn decode_prefix_nal_unit_svc(nh: NALUheader, bs: &mut ByteStream) -> Prefix
         let i = 0;
         while bs.more_data() {
             res.additional_prefix_nal_unit_extension_data_flag
-                .push(1 == bs.read_bits(1));
+                .push(1 == bs.read_bits(1)?);
             decoder_formatted_print(
                 "Prefix NALU: additional_prefix_nal_unit_extension_data_flag",
                 &res.additional_prefix_nal_unit_extension_data_flag[i],
@@ -358,12 +480,12 @@ pub fn decode_prefix_nal_unit_svc(nh: NALUheader, bs: &mut ByteStream) -> Prefix
         }
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in G.7.3.3.5 Decoded reference base picture marking syntax
-fn dec_ref_base_pic_marking(res: &mut PrefixNALU, bs: &mut ByteStream) {
-    res.adaptive_ref_base_pic_marking_mode_flag = 1 == bs.read_bits(1);
+fn dec_ref_base_pic_marking(res: &mut PrefixNALU, bs: &mut ByteStream) -> Result<(), DecodeError> {
+    res.adaptive_ref_base_pic_marking_mode_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "Prefix NALU: adaptive_ref_base_pic_marking_mode_flag",
         &res.adaptive_ref_base_pic_marking_mode_flag,
@@ -373,7 +495,7 @@ fn dec_ref_base_pic_marking(res: &mut PrefixNALU, bs: &mut ByteStream) {
         let mut i = 0;
         loop {
             res.memory_management_base_control_operation
-                .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
             decoder_formatted_print(
                 "Prefix NALU: memory_management_base_control_operation",
                 &res.memory_management_base_control_operation[i],
@@ -382,7 +504,7 @@ fn dec_ref_base_pic_marking(res: &mut PrefixNALU, bs: &mut ByteStream) {
 
             if res.memory_management_base_control_operation[i] == 1 {
                 res.difference_of_base_pic_nums_minus1
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                 decoder_formatted_print(
                     "Prefix NALU: difference_of_base_pic_nums_minus1",
                     &res.difference_of_base_pic_nums_minus1[i],
@@ -394,7 +516,7 @@ fn dec_ref_base_pic_marking(res: &mut PrefixNALU, bs: &mut ByteStream) {
 
             if res.memory_management_base_control_operation[i] == 2 {
                 res.long_term_base_pic_num
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                 decoder_formatted_print(
                     "Prefix NALU: long_term_base_pic_num",
                     &res.long_term_base_pic_num[i],
@@ -411,14 +533,139 @@ fn dec_ref_base_pic_marking(res: &mut PrefixNALU, bs: &mut ByteStream) {
             i += 1;
         }
     }
+
+    Ok(())
 }
 
 /// Described in 7.3.2.4 Access unit delimiter syntax
-pub fn decode_access_unit_delimiter(bs: &mut ByteStream) -> AccessUnitDelim {
+pub fn decode_access_unit_delimiter(bs: &mut ByteStream) -> Result<AccessUnitDelim, DecodeError> {
     let mut aud = AccessUnitDelim::new();
 
-    aud.primary_pic_type = bs.read_bits(3) as u8;
+    aud.primary_pic_type = bs.read_bits(3)? as u8;
     decoder_formatted_print("AUD: primary_pic_type", &aud.primary_pic_type, 63);
 
-    aud
+    Ok(aud)
+}
+
+/// Described in 7.3.2.7 Filler data RBSP syntax
+pub fn decode_filler_data(bs: &mut ByteStream) -> FillerData {
+    let mut fd = FillerData::new();
+
+    // every byte before rbsp_trailing_bits() is kept, even when it is not 0xFF
+    let mut ff_byte: Vec<u8> = bs.bytestream.drain(..).collect();
+    fd.rbsp_trailing_bits = ff_byte.last() == Some(&0x80);
+    if fd.rbsp_trailing_bits {
+        ff_byte.pop();
+    }
+    fd.ff_byte = ff_byte;
+    decoder_formatted_print("Filler data: ff_byte", &fd.ff_byte, 63);
+    decoder_formatted_print("Filler data: rbsp_trailing_bits", fd.rbsp_trailing_bits, 63);
+
+    if fd.ff_byte.iter().any(|&b| b != 0xff) {
+        println!("[WARNING] Filler data contains bytes that are not 0xFF");
+    }
+
+    fd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::SPS_PPS_I_P;
+    use crate::decoder::decoder::decode_bitstream_from_bytes;
+
+    /// Hands out one byte per read so that NALUs span many reader buffers
+    struct OneByteReader<'a>(&'a [u8]);
+
+    impl Read for OneByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() || buf.is_empty() {
+                return Ok(0);
+            }
+            buf[0] = self.0[0];
+            self.0 = &self.0[1..];
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn test_split_into_nalu_from_bytes_and_reader_match() {
+        // a long start code SEI whose payload needs an emulation prevention byte, then a
+        // short start code End of Stream
+        let data = [
+            0, 0, 0, 1, 0x06, 0x00, 0x00, 0x03, 0x01, 0x80, 0, 0, 1, 0x0b,
+        ];
+        let nalus = split_into_nalu_from_bytes(&data);
+        assert_eq!(nalus.len(), 2);
+        assert!(nalus[0].longstartcode);
+        assert_eq!(nalus[0].content, vec![0x06, 0x00, 0x00, 0x01, 0x80]);
+        assert!(!nalus[1].longstartcode);
+        assert_eq!(nalus[1].content, vec![0x0b]);
+
+        for data in [&data[..], SPS_PPS_I_P] {
+            let from_bytes = split_into_nalu_from_bytes(data);
+            let from_reader = split_into_nalu_from_reader(OneByteReader(data)).unwrap();
+            let incremental: Vec<NALU> = NALUIterator::new(data).map(|x| x.unwrap()).collect();
+            assert_eq!(from_reader.len(), from_bytes.len());
+            assert_eq!(incremental.len(), from_bytes.len());
+            for (i, n) in from_bytes.iter().enumerate() {
+                assert_eq!(from_reader[i].content, n.content);
+                assert_eq!(from_reader[i].longstartcode, n.longstartcode);
+                assert_eq!(incremental[i].content, n.content);
+            }
+        }
+        assert_eq!(split_into_nalu_from_bytes(SPS_PPS_I_P).len(), 4);
+    }
+
+    #[test]
+    fn test_filler_data_and_end_nalu_junk_round_trip() {
+        use crate::vidgen::film::FilmState;
+        use crate::vidgen::generate_configurations::RandomBoolRange;
+        use crate::vidgen::generate_configurations::RandomFillerDataRange;
+        use crate::vidgen::nalu::random_filler_data;
+
+        let mut data = SPS_PPS_I_P.to_vec();
+        // filler data, End of Sequence with trailing junk, and a plain End of Stream
+        data.extend([0, 0, 0, 1, 0x0c, 0xff, 0xff, 0xfe, 0xff, 0x80]);
+        data.extend([0, 0, 0, 1, 0x0a, 0x12, 0x34]);
+        data.extend([0, 0, 0, 1, 0x0b]);
+        let mut ds = decode_bitstream_from_bytes(&data, false, false, false, false).unwrap();
+
+        assert_eq!(ds.filler_data.len(), 1);
+        assert_eq!(ds.filler_data[0].ff_byte, vec![0xff, 0xff, 0xfe, 0xff]);
+        assert!(ds.filler_data[0].rbsp_trailing_bits);
+        assert_eq!(ds.nalu_elements[5].content[1..], [0x12, 0x34]);
+
+        // a generated filler NALU with only non-0xFF bytes and no trailing bits
+        let mut nh = NALUheader::new();
+        nh.nal_unit_type = 12;
+        ds.nalu_headers.push(nh);
+        ds.nalu_elements.push(NALU::new());
+        ds.filler_data.push(FillerData::new());
+        let rconfig = RandomFillerDataRange {
+            non_ff_byte: RandomBoolRange::new(1, 1, 1),
+            missing_rbsp_trailing_bits: RandomBoolRange::new(1, 1, 1),
+        };
+        let mut film = FilmState::setup_film_from_seed(19);
+        random_filler_data(1, 16, rconfig, &mut ds, &mut film);
+        assert!(!ds.filler_data[1].rbsp_trailing_bits);
+        assert_eq!(ds.filler_data[1].ff_byte.len(), 16);
+
+        let (encoded, _, _) = crate::encoder::encoder::reencode_syntax_elements(
+            &mut ds, -1, -1, -1, false, true, false,
+        );
+        assert!(encoded.starts_with(&data));
+        let decoded = decode_bitstream_from_bytes(&encoded, false, false, false, false).unwrap();
+
+        assert_eq!(decoded.nalu_elements.len(), 8);
+        assert_eq!(
+            serde_json::to_string(&decoded.filler_data).unwrap(),
+            serde_json::to_string(&ds.filler_data).unwrap()
+        );
+        assert_eq!(
+            decoded.nalu_elements[5].content,
+            ds.nalu_elements[5].content
+        );
+        assert_eq!(decoded.nalu_elements[6].content.len(), 1);
+    }
 }
@@ -1,6 +1,9 @@
 //! Parameter Set (SPS, PPS, VUI, extensions) syntax element decoding.
 
 use crate::common::data_structures::AVC3DSPSExtension;
+use crate::common::data_structures::AcquisitionElement3DV;
+use crate::common::data_structures::DepthParameterSet;
+use crate::common::data_structures::DepthRanges;
 use crate::common::data_structures::HRDParameters;
 use crate::common::data_structures::MVCDSPSExtension;
 use crate::common::data_structures::MVCDVUIParameters;
@@ -12,9 +15,11 @@ use crate::common::data_structures::SVCSPSExtension;
 use crate::common::data_structures::SVCVUIParameters;
 use crate::common::data_structures::SeqParameterSet;
 use crate::common::data_structures::SubsetSPS;
+use crate::common::data_structures::VSPParam;
 use crate::common::data_structures::VUIParameters;
 use crate::common::helper::decoder_formatted_print;
 use crate::common::helper::ByteStream;
+use crate::decoder::error::DecodeError;
 use crate::decoder::expgolomb::exp_golomb_decode_one_wrapper;
 use log::debug;
 
@@ -23,14 +28,14 @@ pub fn decode_pic_parameter_set(
     bs: &mut ByteStream,
     spses: &Vec<SeqParameterSet>,
     subset_spses: &Vec<SubsetSPS>,
-) -> PicParameterSet {
+) -> Result<PicParameterSet, DecodeError> {
     let mut res = PicParameterSet::new();
     res.available = true;
 
-    res.pic_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.pic_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print("PPS: pic_parameter_set_id", &res.pic_parameter_set_id, 63);
 
-    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print("PPS: seq_parameter_set_id", &res.seq_parameter_set_id, 63);
 
     let mut cur_sps_wrapper: Option<&SeqParameterSet> = None;
@@ -59,15 +64,15 @@ pub fn decode_pic_parameter_set(
                     s = x;
                     res.is_subset_pps = true;
                 }
-                _ => panic!(
+                _ => return Err(DecodeError::new(format!(
                     "decode_pic_parameter_set - SPS or SubsetSPS with id {} not found",
                     res.seq_parameter_set_id
-                ),
+                ))),
             }
         }
     }
 
-    res.entropy_coding_mode_flag = 1 == bs.read_bits(1);
+    res.entropy_coding_mode_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "PPS: entropy_coding_mode_flag",
         &res.entropy_coding_mode_flag,
@@ -78,14 +83,14 @@ pub fn decode_pic_parameter_set(
         res.entropy_coding_mode_flag
     );
 
-    res.bottom_field_pic_order_in_frame_present_flag = 1 == bs.read_bits(1);
+    res.bottom_field_pic_order_in_frame_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "PPS: bottom_field_pic_order_in_frame_present_flag",
         &res.bottom_field_pic_order_in_frame_present_flag,
         63,
     );
 
-    res.num_slice_groups_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.num_slice_groups_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "PPS: num_slice_groups_minus1",
         &res.num_slice_groups_minus1,
@@ -94,13 +99,13 @@ pub fn decode_pic_parameter_set(
 
     if res.num_slice_groups_minus1 > 0 {
         println!("[WARNING] Correct FMO decoding/encoding not yet completely available - issues may arise");
-        res.slice_group_map_type = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.slice_group_map_type = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print("PPS: slice_group_map_type", &res.slice_group_map_type, 63);
 
         if res.slice_group_map_type == 0 {
             for i in 0..=res.num_slice_groups_minus1 {
                 res.run_length_minus1
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                 decoder_formatted_print(
                     "PPS: run_length_minus1",
                     &res.run_length_minus1[i as usize],
@@ -110,32 +115,32 @@ pub fn decode_pic_parameter_set(
         } else if res.slice_group_map_type == 2 {
             for i in 0..res.num_slice_groups_minus1 {
                 res.top_left
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                 decoder_formatted_print("PPS: top_left", &res.top_left[i as usize], 63);
 
                 res.bottom_right
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                 decoder_formatted_print("PPS: bottom_right", &res.bottom_right[i as usize], 63);
             }
         } else if res.slice_group_map_type == 3
             || res.slice_group_map_type == 4
             || res.slice_group_map_type == 5
         {
-            res.slice_group_change_direction_flag = 1 == bs.read_bits(1);
+            res.slice_group_change_direction_flag = 1 == bs.read_bits(1)?;
             decoder_formatted_print(
                 "PPS: slice_group_change_direction_flag",
                 &res.slice_group_change_direction_flag,
                 63,
             );
 
-            res.slice_group_change_rate_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+            res.slice_group_change_rate_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
             decoder_formatted_print(
                 "PPS: slice_group_change_rate_minus1",
                 &res.slice_group_change_rate_minus1,
                 63,
             );
         } else if res.slice_group_map_type == 6 {
-            res.pic_size_in_map_units_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+            res.pic_size_in_map_units_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
             decoder_formatted_print(
                 "PPS: pic_size_in_map_units_minus1",
                 &res.pic_size_in_map_units_minus1,
@@ -144,60 +149,60 @@ pub fn decode_pic_parameter_set(
 
             let bits_to_read = ((res.num_slice_groups_minus1 + 1) as f64).log2().ceil() as u8;
             for _ in 0..=res.pic_size_in_map_units_minus1 {
-                res.slice_group_id.push(bs.read_bits(bits_to_read));
+                res.slice_group_id.push(bs.read_bits(bits_to_read)?);
             }
             decoder_formatted_print("PPS: slice_group_id", &res.slice_group_id, 63);
         }
     }
 
-    res.num_ref_idx_l0_default_active_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.num_ref_idx_l0_default_active_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "PPS: num_ref_idx_l0_default_active_minus1",
         &res.num_ref_idx_l0_default_active_minus1,
         63,
     );
 
-    res.num_ref_idx_l1_default_active_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.num_ref_idx_l1_default_active_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "PPS: num_ref_idx_l1_default_active_minus1",
         &res.num_ref_idx_l1_default_active_minus1,
         63,
     );
 
-    res.weighted_pred_flag = 1 == bs.read_bits(1);
+    res.weighted_pred_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("PPS: weighted_pred_flag", &res.weighted_pred_flag, 63);
 
-    res.weighted_bipred_idc = bs.read_bits(2) as u8;
+    res.weighted_bipred_idc = bs.read_bits(2)? as u8;
     decoder_formatted_print("PPS: weighted_bipred_idc", &res.weighted_bipred_idc, 63);
 
-    res.pic_init_qp_minus26 = exp_golomb_decode_one_wrapper(bs, true, 0);
+    res.pic_init_qp_minus26 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
     decoder_formatted_print("PPS: pic_init_qp_minus26", &res.pic_init_qp_minus26, 63);
 
-    res.pic_init_qs_minus26 = exp_golomb_decode_one_wrapper(bs, true, 0);
+    res.pic_init_qs_minus26 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
     decoder_formatted_print("PPS: pic_init_qs_minus26", &res.pic_init_qs_minus26, 63);
 
-    res.chroma_qp_index_offset = exp_golomb_decode_one_wrapper(bs, true, 0);
+    res.chroma_qp_index_offset = exp_golomb_decode_one_wrapper(bs, true, 0)?;
     decoder_formatted_print(
         "PPS: chroma_qp_index_offset",
         &res.chroma_qp_index_offset,
         63,
     );
 
-    res.deblocking_filter_control_present_flag = 1 == bs.read_bits(1);
+    res.deblocking_filter_control_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "PPS: deblocking_filter_control_present_flag",
         &res.deblocking_filter_control_present_flag,
         63,
     );
 
-    res.constrained_intra_pred_flag = 1 == bs.read_bits(1);
+    res.constrained_intra_pred_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "PPS: constrained_intra_pred_flag",
         &res.constrained_intra_pred_flag,
         63,
     );
 
-    res.redundant_pic_cnt_present_flag = 1 == bs.read_bits(1);
+    res.redundant_pic_cnt_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "PPS: redundant_pic_cnt_present_flag",
         &res.redundant_pic_cnt_present_flag,
@@ -207,14 +212,14 @@ pub fn decode_pic_parameter_set(
     // rbsp_more_data()
     if bs.more_data() {
         res.more_data_flag = true;
-        res.transform_8x8_mode_flag = 1 == bs.read_bits(1);
+        res.transform_8x8_mode_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "PPS: transform_8x8_mode_flag",
             &res.transform_8x8_mode_flag,
             63,
         );
 
-        res.pic_scaling_matrix_present_flag = 1 == bs.read_bits(1);
+        res.pic_scaling_matrix_present_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "PPS: pic_scaling_matrix_present_flag",
             &res.pic_scaling_matrix_present_flag,
@@ -230,7 +235,7 @@ pub fn decode_pic_parameter_set(
                 false => 0,
             };
             for i in 0..max_val {
-                res.pic_scaling_list_present_flag.push(1 == bs.read_bits(1));
+                res.pic_scaling_list_present_flag.push(1 == bs.read_bits(1)?);
                 decoder_formatted_print(
                     "PPS: pic_scaling_list_present_flag",
                     &res.pic_scaling_list_present_flag[i],
@@ -254,7 +259,7 @@ pub fn decode_pic_parameter_set(
                             &mut res.scaling_list_4x4[i],
                             16,
                             &mut res.use_default_scaling_matrix_4x4[i],
-                        );
+                        )?;
                     } else {
                         decode_scaling_list(
                             bs,
@@ -262,13 +267,13 @@ pub fn decode_pic_parameter_set(
                             &mut res.scaling_list_8x8[i],
                             64,
                             &mut res.use_default_scaling_matrix_8x8[i],
-                        );
+                        )?;
                     }
                 }
             }
         }
 
-        res.second_chroma_qp_index_offset = exp_golomb_decode_one_wrapper(bs, true, 0);
+        res.second_chroma_qp_index_offset = exp_golomb_decode_one_wrapper(bs, true, 0)?;
         decoder_formatted_print(
             "PPS: second_chroma_qp_index_offset",
             &res.second_chroma_qp_index_offset,
@@ -276,47 +281,47 @@ pub fn decode_pic_parameter_set(
         );
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in 7.3.2.1.1 -- Sequence Parameter Set
-pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
+pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> Result<SeqParameterSet, DecodeError> {
     let mut res: SeqParameterSet = SeqParameterSet::new();
 
     res.available = true;
 
-    res.profile_idc = bs.read_bits(8) as u8;
+    res.profile_idc = bs.read_bits(8)? as u8;
     decoder_formatted_print("SPS: profile_idc", &res.profile_idc, 63);
     println!("\t SPS: profile_idc {}", res.profile_idc);
 
-    res.constraint_set0_flag = 1 == bs.read_bits(1);
+    res.constraint_set0_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("SPS: constraint_set0_flag", &res.constraint_set0_flag, 63);
 
-    res.constraint_set1_flag = 1 == bs.read_bits(1);
+    res.constraint_set1_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("SPS: constraint_set1_flag", &res.constraint_set1_flag, 63);
 
-    res.constraint_set2_flag = 1 == bs.read_bits(1);
+    res.constraint_set2_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("SPS: constraint_set2_flag", &res.constraint_set2_flag, 63);
 
-    res.constraint_set3_flag = 1 == bs.read_bits(1);
+    res.constraint_set3_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("SPS: constraint_set3_flag", &res.constraint_set3_flag, 63);
 
-    res.constraint_set4_flag = 1 == bs.read_bits(1);
+    res.constraint_set4_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("SPS: constraint_set4_flag", &res.constraint_set4_flag, 63);
 
-    res.constraint_set5_flag = 1 == bs.read_bits(1);
+    res.constraint_set5_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("SPS: constraint_set5_flag", &res.constraint_set5_flag, 63);
 
-    res.reserved_zero_2bits = bs.read_bits(2) as u8;
+    res.reserved_zero_2bits = bs.read_bits(2)? as u8;
     decoder_formatted_print("SPS: reserved_zero_2bits", &res.reserved_zero_2bits, 63);
 
     // 7.4.2.1.1
     //assert_eq!(res.reserved_zero_2bits, 0);
 
-    res.level_idc = bs.read_bits(8) as u8;
+    res.level_idc = bs.read_bits(8)? as u8;
     decoder_formatted_print("SPS: level_idc", &res.level_idc, 63);
 
-    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     //assert!(res.seq_parameter_set_id < 32);
     decoder_formatted_print("SPS: seq_parameter_set_id", &res.seq_parameter_set_id, 63);
 
@@ -334,7 +339,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
         || res.profile_idc == 134
         || res.profile_idc == 135
     {
-        res.chroma_format_idc = exp_golomb_decode_one_wrapper(bs, false, 0) as u8;
+        res.chroma_format_idc = exp_golomb_decode_one_wrapper(bs, false, 0)? as u8;
 
         // Not-spec compliant
         if res.chroma_format_idc >= 4 {
@@ -345,7 +350,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
         println!("\t SPS: chroma_format_idc {}", res.chroma_format_idc);
 
         if res.chroma_format_idc == 3 {
-            res.separate_colour_plane_flag = 1 == bs.read_bits(1);
+            res.separate_colour_plane_flag = 1 == bs.read_bits(1)?;
             decoder_formatted_print(
                 "SPS: separate_colour_plane_flag",
                 &res.separate_colour_plane_flag,
@@ -353,7 +358,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
             );
         }
 
-        res.bit_depth_luma_minus8 = exp_golomb_decode_one_wrapper(bs, false, 0) as u8;
+        res.bit_depth_luma_minus8 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u8;
 
         // Not-spec compliant
         if res.bit_depth_luma_minus8 >= 7 {
@@ -366,7 +371,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
             res.bit_depth_luma_minus8
         );
 
-        res.bit_depth_chroma_minus8 = exp_golomb_decode_one_wrapper(bs, false, 0) as u8;
+        res.bit_depth_chroma_minus8 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u8;
         // Not-spec compliant
         if res.bit_depth_chroma_minus8 >= 7 {
             debug!(target: "decode","[WARNING] res.bit_depth_chroma_minus8 >= 7");
@@ -382,14 +387,14 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
             res.bit_depth_chroma_minus8
         );
 
-        res.qpprime_y_zero_transform_bypass_flag = 1 == bs.read_bits(1);
+        res.qpprime_y_zero_transform_bypass_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "SPS: qpprime_y_zero_transform_bypass_flag",
             &res.qpprime_y_zero_transform_bypass_flag,
             63,
         );
 
-        res.seq_scaling_matrix_present_flag = 1 == bs.read_bits(1);
+        res.seq_scaling_matrix_present_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "SPS: seq_scaling_matrix_present_flag",
             &res.seq_scaling_matrix_present_flag,
@@ -403,7 +408,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
             };
 
             for i in 0..cur_max {
-                res.seq_scaling_list_present_flag.push(1 == bs.read_bits(1));
+                res.seq_scaling_list_present_flag.push(1 == bs.read_bits(1)?);
                 decoder_formatted_print(
                     "SPS: seq_scaling_list_present_flag",
                     &res.seq_scaling_list_present_flag[i],
@@ -427,7 +432,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
                             &mut res.scaling_list_4x4[i],
                             16,
                             &mut res.use_default_scaling_matrix_4x4[i],
-                        );
+                        )?;
                     } else {
                         decode_scaling_list(
                             bs,
@@ -435,46 +440,46 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
                             &mut res.scaling_list_8x8[i],
                             64,
                             &mut res.use_default_scaling_matrix_8x8[i],
-                        );
+                        )?;
                     }
                 }
             }
         }
     }
 
-    res.log2_max_frame_num_minus4 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.log2_max_frame_num_minus4 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "SPS: log2_max_frame_num_minus4",
         &res.log2_max_frame_num_minus4,
         63,
     );
 
-    res.pic_order_cnt_type = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.pic_order_cnt_type = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print("SPS: pic_order_cnt_type", &res.pic_order_cnt_type, 63);
 
     if res.pic_order_cnt_type == 0 {
-        res.log2_max_pic_order_cnt_lsb_minus4 = exp_golomb_decode_one_wrapper(bs, false, 0) as u8;
+        res.log2_max_pic_order_cnt_lsb_minus4 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u8;
         decoder_formatted_print(
             "SPS: log2_max_pic_order_cnt_lsb_minus4",
             &res.log2_max_pic_order_cnt_lsb_minus4,
             63,
         );
     } else if res.pic_order_cnt_type == 1 {
-        res.delta_pic_order_always_zero_flag = 1 == bs.read_bits(1);
+        res.delta_pic_order_always_zero_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "SPS: delta_pic_order_always_zero_flag",
             &res.delta_pic_order_always_zero_flag,
             63,
         );
 
-        res.offset_for_non_ref_pic = exp_golomb_decode_one_wrapper(bs, true, 0);
+        res.offset_for_non_ref_pic = exp_golomb_decode_one_wrapper(bs, true, 0)?;
         decoder_formatted_print(
             "SPS: offset_for_non_ref_pic",
             &res.offset_for_non_ref_pic,
             63,
         );
 
-        res.offset_for_top_to_bottom_field = exp_golomb_decode_one_wrapper(bs, true, 0);
+        res.offset_for_top_to_bottom_field = exp_golomb_decode_one_wrapper(bs, true, 0)?;
         decoder_formatted_print(
             "SPS: offset_for_top_to_bottom_field",
             &res.offset_for_top_to_bottom_field,
@@ -482,7 +487,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
         );
 
         res.num_ref_frames_in_pic_order_cnt_cycle =
-            exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+            exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SPS: num_ref_frames_in_pic_order_cnt_cycle",
             &res.num_ref_frames_in_pic_order_cnt_cycle,
@@ -491,7 +496,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
 
         for i in 0..res.num_ref_frames_in_pic_order_cnt_cycle {
             res.offset_for_ref_frame
-                .push(exp_golomb_decode_one_wrapper(bs, true, 0));
+                .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
             decoder_formatted_print(
                 "SPS: offset_for_ref_frame",
                 &res.offset_for_ref_frame[i as usize],
@@ -500,35 +505,35 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
         }
     }
 
-    res.max_num_ref_frames = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.max_num_ref_frames = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print("SPS: max_num_ref_frames", &res.max_num_ref_frames, 63);
 
-    res.gaps_in_frame_num_value_allowed_flag = 1 == bs.read_bits(1);
+    res.gaps_in_frame_num_value_allowed_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SPS: gaps_in_frame_num_value_allowed_flag",
         &res.gaps_in_frame_num_value_allowed_flag,
         63,
     );
 
-    res.pic_width_in_mbs_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.pic_width_in_mbs_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "SPS: pic_width_in_mbs_minus1",
         &res.pic_width_in_mbs_minus1,
         63,
     );
 
-    res.pic_height_in_map_units_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.pic_height_in_map_units_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "SPS: pic_height_in_map_units_minus1",
         &res.pic_height_in_map_units_minus1,
         63,
     );
 
-    res.frame_mbs_only_flag = 1 == bs.read_bits(1);
+    res.frame_mbs_only_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("SPS: frame_mbs_only_flag", &res.frame_mbs_only_flag, 63);
 
     if !res.frame_mbs_only_flag {
-        res.mb_adaptive_frame_field_flag = 1 == bs.read_bits(1);
+        res.mb_adaptive_frame_field_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "SPS: mb_adaptive_frame_field_flag",
             &res.mb_adaptive_frame_field_flag,
@@ -536,35 +541,35 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
         );
     }
 
-    res.direct_8x8_inference_flag = 1 == bs.read_bits(1);
+    res.direct_8x8_inference_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SPS: direct_8x8_inference_flag",
         &res.direct_8x8_inference_flag,
         63,
     );
 
-    res.frame_cropping_flag = 1 == bs.read_bits(1);
+    res.frame_cropping_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print("SPS: frame_cropping_flag", &res.frame_cropping_flag, 63);
 
     if res.frame_cropping_flag {
-        res.frame_crop_left_offset = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.frame_crop_left_offset = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SPS: frame_crop_left_offset",
             &res.frame_crop_left_offset,
             63,
         );
 
-        res.frame_crop_right_offset = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.frame_crop_right_offset = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SPS: frame_crop_right_offset",
             &res.frame_crop_right_offset,
             63,
         );
 
-        res.frame_crop_top_offset = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.frame_crop_top_offset = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print("SPS: frame_crop_top_offset", &res.frame_crop_top_offset, 63);
 
-        res.frame_crop_bottom_offset = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.frame_crop_bottom_offset = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SPS: frame_crop_bottom_offset",
             &res.frame_crop_bottom_offset,
@@ -572,7 +577,7 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
         );
     }
 
-    res.vui_parameters_present_flag = 1 == bs.read_bits(1);
+    res.vui_parameters_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SPS: vui_parameters_present_flag",
         &res.vui_parameters_present_flag,
@@ -580,10 +585,10 @@ pub fn decode_seq_parameter_set(bs: &mut ByteStream) -> SeqParameterSet {
     );
 
     if res.vui_parameters_present_flag {
-        res.vui_parameters = decode_vui_parameters(bs);
+        res.vui_parameters = decode_vui_parameters(bs)?;
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in 7.3.2.1.1.1 -- Scaling list
@@ -593,7 +598,7 @@ fn decode_scaling_list(
     scaling_list: &mut Vec<i32>,
     size_of_scaling_list: usize,
     use_default_scaling_matrix_flag: &mut bool,
-) {
+) -> Result<(), DecodeError> {
     let mut last_scale = 8;
     let mut next_scale = 8;
 
@@ -601,7 +606,7 @@ fn decode_scaling_list(
 
     for j in 0..size_of_scaling_list {
         if next_scale != 0 {
-            let delta_scale = exp_golomb_decode_one_wrapper(bs, true, 0);
+            let delta_scale = exp_golomb_decode_one_wrapper(bs, true, 0)?;
             delta_scaling_list.push(delta_scale);
             decoder_formatted_print("   : delta_sl", &delta_scale, 63);
 
@@ -618,19 +623,21 @@ fn decode_scaling_list(
     }
 
     *use_default_scaling_matrix_flag = udsm_flag;
+
+    Ok(())
 }
 
 /// Described in G.7.3.2.1.4 -- Sequence Parameter Set SVC extension
-fn decode_sps_svc_extension(chroma_array_type: u8, bs: &mut ByteStream) -> SVCSPSExtension {
+fn decode_sps_svc_extension(chroma_array_type: u8, bs: &mut ByteStream) -> Result<SVCSPSExtension, DecodeError> {
     let mut res = SVCSPSExtension::new();
 
-    res.inter_layer_deblocking_filter_control_present_flag = 1 == bs.read_bits(1);
+    res.inter_layer_deblocking_filter_control_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SVC SPS: num_views_minus1",
         &res.inter_layer_deblocking_filter_control_present_flag,
         63,
     );
-    res.extended_spatial_scalability_idc = bs.read_bits(2) as u8;
+    res.extended_spatial_scalability_idc = bs.read_bits(2)? as u8;
     decoder_formatted_print(
         "SVC SPS: extended_spatial_scalability_idc",
         &res.extended_spatial_scalability_idc,
@@ -638,7 +645,7 @@ fn decode_sps_svc_extension(chroma_array_type: u8, bs: &mut ByteStream) -> SVCSP
     );
 
     if chroma_array_type == 1 || chroma_array_type == 2 {
-        res.chroma_phase_x_plus1_flag = 1 == bs.read_bits(1);
+        res.chroma_phase_x_plus1_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "SVC SPS: chroma_phase_x_plus1_flag",
             &res.chroma_phase_x_plus1_flag,
@@ -646,7 +653,7 @@ fn decode_sps_svc_extension(chroma_array_type: u8, bs: &mut ByteStream) -> SVCSP
         );
     }
     if chroma_array_type == 1 {
-        res.chroma_phase_y_plus1 = bs.read_bits(2) as u8;
+        res.chroma_phase_y_plus1 = bs.read_bits(2)? as u8;
         decoder_formatted_print(
             "SVC SPS: chroma_phase_y_plus1",
             &res.chroma_phase_y_plus1,
@@ -656,49 +663,49 @@ fn decode_sps_svc_extension(chroma_array_type: u8, bs: &mut ByteStream) -> SVCSP
 
     if res.extended_spatial_scalability_idc == 1 {
         if chroma_array_type > 0 {
-            res.seq_ref_layer_chroma_phase_x_plus1_flag = 1 == bs.read_bits(1);
+            res.seq_ref_layer_chroma_phase_x_plus1_flag = 1 == bs.read_bits(1)?;
             decoder_formatted_print(
                 "SVC SPS: seq_ref_layer_chroma_phase_x_plus1_flag",
                 &res.seq_ref_layer_chroma_phase_x_plus1_flag,
                 63,
             );
 
-            res.seq_ref_layer_chroma_phase_y_plus1 = bs.read_bits(2) as u8;
+            res.seq_ref_layer_chroma_phase_y_plus1 = bs.read_bits(2)? as u8;
             decoder_formatted_print(
                 "SVC SPS: seq_ref_layer_chroma_phase_y_plus1",
                 &res.seq_ref_layer_chroma_phase_y_plus1,
                 63,
             );
         }
-        res.seq_scaled_ref_layer_left_offset = exp_golomb_decode_one_wrapper(bs, true, 0);
+        res.seq_scaled_ref_layer_left_offset = exp_golomb_decode_one_wrapper(bs, true, 0)?;
         decoder_formatted_print(
             "SVC SPS: seq_scaled_ref_layer_left_offset",
             &res.seq_scaled_ref_layer_left_offset,
             63,
         );
 
-        res.seq_scaled_ref_layer_top_offset = exp_golomb_decode_one_wrapper(bs, true, 0);
+        res.seq_scaled_ref_layer_top_offset = exp_golomb_decode_one_wrapper(bs, true, 0)?;
         decoder_formatted_print(
             "SVC SPS: seq_scaled_ref_layer_top_offset",
             &res.seq_scaled_ref_layer_top_offset,
             63,
         );
 
-        res.seq_scaled_ref_layer_right_offset = exp_golomb_decode_one_wrapper(bs, true, 0);
+        res.seq_scaled_ref_layer_right_offset = exp_golomb_decode_one_wrapper(bs, true, 0)?;
         decoder_formatted_print(
             "SVC SPS: seq_scaled_ref_layer_right_offset",
             &res.seq_scaled_ref_layer_right_offset,
             63,
         );
 
-        res.seq_scaled_ref_layer_bottom_offset = exp_golomb_decode_one_wrapper(bs, true, 0);
+        res.seq_scaled_ref_layer_bottom_offset = exp_golomb_decode_one_wrapper(bs, true, 0)?;
         decoder_formatted_print(
             "SVC SPS: seq_scaled_ref_layer_bottom_offset",
             &res.seq_scaled_ref_layer_bottom_offset,
             63,
         );
     }
-    res.seq_tcoeff_level_prediction_flag = 1 == bs.read_bits(1);
+    res.seq_tcoeff_level_prediction_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SVC SPS: seq_tcoeff_level_prediction_flag",
         &res.seq_tcoeff_level_prediction_flag,
@@ -706,21 +713,21 @@ fn decode_sps_svc_extension(chroma_array_type: u8, bs: &mut ByteStream) -> SVCSP
     );
 
     if res.seq_tcoeff_level_prediction_flag {
-        res.adaptive_tcoeff_level_prediction_flag = 1 == bs.read_bits(1);
+        res.adaptive_tcoeff_level_prediction_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "SVC SPS: adaptive_tcoeff_level_prediction_flag",
             &res.adaptive_tcoeff_level_prediction_flag,
             63,
         );
     }
-    res.slice_header_restriction_flag = 1 == bs.read_bits(1);
+    res.slice_header_restriction_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SVC SPS: slice_header_restriction_flag",
         &res.slice_header_restriction_flag,
         63,
     );
 
-    res
+    Ok(res)
 }
 
 /// Described in H.7.3.2.1.4 -- Sequence Parameter Set MVC extension
@@ -728,14 +735,14 @@ fn decode_sps_mvc_extension(
     profile_idc: u8,
     frame_mbs_only_flag: bool,
     bs: &mut ByteStream,
-) -> MVCSPSExtension {
+) -> Result<MVCSPSExtension, DecodeError> {
     let mut res = MVCSPSExtension::new();
 
-    res.num_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as usize;
+    res.num_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as usize;
     decoder_formatted_print("MVC SPS: num_views_minus1", &res.num_views_minus1, 63);
     for _ in 0..=res.num_views_minus1 {
         res.view_id
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
     }
     decoder_formatted_print("MVC SPS: res.view_id[]", &res.view_id, 63);
 
@@ -747,17 +754,17 @@ fn decode_sps_mvc_extension(
 
     for i in 1..=res.num_views_minus1 {
         res.num_anchor_refs_l0
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
 
         res.anchor_refs_l0.push(Vec::new());
         for _ in 0..res.num_anchor_refs_l0[i] {
-            res.anchor_refs_l0[i].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.anchor_refs_l0[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         }
         res.num_anchor_refs_l1
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         res.anchor_refs_l1.push(Vec::new());
         for _ in 0..res.num_anchor_refs_l1[i] {
-            res.anchor_refs_l1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.anchor_refs_l1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         }
     }
     decoder_formatted_print("MVC SPS: num_anchor_refs_l0", &res.num_anchor_refs_l0, 63);
@@ -771,16 +778,16 @@ fn decode_sps_mvc_extension(
 
     for i in 1..=res.num_views_minus1 {
         res.num_non_anchor_refs_l0
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         res.non_anchor_refs_l0.push(Vec::new());
         for _ in 0..res.num_non_anchor_refs_l0[i] {
-            res.non_anchor_refs_l0[i].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.non_anchor_refs_l0[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         }
         res.num_non_anchor_refs_l1
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         res.non_anchor_refs_l1.push(Vec::new());
         for _ in 0..res.num_non_anchor_refs_l1[i] {
-            res.non_anchor_refs_l1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.non_anchor_refs_l1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         }
     }
     decoder_formatted_print(
@@ -794,16 +801,16 @@ fn decode_sps_mvc_extension(
         63,
     );
 
-    res.num_level_values_signalled_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as usize;
+    res.num_level_values_signalled_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as usize;
     decoder_formatted_print(
         "MVC SPS: num_level_values_signalled_minus1",
         &res.num_level_values_signalled_minus1,
         63,
     );
     for i in 0..=res.num_level_values_signalled_minus1 {
-        res.level_idc.push(bs.read_bits(8) as u8);
+        res.level_idc.push(bs.read_bits(8)? as u8);
         res.num_applicable_ops_minus1
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as usize);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as usize);
 
         // insert new
         res.applicable_op_temporal_id.push(Vec::new());
@@ -811,18 +818,18 @@ fn decode_sps_mvc_extension(
         res.applicable_op_target_view_id.push(Vec::new());
         res.applicable_op_num_views_minus1.push(Vec::new());
         for j in 0..=res.num_applicable_ops_minus1[i] {
-            res.applicable_op_temporal_id[i].push(bs.read_bits(3) as u8);
+            res.applicable_op_temporal_id[i].push(bs.read_bits(3)? as u8);
             res.applicable_op_num_target_views_minus1[i]
-                .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
 
             //insert new
             res.applicable_op_target_view_id[i].push(Vec::new());
             for _ in 0..=res.applicable_op_num_target_views_minus1[i][j] {
                 res.applicable_op_target_view_id[i][j]
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
             }
             res.applicable_op_num_views_minus1[i]
-                .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         }
     }
     decoder_formatted_print("MVC SPS: level_idc[]", &res.level_idc, 63);
@@ -853,35 +860,35 @@ fn decode_sps_mvc_extension(
     );
 
     if profile_idc == 134 {
-        res.mfc_format_idc = bs.read_bits(6) as u8;
+        res.mfc_format_idc = bs.read_bits(6)? as u8;
         decoder_formatted_print("MVC SPS: mfc_format_idc", &res.mfc_format_idc, 63);
         if res.mfc_format_idc == 0 || res.mfc_format_idc == 1 {
-            res.default_grid_position_flag = 1 == bs.read_bits(1);
+            res.default_grid_position_flag = 1 == bs.read_bits(1)?;
             decoder_formatted_print(
                 "MVC SPS: default_grid_position_flag",
                 &res.default_grid_position_flag,
                 63,
             );
             if !res.default_grid_position_flag {
-                res.view0_grid_position_x = bs.read_bits(4) as u8;
+                res.view0_grid_position_x = bs.read_bits(4)? as u8;
                 decoder_formatted_print(
                     "MVC SPS: view0_grid_position_x",
                     &res.view0_grid_position_x,
                     63,
                 );
-                res.view0_grid_position_y = bs.read_bits(4) as u8;
+                res.view0_grid_position_y = bs.read_bits(4)? as u8;
                 decoder_formatted_print(
                     "MVC SPS: view0_grid_position_y",
                     &res.view0_grid_position_y,
                     63,
                 );
-                res.view1_grid_position_x = bs.read_bits(4) as u8;
+                res.view1_grid_position_x = bs.read_bits(4)? as u8;
                 decoder_formatted_print(
                     "MVC SPS: view1_grid_position_x",
                     &res.view1_grid_position_x,
                     63,
                 );
-                res.view1_grid_position_y = bs.read_bits(4) as u8;
+                res.view1_grid_position_y = bs.read_bits(4)? as u8;
                 decoder_formatted_print(
                     "MVC SPS: view1_grid_position_y",
                     &res.view1_grid_position_y,
@@ -889,14 +896,14 @@ fn decode_sps_mvc_extension(
                 );
             }
         }
-        res.rpu_filter_enabled_flag = 1 == bs.read_bits(1);
+        res.rpu_filter_enabled_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "MVC SPS: rpu_filter_enabled_flag",
             &res.rpu_filter_enabled_flag,
             63,
         );
         if !frame_mbs_only_flag {
-            res.rpu_field_processing_flag = 1 == bs.read_bits(1);
+            res.rpu_field_processing_flag = 1 == bs.read_bits(1)?;
             decoder_formatted_print(
                 "MVC SPS: rpu_field_processing_flag",
                 &res.rpu_field_processing_flag,
@@ -905,58 +912,71 @@ fn decode_sps_mvc_extension(
         }
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in I.7.3.2.1.5 -- Sequence Parameter Set MVCD extension
-fn decode_sps_mvcd_extension(bs: &mut ByteStream) -> MVCDSPSExtension {
+fn decode_sps_mvcd_extension(bs: &mut ByteStream) -> Result<MVCDSPSExtension, DecodeError> {
     let mut res = MVCDSPSExtension::new();
-    res.num_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
-
-    for i in 0..=res.num_views_minus1 {
-        res.view_id.push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
-        res.depth_view_present_flag.push(1 == bs.read_bits(1));
-        res.depth_view_id[res.num_depth_views as usize] = res.view_id[i as usize];
-        res.num_depth_views += match res.depth_view_present_flag[i as usize] {true => 1, false => 0};
-        res.texture_view_present_flag.push(1 == bs.read_bits(1));
+    res.num_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+
+    for i in 0..=res.num_views_minus1 as usize {
+        res.view_id.push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        res.depth_view_present_flag.push(1 == bs.read_bits(1)?);
+        if res.depth_view_present_flag[i] {
+            res.depth_view_id.push(res.view_id[i]);
+            res.num_depth_views += 1;
+        }
+        res.texture_view_present_flag.push(1 == bs.read_bits(1)?);
     }
-    for i in 1..=res.num_views_minus1 {
+
+    // 0th index is skipped
+    res.num_anchor_refs_l0.push(0);
+    res.num_anchor_refs_l1.push(0);
+    res.anchor_ref_l0.push(Vec::new());
+    res.anchor_ref_l1.push(Vec::new());
+    for i in 1..=res.num_views_minus1 as usize {
         res.num_anchor_refs_l0.push(0);
         res.num_anchor_refs_l1.push(0);
         res.anchor_ref_l0.push(Vec::new());
         res.anchor_ref_l1.push(Vec::new());
-        if res.depth_view_present_flag[i as usize] {
-            res.num_anchor_refs_l0[i as usize] = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
-            for _ in 0..res.num_anchor_refs_l0[i as usize] {
-                res.anchor_ref_l0[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+        if res.depth_view_present_flag[i] {
+            res.num_anchor_refs_l0[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            for _ in 0..res.num_anchor_refs_l0[i] {
+                res.anchor_ref_l0[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
             }
-            res.num_anchor_refs_l1[i as usize] = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
-            for _ in 0..res.num_anchor_refs_l1[i as usize] {
-                res.anchor_ref_l1[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.num_anchor_refs_l1[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            for _ in 0..res.num_anchor_refs_l1[i] {
+                res.anchor_ref_l1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
             }
         }
     }
 
-    for i in 1..=res.num_views_minus1 {
+    // 0th index is skipped
+    res.num_non_anchor_refs_l0.push(0);
+    res.num_non_anchor_refs_l1.push(0);
+    res.non_anchor_ref_l0.push(Vec::new());
+    res.non_anchor_ref_l1.push(Vec::new());
+    for i in 1..=res.num_views_minus1 as usize {
         res.num_non_anchor_refs_l0.push(0);
         res.num_non_anchor_refs_l1.push(0);
         res.non_anchor_ref_l0.push(Vec::new());
         res.non_anchor_ref_l1.push(Vec::new());
-        if res.depth_view_present_flag[i as usize] {
-            res.num_non_anchor_refs_l0[i as usize] = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
-            for _ in 0..res.num_non_anchor_refs_l0[i as usize] {
-                res.non_anchor_ref_l0[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+        if res.depth_view_present_flag[i] {
+            res.num_non_anchor_refs_l0[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            for _ in 0..res.num_non_anchor_refs_l0[i] {
+                res.non_anchor_ref_l0[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
             }
-            res.num_non_anchor_refs_l1[i as usize] = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
-            for _ in 0..res.num_non_anchor_refs_l1[i as usize] {
-                res.non_anchor_ref_l1[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.num_non_anchor_refs_l1[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            for _ in 0..res.num_non_anchor_refs_l1[i] {
+                res.non_anchor_ref_l1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
             }
         }
     }
-    res.num_level_values_signalled_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.num_level_values_signalled_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     for i in 0..=res.num_level_values_signalled_minus1 {
-        res.level_idc.push(bs.read_bits(8) as u8);
-        res.num_applicable_ops_minus1.push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+        res.level_idc.push(bs.read_bits(8)? as u8);
+        res.num_applicable_ops_minus1.push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         res.applicable_op_temporal_id.push(Vec::new());
         res.applicable_op_num_target_views_minus1.push(Vec::new());
         res.applicable_op_target_view_id.push(Vec::new());
@@ -965,64 +985,422 @@ fn decode_sps_mvcd_extension(bs: &mut ByteStream) -> MVCDSPSExtension {
         res.applicable_op_num_texture_views_minus1.push(Vec::new());
         res.applicable_op_num_depth_views.push(Vec::new());
         for j in 0..=res.num_applicable_ops_minus1[i as usize] {
-            res.applicable_op_temporal_id[i as usize].push(bs.read_bits(3) as u8);
-            res.applicable_op_num_target_views_minus1[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.applicable_op_temporal_id[i as usize].push(bs.read_bits(3)? as u8);
+            res.applicable_op_num_target_views_minus1[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
             res.applicable_op_target_view_id[i as usize].push(Vec::new());
             res.applicable_op_depth_flag[i as usize].push(Vec::new());
             res.applicable_op_texture_flag[i as usize].push(Vec::new());
             for _ in 0..=res.applicable_op_num_target_views_minus1[i as usize][j as usize]{
-                res.applicable_op_target_view_id[i as usize][j as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
-                res.applicable_op_depth_flag[i as usize][j as usize].push(1 == bs.read_bits(1));
-                res.applicable_op_texture_flag[i as usize][j as usize].push(1 == bs.read_bits(1));
+                res.applicable_op_target_view_id[i as usize][j as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                res.applicable_op_depth_flag[i as usize][j as usize].push(1 == bs.read_bits(1)?);
+                res.applicable_op_texture_flag[i as usize][j as usize].push(1 == bs.read_bits(1)?);
             }
-            res.applicable_op_num_texture_views_minus1[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
-            res.applicable_op_num_depth_views[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.applicable_op_num_texture_views_minus1[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            res.applicable_op_num_depth_views[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         }
     }
-    res.mvcd_vui_parameters_present_flag = 1 == bs.read_bits(1);
+    res.mvcd_vui_parameters_present_flag = 1 == bs.read_bits(1)?;
     if res.mvcd_vui_parameters_present_flag {
-        res.mvcd_vui_parameters = decode_vui_mvcd_parameters(bs);
+        res.mvcd_vui_parameters = decode_vui_mvcd_parameters(bs)?;
     }
-    res.texture_vui_parameters_present_flag = 1 == bs.read_bits(1);
+    res.texture_vui_parameters_present_flag = 1 == bs.read_bits(1)?;
     if res.texture_vui_parameters_present_flag {
-        res.mvc_vui_parameters_extension = decode_vui_mvc_parameters(bs);
+        res.mvc_vui_parameters_extension = decode_vui_mvc_parameters(bs)?;
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in J.7.3.2.1.5 -- Sequence parameter Set 3D-AVC extension
-fn decode_sps_3davc_extension(_bs: &mut ByteStream) -> AVC3DSPSExtension {
-    let res = AVC3DSPSExtension::new();
-    // TODO: 3D AVC SPS Decoding
-    println!("decode_sps_3davc_extension - not yet supported");
-    res
+fn decode_sps_3davc_extension(mvcd: &MVCDSPSExtension, bs: &mut ByteStream) -> Result<AVC3DSPSExtension, DecodeError> {
+    let mut res = AVC3DSPSExtension::new();
+
+    if mvcd.num_depth_views > 0 {
+        res.three_dv_acquisition_idc = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "3D-AVC SPS: 3dv_acquisition_idc",
+            res.three_dv_acquisition_idc,
+            63,
+        );
+        for _ in 0..mvcd.num_depth_views {
+            res.view_id_3dv
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        }
+        decoder_formatted_print("3D-AVC SPS: view_id_3dv", &res.view_id_3dv, 63);
+
+        if res.three_dv_acquisition_idc > 0 {
+            res.depth_ranges = decode_depth_ranges(mvcd.num_depth_views, 2, bs)?;
+            res.vsp_param = decode_vsp_param(mvcd.num_depth_views, bs)?;
+        }
+
+        res.reduced_resolution_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "3D-AVC SPS: reduced_resolution_flag",
+            res.reduced_resolution_flag,
+            63,
+        );
+        if res.reduced_resolution_flag {
+            res.depth_pic_width_in_mbs_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            res.depth_pic_height_in_map_units_minus1 =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            res.depth_hor_mult_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            res.depth_ver_mult_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            res.depth_hor_rsh = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            res.depth_ver_rsh = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "3D-AVC SPS: depth_pic_width_in_mbs_minus1",
+                res.depth_pic_width_in_mbs_minus1,
+                63,
+            );
+            decoder_formatted_print(
+                "3D-AVC SPS: depth_pic_height_in_map_units_minus1",
+                res.depth_pic_height_in_map_units_minus1,
+                63,
+            );
+        }
+
+        res.depth_frame_cropping_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "3D-AVC SPS: depth_frame_cropping_flag",
+            res.depth_frame_cropping_flag,
+            63,
+        );
+        if res.depth_frame_cropping_flag {
+            res.depth_frame_crop_left_offset = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            res.depth_frame_crop_right_offset = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            res.depth_frame_crop_top_offset = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            res.depth_frame_crop_bottom_offset =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        }
+
+        res.grid_pos_num_views = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print("3D-AVC SPS: grid_pos_num_views", res.grid_pos_num_views, 63);
+        for _ in 0..res.grid_pos_num_views {
+            res.grid_pos_view_id
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            res.grid_pos_x.push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
+            res.grid_pos_y.push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
+        }
+
+        res.slice_header_prediction_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "3D-AVC SPS: slice_header_prediction_flag",
+            res.slice_header_prediction_flag,
+            63,
+        );
+        res.seq_view_synthesis_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "3D-AVC SPS: seq_view_synthesis_flag",
+            res.seq_view_synthesis_flag,
+            63,
+        );
+    }
+
+    res.alc_sps_enable_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "3D-AVC SPS: alc_sps_enable_flag",
+        res.alc_sps_enable_flag,
+        63,
+    );
+    res.enable_rle_skip_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "3D-AVC SPS: enable_rle_skip_flag",
+        res.enable_rle_skip_flag,
+        63,
+    );
+
+    if !mvcd.all_views_paired_flag() {
+        // 0th index is skipped
+        res.num_anchor_refs_l0.push(0);
+        res.num_anchor_refs_l1.push(0);
+        res.anchor_ref_l0.push(Vec::new());
+        res.anchor_ref_l1.push(Vec::new());
+        for i in 1..=mvcd.num_views_minus1 as usize {
+            res.num_anchor_refs_l0.push(0);
+            res.num_anchor_refs_l1.push(0);
+            res.anchor_ref_l0.push(Vec::new());
+            res.anchor_ref_l1.push(Vec::new());
+            if mvcd.texture_view_present_flag[i] {
+                res.num_anchor_refs_l0[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                for _ in 0..res.num_anchor_refs_l0[i] {
+                    res.anchor_ref_l0[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                }
+                res.num_anchor_refs_l1[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                for _ in 0..res.num_anchor_refs_l1[i] {
+                    res.anchor_ref_l1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                }
+            }
+        }
+        decoder_formatted_print(
+            "3D-AVC SPS: num_anchor_refs_l0",
+            &res.num_anchor_refs_l0,
+            63,
+        );
+        decoder_formatted_print(
+            "3D-AVC SPS: num_anchor_refs_l1",
+            &res.num_anchor_refs_l1,
+            63,
+        );
+
+        // 0th index is skipped
+        res.num_non_anchor_refs_l0.push(0);
+        res.num_non_anchor_refs_l1.push(0);
+        res.non_anchor_ref_l0.push(Vec::new());
+        res.non_anchor_ref_l1.push(Vec::new());
+        for i in 1..=mvcd.num_views_minus1 as usize {
+            res.num_non_anchor_refs_l0.push(0);
+            res.num_non_anchor_refs_l1.push(0);
+            res.non_anchor_ref_l0.push(Vec::new());
+            res.non_anchor_ref_l1.push(Vec::new());
+            if mvcd.texture_view_present_flag[i] {
+                res.num_non_anchor_refs_l0[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                for _ in 0..res.num_non_anchor_refs_l0[i] {
+                    res.non_anchor_ref_l0[i]
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                }
+                res.num_non_anchor_refs_l1[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                for _ in 0..res.num_non_anchor_refs_l1[i] {
+                    res.non_anchor_ref_l1[i]
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                }
+            }
+        }
+        decoder_formatted_print(
+            "3D-AVC SPS: num_non_anchor_refs_l0",
+            &res.num_non_anchor_refs_l0,
+            63,
+        );
+        decoder_formatted_print(
+            "3D-AVC SPS: num_non_anchor_refs_l1",
+            &res.num_non_anchor_refs_l1,
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// Described in J.7.3.2.5 -- Depth parameter set RBSP
+pub fn decode_depth_parameter_set(bs: &mut ByteStream) -> Result<DepthParameterSet, DecodeError> {
+    let mut res = DepthParameterSet::new();
+
+    res.depth_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "DPS: depth_parameter_set_id",
+        res.depth_parameter_set_id,
+        63,
+    );
+
+    res.pred_direction = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("DPS: pred_direction", res.pred_direction, 63);
+
+    if res.pred_direction == 0 || res.pred_direction == 1 {
+        res.ref_dps_id0 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print("DPS: ref_dps_id0", res.ref_dps_id0, 63);
+    }
+    if res.pred_direction == 0 {
+        res.ref_dps_id1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print("DPS: ref_dps_id1", res.ref_dps_id1, 63);
+        res.pred_weight0 = bs.read_bits(6)? as u8;
+        decoder_formatted_print("DPS: pred_weight0", res.pred_weight0, 63);
+    }
+
+    res.num_depth_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "DPS: num_depth_views_minus1",
+        res.num_depth_views_minus1,
+        63,
+    );
+
+    res.depth_ranges = decode_depth_ranges(res.num_depth_views_minus1 + 1, res.pred_direction, bs)?;
+
+    res.vsp_param_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print("DPS: vsp_param_flag", res.vsp_param_flag, 63);
+    if res.vsp_param_flag {
+        res.vsp_param = decode_vsp_param(res.num_depth_views_minus1 + 1, bs)?;
+    }
+
+    res.depth_param_additional_extension_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "DPS: depth_param_additional_extension_flag",
+        res.depth_param_additional_extension_flag,
+        63,
+    );
+
+    res.nonlinear_depth_representation_num = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "DPS: nonlinear_depth_representation_num",
+        res.nonlinear_depth_representation_num,
+        63,
+    );
+    for _ in 1..=res.nonlinear_depth_representation_num {
+        res.nonlinear_depth_representation_model
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+    }
+    decoder_formatted_print(
+        "DPS: nonlinear_depth_representation_model",
+        &res.nonlinear_depth_representation_model,
+        63,
+    );
+
+    if res.depth_param_additional_extension_flag {
+        while bs.more_data() {
+            res.depth_param_additional_extension_data_flag
+                .push(1 == bs.read_bits(1)?);
+        }
+    }
+
+    Ok(res)
+}
+
+/// Described in J.7.3.2.5.1 -- Depth ranges
+fn decode_depth_ranges(num_views: u32, pred_direction: u32, bs: &mut ByteStream) -> Result<DepthRanges, DecodeError> {
+    let mut res = DepthRanges::new();
+
+    res.z_near_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print("Depth ranges: z_near_flag", res.z_near_flag, 63);
+    res.z_far_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print("Depth ranges: z_far_flag", res.z_far_flag, 63);
+
+    if res.z_near_flag {
+        res.z_near = decode_3dv_acquisition_element(num_views, pred_direction, 7, bs)?;
+    }
+    if res.z_far_flag {
+        res.z_far = decode_3dv_acquisition_element(num_views, pred_direction, 7, bs)?;
+    }
+
+    Ok(res)
+}
+
+/// Described in J.7.3.2.5.2 -- 3DV acquisition element
+fn decode_3dv_acquisition_element(
+    num_views: u32,
+    pred_direction: u32,
+    exp_len: u8,
+    bs: &mut ByteStream,
+) -> Result<AcquisitionElement3DV, DecodeError> {
+    let mut res = AcquisitionElement3DV::new();
+
+    if num_views > 1 {
+        res.element_equal_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "3DV acquisition element: element_equal_flag",
+            res.element_equal_flag,
+            63,
+        );
+    }
+
+    for i in 0..res.num_values(num_views) {
+        if pred_direction == 2 && i == 0 {
+            res.mantissa_len_minus1 = bs.read_bits(5)? as u8;
+            decoder_formatted_print(
+                "3DV acquisition element: mantissa_len_minus1",
+                res.mantissa_len_minus1,
+                63,
+            );
+        }
+
+        if pred_direction == 2 {
+            res.sign0.push(1 == bs.read_bits(1)?);
+            res.exponent0.push(bs.read_bits(exp_len)?);
+            res.mantissa0
+                .push(bs.read_bits(res.mantissa_len_minus1 + 1)?);
+            res.skip_flag.push(false);
+            res.sign1.push(false);
+            res.exponent_skip_flag.push(false);
+            res.exponent1.push(0);
+            res.mantissa_diff.push(0);
+        } else {
+            res.sign0.push(false);
+            res.exponent0.push(0);
+            res.mantissa0.push(0);
+            res.skip_flag.push(1 == bs.read_bits(1)?);
+            if res.skip_flag[i as usize] {
+                res.sign1.push(false);
+                res.exponent_skip_flag.push(false);
+                res.exponent1.push(0);
+                res.mantissa_diff.push(0);
+            } else {
+                res.sign1.push(1 == bs.read_bits(1)?);
+                res.exponent_skip_flag.push(1 == bs.read_bits(1)?);
+                if res.exponent_skip_flag[i as usize] {
+                    res.exponent1.push(0);
+                } else {
+                    res.exponent1.push(bs.read_bits(exp_len)?);
+                }
+                res.mantissa_diff
+                    .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
+            }
+        }
+    }
+    decoder_formatted_print("3DV acquisition element: sign0", &res.sign0, 63);
+    decoder_formatted_print("3DV acquisition element: exponent0", &res.exponent0, 63);
+    decoder_formatted_print("3DV acquisition element: mantissa0", &res.mantissa0, 63);
+    decoder_formatted_print("3DV acquisition element: skip_flag", &res.skip_flag, 63);
+    decoder_formatted_print("3DV acquisition element: sign1", &res.sign1, 63);
+    decoder_formatted_print(
+        "3DV acquisition element: exponent_skip_flag",
+        &res.exponent_skip_flag,
+        63,
+    );
+    decoder_formatted_print("3DV acquisition element: exponent1", &res.exponent1, 63);
+    decoder_formatted_print(
+        "3DV acquisition element: mantissa_diff",
+        &res.mantissa_diff,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// Described in J.7.3.2.5.3 -- View synthesis prediction parameters
+fn decode_vsp_param(num_views: u32, bs: &mut ByteStream) -> Result<VSPParam, DecodeError> {
+    let mut res = VSPParam::new();
+
+    for i in 0..num_views as usize {
+        res.disparity_diff_wji.push(Vec::new());
+        res.disparity_diff_oji.push(Vec::new());
+        res.disparity_diff_wij.push(Vec::new());
+        res.disparity_diff_oij.push(Vec::new());
+        for _ in 0..i {
+            res.disparity_diff_wji[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            res.disparity_diff_oji[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            res.disparity_diff_wij[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            res.disparity_diff_oij[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        }
+    }
+    decoder_formatted_print("VSP param: disparity_diff_wji", &res.disparity_diff_wji, 63);
+    decoder_formatted_print("VSP param: disparity_diff_oji", &res.disparity_diff_oji, 63);
+    decoder_formatted_print("VSP param: disparity_diff_wij", &res.disparity_diff_wij, 63);
+    decoder_formatted_print("VSP param: disparity_diff_oij", &res.disparity_diff_oij, 63);
+
+    Ok(res)
 }
 
 /// Described in 7.3.2.1.2 -- Sequence Parameter Set Extension
-pub fn decode_sps_extension(bs: &mut ByteStream) -> SPSExtension {
+pub fn decode_sps_extension(bs: &mut ByteStream) -> Result<SPSExtension, DecodeError> {
     let mut res = SPSExtension::new();
-    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "SPS Extension: seq_parameter_set_id",
         &res.seq_parameter_set_id,
         63,
     );
-    res.aux_format_idc = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.aux_format_idc = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print("SPS Extension: aux_format_idc", &res.aux_format_idc, 63);
 
     if res.aux_format_idc != 0 {
-        res.bit_depth_aux_minus8 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.bit_depth_aux_minus8 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SPS Extension: bit_depth_aux_minus8",
             &res.bit_depth_aux_minus8,
             63,
         );
-        res.alpha_incr_flag = 1 == bs.read_bits(1);
+        res.alpha_incr_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print("SPS Extension: alpha_incr_flag", &res.alpha_incr_flag, 63);
 
         let bits_to_read = (res.bit_depth_aux_minus8 + 9) as u8;
-        res.alpha_opaque_value = bs.read_bits(bits_to_read) as u32;
+        res.alpha_opaque_value = bs.read_bits(bits_to_read)?;
         decoder_formatted_print(
             "SPS Extension: alpha_opaque_value",
             &res.alpha_opaque_value,
@@ -1030,14 +1408,14 @@ pub fn decode_sps_extension(bs: &mut ByteStream) -> SPSExtension {
         );
 
         let bits_to_read = (res.bit_depth_aux_minus8 + 9) as u8;
-        res.alpha_transparent_value = bs.read_bits(bits_to_read) as u32;
+        res.alpha_transparent_value = bs.read_bits(bits_to_read)?;
         decoder_formatted_print(
             "SPS Extension: alpha_transparent_value",
             &res.alpha_transparent_value,
             63,
         );
     }
-    res.additional_extension_flag = 1 == bs.read_bits(1);
+    res.additional_extension_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SPS Extension: additional_extension_flag",
         &res.additional_extension_flag,
@@ -1046,53 +1424,53 @@ pub fn decode_sps_extension(bs: &mut ByteStream) -> SPSExtension {
 
     // rbsp_trailing_bits()
 
-    res
+    Ok(res)
 }
 
 /// Described in 7.3.2.1.3 -- Subset Sequence Parameter Set
-pub fn decode_subset_sps(bs: &mut ByteStream) -> SubsetSPS {
+pub fn decode_subset_sps(bs: &mut ByteStream) -> Result<SubsetSPS, DecodeError> {
     let mut res = SubsetSPS::new();
-    res.sps = decode_seq_parameter_set(bs);
+    res.sps = decode_seq_parameter_set(bs)?;
 
     if res.sps.profile_idc == 83 || res.sps.profile_idc == 86 {
         let chroma_array_type = match res.sps.separate_colour_plane_flag {
             true => res.sps.chroma_format_idc,
             false => 0,
         };
-        res.sps_svc = decode_sps_svc_extension(chroma_array_type, bs); // specified in Annex G
-        res.svc_vui_parameters_present_flag = 1 == bs.read_bits(1);
+        res.sps_svc = decode_sps_svc_extension(chroma_array_type, bs)?; // specified in Annex G
+        res.svc_vui_parameters_present_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "Subset SPS: svc_vui_parameters_present_flag",
             &res.svc_vui_parameters_present_flag,
             63,
         );
         if res.svc_vui_parameters_present_flag {
-            res.svc_vui = decode_vui_svc_parameters(bs); // specified in Annex G
+            res.svc_vui = decode_vui_svc_parameters(bs)?; // specified in Annex G
         }
     } else if res.sps.profile_idc == 118 || res.sps.profile_idc == 128 || res.sps.profile_idc == 134
     {
-        res.bit_equal_to_one = bs.read_bits(1) as u8;
+        res.bit_equal_to_one = bs.read_bits(1)? as u8;
         res.sps_mvc =
-            decode_sps_mvc_extension(res.sps.profile_idc, res.sps.frame_mbs_only_flag, bs); // specified in Annex H
-        res.mvc_vui_parameters_present_flag = 1 == bs.read_bits(1);
+            decode_sps_mvc_extension(res.sps.profile_idc, res.sps.frame_mbs_only_flag, bs)?; // specified in Annex H
+        res.mvc_vui_parameters_present_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "Subset SPS: mvc_vui_parameters_present_flag",
             &res.mvc_vui_parameters_present_flag,
             63,
         );
         if res.mvc_vui_parameters_present_flag {
-            res.mvc_vui = decode_vui_mvc_parameters(bs); // specified in Annex H
+            res.mvc_vui = decode_vui_mvc_parameters(bs)?; // specified in Annex H
         }
     } else if res.sps.profile_idc == 138 || res.sps.profile_idc == 135 {
-        res.bit_equal_to_one = bs.read_bits(1) as u8;
-        res.sps_mvcd = decode_sps_mvcd_extension(bs); // specified in Annex I
+        res.bit_equal_to_one = bs.read_bits(1)? as u8;
+        res.sps_mvcd = decode_sps_mvcd_extension(bs)?; // specified in Annex I
     } else if res.sps.profile_idc == 139 {
-        res.bit_equal_to_one = bs.read_bits(1) as u8;
-        res.sps_mvcd = decode_sps_mvcd_extension(bs); // specified in Annex I
-        res.sps_3davc = decode_sps_3davc_extension(bs); // specified in Annex J
+        res.bit_equal_to_one = bs.read_bits(1)? as u8;
+        res.sps_mvcd = decode_sps_mvcd_extension(bs)?; // specified in Annex I
+        res.sps_3davc = decode_sps_3davc_extension(&res.sps_mvcd, bs)?; // specified in Annex J
     }
 
-    res.additional_extension2_flag.push(1 == bs.read_bits(1));
+    res.additional_extension2_flag.push(1 == bs.read_bits(1)?);
     decoder_formatted_print(
         "Subset SPS: additional_extension2_flag",
         &res.additional_extension2_flag[0],
@@ -1100,18 +1478,18 @@ pub fn decode_subset_sps(bs: &mut ByteStream) -> SubsetSPS {
     );
     if res.additional_extension2_flag[0] {
         while bs.more_data() {
-            res.additional_extension2_flag.push(1 == bs.read_bits(1));
+            res.additional_extension2_flag.push(1 == bs.read_bits(1)?);
         }
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in E.1.1 -- VUI parameters
-fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
+fn decode_vui_parameters(bs: &mut ByteStream) -> Result<VUIParameters, DecodeError> {
     let mut res = VUIParameters::new();
 
-    res.aspect_ratio_info_present_flag = 1 == bs.read_bits(1);
+    res.aspect_ratio_info_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: aspect_ratio_info_present_flag",
         &res.aspect_ratio_info_present_flag,
@@ -1119,21 +1497,21 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
     );
 
     if res.aspect_ratio_info_present_flag {
-        res.aspect_ratio_idc = bs.read_bits(8) as u8;
+        res.aspect_ratio_idc = bs.read_bits(8)? as u8;
         decoder_formatted_print("VUI: aspect_ratio_idc", &res.aspect_ratio_idc, 63);
 
         // see table E-1 for parsing aspect_ratio_idc
         if res.aspect_ratio_idc == 255 {
             // Extended_SAR
-            res.sar_width = bs.read_bits(16) as u16;
+            res.sar_width = bs.read_bits(16)? as u16;
             decoder_formatted_print("VUI: sar_width", &res.sar_width, 63);
 
-            res.sar_height = bs.read_bits(16) as u16;
+            res.sar_height = bs.read_bits(16)? as u16;
             decoder_formatted_print("VUI: sar_height", &res.sar_height, 63);
         }
     }
 
-    res.overscan_info_present_flag = 1 == bs.read_bits(1);
+    res.overscan_info_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: overscan_info_present_flag",
         &res.overscan_info_present_flag,
@@ -1141,7 +1519,7 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
     );
 
     if res.overscan_info_present_flag {
-        res.overscan_appropriate_flag = 1 == bs.read_bits(1);
+        res.overscan_appropriate_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "VUI: overscan_appropriate_flag",
             &res.overscan_appropriate_flag,
@@ -1149,7 +1527,7 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
         );
     }
 
-    res.video_signal_type_present_flag = 1 == bs.read_bits(1);
+    res.video_signal_type_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: video_signal_type_present_flag",
         &res.video_signal_type_present_flag,
@@ -1157,13 +1535,13 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
     );
 
     if res.video_signal_type_present_flag {
-        res.video_format = bs.read_bits(3) as u8;
+        res.video_format = bs.read_bits(3)? as u8;
         decoder_formatted_print("VUI: video_format", &res.video_format, 63);
 
-        res.video_full_range_flag = 1 == bs.read_bits(1);
+        res.video_full_range_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print("VUI: video_full_range_flag", &res.video_full_range_flag, 63);
 
-        res.colour_description_present_flag = 1 == bs.read_bits(1);
+        res.colour_description_present_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "VUI: colour_description_present_flag",
             &res.colour_description_present_flag,
@@ -1171,22 +1549,22 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
         );
 
         if res.colour_description_present_flag {
-            res.colour_primaries = bs.read_bits(8) as u8;
+            res.colour_primaries = bs.read_bits(8)? as u8;
             decoder_formatted_print("VUI: colour_primaries", &res.colour_primaries, 63);
 
-            res.transfer_characteristics = bs.read_bits(8) as u8;
+            res.transfer_characteristics = bs.read_bits(8)? as u8;
             decoder_formatted_print(
                 "VUI: transfer_characteristics",
                 &res.transfer_characteristics,
                 63,
             );
 
-            res.matrix_coefficients = bs.read_bits(8) as u8;
+            res.matrix_coefficients = bs.read_bits(8)? as u8;
             decoder_formatted_print("VUI: matrix_coefficients", &res.matrix_coefficients, 63);
         }
     }
 
-    res.chroma_loc_info_present_flag = 1 == bs.read_bits(1);
+    res.chroma_loc_info_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: chroma_loc_info_present_flag",
         &res.chroma_loc_info_present_flag,
@@ -1194,7 +1572,7 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
     );
 
     if res.chroma_loc_info_present_flag {
-        res.chroma_sample_loc_type_top_field = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.chroma_sample_loc_type_top_field = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "VUI: chroma_sample_loc_type_top_field",
             &res.chroma_sample_loc_type_top_field,
@@ -1202,7 +1580,7 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
         );
 
         res.chroma_sample_loc_type_bottom_field =
-            exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+            exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "VUI: chroma_sample_loc_type_bottom_field",
             &res.chroma_sample_loc_type_bottom_field,
@@ -1210,7 +1588,7 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
         );
     }
 
-    res.timing_info_present_flag = 1 == bs.read_bits(1);
+    res.timing_info_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: timing_info_present_flag",
         &res.timing_info_present_flag,
@@ -1218,17 +1596,17 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
     );
 
     if res.timing_info_present_flag {
-        res.num_units_in_tick = bs.read_bits(32);
+        res.num_units_in_tick = bs.read_bits(32)?;
         decoder_formatted_print("VUI: num_units_in_tick", &res.num_units_in_tick, 63);
 
-        res.time_scale = bs.read_bits(32);
+        res.time_scale = bs.read_bits(32)?;
         decoder_formatted_print("VUI: time_scale", &res.time_scale, 63);
 
-        res.fixed_frame_rate_flag = 1 == bs.read_bits(1);
+        res.fixed_frame_rate_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print("VUI: fixed_frame_rate_flag", &res.fixed_frame_rate_flag, 63);
     }
 
-    res.nal_hrd_parameters_present_flag = 1 == bs.read_bits(1);
+    res.nal_hrd_parameters_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: nal_hrd_parameters_present_flag",
         &res.nal_hrd_parameters_present_flag,
@@ -1236,10 +1614,10 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
     );
 
     if res.nal_hrd_parameters_present_flag {
-        res.nal_hrd_parameters = decode_hrd_parameters(bs);
+        res.nal_hrd_parameters = decode_hrd_parameters(bs)?;
     }
 
-    res.vcl_hrd_parameters_present_flag = 1 == bs.read_bits(1);
+    res.vcl_hrd_parameters_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: vcl_hrd_parameters_present_flag",
         &res.vcl_hrd_parameters_present_flag,
@@ -1247,22 +1625,22 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
     );
 
     if res.vcl_hrd_parameters_present_flag {
-        res.vcl_hrd_parameters = decode_hrd_parameters(bs);
+        res.vcl_hrd_parameters = decode_hrd_parameters(bs)?;
     }
 
     if res.nal_hrd_parameters_present_flag || res.vcl_hrd_parameters_present_flag {
-        res.low_delay_hrd_flag = 1 == bs.read_bits(1);
+        res.low_delay_hrd_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print("VUI: low_delay_hrd_flag", &res.low_delay_hrd_flag, 63);
     }
 
-    res.pic_struct_present_flag = 1 == bs.read_bits(1);
+    res.pic_struct_present_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: pic_struct_present_flag",
         &res.pic_struct_present_flag,
         63,
     );
 
-    res.bitstream_restriction_flag = 1 == bs.read_bits(1);
+    res.bitstream_restriction_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "VUI: bitstream_restriction_flag",
         &res.bitstream_restriction_flag,
@@ -1270,45 +1648,45 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
     );
 
     if res.bitstream_restriction_flag {
-        res.motion_vectors_over_pic_boundaries_flag = 1 == bs.read_bits(1);
+        res.motion_vectors_over_pic_boundaries_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
             "VUI: motion_vectors_over_pic_boundaries_flag",
             &res.motion_vectors_over_pic_boundaries_flag,
             63,
         );
 
-        res.max_bytes_per_pic_denom = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.max_bytes_per_pic_denom = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "VUI: max_bytes_per_pic_denom",
             &res.max_bytes_per_pic_denom,
             63,
         );
 
-        res.max_bits_per_mb_denom = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.max_bits_per_mb_denom = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print("VUI: max_bits_per_mb_denom", &res.max_bits_per_mb_denom, 63);
 
-        res.log2_max_mv_length_horizontal = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.log2_max_mv_length_horizontal = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "VUI: log2_max_mv_length_horizontal",
             &res.log2_max_mv_length_horizontal,
             63,
         );
 
-        res.log2_max_mv_length_vertical = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.log2_max_mv_length_vertical = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "VUI: log2_max_mv_length_vertical",
             &res.log2_max_mv_length_vertical,
             63,
         );
 
-        res.max_num_reorder_frames = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.max_num_reorder_frames = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "VUI: max_num_reorder_frames",
             &res.max_num_reorder_frames,
             63,
         );
 
-        res.max_dec_frame_buffering = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        res.max_dec_frame_buffering = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "VUI: max_dec_frame_buffering",
             &res.max_dec_frame_buffering,
@@ -1316,68 +1694,68 @@ fn decode_vui_parameters(bs: &mut ByteStream) -> VUIParameters {
         );
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in E.1.2 -- HRD parameters
-fn decode_hrd_parameters(bs: &mut ByteStream) -> HRDParameters {
+pub fn decode_hrd_parameters(bs: &mut ByteStream) -> Result<HRDParameters, DecodeError> {
     let mut res = HRDParameters::new();
 
-    res.cpb_cnt_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.cpb_cnt_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print("HRD: cpb_cnt_minus1", &res.cpb_cnt_minus1, 63);
-    res.bit_rate_scale = bs.read_bits(4) as u8;
+    res.bit_rate_scale = bs.read_bits(4)? as u8;
     decoder_formatted_print("HRD: bit_rate_scale", &res.bit_rate_scale, 63);
-    res.cpb_size_scale = bs.read_bits(4) as u8;
+    res.cpb_size_scale = bs.read_bits(4)? as u8;
     decoder_formatted_print("HRD: cpb_size_scale", &res.cpb_size_scale, 63);
 
     // iterator is sched_sel_idx
     for sched_sel_idx in 0..=res.cpb_cnt_minus1 {
         res.bit_rate_value_minus1
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         decoder_formatted_print(
             "HRD: bit_rate_value_minus1[]",
             &res.bit_rate_value_minus1[sched_sel_idx as usize],
             63,
         );
         res.cpb_size_values_minus1
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         decoder_formatted_print(
             "HRD: cpb_size_values_minus1[]",
             &res.cpb_size_values_minus1[sched_sel_idx as usize],
             63,
         );
-        res.cbr_flag.push(1 == bs.read_bits(1));
+        res.cbr_flag.push(1 == bs.read_bits(1)?);
         decoder_formatted_print("HRD: cbr_flag[]", &res.cbr_flag[sched_sel_idx as usize], 63);
     }
 
-    res.initial_cpb_removal_delay_length_minus1 = bs.read_bits(5) as u8;
+    res.initial_cpb_removal_delay_length_minus1 = bs.read_bits(5)? as u8;
     decoder_formatted_print(
         "HRD: initial_cpb_removal_delay_length_minus1",
         &res.initial_cpb_removal_delay_length_minus1,
         63,
     );
-    res.cpb_removal_delay_length_minus1 = bs.read_bits(5) as u8;
+    res.cpb_removal_delay_length_minus1 = bs.read_bits(5)? as u8;
     decoder_formatted_print(
         "HRD: cpb_removal_delay_length_minus1",
         &res.cpb_removal_delay_length_minus1,
         63,
     );
-    res.dpb_output_delay_length_minus1 = bs.read_bits(5) as u8;
+    res.dpb_output_delay_length_minus1 = bs.read_bits(5)? as u8;
     decoder_formatted_print(
         "HRD: dpb_output_delay_length_minus1",
         &res.dpb_output_delay_length_minus1,
         63,
     );
-    res.time_offset_length = bs.read_bits(5) as u8;
+    res.time_offset_length = bs.read_bits(5)? as u8;
     decoder_formatted_print("HRD: time_offset_length", &res.time_offset_length, 63);
-    res
+    Ok(res)
 }
 
 /// Described in G.14.1 -- SVC VUI parameters extension
-fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
+fn decode_vui_svc_parameters(bs: &mut ByteStream) -> Result<SVCVUIParameters, DecodeError> {
     let mut res = SVCVUIParameters::new();
 
-    res.vui_ext_num_entries_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.vui_ext_num_entries_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "SVC VUI: vui_ext_num_entries_minus1",
         &res.vui_ext_num_entries_minus1,
@@ -1385,21 +1763,21 @@ fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
     );
 
     for i in 0..=(res.vui_ext_num_entries_minus1 as usize) {
-        res.vui_ext_dependency_id.push(bs.read_bits(3) as u8);
+        res.vui_ext_dependency_id.push(bs.read_bits(3)? as u8);
         decoder_formatted_print(
             "SVC VUI: vui_ext_dependency_id[]",
             &res.vui_ext_dependency_id[i],
             63,
         );
 
-        res.vui_ext_quality_id.push(bs.read_bits(4) as u8);
+        res.vui_ext_quality_id.push(bs.read_bits(4)? as u8);
         decoder_formatted_print(
             "SVC VUI: vui_ext_quality_id[]",
             &res.vui_ext_quality_id[i],
             63,
         );
 
-        res.vui_ext_temporal_id.push(bs.read_bits(3) as u8);
+        res.vui_ext_temporal_id.push(bs.read_bits(3)? as u8);
         decoder_formatted_print(
             "SVC VUI: vui_ext_temporal_id[]",
             &res.vui_ext_temporal_id[i],
@@ -1407,7 +1785,7 @@ fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
         );
 
         res.vui_ext_timing_info_present_flag
-            .push(1 == bs.read_bits(1));
+            .push(1 == bs.read_bits(1)?);
         decoder_formatted_print(
             "SVC VUI: vui_ext_timing_info_present_flag[]",
             &res.vui_ext_timing_info_present_flag[i],
@@ -1415,19 +1793,19 @@ fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
         );
 
         if res.vui_ext_timing_info_present_flag[i] {
-            res.vui_ext_num_units_in_tick.push(bs.read_bits(32));
+            res.vui_ext_num_units_in_tick.push(bs.read_bits(32)?);
             decoder_formatted_print(
                 "SVC VUI: vui_ext_num_units_in_tick[]",
                 &res.vui_ext_num_units_in_tick[i],
                 63,
             );
-            res.vui_ext_time_scale.push(bs.read_bits(32));
+            res.vui_ext_time_scale.push(bs.read_bits(32)?);
             decoder_formatted_print(
                 "SVC VUI: vui_ext_time_scale[]",
                 &res.vui_ext_time_scale[i],
                 63,
             );
-            res.vui_ext_fixed_frame_rate_flag.push(1 == bs.read_bits(1));
+            res.vui_ext_fixed_frame_rate_flag.push(1 == bs.read_bits(1)?);
             decoder_formatted_print(
                 "SVC VUI: vui_ext_fixed_frame_rate_flag[]",
                 &res.vui_ext_fixed_frame_rate_flag[i],
@@ -1440,7 +1818,7 @@ fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
         }
 
         res.vui_ext_nal_hrd_parameters_present_flag
-            .push(1 == bs.read_bits(1));
+            .push(1 == bs.read_bits(1)?);
         decoder_formatted_print(
             "SVC VUI: vui_ext_nal_hrd_parameters_present_flag[]",
             &res.vui_ext_nal_hrd_parameters_present_flag[i],
@@ -1448,11 +1826,11 @@ fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
         );
         if res.vui_ext_nal_hrd_parameters_present_flag[i] {
             res.vui_ext_nal_hrd_parameters
-                .push(decode_hrd_parameters(&mut bs));
+                .push(decode_hrd_parameters(bs)?);
         }
 
         res.vui_ext_vcl_hrd_parameters_present_flag
-            .push(1 == bs.read_bits(1));
+            .push(1 == bs.read_bits(1)?);
         decoder_formatted_print(
             "SVC VUI: vui_ext_vcl_hrd_parameters_present_flag[]",
             &res.vui_ext_vcl_hrd_parameters_present_flag[i],
@@ -1460,13 +1838,13 @@ fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
         );
         if res.vui_ext_vcl_hrd_parameters_present_flag[i] {
             res.vui_ext_vcl_hrd_parameters
-                .push(decode_hrd_parameters(&mut bs));
+                .push(decode_hrd_parameters(bs)?);
         }
 
         if res.vui_ext_nal_hrd_parameters_present_flag[i]
             || res.vui_ext_vcl_hrd_parameters_present_flag[i]
         {
-            res.vui_ext_low_delay_hrd_flag.push(1 == bs.read_bits(1));
+            res.vui_ext_low_delay_hrd_flag.push(1 == bs.read_bits(1)?);
             decoder_formatted_print(
                 "SVC VUI: vui_ext_low_delay_hrd_flag[]",
                 &res.vui_ext_low_delay_hrd_flag[i],
@@ -1474,7 +1852,7 @@ fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
             );
         }
         res.vui_ext_pic_struct_present_flag
-            .push(1 == bs.read_bits(1));
+            .push(1 == bs.read_bits(1)?);
         decoder_formatted_print(
             "SVC VUI: vui_ext_pic_struct_present_flag[]",
             &res.vui_ext_pic_struct_present_flag[i],
@@ -1482,14 +1860,14 @@ fn decode_vui_svc_parameters(mut bs: &mut ByteStream) -> SVCVUIParameters {
         );
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in H.14.1 -- MVC VUI parameters extension syntax
-fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
+fn decode_vui_mvc_parameters(bs: &mut ByteStream) -> Result<MVCVUIParameters, DecodeError> {
     let mut res = MVCVUIParameters::new();
 
-    res.vui_mvc_num_ops_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.vui_mvc_num_ops_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "MVC VUI: vui_mvc_num_ops_minus1",
         &res.vui_mvc_num_ops_minus1,
@@ -1497,7 +1875,7 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
     );
 
     for i in 0..=(res.vui_mvc_num_ops_minus1 as usize) {
-        res.vui_mvc_temporal_id.push(bs.read_bits(3) as u8);
+        res.vui_mvc_temporal_id.push(bs.read_bits(3)? as u8);
         decoder_formatted_print(
             "MVC VUI: vui_mvc_temporal_id[]",
             &res.vui_mvc_temporal_id[i],
@@ -1505,7 +1883,7 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
         );
 
         res.vui_mvc_num_target_output_views_minus1
-            .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         decoder_formatted_print(
             "MVC VUI: vui_mvc_num_target_output_views_minus1[]",
             &res.vui_mvc_num_target_output_views_minus1[i],
@@ -1514,12 +1892,12 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
 
         res.vui_mvc_view_id.push(Vec::new());
         for _ in 0..=(res.vui_mvc_num_target_output_views_minus1[i] as usize) {
-            res.vui_mvc_view_id[i].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+            res.vui_mvc_view_id[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         }
         decoder_formatted_print("MVC VUI: vui_mvc_view_id[][]", &res.vui_mvc_view_id[i], 63);
 
         res.vui_mvc_timing_info_present_flag
-            .push(1 == bs.read_bits(1));
+            .push(1 == bs.read_bits(1)?);
         decoder_formatted_print(
             "MVC VUI: vui_mvc_timing_info_present_flag[]",
             &res.vui_mvc_timing_info_present_flag[i],
@@ -1527,19 +1905,19 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
         );
 
         if res.vui_mvc_timing_info_present_flag[i] {
-            res.vui_mvc_num_units_in_tick.push(bs.read_bits(32));
+            res.vui_mvc_num_units_in_tick.push(bs.read_bits(32)?);
             decoder_formatted_print(
                 "MVC VUI: vui_mvc_num_units_in_tick[]",
                 &res.vui_mvc_num_units_in_tick[i],
                 63,
             );
-            res.vui_mvc_time_scale.push(bs.read_bits(32));
+            res.vui_mvc_time_scale.push(bs.read_bits(32)?);
             decoder_formatted_print(
                 "MVC VUI: vui_mvc_time_scale[]",
                 &res.vui_mvc_time_scale[i],
                 63,
             );
-            res.vui_mvc_fixed_frame_rate_flag.push(1 == bs.read_bits(1));
+            res.vui_mvc_fixed_frame_rate_flag.push(1 == bs.read_bits(1)?);
             decoder_formatted_print(
                 "MVC VUI: vui_mvc_fixed_frame_rate_flag[]",
                 &res.vui_mvc_fixed_frame_rate_flag[i],
@@ -1552,7 +1930,7 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
         }
 
         res.vui_mvc_nal_hrd_parameters_present_flag
-            .push(1 == bs.read_bits(1));
+            .push(1 == bs.read_bits(1)?);
         decoder_formatted_print(
             "MVC VUI: vui_mvc_nal_hrd_parameters_present_flag[]",
             &res.vui_mvc_nal_hrd_parameters_present_flag[i],
@@ -1560,11 +1938,11 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
         );
         if res.vui_mvc_nal_hrd_parameters_present_flag[i] {
             res.vui_mvc_nal_hrd_parameters
-                .push(decode_hrd_parameters(&mut bs));
+                .push(decode_hrd_parameters(bs)?);
         }
 
         res.vui_mvc_vcl_hrd_parameters_present_flag
-            .push(1 == bs.read_bits(1));
+            .push(1 == bs.read_bits(1)?);
         decoder_formatted_print(
             "MVC VUI: vui_mvc_vcl_hrd_parameters_present_flag[]",
             &res.vui_mvc_vcl_hrd_parameters_present_flag[i],
@@ -1572,13 +1950,13 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
         );
         if res.vui_mvc_vcl_hrd_parameters_present_flag[i] {
             res.vui_mvc_vcl_hrd_parameters
-                .push(decode_hrd_parameters(&mut bs));
+                .push(decode_hrd_parameters(bs)?);
         }
 
         if res.vui_mvc_nal_hrd_parameters_present_flag[i]
             || res.vui_mvc_vcl_hrd_parameters_present_flag[i]
         {
-            res.vui_mvc_low_delay_hrd_flag.push(1 == bs.read_bits(1));
+            res.vui_mvc_low_delay_hrd_flag.push(1 == bs.read_bits(1)?);
             decoder_formatted_print(
                 "MVC VUI: vui_ext_low_delay_hrd_flag[]",
                 &res.vui_mvc_low_delay_hrd_flag[i],
@@ -1586,7 +1964,7 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
             );
         }
         res.vui_mvc_pic_struct_present_flag
-            .push(1 == bs.read_bits(1));
+            .push(1 == bs.read_bits(1)?);
         decoder_formatted_print(
             "MVC VUI: vui_mvc_pic_struct_present_flag[]",
             &res.vui_mvc_pic_struct_present_flag[i],
@@ -1594,46 +1972,56 @@ fn decode_vui_mvc_parameters(mut bs: &mut ByteStream) -> MVCVUIParameters {
         );
     }
 
-    res
+    Ok(res)
 }
 
 /// Described in I.14.1 -- MVCD VUI parameters extension syntax
-fn decode_vui_mvcd_parameters(bs: &mut ByteStream) -> MVCDVUIParameters {
+fn decode_vui_mvcd_parameters(bs: &mut ByteStream) -> Result<MVCDVUIParameters, DecodeError> {
     let mut res = MVCDVUIParameters::new();
 
-    res.vui_mvcd_num_ops_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.vui_mvcd_num_ops_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
 
     for i in 0..=res.vui_mvcd_num_ops_minus1 {
-        res.vui_mvcd_temporal_id.push(bs.read_bits(3) as u8);
-        res.vui_mvcd_num_target_output_views_minus1.push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+        res.vui_mvcd_temporal_id.push(bs.read_bits(3)? as u8);
+        res.vui_mvcd_num_target_output_views_minus1.push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
 
         res.vui_mvcd_view_id.push(Vec::new());
         res.vui_mvcd_depth_flag.push(Vec::new());
         res.vui_mvcd_texture_flag.push(Vec::new());
         for _ in 0..=res.vui_mvcd_num_target_output_views_minus1[i as usize] {
-            res.vui_mvcd_view_id[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
-            res.vui_mvcd_depth_flag[i as usize].push(1 == bs.read_bits(1));
-            res.vui_mvcd_texture_flag[i as usize].push(1 == bs.read_bits(1));
+            res.vui_mvcd_view_id[i as usize].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            res.vui_mvcd_depth_flag[i as usize].push(1 == bs.read_bits(1)?);
+            res.vui_mvcd_texture_flag[i as usize].push(1 == bs.read_bits(1)?);
         }
-        res.vui_mvcd_timing_info_present_flag.push(1 == bs.read_bits(1));
+        res.vui_mvcd_timing_info_present_flag.push(1 == bs.read_bits(1)?);
         if res.vui_mvcd_timing_info_present_flag[i as usize]{
-            res.vui_mvcd_num_units_in_tick.push(bs.read_bits(32) as u32);
-            res.vui_mvcd_time_scale.push(bs.read_bits(32) as u32);
-            res.vui_mvcd_fixed_frame_rate_flag.push(1 == bs.read_bits(1));
+            res.vui_mvcd_num_units_in_tick.push(bs.read_bits(32)?);
+            res.vui_mvcd_time_scale.push(bs.read_bits(32)?);
+            res.vui_mvcd_fixed_frame_rate_flag.push(1 == bs.read_bits(1)?);
+        } else {
+            res.vui_mvcd_num_units_in_tick.push(0);
+            res.vui_mvcd_time_scale.push(0);
+            res.vui_mvcd_fixed_frame_rate_flag.push(false);
         }
-        res.vui_mvcd_nal_hrd_parameters_present_flag.push(1 == bs.read_bits(1));
+        res.vui_mvcd_nal_hrd_parameters_present_flag.push(1 == bs.read_bits(1)?);
         if res.vui_mvcd_nal_hrd_parameters_present_flag[i as usize] {
-            res.vui_mvcd_nal_hrd_parameters.push(decode_hrd_parameters(bs));
+            res.vui_mvcd_nal_hrd_parameters.push(decode_hrd_parameters(bs)?);
+        } else {
+            res.vui_mvcd_nal_hrd_parameters.push(HRDParameters::new());
         }
-        res.vui_mvcd_vcl_hrd_parameters_present_flag.push(1 == bs.read_bits(1));
+        res.vui_mvcd_vcl_hrd_parameters_present_flag.push(1 == bs.read_bits(1)?);
         if res.vui_mvcd_vcl_hrd_parameters_present_flag[i as usize] {
-            res.vui_mvcd_vcl_hrd_parameters.push(decode_hrd_parameters(bs));
+            res.vui_mvcd_vcl_hrd_parameters.push(decode_hrd_parameters(bs)?);
+        } else {
+            res.vui_mvcd_vcl_hrd_parameters.push(HRDParameters::new());
         }
         if res.vui_mvcd_nal_hrd_parameters_present_flag[i as usize] || res.vui_mvcd_vcl_hrd_parameters_present_flag[i as usize] {
-            res.vui_mvcd_low_delay_hrd_flag.push(1 == bs.read_bits(1))
+            res.vui_mvcd_low_delay_hrd_flag.push(1 == bs.read_bits(1)?)
+        } else {
+            res.vui_mvcd_low_delay_hrd_flag.push(false);
         }
-        res.vui_mvcd_pic_struct_present_flag.push(1 == bs.read_bits(1));
+        res.vui_mvcd_pic_struct_present_flag.push(1 == bs.read_bits(1)?);
     }
 
-    res
+    Ok(res)
 }
\ No newline at end of file
@@ -1,60 +1,131 @@
 //! SEI syntax element decoding.
 
+use crate::common::data_structures::HRDParameters;
+use crate::common::data_structures::SEIAlternativeDepthInfo;
+use crate::common::data_structures::SEIAlternativeTransferCharacteristics;
 use crate::common::data_structures::SEIBufferingPeriod;
+use crate::common::data_structures::SEIColourRemappingInfo;
+use crate::common::data_structures::SEIConstrainedDepthParameterSetIdentifier;
+use crate::common::data_structures::SEIContentColourVolume;
+use crate::common::data_structures::SEIContentLightLevelInfo;
+use crate::common::data_structures::SEICubemapProjection;
+use crate::common::data_structures::SEIDecRefPicMarkingRepetition;
+use crate::common::data_structures::SEIDepthGridPosition;
+use crate::common::data_structures::SEIDepthRepresentationElement;
+use crate::common::data_structures::SEIDepthRepresentationInfo;
+use crate::common::data_structures::SEIDepthSamplingInfo;
+use crate::common::data_structures::SEIDepthTiming;
+use crate::common::data_structures::SEIDepthTimingOffset;
+use crate::common::data_structures::SEIDisplayOrientation;
+use crate::common::data_structures::SEIEquirectangularProjection;
+use crate::common::data_structures::SEIFillerPayload;
 use crate::common::data_structures::SEIFilmGrainCharacteristics;
+use crate::common::data_structures::SEIFloatValue;
+use crate::common::data_structures::SEIFramePacking;
+use crate::common::data_structures::SEIFullFrameFreeze;
+use crate::common::data_structures::SEIFullFrameSnapshot;
+use crate::common::data_structures::SEILayerDependencyChange;
+use crate::common::data_structures::SEILayersNotPresent;
+use crate::common::data_structures::SEIMVCScalableNesting;
+use crate::common::data_structures::SEIMasteringDisplayColourVolume;
+use crate::common::data_structures::SEIMultiviewAcquisitionInfo;
+use crate::common::data_structures::SEIMultiviewSceneInfo;
+use crate::common::data_structures::SEIMultiviewViewPosition;
 use crate::common::data_structures::SEINalu;
+use crate::common::data_structures::SEINonRequiredLayerRep;
+use crate::common::data_structures::SEINonRequiredViewComponent;
+use crate::common::data_structures::SEIOmniViewport;
+use crate::common::data_structures::SEIOperationPointsNotPresent;
+use crate::common::data_structures::SEIPanScanRect;
+use crate::common::data_structures::SEIParallelDecodingInfo;
 use crate::common::data_structures::SEIPayload;
 use crate::common::data_structures::SEIPicTiming;
+use crate::common::data_structures::SEIPriorityLayerInfo;
+use crate::common::data_structures::SEIProgressiveRefinementSegmentEnd;
+use crate::common::data_structures::SEIProgressiveRefinementSegmentStart;
+use crate::common::data_structures::SEIQualityLayerIntegrityCheck;
 use crate::common::data_structures::SEIRecoveryPoint;
+use crate::common::data_structures::SEIRedundantPicProperty;
+use crate::common::data_structures::SEIRegionwisePacking;
+use crate::common::data_structures::SEIScalabilityInfo;
+use crate::common::data_structures::SEIScalableNesting;
+use crate::common::data_structures::SEISceneInfo;
+use crate::common::data_structures::SEISparePic;
+use crate::common::data_structures::SEISphereRotation;
+use crate::common::data_structures::SEISubPicScalableLayer;
+use crate::common::data_structures::SEISubSeqCharacteristics;
+use crate::common::data_structures::SEISubSeqInfo;
+use crate::common::data_structures::SEISubSeqLayerCharacteristics;
+use crate::common::data_structures::SEITemporalHRD;
+use crate::common::data_structures::SEIThreeDimensionalReferenceDisplaysInfo;
+use crate::common::data_structures::SEITl0DepRepIndex;
+use crate::common::data_structures::SEITlSwitchingPoint;
+use crate::common::data_structures::SEIToneMappingInfo;
+use crate::common::data_structures::SEIUserDataRegisteredITUTT35;
 use crate::common::data_structures::SEIUserDataUnregistered;
+use crate::common::data_structures::SEIViewDependencyChange;
+use crate::common::data_structures::SEIViewScalabilityInfo;
 use crate::common::data_structures::SeqParameterSet;
+use crate::common::data_structures::SubsetSPS;
 use crate::common::data_structures::UUID_APPLE1;
 use crate::common::data_structures::UUID_APPLE2;
 use crate::common::helper::decoder_formatted_print;
+use crate::common::helper::sei_mantissa_len;
 use crate::common::helper::ByteStream;
+use crate::decoder::error::DecodeError;
 use crate::decoder::expgolomb::exp_golomb_decode_one_wrapper;
+use crate::decoder::parameter_sets::decode_hrd_parameters;
 use log::debug;
 use std::collections::VecDeque;
 
 /// Follows section 7.3.2.3
-pub fn decode_sei_message(spses: &Vec<SeqParameterSet>, bs: &mut ByteStream) -> SEINalu {
+pub fn decode_sei_message(
+    spses: &Vec<SeqParameterSet>,
+    subset_spses: &Vec<SubsetSPS>,
+    bs: &mut ByteStream,
+) -> Result<SEINalu, DecodeError> {
     let mut res = SEINalu::new();
 
     if bs.bytestream.len() < 2 {
         println!("[WARNING] SEI NALU too short: {}", bs.bytestream.len());
-        return res;
+        return Ok(res);
     }
 
     loop {
         let mut payload_type = 0;
-        let mut next_byte = bs.read_bits(8);
+        let mut next_byte = bs.read_bits(8)?;
 
         while next_byte == 0xff {
-            next_byte = bs.read_bits(8);
+            next_byte = bs.read_bits(8)?;
             payload_type += 255;
         }
         payload_type += next_byte;
 
         let mut payload_size = 0;
-        let mut next_byte = bs.read_bits(8);
+        let mut next_byte = bs.read_bits(8)?;
 
         while next_byte == 0xff {
-            next_byte = bs.read_bits(8);
+            next_byte = bs.read_bits(8)?;
             payload_size += 255;
         }
         // in number of bytes
         payload_size += next_byte;
         res.payload_type.push(payload_type);
         res.payload_size.push(payload_size);
-        res.payload
-            .push(decode_sei_payload(payload_type, payload_size, spses, bs));
+        res.payload.push(decode_sei_payload(
+            payload_type,
+            payload_size,
+            spses,
+            subset_spses,
+            bs,
+        )?);
         // if we don't have enough bytes for a new payload and size, then break
         if !bs.more_data() && bs.bytestream.len() < 2 {
             break;
         }
     }
 
-    return res;
+    Ok(res)
 }
 
 /// D.1.1 General SEI message syntax
@@ -62,9 +133,11 @@ fn decode_sei_payload(
     payload_type: u32,
     payload_size: u32,
     spses: &Vec<SeqParameterSet>,
+    subset_spses: &Vec<SubsetSPS>,
     bs: &mut ByteStream,
-) -> SEIPayload {
+) -> Result<SEIPayload, DecodeError> {
     let mut res = SEIPayload::new();
+    let start_len = bs.bytestream.len();
     println!("\t\tSEI: payload_type {}", payload_type);
     println!("\t\tSEI: payload_size {}", payload_size);
 
@@ -74,7 +147,7 @@ fn decode_sei_payload(
     match payload_type {
         0 => {
             // buffering period
-            res.buffering_period = decode_buffering_period(spses, bs);
+            res.buffering_period = decode_buffering_period(spses, bs)?;
             res.available = true;
         }
         1 => {
@@ -85,76 +158,101 @@ fn decode_sei_payload(
             } else {
                 sps = SeqParameterSet::new();
             }
-            res.pic_timing = decode_pic_timing(&sps, bs);
+            res.pic_timing = decode_pic_timing(&sps, bs)?;
             res.available = true;
         }
         2 => {
             // pan scan rect
-            decode_pan_scan_rect(payload_size, bs);
+            res.pan_scan_rect = decode_pan_scan_rect(bs)?;
+            res.available = true;
         }
         3 => {
             // filler payload
-            decode_filler_payload(payload_size, bs);
+            res.filler_payload = decode_filler_payload(payload_size, bs)?;
+            res.available = true;
         }
         4 => {
             // user data registered ITU T T35
-            decode_user_data_registered_itu_t_t35(payload_size, bs);
+            res.registered_user_data = decode_user_data_registered_itu_t_t35(payload_size, bs)?;
+            res.available = true;
         }
         5 => {
             // Unregistered user data
-            res.unregistered_user_data = decode_user_data_unregistered(payload_size, bs);
+            res.unregistered_user_data = decode_user_data_unregistered(payload_size, bs)?;
             res.available = true;
         }
         6 => {
             // recovery point
-            res.recovery_point = decode_recovery_point(bs);
+            res.recovery_point = decode_recovery_point(bs)?;
             res.available = true;
         }
         7 => {
             // pic marking repetition
-            decode_ref_pic_marking_repetition(payload_size, bs);
+            let sps: SeqParameterSet = if !spses.is_empty() {
+                spses[spses.len() - 1].clone()
+            } else {
+                SeqParameterSet::new()
+            };
+            res.dec_ref_pic_marking_repetition = decode_ref_pic_marking_repetition(&sps, bs)?;
+            res.available = true;
         }
         8 => {
             // spare pic
-            decode_spare_pic(payload_size, bs);
+            let sps: SeqParameterSet = if !spses.is_empty() {
+                spses[spses.len() - 1].clone()
+            } else {
+                SeqParameterSet::new()
+            };
+            res.spare_pic = decode_spare_pic(&sps, bs)?;
+            res.available = true;
         }
         9 => {
             // scene info
-            decode_scene_info(payload_size, bs);
+            res.scene_info = decode_scene_info(bs)?;
+            res.available = true;
         }
         10 => {
             // sub seq info
-            decode_sub_seq_info(payload_size, bs);
+            res.sub_seq_info = decode_sub_seq_info(bs)?;
+            res.available = true;
         }
         11 => {
             // sub seq layer characteristics
-            decode_sub_seq_layer_characteristics(payload_size, bs);
+            res.sub_seq_layer_characteristics = decode_sub_seq_layer_characteristics(bs)?;
+            res.available = true;
         }
         12 => {
             // sub seq characteristics
-            decode_sub_seq_characteristics(payload_size, bs);
+            res.sub_seq_characteristics = decode_sub_seq_characteristics(bs)?;
+            res.available = true;
         }
         13 => {
             // full_frame_freeze
-            decode_full_frame_freeze(payload_size, bs);
+            res.full_frame_freeze = decode_full_frame_freeze(bs)?;
+            res.available = true;
         }
         14 => {
-            decode_full_frame_freeze_release(payload_size, bs);
+            decode_full_frame_freeze_release();
+            res.available = true;
         }
         15 => {
-            decode_full_frame_snapshot(payload_size, bs);
+            res.full_frame_snapshot = decode_full_frame_snapshot(bs)?;
+            res.available = true;
         }
         16 => {
-            decode_progressive_refinement_segment_start(payload_size, bs);
+            res.progressive_refinement_segment_start =
+                decode_progressive_refinement_segment_start(bs)?;
+            res.available = true;
         }
         17 => {
-            decode_progressive_refinement_segment_end(payload_size, bs);
+            res.progressive_refinement_segment_end = decode_progressive_refinement_segment_end(bs)?;
+            res.available = true;
         }
         18 => {
             decode_motion_constrained_slice_group_set(payload_size, bs);
         }
         19 => {
-            res.film_grain_characteristics = decode_film_grain_characteristics(bs);
+            res.film_grain_characteristics = decode_film_grain_characteristics(bs)?;
             res.available = true;
         }
         20 => {
@@ -167,140 +265,188 @@ fn decode_sei_payload(
             decode_post_filter_hint(payload_size, bs);
         }
         23 => {
-            decode_tone_mapping_info(payload_size, bs);
+            res.tone_mapping_info = decode_tone_mapping_info(bs)?;
+            res.available = true;
         }
         24 => {
-            decode_scalability_info(payload_size, bs); // specified in Annex G
+            res.scalability_info = decode_scalability_info(bs)?;
+            res.available = true;
         }
         25 => {
-            decode_sub_pic_scalable_layer(payload_size, bs); // specified in Annex G
+            res.sub_pic_scalable_layer = decode_sub_pic_scalable_layer(bs)?;
+            res.available = true;
         }
         26 => {
-            decode_non_required_layer_rep(payload_size, bs); // specified in Annex G
+            res.non_required_layer_rep = decode_non_required_layer_rep(bs)?;
+            res.available = true;
         }
         27 => {
-            decode_priority_layer_info(payload_size, bs); // specified in Annex G
+            res.priority_layer_info = decode_priority_layer_info(bs)?;
+            res.available = true;
         }
         28 => {
-            decode_layers_not_present(payload_size, bs); // specified in Annex G
+            res.layers_not_present = decode_layers_not_present(bs)?;
+            res.available = true;
         }
         29 => {
-            decode_layer_dependency_change(payload_size, bs); // specified in Annex G
+            res.layer_dependency_change = decode_layer_dependency_change(bs)?;
+            res.available = true;
         }
         30 => {
-            decode_scalable_nesting(payload_size, bs); // specified in Annex G
+            res.scalable_nesting = decode_scalable_nesting(payload_size, spses, subset_spses, bs)?;
+            res.available = true;
         }
         31 => {
-            decode_base_layer_temporal_hrd(payload_size, bs); // specified in Annex G
+            res.base_layer_temporal_hrd = decode_temporal_hrd(bs)?;
+            res.available = true;
         }
         32 => {
-            decode_quality_layer_integrity_check(payload_size, bs); // specified in Annex G
+            res.quality_layer_integrity_check = decode_quality_layer_integrity_check(bs)?;
+            res.available = true;
         }
         33 => {
-            decode_redundant_pic_property(payload_size, bs); // specified in Annex G
+            res.redundant_pic_property = decode_redundant_pic_property(bs)?;
+            res.available = true;
         }
         34 => {
-            decode_tl0_dep_rep_index(payload_size, bs); // specified in Annex G
+            res.tl0_dep_rep_index = decode_tl0_dep_rep_index(bs)?;
+            res.available = true;
         }
         35 => {
-            decode_tl_switching_point(payload_size, bs); // specified in Annex G
+            res.tl_switching_point = decode_tl_switching_point(bs)?;
+            res.available = true;
         }
         36 => {
-            decode_parallel_decoding_info(payload_size, bs); // specified in Annex H
+            res.parallel_decoding_info =
+                decode_parallel_decoding_info(payload_size, subset_spses, bs)?;
+            res.available = true;
         }
         37 => {
-            decode_mvc_scalable_nesting(payload_size, bs); // specified in Annex H
+            res.mvc_scalable_nesting =
+                decode_mvc_scalable_nesting(payload_size, false, spses, subset_spses, bs)?;
+            res.available = true;
         }
         38 => {
-            decode_view_scalability_info(payload_size, bs); // specified in Annex H
+            res.view_scalability_info = decode_view_scalability_info(false, bs)?;
+            res.available = true;
         }
         39 => {
-            decode_multiview_scene_info(payload_size, bs); // specified in Annex H
+            res.multiview_scene_info = decode_multiview_scene_info(bs)?;
+            res.available = true;
         }
         40 => {
-            decode_multiview_acquisition_info(payload_size, bs); // specified in Annex H
+            res.multiview_acquisition_info = decode_multiview_acquisition_info(bs)?;
+            res.available = true;
         }
         41 => {
-            decode_non_required_view_component(payload_size, bs); // specified in Annex H
+            res.non_required_view_component = decode_non_required_view_component(bs)?;
+            res.available = true;
         }
         42 => {
-            decode_view_dependency_change(payload_size, bs); // specified in Annex H
+            res.view_dependency_change = decode_view_dependency_change(subset_spses, bs)?;
+            res.available = true;
         }
         43 => {
-            decode_operation_points_not_present(payload_size, bs); // specified in Annex H
+            res.operation_points_not_present = decode_operation_points_not_present(bs)?;
+            res.available = true;
         }
         44 => {
-            decode_base_view_temporal_hrd(payload_size, bs); // specified in Annex H
+            res.base_view_temporal_hrd = decode_temporal_hrd(bs)?;
+            res.available = true;
         }
         45 => {
-            decode_frame_packing_arrangement(payload_size, bs);
+            res.frame_packing = decode_frame_packing_arrangement(bs)?;
+            res.available = true;
         }
         46 => {
-            decode_multiview_view_position(payload_size, bs); // specified in Annex H
+            res.multiview_view_position = decode_multiview_view_position(bs)?;
+            res.available = true;
         }
         47 => {
-            decode_display_orientation(payload_size, bs);
+            res.display_orientation = decode_display_orientation(bs)?;
+            res.available = true;
         }
         48 => {
-            decode_mvcd_scalable_nesting(payload_size, bs); // specified in Annex I
+            res.mvcd_scalable_nesting =
+                decode_mvc_scalable_nesting(payload_size, true, spses, subset_spses, bs)?;
+            res.available = true;
         }
         49 => {
-            decode_mvcd_view_scalability_info(payload_size, bs); // specified in Annex I
+            res.mvcd_view_scalability_info = decode_view_scalability_info(true, bs)?;
+            res.available = true;
         }
         50 => {
-            decode_depth_representation_info(payload_size, bs); // specified in Annex I
+            res.depth_representation_info = decode_depth_representation_info(bs)?;
+            res.available = true;
         }
         51 => {
-            decode_three_dimensional_reference_displays_info(payload_size, bs); // specified in Annex I
+            res.three_dimensional_reference_displays_info =
+                decode_three_dimensional_reference_displays_info(bs)?;
+            res.available = true;
         }
         52 => {
-            decode_depth_timing(payload_size, bs); // specified in Annex I
+            res.depth_timing = decode_depth_timing(subset_spses, bs)?;
+            res.available = true;
         }
         53 => {
-            decode_depth_sampling_info(payload_size, bs); // specified in Annex I
+            res.depth_sampling_info = decode_depth_sampling_info(bs)?;
+            res.available = true;
         }
         54 => {
-            decode_constrained_depth_parameter_set_identifier(payload_size, bs);
-            // specified in Annex J
+            res.constrained_depth_parameter_set_identifier =
+                decode_constrained_depth_parameter_set_identifier(bs)?;
+            res.available = true;
         }
         56 => {
             decode_green_metadata(payload_size, bs); // specified in ISO/IEC 23001-11
         }
         137 => {
-            decode_mastering_display_colour_volume(payload_size, bs);
+            res.mastering_display_colour_volume = decode_mastering_display_colour_volume(bs)?;
+            res.available = true;
         }
         142 => {
-            decode_colour_remapping_info(payload_size, bs);
+            res.colour_remapping_info = decode_colour_remapping_info(bs)?;
+            res.available = true;
         }
         144 => {
-            decode_content_light_level_info(payload_size, bs);
+            res.content_light_level_info = decode_content_light_level_info(bs)?;
+            res.available = true;
         }
         147 => {
-            decode_alternative_transfer_characteristics(payload_size, bs);
+            res.alternative_transfer_characteristics =
+                decode_alternative_transfer_characteristics(bs)?;
+            res.available = true;
         }
         148 => {
             decode_ambient_viewing_environment(payload_size, bs);
         }
         149 => {
-            decode_content_colour_volume(payload_size, bs);
+            res.content_colour_volume = decode_content_colour_volume(bs)?;
+            res.available = true;
         }
         150 => {
-            decode_equirectangular_projection(payload_size, bs);
+            res.equirectangular_projection = decode_equirectangular_projection(bs)?;
+            res.available = true;
         }
         151 => {
-            decode_cubemap_projection(payload_size, bs);
+            res.cubemap_projection = decode_cubemap_projection(bs)?;
+            res.available = true;
         }
         154 => {
-            decode_sphere_rotation(payload_size, bs);
+            res.sphere_rotation = decode_sphere_rotation(bs)?;
+            res.available = true;
         }
         155 => {
-            decode_regionwise_packing(payload_size, bs);
+            res.regionwise_packing = decode_regionwise_packing(bs)?;
+            res.available = true;
         }
         156 => {
-            decode_omni_viewport(payload_size, bs);
+            res.omni_viewport = decode_omni_viewport(bs)?;
+            res.available = true;
         }
         181 => {
-            decode_alternative_depth_info(payload_size, bs); // specified in Annex I
+            res.alternative_depth_info = decode_alternative_depth_info(bs)?;
+            res.available = true;
         }
         200 => {
             decode_sei_manifest(payload_size, bs);
@@ -320,17 +466,6 @@ fn decode_sei_payload(
         }
     }
 
-    // if not parsed, then just consume the payload_size number of bytes
-    if !res.available {
-        if (payload_size as usize) < bs.bytestream.len() {
-            bs.bytestream.drain(0..(payload_size as usize));
-        } else {
-            debug!(target: "decode","[WARNING] SEI Payload size {} larger than bytes available {} ", payload_size, bs.bytestream.len());
-            bs.bytestream.clear();
-            bs.byte_offset = 0;
-        }
-    }
-
     // if stream is not byte aligned then read bit equal to 1 then 0 bits until byte aligned
     if bs.byte_offset > 0 {
         if bs.bytestream.len() > 0 {
@@ -339,16 +474,75 @@ fn decode_sei_payload(
         }
     }
 
-    return res;
+    // keep whatever the payload parsing did not consume, i.e. the whole body of unmodelled
+    // payloads or any trailing extension data, so that it can be re-encoded verbatim
+    let consumed = start_len - bs.bytestream.len();
+    if (payload_size as usize) > consumed {
+        let remaining = payload_size as usize - consumed;
+        let mut raw_len = remaining;
+        if remaining > bs.bytestream.len() {
+            debug!(target: "decode","[WARNING] SEI Payload size {} larger than bytes available {} ", payload_size, consumed + bs.bytestream.len());
+            raw_len = bs.bytestream.len();
+            // leave the rbsp_stop_one_bit for the caller
+            if bs.bytestream.back() == Some(&0x80) {
+                raw_len -= 1;
+            }
+        }
+        res.raw_payload_byte = bs.bytestream.drain(0..raw_len).collect();
+        decoder_formatted_print("SEI: raw_payload_byte", &res.raw_payload_byte, 63);
+    }
+
+    // a mismatch between payload_size and the bytes actually in the payload is kept so that
+    // malformed sizes survive re-encoding
+    res.payload_size_delta = payload_size as i32 - (consumed + res.raw_payload_byte.len()) as i32;
+    if res.payload_size_delta != 0 {
+        println!(
+            "[WARNING] SEI payload_size {} differs from the payload length by {}",
+            payload_size, res.payload_size_delta
+        );
+    }
+
+    Ok(res)
+}
+
+/// Decodes the sei_message()s carried by a nesting SEI message. The nesting header is
+/// followed by sei_nesting_zero_bits up to the byte boundary, and the nested messages
+/// fill the remainder of the payload
+fn decode_nested_sei_messages(
+    payload_size: u32,
+    start_len: usize,
+    spses: &Vec<SeqParameterSet>,
+    subset_spses: &Vec<SubsetSPS>,
+    bs: &mut ByteStream,
+) -> Result<SEINalu, DecodeError> {
+    // skip the sei_nesting_zero_bits
+    if bs.byte_offset > 0 && !bs.bytestream.is_empty() {
+        bs.bytestream.pop_front();
+        bs.byte_offset = 0;
+    }
+
+    let consumed = start_len - bs.bytestream.len();
+    let mut remaining = (payload_size as usize).saturating_sub(consumed);
+    if remaining > bs.bytestream.len() {
+        println!(
+            "[WARNING] decode_nested_sei_messages - nested size {} larger than bytes available {}",
+            remaining,
+            bs.bytestream.len()
+        );
+        remaining = bs.bytestream.len();
+    }
+
+    let mut nested_bs = ByteStream::new(bs.bytestream.drain(0..remaining).collect());
+    decode_sei_message(spses, subset_spses, &mut nested_bs)
 }
 
 /// D.1.2 Buffering period SEI message syntax
 fn decode_buffering_period(
     spses: &Vec<SeqParameterSet>,
     bs: &mut ByteStream,
-) -> SEIBufferingPeriod {
+) -> Result<SEIBufferingPeriod, DecodeError> {
     let mut res = SEIBufferingPeriod::new();
-    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "SEI (Buffering Period): seq_parameter_set_id",
         &res.seq_parameter_set_id,
@@ -367,10 +561,12 @@ fn decode_buffering_period(
     let cur_sps: &SeqParameterSet;
     match cur_sps_wrapper {
         Some(x) => cur_sps = x,
-        _ => panic!(
-            "decode_buffering_period - SPS with id {} not found",
-            res.seq_parameter_set_id
-        ),
+        _ => {
+            return Err(DecodeError::new(format!(
+                "decode_buffering_period - SPS with id {} not found",
+                res.seq_parameter_set_id
+            )))
+        }
     }
 
     // The variable NalHrdBpPresentFlag is derived as follows:
@@ -404,14 +600,15 @@ fn decode_buffering_period(
             63,
         );
         for sched_sel_idx in 0..=cur_sps.vui_parameters.nal_hrd_parameters.cpb_cnt_minus1 {
-            res.nal_initial_cpb_removal_delay[sched_sel_idx as usize] = bs.read_bits(bits_to_read);
+            res.nal_initial_cpb_removal_delay[sched_sel_idx as usize] =
+                bs.read_bits(bits_to_read)?;
             decoder_formatted_print(
                 "SEI (Buffering Period): NAL initial_cpb_removal_delay[]",
                 &res.nal_initial_cpb_removal_delay[sched_sel_idx as usize],
                 63,
             );
             res.nal_initial_cpb_removal_delay_offset[sched_sel_idx as usize] =
-                bs.read_bits(bits_to_read);
+                bs.read_bits(bits_to_read)?;
             decoder_formatted_print(
                 "SEI (Buffering Period): NAL initial_cpb_removal_delay_offset[]",
                 &res.nal_initial_cpb_removal_delay_offset[sched_sel_idx as usize],
@@ -444,14 +641,15 @@ fn decode_buffering_period(
             63,
         );
         for sched_sel_idx in 0..=cur_sps.vui_parameters.vcl_hrd_parameters.cpb_cnt_minus1 {
-            res.vcl_initial_cpb_removal_delay[sched_sel_idx as usize] = bs.read_bits(bits_to_read);
+            res.vcl_initial_cpb_removal_delay[sched_sel_idx as usize] =
+                bs.read_bits(bits_to_read)?;
             decoder_formatted_print(
                 "SEI (Buffering Period): VCL initial_cpb_removal_delay[]",
                 &res.vcl_initial_cpb_removal_delay[sched_sel_idx as usize],
                 63,
             );
             res.vcl_initial_cpb_removal_delay_offset[sched_sel_idx as usize] =
-                bs.read_bits(bits_to_read);
+                bs.read_bits(bits_to_read)?;
             decoder_formatted_print(
                 "SEI (Buffering Period): VCL initial_cpb_removal_delay_offset[]",
                 &res.vcl_initial_cpb_removal_delay_offset[sched_sel_idx as usize],
@@ -460,11 +658,14 @@ fn decode_buffering_period(
         }
     }
 
-    return res;
+    Ok(res)
 }
 
 /// D.1.3 Picture timing SEI message syntax
-fn decode_pic_timing(sps: &SeqParameterSet, bs: &mut ByteStream) -> SEIPicTiming {
+fn decode_pic_timing(
+    sps: &SeqParameterSet,
+    bs: &mut ByteStream,
+) -> Result<SEIPicTiming, DecodeError> {
     let mut result = SEIPicTiming::new();
     // From Page 460 of the H.264 Spec:
     // The variable CpbDpbDelaysPresentFlag is derived as follows:
@@ -516,10 +717,10 @@ fn decode_pic_timing(sps: &SeqParameterSet, bs: &mut ByteStream) -> SEIPicTiming
                 + 1;
         }
 
-        result.cpb_removal_delay = bs.read_bits(cpb_bits_to_read);
+        result.cpb_removal_delay = bs.read_bits(cpb_bits_to_read)?;
         // From section D.2.3 of the Spec:
         // When vui_parameters.max_dec_frame_buffering is equal to 0, dpb_output_delay shall be equal to 0
-        result.dpb_output_delay = bs.read_bits(dpb_bits_to_read);
+        result.dpb_output_delay = bs.read_bits(dpb_bits_to_read)?;
         decoder_formatted_print(
             "SEI (Pic timing): cpb_removal_delay",
             &result.cpb_removal_delay,
@@ -539,7 +740,7 @@ fn decode_pic_timing(sps: &SeqParameterSet, bs: &mut ByteStream) -> SEIPicTiming
         63,
     );
     if pic_struct_present_flag {
-        result.pic_struct = bs.read_bits(4);
+        result.pic_struct = bs.read_bits(4)?;
         decoder_formatted_print("SEI (Pic timing): pic_struct", &result.pic_struct, 63);
         let num_clock_ts;
 
@@ -602,20 +803,20 @@ fn decode_pic_timing(sps: &SeqParameterSet, bs: &mut ByteStream) -> SEIPicTiming
                 num_clock_ts = 3;
             }
             9..=15 => {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "SEI (Pic timing): Reserved value for pic_struct : {}",
                     result.pic_struct
-                );
+                )));
             }
             _ => {
-                panic!(
+                return Err(DecodeError::new(format!(
                     "SEI (Pic timing): Out of bounds Pic_struct : {}",
                     result.pic_struct
-                );
+                )));
             }
         }
         for i in 0..num_clock_ts {
-            result.clock_timestamp_flag.push(1 == bs.read_bits(1));
+            result.clock_timestamp_flag.push(1 == bs.read_bits(1)?);
             decoder_formatted_print(
                 "SEI (Pic timing): clock_timestamp_flag[]",
                 &result.clock_timestamp_flag[i],
@@ -631,63 +832,63 @@ fn decode_pic_timing(sps: &SeqParameterSet, bs: &mut ByteStream) -> SEIPicTiming
             }
 
             if result.clock_timestamp_flag[i] {
-                result.ct_type.push(bs.read_bits(2));
+                result.ct_type.push(bs.read_bits(2)?);
                 decoder_formatted_print("SEI (Pic timing): ct_type[]", &result.ct_type[i], 63);
 
-                result.nuit_field_based_flag.push(1 == bs.read_bits(1));
+                result.nuit_field_based_flag.push(1 == bs.read_bits(1)?);
                 decoder_formatted_print(
                     "SEI (Pic timing): nuit_field_based_flag[]",
                     &result.nuit_field_based_flag[i],
                     63,
                 );
 
-                result.counting_type.push(bs.read_bits(5));
+                result.counting_type.push(bs.read_bits(5)?);
                 decoder_formatted_print(
                     "SEI (Pic timing): counting_type[]",
                     &result.counting_type[i],
                     63,
                 );
 
-                result.full_timestamp_flag.push(1 == bs.read_bits(1));
+                result.full_timestamp_flag.push(1 == bs.read_bits(1)?);
                 decoder_formatted_print(
                     "SEI (Pic timing): full_timestamp_flag[]",
                     &result.full_timestamp_flag[i],
                     63,
                 );
 
-                result.discontinuity_flag.push(1 == bs.read_bits(1));
+                result.discontinuity_flag.push(1 == bs.read_bits(1)?);
                 decoder_formatted_print(
                     "SEI (Pic timing): discontinuity_flag[]",
                     &result.discontinuity_flag[i],
                     63,
                 );
 
-                result.cnt_dropped_flag.push(1 == bs.read_bits(1));
+                result.cnt_dropped_flag.push(1 == bs.read_bits(1)?);
                 decoder_formatted_print(
                     "SEI (Pic timing): cnt_dropped_flag[]",
                     &result.cnt_dropped_flag[i],
                     63,
                 );
 
-                result.n_frames.push(bs.read_bits(8));
+                result.n_frames.push(bs.read_bits(8)?);
                 decoder_formatted_print("SEI (Pic timing): n_frames[]", &result.n_frames[i], 63);
 
                 if result.full_timestamp_flag[i] {
-                    result.seconds_value.push(bs.read_bits(6));
+                    result.seconds_value.push(bs.read_bits(6)?);
                     decoder_formatted_print(
                         "SEI (Pic timing): seconds_value[]",
                         &result.seconds_value[i],
                         63,
                     );
 
-                    result.minutes_value.push(bs.read_bits(6));
+                    result.minutes_value.push(bs.read_bits(6)?);
                     decoder_formatted_print(
                         "SEI (Pic timing): minutes_value[]",
                         &result.minutes_value[i],
                         63,
                     );
 
-                    result.hours_value.push(bs.read_bits(5));
+                    result.hours_value.push(bs.read_bits(5)?);
                     decoder_formatted_print(
                         "SEI (Pic timing): hours_value[]",
                         &result.hours_value[i],
@@ -699,40 +900,40 @@ fn decode_pic_timing(sps: &SeqParameterSet, bs: &mut ByteStream) -> SEIPicTiming
                     result.minutes_flag.push(true);
                     result.hours_flag.push(true);
                 } else {
-                    result.seconds_flag.push(1 == bs.read_bits(1));
+                    result.seconds_flag.push(1 == bs.read_bits(1)?);
                     decoder_formatted_print(
                         "SEI (Pic timing): seconds_flag[]",
                         &result.seconds_flag[i],
                         63,
                     );
                     if result.seconds_flag[i] {
-                        result.seconds_value.push(bs.read_bits(6));
+                        result.seconds_value.push(bs.read_bits(6)?);
                         decoder_formatted_print(
                             "SEI (Pic timing): seconds_value[]",
                             &result.seconds_value[i],
                             63,
                         );
-                        result.minutes_flag.push(1 == bs.read_bits(1));
+                        result.minutes_flag.push(1 == bs.read_bits(1)?);
                         decoder_formatted_print(
                             "SEI (Pic timing): minutes_flag[]",
                             &result.minutes_flag[i],
                             63,
                         );
                         if result.minutes_flag[i] {
-                            result.minutes_value.push(bs.read_bits(6));
+                            result.minutes_value.push(bs.read_bits(6)?);
                             decoder_formatted_print(
                                 "SEI (Pic timing): minutes_value[]",
                                 &result.minutes_value[i],
                                 63,
                             );
-                            result.hours_flag.push(1 == bs.read_bits(1));
+                            result.hours_flag.push(1 == bs.read_bits(1)?);
                             decoder_formatted_print(
                                 "SEI (Pic timing): hours_flag[]",
                                 &result.hours_flag[i],
                                 63,
                             );
                             if result.hours_flag[i] {
-                                result.hours_value.push(bs.read_bits(5));
+                                result.hours_value.push(bs.read_bits(5)?);
                                 decoder_formatted_print(
                                     "SEI (Pic timing): hours_value[]",
                                     &result.hours_value[i],
@@ -755,7 +956,7 @@ fn decode_pic_timing(sps: &SeqParameterSet, bs: &mut ByteStream) -> SEIPicTiming
                     }
                 }
                 if time_offset_length > 0 {
-                    result.time_offset.push(bs.read_bits(time_offset_length)); // i(v)
+                    result.time_offset.push(bs.read_bits(time_offset_length)?); // i(v)
                 } else {
                     result.time_offset.push(0);
                 }
@@ -792,107 +993,266 @@ fn decode_pic_timing(sps: &SeqParameterSet, bs: &mut ByteStream) -> SEIPicTiming
         // produce these values.
     }
 
-    return result;
+    Ok(result)
 }
 
 /// D.1.4 Pan-scan rectangle SEI message syntax
-fn decode_pan_scan_rect(_payload_size: u32, bs: &mut ByteStream) {
-    let pan_scan_rect_id = exp_golomb_decode_one_wrapper(bs, false, 0);
+fn decode_pan_scan_rect(bs: &mut ByteStream) -> Result<SEIPanScanRect, DecodeError> {
+    let mut res = SEIPanScanRect::new();
+
+    res.pan_scan_rect_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "SEI (Pan-scan rectangle): pan_scan_rect_id",
-        &pan_scan_rect_id,
+        res.pan_scan_rect_id,
         63,
     );
-    let pan_scan_rect_cancel_flag = 1 == bs.read_bits(1);
+    res.pan_scan_rect_cancel_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SEI (Pan-scan rectangle): pan_scan_rect_cancel_flag",
-        &pan_scan_rect_cancel_flag,
+        res.pan_scan_rect_cancel_flag,
         63,
     );
-    if !pan_scan_rect_cancel_flag {
-        let pan_scan_cnt_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0);
+    if !res.pan_scan_rect_cancel_flag {
+        res.pan_scan_cnt_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SEI (Pan-scan rectangle): pan_scan_cnt_minus1",
-            &pan_scan_cnt_minus1,
+            res.pan_scan_cnt_minus1,
             63,
         );
-        // prepare
-        let mut pan_scan_rect_left_offset = Vec::new();
-        let mut pan_scan_rect_right_offset = Vec::new();
-        let mut pan_scan_rect_top_offset = Vec::new();
-        let mut pan_scan_rect_bottom_offset = Vec::new();
-        for _ in 0..=pan_scan_cnt_minus1 {
-            pan_scan_rect_left_offset.push(exp_golomb_decode_one_wrapper(bs, true, 0));
+        for i in 0..=(res.pan_scan_cnt_minus1 as usize) {
+            res.pan_scan_rect_left_offset
+                .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
             decoder_formatted_print(
                 "SEI (Pan-scan rectangle): pan_scan_rect_left_offset",
-                &pan_scan_rect_left_offset,
+                res.pan_scan_rect_left_offset[i],
                 63,
             );
-            pan_scan_rect_right_offset.push(exp_golomb_decode_one_wrapper(bs, true, 0));
+            res.pan_scan_rect_right_offset
+                .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
             decoder_formatted_print(
                 "SEI (Pan-scan rectangle): pan_scan_rect_right_offset",
-                &pan_scan_rect_right_offset,
+                res.pan_scan_rect_right_offset[i],
                 63,
             );
-            pan_scan_rect_top_offset.push(exp_golomb_decode_one_wrapper(bs, true, 0));
+            res.pan_scan_rect_top_offset
+                .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
             decoder_formatted_print(
                 "SEI (Pan-scan rectangle): pan_scan_rect_top_offset",
-                &pan_scan_rect_top_offset,
+                res.pan_scan_rect_top_offset[i],
                 63,
             );
-            pan_scan_rect_bottom_offset.push(exp_golomb_decode_one_wrapper(bs, true, 0));
+            res.pan_scan_rect_bottom_offset
+                .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
             decoder_formatted_print(
                 "SEI (Pan-scan rectangle): pan_scan_rect_bottom_offset",
-                &pan_scan_rect_bottom_offset,
+                res.pan_scan_rect_bottom_offset[i],
                 63,
             );
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_pan_scan_rect - ran out of bytes at pan_scan_cnt {}",
+                    i
+                );
+                break;
+            }
         }
-        let _pan_scan_rect_repetition_period = exp_golomb_decode_one_wrapper(bs, false, 0);
+        res.pan_scan_rect_repetition_period = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Pan-scan rectangle): pan_scan_rect_repetition_period",
+            res.pan_scan_rect_repetition_period,
+            63,
+        );
     }
+
+    Ok(res)
 }
 
 /// D.1.5 Filler payload SEI message syntax
-fn decode_filler_payload(_payload_size: u32, _bs: &mut ByteStream) {}
+fn decode_filler_payload(
+    payload_size: u32,
+    bs: &mut ByteStream,
+) -> Result<SEIFillerPayload, DecodeError> {
+    let mut res = SEIFillerPayload::new();
+
+    if (payload_size as usize) > bs.bytestream.len() {
+        println!(
+            "[WARNING] decode_filler_payload - payload_size {} larger than bytes available {}",
+            payload_size,
+            bs.bytestream.len()
+        );
+    }
+
+    for _ in 0..payload_size {
+        if bs.bytestream.is_empty() {
+            break;
+        }
+        res.ff_byte.push(bs.read_bits(8)? as u8);
+    }
+    decoder_formatted_print("SEI (Filler payload): ff_byte", &res.ff_byte, 63);
+
+    Ok(res)
+}
 
 /// D.1.6 User data registered by Rec. ITU-T T.35 SEI message syntax
-fn decode_user_data_registered_itu_t_t35(payload_size: u32, bs: &mut ByteStream) {
-    let itu_t_t35_country_code = bs.read_bits(8);
+fn decode_user_data_registered_itu_t_t35(
+    payload_size: u32,
+    bs: &mut ByteStream,
+) -> Result<SEIUserDataRegisteredITUTT35, DecodeError> {
+    let mut res = SEIUserDataRegisteredITUTT35::new();
+
+    let start_len = bs.bytestream.len();
+    if start_len < (payload_size as usize) {
+        println!("[WARNING] decode_user_data_registered_itu_t_t35 - Malformed SEI unit - payload_size {} doesn't match NALU content length {}", payload_size, start_len);
+    }
+    let payload_size = std::cmp::min(payload_size as usize, start_len);
+    // bytes of the payload that have not been consumed yet
+    let remaining = |bs: &ByteStream| payload_size.saturating_sub(start_len - bs.bytestream.len());
+
+    if payload_size == 0 {
+        println!("[WARNING] decode_user_data_registered_itu_t_t35 - empty payload");
+        return Ok(res);
+    }
+
+    res.itu_t_t35_country_code = bs.read_bits(8)? as u8;
     decoder_formatted_print(
         "SEI (ITU-T T.35 User Data): itu_t_t35_country_code",
-        &itu_t_t35_country_code,
+        res.itu_t_t35_country_code,
         63,
     );
-    let mut i: u32 = if itu_t_t35_country_code == 0xff {
-        1
-    } else {
-        let itu_t_t35_country_code_extension_byte = bs.read_bits(8);
+    if res.itu_t_t35_country_code == 0xff && remaining(bs) > 0 {
+        res.itu_t_t35_country_code_extension_byte = bs.read_bits(8)? as u8;
         decoder_formatted_print(
             "SEI (ITU-T T.35 User Data): itu_t_t35_country_code_extension_byte",
-            &itu_t_t35_country_code_extension_byte,
+            res.itu_t_t35_country_code_extension_byte,
             63,
         );
-        2
-    };
+    }
 
-    loop {
-        let itu_t_t35_payload_byte = bs.read_bits(8);
+    if remaining(bs) >= 2 {
+        res.itu_t_t35_provider_code = bs.read_bits(16)? as u16;
         decoder_formatted_print(
-            "SEI (ITU-T T.35 User Data): itu_t_t35_payload_byte",
-            &itu_t_t35_payload_byte,
+            "SEI (ITU-T T.35 User Data): itu_t_t35_provider_code",
+            res.itu_t_t35_provider_code,
             63,
         );
-        i += 1;
-        if i < payload_size {
-            break;
+    }
+
+    // ATSC A/53 Part 4 ATSC1_data()
+    if res.is_atsc() && remaining(bs) >= 5 {
+        res.atsc_user_identifier = bs.read_bits(32)?;
+        decoder_formatted_print(
+            "SEI (ITU-T T.35 User Data): atsc_user_identifier",
+            res.atsc_user_identifier,
+            63,
+        );
+        res.atsc_user_data_type_code = bs.read_bits(8)? as u8;
+        decoder_formatted_print(
+            "SEI (ITU-T T.35 User Data): atsc_user_data_type_code",
+            res.atsc_user_data_type_code,
+            63,
+        );
+
+        if res.has_cc_data() && remaining(bs) >= 2 {
+            res.cc_data.process_em_data_flag = 1 == bs.read_bits(1)?;
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): process_em_data_flag",
+                res.cc_data.process_em_data_flag,
+                63,
+            );
+            res.cc_data.process_cc_data_flag = 1 == bs.read_bits(1)?;
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): process_cc_data_flag",
+                res.cc_data.process_cc_data_flag,
+                63,
+            );
+            res.cc_data.additional_data_flag = 1 == bs.read_bits(1)?;
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): additional_data_flag",
+                res.cc_data.additional_data_flag,
+                63,
+            );
+            res.cc_data.cc_count = bs.read_bits(5)? as u8;
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): cc_count",
+                res.cc_data.cc_count,
+                63,
+            );
+            res.cc_data.em_data = bs.read_bits(8)? as u8;
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): em_data",
+                res.cc_data.em_data,
+                63,
+            );
+
+            for i in 0..res.cc_data.cc_count as usize {
+                if remaining(bs) < 3 {
+                    println!("[WARNING] decode_user_data_registered_itu_t_t35 - ran out of bytes at cc_data triplet {} of {}", i, res.cc_data.cc_count);
+                    break;
+                }
+                res.cc_data.cc_marker_bits.push(bs.read_bits(5)? as u8);
+                res.cc_data.cc_valid.push(1 == bs.read_bits(1)?);
+                res.cc_data.cc_type.push(bs.read_bits(2)? as u8);
+                res.cc_data.cc_data_1.push(bs.read_bits(8)? as u8);
+                res.cc_data.cc_data_2.push(bs.read_bits(8)? as u8);
+            }
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): cc_marker_bits",
+                &res.cc_data.cc_marker_bits,
+                63,
+            );
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): cc_valid",
+                &res.cc_data.cc_valid,
+                63,
+            );
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): cc_type",
+                &res.cc_data.cc_type,
+                63,
+            );
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): cc_data_1",
+                &res.cc_data.cc_data_1,
+                63,
+            );
+            decoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): cc_data_2",
+                &res.cc_data.cc_data_2,
+                63,
+            );
+
+            if remaining(bs) > 0 {
+                res.cc_data.marker_bits = bs.read_bits(8)? as u8;
+                decoder_formatted_print(
+                    "SEI (ITU-T T.35 User Data): marker_bits",
+                    res.cc_data.marker_bits,
+                    63,
+                );
+            } else {
+                println!(
+                    "[WARNING] decode_user_data_registered_itu_t_t35 - missing cc_data marker_bits"
+                );
+            }
         }
     }
+
+    while remaining(bs) > 0 {
+        res.itu_t_t35_payload_byte.push(bs.read_bits(8)? as u8);
+    }
+    decoder_formatted_print(
+        "SEI (ITU-T T.35 User Data): itu_t_t35_payload_byte",
+        &res.itu_t_t35_payload_byte,
+        63,
+    );
+
+    Ok(res)
 }
 
 /// D.1.7 User data unregistered SEI message syntax
 fn decode_user_data_unregistered(
     mut payload_size: u32,
     bs: &mut ByteStream,
-) -> SEIUserDataUnregistered {
+) -> Result<SEIUserDataUnregistered, DecodeError> {
     let mut res = SEIUserDataUnregistered::new();
 
     if bs.bytestream.len() < (payload_size as usize) {
@@ -909,7 +1269,7 @@ fn decode_user_data_unregistered(
     }
 
     for i in 0..16 {
-        res.uuid_iso_iec_11578[i] = bs.read_bits(8) as u8;
+        res.uuid_iso_iec_11578[i] = bs.read_bits(8)? as u8;
     }
     decoder_formatted_print(
         "SEI (Unregistered User Data): uuid_iso_iec_11578",
@@ -921,18 +1281,18 @@ fn decode_user_data_unregistered(
     // switch to see what UUID value we're dealing with
     match res.uuid_iso_iec_11578 {
         UUID_APPLE1 => {
-            res.user_data_apple1.mystery_param1 = bs.read_bits(8);
+            res.user_data_apple1.mystery_param1 = bs.read_bits(8)?;
             // TODO: there is a derived parameter on if the above is less than 4, but we hold off for now
         }
         UUID_APPLE2 => {
-            res.user_data_apple2.mystery_param1 = bs.read_bits(8);
-            res.user_data_apple2.mystery_param2 = bs.read_bits(8);
-            res.user_data_apple2.mystery_param3 = bs.read_bits(8);
-            res.user_data_apple2.mystery_param4 = bs.read_bits(8);
-            res.user_data_apple2.mystery_param5 = bs.read_bits(8);
-            res.user_data_apple2.mystery_param6 = bs.read_bits(8);
-            res.user_data_apple2.mystery_param7 = bs.read_bits(8);
-            res.user_data_apple2.mystery_param8 = bs.read_bits(8);
+            res.user_data_apple2.mystery_param1 = bs.read_bits(8)?;
+            res.user_data_apple2.mystery_param2 = bs.read_bits(8)?;
+            res.user_data_apple2.mystery_param3 = bs.read_bits(8)?;
+            res.user_data_apple2.mystery_param4 = bs.read_bits(8)?;
+            res.user_data_apple2.mystery_param5 = bs.read_bits(8)?;
+            res.user_data_apple2.mystery_param6 = bs.read_bits(8)?;
+            res.user_data_apple2.mystery_param7 = bs.read_bits(8)?;
+            res.user_data_apple2.mystery_param8 = bs.read_bits(8)?;
         }
         _ => {
             if (payload_size as usize) > bs.bytestream.len() {
@@ -945,7 +1305,7 @@ fn decode_user_data_unregistered(
                 bs.byte_offset = 0;
             } else {
                 for _ in 0..payload_size {
-                    let cur_byte = bs.read_bits(8) as u8;
+                    let cur_byte = bs.read_bits(8)? as u8;
                     res.user_data_payload_byte.push(cur_byte);
                 }
                 decoder_formatted_print(
@@ -957,154 +1317,620 @@ fn decode_user_data_unregistered(
         }
     }
 
-    res
+    Ok(res)
 }
 
 /// D.1.8 Recovery point SEI message syntax
-fn decode_recovery_point(bs: &mut ByteStream) -> SEIRecoveryPoint {
+fn decode_recovery_point(bs: &mut ByteStream) -> Result<SEIRecoveryPoint, DecodeError> {
     let mut res = SEIRecoveryPoint::new();
 
-    res.recovery_frame_cnt = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    res.recovery_frame_cnt = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
         "SEI (Recovery point): recovery_frame_cnt",
         &res.recovery_frame_cnt,
         63,
     );
-    res.exact_match_flag = 1 == bs.read_bits(1);
+    res.exact_match_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SEI (Recovery point): exact_match_flag",
         &res.exact_match_flag,
         63,
     );
-    res.broken_link_flag = 1 == bs.read_bits(1);
+    res.broken_link_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
         "SEI (Recovery point): broken_link_flag",
         &res.broken_link_flag,
         63,
     );
-    res.changing_slice_group_idc = bs.read_bits(2) as u8;
+    res.changing_slice_group_idc = bs.read_bits(2)? as u8;
     decoder_formatted_print(
         "SEI (Recovery point): changing_slice_group_idc",
         &res.changing_slice_group_idc,
         63,
     );
 
-    res
+    Ok(res)
 }
 
 /// D.1.9 Decoded reference picture marking repetition SEI message syntax
-fn decode_ref_pic_marking_repetition(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// D.1.10 Spare picture SEI message syntax
-fn decode_spare_pic(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// D.1.11 Scene information SEI message syntax
-fn decode_scene_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// D.1.12 Sub-sequence information SEI message syntax
-fn decode_sub_seq_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// D.1.13 Sub-sequence layer characteristics SEI message syntax
-fn decode_sub_seq_layer_characteristics(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// D.1.14 Sub-sequence characteristics SEI message syntax
-fn decode_sub_seq_characteristics(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// D.1.15 Full-frame freeze SEI message syntax
-fn decode_full_frame_freeze(_payload_size: u32, _bs: &mut ByteStream) {}
+fn decode_ref_pic_marking_repetition(
+    sps: &SeqParameterSet,
+    bs: &mut ByteStream,
+) -> Result<SEIDecRefPicMarkingRepetition, DecodeError> {
+    let mut res = SEIDecRefPicMarkingRepetition::new();
 
-/// D.1.16 Full-frame freeze release SEI message syntax
-fn decode_full_frame_freeze_release(_payload_size: u32, _bs: &mut ByteStream) {}
+    res.original_idr_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Ref pic marking repetition): original_idr_flag",
+        res.original_idr_flag,
+        63,
+    );
+    res.original_frame_num = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Ref pic marking repetition): original_frame_num",
+        res.original_frame_num,
+        63,
+    );
+    if !sps.frame_mbs_only_flag {
+        res.original_field_pic_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Ref pic marking repetition): original_field_pic_flag",
+            res.original_field_pic_flag,
+            63,
+        );
+        if res.original_field_pic_flag {
+            res.original_bottom_field_flag = 1 == bs.read_bits(1)?;
+            decoder_formatted_print(
+                "SEI (Ref pic marking repetition): original_bottom_field_flag",
+                res.original_bottom_field_flag,
+                63,
+            );
+        }
+    }
 
-/// D.1.17 Full-frame snapshot SEI message syntax
-fn decode_full_frame_snapshot(_payload_size: u32, _bs: &mut ByteStream) {}
+    // dec_ref_pic_marking() with IdrPicFlag replaced by original_idr_flag
+    if res.original_idr_flag {
+        res.no_output_of_prior_pics_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Ref pic marking repetition): no_output_of_prior_pics_flag",
+            res.no_output_of_prior_pics_flag,
+            63,
+        );
+        res.long_term_reference_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Ref pic marking repetition): long_term_reference_flag",
+            res.long_term_reference_flag,
+            63,
+        );
+    } else {
+        res.adaptive_ref_pic_marking_mode_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Ref pic marking repetition): adaptive_ref_pic_marking_mode_flag",
+            res.adaptive_ref_pic_marking_mode_flag,
+            63,
+        );
+        if res.adaptive_ref_pic_marking_mode_flag {
+            loop {
+                let mmco = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                res.memory_management_control_operation.push(mmco);
+                decoder_formatted_print(
+                    "SEI (Ref pic marking repetition): memory_management_control_operation",
+                    mmco,
+                    63,
+                );
+                if mmco == 1 || mmco == 3 {
+                    res.difference_of_pic_nums_minus1
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                } else {
+                    res.difference_of_pic_nums_minus1.push(0);
+                }
+                if mmco == 2 {
+                    res.long_term_pic_num
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                } else {
+                    res.long_term_pic_num.push(0);
+                }
+                if mmco == 3 || mmco == 6 {
+                    res.long_term_frame_idx
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                } else {
+                    res.long_term_frame_idx.push(0);
+                }
+                if mmco == 4 {
+                    res.max_long_term_frame_idx_plus1
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                } else {
+                    res.max_long_term_frame_idx_plus1.push(0);
+                }
 
-/// D.1.18 Progressive refinement segment start SEI message syntax
-fn decode_progressive_refinement_segment_start(_payload_size: u32, _bs: &mut ByteStream) {}
+                if mmco == 0 || bs.bytestream.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
 
-/// D.1.19 Progressive refinement segment end SEI message syntax
-fn decode_progressive_refinement_segment_end(_payload_size: u32, _bs: &mut ByteStream) {}
+    Ok(res)
+}
 
-/// D.1.20 Motion-constrained slice group set SEI message syntax
-fn decode_motion_constrained_slice_group_set(_payload_size: u32, _bs: &mut ByteStream) {}
+/// D.1.10 Spare picture SEI message syntax
+fn decode_spare_pic(
+    sps: &SeqParameterSet,
+    bs: &mut ByteStream,
+) -> Result<SEISparePic, DecodeError> {
+    let mut res = SEISparePic::new();
 
-/// D.1.21 Film grain characteristics SEI message syntax
-fn decode_film_grain_characteristics(bs: &mut ByteStream) -> SEIFilmGrainCharacteristics {
-    let mut fgc = SEIFilmGrainCharacteristics::new();
+    // PicSizeInMapUnits of the active SPS (equation 7-17)
+    let pic_size_in_map_units: u64 =
+        (sps.pic_width_in_mbs_minus1 as u64 + 1) * (sps.pic_height_in_map_units_minus1 as u64 + 1);
 
-    fgc.film_grain_characteristics_cancel_flag = 1 == bs.read_bits(1);
+    res.target_frame_num = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print(
-        "SEI (Film Grain Characteristics): film_grain_characteristics_cancel_flag",
-        &fgc.film_grain_characteristics_cancel_flag,
+        "SEI (Spare picture): target_frame_num",
+        res.target_frame_num,
         63,
     );
-
-    if !fgc.film_grain_characteristics_cancel_flag {
-        fgc.film_grain_model_id = bs.read_bits(2) as u8;
+    res.spare_field_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Spare picture): spare_field_flag",
+        res.spare_field_flag,
+        63,
+    );
+    if res.spare_field_flag {
+        res.target_bottom_field_flag = 1 == bs.read_bits(1)?;
         decoder_formatted_print(
-            "SEI (Film Grain Characteristics): film_grain_model_id",
-            &fgc.film_grain_model_id,
+            "SEI (Spare picture): target_bottom_field_flag",
+            res.target_bottom_field_flag,
             63,
         );
+    }
+    res.num_spare_pics_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Spare picture): num_spare_pics_minus1",
+        res.num_spare_pics_minus1,
+        63,
+    );
 
-        fgc.separate_colour_description_present_flag = 1 == bs.read_bits(1);
+    for i in 0..=(res.num_spare_pics_minus1 as usize) {
+        res.delta_spare_frame_num
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
         decoder_formatted_print(
-            "SEI (Film Grain Characteristics): separate_colour_description_present_flag",
-            &fgc.separate_colour_description_present_flag,
+            "SEI (Spare picture): delta_spare_frame_num",
+            res.delta_spare_frame_num[i],
             63,
         );
-
-        if fgc.separate_colour_description_present_flag {
-            fgc.film_grain_bit_depth_luma_minus8 = bs.read_bits(3) as u8;
+        if res.spare_field_flag {
+            res.spare_bottom_field_flag.push(1 == bs.read_bits(1)?);
             decoder_formatted_print(
-                "SEI (Film Grain Characteristics): film_grain_bit_depth_luma_minus8",
-                &fgc.film_grain_bit_depth_luma_minus8,
+                "SEI (Spare picture): spare_bottom_field_flag",
+                res.spare_bottom_field_flag[i],
                 63,
             );
+        } else {
+            res.spare_bottom_field_flag.push(false);
+        }
+        res.spare_area_idc
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Spare picture): spare_area_idc",
+            res.spare_area_idc[i],
+            63,
+        );
 
-            fgc.film_grain_bit_depth_chroma_minus8 = bs.read_bits(3) as u8;
+        res.spare_unit_flag.push(Vec::new());
+        res.zero_run_length.push(Vec::new());
+        if res.spare_area_idc[i] == 1 {
+            for _ in 0..pic_size_in_map_units {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_spare_pic - ran out of bytes in spare_unit_flag");
+                    break;
+                }
+                res.spare_unit_flag[i].push(1 == bs.read_bits(1)?);
+            }
+            decoder_formatted_print(
+                "SEI (Spare picture): spare_unit_flag",
+                &res.spare_unit_flag[i],
+                63,
+            );
+        } else if res.spare_area_idc[i] == 2 {
+            let mut map_unit_cnt: u64 = 0;
+            while map_unit_cnt < pic_size_in_map_units {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_spare_pic - ran out of bytes in zero_run_length");
+                    break;
+                }
+                let zero_run_length = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                res.zero_run_length[i].push(zero_run_length);
+                map_unit_cnt += zero_run_length as u64 + 1;
+            }
+            decoder_formatted_print(
+                "SEI (Spare picture): zero_run_length",
+                &res.zero_run_length[i],
+                63,
+            );
+        }
+
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_spare_pic - ran out of bytes at spare pic {}",
+                i
+            );
+            break;
+        }
+    }
+
+    Ok(res)
+}
+
+/// D.1.11 Scene information SEI message syntax
+fn decode_scene_info(bs: &mut ByteStream) -> Result<SEISceneInfo, DecodeError> {
+    let mut res = SEISceneInfo::new();
+
+    res.scene_info_present_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Scene info): scene_info_present_flag",
+        res.scene_info_present_flag,
+        63,
+    );
+    if res.scene_info_present_flag {
+        res.scene_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print("SEI (Scene info): scene_id", res.scene_id, 63);
+        res.scene_transition_type = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Scene info): scene_transition_type",
+            res.scene_transition_type,
+            63,
+        );
+        if res.scene_transition_type > 3 {
+            res.second_scene_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print("SEI (Scene info): second_scene_id", res.second_scene_id, 63);
+        }
+    }
+
+    Ok(res)
+}
+
+/// D.1.12 Sub-sequence information SEI message syntax
+fn decode_sub_seq_info(bs: &mut ByteStream) -> Result<SEISubSeqInfo, DecodeError> {
+    let mut res = SEISubSeqInfo::new();
+
+    res.sub_seq_layer_num = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Sub-sequence info): sub_seq_layer_num",
+        res.sub_seq_layer_num,
+        63,
+    );
+    res.sub_seq_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("SEI (Sub-sequence info): sub_seq_id", res.sub_seq_id, 63);
+    res.first_ref_pic_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Sub-sequence info): first_ref_pic_flag",
+        res.first_ref_pic_flag,
+        63,
+    );
+    res.leading_non_ref_pic_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Sub-sequence info): leading_non_ref_pic_flag",
+        res.leading_non_ref_pic_flag,
+        63,
+    );
+    res.last_pic_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Sub-sequence info): last_pic_flag",
+        res.last_pic_flag,
+        63,
+    );
+    res.sub_seq_frame_num_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Sub-sequence info): sub_seq_frame_num_flag",
+        res.sub_seq_frame_num_flag,
+        63,
+    );
+    if res.sub_seq_frame_num_flag {
+        res.sub_seq_frame_num = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Sub-sequence info): sub_seq_frame_num",
+            res.sub_seq_frame_num,
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// D.1.13 Sub-sequence layer characteristics SEI message syntax
+fn decode_sub_seq_layer_characteristics(
+    bs: &mut ByteStream,
+) -> Result<SEISubSeqLayerCharacteristics, DecodeError> {
+    let mut res = SEISubSeqLayerCharacteristics::new();
+
+    res.num_sub_seq_layers_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Sub-sequence layer characteristics): num_sub_seq_layers_minus1",
+        res.num_sub_seq_layers_minus1,
+        63,
+    );
+    for layer in 0..=(res.num_sub_seq_layers_minus1 as usize) {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_sub_seq_layer_characteristics - ran out of bytes at layer {}",
+                layer
+            );
+            break;
+        }
+        res.accurate_statistics_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Sub-sequence layer characteristics): accurate_statistics_flag",
+            res.accurate_statistics_flag[layer],
+            63,
+        );
+        res.average_bit_rate.push(bs.read_bits(16)?);
+        decoder_formatted_print(
+            "SEI (Sub-sequence layer characteristics): average_bit_rate",
+            res.average_bit_rate[layer],
+            63,
+        );
+        res.average_frame_rate.push(bs.read_bits(16)?);
+        decoder_formatted_print(
+            "SEI (Sub-sequence layer characteristics): average_frame_rate",
+            res.average_frame_rate[layer],
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// D.1.14 Sub-sequence characteristics SEI message syntax
+fn decode_sub_seq_characteristics(
+    bs: &mut ByteStream,
+) -> Result<SEISubSeqCharacteristics, DecodeError> {
+    let mut res = SEISubSeqCharacteristics::new();
+
+    res.sub_seq_layer_num = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Sub-sequence characteristics): sub_seq_layer_num",
+        res.sub_seq_layer_num,
+        63,
+    );
+    res.sub_seq_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Sub-sequence characteristics): sub_seq_id",
+        res.sub_seq_id,
+        63,
+    );
+    res.duration_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Sub-sequence characteristics): duration_flag",
+        res.duration_flag,
+        63,
+    );
+    if res.duration_flag {
+        res.sub_seq_duration = bs.read_bits(32)?;
+        decoder_formatted_print(
+            "SEI (Sub-sequence characteristics): sub_seq_duration",
+            res.sub_seq_duration,
+            63,
+        );
+    }
+    res.average_rate_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Sub-sequence characteristics): average_rate_flag",
+        res.average_rate_flag,
+        63,
+    );
+    if res.average_rate_flag {
+        res.accurate_statistics_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Sub-sequence characteristics): accurate_statistics_flag",
+            res.accurate_statistics_flag,
+            63,
+        );
+        res.average_bit_rate = bs.read_bits(16)?;
+        decoder_formatted_print(
+            "SEI (Sub-sequence characteristics): average_bit_rate",
+            res.average_bit_rate,
+            63,
+        );
+        res.average_frame_rate = bs.read_bits(16)?;
+        decoder_formatted_print(
+            "SEI (Sub-sequence characteristics): average_frame_rate",
+            res.average_frame_rate,
+            63,
+        );
+    }
+    res.num_referenced_subseqs = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Sub-sequence characteristics): num_referenced_subseqs",
+        res.num_referenced_subseqs,
+        63,
+    );
+    for n in 0..(res.num_referenced_subseqs as usize) {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_sub_seq_characteristics - ran out of bytes at referenced subseq {}",
+                n
+            );
+            break;
+        }
+        res.ref_sub_seq_layer_num
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Sub-sequence characteristics): ref_sub_seq_layer_num",
+            res.ref_sub_seq_layer_num[n],
+            63,
+        );
+        res.ref_sub_seq_id
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Sub-sequence characteristics): ref_sub_seq_id",
+            res.ref_sub_seq_id[n],
+            63,
+        );
+        res.ref_sub_seq_direction.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Sub-sequence characteristics): ref_sub_seq_direction",
+            res.ref_sub_seq_direction[n],
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// D.1.15 Full-frame freeze SEI message syntax
+fn decode_full_frame_freeze(bs: &mut ByteStream) -> Result<SEIFullFrameFreeze, DecodeError> {
+    let mut res = SEIFullFrameFreeze::new();
+
+    res.full_frame_freeze_repetition_period = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Full-frame freeze): full_frame_freeze_repetition_period",
+        res.full_frame_freeze_repetition_period,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// D.1.16 Full-frame freeze release SEI message syntax
+///
+/// The payload is empty so there is nothing to parse
+fn decode_full_frame_freeze_release() {}
+
+/// D.1.17 Full-frame snapshot SEI message syntax
+fn decode_full_frame_snapshot(bs: &mut ByteStream) -> Result<SEIFullFrameSnapshot, DecodeError> {
+    let mut res = SEIFullFrameSnapshot::new();
+
+    res.snapshot_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Full-frame snapshot): snapshot_id",
+        res.snapshot_id,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// D.1.18 Progressive refinement segment start SEI message syntax
+fn decode_progressive_refinement_segment_start(
+    bs: &mut ByteStream,
+) -> Result<SEIProgressiveRefinementSegmentStart, DecodeError> {
+    let mut res = SEIProgressiveRefinementSegmentStart::new();
+
+    res.progressive_refinement_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Progressive refinement segment start): progressive_refinement_id",
+        res.progressive_refinement_id,
+        63,
+    );
+    res.num_refinement_steps_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Progressive refinement segment start): num_refinement_steps_minus1",
+        res.num_refinement_steps_minus1,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// D.1.19 Progressive refinement segment end SEI message syntax
+fn decode_progressive_refinement_segment_end(
+    bs: &mut ByteStream,
+) -> Result<SEIProgressiveRefinementSegmentEnd, DecodeError> {
+    let mut res = SEIProgressiveRefinementSegmentEnd::new();
+
+    res.progressive_refinement_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Progressive refinement segment end): progressive_refinement_id",
+        res.progressive_refinement_id,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// D.1.20 Motion-constrained slice group set SEI message syntax
+fn decode_motion_constrained_slice_group_set(_payload_size: u32, _bs: &mut ByteStream) {}
+
+/// D.1.21 Film grain characteristics SEI message syntax
+fn decode_film_grain_characteristics(
+    bs: &mut ByteStream,
+) -> Result<SEIFilmGrainCharacteristics, DecodeError> {
+    let mut fgc = SEIFilmGrainCharacteristics::new();
+
+    fgc.film_grain_characteristics_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Film Grain Characteristics): film_grain_characteristics_cancel_flag",
+        fgc.film_grain_characteristics_cancel_flag,
+        63,
+    );
+
+    if !fgc.film_grain_characteristics_cancel_flag {
+        fgc.film_grain_model_id = bs.read_bits(2)? as u8;
+        decoder_formatted_print(
+            "SEI (Film Grain Characteristics): film_grain_model_id",
+            fgc.film_grain_model_id,
+            63,
+        );
+
+        fgc.separate_colour_description_present_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Film Grain Characteristics): separate_colour_description_present_flag",
+            fgc.separate_colour_description_present_flag,
+            63,
+        );
+
+        if fgc.separate_colour_description_present_flag {
+            fgc.film_grain_bit_depth_luma_minus8 = bs.read_bits(3)? as u8;
+            decoder_formatted_print(
+                "SEI (Film Grain Characteristics): film_grain_bit_depth_luma_minus8",
+                fgc.film_grain_bit_depth_luma_minus8,
+                63,
+            );
+
+            fgc.film_grain_bit_depth_chroma_minus8 = bs.read_bits(3)? as u8;
             decoder_formatted_print(
                 "SEI (Film Grain Characteristics): film_grain_bit_depth_chroma_minus8",
                 &fgc.film_grain_bit_depth_chroma_minus8,
                 63,
             );
 
-            fgc.film_grain_full_range_flag = 1 == bs.read_bits(1);
+            fgc.film_grain_full_range_flag = 1 == bs.read_bits(1)?;
             decoder_formatted_print(
                 "SEI (Film Grain Characteristics): film_grain_full_range_flag",
                 &fgc.film_grain_full_range_flag,
                 63,
             );
 
-            fgc.film_grain_colour_primaries = bs.read_bits(8) as u8;
+            fgc.film_grain_colour_primaries = bs.read_bits(8)? as u8;
             decoder_formatted_print(
                 "SEI (Film Grain Characteristics): film_grain_colour_primaries",
                 &fgc.film_grain_colour_primaries,
                 63,
             );
 
-            fgc.film_grain_transfer_characteristics = bs.read_bits(8) as u8;
+            fgc.film_grain_transfer_characteristics = bs.read_bits(8)? as u8;
             decoder_formatted_print(
                 "SEI (Film Grain Characteristics): film_grain_transfer_characteristics",
                 &fgc.film_grain_transfer_characteristics,
                 63,
             );
 
-            fgc.film_grain_matrix_coefficients = bs.read_bits(8) as u8;
+            fgc.film_grain_matrix_coefficients = bs.read_bits(8)? as u8;
             decoder_formatted_print(
                 "SEI (Film Grain Characteristics): film_grain_matrix_coefficients",
                 &fgc.film_grain_matrix_coefficients,
                 63,
             );
         }
-        fgc.blending_mode_id = bs.read_bits(2) as u8;
+        fgc.blending_mode_id = bs.read_bits(2)? as u8;
         decoder_formatted_print(
             "SEI (Film Grain Characteristics): blending_mode_id",
             &fgc.blending_mode_id,
             63,
         );
 
-        fgc.log2_scale_factor = bs.read_bits(4) as u8;
+        fgc.log2_scale_factor = bs.read_bits(4)? as u8;
         decoder_formatted_print(
             "SEI (Film Grain Characteristics): log2_scale_factor",
             &fgc.log2_scale_factor,
@@ -1112,7 +1938,7 @@ fn decode_film_grain_characteristics(bs: &mut ByteStream) -> SEIFilmGrainCharact
         );
 
         for c in 0..3 {
-            fgc.comp_model_present_flag.push(1 == bs.read_bits(1));
+            fgc.comp_model_present_flag.push(1 == bs.read_bits(1)?);
             decoder_formatted_print(
                 "SEI (Film Grain Characteristics): comp_model_present_flag",
                 &fgc.comp_model_present_flag[c],
@@ -1123,13 +1949,13 @@ fn decode_film_grain_characteristics(bs: &mut ByteStream) -> SEIFilmGrainCharact
         for c in 0..3 {
             if fgc.comp_model_present_flag[c] {
                 fgc.num_intensity_intervals_minus1
-                    .push(bs.read_bits(8) as u8);
+                    .push(bs.read_bits(8)? as u8);
                 decoder_formatted_print(
                     "SEI (Film Grain Characteristics): num_intensity_intervals_minus1",
                     &fgc.num_intensity_intervals_minus1[c],
                     63,
                 );
-                fgc.num_model_values_minus1.push(bs.read_bits(3) as u8);
+                fgc.num_model_values_minus1.push(bs.read_bits(3)? as u8);
                 decoder_formatted_print(
                     "SEI (Film Grain Characteristics): num_model_values_minus1",
                     &fgc.num_model_values_minus1[c],
@@ -1140,14 +1966,14 @@ fn decode_film_grain_characteristics(bs: &mut ByteStream) -> SEIFilmGrainCharact
                 fgc.intensity_interval_upper_bound.push(Vec::new());
                 fgc.comp_model_value.push(Vec::new());
                 for i in 0..fgc.num_intensity_intervals_minus1[c] as usize {
-                    fgc.intensity_interval_lower_bound[c].push(bs.read_bits(8) as u8);
+                    fgc.intensity_interval_lower_bound[c].push(bs.read_bits(8)? as u8);
                     decoder_formatted_print(
                         "SEI (Film Grain Characteristics): intensity_interval_lower_bound",
                         &fgc.intensity_interval_lower_bound[c][i],
                         63,
                     );
 
-                    fgc.intensity_interval_upper_bound[c].push(bs.read_bits(8) as u8);
+                    fgc.intensity_interval_upper_bound[c].push(bs.read_bits(8)? as u8);
                     decoder_formatted_print(
                         "SEI (Film Grain Characteristics): intensity_interval_upper_bound",
                         &fgc.intensity_interval_upper_bound[c][i],
@@ -1156,7 +1982,8 @@ fn decode_film_grain_characteristics(bs: &mut ByteStream) -> SEIFilmGrainCharact
 
                     fgc.comp_model_value[c].push(Vec::new());
                     for j in 0..fgc.num_model_values_minus1[c] as usize {
-                        fgc.comp_model_value[c][i].push(exp_golomb_decode_one_wrapper(bs, true, 0));
+                        fgc.comp_model_value[c][i]
+                            .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
                         decoder_formatted_print(
                             "SEI (Film Grain Characteristics): comp_model_value",
                             &fgc.comp_model_value[c][i][j],
@@ -1173,7 +2000,7 @@ fn decode_film_grain_characteristics(bs: &mut ByteStream) -> SEIFilmGrainCharact
             }
         }
         fgc.film_grain_characteristics_repetition_period =
-            exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+            exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SEI (Film Grain Characteristics): film_grain_characteristics_repetition_period",
             &fgc.film_grain_characteristics_repetition_period,
@@ -1181,7 +2008,7 @@ fn decode_film_grain_characteristics(bs: &mut ByteStream) -> SEIFilmGrainCharact
         );
     }
 
-    fgc
+    Ok(fgc)
 }
 
 /// D.1.22 Deblocking filter display preference SEI message syntax
@@ -1193,268 +2020,4748 @@ fn decode_stereo_video_info(_payload_size: u32, _bs: &mut ByteStream) {}
 /// D.1.24 Post-filter hint SEI message syntax
 fn decode_post_filter_hint(_payload_size: u32, _bs: &mut ByteStream) {}
 
-/// D.1.25 Tone mapping information SEI message syntax
-fn decode_tone_mapping_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.1 Scalability information SEI message syntax
-fn decode_scalability_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.2Sub-picture scalable layer SEI message syntax
-fn decode_sub_pic_scalable_layer(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.3Non-required layer representation SEI message syntax
-fn decode_non_required_layer_rep(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.4Priority layer information SEI message syntax
-fn decode_priority_layer_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.5Layers not present SEI message syntax
-fn decode_layers_not_present(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.6Layer dependency change SEI message syntax
-fn decode_layer_dependency_change(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.7Scalable nesting SEI message syntax
-fn decode_scalable_nesting(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.8Base layer temporal HRD SEI message syntax
-fn decode_base_layer_temporal_hrd(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.9Quality layer integrity check SEI message syntax
-fn decode_quality_layer_integrity_check(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.10 Redundant picture property SEI message syntax
-fn decode_redundant_pic_property(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.11 Temporal level zero dependency representation index SEI message syntax
-fn decode_tl0_dep_rep_index(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// G.13.1.12 Temporal level switching point SEI message syntax
-fn decode_tl_switching_point(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// H.13.1.1Parallel decoding information SEI message syntax
-fn decode_parallel_decoding_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// H.13.1.2MVC scalable nesting SEI message syntax
-fn decode_mvc_scalable_nesting(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// H.13.1.3 View scalability information SEI message syntax
-fn decode_view_scalability_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// H.13.1.4 Multiview scene information SEI message syntax
-fn decode_multiview_scene_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// H.13.1.5 Multiview acquisition information SEI message syntax
-fn decode_multiview_acquisition_info(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// H.13.1.6Non-required view component SEI message syntax
-fn decode_non_required_view_component(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// H.13.1.7View dependency change SEI message syntax
-fn decode_view_dependency_change(_payload_size: u32, _bs: &mut ByteStream) {}
-
-/// H.13.1.8Operation point not present SEI message syntax
-fn decode_operation_points_not_present(_payload_size: u32, _bs: &mut ByteStream) {}
+/// Reads a u(v) element whose length may exceed 32 bits; the leading bits
+/// beyond the lowest 32 are skipped
+fn read_wide_bits(bs: &mut ByteStream, bits: u32) -> Result<u32, DecodeError> {
+    let mut remaining = bits;
+    while remaining > 32 {
+        let to_skip = std::cmp::min(remaining - 32, 32);
+        bs.read_bits(to_skip as u8)?;
+        remaining -= to_skip;
+    }
+    bs.read_bits(remaining as u8)
+}
 
-/// H.13.1.9Base view temporal HRD SEI message syntax
-fn decode_base_view_temporal_hrd(_payload_size: u32, _bs: &mut ByteStream) {}
+/// D.1.25 Tone mapping information SEI message syntax
+fn decode_tone_mapping_info(bs: &mut ByteStream) -> Result<SEIToneMappingInfo, DecodeError> {
+    let mut res = SEIToneMappingInfo::new();
 
-/// D.1.26 Frame packing arrangement SEI message syntax
-fn decode_frame_packing_arrangement(_payload_size: u32, bs: &mut ByteStream) {
-    let frame_packing_arrangement_id = exp_golomb_decode_one_wrapper(bs, false, 0);
-    decoder_formatted_print(
-        "SEI (Frame Packing): frame_packing_arrangement_id",
-        &frame_packing_arrangement_id,
-        63,
-    );
-    let frame_packing_arrangement_cancel_flag = 1 == bs.read_bits(1);
+    res.tone_map_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("SEI (Tone mapping): tone_map_id", res.tone_map_id, 63);
+    res.tone_map_cancel_flag = 1 == bs.read_bits(1)?;
     decoder_formatted_print(
-        "SEI (Frame Packing): frame_packing_arrangement_cancel_flag",
-        &frame_packing_arrangement_cancel_flag,
+        "SEI (Tone mapping): tone_map_cancel_flag",
+        res.tone_map_cancel_flag,
         63,
     );
-    if !frame_packing_arrangement_cancel_flag {
-        let frame_packing_arrangement_type = bs.read_bits(7);
-        decoder_formatted_print(
-            "SEI (Frame Packing): frame_packing_arrangement_type",
-            &frame_packing_arrangement_type,
-            63,
-        );
-        let quincunx_sampling_flag = 1 == bs.read_bits(1);
-        decoder_formatted_print(
-            "SEI (Frame Packing): quincunx_sampling_flag",
-            &quincunx_sampling_flag,
-            63,
-        );
-        let content_interpretation_type = bs.read_bits(6);
-        decoder_formatted_print(
-            "SEI (Frame Packing): content_interpretation_type",
-            &content_interpretation_type,
-            63,
-        );
-        let spatial_flipping_flag = 1 == bs.read_bits(1);
-        decoder_formatted_print(
-            "SEI (Frame Packing): spatial_flipping_flag",
-            &spatial_flipping_flag,
-            63,
-        );
-        let frame0_flipped_flag = 1 == bs.read_bits(1);
-        decoder_formatted_print(
-            "SEI (Frame Packing): frame0_flipped_flag",
-            &frame0_flipped_flag,
-            63,
-        );
-        let field_views_flag = 1 == bs.read_bits(1);
+    if !res.tone_map_cancel_flag {
+        res.tone_map_repetition_period = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
-            "SEI (Frame Packing): field_views_flag",
-            &field_views_flag,
+            "SEI (Tone mapping): tone_map_repetition_period",
+            res.tone_map_repetition_period,
             63,
         );
-        let current_frame_is_frame0_flag = 1 == bs.read_bits(1);
+        res.coded_data_bit_depth = bs.read_bits(8)? as u8;
         decoder_formatted_print(
-            "SEI (Frame Packing): current_frame_is_frame0_flag",
-            &current_frame_is_frame0_flag,
+            "SEI (Tone mapping): coded_data_bit_depth",
+            res.coded_data_bit_depth,
             63,
         );
-        let frame0_self_contained_flag = 1 == bs.read_bits(1);
+        res.target_bit_depth = bs.read_bits(8)? as u8;
         decoder_formatted_print(
-            "SEI (Frame Packing): frame0_self_contained_flag",
-            &frame0_self_contained_flag,
+            "SEI (Tone mapping): target_bit_depth",
+            res.target_bit_depth,
             63,
         );
-        let frame1_self_contained_flag = 1 == bs.read_bits(1);
+        res.tone_map_model_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
-            "SEI (Frame Packing): frame1_self_contained_flag",
-            &frame1_self_contained_flag,
+            "SEI (Tone mapping): tone_map_model_id",
+            res.tone_map_model_id,
             63,
         );
-        if !quincunx_sampling_flag && frame_packing_arrangement_type != 5 {
-            let frame0_grid_position_x = bs.read_bits(4);
-            decoder_formatted_print(
-                "SEI (Frame Packing): frame0_grid_position_x",
-                &frame0_grid_position_x,
+
+        let coded_bits = ((res.coded_data_bit_depth as u32 + 7) >> 3) << 3;
+        let target_bits = ((res.target_bit_depth as u32 + 7) >> 3) << 3;
+        match res.tone_map_model_id {
+            0 => {
+                res.min_value = bs.read_bits(32)?;
+                decoder_formatted_print("SEI (Tone mapping): min_value", res.min_value, 63);
+                res.max_value = bs.read_bits(32)?;
+                decoder_formatted_print("SEI (Tone mapping): max_value", res.max_value, 63);
+            }
+            1 => {
+                res.sigmoid_midpoint = bs.read_bits(32)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): sigmoid_midpoint",
+                    res.sigmoid_midpoint,
+                    63,
+                );
+                res.sigmoid_width = bs.read_bits(32)?;
+                decoder_formatted_print("SEI (Tone mapping): sigmoid_width", res.sigmoid_width, 63);
+            }
+            2 => {
+                let num_intervals = 1u64
+                    .checked_shl(res.target_bit_depth as u32)
+                    .unwrap_or(u64::MAX);
+                for i in 0..num_intervals {
+                    if bs.bytestream.is_empty() {
+                        println!(
+                            "[WARNING] decode_tone_mapping_info - ran out of bytes at start_of_coded_interval {}",
+                            i
+                        );
+                        break;
+                    }
+                    res.start_of_coded_interval
+                        .push(read_wide_bits(bs, coded_bits)?);
+                }
+                decoder_formatted_print(
+                    "SEI (Tone mapping): start_of_coded_interval",
+                    &res.start_of_coded_interval,
+                    63,
+                );
+            }
+            3 => {
+                res.num_pivots = bs.read_bits(16)?;
+                decoder_formatted_print("SEI (Tone mapping): num_pivots", res.num_pivots, 63);
+                for i in 0..res.num_pivots {
+                    if bs.bytestream.is_empty() {
+                        println!(
+                            "[WARNING] decode_tone_mapping_info - ran out of bytes at pivot {}",
+                            i
+                        );
+                        break;
+                    }
+                    res.coded_pivot_value.push(read_wide_bits(bs, coded_bits)?);
+                    res.target_pivot_value
+                        .push(read_wide_bits(bs, target_bits)?);
+                }
+                decoder_formatted_print(
+                    "SEI (Tone mapping): coded_pivot_value",
+                    &res.coded_pivot_value,
+                    63,
+                );
+                decoder_formatted_print(
+                    "SEI (Tone mapping): target_pivot_value",
+                    &res.target_pivot_value,
+                    63,
+                );
+            }
+            4 => {
+                res.camera_iso_speed_idc = bs.read_bits(8)? as u8;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): camera_iso_speed_idc",
+                    res.camera_iso_speed_idc,
+                    63,
+                );
+                // Extended_ISO
+                if res.camera_iso_speed_idc == 255 {
+                    res.camera_iso_speed_value = bs.read_bits(32)?;
+                    decoder_formatted_print(
+                        "SEI (Tone mapping): camera_iso_speed_value",
+                        res.camera_iso_speed_value,
+                        63,
+                    );
+                }
+                res.exposure_index_idc = bs.read_bits(8)? as u8;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): exposure_index_idc",
+                    res.exposure_index_idc,
+                    63,
+                );
+                // Extended_ISO
+                if res.exposure_index_idc == 255 {
+                    res.exposure_index_value = bs.read_bits(32)?;
+                    decoder_formatted_print(
+                        "SEI (Tone mapping): exposure_index_value",
+                        res.exposure_index_value,
+                        63,
+                    );
+                }
+                res.exposure_compensation_value_sign_flag = 1 == bs.read_bits(1)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): exposure_compensation_value_sign_flag",
+                    res.exposure_compensation_value_sign_flag,
+                    63,
+                );
+                res.exposure_compensation_value_numerator = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): exposure_compensation_value_numerator",
+                    res.exposure_compensation_value_numerator,
+                    63,
+                );
+                res.exposure_compensation_value_denom_idc = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): exposure_compensation_value_denom_idc",
+                    res.exposure_compensation_value_denom_idc,
+                    63,
+                );
+                res.ref_screen_luminance_white = bs.read_bits(32)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): ref_screen_luminance_white",
+                    res.ref_screen_luminance_white,
+                    63,
+                );
+                res.extended_range_white_level = bs.read_bits(32)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): extended_range_white_level",
+                    res.extended_range_white_level,
+                    63,
+                );
+                res.nominal_black_level_luma_code_value = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): nominal_black_level_luma_code_value",
+                    res.nominal_black_level_luma_code_value,
+                    63,
+                );
+                res.nominal_white_level_luma_code_value = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): nominal_white_level_luma_code_value",
+                    res.nominal_white_level_luma_code_value,
+                    63,
+                );
+                res.extended_white_level_luma_code_value = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Tone mapping): extended_white_level_luma_code_value",
+                    res.extended_white_level_luma_code_value,
+                    63,
+                );
+            }
+            _ => {
+                println!(
+                    "[WARNING] decode_tone_mapping_info - reserved tone_map_model_id {}",
+                    res.tone_map_model_id
+                );
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.1 Scalability information SEI message syntax
+fn decode_scalability_info(bs: &mut ByteStream) -> Result<SEIScalabilityInfo, DecodeError> {
+    let mut res = SEIScalabilityInfo::new();
+
+    res.temporal_id_nesting_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Scalability info): temporal_id_nesting_flag",
+        res.temporal_id_nesting_flag,
+        63,
+    );
+    res.priority_layer_info_present_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Scalability info): priority_layer_info_present_flag",
+        res.priority_layer_info_present_flag,
+        63,
+    );
+    res.priority_id_setting_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Scalability info): priority_id_setting_flag",
+        res.priority_id_setting_flag,
+        63,
+    );
+    res.num_layers_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Scalability info): num_layers_minus1",
+        res.num_layers_minus1,
+        63,
+    );
+
+    for i in 0..=res.num_layers_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_scalability_info - ran out of bytes at layer {} of {}",
+                i,
+                res.num_layers_minus1 + 1
+            );
+            break;
+        }
+        res.layer_id
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print("SEI (Scalability info): layer_id", res.layer_id[i], 63);
+        res.priority_id.push(bs.read_bits(6)? as u8);
+        decoder_formatted_print(
+            "SEI (Scalability info): priority_id",
+            res.priority_id[i],
+            63,
+        );
+        res.discardable_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): discardable_flag",
+            res.discardable_flag[i],
+            63,
+        );
+        res.dependency_id.push(bs.read_bits(3)? as u8);
+        decoder_formatted_print(
+            "SEI (Scalability info): dependency_id",
+            res.dependency_id[i],
+            63,
+        );
+        res.quality_id.push(bs.read_bits(4)? as u8);
+        decoder_formatted_print("SEI (Scalability info): quality_id", res.quality_id[i], 63);
+        res.temporal_id.push(bs.read_bits(3)? as u8);
+        decoder_formatted_print(
+            "SEI (Scalability info): temporal_id",
+            res.temporal_id[i],
+            63,
+        );
+        res.sub_pic_layer_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): sub_pic_layer_flag",
+            res.sub_pic_layer_flag[i],
+            63,
+        );
+        res.sub_region_layer_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): sub_region_layer_flag",
+            res.sub_region_layer_flag[i],
+            63,
+        );
+        res.iroi_division_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): iroi_division_info_present_flag",
+            res.iroi_division_info_present_flag[i],
+            63,
+        );
+        res.profile_level_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): profile_level_info_present_flag",
+            res.profile_level_info_present_flag[i],
+            63,
+        );
+        res.bitrate_info_present_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): bitrate_info_present_flag",
+            res.bitrate_info_present_flag[i],
+            63,
+        );
+        res.frm_rate_info_present_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): frm_rate_info_present_flag",
+            res.frm_rate_info_present_flag[i],
+            63,
+        );
+        res.frm_size_info_present_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): frm_size_info_present_flag",
+            res.frm_size_info_present_flag[i],
+            63,
+        );
+        res.layer_dependency_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): layer_dependency_info_present_flag",
+            res.layer_dependency_info_present_flag[i],
+            63,
+        );
+        res.parameter_sets_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): parameter_sets_info_present_flag",
+            res.parameter_sets_info_present_flag[i],
+            63,
+        );
+        res.bitstream_restriction_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): bitstream_restriction_info_present_flag",
+            res.bitstream_restriction_info_present_flag[i],
+            63,
+        );
+        res.exact_inter_layer_pred_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): exact_inter_layer_pred_flag",
+            res.exact_inter_layer_pred_flag[i],
+            63,
+        );
+        if res.sub_pic_layer_flag[i] || res.iroi_division_info_present_flag[i] {
+            res.exact_sample_value_match_flag
+                .push(1 == bs.read_bits(1)?);
+            decoder_formatted_print(
+                "SEI (Scalability info): exact_sample_value_match_flag",
+                res.exact_sample_value_match_flag[i],
+                63,
+            );
+        } else {
+            res.exact_sample_value_match_flag.push(false);
+        }
+        res.layer_conversion_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): layer_conversion_flag",
+            res.layer_conversion_flag[i],
+            63,
+        );
+        res.layer_output_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Scalability info): layer_output_flag",
+            res.layer_output_flag[i],
+            63,
+        );
+
+        if res.profile_level_info_present_flag[i] {
+            res.layer_profile_level_idc.push(bs.read_bits(24)?);
+            decoder_formatted_print(
+                "SEI (Scalability info): layer_profile_level_idc",
+                res.layer_profile_level_idc[i],
+                63,
+            );
+        } else {
+            res.layer_profile_level_idc.push(0);
+        }
+
+        if res.bitrate_info_present_flag[i] {
+            res.avg_bitrate.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (Scalability info): avg_bitrate",
+                res.avg_bitrate[i],
+                63,
+            );
+            res.max_bitrate_layer.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (Scalability info): max_bitrate_layer",
+                res.max_bitrate_layer[i],
+                63,
+            );
+            res.max_bitrate_layer_representation.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (Scalability info): max_bitrate_layer_representation",
+                res.max_bitrate_layer_representation[i],
+                63,
+            );
+            res.max_bitrate_calc_window.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (Scalability info): max_bitrate_calc_window",
+                res.max_bitrate_calc_window[i],
+                63,
+            );
+        } else {
+            res.avg_bitrate.push(0);
+            res.max_bitrate_layer.push(0);
+            res.max_bitrate_layer_representation.push(0);
+            res.max_bitrate_calc_window.push(0);
+        }
+
+        if res.frm_rate_info_present_flag[i] {
+            res.constant_frm_rate_idc.push(bs.read_bits(2)? as u8);
+            decoder_formatted_print(
+                "SEI (Scalability info): constant_frm_rate_idc",
+                res.constant_frm_rate_idc[i],
+                63,
+            );
+            res.avg_frm_rate.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (Scalability info): avg_frm_rate",
+                res.avg_frm_rate[i],
+                63,
+            );
+        } else {
+            res.constant_frm_rate_idc.push(0);
+            res.avg_frm_rate.push(0);
+        }
+
+        if res.frm_size_info_present_flag[i] || res.iroi_division_info_present_flag[i] {
+            res.frm_width_in_mbs_minus1
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): frm_width_in_mbs_minus1",
+                res.frm_width_in_mbs_minus1[i],
+                63,
+            );
+            res.frm_height_in_mbs_minus1
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): frm_height_in_mbs_minus1",
+                res.frm_height_in_mbs_minus1[i],
+                63,
+            );
+        } else {
+            res.frm_width_in_mbs_minus1.push(0);
+            res.frm_height_in_mbs_minus1.push(0);
+        }
+
+        res.dynamic_rect_flag.push(false);
+        res.horizontal_offset.push(0);
+        res.vertical_offset.push(0);
+        res.region_width.push(0);
+        res.region_height.push(0);
+        if res.sub_region_layer_flag[i] {
+            res.base_region_layer_id
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): base_region_layer_id",
+                res.base_region_layer_id[i],
+                63,
+            );
+            res.dynamic_rect_flag[i] = 1 == bs.read_bits(1)?;
+            decoder_formatted_print(
+                "SEI (Scalability info): dynamic_rect_flag",
+                res.dynamic_rect_flag[i],
+                63,
+            );
+            if !res.dynamic_rect_flag[i] {
+                res.horizontal_offset[i] = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Scalability info): horizontal_offset",
+                    res.horizontal_offset[i],
+                    63,
+                );
+                res.vertical_offset[i] = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Scalability info): vertical_offset",
+                    res.vertical_offset[i],
+                    63,
+                );
+                res.region_width[i] = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Scalability info): region_width",
+                    res.region_width[i],
+                    63,
+                );
+                res.region_height[i] = bs.read_bits(16)?;
+                decoder_formatted_print(
+                    "SEI (Scalability info): region_height",
+                    res.region_height[i],
+                    63,
+                );
+            }
+        } else {
+            res.base_region_layer_id.push(0);
+        }
+
+        if res.sub_pic_layer_flag[i] {
+            res.roi_id
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print("SEI (Scalability info): roi_id", res.roi_id[i], 63);
+        } else {
+            res.roi_id.push(0);
+        }
+
+        res.iroi_grid_flag.push(false);
+        res.grid_width_in_mbs_minus1.push(0);
+        res.grid_height_in_mbs_minus1.push(0);
+        res.num_rois_minus1.push(0);
+        res.first_mb_in_roi.push(Vec::new());
+        res.roi_width_in_mbs_minus1.push(Vec::new());
+        res.roi_height_in_mbs_minus1.push(Vec::new());
+        if res.iroi_division_info_present_flag[i] {
+            res.iroi_grid_flag[i] = 1 == bs.read_bits(1)?;
+            decoder_formatted_print(
+                "SEI (Scalability info): iroi_grid_flag",
+                res.iroi_grid_flag[i],
+                63,
+            );
+            if res.iroi_grid_flag[i] {
+                res.grid_width_in_mbs_minus1[i] =
+                    exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                decoder_formatted_print(
+                    "SEI (Scalability info): grid_width_in_mbs_minus1",
+                    res.grid_width_in_mbs_minus1[i],
+                    63,
+                );
+                res.grid_height_in_mbs_minus1[i] =
+                    exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                decoder_formatted_print(
+                    "SEI (Scalability info): grid_height_in_mbs_minus1",
+                    res.grid_height_in_mbs_minus1[i],
+                    63,
+                );
+            } else {
+                res.num_rois_minus1[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                decoder_formatted_print(
+                    "SEI (Scalability info): num_rois_minus1",
+                    res.num_rois_minus1[i],
+                    63,
+                );
+                for j in 0..=res.num_rois_minus1[i] as usize {
+                    if bs.bytestream.is_empty() {
+                        println!(
+                            "[WARNING] decode_scalability_info - ran out of bytes at roi {} of layer {}",
+                            j, i
+                        );
+                        break;
+                    }
+                    res.first_mb_in_roi[i]
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                    decoder_formatted_print(
+                        "SEI (Scalability info): first_mb_in_roi",
+                        res.first_mb_in_roi[i][j],
+                        63,
+                    );
+                    res.roi_width_in_mbs_minus1[i]
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                    decoder_formatted_print(
+                        "SEI (Scalability info): roi_width_in_mbs_minus1",
+                        res.roi_width_in_mbs_minus1[i][j],
+                        63,
+                    );
+                    res.roi_height_in_mbs_minus1[i]
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                    decoder_formatted_print(
+                        "SEI (Scalability info): roi_height_in_mbs_minus1",
+                        res.roi_height_in_mbs_minus1[i][j],
+                        63,
+                    );
+                }
+            }
+        }
+
+        res.num_directly_dependent_layers.push(0);
+        res.directly_dependent_layer_id_delta_minus1
+            .push(Vec::new());
+        res.layer_dependency_info_src_layer_id_delta.push(0);
+        if res.layer_dependency_info_present_flag[i] {
+            res.num_directly_dependent_layers[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Scalability info): num_directly_dependent_layers",
+                res.num_directly_dependent_layers[i],
+                63,
+            );
+            for j in 0..res.num_directly_dependent_layers[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_scalability_info - ran out of bytes at directly dependent layer {} of layer {}", j, i);
+                    break;
+                }
+                res.directly_dependent_layer_id_delta_minus1[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (Scalability info): directly_dependent_layer_id_delta_minus1",
+                    res.directly_dependent_layer_id_delta_minus1[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.layer_dependency_info_src_layer_id_delta[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Scalability info): layer_dependency_info_src_layer_id_delta",
+                res.layer_dependency_info_src_layer_id_delta[i],
+                63,
+            );
+        }
+
+        res.num_seq_parameter_sets.push(0);
+        res.seq_parameter_set_id_delta.push(Vec::new());
+        res.num_subset_seq_parameter_sets.push(0);
+        res.subset_seq_parameter_set_id_delta.push(Vec::new());
+        res.num_pic_parameter_sets_minus1.push(0);
+        res.pic_parameter_set_id_delta.push(Vec::new());
+        res.parameter_sets_info_src_layer_id_delta.push(0);
+        if res.parameter_sets_info_present_flag[i] {
+            res.num_seq_parameter_sets[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Scalability info): num_seq_parameter_sets",
+                res.num_seq_parameter_sets[i],
+                63,
+            );
+            for j in 0..res.num_seq_parameter_sets[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_scalability_info - ran out of bytes at seq_parameter_set_id_delta {} of layer {}", j, i);
+                    break;
+                }
+                res.seq_parameter_set_id_delta[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (Scalability info): seq_parameter_set_id_delta",
+                    res.seq_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+            res.num_subset_seq_parameter_sets[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Scalability info): num_subset_seq_parameter_sets",
+                res.num_subset_seq_parameter_sets[i],
+                63,
+            );
+            for j in 0..res.num_subset_seq_parameter_sets[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_scalability_info - ran out of bytes at subset_seq_parameter_set_id_delta {} of layer {}", j, i);
+                    break;
+                }
+                res.subset_seq_parameter_set_id_delta[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (Scalability info): subset_seq_parameter_set_id_delta",
+                    res.subset_seq_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+            res.num_pic_parameter_sets_minus1[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Scalability info): num_pic_parameter_sets_minus1",
+                res.num_pic_parameter_sets_minus1[i],
+                63,
+            );
+            for j in 0..=res.num_pic_parameter_sets_minus1[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_scalability_info - ran out of bytes at pic_parameter_set_id_delta {} of layer {}", j, i);
+                    break;
+                }
+                res.pic_parameter_set_id_delta[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (Scalability info): pic_parameter_set_id_delta",
+                    res.pic_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.parameter_sets_info_src_layer_id_delta[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Scalability info): parameter_sets_info_src_layer_id_delta",
+                res.parameter_sets_info_src_layer_id_delta[i],
+                63,
+            );
+        }
+
+        if res.bitstream_restriction_info_present_flag[i] {
+            res.motion_vectors_over_pic_boundaries_flag
+                .push(1 == bs.read_bits(1)?);
+            decoder_formatted_print(
+                "SEI (Scalability info): motion_vectors_over_pic_boundaries_flag",
+                res.motion_vectors_over_pic_boundaries_flag[i],
+                63,
+            );
+            res.max_bytes_per_pic_denom
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): max_bytes_per_pic_denom",
+                res.max_bytes_per_pic_denom[i],
+                63,
+            );
+            res.max_bits_per_mb_denom
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): max_bits_per_mb_denom",
+                res.max_bits_per_mb_denom[i],
+                63,
+            );
+            res.log2_max_mv_length_horizontal
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): log2_max_mv_length_horizontal",
+                res.log2_max_mv_length_horizontal[i],
+                63,
+            );
+            res.log2_max_mv_length_vertical
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): log2_max_mv_length_vertical",
+                res.log2_max_mv_length_vertical[i],
+                63,
+            );
+            res.max_num_reorder_frames
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): max_num_reorder_frames",
+                res.max_num_reorder_frames[i],
+                63,
+            );
+            res.max_dec_frame_buffering
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): max_dec_frame_buffering",
+                res.max_dec_frame_buffering[i],
+                63,
+            );
+        } else {
+            res.motion_vectors_over_pic_boundaries_flag.push(false);
+            res.max_bytes_per_pic_denom.push(0);
+            res.max_bits_per_mb_denom.push(0);
+            res.log2_max_mv_length_horizontal.push(0);
+            res.log2_max_mv_length_vertical.push(0);
+            res.max_num_reorder_frames.push(0);
+            res.max_dec_frame_buffering.push(0);
+        }
+
+        res.conversion_type_idc.push(0);
+        res.rewriting_info_flag.push([false; 2]);
+        res.rewriting_profile_level_idc.push([0; 2]);
+        res.rewriting_avg_bitrate.push([0; 2]);
+        res.rewriting_max_bitrate.push([0; 2]);
+        if res.layer_conversion_flag[i] {
+            res.conversion_type_idc[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Scalability info): conversion_type_idc",
+                res.conversion_type_idc[i],
+                63,
+            );
+            for j in 0..2 {
+                res.rewriting_info_flag[i][j] = 1 == bs.read_bits(1)?;
+                decoder_formatted_print(
+                    "SEI (Scalability info): rewriting_info_flag",
+                    res.rewriting_info_flag[i][j],
+                    63,
+                );
+                if res.rewriting_info_flag[i][j] {
+                    res.rewriting_profile_level_idc[i][j] = bs.read_bits(24)?;
+                    decoder_formatted_print(
+                        "SEI (Scalability info): rewriting_profile_level_idc",
+                        res.rewriting_profile_level_idc[i][j],
+                        63,
+                    );
+                    res.rewriting_avg_bitrate[i][j] = bs.read_bits(16)?;
+                    decoder_formatted_print(
+                        "SEI (Scalability info): rewriting_avg_bitrate",
+                        res.rewriting_avg_bitrate[i][j],
+                        63,
+                    );
+                    res.rewriting_max_bitrate[i][j] = bs.read_bits(16)?;
+                    decoder_formatted_print(
+                        "SEI (Scalability info): rewriting_max_bitrate",
+                        res.rewriting_max_bitrate[i][j],
+                        63,
+                    );
+                }
+            }
+        }
+    }
+
+    if res.priority_layer_info_present_flag {
+        res.pr_num_dids_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Scalability info): pr_num_dIds_minus1",
+            res.pr_num_dids_minus1,
+            63,
+        );
+        for i in 0..=res.pr_num_dids_minus1 as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_scalability_info - ran out of bytes at pr_dependency_id {}",
+                    i
+                );
+                break;
+            }
+            res.pr_dependency_id.push(bs.read_bits(3)? as u8);
+            decoder_formatted_print(
+                "SEI (Scalability info): pr_dependency_id",
+                res.pr_dependency_id[i],
+                63,
+            );
+            res.pr_num_minus1
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Scalability info): pr_num_minus1",
+                res.pr_num_minus1[i],
+                63,
+            );
+            res.pr_id.push(Vec::new());
+            res.pr_profile_level_idc.push(Vec::new());
+            res.pr_avg_bitrate.push(Vec::new());
+            res.pr_max_bitrate.push(Vec::new());
+            for j in 0..=res.pr_num_minus1[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!(
+                        "[WARNING] decode_scalability_info - ran out of bytes at pr_id {} of {}",
+                        j, i
+                    );
+                    break;
+                }
+                res.pr_id[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print("SEI (Scalability info): pr_id", res.pr_id[i][j], 63);
+                res.pr_profile_level_idc[i].push(bs.read_bits(24)?);
+                decoder_formatted_print(
+                    "SEI (Scalability info): pr_profile_level_idc",
+                    res.pr_profile_level_idc[i][j],
+                    63,
+                );
+                res.pr_avg_bitrate[i].push(bs.read_bits(16)?);
+                decoder_formatted_print(
+                    "SEI (Scalability info): pr_avg_bitrate",
+                    res.pr_avg_bitrate[i][j],
+                    63,
+                );
+                res.pr_max_bitrate[i].push(bs.read_bits(16)?);
+                decoder_formatted_print(
+                    "SEI (Scalability info): pr_max_bitrate",
+                    res.pr_max_bitrate[i][j],
+                    63,
+                );
+            }
+        }
+    }
+
+    if res.priority_id_setting_flag {
+        // the URI is a null-terminated string; the terminator is not stored
+        loop {
+            if bs.bytestream.is_empty() {
+                println!("[WARNING] decode_scalability_info - ran out of bytes in priority_id_setting_uri");
+                break;
+            }
+            let c = bs.read_bits(8)? as u8;
+            if c == 0 {
+                break;
+            }
+            res.priority_id_setting_uri.push(c);
+        }
+        decoder_formatted_print(
+            "SEI (Scalability info): priority_id_setting_uri",
+            String::from_utf8_lossy(&res.priority_id_setting_uri),
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.2 Sub-picture scalable layer SEI message syntax
+fn decode_sub_pic_scalable_layer(
+    bs: &mut ByteStream,
+) -> Result<SEISubPicScalableLayer, DecodeError> {
+    let mut res = SEISubPicScalableLayer::new();
+
+    res.layer_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("SEI (Sub-pic scalable layer): layer_id", res.layer_id, 63);
+
+    Ok(res)
+}
+
+/// G.13.1.3 Non-required layer representation SEI message syntax
+fn decode_non_required_layer_rep(
+    bs: &mut ByteStream,
+) -> Result<SEINonRequiredLayerRep, DecodeError> {
+    let mut res = SEINonRequiredLayerRep::new();
+
+    res.num_info_entries_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Non-required layer rep): num_info_entries_minus1",
+        res.num_info_entries_minus1,
+        63,
+    );
+    for i in 0..=res.num_info_entries_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_non_required_layer_rep - ran out of bytes at entry {}",
+                i
+            );
+            break;
+        }
+        res.entry_dependency_id.push(bs.read_bits(3)? as u8);
+        decoder_formatted_print(
+            "SEI (Non-required layer rep): entry_dependency_id",
+            res.entry_dependency_id[i],
+            63,
+        );
+        res.num_non_required_layer_reps_minus1
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Non-required layer rep): num_non_required_layer_reps_minus1",
+            res.num_non_required_layer_reps_minus1[i],
+            63,
+        );
+        res.non_required_layer_rep_dependency_id.push(Vec::new());
+        res.non_required_layer_rep_quality_id.push(Vec::new());
+        for j in 0..=res.num_non_required_layer_reps_minus1[i] as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_non_required_layer_rep - ran out of bytes at layer rep {} of entry {}",
+                    j, i
+                );
+                break;
+            }
+            res.non_required_layer_rep_dependency_id[i].push(bs.read_bits(3)? as u8);
+            decoder_formatted_print(
+                "SEI (Non-required layer rep): non_required_layer_rep_dependency_id",
+                res.non_required_layer_rep_dependency_id[i][j],
+                63,
+            );
+            res.non_required_layer_rep_quality_id[i].push(bs.read_bits(4)? as u8);
+            decoder_formatted_print(
+                "SEI (Non-required layer rep): non_required_layer_rep_quality_id",
+                res.non_required_layer_rep_quality_id[i][j],
+                63,
+            );
+        }
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.4 Priority layer information SEI message syntax
+fn decode_priority_layer_info(bs: &mut ByteStream) -> Result<SEIPriorityLayerInfo, DecodeError> {
+    let mut res = SEIPriorityLayerInfo::new();
+
+    res.pr_dependency_id = bs.read_bits(3)? as u8;
+    decoder_formatted_print(
+        "SEI (Priority layer info): pr_dependency_id",
+        res.pr_dependency_id,
+        63,
+    );
+    res.num_priority_ids = bs.read_bits(4)? as u8;
+    decoder_formatted_print(
+        "SEI (Priority layer info): num_priority_ids",
+        res.num_priority_ids,
+        63,
+    );
+    for i in 0..res.num_priority_ids as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_priority_layer_info - ran out of bytes at alt_priority_id {}",
+                i
+            );
+            break;
+        }
+        res.alt_priority_id.push(bs.read_bits(6)? as u8);
+        decoder_formatted_print(
+            "SEI (Priority layer info): alt_priority_id",
+            res.alt_priority_id[i],
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.5 Layers not present SEI message syntax
+fn decode_layers_not_present(bs: &mut ByteStream) -> Result<SEILayersNotPresent, DecodeError> {
+    let mut res = SEILayersNotPresent::new();
+
+    res.num_layers = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("SEI (Layers not present): num_layers", res.num_layers, 63);
+    for i in 0..res.num_layers as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_layers_not_present - ran out of bytes at layer {}",
+                i
+            );
+            break;
+        }
+        res.layer_id
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print("SEI (Layers not present): layer_id", res.layer_id[i], 63);
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.6 Layer dependency change SEI message syntax
+fn decode_layer_dependency_change(
+    bs: &mut ByteStream,
+) -> Result<SEILayerDependencyChange, DecodeError> {
+    let mut res = SEILayerDependencyChange::new();
+
+    res.num_layers_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Layer dependency change): num_layers_minus1",
+        res.num_layers_minus1,
+        63,
+    );
+    for i in 0..=res.num_layers_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_layer_dependency_change - ran out of bytes at layer {}",
+                i
+            );
+            break;
+        }
+        res.layer_id
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Layer dependency change): layer_id",
+            res.layer_id[i],
+            63,
+        );
+        res.layer_dependency_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Layer dependency change): layer_dependency_info_present_flag",
+            res.layer_dependency_info_present_flag[i],
+            63,
+        );
+        res.num_directly_dependent_layers.push(0);
+        res.directly_dependent_layer_id_delta_minus1
+            .push(Vec::new());
+        res.layer_dependency_info_src_layer_id_delta_minus1.push(0);
+        if res.layer_dependency_info_present_flag[i] {
+            res.num_directly_dependent_layers[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Layer dependency change): num_directly_dependent_layers",
+                res.num_directly_dependent_layers[i],
+                63,
+            );
+            for j in 0..res.num_directly_dependent_layers[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_layer_dependency_change - ran out of bytes at directly dependent layer {} of layer {}", j, i);
+                    break;
+                }
+                res.directly_dependent_layer_id_delta_minus1[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (Layer dependency change): directly_dependent_layer_id_delta_minus1",
+                    res.directly_dependent_layer_id_delta_minus1[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.layer_dependency_info_src_layer_id_delta_minus1[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Layer dependency change): layer_dependency_info_src_layer_id_delta_minus1",
+                res.layer_dependency_info_src_layer_id_delta_minus1[i],
+                63,
+            );
+        }
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.7 Scalable nesting SEI message syntax
+fn decode_scalable_nesting(
+    payload_size: u32,
+    spses: &Vec<SeqParameterSet>,
+    subset_spses: &Vec<SubsetSPS>,
+    bs: &mut ByteStream,
+) -> Result<SEIScalableNesting, DecodeError> {
+    let mut res = SEIScalableNesting::new();
+    let start_len = bs.bytestream.len();
+
+    res.all_layer_representations_in_au_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Scalable nesting): all_layer_representations_in_au_flag",
+        res.all_layer_representations_in_au_flag,
+        63,
+    );
+    if !res.all_layer_representations_in_au_flag {
+        res.num_layer_representations_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Scalable nesting): num_layer_representations_minus1",
+            res.num_layer_representations_minus1,
+            63,
+        );
+        for i in 0..=res.num_layer_representations_minus1 as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_scalable_nesting - ran out of bytes at layer representation {}",
+                    i
+                );
+                break;
+            }
+            res.sei_dependency_id.push(bs.read_bits(3)? as u8);
+            decoder_formatted_print(
+                "SEI (Scalable nesting): sei_dependency_id",
+                res.sei_dependency_id[i],
+                63,
+            );
+            res.sei_quality_id.push(bs.read_bits(4)? as u8);
+            decoder_formatted_print(
+                "SEI (Scalable nesting): sei_quality_id",
+                res.sei_quality_id[i],
+                63,
+            );
+        }
+        res.sei_temporal_id = bs.read_bits(3)? as u8;
+        decoder_formatted_print(
+            "SEI (Scalable nesting): sei_temporal_id",
+            res.sei_temporal_id,
+            63,
+        );
+    }
+    res.nested_sei = decode_nested_sei_messages(payload_size, start_len, spses, subset_spses, bs)?;
+
+    Ok(res)
+}
+
+/// G.13.1.8 Base layer temporal HRD SEI message syntax and H.13.1.9 Base view temporal HRD SEI
+/// message syntax, which share the same structure
+fn decode_temporal_hrd(bs: &mut ByteStream) -> Result<SEITemporalHRD, DecodeError> {
+    let mut res = SEITemporalHRD::new();
+
+    res.num_of_temporal_layers_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Temporal HRD): num_of_temporal_layers_minus1",
+        res.num_of_temporal_layers_minus1,
+        63,
+    );
+    for i in 0..=res.num_of_temporal_layers_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_temporal_hrd - ran out of bytes at temporal layer {}",
+                i
+            );
+            break;
+        }
+        res.sei_temporal_id.push(bs.read_bits(3)? as u8);
+        decoder_formatted_print(
+            "SEI (Temporal HRD): sei_temporal_id",
+            res.sei_temporal_id[i],
+            63,
+        );
+        res.sei_timing_info_present_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Temporal HRD): sei_timing_info_present_flag",
+            res.sei_timing_info_present_flag[i],
+            63,
+        );
+        if res.sei_timing_info_present_flag[i] {
+            res.sei_num_units_in_tick.push(bs.read_bits(32)?);
+            decoder_formatted_print(
+                "SEI (Temporal HRD): sei_num_units_in_tick",
+                res.sei_num_units_in_tick[i],
+                63,
+            );
+            res.sei_time_scale.push(bs.read_bits(32)?);
+            decoder_formatted_print(
+                "SEI (Temporal HRD): sei_time_scale",
+                res.sei_time_scale[i],
+                63,
+            );
+            res.sei_fixed_frame_rate_flag.push(1 == bs.read_bits(1)?);
+            decoder_formatted_print(
+                "SEI (Temporal HRD): sei_fixed_frame_rate_flag",
+                res.sei_fixed_frame_rate_flag[i],
+                63,
+            );
+        } else {
+            res.sei_num_units_in_tick.push(0);
+            res.sei_time_scale.push(0);
+            res.sei_fixed_frame_rate_flag.push(false);
+        }
+        res.sei_nal_hrd_parameters_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Temporal HRD): sei_nal_hrd_parameters_present_flag",
+            res.sei_nal_hrd_parameters_present_flag[i],
+            63,
+        );
+        if res.sei_nal_hrd_parameters_present_flag[i] {
+            res.sei_nal_hrd_parameters.push(decode_hrd_parameters(bs)?);
+        } else {
+            res.sei_nal_hrd_parameters.push(HRDParameters::new());
+        }
+        res.sei_vcl_hrd_parameters_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Temporal HRD): sei_vcl_hrd_parameters_present_flag",
+            res.sei_vcl_hrd_parameters_present_flag[i],
+            63,
+        );
+        if res.sei_vcl_hrd_parameters_present_flag[i] {
+            res.sei_vcl_hrd_parameters.push(decode_hrd_parameters(bs)?);
+        } else {
+            res.sei_vcl_hrd_parameters.push(HRDParameters::new());
+        }
+        if res.sei_nal_hrd_parameters_present_flag[i] || res.sei_vcl_hrd_parameters_present_flag[i]
+        {
+            res.sei_low_delay_hrd_flag.push(1 == bs.read_bits(1)?);
+            decoder_formatted_print(
+                "SEI (Temporal HRD): sei_low_delay_hrd_flag",
+                res.sei_low_delay_hrd_flag[i],
+                63,
+            );
+        } else {
+            res.sei_low_delay_hrd_flag.push(false);
+        }
+        res.sei_pic_struct_present_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (Temporal HRD): sei_pic_struct_present_flag",
+            res.sei_pic_struct_present_flag[i],
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.9 Quality layer integrity check SEI message syntax
+fn decode_quality_layer_integrity_check(
+    bs: &mut ByteStream,
+) -> Result<SEIQualityLayerIntegrityCheck, DecodeError> {
+    let mut res = SEIQualityLayerIntegrityCheck::new();
+
+    res.num_info_entries_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Quality layer integrity check): num_info_entries_minus1",
+        res.num_info_entries_minus1,
+        63,
+    );
+    for i in 0..=res.num_info_entries_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_quality_layer_integrity_check - ran out of bytes at entry {}",
+                i
+            );
+            break;
+        }
+        res.entry_dependency_id.push(bs.read_bits(3)? as u8);
+        decoder_formatted_print(
+            "SEI (Quality layer integrity check): entry_dependency_id",
+            res.entry_dependency_id[i],
+            63,
+        );
+        res.quality_layer_crc.push(bs.read_bits(16)?);
+        decoder_formatted_print(
+            "SEI (Quality layer integrity check): quality_layer_crc",
+            res.quality_layer_crc[i],
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.10 Redundant picture property SEI message syntax
+fn decode_redundant_pic_property(
+    bs: &mut ByteStream,
+) -> Result<SEIRedundantPicProperty, DecodeError> {
+    let mut res = SEIRedundantPicProperty::new();
+
+    res.num_dids_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Redundant pic property): num_dIds_minus1",
+        res.num_dids_minus1,
+        63,
+    );
+    for i in 0..=res.num_dids_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_redundant_pic_property - ran out of bytes at dependency_id {}",
+                i
+            );
+            break;
+        }
+        res.dependency_id.push(bs.read_bits(3)? as u8);
+        decoder_formatted_print(
+            "SEI (Redundant pic property): dependency_id",
+            res.dependency_id[i],
+            63,
+        );
+        res.num_qids_minus1
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Redundant pic property): num_qIds_minus1",
+            res.num_qids_minus1[i],
+            63,
+        );
+        res.quality_id.push(Vec::new());
+        res.num_redundant_pics_minus1.push(Vec::new());
+        res.redundant_pic_cnt_minus1.push(Vec::new());
+        res.pic_match_flag.push(Vec::new());
+        res.mb_type_match_flag.push(Vec::new());
+        res.motion_match_flag.push(Vec::new());
+        res.residual_match_flag.push(Vec::new());
+        res.intra_samples_match_flag.push(Vec::new());
+        for j in 0..=res.num_qids_minus1[i] as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_redundant_pic_property - ran out of bytes at quality_id {} of {}",
+                    j, i
+                );
+                break;
+            }
+            res.quality_id[i].push(bs.read_bits(4)? as u8);
+            decoder_formatted_print(
+                "SEI (Redundant pic property): quality_id",
+                res.quality_id[i][j],
+                63,
+            );
+            res.num_redundant_pics_minus1[i]
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Redundant pic property): num_redundant_pics_minus1",
+                res.num_redundant_pics_minus1[i][j],
+                63,
+            );
+            res.redundant_pic_cnt_minus1[i].push(Vec::new());
+            res.pic_match_flag[i].push(Vec::new());
+            res.mb_type_match_flag[i].push(Vec::new());
+            res.motion_match_flag[i].push(Vec::new());
+            res.residual_match_flag[i].push(Vec::new());
+            res.intra_samples_match_flag[i].push(Vec::new());
+            for k in 0..=res.num_redundant_pics_minus1[i][j] as usize {
+                if bs.bytestream.is_empty() {
+                    println!(
+                        "[WARNING] decode_redundant_pic_property - ran out of bytes at redundant pic {} of {}/{}",
+                        k, i, j
+                    );
+                    break;
+                }
+                res.redundant_pic_cnt_minus1[i][j]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (Redundant pic property): redundant_pic_cnt_minus1",
+                    res.redundant_pic_cnt_minus1[i][j][k],
+                    63,
+                );
+                res.pic_match_flag[i][j].push(1 == bs.read_bits(1)?);
+                decoder_formatted_print(
+                    "SEI (Redundant pic property): pic_match_flag",
+                    res.pic_match_flag[i][j][k],
+                    63,
+                );
+                if !res.pic_match_flag[i][j][k] {
+                    res.mb_type_match_flag[i][j].push(1 == bs.read_bits(1)?);
+                    decoder_formatted_print(
+                        "SEI (Redundant pic property): mb_type_match_flag",
+                        res.mb_type_match_flag[i][j][k],
+                        63,
+                    );
+                    res.motion_match_flag[i][j].push(1 == bs.read_bits(1)?);
+                    decoder_formatted_print(
+                        "SEI (Redundant pic property): motion_match_flag",
+                        res.motion_match_flag[i][j][k],
+                        63,
+                    );
+                    res.residual_match_flag[i][j].push(1 == bs.read_bits(1)?);
+                    decoder_formatted_print(
+                        "SEI (Redundant pic property): residual_match_flag",
+                        res.residual_match_flag[i][j][k],
+                        63,
+                    );
+                    res.intra_samples_match_flag[i][j].push(1 == bs.read_bits(1)?);
+                    decoder_formatted_print(
+                        "SEI (Redundant pic property): intra_samples_match_flag",
+                        res.intra_samples_match_flag[i][j][k],
+                        63,
+                    );
+                } else {
+                    res.mb_type_match_flag[i][j].push(false);
+                    res.motion_match_flag[i][j].push(false);
+                    res.residual_match_flag[i][j].push(false);
+                    res.intra_samples_match_flag[i][j].push(false);
+                }
+            }
+        }
+    }
+
+    Ok(res)
+}
+
+/// G.13.1.11 Temporal level zero dependency representation index SEI message syntax
+fn decode_tl0_dep_rep_index(bs: &mut ByteStream) -> Result<SEITl0DepRepIndex, DecodeError> {
+    let mut res = SEITl0DepRepIndex::new();
+
+    res.tl0_dep_rep_idx = bs.read_bits(8)?;
+    decoder_formatted_print(
+        "SEI (Tl0 dep rep index): tl0_dep_rep_idx",
+        res.tl0_dep_rep_idx,
+        63,
+    );
+    res.effective_idr_pic_id = bs.read_bits(16)?;
+    decoder_formatted_print(
+        "SEI (Tl0 dep rep index): effective_idr_pic_id",
+        res.effective_idr_pic_id,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// G.13.1.12 Temporal level switching point SEI message syntax
+fn decode_tl_switching_point(bs: &mut ByteStream) -> Result<SEITlSwitchingPoint, DecodeError> {
+    let mut res = SEITlSwitchingPoint::new();
+
+    res.delta_frame_num = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+    decoder_formatted_print(
+        "SEI (Tl switching point): delta_frame_num",
+        res.delta_frame_num,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// Finds the most recent subset SPS with the given id
+fn find_subset_sps<'a>(
+    caller: &str,
+    seq_parameter_set_id: u32,
+    subset_spses: &'a [SubsetSPS],
+) -> Result<&'a SubsetSPS, DecodeError> {
+    match subset_spses
+        .iter()
+        .rev()
+        .find(|x| x.sps.seq_parameter_set_id == seq_parameter_set_id)
+    {
+        Some(x) => Ok(x),
+        None => Err(DecodeError::new(format!(
+            "{} - Subset SPS with id {} not found",
+            caller, seq_parameter_set_id
+        ))),
+    }
+}
+
+/// H.13.1.1 Parallel decoding information SEI message syntax
+///
+/// anchor_pic_flag belongs to the VCL NAL units that follow this message, so the
+/// anchor delays are read when they exactly fill the payload
+fn decode_parallel_decoding_info(
+    payload_size: u32,
+    subset_spses: &[SubsetSPS],
+    bs: &mut ByteStream,
+) -> Result<SEIParallelDecodingInfo, DecodeError> {
+    let mut res = SEIParallelDecodingInfo::new();
+    let start_len = bs.bytestream.len();
+
+    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Parallel decoding info): seq_parameter_set_id",
+        res.seq_parameter_set_id,
+        63,
+    );
+
+    let subset_sps = find_subset_sps(
+        "decode_parallel_decoding_info",
+        res.seq_parameter_set_id,
+        subset_spses,
+    )?;
+    let [num_anchor_refs_l0, num_anchor_refs_l1, num_non_anchor_refs_l0, num_non_anchor_refs_l1] =
+        subset_sps.inter_view_ref_counts();
+
+    res.anchor_pic_flag = pdi_anchor_delays_fill_payload(
+        &num_anchor_refs_l0,
+        &num_anchor_refs_l1,
+        payload_size,
+        start_len,
+        bs,
+    );
+    decoder_formatted_print(
+        "SEI (Parallel decoding info): anchor_pic_flag (inferred)",
+        res.anchor_pic_flag,
+        63,
+    );
+
+    if res.anchor_pic_flag {
+        decode_pdi_init_delays(
+            "anchor",
+            &num_anchor_refs_l0,
+            &num_anchor_refs_l1,
+            &mut res.pdi_init_delay_anchor_minus2_l0,
+            &mut res.pdi_init_delay_anchor_minus2_l1,
+            bs,
+        )?;
+    } else {
+        decode_pdi_init_delays(
+            "non_anchor",
+            &num_non_anchor_refs_l0,
+            &num_non_anchor_refs_l1,
+            &mut res.pdi_init_delay_non_anchor_minus2_l0,
+            &mut res.pdi_init_delay_non_anchor_minus2_l1,
+            bs,
+        )?;
+    }
+
+    Ok(res)
+}
+
+/// Checks on a copy of the payload bytes whether the anchor delays followed by
+/// the payload trailing bits end exactly at the end of the payload
+fn pdi_anchor_delays_fill_payload(
+    num_anchor_refs_l0: &[u32],
+    num_anchor_refs_l1: &[u32],
+    payload_size: u32,
+    start_len: usize,
+    bs: &ByteStream,
+) -> bool {
+    let consumed = start_len - bs.bytestream.len();
+    let payload_len = (payload_size as usize)
+        .saturating_sub(consumed)
+        .min(bs.bytestream.len());
+    let mut probe = ByteStream::new(bs.bytestream.iter().take(payload_len).copied().collect());
+    probe.byte_offset = bs.byte_offset;
+
+    let num_views = num_anchor_refs_l0.len().min(num_anchor_refs_l1.len());
+    for i in 1..num_views {
+        for _ in 0..(num_anchor_refs_l0[i] as u64 + num_anchor_refs_l1[i] as u64) {
+            if exp_golomb_decode_one_wrapper(&mut probe, false, 0).is_err() {
+                return false;
+            }
+        }
+    }
+
+    if probe.byte_offset > 0 {
+        // payload_bit_equal_to_one followed by payload_bit_equal_to_zero
+        let remaining = 8 - probe.byte_offset;
+        match probe.read_bits(remaining) {
+            Ok(x) if x == 1 << (remaining - 1) => (),
+            _ => return false,
+        }
+    }
+
+    probe.bytestream.is_empty()
+}
+
+/// Reads the pdi_init_delay_*_minus2_l0 and _l1 values of views 1 and up
+fn decode_pdi_init_delays(
+    name: &str,
+    num_refs_l0: &[u32],
+    num_refs_l1: &[u32],
+    l0: &mut Vec<Vec<u32>>,
+    l1: &mut Vec<Vec<u32>>,
+    bs: &mut ByteStream,
+) -> Result<(), DecodeError> {
+    l0.push(Vec::new());
+    l1.push(Vec::new());
+
+    let num_views = num_refs_l0.len().min(num_refs_l1.len());
+    for i in 1..num_views {
+        l0.push(Vec::new());
+        l1.push(Vec::new());
+        for j in 0..num_refs_l0[i] {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_parallel_decoding_info - ran out of bytes at l0 ref {} of view {}",
+                    j, i
+                );
+                break;
+            }
+            l0[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        }
+        decoder_formatted_print(
+            &format!(
+                "SEI (Parallel decoding info): pdi_init_delay_{}_minus2_l0",
+                name
+            ),
+            &l0[i],
+            63,
+        );
+        for j in 0..num_refs_l1[i] {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_parallel_decoding_info - ran out of bytes at l1 ref {} of view {}",
+                    j, i
+                );
+                break;
+            }
+            l1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        }
+        decoder_formatted_print(
+            &format!(
+                "SEI (Parallel decoding info): pdi_init_delay_{}_minus2_l1",
+                name
+            ),
+            &l1[i],
+            63,
+        );
+    }
+
+    Ok(())
+}
+
+/// H.13.1.2 MVC scalable nesting SEI message syntax and I.13.1.2 MVCD scalable nesting SEI
+/// message syntax. The MVCD variant adds the depth and texture applicability flags
+fn decode_mvc_scalable_nesting(
+    payload_size: u32,
+    mvcd: bool,
+    spses: &Vec<SeqParameterSet>,
+    subset_spses: &Vec<SubsetSPS>,
+    bs: &mut ByteStream,
+) -> Result<SEIMVCScalableNesting, DecodeError> {
+    let mut res = SEIMVCScalableNesting::new();
+    let start_len = bs.bytestream.len();
+
+    res.operation_point_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (MVC scalable nesting): operation_point_flag",
+        res.operation_point_flag,
+        63,
+    );
+    if !res.operation_point_flag {
+        res.all_view_components_in_au_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (MVC scalable nesting): all_view_components_in_au_flag",
+            res.all_view_components_in_au_flag,
+            63,
+        );
+        if !res.all_view_components_in_au_flag {
+            res.num_view_components_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (MVC scalable nesting): num_view_components_minus1",
+                res.num_view_components_minus1,
+                63,
+            );
+            for i in 0..=res.num_view_components_minus1 as usize {
+                if bs.bytestream.is_empty() {
+                    println!(
+                        "[WARNING] decode_mvc_scalable_nesting - ran out of bytes at view component {}",
+                        i
+                    );
+                    break;
+                }
+                res.sei_view_id.push(bs.read_bits(10)?);
+                decoder_formatted_print(
+                    "SEI (MVC scalable nesting): sei_view_id",
+                    res.sei_view_id[i],
+                    63,
+                );
+                if mvcd {
+                    res.sei_view_applicability_flag.push(1 == bs.read_bits(1)?);
+                    decoder_formatted_print(
+                        "SEI (MVC scalable nesting): sei_view_applicability_flag",
+                        res.sei_view_applicability_flag[i],
+                        63,
+                    );
+                }
+            }
+        }
+    } else {
+        if mvcd {
+            res.sei_op_texture_only_flag = 1 == bs.read_bits(1)?;
+            decoder_formatted_print(
+                "SEI (MVC scalable nesting): sei_op_texture_only_flag",
+                res.sei_op_texture_only_flag,
+                63,
+            );
+        }
+        res.num_view_components_op_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (MVC scalable nesting): num_view_components_op_minus1",
+            res.num_view_components_op_minus1,
+            63,
+        );
+        for i in 0..=res.num_view_components_op_minus1 as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_mvc_scalable_nesting - ran out of bytes at operation point view component {}",
+                    i
+                );
+                break;
+            }
+            res.sei_op_view_id.push(bs.read_bits(10)?);
+            decoder_formatted_print(
+                "SEI (MVC scalable nesting): sei_op_view_id",
+                res.sei_op_view_id[i],
+                63,
+            );
+            if mvcd && !res.sei_op_texture_only_flag {
+                res.sei_op_depth_flag.push(1 == bs.read_bits(1)?);
+                decoder_formatted_print(
+                    "SEI (MVC scalable nesting): sei_op_depth_flag",
+                    res.sei_op_depth_flag[i],
+                    63,
+                );
+                res.sei_op_texture_flag.push(1 == bs.read_bits(1)?);
+                decoder_formatted_print(
+                    "SEI (MVC scalable nesting): sei_op_texture_flag",
+                    res.sei_op_texture_flag[i],
+                    63,
+                );
+            }
+        }
+        res.sei_op_temporal_id = bs.read_bits(3)? as u8;
+        decoder_formatted_print(
+            "SEI (MVC scalable nesting): sei_op_temporal_id",
+            res.sei_op_temporal_id,
+            63,
+        );
+    }
+    res.nested_sei = decode_nested_sei_messages(payload_size, start_len, spses, subset_spses, bs)?;
+
+    Ok(res)
+}
+
+/// H.13.1.3 View scalability information SEI message syntax and I.13.1.1 MVCD view scalability
+/// information SEI message syntax. The MVCD variant adds mvcd_op_view_info() to each view
+fn decode_view_scalability_info(
+    mvcd: bool,
+    bs: &mut ByteStream,
+) -> Result<SEIViewScalabilityInfo, DecodeError> {
+    let mut res = SEIViewScalabilityInfo::new();
+
+    res.num_operation_points_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (View scalability info): num_operation_points_minus1",
+        res.num_operation_points_minus1,
+        63,
+    );
+    for i in 0..=res.num_operation_points_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_view_scalability_info - ran out of bytes at operation point {}",
+                i
+            );
+            break;
+        }
+        res.operation_point_id
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (View scalability info): operation_point_id",
+            res.operation_point_id[i],
+            63,
+        );
+        res.priority_id.push(bs.read_bits(5)? as u8);
+        decoder_formatted_print(
+            "SEI (View scalability info): priority_id",
+            res.priority_id[i],
+            63,
+        );
+        res.temporal_id.push(bs.read_bits(3)? as u8);
+        decoder_formatted_print(
+            "SEI (View scalability info): temporal_id",
+            res.temporal_id[i],
+            63,
+        );
+        res.num_target_output_views_minus1
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (View scalability info): num_target_output_views_minus1",
+            res.num_target_output_views_minus1[i],
+            63,
+        );
+        res.view_id.push(Vec::new());
+        res.view_info_depth_view_present_flag.push(Vec::new());
+        res.mvcd_depth_view_flag.push(Vec::new());
+        res.view_info_texture_view_present_flag.push(Vec::new());
+        res.mvcd_texture_view_flag.push(Vec::new());
+        for j in 0..=res.num_target_output_views_minus1[i] as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_view_scalability_info - ran out of bytes at target output view {} of {}",
+                    j, i
+                );
+                break;
+            }
+            res.view_id[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (View scalability info): view_id",
+                res.view_id[i][j],
+                63,
+            );
+            if mvcd {
+                // mvcd_op_view_info()
+                res.view_info_depth_view_present_flag[i].push(1 == bs.read_bits(1)?);
+                decoder_formatted_print(
+                    "SEI (View scalability info): view_info_depth_view_present_flag",
+                    res.view_info_depth_view_present_flag[i][j],
+                    63,
+                );
+                if res.view_info_depth_view_present_flag[i][j] {
+                    res.mvcd_depth_view_flag[i].push(1 == bs.read_bits(1)?);
+                    decoder_formatted_print(
+                        "SEI (View scalability info): mvcd_depth_view_flag",
+                        res.mvcd_depth_view_flag[i][j],
+                        63,
+                    );
+                } else {
+                    res.mvcd_depth_view_flag[i].push(false);
+                }
+                res.view_info_texture_view_present_flag[i].push(1 == bs.read_bits(1)?);
+                decoder_formatted_print(
+                    "SEI (View scalability info): view_info_texture_view_present_flag",
+                    res.view_info_texture_view_present_flag[i][j],
+                    63,
+                );
+                if res.view_info_texture_view_present_flag[i][j] {
+                    res.mvcd_texture_view_flag[i].push(1 == bs.read_bits(1)?);
+                    decoder_formatted_print(
+                        "SEI (View scalability info): mvcd_texture_view_flag",
+                        res.mvcd_texture_view_flag[i][j],
+                        63,
+                    );
+                } else {
+                    res.mvcd_texture_view_flag[i].push(false);
+                }
+            }
+        }
+        res.profile_level_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (View scalability info): profile_level_info_present_flag",
+            res.profile_level_info_present_flag[i],
+            63,
+        );
+        res.bitrate_info_present_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (View scalability info): bitrate_info_present_flag",
+            res.bitrate_info_present_flag[i],
+            63,
+        );
+        res.frm_rate_info_present_flag.push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (View scalability info): frm_rate_info_present_flag",
+            res.frm_rate_info_present_flag[i],
+            63,
+        );
+        if res.num_target_output_views_minus1[i] == 0 {
+            res.view_dependency_info_present_flag
+                .push(1 == bs.read_bits(1)?);
+            decoder_formatted_print(
+                "SEI (View scalability info): view_dependency_info_present_flag",
+                res.view_dependency_info_present_flag[i],
+                63,
+            );
+        } else {
+            res.view_dependency_info_present_flag.push(false);
+        }
+        res.parameter_sets_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (View scalability info): parameter_sets_info_present_flag",
+            res.parameter_sets_info_present_flag[i],
+            63,
+        );
+        res.bitstream_restriction_info_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (View scalability info): bitstream_restriction_info_present_flag",
+            res.bitstream_restriction_info_present_flag[i],
+            63,
+        );
+
+        if res.profile_level_info_present_flag[i] {
+            res.op_profile_level_idc.push(bs.read_bits(24)?);
+            decoder_formatted_print(
+                "SEI (View scalability info): op_profile_level_idc",
+                res.op_profile_level_idc[i],
+                63,
+            );
+        } else {
+            res.op_profile_level_idc.push(0);
+        }
+
+        if res.bitrate_info_present_flag[i] {
+            res.avg_bitrate.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (View scalability info): avg_bitrate",
+                res.avg_bitrate[i],
+                63,
+            );
+            res.max_bitrate.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (View scalability info): max_bitrate",
+                res.max_bitrate[i],
+                63,
+            );
+            res.max_bitrate_calc_window.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (View scalability info): max_bitrate_calc_window",
+                res.max_bitrate_calc_window[i],
+                63,
+            );
+        } else {
+            res.avg_bitrate.push(0);
+            res.max_bitrate.push(0);
+            res.max_bitrate_calc_window.push(0);
+        }
+
+        if res.frm_rate_info_present_flag[i] {
+            res.constant_frm_rate_idc.push(bs.read_bits(2)? as u8);
+            decoder_formatted_print(
+                "SEI (View scalability info): constant_frm_rate_idc",
+                res.constant_frm_rate_idc[i],
+                63,
+            );
+            res.avg_frm_rate.push(bs.read_bits(16)?);
+            decoder_formatted_print(
+                "SEI (View scalability info): avg_frm_rate",
+                res.avg_frm_rate[i],
+                63,
+            );
+        } else {
+            res.constant_frm_rate_idc.push(0);
+            res.avg_frm_rate.push(0);
+        }
+
+        res.num_directly_dependent_views.push(0);
+        res.directly_dependent_view_id.push(Vec::new());
+        res.view_dependency_info_src_op_id.push(0);
+        if res.view_dependency_info_present_flag[i] {
+            res.num_directly_dependent_views[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (View scalability info): num_directly_dependent_views",
+                res.num_directly_dependent_views[i],
+                63,
+            );
+            for j in 0..res.num_directly_dependent_views[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_view_scalability_info - ran out of bytes at directly dependent view {} of {}", j, i);
+                    break;
+                }
+                res.directly_dependent_view_id[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (View scalability info): directly_dependent_view_id",
+                    res.directly_dependent_view_id[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.view_dependency_info_src_op_id[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (View scalability info): view_dependency_info_src_op_id",
+                res.view_dependency_info_src_op_id[i],
+                63,
+            );
+        }
+
+        res.num_seq_parameter_sets.push(0);
+        res.seq_parameter_set_id_delta.push(Vec::new());
+        res.num_subset_seq_parameter_sets.push(0);
+        res.subset_seq_parameter_set_id_delta.push(Vec::new());
+        res.num_pic_parameter_sets_minus1.push(0);
+        res.pic_parameter_set_id_delta.push(Vec::new());
+        res.parameter_sets_info_src_op_id.push(0);
+        if res.parameter_sets_info_present_flag[i] {
+            res.num_seq_parameter_sets[i] = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (View scalability info): num_seq_parameter_sets",
+                res.num_seq_parameter_sets[i],
+                63,
+            );
+            for j in 0..res.num_seq_parameter_sets[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_view_scalability_info - ran out of bytes at seq_parameter_set_id_delta {} of {}", j, i);
+                    break;
+                }
+                res.seq_parameter_set_id_delta[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (View scalability info): seq_parameter_set_id_delta",
+                    res.seq_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+            res.num_subset_seq_parameter_sets[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (View scalability info): num_subset_seq_parameter_sets",
+                res.num_subset_seq_parameter_sets[i],
+                63,
+            );
+            for j in 0..res.num_subset_seq_parameter_sets[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_view_scalability_info - ran out of bytes at subset_seq_parameter_set_id_delta {} of {}", j, i);
+                    break;
+                }
+                res.subset_seq_parameter_set_id_delta[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (View scalability info): subset_seq_parameter_set_id_delta",
+                    res.subset_seq_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+            res.num_pic_parameter_sets_minus1[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (View scalability info): num_pic_parameter_sets_minus1",
+                res.num_pic_parameter_sets_minus1[i],
+                63,
+            );
+            for j in 0..=res.num_pic_parameter_sets_minus1[i] as usize {
+                if bs.bytestream.is_empty() {
+                    println!("[WARNING] decode_view_scalability_info - ran out of bytes at pic_parameter_set_id_delta {} of {}", j, i);
+                    break;
+                }
+                res.pic_parameter_set_id_delta[i]
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SEI (View scalability info): pic_parameter_set_id_delta",
+                    res.pic_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.parameter_sets_info_src_op_id[i] =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (View scalability info): parameter_sets_info_src_op_id",
+                res.parameter_sets_info_src_op_id[i],
+                63,
+            );
+        }
+
+        if res.bitstream_restriction_info_present_flag[i] {
+            res.motion_vectors_over_pic_boundaries_flag
+                .push(1 == bs.read_bits(1)?);
+            decoder_formatted_print(
+                "SEI (View scalability info): motion_vectors_over_pic_boundaries_flag",
+                res.motion_vectors_over_pic_boundaries_flag[i],
+                63,
+            );
+            res.max_bytes_per_pic_denom
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (View scalability info): max_bytes_per_pic_denom",
+                res.max_bytes_per_pic_denom[i],
+                63,
+            );
+            res.max_bits_per_mb_denom
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (View scalability info): max_bits_per_mb_denom",
+                res.max_bits_per_mb_denom[i],
+                63,
+            );
+            res.log2_max_mv_length_horizontal
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (View scalability info): log2_max_mv_length_horizontal",
+                res.log2_max_mv_length_horizontal[i],
+                63,
+            );
+            res.log2_max_mv_length_vertical
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (View scalability info): log2_max_mv_length_vertical",
+                res.log2_max_mv_length_vertical[i],
+                63,
+            );
+            res.max_num_reorder_frames
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (View scalability info): max_num_reorder_frames",
+                res.max_num_reorder_frames[i],
+                63,
+            );
+            res.max_dec_frame_buffering
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (View scalability info): max_dec_frame_buffering",
+                res.max_dec_frame_buffering[i],
+                63,
+            );
+        } else {
+            res.motion_vectors_over_pic_boundaries_flag.push(false);
+            res.max_bytes_per_pic_denom.push(0);
+            res.max_bits_per_mb_denom.push(0);
+            res.log2_max_mv_length_horizontal.push(0);
+            res.log2_max_mv_length_vertical.push(0);
+            res.max_num_reorder_frames.push(0);
+            res.max_dec_frame_buffering.push(0);
+        }
+    }
+
+    Ok(res)
+}
+
+/// H.13.1.4 Multiview scene information SEI message syntax
+fn decode_multiview_scene_info(bs: &mut ByteStream) -> Result<SEIMultiviewSceneInfo, DecodeError> {
+    let mut res = SEIMultiviewSceneInfo::new();
+
+    res.max_disparity = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Multiview scene info): max_disparity",
+        res.max_disparity,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// Reads the u(v) mantissa that follows an exponent of a camera or display parameter
+fn decode_sei_mantissa(
+    name: &str,
+    exponent: u8,
+    prec: u32,
+    bs: &mut ByteStream,
+) -> Result<u64, DecodeError> {
+    let len = sei_mantissa_len(exponent, prec);
+    if len > 64 {
+        return Err(DecodeError::new(format!(
+            "decode_sei_mantissa - {} is {} bits long, more than 64",
+            name, len
+        )));
+    }
+    let mantissa = bs.read_bits64(len as u8)?;
+    decoder_formatted_print(name, mantissa, 63);
+
+    Ok(mantissa)
+}
+
+/// Reads the sign, exponent and mantissa of a camera parameter
+fn decode_sei_float_value(
+    name: &str,
+    prec: u32,
+    bs: &mut ByteStream,
+) -> Result<SEIFloatValue, DecodeError> {
+    let sign = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(&format!("{} sign", name), sign, 63);
+    let exponent = bs.read_bits(6)? as u8;
+    decoder_formatted_print(&format!("{} exponent", name), exponent, 63);
+    let mantissa = decode_sei_mantissa(&format!("{} mantissa", name), exponent, prec, bs)?;
+
+    Ok(SEIFloatValue {
+        sign,
+        exponent,
+        mantissa,
+    })
+}
+
+/// H.13.1.5 Multiview acquisition information SEI message syntax
+fn decode_multiview_acquisition_info(
+    bs: &mut ByteStream,
+) -> Result<SEIMultiviewAcquisitionInfo, DecodeError> {
+    let mut res = SEIMultiviewAcquisitionInfo::new();
+
+    res.num_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Multiview acquisition info): num_views_minus1",
+        res.num_views_minus1,
+        63,
+    );
+    res.intrinsic_param_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Multiview acquisition info): intrinsic_param_flag",
+        res.intrinsic_param_flag,
+        63,
+    );
+    res.extrinsic_param_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Multiview acquisition info): extrinsic_param_flag",
+        res.extrinsic_param_flag,
+        63,
+    );
+
+    if res.intrinsic_param_flag {
+        res.intrinsic_params_equal_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Multiview acquisition info): intrinsic_params_equal_flag",
+            res.intrinsic_params_equal_flag,
+            63,
+        );
+        res.prec_focal_length = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_focal_length",
+            res.prec_focal_length,
+            63,
+        );
+        res.prec_principal_point = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_principal_point",
+            res.prec_principal_point,
+            63,
+        );
+        res.prec_skew_factor = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_skew_factor",
+            res.prec_skew_factor,
+            63,
+        );
+
+        let num_intrinsic_params = if res.intrinsic_params_equal_flag {
+            0
+        } else {
+            res.num_views_minus1
+        };
+        for i in 0..=num_intrinsic_params {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_multiview_acquisition_info - ran out of bytes at intrinsic params {}",
+                    i
+                );
+                break;
+            }
+            res.focal_length_x.push(decode_sei_float_value(
+                "SEI (Multiview acquisition info): focal_length_x",
+                res.prec_focal_length,
+                bs,
+            )?);
+            res.focal_length_y.push(decode_sei_float_value(
+                "SEI (Multiview acquisition info): focal_length_y",
+                res.prec_focal_length,
+                bs,
+            )?);
+            res.principal_point_x.push(decode_sei_float_value(
+                "SEI (Multiview acquisition info): principal_point_x",
+                res.prec_principal_point,
+                bs,
+            )?);
+            res.principal_point_y.push(decode_sei_float_value(
+                "SEI (Multiview acquisition info): principal_point_y",
+                res.prec_principal_point,
+                bs,
+            )?);
+            res.skew_factor.push(decode_sei_float_value(
+                "SEI (Multiview acquisition info): skew_factor",
+                res.prec_skew_factor,
+                bs,
+            )?);
+        }
+    }
+
+    if res.extrinsic_param_flag {
+        res.prec_rotation_param = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_rotation_param",
+            res.prec_rotation_param,
+            63,
+        );
+        res.prec_translation_param = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_translation_param",
+            res.prec_translation_param,
+            63,
+        );
+
+        for i in 0..=res.num_views_minus1 {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_multiview_acquisition_info - ran out of bytes at extrinsic params {}",
+                    i
+                );
+                break;
+            }
+            let mut r = [[SEIFloatValue::default(); 3]; 3];
+            let mut t = [SEIFloatValue::default(); 3];
+            for (row, t_value) in r.iter_mut().zip(t.iter_mut()) {
+                for value in row.iter_mut() {
+                    *value = decode_sei_float_value(
+                        "SEI (Multiview acquisition info): r",
+                        res.prec_rotation_param,
+                        bs,
+                    )?;
+                }
+                *t_value = decode_sei_float_value(
+                    "SEI (Multiview acquisition info): t",
+                    res.prec_translation_param,
+                    bs,
+                )?;
+            }
+            res.r.push(r);
+            res.t.push(t);
+        }
+    }
+
+    Ok(res)
+}
+
+/// H.13.1.6 Non-required view component SEI message syntax
+fn decode_non_required_view_component(
+    bs: &mut ByteStream,
+) -> Result<SEINonRequiredViewComponent, DecodeError> {
+    let mut res = SEINonRequiredViewComponent::new();
+
+    res.num_info_entries_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Non-required view component): num_info_entries_minus1",
+        res.num_info_entries_minus1,
+        63,
+    );
+    for i in 0..=res.num_info_entries_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_non_required_view_component - ran out of bytes at entry {}",
+                i
+            );
+            break;
+        }
+        res.view_order_index
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Non-required view component): view_order_index",
+            res.view_order_index[i],
+            63,
+        );
+        res.num_non_required_view_components_minus1
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Non-required view component): num_non_required_view_components_minus1",
+            res.num_non_required_view_components_minus1[i],
+            63,
+        );
+        res.index_delta_minus1.push(Vec::new());
+        for j in 0..=res.num_non_required_view_components_minus1[i] as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_non_required_view_component - ran out of bytes at view component {} of entry {}",
+                    j, i
+                );
+                break;
+            }
+            res.index_delta_minus1[i].push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Non-required view component): index_delta_minus1",
+                res.index_delta_minus1[i][j],
+                63,
+            );
+        }
+    }
+
+    Ok(res)
+}
+
+/// H.13.1.7 View dependency change SEI message syntax
+fn decode_view_dependency_change(
+    subset_spses: &[SubsetSPS],
+    bs: &mut ByteStream,
+) -> Result<SEIViewDependencyChange, DecodeError> {
+    let mut res = SEIViewDependencyChange::new();
+
+    res.seq_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (View dependency change): seq_parameter_set_id",
+        res.seq_parameter_set_id,
+        63,
+    );
+    res.anchor_update_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (View dependency change): anchor_update_flag",
+        res.anchor_update_flag,
+        63,
+    );
+    res.non_anchor_update_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (View dependency change): non_anchor_update_flag",
+        res.non_anchor_update_flag,
+        63,
+    );
+
+    let subset_sps = find_subset_sps(
+        "decode_view_dependency_change",
+        res.seq_parameter_set_id,
+        subset_spses,
+    )?;
+    let [num_anchor_refs_l0, num_anchor_refs_l1, num_non_anchor_refs_l0, num_non_anchor_refs_l1] =
+        subset_sps.inter_view_ref_counts();
+
+    if res.anchor_update_flag {
+        decode_view_dependency_flags(
+            "anchor",
+            &num_anchor_refs_l0,
+            &num_anchor_refs_l1,
+            &mut res.anchor_ref_l0_flag,
+            &mut res.anchor_ref_l1_flag,
+            bs,
+        )?;
+    }
+    if res.non_anchor_update_flag {
+        decode_view_dependency_flags(
+            "non_anchor",
+            &num_non_anchor_refs_l0,
+            &num_non_anchor_refs_l1,
+            &mut res.non_anchor_ref_l0_flag,
+            &mut res.non_anchor_ref_l1_flag,
+            bs,
+        )?;
+    }
+
+    Ok(res)
+}
+
+/// Reads the *_ref_l0_flag and _l1_flag values of views 1 and up
+fn decode_view_dependency_flags(
+    name: &str,
+    num_refs_l0: &[u32],
+    num_refs_l1: &[u32],
+    l0: &mut Vec<Vec<bool>>,
+    l1: &mut Vec<Vec<bool>>,
+    bs: &mut ByteStream,
+) -> Result<(), DecodeError> {
+    l0.push(Vec::new());
+    l1.push(Vec::new());
+
+    let num_views = num_refs_l0.len().min(num_refs_l1.len());
+    for i in 1..num_views {
+        l0.push(Vec::new());
+        l1.push(Vec::new());
+        for j in 0..num_refs_l0[i] {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_view_dependency_change - ran out of bytes at l0 ref {} of view {}",
+                    j, i
+                );
+                break;
+            }
+            l0[i].push(1 == bs.read_bits(1)?);
+        }
+        decoder_formatted_print(
+            &format!("SEI (View dependency change): {}_ref_l0_flag", name),
+            &l0[i],
+            63,
+        );
+        for j in 0..num_refs_l1[i] {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_view_dependency_change - ran out of bytes at l1 ref {} of view {}",
+                    j, i
+                );
+                break;
+            }
+            l1[i].push(1 == bs.read_bits(1)?);
+        }
+        decoder_formatted_print(
+            &format!("SEI (View dependency change): {}_ref_l1_flag", name),
+            &l1[i],
+            63,
+        );
+    }
+
+    Ok(())
+}
+
+/// H.13.1.8 Operation point not present SEI message syntax
+fn decode_operation_points_not_present(
+    bs: &mut ByteStream,
+) -> Result<SEIOperationPointsNotPresent, DecodeError> {
+    let mut res = SEIOperationPointsNotPresent::new();
+
+    res.num_operation_points = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Operation points not present): num_operation_points",
+        res.num_operation_points,
+        63,
+    );
+    for i in 0..res.num_operation_points as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_operation_points_not_present - ran out of bytes at operation point {}",
+                i
+            );
+            break;
+        }
+        res.operation_point_not_present_id
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Operation points not present): operation_point_not_present_id",
+            res.operation_point_not_present_id[i],
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// D.1.26 Frame packing arrangement SEI message syntax
+fn decode_frame_packing_arrangement(bs: &mut ByteStream) -> Result<SEIFramePacking, DecodeError> {
+    let mut res = SEIFramePacking::new();
+
+    res.frame_packing_arrangement_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Frame Packing): frame_packing_arrangement_id",
+        res.frame_packing_arrangement_id,
+        63,
+    );
+    res.frame_packing_arrangement_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Frame Packing): frame_packing_arrangement_cancel_flag",
+        res.frame_packing_arrangement_cancel_flag,
+        63,
+    );
+    if !res.frame_packing_arrangement_cancel_flag {
+        res.frame_packing_arrangement_type = bs.read_bits(7)? as u8;
+        decoder_formatted_print(
+            "SEI (Frame Packing): frame_packing_arrangement_type",
+            res.frame_packing_arrangement_type,
+            63,
+        );
+        res.quincunx_sampling_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Frame Packing): quincunx_sampling_flag",
+            res.quincunx_sampling_flag,
+            63,
+        );
+        res.content_interpretation_type = bs.read_bits(6)? as u8;
+        decoder_formatted_print(
+            "SEI (Frame Packing): content_interpretation_type",
+            res.content_interpretation_type,
+            63,
+        );
+        res.spatial_flipping_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Frame Packing): spatial_flipping_flag",
+            res.spatial_flipping_flag,
+            63,
+        );
+        res.frame0_flipped_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Frame Packing): frame0_flipped_flag",
+            res.frame0_flipped_flag,
+            63,
+        );
+        res.field_views_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Frame Packing): field_views_flag",
+            res.field_views_flag,
+            63,
+        );
+        res.current_frame_is_frame0_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Frame Packing): current_frame_is_frame0_flag",
+            res.current_frame_is_frame0_flag,
+            63,
+        );
+        res.frame0_self_contained_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Frame Packing): frame0_self_contained_flag",
+            res.frame0_self_contained_flag,
+            63,
+        );
+        res.frame1_self_contained_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Frame Packing): frame1_self_contained_flag",
+            res.frame1_self_contained_flag,
+            63,
+        );
+        if res.grid_positions_present() {
+            res.frame0_grid_position_x = bs.read_bits(4)? as u8;
+            decoder_formatted_print(
+                "SEI (Frame Packing): frame0_grid_position_x",
+                res.frame0_grid_position_x,
+                63,
+            );
+            res.frame0_grid_position_y = bs.read_bits(4)? as u8;
+            decoder_formatted_print(
+                "SEI (Frame Packing): frame0_grid_position_y",
+                res.frame0_grid_position_y,
+                63,
+            );
+            res.frame1_grid_position_x = bs.read_bits(4)? as u8;
+            decoder_formatted_print(
+                "SEI (Frame Packing): frame1_grid_position_x",
+                res.frame1_grid_position_x,
+                63,
+            );
+            res.frame1_grid_position_y = bs.read_bits(4)? as u8;
+            decoder_formatted_print(
+                "SEI (Frame Packing): frame1_grid_position_y",
+                res.frame1_grid_position_y,
+                63,
+            );
+        }
+        res.frame_packing_arrangement_reserved_byte = bs.read_bits(8)? as u8;
+        decoder_formatted_print(
+            "SEI (Frame Packing): frame_packing_arrangement_reserved_byte",
+            res.frame_packing_arrangement_reserved_byte,
+            63,
+        );
+        res.frame_packing_arrangement_repetition_period =
+            exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Frame Packing): frame_packing_arrangement_repetition_period",
+            res.frame_packing_arrangement_repetition_period,
+            63,
+        );
+    }
+    res.frame_packing_arrangement_extension_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Frame Packing): frame_packing_arrangement_extension_flag",
+        res.frame_packing_arrangement_extension_flag,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// H.13.1.10 Multiview view position SEI message syntax
+fn decode_multiview_view_position(
+    bs: &mut ByteStream,
+) -> Result<SEIMultiviewViewPosition, DecodeError> {
+    let mut res = SEIMultiviewViewPosition::new();
+
+    res.num_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Multiview view position): num_views_minus1",
+        res.num_views_minus1,
+        63,
+    );
+    for i in 0..=res.num_views_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_multiview_view_position - ran out of bytes at view {}",
+                i
+            );
+            break;
+        }
+        res.view_position
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Multiview view position): view_position",
+            res.view_position[i],
+            63,
+        );
+    }
+    res.multiview_view_position_extension_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Multiview view position): multiview_view_position_extension_flag",
+        res.multiview_view_position_extension_flag,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// D.1.27 Display orientation SEI message syntax
+fn decode_display_orientation(bs: &mut ByteStream) -> Result<SEIDisplayOrientation, DecodeError> {
+    let mut res = SEIDisplayOrientation::new();
+
+    res.display_orientation_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Display Orientation): display_orientation_cancel_flag",
+        res.display_orientation_cancel_flag,
+        63,
+    );
+    if !res.display_orientation_cancel_flag {
+        res.hor_flip = 1 == bs.read_bits(1)?;
+        decoder_formatted_print("SEI (Display Orientation): hor_flip", res.hor_flip, 63);
+        res.ver_flip = 1 == bs.read_bits(1)?;
+        decoder_formatted_print("SEI (Display Orientation): ver_flip", res.ver_flip, 63);
+        res.anticlockwise_rotation = bs.read_bits(16)?;
+        decoder_formatted_print(
+            "SEI (Display Orientation): anticlockwise_rotation",
+            res.anticlockwise_rotation,
+            63,
+        );
+        res.display_orientation_repetition_period =
+            exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Display Orientation): display_orientation_repetition_period",
+            res.display_orientation_repetition_period,
+            63,
+        );
+        res.display_orientation_extension_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Display Orientation): display_orientation_extension_flag",
+            res.display_orientation_extension_flag,
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// I.13.1.3.1 Depth representation SEI element syntax
+fn decode_depth_representation_sei_element(
+    name: &str,
+    bs: &mut ByteStream,
+) -> Result<SEIDepthRepresentationElement, DecodeError> {
+    let mut res = SEIDepthRepresentationElement::default();
+
+    res.da_sign_flag = 1 == bs.read_bits(1)?;
+    res.da_exponent = bs.read_bits(7)? as u8;
+    res.da_mantissa_len_minus1 = bs.read_bits(5)? as u8;
+    res.da_mantissa = bs.read_bits(res.da_mantissa_len_minus1 + 1)?;
+    decoder_formatted_print(name, res, 63);
+
+    Ok(res)
+}
+
+/// I.13.1.3 Depth representation information SEI message syntax
+fn decode_depth_representation_info(
+    bs: &mut ByteStream,
+) -> Result<SEIDepthRepresentationInfo, DecodeError> {
+    let mut res = SEIDepthRepresentationInfo::new();
+
+    res.all_views_equal_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Depth representation info): all_views_equal_flag",
+        res.all_views_equal_flag,
+        63,
+    );
+    if !res.all_views_equal_flag {
+        res.num_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Depth representation info): num_views_minus1",
+            res.num_views_minus1,
+            63,
+        );
+    }
+    res.z_near_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Depth representation info): z_near_flag",
+        res.z_near_flag,
+        63,
+    );
+    res.z_far_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Depth representation info): z_far_flag",
+        res.z_far_flag,
+        63,
+    );
+    if res.z_near_flag || res.z_far_flag {
+        res.z_axis_equal_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Depth representation info): z_axis_equal_flag",
+            res.z_axis_equal_flag,
+            63,
+        );
+        if res.z_axis_equal_flag {
+            res.common_z_axis_reference_view = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SEI (Depth representation info): common_z_axis_reference_view",
+                res.common_z_axis_reference_view,
+                63,
+            );
+        }
+    }
+    res.d_min_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Depth representation info): d_min_flag",
+        res.d_min_flag,
+        63,
+    );
+    res.d_max_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Depth representation info): d_max_flag",
+        res.d_max_flag,
+        63,
+    );
+    res.depth_representation_type = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Depth representation info): depth_representation_type",
+        res.depth_representation_type,
+        63,
+    );
+
+    let num_views = if res.all_views_equal_flag {
+        1
+    } else {
+        res.num_views_minus1 as u64 + 1
+    };
+    for i in 0..num_views {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_depth_representation_info - ran out of bytes at view {}",
+                i
+            );
+            break;
+        }
+        res.depth_info_view_id
+            .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        decoder_formatted_print(
+            "SEI (Depth representation info): depth_info_view_id",
+            res.depth_info_view_id[i as usize],
+            63,
+        );
+        if (res.z_near_flag || res.z_far_flag) && !res.z_axis_equal_flag {
+            res.z_axis_reference_view
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Depth representation info): z_axis_reference_view",
+                res.z_axis_reference_view[i as usize],
+                63,
+            );
+        } else {
+            res.z_axis_reference_view.push(0);
+        }
+        if res.d_min_flag || res.d_max_flag {
+            res.disparity_reference_view
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Depth representation info): disparity_reference_view",
+                res.disparity_reference_view[i as usize],
+                63,
+            );
+        } else {
+            res.disparity_reference_view.push(0);
+        }
+        res.z_near.push(if res.z_near_flag {
+            decode_depth_representation_sei_element("SEI (Depth representation info): z_near", bs)?
+        } else {
+            SEIDepthRepresentationElement::default()
+        });
+        res.z_far.push(if res.z_far_flag {
+            decode_depth_representation_sei_element("SEI (Depth representation info): z_far", bs)?
+        } else {
+            SEIDepthRepresentationElement::default()
+        });
+        res.d_min.push(if res.d_min_flag {
+            decode_depth_representation_sei_element("SEI (Depth representation info): d_min", bs)?
+        } else {
+            SEIDepthRepresentationElement::default()
+        });
+        res.d_max.push(if res.d_max_flag {
+            decode_depth_representation_sei_element("SEI (Depth representation info): d_max", bs)?
+        } else {
+            SEIDepthRepresentationElement::default()
+        });
+    }
+
+    if res.depth_representation_type == 3 {
+        res.depth_nonlinear_representation_num_minus1 =
+            exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Depth representation info): depth_nonlinear_representation_num_minus1",
+            res.depth_nonlinear_representation_num_minus1,
+            63,
+        );
+        for i in 1..=res.depth_nonlinear_representation_num_minus1 as u64 + 1 {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_depth_representation_info - ran out of bytes at nonlinear model {}",
+                    i
+                );
+                break;
+            }
+            res.depth_nonlinear_representation_model
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+        }
+        decoder_formatted_print(
+            "SEI (Depth representation info): depth_nonlinear_representation_model",
+            &res.depth_nonlinear_representation_model,
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// I.13.1.4 3D reference displays information SEI message syntax
+fn decode_three_dimensional_reference_displays_info(
+    bs: &mut ByteStream,
+) -> Result<SEIThreeDimensionalReferenceDisplaysInfo, DecodeError> {
+    let mut res = SEIThreeDimensionalReferenceDisplaysInfo::new();
+
+    res.prec_ref_baseline = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (3D reference displays info): prec_ref_baseline",
+        res.prec_ref_baseline,
+        63,
+    );
+    res.prec_ref_display_width = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (3D reference displays info): prec_ref_display_width",
+        res.prec_ref_display_width,
+        63,
+    );
+    res.ref_viewing_distance_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (3D reference displays info): ref_viewing_distance_flag",
+        res.ref_viewing_distance_flag,
+        63,
+    );
+    if res.ref_viewing_distance_flag {
+        res.prec_ref_viewing_dist = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (3D reference displays info): prec_ref_viewing_dist",
+            res.prec_ref_viewing_dist,
+            63,
+        );
+    }
+    res.num_ref_displays_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (3D reference displays info): num_ref_displays_minus1",
+        res.num_ref_displays_minus1,
+        63,
+    );
+
+    for i in 0..=res.num_ref_displays_minus1 as usize {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_three_dimensional_reference_displays_info - ran out of bytes at display {}",
+                i
+            );
+            break;
+        }
+        res.exponent_ref_baseline.push(bs.read_bits(6)? as u8);
+        decoder_formatted_print(
+            "SEI (3D reference displays info): exponent_ref_baseline",
+            res.exponent_ref_baseline[i],
+            63,
+        );
+        res.mantissa_ref_baseline.push(decode_sei_mantissa(
+            "SEI (3D reference displays info): mantissa_ref_baseline",
+            res.exponent_ref_baseline[i],
+            res.prec_ref_baseline,
+            bs,
+        )?);
+        res.exponent_ref_display_width.push(bs.read_bits(6)? as u8);
+        decoder_formatted_print(
+            "SEI (3D reference displays info): exponent_ref_display_width",
+            res.exponent_ref_display_width[i],
+            63,
+        );
+        res.mantissa_ref_display_width.push(decode_sei_mantissa(
+            "SEI (3D reference displays info): mantissa_ref_display_width",
+            res.exponent_ref_display_width[i],
+            res.prec_ref_display_width,
+            bs,
+        )?);
+        if res.ref_viewing_distance_flag {
+            res.exponent_ref_viewing_distance
+                .push(bs.read_bits(6)? as u8);
+            decoder_formatted_print(
+                "SEI (3D reference displays info): exponent_ref_viewing_distance",
+                res.exponent_ref_viewing_distance[i],
+                63,
+            );
+            res.mantissa_ref_viewing_distance.push(decode_sei_mantissa(
+                "SEI (3D reference displays info): mantissa_ref_viewing_distance",
+                res.exponent_ref_viewing_distance[i],
+                res.prec_ref_viewing_dist,
+                bs,
+            )?);
+        } else {
+            res.exponent_ref_viewing_distance.push(0);
+            res.mantissa_ref_viewing_distance.push(0);
+        }
+        res.additional_shift_present_flag
+            .push(1 == bs.read_bits(1)?);
+        decoder_formatted_print(
+            "SEI (3D reference displays info): additional_shift_present_flag",
+            res.additional_shift_present_flag[i],
+            63,
+        );
+        if res.additional_shift_present_flag[i] {
+            res.num_sample_shift_plus512.push(bs.read_bits(10)?);
+            decoder_formatted_print(
+                "SEI (3D reference displays info): num_sample_shift_plus512",
+                res.num_sample_shift_plus512[i],
+                63,
+            );
+        } else {
+            res.num_sample_shift_plus512.push(0);
+        }
+    }
+    res.three_dimensional_reference_displays_extension_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (3D reference displays info): three_dimensional_reference_displays_extension_flag",
+        res.three_dimensional_reference_displays_extension_flag,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// I.13.1.5.1 Depth timing offset syntax
+fn decode_depth_timing_offset(bs: &mut ByteStream) -> Result<SEIDepthTimingOffset, DecodeError> {
+    let mut res = SEIDepthTimingOffset::default();
+
+    res.offset_len_minus1 = bs.read_bits(5)? as u8;
+    res.depth_disp_delay_offset_fp = bs.read_bits(res.offset_len_minus1 + 1)?;
+    res.depth_disp_delay_offset_dp = bs.read_bits(6)? as u8;
+    decoder_formatted_print("SEI (Depth timing): depth_timing_offset", res, 63);
+
+    Ok(res)
+}
+
+/// I.13.1.5 Depth timing SEI message syntax
+///
+/// NumDepthViews comes from the most recent MVCD subset SPS
+fn decode_depth_timing(
+    subset_spses: &[SubsetSPS],
+    bs: &mut ByteStream,
+) -> Result<SEIDepthTiming, DecodeError> {
+    let mut res = SEIDepthTiming::new();
+
+    res.per_view_depth_timing_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Depth timing): per_view_depth_timing_flag",
+        res.per_view_depth_timing_flag,
+        63,
+    );
+
+    let num_offsets = if res.per_view_depth_timing_flag {
+        match subset_spses.iter().rev().find(|x| x.is_mvcd()) {
+            Some(x) => x.sps_mvcd.num_depth_views,
+            None => {
+                return Err(DecodeError::new(String::from(
+                    "decode_depth_timing - per_view_depth_timing_flag is set but no MVCD subset SPS was found",
+                )))
+            }
+        }
+    } else {
+        1
+    };
+    for i in 0..num_offsets {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_depth_timing - ran out of bytes at depth view {}",
+                i
+            );
+            break;
+        }
+        res.depth_timing_offset
+            .push(decode_depth_timing_offset(bs)?);
+    }
+
+    Ok(res)
+}
+
+/// I.13.1.7.1 Depth grid position syntax
+fn decode_depth_grid_position(bs: &mut ByteStream) -> Result<SEIDepthGridPosition, DecodeError> {
+    let res = SEIDepthGridPosition {
+        depth_grid_pos_x_fp: bs.read_bits(20)?,
+        depth_grid_pos_x_dp: bs.read_bits(4)? as u8,
+        depth_grid_pos_x_sign_flag: 1 == bs.read_bits(1)?,
+        depth_grid_pos_y_fp: bs.read_bits(20)?,
+        depth_grid_pos_y_dp: bs.read_bits(4)? as u8,
+        depth_grid_pos_y_sign_flag: 1 == bs.read_bits(1)?,
+    };
+    decoder_formatted_print("SEI (Depth sampling info): depth_grid_position", res, 63);
+
+    Ok(res)
+}
+
+/// I.13.1.7 Depth sampling information SEI message syntax
+fn decode_depth_sampling_info(bs: &mut ByteStream) -> Result<SEIDepthSamplingInfo, DecodeError> {
+    let mut res = SEIDepthSamplingInfo::new();
+
+    res.dttsr_x_mul = bs.read_bits(16)?;
+    decoder_formatted_print(
+        "SEI (Depth sampling info): dttsr_x_mul",
+        res.dttsr_x_mul,
+        63,
+    );
+    res.dttsr_x_dp = bs.read_bits(4)? as u8;
+    decoder_formatted_print("SEI (Depth sampling info): dttsr_x_dp", res.dttsr_x_dp, 63);
+    res.dttsr_y_mul = bs.read_bits(16)?;
+    decoder_formatted_print(
+        "SEI (Depth sampling info): dttsr_y_mul",
+        res.dttsr_y_mul,
+        63,
+    );
+    res.dttsr_y_dp = bs.read_bits(4)? as u8;
+    decoder_formatted_print("SEI (Depth sampling info): dttsr_y_dp", res.dttsr_y_dp, 63);
+    res.per_view_depth_grid_pos_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Depth sampling info): per_view_depth_grid_pos_flag",
+        res.per_view_depth_grid_pos_flag,
+        63,
+    );
+
+    if res.per_view_depth_grid_pos_flag {
+        res.num_video_plus_depth_views_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Depth sampling info): num_video_plus_depth_views_minus1",
+            res.num_video_plus_depth_views_minus1,
+            63,
+        );
+        for i in 0..=res.num_video_plus_depth_views_minus1 as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_depth_sampling_info - ran out of bytes at view {}",
+                    i
+                );
+                break;
+            }
+            res.depth_grid_view_id
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SEI (Depth sampling info): depth_grid_view_id",
+                res.depth_grid_view_id[i],
+                63,
+            );
+            res.depth_grid_position
+                .push(decode_depth_grid_position(bs)?);
+        }
+    } else {
+        res.depth_grid_position
+            .push(decode_depth_grid_position(bs)?);
+    }
+
+    Ok(res)
+}
+
+/// J.13.1.1 Constrained depth parameter set identifier SEI message syntax
+fn decode_constrained_depth_parameter_set_identifier(
+    bs: &mut ByteStream,
+) -> Result<SEIConstrainedDepthParameterSetIdentifier, DecodeError> {
+    let mut res = SEIConstrainedDepthParameterSetIdentifier::new();
+
+    res.max_dps_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Constrained depth parameter set identifier): max_dps_id",
+        res.max_dps_id,
+        63,
+    );
+    res.max_dps_id_diff = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Constrained depth parameter set identifier): max_dps_id_diff",
+        res.max_dps_id_diff,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// D.1.28 Green metadata SEI message syntax
+/// The syntax for this SEI message is specified in ISO/IEC 23001-11 (Green metadata), which facilitates reduced power
+/// consumption in decoders, encoders, displays, and in media selection
+fn decode_green_metadata(_payload_size: u32, _bs: &mut ByteStream) {}
+
+/// D.1.29 Mastering display colour volume SEI message syntax
+fn decode_mastering_display_colour_volume(
+    bs: &mut ByteStream,
+) -> Result<SEIMasteringDisplayColourVolume, DecodeError> {
+    let mut res = SEIMasteringDisplayColourVolume::new();
+
+    for c in 0..3 {
+        res.display_primaries_x[c] = bs.read_bits(16)?;
+        res.display_primaries_y[c] = bs.read_bits(16)?;
+    }
+    decoder_formatted_print(
+        "SEI (Mastering display colour volume): display_primaries_x",
+        res.display_primaries_x,
+        63,
+    );
+    decoder_formatted_print(
+        "SEI (Mastering display colour volume): display_primaries_y",
+        res.display_primaries_y,
+        63,
+    );
+    res.white_point_x = bs.read_bits(16)?;
+    decoder_formatted_print(
+        "SEI (Mastering display colour volume): white_point_x",
+        res.white_point_x,
+        63,
+    );
+    res.white_point_y = bs.read_bits(16)?;
+    decoder_formatted_print(
+        "SEI (Mastering display colour volume): white_point_y",
+        res.white_point_y,
+        63,
+    );
+    res.max_display_mastering_luminance = bs.read_bits(32)?;
+    decoder_formatted_print(
+        "SEI (Mastering display colour volume): max_display_mastering_luminance",
+        res.max_display_mastering_luminance,
+        63,
+    );
+    res.min_display_mastering_luminance = bs.read_bits(32)?;
+    decoder_formatted_print(
+        "SEI (Mastering display colour volume): min_display_mastering_luminance",
+        res.min_display_mastering_luminance,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// Reads the pre or post look-up table values of the colour remapping SEI
+fn decode_colour_remap_lut(
+    bs: &mut ByteStream,
+    num_val_minus1: u8,
+    coded_bits: u32,
+    target_bits: u32,
+) -> Result<(Vec<u32>, Vec<u32>), DecodeError> {
+    let mut coded_value = Vec::new();
+    let mut target_value = Vec::new();
+    if num_val_minus1 > 0 {
+        for _ in 0..=num_val_minus1 {
+            coded_value.push(read_wide_bits(bs, coded_bits)?);
+            target_value.push(read_wide_bits(bs, target_bits)?);
+        }
+    }
+    Ok((coded_value, target_value))
+}
+
+/// D.1.30 Colour remapping information SEI message syntax
+fn decode_colour_remapping_info(
+    bs: &mut ByteStream,
+) -> Result<SEIColourRemappingInfo, DecodeError> {
+    let mut res = SEIColourRemappingInfo::new();
+
+    res.colour_remap_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Colour remapping): colour_remap_id",
+        res.colour_remap_id,
+        63,
+    );
+    res.colour_remap_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Colour remapping): colour_remap_cancel_flag",
+        res.colour_remap_cancel_flag,
+        63,
+    );
+    if !res.colour_remap_cancel_flag {
+        res.colour_remap_repetition_period = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_repetition_period",
+            res.colour_remap_repetition_period,
+            63,
+        );
+        res.colour_remap_video_signal_info_present_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_video_signal_info_present_flag",
+            res.colour_remap_video_signal_info_present_flag,
+            63,
+        );
+        if res.colour_remap_video_signal_info_present_flag {
+            res.colour_remap_full_range_flag = 1 == bs.read_bits(1)?;
+            decoder_formatted_print(
+                "SEI (Colour remapping): colour_remap_full_range_flag",
+                res.colour_remap_full_range_flag,
                 63,
             );
-            let frame0_grid_position_y = bs.read_bits(4);
+            res.colour_remap_primaries = bs.read_bits(8)? as u8;
             decoder_formatted_print(
-                "SEI (Frame Packing): frame0_grid_position_y",
-                &frame0_grid_position_y,
+                "SEI (Colour remapping): colour_remap_primaries",
+                res.colour_remap_primaries,
                 63,
             );
-            let frame1_grid_position_x = bs.read_bits(4);
+            res.colour_remap_transfer_function = bs.read_bits(8)? as u8;
             decoder_formatted_print(
-                "SEI (Frame Packing): frame1_grid_position_x",
-                &frame1_grid_position_x,
+                "SEI (Colour remapping): colour_remap_transfer_function",
+                res.colour_remap_transfer_function,
                 63,
             );
-            let frame1_grid_position_y = bs.read_bits(4);
+            res.colour_remap_matrix_coefficients = bs.read_bits(8)? as u8;
             decoder_formatted_print(
-                "SEI (Frame Packing): frame1_grid_position_y",
-                &frame1_grid_position_y,
+                "SEI (Colour remapping): colour_remap_matrix_coefficients",
+                res.colour_remap_matrix_coefficients,
+                63,
+            );
+        }
+        res.colour_remap_input_bit_depth = bs.read_bits(8)? as u8;
+        decoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_input_bit_depth",
+            res.colour_remap_input_bit_depth,
+            63,
+        );
+        res.colour_remap_output_bit_depth = bs.read_bits(8)? as u8;
+        decoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_output_bit_depth",
+            res.colour_remap_output_bit_depth,
+            63,
+        );
+
+        let input_bits = ((res.colour_remap_input_bit_depth as u32 + 7) >> 3) << 3;
+        let output_bits = ((res.colour_remap_output_bit_depth as u32 + 7) >> 3) << 3;
+        for c in 0..3 {
+            res.pre_lut_num_val_minus1[c] = bs.read_bits(8)? as u8;
+            let (coded_value, target_value) = decode_colour_remap_lut(
+                bs,
+                res.pre_lut_num_val_minus1[c],
+                input_bits,
+                output_bits,
+            )?;
+            res.pre_lut_coded_value.push(coded_value);
+            res.pre_lut_target_value.push(target_value);
+        }
+        decoder_formatted_print(
+            "SEI (Colour remapping): pre_lut_num_val_minus1",
+            res.pre_lut_num_val_minus1,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Colour remapping): pre_lut_coded_value",
+            &res.pre_lut_coded_value,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Colour remapping): pre_lut_target_value",
+            &res.pre_lut_target_value,
+            63,
+        );
+
+        res.colour_remap_matrix_present_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_matrix_present_flag",
+            res.colour_remap_matrix_present_flag,
+            63,
+        );
+        if res.colour_remap_matrix_present_flag {
+            res.log2_matrix_denom = bs.read_bits(4)? as u8;
+            decoder_formatted_print(
+                "SEI (Colour remapping): log2_matrix_denom",
+                res.log2_matrix_denom,
+                63,
+            );
+            for c in 0..3 {
+                for i in 0..3 {
+                    res.colour_remap_coeffs[c][i] = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+                }
+            }
+            decoder_formatted_print(
+                "SEI (Colour remapping): colour_remap_coeffs",
+                res.colour_remap_coeffs,
+                63,
+            );
+        }
+
+        for c in 0..3 {
+            res.post_lut_num_val_minus1[c] = bs.read_bits(8)? as u8;
+            let (coded_value, target_value) = decode_colour_remap_lut(
+                bs,
+                res.post_lut_num_val_minus1[c],
+                output_bits,
+                output_bits,
+            )?;
+            res.post_lut_coded_value.push(coded_value);
+            res.post_lut_target_value.push(target_value);
+        }
+        decoder_formatted_print(
+            "SEI (Colour remapping): post_lut_num_val_minus1",
+            res.post_lut_num_val_minus1,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Colour remapping): post_lut_coded_value",
+            &res.post_lut_coded_value,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Colour remapping): post_lut_target_value",
+            &res.post_lut_target_value,
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// D.1.31 Content light level information SEI message syntax
+fn decode_content_light_level_info(
+    bs: &mut ByteStream,
+) -> Result<SEIContentLightLevelInfo, DecodeError> {
+    let mut res = SEIContentLightLevelInfo::new();
+
+    res.max_content_light_level = bs.read_bits(16)?;
+    decoder_formatted_print(
+        "SEI (Content light level): max_content_light_level",
+        res.max_content_light_level,
+        63,
+    );
+    res.max_pic_average_light_level = bs.read_bits(16)?;
+    decoder_formatted_print(
+        "SEI (Content light level): max_pic_average_light_level",
+        res.max_pic_average_light_level,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// D.1.32 Alternative transfer characteristics SEI message syntax
+fn decode_alternative_transfer_characteristics(
+    bs: &mut ByteStream,
+) -> Result<SEIAlternativeTransferCharacteristics, DecodeError> {
+    let mut res = SEIAlternativeTransferCharacteristics::new();
+
+    res.preferred_transfer_characteristics = bs.read_bits(8)? as u8;
+    decoder_formatted_print(
+        "SEI (Alternative transfer characteristics): preferred_transfer_characteristics",
+        res.preferred_transfer_characteristics,
+        63,
+    );
+
+    Ok(res)
+}
+
+/// D.1.33 Content colour volume SEI message syntax
+fn decode_content_colour_volume(
+    bs: &mut ByteStream,
+) -> Result<SEIContentColourVolume, DecodeError> {
+    let mut res = SEIContentColourVolume::new();
+
+    res.ccv_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Content colour volume): ccv_cancel_flag",
+        res.ccv_cancel_flag,
+        63,
+    );
+    if !res.ccv_cancel_flag {
+        res.ccv_persistence_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Content colour volume): ccv_persistence_flag",
+            res.ccv_persistence_flag,
+            63,
+        );
+        res.ccv_primaries_present_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Content colour volume): ccv_primaries_present_flag",
+            res.ccv_primaries_present_flag,
+            63,
+        );
+        res.ccv_min_luminance_value_present_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Content colour volume): ccv_min_luminance_value_present_flag",
+            res.ccv_min_luminance_value_present_flag,
+            63,
+        );
+        res.ccv_max_luminance_value_present_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Content colour volume): ccv_max_luminance_value_present_flag",
+            res.ccv_max_luminance_value_present_flag,
+            63,
+        );
+        res.ccv_avg_luminance_value_present_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Content colour volume): ccv_avg_luminance_value_present_flag",
+            res.ccv_avg_luminance_value_present_flag,
+            63,
+        );
+        res.ccv_reserved_zero_2bits = bs.read_bits(2)? as u8;
+        decoder_formatted_print(
+            "SEI (Content colour volume): ccv_reserved_zero_2bits",
+            res.ccv_reserved_zero_2bits,
+            63,
+        );
+        if res.ccv_primaries_present_flag {
+            for c in 0..3 {
+                res.ccv_primaries_x[c] = bs.read_bits(32)? as i32;
+                res.ccv_primaries_y[c] = bs.read_bits(32)? as i32;
+            }
+            decoder_formatted_print(
+                "SEI (Content colour volume): ccv_primaries_x",
+                res.ccv_primaries_x,
+                63,
+            );
+            decoder_formatted_print(
+                "SEI (Content colour volume): ccv_primaries_y",
+                res.ccv_primaries_y,
+                63,
+            );
+        }
+        if res.ccv_min_luminance_value_present_flag {
+            res.ccv_min_luminance_value = bs.read_bits(32)?;
+            decoder_formatted_print(
+                "SEI (Content colour volume): ccv_min_luminance_value",
+                res.ccv_min_luminance_value,
+                63,
+            );
+        }
+        if res.ccv_max_luminance_value_present_flag {
+            res.ccv_max_luminance_value = bs.read_bits(32)?;
+            decoder_formatted_print(
+                "SEI (Content colour volume): ccv_max_luminance_value",
+                res.ccv_max_luminance_value,
+                63,
+            );
+        }
+        if res.ccv_avg_luminance_value_present_flag {
+            res.ccv_avg_luminance_value = bs.read_bits(32)?;
+            decoder_formatted_print(
+                "SEI (Content colour volume): ccv_avg_luminance_value",
+                res.ccv_avg_luminance_value,
+                63,
+            );
+        }
+    }
+
+    Ok(res)
+}
+
+/// D.1.34 Ambient viewing environment SEI message syntax
+fn decode_ambient_viewing_environment(_payload_size: u32, _bs: &mut ByteStream) {}
+
+/// D.1.35.1 Equirectangular projection SEI message syntax
+fn decode_equirectangular_projection(
+    bs: &mut ByteStream,
+) -> Result<SEIEquirectangularProjection, DecodeError> {
+    let mut res = SEIEquirectangularProjection::new();
+
+    res.erp_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Equirectangular projection): erp_cancel_flag",
+        res.erp_cancel_flag,
+        63,
+    );
+    if !res.erp_cancel_flag {
+        res.erp_persistence_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Equirectangular projection): erp_persistence_flag",
+            res.erp_persistence_flag,
+            63,
+        );
+        res.erp_padding_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Equirectangular projection): erp_padding_flag",
+            res.erp_padding_flag,
+            63,
+        );
+        res.erp_reserved_zero_2bits = bs.read_bits(2)? as u8;
+        decoder_formatted_print(
+            "SEI (Equirectangular projection): erp_reserved_zero_2bits",
+            res.erp_reserved_zero_2bits,
+            63,
+        );
+        if res.erp_padding_flag {
+            res.gb_erp_type = bs.read_bits(3)? as u8;
+            decoder_formatted_print(
+                "SEI (Equirectangular projection): gb_erp_type",
+                res.gb_erp_type,
+                63,
+            );
+            res.left_gb_erp_width = bs.read_bits(8)? as u8;
+            decoder_formatted_print(
+                "SEI (Equirectangular projection): left_gb_erp_width",
+                res.left_gb_erp_width,
+                63,
+            );
+            res.right_gb_erp_width = bs.read_bits(8)? as u8;
+            decoder_formatted_print(
+                "SEI (Equirectangular projection): right_gb_erp_width",
+                res.right_gb_erp_width,
                 63,
             );
         }
-        let frame_packing_arrangement_reserved_byte = bs.read_bits(8);
+    }
+
+    Ok(res)
+}
+
+/// D.1.35.2 Cubemap projection SEI message syntax
+fn decode_cubemap_projection(bs: &mut ByteStream) -> Result<SEICubemapProjection, DecodeError> {
+    let mut res = SEICubemapProjection::new();
+
+    res.cmp_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Cubemap projection): cmp_cancel_flag",
+        res.cmp_cancel_flag,
+        63,
+    );
+    if !res.cmp_cancel_flag {
+        res.cmp_persistence_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Cubemap projection): cmp_persistence_flag",
+            res.cmp_persistence_flag,
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// D.1.35.3 Sphere rotation SEI message syntax
+fn decode_sphere_rotation(bs: &mut ByteStream) -> Result<SEISphereRotation, DecodeError> {
+    let mut res = SEISphereRotation::new();
+
+    res.sphere_rotation_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Sphere rotation): sphere_rotation_cancel_flag",
+        res.sphere_rotation_cancel_flag,
+        63,
+    );
+    if !res.sphere_rotation_cancel_flag {
+        res.sphere_rotation_persistence_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Sphere rotation): sphere_rotation_persistence_flag",
+            res.sphere_rotation_persistence_flag,
+            63,
+        );
+        res.sphere_rotation_reserved_zero_6bits = bs.read_bits(6)? as u8;
+        decoder_formatted_print(
+            "SEI (Sphere rotation): sphere_rotation_reserved_zero_6bits",
+            res.sphere_rotation_reserved_zero_6bits,
+            63,
+        );
+        res.yaw_rotation = bs.read_bits(32)? as i32;
+        decoder_formatted_print("SEI (Sphere rotation): yaw_rotation", res.yaw_rotation, 63);
+        res.pitch_rotation = bs.read_bits(32)? as i32;
+        decoder_formatted_print(
+            "SEI (Sphere rotation): pitch_rotation",
+            res.pitch_rotation,
+            63,
+        );
+        res.roll_rotation = bs.read_bits(32)? as i32;
+        decoder_formatted_print(
+            "SEI (Sphere rotation): roll_rotation",
+            res.roll_rotation,
+            63,
+        );
+    }
+
+    Ok(res)
+}
+
+/// D.1.35.4 Region-wise packing SEI message syntax
+fn decode_regionwise_packing(bs: &mut ByteStream) -> Result<SEIRegionwisePacking, DecodeError> {
+    let mut res = SEIRegionwisePacking::new();
+
+    res.rwp_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Region-wise packing): rwp_cancel_flag",
+        res.rwp_cancel_flag,
+        63,
+    );
+    if !res.rwp_cancel_flag {
+        res.rwp_persistence_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Region-wise packing): rwp_persistence_flag",
+            res.rwp_persistence_flag,
+            63,
+        );
+        res.constituent_picture_matching_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Region-wise packing): constituent_picture_matching_flag",
+            res.constituent_picture_matching_flag,
+            63,
+        );
+        res.rwp_reserved_zero_5bits = bs.read_bits(5)? as u8;
+        decoder_formatted_print(
+            "SEI (Region-wise packing): rwp_reserved_zero_5bits",
+            res.rwp_reserved_zero_5bits,
+            63,
+        );
+        res.num_packed_regions = bs.read_bits(8)? as u8;
+        decoder_formatted_print(
+            "SEI (Region-wise packing): num_packed_regions",
+            res.num_packed_regions,
+            63,
+        );
+        res.proj_picture_width = bs.read_bits(32)?;
+        decoder_formatted_print(
+            "SEI (Region-wise packing): proj_picture_width",
+            res.proj_picture_width,
+            63,
+        );
+        res.proj_picture_height = bs.read_bits(32)?;
+        decoder_formatted_print(
+            "SEI (Region-wise packing): proj_picture_height",
+            res.proj_picture_height,
+            63,
+        );
+        res.packed_picture_width = bs.read_bits(16)?;
+        decoder_formatted_print(
+            "SEI (Region-wise packing): packed_picture_width",
+            res.packed_picture_width,
+            63,
+        );
+        res.packed_picture_height = bs.read_bits(16)?;
+        decoder_formatted_print(
+            "SEI (Region-wise packing): packed_picture_height",
+            res.packed_picture_height,
+            63,
+        );
+
+        for i in 0..res.num_packed_regions as usize {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_regionwise_packing - ran out of bytes at region {} of {}",
+                    i, res.num_packed_regions
+                );
+                break;
+            }
+            res.rwp_reserved_zero_4bits.push(bs.read_bits(4)? as u8);
+            res.rwp_transform_type.push(bs.read_bits(3)? as u8);
+            res.rwp_guard_band_flag.push(1 == bs.read_bits(1)?);
+            res.proj_region_width.push(bs.read_bits(32)?);
+            res.proj_region_height.push(bs.read_bits(32)?);
+            res.proj_region_top.push(bs.read_bits(32)?);
+            res.proj_region_left.push(bs.read_bits(32)?);
+            res.packed_region_width.push(bs.read_bits(16)?);
+            res.packed_region_height.push(bs.read_bits(16)?);
+            res.packed_region_top.push(bs.read_bits(16)?);
+            res.packed_region_left.push(bs.read_bits(16)?);
+            if res.rwp_guard_band_flag[i] {
+                res.rwp_left_guard_band_width.push(bs.read_bits(8)? as u8);
+                res.rwp_right_guard_band_width.push(bs.read_bits(8)? as u8);
+                res.rwp_top_guard_band_height.push(bs.read_bits(8)? as u8);
+                res.rwp_bottom_guard_band_height
+                    .push(bs.read_bits(8)? as u8);
+                res.rwp_guard_band_not_used_for_pred_flag
+                    .push(1 == bs.read_bits(1)?);
+                let mut guard_band_type = [0; 4];
+                for t in guard_band_type.iter_mut() {
+                    *t = bs.read_bits(3)? as u8;
+                }
+                res.rwp_guard_band_type.push(guard_band_type);
+                res.rwp_guard_band_reserved_zero_3bits
+                    .push(bs.read_bits(3)? as u8);
+            } else {
+                res.rwp_left_guard_band_width.push(0);
+                res.rwp_right_guard_band_width.push(0);
+                res.rwp_top_guard_band_height.push(0);
+                res.rwp_bottom_guard_band_height.push(0);
+                res.rwp_guard_band_not_used_for_pred_flag.push(false);
+                res.rwp_guard_band_type.push([0; 4]);
+                res.rwp_guard_band_reserved_zero_3bits.push(0);
+            }
+        }
         decoder_formatted_print(
-            "SEI (Frame Packing): frame_packing_arrangement_reserved_byte",
-            &frame_packing_arrangement_reserved_byte,
+            "SEI (Region-wise packing): rwp_transform_type",
+            &res.rwp_transform_type,
             63,
         );
-        let frame_packing_arrangement_repetition_period =
-            exp_golomb_decode_one_wrapper(bs, false, 0);
         decoder_formatted_print(
-            "SEI (Frame Packing): frame_packing_arrangement_repetition_period",
-            &frame_packing_arrangement_repetition_period,
+            "SEI (Region-wise packing): rwp_guard_band_flag",
+            &res.rwp_guard_band_flag,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Region-wise packing): proj_region_width",
+            &res.proj_region_width,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Region-wise packing): proj_region_height",
+            &res.proj_region_height,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Region-wise packing): packed_region_width",
+            &res.packed_region_width,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Region-wise packing): packed_region_height",
+            &res.packed_region_height,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Region-wise packing): rwp_guard_band_type",
+            &res.rwp_guard_band_type,
             63,
         );
     }
-    let frame_packing_arrangement_extension_flag = 1 == bs.read_bits(1);
+
+    Ok(res)
+}
+
+/// D.1.35.5 Omnidirectional viewport SEI message syntax
+fn decode_omni_viewport(bs: &mut ByteStream) -> Result<SEIOmniViewport, DecodeError> {
+    let mut res = SEIOmniViewport::new();
+
+    res.omni_viewport_id = bs.read_bits(10)?;
     decoder_formatted_print(
-        "SEI (Frame Packing): frame_packing_arrangement_extension_flag",
-        &frame_packing_arrangement_extension_flag,
+        "SEI (Omnidirectional viewport): omni_viewport_id",
+        res.omni_viewport_id,
+        63,
+    );
+    res.omni_viewport_cancel_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Omnidirectional viewport): omni_viewport_cancel_flag",
+        res.omni_viewport_cancel_flag,
         63,
     );
+    if !res.omni_viewport_cancel_flag {
+        res.omni_viewport_persistence_flag = 1 == bs.read_bits(1)?;
+        decoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_persistence_flag",
+            res.omni_viewport_persistence_flag,
+            63,
+        );
+        res.omni_viewport_cnt_minus1 = bs.read_bits(4)? as u8;
+        decoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_cnt_minus1",
+            res.omni_viewport_cnt_minus1,
+            63,
+        );
+        for i in 0..=res.omni_viewport_cnt_minus1 {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_omni_viewport - ran out of bytes at viewport {}",
+                    i
+                );
+                break;
+            }
+            res.omni_viewport_azimuth_centre
+                .push(bs.read_bits(32)? as i32);
+            res.omni_viewport_elevation_centre
+                .push(bs.read_bits(32)? as i32);
+            res.omni_viewport_tilt_centre.push(bs.read_bits(32)? as i32);
+            res.omni_viewport_hor_range.push(bs.read_bits(32)?);
+            res.omni_viewport_ver_range.push(bs.read_bits(32)?);
+        }
+        decoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_azimuth_centre",
+            &res.omni_viewport_azimuth_centre,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_elevation_centre",
+            &res.omni_viewport_elevation_centre,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_tilt_centre",
+            &res.omni_viewport_tilt_centre,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_hor_range",
+            &res.omni_viewport_hor_range,
+            63,
+        );
+        decoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_ver_range",
+            &res.omni_viewport_ver_range,
+            63,
+        );
+    }
+
+    Ok(res)
 }
 
-/// H.13.1.10 Multiview view position SEI message syntax
-fn decode_multiview_view_position(_payload_size: u32, _bs: &mut ByteStream) {}
+/// I.13.1.6 Alternative depth information SEI message syntax
+fn decode_alternative_depth_info(
+    bs: &mut ByteStream,
+) -> Result<SEIAlternativeDepthInfo, DecodeError> {
+    let mut res = SEIAlternativeDepthInfo::new();
 
-/// D.1.27 Display orientation SEI message syntax
-fn decode_display_orientation(_payload_size: u32, _bs: &mut ByteStream) {}
+    res.depth_type = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Alternative depth info): depth_type",
+        res.depth_type,
+        63,
+    );
+    if res.depth_type != 0 {
+        return Ok(res);
+    }
+
+    res.num_constituent_views_gvd_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(
+        "SEI (Alternative depth info): num_constituent_views_gvd_minus1",
+        res.num_constituent_views_gvd_minus1,
+        63,
+    );
+    res.depth_present_gvd_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Alternative depth info): depth_present_gvd_flag",
+        res.depth_present_gvd_flag,
+        63,
+    );
+    res.z_gvd_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Alternative depth info): z_gvd_flag",
+        res.z_gvd_flag,
+        63,
+    );
+    res.intrinsic_param_gvd_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Alternative depth info): intrinsic_param_gvd_flag",
+        res.intrinsic_param_gvd_flag,
+        63,
+    );
+    res.rotation_gvd_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Alternative depth info): rotation_gvd_flag",
+        res.rotation_gvd_flag,
+        63,
+    );
+    res.translation_gvd_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SEI (Alternative depth info): translation_gvd_flag",
+        res.translation_gvd_flag,
+        63,
+    );
 
-/// I.13.1.2 MVCD scalable nesting SEI message syntax
-fn decode_mvcd_scalable_nesting(_payload_size: u32, _bs: &mut ByteStream) {}
+    // the constituent views plus the generated view
+    let num_views = res.num_constituent_views_gvd_minus1 as u64 + 2;
+    if res.z_gvd_flag {
+        for i in 0..num_views {
+            if bs.bytestream.is_empty() {
+                println!(
+                    "[WARNING] decode_alternative_depth_info - ran out of bytes at z values of view {}",
+                    i
+                );
+                break;
+            }
+            res.gvd_z_near.push(decode_depth_representation_sei_element(
+                "SEI (Alternative depth info): gvd_z_near",
+                bs,
+            )?);
+            res.gvd_z_far.push(decode_depth_representation_sei_element(
+                "SEI (Alternative depth info): gvd_z_far",
+                bs,
+            )?);
+        }
+    }
+    if res.intrinsic_param_gvd_flag {
+        res.prec_gvd_focal_length = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Alternative depth info): prec_gvd_focal_length",
+            res.prec_gvd_focal_length,
+            63,
+        );
+        res.prec_gvd_principal_point = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Alternative depth info): prec_gvd_principal_point",
+            res.prec_gvd_principal_point,
+            63,
+        );
+    }
+    if res.rotation_gvd_flag {
+        res.prec_gvd_rotation_param = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Alternative depth info): prec_gvd_rotation_param",
+            res.prec_gvd_rotation_param,
+            63,
+        );
+    }
+    if res.translation_gvd_flag {
+        res.prec_gvd_translation_param = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SEI (Alternative depth info): prec_gvd_translation_param",
+            res.prec_gvd_translation_param,
+            63,
+        );
+    }
+    if !res.intrinsic_param_gvd_flag && !res.rotation_gvd_flag && !res.translation_gvd_flag {
+        return Ok(res);
+    }
 
-/// I.13.1.1 MVCD view scalability information SEI message syntax
-fn decode_mvcd_view_scalability_info(_payload_size: u32, _bs: &mut ByteStream) {}
+    for i in 0..num_views {
+        if bs.bytestream.is_empty() {
+            println!(
+                "[WARNING] decode_alternative_depth_info - ran out of bytes at camera parameters of view {}",
+                i
+            );
+            break;
+        }
+        if res.intrinsic_param_gvd_flag {
+            res.gvd_focal_length_x.push(decode_sei_float_value(
+                "SEI (Alternative depth info): gvd_focal_length_x",
+                res.prec_gvd_focal_length,
+                bs,
+            )?);
+            res.gvd_focal_length_y.push(decode_sei_float_value(
+                "SEI (Alternative depth info): gvd_focal_length_y",
+                res.prec_gvd_focal_length,
+                bs,
+            )?);
+            res.gvd_principal_point_x.push(decode_sei_float_value(
+                "SEI (Alternative depth info): gvd_principal_point_x",
+                res.prec_gvd_principal_point,
+                bs,
+            )?);
+            res.gvd_principal_point_y.push(decode_sei_float_value(
+                "SEI (Alternative depth info): gvd_principal_point_y",
+                res.prec_gvd_principal_point,
+                bs,
+            )?);
+        } else {
+            res.gvd_focal_length_x.push(SEIFloatValue::default());
+            res.gvd_focal_length_y.push(SEIFloatValue::default());
+            res.gvd_principal_point_x.push(SEIFloatValue::default());
+            res.gvd_principal_point_y.push(SEIFloatValue::default());
+        }
+        let mut r = [[SEIFloatValue::default(); 3]; 3];
+        if res.rotation_gvd_flag {
+            for row in r.iter_mut() {
+                for value in row.iter_mut() {
+                    *value = decode_sei_float_value(
+                        "SEI (Alternative depth info): gvd_r",
+                        res.prec_gvd_rotation_param,
+                        bs,
+                    )?;
+                }
+            }
+        }
+        res.gvd_r.push(r);
+        res.gvd_t_x.push(if res.translation_gvd_flag {
+            decode_sei_float_value(
+                "SEI (Alternative depth info): gvd_t_x",
+                res.prec_gvd_translation_param,
+                bs,
+            )?
+        } else {
+            SEIFloatValue::default()
+        });
+    }
 
-/// I.13.1.3 Depth representation information SEI message syntax
-fn decode_depth_representation_info(_payload_size: u32, _bs: &mut ByteStream) {}
+    Ok(res)
+}
 
-/// I.13.1.4 3D reference displays information SEI message syntax
-fn decode_three_dimensional_reference_displays_info(_payload_size: u32, _bs: &mut ByteStream) {}
+/// D.1.36 SEI manifest SEI message syntax
+fn decode_sei_manifest(_payload_size: u32, _bs: &mut ByteStream) {}
 
-/// I.13.1.5 Depth timing SEI message syntax
-fn decode_depth_timing(_payload_size: u32, _bs: &mut ByteStream) {}
+/// D.1.37 SEI prefix indication SEI message syntax
+fn decode_sei_prefix_indication(_payload_size: u32, _bs: &mut ByteStream) {}
 
-/// I.13.1.7 Depth sampling information SEI message syntax
-fn decode_depth_sampling_info(_payload_size: u32, _bs: &mut ByteStream) {}
+/// D.1.38 Annotated regions SEI message syntax
+fn decode_annotated_regions(_payload_size: u32, _bs: &mut ByteStream) {}
 
-/// J.13.1.1 Constrained depth parameter set identifier SEI message syntax
-fn decode_constrained_depth_parameter_set_identifier(_payload_size: u32, _bs: &mut ByteStream) {}
+/// D.1.39 Shutter interval information SEI message syntax
+fn decode_shutter_interval_info(_payload_size: u32, _bs: &mut ByteStream) {}
 
-/// D.1.28 Green metadata SEI message syntax
-/// The syntax for this SEI message is specified in ISO/IEC 23001-11 (Green metadata), which facilitates reduced power
-/// consumption in decoders, encoders, displays, and in media selection
-fn decode_green_metadata(_payload_size: u32, _bs: &mut ByteStream) {}
+/// D.1.40 Reserved SEI message syntax
+fn decode_reserved_sei_message(_payload_size: u32, _bs: &mut ByteStream) {}
 
-/// D.1.29 Mastering display colour volume SEI message syntax
-fn decode_mastering_display_colour_volume(_payload_size: u32, _bs: &mut ByteStream) {}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::data_structures::H264DecodedStream;
+    use crate::common::test_support::assert_sei_payloads_match;
+    use crate::common::test_support::round_trip;
+    use crate::common::test_support::stream_with_empty_sei;
+    use crate::encoder::sei::encode_sei_message;
+    use crate::vidgen::film::FilmState;
+    use crate::vidgen::generate_configurations::RandomBoolRange;
+    use crate::vidgen::generate_configurations::RandomSEIRange;
+    use crate::vidgen::generate_configurations::RandomU32Enum;
+    use crate::vidgen::generate_configurations::RandomU32Range;
+    use crate::vidgen::sei::random_sei;
+
+    #[test]
+    fn test_sei_d2_messages() {
+        // hand-assembled D.2.4 to D.2.19 messages for a 2x2 map unit SPS that allows fields
+        let sei_bytes = vec![
+            0x02, 0x06, 0x44, 0x71, 0x12, 0x0a, 0x68, 0xae, 0x03, 0x03, 0xff, 0xff, 0xff, 0x07,
+            0x05, 0x1b, 0xa6, 0x4a, 0x2b, 0xc0, 0x08, 0x04, 0x24, 0xb5, 0x6c, 0xd2, 0x09, 0x03,
+            0x88, 0x28, 0x98, 0x0a, 0x02, 0x4e, 0xcf, 0x0b, 0x09, 0x50, 0x3e, 0x81, 0xe0, 0x00,
+            0x0f, 0xa0, 0x78, 0x04, 0x0c, 0x0b, 0x92, 0x00, 0x02, 0xbf, 0x21, 0x03, 0xe8, 0x07,
+            0x80, 0x24, 0xf0, 0x0d, 0x01, 0x2c, 0x0e, 0x00, 0x0f, 0x01, 0x15, 0x10, 0x01, 0x64,
+            0x11, 0x01, 0x70, 0x80,
+        ];
+        let mut sps = SeqParameterSet::new();
+        sps.pic_width_in_mbs_minus1 = 1;
+        sps.pic_height_in_map_units_minus1 = 1;
+        let spses = vec![sps];
+
+        let sei = decode_sei_message(&spses, &Vec::new(), &mut ByteStream::new(sei_bytes.clone()))
+            .unwrap();
+        assert_eq!(
+            sei.payload_type,
+            vec![2, 3, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17]
+        );
+        assert!(sei.payload.iter().all(|x| x.available));
+
+        let psr = &sei.payload[0].pan_scan_rect;
+        assert_eq!(psr.pan_scan_rect_id, 1);
+        assert!(!psr.pan_scan_rect_cancel_flag);
+        assert_eq!(psr.pan_scan_cnt_minus1, 1);
+        assert_eq!(psr.pan_scan_rect_left_offset, vec![-3, 5]);
+        assert_eq!(psr.pan_scan_rect_right_offset, vec![4, -1]);
+        assert_eq!(psr.pan_scan_rect_top_offset, vec![0, 1]);
+        assert_eq!(psr.pan_scan_rect_bottom_offset, vec![2, -2]);
+        assert_eq!(psr.pan_scan_rect_repetition_period, 2);
+
+        assert_eq!(sei.payload[1].filler_payload.ff_byte, vec![0xff; 3]);
+
+        let drpm = &sei.payload[2].dec_ref_pic_marking_repetition;
+        assert!(!drpm.original_idr_flag);
+        assert_eq!(drpm.original_frame_num, 5);
+        assert!(drpm.original_field_pic_flag);
+        assert!(drpm.original_bottom_field_flag);
+        assert!(drpm.adaptive_ref_pic_marking_mode_flag);
+        assert_eq!(drpm.memory_management_control_operation, vec![1, 3, 4, 0]);
+        assert_eq!(drpm.difference_of_pic_nums_minus1, vec![2, 0, 0, 0]);
+        assert_eq!(drpm.long_term_frame_idx, vec![0, 1, 0, 0]);
+        assert_eq!(drpm.max_long_term_frame_idx_plus1, vec![0, 0, 2, 0]);
+
+        let spare = &sei.payload[3].spare_pic;
+        assert_eq!(spare.target_frame_num, 3);
+        assert!(spare.spare_field_flag);
+        assert!(!spare.target_bottom_field_flag);
+        assert_eq!(spare.num_spare_pics_minus1, 1);
+        assert_eq!(spare.delta_spare_frame_num, vec![0, 2]);
+        assert_eq!(spare.spare_bottom_field_flag, vec![true, false]);
+        assert_eq!(spare.spare_area_idc, vec![1, 2]);
+        assert_eq!(spare.spare_unit_flag[0], vec![true, false, true, true]);
+        assert_eq!(spare.zero_run_length[1], vec![1, 1]);
+
+        let scene = &sei.payload[4].scene_info;
+        assert!(scene.scene_info_present_flag);
+        assert_eq!(scene.scene_id, 7);
+        assert_eq!(scene.scene_transition_type, 4);
+        assert_eq!(scene.second_scene_id, 8);
+
+        let ssi = &sei.payload[5].sub_seq_info;
+        assert_eq!(ssi.sub_seq_layer_num, 1);
+        assert_eq!(ssi.sub_seq_id, 2);
+        assert!(ssi.first_ref_pic_flag);
+        assert!(!ssi.leading_non_ref_pic_flag);
+        assert!(ssi.last_pic_flag);
+        assert_eq!(ssi.sub_seq_frame_num, 6);
+
+        let sslc = &sei.payload[6].sub_seq_layer_characteristics;
+        assert_eq!(sslc.num_sub_seq_layers_minus1, 1);
+        assert_eq!(sslc.accurate_statistics_flag, vec![true, false]);
+        assert_eq!(sslc.average_bit_rate, vec![1000, 500]);
+        assert_eq!(sslc.average_frame_rate, vec![7680, 3840]);
+
+        let ssc = &sei.payload[7].sub_seq_characteristics;
+        assert_eq!(ssc.sub_seq_id, 3);
+        assert_eq!(ssc.sub_seq_duration, 90000);
+        assert!(ssc.average_rate_flag);
+        assert!(!ssc.accurate_statistics_flag);
+        assert_eq!(ssc.average_bit_rate, 2000);
+        assert_eq!(ssc.average_frame_rate, 3840);
+        assert_eq!(ssc.ref_sub_seq_layer_num, vec![1]);
+        assert_eq!(ssc.ref_sub_seq_id, vec![2]);
+        assert_eq!(ssc.ref_sub_seq_direction, vec![true]);
+
+        assert_eq!(
+            sei.payload[8]
+                .full_frame_freeze
+                .full_frame_freeze_repetition_period,
+            4
+        );
+        assert_eq!(sei.payload_size[9], 0);
+        assert_eq!(sei.payload[10].full_frame_snapshot.snapshot_id, 9);
+        let prss = &sei.payload[11].progressive_refinement_segment_start;
+        assert_eq!(prss.progressive_refinement_id, 2);
+        assert_eq!(prss.num_refinement_steps_minus1, 3);
+        assert_eq!(
+            sei.payload[12]
+                .progressive_refinement_segment_end
+                .progressive_refinement_id,
+            2
+        );
 
-/// D.1.30 Colour remapping information SEI message syntax
-fn decode_colour_remapping_info(_payload_size: u32, _bs: &mut ByteStream) {}
+        assert_eq!(encode_sei_message(&sei, &spses, true), sei_bytes);
+    }
 
-/// D.1.31 Content light level information SEI message syntax
-fn decode_content_light_level_info(_payload_size: u32, _bs: &mut ByteStream) {}
+    #[test]
+    fn test_sei_d2_messages_round_trip() {
+        // one message of each type in the SEI between the PPS and the IDR slice
+        let mut ds = stream_with_empty_sei();
+        let payload_types = vec![2, 3, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17];
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        // exercise the loops that depend on the SPS and on repeated elements
+        rconfig
+            .random_dec_ref_pic_marking_repetition_range
+            .original_idr_flag = RandomBoolRange::new(0, 0, 2);
+        rconfig
+            .random_dec_ref_pic_marking_repetition_range
+            .adaptive_ref_pic_marking_mode_flag = RandomBoolRange::new(1, 1, 1);
+        rconfig.random_spare_pic_range.spare_area_idc = RandomU32Range::new(1, 2);
+        let mut film = FilmState::setup_film_from_seed(11);
+        for payload_type in payload_types.iter() {
+            rconfig.payload_type = RandomU32Enum::new(vec![*payload_type]);
+            random_sei(0, &rconfig, &mut ds, &mut film);
+        }
 
-/// D.1.32 Alternative transfer characteristics SEI message syntax
-fn decode_alternative_transfer_characteristics(_payload_size: u32, _bs: &mut ByteStream) {}
+        let decoded = round_trip(&mut ds);
+        assert_eq!(decoded.seis.len(), 1);
+        assert_eq!(decoded.seis[0].payload_type, payload_types);
+        assert_sei_payloads_match(&decoded.seis[0].payload, &ds.seis[0].payload);
+        assert_eq!(decoded.slices.len(), 2);
+    }
 
-/// D.1.33 Content colour volume SEI message syntax
-fn decode_content_colour_volume(_payload_size: u32, _bs: &mut ByteStream) {}
+    #[test]
+    fn test_sei_hdr_colour_messages() {
+        // hand-assembled BT.2020 PQ mastering display, light level, transfer, tone mapping,
+        // colour remapping and content colour volume messages
+        let sei_bytes = vec![
+            0x89, 0x18, 0x21, 0x34, 0x9b, 0xaa, 0x19, 0x96, 0x08, 0xfc, 0x8a, 0x48, 0x39, 0x08,
+            0x3d, 0x13, 0x40, 0x42, 0x00, 0x98, 0x96, 0x80, 0x00, 0x00, 0x00, 0x32, 0x90, 0x04,
+            0x03, 0xe8, 0x01, 0x90, 0x93, 0x01, 0x10, 0x17, 0x0b, 0xa1, 0x41, 0x04, 0x00, 0x02,
+            0x00, 0x00, 0x00, 0x03, 0xff, 0xff, 0x8e, 0x18, 0x4c, 0x12, 0x20, 0x12, 0x14, 0x10,
+            0x02, 0x00, 0x00, 0x00, 0x07, 0xff, 0xfe, 0x00, 0x01, 0x40, 0x40, 0x5c, 0x10, 0x70,
+            0x40, 0x00, 0x00, 0x01, 0x95, 0x09, 0x58, 0x00, 0x00, 0x01, 0xf4, 0x00, 0x98, 0x96,
+            0x80, 0x80,
+        ];
+        let sei = decode_sei_message(
+            &Vec::new(),
+            &Vec::new(),
+            &mut ByteStream::new(sei_bytes.clone()),
+        )
+        .unwrap();
+        assert_eq!(sei.payload_type, vec![137, 144, 147, 23, 142, 149]);
+        assert!(sei.payload.iter().all(|x| x.available));
+
+        let mdcv = &sei.payload[0].mastering_display_colour_volume;
+        assert_eq!(mdcv.display_primaries_x, [8500, 6550, 35400]);
+        assert_eq!(mdcv.display_primaries_y, [39850, 2300, 14600]);
+        assert_eq!(mdcv.white_point_x, 15635);
+        assert_eq!(mdcv.white_point_y, 16450);
+        assert_eq!(mdcv.max_display_mastering_luminance, 10000000);
+        assert_eq!(mdcv.min_display_mastering_luminance, 50);
+
+        let clli = &sei.payload[1].content_light_level_info;
+        assert_eq!(clli.max_content_light_level, 1000);
+        assert_eq!(clli.max_pic_average_light_level, 400);
+
+        assert_eq!(
+            sei.payload[2]
+                .alternative_transfer_characteristics
+                .preferred_transfer_characteristics,
+            16
+        );
 
-/// D.1.34 Ambient viewing environment SEI message syntax
-fn decode_ambient_viewing_environment(_payload_size: u32, _bs: &mut ByteStream) {}
+        // 10-bit to 8-bit piecewise linear mapping, so coded pivots take 16 bits
+        let tmi = &sei.payload[3].tone_mapping_info;
+        assert!(!tmi.tone_map_cancel_flag);
+        assert_eq!(tmi.coded_data_bit_depth, 10);
+        assert_eq!(tmi.target_bit_depth, 8);
+        assert_eq!(tmi.tone_map_model_id, 3);
+        assert_eq!(tmi.num_pivots, 2);
+        assert_eq!(tmi.coded_pivot_value, vec![0, 1023]);
+        assert_eq!(tmi.target_pivot_value, vec![0, 255]);
+
+        let cri = &sei.payload[4].colour_remapping_info;
+        assert_eq!(cri.colour_remap_id, 1);
+        assert!(cri.colour_remap_video_signal_info_present_flag);
+        assert!(!cri.colour_remap_full_range_flag);
+        assert_eq!(cri.colour_remap_primaries, 9);
+        assert_eq!(cri.colour_remap_transfer_function, 16);
+        assert_eq!(cri.colour_remap_matrix_coefficients, 9);
+        assert_eq!(cri.colour_remap_input_bit_depth, 10);
+        assert_eq!(cri.colour_remap_output_bit_depth, 8);
+        assert_eq!(cri.pre_lut_num_val_minus1, [1, 0, 0]);
+        assert_eq!(cri.pre_lut_coded_value[0], vec![0, 1023]);
+        assert_eq!(cri.pre_lut_target_value[0], vec![0, 255]);
+        assert!(cri.pre_lut_coded_value[1].is_empty());
+        assert!(cri.colour_remap_matrix_present_flag);
+        assert_eq!(cri.log2_matrix_denom, 4);
+        assert_eq!(
+            cri.colour_remap_coeffs,
+            [[16, -2, 0], [0, 16, 0], [0, 0, 16]]
+        );
+        assert_eq!(cri.post_lut_num_val_minus1, [0, 0, 0]);
 
-/// D.1.35.1 Equirectangular projection SEI message syntax
-fn decode_equirectangular_projection(_payload_size: u32, _bs: &mut ByteStream) {}
+        let ccv = &sei.payload[5].content_colour_volume;
+        assert!(!ccv.ccv_cancel_flag);
+        assert!(ccv.ccv_persistence_flag);
+        assert!(!ccv.ccv_primaries_present_flag);
+        assert!(!ccv.ccv_avg_luminance_value_present_flag);
+        assert_eq!(ccv.ccv_min_luminance_value, 500);
+        assert_eq!(ccv.ccv_max_luminance_value, 10000000);
 
-/// D.1.35.2 Cubemap projection SEI message syntax
-fn decode_cubemap_projection(_payload_size: u32, _bs: &mut ByteStream) {}
+        assert_eq!(encode_sei_message(&sei, &[], true), sei_bytes);
+    }
 
-/// D.1.35.3 Sphere rotation SEI message syntax
-fn decode_sphere_rotation(_payload_size: u32, _bs: &mut ByteStream) {}
+    #[test]
+    fn test_sei_hdr_colour_messages_round_trip() {
+        let mut ds = stream_with_empty_sei();
+
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        rconfig.random_tone_mapping_info_range.tone_map_cancel_flag = RandomBoolRange::new(0, 0, 2);
+        rconfig
+            .random_colour_remapping_info_range
+            .colour_remap_cancel_flag = RandomBoolRange::new(0, 0, 2);
+        rconfig
+            .random_colour_remapping_info_range
+            .colour_remap_matrix_present_flag = RandomBoolRange::new(1, 1, 1);
+        rconfig.random_content_colour_volume_range.ccv_cancel_flag = RandomBoolRange::new(0, 0, 2);
+        let mut film = FilmState::setup_film_from_seed(12);
+
+        // one tone mapping message per model, then the remaining HDR messages
+        let mut payload_types = Vec::new();
+        rconfig.payload_type = RandomU32Enum::new(vec![23]);
+        for model_id in 0..=4 {
+            rconfig.random_tone_mapping_info_range.tone_map_model_id =
+                RandomU32Range::new(model_id, model_id);
+            random_sei(0, &rconfig, &mut ds, &mut film);
+            payload_types.push(23);
+        }
+        for payload_type in [137, 142, 144, 147, 149] {
+            rconfig.payload_type = RandomU32Enum::new(vec![payload_type]);
+            random_sei(0, &rconfig, &mut ds, &mut film);
+            payload_types.push(payload_type);
+        }
 
-/// D.1.35.4 Region-wise packing SEI message syntax
-fn decode_regionwise_packing(_payload_size: u32, _bs: &mut ByteStream) {}
+        let decoded = round_trip(&mut ds);
 
-/// D.1.35.5 Omnidirectional viewport SEI message syntax
-fn decode_omni_viewport(_payload_size: u32, _bs: &mut ByteStream) {}
+        assert_eq!(decoded.seis.len(), 1);
+        assert_eq!(decoded.seis[0].payload_type, payload_types);
+        assert_sei_payloads_match(&decoded.seis[0].payload, &ds.seis[0].payload);
+        assert_eq!(decoded.slices.len(), 2);
+    }
 
-/// I.13.1.6 Alternative depth information SEI message syntax
-fn decode_alternative_depth_info(_payload_size: u32, _bs: &mut ByteStream) {}
+    #[test]
+    fn test_sei_omnidirectional_messages() {
+        // hand-assembled projection messages: a padded ERP, a cubemap, a yaw and pitch
+        // rotation, a two region packing with a guard band on the second region and a
+        // single viewport. Angles are in units of 2^-16 degrees
+        let sei_bytes = vec![
+            0x96, 0x03, 0x62, 0x08, 0x08, 0x97, 0x01, 0x60, 0x9a, 0x0d, 0x00, 0xff, 0xa6, 0x00,
+            0x00, 0x00, 0x2d, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x9b, 0x46, 0x40, 0x02, 0x00,
+            0x00, 0x0f, 0x00, 0x00, 0x00, 0x07, 0x80, 0x0b, 0x40, 0x07, 0x80, 0x00, 0x00, 0x00,
+            0x07, 0x80, 0x00, 0x00, 0x07, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x07, 0x80, 0x07, 0x80, 0x00, 0x00, 0x00, 0x00, 0x0b, 0x00, 0x00, 0x07, 0x80, 0x00,
+            0x00, 0x07, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x07, 0x80, 0x03, 0xc0, 0x07,
+            0x80, 0x00, 0x00, 0x07, 0x80, 0x04, 0x04, 0x00, 0x00, 0x92, 0x00, 0x9c, 0x16, 0x01,
+            0x40, 0xff, 0xe2, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+            0x5a, 0x00, 0x00, 0x00, 0x3c, 0x00, 0x00, 0x80,
+        ];
+        let sei = decode_sei_message(
+            &Vec::new(),
+            &Vec::new(),
+            &mut ByteStream::new(sei_bytes.clone()),
+        )
+        .unwrap();
+        assert_eq!(sei.payload_type, vec![150, 151, 154, 155, 156]);
+        assert!(sei.payload.iter().all(|x| x.available));
+
+        let erp = &sei.payload[0].equirectangular_projection;
+        assert!(!erp.erp_cancel_flag);
+        assert!(erp.erp_persistence_flag);
+        assert!(erp.erp_padding_flag);
+        assert_eq!(erp.gb_erp_type, 2);
+        assert_eq!(erp.left_gb_erp_width, 8);
+        assert_eq!(erp.right_gb_erp_width, 8);
+
+        let cmp = &sei.payload[1].cubemap_projection;
+        assert!(!cmp.cmp_cancel_flag);
+        assert!(cmp.cmp_persistence_flag);
+
+        let sr = &sei.payload[2].sphere_rotation;
+        assert!(!sr.sphere_rotation_persistence_flag);
+        assert_eq!(sr.yaw_rotation, -90 << 16);
+        assert_eq!(sr.pitch_rotation, 45 << 16);
+        assert_eq!(sr.roll_rotation, 0);
+
+        let rwp = &sei.payload[3].regionwise_packing;
+        assert!(rwp.rwp_persistence_flag);
+        assert_eq!(rwp.num_packed_regions, 2);
+        assert_eq!(rwp.proj_picture_width, 3840);
+        assert_eq!(rwp.proj_picture_height, 1920);
+        assert_eq!(rwp.packed_picture_width, 2880);
+        assert_eq!(rwp.packed_picture_height, 1920);
+        assert_eq!(rwp.rwp_transform_type, vec![0, 5]);
+        assert_eq!(rwp.rwp_guard_band_flag, vec![false, true]);
+        assert_eq!(rwp.proj_region_left, vec![0, 1920]);
+        assert_eq!(rwp.packed_region_width, vec![1920, 960]);
+        assert_eq!(rwp.packed_region_left, vec![0, 1920]);
+        assert_eq!(rwp.rwp_left_guard_band_width, vec![0, 4]);
+        assert_eq!(rwp.rwp_right_guard_band_width, vec![0, 4]);
+        assert_eq!(rwp.rwp_guard_band_not_used_for_pred_flag, vec![false, true]);
+        assert_eq!(rwp.rwp_guard_band_type, vec![[0; 4], [1, 1, 0, 0]]);
+
+        let ov = &sei.payload[4].omni_viewport;
+        assert_eq!(ov.omni_viewport_id, 5);
+        assert!(!ov.omni_viewport_cancel_flag);
+        assert_eq!(ov.omni_viewport_cnt_minus1, 0);
+        assert_eq!(ov.omni_viewport_azimuth_centre, vec![-30 << 16]);
+        assert_eq!(ov.omni_viewport_elevation_centre, vec![10 << 16]);
+        assert_eq!(ov.omni_viewport_tilt_centre, vec![0]);
+        assert_eq!(ov.omni_viewport_hor_range, vec![90 << 16]);
+        assert_eq!(ov.omni_viewport_ver_range, vec![60 << 16]);
+
+        assert_eq!(encode_sei_message(&sei, &[], true), sei_bytes);
+    }
 
-/// D.1.36 SEI manifest SEI message syntax
-fn decode_sei_manifest(_payload_size: u32, _bs: &mut ByteStream) {}
+    #[test]
+    fn test_sei_omnidirectional_messages_round_trip() {
+        let mut ds = stream_with_empty_sei();
+
+        let payload_types = vec![150, 151, 154, 155, 156];
+        let never = RandomBoolRange::new(0, 0, 2);
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        rconfig
+            .random_equirectangular_projection_range
+            .erp_cancel_flag = never;
+        rconfig
+            .random_equirectangular_projection_range
+            .erp_padding_flag = RandomBoolRange::new(1, 1, 1);
+        rconfig.random_cubemap_projection_range.cmp_cancel_flag = never;
+        rconfig
+            .random_sphere_rotation_range
+            .sphere_rotation_cancel_flag = never;
+        rconfig.random_regionwise_packing_range.rwp_cancel_flag = never;
+        // enough regions to exercise both sides of the guard band branch
+        rconfig.random_regionwise_packing_range.num_packed_regions = RandomU32Range::new(8, 8);
+        rconfig.random_omni_viewport_range.omni_viewport_cancel_flag = never;
+        let mut film = FilmState::setup_film_from_seed(13);
+        for payload_type in payload_types.iter() {
+            rconfig.payload_type = RandomU32Enum::new(vec![*payload_type]);
+            random_sei(0, &rconfig, &mut ds, &mut film);
+        }
 
-/// D.1.37 SEI prefix indication SEI message syntax
-fn decode_sei_prefix_indication(_payload_size: u32, _bs: &mut ByteStream) {}
+        let decoded = round_trip(&mut ds);
 
-/// D.1.38 Annotated regions SEI message syntax
-fn decode_annotated_regions(_payload_size: u32, _bs: &mut ByteStream) {}
+        assert_eq!(decoded.seis.len(), 1);
+        assert_eq!(decoded.seis[0].payload_type, payload_types);
+        assert_eq!(
+            decoded.seis[0].payload[3]
+                .regionwise_packing
+                .proj_region_width
+                .len(),
+            8
+        );
+        assert_sei_payloads_match(&decoded.seis[0].payload, &ds.seis[0].payload);
+        assert_eq!(decoded.slices.len(), 2);
+    }
 
-/// D.1.39 Shutter interval information SEI message syntax
-fn decode_shutter_interval_info(_payload_size: u32, _bs: &mut ByteStream) {}
+    #[test]
+    fn test_sei_frame_packing_display_orientation_messages() {
+        // hand-assembled side-by-side and temporal interleaving arrangements, then a
+        // mirrored display rotated by 90 degrees
+        let sei_bytes = vec![
+            0x2d, 0x07, 0x81, 0x81, 0x01, 0x23, 0x20, 0x01, 0x20, 0x2d, 0x05, 0x40, 0xa0, 0x84,
+            0x00, 0xa0, 0x2f, 0x03, 0x48, 0x00, 0x09, 0x80,
+        ];
+        let sei = decode_sei_message(
+            &Vec::new(),
+            &Vec::new(),
+            &mut ByteStream::new(sei_bytes.clone()),
+        )
+        .unwrap();
+        assert_eq!(sei.payload_type, vec![45, 45, 47]);
+        assert!(sei.payload.iter().all(|x| x.available));
+
+        let sbs = &sei.payload[0].frame_packing;
+        assert_eq!(sbs.frame_packing_arrangement_id, 0);
+        assert!(!sbs.frame_packing_arrangement_cancel_flag);
+        assert_eq!(sbs.frame_packing_arrangement_type, 3);
+        assert!(!sbs.quincunx_sampling_flag);
+        assert_eq!(sbs.content_interpretation_type, 1);
+        assert!(!sbs.current_frame_is_frame0_flag);
+        assert_eq!(sbs.frame0_grid_position_x, 4);
+        assert_eq!(sbs.frame0_grid_position_y, 8);
+        assert_eq!(sbs.frame1_grid_position_x, 12);
+        assert_eq!(sbs.frame1_grid_position_y, 8);
+        assert_eq!(sbs.frame_packing_arrangement_repetition_period, 1);
+        assert!(!sbs.frame_packing_arrangement_extension_flag);
+
+        // temporal interleaving has no grid positions
+        let temporal = &sei.payload[1].frame_packing;
+        assert_eq!(temporal.frame_packing_arrangement_id, 1);
+        assert_eq!(temporal.frame_packing_arrangement_type, 5);
+        assert_eq!(temporal.content_interpretation_type, 2);
+        assert!(temporal.current_frame_is_frame0_flag);
+        assert_eq!(temporal.frame0_grid_position_x, 0);
+        assert_eq!(temporal.frame_packing_arrangement_repetition_period, 0);
+
+        let dor = &sei.payload[2].display_orientation;
+        assert!(!dor.display_orientation_cancel_flag);
+        assert!(dor.hor_flip);
+        assert!(!dor.ver_flip);
+        assert_eq!(dor.anticlockwise_rotation, 16384);
+        assert_eq!(dor.display_orientation_repetition_period, 1);
+        assert!(!dor.display_orientation_extension_flag);
+
+        assert_eq!(encode_sei_message(&sei, &[], true), sei_bytes);
+    }
 
-/// D.1.40 Reserved SEI message syntax
-fn decode_reserved_sei_message(_payload_size: u32, _bs: &mut ByteStream) {}
+    #[test]
+    fn test_sei_frame_packing_display_orientation_round_trip() {
+        use crate::common::data_structures::SEIFramePacking;
+
+        // older JSON dumps stored the frame packing SEI as an empty object
+        let fp: SEIFramePacking = serde_json::from_str("{}").unwrap();
+        assert!(!fp.frame_packing_arrangement_cancel_flag);
+
+        let mut ds = stream_with_empty_sei();
+
+        let never = RandomBoolRange::new(0, 0, 2);
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        rconfig
+            .random_frame_packing_range
+            .frame_packing_arrangement_cancel_flag = never;
+        rconfig.random_frame_packing_range.quincunx_sampling_flag = never;
+        rconfig
+            .random_display_orientation_range
+            .display_orientation_cancel_flag = never;
+        let mut film = FilmState::setup_film_from_seed(14);
+
+        // side-by-side and temporal interleaving cover both grid position branches
+        let mut payload_types = Vec::new();
+        rconfig.payload_type = RandomU32Enum::new(vec![45]);
+        for arrangement_type in [3, 5] {
+            rconfig
+                .random_frame_packing_range
+                .frame_packing_arrangement_type =
+                RandomU32Range::new(arrangement_type, arrangement_type);
+            random_sei(0, &rconfig, &mut ds, &mut film);
+            payload_types.push(45);
+        }
+        rconfig.payload_type = RandomU32Enum::new(vec![47]);
+        random_sei(0, &rconfig, &mut ds, &mut film);
+        payload_types.push(47);
+
+        let decoded = round_trip(&mut ds);
+
+        assert_eq!(decoded.seis.len(), 1);
+        assert_eq!(decoded.seis[0].payload_type, payload_types);
+        assert_sei_payloads_match(&decoded.seis[0].payload, &ds.seis[0].payload);
+        assert_eq!(decoded.slices.len(), 2);
+    }
+
+    #[test]
+    fn test_sei_svc_mvc_messages() {
+        // hand-assembled SVC and MVC messages, with a recovery point nested in the scalable
+        // nesting for two layer representations and in the MVC nesting for views 0 and 1
+        let sei_bytes = vec![
+            0x1e, 0x06, 0x22, 0x08, 0x50, 0x06, 0x01, 0xc4, 0x1b, 0x03, 0x24, 0x29, 0x30, 0x1c,
+            0x02, 0x64, 0x11, 0x22, 0x03, 0x11, 0x01, 0x2c, 0x23, 0x01, 0x2c, 0x25, 0x07, 0x10,
+            0x00, 0x00, 0x80, 0x06, 0x01, 0xc4, 0x27, 0x01, 0x1b, 0x80,
+        ];
+        let sei = decode_sei_message(
+            &Vec::new(),
+            &Vec::new(),
+            &mut ByteStream::new(sei_bytes.clone()),
+        )
+        .unwrap();
+        assert_eq!(sei.payload_type, vec![30, 27, 28, 34, 35, 37, 39]);
+        assert!(sei.payload.iter().all(|x| x.available));
+
+        let sn = &sei.payload[0].scalable_nesting;
+        assert!(!sn.all_layer_representations_in_au_flag);
+        assert_eq!(sn.num_layer_representations_minus1, 1);
+        assert_eq!(sn.sei_dependency_id, vec![1, 2]);
+        assert_eq!(sn.sei_quality_id, vec![0, 1]);
+        assert_eq!(sn.sei_temporal_id, 2);
+        assert_eq!(sn.nested_sei.payload_type, vec![6]);
+        let rp = &sn.nested_sei.payload[0].recovery_point;
+        assert_eq!(rp.recovery_frame_cnt, 0);
+        assert!(rp.exact_match_flag);
+        assert!(!rp.broken_link_flag);
+        assert_eq!(rp.changing_slice_group_idc, 0);
+
+        let pli = &sei.payload[1].priority_layer_info;
+        assert_eq!(pli.pr_dependency_id, 1);
+        assert_eq!(pli.num_priority_ids, 2);
+        assert_eq!(pli.alt_priority_id, vec![5, 9]);
+
+        let lnp = &sei.payload[2].layers_not_present;
+        assert_eq!(lnp.num_layers, 2);
+        assert_eq!(lnp.layer_id, vec![3, 7]);
+
+        let tl0 = &sei.payload[3].tl0_dep_rep_index;
+        assert_eq!(tl0.tl0_dep_rep_idx, 17);
+        assert_eq!(tl0.effective_idr_pic_id, 300);
+
+        assert_eq!(sei.payload[4].tl_switching_point.delta_frame_num, -2);
+
+        let mvcn = &sei.payload[5].mvc_scalable_nesting;
+        assert!(!mvcn.operation_point_flag);
+        assert!(!mvcn.all_view_components_in_au_flag);
+        assert_eq!(mvcn.num_view_components_minus1, 1);
+        assert_eq!(mvcn.sei_view_id, vec![0, 1]);
+        assert_eq!(mvcn.nested_sei.payload_type, vec![6]);
+        assert!(mvcn.nested_sei.payload[0].recovery_point.exact_match_flag);
+
+        assert_eq!(sei.payload[6].multiview_scene_info.max_disparity, 12);
+
+        assert_eq!(encode_sei_message(&sei, &[], true), sei_bytes);
+    }
+
+    #[test]
+    fn test_sei_svc_mvc_messages_round_trip() {
+        let mut ds = stream_with_empty_sei();
+
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        rconfig.random_scalable_nesting_range.nested_payload_type = RandomU32Enum::new(vec![6, 47]);
+        rconfig
+            .random_mvc_scalable_nesting_range
+            .nested_payload_type = RandomU32Enum::new(vec![6, 47]);
+        rconfig
+            .random_mvcd_scalable_nesting_range
+            .nested_payload_type = RandomU32Enum::new(vec![6, 47]);
+        // ue(v) values of 2^31 and above do not survive the exp-Golomb round trip
+        for hrd in [
+            &mut rconfig
+                .random_base_layer_temporal_hrd_range
+                .random_hrd_range,
+            &mut rconfig.random_base_view_temporal_hrd_range.random_hrd_range,
+        ] {
+            hrd.bit_rate_value_minus1 = RandomU32Range::new(0, 65535);
+            hrd.cpb_size_value_minus1 = RandomU32Range::new(0, 65535);
+        }
+        let mut film = FilmState::setup_film_from_seed(15);
+
+        // generate each message twice to reach more of the conditional branches
+        let mut payload_types = Vec::new();
+        for payload_type in [
+            24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 37, 38, 39, 40, 41, 43, 44, 46, 48, 49,
+            50, 51, 52, 53, 54, 181,
+        ] {
+            rconfig.payload_type = RandomU32Enum::new(vec![payload_type]);
+            for _ in 0..2 {
+                random_sei(0, &rconfig, &mut ds, &mut film);
+                payload_types.push(payload_type);
+            }
+        }
+
+        let decoded = round_trip(&mut ds);
+
+        assert_eq!(decoded.seis.len(), 1);
+        assert_eq!(decoded.seis[0].payload_type, payload_types);
+        for (a, b) in decoded.seis[0]
+            .payload
+            .iter()
+            .zip(ds.seis[0].payload.iter())
+        {
+            assert!(a.available);
+            let mut a = a.clone();
+            a.available = b.available;
+            // the generator leaves the nested payload sizes for the encoder to fill in
+            for (nested, gen) in [
+                (
+                    &mut a.scalable_nesting.nested_sei,
+                    &b.scalable_nesting.nested_sei,
+                ),
+                (
+                    &mut a.mvc_scalable_nesting.nested_sei,
+                    &b.mvc_scalable_nesting.nested_sei,
+                ),
+                (
+                    &mut a.mvcd_scalable_nesting.nested_sei,
+                    &b.mvcd_scalable_nesting.nested_sei,
+                ),
+            ] {
+                assert_eq!(nested.payload_type, gen.payload_type);
+                nested.payload_size = gen.payload_size.clone();
+                for (na, nb) in nested.payload.iter_mut().zip(gen.payload.iter()) {
+                    assert!(na.available);
+                    na.available = nb.available;
+                }
+            }
+            assert_eq!(
+                serde_json::to_string(&a).unwrap(),
+                serde_json::to_string(b).unwrap()
+            );
+        }
+        assert_eq!(decoded.slices.len(), 2);
+    }
+
+    #[test]
+    fn test_sei_subset_sps_messages_round_trip() {
+        // an MVCD subset SPS whose anchor views have at least two more inter-view
+        // references than the non-anchor ones, so that the decoder can tell which
+        // parallel decoding info delays were sent
+        let mut subset_sps = SubsetSPS::new();
+        subset_sps.sps.profile_idc = 138;
+        subset_sps.sps.seq_parameter_set_id = 1;
+        subset_sps.sps_mvcd.num_depth_views = 2;
+        subset_sps.sps_mvcd.num_anchor_refs_l0 = vec![0, 2, 1];
+        subset_sps.sps_mvcd.num_anchor_refs_l1 = vec![0, 1, 1];
+        subset_sps.sps_mvcd.num_non_anchor_refs_l0 = vec![0, 1, 0];
+        subset_sps.sps_mvcd.num_non_anchor_refs_l1 = vec![0, 0, 1];
+        let mut ds = H264DecodedStream::new();
+        ds.subset_spses.push(subset_sps);
+        ds.seis.push(SEINalu::new());
+
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        let mut film = FilmState::setup_film_from_seed(36);
+        for payload_type in [36, 42, 52] {
+            rconfig.payload_type = RandomU32Enum::new(vec![payload_type]);
+            for _ in 0..4 {
+                random_sei(0, &rconfig, &mut ds, &mut film);
+            }
+        }
+
+        let encoded = encode_sei_message(&ds.seis[0], &[], true);
+        let decoded =
+            decode_sei_message(&Vec::new(), &ds.subset_spses, &mut ByteStream::new(encoded))
+                .unwrap();
+
+        assert_eq!(decoded.payload_type, ds.seis[0].payload_type);
+        assert_sei_payloads_match(&decoded.payload, &ds.seis[0].payload);
+        let anchor_pic_flags: Vec<bool> = decoded.payload[..4]
+            .iter()
+            .map(|x| x.parallel_decoding_info.anchor_pic_flag)
+            .collect();
+        assert!(anchor_pic_flags.contains(&true) && anchor_pic_flags.contains(&false));
+        assert!(decoded.payload[8..]
+            .iter()
+            .any(|x| x.depth_timing.depth_timing_offset.len() == 2));
+    }
+
+    #[test]
+    fn test_sei_registered_user_data_cc_data() {
+        // GA94 cc_data() with a CEA-608 field 1 pair and a field 2 padding pair
+        let payload = vec![
+            0xb5, 0x00, 0x31, 0x47, 0x41, 0x39, 0x34, 0x03, 0xc2, 0xff, 0xfc, 0x94, 0x2c, 0xf9,
+            0x80, 0x80, 0xff,
+        ];
+        let mut sei_bytes = vec![4, payload.len() as u8];
+        sei_bytes.extend(payload.iter());
+        sei_bytes.push(0x80);
+        let sei =
+            decode_sei_message(&Vec::new(), &Vec::new(), &mut ByteStream::new(sei_bytes)).unwrap();
+        let t35 = &sei.payload[0].registered_user_data;
+        assert!(sei.payload[0].available);
+        assert!(t35.has_cc_data());
+        assert!(t35.cc_data.process_cc_data_flag);
+        assert_eq!(t35.cc_data.cc_count, 2);
+        assert_eq!(t35.cc_data.cc_valid, vec![true, false]);
+        assert_eq!(t35.cc_data.cc_type, vec![0, 1]);
+        assert_eq!(t35.cc_data.cc_data_1, vec![0x94, 0x80]);
+        assert_eq!(t35.cc_data.cc_data_2, vec![0x2c, 0x80]);
+        assert_eq!(t35.cc_data.marker_bits, 0xff);
+        assert!(t35.itu_t_t35_payload_byte.is_empty());
+
+        let mut ds = stream_with_empty_sei();
+
+        // keep the caption data well-formed so it round trips
+        let never = RandomBoolRange::new(0, 0, 2);
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        rconfig.payload_type = RandomU32Enum::new(vec![4]);
+        let t35_range = &mut rconfig.random_user_data_registered_itu_t_t35_range;
+        t35_range.cc_count_mismatch = never;
+        t35_range.bad_cc_marker_bits = never;
+        t35_range.bad_marker_bits = never;
+        let mut film = FilmState::setup_film_from_seed(16);
+        for _ in 0..8 {
+            random_sei(0, &rconfig, &mut ds, &mut film);
+        }
+
+        let decoded = round_trip(&mut ds);
+
+        assert_eq!(decoded.seis.len(), 1);
+        assert_eq!(decoded.seis[0].payload_type, vec![4; 8]);
+        for (a, b) in decoded.seis[0]
+            .payload
+            .iter()
+            .zip(ds.seis[0].payload.iter())
+        {
+            assert!(a.available);
+            assert_eq!(
+                serde_json::to_string(&a.registered_user_data).unwrap(),
+                serde_json::to_string(&b.registered_user_data).unwrap()
+            );
+        }
+        assert_eq!(decoded.slices.len(), 2);
+    }
+}
@@ -4,27 +4,32 @@ use crate::common::data_structures::MacroBlock;
 use crate::common::data_structures::MbType;
 use crate::common::data_structures::NALUheader;
 use crate::common::data_structures::PicParameterSet;
+use crate::common::data_structures::SPSExtension;
 use crate::common::data_structures::SeqParameterSet;
 use crate::common::data_structures::Slice;
 use crate::common::data_structures::SliceData;
+use crate::common::data_structures::SliceDataPartition;
 use crate::common::data_structures::SliceHeader;
 use crate::common::data_structures::SubsetSPS;
 use crate::common::data_structures::VideoParameters;
 use crate::common::helper::decoder_formatted_print;
 use crate::common::helper::is_slice_type;
+use crate::common::helper::slice_group_change_cycle_len;
 use crate::common::helper::ByteStream;
 use crate::decoder::cabac::cabac_decode;
 use crate::decoder::cabac::initialize_state;
 use crate::decoder::cabac::CABACState;
+use crate::decoder::error::DecodeError;
 use crate::decoder::expgolomb::exp_golomb_decode_one_wrapper;
 use crate::decoder::macroblock::decode_macroblock_layer;
+use crate::decoder::macroblock::ResidualPartitions;
 use log::debug;
 
 /// Follows section 7.3.3.1
-fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
+fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) -> Result<(), DecodeError> {
     if sh.slice_type % 5 != 2 && sh.slice_type % 5 != 4 {
         // ref_pic_list_modification_flag_l0
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.ref_pic_list_modification_flag_l0 = 1 == r;
         decoder_formatted_print("SH: ref_pic_list_reordering_flag_l0", &r, 63);
 
@@ -33,7 +38,7 @@ fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
             let mut i = 0;
             while {
                 sh.modification_of_pic_nums_idc_l0
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
 
                 decoder_formatted_print(
                     "SH: modification_of_pic_nums_idc_l0",
@@ -46,7 +51,7 @@ fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
                     || sh.modification_of_pic_nums_idc_l0[i] == 1
                 {
                     sh.abs_diff_pic_num_minus1_l0
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: abs_diff_pic_num_minus1_l0",
                         sh.abs_diff_pic_num_minus1_l0[i],
@@ -59,7 +64,7 @@ fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
 
                 if sh.modification_of_pic_nums_idc_l0[i] == 2 {
                     sh.long_term_pic_num_l0
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: long_term_pic_num",
                         sh.long_term_pic_num_l0[i],
@@ -80,7 +85,7 @@ fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
 
     if sh.slice_type % 5 == 1 {
         // ref_pic_list_modification_flag_l1
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.ref_pic_list_modification_flag_l1 = r == 1;
 
         decoder_formatted_print("SH: ref_pic_list_reordering_flag_l1", &r, 63);
@@ -89,7 +94,7 @@ fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
             let mut i = 0;
             while {
                 sh.modification_of_pic_nums_idc_l1
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
 
                 decoder_formatted_print(
                     "SH: modification_of_pic_nums_idc_l1",
@@ -102,7 +107,7 @@ fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
                     || sh.modification_of_pic_nums_idc_l1[i] == 1
                 {
                     sh.abs_diff_pic_num_minus1_l1
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: abs_diff_pic_num_minus1_l1",
                         sh.abs_diff_pic_num_minus1_l1[i],
@@ -115,7 +120,7 @@ fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
 
                 if sh.modification_of_pic_nums_idc_l1[i] == 2 {
                     sh.long_term_pic_num_l1
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: long_term_pic_num",
                         sh.long_term_pic_num_l1[i],
@@ -131,13 +136,18 @@ fn ref_pic_list_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
             } {}
         }
     }
+
+    Ok(())
 }
 
 /// Follows section H.7.3.3.1.1
-fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
+fn ref_pic_list_mvc_modification(
+    sh: &mut SliceHeader,
+    bs: &mut ByteStream,
+) -> Result<(), DecodeError> {
     if sh.slice_type % 5 != 2 && sh.slice_type % 5 != 4 {
         // ref_pic_list_modification_flag_l0
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.ref_pic_list_modification_flag_l0 = 1 == r;
         decoder_formatted_print("SH: ref_pic_list_reordering_flag_l0", &r, 63);
 
@@ -145,7 +155,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
             let mut i = 0;
             while {
                 sh.modification_of_pic_nums_idc_l0
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
 
                 decoder_formatted_print(
                     "SH: modification_of_pic_nums_idc_l0",
@@ -158,7 +168,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
                     || sh.modification_of_pic_nums_idc_l0[i] == 1
                 {
                     sh.abs_diff_pic_num_minus1_l0
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: abs_diff_pic_num_minus1_l0",
                         sh.abs_diff_pic_num_minus1_l0[i],
@@ -170,7 +180,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
 
                 if sh.modification_of_pic_nums_idc_l0[i] == 2 {
                     sh.long_term_pic_num_l0
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: long_term_pic_num",
                         sh.long_term_pic_num_l0[i],
@@ -184,7 +194,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
                     || sh.modification_of_pic_nums_idc_l0[i] == 5
                 {
                     sh.abs_diff_view_idx_minus1_l0
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: abs_diff_view_idx_minus1",
                         sh.abs_diff_view_idx_minus1_l0[i],
@@ -204,7 +214,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
 
     if sh.slice_type % 5 == 1 {
         // ref_pic_list_modification_flag_l1
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.ref_pic_list_modification_flag_l1 = r == 1;
 
         decoder_formatted_print("SH: ref_pic_list_reordering_flag_l1", &r, 63);
@@ -213,7 +223,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
             let mut i = 0;
             while {
                 sh.modification_of_pic_nums_idc_l1
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
 
                 decoder_formatted_print(
                     "SH: modification_of_pic_nums_idc_l1",
@@ -226,7 +236,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
                     || sh.modification_of_pic_nums_idc_l1[i] == 1
                 {
                     sh.abs_diff_pic_num_minus1_l1
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: abs_diff_pic_num_minus1_l1",
                         sh.abs_diff_pic_num_minus1_l1[i],
@@ -238,7 +248,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
 
                 if sh.modification_of_pic_nums_idc_l1[i] == 2 {
                     sh.long_term_pic_num_l1
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: long_term_pic_num",
                         sh.long_term_pic_num_l1[i],
@@ -252,7 +262,7 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
                     || sh.modification_of_pic_nums_idc_l1[i] == 5
                 {
                     sh.abs_diff_view_idx_minus1_l1
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: abs_diff_view_idx_minus1",
                         sh.abs_diff_view_idx_minus1_l1[i],
@@ -267,18 +277,24 @@ fn ref_pic_list_mvc_modification(sh: &mut SliceHeader, bs: &mut ByteStream) {
             } {}
         }
     }
+
+    Ok(())
 }
 
 /// Follows section 7.3.3.2
-fn pred_weight_table(sh: &mut SliceHeader, bs: &mut ByteStream, chroma_array_type: u8) {
+fn pred_weight_table(
+    sh: &mut SliceHeader,
+    bs: &mut ByteStream,
+    chroma_array_type: u8,
+) -> Result<(), DecodeError> {
     // luma_log2_weight_denom
-    sh.luma_log2_weight_denom = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    sh.luma_log2_weight_denom = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
 
     decoder_formatted_print("SH: luma_log2_weight_denom", sh.luma_log2_weight_denom, 63);
 
     // chroma_log2_weight_denom
     if chroma_array_type != 0 {
-        sh.chroma_log2_weight_denom = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        sh.chroma_log2_weight_denom = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SH: chroma_log2_weight_denom",
             sh.chroma_log2_weight_denom,
@@ -288,18 +304,18 @@ fn pred_weight_table(sh: &mut SliceHeader, bs: &mut ByteStream, chroma_array_typ
 
     // get weight tables
     for i in 0..(sh.num_ref_idx_l0_active_minus1 + 1) {
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.luma_weight_l0_flag.push(r == 1);
 
         decoder_formatted_print("SH: luma_weight_flag_l0", &r, 63);
 
         if sh.luma_weight_l0_flag[i as usize] {
-            let r = exp_golomb_decode_one_wrapper(bs, true, 0);
+            let r = exp_golomb_decode_one_wrapper(bs, true, 0)?;
             sh.luma_weight_l0.push(r);
 
             decoder_formatted_print("SH: luma_weight_l0", r, 63);
 
-            let r = exp_golomb_decode_one_wrapper(bs, true, 0);
+            let r = exp_golomb_decode_one_wrapper(bs, true, 0)?;
             sh.luma_offset_l0.push(r);
 
             decoder_formatted_print("SH: luma_offset_l0", r, 63);
@@ -310,19 +326,19 @@ fn pred_weight_table(sh: &mut SliceHeader, bs: &mut ByteStream, chroma_array_typ
         }
 
         if chroma_array_type != 0 {
-            let r = bs.read_bits(1);
+            let r = bs.read_bits(1)?;
             sh.chroma_weight_l0_flag.push(r == 1);
 
             decoder_formatted_print("SH: chroma_weight_flag_l0", &r, 63);
 
             if sh.chroma_weight_l0_flag[i as usize] {
-                let cwl0 = exp_golomb_decode_one_wrapper(bs, true, 0);
+                let cwl0 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
                 decoder_formatted_print("SH: chroma_weight_l0", cwl0, 63);
-                let col0 = exp_golomb_decode_one_wrapper(bs, true, 0);
+                let col0 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
                 decoder_formatted_print("SH: chroma_offset_l0", col0, 63);
-                let cwl1 = exp_golomb_decode_one_wrapper(bs, true, 0);
+                let cwl1 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
                 decoder_formatted_print("SH: chroma_weight_l0", cwl1, 63);
-                let col1 = exp_golomb_decode_one_wrapper(bs, true, 0);
+                let col1 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
                 decoder_formatted_print("SH: chroma_offset_l0", col1, 63);
 
                 sh.chroma_weight_l0.push(vec![cwl0, cwl1]);
@@ -337,7 +353,7 @@ fn pred_weight_table(sh: &mut SliceHeader, bs: &mut ByteStream, chroma_array_typ
     // collect the l1 values
     if sh.slice_type % 5 == 1 {
         for i in 0..(sh.num_ref_idx_l1_active_minus1 + 1) {
-            sh.luma_weight_l1_flag.push(bs.read_bits(1) == 1);
+            sh.luma_weight_l1_flag.push(bs.read_bits(1)? == 1);
 
             decoder_formatted_print(
                 "SH: luma_weight_l1_flag",
@@ -345,11 +361,11 @@ fn pred_weight_table(sh: &mut SliceHeader, bs: &mut ByteStream, chroma_array_typ
                 63,
             );
             if sh.luma_weight_l1_flag[i as usize] {
-                let r = exp_golomb_decode_one_wrapper(bs, true, 0);
+                let r = exp_golomb_decode_one_wrapper(bs, true, 0)?;
                 decoder_formatted_print("SH: luma_weight_l1", r, 63);
                 sh.luma_weight_l1.push(r);
 
-                let r = exp_golomb_decode_one_wrapper(bs, true, 0);
+                let r = exp_golomb_decode_one_wrapper(bs, true, 0)?;
                 sh.luma_offset_l1.push(r);
 
                 decoder_formatted_print("SH: luma_offset_l1", r, 63);
@@ -360,7 +376,7 @@ fn pred_weight_table(sh: &mut SliceHeader, bs: &mut ByteStream, chroma_array_typ
             }
 
             if chroma_array_type != 0 {
-                sh.chroma_weight_l1_flag.push(bs.read_bits(1) == 1);
+                sh.chroma_weight_l1_flag.push(bs.read_bits(1)? == 1);
 
                 decoder_formatted_print(
                     "SH: chroma_weight_l1_flag",
@@ -368,10 +384,10 @@ fn pred_weight_table(sh: &mut SliceHeader, bs: &mut ByteStream, chroma_array_typ
                     63,
                 );
                 if sh.chroma_weight_l1_flag[i as usize] {
-                    let cwl0 = exp_golomb_decode_one_wrapper(bs, true, 0);
-                    let col0 = exp_golomb_decode_one_wrapper(bs, true, 0);
-                    let cwl1 = exp_golomb_decode_one_wrapper(bs, true, 0);
-                    let col1 = exp_golomb_decode_one_wrapper(bs, true, 0);
+                    let cwl0 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+                    let col0 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+                    let cwl1 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+                    let col1 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
 
                     decoder_formatted_print("SH: chroma_weight_l1", cwl0, 63);
                     decoder_formatted_print("SH: chroma_offset_l1", col0, 63);
@@ -388,20 +404,26 @@ fn pred_weight_table(sh: &mut SliceHeader, bs: &mut ByteStream, chroma_array_typ
             }
         }
     }
+
+    Ok(())
 }
 
 /// Follows section 7.3.3.3
-fn dec_ref_pic_marking(sh: &mut SliceHeader, bs: &mut ByteStream, idr_pic_flag: bool) {
+fn dec_ref_pic_marking(
+    sh: &mut SliceHeader,
+    bs: &mut ByteStream,
+    idr_pic_flag: bool,
+) -> Result<(), DecodeError> {
     if idr_pic_flag {
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.no_output_of_prior_pics_flag = r == 1;
-        let s = bs.read_bits(1);
+        let s = bs.read_bits(1)?;
         sh.long_term_reference_flag = s == 1;
 
         decoder_formatted_print("SH: no_output_of_prior_pics_flag", &r, 63);
         decoder_formatted_print("SH: long_term_reference_flag", &s, 63);
     } else {
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.adaptive_ref_pic_marking_mode_flag = r == 1;
 
         decoder_formatted_print("SH: adaptive_ref_pic_buffering_flag", &r, 63);
@@ -411,7 +433,7 @@ fn dec_ref_pic_marking(sh: &mut SliceHeader, bs: &mut ByteStream, idr_pic_flag:
 
             while {
                 sh.memory_management_control_operation
-                    .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
 
                 decoder_formatted_print(
                     "SH: memory_management_control_operation",
@@ -422,7 +444,7 @@ fn dec_ref_pic_marking(sh: &mut SliceHeader, bs: &mut ByteStream, idr_pic_flag:
                     || sh.memory_management_control_operation[i] == 3
                 {
                     sh.difference_of_pic_nums_minus1
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: difference_of_pic_nums_minus1",
                         sh.difference_of_pic_nums_minus1[i],
@@ -434,7 +456,7 @@ fn dec_ref_pic_marking(sh: &mut SliceHeader, bs: &mut ByteStream, idr_pic_flag:
 
                 if sh.memory_management_control_operation[i] == 2 {
                     sh.long_term_pic_num
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print("SH: long_term_pic_num", sh.long_term_pic_num[i], 63);
                 } else {
                     sh.long_term_pic_num.push(0);
@@ -444,7 +466,7 @@ fn dec_ref_pic_marking(sh: &mut SliceHeader, bs: &mut ByteStream, idr_pic_flag:
                     || sh.memory_management_control_operation[i] == 6
                 {
                     sh.long_term_frame_idx
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: long_term_frame_idx",
                         sh.long_term_frame_idx[i],
@@ -455,7 +477,7 @@ fn dec_ref_pic_marking(sh: &mut SliceHeader, bs: &mut ByteStream, idr_pic_flag:
                 }
                 if sh.memory_management_control_operation[i] == 4 {
                     sh.max_long_term_frame_idx_plus1
-                        .push(exp_golomb_decode_one_wrapper(bs, false, 0) as u32);
+                        .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
                     decoder_formatted_print(
                         "SH: max_long_term_frame_idx_plus1",
                         sh.max_long_term_frame_idx_plus1[i],
@@ -471,29 +493,95 @@ fn dec_ref_pic_marking(sh: &mut SliceHeader, bs: &mut ByteStream, idr_pic_flag:
             } {}
         }
     }
+
+    Ok(())
+}
+
+/// Sets the variables that are computed from the spec but not decoded from the stream
+fn set_slice_header_derived_variables(
+    sh: &mut SliceHeader,
+    s: &SeqParameterSet,
+    p: &PicParameterSet,
+    vp: &mut VideoParameters,
+) {
+    // page 87
+    if vp.idr_pic_flag {
+        sh.prev_ref_frame_num = 0;
+    } else {
+        // see clause 8.2.5.2
+        sh.prev_ref_frame_num = sh.frame_num;
+    }
+    // derivation from equation 7-25 in Spec
+    sh.mbaff_frame_flag = s.mb_adaptive_frame_field_flag && !sh.field_pic_flag;
+    // to be used in neighbor decoding
+    vp.mbaff_frame_flag = sh.mbaff_frame_flag;
+
+    // equation 7-26
+    sh.pic_height_in_mbs = vp.frame_height_in_mbs
+        / (1 + match sh.field_pic_flag {
+            true => 1,
+            false => 0,
+        });
+
+    // equation 7-27
+    sh.pic_height_in_samples_luma = sh.pic_height_in_mbs * 16;
+
+    // equation 7-28
+    sh.pic_height_in_samples_chroma = sh.pic_height_in_mbs * vp.mb_height_c;
+
+    // equation 7-29
+    sh.pic_size_in_mbs = vp.pic_width_in_mbs * sh.pic_height_in_mbs;
+
+    // bottom of section field_pic_flag
+    // if !sh.field_pic_flag {
+    //     sh.max_pic_num = vp.max_frame_num;
+    //     sh.curr_pic_num = sh.frame_num;
+    // } else {
+    //     sh.max_pic_num = 2 * vp.max_frame_num;
+    //     sh.curr_pic_num = 2 * sh.frame_num + 1;
+    // }
+    // equation 7-30
+    sh.slice_qp_y = 26 + sh.slice_qp_delta + p.pic_init_qp_minus26;
+    if sh.slice_qp_y < 0 || sh.slice_qp_y > 51 {
+        println!(
+            "[WARNING] slice_qp_y {} is outside of bounds [0, 51] - likely issues decoding",
+            sh.slice_qp_y
+        );
+    }
+    sh.qp_y_prev = sh.slice_qp_y;
+
+    // equation 7-31
+    sh.qs_y = (26 + sh.slice_qs_delta + p.pic_init_qs_minus26) as u8;
+
+    // equation 7-32
+    sh.filter_offset_a = sh.slice_alpha_c0_offset_div2 << 1;
+
+    // equation 7-33
+    sh.filter_offset_b = sh.slice_beta_offset_div2 << 1;
 }
 
-/// Follows section 7.3.3
+/// Follows section 7.3.3; sps_extensions are only used by auxiliary coded pictures
 fn decode_slice_header(
     bs: &mut ByteStream,
     nh: &NALUheader,
     spses: &Vec<SeqParameterSet>,
+    sps_extensions: &[SPSExtension],
     ppses: &Vec<PicParameterSet>,
-) -> (SliceHeader, usize, usize, VideoParameters) {
+) -> Result<(SliceHeader, usize, usize, VideoParameters), DecodeError> {
     let mut sh = SliceHeader::new();
     let mut pps_idx = 0; // search in reverse
     let mut sps_idx = 0; // search in reverse
 
     // first_mb_in_slice
-    sh.first_mb_in_slice = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    sh.first_mb_in_slice = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print("SH: first_mb_in_slice", sh.first_mb_in_slice, 63);
 
     // slice_type
-    sh.slice_type = exp_golomb_decode_one_wrapper(bs, false, 0) as u8;
+    sh.slice_type = exp_golomb_decode_one_wrapper(bs, false, 0)? as u8;
     decoder_formatted_print("SH: slice_type", sh.slice_type, 63);
 
     // pic_parameter_set_id
-    sh.pic_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+    sh.pic_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
     decoder_formatted_print("SH: pic_parameter_set_id", sh.pic_parameter_set_id, 63);
     let mut cur_pps_wrapper: Option<&PicParameterSet> = None;
     // retrieve the corresponding PPS
@@ -508,10 +596,12 @@ fn decode_slice_header(
     let p: &PicParameterSet;
     match cur_pps_wrapper {
         Some(x) => p = x,
-        _ => panic!(
-            "decode_slice_header - PPS with id {} not found",
-            sh.pic_parameter_set_id
-        ),
+        _ => {
+            return Err(DecodeError::new(format!(
+                "decode_slice_header - PPS with id {} not found",
+                sh.pic_parameter_set_id
+            )))
+        }
     }
 
     let mut cur_sps_wrapper: Option<&SeqParameterSet> = None;
@@ -527,34 +617,55 @@ fn decode_slice_header(
     let s: &SeqParameterSet;
     match cur_sps_wrapper {
         Some(x) => s = x,
-        _ => panic!(
-            "decode_slice_header - SPS with id {} not found",
-            p.seq_parameter_set_id
-        ),
+        _ => {
+            return Err(DecodeError::new(format!(
+                "decode_slice_header - SPS with id {} not found",
+                p.seq_parameter_set_id
+            )))
+        }
     }
 
     let mut vp = VideoParameters::new(nh, p, s);
+    if nh.nal_unit_type == 19 {
+        vp.set_auxiliary_picture(sps_extensions, s.seq_parameter_set_id)?;
+    }
+
+    decode_slice_header_body(bs, nh, &mut sh, s, p, &vp)?;
 
+    set_slice_header_derived_variables(&mut sh, s, p, &mut vp);
+
+    Ok((sh, pps_idx, sps_idx, vp))
+}
+
+/// Follows section 7.3.3 from colour_plane_id onwards; shared with slice_header_in_3davc_extension() (J.7.3.3.4)
+fn decode_slice_header_body(
+    bs: &mut ByteStream,
+    nh: &NALUheader,
+    sh: &mut SliceHeader,
+    s: &SeqParameterSet,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+) -> Result<(), DecodeError> {
     // colour_plane_id
     if s.separate_colour_plane_flag {
         // consume two unsigned bits
-        sh.colour_plane_id = bs.read_bits(2) as u8;
+        sh.colour_plane_id = bs.read_bits(2)? as u8;
         decoder_formatted_print("SH: colour_plane_id", sh.colour_plane_id, 63);
     }
 
     // frame_num
     // - this is represented by log2_max_frame_num_minus4 + 4 bits in the bitstream
-    sh.frame_num = bs.read_bits((s.log2_max_frame_num_minus4 + 4) as u8);
+    sh.frame_num = bs.read_bits((s.log2_max_frame_num_minus4 + 4) as u8)?;
     decoder_formatted_print("SH: frame_num", sh.frame_num, 63);
 
     // field_pic_flag and bottom_field_flag
     if !s.frame_mbs_only_flag {
-        sh.field_pic_flag = bs.read_bits(1) == 1;
+        sh.field_pic_flag = bs.read_bits(1)? == 1;
         decoder_formatted_print("SH: field_pic_flag", sh.field_pic_flag, 63);
 
         // bottom_field_flag
         if sh.field_pic_flag {
-            sh.bottom_field_flag = bs.read_bits(1) == 1;
+            sh.bottom_field_flag = bs.read_bits(1)? == 1;
             decoder_formatted_print("SH: bottom_field_flag", sh.bottom_field_flag, 63);
         }
     }
@@ -562,19 +673,19 @@ fn decode_slice_header(
     //idr_pic_id
     // check the NAL Unit Type to see if it is an IDR picture
     if vp.idr_pic_flag {
-        sh.idr_pic_id = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        sh.idr_pic_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print("SH: idr_pic_id", sh.idr_pic_id, 63);
     }
 
     // pic_order_cnt_lsb and delta_pic_order_cnt_bottom
     if s.pic_order_cnt_type == 0 {
         // the length is log2_max_pic_order_cnt_lsb_minus4 + 4
-        sh.pic_order_cnt_lsb = bs.read_bits(s.log2_max_pic_order_cnt_lsb_minus4 + 4);
+        sh.pic_order_cnt_lsb = bs.read_bits(s.log2_max_pic_order_cnt_lsb_minus4 + 4)?;
         decoder_formatted_print("SH: pic_order_cnt_lsb", sh.pic_order_cnt_lsb, 63);
 
         // delta_pic_order_cnt_bottom
         if p.bottom_field_pic_order_in_frame_present_flag && !sh.field_pic_flag {
-            sh.delta_pic_order_cnt_bottom = exp_golomb_decode_one_wrapper(bs, true, 0);
+            sh.delta_pic_order_cnt_bottom = exp_golomb_decode_one_wrapper(bs, true, 0)?;
             decoder_formatted_print(
                 "SH: delta_pic_order_cnt_bottom",
                 sh.delta_pic_order_cnt_bottom,
@@ -586,26 +697,26 @@ fn decode_slice_header(
     // delta_pic_order_cnt[0] and [1]
     if s.pic_order_cnt_type == 1 && !s.delta_pic_order_always_zero_flag {
         sh.delta_pic_order_cnt
-            .push(exp_golomb_decode_one_wrapper(bs, true, 0));
+            .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
         decoder_formatted_print("SH: delta_pic_order_cnt[0]", sh.delta_pic_order_cnt[0], 63);
 
         // delta_pic_order_cnt[1]
         if p.bottom_field_pic_order_in_frame_present_flag && !sh.field_pic_flag {
             sh.delta_pic_order_cnt
-                .push(exp_golomb_decode_one_wrapper(bs, true, 0));
+                .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
             decoder_formatted_print("SH: delta_pic_order_cnt[1]", sh.delta_pic_order_cnt[1], 63);
         }
     }
 
     // redundant_pic_cnt
     if p.redundant_pic_cnt_present_flag {
-        sh.redundant_pic_cnt = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        sh.redundant_pic_cnt = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print("SH: redundant_pic_cnt", sh.redundant_pic_cnt, 63);
     }
 
     // direct_spatial_mv_pred_flag
     if is_slice_type(sh.slice_type, "B") {
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.direct_spatial_mv_pred_flag = r == 1;
         decoder_formatted_print("SH: direct_spatial_mv_pred_flag", &r, 63);
     }
@@ -616,13 +727,13 @@ fn decode_slice_header(
         || is_slice_type(sh.slice_type, "SP")
         || is_slice_type(sh.slice_type, "B")
     {
-        let r = bs.read_bits(1);
+        let r = bs.read_bits(1)?;
         sh.num_ref_idx_active_override_flag = r == 1;
         decoder_formatted_print("SH: num_ref_idx_override_flag", &r, 63);
 
         // num_ref_idxl0_active_minus1
         if sh.num_ref_idx_active_override_flag {
-            sh.num_ref_idx_l0_active_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+            sh.num_ref_idx_l0_active_minus1 = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
             decoder_formatted_print(
                 "SH: num_ref_idx_l0_active_minus1",
                 sh.num_ref_idx_l0_active_minus1,
@@ -631,7 +742,7 @@ fn decode_slice_header(
             // num_ref_idxl1_active_minus1
             if is_slice_type(sh.slice_type, "B") {
                 sh.num_ref_idx_l1_active_minus1 =
-                    exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+                    exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
                 decoder_formatted_print(
                     "SH: num_ref_idx_l1_active_minus1",
                     sh.num_ref_idx_l1_active_minus1,
@@ -647,9 +758,9 @@ fn decode_slice_header(
 
     if nh.nal_unit_type == 20 || nh.nal_unit_type == 21 {
         // ref_pic_list_mvc_modification (specified in Annex H: Multiview Video Coding)
-        ref_pic_list_mvc_modification(&mut sh, bs);
+        ref_pic_list_mvc_modification(sh, bs)?;
     } else {
-        ref_pic_list_modification(&mut sh, bs);
+        ref_pic_list_modification(sh, bs)?;
     }
 
     // pred_weight_table()
@@ -657,12 +768,12 @@ fn decode_slice_header(
         && (is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "SP")))
         || (p.weighted_bipred_idc == 1 && is_slice_type(sh.slice_type, "B"))
     {
-        pred_weight_table(&mut sh, bs, vp.chroma_array_type);
+        pred_weight_table(sh, bs, vp.chroma_array_type)?;
     }
 
     // dec_ref_pic_marking()
     if nh.nal_ref_idc != 0 {
-        dec_ref_pic_marking(&mut sh, bs, vp.idr_pic_flag);
+        dec_ref_pic_marking(sh, bs, vp.idr_pic_flag)?;
     }
 
     // cabac_init_idc
@@ -670,27 +781,27 @@ fn decode_slice_header(
         && !is_slice_type(sh.slice_type, "I")
         && !is_slice_type(sh.slice_type, "SI")
     {
-        sh.cabac_init_idc = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        sh.cabac_init_idc = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print("SH: cabac_init_idc", sh.cabac_init_idc, 63);
     }
 
     // slice_qp_delta
-    sh.slice_qp_delta = exp_golomb_decode_one_wrapper(bs, true, 0);
+    sh.slice_qp_delta = exp_golomb_decode_one_wrapper(bs, true, 0)?;
     decoder_formatted_print("SH: slice_qp_delta", sh.slice_qp_delta, 63);
 
     // sp_for_switch_flag and slice_qs_delta
     if is_slice_type(sh.slice_type, "SP") || is_slice_type(sh.slice_type, "SI") {
         if is_slice_type(sh.slice_type, "SP") {
-            sh.sp_for_switch_flag = bs.read_bits(1) == 1;
+            sh.sp_for_switch_flag = bs.read_bits(1)? == 1;
             decoder_formatted_print("SH: sp_for_switch_flag", sh.sp_for_switch_flag, 63);
         }
-        sh.slice_qs_delta = exp_golomb_decode_one_wrapper(bs, true, 0);
+        sh.slice_qs_delta = exp_golomb_decode_one_wrapper(bs, true, 0)?;
         decoder_formatted_print("SH: slice_qs_delta", sh.slice_qs_delta, 63);
     }
 
     // disable_deblocking_filter_idc and slice_alpha_c0_offset_div2 and slice_beta_offset_div2
     if p.deblocking_filter_control_present_flag {
-        sh.disable_deblocking_filter_idc = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+        sh.disable_deblocking_filter_idc = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
         decoder_formatted_print(
             "SH: disable_deblocking_filter_idc",
             sh.disable_deblocking_filter_idc,
@@ -698,8 +809,8 @@ fn decode_slice_header(
         );
 
         if sh.disable_deblocking_filter_idc != 1 {
-            sh.slice_alpha_c0_offset_div2 = exp_golomb_decode_one_wrapper(bs, true, 0);
-            sh.slice_beta_offset_div2 = exp_golomb_decode_one_wrapper(bs, true, 0);
+            sh.slice_alpha_c0_offset_div2 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+            sh.slice_beta_offset_div2 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
 
             decoder_formatted_print(
                 "SH: slice_alpha_c0_offset_div2",
@@ -712,13 +823,11 @@ fn decode_slice_header(
 
     // slice_group_change_cycle
     if p.num_slice_groups_minus1 > 0 && p.slice_group_map_type >= 3 && p.slice_group_map_type <= 5 {
-        // size is dictated by equation 7-35:
-        // Ceil(log2(PicSizeInMapUnits / SliceGroupChangeRate + 1))
-        let bits_to_read = ((vp.pic_size_in_map_units / (p.slice_group_change_rate_minus1 + 1) + 1)
-            as f64)
-            .log2()
-            .ceil() as u8;
-        sh.slice_group_change_cycle = bs.read_bits(bits_to_read);
+        let bits_to_read = slice_group_change_cycle_len(
+            vp.pic_size_in_map_units,
+            p.slice_group_change_rate_minus1,
+        ) as u8;
+        sh.slice_group_change_cycle = bs.read_bits(bits_to_read)?;
         decoder_formatted_print(
             "SH: slice_group_change_cycle",
             sh.slice_group_change_cycle,
@@ -726,69 +835,13 @@ fn decode_slice_header(
         );
     }
 
-    // Variables that are computed from the spec but not decoded from the stream
-    // page 87
-    if vp.idr_pic_flag {
-        sh.prev_ref_frame_num = 0;
-    } else {
-        // see clause 8.2.5.2
-        sh.prev_ref_frame_num = sh.frame_num;
-    }
-    // derivation from equation 7-25 in Spec
-    sh.mbaff_frame_flag = s.mb_adaptive_frame_field_flag && !sh.field_pic_flag;
-    // to be used in neighbor decoding
-    vp.mbaff_frame_flag = sh.mbaff_frame_flag;
-
-    // equation 7-26
-    sh.pic_height_in_mbs = vp.frame_height_in_mbs
-        / (1 + match sh.field_pic_flag {
-            true => 1,
-            false => 0,
-        });
-
-    // equation 7-27
-    sh.pic_height_in_samples_luma = sh.pic_height_in_mbs * 16;
-
-    // equation 7-28
-    sh.pic_height_in_samples_chroma = sh.pic_height_in_mbs * (vp.mb_height_c as u32);
-
-    // equation 7-29
-    sh.pic_size_in_mbs = vp.pic_width_in_mbs * sh.pic_height_in_mbs;
-
-    // bottom of section field_pic_flag
-    // if !sh.field_pic_flag {
-    //     sh.max_pic_num = vp.max_frame_num;
-    //     sh.curr_pic_num = sh.frame_num;
-    // } else {
-    //     sh.max_pic_num = 2 * vp.max_frame_num;
-    //     sh.curr_pic_num = 2 * sh.frame_num + 1;
-    // }
-    // equation 7-30
-    sh.slice_qp_y = 26 + sh.slice_qp_delta + p.pic_init_qp_minus26;
-    if sh.slice_qp_y < 0 || sh.slice_qp_y > 51 {
-        println!(
-            "[WARNING] slice_qp_y {} is outside of bounds [0, 51] - likely issues decoding",
-            sh.slice_qp_y
-        );
-    }
-    sh.qp_y_prev = sh.slice_qp_y;
-
-    // equation 7-31
-    sh.qs_y = (26 + sh.slice_qs_delta + p.pic_init_qs_minus26) as u8;
-
-    // equation 7-32
-    sh.filter_offset_a = sh.slice_alpha_c0_offset_div2 << 1;
-
-    // equation 7-33
-    sh.filter_offset_b = sh.slice_beta_offset_div2 << 1;
-
-    (sh, pps_idx, sps_idx, vp)
+    Ok(())
 }
 
 /// Defined in Section 8.2.2. Handles Slice Groups
-fn next_mb_addr(curr_mb_addr: usize, pic_size_in_mbs: usize, sgm: &Vec<u32>) -> usize {
+fn next_mb_addr(curr_mb_addr: usize, pic_size_in_mbs: usize, sgm: &[u32]) -> usize {
     let mut next_addr = curr_mb_addr + 1;
-    while next_addr < pic_size_in_mbs && sgm[next_addr] != sgm[curr_mb_addr] {
+    while next_addr < pic_size_in_mbs && sgm.get(next_addr) != sgm.get(curr_mb_addr) {
         next_addr += 1;
     }
     next_addr
@@ -802,7 +855,8 @@ fn decode_slice_data(
     p: &PicParameterSet,
     vp: &VideoParameters,
     decode_strict_fmo: bool,
-) -> SliceData {
+    mut partitions: Option<&mut ResidualPartitions>,
+) -> Result<SliceData, DecodeError> {
     // Picture Order Count, current_macroblock_number, current_slice_number, current_slice_type`
     let mut sd = SliceData::new();
     let mut cabac_state = CABACState::new();
@@ -811,9 +865,9 @@ fn decode_slice_data(
         ((s.pic_height_in_map_units_minus1 + 1) * (s.pic_width_in_mbs_minus1 + 1)) as usize;
 
     let sgm = if decode_strict_fmo {
-        sh.generate_slice_group_map(s, p, vp)
+        sh.generate_slice_group_map(s, p, vp)?
     } else {
-        vec![0; vp.pic_size_in_map_units as usize]
+        vec![0; sh.pic_size_in_mbs as usize]
     };
 
     // multiply by 2 for frame slices in field-supporting videos
@@ -827,7 +881,7 @@ fn decode_slice_data(
             bs.bytestream.pop_front();
             bs.byte_offset = 0;
         }
-        cabac_state = initialize_state(bs);
+        cabac_state = initialize_state(bs)?;
     }
     // create the current Macroblock and set its address
 
@@ -840,6 +894,7 @@ fn decode_slice_data(
     let mut more_data_flag = true;
     let mut prev_mb_skipped = false;
     let mut prev_predicted_mb_field_decoding_flag = false; // necessary in MBAFF when the top MB is skipped, and the bottom one needs to decode mb_field_decoding_flag
+    let mut skip_run_past_picture = false;
 
     // do while trick
     while {
@@ -869,7 +924,7 @@ fn decode_slice_data(
         );
         if !is_slice_type(sh.slice_type, "I") && !is_slice_type(sh.slice_type, "SI") {
             if !p.entropy_coding_mode_flag {
-                let mb_skip_run = exp_golomb_decode_one_wrapper(bs, false, 0) as u32;
+                let mb_skip_run = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
                 decoder_formatted_print("mb_skip_run", mb_skip_run, 63);
 
                 //debug!(target: "decode","*********** Skipping over {} Macroblocks ***********", mb_skip_run);
@@ -891,6 +946,17 @@ fn decode_slice_data(
                 // this may be important for displaying info, but we can skip for now (hopefully)
                 for i in 0..mb_skip_run {
                     curr_mb_addr = next_mb_addr(curr_mb_addr, sh.pic_size_in_mbs as usize, &sgm);
+                    // skipped macroblocks past the end of the picture are not stored and end
+                    // the slice, so a large mb_skip_run is not allocated; the encoder writes
+                    // mb_skip_run as is and stops at the end of the picture as well
+                    if curr_mb_addr >= sh.pic_size_in_mbs as usize {
+                        println!(
+                            "\t [WARNING] mb_skip_run {} goes past the {} macroblocks of the picture",
+                            mb_skip_run, sh.pic_size_in_mbs
+                        );
+                        skip_run_past_picture = true;
+                        break;
+                    }
                     curr_mb_idx += 1;
 
                     curr_mb = MacroBlock::new();
@@ -919,7 +985,9 @@ fn decode_slice_data(
                     }
                 }
 
-                if mb_skip_run > 0 {
+                if skip_run_past_picture {
+                    more_data_flag = false;
+                } else if mb_skip_run > 0 {
                     more_data_flag = bs.more_data();
                     if more_data_flag {
                         // Do an extra print
@@ -1073,7 +1141,7 @@ fn decode_slice_data(
                     vp,
                     0,
                     Vec::new(),
-                );
+                )?;
                 let res = match r {
                     1 => true,
                     _ => false,
@@ -1110,15 +1178,12 @@ fn decode_slice_data(
                         vp,
                         0,
                         Vec::new(),
-                    ) {
+                    )? {
                         1 => true,
                         _ => false,
                     }
                 } else {
-                    match bs.read_bits(1) {
-                        1 => true,
-                        _ => false,
-                    }
+                    1 == bs.read_bits(1)?
                 };
 
                 // in case we added our inference for neighbor prediction above,
@@ -1171,7 +1236,17 @@ fn decode_slice_data(
                     // macroblock is also skipped) before it starts the decoding process for the top macroblock
                 }
             }
-            decode_macroblock_layer(curr_mb_idx, &mut cabac_state, bs, &mut sd, sh, vp, s, p);
+            decode_macroblock_layer(
+                curr_mb_idx,
+                &mut cabac_state,
+                bs,
+                &mut sd,
+                sh,
+                vp,
+                s,
+                p,
+                partitions.as_deref_mut(),
+            )?;
         } else {
             // only push this in cases where we haven't added this list yet
             // only copy over values that are bottom macroblocks
@@ -1193,7 +1268,7 @@ fn decode_slice_data(
             }
         }
         if !p.entropy_coding_mode_flag {
-            more_data_flag = bs.more_data();
+            more_data_flag = !skip_run_past_picture && bs.more_data();
         } else {
             if !is_slice_type(sh.slice_type, "I") && !is_slice_type(sh.slice_type, "SI") {
                 prev_mb_skipped = sd.macroblock_vec[curr_mb_idx].mb_skip_flag;
@@ -1212,7 +1287,7 @@ fn decode_slice_data(
                     vp,
                     0,
                     Vec::new(),
-                );
+                )?;
                 sd.end_of_slice_flag.push(match r {
                     1 => true,
                     _ => false,
@@ -1228,19 +1303,21 @@ fn decode_slice_data(
         more_data_flag
     } {}
 
-    sd
+    Ok(sd)
 }
 
-/// Follows section 7.3.2.8
+/// Follows section 7.3.2.8. Also used for auxiliary coded pictures (NALU type 19), which
+/// are decoded as monochrome pictures with the bit depth from the matching SPS extension
 pub fn decode_slice_layer_without_partitioning_rbsp(
     nalu_data: &mut ByteStream,
     nh: &NALUheader,
     spses: &Vec<SeqParameterSet>,
+    sps_extensions: &[SPSExtension],
     ppses: &Vec<PicParameterSet>,
     only_headers: bool,
     decode_strict_fmo: bool,
-) -> Slice {
-    let res = decode_slice_header(nalu_data, nh, spses, ppses);
+) -> Result<Slice, DecodeError> {
+    let res = decode_slice_header(nalu_data, nh, spses, sps_extensions, ppses)?;
     let mut sh = res.0;
     let p = &ppses[res.1];
     let s = &spses[res.2];
@@ -1249,10 +1326,105 @@ pub fn decode_slice_layer_without_partitioning_rbsp(
     let sd: SliceData = if only_headers {
         SliceData::new()
     } else {
-        decode_slice_data(nalu_data, &mut sh, s, p, &vp, decode_strict_fmo)
+        decode_slice_data(nalu_data, &mut sh, s, p, &vp, decode_strict_fmo, None)?
+    };
+
+    Ok(Slice { sh, sd })
+}
+
+/// Reads the syntax elements that start partitions B and C
+fn decode_slice_data_partition_bc_header(
+    bs: &mut ByteStream,
+    s: &SeqParameterSet,
+    p: &PicParameterSet,
+    name: &str,
+) -> Result<SliceDataPartition, DecodeError> {
+    let mut sdp = SliceDataPartition::new();
+
+    sdp.slice_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print(&format!("SDP {}: slice_id", name), sdp.slice_id, 63);
+
+    if s.separate_colour_plane_flag {
+        sdp.colour_plane_id = bs.read_bits(2)? as u8;
+        decoder_formatted_print(
+            &format!("SDP {}: colour_plane_id", name),
+            sdp.colour_plane_id,
+            63,
+        );
+    }
+
+    if p.redundant_pic_cnt_present_flag {
+        sdp.redundant_pic_cnt = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            &format!("SDP {}: redundant_pic_cnt", name),
+            sdp.redundant_pic_cnt,
+            63,
+        );
+    }
+
+    Ok(sdp)
+}
+
+/// Follows section 7.3.2.9. Decodes partition A along with the optional partitions B and C
+/// of the same slice. The slice data of all three partitions is merged into the returned Slice,
+/// and the partition syntax elements are returned in the order A, B, C for the present partitions
+#[allow(clippy::too_many_arguments)]
+pub fn decode_slice_data_partitions(
+    a_data: &mut ByteStream,
+    mut b_data: Option<&mut ByteStream>,
+    mut c_data: Option<&mut ByteStream>,
+    nh: &NALUheader,
+    spses: &Vec<SeqParameterSet>,
+    ppses: &Vec<PicParameterSet>,
+    only_headers: bool,
+    decode_strict_fmo: bool,
+) -> Result<(Slice, Vec<SliceDataPartition>), DecodeError> {
+    let res = decode_slice_header(a_data, nh, spses, &Vec::new(), ppses)?;
+    let mut sh = res.0;
+    let p = &ppses[res.1];
+    let s = &spses[res.2];
+    let vp = res.3;
+
+    let mut sdp_a = SliceDataPartition::new();
+    sdp_a.slice_id = exp_golomb_decode_one_wrapper(a_data, false, 0)? as u32;
+    decoder_formatted_print("SDP A: slice_id", sdp_a.slice_id, 63);
+    let mut res_partitions = vec![sdp_a];
+
+    for (data, name) in [(b_data.as_deref_mut(), "B"), (c_data.as_deref_mut(), "C")] {
+        if let Some(bs) = data {
+            let sdp = decode_slice_data_partition_bc_header(bs, s, p, name)?;
+            if sdp.slice_id != res_partitions[0].slice_id {
+                println!(
+                    "[WARNING] Slice data partition {} slice_id {} does not match partition A slice_id {}",
+                    name, sdp.slice_id, res_partitions[0].slice_id
+                );
+            }
+            res_partitions.push(sdp);
+        }
+    }
+
+    let sd: SliceData = if only_headers {
+        SliceData::new()
+    } else if p.entropy_coding_mode_flag {
+        println!("[WARNING] Slice data partitioning with CABAC is not allowed - decoding all slice data from partition A");
+        decode_slice_data(a_data, &mut sh, s, p, &vp, decode_strict_fmo, None)?
+    } else {
+        let mut partitions = ResidualPartitions {
+            b: b_data,
+            c: c_data,
+        };
+        decode_slice_data(
+            a_data,
+            &mut sh,
+            s,
+            p,
+            &vp,
+            decode_strict_fmo,
+            Some(&mut partitions),
+        )?
     };
 
-    Slice { sh, sd }
+    Ok((Slice { sh, sd }, res_partitions))
 }
 
 /// Follows section 7.3.2.13
@@ -1263,30 +1435,44 @@ pub fn decode_slice_layer_extension_rbsp(
     ppses: &Vec<PicParameterSet>,
     only_headers: bool,
     decode_strict_fmo: bool,
-) -> Slice {
+) -> Result<Slice, DecodeError> {
     if nh.svc_extension_flag {
-        println!("[WARNING] NALU SVC Extension Flag enabled - Decoding may not be correct");
+        let res = decode_slice_header_in_scalable_extension(nalu_data, nh, subset_spses, ppses)?;
+        let mut sh = res.0;
+        let p = &ppses[res.1];
+        let s = &subset_spses[res.2];
+        let vp = res.3;
+
+        let sd: SliceData = if only_headers || sh.svc_extension.slice_skip_flag {
+            SliceData::new()
+        } else {
+            decode_slice_data_in_scalable_extension(
+                nalu_data,
+                &mut sh,
+                s,
+                p,
+                &vp,
+                decode_strict_fmo,
+            )?
+        };
+
+        return Ok(Slice { sh, sd });
     } else if nh.avc_3d_extension_flag {
-        println!("[WARNING] NALU AVC 3D Extension Flag enabled - Decoding may not be correct");
-    }
-
-    // TODO: Uncomment whenever implementation below is done
-
-    //let sh : SliceHeader;
-    //let sd : SliceData;
-    //if nh.svc_extension_flag {
-    //    let res = decode_slice_header_in_scalable_extension(); // specified in Annex G
-    //    sh = res.0;
-    //    let slice_skip_flag = res.1;
-    //    if !slice_skip_flag {
-    //        sd = decode_slice_data_in_scalable_extension(); // specified in Annex G
-    //    } else {
-    //        sd = SliceData::new();
-    //    }
-    //} else if nh.avc_3d_extension_flag {
-    //    sh = decode_slice_header_in_3davc_extension();
-    //    sd = decode_slice_data_in_3davc_extension();
-    //} else {
+        let res = decode_slice_header_in_3davc_extension(nalu_data, nh, subset_spses, ppses)?;
+        let mut sh = res.0;
+        let p = &ppses[res.1];
+        let s = &subset_spses[res.2];
+        let vp = res.3;
+
+        let sd: SliceData = if only_headers {
+            SliceData::new()
+        } else {
+            decode_slice_data_in_3davc_extension(nalu_data, &mut sh, s, p, &vp, decode_strict_fmo)?
+        };
+
+        return Ok(Slice { sh, sd });
+    }
+
     let mut spses = Vec::new();
     for s in subset_spses {
         spses.push(s.sps.clone());
@@ -1295,42 +1481,1355 @@ pub fn decode_slice_layer_extension_rbsp(
         nalu_data,
         nh,
         &spses,
+        &Vec::new(),
         ppses,
         only_headers,
         decode_strict_fmo,
     )
-    //}
-    //
-    //return Slice{ sh : sh, sd : sd};
 }
 
-/// Follows section G.7.3.3.4
-#[allow(dead_code)]
-fn decode_slice_header_in_scalable_extension() -> (SliceHeader, bool) {
-    let res = SliceHeader::new();
-    let slice_skip_flag = true;
-    // TODO: Annex G
+/// Follows section G.7.3.3.5
+fn dec_ref_base_pic_marking(sh: &mut SliceHeader, bs: &mut ByteStream) -> Result<(), DecodeError> {
+    sh.svc_extension.adaptive_ref_base_pic_marking_mode_flag = 1 == bs.read_bits(1)?;
+    decoder_formatted_print(
+        "SH: adaptive_ref_base_pic_marking_mode_flag",
+        sh.svc_extension.adaptive_ref_base_pic_marking_mode_flag,
+        63,
+    );
+
+    if sh.svc_extension.adaptive_ref_base_pic_marking_mode_flag {
+        let mut i = 0;
+        loop {
+            sh.svc_extension
+                .memory_management_base_control_operation
+                .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+            decoder_formatted_print(
+                "SH: memory_management_base_control_operation",
+                sh.svc_extension.memory_management_base_control_operation[i],
+                63,
+            );
 
-    (res, slice_skip_flag)
-}
+            if sh.svc_extension.memory_management_base_control_operation[i] == 1 {
+                sh.svc_extension
+                    .difference_of_base_pic_nums_minus1
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SH: difference_of_base_pic_nums_minus1",
+                    sh.svc_extension.difference_of_base_pic_nums_minus1[i],
+                    63,
+                );
+            } else {
+                sh.svc_extension.difference_of_base_pic_nums_minus1.push(0);
+            }
 
-/// Follows section G.7.3.4.1
-#[allow(dead_code)]
-fn decode_slice_data_in_scalable_extension() -> SliceData {
-    // TODO: Annex G
-    SliceData::new()
-}
+            if sh.svc_extension.memory_management_base_control_operation[i] == 2 {
+                sh.svc_extension
+                    .long_term_base_pic_num
+                    .push(exp_golomb_decode_one_wrapper(bs, false, 0)? as u32);
+                decoder_formatted_print(
+                    "SH: long_term_base_pic_num",
+                    sh.svc_extension.long_term_base_pic_num[i],
+                    63,
+                );
+            } else {
+                sh.svc_extension.long_term_base_pic_num.push(0);
+            }
 
-/// Follows section J.7.3.3.4
-#[allow(dead_code)]
-fn decode_slice_header_in_3davc_extension() -> SliceHeader {
-    // TODO: Annex J
-    SliceHeader::new()
+            if sh.svc_extension.memory_management_base_control_operation[i] == 0 {
+                break;
+            }
+
+            i += 1;
+        }
+    }
+
+    Ok(())
 }
 
-/// Follows section J.7.3.4.1
-#[allow(dead_code)]
-fn decode_slice_data_in_3davc_extension() -> SliceData {
-    // TODO: Annex J
-    SliceData::new()
+/// Follows section G.7.3.3.4
+///
+/// Returns the slice header along with the index of the PPS and subset SPS it refers to
+fn decode_slice_header_in_scalable_extension(
+    bs: &mut ByteStream,
+    nh: &NALUheader,
+    subset_spses: &[SubsetSPS],
+    ppses: &[PicParameterSet],
+) -> Result<(SliceHeader, usize, usize, VideoParameters), DecodeError> {
+    let mut sh = SliceHeader::new();
+    sh.svc_extension_flag = true;
+    sh.svc_extension.no_inter_layer_pred_flag = nh.svc_extension.no_inter_layer_pred_flag;
+    sh.svc_extension.quality_id = nh.svc_extension.quality_id;
+
+    // first_mb_in_slice
+    sh.first_mb_in_slice = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("SH: first_mb_in_slice", sh.first_mb_in_slice, 63);
+
+    // slice_type
+    sh.slice_type = exp_golomb_decode_one_wrapper(bs, false, 0)? as u8;
+    decoder_formatted_print("SH: slice_type", sh.slice_type, 63);
+
+    // pic_parameter_set_id
+    sh.pic_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("SH: pic_parameter_set_id", sh.pic_parameter_set_id, 63);
+
+    // retrieve the corresponding PPS and subset SPS
+    let pps_idx = match (0..ppses.len())
+        .rev()
+        .find(|&i| ppses[i].pic_parameter_set_id == sh.pic_parameter_set_id)
+    {
+        Some(i) => i,
+        None => {
+            return Err(DecodeError::new(format!(
+                "decode_slice_header_in_scalable_extension - PPS with id {} not found",
+                sh.pic_parameter_set_id
+            )))
+        }
+    };
+    let p = &ppses[pps_idx];
+
+    let sps_idx = match (0..subset_spses.len())
+        .rev()
+        .find(|&i| subset_spses[i].sps.seq_parameter_set_id == p.seq_parameter_set_id)
+    {
+        Some(i) => i,
+        None => {
+            return Err(DecodeError::new(format!(
+                "decode_slice_header_in_scalable_extension - Subset SPS with id {} not found",
+                p.seq_parameter_set_id
+            )))
+        }
+    };
+    let subset_sps = &subset_spses[sps_idx];
+    let s = &subset_sps.sps;
+
+    let mut vp = VideoParameters::new(nh, p, s);
+
+    // colour_plane_id
+    if s.separate_colour_plane_flag {
+        sh.colour_plane_id = bs.read_bits(2)? as u8;
+        decoder_formatted_print("SH: colour_plane_id", sh.colour_plane_id, 63);
+    }
+
+    // frame_num
+    sh.frame_num = bs.read_bits((s.log2_max_frame_num_minus4 + 4) as u8)?;
+    decoder_formatted_print("SH: frame_num", sh.frame_num, 63);
+
+    // field_pic_flag and bottom_field_flag
+    if !s.frame_mbs_only_flag {
+        sh.field_pic_flag = bs.read_bits(1)? == 1;
+        decoder_formatted_print("SH: field_pic_flag", sh.field_pic_flag, 63);
+
+        if sh.field_pic_flag {
+            sh.bottom_field_flag = bs.read_bits(1)? == 1;
+            decoder_formatted_print("SH: bottom_field_flag", sh.bottom_field_flag, 63);
+        }
+    }
+
+    // idr_pic_id - vp.idr_pic_flag is set from the NALU header idr_flag
+    if vp.idr_pic_flag {
+        sh.idr_pic_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print("SH: idr_pic_id", sh.idr_pic_id, 63);
+    }
+
+    // pic_order_cnt_lsb and delta_pic_order_cnt_bottom
+    if s.pic_order_cnt_type == 0 {
+        sh.pic_order_cnt_lsb = bs.read_bits(s.log2_max_pic_order_cnt_lsb_minus4 + 4)?;
+        decoder_formatted_print("SH: pic_order_cnt_lsb", sh.pic_order_cnt_lsb, 63);
+
+        if p.bottom_field_pic_order_in_frame_present_flag && !sh.field_pic_flag {
+            sh.delta_pic_order_cnt_bottom = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+            decoder_formatted_print(
+                "SH: delta_pic_order_cnt_bottom",
+                sh.delta_pic_order_cnt_bottom,
+                63,
+            );
+        }
+    }
+
+    // delta_pic_order_cnt[0] and [1]
+    if s.pic_order_cnt_type == 1 && !s.delta_pic_order_always_zero_flag {
+        sh.delta_pic_order_cnt
+            .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
+        decoder_formatted_print("SH: delta_pic_order_cnt[0]", sh.delta_pic_order_cnt[0], 63);
+
+        if p.bottom_field_pic_order_in_frame_present_flag && !sh.field_pic_flag {
+            sh.delta_pic_order_cnt
+                .push(exp_golomb_decode_one_wrapper(bs, true, 0)?);
+            decoder_formatted_print("SH: delta_pic_order_cnt[1]", sh.delta_pic_order_cnt[1], 63);
+        }
+    }
+
+    // redundant_pic_cnt
+    if p.redundant_pic_cnt_present_flag {
+        sh.redundant_pic_cnt = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print("SH: redundant_pic_cnt", sh.redundant_pic_cnt, 63);
+    }
+
+    // if not overridden, the number of active references comes from the PPS
+    sh.num_ref_idx_l0_active_minus1 = p.num_ref_idx_l0_default_active_minus1;
+    sh.num_ref_idx_l1_active_minus1 = p.num_ref_idx_l1_default_active_minus1;
+
+    if sh.svc_extension.quality_id == 0 {
+        // direct_spatial_mv_pred_flag (EB slices)
+        if is_slice_type(sh.slice_type, "B") {
+            sh.direct_spatial_mv_pred_flag = bs.read_bits(1)? == 1;
+            decoder_formatted_print(
+                "SH: direct_spatial_mv_pred_flag",
+                sh.direct_spatial_mv_pred_flag,
+                63,
+            );
+        }
+
+        // num_ref_idx_active_override_flag (EP and EB slices)
+        if is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "B") {
+            sh.num_ref_idx_active_override_flag = bs.read_bits(1)? == 1;
+            decoder_formatted_print(
+                "SH: num_ref_idx_override_flag",
+                sh.num_ref_idx_active_override_flag,
+                63,
+            );
+
+            if sh.num_ref_idx_active_override_flag {
+                sh.num_ref_idx_l0_active_minus1 =
+                    exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                decoder_formatted_print(
+                    "SH: num_ref_idx_l0_active_minus1",
+                    sh.num_ref_idx_l0_active_minus1,
+                    63,
+                );
+                if is_slice_type(sh.slice_type, "B") {
+                    sh.num_ref_idx_l1_active_minus1 =
+                        exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                    decoder_formatted_print(
+                        "SH: num_ref_idx_l1_active_minus1",
+                        sh.num_ref_idx_l1_active_minus1,
+                        63,
+                    );
+                }
+            }
+        }
+
+        ref_pic_list_modification(&mut sh, bs)?;
+
+        if (p.weighted_pred_flag && is_slice_type(sh.slice_type, "P"))
+            || (p.weighted_bipred_idc == 1 && is_slice_type(sh.slice_type, "B"))
+        {
+            if !sh.svc_extension.no_inter_layer_pred_flag {
+                sh.svc_extension.base_pred_weight_table_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print(
+                    "SH: base_pred_weight_table_flag",
+                    sh.svc_extension.base_pred_weight_table_flag,
+                    63,
+                );
+            }
+            if sh.svc_extension.no_inter_layer_pred_flag
+                || !sh.svc_extension.base_pred_weight_table_flag
+            {
+                pred_weight_table(&mut sh, bs, vp.chroma_array_type)?;
+            }
+        }
+
+        if nh.nal_ref_idc != 0 {
+            dec_ref_pic_marking(&mut sh, bs, vp.idr_pic_flag)?;
+
+            if !subset_sps.sps_svc.slice_header_restriction_flag {
+                sh.svc_extension.store_ref_base_pic_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print(
+                    "SH: store_ref_base_pic_flag",
+                    sh.svc_extension.store_ref_base_pic_flag,
+                    63,
+                );
+
+                if (nh.svc_extension.use_ref_base_pic_flag
+                    || sh.svc_extension.store_ref_base_pic_flag)
+                    && !nh.svc_extension.idr_flag
+                {
+                    dec_ref_base_pic_marking(&mut sh, bs)?;
+                }
+            }
+        }
+    }
+
+    // cabac_init_idc
+    if p.entropy_coding_mode_flag && !is_slice_type(sh.slice_type, "I") {
+        sh.cabac_init_idc = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print("SH: cabac_init_idc", sh.cabac_init_idc, 63);
+    }
+
+    // slice_qp_delta
+    sh.slice_qp_delta = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+    decoder_formatted_print("SH: slice_qp_delta", sh.slice_qp_delta, 63);
+
+    // disable_deblocking_filter_idc and slice_alpha_c0_offset_div2 and slice_beta_offset_div2
+    if p.deblocking_filter_control_present_flag {
+        sh.disable_deblocking_filter_idc = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print(
+            "SH: disable_deblocking_filter_idc",
+            sh.disable_deblocking_filter_idc,
+            63,
+        );
+
+        if sh.disable_deblocking_filter_idc != 1 {
+            sh.slice_alpha_c0_offset_div2 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+            sh.slice_beta_offset_div2 = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+
+            decoder_formatted_print(
+                "SH: slice_alpha_c0_offset_div2",
+                sh.slice_alpha_c0_offset_div2,
+                63,
+            );
+            decoder_formatted_print("SH: slice_beta_offset_div2", sh.slice_beta_offset_div2, 63);
+        }
+    }
+
+    // slice_group_change_cycle
+    if p.num_slice_groups_minus1 > 0 && p.slice_group_map_type >= 3 && p.slice_group_map_type <= 5 {
+        // size is dictated by equation 7-35
+        let bits_to_read = ((vp.pic_size_in_map_units / (p.slice_group_change_rate_minus1 + 1) + 1)
+            as f64)
+            .log2()
+            .ceil() as u8;
+        sh.slice_group_change_cycle = bs.read_bits(bits_to_read)?;
+        decoder_formatted_print(
+            "SH: slice_group_change_cycle",
+            sh.slice_group_change_cycle,
+            63,
+        );
+    }
+
+    if !sh.svc_extension.no_inter_layer_pred_flag && sh.svc_extension.quality_id == 0 {
+        sh.svc_extension.ref_layer_dq_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+        decoder_formatted_print("SH: ref_layer_dq_id", sh.svc_extension.ref_layer_dq_id, 63);
+
+        if subset_sps
+            .sps_svc
+            .inter_layer_deblocking_filter_control_present_flag
+        {
+            sh.svc_extension.disable_inter_layer_deblocking_filter_idc =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SH: disable_inter_layer_deblocking_filter_idc",
+                sh.svc_extension.disable_inter_layer_deblocking_filter_idc,
+                63,
+            );
+
+            if sh.svc_extension.disable_inter_layer_deblocking_filter_idc != 1 {
+                sh.svc_extension.inter_layer_slice_alpha_c0_offset_div2 =
+                    exp_golomb_decode_one_wrapper(bs, true, 0)?;
+                decoder_formatted_print(
+                    "SH: inter_layer_slice_alpha_c0_offset_div2",
+                    sh.svc_extension.inter_layer_slice_alpha_c0_offset_div2,
+                    63,
+                );
+                sh.svc_extension.inter_layer_slice_beta_offset_div2 =
+                    exp_golomb_decode_one_wrapper(bs, true, 0)?;
+                decoder_formatted_print(
+                    "SH: inter_layer_slice_beta_offset_div2",
+                    sh.svc_extension.inter_layer_slice_beta_offset_div2,
+                    63,
+                );
+            }
+        }
+
+        sh.svc_extension.constrained_intra_resampling_flag = bs.read_bits(1)? == 1;
+        decoder_formatted_print(
+            "SH: constrained_intra_resampling_flag",
+            sh.svc_extension.constrained_intra_resampling_flag,
+            63,
+        );
+
+        if subset_sps.sps_svc.extended_spatial_scalability_idc == 2 {
+            if vp.chroma_array_type > 0 {
+                sh.svc_extension.ref_layer_chroma_phase_x_plus1_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print(
+                    "SH: ref_layer_chroma_phase_x_plus1_flag",
+                    sh.svc_extension.ref_layer_chroma_phase_x_plus1_flag,
+                    63,
+                );
+                sh.svc_extension.ref_layer_chroma_phase_y_plus1 = bs.read_bits(2)? as u8;
+                decoder_formatted_print(
+                    "SH: ref_layer_chroma_phase_y_plus1",
+                    sh.svc_extension.ref_layer_chroma_phase_y_plus1,
+                    63,
+                );
+            }
+            sh.svc_extension.scaled_ref_layer_left_offset =
+                exp_golomb_decode_one_wrapper(bs, true, 0)?;
+            decoder_formatted_print(
+                "SH: scaled_ref_layer_left_offset",
+                sh.svc_extension.scaled_ref_layer_left_offset,
+                63,
+            );
+            sh.svc_extension.scaled_ref_layer_top_offset =
+                exp_golomb_decode_one_wrapper(bs, true, 0)?;
+            decoder_formatted_print(
+                "SH: scaled_ref_layer_top_offset",
+                sh.svc_extension.scaled_ref_layer_top_offset,
+                63,
+            );
+            sh.svc_extension.scaled_ref_layer_right_offset =
+                exp_golomb_decode_one_wrapper(bs, true, 0)?;
+            decoder_formatted_print(
+                "SH: scaled_ref_layer_right_offset",
+                sh.svc_extension.scaled_ref_layer_right_offset,
+                63,
+            );
+            sh.svc_extension.scaled_ref_layer_bottom_offset =
+                exp_golomb_decode_one_wrapper(bs, true, 0)?;
+            decoder_formatted_print(
+                "SH: scaled_ref_layer_bottom_offset",
+                sh.svc_extension.scaled_ref_layer_bottom_offset,
+                63,
+            );
+        }
+    }
+
+    if !sh.svc_extension.no_inter_layer_pred_flag {
+        sh.svc_extension.slice_skip_flag = bs.read_bits(1)? == 1;
+        decoder_formatted_print("SH: slice_skip_flag", sh.svc_extension.slice_skip_flag, 63);
+
+        if sh.svc_extension.slice_skip_flag {
+            sh.svc_extension.num_mbs_in_slice_minus1 =
+                exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print(
+                "SH: num_mbs_in_slice_minus1",
+                sh.svc_extension.num_mbs_in_slice_minus1,
+                63,
+            );
+        } else {
+            sh.svc_extension.adaptive_base_mode_flag = bs.read_bits(1)? == 1;
+            decoder_formatted_print(
+                "SH: adaptive_base_mode_flag",
+                sh.svc_extension.adaptive_base_mode_flag,
+                63,
+            );
+            if !sh.svc_extension.adaptive_base_mode_flag {
+                sh.svc_extension.default_base_mode_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print(
+                    "SH: default_base_mode_flag",
+                    sh.svc_extension.default_base_mode_flag,
+                    63,
+                );
+            }
+            if !sh.svc_extension.default_base_mode_flag {
+                sh.svc_extension.adaptive_motion_prediction_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print(
+                    "SH: adaptive_motion_prediction_flag",
+                    sh.svc_extension.adaptive_motion_prediction_flag,
+                    63,
+                );
+                if !sh.svc_extension.adaptive_motion_prediction_flag {
+                    sh.svc_extension.default_motion_prediction_flag = bs.read_bits(1)? == 1;
+                    decoder_formatted_print(
+                        "SH: default_motion_prediction_flag",
+                        sh.svc_extension.default_motion_prediction_flag,
+                        63,
+                    );
+                }
+            }
+            sh.svc_extension.adaptive_residual_prediction_flag = bs.read_bits(1)? == 1;
+            decoder_formatted_print(
+                "SH: adaptive_residual_prediction_flag",
+                sh.svc_extension.adaptive_residual_prediction_flag,
+                63,
+            );
+            if !sh.svc_extension.adaptive_residual_prediction_flag {
+                sh.svc_extension.default_residual_prediction_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print(
+                    "SH: default_residual_prediction_flag",
+                    sh.svc_extension.default_residual_prediction_flag,
+                    63,
+                );
+            }
+        }
+
+        if subset_sps.sps_svc.adaptive_tcoeff_level_prediction_flag {
+            sh.svc_extension.tcoeff_level_prediction_flag = bs.read_bits(1)? == 1;
+            decoder_formatted_print(
+                "SH: tcoeff_level_prediction_flag",
+                sh.svc_extension.tcoeff_level_prediction_flag,
+                63,
+            );
+        }
+    }
+
+    if !subset_sps.sps_svc.slice_header_restriction_flag && !sh.svc_extension.slice_skip_flag {
+        sh.svc_extension.scan_idx_start = bs.read_bits(4)? as u8;
+        decoder_formatted_print("SH: scan_idx_start", sh.svc_extension.scan_idx_start, 63);
+        sh.svc_extension.scan_idx_end = bs.read_bits(4)? as u8;
+        decoder_formatted_print("SH: scan_idx_end", sh.svc_extension.scan_idx_end, 63);
+    }
+
+    sh.svc_extension.set_scaled_ref_layer_offsets(subset_sps);
+    set_slice_header_derived_variables(&mut sh, s, p, &mut vp);
+
+    Ok((sh, pps_idx, sps_idx, vp))
+}
+
+/// Follows section G.7.3.4.1
+///
+/// The slice data syntax is shared with 7.3.4; the scalable extension
+/// syntax elements are read at the macroblock layer when sh.svc_extension_flag is set
+fn decode_slice_data_in_scalable_extension(
+    bs: &mut ByteStream,
+    sh: &mut SliceHeader,
+    s: &SubsetSPS,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+    decode_strict_fmo: bool,
+) -> Result<SliceData, DecodeError> {
+    decode_slice_data(bs, sh, &s.sps, p, vp, decode_strict_fmo, None)
+}
+
+/// Follows section J.7.3.3.4
+///
+/// When slice_header_prediction_flag is set, the syntax elements that are predicted from another
+/// view component are not copied over and keep their default values.
+///
+/// Returns the slice header along with the index of the PPS and subset SPS it refers to
+fn decode_slice_header_in_3davc_extension(
+    bs: &mut ByteStream,
+    nh: &NALUheader,
+    subset_spses: &[SubsetSPS],
+    ppses: &[PicParameterSet],
+) -> Result<(SliceHeader, usize, usize, VideoParameters), DecodeError> {
+    let mut sh = SliceHeader::new();
+    sh.avc_3d_extension_flag = true;
+
+    // first_mb_in_slice
+    sh.first_mb_in_slice = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("SH: first_mb_in_slice", sh.first_mb_in_slice, 63);
+
+    // slice_type
+    sh.slice_type = exp_golomb_decode_one_wrapper(bs, false, 0)? as u8;
+    decoder_formatted_print("SH: slice_type", sh.slice_type, 63);
+
+    // pic_parameter_set_id
+    sh.pic_parameter_set_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+    decoder_formatted_print("SH: pic_parameter_set_id", sh.pic_parameter_set_id, 63);
+
+    // retrieve the corresponding PPS and subset SPS
+    let pps_idx = match (0..ppses.len())
+        .rev()
+        .find(|&i| ppses[i].pic_parameter_set_id == sh.pic_parameter_set_id)
+    {
+        Some(i) => i,
+        None => {
+            return Err(DecodeError::new(format!(
+                "decode_slice_header_in_3davc_extension - PPS with id {} not found",
+                sh.pic_parameter_set_id
+            )))
+        }
+    };
+    let p = &ppses[pps_idx];
+
+    let sps_idx = match (0..subset_spses.len())
+        .rev()
+        .find(|&i| subset_spses[i].sps.seq_parameter_set_id == p.seq_parameter_set_id)
+    {
+        Some(i) => i,
+        None => {
+            return Err(DecodeError::new(format!(
+                "decode_slice_header_in_3davc_extension - Subset SPS with id {} not found",
+                p.seq_parameter_set_id
+            )))
+        }
+    };
+    let subset_sps = &subset_spses[sps_idx];
+    let s = &subset_sps.sps;
+
+    let mut vp = VideoParameters::new(nh, p, s);
+
+    // if not overridden, the number of active references comes from the PPS
+    sh.num_ref_idx_l0_active_minus1 = p.num_ref_idx_l0_default_active_minus1;
+    sh.num_ref_idx_l1_active_minus1 = p.num_ref_idx_l1_default_active_minus1;
+
+    if subset_sps.sps_3davc.slice_header_prediction_flag {
+        sh.avc_3d_extension.pre_slice_header_src = bs.read_bits(2)? as u8;
+        decoder_formatted_print(
+            "SH: pre_slice_header_src",
+            sh.avc_3d_extension.pre_slice_header_src,
+            63,
+        );
+
+        if is_slice_type(sh.slice_type, "P")
+            || is_slice_type(sh.slice_type, "SP")
+            || is_slice_type(sh.slice_type, "B")
+        {
+            sh.avc_3d_extension.pre_ref_lists_src = bs.read_bits(2)? as u8;
+            decoder_formatted_print(
+                "SH: pre_ref_lists_src",
+                sh.avc_3d_extension.pre_ref_lists_src,
+                63,
+            );
+
+            if sh.avc_3d_extension.pre_ref_lists_src == 0 {
+                sh.num_ref_idx_active_override_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print(
+                    "SH: num_ref_idx_override_flag",
+                    sh.num_ref_idx_active_override_flag,
+                    63,
+                );
+
+                if sh.num_ref_idx_active_override_flag {
+                    sh.num_ref_idx_l0_active_minus1 =
+                        exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                    decoder_formatted_print(
+                        "SH: num_ref_idx_l0_active_minus1",
+                        sh.num_ref_idx_l0_active_minus1,
+                        63,
+                    );
+                    if is_slice_type(sh.slice_type, "B") {
+                        sh.num_ref_idx_l1_active_minus1 =
+                            exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+                        decoder_formatted_print(
+                            "SH: num_ref_idx_l1_active_minus1",
+                            sh.num_ref_idx_l1_active_minus1,
+                            63,
+                        );
+                    }
+                }
+
+                ref_pic_list_mvc_modification(&mut sh, bs)?;
+            }
+        }
+
+        if (p.weighted_pred_flag
+            && (is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "SP")))
+            || (p.weighted_bipred_idc == 1 && is_slice_type(sh.slice_type, "B"))
+        {
+            sh.avc_3d_extension.pre_pred_weight_table_src = bs.read_bits(2)? as u8;
+            decoder_formatted_print(
+                "SH: pre_pred_weight_table_src",
+                sh.avc_3d_extension.pre_pred_weight_table_src,
+                63,
+            );
+            if sh.avc_3d_extension.pre_pred_weight_table_src == 0 {
+                pred_weight_table(&mut sh, bs, vp.chroma_array_type)?;
+            }
+        }
+
+        if nh.nal_ref_idc != 0 {
+            sh.avc_3d_extension.pre_dec_ref_pic_marking_src = bs.read_bits(2)? as u8;
+            decoder_formatted_print(
+                "SH: pre_dec_ref_pic_marking_src",
+                sh.avc_3d_extension.pre_dec_ref_pic_marking_src,
+                63,
+            );
+            if sh.avc_3d_extension.pre_dec_ref_pic_marking_src == 0 {
+                dec_ref_pic_marking(&mut sh, bs, vp.idr_pic_flag)?;
+            }
+        }
+
+        // slice_qp_delta
+        sh.slice_qp_delta = exp_golomb_decode_one_wrapper(bs, true, 0)?;
+        decoder_formatted_print("SH: slice_qp_delta", sh.slice_qp_delta, 63);
+    } else {
+        decode_slice_header_body(bs, nh, &mut sh, s, p, &vp)?;
+
+        if !is_slice_type(sh.slice_type, "I") && !is_slice_type(sh.slice_type, "SI") {
+            if vp.depth_flag {
+                sh.avc_3d_extension.depth_weighted_pred_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print(
+                    "SH: depth_weighted_pred_flag",
+                    sh.avc_3d_extension.depth_weighted_pred_flag,
+                    63,
+                );
+            } else {
+                sh.avc_3d_extension.dmvp_flag = bs.read_bits(1)? == 1;
+                decoder_formatted_print("SH: dmvp_flag", sh.avc_3d_extension.dmvp_flag, 63);
+
+                if subset_sps.sps_3davc.seq_view_synthesis_flag {
+                    sh.avc_3d_extension.slice_vsp_flag = bs.read_bits(1)? == 1;
+                    decoder_formatted_print(
+                        "SH: slice_vsp_flag",
+                        sh.avc_3d_extension.slice_vsp_flag,
+                        63,
+                    );
+                }
+            }
+        }
+
+        if subset_sps.sps_3davc.three_dv_acquisition_idc != 1
+            && (sh.avc_3d_extension.depth_weighted_pred_flag || sh.avc_3d_extension.dmvp_flag)
+        {
+            sh.avc_3d_extension.dps_id = exp_golomb_decode_one_wrapper(bs, false, 0)? as u32;
+            decoder_formatted_print("SH: dps_id", sh.avc_3d_extension.dps_id, 63);
+        }
+    }
+
+    set_slice_header_derived_variables(&mut sh, s, p, &mut vp);
+
+    Ok((sh, pps_idx, sps_idx, vp))
+}
+
+/// Follows section J.7.3.4.1
+///
+/// Only the macroblock syntax shared with 7.3.4 is decoded; texture view P and B slices that
+/// enable view synthesis prediction, adaptive luminance compensation, or RLE skip carry
+/// macroblock syntax elements that are not yet supported, so they return an error
+fn decode_slice_data_in_3davc_extension(
+    bs: &mut ByteStream,
+    sh: &mut SliceHeader,
+    s: &SubsetSPS,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+    decode_strict_fmo: bool,
+) -> Result<SliceData, DecodeError> {
+    if !vp.depth_flag && !is_slice_type(sh.slice_type, "I") && !is_slice_type(sh.slice_type, "SI") {
+        let tool = if sh.avc_3d_extension.slice_vsp_flag {
+            Some("view synthesis prediction (slice_vsp_flag)")
+        } else if s.sps_3davc.alc_sps_enable_flag {
+            Some("adaptive luminance compensation (alc_sps_enable_flag)")
+        } else if s.sps_3davc.enable_rle_skip_flag {
+            Some("RLE skip (enable_rle_skip_flag)")
+        } else {
+            None
+        };
+        if let Some(tool) = tool {
+            return Err(DecodeError::new(format!(
+                "decode_slice_data_in_3davc_extension - 3D-AVC texture view macroblock syntax for {} not yet supported",
+                tool
+            )));
+        }
+    }
+
+    decode_slice_data(bs, sh, &s.sps, p, vp, decode_strict_fmo, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::data_structures::H264DecodedStream;
+    use crate::common::data_structures::NALU;
+    use crate::common::test_support::round_trip;
+    use crate::common::test_support::SPS_PPS_I_P;
+    use crate::decoder::decoder::decode_bitstream_from_bytes;
+
+    #[test]
+    fn test_mb_skip_run_past_picture() {
+        // a CAVLC P slice that skips far past the end of the picture
+        let mut ds = decode_bitstream_from_bytes(SPS_PPS_I_P, false, false, false, false).unwrap();
+        ds.ppses[0].entropy_coding_mode_flag = false;
+        let mb_amount = ds.slices[1].sd.macroblock_vec.len();
+        ds.slices[1].sd.mb_skip_run = vec![0; mb_amount];
+        ds.slices[1].sd.mb_skip_run[0] = 1 << 30;
+
+        let ds = round_trip(&mut ds);
+        let sd = &ds.slices[1].sd;
+        assert_eq!(sd.mb_skip_run[0], 1 << 30);
+        assert_eq!(
+            sd.macroblock_vec.len(),
+            ds.slices[1].sh.pic_size_in_mbs as usize
+        );
+        assert!(sd
+            .macroblock_vec
+            .iter()
+            .all(|mb| mb.mb_type == MbType::PSkip));
+    }
+
+    #[test]
+    fn test_fmo_explicit_and_raster_neighbors() {
+        // Two Baseline CAVLC IDR pictures of 3x2 macroblocks, written bit by bit outside
+        // h26forge. The first has an explicit slice group map of [0, 1, 0, 0, 1, 0] and
+        // the second a raster scan map that puts the first 4 macroblocks in slice group 0.
+        // Every 4x4 luma block of macroblock mb_addr holds (mb_addr + blk_idx) % 4 trailing
+        // ones, so nC only picks the right coeff_token table if neighbors in other slice
+        // groups are unavailable and neighbors in the same slice group are found by address
+        let original = include_bytes!("../../input_vids/SPS_PPS_I_FMO_EXPLICIT_RASTER.264");
+        let mut ds = decode_bitstream_from_bytes(original, false, false, true, false).unwrap();
+        assert_eq!(ds.ppses[0].slice_group_map_type, 6);
+        assert_eq!(ds.ppses[1].slice_group_map_type, 4);
+        assert_eq!(ds.slices.len(), 4);
+        assert_eq!(ds.slices[3].sh.slice_group_change_cycle, 2);
+
+        // nC of each 4x4 luma block, following 9.2.1 with only the left and above
+        // macroblocks of the same slice available
+        let explicit_n_c: [[i8; 16]; 6] = [
+            [0, 0, 0, 2, 1, 0, 2, 2, 2, 2, 0, 2, 2, 2, 2, 2],
+            [0, 1, 1, 3, 2, 1, 1, 3, 3, 1, 1, 3, 3, 1, 1, 3],
+            [0, 2, 2, 2, 3, 2, 2, 2, 0, 2, 2, 2, 2, 2, 2, 2],
+            [2, 3, 3, 1, 1, 3, 3, 1, 1, 3, 3, 1, 1, 3, 3, 1],
+            [3, 0, 0, 2, 2, 0, 2, 2, 2, 2, 0, 2, 2, 2, 2, 2],
+            [0, 1, 1, 3, 1, 1, 1, 3, 3, 1, 1, 3, 3, 1, 1, 3],
+        ];
+        let raster_n_c: [[i8; 16]; 6] = [
+            [0, 0, 0, 2, 1, 0, 2, 2, 2, 2, 0, 2, 2, 2, 2, 2],
+            [1, 1, 2, 3, 2, 1, 1, 3, 2, 1, 2, 3, 3, 1, 1, 3],
+            [2, 2, 1, 2, 3, 2, 2, 2, 1, 2, 1, 2, 2, 2, 2, 2],
+            [2, 3, 3, 1, 1, 3, 3, 1, 1, 3, 3, 1, 1, 3, 3, 1],
+            [0, 0, 0, 2, 1, 0, 2, 2, 2, 2, 0, 2, 2, 2, 2, 2],
+            [1, 1, 2, 3, 2, 1, 1, 3, 2, 1, 2, 3, 3, 1, 1, 3],
+        ];
+        let mb_addrs = [vec![0, 2, 3, 5], vec![1, 4], vec![0, 1, 2, 3], vec![4, 5]];
+        for (i, slice) in ds.slices.iter().enumerate() {
+            let n_c = if i < 2 { &explicit_n_c } else { &raster_n_c };
+            let addrs: Vec<usize> = slice.sd.macroblock_vec.iter().map(|x| x.mb_addr).collect();
+            assert_eq!(addrs, mb_addrs[i]);
+            for mb in slice.sd.macroblock_vec.iter() {
+                assert_eq!(mb.mb_type, MbType::INxN);
+                assert_eq!(mb.coded_block_pattern, 15);
+                for (blk_idx, blk) in mb.luma_level_4x4_transform_blocks.iter().enumerate() {
+                    let total_coeff = (mb.mb_addr + blk_idx) % 4;
+                    assert_eq!(blk.coeff_token.n_c, n_c[mb.mb_addr][blk_idx]);
+                    assert_eq!(blk.coeff_token.total_coeff, total_coeff);
+                    assert_eq!(blk.coeff_token.trailing_ones, total_coeff);
+                    assert_eq!(blk.total_zeros, 0);
+                    let mut level = vec![0; 16];
+                    level[..total_coeff].fill(1);
+                    assert_eq!(mb.luma_level_4x4[blk_idx], level);
+                }
+            }
+        }
+
+        let (encoded, _, _) = crate::encoder::encoder::reencode_syntax_elements(
+            &mut ds, -1, -1, -1, false, true, false,
+        );
+        assert!(encoded == original);
+    }
+
+    #[test]
+    fn test_auxiliary_picture_without_sps_extension() {
+        // turn the P slice into an auxiliary coded picture; the stream has no SPS extension
+        let mut bytes = SPS_PPS_I_P.to_vec();
+        let p_slice = bytes.len() - 8;
+        assert_eq!(bytes[p_slice], 0x41);
+        bytes[p_slice] = 0x53;
+
+        let Err(e) = decode_bitstream_from_bytes(&bytes, false, false, false, false) else {
+            panic!("auxiliary picture without SPS extension should not decode");
+        };
+        assert_eq!(e.nalu_idx, 3);
+        assert!(e
+            .message
+            .starts_with("set_auxiliary_picture - SPS extension with id 0 not found"));
+
+        let ds = decode_bitstream_from_bytes(&bytes, false, false, false, true).unwrap();
+        assert_eq!(ds.nalu_headers[3].nal_unit_type, 19);
+        assert_eq!(ds.undecoded_nalus, vec![3]);
+        assert_eq!(ds.slices.len(), 1);
+    }
+
+    #[test]
+    fn test_3davc_texture_tools_return_error() {
+        let mut nh = NALUheader::new();
+        nh.nal_unit_type = 21;
+        nh.avc_3d_extension_flag = true;
+        let mut s = SubsetSPS::new();
+        s.sps_3davc.enable_rle_skip_flag = true;
+        let p = PicParameterSet::new();
+        let vp = VideoParameters::new(&nh, &p, &s.sps);
+        assert!(!vp.depth_flag);
+        let mut sh = SliceHeader::new();
+        sh.slice_type = 5; // P
+
+        let mut bs = ByteStream::new(vec![0xff]);
+        let e =
+            decode_slice_data_in_3davc_extension(&mut bs, &mut sh, &s, &p, &vp, false).unwrap_err();
+        assert_eq!(
+            e.message,
+            "decode_slice_data_in_3davc_extension - 3D-AVC texture view macroblock syntax for RLE skip (enable_rle_skip_flag) not yet supported"
+        );
+    }
+
+    #[test]
+    fn test_slice_data_partitions_round_trip() {
+        let mut ds = decode_bitstream_from_bytes(SPS_PPS_I_P, false, false, false, false).unwrap();
+        assert_eq!(ds.nalu_headers[3].nal_unit_type, 1);
+
+        // split the P slice into partitions A, B, and C
+        ds.nalu_headers[3].nal_unit_type = 2;
+        for nal_unit_type in [2, 3, 4] {
+            if nal_unit_type != 2 {
+                let mut nh = ds.nalu_headers[3].clone();
+                nh.nal_unit_type = nal_unit_type;
+                ds.nalu_headers.push(nh);
+                ds.nalu_elements.push(NALU::new());
+            }
+            let mut sdp = SliceDataPartition::new();
+            sdp.slice_id = 7;
+            ds.slice_partitions.push(sdp);
+        }
+
+        let decoded = round_trip(&mut ds);
+
+        let types: Vec<u8> = decoded
+            .nalu_headers
+            .iter()
+            .map(|nh| nh.nal_unit_type)
+            .collect();
+        assert_eq!(types, vec![7, 8, 5, 2, 3, 4]);
+        assert_eq!(decoded.slices.len(), 2);
+        assert_eq!(decoded.slice_partitions.len(), 3);
+        assert!(decoded.slice_partitions.iter().all(|p| p.slice_id == 7));
+        assert_eq!(
+            decoded.slices[1].sd.macroblock_vec.len(),
+            ds.slices[1].sd.macroblock_vec.len()
+        );
+        for (a, b) in decoded.slices[1]
+            .sd
+            .macroblock_vec
+            .iter()
+            .zip(ds.slices[1].sd.macroblock_vec.iter())
+        {
+            assert_eq!(a.coded_block_pattern, b.coded_block_pattern);
+            assert_eq!(a.mb_skip_flag, b.mb_skip_flag);
+        }
+    }
+
+    #[test]
+    fn test_fmo_round_trip() {
+        // a Baseline CAVLC IDR picture with a foreground box slice group. The macroblock
+        // above a background macroblock is not one row earlier in its slice, so nC is
+        // only right if neighbors are found by address
+        let original = include_bytes!("../../input_vids/SPS_PPS_I_FMO.264");
+        let mut ds = decode_bitstream_from_bytes(original, false, false, true, false).unwrap();
+        assert_eq!(ds.ppses[0].num_slice_groups_minus1, 1);
+        assert_eq!(ds.ppses[0].slice_group_map_type, 2);
+        assert_eq!(ds.slices.len(), 2);
+        let num_mbs: Vec<usize> = ds
+            .slices
+            .iter()
+            .map(|x| x.sd.macroblock_vec.len())
+            .collect();
+        assert_eq!(num_mbs, [6, 18]);
+
+        let (encoded, _, _) = crate::encoder::encoder::reencode_syntax_elements(
+            &mut ds, -1, -1, -1, false, true, false,
+        );
+        assert!(encoded == original);
+    }
+
+    /// Generates an SVC enhancement layer slice with the entropy coder chosen by
+    /// entropy_coding_mode_flag and checks that it round-trips. When slice_type is
+    /// given, every generated slice is of that type
+    fn svc_slice_round_trip(entropy_coding_mode_flag: bool, slice_type: Option<u32>) {
+        use crate::vidgen::film::FilmState;
+        use crate::vidgen::generate_configurations::RandomBoolRange;
+        use crate::vidgen::generate_configurations::RandomU32Enum;
+        use crate::vidgen::generate_configurations::RandomU32Range;
+        use crate::vidgen::generate_configurations::RandomizeConfig;
+        use crate::vidgen::slice::random_slice_layer_extension;
+
+        let mut ds = decode_bitstream_from_bytes(SPS_PPS_I_P, false, false, false, false).unwrap();
+
+        // a subset SPS and PPS for the enhancement layer
+        let mut subset_sps = SubsetSPS::new();
+        subset_sps.sps = ds.spses[0].clone();
+        subset_sps.sps.profile_idc = 83;
+        subset_sps.sps.seq_parameter_set_id = 1;
+        ds.subset_spses.push(subset_sps);
+        let mut pps = ds.ppses[0].clone();
+        pps.pic_parameter_set_id = 1;
+        pps.seq_parameter_set_id = 1;
+        pps.is_subset_pps = true;
+        pps.entropy_coding_mode_flag = entropy_coding_mode_flag;
+        ds.ppses.push(pps);
+
+        for nal_unit_type in [15, 8, 20] {
+            let mut nh = NALUheader::new();
+            nh.nal_ref_idc = 1;
+            nh.nal_unit_type = nal_unit_type;
+            ds.nalu_headers.push(nh);
+            ds.nalu_elements.push(NALU::new());
+        }
+        let nalu_idx = ds.nalu_headers.len() - 1;
+        ds.nalu_headers[nalu_idx].svc_extension_flag = true;
+        ds.nalu_headers[nalu_idx].svc_extension.dependency_id = 1;
+
+        let mut rconfig = RandomizeConfig::new();
+        rconfig
+            .random_slice_header_range
+            .bias_zero_first_mb_in_slice = RandomBoolRange::new(1, 1, 1);
+        rconfig.random_mb_range.mb_skip_run = RandomU32Range::new(0, 0);
+        if let Some(slice_type) = slice_type {
+            rconfig.random_slice_header_range.bias_i_slice = RandomBoolRange::new(0, 0, 1);
+            rconfig.random_slice_header_range.slice_type = RandomU32Enum::new(vec![slice_type]);
+        }
+        rconfig.random_slice_svc_extension_range.slice_skip_flag = RandomBoolRange::new(0, 0, 2);
+        rconfig
+            .random_slice_svc_extension_range
+            .adaptive_base_mode_flag = RandomBoolRange::new(1, 1, 1);
+        rconfig
+            .random_slice_svc_extension_range
+            .adaptive_motion_prediction_flag = RandomBoolRange::new(1, 1, 1);
+        rconfig
+            .random_slice_svc_extension_range
+            .adaptive_residual_prediction_flag = RandomBoolRange::new(1, 1, 1);
+
+        let slice_idx = ds.slices.len();
+        ds.slices.push(Slice::new());
+        let mb_amount = ((ds.spses[0].pic_width_in_mbs_minus1 + 1)
+            * (ds.spses[0].pic_height_in_map_units_minus1 + 1)) as usize;
+        ds.slices[slice_idx].sd.macroblock_vec = vec![MacroBlock::new(); mb_amount];
+        let mut film = FilmState::setup_film_from_seed(8);
+        random_slice_layer_extension(
+            nalu_idx, slice_idx, 1, 0, true, true, true, false, true, true, &rconfig, &mut ds,
+            &mut film,
+        );
+
+        let decoded = round_trip(&mut ds);
+
+        assert_eq!(decoded.nalu_headers[nalu_idx].nal_unit_type, 20);
+        let (a, b) = (&decoded.slices[slice_idx], &ds.slices[slice_idx]);
+        assert!(a.sh.svc_extension_flag);
+        assert_eq!(a.sh.slice_type, b.sh.slice_type);
+        assert_eq!(
+            a.sh.svc_extension.ref_layer_dq_id,
+            b.sh.svc_extension.ref_layer_dq_id
+        );
+        assert_eq!(
+            a.sh.svc_extension.store_ref_base_pic_flag,
+            b.sh.svc_extension.store_ref_base_pic_flag
+        );
+        assert_eq!(
+            a.sh.svc_extension.memory_management_base_control_operation,
+            b.sh.svc_extension.memory_management_base_control_operation
+        );
+        assert_eq!(a.sd.macroblock_vec.len(), b.sd.macroblock_vec.len());
+        for (x, y) in a.sd.macroblock_vec.iter().zip(b.sd.macroblock_vec.iter()) {
+            assert_eq!(x.base_mode_flag, y.base_mode_flag);
+            assert_eq!(x.mb_type, y.mb_type);
+            assert_eq!(x.motion_prediction_flag_l0, y.motion_prediction_flag_l0);
+            assert_eq!(x.motion_prediction_flag_l1, y.motion_prediction_flag_l1);
+            assert_eq!(x.residual_prediction_flag, y.residual_prediction_flag);
+            assert_eq!(x.coded_block_pattern, y.coded_block_pattern);
+        }
+        assert!(a.sd.macroblock_vec.iter().any(|mb| mb.base_mode_flag));
+        assert!(a.sd.macroblock_vec.iter().any(|mb| !mb.base_mode_flag));
+        if slice_type.is_some() {
+            assert!(a
+                .sd
+                .macroblock_vec
+                .iter()
+                .any(|mb| mb.residual_prediction_flag));
+        }
+    }
+
+    #[test]
+    fn test_svc_slice_round_trip() {
+        svc_slice_round_trip(false, None);
+    }
+
+    #[test]
+    fn test_svc_cabac_slice_round_trip() {
+        // an EP slice exercises the base_mode_flag, motion_prediction_flag and
+        // residual_prediction_flag contexts
+        svc_slice_round_trip(true, Some(5));
+    }
+
+    /// SPS_PPS_I_P with a 3D-AVC subset SPS and PPS for a base view and a second view,
+    /// both with depth, and the texture of the second view only when given
+    fn avc_3d_stream(texture_view_present_flag: Vec<bool>) -> H264DecodedStream {
+        let mut ds = decode_bitstream_from_bytes(SPS_PPS_I_P, false, false, false, false).unwrap();
+
+        let mut subset_sps = SubsetSPS::new();
+        subset_sps.sps = ds.spses[0].clone();
+        subset_sps.sps.profile_idc = 139;
+        subset_sps.sps.seq_parameter_set_id = 1;
+        let mvcd = &mut subset_sps.sps_mvcd;
+        mvcd.num_views_minus1 = 1;
+        mvcd.view_id = vec![0, 1];
+        mvcd.depth_view_present_flag = vec![true, true];
+        mvcd.texture_view_present_flag = texture_view_present_flag;
+        mvcd.num_depth_views = 2;
+        mvcd.depth_view_id = vec![0, 1];
+        mvcd.num_anchor_refs_l0 = vec![0, 1];
+        mvcd.anchor_ref_l0 = vec![vec![], vec![0]];
+        mvcd.num_anchor_refs_l1 = vec![0, 0];
+        mvcd.anchor_ref_l1 = vec![vec![], vec![]];
+        mvcd.num_non_anchor_refs_l0 = vec![0, 0];
+        mvcd.non_anchor_ref_l0 = vec![vec![], vec![]];
+        mvcd.num_non_anchor_refs_l1 = vec![0, 0];
+        mvcd.non_anchor_ref_l1 = vec![vec![], vec![]];
+        mvcd.level_idc = vec![30];
+        mvcd.num_applicable_ops_minus1 = vec![0];
+        mvcd.applicable_op_temporal_id = vec![vec![0]];
+        mvcd.applicable_op_num_target_views_minus1 = vec![vec![0]];
+        mvcd.applicable_op_target_view_id = vec![vec![vec![0]]];
+        mvcd.applicable_op_depth_flag = vec![vec![vec![true]]];
+        mvcd.applicable_op_texture_flag = vec![vec![vec![true]]];
+        mvcd.applicable_op_num_texture_views_minus1 = vec![vec![0]];
+        mvcd.applicable_op_num_depth_views = vec![vec![1]];
+        let avc3d = &mut subset_sps.sps_3davc;
+        avc3d.view_id_3dv = vec![0, 1];
+        avc3d.grid_pos_num_views = 1;
+        avc3d.grid_pos_view_id = vec![1];
+        avc3d.grid_pos_x = vec![-3];
+        avc3d.grid_pos_y = vec![5];
+        avc3d.seq_view_synthesis_flag = true;
+        avc3d.num_anchor_refs_l0 = vec![0, 0];
+        avc3d.anchor_ref_l0 = vec![vec![], vec![]];
+        avc3d.num_anchor_refs_l1 = vec![0, 0];
+        avc3d.anchor_ref_l1 = vec![vec![], vec![]];
+        avc3d.num_non_anchor_refs_l0 = vec![0, 0];
+        avc3d.non_anchor_ref_l0 = vec![vec![], vec![]];
+        avc3d.num_non_anchor_refs_l1 = vec![0, 0];
+        avc3d.non_anchor_ref_l1 = vec![vec![], vec![]];
+        ds.subset_spses.push(subset_sps);
+        let mut pps = ds.ppses[0].clone();
+        pps.pic_parameter_set_id = 1;
+        pps.seq_parameter_set_id = 1;
+        pps.is_subset_pps = true;
+        ds.ppses.push(pps);
+        ds
+    }
+
+    #[test]
+    fn test_3davc_round_trip() {
+        use crate::common::data_structures::DepthParameterSet;
+        use crate::vidgen::film::FilmState;
+        use crate::vidgen::generate_configurations::RandomBoolRange;
+        use crate::vidgen::generate_configurations::RandomU32Range;
+        use crate::vidgen::generate_configurations::RandomizeConfig;
+        use crate::vidgen::parameter_sets::random_depth_parameter_set;
+        use crate::vidgen::slice::random_slice_layer_extension;
+
+        // a texture and depth base view and an unpaired depth view
+        let mut ds = avc_3d_stream(vec![true, false]);
+
+        for nal_unit_type in [15, 8, 16, 21] {
+            let mut nh = NALUheader::new();
+            nh.nal_ref_idc = 1;
+            nh.nal_unit_type = nal_unit_type;
+            ds.nalu_headers.push(nh);
+            ds.nalu_elements.push(NALU::new());
+        }
+        let nalu_idx = ds.nalu_headers.len() - 1;
+        ds.nalu_headers[nalu_idx].avc_3d_extension_flag = true;
+        ds.nalu_headers[nalu_idx].avc_3d_extension.depth_flag = true;
+        ds.nalu_headers[nalu_idx].avc_3d_extension.non_idr_flag = true;
+
+        let mut rconfig = RandomizeConfig::new();
+        rconfig
+            .random_slice_header_range
+            .bias_zero_first_mb_in_slice = RandomBoolRange::new(1, 1, 1);
+        rconfig.random_mb_range.mb_skip_run = RandomU32Range::new(0, 0);
+        rconfig
+            .random_depth_parameter_set_range
+            .depth_param_additional_extension_flag = RandomBoolRange::new(1, 1, 1);
+
+        let mut film = FilmState::setup_film_from_seed(9);
+        ds.dpses.push(DepthParameterSet::new());
+        random_depth_parameter_set(
+            0,
+            &rconfig.random_depth_parameter_set_range,
+            &mut ds,
+            &mut film,
+        );
+
+        let slice_idx = ds.slices.len();
+        ds.slices.push(Slice::new());
+        let mb_amount = ((ds.spses[0].pic_width_in_mbs_minus1 + 1)
+            * (ds.spses[0].pic_height_in_map_units_minus1 + 1)) as usize;
+        ds.slices[slice_idx].sd.macroblock_vec = vec![MacroBlock::new(); mb_amount];
+        random_slice_layer_extension(
+            nalu_idx, slice_idx, 1, 0, true, true, true, false, true, true, &rconfig, &mut ds,
+            &mut film,
+        );
+
+        let decoded = round_trip(&mut ds);
+
+        assert_eq!(
+            serde_json::to_string(&decoded.subset_spses[0].sps_mvcd).unwrap(),
+            serde_json::to_string(&ds.subset_spses[0].sps_mvcd).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_string(&decoded.subset_spses[0].sps_3davc).unwrap(),
+            serde_json::to_string(&ds.subset_spses[0].sps_3davc).unwrap()
+        );
+        let mvcd = &decoded.subset_spses[0].sps_mvcd;
+        assert_eq!(mvcd.texture_view_present_flag, vec![true, false]);
+        assert_eq!(mvcd.depth_view_id, vec![0, 1]);
+        let avc3d = &decoded.subset_spses[0].sps_3davc;
+        assert_eq!(avc3d.grid_pos_view_id, vec![1]);
+        assert_eq!(avc3d.grid_pos_x, vec![-3]);
+        assert_eq!(avc3d.grid_pos_y, vec![5]);
+        assert_eq!(decoded.dpses.len(), 1);
+        assert_eq!(
+            serde_json::to_string(&decoded.dpses[0]).unwrap(),
+            serde_json::to_string(&ds.dpses[0]).unwrap()
+        );
+
+        assert_eq!(decoded.nalu_headers[nalu_idx].nal_unit_type, 21);
+        let (a, b) = (&decoded.slices[slice_idx], &ds.slices[slice_idx]);
+        assert!(a.sh.avc_3d_extension_flag);
+        assert_eq!(a.sh.slice_type, b.sh.slice_type);
+        assert_eq!(a.sh.slice_qp_delta, b.sh.slice_qp_delta);
+        assert_eq!(
+            serde_json::to_string(&a.sh.avc_3d_extension).unwrap(),
+            serde_json::to_string(&b.sh.avc_3d_extension).unwrap()
+        );
+        assert_eq!(a.sd.macroblock_vec.len(), b.sd.macroblock_vec.len());
+        for (x, y) in a.sd.macroblock_vec.iter().zip(b.sd.macroblock_vec.iter()) {
+            assert_eq!(x.mb_type, y.mb_type);
+            assert_eq!(x.coded_block_pattern, y.coded_block_pattern);
+        }
+    }
+
+    #[test]
+    fn test_3davc_texture_and_depth_round_trip() {
+        use crate::common::data_structures::DepthParameterSet;
+        use crate::vidgen::film::FilmState;
+        use crate::vidgen::generate_configurations::RandomBoolRange;
+        use crate::vidgen::generate_configurations::RandomU32Enum;
+        use crate::vidgen::generate_configurations::RandomU32Range;
+        use crate::vidgen::generate_configurations::RandomizeConfig;
+        use crate::vidgen::parameter_sets::random_depth_parameter_set;
+        use crate::vidgen::slice::random_slice_layer_extension;
+
+        // the texture and depth of a second view, with view synthesis enabled in the SPS
+        let mut ds = avc_3d_stream(vec![true, true]);
+        assert!(ds.subset_spses[0].sps_3davc.seq_view_synthesis_flag);
+
+        let mut rconfig = RandomizeConfig::new();
+        rconfig
+            .random_slice_header_range
+            .bias_zero_first_mb_in_slice = RandomBoolRange::new(1, 1, 1);
+        rconfig.random_slice_header_range.slice_type = RandomU32Enum::new(vec![0]);
+        rconfig.random_mb_range.mb_skip_run = RandomU32Range::new(0, 0);
+
+        // the texture view tools come from the default ranges
+        let mut film = FilmState::setup_film_from_seed(3);
+        let sps_3davc_range = &rconfig.random_subset_sps_range.random_sps_3davc_range;
+        let avc3d = &mut ds.subset_spses[0].sps_3davc;
+        avc3d.alc_sps_enable_flag = sps_3davc_range.alc_sps_enable_flag.sample(&mut film);
+        avc3d.enable_rle_skip_flag = sps_3davc_range.enable_rle_skip_flag.sample(&mut film);
+
+        ds.dpses.push(DepthParameterSet::new());
+        random_depth_parameter_set(
+            0,
+            &rconfig.random_depth_parameter_set_range,
+            &mut ds,
+            &mut film,
+        );
+
+        for (nal_unit_type, depth_flag) in [(15, false), (8, false), (21, false), (21, true)] {
+            let mut nh = NALUheader::new();
+            nh.nal_ref_idc = 1;
+            nh.nal_unit_type = nal_unit_type;
+            if nal_unit_type == 21 {
+                nh.avc_3d_extension_flag = true;
+                nh.avc_3d_extension.view_idx = 1;
+                nh.avc_3d_extension.depth_flag = depth_flag;
+                nh.avc_3d_extension.non_idr_flag = true;
+            }
+            ds.nalu_headers.push(nh);
+            ds.nalu_elements.push(NALU::new());
+        }
+
+        let texture_nalu_idx = ds.nalu_headers.len() - 2;
+        let texture_slice_idx = ds.slices.len();
+        let mb_amount = ((ds.spses[0].pic_width_in_mbs_minus1 + 1)
+            * (ds.spses[0].pic_height_in_map_units_minus1 + 1)) as usize;
+        for i in 0..2 {
+            ds.slices.push(Slice::new());
+            ds.slices[texture_slice_idx + i].sd.macroblock_vec = vec![MacroBlock::new(); mb_amount];
+            random_slice_layer_extension(
+                texture_nalu_idx + i,
+                texture_slice_idx + i,
+                1,
+                0,
+                true,
+                true,
+                true,
+                false,
+                true,
+                true,
+                &rconfig,
+                &mut ds,
+                &mut film,
+            );
+        }
+
+        let decoded = round_trip(&mut ds);
+
+        let avc3d = &decoded.subset_spses[0].sps_3davc;
+        assert!(!avc3d.alc_sps_enable_flag);
+        assert!(!avc3d.enable_rle_skip_flag);
+        for i in 0..2 {
+            let (a, b) = (
+                &decoded.slices[texture_slice_idx + i],
+                &ds.slices[texture_slice_idx + i],
+            );
+            assert_eq!(
+                decoded.nalu_headers[texture_nalu_idx + i]
+                    .avc_3d_extension
+                    .depth_flag,
+                i == 1
+            );
+            assert_eq!(a.sh.slice_type, 0);
+            assert!(!a.sh.avc_3d_extension.slice_vsp_flag);
+            assert_eq!(
+                a.sh.avc_3d_extension.depth_weighted_pred_flag,
+                b.sh.avc_3d_extension.depth_weighted_pred_flag
+            );
+            assert_eq!(
+                a.sh.avc_3d_extension.dmvp_flag,
+                b.sh.avc_3d_extension.dmvp_flag
+            );
+            assert_eq!(a.sd.macroblock_vec.len(), b.sd.macroblock_vec.len());
+            for (x, y) in a.sd.macroblock_vec.iter().zip(b.sd.macroblock_vec.iter()) {
+                assert_eq!(x.mb_type, y.mb_type);
+                assert_eq!(x.coded_block_pattern, y.coded_block_pattern);
+            }
+        }
+    }
+
+    #[test]
+    fn test_auxiliary_picture_round_trip() {
+        use crate::vidgen::film::FilmState;
+        use crate::vidgen::generate_configurations::RandomizeConfig;
+        use crate::vidgen::slice::random_slice;
+
+        let mut ds = decode_bitstream_from_bytes(SPS_PPS_I_P, false, false, false, false).unwrap();
+
+        // a 10-bit alpha plane for the primary coded pictures
+        let mut sps_ext = SPSExtension::new();
+        sps_ext.seq_parameter_set_id = ds.spses[0].seq_parameter_set_id;
+        sps_ext.aux_format_idc = 1;
+        sps_ext.bit_depth_aux_minus8 = 2;
+        sps_ext.alpha_opaque_value = 1023;
+        ds.sps_extensions.push(sps_ext);
+
+        for nal_unit_type in [13, 19] {
+            let mut nh = NALUheader::new();
+            nh.nal_ref_idc = 1;
+            nh.nal_unit_type = nal_unit_type;
+            ds.nalu_headers.push(nh);
+            ds.nalu_elements.push(NALU::new());
+        }
+        let nalu_idx = ds.nalu_headers.len() - 1;
+
+        let slice_idx = ds.slices.len();
+        ds.slices.push(Slice::new());
+        let mb_amount = ((ds.spses[0].pic_width_in_mbs_minus1 + 1)
+            * (ds.spses[0].pic_height_in_map_units_minus1 + 1)) as usize;
+        ds.slices[slice_idx].sd.macroblock_vec = vec![MacroBlock::new(); mb_amount];
+        let sps = ds.spses[0].clone();
+        let pps = ds.ppses[0].clone();
+        let rconfig = RandomizeConfig::new();
+        let mut film = FilmState::setup_film_from_seed(10);
+        random_slice(
+            nalu_idx, slice_idx, &pps, &sps, true, true, false, false, true, true, &rconfig,
+            &mut ds, &mut film,
+        );
+
+        let decoded = round_trip(&mut ds);
+
+        assert_eq!(decoded.sps_extensions.len(), 1);
+        assert_eq!(decoded.sps_extensions[0].bit_depth_aux_minus8, 2);
+        assert_eq!(decoded.sps_extensions[0].alpha_opaque_value, 1023);
+        assert_eq!(decoded.nalu_headers[nalu_idx].nal_unit_type, 19);
+        let (a, b) = (&decoded.slices[slice_idx], &ds.slices[slice_idx]);
+        assert_eq!(a.sh.slice_type, b.sh.slice_type);
+        assert_eq!(a.sd.macroblock_vec.len(), b.sd.macroblock_vec.len());
+        for (x, y) in a.sd.macroblock_vec.iter().zip(b.sd.macroblock_vec.iter()) {
+            assert_eq!(x.mb_type, y.mb_type);
+            assert_eq!(x.coded_block_pattern, y.coded_block_pattern);
+            // auxiliary coded pictures are monochrome
+            assert!(x.coded_block_pattern < 16);
+            assert_eq!(x.pcm_sample_luma, y.pcm_sample_luma);
+        }
+    }
 }
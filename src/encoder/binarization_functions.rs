@@ -646,8 +646,7 @@ pub fn generate_uegk(num: i32, ucoff: u32, k: usize, _signed_val_flag: bool) ->
 }
 
 /// Specified in clause 9.3.2.6
-// TODO: pass in VP or just ChromaArrayType for CAVLC 422/444
-pub fn generate_coded_block_pattern_value(num: u32) -> Vec<u8> {
+pub fn generate_coded_block_pattern_value(num: u32, chroma_array_type: u8) -> Vec<u8> {
     let mut res: Vec<u8> = Vec::new();
 
     // consists of a prefix part and (when present) a suffix part
@@ -661,8 +660,9 @@ pub fn generate_coded_block_pattern_value(num: u32) -> Vec<u8> {
     // reverse it because the bits are read from lsb to msb
     res.reverse();
 
-    // TODO: adjust for other ChromaArrayType for CAVLC 422/444
-    res.append(&mut generate_truncated_unary_value((num & 0x30) >> 4, 2)); // next 2 are chroma
+    if chroma_array_type != 0 && chroma_array_type != 3 {
+        res.append(&mut generate_truncated_unary_value((num & 0x30) >> 4, 2)); // next 2 are chroma
+    }
 
     res
 }
@@ -827,7 +827,27 @@ mod tests {
         ];
 
         for t in test_cases.iter() {
-            let r = generate_coded_block_pattern_value(t.0);
+            let r = generate_coded_block_pattern_value(t.0, 1);
+            assert_eq!(r, t.1);
+            let r = generate_coded_block_pattern_value(t.0, 2);
+            assert_eq!(r, t.1);
+        }
+    }
+
+    #[test]
+    fn test_generate_coded_block_pattern_value_chroma_array_type_0_or_3() {
+        // only the luma prefix is present
+        let test_cases = [
+            (0, vec![0u8, 0u8, 0u8, 0u8]),
+            (1, vec![1u8, 0u8, 0u8, 0u8]),
+            (12, vec![0u8, 0u8, 1u8, 1u8]),
+            (15, vec![1u8, 1u8, 1u8, 1u8]),
+        ];
+
+        for t in test_cases.iter() {
+            let r = generate_coded_block_pattern_value(t.0, 0);
+            assert_eq!(r, t.1);
+            let r = generate_coded_block_pattern_value(t.0, 3);
             assert_eq!(r, t.1);
         }
     }
@@ -817,6 +817,153 @@ pub fn cabac_encode_transform_size_8x8_flag(
     }
 }
 
+/// Macroblock Data - base_mode_flag
+pub fn cabac_encode_base_mode_flag(
+    se_val: bool,
+    sh: &SliceHeader,
+    stream: &mut Vec<u8>,
+    cs: &mut CABACState,
+    neighbor_info: (MacroBlock, MacroBlock),
+) {
+    // binarization is fixed length of 1
+    let binarized = match se_val {
+        true => 1u8,
+        false => 0u8,
+    };
+    if CABAC_DEBUG {
+        debug!(target: "encode","\tcabac_encode_base_mode_flag - Se_val is {:?} and the binarized value is {:?}", se_val, binarized);
+    } else {
+        encoder_formatted_print("base_mode_flag", se_val, 63);
+    }
+    // cabac max_bin_idx_ctx = 0; ctx_idx_offset = 1024
+
+    let mut ctx_idx = 1024;
+
+    // more specific model selection following G.9.3.3.1.1
+    let mut cond_term_flag_a: usize = 1;
+    let mut cond_term_flag_b: usize = 1;
+
+    let mb_a: MacroBlock = neighbor_info.0;
+    let mb_b: MacroBlock = neighbor_info.1;
+
+    if !mb_a.available || mb_a.base_mode_flag {
+        cond_term_flag_a = 0;
+    }
+
+    if !mb_b.available || mb_b.base_mode_flag {
+        cond_term_flag_b = 0;
+    }
+
+    ctx_idx += cond_term_flag_a + cond_term_flag_b;
+
+    // binary encode with model
+
+    let mut idx1 = sh.cabac_init_idc as usize;
+    let idx2 = clip3(0, 51, sh.slice_qp_y) as usize;
+
+    if !is_slice_type(sh.slice_type, "I") && !is_slice_type(sh.slice_type, "SI") {
+        idx1 += 1;
+    }
+
+    if CABAC_DEBUG {
+        debug!(target: "encode","cod_i_offset: {:x}", cs.cod_i_offset << 7);
+        debug!(target: "encode","state: {:x}", cs.states[idx1][idx2][ctx_idx].p_state_idx);
+    }
+    stream.append(&mut arithmetic_encode(&binarized, cs, idx1, idx2, ctx_idx));
+
+    if CABAC_DEBUG {
+        debug!(target: "encode",
+            "updated state: {:x}",
+            cs.states[idx1][idx2][ctx_idx].p_state_idx
+        );
+    }
+}
+
+/// Macroblock Prediction - motion_prediction_flag_l0 and motion_prediction_flag_l1
+pub fn cabac_encode_motion_prediction_flag(
+    se_val: bool,
+    list_idx: usize,
+    sh: &SliceHeader,
+    stream: &mut Vec<u8>,
+    cs: &mut CABACState,
+) {
+    // binarization is fixed length of 1
+    let binarized = match se_val {
+        true => 1u8,
+        false => 0u8,
+    };
+    if CABAC_DEBUG {
+        debug!(target: "encode","\tcabac_encode_motion_prediction_flag_l{} - Se_val is {:?} and the binarized value is {:?}", list_idx, se_val, binarized);
+    } else if list_idx == 0 {
+        encoder_formatted_print("motion_prediction_flag_l0", se_val, 63);
+    } else {
+        encoder_formatted_print("motion_prediction_flag_l1", se_val, 63);
+    }
+
+    // cabac max_bin_idx_ctx = 0; ctx_idx_offset = 1027 for l0 and 1028 for l1
+    let ctx_idx = 1027 + list_idx;
+
+    // only present in EP and EB slices
+    let idx1 = sh.cabac_init_idc as usize + 1;
+    let idx2 = clip3(0, 51, sh.slice_qp_y) as usize;
+
+    if CABAC_DEBUG {
+        debug!(target: "encode","cod_i_offset: {:x}", cs.cod_i_offset << 7);
+        debug!(target: "encode","state: {:x}", cs.states[idx1][idx2][ctx_idx].p_state_idx);
+    }
+    stream.append(&mut arithmetic_encode(&binarized, cs, idx1, idx2, ctx_idx));
+
+    if CABAC_DEBUG {
+        debug!(target: "encode",
+            "updated state: {:x}",
+            cs.states[idx1][idx2][ctx_idx].p_state_idx
+        );
+    }
+}
+
+/// Macroblock Data - residual_prediction_flag
+pub fn cabac_encode_residual_prediction_flag(
+    se_val: bool,
+    base_mode_flag: bool,
+    sh: &SliceHeader,
+    stream: &mut Vec<u8>,
+    cs: &mut CABACState,
+) {
+    // binarization is fixed length of 1
+    let binarized = match se_val {
+        true => 1u8,
+        false => 0u8,
+    };
+    if CABAC_DEBUG {
+        debug!(target: "encode","\tcabac_encode_residual_prediction_flag - Se_val is {:?} and the binarized value is {:?}", se_val, binarized);
+    } else {
+        encoder_formatted_print("residual_prediction_flag", se_val, 63);
+    }
+
+    // cabac max_bin_idx_ctx = 0; ctx_idx_offset = 1029
+    let ctx_idx = match base_mode_flag {
+        true => 1029,
+        false => 1030,
+    };
+
+    // only present in EP and EB slices
+    let idx1 = sh.cabac_init_idc as usize + 1;
+    let idx2 = clip3(0, 51, sh.slice_qp_y) as usize;
+
+    if CABAC_DEBUG {
+        debug!(target: "encode","cod_i_offset: {:x}", cs.cod_i_offset << 7);
+        debug!(target: "encode","state: {:x}", cs.states[idx1][idx2][ctx_idx].p_state_idx);
+    }
+    stream.append(&mut arithmetic_encode(&binarized, cs, idx1, idx2, ctx_idx));
+
+    if CABAC_DEBUG {
+        debug!(target: "encode",
+            "updated state: {:x}",
+            cs.states[idx1][idx2][ctx_idx].p_state_idx
+        );
+    }
+}
+
 /// Macroblock Data - coded_block_pattern
 pub fn cabac_encode_coded_block_pattern(
     se_val: u32,
@@ -829,7 +976,7 @@ pub fn cabac_encode_coded_block_pattern(
 ) {
     // binarization specified in clause 9.3.2.6
 
-    let binarized = generate_coded_block_pattern_value(se_val);
+    let binarized = generate_coded_block_pattern_value(se_val, vp.chroma_array_type);
     if CABAC_DEBUG {
         debug!(target: "encode","\tcabac_encode_coded_block_pattern - Se_val is {:?} and the binarized value is {:?}", se_val, binarized);
     } else {
@@ -4,7 +4,6 @@ use crate::common::cavlc_tables::ENCODE_MAPPED_EXP_GOLOMB_CAT03;
 use crate::common::cavlc_tables::ENCODE_MAPPED_EXP_GOLOMB_CAT12;
 use crate::common::data_structures::CoeffToken;
 use crate::common::data_structures::MacroBlock;
-use crate::common::data_structures::MbPartPredMode;
 use crate::common::data_structures::MbType;
 use crate::common::data_structures::ResidualMode;
 use crate::common::data_structures::SliceData;
@@ -398,55 +397,29 @@ pub fn cavlc_encode_coeff_token(
                 blk_idx_a = res.2;
                 blk_idx_b = res.3;
 
-                if mb_a.available && (mb_a.coded_block_pattern_luma >> (blk_idx_a >> 2)) & 1 != 0 {
-                    if mb_a.mb_part_pred_mode(0) == MbPartPredMode::Intra16x16 {
-                        blk_a = mb_a.intra_16x16_ac_level_transform_blocks[blk_idx_a].clone();
-                    } else {
-                        blk_a = mb_a.luma_level_4x4_transform_blocks[blk_idx_a].clone();
-                    }
-                } else {
-                    blk_a = TransformBlock::new();
-                }
-
-                if mb_b.available && (mb_b.coded_block_pattern_luma >> (blk_idx_b >> 2)) & 1 != 0 {
-                    if mb_b.mb_part_pred_mode(0) == MbPartPredMode::Intra16x16 {
-                        blk_b = mb_b.intra_16x16_ac_level_transform_blocks[blk_idx_b].clone();
-                    } else {
-                        blk_b = mb_b.luma_level_4x4_transform_blocks[blk_idx_b].clone();
-                    }
-                } else {
-                    blk_b = TransformBlock::new();
-                }
-
                 ac_mode = 0;
             }
             ResidualMode::CbIntra16x16DCLevel
             | ResidualMode::CbIntra16x16ACLevel
             | ResidualMode::CbLevel4x4 => {
-                let _res = sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp);
-                //mb_a = res.0;
-                //mb_b = res.1;
-                //blk_idx_a = res.2;
-                //blk_idx_b = res.3;
-
-                // TODO: CAVLC 422/444
-                panic!("sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp); - no TransformBlock yet");
+                let res = sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp);
+                mb_a = res.0;
+                mb_b = res.1;
+                blk_idx_a = res.2;
+                blk_idx_b = res.3;
 
-                //ac_mode = 1;
+                ac_mode = 1;
             }
             ResidualMode::CrIntra16x16DCLevel
             | ResidualMode::CrIntra16x16ACLevel
             | ResidualMode::CrLevel4x4 => {
-                let _res = sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp);
-                //mb_a = res.0;
-                //mb_b = res.1;
-                //blk_idx_a = res.2;
-                //blk_idx_b = res.3;
-
-                // TODO: CAVLC 422/444
-                panic!("sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp); - no TransformBlock yet");
+                let res = sd.get_neighbor_4x4_cr_cb_blocks_info(curr_mb_idx, blk_idx, vp);
+                mb_a = res.0;
+                mb_b = res.1;
+                blk_idx_a = res.2;
+                blk_idx_b = res.3;
 
-                //ac_mode = 2;
+                ac_mode = 2;
             }
             ResidualMode::ChromaACLevel => {
                 let res = sd.get_neighbor_4x4_chroma_block(curr_mb_idx, blk_idx, vp);
@@ -472,6 +445,17 @@ pub fn cavlc_encode_coeff_token(
             }
             _ => (),
         }
+
+        // Luma, and Cb and Cr when ChromaArrayType is 3, share the same 4x4 block layout
+        if ac_mode < 3 {
+            if mb_a.available {
+                blk_a = mb_a.get_4x4_transform_block(ac_mode, blk_idx_a);
+            }
+            if mb_b.available {
+                blk_b = mb_b.get_4x4_transform_block(ac_mode, blk_idx_b);
+            }
+        }
+
         if CAVLC_DEBUG {
             debug!(target: "encode","blk_idx_a {}, blk_idx_b {}", blk_idx_a, blk_idx_b);
         }
@@ -4915,3 +4899,130 @@ pub fn cavlc_encode_run_before(se_val: usize, zeros_left: i32, stream: &mut Vec<
 
     stream.append(&mut encoded);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::cavlc_tables::create_coeff_token_mappings;
+    use crate::common::cavlc_tables::create_total_zeros_mappings;
+    use crate::common::data_structures::H264DecodedStream;
+    use crate::common::data_structures::NALUheader;
+    use crate::common::data_structures::PicParameterSet;
+    use crate::common::data_structures::SeqParameterSet;
+    use crate::decoder::decoder::decode_bitstream_from_bytes;
+    use crate::encoder::encoder::reencode_syntax_elements;
+
+    fn bits_to_string(bits: &[u8]) -> String {
+        bits.iter().map(|b| b.to_string()).collect()
+    }
+
+    #[test]
+    fn test_cavlc_encode_coeff_token_chroma_dc_422() {
+        // 4:2:2 Chroma DC uses the nC == -2 column of Table 9-5
+        let mut sps = SeqParameterSet::new();
+        sps.chroma_format_idc = 2;
+        let vp = VideoParameters::new(&NALUheader::new(), &PicParameterSet::new(), &sps);
+        let sd = SliceData::new();
+
+        for (key, (trailing_ones, total_coeff)) in create_coeff_token_mappings(-2) {
+            let coeff_token = CoeffToken {
+                total_coeff,
+                trailing_ones,
+                n_c: 0,
+            };
+            let mut stream = Vec::new();
+            cavlc_encode_coeff_token(
+                &coeff_token,
+                0,
+                0,
+                ResidualMode::ChromaDCLevel,
+                0,
+                &sd,
+                &vp,
+                &mut stream,
+            );
+            assert_eq!(bits_to_string(&stream), key);
+        }
+    }
+
+    #[test]
+    fn test_cavlc_encode_total_zeros_chroma_dc_422() {
+        // 4:2:2 Chroma DC uses Table 9-9 (b)
+        for tz_vlc_index in 1..8 {
+            for (key, total_zeros) in create_total_zeros_mappings(8, tz_vlc_index) {
+                let mut stream = Vec::new();
+                cavlc_encode_total_zeros(total_zeros, 8, tz_vlc_index, &mut stream);
+                assert_eq!(bits_to_string(&stream), key);
+            }
+        }
+    }
+
+    /// Decode a 2x2 macroblock IDR picture of I_NxN macroblocks and check that it
+    /// re-encodes to the same bytes. The streams were written bit by bit outside
+    /// h26forge, with (mb_addr + blk_idx + i) % 4 trailing ones in each 4x4 block, where
+    /// i is 0 for luma, 1 for Cb and 2 for Cr, so a wrong nC misparses the stream
+    fn assert_cavlc_round_trip(original: &[u8], chroma_format_idc: u8) -> H264DecodedStream {
+        let mut ds = decode_bitstream_from_bytes(original, false, false, false, false).unwrap();
+        assert_eq!(ds.spses[0].chroma_format_idc, chroma_format_idc);
+        assert!(!ds.ppses[0].entropy_coding_mode_flag);
+        assert_eq!(ds.slices[0].sd.macroblock_vec.len(), 4);
+
+        let total_coeff = |blocks: &[TransformBlock], mb_addr: usize, i: usize| {
+            for (blk_idx, blk) in blocks.iter().enumerate() {
+                assert_eq!(blk.coeff_token.total_coeff, (mb_addr + blk_idx + i) % 4);
+                assert_eq!(blk.coeff_token.trailing_ones, (mb_addr + blk_idx + i) % 4);
+            }
+        };
+        for mb in ds.slices[0].sd.macroblock_vec.iter() {
+            total_coeff(&mb.luma_level_4x4_transform_blocks, mb.mb_addr, 0);
+            if chroma_format_idc == 3 {
+                total_coeff(&mb.cb_level_4x4_transform_blocks, mb.mb_addr, 1);
+                total_coeff(&mb.cr_level_4x4_transform_blocks, mb.mb_addr, 2);
+            }
+        }
+
+        let (encoded, _, _) = reencode_syntax_elements(&mut ds, -1, -1, -1, false, true, false);
+        assert!(encoded == original);
+        ds
+    }
+
+    #[test]
+    fn test_cavlc_422_round_trip() {
+        let ds = assert_cavlc_round_trip(
+            include_bytes!("../../input_vids/SPS_PPS_I_CAVLC_422.264"),
+            2,
+        );
+
+        for mb in ds.slices[0].sd.macroblock_vec.iter() {
+            assert_eq!(mb.coded_block_pattern, 47);
+            for i_cb_cr in 0..2 {
+                // 8 coefficients of Chroma DC and 8 4x4 blocks of Chroma AC per component
+                let dc = &mb.chroma_dc_level_transform_blocks[i_cb_cr];
+                assert_eq!(dc.coeff_token.n_c, -2);
+                assert_eq!(dc.coeff_token.total_coeff, (mb.mb_addr + i_cb_cr) % 4);
+                let ac = &mb.chroma_ac_level_transform_blocks[i_cb_cr];
+                assert_eq!(ac.len(), 8);
+                for (blk_idx, blk) in ac.iter().enumerate() {
+                    assert_eq!(
+                        blk.coeff_token.total_coeff,
+                        (mb.mb_addr + blk_idx + i_cb_cr + 1) % 4
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_cavlc_444_round_trip() {
+        let ds = assert_cavlc_round_trip(
+            include_bytes!("../../input_vids/SPS_PPS_I_CAVLC_444.264"),
+            3,
+        );
+
+        for mb in ds.slices[0].sd.macroblock_vec.iter() {
+            assert_eq!(mb.coded_block_pattern, 15);
+            assert_eq!(mb.cb_level_4x4_transform_blocks.len(), 16);
+            assert_eq!(mb.cr_level_4x4_transform_blocks.len(), 16);
+        }
+    }
+}
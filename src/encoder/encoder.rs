@@ -7,14 +7,17 @@ use crate::common::data_structures::SeqParameterSet;
 use crate::common::data_structures::SubsetSPS;
 use crate::common::data_structures::VideoParameters;
 use crate::encoder::nalu::encode_access_unit_delimiter;
+use crate::encoder::nalu::encode_filler_data;
 use crate::encoder::nalu::encode_nalu_header;
 use crate::encoder::nalu::encode_prefix_nal_unit_svc;
+use crate::encoder::parameter_sets::encode_depth_parameter_set;
 use crate::encoder::parameter_sets::encode_pps;
 use crate::encoder::parameter_sets::encode_sps;
 use crate::encoder::parameter_sets::encode_sps_extension;
 use crate::encoder::parameter_sets::encode_subset_sps;
 use crate::encoder::sei::encode_sei_message;
 use crate::encoder::slice::encode_slice;
+use crate::encoder::slice::encode_slice_data_partitions;
 use crate::encoder::slice::encode_slice_layer_extension_rbsp;
 use hex;
 use log::debug;
@@ -1083,9 +1086,15 @@ pub fn reencode_syntax_elements(
     let mut pps_idx = 0;
     let mut prefix_nalu_idx = 0;
     let mut slice_idx = 0;
+    let mut slice_partition_idx = 0;
     let mut sei_idx = 0;
     let mut aud_idx = 0;
+    let mut filler_data_idx = 0;
+    let mut dps_idx = 0;
     let mut saved_dupe_nalu: Vec<u8> = Vec::new();
+    // partitions B and C are encoded along with partition A
+    let mut encoded_partition_b: Option<Vec<u8>> = None;
+    let mut encoded_partition_c: Option<Vec<u8>> = None;
 
     // AVCC encoding elements
     let mut avcc_encoding = AVCCFormat::new();
@@ -1130,6 +1139,26 @@ pub fn reencode_syntax_elements(
         encoded_str.extend(encoded_header.iter());
 
         match ds.nalu_headers[i].nal_unit_type {
+            _ if ds.undecoded_nalus.contains(&i) => {
+                if !silent_mode {
+                    println!(
+                        "\t reencode_syntax_elements - NALU {} - Undecoded NALU - copying raw bytes",
+                        i
+                    );
+                }
+                // the header may not have decoded either, so copy it raw as well
+                let res = insert_emulation_three_byte(&ds.nalu_elements[i].content);
+                encoded_str.truncate(encoded_str.len() - encoded_header.len());
+                encoded_str.extend(res.clone());
+
+                if rtp_out {
+                    curr_nal = res.clone();
+                }
+
+                if avcc_out {
+                    avcc_encoding.nalus.push(res);
+                }
+            }
             0 => {
                 if !silent_mode {
                     println!("\t reencode_syntax_elements - NALU {} - Unknown nal_unit_type of 0 - not affecting encoding process", i);
@@ -1232,61 +1261,135 @@ pub fn reencode_syntax_elements(
                         i
                     );
                 }
-                // TODO: Coded slice data partition A encoding. For now, just append nalu elements
-                encoded_str.extend(&ds.nalu_elements[i].content[1..]);
 
-                if rtp_out {
-                    curr_nal.extend(&ds.nalu_elements[i].content[1..]);
-                }
+                // partitions B and C belong to this slice if they directly follow it
+                let has_b = i + 1 < ds.nalu_headers.len()
+                    && ds.nalu_headers[i + 1].nal_unit_type == 3
+                    && !ds.undecoded_nalus.contains(&(i + 1));
+                let c_nalu_idx = if has_b { i + 2 } else { i + 1 };
+                let has_c = c_nalu_idx < ds.nalu_headers.len()
+                    && ds.nalu_headers[c_nalu_idx].nal_unit_type == 4
+                    && !ds.undecoded_nalus.contains(&c_nalu_idx);
 
-                if avcc_out {
-                    let mut cur_encoded_slice = encoded_header.clone();
-                    cur_encoded_slice.extend(&ds.nalu_elements[i].content[1..]);
+                let associated_pps_id = ds.slices[slice_idx].sh.pic_parameter_set_id;
 
-                    avcc_encoding.nalus.push(cur_encoded_slice);
-                }
-            }
-            3 => {
-                if !silent_mode {
-                    println!(
-                        "\t reencode_syntax_elements - NALU {} - Coded slice data partition B",
-                        i
-                    );
+                let mut cur_pps_wrapper: Option<&PicParameterSet> = None;
+                // retrieve the corresponding PPS
+                // we search in reverse to get the most recent; ID collision is possible with random video generation
+                // we use pps_idx to ensure only already encoded PPS's are used
+                for i in (0..pps_idx).rev() {
+                    if ds.ppses[i].pic_parameter_set_id == associated_pps_id {
+                        cur_pps_wrapper = Some(&ds.ppses[i]);
+                        break;
+                    }
                 }
-                // TODO: Coded slice data partition B encoding. For now, just append nalu elements
-                encoded_str.extend(&ds.nalu_elements[i].content[1..]);
 
-                if rtp_out {
-                    curr_nal.extend(&ds.nalu_elements[i].content[1..]);
+                let cur_pps: &PicParameterSet = match cur_pps_wrapper {
+                    Some(x) => x,
+                    _ => panic!("reencode_syntax_elements - Associated SPS not found for PPS - associated_sps_idx : {}", associated_pps_id),
+                };
+
+                let associated_sps_id = cur_pps.seq_parameter_set_id;
+                let mut cur_sps_wrapper: Option<&SeqParameterSet> = None;
+
+                // we search in reverse to get the most recent; ID collision is possible with random video generation
+                // we use sps_idx to ensure only already encoded SPS's are used
+                for i in (0..sps_idx).rev() {
+                    if ds.spses[i].seq_parameter_set_id == associated_sps_id {
+                        cur_sps_wrapper = Some(&ds.spses[i]);
+                        break;
+                    }
                 }
 
+                let cur_sps: &SeqParameterSet = match cur_sps_wrapper {
+                    Some(x) => x,
+                    None => panic!("reencode_syntax_elements - Associated SPS not found for PPS - associated_sps_idx : {}", associated_sps_id),
+                };
+                let mut vp = VideoParameters::new(&ds.nalu_headers[i], cur_pps, cur_sps);
+                // for neighbor macroblock processing
+                vp.mbaff_frame_flag = ds.slices[slice_idx].sh.mbaff_frame_flag;
+
+                let sdp_a = &ds.slice_partitions[slice_partition_idx];
+                let sdp_b = if has_b {
+                    Some(&ds.slice_partitions[slice_partition_idx + 1])
+                } else {
+                    None
+                };
+                let sdp_c = if has_c {
+                    Some(&ds.slice_partitions[slice_partition_idx + 1 + has_b as usize])
+                } else {
+                    None
+                };
+
+                let (partition_a, partition_b, partition_c) = encode_slice_data_partitions(
+                    &ds.nalu_headers[i],
+                    &ds.slices[slice_idx],
+                    sdp_a,
+                    sdp_b,
+                    sdp_c,
+                    cur_sps,
+                    cur_pps,
+                    &vp,
+                    silent_mode,
+                );
+                encoded_partition_b = partition_b.map(|x| insert_emulation_three_byte(&x));
+                encoded_partition_c = partition_c.map(|x| insert_emulation_three_byte(&x));
+
+                let res = insert_emulation_three_byte(&partition_a);
+
                 if avcc_out {
                     let mut cur_encoded_slice = encoded_header.clone();
-                    cur_encoded_slice.extend(&ds.nalu_elements[i].content[1..]);
+                    cur_encoded_slice.extend(&res);
 
                     avcc_encoding.nalus.push(cur_encoded_slice);
                 }
+
+                encoded_str.extend(res.clone());
+
+                if rtp_out {
+                    curr_nal.extend(res);
+                }
+                slice_idx += 1;
+                slice_partition_idx += 1;
             }
-            4 => {
+            3 | 4 => {
+                let (name, encoded_partition) = match ds.nalu_headers[i].nal_unit_type {
+                    3 => ("B", encoded_partition_b.take()),
+                    _ => ("C", encoded_partition_c.take()),
+                };
                 if !silent_mode {
                     println!(
-                        "\t reencode_syntax_elements - NALU {} - Coded slice data partition C",
-                        i
+                        "\t reencode_syntax_elements - NALU {} - Coded slice data partition {}",
+                        i, name
                     );
                 }
-                // TODO: Coded slice data partition C encoding. For now, just append nalu elements
-                encoded_str.extend(&ds.nalu_elements[i].content[1..]);
 
-                if rtp_out {
-                    curr_nal.extend(&ds.nalu_elements[i].content[1..]);
-                }
+                let res = match encoded_partition {
+                    Some(x) => {
+                        slice_partition_idx += 1;
+                        x
+                    }
+                    None => {
+                        // not preceded by its partition A, so there is nothing to encode it from
+                        if !silent_mode {
+                            println!("\t reencode_syntax_elements - NALU {} - [WARNING] partition {} without a preceding partition A - copying raw bytes", i, name);
+                        }
+                        ds.nalu_elements[i].content.iter().skip(1).copied().collect()
+                    }
+                };
 
                 if avcc_out {
                     let mut cur_encoded_slice = encoded_header.clone();
-                    cur_encoded_slice.extend(&ds.nalu_elements[i].content[1..]);
+                    cur_encoded_slice.extend(&res);
 
                     avcc_encoding.nalus.push(cur_encoded_slice);
                 }
+
+                encoded_str.extend(res.clone());
+
+                if rtp_out {
+                    curr_nal.extend(res);
+                }
             }
             5 => {
                 if !silent_mode {
@@ -1579,26 +1682,27 @@ pub fn reencode_syntax_elements(
                 }
                 // According to 7.3.2.7 and 7.4.2.7 this is, as the name describes, filler data
                 // that should be all 0xff bytes
-                // TODO: implement 7.3.2.7
-                //filler_data_rbsp();
-                encoded_str.extend(insert_emulation_three_byte(
-                    &ds.nalu_elements[i].content[1..],
-                ));
-
-                if rtp_out {
-                    curr_nal.extend(insert_emulation_three_byte(
-                        &ds.nalu_elements[i].content[1..],
-                    ));
-                }
+                let res = if filler_data_idx < ds.filler_data.len() {
+                    filler_data_idx += 1;
+                    insert_emulation_three_byte(&encode_filler_data(
+                        &ds.filler_data[filler_data_idx - 1],
+                    ))
+                } else {
+                    // streams saved before filler data was parsed only have the raw bytes
+                    insert_emulation_three_byte(&ds.nalu_elements[i].content[1..])
+                };
 
                 if avcc_out {
                     let mut cur_encoded_slice = encoded_header.clone();
-                    cur_encoded_slice.extend(insert_emulation_three_byte(
-                        &ds.nalu_elements[i].content[1..],
-                    ));
+                    cur_encoded_slice.extend(&res);
 
                     avcc_encoding.nalus.push(cur_encoded_slice);
                 }
+                encoded_str.extend(res.clone());
+
+                if rtp_out {
+                    curr_nal.extend(res);
+                }
             }
             13 => {
                 if !silent_mode {
@@ -1684,25 +1788,21 @@ pub fn reencode_syntax_elements(
                         i
                     );
                 }
-                // TODO: depth_parameter_set_rbsp();
-                encoded_str.extend(insert_emulation_three_byte(
-                    &ds.nalu_elements[i].content[1..],
-                ));
+                let res =
+                    insert_emulation_three_byte(&encode_depth_parameter_set(&ds.dpses[dps_idx]));
+                if avcc_out {
+                    let mut cur_encoded_dps = encoded_header.clone();
+                    cur_encoded_dps.extend(&res);
 
-                if rtp_out {
-                    curr_nal.extend(insert_emulation_three_byte(
-                        &ds.nalu_elements[i].content[1..],
-                    ));
+                    avcc_encoding.nalus.push(cur_encoded_dps);
                 }
+                encoded_str.extend(res.clone());
 
-                if avcc_out {
-                    let mut cur_encoded_slice = encoded_header.clone();
-                    cur_encoded_slice.extend(insert_emulation_three_byte(
-                        &ds.nalu_elements[i].content[1..],
-                    ));
-
-                    avcc_encoding.nalus.push(cur_encoded_slice);
+                if rtp_out {
+                    curr_nal.extend(res);
                 }
+
+                dps_idx += 1;
             }
             17..=18 => {
                 if !silent_mode {
@@ -1732,32 +1832,85 @@ pub fn reencode_syntax_elements(
                 if !silent_mode {
                     println!("\t reencode_syntax_elements - NALU {} - Coded slice of an auxiliary coded picture without partitioning", i);
                 }
-                // TODO: slice_layer_without_partitioning_rbsp(); // but non-VCL
-                encoded_str.extend(insert_emulation_three_byte(
-                    &ds.nalu_elements[i].content[1..],
-                ));
 
-                if rtp_out {
-                    curr_nal.extend(insert_emulation_three_byte(
-                        &ds.nalu_elements[i].content[1..],
-                    ));
+                let associated_pps_id = ds.slices[slice_idx].sh.pic_parameter_set_id;
+
+                let mut cur_pps_wrapper: Option<&PicParameterSet> = None;
+                // retrieve the corresponding PPS
+                // we search in reverse to get the most recent; ID collision is possible with random video generation
+                // we use pps_idx to ensure only already encoded PPS's are used
+                for i in (0..pps_idx).rev() {
+                    if ds.ppses[i].pic_parameter_set_id == associated_pps_id {
+                        cur_pps_wrapper = Some(&ds.ppses[i]);
+                        break;
+                    }
                 }
 
+                let cur_pps: &PicParameterSet = match cur_pps_wrapper {
+                    Some(x) => x,
+                    _ => panic!("reencode_syntax_elements - Associated SPS not found for PPS - associated_sps_idx : {}", associated_pps_id),
+                };
+
+                // auxiliary coded pictures share the SPS of their primary coded picture
+                let associated_sps_id = cur_pps.seq_parameter_set_id;
+                let mut cur_sps_wrapper: Option<&SeqParameterSet> = None;
+                for i in (0..sps_idx).rev() {
+                    if ds.spses[i].seq_parameter_set_id == associated_sps_id {
+                        cur_sps_wrapper = Some(&ds.spses[i]);
+                        break;
+                    }
+                }
+
+                let cur_sps: &SeqParameterSet = match cur_sps_wrapper {
+                    Some(x) => x,
+                    None => panic!("reencode_syntax_elements - Associated SPS not found for PPS - associated_sps_idx : {}", associated_sps_id),
+                };
+                let mut vp = VideoParameters::new(&ds.nalu_headers[i], cur_pps, cur_sps);
+                if let Err(e) = vp.set_auxiliary_picture(
+                    &ds.sps_extensions[..sps_extension_idx],
+                    cur_sps.seq_parameter_set_id,
+                ) {
+                    println!(
+                        "\t [WARNING] {} - encoding with the primary picture parameters",
+                        e.message
+                    );
+                }
+                vp.mbaff_frame_flag = ds.slices[slice_idx].sh.mbaff_frame_flag;
+
+                let res = insert_emulation_three_byte(&encode_slice(
+                    &ds.nalu_headers[i],
+                    &ds.slices[slice_idx],
+                    cur_sps,
+                    cur_pps,
+                    &vp,
+                    silent_mode,
+                ));
+
                 if avcc_out {
                     let mut cur_encoded_slice = encoded_header.clone();
-                    cur_encoded_slice.extend(insert_emulation_three_byte(
-                        &ds.nalu_elements[i].content[1..],
-                    ));
+                    cur_encoded_slice.extend(&res);
 
                     avcc_encoding.nalus.push(cur_encoded_slice);
                 }
+
+                encoded_str.extend(res.clone());
+
+                if rtp_out {
+                    curr_nal.extend(res);
+                }
+
+                slice_idx += 1;
             }
-            20 => {
+            20 | 21 => {
                 if !silent_mode {
-                    println!(
-                        "\t reencode_syntax_elements - NALU {} - Coded slice extension",
-                        i
-                    );
+                    if ds.nalu_headers[i].nal_unit_type == 20 {
+                        println!(
+                            "\t reencode_syntax_elements - NALU {} - Coded slice extension",
+                            i
+                        );
+                    } else {
+                        println!("\t reencode_syntax_elements - NALU {} - Coded slice extension for a depth view component or a 3D-AVC texture view component", i);
+                    }
                 }
                 let associated_pps_id = ds.slices[slice_idx].sh.pic_parameter_set_id;
 
@@ -1826,31 +1979,6 @@ pub fn reencode_syntax_elements(
                 slice_idx += 1;
                 //encoded_str.extend(insert_emulation_three_byte(ds.nalu_elements[i].content[1..]));
             }
-            21 => {
-                if !silent_mode {
-                    println!("\t reencode_syntax_elements - NALU {} - Coded slice extension for a depth view component or a 3D-AVC texture view component", i);
-                }
-                // specified in Annex J
-                // slice_layer_extension_rbsp();
-                encoded_str.extend(insert_emulation_three_byte(
-                    &ds.nalu_elements[i].content[1..],
-                ));
-
-                if rtp_out {
-                    curr_nal.extend(insert_emulation_three_byte(
-                        &ds.nalu_elements[i].content[1..],
-                    ));
-                }
-
-                if avcc_out {
-                    let mut cur_encoded_slice = encoded_header.clone();
-                    cur_encoded_slice.extend(insert_emulation_three_byte(
-                        &ds.nalu_elements[i].content[1..],
-                    ));
-
-                    avcc_encoding.nalus.push(cur_encoded_slice);
-                }
-            }
             22..=23 => {
                 if !silent_mode {
                     println!("\t reencode_syntax_elements - NALU {} - RESERVED nal_unit_type of {} - Copying Bytes", i, ds.nalu_headers[i].nal_unit_type);
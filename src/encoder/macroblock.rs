@@ -11,12 +11,21 @@ use crate::common::data_structures::SubMbType;
 use crate::common::data_structures::TransformBlock;
 use crate::common::data_structures::VideoParameters;
 use crate::common::helper::encoder_formatted_print;
+use crate::common::helper::is_slice_type;
 use crate::encoder::cabac;
 use crate::encoder::cavlc;
 use log::debug;
 use std::cmp;
 
+/// Bits of slice data partitions B and C of a partitioned slice. Intra residual and
+/// PCM samples go in B, and inter residual goes in C. See Section 7.3.2.9
+pub struct ResidualPartitionBits {
+    pub b: Vec<u8>,
+    pub c: Vec<u8>,
+}
+
 /// Encode the Macroblock elements, including all residual information
+#[allow(clippy::too_many_arguments)]
 pub fn encode_macroblock(
     bitstream_array: &mut Vec<u8>,
     mb: &MacroBlock,
@@ -25,6 +34,7 @@ pub fn encode_macroblock(
     p: &PicParameterSet,
     vp: &VideoParameters,
     cs: &mut cabac::CABACState,
+    partitions: Option<&mut ResidualPartitionBits>,
 ) {
     // when decoding with mb_skip_run we'll insert empty Macroblocks in the vector for simplicity
     // we can just return if that's the case
@@ -36,18 +46,46 @@ pub fn encode_macroblock(
         return;
     }
 
-    if p.entropy_coding_mode_flag {
-        cabac::cabac_encode_mb_type(
-            mb.mb_type,
-            &s.sh,
-            bitstream_array,
-            cs,
-            s.sd.get_neighbor(mb.mb_idx, false, vp),
-        );
-    } else {
-        cavlc::cavlc_encode_mb_type(mb.mb_type, s.sh.slice_type, bitstream_array);
+    // base_mode_flag - G.7.3.6
+    let in_crop_window = s.sh.in_crop_window(mb.mb_addr, vp);
+    if s.sh.svc_extension_flag && in_crop_window && s.sh.svc_extension.adaptive_base_mode_flag {
+        if p.entropy_coding_mode_flag {
+            cabac::cabac_encode_base_mode_flag(
+                mb.base_mode_flag,
+                &s.sh,
+                bitstream_array,
+                cs,
+                s.sd.get_neighbor(mb.mb_idx, false, vp),
+            );
+        } else {
+            bitstream_array.push(match mb.base_mode_flag {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print("base_mode_flag", mb.base_mode_flag, 63);
+        }
+    }
+
+    if !mb.base_mode_flag {
+        if p.entropy_coding_mode_flag {
+            cabac::cabac_encode_mb_type(
+                mb.mb_type,
+                &s.sh,
+                bitstream_array,
+                cs,
+                s.sd.get_neighbor(mb.mb_idx, false, vp),
+            );
+        } else {
+            cavlc::cavlc_encode_mb_type(mb.mb_type, s.sh.slice_type, bitstream_array);
+        }
     }
     if mb.mb_type == MbType::IPCM {
+        // PCM samples are in partition B when the slice is partitioned
+        let bitstream_array = match partitions {
+            Some(rp) => &mut rp.b,
+            None => bitstream_array,
+        };
+
         // Need to align to byte first
         while bitstream_array.len() % 8 != 0 {
             debug!(target: "encode","IPCM aligning zero bit");
@@ -88,7 +126,9 @@ pub fn encode_macroblock(
             cs.bits_outstanding = 0;
         }
     } else {
-        if mb.mb_type != MbType::INxN
+        if mb.base_mode_flag {
+            // prediction data is inferred from the reference layer
+        } else if mb.mb_type != MbType::INxN
             && mb.mb_part_pred_mode(0) != MbPartPredMode::Intra16x16
             && mb.num_mb_part() == 4
         {
@@ -118,7 +158,47 @@ pub fn encode_macroblock(
             bitstream_array.append(&mut encode_mb_pred(mb, s, vp, cs));
         }
 
-        if mb.mb_part_pred_mode(0) != MbPartPredMode::Intra16x16 {
+        if s.sh.svc_extension_flag
+            && s.sh.svc_extension.adaptive_residual_prediction_flag
+            && !is_slice_type(s.sh.slice_type, "I")
+            && in_crop_window
+            && (mb.base_mode_flag || !mb.is_intra_non_mut())
+        {
+            if p.entropy_coding_mode_flag {
+                cabac::cabac_encode_residual_prediction_flag(
+                    mb.residual_prediction_flag,
+                    mb.base_mode_flag,
+                    &s.sh,
+                    bitstream_array,
+                    cs,
+                );
+            } else {
+                bitstream_array.push(match mb.residual_prediction_flag {
+                    true => 1,
+                    false => 0,
+                });
+                encoder_formatted_print(
+                    "residual_prediction_flag",
+                    mb.residual_prediction_flag,
+                    63,
+                );
+            }
+        }
+
+        // an empty scan range means no residual syntax elements are present
+        let (scan_idx_start, scan_idx_end) = if s.sh.svc_extension_flag {
+            (
+                s.sh.svc_extension.scan_idx_start as usize,
+                s.sh.svc_extension.scan_idx_end as usize,
+            )
+        } else {
+            (0, 15)
+        };
+        if scan_idx_end < scan_idx_start {
+            return;
+        }
+
+        if mb.base_mode_flag || mb.mb_part_pred_mode(0) != MbPartPredMode::Intra16x16 {
             if p.entropy_coding_mode_flag {
                 cabac::cabac_encode_coded_block_pattern(
                     mb.coded_block_pattern,
@@ -143,9 +223,10 @@ pub fn encode_macroblock(
 
             if mb.coded_block_pattern_luma > 0
                 && p.transform_8x8_mode_flag
-                && mb.mb_type != MbType::INxN
-                && mb.no_sub_mb_part_size_less_than_8x8_flag
-                && (mb.mb_type != MbType::BDirect16x16 || sps.direct_8x8_inference_flag)
+                && (mb.base_mode_flag
+                    || (mb.mb_type != MbType::INxN
+                        && mb.no_sub_mb_part_size_less_than_8x8_flag
+                        && (mb.mb_type != MbType::BDirect16x16 || sps.direct_8x8_inference_flag)))
             {
                 if p.entropy_coding_mode_flag {
                     cabac::cabac_encode_transform_size_8x8_flag(
@@ -182,11 +263,88 @@ pub fn encode_macroblock(
             } else {
                 cavlc::cavlc_encode_mb_qp_delta(mb.mb_qp_delta, bitstream_array);
             }
-            bitstream_array.append(&mut encode_residual(mb, vp, 0, 15, s, p, cs));
+            let bitstream_array = match partitions {
+                Some(rp) if mb.is_intra_non_mut() => &mut rp.b,
+                Some(rp) => &mut rp.c,
+                None => bitstream_array,
+            };
+            bitstream_array.append(&mut encode_residual(
+                mb,
+                vp,
+                scan_idx_start,
+                scan_idx_end,
+                s,
+                p,
+                cs,
+            ));
         }
     }
 }
 
+/// Encode motion_prediction_flag_l0 and motion_prediction_flag_l1 following G.7.3.6.1 and G.7.3.6.2
+///
+/// pred_modes holds the prediction mode of each macroblock or sub-macroblock partition
+fn encode_motion_prediction_flags(
+    mb: &MacroBlock,
+    pred_modes: &[MbPartPredMode],
+    s: &Slice,
+    vp: &VideoParameters,
+    cs: &mut cabac::CABACState,
+) -> Vec<u8> {
+    let mut bitstream_array: Vec<u8> = Vec::new();
+
+    if s.sh.in_crop_window(mb.mb_addr, vp) && s.sh.svc_extension.adaptive_motion_prediction_flag {
+        for (mb_part_idx, pred_mode) in pred_modes.iter().enumerate() {
+            if *pred_mode != MbPartPredMode::PredL1 && *pred_mode != MbPartPredMode::Direct {
+                if vp.entropy_coding_mode_flag {
+                    cabac::cabac_encode_motion_prediction_flag(
+                        mb.motion_prediction_flag_l0[mb_part_idx],
+                        0,
+                        &s.sh,
+                        &mut bitstream_array,
+                        cs,
+                    );
+                } else {
+                    bitstream_array.push(match mb.motion_prediction_flag_l0[mb_part_idx] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "motion_prediction_flag_l0",
+                        mb.motion_prediction_flag_l0[mb_part_idx],
+                        63,
+                    );
+                }
+            }
+        }
+        for (mb_part_idx, pred_mode) in pred_modes.iter().enumerate() {
+            if *pred_mode != MbPartPredMode::PredL0 && *pred_mode != MbPartPredMode::Direct {
+                if vp.entropy_coding_mode_flag {
+                    cabac::cabac_encode_motion_prediction_flag(
+                        mb.motion_prediction_flag_l1[mb_part_idx],
+                        1,
+                        &s.sh,
+                        &mut bitstream_array,
+                        cs,
+                    );
+                } else {
+                    bitstream_array.push(match mb.motion_prediction_flag_l1[mb_part_idx] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "motion_prediction_flag_l1",
+                        mb.motion_prediction_flag_l1[mb_part_idx],
+                        63,
+                    );
+                }
+            }
+        }
+    }
+
+    bitstream_array
+}
+
 /// Encode MB prediction values
 fn encode_mb_pred(
     mb: &MacroBlock,
@@ -287,10 +445,23 @@ fn encode_mb_pred(
             }
         }
     } else if mppm != MbPartPredMode::Direct {
+        if s.sh.svc_extension_flag {
+            let pred_modes: Vec<MbPartPredMode> = (0..mb.num_mb_part())
+                .map(|mb_part_idx| mb.mb_part_pred_mode(mb_part_idx))
+                .collect();
+            bitstream_array.append(&mut encode_motion_prediction_flags(
+                mb,
+                &pred_modes,
+                s,
+                vp,
+                cs,
+            ));
+        }
         for mb_part_idx in 0..mb.num_mb_part() {
             if (s.sh.num_ref_idx_l0_active_minus1 > 0
                 || s.sd.mb_field_decoding_flag[mb.mb_idx] != s.sh.field_pic_flag)
                 && mb.mb_part_pred_mode(mb_part_idx) != MbPartPredMode::PredL1
+                && !mb.motion_prediction_flag_l0[mb_part_idx]
             {
                 if vp.entropy_coding_mode_flag {
                     cabac::cabac_encode_ref_idx(
@@ -325,6 +496,7 @@ fn encode_mb_pred(
             if (s.sh.num_ref_idx_l1_active_minus1 > 0
                 || s.sd.mb_field_decoding_flag[mb.mb_idx] != s.sh.field_pic_flag)
                 && mb.mb_part_pred_mode(mb_part_idx) != MbPartPredMode::PredL0
+                && !mb.motion_prediction_flag_l1[mb_part_idx]
             {
                 if vp.entropy_coding_mode_flag {
                     cabac::cabac_encode_ref_idx(
@@ -441,12 +613,26 @@ fn encode_sub_mb_pred(
         }
     }
 
+    if s.sh.svc_extension_flag {
+        let pred_modes: Vec<MbPartPredMode> = (0..4)
+            .map(|mb_part_idx| mb.sub_mb_part_pred_mode(mb_part_idx))
+            .collect();
+        bitstream_array.append(&mut encode_motion_prediction_flags(
+            mb,
+            &pred_modes,
+            s,
+            vp,
+            cs,
+        ));
+    }
+
     for mb_part_idx in 0..4 {
         if (s.sh.num_ref_idx_l0_active_minus1 > 0
             || s.sd.mb_field_decoding_flag[mb.mb_idx] != s.sh.field_pic_flag)
             && mb.mb_type != MbType::P8x8ref0
             && mb.sub_mb_type[mb_part_idx] != SubMbType::BDirect8x8
             && mb.sub_mb_part_pred_mode(mb_part_idx) != MbPartPredMode::PredL1
+            && !mb.motion_prediction_flag_l0[mb_part_idx]
         {
             if vp.entropy_coding_mode_flag {
                 cabac::cabac_encode_ref_idx(
@@ -482,6 +668,7 @@ fn encode_sub_mb_pred(
             || s.sd.mb_field_decoding_flag[mb.mb_idx] != s.sh.field_pic_flag)
             && mb.sub_mb_type[mb_part_idx] != SubMbType::BDirect8x8
             && mb.sub_mb_part_pred_mode(mb_part_idx) != MbPartPredMode::PredL0
+            && !mb.motion_prediction_flag_l1[mb_part_idx]
         {
             if vp.entropy_coding_mode_flag {
                 cabac::cabac_encode_ref_idx(
@@ -782,6 +969,24 @@ fn encode_residual_luma(
     cs: &mut cabac::CABACState,
 ) -> Vec<u8> {
     let mut bitstream_array: Vec<u8> = Vec::new();
+    // Cb and Cr of ChromaArrayType 3 are coded like Luma, but take CAVLC neighbors from their own component
+    let (dc_mode, ac_mode, level4x4_mode) = match ctx_block_cat_offset {
+        6 => (
+            ResidualMode::CbIntra16x16DCLevel,
+            ResidualMode::CbIntra16x16ACLevel,
+            ResidualMode::CbLevel4x4,
+        ),
+        10 => (
+            ResidualMode::CrIntra16x16DCLevel,
+            ResidualMode::CrIntra16x16ACLevel,
+            ResidualMode::CrLevel4x4,
+        ),
+        _ => (
+            ResidualMode::Intra16x16DCLevel,
+            ResidualMode::Intra16x16ACLevel,
+            ResidualMode::LumaLevel4x4,
+        ),
+    };
 
     if start_idx == 0 && mb.mb_part_pred_mode(0) == MbPartPredMode::Intra16x16 {
         debug!(target: "encode","Intra16x16 Pred Mode && DC levels");
@@ -804,7 +1009,7 @@ fn encode_residual_luma(
             bitstream_array.extend(encode_residual_block_cavlc(
                 mb,
                 i16x16_dc_level,
-                ResidualMode::Intra16x16DCLevel,
+                dc_mode,
                 0,
                 15,
                 16,
@@ -844,7 +1049,7 @@ fn encode_residual_luma(
                                 bitstream_array.extend(encode_residual_block_cavlc(
                                     mb,
                                     i16x16_ac_level[i_8x8 * 4 + i_4x4].clone(),
-                                    ResidualMode::Intra16x16ACLevel,
+                                    ac_mode,
                                     0,
                                     end_idx - 1,
                                     15,
@@ -870,7 +1075,7 @@ fn encode_residual_luma(
                             bitstream_array.extend(encode_residual_block_cavlc(
                                 mb,
                                 i16x16_ac_level[i_8x8 * 4 + i_4x4].clone(),
-                                ResidualMode::Intra16x16ACLevel,
+                                ac_mode,
                                 start_idx - 1,
                                 end_idx - 1,
                                 15,
@@ -900,7 +1105,7 @@ fn encode_residual_luma(
                             bitstream_array.extend(encode_residual_block_cavlc(
                                 mb,
                                 level_4x4[i_8x8 * 4 + i_4x4].clone(),
-                                ResidualMode::LumaLevel4x4,
+                                level4x4_mode,
                                 start_idx,
                                 end_idx,
                                 16,
@@ -1,6 +1,7 @@
 //! NALU header and extensions syntax element encoding.
 
 use crate::common::data_structures::AccessUnitDelim;
+use crate::common::data_structures::FillerData;
 use crate::common::data_structures::NALUheader;
 use crate::common::data_structures::PrefixNALU;
 use crate::common::helper::bitstream_to_bytestream;
@@ -245,3 +246,16 @@ pub fn encode_access_unit_delimiter(aud: &AccessUnitDelim) -> Vec<u8> {
 
     bitstream_to_bytestream(bitstream_array, 0)
 }
+
+/// Described in 7.3.2.7 -- Filler Data
+pub fn encode_filler_data(fd: &FillerData) -> Vec<u8> {
+    let mut bytestream_array = fd.ff_byte.clone();
+    fd.encoder_pretty_print();
+
+    // RBSP trailing bits
+    if fd.rbsp_trailing_bits {
+        bytestream_array.push(0x80);
+    }
+
+    bytestream_array
+}
@@ -1,8 +1,12 @@
 //! Parameter Set (SPS, PPS, VUI, extensions) syntax element encoding.
 
 use crate::common::data_structures::AVC3DSPSExtension;
+use crate::common::data_structures::AcquisitionElement3DV;
+use crate::common::data_structures::DepthParameterSet;
+use crate::common::data_structures::DepthRanges;
 use crate::common::data_structures::HRDParameters;
 use crate::common::data_structures::MVCDSPSExtension;
+use crate::common::data_structures::MVCDVUIParameters;
 use crate::common::data_structures::MVCSPSExtension;
 use crate::common::data_structures::MVCVUIParameters;
 use crate::common::data_structures::PicParameterSet;
@@ -11,6 +15,7 @@ use crate::common::data_structures::SVCSPSExtension;
 use crate::common::data_structures::SVCVUIParameters;
 use crate::common::data_structures::SeqParameterSet;
 use crate::common::data_structures::SubsetSPS;
+use crate::common::data_structures::VSPParam;
 use crate::common::data_structures::VUIParameters;
 use crate::common::helper::bitstream_to_bytestream;
 use crate::encoder::binarization_functions::generate_unsigned_binary;
@@ -473,7 +478,7 @@ fn encode_vui_parameters(v: &VUIParameters) -> Vec<u8> {
     bitstream_array
 }
 
-fn encode_hrd(hrd: &HRDParameters) -> Vec<u8> {
+pub fn encode_hrd(hrd: &HRDParameters) -> Vec<u8> {
     let mut bitstream_array: Vec<u8> = Vec::new();
 
     bitstream_array.append(&mut exp_golomb_encode_one(
@@ -981,15 +986,625 @@ fn encode_sps_mvc_extension(
     bitstream_array
 }
 
-fn encode_sps_mvcd_extension(_ext: &MVCDSPSExtension) -> Vec<u8> {
-    let bitstream_array = Vec::new();
-    println!("encode_sps_mvcd_extension - not yet supported");
+/// Described in I.7.3.2.1.5 -- Sequence parameter set MVCD extension syntax
+fn encode_sps_mvcd_extension(ext: &MVCDSPSExtension) -> Vec<u8> {
+    let mut bitstream_array = Vec::new();
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        ext.num_views_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+
+    for i in 0..=(ext.num_views_minus1 as usize) {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            ext.view_id[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        bitstream_array.push(match ext.depth_view_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        bitstream_array.push(match ext.texture_view_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+    }
+
+    // there are 1 to ext.num_views_minus1 values
+    for i in 1..=(ext.num_views_minus1 as usize) {
+        if ext.depth_view_present_flag[i] {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.num_anchor_refs_l0[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            for j in 0..(ext.num_anchor_refs_l0[i] as usize) {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.anchor_ref_l0[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+            }
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.num_anchor_refs_l1[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            for j in 0..(ext.num_anchor_refs_l1[i] as usize) {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.anchor_ref_l1[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+            }
+        }
+    }
+
+    for i in 1..=(ext.num_views_minus1 as usize) {
+        if ext.depth_view_present_flag[i] {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.num_non_anchor_refs_l0[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            for j in 0..(ext.num_non_anchor_refs_l0[i] as usize) {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.non_anchor_ref_l0[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+            }
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.num_non_anchor_refs_l1[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            for j in 0..(ext.num_non_anchor_refs_l1[i] as usize) {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.non_anchor_ref_l1[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+            }
+        }
+    }
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        ext.num_level_values_signalled_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    for i in 0..=(ext.num_level_values_signalled_minus1 as usize) {
+        bitstream_array.append(&mut generate_unsigned_binary(ext.level_idc[i] as u32, 8));
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            ext.num_applicable_ops_minus1[i] as i32,
+            false,
+            0,
+            false,
+        ));
+
+        for j in 0..=(ext.num_applicable_ops_minus1[i] as usize) {
+            bitstream_array.append(&mut generate_unsigned_binary(
+                ext.applicable_op_temporal_id[i][j] as u32,
+                3,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.applicable_op_num_target_views_minus1[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+
+            for k in 0..=(ext.applicable_op_num_target_views_minus1[i][j] as usize) {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.applicable_op_target_view_id[i][j][k] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                bitstream_array.push(match ext.applicable_op_depth_flag[i][j][k] {
+                    true => 1,
+                    false => 0,
+                });
+                bitstream_array.push(match ext.applicable_op_texture_flag[i][j][k] {
+                    true => 1,
+                    false => 0,
+                });
+            }
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.applicable_op_num_texture_views_minus1[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.applicable_op_num_depth_views[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+        }
+    }
+
+    bitstream_array.push(match ext.mvcd_vui_parameters_present_flag {
+        true => 1,
+        false => 0,
+    });
+    if ext.mvcd_vui_parameters_present_flag {
+        bitstream_array.append(&mut encode_vui_mvcd_parameters(&ext.mvcd_vui_parameters));
+    }
+    bitstream_array.push(match ext.texture_vui_parameters_present_flag {
+        true => 1,
+        false => 0,
+    });
+    if ext.texture_vui_parameters_present_flag {
+        bitstream_array.append(&mut encode_vui_mvc_parameters(
+            &ext.mvc_vui_parameters_extension,
+        ));
+    }
+
     bitstream_array
 }
 
-fn encode_sps_3davc_extension(_ext: &AVC3DSPSExtension) -> Vec<u8> {
-    let bitstream_array = Vec::new();
-    println!("encode_sps_3davc_extension - not yet supported");
+/// Described in J.7.3.2.1.5 -- Sequence parameter set 3D-AVC extension syntax
+fn encode_sps_3davc_extension(mvcd: &MVCDSPSExtension, ext: &AVC3DSPSExtension) -> Vec<u8> {
+    let mut bitstream_array = Vec::new();
+
+    if mvcd.num_depth_views > 0 {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            ext.three_dv_acquisition_idc as i32,
+            false,
+            0,
+            false,
+        ));
+        for i in 0..(mvcd.num_depth_views as usize) {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.view_id_3dv[i] as i32,
+                false,
+                0,
+                false,
+            ));
+        }
+
+        if ext.three_dv_acquisition_idc > 0 {
+            bitstream_array.append(&mut encode_depth_ranges(
+                mvcd.num_depth_views,
+                2,
+                &ext.depth_ranges,
+            ));
+            bitstream_array.append(&mut encode_vsp_param(mvcd.num_depth_views, &ext.vsp_param));
+        }
+
+        bitstream_array.push(match ext.reduced_resolution_flag {
+            true => 1,
+            false => 0,
+        });
+        if ext.reduced_resolution_flag {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_pic_width_in_mbs_minus1 as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_pic_height_in_map_units_minus1 as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_hor_mult_minus1 as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_ver_mult_minus1 as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_hor_rsh as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_ver_rsh as i32,
+                false,
+                0,
+                false,
+            ));
+        }
+
+        bitstream_array.push(match ext.depth_frame_cropping_flag {
+            true => 1,
+            false => 0,
+        });
+        if ext.depth_frame_cropping_flag {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_frame_crop_left_offset as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_frame_crop_right_offset as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_frame_crop_top_offset as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.depth_frame_crop_bottom_offset as i32,
+                false,
+                0,
+                false,
+            ));
+        }
+
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            ext.grid_pos_num_views as i32,
+            false,
+            0,
+            false,
+        ));
+        for i in 0..(ext.grid_pos_num_views as usize) {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.grid_pos_view_id[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.grid_pos_x[i],
+                true,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                ext.grid_pos_y[i],
+                true,
+                0,
+                false,
+            ));
+        }
+
+        bitstream_array.push(match ext.slice_header_prediction_flag {
+            true => 1,
+            false => 0,
+        });
+        bitstream_array.push(match ext.seq_view_synthesis_flag {
+            true => 1,
+            false => 0,
+        });
+    }
+
+    bitstream_array.push(match ext.alc_sps_enable_flag {
+        true => 1,
+        false => 0,
+    });
+    bitstream_array.push(match ext.enable_rle_skip_flag {
+        true => 1,
+        false => 0,
+    });
+
+    if !mvcd.all_views_paired_flag() {
+        // there are 1 to mvcd.num_views_minus1 values
+        for i in 1..=(mvcd.num_views_minus1 as usize) {
+            if mvcd.texture_view_present_flag[i] {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.num_anchor_refs_l0[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                for j in 0..(ext.num_anchor_refs_l0[i] as usize) {
+                    bitstream_array.append(&mut exp_golomb_encode_one(
+                        ext.anchor_ref_l0[i][j] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                }
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.num_anchor_refs_l1[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                for j in 0..(ext.num_anchor_refs_l1[i] as usize) {
+                    bitstream_array.append(&mut exp_golomb_encode_one(
+                        ext.anchor_ref_l1[i][j] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                }
+            }
+        }
+
+        for i in 1..=(mvcd.num_views_minus1 as usize) {
+            if mvcd.texture_view_present_flag[i] {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.num_non_anchor_refs_l0[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                for j in 0..(ext.num_non_anchor_refs_l0[i] as usize) {
+                    bitstream_array.append(&mut exp_golomb_encode_one(
+                        ext.non_anchor_ref_l0[i][j] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                }
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ext.num_non_anchor_refs_l1[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                for j in 0..(ext.num_non_anchor_refs_l1[i] as usize) {
+                    bitstream_array.append(&mut exp_golomb_encode_one(
+                        ext.non_anchor_ref_l1[i][j] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                }
+            }
+        }
+    }
+
+    bitstream_array
+}
+
+/// Described in J.7.3.2.5 -- Depth parameter set RBSP syntax
+pub fn encode_depth_parameter_set(dps: &DepthParameterSet) -> Vec<u8> {
+    let mut bitstream_array = Vec::new();
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        dps.depth_parameter_set_id as i32,
+        false,
+        0,
+        false,
+    ));
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        dps.pred_direction as i32,
+        false,
+        0,
+        false,
+    ));
+
+    if dps.pred_direction == 0 || dps.pred_direction == 1 {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            dps.ref_dps_id0 as i32,
+            false,
+            0,
+            false,
+        ));
+    }
+    if dps.pred_direction == 0 {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            dps.ref_dps_id1 as i32,
+            false,
+            0,
+            false,
+        ));
+        bitstream_array.append(&mut generate_unsigned_binary(dps.pred_weight0 as u32, 6));
+    }
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        dps.num_depth_views_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    bitstream_array.append(&mut encode_depth_ranges(
+        dps.num_depth_views_minus1 + 1,
+        dps.pred_direction,
+        &dps.depth_ranges,
+    ));
+
+    bitstream_array.push(match dps.vsp_param_flag {
+        true => 1,
+        false => 0,
+    });
+    if dps.vsp_param_flag {
+        bitstream_array.append(&mut encode_vsp_param(
+            dps.num_depth_views_minus1 + 1,
+            &dps.vsp_param,
+        ));
+    }
+
+    bitstream_array.push(match dps.depth_param_additional_extension_flag {
+        true => 1,
+        false => 0,
+    });
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        dps.nonlinear_depth_representation_num as i32,
+        false,
+        0,
+        false,
+    ));
+    for i in 0..(dps.nonlinear_depth_representation_num as usize) {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            dps.nonlinear_depth_representation_model[i] as i32,
+            false,
+            0,
+            false,
+        ));
+    }
+
+    if dps.depth_param_additional_extension_flag {
+        for i in 0..dps.depth_param_additional_extension_data_flag.len() {
+            bitstream_array.push(match dps.depth_param_additional_extension_data_flag[i] {
+                true => 1,
+                false => 0,
+            });
+        }
+    }
+
+    dps.encoder_pretty_print();
+
+    // rbsp_trailing_bits()
+    bitstream_array.push(1);
+
+    bitstream_to_bytestream(bitstream_array, 0)
+}
+
+/// Described in J.7.3.2.5.1 -- Depth ranges syntax
+fn encode_depth_ranges(num_views: u32, pred_direction: u32, dr: &DepthRanges) -> Vec<u8> {
+    let mut bitstream_array = Vec::new();
+
+    bitstream_array.push(match dr.z_near_flag {
+        true => 1,
+        false => 0,
+    });
+    bitstream_array.push(match dr.z_far_flag {
+        true => 1,
+        false => 0,
+    });
+
+    if dr.z_near_flag {
+        bitstream_array.append(&mut encode_3dv_acquisition_element(
+            num_views,
+            pred_direction,
+            7,
+            &dr.z_near,
+        ));
+    }
+    if dr.z_far_flag {
+        bitstream_array.append(&mut encode_3dv_acquisition_element(
+            num_views,
+            pred_direction,
+            7,
+            &dr.z_far,
+        ));
+    }
+
+    bitstream_array
+}
+
+/// Described in J.7.3.2.5.2 -- 3DV acquisition element syntax
+fn encode_3dv_acquisition_element(
+    num_views: u32,
+    pred_direction: u32,
+    exp_len: usize,
+    ae: &AcquisitionElement3DV,
+) -> Vec<u8> {
+    let mut bitstream_array = Vec::new();
+
+    if num_views > 1 {
+        bitstream_array.push(match ae.element_equal_flag {
+            true => 1,
+            false => 0,
+        });
+    }
+
+    for i in 0..(ae.num_values(num_views) as usize) {
+        if pred_direction == 2 && i == 0 {
+            bitstream_array.append(&mut generate_unsigned_binary(
+                ae.mantissa_len_minus1 as u32,
+                5,
+            ));
+        }
+
+        if pred_direction == 2 {
+            bitstream_array.push(match ae.sign0[i] {
+                true => 1,
+                false => 0,
+            });
+            bitstream_array.append(&mut generate_unsigned_binary(ae.exponent0[i], exp_len));
+            bitstream_array.append(&mut generate_unsigned_binary(
+                ae.mantissa0[i],
+                ae.mantissa_len_minus1 as usize + 1,
+            ));
+        } else {
+            bitstream_array.push(match ae.skip_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            if !ae.skip_flag[i] {
+                bitstream_array.push(match ae.sign1[i] {
+                    true => 1,
+                    false => 0,
+                });
+                bitstream_array.push(match ae.exponent_skip_flag[i] {
+                    true => 1,
+                    false => 0,
+                });
+                if !ae.exponent_skip_flag[i] {
+                    bitstream_array.append(&mut generate_unsigned_binary(ae.exponent1[i], exp_len));
+                }
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    ae.mantissa_diff[i],
+                    true,
+                    0,
+                    false,
+                ));
+            }
+        }
+    }
+
+    bitstream_array
+}
+
+/// Described in J.7.3.2.5.3 -- View synthesis prediction parameter syntax
+fn encode_vsp_param(num_views: u32, vsp: &VSPParam) -> Vec<u8> {
+    let mut bitstream_array = Vec::new();
+
+    for i in 0..(num_views as usize) {
+        for j in 0..i {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                vsp.disparity_diff_wji[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                vsp.disparity_diff_oji[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                vsp.disparity_diff_wij[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                vsp.disparity_diff_oij[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+        }
+    }
+
     bitstream_array
 }
 
@@ -1082,7 +1697,8 @@ pub fn encode_subset_sps(s: &SubsetSPS) -> Vec<u8> {
     } else if s.sps.profile_idc == 139 {
         bitstream_array.push(s.bit_equal_to_one);
         bitstream_array.append(&mut encode_sps_mvcd_extension(&s.sps_mvcd)); // specified in Annex I
-        bitstream_array.append(&mut encode_sps_3davc_extension(&s.sps_3davc)); // specified in Annex J
+        bitstream_array.append(&mut encode_sps_3davc_extension(&s.sps_mvcd, &s.sps_3davc));
+        // specified in Annex J
     }
 
     for i in 0..s.additional_extension2_flag.len() {
@@ -1259,3 +1875,94 @@ fn encode_vui_mvc_parameters(vui: &MVCVUIParameters) -> Vec<u8> {
     }
     bitstream_array
 }
+
+/// Described in I.14.1 -- MVCD VUI parameters extension syntax
+fn encode_vui_mvcd_parameters(vui: &MVCDVUIParameters) -> Vec<u8> {
+    let mut bitstream_array = Vec::new();
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        vui.vui_mvcd_num_ops_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+
+    for i in 0..=(vui.vui_mvcd_num_ops_minus1 as usize) {
+        bitstream_array.append(&mut generate_unsigned_binary(
+            vui.vui_mvcd_temporal_id[i] as u32,
+            3,
+        ));
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            vui.vui_mvcd_num_target_output_views_minus1[i] as i32,
+            false,
+            0,
+            false,
+        ));
+
+        for j in 0..=(vui.vui_mvcd_num_target_output_views_minus1[i] as usize) {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                vui.vui_mvcd_view_id[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+            bitstream_array.push(match vui.vui_mvcd_depth_flag[i][j] {
+                true => 1,
+                false => 0,
+            });
+            bitstream_array.push(match vui.vui_mvcd_texture_flag[i][j] {
+                true => 1,
+                false => 0,
+            });
+        }
+
+        bitstream_array.push(match vui.vui_mvcd_timing_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        if vui.vui_mvcd_timing_info_present_flag[i] {
+            bitstream_array.append(&mut generate_unsigned_binary(
+                vui.vui_mvcd_num_units_in_tick[i],
+                32,
+            ));
+            bitstream_array.append(&mut generate_unsigned_binary(
+                vui.vui_mvcd_time_scale[i],
+                32,
+            ));
+            bitstream_array.push(match vui.vui_mvcd_fixed_frame_rate_flag[i] {
+                true => 1,
+                false => 0,
+            });
+        }
+        bitstream_array.push(match vui.vui_mvcd_nal_hrd_parameters_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        if vui.vui_mvcd_nal_hrd_parameters_present_flag[i] {
+            bitstream_array.append(&mut encode_hrd(&vui.vui_mvcd_nal_hrd_parameters[i]));
+        }
+
+        bitstream_array.push(match vui.vui_mvcd_vcl_hrd_parameters_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        if vui.vui_mvcd_vcl_hrd_parameters_present_flag[i] {
+            bitstream_array.append(&mut encode_hrd(&vui.vui_mvcd_vcl_hrd_parameters[i]));
+        }
+
+        if vui.vui_mvcd_nal_hrd_parameters_present_flag[i]
+            || vui.vui_mvcd_vcl_hrd_parameters_present_flag[i]
+        {
+            bitstream_array.push(match vui.vui_mvcd_low_delay_hrd_flag[i] {
+                true => 1,
+                false => 0,
+            });
+        }
+
+        bitstream_array.push(match vui.vui_mvcd_pic_struct_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+    }
+    bitstream_array
+}
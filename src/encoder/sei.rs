@@ -1,23 +1,95 @@
 //! SEI syntax element encoding.
 
+use crate::common::data_structures::SEIAlternativeDepthInfo;
+use crate::common::data_structures::SEIAlternativeTransferCharacteristics;
 use crate::common::data_structures::SEIBufferingPeriod;
+use crate::common::data_structures::SEIColourRemappingInfo;
+use crate::common::data_structures::SEIConstrainedDepthParameterSetIdentifier;
+use crate::common::data_structures::SEIContentColourVolume;
+use crate::common::data_structures::SEIContentLightLevelInfo;
+use crate::common::data_structures::SEICubemapProjection;
+use crate::common::data_structures::SEIDecRefPicMarkingRepetition;
+use crate::common::data_structures::SEIDepthGridPosition;
+use crate::common::data_structures::SEIDepthRepresentationElement;
+use crate::common::data_structures::SEIDepthRepresentationInfo;
+use crate::common::data_structures::SEIDepthSamplingInfo;
+use crate::common::data_structures::SEIDepthTiming;
+use crate::common::data_structures::SEIDisplayOrientation;
+use crate::common::data_structures::SEIEquirectangularProjection;
+use crate::common::data_structures::SEIFillerPayload;
 use crate::common::data_structures::SEIFilmGrainCharacteristics;
+use crate::common::data_structures::SEIFloatValue;
+use crate::common::data_structures::SEIFramePacking;
+use crate::common::data_structures::SEIFullFrameFreeze;
+use crate::common::data_structures::SEIFullFrameSnapshot;
+use crate::common::data_structures::SEILayerDependencyChange;
+use crate::common::data_structures::SEILayersNotPresent;
+use crate::common::data_structures::SEIMVCScalableNesting;
+use crate::common::data_structures::SEIMasteringDisplayColourVolume;
+use crate::common::data_structures::SEIMultiviewAcquisitionInfo;
+use crate::common::data_structures::SEIMultiviewSceneInfo;
+use crate::common::data_structures::SEIMultiviewViewPosition;
 use crate::common::data_structures::SEINalu;
+use crate::common::data_structures::SEINonRequiredLayerRep;
+use crate::common::data_structures::SEINonRequiredViewComponent;
+use crate::common::data_structures::SEIOmniViewport;
+use crate::common::data_structures::SEIOperationPointsNotPresent;
+use crate::common::data_structures::SEIPanScanRect;
+use crate::common::data_structures::SEIParallelDecodingInfo;
 use crate::common::data_structures::SEIPayload;
 use crate::common::data_structures::SEIPicTiming;
+use crate::common::data_structures::SEIPriorityLayerInfo;
+use crate::common::data_structures::SEIProgressiveRefinementSegmentEnd;
+use crate::common::data_structures::SEIProgressiveRefinementSegmentStart;
+use crate::common::data_structures::SEIQualityLayerIntegrityCheck;
 use crate::common::data_structures::SEIRecoveryPoint;
+use crate::common::data_structures::SEIRedundantPicProperty;
+use crate::common::data_structures::SEIRegionwisePacking;
+use crate::common::data_structures::SEIScalabilityInfo;
+use crate::common::data_structures::SEIScalableNesting;
+use crate::common::data_structures::SEISceneInfo;
+use crate::common::data_structures::SEISparePic;
+use crate::common::data_structures::SEISphereRotation;
+use crate::common::data_structures::SEISubPicScalableLayer;
+use crate::common::data_structures::SEISubSeqCharacteristics;
+use crate::common::data_structures::SEISubSeqInfo;
+use crate::common::data_structures::SEISubSeqLayerCharacteristics;
+use crate::common::data_structures::SEITemporalHRD;
+use crate::common::data_structures::SEIThreeDimensionalReferenceDisplaysInfo;
+use crate::common::data_structures::SEITl0DepRepIndex;
+use crate::common::data_structures::SEITlSwitchingPoint;
+use crate::common::data_structures::SEIToneMappingInfo;
+use crate::common::data_structures::SEIUserDataRegisteredITUTT35;
 use crate::common::data_structures::SEIUserDataUnregistered;
+use crate::common::data_structures::SEIViewDependencyChange;
+use crate::common::data_structures::SEIViewScalabilityInfo;
 use crate::common::data_structures::SeqParameterSet;
 use crate::common::data_structures::UUID_APPLE1;
 use crate::common::data_structures::UUID_APPLE2;
 use crate::common::helper::bitstream_to_bytestream;
 use crate::common::helper::encoder_formatted_print;
+use crate::common::helper::sei_mantissa_len;
 use crate::encoder::binarization_functions::generate_unsigned_binary;
 use crate::encoder::expgolomb::exp_golomb_encode_one;
+use crate::encoder::parameter_sets::encode_hrd;
 use log::debug;
 
 /// Follows section 7.3.2.3
 pub fn encode_sei_message(sei: &SEINalu, spses: &[SeqParameterSet], silent_mode: bool) -> Vec<u8> {
+    let mut bytestream_array = encode_sei_messages(sei, spses, silent_mode);
+    if bytestream_array.is_empty() && !sei.payload.is_empty() {
+        return bytestream_array;
+    }
+
+    // add the RBSP trailing 1 bit
+    bytestream_array.push(128);
+
+    bytestream_array
+}
+
+/// Encodes each sei_message() in order, without the RBSP trailing bits. Used
+/// both for SEI NALUs and for the messages carried by nesting SEIs
+fn encode_sei_messages(sei: &SEINalu, spses: &[SeqParameterSet], silent_mode: bool) -> Vec<u8> {
     let mut bytestream_array = Vec::new();
     for i in 0..sei.payload.len() {
         if !silent_mode {
@@ -37,7 +109,9 @@ pub fn encode_sei_message(sei: &SEINalu, spses: &[SeqParameterSet], silent_mode:
                 encoded_payload_bitstream.push(0);
             }
         }
-        let encoded_payload = bitstream_to_bytestream(encoded_payload_bitstream, 0);
+        let mut encoded_payload = bitstream_to_bytestream(encoded_payload_bitstream, 0);
+        // unparsed bytes follow the modelled fields verbatim
+        encoded_payload.extend(sei.payload[i].raw_payload_byte.iter());
 
         if encoded_payload.len() == 0 && sei.payload_size[i] > 0 {
             debug!(target: "encode","[WARNING] SEI Encoded Payload is empty, likely not yet implemented");
@@ -45,31 +119,31 @@ pub fn encode_sei_message(sei: &SEINalu, spses: &[SeqParameterSet], silent_mode:
         }
 
         let mut payload_type = sei.payload_type[i];
-        while payload_type > 0xff {
+        while payload_type >= 0xff {
             bytestream_array.push(0xff);
             payload_type -= 0xff;
         }
         bytestream_array.push(payload_type as u8);
 
-        let mut payload_size = sei.payload_size[i];
-
-        // TODO: set a flag to decide whether to go with the actual size or specified size.
-        //       For now, if payload_size is 0, then we'll write it in the encoded size
-        if (encoded_payload.len() as u32) != sei.payload_size[i] && sei.payload_size[i] == 0 {
+        // a nonzero payload_size is written as given; otherwise write the actual encoded
+        // size, offset by payload_size_delta to declare a mismatched size
+        let mut payload_size = if sei.payload_size[i] > 0 {
+            sei.payload_size[i]
+        } else {
+            (encoded_payload.len() as i64 + sei.payload[i].payload_size_delta as i64).max(0) as u32
+        };
+        if payload_size as usize != encoded_payload.len() {
             if !silent_mode {
                 println!(
                     "[WARNING] SEI Payload Size {} doesn't match actual encoded size {}",
-                    sei.payload_size[i],
+                    payload_size,
                     encoded_payload.len()
                 );
             }
-            debug!(target: "encode","[WARNING] SEI Payload Size {} doesn't match actual encoded size {}", sei.payload_size[i], encoded_payload.len());
-
-            // use actual encoded size
-            payload_size = encoded_payload.len() as u32;
+            debug!(target: "encode","[WARNING] SEI Payload Size {} doesn't match actual encoded size {}", payload_size, encoded_payload.len());
         }
 
-        while payload_size > 0xff {
+        while payload_size >= 0xff {
             bytestream_array.push(0xff);
             payload_size -= 0xff;
         }
@@ -78,12 +152,21 @@ pub fn encode_sei_message(sei: &SEINalu, spses: &[SeqParameterSet], silent_mode:
         bytestream_array.append(&mut encoded_payload.clone());
     }
 
-    // add the RBSP trailing 1 bit
-    bytestream_array.push(128);
-
     bytestream_array
 }
 
+/// Nested sei_message()s start byte aligned inside the nesting SEI payload, so
+/// they are encoded as bytes and appended bit by bit
+fn encode_nested_sei_messages(sei: &SEINalu, spses: &[SeqParameterSet]) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    for b in encode_sei_messages(sei, spses, true) {
+        res.append(&mut generate_unsigned_binary(b as u32, 8));
+    }
+
+    res
+}
+
 // described in D.1.1 General SEI message syntax
 fn encode_sei_payload(
     payload_type: u32,
@@ -112,15 +195,17 @@ fn encode_sei_payload(
         }
         2 => {
             // pan scan rect
-            res.append(&mut encode_pan_scan_rect());
+            res.append(&mut encode_pan_scan_rect(&payload.pan_scan_rect));
         }
         3 => {
             // filler payload
-            res.append(&mut encode_filler_payload());
+            res.append(&mut encode_filler_payload(&payload.filler_payload));
         }
         4 => {
             // user data registered ITU T T35
-            res.append(&mut encode_user_data_registered_itu_t_t35());
+            res.append(&mut encode_user_data_registered_itu_t_t35(
+                &payload.registered_user_data,
+            ));
         }
         5 => {
             res.append(&mut encode_user_data_unregistered(
@@ -133,43 +218,61 @@ fn encode_sei_payload(
         }
         7 => {
             // pic marking repetition
-            res.append(&mut encode_ref_pic_marking_repetition());
+            let sps: SeqParameterSet = if !spses.is_empty() {
+                spses[spses.len() - 1].clone()
+            } else {
+                SeqParameterSet::new()
+            };
+            res.append(&mut encode_ref_pic_marking_repetition(
+                &payload.dec_ref_pic_marking_repetition,
+                &sps,
+            ));
         }
         8 => {
             // spare pic
-            res.append(&mut encode_spare_pic());
+            res.append(&mut encode_spare_pic(&payload.spare_pic));
         }
         9 => {
             // scene info
-            res.append(&mut encode_scene_info());
+            res.append(&mut encode_scene_info(&payload.scene_info));
         }
         10 => {
             // sub seq info
-            res.append(&mut encode_sub_seq_info());
+            res.append(&mut encode_sub_seq_info(&payload.sub_seq_info));
         }
         11 => {
             // sub seq layer characteristics
-            res.append(&mut encode_sub_seq_layer_characteristics());
+            res.append(&mut encode_sub_seq_layer_characteristics(
+                &payload.sub_seq_layer_characteristics,
+            ));
         }
         12 => {
             // sub seq characteristics
-            res.append(&mut encode_sub_seq_characteristics());
+            res.append(&mut encode_sub_seq_characteristics(
+                &payload.sub_seq_characteristics,
+            ));
         }
         13 => {
             // full_frame_freeze
-            res.append(&mut encode_full_frame_freeze());
+            res.append(&mut encode_full_frame_freeze(&payload.full_frame_freeze));
         }
         14 => {
             res.append(&mut encode_full_frame_freeze_release());
         }
         15 => {
-            res.append(&mut encode_full_frame_snapshot());
+            res.append(&mut encode_full_frame_snapshot(
+                &payload.full_frame_snapshot,
+            ));
         }
         16 => {
-            res.append(&mut encode_progressive_refinement_segment_start());
+            res.append(&mut encode_progressive_refinement_segment_start(
+                &payload.progressive_refinement_segment_start,
+            ));
         }
         17 => {
-            res.append(&mut encode_progressive_refinement_segment_end());
+            res.append(&mut encode_progressive_refinement_segment_end(
+                &payload.progressive_refinement_segment_end,
+            ));
         }
         18 => {
             res.append(&mut encode_motion_constrained_slice_group_set());
@@ -189,141 +292,208 @@ fn encode_sei_payload(
             res.append(&mut encode_post_filter_hint());
         }
         23 => {
-            res.append(&mut encode_tone_mapping_info());
+            res.append(&mut encode_tone_mapping_info(&payload.tone_mapping_info));
         }
         24 => {
-            res.append(&mut encode_scalability_info()); // specified in Annex G
+            res.append(&mut encode_scalability_info(&payload.scalability_info));
         }
         25 => {
-            res.append(&mut encode_sub_pic_scalable_layer()); // specified in Annex G
+            res.append(&mut encode_sub_pic_scalable_layer(
+                &payload.sub_pic_scalable_layer,
+            ));
         }
         26 => {
-            res.append(&mut encode_non_required_layer_rep()); // specified in Annex G
+            res.append(&mut encode_non_required_layer_rep(
+                &payload.non_required_layer_rep,
+            ));
         }
         27 => {
-            res.append(&mut encode_priority_layer_info()); // specified in Annex G
+            res.append(&mut encode_priority_layer_info(
+                &payload.priority_layer_info,
+            ));
         }
         28 => {
-            res.append(&mut encode_layers_not_present()); // specified in Annex G
+            res.append(&mut encode_layers_not_present(&payload.layers_not_present));
         }
         29 => {
-            res.append(&mut encode_layer_dependency_change()); // specified in Annex G
+            res.append(&mut encode_layer_dependency_change(
+                &payload.layer_dependency_change,
+            ));
         }
         30 => {
-            res.append(&mut encode_scalable_nesting()); // specified in Annex G
+            res.append(&mut encode_scalable_nesting(
+                &payload.scalable_nesting,
+                spses,
+            ));
         }
         31 => {
-            res.append(&mut encode_base_layer_temporal_hrd()); // specified in Annex G
+            res.append(&mut encode_temporal_hrd(&payload.base_layer_temporal_hrd));
         }
         32 => {
-            res.append(&mut encode_quality_layer_integrity_check()); // specified in Annex G
+            res.append(&mut encode_quality_layer_integrity_check(
+                &payload.quality_layer_integrity_check,
+            ));
         }
         33 => {
-            res.append(&mut encode_redundant_pic_property()); // specified in Annex G
+            res.append(&mut encode_redundant_pic_property(
+                &payload.redundant_pic_property,
+            ));
         }
         34 => {
-            res.append(&mut encode_tl0_dep_rep_index()); // specified in Annex G
+            res.append(&mut encode_tl0_dep_rep_index(&payload.tl0_dep_rep_index));
         }
         35 => {
-            res.append(&mut encode_tl_switching_point()); // specified in Annex G
+            res.append(&mut encode_tl_switching_point(&payload.tl_switching_point));
         }
         36 => {
-            res.append(&mut encode_parallel_decoding_info()); // specified in Annex H
+            res.append(&mut encode_parallel_decoding_info(
+                &payload.parallel_decoding_info,
+            ));
         }
         37 => {
-            res.append(&mut encode_mvc_scalable_nesting()); // specified in Annex H
+            res.append(&mut encode_mvc_scalable_nesting(
+                &payload.mvc_scalable_nesting,
+                false,
+                spses,
+            ));
         }
         38 => {
-            res.append(&mut encode_view_scalability_info()); // specified in Annex H
+            res.append(&mut encode_view_scalability_info(
+                &payload.view_scalability_info,
+                false,
+            ));
         }
         39 => {
-            res.append(&mut encode_multiview_scene_info()); // specified in Annex H
+            res.append(&mut encode_multiview_scene_info(
+                &payload.multiview_scene_info,
+            ));
         }
         40 => {
-            res.append(&mut encode_multiview_acquisition_info()); // specified in Annex H
+            res.append(&mut encode_multiview_acquisition_info(
+                &payload.multiview_acquisition_info,
+            ));
         }
         41 => {
-            res.append(&mut encode_non_required_view_component()); // specified in Annex H
+            res.append(&mut encode_non_required_view_component(
+                &payload.non_required_view_component,
+            ));
         }
         42 => {
-            res.append(&mut encode_view_dependency_change()); // specified in Annex H
+            res.append(&mut encode_view_dependency_change(
+                &payload.view_dependency_change,
+            ));
         }
         43 => {
-            res.append(&mut encode_operation_points_not_present()); // specified in Annex H
+            res.append(&mut encode_operation_points_not_present(
+                &payload.operation_points_not_present,
+            ));
         }
         44 => {
-            res.append(&mut encode_base_view_temporal_hrd()); // specified in Annex H
+            res.append(&mut encode_temporal_hrd(&payload.base_view_temporal_hrd));
         }
         45 => {
-            res.append(&mut encode_frame_packing_arrangement());
+            res.append(&mut encode_frame_packing_arrangement(
+                &payload.frame_packing,
+            ));
         }
         46 => {
-            res.append(&mut encode_multiview_view_position()); // specified in Annex H
+            res.append(&mut encode_multiview_view_position(
+                &payload.multiview_view_position,
+            ));
         }
         47 => {
-            res.append(&mut encode_display_orientation());
+            res.append(&mut encode_display_orientation(
+                &payload.display_orientation,
+            ));
         }
         48 => {
-            res.append(&mut encode_mvcd_scalable_nesting()); // specified in Annex I
+            res.append(&mut encode_mvc_scalable_nesting(
+                &payload.mvcd_scalable_nesting,
+                true,
+                spses,
+            ));
         }
         49 => {
-            res.append(&mut encode_mvcd_view_scalability_info()); // specified in Annex I
+            res.append(&mut encode_view_scalability_info(
+                &payload.mvcd_view_scalability_info,
+                true,
+            ));
         }
         50 => {
-            res.append(&mut encode_depth_representation_info()); // specified in Annex I
+            res.append(&mut encode_depth_representation_info(
+                &payload.depth_representation_info,
+            ));
         }
         51 => {
-            res.append(&mut encode_three_dimensional_reference_displays_info());
-            // specified in Annex I
+            res.append(&mut encode_three_dimensional_reference_displays_info(
+                &payload.three_dimensional_reference_displays_info,
+            ));
         }
         52 => {
-            res.append(&mut encode_depth_timing()); // specified in Annex I
+            res.append(&mut encode_depth_timing(&payload.depth_timing));
         }
         53 => {
-            res.append(&mut encode_depth_sampling_info()); // specified in Annex I
+            res.append(&mut encode_depth_sampling_info(
+                &payload.depth_sampling_info,
+            ));
         }
         54 => {
-            res.append(&mut encode_constrained_depth_parameter_set_identifier());
-            // specified in Annex J
+            res.append(&mut encode_constrained_depth_parameter_set_identifier(
+                &payload.constrained_depth_parameter_set_identifier,
+            ));
         }
         56 => {
             res.append(&mut encode_green_metadata()); // specified in ISO/IEC 23001-11
         }
         137 => {
-            res.append(&mut encode_mastering_display_colour_volume());
+            res.append(&mut encode_mastering_display_colour_volume(
+                &payload.mastering_display_colour_volume,
+            ));
         }
         142 => {
-            res.append(&mut encode_colour_remapping_info());
+            res.append(&mut encode_colour_remapping_info(
+                &payload.colour_remapping_info,
+            ));
         }
         144 => {
-            res.append(&mut encode_content_light_level_info());
+            res.append(&mut encode_content_light_level_info(
+                &payload.content_light_level_info,
+            ));
         }
         147 => {
-            res.append(&mut encode_alternative_transfer_characteristics());
+            res.append(&mut encode_alternative_transfer_characteristics(
+                &payload.alternative_transfer_characteristics,
+            ));
         }
         148 => {
             res.append(&mut encode_ambient_viewing_environment());
         }
         149 => {
-            res.append(&mut encode_content_colour_volume());
+            res.append(&mut encode_content_colour_volume(
+                &payload.content_colour_volume,
+            ));
         }
         150 => {
-            res.append(&mut encode_equirectangular_projection());
+            res.append(&mut encode_equirectangular_projection(
+                &payload.equirectangular_projection,
+            ));
         }
         151 => {
-            res.append(&mut encode_cubemap_projection());
+            res.append(&mut encode_cubemap_projection(&payload.cubemap_projection));
         }
         154 => {
-            res.append(&mut encode_sphere_rotation());
+            res.append(&mut encode_sphere_rotation(&payload.sphere_rotation));
         }
         155 => {
-            res.append(&mut encode_regionwise_packing());
+            res.append(&mut encode_regionwise_packing(&payload.regionwise_packing));
         }
         156 => {
-            res.append(&mut encode_omni_viewport());
+            res.append(&mut encode_omni_viewport(&payload.omni_viewport));
         }
         181 => {
-            res.append(&mut encode_alternative_depth_info()); // specified in Annex I
+            res.append(&mut encode_alternative_depth_info(
+                &payload.alternative_depth_info,
+            ));
         }
         200 => {
             res.append(&mut encode_sei_manifest());
@@ -763,22 +933,241 @@ fn encode_pic_timing(pt: &SEIPicTiming, sps: &SeqParameterSet) -> Vec<u8> {
     return bitstream_array;
 }
 
-fn encode_pan_scan_rect() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_pan_scan_rect(psr: &SEIPanScanRect) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    res.append(&mut exp_golomb_encode_one(
+        psr.pan_scan_rect_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Pan-scan rectangle): pan_scan_rect_id",
+        psr.pan_scan_rect_id,
+        63,
+    );
+    res.push(match psr.pan_scan_rect_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Pan-scan rectangle): pan_scan_rect_cancel_flag",
+        psr.pan_scan_rect_cancel_flag,
+        63,
+    );
+    if !psr.pan_scan_rect_cancel_flag {
+        res.append(&mut exp_golomb_encode_one(
+            psr.pan_scan_cnt_minus1 as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Pan-scan rectangle): pan_scan_cnt_minus1",
+            psr.pan_scan_cnt_minus1,
+            63,
+        );
+        for i in 0..psr.pan_scan_rect_left_offset.len() {
+            res.append(&mut exp_golomb_encode_one(
+                psr.pan_scan_rect_left_offset[i],
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Pan-scan rectangle): pan_scan_rect_left_offset",
+                psr.pan_scan_rect_left_offset[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                psr.pan_scan_rect_right_offset[i],
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Pan-scan rectangle): pan_scan_rect_right_offset",
+                psr.pan_scan_rect_right_offset[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                psr.pan_scan_rect_top_offset[i],
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Pan-scan rectangle): pan_scan_rect_top_offset",
+                psr.pan_scan_rect_top_offset[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                psr.pan_scan_rect_bottom_offset[i],
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Pan-scan rectangle): pan_scan_rect_bottom_offset",
+                psr.pan_scan_rect_bottom_offset[i],
+                63,
+            );
+        }
+        res.append(&mut exp_golomb_encode_one(
+            psr.pan_scan_rect_repetition_period as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Pan-scan rectangle): pan_scan_rect_repetition_period",
+            psr.pan_scan_rect_repetition_period,
+            63,
+        );
+    }
+
+    res
 }
 
-fn encode_filler_payload() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_filler_payload(fp: &SEIFillerPayload) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    for b in fp.ff_byte.iter() {
+        res.append(&mut generate_unsigned_binary(*b as u32, 8));
+    }
+    encoder_formatted_print("SEI (Filler payload): ff_byte", &fp.ff_byte, 63);
+
+    res
 }
 
-fn encode_user_data_registered_itu_t_t35() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_user_data_registered_itu_t_t35(t35: &SEIUserDataRegisteredITUTT35) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    res.append(&mut generate_unsigned_binary(
+        t35.itu_t_t35_country_code as u32,
+        8,
+    ));
+    encoder_formatted_print(
+        "SEI (ITU-T T.35 User Data): itu_t_t35_country_code",
+        t35.itu_t_t35_country_code,
+        63,
+    );
+    if t35.itu_t_t35_country_code == 0xff {
+        res.append(&mut generate_unsigned_binary(
+            t35.itu_t_t35_country_code_extension_byte as u32,
+            8,
+        ));
+        encoder_formatted_print(
+            "SEI (ITU-T T.35 User Data): itu_t_t35_country_code_extension_byte",
+            t35.itu_t_t35_country_code_extension_byte,
+            63,
+        );
+    }
+    res.append(&mut generate_unsigned_binary(
+        t35.itu_t_t35_provider_code as u32,
+        16,
+    ));
+    encoder_formatted_print(
+        "SEI (ITU-T T.35 User Data): itu_t_t35_provider_code",
+        t35.itu_t_t35_provider_code,
+        63,
+    );
+
+    if t35.is_atsc() {
+        res.append(&mut generate_unsigned_binary(t35.atsc_user_identifier, 32));
+        encoder_formatted_print(
+            "SEI (ITU-T T.35 User Data): atsc_user_identifier",
+            t35.atsc_user_identifier,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            t35.atsc_user_data_type_code as u32,
+            8,
+        ));
+        encoder_formatted_print(
+            "SEI (ITU-T T.35 User Data): atsc_user_data_type_code",
+            t35.atsc_user_data_type_code,
+            63,
+        );
+
+        if t35.has_cc_data() {
+            let cc = &t35.cc_data;
+            res.push(match cc.process_em_data_flag {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): process_em_data_flag",
+                cc.process_em_data_flag,
+                63,
+            );
+            res.push(match cc.process_cc_data_flag {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): process_cc_data_flag",
+                cc.process_cc_data_flag,
+                63,
+            );
+            res.push(match cc.additional_data_flag {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): additional_data_flag",
+                cc.additional_data_flag,
+                63,
+            );
+            // cc_count is written as is so it may disagree with the number of triplets
+            res.append(&mut generate_unsigned_binary(cc.cc_count as u32, 5));
+            encoder_formatted_print("SEI (ITU-T T.35 User Data): cc_count", cc.cc_count, 63);
+            res.append(&mut generate_unsigned_binary(cc.em_data as u32, 8));
+            encoder_formatted_print("SEI (ITU-T T.35 User Data): em_data", cc.em_data, 63);
+
+            for i in 0..cc.cc_type.len() {
+                res.append(&mut generate_unsigned_binary(
+                    cc.cc_marker_bits[i] as u32,
+                    5,
+                ));
+                res.push(match cc.cc_valid[i] {
+                    true => 1,
+                    false => 0,
+                });
+                res.append(&mut generate_unsigned_binary(cc.cc_type[i] as u32, 2));
+                res.append(&mut generate_unsigned_binary(cc.cc_data_1[i] as u32, 8));
+                res.append(&mut generate_unsigned_binary(cc.cc_data_2[i] as u32, 8));
+            }
+            encoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): cc_marker_bits",
+                &cc.cc_marker_bits,
+                63,
+            );
+            encoder_formatted_print("SEI (ITU-T T.35 User Data): cc_valid", &cc.cc_valid, 63);
+            encoder_formatted_print("SEI (ITU-T T.35 User Data): cc_type", &cc.cc_type, 63);
+            encoder_formatted_print("SEI (ITU-T T.35 User Data): cc_data_1", &cc.cc_data_1, 63);
+            encoder_formatted_print("SEI (ITU-T T.35 User Data): cc_data_2", &cc.cc_data_2, 63);
+
+            res.append(&mut generate_unsigned_binary(cc.marker_bits as u32, 8));
+            encoder_formatted_print(
+                "SEI (ITU-T T.35 User Data): marker_bits",
+                cc.marker_bits,
+                63,
+            );
+        }
+    }
+
+    for b in t35.itu_t_t35_payload_byte.iter() {
+        res.append(&mut generate_unsigned_binary(*b as u32, 8));
+    }
+    encoder_formatted_print(
+        "SEI (ITU-T T.35 User Data): itu_t_t35_payload_byte",
+        &t35.itu_t_t35_payload_byte,
+        63,
+    );
+
+    res
 }
 
 fn encode_user_data_unregistered(uud: &SEIUserDataUnregistered) -> Vec<u8> {
@@ -866,495 +1255,5006 @@ fn encode_recovery_point(rp: &SEIRecoveryPoint) -> Vec<u8> {
     res
 }
 
-fn encode_ref_pic_marking_repetition() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_ref_pic_marking_repetition(
+    rpmr: &SEIDecRefPicMarkingRepetition,
+    sps: &SeqParameterSet,
+) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
-}
+    res.push(match rpmr.original_idr_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Ref pic marking repetition): original_idr_flag",
+        rpmr.original_idr_flag,
+        63,
+    );
+    res.append(&mut exp_golomb_encode_one(
+        rpmr.original_frame_num as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Ref pic marking repetition): original_frame_num",
+        rpmr.original_frame_num,
+        63,
+    );
+    if !sps.frame_mbs_only_flag {
+        res.push(match rpmr.original_field_pic_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Ref pic marking repetition): original_field_pic_flag",
+            rpmr.original_field_pic_flag,
+            63,
+        );
+        if rpmr.original_field_pic_flag {
+            res.push(match rpmr.original_bottom_field_flag {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Ref pic marking repetition): original_bottom_field_flag",
+                rpmr.original_bottom_field_flag,
+                63,
+            );
+        }
+    }
 
-fn encode_spare_pic() -> Vec<u8> {
-    let res = Vec::new();
+    // dec_ref_pic_marking() with IdrPicFlag replaced by original_idr_flag
+    if rpmr.original_idr_flag {
+        res.push(match rpmr.no_output_of_prior_pics_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Ref pic marking repetition): no_output_of_prior_pics_flag",
+            rpmr.no_output_of_prior_pics_flag,
+            63,
+        );
+        res.push(match rpmr.long_term_reference_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Ref pic marking repetition): long_term_reference_flag",
+            rpmr.long_term_reference_flag,
+            63,
+        );
+    } else {
+        res.push(match rpmr.adaptive_ref_pic_marking_mode_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Ref pic marking repetition): adaptive_ref_pic_marking_mode_flag",
+            rpmr.adaptive_ref_pic_marking_mode_flag,
+            63,
+        );
+        if rpmr.adaptive_ref_pic_marking_mode_flag {
+            for i in 0..rpmr.memory_management_control_operation.len() {
+                let mmco = rpmr.memory_management_control_operation[i];
+                res.append(&mut exp_golomb_encode_one(mmco as i32, false, 0, false));
+                encoder_formatted_print(
+                    "SEI (Ref pic marking repetition): memory_management_control_operation",
+                    mmco,
+                    63,
+                );
+                if mmco == 1 || mmco == 3 {
+                    res.append(&mut exp_golomb_encode_one(
+                        rpmr.difference_of_pic_nums_minus1[i] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                }
+                if mmco == 2 {
+                    res.append(&mut exp_golomb_encode_one(
+                        rpmr.long_term_pic_num[i] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                }
+                if mmco == 3 || mmco == 6 {
+                    res.append(&mut exp_golomb_encode_one(
+                        rpmr.long_term_frame_idx[i] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                }
+                if mmco == 4 {
+                    res.append(&mut exp_golomb_encode_one(
+                        rpmr.max_long_term_frame_idx_plus1[i] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                }
+            }
+        }
+    }
 
-    return res;
+    res
 }
 
-fn encode_scene_info() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_spare_pic(sp: &SEISparePic) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    res.append(&mut exp_golomb_encode_one(
+        sp.target_frame_num as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Spare picture): target_frame_num",
+        sp.target_frame_num,
+        63,
+    );
+    res.push(match sp.spare_field_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Spare picture): spare_field_flag",
+        sp.spare_field_flag,
+        63,
+    );
+    if sp.spare_field_flag {
+        res.push(match sp.target_bottom_field_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Spare picture): target_bottom_field_flag",
+            sp.target_bottom_field_flag,
+            63,
+        );
+    }
+    res.append(&mut exp_golomb_encode_one(
+        sp.num_spare_pics_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Spare picture): num_spare_pics_minus1",
+        sp.num_spare_pics_minus1,
+        63,
+    );
+
+    for i in 0..sp.delta_spare_frame_num.len() {
+        res.append(&mut exp_golomb_encode_one(
+            sp.delta_spare_frame_num[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Spare picture): delta_spare_frame_num",
+            sp.delta_spare_frame_num[i],
+            63,
+        );
+        if sp.spare_field_flag {
+            res.push(match sp.spare_bottom_field_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Spare picture): spare_bottom_field_flag",
+                sp.spare_bottom_field_flag[i],
+                63,
+            );
+        }
+        res.append(&mut exp_golomb_encode_one(
+            sp.spare_area_idc[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Spare picture): spare_area_idc",
+            sp.spare_area_idc[i],
+            63,
+        );
+        if sp.spare_area_idc[i] == 1 {
+            for f in sp.spare_unit_flag[i].iter() {
+                res.push(match f {
+                    true => 1,
+                    false => 0,
+                });
+            }
+            encoder_formatted_print(
+                "SEI (Spare picture): spare_unit_flag",
+                &sp.spare_unit_flag[i],
+                63,
+            );
+        } else if sp.spare_area_idc[i] == 2 {
+            for z in sp.zero_run_length[i].iter() {
+                res.append(&mut exp_golomb_encode_one(*z as i32, false, 0, false));
+            }
+            encoder_formatted_print(
+                "SEI (Spare picture): zero_run_length",
+                &sp.zero_run_length[i],
+                63,
+            );
+        }
+    }
+
+    res
+}
+
+fn encode_scene_info(si: &SEISceneInfo) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.push(match si.scene_info_present_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Scene info): scene_info_present_flag",
+        si.scene_info_present_flag,
+        63,
+    );
+    if si.scene_info_present_flag {
+        res.append(&mut exp_golomb_encode_one(
+            si.scene_id as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SEI (Scene info): scene_id", si.scene_id, 63);
+        res.append(&mut exp_golomb_encode_one(
+            si.scene_transition_type as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Scene info): scene_transition_type",
+            si.scene_transition_type,
+            63,
+        );
+        if si.scene_transition_type > 3 {
+            res.append(&mut exp_golomb_encode_one(
+                si.second_scene_id as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print("SEI (Scene info): second_scene_id", si.second_scene_id, 63);
+        }
+    }
+
+    res
+}
+
+fn encode_sub_seq_info(ssi: &SEISubSeqInfo) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        ssi.sub_seq_layer_num as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Sub-sequence info): sub_seq_layer_num",
+        ssi.sub_seq_layer_num,
+        63,
+    );
+    res.append(&mut exp_golomb_encode_one(
+        ssi.sub_seq_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SEI (Sub-sequence info): sub_seq_id", ssi.sub_seq_id, 63);
+    res.push(match ssi.first_ref_pic_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Sub-sequence info): first_ref_pic_flag",
+        ssi.first_ref_pic_flag,
+        63,
+    );
+    res.push(match ssi.leading_non_ref_pic_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Sub-sequence info): leading_non_ref_pic_flag",
+        ssi.leading_non_ref_pic_flag,
+        63,
+    );
+    res.push(match ssi.last_pic_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Sub-sequence info): last_pic_flag",
+        ssi.last_pic_flag,
+        63,
+    );
+    res.push(match ssi.sub_seq_frame_num_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Sub-sequence info): sub_seq_frame_num_flag",
+        ssi.sub_seq_frame_num_flag,
+        63,
+    );
+    if ssi.sub_seq_frame_num_flag {
+        res.append(&mut exp_golomb_encode_one(
+            ssi.sub_seq_frame_num as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Sub-sequence info): sub_seq_frame_num",
+            ssi.sub_seq_frame_num,
+            63,
+        );
+    }
+
+    res
+}
+
+fn encode_sub_seq_layer_characteristics(sslc: &SEISubSeqLayerCharacteristics) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        sslc.num_sub_seq_layers_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Sub-sequence layer characteristics): num_sub_seq_layers_minus1",
+        sslc.num_sub_seq_layers_minus1,
+        63,
+    );
+    for layer in 0..sslc.accurate_statistics_flag.len() {
+        res.push(match sslc.accurate_statistics_flag[layer] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Sub-sequence layer characteristics): accurate_statistics_flag",
+            sslc.accurate_statistics_flag[layer],
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            sslc.average_bit_rate[layer],
+            16,
+        ));
+        encoder_formatted_print(
+            "SEI (Sub-sequence layer characteristics): average_bit_rate",
+            sslc.average_bit_rate[layer],
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            sslc.average_frame_rate[layer],
+            16,
+        ));
+        encoder_formatted_print(
+            "SEI (Sub-sequence layer characteristics): average_frame_rate",
+            sslc.average_frame_rate[layer],
+            63,
+        );
+    }
+
+    res
+}
+
+fn encode_sub_seq_characteristics(ssc: &SEISubSeqCharacteristics) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        ssc.sub_seq_layer_num as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Sub-sequence characteristics): sub_seq_layer_num",
+        ssc.sub_seq_layer_num,
+        63,
+    );
+    res.append(&mut exp_golomb_encode_one(
+        ssc.sub_seq_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Sub-sequence characteristics): sub_seq_id",
+        ssc.sub_seq_id,
+        63,
+    );
+    res.push(match ssc.duration_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Sub-sequence characteristics): duration_flag",
+        ssc.duration_flag,
+        63,
+    );
+    if ssc.duration_flag {
+        res.append(&mut generate_unsigned_binary(ssc.sub_seq_duration, 32));
+        encoder_formatted_print(
+            "SEI (Sub-sequence characteristics): sub_seq_duration",
+            ssc.sub_seq_duration,
+            63,
+        );
+    }
+    res.push(match ssc.average_rate_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Sub-sequence characteristics): average_rate_flag",
+        ssc.average_rate_flag,
+        63,
+    );
+    if ssc.average_rate_flag {
+        res.push(match ssc.accurate_statistics_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Sub-sequence characteristics): accurate_statistics_flag",
+            ssc.accurate_statistics_flag,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(ssc.average_bit_rate, 16));
+        encoder_formatted_print(
+            "SEI (Sub-sequence characteristics): average_bit_rate",
+            ssc.average_bit_rate,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(ssc.average_frame_rate, 16));
+        encoder_formatted_print(
+            "SEI (Sub-sequence characteristics): average_frame_rate",
+            ssc.average_frame_rate,
+            63,
+        );
+    }
+    res.append(&mut exp_golomb_encode_one(
+        ssc.num_referenced_subseqs as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Sub-sequence characteristics): num_referenced_subseqs",
+        ssc.num_referenced_subseqs,
+        63,
+    );
+    for n in 0..ssc.ref_sub_seq_layer_num.len() {
+        res.append(&mut exp_golomb_encode_one(
+            ssc.ref_sub_seq_layer_num[n] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Sub-sequence characteristics): ref_sub_seq_layer_num",
+            ssc.ref_sub_seq_layer_num[n],
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            ssc.ref_sub_seq_id[n] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Sub-sequence characteristics): ref_sub_seq_id",
+            ssc.ref_sub_seq_id[n],
+            63,
+        );
+        res.push(match ssc.ref_sub_seq_direction[n] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Sub-sequence characteristics): ref_sub_seq_direction",
+            ssc.ref_sub_seq_direction[n],
+            63,
+        );
+    }
+
+    res
+}
+
+fn encode_full_frame_freeze(fff: &SEIFullFrameFreeze) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        fff.full_frame_freeze_repetition_period as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Full-frame freeze): full_frame_freeze_repetition_period",
+        fff.full_frame_freeze_repetition_period,
+        63,
+    );
+
+    res
+}
+
+/// The full-frame freeze release payload is empty
+fn encode_full_frame_freeze_release() -> Vec<u8> {
+    Vec::new()
+}
+
+fn encode_full_frame_snapshot(ffs: &SEIFullFrameSnapshot) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        ffs.snapshot_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Full-frame snapshot): snapshot_id",
+        ffs.snapshot_id,
+        63,
+    );
+
+    res
+}
+
+fn encode_progressive_refinement_segment_start(
+    prss: &SEIProgressiveRefinementSegmentStart,
+) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        prss.progressive_refinement_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Progressive refinement segment start): progressive_refinement_id",
+        prss.progressive_refinement_id,
+        63,
+    );
+    res.append(&mut exp_golomb_encode_one(
+        prss.num_refinement_steps_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Progressive refinement segment start): num_refinement_steps_minus1",
+        prss.num_refinement_steps_minus1,
+        63,
+    );
+
+    res
+}
+
+fn encode_progressive_refinement_segment_end(prse: &SEIProgressiveRefinementSegmentEnd) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        prse.progressive_refinement_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Progressive refinement segment end): progressive_refinement_id",
+        prse.progressive_refinement_id,
+        63,
+    );
+
+    res
+}
+
+fn encode_motion_constrained_slice_group_set() -> Vec<u8> {
+    Vec::new()
+}
+
+fn encode_film_grain_characteristics(fgc: &SEIFilmGrainCharacteristics) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.push(match fgc.film_grain_characteristics_cancel_flag {
+        true => 1,
+        _ => 0,
+    });
+
+    if !fgc.film_grain_characteristics_cancel_flag {
+        res.append(&mut generate_unsigned_binary(
+            fgc.film_grain_model_id as u32,
+            2,
+        ));
+        res.push(match fgc.separate_colour_description_present_flag {
+            true => 1,
+            _ => 0,
+        });
+
+        if fgc.separate_colour_description_present_flag {
+            res.append(&mut generate_unsigned_binary(
+                fgc.film_grain_bit_depth_luma_minus8 as u32,
+                3,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                fgc.film_grain_bit_depth_chroma_minus8 as u32,
+                3,
+            ));
+            res.push(match fgc.film_grain_full_range_flag {
+                true => 1,
+                _ => 0,
+            });
+            res.append(&mut generate_unsigned_binary(
+                fgc.film_grain_colour_primaries as u32,
+                8,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                fgc.film_grain_transfer_characteristics as u32,
+                8,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                fgc.film_grain_matrix_coefficients as u32,
+                8,
+            ));
+        }
+
+        res.append(&mut generate_unsigned_binary(
+            fgc.blending_mode_id as u32,
+            2,
+        ));
+        res.append(&mut generate_unsigned_binary(
+            fgc.log2_scale_factor as u32,
+            4,
+        ));
+
+        for c in 0..3 {
+            res.push(match fgc.comp_model_present_flag[c] {
+                true => 1,
+                _ => 0,
+            });
+        }
+
+        for c in 0..3 {
+            if fgc.comp_model_present_flag[c] {
+                res.append(&mut generate_unsigned_binary(
+                    fgc.num_intensity_intervals_minus1[c] as u32,
+                    8,
+                ));
+                res.append(&mut generate_unsigned_binary(
+                    fgc.num_model_values_minus1[c] as u32,
+                    3,
+                ));
+
+                for i in 0..fgc.num_intensity_intervals_minus1[c] as usize {
+                    res.append(&mut generate_unsigned_binary(
+                        fgc.intensity_interval_lower_bound[c][i] as u32,
+                        8,
+                    ));
+                    res.append(&mut generate_unsigned_binary(
+                        fgc.intensity_interval_upper_bound[c][i] as u32,
+                        8,
+                    ));
+                    for j in 0..fgc.num_model_values_minus1[c] as usize {
+                        exp_golomb_encode_one(fgc.comp_model_value[c][i][j], true, 0, false);
+                    }
+                }
+            }
+        }
+        exp_golomb_encode_one(
+            fgc.film_grain_characteristics_repetition_period as i32,
+            false,
+            0,
+            false,
+        );
+    }
+
+    fgc.encoder_pretty_print();
+
+    res
+}
+
+fn encode_deblocking_filter_display_preference() -> Vec<u8> {
+    Vec::new()
+}
+
+fn encode_stereo_video_info() -> Vec<u8> {
+    Vec::new()
+}
+
+fn encode_post_filter_hint() -> Vec<u8> {
+    Vec::new()
+}
+
+fn encode_tone_mapping_info(tmi: &SEIToneMappingInfo) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        tmi.tone_map_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SEI (Tone mapping): tone_map_id", tmi.tone_map_id, 63);
+    res.push(match tmi.tone_map_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Tone mapping): tone_map_cancel_flag",
+        tmi.tone_map_cancel_flag,
+        63,
+    );
+    if !tmi.tone_map_cancel_flag {
+        res.append(&mut exp_golomb_encode_one(
+            tmi.tone_map_repetition_period as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Tone mapping): tone_map_repetition_period",
+            tmi.tone_map_repetition_period,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            tmi.coded_data_bit_depth as u32,
+            8,
+        ));
+        encoder_formatted_print(
+            "SEI (Tone mapping): coded_data_bit_depth",
+            tmi.coded_data_bit_depth,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            tmi.target_bit_depth as u32,
+            8,
+        ));
+        encoder_formatted_print(
+            "SEI (Tone mapping): target_bit_depth",
+            tmi.target_bit_depth,
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            tmi.tone_map_model_id as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Tone mapping): tone_map_model_id",
+            tmi.tone_map_model_id,
+            63,
+        );
+
+        let coded_bits = (((tmi.coded_data_bit_depth as usize) + 7) >> 3) << 3;
+        let target_bits = (((tmi.target_bit_depth as usize) + 7) >> 3) << 3;
+        match tmi.tone_map_model_id {
+            0 => {
+                res.append(&mut generate_unsigned_binary(tmi.min_value, 32));
+                encoder_formatted_print("SEI (Tone mapping): min_value", tmi.min_value, 63);
+                res.append(&mut generate_unsigned_binary(tmi.max_value, 32));
+                encoder_formatted_print("SEI (Tone mapping): max_value", tmi.max_value, 63);
+            }
+            1 => {
+                res.append(&mut generate_unsigned_binary(tmi.sigmoid_midpoint, 32));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): sigmoid_midpoint",
+                    tmi.sigmoid_midpoint,
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(tmi.sigmoid_width, 32));
+                encoder_formatted_print("SEI (Tone mapping): sigmoid_width", tmi.sigmoid_width, 63);
+            }
+            2 => {
+                for v in tmi.start_of_coded_interval.iter() {
+                    res.append(&mut generate_unsigned_binary(*v, coded_bits));
+                }
+                encoder_formatted_print(
+                    "SEI (Tone mapping): start_of_coded_interval",
+                    &tmi.start_of_coded_interval,
+                    63,
+                );
+            }
+            3 => {
+                res.append(&mut generate_unsigned_binary(tmi.num_pivots, 16));
+                encoder_formatted_print("SEI (Tone mapping): num_pivots", tmi.num_pivots, 63);
+                for i in 0..tmi.coded_pivot_value.len() {
+                    res.append(&mut generate_unsigned_binary(
+                        tmi.coded_pivot_value[i],
+                        coded_bits,
+                    ));
+                    res.append(&mut generate_unsigned_binary(
+                        tmi.target_pivot_value[i],
+                        target_bits,
+                    ));
+                }
+                encoder_formatted_print(
+                    "SEI (Tone mapping): coded_pivot_value",
+                    &tmi.coded_pivot_value,
+                    63,
+                );
+                encoder_formatted_print(
+                    "SEI (Tone mapping): target_pivot_value",
+                    &tmi.target_pivot_value,
+                    63,
+                );
+            }
+            4 => {
+                res.append(&mut generate_unsigned_binary(
+                    tmi.camera_iso_speed_idc as u32,
+                    8,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): camera_iso_speed_idc",
+                    tmi.camera_iso_speed_idc,
+                    63,
+                );
+                // Extended_ISO
+                if tmi.camera_iso_speed_idc == 255 {
+                    res.append(&mut generate_unsigned_binary(
+                        tmi.camera_iso_speed_value,
+                        32,
+                    ));
+                    encoder_formatted_print(
+                        "SEI (Tone mapping): camera_iso_speed_value",
+                        tmi.camera_iso_speed_value,
+                        63,
+                    );
+                }
+                res.append(&mut generate_unsigned_binary(
+                    tmi.exposure_index_idc as u32,
+                    8,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): exposure_index_idc",
+                    tmi.exposure_index_idc,
+                    63,
+                );
+                // Extended_ISO
+                if tmi.exposure_index_idc == 255 {
+                    res.append(&mut generate_unsigned_binary(tmi.exposure_index_value, 32));
+                    encoder_formatted_print(
+                        "SEI (Tone mapping): exposure_index_value",
+                        tmi.exposure_index_value,
+                        63,
+                    );
+                }
+                res.push(match tmi.exposure_compensation_value_sign_flag {
+                    true => 1,
+                    false => 0,
+                });
+                encoder_formatted_print(
+                    "SEI (Tone mapping): exposure_compensation_value_sign_flag",
+                    tmi.exposure_compensation_value_sign_flag,
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(
+                    tmi.exposure_compensation_value_numerator,
+                    16,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): exposure_compensation_value_numerator",
+                    tmi.exposure_compensation_value_numerator,
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(
+                    tmi.exposure_compensation_value_denom_idc,
+                    16,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): exposure_compensation_value_denom_idc",
+                    tmi.exposure_compensation_value_denom_idc,
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(
+                    tmi.ref_screen_luminance_white,
+                    32,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): ref_screen_luminance_white",
+                    tmi.ref_screen_luminance_white,
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(
+                    tmi.extended_range_white_level,
+                    32,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): extended_range_white_level",
+                    tmi.extended_range_white_level,
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(
+                    tmi.nominal_black_level_luma_code_value,
+                    16,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): nominal_black_level_luma_code_value",
+                    tmi.nominal_black_level_luma_code_value,
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(
+                    tmi.nominal_white_level_luma_code_value,
+                    16,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): nominal_white_level_luma_code_value",
+                    tmi.nominal_white_level_luma_code_value,
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(
+                    tmi.extended_white_level_luma_code_value,
+                    16,
+                ));
+                encoder_formatted_print(
+                    "SEI (Tone mapping): extended_white_level_luma_code_value",
+                    tmi.extended_white_level_luma_code_value,
+                    63,
+                );
+            }
+            _ => {
+                println!(
+                    "[WARNING] encode_tone_mapping_info - reserved tone_map_model_id {}",
+                    tmi.tone_map_model_id
+                );
+            }
+        }
+    }
+
+    res
+}
+
+fn encode_scalability_info(si: &SEIScalabilityInfo) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.push(match si.temporal_id_nesting_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Scalability info): temporal_id_nesting_flag",
+        si.temporal_id_nesting_flag,
+        63,
+    );
+    res.push(match si.priority_layer_info_present_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Scalability info): priority_layer_info_present_flag",
+        si.priority_layer_info_present_flag,
+        63,
+    );
+    res.push(match si.priority_id_setting_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Scalability info): priority_id_setting_flag",
+        si.priority_id_setting_flag,
+        63,
+    );
+    res.append(&mut exp_golomb_encode_one(
+        si.num_layers_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Scalability info): num_layers_minus1",
+        si.num_layers_minus1,
+        63,
+    );
+
+    // the layer vectors may be shorter than num_layers_minus1 + 1 when the
+    // decoded stream was truncated, so only write what is available
+    for i in 0..si.layer_id.len() {
+        res.append(&mut exp_golomb_encode_one(
+            si.layer_id[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SEI (Scalability info): layer_id", si.layer_id[i], 63);
+        res.append(&mut generate_unsigned_binary(si.priority_id[i] as u32, 6));
+        encoder_formatted_print("SEI (Scalability info): priority_id", si.priority_id[i], 63);
+        res.push(match si.discardable_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): discardable_flag",
+            si.discardable_flag[i],
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(si.dependency_id[i] as u32, 3));
+        encoder_formatted_print(
+            "SEI (Scalability info): dependency_id",
+            si.dependency_id[i],
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(si.quality_id[i] as u32, 4));
+        encoder_formatted_print("SEI (Scalability info): quality_id", si.quality_id[i], 63);
+        res.append(&mut generate_unsigned_binary(si.temporal_id[i] as u32, 3));
+        encoder_formatted_print("SEI (Scalability info): temporal_id", si.temporal_id[i], 63);
+        res.push(match si.sub_pic_layer_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): sub_pic_layer_flag",
+            si.sub_pic_layer_flag[i],
+            63,
+        );
+        res.push(match si.sub_region_layer_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): sub_region_layer_flag",
+            si.sub_region_layer_flag[i],
+            63,
+        );
+        res.push(match si.iroi_division_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): iroi_division_info_present_flag",
+            si.iroi_division_info_present_flag[i],
+            63,
+        );
+        res.push(match si.profile_level_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): profile_level_info_present_flag",
+            si.profile_level_info_present_flag[i],
+            63,
+        );
+        res.push(match si.bitrate_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): bitrate_info_present_flag",
+            si.bitrate_info_present_flag[i],
+            63,
+        );
+        res.push(match si.frm_rate_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): frm_rate_info_present_flag",
+            si.frm_rate_info_present_flag[i],
+            63,
+        );
+        res.push(match si.frm_size_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): frm_size_info_present_flag",
+            si.frm_size_info_present_flag[i],
+            63,
+        );
+        res.push(match si.layer_dependency_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): layer_dependency_info_present_flag",
+            si.layer_dependency_info_present_flag[i],
+            63,
+        );
+        res.push(match si.parameter_sets_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): parameter_sets_info_present_flag",
+            si.parameter_sets_info_present_flag[i],
+            63,
+        );
+        res.push(match si.bitstream_restriction_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): bitstream_restriction_info_present_flag",
+            si.bitstream_restriction_info_present_flag[i],
+            63,
+        );
+        res.push(match si.exact_inter_layer_pred_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): exact_inter_layer_pred_flag",
+            si.exact_inter_layer_pred_flag[i],
+            63,
+        );
+        if si.sub_pic_layer_flag[i] || si.iroi_division_info_present_flag[i] {
+            res.push(match si.exact_sample_value_match_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Scalability info): exact_sample_value_match_flag",
+                si.exact_sample_value_match_flag[i],
+                63,
+            );
+        }
+        res.push(match si.layer_conversion_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): layer_conversion_flag",
+            si.layer_conversion_flag[i],
+            63,
+        );
+        res.push(match si.layer_output_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Scalability info): layer_output_flag",
+            si.layer_output_flag[i],
+            63,
+        );
+
+        if si.profile_level_info_present_flag[i] {
+            res.append(&mut generate_unsigned_binary(
+                si.layer_profile_level_idc[i],
+                24,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): layer_profile_level_idc",
+                si.layer_profile_level_idc[i],
+                63,
+            );
+        }
+
+        if si.bitrate_info_present_flag[i] {
+            res.append(&mut generate_unsigned_binary(si.avg_bitrate[i], 16));
+            encoder_formatted_print("SEI (Scalability info): avg_bitrate", si.avg_bitrate[i], 63);
+            res.append(&mut generate_unsigned_binary(si.max_bitrate_layer[i], 16));
+            encoder_formatted_print(
+                "SEI (Scalability info): max_bitrate_layer",
+                si.max_bitrate_layer[i],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                si.max_bitrate_layer_representation[i],
+                16,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): max_bitrate_layer_representation",
+                si.max_bitrate_layer_representation[i],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                si.max_bitrate_calc_window[i],
+                16,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): max_bitrate_calc_window",
+                si.max_bitrate_calc_window[i],
+                63,
+            );
+        }
+
+        if si.frm_rate_info_present_flag[i] {
+            res.append(&mut generate_unsigned_binary(
+                si.constant_frm_rate_idc[i] as u32,
+                2,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): constant_frm_rate_idc",
+                si.constant_frm_rate_idc[i],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(si.avg_frm_rate[i], 16));
+            encoder_formatted_print(
+                "SEI (Scalability info): avg_frm_rate",
+                si.avg_frm_rate[i],
+                63,
+            );
+        }
+
+        if si.frm_size_info_present_flag[i] || si.iroi_division_info_present_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                si.frm_width_in_mbs_minus1[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): frm_width_in_mbs_minus1",
+                si.frm_width_in_mbs_minus1[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                si.frm_height_in_mbs_minus1[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): frm_height_in_mbs_minus1",
+                si.frm_height_in_mbs_minus1[i],
+                63,
+            );
+        }
+
+        if si.sub_region_layer_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                si.base_region_layer_id[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): base_region_layer_id",
+                si.base_region_layer_id[i],
+                63,
+            );
+            res.push(match si.dynamic_rect_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Scalability info): dynamic_rect_flag",
+                si.dynamic_rect_flag[i],
+                63,
+            );
+            if !si.dynamic_rect_flag[i] {
+                res.append(&mut generate_unsigned_binary(si.horizontal_offset[i], 16));
+                encoder_formatted_print(
+                    "SEI (Scalability info): horizontal_offset",
+                    si.horizontal_offset[i],
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(si.vertical_offset[i], 16));
+                encoder_formatted_print(
+                    "SEI (Scalability info): vertical_offset",
+                    si.vertical_offset[i],
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(si.region_width[i], 16));
+                encoder_formatted_print(
+                    "SEI (Scalability info): region_width",
+                    si.region_width[i],
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(si.region_height[i], 16));
+                encoder_formatted_print(
+                    "SEI (Scalability info): region_height",
+                    si.region_height[i],
+                    63,
+                );
+            }
+        }
+
+        if si.sub_pic_layer_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                si.roi_id[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print("SEI (Scalability info): roi_id", si.roi_id[i], 63);
+        }
+
+        if si.iroi_division_info_present_flag[i] {
+            res.push(match si.iroi_grid_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Scalability info): iroi_grid_flag",
+                si.iroi_grid_flag[i],
+                63,
+            );
+            if si.iroi_grid_flag[i] {
+                res.append(&mut exp_golomb_encode_one(
+                    si.grid_width_in_mbs_minus1[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Scalability info): grid_width_in_mbs_minus1",
+                    si.grid_width_in_mbs_minus1[i],
+                    63,
+                );
+                res.append(&mut exp_golomb_encode_one(
+                    si.grid_height_in_mbs_minus1[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Scalability info): grid_height_in_mbs_minus1",
+                    si.grid_height_in_mbs_minus1[i],
+                    63,
+                );
+            } else {
+                res.append(&mut exp_golomb_encode_one(
+                    si.num_rois_minus1[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Scalability info): num_rois_minus1",
+                    si.num_rois_minus1[i],
+                    63,
+                );
+                for j in 0..si.first_mb_in_roi[i].len() {
+                    res.append(&mut exp_golomb_encode_one(
+                        si.first_mb_in_roi[i][j] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                    encoder_formatted_print(
+                        "SEI (Scalability info): first_mb_in_roi",
+                        si.first_mb_in_roi[i][j],
+                        63,
+                    );
+                    res.append(&mut exp_golomb_encode_one(
+                        si.roi_width_in_mbs_minus1[i][j] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                    encoder_formatted_print(
+                        "SEI (Scalability info): roi_width_in_mbs_minus1",
+                        si.roi_width_in_mbs_minus1[i][j],
+                        63,
+                    );
+                    res.append(&mut exp_golomb_encode_one(
+                        si.roi_height_in_mbs_minus1[i][j] as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                    encoder_formatted_print(
+                        "SEI (Scalability info): roi_height_in_mbs_minus1",
+                        si.roi_height_in_mbs_minus1[i][j],
+                        63,
+                    );
+                }
+            }
+        }
+
+        if si.layer_dependency_info_present_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                si.num_directly_dependent_layers[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): num_directly_dependent_layers",
+                si.num_directly_dependent_layers[i],
+                63,
+            );
+            for j in 0..si.directly_dependent_layer_id_delta_minus1[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    si.directly_dependent_layer_id_delta_minus1[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Scalability info): directly_dependent_layer_id_delta_minus1",
+                    si.directly_dependent_layer_id_delta_minus1[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.append(&mut exp_golomb_encode_one(
+                si.layer_dependency_info_src_layer_id_delta[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): layer_dependency_info_src_layer_id_delta",
+                si.layer_dependency_info_src_layer_id_delta[i],
+                63,
+            );
+        }
+
+        if si.parameter_sets_info_present_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                si.num_seq_parameter_sets[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): num_seq_parameter_sets",
+                si.num_seq_parameter_sets[i],
+                63,
+            );
+            for j in 0..si.seq_parameter_set_id_delta[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    si.seq_parameter_set_id_delta[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Scalability info): seq_parameter_set_id_delta",
+                    si.seq_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+            res.append(&mut exp_golomb_encode_one(
+                si.num_subset_seq_parameter_sets[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): num_subset_seq_parameter_sets",
+                si.num_subset_seq_parameter_sets[i],
+                63,
+            );
+            for j in 0..si.subset_seq_parameter_set_id_delta[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    si.subset_seq_parameter_set_id_delta[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Scalability info): subset_seq_parameter_set_id_delta",
+                    si.subset_seq_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+            res.append(&mut exp_golomb_encode_one(
+                si.num_pic_parameter_sets_minus1[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): num_pic_parameter_sets_minus1",
+                si.num_pic_parameter_sets_minus1[i],
+                63,
+            );
+            for j in 0..si.pic_parameter_set_id_delta[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    si.pic_parameter_set_id_delta[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Scalability info): pic_parameter_set_id_delta",
+                    si.pic_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.append(&mut exp_golomb_encode_one(
+                si.parameter_sets_info_src_layer_id_delta[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): parameter_sets_info_src_layer_id_delta",
+                si.parameter_sets_info_src_layer_id_delta[i],
+                63,
+            );
+        }
+
+        if si.bitstream_restriction_info_present_flag[i] {
+            res.push(match si.motion_vectors_over_pic_boundaries_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Scalability info): motion_vectors_over_pic_boundaries_flag",
+                si.motion_vectors_over_pic_boundaries_flag[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                si.max_bytes_per_pic_denom[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): max_bytes_per_pic_denom",
+                si.max_bytes_per_pic_denom[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                si.max_bits_per_mb_denom[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): max_bits_per_mb_denom",
+                si.max_bits_per_mb_denom[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                si.log2_max_mv_length_horizontal[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): log2_max_mv_length_horizontal",
+                si.log2_max_mv_length_horizontal[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                si.log2_max_mv_length_vertical[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): log2_max_mv_length_vertical",
+                si.log2_max_mv_length_vertical[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                si.max_num_reorder_frames[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): max_num_reorder_frames",
+                si.max_num_reorder_frames[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                si.max_dec_frame_buffering[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): max_dec_frame_buffering",
+                si.max_dec_frame_buffering[i],
+                63,
+            );
+        }
+
+        if si.layer_conversion_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                si.conversion_type_idc[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): conversion_type_idc",
+                si.conversion_type_idc[i],
+                63,
+            );
+            for j in 0..2 {
+                res.push(match si.rewriting_info_flag[i][j] {
+                    true => 1,
+                    false => 0,
+                });
+                encoder_formatted_print(
+                    "SEI (Scalability info): rewriting_info_flag",
+                    si.rewriting_info_flag[i][j],
+                    63,
+                );
+                if si.rewriting_info_flag[i][j] {
+                    res.append(&mut generate_unsigned_binary(
+                        si.rewriting_profile_level_idc[i][j],
+                        24,
+                    ));
+                    encoder_formatted_print(
+                        "SEI (Scalability info): rewriting_profile_level_idc",
+                        si.rewriting_profile_level_idc[i][j],
+                        63,
+                    );
+                    res.append(&mut generate_unsigned_binary(
+                        si.rewriting_avg_bitrate[i][j],
+                        16,
+                    ));
+                    encoder_formatted_print(
+                        "SEI (Scalability info): rewriting_avg_bitrate",
+                        si.rewriting_avg_bitrate[i][j],
+                        63,
+                    );
+                    res.append(&mut generate_unsigned_binary(
+                        si.rewriting_max_bitrate[i][j],
+                        16,
+                    ));
+                    encoder_formatted_print(
+                        "SEI (Scalability info): rewriting_max_bitrate",
+                        si.rewriting_max_bitrate[i][j],
+                        63,
+                    );
+                }
+            }
+        }
+    }
+
+    if si.priority_layer_info_present_flag {
+        res.append(&mut exp_golomb_encode_one(
+            si.pr_num_dids_minus1 as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Scalability info): pr_num_dIds_minus1",
+            si.pr_num_dids_minus1,
+            63,
+        );
+        for i in 0..si.pr_dependency_id.len() {
+            res.append(&mut generate_unsigned_binary(
+                si.pr_dependency_id[i] as u32,
+                3,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): pr_dependency_id",
+                si.pr_dependency_id[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                si.pr_num_minus1[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalability info): pr_num_minus1",
+                si.pr_num_minus1[i],
+                63,
+            );
+            for j in 0..si.pr_id[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    si.pr_id[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print("SEI (Scalability info): pr_id", si.pr_id[i][j], 63);
+                res.append(&mut generate_unsigned_binary(
+                    si.pr_profile_level_idc[i][j],
+                    24,
+                ));
+                encoder_formatted_print(
+                    "SEI (Scalability info): pr_profile_level_idc",
+                    si.pr_profile_level_idc[i][j],
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(si.pr_avg_bitrate[i][j], 16));
+                encoder_formatted_print(
+                    "SEI (Scalability info): pr_avg_bitrate",
+                    si.pr_avg_bitrate[i][j],
+                    63,
+                );
+                res.append(&mut generate_unsigned_binary(si.pr_max_bitrate[i][j], 16));
+                encoder_formatted_print(
+                    "SEI (Scalability info): pr_max_bitrate",
+                    si.pr_max_bitrate[i][j],
+                    63,
+                );
+            }
+        }
+    }
+
+    if si.priority_id_setting_flag {
+        for c in si.priority_id_setting_uri.iter() {
+            res.append(&mut generate_unsigned_binary(*c as u32, 8));
+        }
+        // terminating null byte
+        res.append(&mut generate_unsigned_binary(0, 8));
+        encoder_formatted_print(
+            "SEI (Scalability info): priority_id_setting_uri",
+            String::from_utf8_lossy(&si.priority_id_setting_uri),
+            63,
+        );
+    }
+
+    res
+}
+
+fn encode_sub_pic_scalable_layer(spsl: &SEISubPicScalableLayer) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        spsl.layer_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SEI (Sub-pic scalable layer): layer_id", spsl.layer_id, 63);
+
+    res
+}
+
+fn encode_non_required_layer_rep(nrlr: &SEINonRequiredLayerRep) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        nrlr.num_info_entries_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Non-required layer rep): num_info_entries_minus1",
+        nrlr.num_info_entries_minus1,
+        63,
+    );
+    for i in 0..nrlr.entry_dependency_id.len() {
+        res.append(&mut generate_unsigned_binary(
+            nrlr.entry_dependency_id[i] as u32,
+            3,
+        ));
+        encoder_formatted_print(
+            "SEI (Non-required layer rep): entry_dependency_id",
+            nrlr.entry_dependency_id[i],
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            nrlr.num_non_required_layer_reps_minus1[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Non-required layer rep): num_non_required_layer_reps_minus1",
+            nrlr.num_non_required_layer_reps_minus1[i],
+            63,
+        );
+        for j in 0..nrlr.non_required_layer_rep_dependency_id[i].len() {
+            res.append(&mut generate_unsigned_binary(
+                nrlr.non_required_layer_rep_dependency_id[i][j] as u32,
+                3,
+            ));
+            encoder_formatted_print(
+                "SEI (Non-required layer rep): non_required_layer_rep_dependency_id",
+                nrlr.non_required_layer_rep_dependency_id[i][j],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                nrlr.non_required_layer_rep_quality_id[i][j] as u32,
+                4,
+            ));
+            encoder_formatted_print(
+                "SEI (Non-required layer rep): non_required_layer_rep_quality_id",
+                nrlr.non_required_layer_rep_quality_id[i][j],
+                63,
+            );
+        }
+    }
+
+    res
+}
+
+fn encode_priority_layer_info(pli: &SEIPriorityLayerInfo) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut generate_unsigned_binary(
+        pli.pr_dependency_id as u32,
+        3,
+    ));
+    encoder_formatted_print(
+        "SEI (Priority layer info): pr_dependency_id",
+        pli.pr_dependency_id,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(
+        pli.num_priority_ids as u32,
+        4,
+    ));
+    encoder_formatted_print(
+        "SEI (Priority layer info): num_priority_ids",
+        pli.num_priority_ids,
+        63,
+    );
+    for i in 0..pli.alt_priority_id.len() {
+        res.append(&mut generate_unsigned_binary(
+            pli.alt_priority_id[i] as u32,
+            6,
+        ));
+        encoder_formatted_print(
+            "SEI (Priority layer info): alt_priority_id",
+            pli.alt_priority_id[i],
+            63,
+        );
+    }
+
+    res
+}
+
+fn encode_layers_not_present(lnp: &SEILayersNotPresent) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        lnp.num_layers as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SEI (Layers not present): num_layers", lnp.num_layers, 63);
+    for i in 0..lnp.layer_id.len() {
+        res.append(&mut exp_golomb_encode_one(
+            lnp.layer_id[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SEI (Layers not present): layer_id", lnp.layer_id[i], 63);
+    }
+
+    res
+}
+
+fn encode_layer_dependency_change(ldc: &SEILayerDependencyChange) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        ldc.num_layers_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Layer dependency change): num_layers_minus1",
+        ldc.num_layers_minus1,
+        63,
+    );
+    for i in 0..ldc.layer_id.len() {
+        res.append(&mut exp_golomb_encode_one(
+            ldc.layer_id[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Layer dependency change): layer_id",
+            ldc.layer_id[i],
+            63,
+        );
+        res.push(match ldc.layer_dependency_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Layer dependency change): layer_dependency_info_present_flag",
+            ldc.layer_dependency_info_present_flag[i],
+            63,
+        );
+        if ldc.layer_dependency_info_present_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                ldc.num_directly_dependent_layers[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Layer dependency change): num_directly_dependent_layers",
+                ldc.num_directly_dependent_layers[i],
+                63,
+            );
+            for j in 0..ldc.directly_dependent_layer_id_delta_minus1[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    ldc.directly_dependent_layer_id_delta_minus1[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Layer dependency change): directly_dependent_layer_id_delta_minus1",
+                    ldc.directly_dependent_layer_id_delta_minus1[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.append(&mut exp_golomb_encode_one(
+                ldc.layer_dependency_info_src_layer_id_delta_minus1[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Layer dependency change): layer_dependency_info_src_layer_id_delta_minus1",
+                ldc.layer_dependency_info_src_layer_id_delta_minus1[i],
+                63,
+            );
+        }
+    }
+
+    res
+}
+
+fn encode_scalable_nesting(sn: &SEIScalableNesting, spses: &[SeqParameterSet]) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.push(match sn.all_layer_representations_in_au_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Scalable nesting): all_layer_representations_in_au_flag",
+        sn.all_layer_representations_in_au_flag,
+        63,
+    );
+    if !sn.all_layer_representations_in_au_flag {
+        res.append(&mut exp_golomb_encode_one(
+            sn.num_layer_representations_minus1 as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Scalable nesting): num_layer_representations_minus1",
+            sn.num_layer_representations_minus1,
+            63,
+        );
+        for i in 0..sn.sei_dependency_id.len() {
+            res.append(&mut generate_unsigned_binary(
+                sn.sei_dependency_id[i] as u32,
+                3,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalable nesting): sei_dependency_id",
+                sn.sei_dependency_id[i],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                sn.sei_quality_id[i] as u32,
+                4,
+            ));
+            encoder_formatted_print(
+                "SEI (Scalable nesting): sei_quality_id",
+                sn.sei_quality_id[i],
+                63,
+            );
+        }
+        res.append(&mut generate_unsigned_binary(sn.sei_temporal_id as u32, 3));
+        encoder_formatted_print(
+            "SEI (Scalable nesting): sei_temporal_id",
+            sn.sei_temporal_id,
+            63,
+        );
+    }
+    // sei_nesting_zero_bit until byte aligned
+    while res.len() % 8 != 0 {
+        res.push(0);
+    }
+    res.append(&mut encode_nested_sei_messages(&sn.nested_sei, spses));
+
+    res
+}
+
+/// G.13.1.8 Base layer temporal HRD SEI message syntax and H.13.1.9 Base view temporal HRD SEI
+/// message syntax, which share the same structure
+fn encode_temporal_hrd(th: &SEITemporalHRD) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        th.num_of_temporal_layers_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Temporal HRD): num_of_temporal_layers_minus1",
+        th.num_of_temporal_layers_minus1,
+        63,
+    );
+    for i in 0..th.sei_temporal_id.len() {
+        res.append(&mut generate_unsigned_binary(
+            th.sei_temporal_id[i] as u32,
+            3,
+        ));
+        encoder_formatted_print(
+            "SEI (Temporal HRD): sei_temporal_id",
+            th.sei_temporal_id[i],
+            63,
+        );
+        res.push(match th.sei_timing_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Temporal HRD): sei_timing_info_present_flag",
+            th.sei_timing_info_present_flag[i],
+            63,
+        );
+        if th.sei_timing_info_present_flag[i] {
+            res.append(&mut generate_unsigned_binary(
+                th.sei_num_units_in_tick[i],
+                32,
+            ));
+            encoder_formatted_print(
+                "SEI (Temporal HRD): sei_num_units_in_tick",
+                th.sei_num_units_in_tick[i],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(th.sei_time_scale[i], 32));
+            encoder_formatted_print(
+                "SEI (Temporal HRD): sei_time_scale",
+                th.sei_time_scale[i],
+                63,
+            );
+            res.push(match th.sei_fixed_frame_rate_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Temporal HRD): sei_fixed_frame_rate_flag",
+                th.sei_fixed_frame_rate_flag[i],
+                63,
+            );
+        }
+        res.push(match th.sei_nal_hrd_parameters_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Temporal HRD): sei_nal_hrd_parameters_present_flag",
+            th.sei_nal_hrd_parameters_present_flag[i],
+            63,
+        );
+        if th.sei_nal_hrd_parameters_present_flag[i] {
+            res.append(&mut encode_hrd(&th.sei_nal_hrd_parameters[i]));
+        }
+        res.push(match th.sei_vcl_hrd_parameters_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Temporal HRD): sei_vcl_hrd_parameters_present_flag",
+            th.sei_vcl_hrd_parameters_present_flag[i],
+            63,
+        );
+        if th.sei_vcl_hrd_parameters_present_flag[i] {
+            res.append(&mut encode_hrd(&th.sei_vcl_hrd_parameters[i]));
+        }
+        if th.sei_nal_hrd_parameters_present_flag[i] || th.sei_vcl_hrd_parameters_present_flag[i] {
+            res.push(match th.sei_low_delay_hrd_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Temporal HRD): sei_low_delay_hrd_flag",
+                th.sei_low_delay_hrd_flag[i],
+                63,
+            );
+        }
+        res.push(match th.sei_pic_struct_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Temporal HRD): sei_pic_struct_present_flag",
+            th.sei_pic_struct_present_flag[i],
+            63,
+        );
+    }
+
+    res
+}
+
+fn encode_quality_layer_integrity_check(qlic: &SEIQualityLayerIntegrityCheck) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        qlic.num_info_entries_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Quality layer integrity check): num_info_entries_minus1",
+        qlic.num_info_entries_minus1,
+        63,
+    );
+    for i in 0..qlic.entry_dependency_id.len() {
+        res.append(&mut generate_unsigned_binary(
+            qlic.entry_dependency_id[i] as u32,
+            3,
+        ));
+        encoder_formatted_print(
+            "SEI (Quality layer integrity check): entry_dependency_id",
+            qlic.entry_dependency_id[i],
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(qlic.quality_layer_crc[i], 16));
+        encoder_formatted_print(
+            "SEI (Quality layer integrity check): quality_layer_crc",
+            qlic.quality_layer_crc[i],
+            63,
+        );
+    }
+
+    res
+}
+
+fn encode_redundant_pic_property(rpp: &SEIRedundantPicProperty) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        rpp.num_dids_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Redundant pic property): num_dIds_minus1",
+        rpp.num_dids_minus1,
+        63,
+    );
+    for i in 0..rpp.dependency_id.len() {
+        res.append(&mut generate_unsigned_binary(
+            rpp.dependency_id[i] as u32,
+            3,
+        ));
+        encoder_formatted_print(
+            "SEI (Redundant pic property): dependency_id",
+            rpp.dependency_id[i],
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            rpp.num_qids_minus1[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Redundant pic property): num_qIds_minus1",
+            rpp.num_qids_minus1[i],
+            63,
+        );
+        for j in 0..rpp.quality_id[i].len() {
+            res.append(&mut generate_unsigned_binary(
+                rpp.quality_id[i][j] as u32,
+                4,
+            ));
+            encoder_formatted_print(
+                "SEI (Redundant pic property): quality_id",
+                rpp.quality_id[i][j],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                rpp.num_redundant_pics_minus1[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Redundant pic property): num_redundant_pics_minus1",
+                rpp.num_redundant_pics_minus1[i][j],
+                63,
+            );
+            for k in 0..rpp.redundant_pic_cnt_minus1[i][j].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    rpp.redundant_pic_cnt_minus1[i][j][k] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (Redundant pic property): redundant_pic_cnt_minus1",
+                    rpp.redundant_pic_cnt_minus1[i][j][k],
+                    63,
+                );
+                res.push(match rpp.pic_match_flag[i][j][k] {
+                    true => 1,
+                    false => 0,
+                });
+                encoder_formatted_print(
+                    "SEI (Redundant pic property): pic_match_flag",
+                    rpp.pic_match_flag[i][j][k],
+                    63,
+                );
+                if !rpp.pic_match_flag[i][j][k] {
+                    res.push(match rpp.mb_type_match_flag[i][j][k] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "SEI (Redundant pic property): mb_type_match_flag",
+                        rpp.mb_type_match_flag[i][j][k],
+                        63,
+                    );
+                    res.push(match rpp.motion_match_flag[i][j][k] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "SEI (Redundant pic property): motion_match_flag",
+                        rpp.motion_match_flag[i][j][k],
+                        63,
+                    );
+                    res.push(match rpp.residual_match_flag[i][j][k] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "SEI (Redundant pic property): residual_match_flag",
+                        rpp.residual_match_flag[i][j][k],
+                        63,
+                    );
+                    res.push(match rpp.intra_samples_match_flag[i][j][k] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "SEI (Redundant pic property): intra_samples_match_flag",
+                        rpp.intra_samples_match_flag[i][j][k],
+                        63,
+                    );
+                }
+            }
+        }
+    }
+
+    res
+}
+
+fn encode_tl0_dep_rep_index(tdri: &SEITl0DepRepIndex) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut generate_unsigned_binary(tdri.tl0_dep_rep_idx, 8));
+    encoder_formatted_print(
+        "SEI (Tl0 dep rep index): tl0_dep_rep_idx",
+        tdri.tl0_dep_rep_idx,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(tdri.effective_idr_pic_id, 16));
+    encoder_formatted_print(
+        "SEI (Tl0 dep rep index): effective_idr_pic_id",
+        tdri.effective_idr_pic_id,
+        63,
+    );
+
+    res
+}
+
+fn encode_tl_switching_point(tsp: &SEITlSwitchingPoint) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        tsp.delta_frame_num,
+        true,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Tl switching point): delta_frame_num",
+        tsp.delta_frame_num,
+        63,
+    );
+
+    res
+}
+
+/// H.13.1.1 Parallel decoding information SEI message syntax
+fn encode_parallel_decoding_info(pdi: &SEIParallelDecodingInfo) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        pdi.seq_parameter_set_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Parallel decoding info): seq_parameter_set_id",
+        pdi.seq_parameter_set_id,
+        63,
+    );
+    if pdi.anchor_pic_flag {
+        res.append(&mut encode_pdi_init_delays(
+            "anchor",
+            &pdi.pdi_init_delay_anchor_minus2_l0,
+            &pdi.pdi_init_delay_anchor_minus2_l1,
+        ));
+    } else {
+        res.append(&mut encode_pdi_init_delays(
+            "non_anchor",
+            &pdi.pdi_init_delay_non_anchor_minus2_l0,
+            &pdi.pdi_init_delay_non_anchor_minus2_l1,
+        ));
+    }
+
+    res
+}
+
+/// Writes the pdi_init_delay_*_minus2_l0 and _l1 values of views 1 and up
+fn encode_pdi_init_delays(name: &str, l0: &[Vec<u32>], l1: &[Vec<u32>]) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    for i in 1..l0.len().min(l1.len()) {
+        for delay in l0[i].iter() {
+            res.append(&mut exp_golomb_encode_one(*delay as i32, false, 0, false));
+        }
+        encoder_formatted_print(
+            &format!(
+                "SEI (Parallel decoding info): pdi_init_delay_{}_minus2_l0",
+                name
+            ),
+            &l0[i],
+            63,
+        );
+        for delay in l1[i].iter() {
+            res.append(&mut exp_golomb_encode_one(*delay as i32, false, 0, false));
+        }
+        encoder_formatted_print(
+            &format!(
+                "SEI (Parallel decoding info): pdi_init_delay_{}_minus2_l1",
+                name
+            ),
+            &l1[i],
+            63,
+        );
+    }
+
+    res
+}
+
+/// H.13.1.2 MVC scalable nesting SEI message syntax and I.13.1.2 MVCD scalable nesting SEI
+/// message syntax. The MVCD variant adds the depth and texture applicability flags
+fn encode_mvc_scalable_nesting(
+    msn: &SEIMVCScalableNesting,
+    mvcd: bool,
+    spses: &[SeqParameterSet],
+) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.push(match msn.operation_point_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (MVC scalable nesting): operation_point_flag",
+        msn.operation_point_flag,
+        63,
+    );
+    if !msn.operation_point_flag {
+        res.push(match msn.all_view_components_in_au_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (MVC scalable nesting): all_view_components_in_au_flag",
+            msn.all_view_components_in_au_flag,
+            63,
+        );
+        if !msn.all_view_components_in_au_flag {
+            res.append(&mut exp_golomb_encode_one(
+                msn.num_view_components_minus1 as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (MVC scalable nesting): num_view_components_minus1",
+                msn.num_view_components_minus1,
+                63,
+            );
+            for i in 0..msn.sei_view_id.len() {
+                res.append(&mut generate_unsigned_binary(msn.sei_view_id[i], 10));
+                encoder_formatted_print(
+                    "SEI (MVC scalable nesting): sei_view_id",
+                    msn.sei_view_id[i],
+                    63,
+                );
+                if mvcd {
+                    res.push(match msn.sei_view_applicability_flag[i] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "SEI (MVC scalable nesting): sei_view_applicability_flag",
+                        msn.sei_view_applicability_flag[i],
+                        63,
+                    );
+                }
+            }
+        }
+    } else {
+        if mvcd {
+            res.push(match msn.sei_op_texture_only_flag {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (MVC scalable nesting): sei_op_texture_only_flag",
+                msn.sei_op_texture_only_flag,
+                63,
+            );
+        }
+        res.append(&mut exp_golomb_encode_one(
+            msn.num_view_components_op_minus1 as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (MVC scalable nesting): num_view_components_op_minus1",
+            msn.num_view_components_op_minus1,
+            63,
+        );
+        for i in 0..msn.sei_op_view_id.len() {
+            res.append(&mut generate_unsigned_binary(msn.sei_op_view_id[i], 10));
+            encoder_formatted_print(
+                "SEI (MVC scalable nesting): sei_op_view_id",
+                msn.sei_op_view_id[i],
+                63,
+            );
+            if mvcd && !msn.sei_op_texture_only_flag {
+                res.push(match msn.sei_op_depth_flag[i] {
+                    true => 1,
+                    false => 0,
+                });
+                encoder_formatted_print(
+                    "SEI (MVC scalable nesting): sei_op_depth_flag",
+                    msn.sei_op_depth_flag[i],
+                    63,
+                );
+                res.push(match msn.sei_op_texture_flag[i] {
+                    true => 1,
+                    false => 0,
+                });
+                encoder_formatted_print(
+                    "SEI (MVC scalable nesting): sei_op_texture_flag",
+                    msn.sei_op_texture_flag[i],
+                    63,
+                );
+            }
+        }
+        res.append(&mut generate_unsigned_binary(
+            msn.sei_op_temporal_id as u32,
+            3,
+        ));
+        encoder_formatted_print(
+            "SEI (MVC scalable nesting): sei_op_temporal_id",
+            msn.sei_op_temporal_id,
+            63,
+        );
+    }
+    // sei_nesting_zero_bit until byte aligned
+    while res.len() % 8 != 0 {
+        res.push(0);
+    }
+    res.append(&mut encode_nested_sei_messages(&msn.nested_sei, spses));
+
+    res
+}
+
+/// H.13.1.3 View scalability information SEI message syntax and I.13.1.1 MVCD view scalability
+/// information SEI message syntax. The MVCD variant adds mvcd_op_view_info() to each view
+fn encode_view_scalability_info(vsi: &SEIViewScalabilityInfo, mvcd: bool) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        vsi.num_operation_points_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (View scalability info): num_operation_points_minus1",
+        vsi.num_operation_points_minus1,
+        63,
+    );
+    for i in 0..vsi.operation_point_id.len() {
+        res.append(&mut exp_golomb_encode_one(
+            vsi.operation_point_id[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (View scalability info): operation_point_id",
+            vsi.operation_point_id[i],
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(vsi.priority_id[i] as u32, 5));
+        encoder_formatted_print(
+            "SEI (View scalability info): priority_id",
+            vsi.priority_id[i],
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(vsi.temporal_id[i] as u32, 3));
+        encoder_formatted_print(
+            "SEI (View scalability info): temporal_id",
+            vsi.temporal_id[i],
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            vsi.num_target_output_views_minus1[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (View scalability info): num_target_output_views_minus1",
+            vsi.num_target_output_views_minus1[i],
+            63,
+        );
+        for j in 0..vsi.view_id[i].len() {
+            res.append(&mut exp_golomb_encode_one(
+                vsi.view_id[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): view_id",
+                vsi.view_id[i][j],
+                63,
+            );
+            if mvcd {
+                // mvcd_op_view_info()
+                res.push(match vsi.view_info_depth_view_present_flag[i][j] {
+                    true => 1,
+                    false => 0,
+                });
+                encoder_formatted_print(
+                    "SEI (View scalability info): view_info_depth_view_present_flag",
+                    vsi.view_info_depth_view_present_flag[i][j],
+                    63,
+                );
+                if vsi.view_info_depth_view_present_flag[i][j] {
+                    res.push(match vsi.mvcd_depth_view_flag[i][j] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "SEI (View scalability info): mvcd_depth_view_flag",
+                        vsi.mvcd_depth_view_flag[i][j],
+                        63,
+                    );
+                }
+                res.push(match vsi.view_info_texture_view_present_flag[i][j] {
+                    true => 1,
+                    false => 0,
+                });
+                encoder_formatted_print(
+                    "SEI (View scalability info): view_info_texture_view_present_flag",
+                    vsi.view_info_texture_view_present_flag[i][j],
+                    63,
+                );
+                if vsi.view_info_texture_view_present_flag[i][j] {
+                    res.push(match vsi.mvcd_texture_view_flag[i][j] {
+                        true => 1,
+                        false => 0,
+                    });
+                    encoder_formatted_print(
+                        "SEI (View scalability info): mvcd_texture_view_flag",
+                        vsi.mvcd_texture_view_flag[i][j],
+                        63,
+                    );
+                }
+            }
+        }
+        res.push(match vsi.profile_level_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (View scalability info): profile_level_info_present_flag",
+            vsi.profile_level_info_present_flag[i],
+            63,
+        );
+        res.push(match vsi.bitrate_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (View scalability info): bitrate_info_present_flag",
+            vsi.bitrate_info_present_flag[i],
+            63,
+        );
+        res.push(match vsi.frm_rate_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (View scalability info): frm_rate_info_present_flag",
+            vsi.frm_rate_info_present_flag[i],
+            63,
+        );
+        if vsi.num_target_output_views_minus1[i] == 0 {
+            res.push(match vsi.view_dependency_info_present_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (View scalability info): view_dependency_info_present_flag",
+                vsi.view_dependency_info_present_flag[i],
+                63,
+            );
+        }
+        res.push(match vsi.parameter_sets_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (View scalability info): parameter_sets_info_present_flag",
+            vsi.parameter_sets_info_present_flag[i],
+            63,
+        );
+        res.push(match vsi.bitstream_restriction_info_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (View scalability info): bitstream_restriction_info_present_flag",
+            vsi.bitstream_restriction_info_present_flag[i],
+            63,
+        );
+
+        if vsi.profile_level_info_present_flag[i] {
+            res.append(&mut generate_unsigned_binary(
+                vsi.op_profile_level_idc[i],
+                24,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): op_profile_level_idc",
+                vsi.op_profile_level_idc[i],
+                63,
+            );
+        }
+
+        if vsi.bitrate_info_present_flag[i] {
+            res.append(&mut generate_unsigned_binary(vsi.avg_bitrate[i], 16));
+            encoder_formatted_print(
+                "SEI (View scalability info): avg_bitrate",
+                vsi.avg_bitrate[i],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(vsi.max_bitrate[i], 16));
+            encoder_formatted_print(
+                "SEI (View scalability info): max_bitrate",
+                vsi.max_bitrate[i],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                vsi.max_bitrate_calc_window[i],
+                16,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): max_bitrate_calc_window",
+                vsi.max_bitrate_calc_window[i],
+                63,
+            );
+        }
+
+        if vsi.frm_rate_info_present_flag[i] {
+            res.append(&mut generate_unsigned_binary(
+                vsi.constant_frm_rate_idc[i] as u32,
+                2,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): constant_frm_rate_idc",
+                vsi.constant_frm_rate_idc[i],
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(vsi.avg_frm_rate[i], 16));
+            encoder_formatted_print(
+                "SEI (View scalability info): avg_frm_rate",
+                vsi.avg_frm_rate[i],
+                63,
+            );
+        }
+
+        // view_dependency_info_present_flag is inferred to be 0 when not present
+        if vsi.num_target_output_views_minus1[i] == 0 && vsi.view_dependency_info_present_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                vsi.num_directly_dependent_views[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): num_directly_dependent_views",
+                vsi.num_directly_dependent_views[i],
+                63,
+            );
+            for j in 0..vsi.directly_dependent_view_id[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    vsi.directly_dependent_view_id[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (View scalability info): directly_dependent_view_id",
+                    vsi.directly_dependent_view_id[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.append(&mut exp_golomb_encode_one(
+                vsi.view_dependency_info_src_op_id[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): view_dependency_info_src_op_id",
+                vsi.view_dependency_info_src_op_id[i],
+                63,
+            );
+        }
+
+        if vsi.parameter_sets_info_present_flag[i] {
+            res.append(&mut exp_golomb_encode_one(
+                vsi.num_seq_parameter_sets[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): num_seq_parameter_sets",
+                vsi.num_seq_parameter_sets[i],
+                63,
+            );
+            for j in 0..vsi.seq_parameter_set_id_delta[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    vsi.seq_parameter_set_id_delta[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (View scalability info): seq_parameter_set_id_delta",
+                    vsi.seq_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+            res.append(&mut exp_golomb_encode_one(
+                vsi.num_subset_seq_parameter_sets[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): num_subset_seq_parameter_sets",
+                vsi.num_subset_seq_parameter_sets[i],
+                63,
+            );
+            for j in 0..vsi.subset_seq_parameter_set_id_delta[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    vsi.subset_seq_parameter_set_id_delta[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (View scalability info): subset_seq_parameter_set_id_delta",
+                    vsi.subset_seq_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+            res.append(&mut exp_golomb_encode_one(
+                vsi.num_pic_parameter_sets_minus1[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): num_pic_parameter_sets_minus1",
+                vsi.num_pic_parameter_sets_minus1[i],
+                63,
+            );
+            for j in 0..vsi.pic_parameter_set_id_delta[i].len() {
+                res.append(&mut exp_golomb_encode_one(
+                    vsi.pic_parameter_set_id_delta[i][j] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SEI (View scalability info): pic_parameter_set_id_delta",
+                    vsi.pic_parameter_set_id_delta[i][j],
+                    63,
+                );
+            }
+        } else {
+            res.append(&mut exp_golomb_encode_one(
+                vsi.parameter_sets_info_src_op_id[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): parameter_sets_info_src_op_id",
+                vsi.parameter_sets_info_src_op_id[i],
+                63,
+            );
+        }
+
+        if vsi.bitstream_restriction_info_present_flag[i] {
+            res.push(match vsi.motion_vectors_over_pic_boundaries_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (View scalability info): motion_vectors_over_pic_boundaries_flag",
+                vsi.motion_vectors_over_pic_boundaries_flag[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                vsi.max_bytes_per_pic_denom[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): max_bytes_per_pic_denom",
+                vsi.max_bytes_per_pic_denom[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                vsi.max_bits_per_mb_denom[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): max_bits_per_mb_denom",
+                vsi.max_bits_per_mb_denom[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                vsi.log2_max_mv_length_horizontal[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): log2_max_mv_length_horizontal",
+                vsi.log2_max_mv_length_horizontal[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                vsi.log2_max_mv_length_vertical[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): log2_max_mv_length_vertical",
+                vsi.log2_max_mv_length_vertical[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                vsi.max_num_reorder_frames[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): max_num_reorder_frames",
+                vsi.max_num_reorder_frames[i],
+                63,
+            );
+            res.append(&mut exp_golomb_encode_one(
+                vsi.max_dec_frame_buffering[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (View scalability info): max_dec_frame_buffering",
+                vsi.max_dec_frame_buffering[i],
+                63,
+            );
+        }
+    }
+
+    res
+}
+
+fn encode_multiview_scene_info(msi: &SEIMultiviewSceneInfo) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        msi.max_disparity as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Multiview scene info): max_disparity",
+        msi.max_disparity,
+        63,
+    );
+
+    res
+}
+
+/// Writes the u(v) mantissa that follows an exponent of a camera or display parameter
+fn encode_sei_mantissa(name: &str, mantissa: u64, exponent: u8, prec: u32) -> Vec<u8> {
+    let len = sei_mantissa_len(exponent, prec).min(64);
+    encoder_formatted_print(name, mantissa, 63);
+
+    (0..len)
+        .rev()
+        .map(|i| ((mantissa >> i) & 1) as u8)
+        .collect()
+}
+
+/// Writes the sign, exponent and mantissa of a camera parameter
+fn encode_sei_float_value(name: &str, value: &SEIFloatValue, prec: u32) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.push(match value.sign {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(&format!("{} sign", name), value.sign, 63);
+    res.append(&mut generate_unsigned_binary(value.exponent as u32, 6));
+    encoder_formatted_print(&format!("{} exponent", name), value.exponent, 63);
+    res.append(&mut encode_sei_mantissa(
+        &format!("{} mantissa", name),
+        value.mantissa,
+        value.exponent,
+        prec,
+    ));
+
+    res
+}
+
+/// H.13.1.5 Multiview acquisition information SEI message syntax
+fn encode_multiview_acquisition_info(mai: &SEIMultiviewAcquisitionInfo) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.append(&mut exp_golomb_encode_one(
+        mai.num_views_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Multiview acquisition info): num_views_minus1",
+        mai.num_views_minus1,
+        63,
+    );
+    res.push(match mai.intrinsic_param_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Multiview acquisition info): intrinsic_param_flag",
+        mai.intrinsic_param_flag,
+        63,
+    );
+    res.push(match mai.extrinsic_param_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Multiview acquisition info): extrinsic_param_flag",
+        mai.extrinsic_param_flag,
+        63,
+    );
+
+    if mai.intrinsic_param_flag {
+        res.push(match mai.intrinsic_params_equal_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Multiview acquisition info): intrinsic_params_equal_flag",
+            mai.intrinsic_params_equal_flag,
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            mai.prec_focal_length as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_focal_length",
+            mai.prec_focal_length,
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            mai.prec_principal_point as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_principal_point",
+            mai.prec_principal_point,
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            mai.prec_skew_factor as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_skew_factor",
+            mai.prec_skew_factor,
+            63,
+        );
+        for i in 0..mai.focal_length_x.len() {
+            res.append(&mut encode_sei_float_value(
+                "SEI (Multiview acquisition info): focal_length_x",
+                &mai.focal_length_x[i],
+                mai.prec_focal_length,
+            ));
+            res.append(&mut encode_sei_float_value(
+                "SEI (Multiview acquisition info): focal_length_y",
+                &mai.focal_length_y[i],
+                mai.prec_focal_length,
+            ));
+            res.append(&mut encode_sei_float_value(
+                "SEI (Multiview acquisition info): principal_point_x",
+                &mai.principal_point_x[i],
+                mai.prec_principal_point,
+            ));
+            res.append(&mut encode_sei_float_value(
+                "SEI (Multiview acquisition info): principal_point_y",
+                &mai.principal_point_y[i],
+                mai.prec_principal_point,
+            ));
+            res.append(&mut encode_sei_float_value(
+                "SEI (Multiview acquisition info): skew_factor",
+                &mai.skew_factor[i],
+                mai.prec_skew_factor,
+            ));
+        }
+    }
+
+    if mai.extrinsic_param_flag {
+        res.append(&mut exp_golomb_encode_one(
+            mai.prec_rotation_param as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_rotation_param",
+            mai.prec_rotation_param,
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            mai.prec_translation_param as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Multiview acquisition info): prec_translation_param",
+            mai.prec_translation_param,
+            63,
+        );
+        for i in 0..mai.r.len() {
+            for j in 0..3 {
+                for k in 0..3 {
+                    res.append(&mut encode_sei_float_value(
+                        "SEI (Multiview acquisition info): r",
+                        &mai.r[i][j][k],
+                        mai.prec_rotation_param,
+                    ));
+                }
+                res.append(&mut encode_sei_float_value(
+                    "SEI (Multiview acquisition info): t",
+                    &mai.t[i][j],
+                    mai.prec_translation_param,
+                ));
+            }
+        }
+    }
+
+    res
 }
 
-fn encode_sub_seq_info() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_non_required_view_component(nrvc: &SEINonRequiredViewComponent) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    res.append(&mut exp_golomb_encode_one(
+        nrvc.num_info_entries_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Non-required view component): num_info_entries_minus1",
+        nrvc.num_info_entries_minus1,
+        63,
+    );
+    for i in 0..nrvc.view_order_index.len() {
+        res.append(&mut exp_golomb_encode_one(
+            nrvc.view_order_index[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Non-required view component): view_order_index",
+            nrvc.view_order_index[i],
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            nrvc.num_non_required_view_components_minus1[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Non-required view component): num_non_required_view_components_minus1",
+            nrvc.num_non_required_view_components_minus1[i],
+            63,
+        );
+        for j in 0..nrvc.index_delta_minus1[i].len() {
+            res.append(&mut exp_golomb_encode_one(
+                nrvc.index_delta_minus1[i][j] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Non-required view component): index_delta_minus1",
+                nrvc.index_delta_minus1[i][j],
+                63,
+            );
+        }
+    }
+
+    res
 }
 
-fn encode_sub_seq_layer_characteristics() -> Vec<u8> {
-    let res = Vec::new();
+/// H.13.1.7 View dependency change SEI message syntax
+fn encode_view_dependency_change(vdc: &SEIViewDependencyChange) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    res.append(&mut exp_golomb_encode_one(
+        vdc.seq_parameter_set_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (View dependency change): seq_parameter_set_id",
+        vdc.seq_parameter_set_id,
+        63,
+    );
+    res.push(match vdc.anchor_update_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (View dependency change): anchor_update_flag",
+        vdc.anchor_update_flag,
+        63,
+    );
+    res.push(match vdc.non_anchor_update_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (View dependency change): non_anchor_update_flag",
+        vdc.non_anchor_update_flag,
+        63,
+    );
+    if vdc.anchor_update_flag {
+        res.append(&mut encode_view_dependency_flags(
+            "anchor",
+            &vdc.anchor_ref_l0_flag,
+            &vdc.anchor_ref_l1_flag,
+        ));
+    }
+    if vdc.non_anchor_update_flag {
+        res.append(&mut encode_view_dependency_flags(
+            "non_anchor",
+            &vdc.non_anchor_ref_l0_flag,
+            &vdc.non_anchor_ref_l1_flag,
+        ));
+    }
+
+    res
 }
 
-fn encode_sub_seq_characteristics() -> Vec<u8> {
-    let res = Vec::new();
+/// Writes the *_ref_l0_flag and _l1_flag values of views 1 and up
+fn encode_view_dependency_flags(name: &str, l0: &[Vec<bool>], l1: &[Vec<bool>]) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    for i in 1..l0.len().min(l1.len()) {
+        for flag in l0[i].iter() {
+            res.push(match flag {
+                true => 1,
+                false => 0,
+            });
+        }
+        encoder_formatted_print(
+            &format!("SEI (View dependency change): {}_ref_l0_flag", name),
+            &l0[i],
+            63,
+        );
+        for flag in l1[i].iter() {
+            res.push(match flag {
+                true => 1,
+                false => 0,
+            });
+        }
+        encoder_formatted_print(
+            &format!("SEI (View dependency change): {}_ref_l1_flag", name),
+            &l1[i],
+            63,
+        );
+    }
+
+    res
 }
 
-fn encode_full_frame_freeze() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_operation_points_not_present(opnp: &SEIOperationPointsNotPresent) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    res.append(&mut exp_golomb_encode_one(
+        opnp.num_operation_points as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Operation points not present): num_operation_points",
+        opnp.num_operation_points,
+        63,
+    );
+    for i in 0..opnp.operation_point_not_present_id.len() {
+        res.append(&mut exp_golomb_encode_one(
+            opnp.operation_point_not_present_id[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Operation points not present): operation_point_not_present_id",
+            opnp.operation_point_not_present_id[i],
+            63,
+        );
+    }
+
+    res
 }
 
-fn encode_full_frame_freeze_release() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_frame_packing_arrangement(fp: &SEIFramePacking) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    res.append(&mut exp_golomb_encode_one(
+        fp.frame_packing_arrangement_id as i32,
+        false,
+        0,
+        false,
+    ));
+    res.push(match fp.frame_packing_arrangement_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    if !fp.frame_packing_arrangement_cancel_flag {
+        res.append(&mut generate_unsigned_binary(
+            fp.frame_packing_arrangement_type as u32,
+            7,
+        ));
+        res.push(match fp.quincunx_sampling_flag {
+            true => 1,
+            false => 0,
+        });
+        res.append(&mut generate_unsigned_binary(
+            fp.content_interpretation_type as u32,
+            6,
+        ));
+        res.push(match fp.spatial_flipping_flag {
+            true => 1,
+            false => 0,
+        });
+        res.push(match fp.frame0_flipped_flag {
+            true => 1,
+            false => 0,
+        });
+        res.push(match fp.field_views_flag {
+            true => 1,
+            false => 0,
+        });
+        res.push(match fp.current_frame_is_frame0_flag {
+            true => 1,
+            false => 0,
+        });
+        res.push(match fp.frame0_self_contained_flag {
+            true => 1,
+            false => 0,
+        });
+        res.push(match fp.frame1_self_contained_flag {
+            true => 1,
+            false => 0,
+        });
+        if fp.grid_positions_present() {
+            res.append(&mut generate_unsigned_binary(
+                fp.frame0_grid_position_x as u32,
+                4,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                fp.frame0_grid_position_y as u32,
+                4,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                fp.frame1_grid_position_x as u32,
+                4,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                fp.frame1_grid_position_y as u32,
+                4,
+            ));
+        }
+        res.append(&mut generate_unsigned_binary(
+            fp.frame_packing_arrangement_reserved_byte as u32,
+            8,
+        ));
+        res.append(&mut exp_golomb_encode_one(
+            fp.frame_packing_arrangement_repetition_period as i32,
+            false,
+            0,
+            false,
+        ));
+    }
+    res.push(match fp.frame_packing_arrangement_extension_flag {
+        true => 1,
+        false => 0,
+    });
+
+    fp.encoder_pretty_print();
+
+    res
 }
 
-fn encode_full_frame_snapshot() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_multiview_view_position(mvp: &SEIMultiviewViewPosition) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
+    res.append(&mut exp_golomb_encode_one(
+        mvp.num_views_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Multiview view position): num_views_minus1",
+        mvp.num_views_minus1,
+        63,
+    );
+    for i in 0..mvp.view_position.len() {
+        res.append(&mut exp_golomb_encode_one(
+            mvp.view_position[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Multiview view position): view_position",
+            mvp.view_position[i],
+            63,
+        );
+    }
+    res.push(match mvp.multiview_view_position_extension_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Multiview view position): multiview_view_position_extension_flag",
+        mvp.multiview_view_position_extension_flag,
+        63,
+    );
+
+    res
 }
 
-fn encode_progressive_refinement_segment_start() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_display_orientation(dor: &SEIDisplayOrientation) -> Vec<u8> {
+    let mut res = Vec::new();
+
+    res.push(match dor.display_orientation_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    if !dor.display_orientation_cancel_flag {
+        res.push(match dor.hor_flip {
+            true => 1,
+            false => 0,
+        });
+        res.push(match dor.ver_flip {
+            true => 1,
+            false => 0,
+        });
+        res.append(&mut generate_unsigned_binary(
+            dor.anticlockwise_rotation,
+            16,
+        ));
+        res.append(&mut exp_golomb_encode_one(
+            dor.display_orientation_repetition_period as i32,
+            false,
+            0,
+            false,
+        ));
+        res.push(match dor.display_orientation_extension_flag {
+            true => 1,
+            false => 0,
+        });
+    }
+
+    dor.encoder_pretty_print();
 
-    return res;
+    res
 }
 
-fn encode_progressive_refinement_segment_end() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+/// I.13.1.3.1 Depth representation SEI element syntax
+fn encode_depth_representation_sei_element(
+    name: &str,
+    dre: &SEIDepthRepresentationElement,
+) -> Vec<u8> {
+    let mut res = Vec::new();
 
-fn encode_motion_constrained_slice_group_set() -> Vec<u8> {
-    let res = Vec::new();
+    res.push(match dre.da_sign_flag {
+        true => 1,
+        false => 0,
+    });
+    res.append(&mut generate_unsigned_binary(dre.da_exponent as u32, 7));
+    res.append(&mut generate_unsigned_binary(
+        dre.da_mantissa_len_minus1 as u32,
+        5,
+    ));
+    res.append(&mut generate_unsigned_binary(
+        dre.da_mantissa,
+        dre.da_mantissa_len_minus1 as usize + 1,
+    ));
+    encoder_formatted_print(name, dre, 63);
 
-    return res;
+    res
 }
 
-fn encode_film_grain_characteristics(fgc: &SEIFilmGrainCharacteristics) -> Vec<u8> {
+/// I.13.1.3 Depth representation information SEI message syntax
+fn encode_depth_representation_info(dri: &SEIDepthRepresentationInfo) -> Vec<u8> {
     let mut res = Vec::new();
 
-    res.push(match fgc.film_grain_characteristics_cancel_flag {
+    res.push(match dri.all_views_equal_flag {
         true => 1,
-        _ => 0,
+        false => 0,
     });
-
-    if !fgc.film_grain_characteristics_cancel_flag {
-        res.append(&mut generate_unsigned_binary(
-            fgc.film_grain_model_id as u32,
-            2,
+    encoder_formatted_print(
+        "SEI (Depth representation info): all_views_equal_flag",
+        dri.all_views_equal_flag,
+        63,
+    );
+    if !dri.all_views_equal_flag {
+        res.append(&mut exp_golomb_encode_one(
+            dri.num_views_minus1 as i32,
+            false,
+            0,
+            false,
         ));
-        res.push(match fgc.separate_colour_description_present_flag {
+        encoder_formatted_print(
+            "SEI (Depth representation info): num_views_minus1",
+            dri.num_views_minus1,
+            63,
+        );
+    }
+    res.push(match dri.z_near_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Depth representation info): z_near_flag",
+        dri.z_near_flag,
+        63,
+    );
+    res.push(match dri.z_far_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Depth representation info): z_far_flag",
+        dri.z_far_flag,
+        63,
+    );
+    if dri.z_near_flag || dri.z_far_flag {
+        res.push(match dri.z_axis_equal_flag {
             true => 1,
-            _ => 0,
+            false => 0,
         });
+        encoder_formatted_print(
+            "SEI (Depth representation info): z_axis_equal_flag",
+            dri.z_axis_equal_flag,
+            63,
+        );
+        if dri.z_axis_equal_flag {
+            res.append(&mut exp_golomb_encode_one(
+                dri.common_z_axis_reference_view as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Depth representation info): common_z_axis_reference_view",
+                dri.common_z_axis_reference_view,
+                63,
+            );
+        }
+    }
+    res.push(match dri.d_min_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Depth representation info): d_min_flag",
+        dri.d_min_flag,
+        63,
+    );
+    res.push(match dri.d_max_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Depth representation info): d_max_flag",
+        dri.d_max_flag,
+        63,
+    );
+    res.append(&mut exp_golomb_encode_one(
+        dri.depth_representation_type as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Depth representation info): depth_representation_type",
+        dri.depth_representation_type,
+        63,
+    );
 
-        if fgc.separate_colour_description_present_flag {
-            res.append(&mut generate_unsigned_binary(
-                fgc.film_grain_bit_depth_luma_minus8 as u32,
-                3,
+    for i in 0..dri.depth_info_view_id.len() {
+        res.append(&mut exp_golomb_encode_one(
+            dri.depth_info_view_id[i] as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Depth representation info): depth_info_view_id",
+            dri.depth_info_view_id[i],
+            63,
+        );
+        if (dri.z_near_flag || dri.z_far_flag) && !dri.z_axis_equal_flag {
+            res.append(&mut exp_golomb_encode_one(
+                dri.z_axis_reference_view[i] as i32,
+                false,
+                0,
+                false,
             ));
-            res.append(&mut generate_unsigned_binary(
-                fgc.film_grain_bit_depth_chroma_minus8 as u32,
-                3,
+            encoder_formatted_print(
+                "SEI (Depth representation info): z_axis_reference_view",
+                dri.z_axis_reference_view[i],
+                63,
+            );
+        }
+        if dri.d_min_flag || dri.d_max_flag {
+            res.append(&mut exp_golomb_encode_one(
+                dri.disparity_reference_view[i] as i32,
+                false,
+                0,
+                false,
             ));
-            res.push(match fgc.film_grain_full_range_flag {
-                true => 1,
-                _ => 0,
-            });
-            res.append(&mut generate_unsigned_binary(
-                fgc.film_grain_colour_primaries as u32,
-                8,
+            encoder_formatted_print(
+                "SEI (Depth representation info): disparity_reference_view",
+                dri.disparity_reference_view[i],
+                63,
+            );
+        }
+        if dri.z_near_flag {
+            res.append(&mut encode_depth_representation_sei_element(
+                "SEI (Depth representation info): z_near",
+                &dri.z_near[i],
             ));
-            res.append(&mut generate_unsigned_binary(
-                fgc.film_grain_transfer_characteristics as u32,
-                8,
+        }
+        if dri.z_far_flag {
+            res.append(&mut encode_depth_representation_sei_element(
+                "SEI (Depth representation info): z_far",
+                &dri.z_far[i],
             ));
-            res.append(&mut generate_unsigned_binary(
-                fgc.film_grain_matrix_coefficients as u32,
-                8,
+        }
+        if dri.d_min_flag {
+            res.append(&mut encode_depth_representation_sei_element(
+                "SEI (Depth representation info): d_min",
+                &dri.d_min[i],
             ));
         }
-
-        res.append(&mut generate_unsigned_binary(
-            fgc.blending_mode_id as u32,
-            2,
-        ));
-        res.append(&mut generate_unsigned_binary(
-            fgc.log2_scale_factor as u32,
-            4,
-        ));
-
-        for c in 0..3 {
-            res.push(match fgc.comp_model_present_flag[c] {
-                true => 1,
-                _ => 0,
-            });
+        if dri.d_max_flag {
+            res.append(&mut encode_depth_representation_sei_element(
+                "SEI (Depth representation info): d_max",
+                &dri.d_max[i],
+            ));
         }
+    }
 
-        for c in 0..3 {
-            if fgc.comp_model_present_flag[c] {
-                res.append(&mut generate_unsigned_binary(
-                    fgc.num_intensity_intervals_minus1[c] as u32,
-                    8,
-                ));
-                res.append(&mut generate_unsigned_binary(
-                    fgc.num_model_values_minus1[c] as u32,
-                    3,
-                ));
-
-                for i in 0..fgc.num_intensity_intervals_minus1[c] as usize {
-                    res.append(&mut generate_unsigned_binary(
-                        fgc.intensity_interval_lower_bound[c][i] as u32,
-                        8,
-                    ));
-                    res.append(&mut generate_unsigned_binary(
-                        fgc.intensity_interval_upper_bound[c][i] as u32,
-                        8,
-                    ));
-                    for j in 0..fgc.num_model_values_minus1[c] as usize {
-                        exp_golomb_encode_one(fgc.comp_model_value[c][i][j], true, 0, false);
-                    }
-                }
-            }
-        }
-        exp_golomb_encode_one(
-            fgc.film_grain_characteristics_repetition_period as i32,
+    if dri.depth_representation_type == 3 {
+        res.append(&mut exp_golomb_encode_one(
+            dri.depth_nonlinear_representation_num_minus1 as i32,
             false,
             0,
             false,
+        ));
+        encoder_formatted_print(
+            "SEI (Depth representation info): depth_nonlinear_representation_num_minus1",
+            dri.depth_nonlinear_representation_num_minus1,
+            63,
+        );
+        for model in dri.depth_nonlinear_representation_model.iter() {
+            res.append(&mut exp_golomb_encode_one(*model as i32, false, 0, false));
+        }
+        encoder_formatted_print(
+            "SEI (Depth representation info): depth_nonlinear_representation_model",
+            &dri.depth_nonlinear_representation_model,
+            63,
         );
     }
 
-    fgc.encoder_pretty_print();
-
     res
 }
 
-fn encode_deblocking_filter_display_preference() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-fn encode_stereo_video_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-fn encode_post_filter_hint() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-fn encode_tone_mapping_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-// specified in Annex G
-fn encode_scalability_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-// specified in Annex G
-fn encode_sub_pic_scalable_layer() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-// specified in Annex G
-fn encode_non_required_layer_rep() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-// specified in Annex G
-fn encode_priority_layer_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-// specified in Annex G
-fn encode_layers_not_present() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-// specified in Annex G
-fn encode_layer_dependency_change() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
-
-// specified in Annex G
-fn encode_scalable_nesting() -> Vec<u8> {
-    let res = Vec::new();
+/// I.13.1.4 3D reference displays information SEI message syntax
+fn encode_three_dimensional_reference_displays_info(
+    trdi: &SEIThreeDimensionalReferenceDisplaysInfo,
+) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
-}
+    res.append(&mut exp_golomb_encode_one(
+        trdi.prec_ref_baseline as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (3D reference displays info): prec_ref_baseline",
+        trdi.prec_ref_baseline,
+        63,
+    );
+    res.append(&mut exp_golomb_encode_one(
+        trdi.prec_ref_display_width as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (3D reference displays info): prec_ref_display_width",
+        trdi.prec_ref_display_width,
+        63,
+    );
+    res.push(match trdi.ref_viewing_distance_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (3D reference displays info): ref_viewing_distance_flag",
+        trdi.ref_viewing_distance_flag,
+        63,
+    );
+    if trdi.ref_viewing_distance_flag {
+        res.append(&mut exp_golomb_encode_one(
+            trdi.prec_ref_viewing_dist as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (3D reference displays info): prec_ref_viewing_dist",
+            trdi.prec_ref_viewing_dist,
+            63,
+        );
+    }
+    res.append(&mut exp_golomb_encode_one(
+        trdi.num_ref_displays_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (3D reference displays info): num_ref_displays_minus1",
+        trdi.num_ref_displays_minus1,
+        63,
+    );
 
-// specified in Annex G
-fn encode_base_layer_temporal_hrd() -> Vec<u8> {
-    let res = Vec::new();
+    for i in 0..trdi.exponent_ref_baseline.len() {
+        res.append(&mut generate_unsigned_binary(
+            trdi.exponent_ref_baseline[i] as u32,
+            6,
+        ));
+        encoder_formatted_print(
+            "SEI (3D reference displays info): exponent_ref_baseline",
+            trdi.exponent_ref_baseline[i],
+            63,
+        );
+        res.append(&mut encode_sei_mantissa(
+            "SEI (3D reference displays info): mantissa_ref_baseline",
+            trdi.mantissa_ref_baseline[i],
+            trdi.exponent_ref_baseline[i],
+            trdi.prec_ref_baseline,
+        ));
+        res.append(&mut generate_unsigned_binary(
+            trdi.exponent_ref_display_width[i] as u32,
+            6,
+        ));
+        encoder_formatted_print(
+            "SEI (3D reference displays info): exponent_ref_display_width",
+            trdi.exponent_ref_display_width[i],
+            63,
+        );
+        res.append(&mut encode_sei_mantissa(
+            "SEI (3D reference displays info): mantissa_ref_display_width",
+            trdi.mantissa_ref_display_width[i],
+            trdi.exponent_ref_display_width[i],
+            trdi.prec_ref_display_width,
+        ));
+        if trdi.ref_viewing_distance_flag {
+            res.append(&mut generate_unsigned_binary(
+                trdi.exponent_ref_viewing_distance[i] as u32,
+                6,
+            ));
+            encoder_formatted_print(
+                "SEI (3D reference displays info): exponent_ref_viewing_distance",
+                trdi.exponent_ref_viewing_distance[i],
+                63,
+            );
+            res.append(&mut encode_sei_mantissa(
+                "SEI (3D reference displays info): mantissa_ref_viewing_distance",
+                trdi.mantissa_ref_viewing_distance[i],
+                trdi.exponent_ref_viewing_distance[i],
+                trdi.prec_ref_viewing_dist,
+            ));
+        }
+        res.push(match trdi.additional_shift_present_flag[i] {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (3D reference displays info): additional_shift_present_flag",
+            trdi.additional_shift_present_flag[i],
+            63,
+        );
+        if trdi.additional_shift_present_flag[i] {
+            res.append(&mut generate_unsigned_binary(
+                trdi.num_sample_shift_plus512[i],
+                10,
+            ));
+            encoder_formatted_print(
+                "SEI (3D reference displays info): num_sample_shift_plus512",
+                trdi.num_sample_shift_plus512[i],
+                63,
+            );
+        }
+    }
+    res.push(
+        match trdi.three_dimensional_reference_displays_extension_flag {
+            true => 1,
+            false => 0,
+        },
+    );
+    encoder_formatted_print(
+        "SEI (3D reference displays info): three_dimensional_reference_displays_extension_flag",
+        trdi.three_dimensional_reference_displays_extension_flag,
+        63,
+    );
 
-    return res;
+    res
 }
 
-// specified in Annex G
-fn encode_quality_layer_integrity_check() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+/// I.13.1.5 Depth timing SEI message syntax
+fn encode_depth_timing(dt: &SEIDepthTiming) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex G
-fn encode_redundant_pic_property() -> Vec<u8> {
-    let res = Vec::new();
+    res.push(match dt.per_view_depth_timing_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Depth timing): per_view_depth_timing_flag",
+        dt.per_view_depth_timing_flag,
+        63,
+    );
+    for dto in dt.depth_timing_offset.iter() {
+        res.append(&mut generate_unsigned_binary(
+            dto.offset_len_minus1 as u32,
+            5,
+        ));
+        res.append(&mut generate_unsigned_binary(
+            dto.depth_disp_delay_offset_fp,
+            dto.offset_len_minus1 as usize + 1,
+        ));
+        res.append(&mut generate_unsigned_binary(
+            dto.depth_disp_delay_offset_dp as u32,
+            6,
+        ));
+        encoder_formatted_print("SEI (Depth timing): depth_timing_offset", dto, 63);
+    }
 
-    return res;
+    res
 }
 
-// specified in Annex G
-fn encode_tl0_dep_rep_index() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+/// I.13.1.7 Depth sampling information SEI message syntax
+fn encode_depth_sampling_info(dsi: &SEIDepthSamplingInfo) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex G
-fn encode_tl_switching_point() -> Vec<u8> {
-    let res = Vec::new();
+    res.append(&mut generate_unsigned_binary(dsi.dttsr_x_mul, 16));
+    encoder_formatted_print(
+        "SEI (Depth sampling info): dttsr_x_mul",
+        dsi.dttsr_x_mul,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(dsi.dttsr_x_dp as u32, 4));
+    encoder_formatted_print("SEI (Depth sampling info): dttsr_x_dp", dsi.dttsr_x_dp, 63);
+    res.append(&mut generate_unsigned_binary(dsi.dttsr_y_mul, 16));
+    encoder_formatted_print(
+        "SEI (Depth sampling info): dttsr_y_mul",
+        dsi.dttsr_y_mul,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(dsi.dttsr_y_dp as u32, 4));
+    encoder_formatted_print("SEI (Depth sampling info): dttsr_y_dp", dsi.dttsr_y_dp, 63);
+    res.push(match dsi.per_view_depth_grid_pos_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Depth sampling info): per_view_depth_grid_pos_flag",
+        dsi.per_view_depth_grid_pos_flag,
+        63,
+    );
+    if dsi.per_view_depth_grid_pos_flag {
+        res.append(&mut exp_golomb_encode_one(
+            dsi.num_video_plus_depth_views_minus1 as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Depth sampling info): num_video_plus_depth_views_minus1",
+            dsi.num_video_plus_depth_views_minus1,
+            63,
+        );
+    }
+    for i in 0..dsi.depth_grid_position.len() {
+        if dsi.per_view_depth_grid_pos_flag {
+            res.append(&mut exp_golomb_encode_one(
+                dsi.depth_grid_view_id[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SEI (Depth sampling info): depth_grid_view_id",
+                dsi.depth_grid_view_id[i],
+                63,
+            );
+        }
+        res.append(&mut encode_depth_grid_position(&dsi.depth_grid_position[i]));
+    }
 
-    return res;
+    res
 }
 
-// specified in Annex H
-fn encode_parallel_decoding_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+/// I.13.1.7.1 Depth grid position syntax
+fn encode_depth_grid_position(dgp: &SEIDepthGridPosition) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex H
-fn encode_mvc_scalable_nesting() -> Vec<u8> {
-    let res = Vec::new();
+    res.append(&mut generate_unsigned_binary(dgp.depth_grid_pos_x_fp, 20));
+    res.append(&mut generate_unsigned_binary(
+        dgp.depth_grid_pos_x_dp as u32,
+        4,
+    ));
+    res.push(match dgp.depth_grid_pos_x_sign_flag {
+        true => 1,
+        false => 0,
+    });
+    res.append(&mut generate_unsigned_binary(dgp.depth_grid_pos_y_fp, 20));
+    res.append(&mut generate_unsigned_binary(
+        dgp.depth_grid_pos_y_dp as u32,
+        4,
+    ));
+    res.push(match dgp.depth_grid_pos_y_sign_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print("SEI (Depth sampling info): depth_grid_position", dgp, 63);
 
-    return res;
+    res
 }
 
-// specified in Annex H
-fn encode_view_scalability_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_constrained_depth_parameter_set_identifier(
+    cdpsi: &SEIConstrainedDepthParameterSetIdentifier,
+) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex H
-fn encode_multiview_scene_info() -> Vec<u8> {
-    let res = Vec::new();
+    res.append(&mut exp_golomb_encode_one(
+        cdpsi.max_dps_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Constrained depth parameter set identifier): max_dps_id",
+        cdpsi.max_dps_id,
+        63,
+    );
+    res.append(&mut exp_golomb_encode_one(
+        cdpsi.max_dps_id_diff as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Constrained depth parameter set identifier): max_dps_id_diff",
+        cdpsi.max_dps_id_diff,
+        63,
+    );
 
-    return res;
+    res
 }
 
-// specified in Annex H
-fn encode_multiview_acquisition_info() -> Vec<u8> {
+// specified in ISO/IEC 23001-11
+fn encode_green_metadata() -> Vec<u8> {
     let res = Vec::new();
 
     return res;
 }
 
-// specified in Annex H
-fn encode_non_required_view_component() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_mastering_display_colour_volume(mdcv: &SEIMasteringDisplayColourVolume) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex H
-fn encode_view_dependency_change() -> Vec<u8> {
-    let res = Vec::new();
+    for c in 0..3 {
+        res.append(&mut generate_unsigned_binary(
+            mdcv.display_primaries_x[c],
+            16,
+        ));
+        res.append(&mut generate_unsigned_binary(
+            mdcv.display_primaries_y[c],
+            16,
+        ));
+    }
+    encoder_formatted_print(
+        "SEI (Mastering display colour volume): display_primaries_x",
+        mdcv.display_primaries_x,
+        63,
+    );
+    encoder_formatted_print(
+        "SEI (Mastering display colour volume): display_primaries_y",
+        mdcv.display_primaries_y,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(mdcv.white_point_x, 16));
+    encoder_formatted_print(
+        "SEI (Mastering display colour volume): white_point_x",
+        mdcv.white_point_x,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(mdcv.white_point_y, 16));
+    encoder_formatted_print(
+        "SEI (Mastering display colour volume): white_point_y",
+        mdcv.white_point_y,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(
+        mdcv.max_display_mastering_luminance,
+        32,
+    ));
+    encoder_formatted_print(
+        "SEI (Mastering display colour volume): max_display_mastering_luminance",
+        mdcv.max_display_mastering_luminance,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(
+        mdcv.min_display_mastering_luminance,
+        32,
+    ));
+    encoder_formatted_print(
+        "SEI (Mastering display colour volume): min_display_mastering_luminance",
+        mdcv.min_display_mastering_luminance,
+        63,
+    );
 
-    return res;
+    res
 }
 
-// specified in Annex H
-fn encode_operation_points_not_present() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+/// Writes the pre or post look-up table values of the colour remapping SEI
+fn encode_colour_remap_lut(
+    num_val_minus1: u8,
+    coded_value: &[u32],
+    target_value: &[u32],
+    coded_bits: usize,
+    target_bits: usize,
+) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex H
-fn encode_base_view_temporal_hrd() -> Vec<u8> {
-    let res = Vec::new();
+    res.append(&mut generate_unsigned_binary(num_val_minus1 as u32, 8));
+    if num_val_minus1 > 0 {
+        for i in 0..coded_value.len() {
+            res.append(&mut generate_unsigned_binary(coded_value[i], coded_bits));
+            res.append(&mut generate_unsigned_binary(target_value[i], target_bits));
+        }
+    }
 
-    return res;
+    res
 }
 
-fn encode_frame_packing_arrangement() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_colour_remapping_info(cri: &SEIColourRemappingInfo) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
-}
+    res.append(&mut exp_golomb_encode_one(
+        cri.colour_remap_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Colour remapping): colour_remap_id",
+        cri.colour_remap_id,
+        63,
+    );
+    res.push(match cri.colour_remap_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Colour remapping): colour_remap_cancel_flag",
+        cri.colour_remap_cancel_flag,
+        63,
+    );
+    if !cri.colour_remap_cancel_flag {
+        res.append(&mut exp_golomb_encode_one(
+            cri.colour_remap_repetition_period as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_repetition_period",
+            cri.colour_remap_repetition_period,
+            63,
+        );
+        res.push(match cri.colour_remap_video_signal_info_present_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_video_signal_info_present_flag",
+            cri.colour_remap_video_signal_info_present_flag,
+            63,
+        );
+        if cri.colour_remap_video_signal_info_present_flag {
+            res.push(match cri.colour_remap_full_range_flag {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(
+                "SEI (Colour remapping): colour_remap_full_range_flag",
+                cri.colour_remap_full_range_flag,
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                cri.colour_remap_primaries as u32,
+                8,
+            ));
+            encoder_formatted_print(
+                "SEI (Colour remapping): colour_remap_primaries",
+                cri.colour_remap_primaries,
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                cri.colour_remap_transfer_function as u32,
+                8,
+            ));
+            encoder_formatted_print(
+                "SEI (Colour remapping): colour_remap_transfer_function",
+                cri.colour_remap_transfer_function,
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                cri.colour_remap_matrix_coefficients as u32,
+                8,
+            ));
+            encoder_formatted_print(
+                "SEI (Colour remapping): colour_remap_matrix_coefficients",
+                cri.colour_remap_matrix_coefficients,
+                63,
+            );
+        }
+        res.append(&mut generate_unsigned_binary(
+            cri.colour_remap_input_bit_depth as u32,
+            8,
+        ));
+        encoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_input_bit_depth",
+            cri.colour_remap_input_bit_depth,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            cri.colour_remap_output_bit_depth as u32,
+            8,
+        ));
+        encoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_output_bit_depth",
+            cri.colour_remap_output_bit_depth,
+            63,
+        );
 
-// specified in Annex H
-fn encode_multiview_view_position() -> Vec<u8> {
-    let res = Vec::new();
+        let input_bits = (((cri.colour_remap_input_bit_depth as usize) + 7) >> 3) << 3;
+        let output_bits = (((cri.colour_remap_output_bit_depth as usize) + 7) >> 3) << 3;
+        for c in 0..3 {
+            res.append(&mut encode_colour_remap_lut(
+                cri.pre_lut_num_val_minus1[c],
+                &cri.pre_lut_coded_value[c],
+                &cri.pre_lut_target_value[c],
+                input_bits,
+                output_bits,
+            ));
+        }
+        encoder_formatted_print(
+            "SEI (Colour remapping): pre_lut_num_val_minus1",
+            cri.pre_lut_num_val_minus1,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Colour remapping): pre_lut_coded_value",
+            &cri.pre_lut_coded_value,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Colour remapping): pre_lut_target_value",
+            &cri.pre_lut_target_value,
+            63,
+        );
 
-    return res;
-}
+        res.push(match cri.colour_remap_matrix_present_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Colour remapping): colour_remap_matrix_present_flag",
+            cri.colour_remap_matrix_present_flag,
+            63,
+        );
+        if cri.colour_remap_matrix_present_flag {
+            res.append(&mut generate_unsigned_binary(
+                cri.log2_matrix_denom as u32,
+                4,
+            ));
+            encoder_formatted_print(
+                "SEI (Colour remapping): log2_matrix_denom",
+                cri.log2_matrix_denom,
+                63,
+            );
+            for c in 0..3 {
+                for i in 0..3 {
+                    res.append(&mut exp_golomb_encode_one(
+                        cri.colour_remap_coeffs[c][i],
+                        true,
+                        0,
+                        false,
+                    ));
+                }
+            }
+            encoder_formatted_print(
+                "SEI (Colour remapping): colour_remap_coeffs",
+                cri.colour_remap_coeffs,
+                63,
+            );
+        }
 
-fn encode_display_orientation() -> Vec<u8> {
-    let res = Vec::new();
+        for c in 0..3 {
+            res.append(&mut encode_colour_remap_lut(
+                cri.post_lut_num_val_minus1[c],
+                &cri.post_lut_coded_value[c],
+                &cri.post_lut_target_value[c],
+                output_bits,
+                output_bits,
+            ));
+        }
+        encoder_formatted_print(
+            "SEI (Colour remapping): post_lut_num_val_minus1",
+            cri.post_lut_num_val_minus1,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Colour remapping): post_lut_coded_value",
+            &cri.post_lut_coded_value,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Colour remapping): post_lut_target_value",
+            &cri.post_lut_target_value,
+            63,
+        );
+    }
 
-    return res;
+    res
 }
 
-// specified in Annex I
-fn encode_mvcd_scalable_nesting() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_content_light_level_info(clli: &SEIContentLightLevelInfo) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex I
-fn encode_mvcd_view_scalability_info() -> Vec<u8> {
-    let res = Vec::new();
+    res.append(&mut generate_unsigned_binary(
+        clli.max_content_light_level,
+        16,
+    ));
+    encoder_formatted_print(
+        "SEI (Content light level): max_content_light_level",
+        clli.max_content_light_level,
+        63,
+    );
+    res.append(&mut generate_unsigned_binary(
+        clli.max_pic_average_light_level,
+        16,
+    ));
+    encoder_formatted_print(
+        "SEI (Content light level): max_pic_average_light_level",
+        clli.max_pic_average_light_level,
+        63,
+    );
 
-    return res;
+    res
 }
 
-// specified in Annex I
-fn encode_depth_representation_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_alternative_transfer_characteristics(
+    atc: &SEIAlternativeTransferCharacteristics,
+) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex I
-fn encode_three_dimensional_reference_displays_info() -> Vec<u8> {
-    let res = Vec::new();
+    res.append(&mut generate_unsigned_binary(
+        atc.preferred_transfer_characteristics as u32,
+        8,
+    ));
+    encoder_formatted_print(
+        "SEI (Alternative transfer characteristics): preferred_transfer_characteristics",
+        atc.preferred_transfer_characteristics,
+        63,
+    );
 
-    return res;
+    res
 }
 
-// specified in Annex I
-fn encode_depth_timing() -> Vec<u8> {
+fn encode_ambient_viewing_environment() -> Vec<u8> {
     let res = Vec::new();
 
     return res;
 }
 
-// specified in Annex I
-fn encode_depth_sampling_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_content_colour_volume(ccv: &SEIContentColourVolume) -> Vec<u8> {
+    let mut res = Vec::new();
 
-// specified in Annex J
-fn encode_constrained_depth_parameter_set_identifier() -> Vec<u8> {
-    let res = Vec::new();
+    res.push(match ccv.ccv_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Content colour volume): ccv_cancel_flag",
+        ccv.ccv_cancel_flag,
+        63,
+    );
+    if !ccv.ccv_cancel_flag {
+        for (name, flag) in [
+            ("ccv_persistence_flag", ccv.ccv_persistence_flag),
+            ("ccv_primaries_present_flag", ccv.ccv_primaries_present_flag),
+            (
+                "ccv_min_luminance_value_present_flag",
+                ccv.ccv_min_luminance_value_present_flag,
+            ),
+            (
+                "ccv_max_luminance_value_present_flag",
+                ccv.ccv_max_luminance_value_present_flag,
+            ),
+            (
+                "ccv_avg_luminance_value_present_flag",
+                ccv.ccv_avg_luminance_value_present_flag,
+            ),
+        ] {
+            res.push(match flag {
+                true => 1,
+                false => 0,
+            });
+            encoder_formatted_print(&format!("SEI (Content colour volume): {}", name), flag, 63);
+        }
+        res.append(&mut generate_unsigned_binary(
+            ccv.ccv_reserved_zero_2bits as u32,
+            2,
+        ));
+        encoder_formatted_print(
+            "SEI (Content colour volume): ccv_reserved_zero_2bits",
+            ccv.ccv_reserved_zero_2bits,
+            63,
+        );
+        if ccv.ccv_primaries_present_flag {
+            for c in 0..3 {
+                res.append(&mut generate_unsigned_binary(
+                    ccv.ccv_primaries_x[c] as u32,
+                    32,
+                ));
+                res.append(&mut generate_unsigned_binary(
+                    ccv.ccv_primaries_y[c] as u32,
+                    32,
+                ));
+            }
+            encoder_formatted_print(
+                "SEI (Content colour volume): ccv_primaries_x",
+                ccv.ccv_primaries_x,
+                63,
+            );
+            encoder_formatted_print(
+                "SEI (Content colour volume): ccv_primaries_y",
+                ccv.ccv_primaries_y,
+                63,
+            );
+        }
+        if ccv.ccv_min_luminance_value_present_flag {
+            res.append(&mut generate_unsigned_binary(
+                ccv.ccv_min_luminance_value,
+                32,
+            ));
+            encoder_formatted_print(
+                "SEI (Content colour volume): ccv_min_luminance_value",
+                ccv.ccv_min_luminance_value,
+                63,
+            );
+        }
+        if ccv.ccv_max_luminance_value_present_flag {
+            res.append(&mut generate_unsigned_binary(
+                ccv.ccv_max_luminance_value,
+                32,
+            ));
+            encoder_formatted_print(
+                "SEI (Content colour volume): ccv_max_luminance_value",
+                ccv.ccv_max_luminance_value,
+                63,
+            );
+        }
+        if ccv.ccv_avg_luminance_value_present_flag {
+            res.append(&mut generate_unsigned_binary(
+                ccv.ccv_avg_luminance_value,
+                32,
+            ));
+            encoder_formatted_print(
+                "SEI (Content colour volume): ccv_avg_luminance_value",
+                ccv.ccv_avg_luminance_value,
+                63,
+            );
+        }
+    }
 
-    return res;
+    res
 }
 
-// specified in ISO/IEC 23001-11
-fn encode_green_metadata() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_equirectangular_projection(erp: &SEIEquirectangularProjection) -> Vec<u8> {
+    let mut res = Vec::new();
 
-fn encode_mastering_display_colour_volume() -> Vec<u8> {
-    let res = Vec::new();
+    res.push(match erp.erp_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Equirectangular projection): erp_cancel_flag",
+        erp.erp_cancel_flag,
+        63,
+    );
+    if !erp.erp_cancel_flag {
+        res.push(match erp.erp_persistence_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Equirectangular projection): erp_persistence_flag",
+            erp.erp_persistence_flag,
+            63,
+        );
+        res.push(match erp.erp_padding_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Equirectangular projection): erp_padding_flag",
+            erp.erp_padding_flag,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            erp.erp_reserved_zero_2bits as u32,
+            2,
+        ));
+        encoder_formatted_print(
+            "SEI (Equirectangular projection): erp_reserved_zero_2bits",
+            erp.erp_reserved_zero_2bits,
+            63,
+        );
+        if erp.erp_padding_flag {
+            res.append(&mut generate_unsigned_binary(erp.gb_erp_type as u32, 3));
+            encoder_formatted_print(
+                "SEI (Equirectangular projection): gb_erp_type",
+                erp.gb_erp_type,
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                erp.left_gb_erp_width as u32,
+                8,
+            ));
+            encoder_formatted_print(
+                "SEI (Equirectangular projection): left_gb_erp_width",
+                erp.left_gb_erp_width,
+                63,
+            );
+            res.append(&mut generate_unsigned_binary(
+                erp.right_gb_erp_width as u32,
+                8,
+            ));
+            encoder_formatted_print(
+                "SEI (Equirectangular projection): right_gb_erp_width",
+                erp.right_gb_erp_width,
+                63,
+            );
+        }
+    }
 
-    return res;
+    res
 }
 
-fn encode_colour_remapping_info() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_cubemap_projection(cmp: &SEICubemapProjection) -> Vec<u8> {
+    let mut res = Vec::new();
 
-fn encode_content_light_level_info() -> Vec<u8> {
-    let res = Vec::new();
+    res.push(match cmp.cmp_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Cubemap projection): cmp_cancel_flag",
+        cmp.cmp_cancel_flag,
+        63,
+    );
+    if !cmp.cmp_cancel_flag {
+        res.push(match cmp.cmp_persistence_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Cubemap projection): cmp_persistence_flag",
+            cmp.cmp_persistence_flag,
+            63,
+        );
+    }
 
-    return res;
+    res
 }
 
-fn encode_alternative_transfer_characteristics() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_sphere_rotation(sr: &SEISphereRotation) -> Vec<u8> {
+    let mut res = Vec::new();
 
-fn encode_ambient_viewing_environment() -> Vec<u8> {
-    let res = Vec::new();
+    res.push(match sr.sphere_rotation_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Sphere rotation): sphere_rotation_cancel_flag",
+        sr.sphere_rotation_cancel_flag,
+        63,
+    );
+    if !sr.sphere_rotation_cancel_flag {
+        res.push(match sr.sphere_rotation_persistence_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Sphere rotation): sphere_rotation_persistence_flag",
+            sr.sphere_rotation_persistence_flag,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            sr.sphere_rotation_reserved_zero_6bits as u32,
+            6,
+        ));
+        encoder_formatted_print(
+            "SEI (Sphere rotation): sphere_rotation_reserved_zero_6bits",
+            sr.sphere_rotation_reserved_zero_6bits,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(sr.yaw_rotation as u32, 32));
+        encoder_formatted_print("SEI (Sphere rotation): yaw_rotation", sr.yaw_rotation, 63);
+        res.append(&mut generate_unsigned_binary(sr.pitch_rotation as u32, 32));
+        encoder_formatted_print(
+            "SEI (Sphere rotation): pitch_rotation",
+            sr.pitch_rotation,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(sr.roll_rotation as u32, 32));
+        encoder_formatted_print("SEI (Sphere rotation): roll_rotation", sr.roll_rotation, 63);
+    }
 
-    return res;
+    res
 }
 
-fn encode_content_colour_volume() -> Vec<u8> {
-    let res = Vec::new();
+fn encode_regionwise_packing(rwp: &SEIRegionwisePacking) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
-}
+    res.push(match rwp.rwp_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Region-wise packing): rwp_cancel_flag",
+        rwp.rwp_cancel_flag,
+        63,
+    );
+    if !rwp.rwp_cancel_flag {
+        res.push(match rwp.rwp_persistence_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Region-wise packing): rwp_persistence_flag",
+            rwp.rwp_persistence_flag,
+            63,
+        );
+        res.push(match rwp.constituent_picture_matching_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Region-wise packing): constituent_picture_matching_flag",
+            rwp.constituent_picture_matching_flag,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            rwp.rwp_reserved_zero_5bits as u32,
+            5,
+        ));
+        encoder_formatted_print(
+            "SEI (Region-wise packing): rwp_reserved_zero_5bits",
+            rwp.rwp_reserved_zero_5bits,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            rwp.num_packed_regions as u32,
+            8,
+        ));
+        encoder_formatted_print(
+            "SEI (Region-wise packing): num_packed_regions",
+            rwp.num_packed_regions,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(rwp.proj_picture_width, 32));
+        encoder_formatted_print(
+            "SEI (Region-wise packing): proj_picture_width",
+            rwp.proj_picture_width,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(rwp.proj_picture_height, 32));
+        encoder_formatted_print(
+            "SEI (Region-wise packing): proj_picture_height",
+            rwp.proj_picture_height,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(rwp.packed_picture_width, 16));
+        encoder_formatted_print(
+            "SEI (Region-wise packing): packed_picture_width",
+            rwp.packed_picture_width,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(rwp.packed_picture_height, 16));
+        encoder_formatted_print(
+            "SEI (Region-wise packing): packed_picture_height",
+            rwp.packed_picture_height,
+            63,
+        );
 
-fn encode_equirectangular_projection() -> Vec<u8> {
-    let res = Vec::new();
+        // the region vectors may be shorter than num_packed_regions when the
+        // decoded stream was truncated, so only write what is available
+        for i in 0..rwp.rwp_transform_type.len() {
+            res.append(&mut generate_unsigned_binary(
+                rwp.rwp_reserved_zero_4bits[i] as u32,
+                4,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                rwp.rwp_transform_type[i] as u32,
+                3,
+            ));
+            res.push(match rwp.rwp_guard_band_flag[i] {
+                true => 1,
+                false => 0,
+            });
+            res.append(&mut generate_unsigned_binary(rwp.proj_region_width[i], 32));
+            res.append(&mut generate_unsigned_binary(rwp.proj_region_height[i], 32));
+            res.append(&mut generate_unsigned_binary(rwp.proj_region_top[i], 32));
+            res.append(&mut generate_unsigned_binary(rwp.proj_region_left[i], 32));
+            res.append(&mut generate_unsigned_binary(
+                rwp.packed_region_width[i],
+                16,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                rwp.packed_region_height[i],
+                16,
+            ));
+            res.append(&mut generate_unsigned_binary(rwp.packed_region_top[i], 16));
+            res.append(&mut generate_unsigned_binary(rwp.packed_region_left[i], 16));
+            if rwp.rwp_guard_band_flag[i] {
+                res.append(&mut generate_unsigned_binary(
+                    rwp.rwp_left_guard_band_width[i] as u32,
+                    8,
+                ));
+                res.append(&mut generate_unsigned_binary(
+                    rwp.rwp_right_guard_band_width[i] as u32,
+                    8,
+                ));
+                res.append(&mut generate_unsigned_binary(
+                    rwp.rwp_top_guard_band_height[i] as u32,
+                    8,
+                ));
+                res.append(&mut generate_unsigned_binary(
+                    rwp.rwp_bottom_guard_band_height[i] as u32,
+                    8,
+                ));
+                res.push(match rwp.rwp_guard_band_not_used_for_pred_flag[i] {
+                    true => 1,
+                    false => 0,
+                });
+                for j in 0..4 {
+                    res.append(&mut generate_unsigned_binary(
+                        rwp.rwp_guard_band_type[i][j] as u32,
+                        3,
+                    ));
+                }
+                res.append(&mut generate_unsigned_binary(
+                    rwp.rwp_guard_band_reserved_zero_3bits[i] as u32,
+                    3,
+                ));
+            }
+        }
+        encoder_formatted_print(
+            "SEI (Region-wise packing): rwp_transform_type",
+            &rwp.rwp_transform_type,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Region-wise packing): rwp_guard_band_flag",
+            &rwp.rwp_guard_band_flag,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Region-wise packing): proj_region_width",
+            &rwp.proj_region_width,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Region-wise packing): proj_region_height",
+            &rwp.proj_region_height,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Region-wise packing): packed_region_width",
+            &rwp.packed_region_width,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Region-wise packing): packed_region_height",
+            &rwp.packed_region_height,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Region-wise packing): rwp_guard_band_type",
+            &rwp.rwp_guard_band_type,
+            63,
+        );
+    }
 
-    return res;
+    res
 }
 
-fn encode_cubemap_projection() -> Vec<u8> {
-    let res = Vec::new();
-
-    return res;
-}
+fn encode_omni_viewport(ov: &SEIOmniViewport) -> Vec<u8> {
+    let mut res = Vec::new();
 
-fn encode_sphere_rotation() -> Vec<u8> {
-    let res = Vec::new();
+    res.append(&mut generate_unsigned_binary(ov.omni_viewport_id, 10));
+    encoder_formatted_print(
+        "SEI (Omnidirectional viewport): omni_viewport_id",
+        ov.omni_viewport_id,
+        63,
+    );
+    res.push(match ov.omni_viewport_cancel_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Omnidirectional viewport): omni_viewport_cancel_flag",
+        ov.omni_viewport_cancel_flag,
+        63,
+    );
+    if !ov.omni_viewport_cancel_flag {
+        res.push(match ov.omni_viewport_persistence_flag {
+            true => 1,
+            false => 0,
+        });
+        encoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_persistence_flag",
+            ov.omni_viewport_persistence_flag,
+            63,
+        );
+        res.append(&mut generate_unsigned_binary(
+            ov.omni_viewport_cnt_minus1 as u32,
+            4,
+        ));
+        encoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_cnt_minus1",
+            ov.omni_viewport_cnt_minus1,
+            63,
+        );
+        for i in 0..ov.omni_viewport_azimuth_centre.len() {
+            res.append(&mut generate_unsigned_binary(
+                ov.omni_viewport_azimuth_centre[i] as u32,
+                32,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                ov.omni_viewport_elevation_centre[i] as u32,
+                32,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                ov.omni_viewport_tilt_centre[i] as u32,
+                32,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                ov.omni_viewport_hor_range[i],
+                32,
+            ));
+            res.append(&mut generate_unsigned_binary(
+                ov.omni_viewport_ver_range[i],
+                32,
+            ));
+        }
+        encoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_azimuth_centre",
+            &ov.omni_viewport_azimuth_centre,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_elevation_centre",
+            &ov.omni_viewport_elevation_centre,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_tilt_centre",
+            &ov.omni_viewport_tilt_centre,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_hor_range",
+            &ov.omni_viewport_hor_range,
+            63,
+        );
+        encoder_formatted_print(
+            "SEI (Omnidirectional viewport): omni_viewport_ver_range",
+            &ov.omni_viewport_ver_range,
+            63,
+        );
+    }
 
-    return res;
+    res
 }
 
-fn encode_regionwise_packing() -> Vec<u8> {
-    let res = Vec::new();
+/// I.13.1.6 Alternative depth information SEI message syntax
+fn encode_alternative_depth_info(adi: &SEIAlternativeDepthInfo) -> Vec<u8> {
+    let mut res = Vec::new();
 
-    return res;
-}
+    res.append(&mut exp_golomb_encode_one(
+        adi.depth_type as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Alternative depth info): depth_type",
+        adi.depth_type,
+        63,
+    );
+    if adi.depth_type != 0 {
+        return res;
+    }
 
-fn encode_omni_viewport() -> Vec<u8> {
-    let res = Vec::new();
+    res.append(&mut exp_golomb_encode_one(
+        adi.num_constituent_views_gvd_minus1 as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(
+        "SEI (Alternative depth info): num_constituent_views_gvd_minus1",
+        adi.num_constituent_views_gvd_minus1,
+        63,
+    );
+    res.push(match adi.depth_present_gvd_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Alternative depth info): depth_present_gvd_flag",
+        adi.depth_present_gvd_flag,
+        63,
+    );
+    res.push(match adi.z_gvd_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Alternative depth info): z_gvd_flag",
+        adi.z_gvd_flag,
+        63,
+    );
+    res.push(match adi.intrinsic_param_gvd_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Alternative depth info): intrinsic_param_gvd_flag",
+        adi.intrinsic_param_gvd_flag,
+        63,
+    );
+    res.push(match adi.rotation_gvd_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Alternative depth info): rotation_gvd_flag",
+        adi.rotation_gvd_flag,
+        63,
+    );
+    res.push(match adi.translation_gvd_flag {
+        true => 1,
+        false => 0,
+    });
+    encoder_formatted_print(
+        "SEI (Alternative depth info): translation_gvd_flag",
+        adi.translation_gvd_flag,
+        63,
+    );
 
-    return res;
-}
+    if adi.z_gvd_flag {
+        for i in 0..adi.gvd_z_near.len() {
+            res.append(&mut encode_depth_representation_sei_element(
+                "SEI (Alternative depth info): gvd_z_near",
+                &adi.gvd_z_near[i],
+            ));
+            res.append(&mut encode_depth_representation_sei_element(
+                "SEI (Alternative depth info): gvd_z_far",
+                &adi.gvd_z_far[i],
+            ));
+        }
+    }
+    if adi.intrinsic_param_gvd_flag {
+        res.append(&mut exp_golomb_encode_one(
+            adi.prec_gvd_focal_length as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Alternative depth info): prec_gvd_focal_length",
+            adi.prec_gvd_focal_length,
+            63,
+        );
+        res.append(&mut exp_golomb_encode_one(
+            adi.prec_gvd_principal_point as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Alternative depth info): prec_gvd_principal_point",
+            adi.prec_gvd_principal_point,
+            63,
+        );
+    }
+    if adi.rotation_gvd_flag {
+        res.append(&mut exp_golomb_encode_one(
+            adi.prec_gvd_rotation_param as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Alternative depth info): prec_gvd_rotation_param",
+            adi.prec_gvd_rotation_param,
+            63,
+        );
+    }
+    if adi.translation_gvd_flag {
+        res.append(&mut exp_golomb_encode_one(
+            adi.prec_gvd_translation_param as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SEI (Alternative depth info): prec_gvd_translation_param",
+            adi.prec_gvd_translation_param,
+            63,
+        );
+    }
 
-// specified in Annex I
-fn encode_alternative_depth_info() -> Vec<u8> {
-    let res = Vec::new();
+    for i in 0..adi.gvd_t_x.len() {
+        if adi.intrinsic_param_gvd_flag {
+            res.append(&mut encode_sei_float_value(
+                "SEI (Alternative depth info): gvd_focal_length_x",
+                &adi.gvd_focal_length_x[i],
+                adi.prec_gvd_focal_length,
+            ));
+            res.append(&mut encode_sei_float_value(
+                "SEI (Alternative depth info): gvd_focal_length_y",
+                &adi.gvd_focal_length_y[i],
+                adi.prec_gvd_focal_length,
+            ));
+            res.append(&mut encode_sei_float_value(
+                "SEI (Alternative depth info): gvd_principal_point_x",
+                &adi.gvd_principal_point_x[i],
+                adi.prec_gvd_principal_point,
+            ));
+            res.append(&mut encode_sei_float_value(
+                "SEI (Alternative depth info): gvd_principal_point_y",
+                &adi.gvd_principal_point_y[i],
+                adi.prec_gvd_principal_point,
+            ));
+        }
+        if adi.rotation_gvd_flag {
+            for row in adi.gvd_r[i].iter() {
+                for value in row.iter() {
+                    res.append(&mut encode_sei_float_value(
+                        "SEI (Alternative depth info): gvd_r",
+                        value,
+                        adi.prec_gvd_rotation_param,
+                    ));
+                }
+            }
+        }
+        if adi.translation_gvd_flag {
+            res.append(&mut encode_sei_float_value(
+                "SEI (Alternative depth info): gvd_t_x",
+                &adi.gvd_t_x[i],
+                adi.prec_gvd_translation_param,
+            ));
+        }
+    }
 
-    return res;
+    res
 }
 
 fn encode_sei_manifest() -> Vec<u8> {
@@ -1386,3 +6286,105 @@ fn encode_reserved_sei_message() -> Vec<u8> {
 
     return res;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::data_structures::H264DecodedStream;
+    use crate::common::helper::ByteStream;
+    use crate::decoder::sei::decode_sei_message;
+    use crate::vidgen::film::FilmState;
+    use crate::vidgen::generate_configurations::RandomBoolRange;
+    use crate::vidgen::generate_configurations::RandomI32Range;
+    use crate::vidgen::generate_configurations::RandomSEIRange;
+    use crate::vidgen::generate_configurations::RandomU32Enum;
+    use crate::vidgen::generate_configurations::RandomU32Range;
+    use crate::vidgen::sei::random_sei;
+
+    #[test]
+    fn test_sei_raw_payload_and_size_mismatch_passthrough() {
+        // a reserved payload, a recovery point followed by an extension byte, and a
+        // reserved payload whose payload_size runs past the end of the NALU
+        let sei_bytes = vec![
+            100, 3, 0xde, 0xad, 0xbe, 6, 2, 0x84, 0x5a, 100, 9, 0x01, 0x02, 0x80,
+        ];
+        let sei = decode_sei_message(
+            &Vec::new(),
+            &Vec::new(),
+            &mut ByteStream::new(sei_bytes.clone()),
+        )
+        .unwrap();
+        assert_eq!(sei.payload_type, vec![100, 6, 100]);
+        assert_eq!(sei.payload[0].raw_payload_byte, vec![0xde, 0xad, 0xbe]);
+        assert!(sei.payload[1].available);
+        assert_eq!(sei.payload[1].raw_payload_byte, vec![0x5a]);
+        assert_eq!(sei.payload[2].raw_payload_byte, vec![0x01, 0x02]);
+        assert_eq!(sei.payload[2].payload_size_delta, 7);
+        assert_eq!(encode_sei_message(&sei, &[], true), sei_bytes);
+
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        rconfig.payload_type = RandomU32Enum::new(vec![6]);
+        rconfig.random_payload_size_range.payload_size_mismatch = RandomBoolRange::new(1, 1, 1);
+        rconfig.random_payload_size_range.payload_size_delta = RandomI32Range::new(3, 3);
+        let mut film = FilmState::setup_film_from_seed(17);
+        let mut ds = H264DecodedStream::new();
+        ds.seis.push(SEINalu::new());
+        random_sei(0, &rconfig, &mut ds, &mut film);
+
+        let encoded = encode_sei_message(&ds.seis[0], &[], true);
+        // payload type, payload size, payload, rbsp trailing bits
+        let payload_len = encoded.len() - 3;
+        assert_eq!(encoded[0], 6);
+        assert_eq!(encoded[1] as usize, payload_len + 3);
+    }
+
+    #[test]
+    fn test_sei_edited_payload_size() {
+        // a recovery point whose payload_size is edited in the JSON of the decoded SEI
+        let sei_bytes = vec![6, 1, 0x84, 0x80];
+        let sei =
+            decode_sei_message(&Vec::new(), &Vec::new(), &mut ByteStream::new(sei_bytes)).unwrap();
+        let json = serde_json::to_string(&sei).unwrap();
+        assert!(json.contains("\"payload_size\":[1]"));
+        let edited: SEINalu =
+            serde_json::from_str(&json.replace("\"payload_size\":[1]", "\"payload_size\":[5]"))
+                .unwrap();
+
+        let encoded = encode_sei_message(&edited, &[], true);
+        assert_eq!(encoded, vec![6, 5, 0x84, 0x80]);
+
+        let decoded =
+            decode_sei_message(&Vec::new(), &Vec::new(), &mut ByteStream::new(encoded)).unwrap();
+        assert_eq!(decoded.payload_size, vec![5]);
+        assert!(decoded.payload[0].available);
+        assert_eq!(decoded.payload[0].recovery_point.recovery_frame_cnt, 0);
+        assert_eq!(decoded.payload[0].payload_size_delta, 4);
+    }
+
+    #[test]
+    fn test_sei_payload_type_and_size_ladders() {
+        // each value that is a multiple of 255 needs a terminating 0x00 byte
+        let mut sei = SEINalu::new();
+        for (payload_type, payload_size) in [(100, 255), (100, 510), (255, 2)] {
+            let mut payload = SEIPayload::new();
+            payload.raw_payload_byte = vec![0x5a; payload_size];
+            sei.payload_type.push(payload_type);
+            sei.payload_size.push(payload_size as u32);
+            sei.payload.push(payload);
+        }
+
+        let encoded = encode_sei_message(&sei, &[], true);
+        assert_eq!(encoded[..3], [100, 0xff, 0x00]);
+        assert_eq!(encoded[3 + 255..3 + 255 + 4], [100, 0xff, 0xff, 0x00]);
+        assert_eq!(encoded[7 + 255 + 510..7 + 255 + 510 + 3], [0xff, 0x00, 2]);
+
+        let decoded =
+            decode_sei_message(&Vec::new(), &Vec::new(), &mut ByteStream::new(encoded)).unwrap();
+        assert_eq!(decoded.payload_type, sei.payload_type);
+        assert_eq!(decoded.payload_size, sei.payload_size);
+        for (a, b) in decoded.payload.iter().zip(sei.payload.iter()) {
+            assert_eq!(a.raw_payload_byte, b.raw_payload_byte);
+        }
+    }
+}
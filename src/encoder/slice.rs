@@ -6,15 +6,18 @@ use crate::common::data_structures::NALUheader;
 use crate::common::data_structures::PicParameterSet;
 use crate::common::data_structures::SeqParameterSet;
 use crate::common::data_structures::Slice;
+use crate::common::data_structures::SliceDataPartition;
 use crate::common::data_structures::SliceHeader;
 use crate::common::data_structures::SubsetSPS;
 use crate::common::data_structures::VideoParameters;
 use crate::common::helper::bitstream_to_bytestream;
 use crate::common::helper::encoder_formatted_print;
 use crate::common::helper::is_slice_type;
+use crate::common::helper::slice_group_change_cycle_len;
 use crate::encoder::cabac;
 use crate::encoder::expgolomb::exp_golomb_encode_one;
 use crate::encoder::macroblock::encode_macroblock;
+use crate::encoder::macroblock::ResidualPartitionBits;
 use log::debug;
 
 use super::binarization_functions::generate_unsigned_binary;
@@ -634,6 +637,18 @@ fn encode_slice_header(
     bitstream_array.append(&mut res);
     encoder_formatted_print("SH: pic_parameter_set_id", sh.pic_parameter_set_id, 63);
 
+    encode_slice_header_body(bitstream_array, nh, sh, s, p, vp);
+}
+
+/// Follows section 7.3.3 from colour_plane_id onwards; shared with slice_header_in_3davc_extension() (J.7.3.3.4)
+fn encode_slice_header_body(
+    bitstream_array: &mut Vec<u8>,
+    nh: &NALUheader,
+    sh: &SliceHeader,
+    s: &SeqParameterSet,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+) {
     if s.separate_colour_plane_flag {
         let mut res = vec![(sh.colour_plane_id & 2) >> 1, sh.colour_plane_id & 1]; // 2-bit unsigned values
         bitstream_array.append(&mut res);
@@ -888,12 +903,10 @@ fn encode_slice_header(
     }
 
     if p.num_slice_groups_minus1 > 0 && p.slice_group_map_type >= 3 && p.slice_group_map_type <= 5 {
-        // number of bits defined by equation 7-35
-        let bits_to_write = (((p.pic_size_in_map_units_minus1 + 1)
-            / (p.slice_group_change_rate_minus1 + 1)
-            + 1) as f64)
-            .log2()
-            .ceil() as usize;
+        let bits_to_write = slice_group_change_cycle_len(
+            vp.pic_size_in_map_units,
+            p.slice_group_change_rate_minus1,
+        );
 
         let mut slice_group_change_cycle: Vec<u8> =
             generate_unsigned_binary(sh.slice_group_change_cycle, bits_to_write);
@@ -905,13 +918,6 @@ fn encode_slice_header(
             63,
         );
     }
-
-    // padding bit is 1 due to cabac_alignment_one_bit
-    if p.entropy_coding_mode_flag {
-        while bitstream_array.len() % 8 != 0 {
-            bitstream_array.push(1);
-        }
-    }
 }
 
 /// Encodes the Slice Data, consisting of MacroBlocks and various flags
@@ -922,7 +928,15 @@ fn encode_slice_data(
     p: &PicParameterSet,
     vp: &VideoParameters,
     silent_mode: bool,
+    mut partitions: Option<&mut ResidualPartitionBits>,
 ) {
+    // padding bit is 1 due to cabac_alignment_one_bit
+    if p.entropy_coding_mode_flag {
+        while !bitstream_array.len().is_multiple_of(8) {
+            bitstream_array.push(1);
+        }
+    }
+
     // Set the bin_counts_in_nal_units to 0 for now
     let mut cs = cabac::initialize_state(0);
 
@@ -1182,7 +1196,16 @@ fn encode_slice_data(
                 );
             }
             // encode each macroblock
-            encode_macroblock(bitstream_array, mb, slice, s, p, vp, &mut cs);
+            encode_macroblock(
+                bitstream_array,
+                mb,
+                slice,
+                s,
+                p,
+                vp,
+                &mut cs,
+                partitions.as_deref_mut(),
+            );
         }
 
         if p.entropy_coding_mode_flag {
@@ -1225,7 +1248,7 @@ pub fn encode_slice(
     // encode the header
     encode_slice_header(&mut bitstream_array, nh, &slice.sh, s, p, vp);
     // encode the macroblocks
-    encode_slice_data(&mut bitstream_array, slice, s, p, vp, silent_mode);
+    encode_slice_data(&mut bitstream_array, slice, s, p, vp, silent_mode, None);
 
     //let l = res.len();
     //res[l - 1] = res[l - 1] | 1; // set the last bit to 1
@@ -1237,6 +1260,115 @@ pub fn encode_slice(
     bitstream_to_bytestream(bitstream_array, 0)
 }
 
+/// Encodes the syntax elements that start partitions B and C
+fn encode_slice_data_partition_bc_header(
+    bitstream_array: &mut Vec<u8>,
+    sdp: &SliceDataPartition,
+    s: &SeqParameterSet,
+    p: &PicParameterSet,
+    name: &str,
+) {
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        sdp.slice_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print(&format!("SDP {}: slice_id", name), sdp.slice_id, 63);
+
+    if s.separate_colour_plane_flag {
+        bitstream_array.append(&mut generate_unsigned_binary(sdp.colour_plane_id as u32, 2));
+        encoder_formatted_print(
+            &format!("SDP {}: colour_plane_id", name),
+            sdp.colour_plane_id,
+            63,
+        );
+    }
+
+    if p.redundant_pic_cnt_present_flag {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            sdp.redundant_pic_cnt as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            &format!("SDP {}: redundant_pic_cnt", name),
+            sdp.redundant_pic_cnt,
+            63,
+        );
+    }
+}
+
+/// Follows section 7.3.2.9. Returns the encoded partition A, and partitions B and C
+/// when their syntax elements are passed in. The residual of intra and inter
+/// macroblocks goes in partitions B and C respectively, and is dropped if that
+/// partition is not requested
+#[allow(clippy::too_many_arguments)]
+pub fn encode_slice_data_partitions(
+    nh: &NALUheader,
+    slice: &Slice,
+    sdp_a: &SliceDataPartition,
+    sdp_b: Option<&SliceDataPartition>,
+    sdp_c: Option<&SliceDataPartition>,
+    s: &SeqParameterSet,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+    silent_mode: bool,
+) -> (Vec<u8>, Option<Vec<u8>>, Option<Vec<u8>>) {
+    let mut bitstream_array: Vec<u8> = Vec::new();
+
+    encode_slice_header(&mut bitstream_array, nh, &slice.sh, s, p, vp);
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        sdp_a.slice_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SDP A: slice_id", sdp_a.slice_id, 63);
+
+    // B and C start with their own syntax elements so that PCM sample alignment is correct
+    let mut partitions = ResidualPartitionBits {
+        b: Vec::new(),
+        c: Vec::new(),
+    };
+    if let Some(sdp) = sdp_b {
+        encode_slice_data_partition_bc_header(&mut partitions.b, sdp, s, p, "B");
+    }
+    if let Some(sdp) = sdp_c {
+        encode_slice_data_partition_bc_header(&mut partitions.c, sdp, s, p, "C");
+    }
+
+    if p.entropy_coding_mode_flag {
+        if !silent_mode {
+            println!("[WARNING] Slice data partitioning with CABAC is not allowed - encoding all slice data in partition A");
+        }
+        encode_slice_data(&mut bitstream_array, slice, s, p, vp, silent_mode, None);
+    } else {
+        encode_slice_data(
+            &mut bitstream_array,
+            slice,
+            s,
+            p,
+            vp,
+            silent_mode,
+            Some(&mut partitions),
+        );
+    }
+
+    // push rbsp 1 bit for each partition
+    bitstream_array.push(1);
+    partitions.b.push(1);
+    partitions.c.push(1);
+
+    (
+        bitstream_to_bytestream(bitstream_array, 0),
+        sdp_b.map(|_| bitstream_to_bytestream(partitions.b, 0)),
+        sdp_c.map(|_| bitstream_to_bytestream(partitions.c, 0)),
+    )
+}
+
 /// Follows section 7.3.2.13
 pub fn encode_slice_layer_extension_rbsp(
     nh: &NALUheader,
@@ -1246,69 +1378,841 @@ pub fn encode_slice_layer_extension_rbsp(
     vp: &VideoParameters,
     silent_mode: bool,
 ) -> Vec<u8> {
-    //let mut bitstream_array = Vec::new();
-
-    // TODO: Uncomment whenever implementation below is done
-
-    //if nh.svc_extension_flag {
-    //    bitstream_array.append(&mut encode_slice_header_in_scalable_extension(nh, &slice.sh, s, p, vp)); // Annex G
-    //    let slice_skip_flag = true;
-    //    if !slice_skip_flag {
-    //        bitstream_array.append(&mut encode_slice_data_in_scalable_extension()); // Annex G
-    //    }
-    //} else if nh.avc_3d_extension_flag {
-    //    bitstream_array.append(&mut encode_slice_header_in_3davc_extension(nh, &slice.sh, s, p, vp)); // Annex J
-    //    bitstream_array.append(&mut encode_slice_data_in_3davc_extension()); // Annex J
-    //} else {
-    encode_slice(nh, slice, &s.sps, p, vp, silent_mode)
-    //}
+    if nh.svc_extension_flag {
+        let mut bitstream_array = Vec::new();
+
+        encode_slice_header_in_scalable_extension(&mut bitstream_array, nh, &slice.sh, s, p, vp);
+        if !slice.sh.svc_extension.slice_skip_flag {
+            encode_slice_data_in_scalable_extension(
+                &mut bitstream_array,
+                slice,
+                s,
+                p,
+                vp,
+                silent_mode,
+            );
+        }
 
-    // rbsp trailing bit
-    //bitstream_array.push(1);
-    //
-    //return bitstream_to_bytestream(bitstream_array, 0);
-}
+        // rbsp trailing bit
+        bitstream_array.push(1);
 
-#[allow(dead_code)]
-fn encode_slice_header_in_scalable_extension(
-    _nh: &NALUheader,
-    _sh: &SliceHeader,
-    _s: &SubsetSPS,
-    _p: &PicParameterSet,
-    _vp: &VideoParameters,
-) -> Vec<u8> {
-    let bitstream_array = Vec::new();
-    // TODO: Annex G
-    println!("encode_slice_header_in_scalable_extension - not yet supported");
-    bitstream_array
-}
+        return bitstream_to_bytestream(bitstream_array, 0);
+    }
 
-#[allow(dead_code)]
-fn encode_slice_data_in_scalable_extension() -> Vec<u8> {
-    let bitstream_array = Vec::new();
-    // TODO: Annex G
-    println!("encode_slice_data_in_scalable_extension - not yet supported");
-    bitstream_array
+    if nh.avc_3d_extension_flag {
+        let mut bitstream_array = Vec::new();
+
+        encode_slice_header_in_3davc_extension(&mut bitstream_array, nh, &slice.sh, s, p, vp);
+        encode_slice_data_in_3davc_extension(&mut bitstream_array, slice, s, p, vp, silent_mode);
+
+        // rbsp trailing bit
+        bitstream_array.push(1);
+
+        return bitstream_to_bytestream(bitstream_array, 0);
+    }
+
+    encode_slice(nh, slice, &s.sps, p, vp, silent_mode)
 }
 
-#[allow(dead_code)]
-fn encode_slice_header_in_3davc_extension(
-    _nh: &NALUheader,
-    _sh: &SliceHeader,
-    _s: &SubsetSPS,
-    _p: &PicParameterSet,
-    _vp: &VideoParameters,
-) -> Vec<u8> {
-    let bitstream_array = Vec::new();
-    // TODO: Annex J
-    println!("encode_slice_header_in_3davc_extension - not yet supported");
+/// Follows section G.7.3.3.5
+fn encode_slice_header_dec_ref_base_pic_marking(sh: &SliceHeader) -> Vec<u8> {
+    let mut bitstream_array = Vec::new();
+
+    bitstream_array.push(
+        match sh.svc_extension.adaptive_ref_base_pic_marking_mode_flag {
+            true => 1u8,
+            false => 0u8,
+        },
+    );
+    encoder_formatted_print(
+        "SH: adaptive_ref_base_pic_marking_mode_flag",
+        sh.svc_extension.adaptive_ref_base_pic_marking_mode_flag,
+        63,
+    );
+
+    if sh.svc_extension.adaptive_ref_base_pic_marking_mode_flag {
+        for i in 0..sh
+            .svc_extension
+            .memory_management_base_control_operation
+            .len()
+        {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.svc_extension.memory_management_base_control_operation[i] as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: memory_management_base_control_operation",
+                sh.svc_extension.memory_management_base_control_operation[i],
+                63,
+            );
+
+            if sh.svc_extension.memory_management_base_control_operation[i] == 1 {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    sh.svc_extension.difference_of_base_pic_nums_minus1[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SH: difference_of_base_pic_nums_minus1",
+                    sh.svc_extension.difference_of_base_pic_nums_minus1[i],
+                    63,
+                );
+            } else if sh.svc_extension.memory_management_base_control_operation[i] == 2 {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    sh.svc_extension.long_term_base_pic_num[i] as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SH: long_term_base_pic_num",
+                    sh.svc_extension.long_term_base_pic_num[i],
+                    63,
+                );
+            }
+        }
+    }
+
     bitstream_array
 }
 
-#[allow(dead_code)]
-fn encode_slice_data_in_3davc_extension() -> Vec<u8> {
-    let bitstream_array = Vec::new();
-    // TODO: Annex J
-    println!("encode_slice_data_in_3davc_extension - not yet supported");
-    bitstream_array
+/// Follows section G.7.3.3.4
+fn encode_slice_header_in_scalable_extension(
+    bitstream_array: &mut Vec<u8>,
+    nh: &NALUheader,
+    sh: &SliceHeader,
+    s: &SubsetSPS,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+) {
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        sh.first_mb_in_slice as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SH: first_mb_in_slice", sh.first_mb_in_slice, 63);
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        sh.slice_type as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SH: slice_type", sh.slice_type, 63);
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        sh.pic_parameter_set_id as i32,
+        false,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SH: pic_parameter_set_id", sh.pic_parameter_set_id, 63);
+
+    if s.sps.separate_colour_plane_flag {
+        bitstream_array.append(&mut generate_unsigned_binary(sh.colour_plane_id as u32, 2));
+        encoder_formatted_print("SH: colour_plane_id", sh.colour_plane_id, 63);
+    }
+
+    bitstream_array.append(&mut generate_unsigned_binary(
+        sh.frame_num,
+        (s.sps.log2_max_frame_num_minus4 + 4) as usize,
+    ));
+    encoder_formatted_print("SH: frame_num", sh.frame_num, 63);
+
+    if !s.sps.frame_mbs_only_flag {
+        bitstream_array.push(match sh.field_pic_flag {
+            true => 1u8,
+            false => 0u8,
+        });
+        encoder_formatted_print("SH: field_pic_flag", sh.field_pic_flag, 63);
+        if sh.field_pic_flag {
+            bitstream_array.push(match sh.bottom_field_flag {
+                true => 1u8,
+                false => 0u8,
+            });
+            encoder_formatted_print("SH: bottom_field_flag", sh.bottom_field_flag, 63);
+        }
+    }
+
+    // vp.idr_pic_flag is set from the NALU header idr_flag
+    if vp.idr_pic_flag {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            sh.idr_pic_id as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SH: idr_pic_id", sh.idr_pic_id, 63);
+    }
+
+    if s.sps.pic_order_cnt_type == 0 {
+        bitstream_array.append(&mut generate_unsigned_binary(
+            sh.pic_order_cnt_lsb,
+            s.sps.log2_max_pic_order_cnt_lsb_minus4 as usize + 4,
+        ));
+        encoder_formatted_print("SH: pic_order_cnt_lsb", sh.pic_order_cnt_lsb, 63);
+
+        if p.bottom_field_pic_order_in_frame_present_flag && !sh.field_pic_flag {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.delta_pic_order_cnt_bottom,
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: delta_pic_order_cnt_bottom",
+                sh.delta_pic_order_cnt_bottom,
+                63,
+            );
+        }
+    }
+
+    if s.sps.pic_order_cnt_type == 1 && !s.sps.delta_pic_order_always_zero_flag {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            sh.delta_pic_order_cnt[0],
+            true,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SH: delta_pic_order_cnt[0]", sh.delta_pic_order_cnt[0], 63);
+        if p.bottom_field_pic_order_in_frame_present_flag && !sh.field_pic_flag {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.delta_pic_order_cnt[1],
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print("SH: delta_pic_order_cnt[1]", sh.delta_pic_order_cnt[1], 63);
+        }
+    }
+
+    if p.redundant_pic_cnt_present_flag {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            sh.redundant_pic_cnt as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SH: redundant_pic_cnt", sh.redundant_pic_cnt, 63);
+    }
+
+    if sh.svc_extension.quality_id == 0 {
+        if is_slice_type(sh.slice_type, "B") {
+            bitstream_array.push(match sh.direct_spatial_mv_pred_flag {
+                true => 1u8,
+                false => 0u8,
+            });
+            encoder_formatted_print(
+                "SH: direct_spatial_mv_pred_flag",
+                sh.direct_spatial_mv_pred_flag,
+                63,
+            );
+        }
+
+        if is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "B") {
+            bitstream_array.push(match sh.num_ref_idx_active_override_flag {
+                true => 1u8,
+                false => 0u8,
+            });
+            encoder_formatted_print(
+                "SH: num_ref_idx_active_override_flag",
+                sh.num_ref_idx_active_override_flag,
+                63,
+            );
+
+            if sh.num_ref_idx_active_override_flag {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    sh.num_ref_idx_l0_active_minus1 as i32,
+                    false,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SH: num_ref_idx_l0_active_minus1",
+                    sh.num_ref_idx_l0_active_minus1,
+                    63,
+                );
+                if is_slice_type(sh.slice_type, "B") {
+                    bitstream_array.append(&mut exp_golomb_encode_one(
+                        sh.num_ref_idx_l1_active_minus1 as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                    encoder_formatted_print(
+                        "SH: num_ref_idx_l1_active_minus1",
+                        sh.num_ref_idx_l1_active_minus1,
+                        63,
+                    );
+                }
+            }
+        }
+
+        bitstream_array.append(&mut encode_slice_header_ref_pic_list_modification(sh));
+
+        if (p.weighted_pred_flag && is_slice_type(sh.slice_type, "P"))
+            || (p.weighted_bipred_idc == 1 && is_slice_type(sh.slice_type, "B"))
+        {
+            if !sh.svc_extension.no_inter_layer_pred_flag {
+                bitstream_array.push(match sh.svc_extension.base_pred_weight_table_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print(
+                    "SH: base_pred_weight_table_flag",
+                    sh.svc_extension.base_pred_weight_table_flag,
+                    63,
+                );
+            }
+            if sh.svc_extension.no_inter_layer_pred_flag
+                || !sh.svc_extension.base_pred_weight_table_flag
+            {
+                bitstream_array.append(&mut encode_slice_header_pred_weight_table(sh, vp));
+            }
+        }
+
+        if nh.nal_ref_idc != 0 {
+            bitstream_array.append(&mut encode_slice_header_dec_ref_pic_marking(sh, vp));
+
+            if !s.sps_svc.slice_header_restriction_flag {
+                bitstream_array.push(match sh.svc_extension.store_ref_base_pic_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print(
+                    "SH: store_ref_base_pic_flag",
+                    sh.svc_extension.store_ref_base_pic_flag,
+                    63,
+                );
+
+                if (nh.svc_extension.use_ref_base_pic_flag
+                    || sh.svc_extension.store_ref_base_pic_flag)
+                    && !nh.svc_extension.idr_flag
+                {
+                    bitstream_array.append(&mut encode_slice_header_dec_ref_base_pic_marking(sh));
+                }
+            }
+        }
+    }
+
+    if p.entropy_coding_mode_flag && !is_slice_type(sh.slice_type, "I") {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            sh.cabac_init_idc as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SH: cabac_init_idc", sh.cabac_init_idc, 63);
+    }
+
+    bitstream_array.append(&mut exp_golomb_encode_one(
+        sh.slice_qp_delta,
+        true,
+        0,
+        false,
+    ));
+    encoder_formatted_print("SH: slice_qp_delta", sh.slice_qp_delta, 63);
+
+    if p.deblocking_filter_control_present_flag {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            sh.disable_deblocking_filter_idc as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print(
+            "SH: disable_deblocking_filter_idc",
+            sh.disable_deblocking_filter_idc,
+            63,
+        );
+
+        if sh.disable_deblocking_filter_idc != 1 {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.slice_alpha_c0_offset_div2,
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: slice_alpha_c0_offset_div2",
+                sh.slice_alpha_c0_offset_div2,
+                63,
+            );
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.slice_beta_offset_div2,
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print("SH: slice_beta_offset_div2", sh.slice_beta_offset_div2, 63);
+        }
+    }
+
+    if p.num_slice_groups_minus1 > 0 && p.slice_group_map_type >= 3 && p.slice_group_map_type <= 5 {
+        let bits_to_write = slice_group_change_cycle_len(
+            vp.pic_size_in_map_units,
+            p.slice_group_change_rate_minus1,
+        );
+
+        bitstream_array.append(&mut generate_unsigned_binary(
+            sh.slice_group_change_cycle,
+            bits_to_write,
+        ));
+        encoder_formatted_print(
+            "SH: slice_group_change_cycle",
+            sh.slice_group_change_cycle,
+            63,
+        );
+    }
+
+    if !sh.svc_extension.no_inter_layer_pred_flag && sh.svc_extension.quality_id == 0 {
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            sh.svc_extension.ref_layer_dq_id as i32,
+            false,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SH: ref_layer_dq_id", sh.svc_extension.ref_layer_dq_id, 63);
+
+        if s.sps_svc.inter_layer_deblocking_filter_control_present_flag {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.svc_extension.disable_inter_layer_deblocking_filter_idc as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: disable_inter_layer_deblocking_filter_idc",
+                sh.svc_extension.disable_inter_layer_deblocking_filter_idc,
+                63,
+            );
+
+            if sh.svc_extension.disable_inter_layer_deblocking_filter_idc != 1 {
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    sh.svc_extension.inter_layer_slice_alpha_c0_offset_div2,
+                    true,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SH: inter_layer_slice_alpha_c0_offset_div2",
+                    sh.svc_extension.inter_layer_slice_alpha_c0_offset_div2,
+                    63,
+                );
+                bitstream_array.append(&mut exp_golomb_encode_one(
+                    sh.svc_extension.inter_layer_slice_beta_offset_div2,
+                    true,
+                    0,
+                    false,
+                ));
+                encoder_formatted_print(
+                    "SH: inter_layer_slice_beta_offset_div2",
+                    sh.svc_extension.inter_layer_slice_beta_offset_div2,
+                    63,
+                );
+            }
+        }
+
+        bitstream_array.push(match sh.svc_extension.constrained_intra_resampling_flag {
+            true => 1u8,
+            false => 0u8,
+        });
+        encoder_formatted_print(
+            "SH: constrained_intra_resampling_flag",
+            sh.svc_extension.constrained_intra_resampling_flag,
+            63,
+        );
+
+        if s.sps_svc.extended_spatial_scalability_idc == 2 {
+            if vp.chroma_array_type > 0 {
+                bitstream_array.push(match sh.svc_extension.ref_layer_chroma_phase_x_plus1_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print(
+                    "SH: ref_layer_chroma_phase_x_plus1_flag",
+                    sh.svc_extension.ref_layer_chroma_phase_x_plus1_flag,
+                    63,
+                );
+                bitstream_array.append(&mut generate_unsigned_binary(
+                    sh.svc_extension.ref_layer_chroma_phase_y_plus1 as u32,
+                    2,
+                ));
+                encoder_formatted_print(
+                    "SH: ref_layer_chroma_phase_y_plus1",
+                    sh.svc_extension.ref_layer_chroma_phase_y_plus1,
+                    63,
+                );
+            }
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.svc_extension.scaled_ref_layer_left_offset,
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: scaled_ref_layer_left_offset",
+                sh.svc_extension.scaled_ref_layer_left_offset,
+                63,
+            );
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.svc_extension.scaled_ref_layer_top_offset,
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: scaled_ref_layer_top_offset",
+                sh.svc_extension.scaled_ref_layer_top_offset,
+                63,
+            );
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.svc_extension.scaled_ref_layer_right_offset,
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: scaled_ref_layer_right_offset",
+                sh.svc_extension.scaled_ref_layer_right_offset,
+                63,
+            );
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.svc_extension.scaled_ref_layer_bottom_offset,
+                true,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: scaled_ref_layer_bottom_offset",
+                sh.svc_extension.scaled_ref_layer_bottom_offset,
+                63,
+            );
+        }
+    }
+
+    if !sh.svc_extension.no_inter_layer_pred_flag {
+        bitstream_array.push(match sh.svc_extension.slice_skip_flag {
+            true => 1u8,
+            false => 0u8,
+        });
+        encoder_formatted_print("SH: slice_skip_flag", sh.svc_extension.slice_skip_flag, 63);
+
+        if sh.svc_extension.slice_skip_flag {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.svc_extension.num_mbs_in_slice_minus1 as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print(
+                "SH: num_mbs_in_slice_minus1",
+                sh.svc_extension.num_mbs_in_slice_minus1,
+                63,
+            );
+        } else {
+            bitstream_array.push(match sh.svc_extension.adaptive_base_mode_flag {
+                true => 1u8,
+                false => 0u8,
+            });
+            encoder_formatted_print(
+                "SH: adaptive_base_mode_flag",
+                sh.svc_extension.adaptive_base_mode_flag,
+                63,
+            );
+            if !sh.svc_extension.adaptive_base_mode_flag {
+                bitstream_array.push(match sh.svc_extension.default_base_mode_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print(
+                    "SH: default_base_mode_flag",
+                    sh.svc_extension.default_base_mode_flag,
+                    63,
+                );
+            }
+            if !sh.svc_extension.default_base_mode_flag {
+                bitstream_array.push(match sh.svc_extension.adaptive_motion_prediction_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print(
+                    "SH: adaptive_motion_prediction_flag",
+                    sh.svc_extension.adaptive_motion_prediction_flag,
+                    63,
+                );
+                if !sh.svc_extension.adaptive_motion_prediction_flag {
+                    bitstream_array.push(match sh.svc_extension.default_motion_prediction_flag {
+                        true => 1u8,
+                        false => 0u8,
+                    });
+                    encoder_formatted_print(
+                        "SH: default_motion_prediction_flag",
+                        sh.svc_extension.default_motion_prediction_flag,
+                        63,
+                    );
+                }
+            }
+            bitstream_array.push(match sh.svc_extension.adaptive_residual_prediction_flag {
+                true => 1u8,
+                false => 0u8,
+            });
+            encoder_formatted_print(
+                "SH: adaptive_residual_prediction_flag",
+                sh.svc_extension.adaptive_residual_prediction_flag,
+                63,
+            );
+            if !sh.svc_extension.adaptive_residual_prediction_flag {
+                bitstream_array.push(match sh.svc_extension.default_residual_prediction_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print(
+                    "SH: default_residual_prediction_flag",
+                    sh.svc_extension.default_residual_prediction_flag,
+                    63,
+                );
+            }
+        }
+
+        if s.sps_svc.adaptive_tcoeff_level_prediction_flag {
+            bitstream_array.push(match sh.svc_extension.tcoeff_level_prediction_flag {
+                true => 1u8,
+                false => 0u8,
+            });
+            encoder_formatted_print(
+                "SH: tcoeff_level_prediction_flag",
+                sh.svc_extension.tcoeff_level_prediction_flag,
+                63,
+            );
+        }
+    }
+
+    if !s.sps_svc.slice_header_restriction_flag && !sh.svc_extension.slice_skip_flag {
+        bitstream_array.append(&mut generate_unsigned_binary(
+            sh.svc_extension.scan_idx_start as u32,
+            4,
+        ));
+        encoder_formatted_print("SH: scan_idx_start", sh.svc_extension.scan_idx_start, 63);
+        bitstream_array.append(&mut generate_unsigned_binary(
+            sh.svc_extension.scan_idx_end as u32,
+            4,
+        ));
+        encoder_formatted_print("SH: scan_idx_end", sh.svc_extension.scan_idx_end, 63);
+    }
+}
+
+/// Follows section G.7.3.4.1
+///
+/// The slice data syntax is shared with 7.3.4; the scalable extension
+/// syntax elements are written at the macroblock layer when sh.svc_extension_flag is set
+fn encode_slice_data_in_scalable_extension(
+    bitstream_array: &mut Vec<u8>,
+    slice: &Slice,
+    s: &SubsetSPS,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+    silent_mode: bool,
+) {
+    encode_slice_data(bitstream_array, slice, &s.sps, p, vp, silent_mode, None);
+}
+
+/// Follows section J.7.3.3.4
+///
+/// When slice_header_prediction_flag is set, the syntax elements predicted from another
+/// view component are not written
+fn encode_slice_header_in_3davc_extension(
+    bitstream_array: &mut Vec<u8>,
+    nh: &NALUheader,
+    sh: &SliceHeader,
+    s: &SubsetSPS,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+) {
+    let mut res = exp_golomb_encode_one(sh.first_mb_in_slice as i32, false, 0, false);
+    bitstream_array.append(&mut res);
+    encoder_formatted_print("SH: first_mb_in_slice", sh.first_mb_in_slice, 63);
+
+    let mut res = exp_golomb_encode_one(sh.slice_type as i32, false, 0, false);
+    bitstream_array.append(&mut res);
+    encoder_formatted_print("SH: slice_type", sh.slice_type, 63);
+
+    let mut res = exp_golomb_encode_one(sh.pic_parameter_set_id as i32, false, 0, false);
+    bitstream_array.append(&mut res);
+    encoder_formatted_print("SH: pic_parameter_set_id", sh.pic_parameter_set_id, 63);
+
+    if s.sps_3davc.slice_header_prediction_flag {
+        bitstream_array.append(&mut generate_unsigned_binary(
+            sh.avc_3d_extension.pre_slice_header_src as u32,
+            2,
+        ));
+        encoder_formatted_print(
+            "SH: pre_slice_header_src",
+            sh.avc_3d_extension.pre_slice_header_src,
+            63,
+        );
+
+        if is_slice_type(sh.slice_type, "P")
+            || is_slice_type(sh.slice_type, "SP")
+            || is_slice_type(sh.slice_type, "B")
+        {
+            bitstream_array.append(&mut generate_unsigned_binary(
+                sh.avc_3d_extension.pre_ref_lists_src as u32,
+                2,
+            ));
+            encoder_formatted_print(
+                "SH: pre_ref_lists_src",
+                sh.avc_3d_extension.pre_ref_lists_src,
+                63,
+            );
+
+            if sh.avc_3d_extension.pre_ref_lists_src == 0 {
+                bitstream_array.push(match sh.num_ref_idx_active_override_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print(
+                    "SH: num_ref_idx_active_override_flag",
+                    sh.num_ref_idx_active_override_flag,
+                    63,
+                );
+
+                if sh.num_ref_idx_active_override_flag {
+                    bitstream_array.append(&mut exp_golomb_encode_one(
+                        sh.num_ref_idx_l0_active_minus1 as i32,
+                        false,
+                        0,
+                        false,
+                    ));
+                    encoder_formatted_print(
+                        "SH: num_ref_idx_l0_active_minus1",
+                        sh.num_ref_idx_l0_active_minus1,
+                        63,
+                    );
+                    if is_slice_type(sh.slice_type, "B") {
+                        bitstream_array.append(&mut exp_golomb_encode_one(
+                            sh.num_ref_idx_l1_active_minus1 as i32,
+                            false,
+                            0,
+                            false,
+                        ));
+                        encoder_formatted_print(
+                            "SH: num_ref_idx_l1_active_minus1",
+                            sh.num_ref_idx_l1_active_minus1,
+                            63,
+                        );
+                    }
+                }
+
+                bitstream_array.append(&mut encode_slice_header_ref_pic_list_mvc_modification(sh));
+            }
+        }
+
+        if (p.weighted_pred_flag
+            && (is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "SP")))
+            || (p.weighted_bipred_idc == 1 && is_slice_type(sh.slice_type, "B"))
+        {
+            bitstream_array.append(&mut generate_unsigned_binary(
+                sh.avc_3d_extension.pre_pred_weight_table_src as u32,
+                2,
+            ));
+            encoder_formatted_print(
+                "SH: pre_pred_weight_table_src",
+                sh.avc_3d_extension.pre_pred_weight_table_src,
+                63,
+            );
+            if sh.avc_3d_extension.pre_pred_weight_table_src == 0 {
+                bitstream_array.append(&mut encode_slice_header_pred_weight_table(sh, vp));
+            }
+        }
+
+        if nh.nal_ref_idc != 0 {
+            bitstream_array.append(&mut generate_unsigned_binary(
+                sh.avc_3d_extension.pre_dec_ref_pic_marking_src as u32,
+                2,
+            ));
+            encoder_formatted_print(
+                "SH: pre_dec_ref_pic_marking_src",
+                sh.avc_3d_extension.pre_dec_ref_pic_marking_src,
+                63,
+            );
+            if sh.avc_3d_extension.pre_dec_ref_pic_marking_src == 0 {
+                bitstream_array.append(&mut encode_slice_header_dec_ref_pic_marking(sh, vp));
+            }
+        }
+
+        // slice_qp_delta se(v)
+        bitstream_array.append(&mut exp_golomb_encode_one(
+            sh.slice_qp_delta,
+            true,
+            0,
+            false,
+        ));
+        encoder_formatted_print("SH: slice_qp_delta", sh.slice_qp_delta, 63);
+    } else {
+        encode_slice_header_body(bitstream_array, nh, sh, &s.sps, p, vp);
+
+        if !is_slice_type(sh.slice_type, "I") && !is_slice_type(sh.slice_type, "SI") {
+            if vp.depth_flag {
+                bitstream_array.push(match sh.avc_3d_extension.depth_weighted_pred_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print(
+                    "SH: depth_weighted_pred_flag",
+                    sh.avc_3d_extension.depth_weighted_pred_flag,
+                    63,
+                );
+            } else {
+                bitstream_array.push(match sh.avc_3d_extension.dmvp_flag {
+                    true => 1u8,
+                    false => 0u8,
+                });
+                encoder_formatted_print("SH: dmvp_flag", sh.avc_3d_extension.dmvp_flag, 63);
+
+                if s.sps_3davc.seq_view_synthesis_flag {
+                    bitstream_array.push(match sh.avc_3d_extension.slice_vsp_flag {
+                        true => 1u8,
+                        false => 0u8,
+                    });
+                    encoder_formatted_print(
+                        "SH: slice_vsp_flag",
+                        sh.avc_3d_extension.slice_vsp_flag,
+                        63,
+                    );
+                }
+            }
+        }
+
+        if s.sps_3davc.three_dv_acquisition_idc != 1
+            && (sh.avc_3d_extension.depth_weighted_pred_flag || sh.avc_3d_extension.dmvp_flag)
+        {
+            bitstream_array.append(&mut exp_golomb_encode_one(
+                sh.avc_3d_extension.dps_id as i32,
+                false,
+                0,
+                false,
+            ));
+            encoder_formatted_print("SH: dps_id", sh.avc_3d_extension.dps_id, 63);
+        }
+    }
+}
+
+/// Follows section J.7.3.4.1
+///
+/// Only the macroblock syntax shared with 7.3.4 is encoded; view synthesis prediction,
+/// adaptive luminance compensation, and RLE skip macroblock syntax elements are not yet supported
+fn encode_slice_data_in_3davc_extension(
+    bitstream_array: &mut Vec<u8>,
+    slice: &Slice,
+    s: &SubsetSPS,
+    p: &PicParameterSet,
+    vp: &VideoParameters,
+    silent_mode: bool,
+) {
+    encode_slice_data(bitstream_array, slice, &s.sps, p, vp, silent_mode, None);
 }
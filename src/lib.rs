@@ -7,6 +7,7 @@
 pub mod common;
 pub mod decoder;
 pub mod encoder;
+pub mod reconstruction;
 pub mod vidgen;
 
 use common::data_structures::H264DecodedStream;
@@ -174,6 +175,7 @@ pub fn generate_video_from_seed(
     let cut_nalu = -1;
     let dupe_nalu = -1;
     let dupe_index = -1;
+    let data_partitioning = false;
 
     let mut vid = random_video(
         ignore_intra_pred,
@@ -183,6 +185,7 @@ pub fn generate_video_from_seed(
         small_video,
         silent_mode,
         undefined_nalus,
+        data_partitioning,
         &rconfig,
         &mut film_state,
     );
@@ -229,6 +232,7 @@ pub fn generate_video_from_film_contents(
     let cut_nalu = -1;
     let dupe_nalu = -1;
     let dupe_index = -1;
+    let data_partitioning = false;
 
     let mut vid = random_video(
         ignore_intra_pred,
@@ -238,6 +242,7 @@ pub fn generate_video_from_film_contents(
         small_video,
         silent_mode,
         undefined_nalus,
+        data_partitioning,
         &rconfig,
         &mut film_state,
     );
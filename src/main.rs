@@ -2,6 +2,7 @@ mod common;
 mod decoder;
 mod encoder;
 mod experimental;
+mod reconstruction;
 mod streaming;
 mod vidgen;
 
@@ -47,6 +48,10 @@ struct H26ForgeOptions {
     #[arg(long = "strict-fmo")]
     decode_strict_fmo: bool,
 
+    /// Keep NALUs that fail to decode as raw bytes instead of stopping
+    #[arg(long = "lenient")]
+    decode_lenient: bool,
+
     /// Prepend output video with known good video
     #[arg(long = "safestart")]
     include_safestart: bool,
@@ -145,9 +150,10 @@ enum Commands {
         /// Seed value for the RNG
         #[arg(short = 's', long)]
         seed: Option<u64>,
-        /// Path to configuration file containing the ranges to use in random video generation
+        /// Path to configuration file containing the ranges to use in random video generation.
+        /// Repeat to layer several files; later files override earlier ones
         #[arg(short = 'c', long)]
-        config: Option<String>,
+        config: Vec<String>,
         /// Produce videos by reading from a film file rather than sampling from a random number generator
         #[arg(long = "film")]
         film_file: Option<String>,
@@ -193,18 +199,38 @@ enum Commands {
         /// Incorporate undefined NALUs (e.g., 17, 18, 22-31) into generated video
         #[arg(long = "include-undefined-nalus")]
         include_undefined_nalus: bool,
+        /// Split non-IDR slices into slice data partitions A, B, and C
+        #[arg(long = "data-partitioning")]
+        data_partitioning: bool,
         /// Seed value for the RNG
         #[arg(short = 's', long)]
         seed: Option<u64>,
-        /// Path to configuration file containing the ranges to use in random video generation
+        /// Path to configuration file containing the ranges to use in random video generation.
+        /// Repeat to layer several files; later files override earlier ones
         #[arg(short = 'c', long)]
-        config: Option<String>,
+        config: Vec<String>,
         /// Produce videos by reading from a film file rather than sampling from a random number generator
         #[arg(long = "film")]
         film_file: Option<String>,
         /// Save the film file that was used to generate a video
         #[arg(long = "output-film")]
         output_film: bool,
+        /// Only generate streams that conform to Annex A for the chosen profile and level
+        #[arg(long = "spec-compliant")]
+        spec_compliant: bool,
+        /// Profile to conform to in spec-compliant mode (66, 77, or 100)
+        #[arg(long = "profile-idc", default_value = "77")]
+        profile_idc: u32,
+        /// Level to conform to in spec-compliant mode (e.g., 30 for Level 3; 9 for Level 1b)
+        #[arg(long = "level-idc", default_value = "30")]
+        level_idc: u32,
+        /// Generate IDR-started GOPs with consistent frame_num, POC, and reference lists
+        #[arg(long = "gop")]
+        gop_structure: bool,
+        /// Partial H264DecodedStream JSON whose NALU order and syntax elements are kept; only
+        /// the fields it leaves out are sampled
+        #[arg(long = "template")]
+        template: Option<String>,
     },
     /// Stream RTP packets containing random H.264
     Stream {
@@ -228,9 +254,10 @@ enum Commands {
         /// Seed value for the RNG
         #[arg(short = 's', long, default_value="0")]
         seed: u64,
-        /// Path to configuration file containing the ranges to use in random video generation
+        /// Path to configuration file containing the ranges to use in random video generation.
+        /// Repeat to layer several files; later files override earlier ones
         #[arg(short = 'c', long)]
-        config: Option<String>,
+        config: Vec<String>,
         /// WebRTC SDP file
         #[arg(long = "webrtc-file", required=false, default_value="")]
         webrtc_file: String,
@@ -256,6 +283,24 @@ enum Commands {
         #[arg(short, long, required = true)]
         output: String,
     },
+    /// Reconstruct the pictures of an H.264 video to raw YUV
+    Reconstruct {
+        /// Input H.264 file
+        #[arg(short, long, required = true)]
+        input: String,
+        /// Output YUV file
+        #[arg(short, long, required = true)]
+        output: String,
+        /// Print the MD5 of each reconstructed picture in output order
+        #[arg(long)]
+        md5: bool,
+    },
+    /// Check configuration files for unknown fields, empty ranges and impossible dependencies
+    ValidateConfig {
+        /// Configuration files, layered in order over the default ranges
+        #[arg(short = 'c', long, required = true)]
+        config: Vec<String>,
+    },
     /// Experimental features
     Experimental {
         /// Input H.265 file
@@ -274,6 +319,7 @@ impl H26ForgeOptions {
 
         debug!(target: "encode"," - input_is_hevc: {}", self.input_is_hevc);
         debug!(target: "encode"," - decode_strict_fmo: {}", self.decode_strict_fmo);
+        debug!(target: "encode"," - decode_lenient: {}", self.decode_lenient);
         debug!(target: "encode"," - include_safestart: {}", self.include_safestart);
 
         debug!(target: "encode"," - output_syntax_json: {}", self.output_syntax_json);
@@ -381,6 +427,42 @@ fn setup_debug_file(
     Ok(())
 }
 
+/// Decode the input bitstream, exiting if any NALU is undecodable and lenient decoding is off
+fn decode_input(
+    input_filename: &str,
+    only_headers: bool,
+    options: &H26ForgeOptions,
+) -> common::data_structures::H264DecodedStream {
+    match decoder::decoder::decode_bitstream(
+        input_filename,
+        only_headers,
+        options.print_perf,
+        options.decode_strict_fmo,
+        options.decode_lenient,
+    ) {
+        Ok(ds) => ds,
+        Err(e) => {
+            println!("ERROR - unable to decode {}: {}", input_filename, e);
+            println!("\t Use --lenient to keep undecodable NALUs as raw bytes");
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Layer the configuration files over the default ranges, exiting if any of them has a
+/// problem that validate-config would report
+fn load_config_input(config: &[String]) -> vidgen::generate_configurations::RandomizeConfig {
+    match vidgen::generate_configurations::load_config(config) {
+        Ok(rconfig) => rconfig,
+        Err(errors) => {
+            for e in errors.iter() {
+                println!("ERROR - {}", e);
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
 /// Given a H264DecodedStream object in json format, output an encoded bitstream
 fn mode_synthesize(input_filename: &str, output_filename: &str, options: &H26ForgeOptions) {
     // 1. Use the passed in file to get the decoded_elements
@@ -447,12 +529,7 @@ fn mode_randomize(
 ) {
     // 1. Decode the bitstream to get the Syntax Elements
     println!("1. Decoding bitstream into H.264 Syntax Elements");
-    let mut decoded_elements = decoder::decoder::decode_bitstream(
-        input_filename,
-        false,
-        options.print_perf,
-        options.decode_strict_fmo,
-    );
+    let mut decoded_elements = decode_input(input_filename, false, options);
 
     println!("2. Randomly mutating H.264 Syntax Elements");
 
@@ -484,8 +561,10 @@ fn mode_randomize(
 
         let mut slice_count = 0;
         for nalu_idx in 0..decoded_elements.nalu_headers.len() {
-            if decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 1
-                || decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 5
+            if (decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 1
+                || decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 2
+                || decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 5)
+                && !decoded_elements.undecoded_nalus.contains(&nalu_idx)
             {
                 // choose pps and sps to use
                 let mut pps_idx = 0;
@@ -543,8 +622,10 @@ fn mode_randomize(
     } else {
         let mut slice_count = 0;
         for nalu_idx in 0..decoded_elements.nalu_headers.len() {
-            if decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 1
-                || decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 5
+            if (decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 1
+                || decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 2
+                || decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 5)
+                && !decoded_elements.undecoded_nalus.contains(&nalu_idx)
             {
                 if slice_count == slice_idx {
                     // choose pps and sps to use
@@ -587,6 +668,8 @@ fn mode_randomize(
                     let param_sets_exist = true;
                     let enable_extensions = true;
                     let undefined_nalus = false;
+                    // partitions B and C that follow depend on this staying a partition A
+                    let is_partition_a = decoded_elements.nalu_headers[nalu_idx].nal_unit_type == 2;
 
                     vidgen::nalu::random_nalu_header(
                         nalu_idx,
@@ -599,7 +682,9 @@ fn mode_randomize(
                     );
 
                     // set the header to a slice type if not already
-                    if decoded_elements.nalu_headers[nalu_idx].nal_unit_type != 1
+                    if is_partition_a {
+                        decoded_elements.nalu_headers[nalu_idx].nal_unit_type = 2;
+                    } else if decoded_elements.nalu_headers[nalu_idx].nal_unit_type != 1
                         && decoded_elements.nalu_headers[nalu_idx].nal_unit_type != 5
                     {
                         // randomly assign to 1 or 5
@@ -734,12 +819,7 @@ fn mode_modify(
 ) {
     // 1. Decode the bitstream to get the Syntax Elements
     println!("1. Decoding bitstream into H.264 Syntax Elements");
-    let mut decoded_elements = decoder::decoder::decode_bitstream(
-        input_filename,
-        false,
-        options.print_perf,
-        options.decode_strict_fmo,
-    );
+    let mut decoded_elements = decode_input(input_filename, false, options);
 
     println!("2. Mutating H.264 Syntax Elements with provided code");
 
@@ -810,7 +890,9 @@ fn mode_generate(
     property_empty_slice_data: bool,
     property_small_video: bool,
     include_undefined_nalus: bool,
+    data_partitioning: bool,
     output_film: bool,
+    template: Option<&vidgen::template::VideoTemplate>,
     options: &H26ForgeOptions,
 ) {
     // 1. Generate video
@@ -854,20 +936,43 @@ fn mode_generate(
     debug!(target: "encode"," - property_empty_slice_data : {}", property_empty_slice_data);
     debug!(target: "encode"," - property_small_video : {}", property_small_video);
     debug!(target: "encode"," - include_undefined_nalus : {}", include_undefined_nalus);
+    debug!(target: "encode"," - data_partitioning : {}", data_partitioning);
     debug!(target: "encode"," - output_film : {}", output_film);
 
     let start_time = SystemTime::now();
-    let mut decoded_elements = vidgen::vidgen::random_video(
-        ignore_intra_pred,
-        ignore_edge_intra_pred,
-        ignore_ipcm,
-        property_empty_slice_data,
-        property_small_video,
-        options.print_silent,
-        include_undefined_nalus,
-        &rconfig,
-        &mut film_state,
-    );
+    let mut decoded_elements = match template {
+        Some(x) => match vidgen::vidgen::random_video_from_template(
+            x,
+            ignore_intra_pred,
+            ignore_edge_intra_pred,
+            ignore_ipcm,
+            property_empty_slice_data,
+            property_small_video,
+            options.print_silent,
+            include_undefined_nalus,
+            data_partitioning,
+            &rconfig,
+            &mut film_state,
+        ) {
+            Ok(x) => x,
+            Err(e) => {
+                println!("ERROR - {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => vidgen::vidgen::random_video(
+            ignore_intra_pred,
+            ignore_edge_intra_pred,
+            ignore_ipcm,
+            property_empty_slice_data,
+            property_small_video,
+            options.print_silent,
+            include_undefined_nalus,
+            data_partitioning,
+            &rconfig,
+            &mut film_state,
+        ),
+    };
 
     if options.print_perf {
         let duration = start_time.elapsed();
@@ -1006,6 +1111,61 @@ fn mode_generate(
     }
 }
 
+fn mode_reconstruct(
+    input_filename: &str,
+    output_filename: &str,
+    print_md5: bool,
+    options: &H26ForgeOptions,
+) {
+    println!("1. Decoding H.264 Syntax Elements");
+    let decoded_elements = decode_input(input_filename, false, options);
+
+    println!("2. Reconstructing Pictures");
+    let start_time = SystemTime::now();
+    let pictures =
+        reconstruction::reconstruct::reconstruct_stream(&decoded_elements, options.print_silent);
+    if options.print_perf {
+        let duration = start_time.elapsed();
+        match duration {
+            Ok(elapsed) => {
+                println!(
+                    "[PERF] main_reconstruct;reconstruct_stream;{} ns",
+                    elapsed.as_nanos()
+                );
+            }
+            Err(e) => {
+                println!("Error: {:?}", e);
+            }
+        }
+    }
+
+    if let Some(pic) = pictures.first() {
+        let (width, height) = pic.cropped_size();
+        println!(
+            "3. Writing {} pictures of size {}x{} to {}",
+            pictures.len(),
+            width,
+            height,
+            output_filename
+        );
+    } else {
+        println!("3. No pictures reconstructed - writing empty file {}", output_filename);
+    }
+    reconstruction::picture::save_yuv(output_filename, &pictures);
+
+    if print_md5 {
+        for (i, pic) in pictures.iter().enumerate() {
+            println!(
+                "Picture {} - POC {} - frame_num {} - MD5 {}",
+                i,
+                pic.poc,
+                pic.frame_num,
+                pic.md5_hex()
+            );
+        }
+    }
+}
+
 fn main() {
     let options = H26ForgeOptions::parse();
 
@@ -1048,12 +1208,7 @@ fn main() {
             }
 
             let start_time = SystemTime::now();
-            let mut decoded_elements = decoder::decoder::decode_bitstream(
-                input,
-                *decode_only_headers,
-                options.print_perf,
-                options.decode_strict_fmo,
-            );
+            let mut decoded_elements = decode_input(input, *decode_only_headers, &options);
             let (width, height) = decoded_elements.spses[0].get_framesize();
             if options.print_perf {
                 let duration = start_time.elapsed();
@@ -1173,10 +1328,16 @@ fn main() {
             property_small_video,
             property_empty_slice_data,
             include_undefined_nalus,
+            data_partitioning,
             seed,
             config,
             film_file,
             output_film,
+            spec_compliant,
+            profile_idc,
+            level_idc,
+            gop_structure,
+            template,
         }) => {
             if options.debug_encode {
                 let res = setup_debug_file(false, options.debug_encode, "", output);
@@ -1203,21 +1364,73 @@ fn main() {
                 }
             };
 
-            let rconfig = match config {
-                Some(x) => {
+            let rconfig = if config.is_empty() {
+                if !options.print_silent {
+                    println!("\t using default random value ranges");
+                }
+                debug!(target: "encode","\t using default random value ranges");
+                vidgen::generate_configurations::RandomizeConfig::new()
+            } else {
+                for x in config.iter() {
                     if !options.print_silent {
                         println!("\t loading config file {}", x);
                     }
                     debug!(target: "encode","\t loading config file {}", x);
-                    vidgen::generate_configurations::load_config(x)
                 }
-                _ => {
+                load_config_input(config)
+            };
+
+            let mut ignore_edge_intra_pred = *ignore_edge_intra_pred;
+            let mut ignore_ipcm = *ignore_ipcm;
+            let mut include_undefined_nalus = *include_undefined_nalus;
+            let mut data_partitioning = *data_partitioning;
+
+            let mut rconfig = if *spec_compliant {
+                if !options.print_silent {
+                    println!(
+                        "\t restricting ranges to profile_idc {} level_idc {}",
+                        profile_idc, level_idc
+                    );
+                }
+                debug!(target: "encode","\t restricting ranges to profile_idc {} level_idc {}", profile_idc, level_idc);
+                // edge intra prediction may reference unavailable samples, and IPCM
+                // macroblocks exceed the MinCR limit of the lower levels
+                ignore_edge_intra_pred = true;
+                ignore_ipcm = true;
+                include_undefined_nalus = false;
+                data_partitioning = false;
+                match vidgen::compliance::spec_compliant_config(&rconfig, *profile_idc, *level_idc)
+                {
+                    Ok(x) => x,
+                    Err(e) => {
+                        println!("ERROR - {}", e);
+                        std::process::exit(1);
+                    }
+                }
+            } else {
+                rconfig
+            };
+
+            if *gop_structure {
+                if !options.print_silent {
+                    println!("\t generating a GOP structure");
+                }
+                rconfig.random_gop_range.enable_gop_structure =
+                    vidgen::generate_configurations::RandomBoolRange::always();
+            }
+
+            let template = match template {
+                Some(x) => {
                     if !options.print_silent {
-                        println!("\t using default random value ranges");
+                        println!("\t pinning syntax elements from template {}", x);
                     }
-                    debug!(target: "encode","\t using default random value ranges");
-                    vidgen::generate_configurations::RandomizeConfig::new()
+                    debug!(target: "encode","\t pinning syntax elements from template {}", x);
+                    if *gop_structure {
+                        println!("[WARNING] The template sets the NALU order --- ignoring --gop");
+                    }
+                    Some(vidgen::template::load_template(x))
                 }
+                None => None,
             };
 
             let use_film_file;
@@ -1248,12 +1461,14 @@ fn main() {
                 use_film_file,
                 film_file,
                 *ignore_intra_pred,
-                *ignore_edge_intra_pred,
-                *ignore_ipcm,
+                ignore_edge_intra_pred,
+                ignore_ipcm,
                 *property_empty_slice_data,
                 *property_small_video,
-                *include_undefined_nalus,
+                include_undefined_nalus,
+                data_partitioning,
                 *output_film,
+                template.as_ref(),
                 &options,
             );
             if options.print_perf {
@@ -1293,21 +1508,20 @@ fn main() {
                     _ => println!("Issue setting up debug logs"),
                 }
             }
-            let rconfig = match config {
-                Some(x) => {
+            let rconfig = if config.is_empty() {
+                if !options.print_silent {
+                    println!("\t using default random value ranges");
+                }
+                debug!(target: "encode","\t using default random value ranges");
+                vidgen::generate_configurations::RandomizeConfig::new()
+            } else {
+                for x in config.iter() {
                     if !options.print_silent {
                         println!("\t loading config file {}", x);
                     }
                     debug!(target: "encode","\t loading config file {}", x);
-                    vidgen::generate_configurations::load_config(x)
-                }
-                _ => {
-                    if !options.print_silent {
-                        println!("\t using default random value ranges");
-                    }
-                    debug!(target: "encode","\t using default random value ranges");
-                    vidgen::generate_configurations::RandomizeConfig::new()
                 }
+                load_config_input(config)
             };
             let _ = streaming::webrtc::stream(
                 rconfig,
@@ -1391,21 +1605,20 @@ fn main() {
                 println!("[WARNING] Passed both a film file and a random seed --- defaulting to file; seed will be used if file terminates early");
             }
 
-            let rconfig = match config {
-                Some(x) => {
+            let rconfig = if config.is_empty() {
+                if !options.print_silent {
+                    println!("\t using default random value ranges");
+                }
+                debug!(target: "encode","\t using default random value ranges");
+                vidgen::generate_configurations::RandomizeConfig::new()
+            } else {
+                for x in config.iter() {
                     if !options.print_silent {
                         println!("\t loading config file {}", x);
                     }
                     debug!(target: "encode","\t loading config file {}", x);
-                    vidgen::generate_configurations::load_config(x)
-                }
-                _ => {
-                    if !options.print_silent {
-                        println!("\t using default random value ranges");
-                    }
-                    debug!(target: "encode","\t using default random value ranges");
-                    vidgen::generate_configurations::RandomizeConfig::new()
                 }
+                load_config_input(config)
             };
 
             let start_time = SystemTime::now();
@@ -1498,6 +1711,33 @@ fn main() {
                 &encoded_str,
             )
         }
+        Some(Commands::Reconstruct { input, output, md5 }) => {
+            if options.debug_decode {
+                let res = setup_debug_file(options.debug_decode, false, input, output);
+                match res {
+                    Ok(_) => println!("Set up debug logs"),
+                    _ => println!("Issue setting up debug logs"),
+                }
+            }
+
+            if !options.print_silent {
+                println!("Using input file: {}", input);
+                println!("Running in Reconstruct mode");
+            }
+
+            mode_reconstruct(input, output, *md5, &options);
+        }
+        Some(Commands::ValidateConfig { config }) => {
+            let (_, errors) = vidgen::config_layers::layer_config_files(config);
+            if errors.is_empty() {
+                println!("No problems found in {}", config.join(", "));
+            } else {
+                for e in errors.iter() {
+                    println!("ERROR - {}", e);
+                }
+                std::process::exit(1);
+            }
+        }
         Some(Commands::Experimental { input, output }) => {
             if !options.print_silent {
                 println!("Using input file: {}", input);
@@ -1505,7 +1745,13 @@ fn main() {
             }
 
             println!("1. Decoding H.265 Stream");
-            let mut ds = experimental::h265_decoder::decode_bitstream(input, false);
+            let mut ds = match experimental::h265_decoder::decode_bitstream(input, false) {
+                Ok(ds) => ds,
+                Err(e) => {
+                    println!("ERROR - unable to decode {}: {}", input, e);
+                    std::process::exit(1);
+                }
+            };
 
             println!("2. Modifying H.265 Stream");
 
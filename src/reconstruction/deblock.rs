@@ -0,0 +1,432 @@
+//! Deblocking filter process (Section 8.7).
+
+use crate::common::data_structures::MbType;
+use crate::reconstruction::picture::MbInfo;
+use crate::reconstruction::picture::Picture;
+use crate::reconstruction::picture::Plane;
+use crate::reconstruction::transform::chroma_qp;
+
+/// Table 8-16 -- alpha' indexed by indexA
+const ALPHA_TABLE: [i32; 52] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4, 4, 5, 6, 7, 8, 9, 10, 12, 13, 15, 17, 20,
+    22, 25, 28, 32, 36, 40, 45, 50, 56, 63, 71, 80, 90, 101, 113, 127, 144, 162, 182, 203, 226,
+    255, 255,
+];
+
+/// Table 8-16 -- beta' indexed by indexB
+const BETA_TABLE: [i32; 52] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 6, 6, 7, 7, 8, 8,
+    9, 9, 10, 10, 11, 11, 12, 12, 13, 13, 14, 14, 15, 15, 16, 16, 17, 17, 18, 18,
+];
+
+/// Table 8-17 -- tC0' indexed by indexA and bS - 1
+const TC0_TABLE: [[i32; 3]; 52] = [
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 0],
+    [0, 0, 1],
+    [0, 0, 1],
+    [0, 0, 1],
+    [0, 0, 1],
+    [0, 1, 1],
+    [0, 1, 1],
+    [1, 1, 1],
+    [1, 1, 1],
+    [1, 1, 1],
+    [1, 1, 1],
+    [1, 1, 2],
+    [1, 1, 2],
+    [1, 1, 2],
+    [1, 1, 2],
+    [1, 2, 3],
+    [1, 2, 3],
+    [2, 2, 3],
+    [2, 2, 4],
+    [2, 3, 4],
+    [2, 3, 4],
+    [3, 3, 5],
+    [3, 4, 6],
+    [3, 4, 6],
+    [4, 5, 7],
+    [4, 5, 8],
+    [4, 6, 9],
+    [5, 7, 10],
+    [6, 8, 11],
+    [6, 8, 13],
+    [7, 10, 14],
+    [8, 11, 16],
+    [9, 12, 18],
+    [10, 13, 20],
+    [11, 15, 23],
+    [13, 17, 25],
+];
+
+/// Motion vectors of a 4x4 block paired with the picture they refer to
+fn block_motion(mb: &MbInfo, blk: usize) -> Vec<(Option<usize>, [i32; 2])> {
+    (0..2)
+        .filter(|&l| mb.ref_idx[l][blk] >= 0)
+        .map(|l| (mb.ref_pic_id[l][blk], mb.mv[l][blk]))
+        .collect()
+}
+
+/// Returns true if the motion vectors differ by 4 or more in units of quarter luma frame samples
+fn mv_differs(a: [i32; 2], b: [i32; 2]) -> bool {
+    (a[0] as i64 - b[0] as i64).abs() >= 4 || (a[1] as i64 - b[1] as i64).abs() >= 4
+}
+
+/// Section 8.7.2.1 -- derivation of bS for frame macroblocks. `p_blk` and `q_blk` are
+/// raster ordered 4x4 luma blocks of the macroblocks containing p0 and q0
+fn boundary_strength(p: &MbInfo, p_blk: usize, q: &MbInfo, q_blk: usize, mb_edge: bool) -> u8 {
+    let intra = p.is_intra || q.is_intra || p.sp_or_si_slice || q.sp_or_si_slice;
+    if intra {
+        return if mb_edge { 4 } else { 3 };
+    }
+
+    if p.non_zero_coeffs[p_blk] || q.non_zero_coeffs[q_blk] {
+        return 2;
+    }
+
+    let mp = block_motion(p, p_blk);
+    let mq = block_motion(q, q_blk);
+    if mp.len() != mq.len() {
+        return 1;
+    }
+
+    let differs = match mp.len() {
+        1 => mp[0].0 != mq[0].0 || mv_differs(mp[0].1, mq[0].1),
+        2 => {
+            let same_set = (mp[0].0 == mq[0].0 && mp[1].0 == mq[1].0)
+                || (mp[0].0 == mq[1].0 && mp[1].0 == mq[0].0);
+            if !same_set {
+                true
+            } else if mp[0].0 != mp[1].0 {
+                // two different reference pictures, compare the vectors referring to the same picture
+                if mp[0].0 == mq[0].0 {
+                    mv_differs(mp[0].1, mq[0].1) || mv_differs(mp[1].1, mq[1].1)
+                } else {
+                    mv_differs(mp[0].1, mq[1].1) || mv_differs(mp[1].1, mq[0].1)
+                }
+            } else {
+                // both vectors refer to the same picture
+                (mv_differs(mp[0].1, mq[0].1) || mv_differs(mp[1].1, mq[1].1))
+                    && (mv_differs(mp[0].1, mq[1].1) || mv_differs(mp[1].1, mq[0].1))
+            }
+        }
+        _ => false,
+    };
+
+    differs as u8
+}
+
+/// Filter thresholds of an edge, Equations 8-460 to 8-467
+struct EdgeParams {
+    alpha: i32,
+    beta: i32,
+    index_a: usize,
+    bit_depth: u32,
+    chroma_style: bool,
+}
+
+impl EdgeParams {
+    fn new(qp_p: i32, qp_q: i32, q: &MbInfo, bit_depth: u32, chroma_style: bool) -> EdgeParams {
+        let qp_av = (qp_p + qp_q + 1) >> 1;
+        let index_a = (qp_av + q.filter_offset_a).clamp(0, 51) as usize;
+        let index_b = (qp_av + q.filter_offset_b).clamp(0, 51) as usize;
+        let scale = 1 << (bit_depth - 8);
+        EdgeParams {
+            alpha: ALPHA_TABLE[index_a] * scale,
+            beta: BETA_TABLE[index_b] * scale,
+            index_a,
+            bit_depth,
+            chroma_style,
+        }
+    }
+}
+
+/// Section 8.7.2.3 and 8.7.2.4 -- filters one line of samples across an edge. (x, y) is the
+/// location of q0 and `vertical` is set for vertical edges
+fn filter_line(plane: &mut Plane, x: i32, y: i32, vertical: bool, bs: u8, ep: &EdgeParams) {
+    let pos = |i: i32| -> (i32, i32) {
+        if vertical {
+            (x + i, y)
+        } else {
+            (x, y + i)
+        }
+    };
+    // p_i is at -1 - i and q_i is at i
+    let s = |i: i32| {
+        let (sx, sy) = pos(i);
+        plane.get(sx, sy)
+    };
+
+    let (p0, p1, p2) = (s(-1), s(-2), s(-3));
+    let (q0, q1, q2) = (s(0), s(1), s(2));
+
+    let filter_samples_flag = bs != 0
+        && (p0 - q0).abs() < ep.alpha
+        && (p1 - p0).abs() < ep.beta
+        && (q1 - q0).abs() < ep.beta;
+    if !filter_samples_flag {
+        return;
+    }
+
+    let max = (1 << ep.bit_depth) - 1;
+    let ap = (p2 - p0).abs();
+    let aq = (q2 - q0).abs();
+    let mut out: Vec<(i32, i32)> = Vec::with_capacity(6);
+
+    if bs < 4 {
+        let tc0 = TC0_TABLE[ep.index_a][bs as usize - 1] * (1 << (ep.bit_depth - 8));
+        let tc = if ep.chroma_style {
+            tc0 + 1
+        } else {
+            tc0 + (ap < ep.beta) as i32 + (aq < ep.beta) as i32
+        };
+        let delta = (((q0 - p0) * 4 + (p1 - q1) + 4) >> 3).clamp(-tc, tc);
+        out.push((-1, (p0 + delta).clamp(0, max)));
+        out.push((0, (q0 - delta).clamp(0, max)));
+
+        if !ep.chroma_style {
+            if ap < ep.beta {
+                out.push((
+                    -2,
+                    p1 + ((p2 + ((p0 + q0 + 1) >> 1) - (p1 << 1)) >> 1).clamp(-tc0, tc0),
+                ));
+            }
+            if aq < ep.beta {
+                out.push((
+                    1,
+                    q1 + ((q2 + ((p0 + q0 + 1) >> 1) - (q1 << 1)) >> 1).clamp(-tc0, tc0),
+                ));
+            }
+        }
+    } else {
+        let (p3, q3) = (s(-4), s(3));
+        let strong = (p0 - q0).abs() < ((ep.alpha >> 2) + 2);
+
+        if !ep.chroma_style && ap < ep.beta && strong {
+            out.push((-1, (p2 + 2 * p1 + 2 * p0 + 2 * q0 + q1 + 4) >> 3));
+            out.push((-2, (p2 + p1 + p0 + q0 + 2) >> 2));
+            out.push((-3, (2 * p3 + 3 * p2 + p1 + p0 + q0 + 4) >> 3));
+        } else {
+            out.push((-1, (2 * p1 + p0 + q1 + 2) >> 2));
+        }
+
+        if !ep.chroma_style && aq < ep.beta && strong {
+            out.push((0, (p1 + 2 * p0 + 2 * q0 + 2 * q1 + q2 + 4) >> 3));
+            out.push((1, (p0 + q0 + q1 + q2 + 2) >> 2));
+            out.push((2, (2 * q3 + 3 * q2 + q1 + q0 + p0 + 4) >> 3));
+        } else {
+            out.push((0, (2 * q1 + q0 + p1 + 2) >> 2));
+        }
+    }
+
+    for (i, v) in out {
+        let (sx, sy) = pos(i);
+        if sx >= 0 && sy >= 0 {
+            plane.set(sx as usize, sy as usize, v);
+        }
+    }
+}
+
+/// qPp or qPq for luma (Equation 8-453 and its exception for I_PCM and lossless macroblocks)
+fn luma_qp(mb: &MbInfo) -> i32 {
+    if mb.mb_type == MbType::IPCM || mb.transform_bypass {
+        0
+    } else {
+        mb.qp_y
+    }
+}
+
+/// QPc of a macroblock for the chroma component `i_cb_cr`, without the bit depth offset
+fn deblock_chroma_qp(mb: &MbInfo, i_cb_cr: usize, qp_bd_offset_c: i32) -> i32 {
+    let qp_y = if mb.mb_type == MbType::IPCM {
+        0
+    } else {
+        mb.qp_y
+    };
+    chroma_qp(qp_y, mb.chroma_qp_index_offset[i_cb_cr], qp_bd_offset_c) - qp_bd_offset_c
+}
+
+/// A plane filtered across the edges of a macroblock
+struct PlaneEdges {
+    // 0 for luma, or 1 and 2 for Cb and Cr
+    comp: usize,
+    mb_w: i32,
+    mb_h: i32,
+    bit_depth: u32,
+    chroma_style: bool,
+}
+
+/// Section 8.7 -- applies the deblocking filter to a reconstructed frame, in macroblock address order
+pub fn deblock_picture(pic: &mut Picture) {
+    let w = pic.pic_width_in_mbs;
+    let qp_bd_offset_c = 6 * (pic.bit_depth_chroma as i32 - 8);
+
+    let mut planes = vec![PlaneEdges {
+        comp: 0,
+        mb_w: 16,
+        mb_h: 16,
+        bit_depth: pic.bit_depth_luma,
+        chroma_style: false,
+    }];
+    if pic.cb.width > 0 && pic.cb.height > 0 {
+        let (mb_w, mb_h) = match pic.chroma_format_idc {
+            1 => (8, 8),
+            2 => (8, 16),
+            _ => (16, 16),
+        };
+        for comp in 1..3 {
+            planes.push(PlaneEdges {
+                comp,
+                mb_w,
+                mb_h,
+                bit_depth: pic.bit_depth_chroma,
+                chroma_style: pic.chroma_format_idc != 3,
+            });
+        }
+    }
+
+    for addr in 0..pic.mb_info.len() {
+        let q = pic.mb_info[addr].clone();
+        if !q.available || q.disable_deblocking_filter_idc == 1 {
+            continue;
+        }
+
+        let neighbor = |n: Option<usize>| -> Option<MbInfo> {
+            let n = n?;
+            let mb = &pic.mb_info[n];
+            if !mb.available
+                || (q.disable_deblocking_filter_idc == 2 && mb.slice_num != q.slice_num)
+            {
+                None
+            } else {
+                Some(mb.clone())
+            }
+        };
+        let left = neighbor(if addr % w > 0 { Some(addr - 1) } else { None });
+        let top = neighbor(if addr >= w { Some(addr - w) } else { None });
+
+        // bS for each direction (0 is vertical edges), edge, and 4 sample segment along the edge
+        let mut bs = [[[0u8; 4]; 4]; 2];
+        for (dir, bs_dir) in bs.iter_mut().enumerate() {
+            for (edge, bs_edge) in bs_dir.iter_mut().enumerate() {
+                for (seg, v) in bs_edge.iter_mut().enumerate() {
+                    let q_blk = if dir == 0 {
+                        seg * 4 + edge
+                    } else {
+                        edge * 4 + seg
+                    };
+                    *v = if edge == 0 {
+                        let p = if dir == 0 { &left } else { &top };
+                        match p {
+                            Some(p) => {
+                                let p_blk = if dir == 0 { seg * 4 + 3 } else { 12 + seg };
+                                boundary_strength(p, p_blk, &q, q_blk, true)
+                            }
+                            None => 0,
+                        }
+                    } else {
+                        let p_blk = if dir == 0 { q_blk - 1 } else { q_blk - 4 };
+                        boundary_strength(&q, p_blk, &q, q_blk, false)
+                    };
+                }
+            }
+        }
+
+        let mb_col = (addr % w) as i32;
+        let mb_row = (addr / w) as i32;
+
+        for pe in planes.iter() {
+            let (qp_p_left, qp_p_top, qp_q) = if pe.comp == 0 {
+                (
+                    left.as_ref().map(luma_qp),
+                    top.as_ref().map(luma_qp),
+                    luma_qp(&q),
+                )
+            } else {
+                let i_cb_cr = pe.comp - 1;
+                (
+                    left.as_ref()
+                        .map(|m| deblock_chroma_qp(m, i_cb_cr, qp_bd_offset_c)),
+                    top.as_ref()
+                        .map(|m| deblock_chroma_qp(m, i_cb_cr, qp_bd_offset_c)),
+                    deblock_chroma_qp(&q, i_cb_cr, qp_bd_offset_c),
+                )
+            };
+
+            let x0 = mb_col * pe.mb_w;
+            let y0 = mb_row * pe.mb_h;
+
+            for (dir, bs_dir) in bs.iter().enumerate() {
+                let vertical = dir == 0;
+                // edges are in units of luma 4x4 blocks
+                for (edge, bs_edge) in bs_dir.iter().enumerate() {
+                    let qp_p = match (edge, vertical) {
+                        (0, true) => qp_p_left,
+                        (0, false) => qp_p_top,
+                        _ => Some(qp_q),
+                    };
+                    let qp_p = match qp_p {
+                        Some(qp) => qp,
+                        None => continue,
+                    };
+
+                    // the position of the edge within the plane's macroblock, and the luma sample
+                    // scale of the samples along the edge
+                    let (offset, along_scale) = if pe.chroma_style {
+                        let (sub_w, sub_h) = (16 / pe.mb_w, 16 / pe.mb_h);
+                        let sub = if vertical { sub_w } else { sub_h };
+                        let luma_pos = edge as i32 * 4;
+                        // only edges that fall on a chroma 4x4 block boundary are filtered
+                        if (luma_pos / sub) % 4 != 0 {
+                            continue;
+                        }
+                        (luma_pos / sub, if vertical { sub_h } else { sub_w })
+                    } else {
+                        if q.transform_size_8x8_flag && edge % 2 == 1 {
+                            continue;
+                        }
+                        (edge as i32 * 4, 1)
+                    };
+
+                    let ep = EdgeParams::new(qp_p, qp_q, &q, pe.bit_depth, pe.chroma_style);
+                    let len = if vertical { pe.mb_h } else { pe.mb_w };
+                    let plane = match pe.comp {
+                        0 => &mut pic.luma,
+                        1 => &mut pic.cb,
+                        _ => &mut pic.cr,
+                    };
+                    for k in 0..len {
+                        // the segment is taken from the luma sample covering this location
+                        let seg = ((k * along_scale) / 4) as usize;
+                        let b = bs_edge[seg.min(3)];
+                        if b == 0 {
+                            continue;
+                        }
+                        let (x, y) = if vertical {
+                            (x0 + offset, y0 + k)
+                        } else {
+                            (x0 + k, y0 + offset)
+                        };
+                        filter_line(plane, x, y, vertical, b, &ep);
+                    }
+                }
+            }
+        }
+    }
+}
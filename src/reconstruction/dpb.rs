@@ -0,0 +1,567 @@
+//! Picture order count, reference picture lists and decoded reference picture marking
+//! (Sections 8.2.1, 8.2.4 and 8.2.5).
+
+use crate::common::data_structures::NALUheader;
+use crate::common::data_structures::SeqParameterSet;
+use crate::common::data_structures::SliceHeader;
+use crate::common::helper::is_slice_type;
+use crate::reconstruction::picture::Picture;
+
+/// More reference frames than this are never kept, even if a random SPS asks for it
+const MAX_REF_FRAMES: usize = 16;
+
+/// A frame marked as used for reference
+#[derive(Debug, Clone)]
+pub struct RefFrame {
+    pub pic: Picture,
+    pub frame_num: u32,
+    // set if the frame is marked as used for long-term reference
+    pub long_term_frame_idx: Option<u32>,
+}
+
+impl RefFrame {
+    pub fn is_long_term(&self) -> bool {
+        self.long_term_frame_idx.is_some()
+    }
+}
+
+/// Values of the current picture needed by the decoding process of the next picture
+#[derive(Debug, Clone, Copy, Default)]
+struct PocState {
+    pic_order_cnt_msb: i32,
+    pic_order_cnt_lsb: i32,
+    frame_num_offset: i32,
+    frame_num: u32,
+    top_field_order_cnt: i32,
+    bottom_field_order_cnt: i32,
+}
+
+/// The reference frames of the decoded picture buffer and the state used to derive
+/// picture order counts
+#[derive(Debug, Clone, Default)]
+pub struct Dpb {
+    pub frames: Vec<RefFrame>,
+    max_long_term_frame_idx: Option<u32>,
+    // the previous reference picture, for POC type 0 and gaps in frame_num
+    prev_ref: PocState,
+    prev_ref_has_mmco5: bool,
+    // the previous picture, for POC types 1 and 2
+    prev: PocState,
+    prev_has_mmco5: bool,
+    curr: PocState,
+}
+
+/// Returns MaxFrameNum, Equation 7-10
+pub fn max_frame_num(sps: &SeqParameterSet) -> i64 {
+    1i64 << (sps.log2_max_frame_num_minus4.min(12) + 4)
+}
+
+impl Dpb {
+    pub fn new() -> Dpb {
+        Default::default()
+    }
+
+    /// Section 8.2.1 -- derives the picture order count of the current frame. The result
+    /// is PicOrderCnt( CurrPic ), the minimum of TopFieldOrderCnt and BottomFieldOrderCnt
+    pub fn decode_poc(&mut self, sps: &SeqParameterSet, nh: &NALUheader, sh: &SliceHeader) -> i32 {
+        let idr = nh.nal_unit_type == 5;
+        let is_ref = nh.nal_ref_idc != 0;
+        let max_frame_num = max_frame_num(sps);
+
+        let mut curr = PocState {
+            frame_num: sh.frame_num,
+            ..Default::default()
+        };
+
+        match sps.pic_order_cnt_type {
+            0 => {
+                // Section 8.2.1.1
+                let (prev_msb, prev_lsb) = if idr {
+                    (0, 0)
+                } else if self.prev_ref_has_mmco5 {
+                    (0, self.prev_ref.top_field_order_cnt)
+                } else {
+                    (
+                        self.prev_ref.pic_order_cnt_msb,
+                        self.prev_ref.pic_order_cnt_lsb,
+                    )
+                };
+                let max_lsb = 1i32 << (sps.log2_max_pic_order_cnt_lsb_minus4.min(12) + 4);
+                let lsb = sh.pic_order_cnt_lsb as i32;
+
+                // Equation 8-3
+                let msb = if lsb < prev_lsb && (prev_lsb - lsb) >= max_lsb / 2 {
+                    prev_msb.wrapping_add(max_lsb)
+                } else if lsb > prev_lsb && (lsb - prev_lsb) > max_lsb / 2 {
+                    prev_msb.wrapping_sub(max_lsb)
+                } else {
+                    prev_msb
+                };
+
+                curr.pic_order_cnt_msb = msb;
+                curr.pic_order_cnt_lsb = lsb;
+                curr.top_field_order_cnt = msb.wrapping_add(lsb);
+                curr.bottom_field_order_cnt = curr
+                    .top_field_order_cnt
+                    .wrapping_add(sh.delta_pic_order_cnt_bottom);
+            }
+            1 | 2 => {
+                // Equations 8-6 and 8-11
+                let prev_frame_num_offset = if self.prev_has_mmco5 {
+                    0
+                } else {
+                    self.prev.frame_num_offset
+                };
+                let frame_num_offset = if idr {
+                    0
+                } else if self.prev.frame_num > sh.frame_num {
+                    prev_frame_num_offset.wrapping_add(max_frame_num as i32)
+                } else {
+                    prev_frame_num_offset
+                };
+                curr.frame_num_offset = frame_num_offset;
+
+                if sps.pic_order_cnt_type == 1 {
+                    // Section 8.2.1.2
+                    let cycle_len = sps
+                        .num_ref_frames_in_pic_order_cnt_cycle
+                        .min(sps.offset_for_ref_frame.len() as u32)
+                        as i64;
+                    let mut abs_frame_num = if cycle_len != 0 {
+                        frame_num_offset as i64 + sh.frame_num as i64
+                    } else {
+                        0
+                    };
+                    if !is_ref && abs_frame_num > 0 {
+                        abs_frame_num -= 1;
+                    }
+
+                    let mut expected_poc: i64 = 0;
+                    if abs_frame_num > 0 {
+                        let delta_per_cycle: i64 = sps.offset_for_ref_frame[..cycle_len as usize]
+                            .iter()
+                            .map(|&x| x as i64)
+                            .sum();
+                        let cycle_cnt = (abs_frame_num - 1) / cycle_len;
+                        let in_cycle = (abs_frame_num - 1) % cycle_len;
+                        expected_poc = cycle_cnt * delta_per_cycle;
+                        for i in 0..=in_cycle as usize {
+                            expected_poc += sps.offset_for_ref_frame[i] as i64;
+                        }
+                    }
+                    if !is_ref {
+                        expected_poc += sps.offset_for_non_ref_pic as i64;
+                    }
+
+                    let delta = |i: usize| *sh.delta_pic_order_cnt.get(i).unwrap_or(&0) as i64;
+                    let top = expected_poc + delta(0);
+                    let bottom = top + sps.offset_for_top_to_bottom_field as i64 + delta(1);
+                    curr.top_field_order_cnt = top as i32;
+                    curr.bottom_field_order_cnt = bottom as i32;
+                } else {
+                    // Section 8.2.1.3
+                    let temp = if idr {
+                        0
+                    } else if !is_ref {
+                        2 * (frame_num_offset as i64 + sh.frame_num as i64) - 1
+                    } else {
+                        2 * (frame_num_offset as i64 + sh.frame_num as i64)
+                    };
+                    curr.top_field_order_cnt = temp as i32;
+                    curr.bottom_field_order_cnt = temp as i32;
+                }
+            }
+            _ => (),
+        }
+
+        self.curr = curr;
+        curr.top_field_order_cnt.min(curr.bottom_field_order_cnt)
+    }
+
+    /// Section 8.2.5.2 -- inserts "non-existing" frames for each missing frame_num value, using
+    /// the samples of `template`
+    pub fn fill_frame_num_gap(
+        &mut self,
+        sps: &SeqParameterSet,
+        nh: &NALUheader,
+        sh: &SliceHeader,
+        template: &Picture,
+    ) {
+        let max_frame_num = max_frame_num(sps);
+        let prev_ref_frame_num = self.prev_ref.frame_num as i64;
+        let frame_num = sh.frame_num as i64;
+
+        if nh.nal_unit_type == 5
+            || frame_num == prev_ref_frame_num
+            || frame_num == (prev_ref_frame_num + 1) % max_frame_num
+        {
+            return;
+        }
+
+        if !sps.gaps_in_frame_num_value_allowed_flag {
+            println!(
+                "\t reconstruct_stream - frame_num gap from {} to {} without gaps_in_frame_num_value_allowed_flag",
+                prev_ref_frame_num, frame_num
+            );
+            return;
+        }
+
+        let mut unused = (prev_ref_frame_num + 1) % max_frame_num;
+        // at most one buffer worth of frames can be referenced after the gap
+        let mut count = 0;
+        while unused != frame_num && count < max_frame_num {
+            count += 1;
+            let skipped = (frame_num - unused).rem_euclid(max_frame_num) as usize;
+            if skipped <= MAX_REF_FRAMES {
+                self.sliding_window(sps, unused as u32, max_frame_num);
+                let mut pic = template.clone();
+                pic.frame_num = unused as u32;
+                self.frames.push(RefFrame {
+                    pic,
+                    frame_num: unused as u32,
+                    long_term_frame_idx: None,
+                });
+            }
+            unused = (unused + 1) % max_frame_num;
+        }
+
+        // the last inferred frame becomes the previous picture
+        let last = (frame_num - 1).rem_euclid(max_frame_num);
+        if last < self.prev.frame_num as i64 {
+            let base = if self.prev_has_mmco5 {
+                0
+            } else {
+                self.prev.frame_num_offset
+            };
+            self.prev.frame_num_offset = base.wrapping_add(max_frame_num as i32);
+            self.prev_has_mmco5 = false;
+        }
+        self.prev_ref.frame_num = last as u32;
+        self.prev.frame_num = last as u32;
+    }
+
+    /// Equation 8-27 -- FrameNumWrap of a reference frame
+    fn frame_num_wrap(&self, f: &RefFrame, curr_frame_num: u32, max_frame_num: i64) -> i64 {
+        if f.frame_num > curr_frame_num {
+            f.frame_num as i64 - max_frame_num
+        } else {
+            f.frame_num as i64
+        }
+    }
+
+    /// Section 8.2.4 -- returns RefPicList0 and RefPicList1 as indices into `frames`.
+    /// Entries that do not refer to a frame are set to None
+    pub fn ref_pic_lists(
+        &self,
+        sps: &SeqParameterSet,
+        sh: &SliceHeader,
+        curr_poc: i32,
+    ) -> [Vec<Option<usize>>; 2] {
+        let max_frame_num = max_frame_num(sps);
+        let short_term: Vec<usize> = (0..self.frames.len())
+            .filter(|&i| !self.frames[i].is_long_term())
+            .collect();
+        let mut long_term: Vec<usize> = (0..self.frames.len())
+            .filter(|&i| self.frames[i].is_long_term())
+            .collect();
+        long_term.sort_by_key(|&i| self.frames[i].long_term_frame_idx);
+
+        let mut lists: [Vec<usize>; 2] = [Vec::new(), Vec::new()];
+        if is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "SP") {
+            // Section 8.2.4.2.1 -- descending PicNum, then ascending LongTermPicNum
+            let mut st = short_term;
+            st.sort_by_key(|&i| -self.frame_num_wrap(&self.frames[i], sh.frame_num, max_frame_num));
+            lists[0] = st;
+            lists[0].extend(long_term.iter());
+        } else if is_slice_type(sh.slice_type, "B") {
+            // Section 8.2.4.2.3
+            let poc = |i: &usize| self.frames[*i].pic.poc;
+            let mut before: Vec<usize> = short_term
+                .iter()
+                .copied()
+                .filter(|i| poc(i) <= curr_poc)
+                .collect();
+            let mut after: Vec<usize> = short_term
+                .iter()
+                .copied()
+                .filter(|i| poc(i) > curr_poc)
+                .collect();
+            before.sort_by_key(|i| -(poc(i) as i64));
+            after.sort_by_key(poc);
+
+            lists[0] = before.clone();
+            lists[0].extend(after.iter());
+            lists[0].extend(long_term.iter());
+
+            lists[1] = after;
+            lists[1].extend(before.iter());
+            lists[1].extend(long_term.iter());
+
+            if lists[1].len() > 1 && lists[0] == lists[1] {
+                lists[1].swap(0, 1);
+            }
+        }
+
+        let num_active = [
+            sh.num_ref_idx_l0_active_minus1 as usize + 1,
+            sh.num_ref_idx_l1_active_minus1 as usize + 1,
+        ];
+        let modification = [
+            (
+                sh.ref_pic_list_modification_flag_l0,
+                &sh.modification_of_pic_nums_idc_l0,
+                &sh.abs_diff_pic_num_minus1_l0,
+                &sh.long_term_pic_num_l0,
+            ),
+            (
+                sh.ref_pic_list_modification_flag_l1,
+                &sh.modification_of_pic_nums_idc_l1,
+                &sh.abs_diff_pic_num_minus1_l1,
+                &sh.long_term_pic_num_l1,
+            ),
+        ];
+
+        let mut res: [Vec<Option<usize>>; 2] = [Vec::new(), Vec::new()];
+        let num_lists = if is_slice_type(sh.slice_type, "B") {
+            2
+        } else if is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "SP") {
+            1
+        } else {
+            0
+        };
+        for x in 0..num_lists {
+            // conforming streams have at most 32 entries; keep random values from allocating too much
+            let n = num_active[x].min(32);
+            let mut list: Vec<Option<usize>> = lists[x].iter().map(|&i| Some(i)).collect();
+            list.resize(n, None);
+
+            let (flag, idcs, abs_diffs, long_term_pic_nums) = modification[x];
+            if flag {
+                self.modify_ref_pic_list(
+                    &mut list,
+                    sh.frame_num,
+                    max_frame_num,
+                    idcs,
+                    abs_diffs,
+                    long_term_pic_nums,
+                );
+            }
+            list.truncate(n);
+            res[x] = list;
+        }
+        res
+    }
+
+    /// Section 8.2.4.3 -- modification process for reference picture lists of frames
+    fn modify_ref_pic_list(
+        &self,
+        list: &mut Vec<Option<usize>>,
+        curr_frame_num: u32,
+        max_frame_num: i64,
+        idcs: &[u32],
+        abs_diffs: &[u32],
+        long_term_pic_nums: &[u32],
+    ) {
+        let num = list.len();
+        let curr_pic_num = curr_frame_num as i64;
+        let mut pic_num_pred = curr_pic_num;
+        let mut ref_idx = 0;
+
+        for (i, &idc) in idcs.iter().enumerate() {
+            if idc == 3 || ref_idx >= num {
+                break;
+            }
+
+            let target = match idc {
+                0 | 1 => {
+                    // Section 8.2.4.3.1
+                    let abs_diff = *abs_diffs.get(i).unwrap_or(&0) as i64 + 1;
+                    let mut no_wrap = if idc == 0 {
+                        pic_num_pred - abs_diff
+                    } else {
+                        pic_num_pred + abs_diff
+                    };
+                    no_wrap = no_wrap.rem_euclid(max_frame_num);
+                    pic_num_pred = no_wrap;
+                    let pic_num = if no_wrap > curr_pic_num {
+                        no_wrap - max_frame_num
+                    } else {
+                        no_wrap
+                    };
+                    let found = (0..self.frames.len()).find(|&k| {
+                        !self.frames[k].is_long_term()
+                            && self.frame_num_wrap(&self.frames[k], curr_frame_num, max_frame_num)
+                                == pic_num
+                    });
+                    (found, false)
+                }
+                2 => {
+                    // Section 8.2.4.3.2
+                    let long_term_pic_num = *long_term_pic_nums.get(i).unwrap_or(&0);
+                    let found = (0..self.frames.len())
+                        .find(|&k| self.frames[k].long_term_frame_idx == Some(long_term_pic_num));
+                    (found, true)
+                }
+                // 4 and 5 are only used for MVC inter-view prediction
+                _ => continue,
+            };
+
+            // insert the picture at ref_idx and remove its later duplicate
+            list.insert(ref_idx, target.0);
+            ref_idx += 1;
+            let mut n_idx = ref_idx;
+            for c_idx in ref_idx..list.len() {
+                let same = match (list[c_idx], target.0) {
+                    (Some(a), Some(b)) => a == b && self.frames[a].is_long_term() == target.1,
+                    _ => false,
+                };
+                if !same {
+                    list[n_idx] = list[c_idx];
+                    n_idx += 1;
+                }
+            }
+            // entries past the active length are dropped, Equation 8-37 keeps one spare entry
+            list.truncate(num);
+        }
+    }
+
+    /// Section 8.2.5.3 -- sliding window marking, made before the current frame is stored
+    fn sliding_window(&mut self, sps: &SeqParameterSet, curr_frame_num: u32, max_frame_num: i64) {
+        let max_refs = (sps.max_num_ref_frames as usize).clamp(1, MAX_REF_FRAMES);
+        while self.frames.len() >= max_refs {
+            let oldest = (0..self.frames.len())
+                .filter(|&i| !self.frames[i].is_long_term())
+                .min_by_key(|&i| {
+                    self.frame_num_wrap(&self.frames[i], curr_frame_num, max_frame_num)
+                });
+            match oldest {
+                Some(i) => {
+                    self.frames.remove(i);
+                }
+                // only long-term frames left, so the stream is not conforming; drop the oldest
+                None => {
+                    self.frames.remove(0);
+                }
+            }
+        }
+    }
+
+    /// Section 8.2.5 -- marks the reference frames after decoding the current picture and stores
+    /// it in the buffer if it is a reference picture. Returns true if a
+    /// memory_management_control_operation equal to 5 was applied
+    pub fn mark_and_store(
+        &mut self,
+        mut pic: Picture,
+        sps: &SeqParameterSet,
+        nh: &NALUheader,
+        sh: &SliceHeader,
+    ) -> bool {
+        let is_ref = nh.nal_ref_idc != 0;
+        let max_frame_num = max_frame_num(sps);
+        let mut mmco5 = false;
+        let mut long_term_frame_idx: Option<u32> = None;
+
+        if is_ref {
+            if nh.nal_unit_type == 5 {
+                self.frames.clear();
+                if sh.long_term_reference_flag {
+                    long_term_frame_idx = Some(0);
+                    self.max_long_term_frame_idx = Some(0);
+                } else {
+                    self.max_long_term_frame_idx = None;
+                }
+            } else if sh.adaptive_ref_pic_marking_mode_flag {
+                // Section 8.2.5.4
+                let curr_pic_num = sh.frame_num;
+                for (i, &mmco) in sh.memory_management_control_operation.iter().enumerate() {
+                    let diff = *sh.difference_of_pic_nums_minus1.get(i).unwrap_or(&0) as i64;
+                    let pic_num_x = curr_pic_num as i64 - (diff + 1);
+                    let find_short_term = |dpb: &Dpb| {
+                        (0..dpb.frames.len()).find(|&k| {
+                            !dpb.frames[k].is_long_term()
+                                && dpb.frame_num_wrap(&dpb.frames[k], curr_pic_num, max_frame_num)
+                                    == pic_num_x
+                        })
+                    };
+                    match mmco {
+                        1 => {
+                            if let Some(k) = find_short_term(self) {
+                                self.frames.remove(k);
+                            }
+                        }
+                        2 => {
+                            let long_term_pic_num = *sh.long_term_pic_num.get(i).unwrap_or(&0);
+                            self.frames
+                                .retain(|f| f.long_term_frame_idx != Some(long_term_pic_num));
+                        }
+                        3 => {
+                            let idx = *sh.long_term_frame_idx.get(i).unwrap_or(&0);
+                            if let Some(k) = find_short_term(self) {
+                                let id = self.frames[k].pic.id;
+                                self.frames.retain(|f| {
+                                    f.long_term_frame_idx != Some(idx) || f.pic.id == id
+                                });
+                                if let Some(f) = self.frames.iter_mut().find(|f| f.pic.id == id) {
+                                    f.long_term_frame_idx = Some(idx);
+                                }
+                            }
+                        }
+                        4 => {
+                            let plus1 = *sh.max_long_term_frame_idx_plus1.get(i).unwrap_or(&0);
+                            self.max_long_term_frame_idx = plus1.checked_sub(1);
+                            let max = self.max_long_term_frame_idx;
+                            self.frames.retain(|f| match (f.long_term_frame_idx, max) {
+                                (Some(idx), Some(m)) => idx <= m,
+                                (Some(_), None) => false,
+                                _ => true,
+                            });
+                        }
+                        5 => {
+                            self.frames.clear();
+                            self.max_long_term_frame_idx = None;
+                            mmco5 = true;
+                        }
+                        6 => {
+                            let idx = *sh.long_term_frame_idx.get(i).unwrap_or(&0);
+                            self.frames.retain(|f| f.long_term_frame_idx != Some(idx));
+                            long_term_frame_idx = Some(idx);
+                        }
+                        _ => (),
+                    }
+                }
+            } else {
+                self.sliding_window(sps, sh.frame_num, max_frame_num);
+            }
+        }
+
+        let mut curr = self.curr;
+        if mmco5 {
+            // Section 8.2.1 -- the picture is treated as having frame_num and POC starting from 0
+            let temp = curr.top_field_order_cnt.min(curr.bottom_field_order_cnt);
+            curr.top_field_order_cnt = curr.top_field_order_cnt.wrapping_sub(temp);
+            curr.bottom_field_order_cnt = curr.bottom_field_order_cnt.wrapping_sub(temp);
+            curr.frame_num = 0;
+            pic.poc = 0;
+            pic.frame_num = 0;
+        }
+
+        self.prev = curr;
+        self.prev_has_mmco5 = mmco5;
+        if is_ref {
+            self.prev_ref = curr;
+            self.prev_ref_has_mmco5 = mmco5;
+
+            // keep the buffer bounded for non-conforming marking
+            while self.frames.len() >= MAX_REF_FRAMES {
+                self.frames.remove(0);
+            }
+            self.frames.push(RefFrame {
+                frame_num: pic.frame_num,
+                pic,
+                long_term_frame_idx,
+            });
+        }
+
+        mmco5
+    }
+}
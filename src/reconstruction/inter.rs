@@ -0,0 +1,867 @@
+//! Inter prediction process (Section 8.4).
+
+use crate::common::data_structures::MacroBlock;
+use crate::common::data_structures::MbPartPredMode;
+use crate::common::data_structures::MbType;
+use crate::common::data_structures::PicParameterSet;
+use crate::common::data_structures::SeqParameterSet;
+use crate::common::data_structures::SliceHeader;
+use crate::common::data_structures::SubMbType;
+use crate::common::helper::is_slice_type;
+use crate::reconstruction::dpb::Dpb;
+use crate::reconstruction::dpb::RefFrame;
+use crate::reconstruction::intra::neighbor_mb_location;
+use crate::reconstruction::picture::Picture;
+use crate::reconstruction::picture::Plane;
+
+/// Slice and macroblock state used by inter prediction
+pub struct InterContext<'a> {
+    pub curr_mb_addr: usize,
+    pub slice_num: usize,
+    pub sps: &'a SeqParameterSet,
+    pub pps: &'a PicParameterSet,
+    pub sh: &'a SliceHeader,
+    pub dpb: &'a Dpb,
+    // RefPicList0 and RefPicList1 as indices into the DPB frames
+    pub ref_lists: &'a [Vec<Option<usize>>; 2],
+    pub curr_poc: i32,
+}
+
+impl<'a> InterContext<'a> {
+    /// Returns the frame referred to by a reference index, if any
+    fn ref_frame(&self, list: usize, ref_idx: i32) -> Option<&'a RefFrame> {
+        if ref_idx < 0 {
+            return None;
+        }
+        let idx = (*self.ref_lists[list].get(ref_idx as usize)?)?;
+        self.dpb.frames.get(idx)
+    }
+}
+
+/// Motion of a partition for one list
+#[derive(Debug, Clone, Copy)]
+struct Motion {
+    ref_idx: i32,
+    mv: [i32; 2],
+}
+
+const NO_MOTION: Motion = Motion {
+    ref_idx: -1,
+    mv: [0, 0],
+};
+
+/// Returns the raster index of the 4x4 block covering the luma location (x, y) in a macroblock
+fn raster_blk(x: usize, y: usize) -> usize {
+    (y / 4) * 4 + x / 4
+}
+
+/// Section 8.4.1.3.2 -- motion of the neighboring partition covering (x, y), relative to the current
+/// macroblock. Returns None if the partition is not available, and NO_MOTION if it is intra coded or
+/// does not use the list. `decoded` marks the 4x4 blocks of the current macroblock already decoded
+fn neighbor_motion(
+    pic: &Picture,
+    ctx: &InterContext,
+    decoded: u16,
+    x: i32,
+    y: i32,
+    list: usize,
+) -> Option<Motion> {
+    let (addr, x_w, y_w) = neighbor_mb_location(pic, ctx.curr_mb_addr, x, y, 16, 16)?;
+    let blk = raster_blk(x_w, y_w);
+    let mb = &pic.mb_info[addr];
+
+    if addr == ctx.curr_mb_addr {
+        if decoded & (1 << blk) == 0 {
+            return None;
+        }
+    } else if !mb.available || mb.slice_num != ctx.slice_num {
+        return None;
+    }
+
+    if mb.is_intra || mb.ref_idx[list][blk] < 0 {
+        return Some(NO_MOTION);
+    }
+    Some(Motion {
+        ref_idx: mb.ref_idx[list][blk],
+        mv: mb.mv[list][blk],
+    })
+}
+
+/// Returns the neighbors A, B and C of a partition at (x, y) with width `pred_part_width`,
+/// substituting D for C when C is not available
+fn neighbors_abc(
+    pic: &Picture,
+    ctx: &InterContext,
+    decoded: u16,
+    x: i32,
+    y: i32,
+    pred_part_width: i32,
+    list: usize,
+) -> [Option<Motion>; 3] {
+    let a = neighbor_motion(pic, ctx, decoded, x - 1, y, list);
+    let b = neighbor_motion(pic, ctx, decoded, x, y - 1, list);
+    let c = match neighbor_motion(pic, ctx, decoded, x + pred_part_width, y - 1, list) {
+        Some(c) => Some(c),
+        None => neighbor_motion(pic, ctx, decoded, x - 1, y - 1, list),
+    };
+    [a, b, c]
+}
+
+/// Partition shapes with directional motion vector prediction
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PartShape {
+    Other,
+    P16x8(usize),
+    P8x16(usize),
+}
+
+/// Section 8.4.1.3 -- derivation of the luma motion vector prediction mvpLX
+#[allow(clippy::too_many_arguments)]
+fn mv_pred(
+    pic: &Picture,
+    ctx: &InterContext,
+    decoded: u16,
+    x: i32,
+    y: i32,
+    pred_part_width: i32,
+    shape: PartShape,
+    list: usize,
+    ref_idx: i32,
+) -> [i32; 2] {
+    let [a, b, c] = neighbors_abc(pic, ctx, decoded, x, y, pred_part_width, list);
+
+    let motion = |n: Option<Motion>| n.unwrap_or(NO_MOTION);
+    let (ma, mb, mc) = (motion(a), motion(b), motion(c));
+
+    match shape {
+        PartShape::P16x8(0) if mb.ref_idx == ref_idx => return mb.mv,
+        PartShape::P16x8(_) if shape != PartShape::P16x8(0) && ma.ref_idx == ref_idx => {
+            return ma.mv
+        }
+        PartShape::P8x16(0) if ma.ref_idx == ref_idx => return ma.mv,
+        PartShape::P8x16(_) if shape != PartShape::P8x16(0) && mc.ref_idx == ref_idx => {
+            return mc.mv
+        }
+        _ => (),
+    }
+
+    // Section 8.4.1.3.1 -- median luma motion vector prediction
+    let (mb, mc) = if b.is_none() && c.is_none() && a.is_some() {
+        (ma, ma)
+    } else {
+        (mb, mc)
+    };
+
+    let matches = [ma, mb, mc].iter().filter(|m| m.ref_idx == ref_idx).count();
+    if matches == 1 {
+        if ma.ref_idx == ref_idx {
+            ma.mv
+        } else if mb.ref_idx == ref_idx {
+            mb.mv
+        } else {
+            mc.mv
+        }
+    } else {
+        let median = |a: i32, b: i32, c: i32| a.max(b).min(a.min(b).max(c));
+        [
+            median(ma.mv[0], mb.mv[0], mc.mv[0]),
+            median(ma.mv[1], mb.mv[1], mc.mv[1]),
+        ]
+    }
+}
+
+/// Section 8.4.1.1 -- luma motion vector for P_Skip macroblocks
+fn p_skip_mv(pic: &Picture, ctx: &InterContext) -> [i32; 2] {
+    let a = neighbor_motion(pic, ctx, 0, -1, 0, 0);
+    let b = neighbor_motion(pic, ctx, 0, 0, -1, 0);
+    let zero = |n: Option<Motion>| match n {
+        None => true,
+        Some(m) => m.ref_idx == 0 && m.mv == [0, 0],
+    };
+    if zero(a) || zero(b) {
+        [0, 0]
+    } else {
+        mv_pred(pic, ctx, 0, 0, 0, 16, PartShape::Other, 0, 0)
+    }
+}
+
+/// Section 8.4.1.2.1 -- motion of the co-located 4x4 block in RefPicList1[ 0 ]. Returns mvCol,
+/// refIdxCol and the picture referred to by refIdxCol
+fn colocated(ctx: &InterContext, blk: usize) -> ([i32; 2], i32, Option<usize>) {
+    let col = match ctx.ref_frame(1, 0) {
+        Some(f) => f,
+        None => return ([0, 0], -1, None),
+    };
+
+    let blk = if ctx.sps.direct_8x8_inference_flag {
+        // the corner 4x4 block of the 8x8 quadrant
+        let quadrant = ((blk / 4) / 2) * 2 + (blk % 4) / 2;
+        [0, 3, 12, 15][quadrant]
+    } else {
+        blk
+    };
+
+    let mb = match col.pic.mb_info.get(ctx.curr_mb_addr) {
+        Some(mb) => mb,
+        None => return ([0, 0], -1, None),
+    };
+    if !mb.available || mb.is_intra {
+        return ([0, 0], -1, None);
+    }
+
+    let list = if mb.ref_idx[0][blk] >= 0 { 0 } else { 1 };
+    (
+        mb.mv[list][blk],
+        mb.ref_idx[list][blk],
+        mb.ref_pic_id[list][blk],
+    )
+}
+
+/// Equation 8-197 -- DistScaleFactor for the given POC distances
+fn dist_scale_factor(curr_poc: i32, poc0: i32, poc1: i32) -> Option<i32> {
+    let tb = (curr_poc as i64 - poc0 as i64).clamp(-128, 127) as i32;
+    let td = (poc1 as i64 - poc0 as i64).clamp(-128, 127) as i32;
+    if td == 0 {
+        return None;
+    }
+    let tx = (16384 + (td / 2).abs()) / td;
+    Some(((tb * tx + 32) >> 6).clamp(-1024, 1023))
+}
+
+/// Direct prediction motion of both lists for the 4x4 blocks of a macroblock
+type DirectMotion = [Motion; 2];
+
+/// Spatial direct prediction values shared by all blocks of a macroblock (Section 8.4.1.2.2)
+struct SpatialDirect {
+    ref_idx: [i32; 2],
+    mvp: [[i32; 2]; 2],
+    direct_zero_prediction: bool,
+}
+
+fn spatial_direct_params(pic: &Picture, ctx: &InterContext) -> SpatialDirect {
+    let min_positive = |x: i32, y: i32| {
+        if x >= 0 && y >= 0 {
+            x.min(y)
+        } else {
+            x.max(y)
+        }
+    };
+
+    let mut ref_idx = [-1; 2];
+    for (list, r) in ref_idx.iter_mut().enumerate() {
+        let [a, b, c] = neighbors_abc(pic, ctx, 0, 0, 0, 16, list);
+        let r_of = |n: Option<Motion>| n.unwrap_or(NO_MOTION).ref_idx;
+        *r = min_positive(r_of(a), min_positive(r_of(b), r_of(c)));
+    }
+
+    let direct_zero_prediction = ref_idx[0] < 0 && ref_idx[1] < 0;
+    if direct_zero_prediction {
+        ref_idx = [0, 0];
+    }
+
+    let mut mvp = [[0; 2]; 2];
+    for list in 0..2 {
+        if !direct_zero_prediction && ref_idx[list] >= 0 {
+            mvp[list] = mv_pred(pic, ctx, 0, 0, 0, 16, PartShape::Other, list, ref_idx[list]);
+        }
+    }
+
+    SpatialDirect {
+        ref_idx,
+        mvp,
+        direct_zero_prediction,
+    }
+}
+
+/// Sections 8.4.1.2.2 and 8.4.1.2.3 -- direct prediction motion of the 4x4 block `blk` (raster order)
+fn direct_motion(ctx: &InterContext, spatial: &Option<SpatialDirect>, blk: usize) -> DirectMotion {
+    let (mv_col, ref_idx_col, ref_pic_col) = colocated(ctx, blk);
+
+    match spatial {
+        Some(sp) => {
+            let col_is_short_term = match ctx.ref_frame(1, 0) {
+                Some(f) => !f.is_long_term(),
+                None => false,
+            };
+            let col_zero_flag = col_is_short_term
+                && ref_idx_col == 0
+                && (-1..=1).contains(&mv_col[0])
+                && (-1..=1).contains(&mv_col[1]);
+
+            [0, 1].map(|list| {
+                let ref_idx = sp.ref_idx[list];
+                let mv = if sp.direct_zero_prediction
+                    || ref_idx < 0
+                    || (ref_idx == 0 && col_zero_flag)
+                {
+                    [0, 0]
+                } else {
+                    sp.mvp[list]
+                };
+                Motion { ref_idx, mv }
+            })
+        }
+        None => {
+            // refIdxL0 is the lowest valued index in RefPicList0 referencing the co-located reference
+            let ref_idx_l0 = if ref_idx_col < 0 {
+                0
+            } else {
+                ctx.ref_lists[0]
+                    .iter()
+                    .position(|r| match r {
+                        Some(i) => Some(ctx.dpb.frames[*i].pic.id) == ref_pic_col,
+                        None => false,
+                    })
+                    .unwrap_or(0) as i32
+            };
+
+            let pic0 = ctx.ref_frame(0, ref_idx_l0);
+            let pic1 = ctx.ref_frame(1, 0);
+            let dsf = match (pic0, pic1) {
+                (Some(p0), Some(p1)) if !p0.is_long_term() => {
+                    dist_scale_factor(ctx.curr_poc, p0.pic.poc, p1.pic.poc)
+                }
+                _ => None,
+            };
+
+            let (mv_l0, mv_l1) = match dsf {
+                Some(dsf) => {
+                    let scale = |c: i32| ((dsf as i64 * c as i64 + 128) >> 8) as i32;
+                    let mv_l0 = [scale(mv_col[0]), scale(mv_col[1])];
+                    (
+                        mv_l0,
+                        [
+                            mv_l0[0].wrapping_sub(mv_col[0]),
+                            mv_l0[1].wrapping_sub(mv_col[1]),
+                        ],
+                    )
+                }
+                None => (mv_col, [0, 0]),
+            };
+
+            [
+                Motion {
+                    ref_idx: ref_idx_l0,
+                    mv: mv_l0,
+                },
+                Motion {
+                    ref_idx: 0,
+                    mv: mv_l1,
+                },
+            ]
+        }
+    }
+}
+
+/// Stores the motion of a rectangle of 4x4 blocks in the current macroblock
+fn set_motion(
+    pic: &mut Picture,
+    ctx: &InterContext,
+    x: usize,
+    y: usize,
+    w: usize,
+    h: usize,
+    motion: &[Motion; 2],
+) {
+    let ref_pic_id = [0, 1].map(|l| ctx.ref_frame(l, motion[l].ref_idx).map(|f| f.pic.id));
+    let info = &mut pic.mb_info[ctx.curr_mb_addr];
+    for by in (y..y + h).step_by(4) {
+        for bx in (x..x + w).step_by(4) {
+            let blk = raster_blk(bx, by);
+            for l in 0..2 {
+                info.ref_idx[l][blk] = motion[l].ref_idx;
+                info.mv[l][blk] = motion[l].mv;
+                info.ref_pic_id[l][blk] = ref_pic_id[l];
+            }
+        }
+    }
+}
+
+/// Returns the bit mask of the 4x4 blocks of a rectangle in the current macroblock
+fn block_mask(x: usize, y: usize, w: usize, h: usize) -> u16 {
+    let mut mask = 0u16;
+    for by in (y..y + h).step_by(4) {
+        for bx in (x..x + w).step_by(4) {
+            mask |= 1 << raster_blk(bx, by);
+        }
+    }
+    mask
+}
+
+/// Returns which lists a partition prediction mode uses
+fn pred_lists(mode: MbPartPredMode) -> [bool; 2] {
+    match mode {
+        MbPartPredMode::PredL0 => [true, false],
+        MbPartPredMode::PredL1 => [false, true],
+        MbPartPredMode::BiPred => [true, true],
+        _ => [false, false],
+    }
+}
+
+/// Section 8.4.1 -- derives the motion vectors and reference indices of every 4x4 block of an inter
+/// macroblock, and stores them in the macroblock's MbInfo
+pub fn derive_motion(pic: &mut Picture, ctx: &InterContext, mb: &MacroBlock) {
+    let mut spatial: Option<SpatialDirect> = None;
+    if is_slice_type(ctx.sh.slice_type, "B") && ctx.sh.direct_spatial_mv_pred_flag {
+        spatial = Some(spatial_direct_params(pic, ctx));
+    }
+
+    let mut decoded: u16 = 0;
+    match mb.mb_type {
+        MbType::PSkip => {
+            let mv = p_skip_mv(pic, ctx);
+            set_motion(
+                pic,
+                ctx,
+                0,
+                0,
+                16,
+                16,
+                &[Motion { ref_idx: 0, mv }, NO_MOTION],
+            );
+        }
+        MbType::BSkip | MbType::BDirect16x16 => {
+            for blk in 0..16 {
+                let m = direct_motion(ctx, &spatial, blk);
+                set_motion(pic, ctx, (blk % 4) * 4, (blk / 4) * 4, 4, 4, &m);
+            }
+        }
+        MbType::P8x8 | MbType::P8x8ref0 | MbType::B8x8 => {
+            for part in 0..4 {
+                let (x, y) = ((part % 2) * 8, (part / 2) * 8);
+                if mb.sub_mb_type[part] == SubMbType::BDirect8x8 {
+                    for sub in 0..4 {
+                        let (bx, by) = (x + (sub % 2) * 4, y + (sub / 2) * 4);
+                        let m = direct_motion(ctx, &spatial, raster_blk(bx, by));
+                        set_motion(pic, ctx, bx, by, 4, 4, &m);
+                    }
+                    decoded |= block_mask(x, y, 8, 8);
+                    continue;
+                }
+
+                let lists = pred_lists(mb.sub_mb_part_pred_mode(part));
+                let (w, h) = mb.sub_mb_part_pred_width_and_height(part);
+                if w == 0 || h == 0 {
+                    continue;
+                }
+                for sub in 0..mb.num_sub_mb_part(part) {
+                    let sx = x + (sub % (8 / w)) * w;
+                    let sy = y + (sub / (8 / w)) * h;
+                    let mut motion = [NO_MOTION; 2];
+                    for (l, m) in motion.iter_mut().enumerate() {
+                        if !lists[l] {
+                            continue;
+                        }
+                        let ref_idx = if mb.mb_type == MbType::P8x8ref0 {
+                            0
+                        } else if l == 0 {
+                            mb.ref_idx_l0[part] as i32
+                        } else {
+                            mb.ref_idx_l1[part] as i32
+                        };
+                        let mvp = mv_pred(
+                            pic,
+                            ctx,
+                            decoded,
+                            sx as i32,
+                            sy as i32,
+                            w as i32,
+                            PartShape::Other,
+                            l,
+                            ref_idx,
+                        );
+                        let mvd = if l == 0 {
+                            mb.mvd_l0[part][sub]
+                        } else {
+                            mb.mvd_l1[part][sub]
+                        };
+                        *m = Motion {
+                            ref_idx,
+                            mv: [mvp[0].wrapping_add(mvd[0]), mvp[1].wrapping_add(mvd[1])],
+                        };
+                    }
+                    set_motion(pic, ctx, sx, sy, w, h, &motion);
+                    decoded |= block_mask(sx, sy, w, h);
+                }
+            }
+        }
+        _ => {
+            let (w, h) = mb.mb_part_pred_width_and_height();
+            for part in 0..mb.num_mb_part() {
+                let (x, y) = if w == 16 {
+                    (0, part * h)
+                } else {
+                    (part * w, 0)
+                };
+                let shape = match (w, h) {
+                    (16, 8) => PartShape::P16x8(part),
+                    (8, 16) => PartShape::P8x16(part),
+                    _ => PartShape::Other,
+                };
+                let lists = pred_lists(mb.mb_part_pred_mode(part));
+                let mut motion = [NO_MOTION; 2];
+                for (l, m) in motion.iter_mut().enumerate() {
+                    if !lists[l] {
+                        continue;
+                    }
+                    let ref_idx = if l == 0 {
+                        mb.ref_idx_l0[part] as i32
+                    } else {
+                        mb.ref_idx_l1[part] as i32
+                    };
+                    let mvp = mv_pred(
+                        pic, ctx, decoded, x as i32, y as i32, w as i32, shape, l, ref_idx,
+                    );
+                    let mvd = if l == 0 {
+                        mb.mvd_l0[part][0]
+                    } else {
+                        mb.mvd_l1[part][0]
+                    };
+                    *m = Motion {
+                        ref_idx,
+                        mv: [mvp[0].wrapping_add(mvd[0]), mvp[1].wrapping_add(mvd[1])],
+                    };
+                }
+                set_motion(pic, ctx, x, y, w, h, &motion);
+                decoded |= block_mask(x, y, w, h);
+            }
+        }
+    }
+}
+
+/// 6-tap filter of Equation 8-241
+fn tap(s: [i32; 6]) -> i32 {
+    s[0] - 5 * s[1] + 20 * s[2] + 20 * s[3] - 5 * s[4] + s[5]
+}
+
+/// Section 8.4.2.2.1 -- luma sample interpolation at the full-sample location (x_int, y_int)
+/// with a quarter sample offset of (x_frac, y_frac)
+fn luma_sample(
+    plane: &Plane,
+    x_int: i32,
+    y_int: i32,
+    x_frac: i32,
+    y_frac: i32,
+    bit_depth: u32,
+) -> i32 {
+    let max = (1 << bit_depth) - 1;
+    let p = |dx: i32, dy: i32| plane.get(x_int.saturating_add(dx), y_int.saturating_add(dy));
+    let clip = |v: i32| v.clamp(0, max);
+
+    // intermediate values b1 (horizontal half sample) and h1 (vertical half sample)
+    let b1 = |dy: i32| tap([-2, -1, 0, 1, 2, 3].map(|dx| p(dx, dy)));
+    let h1 = |dx: i32| tap([-2, -1, 0, 1, 2, 3].map(|dy| p(dx, dy)));
+    let b = || clip((b1(0) + 16) >> 5);
+    let h = || clip((h1(0) + 16) >> 5);
+    let s = || clip((b1(1) + 16) >> 5);
+    let m = || clip((h1(1) + 16) >> 5);
+    let j = || clip((tap([-2, -1, 0, 1, 2, 3].map(b1)) + 512) >> 10);
+    let avg = |a: i32, b: i32| (a + b + 1) >> 1;
+
+    // Table 8-12
+    match (x_frac, y_frac) {
+        (0, 0) => p(0, 0),
+        (0, 1) => avg(p(0, 0), h()),
+        (0, 2) => h(),
+        (0, 3) => avg(p(0, 1), h()),
+        (1, 0) => avg(p(0, 0), b()),
+        (1, 1) => avg(b(), h()),
+        (1, 2) => avg(h(), j()),
+        (1, 3) => avg(h(), s()),
+        (2, 0) => b(),
+        (2, 1) => avg(b(), j()),
+        (2, 2) => j(),
+        (2, 3) => avg(j(), s()),
+        (3, 0) => avg(p(1, 0), b()),
+        (3, 1) => avg(b(), m()),
+        (3, 2) => avg(j(), m()),
+        _ => avg(m(), s()),
+    }
+}
+
+/// Section 8.4.2.2.2 -- chroma sample interpolation in units of 1/8 sample
+fn chroma_sample(plane: &Plane, x_int: i32, y_int: i32, x_frac: i32, y_frac: i32) -> i32 {
+    let a = plane.get(x_int, y_int);
+    let b = plane.get(x_int.saturating_add(1), y_int);
+    let c = plane.get(x_int, y_int.saturating_add(1));
+    let d = plane.get(x_int.saturating_add(1), y_int.saturating_add(1));
+    ((8 - x_frac) * (8 - y_frac) * a
+        + x_frac * (8 - y_frac) * b
+        + (8 - x_frac) * y_frac * c
+        + x_frac * y_frac * d
+        + 32)
+        >> 6
+}
+
+/// Weights of Section 8.4.2.3 for one colour component. None selects the default weighted prediction
+#[derive(Debug, Clone, Copy)]
+struct Weights {
+    log_wd: i32,
+    w: [i32; 2],
+    o: [i32; 2],
+}
+
+/// Section 8.4.2.3 -- derivation of the prediction weights for a pair of reference indices
+fn prediction_weights(
+    ctx: &InterContext,
+    ref_idx: [i32; 2],
+    comp: usize,
+    bit_depth: u32,
+) -> Option<Weights> {
+    let sh = ctx.sh;
+    let explicit = if is_slice_type(sh.slice_type, "B") {
+        match ctx.pps.weighted_bipred_idc {
+            1 => true,
+            2 => {
+                // implicit weights only apply to bi-predicted blocks
+                if ref_idx[0] < 0 || ref_idx[1] < 0 {
+                    return None;
+                }
+                let mut w = [32, 32];
+                if let (Some(p0), Some(p1)) =
+                    (ctx.ref_frame(0, ref_idx[0]), ctx.ref_frame(1, ref_idx[1]))
+                {
+                    if !p0.is_long_term() && !p1.is_long_term() {
+                        if let Some(dsf) = dist_scale_factor(ctx.curr_poc, p0.pic.poc, p1.pic.poc) {
+                            if (-64..=128).contains(&(dsf >> 2)) {
+                                w = [64 - (dsf >> 2), dsf >> 2];
+                            }
+                        }
+                    }
+                }
+                return Some(Weights {
+                    log_wd: 5,
+                    w,
+                    o: [0, 0],
+                });
+            }
+            _ => false,
+        }
+    } else {
+        ctx.pps.weighted_pred_flag
+    };
+
+    if !explicit {
+        return None;
+    }
+
+    let log_wd = if comp == 0 {
+        sh.luma_log2_weight_denom
+    } else {
+        sh.chroma_log2_weight_denom
+    }
+    .min(7) as i32;
+    let offset_scale = 1 << (bit_depth - 8);
+
+    let mut w = [1 << log_wd; 2];
+    let mut o = [0; 2];
+    for list in 0..2 {
+        if ref_idx[list] < 0 {
+            continue;
+        }
+        let r = ref_idx[list] as usize;
+        let (luma_flag, luma_w, luma_o, chroma_flag, chroma_w, chroma_o) = if list == 0 {
+            (
+                &sh.luma_weight_l0_flag,
+                &sh.luma_weight_l0,
+                &sh.luma_offset_l0,
+                &sh.chroma_weight_l0_flag,
+                &sh.chroma_weight_l0,
+                &sh.chroma_offset_l0,
+            )
+        } else {
+            (
+                &sh.luma_weight_l1_flag,
+                &sh.luma_weight_l1,
+                &sh.luma_offset_l1,
+                &sh.chroma_weight_l1_flag,
+                &sh.chroma_weight_l1,
+                &sh.chroma_offset_l1,
+            )
+        };
+
+        if comp == 0 {
+            if *luma_flag.get(r).unwrap_or(&false) {
+                w[list] = *luma_w.get(r).unwrap_or(&0);
+                o[list] = luma_o.get(r).unwrap_or(&0).wrapping_mul(offset_scale);
+            }
+        } else if *chroma_flag.get(r).unwrap_or(&false) {
+            let get = |v: &Vec<Vec<i32>>| *v.get(r).and_then(|c| c.get(comp - 1)).unwrap_or(&0);
+            w[list] = get(chroma_w);
+            o[list] = get(chroma_o).wrapping_mul(offset_scale);
+        }
+    }
+
+    Some(Weights { log_wd, w, o })
+}
+
+/// Section 8.4.2.3 -- combines the prediction samples of both lists
+fn weighted_sample(pred: [Option<i32>; 2], weights: &Option<Weights>, bit_depth: u32) -> i32 {
+    let max = (1i64 << bit_depth) - 1;
+    let res: i64 = match (pred, weights) {
+        ([Some(p0), Some(p1)], None) => ((p0 + p1 + 1) >> 1) as i64,
+        ([Some(p), None], None) | ([None, Some(p)], None) => p as i64,
+        ([Some(p0), Some(p1)], Some(wt)) => {
+            let log_wd = wt.log_wd as i64;
+            ((p0 as i64 * wt.w[0] as i64 + p1 as i64 * wt.w[1] as i64 + (1 << log_wd))
+                >> (log_wd + 1))
+                + ((wt.o[0] as i64 + wt.o[1] as i64 + 1) >> 1)
+        }
+        ([Some(p), None], Some(wt)) | ([None, Some(p)], Some(wt)) => {
+            let list = if pred[0].is_some() { 0 } else { 1 };
+            let log_wd = wt.log_wd as i64;
+            let v = p as i64 * wt.w[list] as i64;
+            if log_wd >= 1 {
+                ((v + (1 << (log_wd - 1))) >> log_wd) + wt.o[list] as i64
+            } else {
+                v + wt.o[list] as i64
+            }
+        }
+        _ => 1 << (bit_depth - 1),
+    };
+    res.clamp(0, max) as i32
+}
+
+/// Section 8.4.2 -- inter prediction samples of the current macroblock, raster ordered per component.
+/// Blocks referring to a missing reference picture are predicted as mid-gray
+pub fn predict_inter(
+    pic: &Picture,
+    ctx: &InterContext,
+    mb_width_c: usize,
+    mb_height_c: usize,
+) -> [Vec<i32>; 3] {
+    let info = &pic.mb_info[ctx.curr_mb_addr];
+    let w = pic.pic_width_in_mbs;
+    let mb_x = (ctx.curr_mb_addr % w) * 16;
+    let mb_y = (ctx.curr_mb_addr / w) * 16;
+    let has_chroma = mb_width_c > 0 && mb_height_c > 0;
+    let (sub_w, sub_h) = if has_chroma {
+        (16 / mb_width_c, 16 / mb_height_c)
+    } else {
+        (1, 1)
+    };
+
+    let mut pred = [
+        vec![0; 256],
+        vec![0; mb_width_c * mb_height_c],
+        vec![0; mb_width_c * mb_height_c],
+    ];
+
+    for blk in 0..16 {
+        let (bx, by) = ((blk % 4) * 4, (blk / 4) * 4);
+        let ref_idx = [info.ref_idx[0][blk], info.ref_idx[1][blk]];
+        let mv = [info.mv[0][blk], info.mv[1][blk]];
+        let frames = [ctx.ref_frame(0, ref_idx[0]), ctx.ref_frame(1, ref_idx[1])];
+        let used = [ref_idx[0] >= 0, ref_idx[1] >= 0];
+
+        for (comp, comp_pred) in pred.iter_mut().enumerate() {
+            if comp > 0 && !has_chroma {
+                break;
+            }
+            let bit_depth = if comp == 0 {
+                pic.bit_depth_luma
+            } else {
+                pic.bit_depth_chroma
+            };
+            let weights = prediction_weights(ctx, ref_idx, comp, bit_depth);
+            let (cw, ch, cx, cy, stride) = if comp == 0 {
+                (4, 4, bx, by, 16)
+            } else {
+                (4 / sub_w, 4 / sub_h, bx / sub_w, by / sub_h, mb_width_c)
+            };
+            let (px, py) = if comp == 0 {
+                (mb_x, mb_y)
+            } else {
+                (mb_x / sub_w, mb_y / sub_h)
+            };
+
+            for y in 0..ch {
+                for x in 0..cw {
+                    let mut samples = [None; 2];
+                    let mut missing = false;
+                    for list in 0..2 {
+                        if !used[list] {
+                            continue;
+                        }
+                        let frame = match frames[list] {
+                            Some(f) => f,
+                            None => {
+                                missing = true;
+                                continue;
+                            }
+                        };
+                        let plane = match comp {
+                            0 => &frame.pic.luma,
+                            1 => &frame.pic.cb,
+                            _ => &frame.pic.cr,
+                        };
+                        let (ax, ay) = ((px + cx + x) as i32, (py + cy + y) as i32);
+                        let (mvx, mvy) = (mv[list][0], mv[list][1]);
+                        samples[list] = Some(if comp == 0 || pic.chroma_format_idc == 3 {
+                            luma_sample(
+                                plane,
+                                ax.saturating_add(mvx >> 2),
+                                ay.saturating_add(mvy >> 2),
+                                mvx & 3,
+                                mvy & 3,
+                                bit_depth,
+                            )
+                        } else {
+                            // chroma vectors are in units of 1/(4 * SubWidthC) and 1/(4 * SubHeightC) samples
+                            let (x_int, x_frac) = (ax.saturating_add(mvx >> 3), mvx & 7);
+                            let (y_int, y_frac) = if sub_h == 2 {
+                                (ay.saturating_add(mvy >> 3), mvy & 7)
+                            } else {
+                                (ay.saturating_add(mvy >> 2), (mvy & 3) << 1)
+                            };
+                            chroma_sample(plane, x_int, y_int, x_frac, y_frac)
+                        });
+                    }
+
+                    comp_pred[(cy + y) * stride + cx + x] = if missing {
+                        1 << (bit_depth - 1)
+                    } else {
+                        weighted_sample(samples, &weights, bit_depth)
+                    };
+                }
+            }
+        }
+    }
+
+    pred
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_luma_interpolation_flat_plane() {
+        let plane = Plane::new(16, 16, 100);
+        for x_frac in 0..4 {
+            for y_frac in 0..4 {
+                assert_eq!(luma_sample(&plane, 4, 4, x_frac, y_frac, 8), 100);
+            }
+        }
+    }
+
+    #[test]
+    fn test_luma_interpolation_half_sample() {
+        // a vertical edge between columns 3 and 4
+        let mut plane = Plane::new(16, 16, 0);
+        for y in 0..16 {
+            for x in 4..16 {
+                plane.set(x, y, 64);
+            }
+        }
+        // b = (E - 5F + 20G + 20H - 5I + J + 16) >> 5 = (20 * 64 - 5 * 64 + 64 + 16) >> 5
+        assert_eq!(luma_sample(&plane, 3, 8, 2, 0, 8), 32);
+        assert_eq!(luma_sample(&plane, 3, 8, 0, 2, 8), 0);
+    }
+
+    #[test]
+    fn test_dist_scale_factor() {
+        assert_eq!(dist_scale_factor(2, 0, 4), Some(128));
+        assert_eq!(dist_scale_factor(2, 4, 4), None);
+    }
+}
@@ -0,0 +1,622 @@
+//! Intra prediction process (Section 8.3).
+
+use crate::common::data_structures::MbType;
+use crate::reconstruction::picture::MbInfo;
+use crate::reconstruction::picture::Picture;
+use crate::reconstruction::picture::Plane;
+
+/// State of the macroblock currently being predicted
+#[derive(Debug, Clone, Copy)]
+pub struct IntraContext {
+    pub curr_mb_addr: usize,
+    pub slice_num: usize,
+    pub constrained_intra_pred_flag: bool,
+}
+
+/// Returns true if the macroblock uses Intra_4x4 prediction
+pub fn is_intra_4x4(mb: &MbInfo) -> bool {
+    (mb.mb_type == MbType::INxN && !mb.transform_size_8x8_flag) || mb.mb_type == MbType::SI
+}
+
+/// Returns true if the macroblock uses Intra_8x8 prediction
+pub fn is_intra_8x8(mb: &MbInfo) -> bool {
+    mb.mb_type == MbType::INxN && mb.transform_size_8x8_flag
+}
+
+/// Returns Intra16x16PredMode from the mb_type name (Table 7-11)
+pub fn intra_16x16_pred_mode(mb_type: MbType) -> u8 {
+    ((mb_type as usize - MbType::I16x16_0_0_0 as usize) % 4) as u8
+}
+
+/// Section 6.4.1 -- upper-left luma sample of a 4x4 luma block
+pub fn luma_4x4_blk_pos(blk: usize) -> (usize, usize) {
+    let x = ((blk / 4) % 2) * 8 + ((blk % 4) % 2) * 4;
+    let y = ((blk / 4) / 2) * 8 + ((blk % 4) / 2) * 4;
+    (x, y)
+}
+
+/// Equation 6-38 -- 4x4 luma block index covering a location inside a macroblock
+fn luma_4x4_blk_idx(x: usize, y: usize) -> usize {
+    8 * (y / 8) + 4 * (x / 8) + 2 * ((y % 8) / 4) + ((x % 8) / 4)
+}
+
+/// Equation 6-40 -- 8x8 luma block index covering a location inside a macroblock
+fn luma_8x8_blk_idx(x: usize, y: usize) -> usize {
+    2 * (y / 8) + (x / 8)
+}
+
+/// Section 6.4.12.1 -- returns the macroblock containing the neighboring location (xN, yN) relative to
+/// the upper-left sample of the macroblock `curr_mb_addr` and the location relative to that macroblock.
+/// Returns None if the location lies outside of the picture or after the current macroblock in
+/// decoding order. The availability of the returned macroblock is left to the caller
+pub fn neighbor_mb_location(
+    pic: &Picture,
+    curr_mb_addr: usize,
+    x_n: i32,
+    y_n: i32,
+    max_w: i32,
+    max_h: i32,
+) -> Option<(usize, usize, usize)> {
+    let w = pic.pic_width_in_mbs;
+    let curr = curr_mb_addr;
+    let mb_col = curr % w;
+    let left_edge = mb_col == 0;
+    let right_edge = mb_col + 1 == w;
+
+    let mb_addr_n = if x_n < 0 && y_n < 0 {
+        // mbAddrD
+        if left_edge || curr < w {
+            return None;
+        }
+        curr - w - 1
+    } else if x_n < 0 && y_n < max_h {
+        // mbAddrA
+        if left_edge {
+            return None;
+        }
+        curr - 1
+    } else if x_n < max_w && y_n < 0 {
+        // mbAddrB
+        if curr < w {
+            return None;
+        }
+        curr - w
+    } else if x_n < max_w && y_n < max_h {
+        curr
+    } else if y_n < 0 {
+        // mbAddrC
+        if right_edge || curr < w {
+            return None;
+        }
+        curr - w + 1
+    } else {
+        return None;
+    };
+
+    let x_w = (x_n + max_w) % max_w;
+    let y_w = (y_n + max_h) % max_h;
+    Some((mb_addr_n, x_w as usize, y_w as usize))
+}
+
+/// Section 6.4.12.1 for intra prediction -- returns None if the neighboring location is marked as not
+/// available for Intra prediction, or lies outside of the picture
+fn neighbor_location(
+    pic: &Picture,
+    ctx: &IntraContext,
+    x_n: i32,
+    y_n: i32,
+    max_w: i32,
+    max_h: i32,
+) -> Option<(usize, usize, usize)> {
+    let n = neighbor_mb_location(pic, ctx.curr_mb_addr, x_n, y_n, max_w, max_h)?;
+    if n.0 != ctx.curr_mb_addr && !mb_available_for_intra(pic, ctx, n.0) {
+        return None;
+    }
+    Some(n)
+}
+
+/// Section 6.4.8 with the constrained_intra_pred_flag restriction in 8.3.1.2
+fn mb_available_for_intra(pic: &Picture, ctx: &IntraContext, mb_addr: usize) -> bool {
+    match pic.mb_info.get(mb_addr) {
+        Some(mb) => {
+            mb.available
+                && mb.slice_num == ctx.slice_num
+                && mb_addr <= ctx.curr_mb_addr
+                && (mb.is_intra || !ctx.constrained_intra_pred_flag)
+        }
+        None => false,
+    }
+}
+
+/// Sections 8.3.1.1 and 8.3.2.1 -- returns predIntraMxMPredMode, the minimum of the prediction modes
+/// of the neighbors of the block at (x, y) in the current macroblock
+fn neighbor_pred_modes(pic: &Picture, ctx: &IntraContext, x: i32, y: i32, is_8x8: bool) -> u8 {
+    let a = neighbor_location(pic, ctx, x - 1, y, 16, 16);
+    let b = neighbor_location(pic, ctx, x, y - 1, 16, 16);
+
+    let (a, b) = match (a, b) {
+        (Some(a), Some(b)) => (a, b),
+        // dcPredModePredictedFlag is equal to 1
+        _ => return 2,
+    };
+
+    let mode_of = |n: (usize, usize, usize), is_a: bool| -> u8 {
+        let mb = &pic.mb_info[n.0];
+        if is_intra_4x4(mb) {
+            if is_8x8 {
+                // 8.3.2.1 uses 4x4 block 1 of the 8x8 block to the left, or block 2 of the one above
+                let blk8 = luma_8x8_blk_idx(n.1, n.2);
+                let sub = if is_a { 1 } else { 2 };
+                mb.intra4x4_pred_mode[blk8 * 4 + sub]
+            } else {
+                mb.intra4x4_pred_mode[luma_4x4_blk_idx(n.1, n.2)]
+            }
+        } else if is_intra_8x8(mb) {
+            mb.intra8x8_pred_mode[luma_8x8_blk_idx(n.1, n.2)]
+        } else {
+            2
+        }
+    };
+
+    mode_of(a, true).min(mode_of(b, false))
+}
+
+/// Section 8.3.1.1 -- derivation of Intra4x4PredMode
+pub fn derive_intra4x4_pred_mode(
+    pic: &Picture,
+    ctx: &IntraContext,
+    blk: usize,
+    prev_flag: bool,
+    rem_mode: u32,
+) -> u8 {
+    let (x, y) = luma_4x4_blk_pos(blk);
+    let pred = neighbor_pred_modes(pic, ctx, x as i32, y as i32, false);
+    derive_mode(pred, prev_flag, rem_mode)
+}
+
+/// Section 8.3.2.1 -- derivation of Intra8x8PredMode
+pub fn derive_intra8x8_pred_mode(
+    pic: &Picture,
+    ctx: &IntraContext,
+    blk8: usize,
+    prev_flag: bool,
+    rem_mode: u32,
+) -> u8 {
+    let x = (blk8 % 2) * 8;
+    let y = (blk8 / 2) * 8;
+    let pred = neighbor_pred_modes(pic, ctx, x as i32, y as i32, true);
+    derive_mode(pred, prev_flag, rem_mode)
+}
+
+fn derive_mode(pred: u8, prev_flag: bool, rem_mode: u32) -> u8 {
+    if prev_flag {
+        pred
+    } else if rem_mode < pred as u32 {
+        rem_mode as u8
+    } else {
+        // out of range values are kept so they can be reported, prediction falls back to DC
+        (rem_mode + 1).min(255) as u8
+    }
+}
+
+/// Neighboring samples used for prediction. `top[0]` is p[-1, -1] and `top[x + 1]` is p[x, -1]
+struct RefSamples {
+    top: Vec<i32>,
+    left: Vec<i32>,
+    top_avail: bool,
+    top_right_avail: bool,
+    left_avail: bool,
+    top_left_avail: bool,
+}
+
+/// Fetches the neighboring samples of a block of size `w`x`h` at (bx, by) inside the current macroblock,
+/// with `top_right` samples to the right of the block above it
+#[allow(clippy::too_many_arguments)]
+fn fetch_ref_samples(
+    pic: &Picture,
+    plane: &Plane,
+    ctx: &IntraContext,
+    bx: i32,
+    by: i32,
+    w: i32,
+    h: i32,
+    top_right: i32,
+    mb_w: i32,
+    mb_h: i32,
+    default: i32,
+) -> RefSamples {
+    let w_in_mbs = pic.pic_width_in_mbs as i32;
+    let mb_x = (ctx.curr_mb_addr as i32 % w_in_mbs) * mb_w;
+    let mb_y = (ctx.curr_mb_addr as i32 / w_in_mbs) * mb_h;
+
+    let avail = |x: i32, y: i32| neighbor_location(pic, ctx, x, y, mb_w, mb_h).is_some();
+    let sample = |x: i32, y: i32| plane.get(mb_x + x, mb_y + y);
+
+    let top_avail = avail(bx, by - 1);
+    let top_right_avail = top_right > 0 && avail(bx + w, by - 1);
+    let left_avail = avail(bx - 1, by);
+    let top_left_avail = avail(bx - 1, by - 1);
+
+    let mut top = vec![default; (w + top_right + 1) as usize];
+    let mut left = vec![default; h as usize];
+
+    if top_left_avail {
+        top[0] = sample(bx - 1, by - 1);
+    }
+    if top_avail {
+        for x in 0..w {
+            top[(x + 1) as usize] = sample(bx + x, by - 1);
+        }
+    }
+    for x in w..w + top_right {
+        top[(x + 1) as usize] = if top_right_avail {
+            sample(bx + x, by - 1)
+        } else if top_avail {
+            // substitute p[w - 1, -1] for unavailable top right samples
+            top[w as usize]
+        } else {
+            default
+        };
+    }
+    if left_avail {
+        for y in 0..h {
+            left[y as usize] = sample(bx - 1, by + y);
+        }
+    }
+
+    RefSamples {
+        top,
+        left,
+        top_avail,
+        top_right_avail,
+        left_avail,
+        top_left_avail,
+    }
+}
+
+/// Section 8.3.2.2.1 -- reference sample filtering for Intra_8x8 prediction
+fn filter_8x8_ref_samples(r: &RefSamples) -> RefSamples {
+    let p = |x: i32| r.top[(x + 1) as usize];
+    let l = |y: i32| r.left[y as usize];
+    let mut top = r.top.clone();
+    let mut left = r.left.clone();
+
+    if r.top_avail {
+        top[1] = if r.top_left_avail {
+            (p(-1) + 2 * p(0) + p(1) + 2) >> 2
+        } else {
+            (3 * p(0) + p(1) + 2) >> 2
+        };
+        for x in 1..15 {
+            top[(x + 1) as usize] = (p(x - 1) + 2 * p(x) + p(x + 1) + 2) >> 2;
+        }
+        top[16] = (p(14) + 3 * p(15) + 2) >> 2;
+    }
+
+    if r.top_left_avail {
+        top[0] = if r.top_avail && r.left_avail {
+            (p(0) + 2 * p(-1) + l(0) + 2) >> 2
+        } else if r.top_avail {
+            (3 * p(-1) + p(0) + 2) >> 2
+        } else if r.left_avail {
+            (3 * p(-1) + l(0) + 2) >> 2
+        } else {
+            p(-1)
+        };
+    }
+
+    if r.left_avail {
+        left[0] = if r.top_left_avail {
+            (p(-1) + 2 * l(0) + l(1) + 2) >> 2
+        } else {
+            (3 * l(0) + l(1) + 2) >> 2
+        };
+        for y in 1..7 {
+            left[y as usize] = (l(y - 1) + 2 * l(y) + l(y + 1) + 2) >> 2;
+        }
+        left[7] = (l(6) + 3 * l(7) + 2) >> 2;
+    }
+
+    RefSamples {
+        top,
+        left,
+        top_avail: r.top_avail,
+        top_right_avail: r.top_right_avail,
+        left_avail: r.left_avail,
+        top_left_avail: r.top_left_avail,
+    }
+}
+
+/// Sections 8.3.1.2 and 8.3.2.2 -- the directional predictions shared by Intra_4x4 and Intra_8x8,
+/// for a block of size n
+fn predict_nxn(r: &RefSamples, n: i32, mode: u8, default: i32) -> Vec<i32> {
+    let p = |x: i32| r.top[(x + 1) as usize];
+    let l = |y: i32| {
+        if y < 0 {
+            r.top[0]
+        } else {
+            r.left[y as usize]
+        }
+    };
+    let log2n = if n == 4 { 2 } else { 3 };
+
+    let mut pred = vec![0; (n * n) as usize];
+    for y in 0..n {
+        for x in 0..n {
+            let v = match mode {
+                // Vertical
+                0 => p(x),
+                // Horizontal
+                1 => l(y),
+                // Diagonal_Down_Left
+                3 => {
+                    if x == n - 1 && y == n - 1 {
+                        (p(2 * n - 2) + 3 * p(2 * n - 1) + 2) >> 2
+                    } else {
+                        (p(x + y) + 2 * p(x + y + 1) + p(x + y + 2) + 2) >> 2
+                    }
+                }
+                // Diagonal_Down_Right
+                4 => {
+                    if x > y {
+                        (p(x - y - 2) + 2 * p(x - y - 1) + p(x - y) + 2) >> 2
+                    } else if x < y {
+                        (l(y - x - 2) + 2 * l(y - x - 1) + l(y - x) + 2) >> 2
+                    } else {
+                        (p(0) + 2 * p(-1) + l(0) + 2) >> 2
+                    }
+                }
+                // Vertical_Right
+                5 => {
+                    let z = 2 * x - y;
+                    if z >= 0 && z % 2 == 0 {
+                        (p(x - (y >> 1) - 1) + p(x - (y >> 1)) + 1) >> 1
+                    } else if z >= 0 {
+                        (p(x - (y >> 1) - 2) + 2 * p(x - (y >> 1) - 1) + p(x - (y >> 1)) + 2) >> 2
+                    } else if z == -1 {
+                        (l(0) + 2 * p(-1) + p(0) + 2) >> 2
+                    } else {
+                        (l(y - 2 * x - 1) + 2 * l(y - 2 * x - 2) + l(y - 2 * x - 3) + 2) >> 2
+                    }
+                }
+                // Horizontal_Down
+                6 => {
+                    let z = 2 * y - x;
+                    if z >= 0 && z % 2 == 0 {
+                        (l(y - (x >> 1) - 1) + l(y - (x >> 1)) + 1) >> 1
+                    } else if z >= 0 {
+                        (l(y - (x >> 1) - 2) + 2 * l(y - (x >> 1) - 1) + l(y - (x >> 1)) + 2) >> 2
+                    } else if z == -1 {
+                        (l(0) + 2 * p(-1) + p(0) + 2) >> 2
+                    } else {
+                        (p(x - 2 * y - 1) + 2 * p(x - 2 * y - 2) + p(x - 2 * y - 3) + 2) >> 2
+                    }
+                }
+                // Vertical_Left
+                7 => {
+                    if y % 2 == 0 {
+                        (p(x + (y >> 1)) + p(x + (y >> 1) + 1) + 1) >> 1
+                    } else {
+                        (p(x + (y >> 1)) + 2 * p(x + (y >> 1) + 1) + p(x + (y >> 1) + 2) + 2) >> 2
+                    }
+                }
+                // Horizontal_Up
+                8 => {
+                    let z = x + 2 * y;
+                    if z < 2 * n - 3 && z % 2 == 0 {
+                        (l(y + (x >> 1)) + l(y + (x >> 1) + 1) + 1) >> 1
+                    } else if z < 2 * n - 3 {
+                        (l(y + (x >> 1)) + 2 * l(y + (x >> 1) + 1) + l(y + (x >> 1) + 2) + 2) >> 2
+                    } else if z == 2 * n - 3 {
+                        (l(n - 2) + 3 * l(n - 1) + 2) >> 2
+                    } else {
+                        l(n - 1)
+                    }
+                }
+                // DC, also used for out of range modes
+                _ => {
+                    let sum_top: i32 = (0..n).map(p).sum();
+                    let sum_left: i32 = (0..n).map(l).sum();
+                    if r.top_avail && r.left_avail {
+                        (sum_top + sum_left + n) >> (log2n + 1)
+                    } else if r.left_avail {
+                        (sum_left + (n >> 1)) >> log2n
+                    } else if r.top_avail {
+                        (sum_top + (n >> 1)) >> log2n
+                    } else {
+                        default
+                    }
+                }
+            };
+            pred[(y * n + x) as usize] = v;
+        }
+    }
+    pred
+}
+
+/// Section 8.3.1.2 -- Intra_4x4 prediction of the 4x4 block `blk` in the plane, raster ordered
+pub fn predict_intra_4x4(
+    pic: &Picture,
+    plane: &Plane,
+    ctx: &IntraContext,
+    blk: usize,
+    mode: u8,
+    bit_depth: u32,
+) -> Vec<i32> {
+    let default = 1 << (bit_depth - 1);
+    let (bx, by) = luma_4x4_blk_pos(blk);
+    let mut r = fetch_ref_samples(
+        pic, plane, ctx, bx as i32, by as i32, 4, 4, 4, 16, 16, default,
+    );
+
+    // blocks 3 and 11 have their upper right samples in blocks that are not yet decoded
+    if (blk == 3 || blk == 11) && r.top_right_avail {
+        r.top_right_avail = false;
+        for x in 4..8 {
+            r.top[x + 1] = r.top[4];
+        }
+    }
+    predict_nxn(&r, 4, mode, default)
+}
+
+/// Section 8.3.2.2 -- Intra_8x8 prediction of the 8x8 block `blk8` in the plane, raster ordered
+pub fn predict_intra_8x8(
+    pic: &Picture,
+    plane: &Plane,
+    ctx: &IntraContext,
+    blk8: usize,
+    mode: u8,
+    bit_depth: u32,
+) -> Vec<i32> {
+    let default = 1 << (bit_depth - 1);
+    let bx = (blk8 % 2) as i32 * 8;
+    let by = (blk8 / 2) as i32 * 8;
+    let r = fetch_ref_samples(pic, plane, ctx, bx, by, 8, 8, 8, 16, 16, default);
+    let r = filter_8x8_ref_samples(&r);
+    predict_nxn(&r, 8, mode, default)
+}
+
+/// Plane prediction shared by Intra_16x16 (Equations 8-114 to 8-118) and chroma (8-141 to 8-145)
+fn predict_plane(r: &RefSamples, w: i32, h: i32, x_cf: i32, y_cf: i32, bit_depth: u32) -> Vec<i32> {
+    let p = |x: i32| r.top[(x + 1) as usize];
+    let l = |y: i32| {
+        if y < 0 {
+            r.top[0]
+        } else {
+            r.left[y as usize]
+        }
+    };
+    let max = (1 << bit_depth) - 1;
+
+    let a = 16 * (l(h - 1) + p(w - 1));
+    let h_sum: i32 = (0..(w / 2))
+        .map(|x| (x + 1) * (p(w / 2 + x) - p(w / 2 - 2 - x)))
+        .sum();
+    let v_sum: i32 = (0..(h / 2))
+        .map(|y| (y + 1) * (l(h / 2 + y) - l(h / 2 - 2 - y)))
+        .sum();
+
+    let (b, c) = if w == 16 && h == 16 && x_cf < 0 {
+        ((5 * h_sum + 32) >> 6, (5 * v_sum + 32) >> 6)
+    } else {
+        let b_mul = if x_cf == 4 { 5 } else { 34 };
+        let c_mul = if y_cf == 4 { 5 } else { 34 };
+        ((b_mul * h_sum + 32) >> 6, (c_mul * v_sum + 32) >> 6)
+    };
+
+    let (x_off, y_off) = if x_cf < 0 {
+        (7, 7)
+    } else {
+        (3 + x_cf, 3 + y_cf)
+    };
+
+    let mut pred = vec![0; (w * h) as usize];
+    for y in 0..h {
+        for x in 0..w {
+            pred[(y * w + x) as usize] =
+                ((a + b * (x - x_off) + c * (y - y_off) + 16) >> 5).clamp(0, max);
+        }
+    }
+    pred
+}
+
+/// Section 8.3.3 -- Intra_16x16 prediction of the whole macroblock in the plane, raster ordered
+pub fn predict_intra_16x16(
+    pic: &Picture,
+    plane: &Plane,
+    ctx: &IntraContext,
+    mode: u8,
+    bit_depth: u32,
+) -> Vec<i32> {
+    let default = 1 << (bit_depth - 1);
+    let r = fetch_ref_samples(pic, plane, ctx, 0, 0, 16, 16, 0, 16, 16, default);
+
+    match mode {
+        0 => (0..256).map(|i| r.top[(i % 16) + 1]).collect(),
+        1 => (0..256).map(|i| r.left[i / 16]).collect(),
+        // a negative xCF marks the luma plane prediction
+        3 => predict_plane(&r, 16, 16, -1, -1, bit_depth),
+        _ => {
+            let sum_top: i32 = r.top[1..17].iter().sum();
+            let sum_left: i32 = r.left.iter().sum();
+            let dc = if r.top_avail && r.left_avail {
+                (sum_top + sum_left + 16) >> 5
+            } else if r.left_avail {
+                (sum_left + 8) >> 4
+            } else if r.top_avail {
+                (sum_top + 8) >> 4
+            } else {
+                default
+            };
+            vec![dc; 256]
+        }
+    }
+}
+
+/// Section 8.3.4 -- chroma prediction for ChromaArrayType 1 and 2, raster ordered
+pub fn predict_intra_chroma(
+    pic: &Picture,
+    plane: &Plane,
+    ctx: &IntraContext,
+    mode: u8,
+    mb_width_c: usize,
+    mb_height_c: usize,
+    bit_depth: u32,
+) -> Vec<i32> {
+    let default = 1 << (bit_depth - 1);
+    let (w, h) = (mb_width_c as i32, mb_height_c as i32);
+    let r = fetch_ref_samples(pic, plane, ctx, 0, 0, w, h, 0, w, h, default);
+
+    match mode {
+        1 => (0..(w * h) as usize)
+            .map(|i| r.left[i / w as usize])
+            .collect(),
+        2 => (0..(w * h) as usize)
+            .map(|i| r.top[(i % w as usize) + 1])
+            .collect(),
+        3 => {
+            let x_cf = if pic.chroma_format_idc == 3 { 4 } else { 0 };
+            let y_cf = if pic.chroma_format_idc != 1 { 4 } else { 0 };
+            predict_plane(&r, w, h, x_cf, y_cf, bit_depth)
+        }
+        _ => {
+            // Section 8.3.4.1 to 8.3.4.3 -- DC prediction per 4x4 chroma block
+            let mut pred = vec![0; (w * h) as usize];
+            for y_o in (0..h).step_by(4) {
+                for x_o in (0..w).step_by(4) {
+                    let sum_top: i32 = (0..4).map(|x| r.top[(x_o + x + 1) as usize]).sum();
+                    let sum_left: i32 = (0..4).map(|y| r.left[(y_o + y) as usize]).sum();
+                    let top = if r.top_avail {
+                        Some((sum_top + 2) >> 2)
+                    } else {
+                        None
+                    };
+                    let left = if r.left_avail {
+                        Some((sum_left + 2) >> 2)
+                    } else {
+                        None
+                    };
+
+                    let dc = if (x_o == 0 && y_o == 0) || (x_o > 0 && y_o > 0) {
+                        if r.top_avail && r.left_avail {
+                            (sum_top + sum_left + 4) >> 3
+                        } else {
+                            left.or(top).unwrap_or(default)
+                        }
+                    } else if x_o > 0 {
+                        top.or(left).unwrap_or(default)
+                    } else {
+                        left.or(top).unwrap_or(default)
+                    };
+
+                    for y in y_o..y_o + 4 {
+                        for x in x_o..x_o + 4 {
+                            pred[(y * w + x) as usize] = dc;
+                        }
+                    }
+                }
+            }
+            pred
+        }
+    }
+}
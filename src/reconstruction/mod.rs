@@ -0,0 +1,10 @@
+//! H.264 picture reconstruction.
+
+pub mod deblock;
+pub mod dpb;
+pub mod inter;
+pub mod intra;
+pub mod md5;
+pub mod picture;
+pub mod reconstruct;
+pub mod transform;
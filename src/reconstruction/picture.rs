@@ -0,0 +1,282 @@
+//! Decoded picture sample storage and raw YUV output.
+
+use crate::common::data_structures::MbType;
+use crate::common::data_structures::SeqParameterSet;
+use crate::reconstruction::md5::md5;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+
+/// A single plane of samples
+#[derive(Debug, Clone)]
+pub struct Plane {
+    pub width: usize,
+    pub height: usize,
+    pub samples: Vec<u16>,
+}
+
+impl Plane {
+    pub fn new(width: usize, height: usize, fill: u16) -> Plane {
+        Plane {
+            width,
+            height,
+            samples: vec![fill; width * height],
+        }
+    }
+
+    /// Returns the sample at (x, y), clamping the coordinates to the plane edges
+    pub fn get(&self, x: i32, y: i32) -> i32 {
+        if self.width == 0 || self.height == 0 {
+            return 0;
+        }
+        let x = x.clamp(0, self.width as i32 - 1) as usize;
+        let y = y.clamp(0, self.height as i32 - 1) as usize;
+        self.samples[y * self.width + x] as i32
+    }
+
+    /// Sets the sample at (x, y), ignoring writes outside of the plane
+    pub fn set(&mut self, x: usize, y: usize, value: i32) {
+        if x < self.width && y < self.height {
+            self.samples[y * self.width + x] = value as u16;
+        }
+    }
+}
+
+/// Per-macroblock state kept after reconstruction, used for neighbor derivations
+#[derive(Debug, Clone)]
+pub struct MbInfo {
+    pub available: bool,
+    pub slice_num: usize,
+    pub mb_type: MbType,
+    pub is_intra: bool,
+    pub transform_size_8x8_flag: bool,
+    pub intra4x4_pred_mode: [u8; 16],
+    pub intra8x8_pred_mode: [u8; 4],
+    pub qp_y: i32,
+    // motion of each 4x4 luma block in raster order, indexed by list. A negative
+    // reference index means the list is not used
+    pub ref_idx: [[i32; 16]; 2],
+    pub mv: [[[i32; 2]; 16]; 2],
+    // identifies the reference picture, so that pictures can be compared across lists
+    pub ref_pic_id: [[Option<usize>; 16]; 2],
+    // 4x4 luma blocks (raster order) with non-zero transform coefficients
+    pub non_zero_coeffs: [bool; 16],
+    // deblocking inputs from the slice and picture parameter set
+    pub sp_or_si_slice: bool,
+    pub transform_bypass: bool,
+    pub disable_deblocking_filter_idc: u32,
+    pub filter_offset_a: i32,
+    pub filter_offset_b: i32,
+    pub chroma_qp_index_offset: [i32; 2],
+}
+
+impl MbInfo {
+    pub fn new() -> MbInfo {
+        MbInfo {
+            available: false,
+            slice_num: 0,
+            mb_type: MbType::INONE,
+            is_intra: false,
+            transform_size_8x8_flag: false,
+            intra4x4_pred_mode: [2; 16],
+            intra8x8_pred_mode: [2; 4],
+            qp_y: 0,
+            ref_idx: [[-1; 16]; 2],
+            mv: [[[0; 2]; 16]; 2],
+            ref_pic_id: [[None; 16]; 2],
+            non_zero_coeffs: [false; 16],
+            sp_or_si_slice: false,
+            transform_bypass: false,
+            disable_deblocking_filter_idc: 0,
+            filter_offset_a: 0,
+            filter_offset_b: 0,
+            chroma_qp_index_offset: [0; 2],
+        }
+    }
+}
+
+impl Default for MbInfo {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A reconstructed frame
+#[derive(Debug, Clone)]
+pub struct Picture {
+    pub luma: Plane,
+    pub cb: Plane,
+    pub cr: Plane,
+    pub chroma_format_idc: u32,
+    pub bit_depth_luma: u32,
+    pub bit_depth_chroma: u32,
+    pub pic_width_in_mbs: usize,
+    pub mb_info: Vec<MbInfo>,
+    // cropping rectangle in luma samples
+    pub crop_left: usize,
+    pub crop_right: usize,
+    pub crop_top: usize,
+    pub crop_bottom: usize,
+    pub frame_num: u32,
+    // PicOrderCnt() of the frame, Equation 8-1
+    pub poc: i32,
+    // unique for each decoded picture, used to compare reference pictures
+    pub id: usize,
+    // index of the first NALU of the picture
+    pub nalu_idx: usize,
+}
+
+impl Picture {
+    /// Allocates a picture using the dimensions in the SPS, filled with mid-gray
+    pub fn new(s: &SeqParameterSet) -> Picture {
+        let pic_width_in_mbs = s.pic_width_in_mbs_minus1 as usize + 1;
+        let frame_height_in_mbs =
+            (2 - s.frame_mbs_only_flag as usize) * (s.pic_height_in_map_units_minus1 as usize + 1);
+
+        let bit_depth_luma = (s.bit_depth_luma_minus8 as u32 + 8).min(16);
+        let bit_depth_chroma = (s.bit_depth_chroma_minus8 as u32 + 8).min(16);
+
+        let (sub_width_c, sub_height_c) = match s.chroma_format_idc {
+            1 => (2, 2),
+            2 => (2, 1),
+            _ => (1, 1),
+        };
+
+        let width = pic_width_in_mbs * 16;
+        let height = frame_height_in_mbs * 16;
+        let (width_c, height_c) = if s.chroma_format_idc == 0 || s.separate_colour_plane_flag {
+            (0, 0)
+        } else {
+            (width / sub_width_c, height / sub_height_c)
+        };
+
+        // Equations 7-19 to 7-22
+        let (crop_unit_x, crop_unit_y) = if s.chroma_format_idc == 0 || s.separate_colour_plane_flag
+        {
+            (1, 2 - s.frame_mbs_only_flag as usize)
+        } else {
+            (
+                sub_width_c,
+                sub_height_c * (2 - s.frame_mbs_only_flag as usize),
+            )
+        };
+
+        let (crop_left, crop_right, crop_top, crop_bottom) = if s.frame_cropping_flag {
+            (
+                crop_unit_x * s.frame_crop_left_offset as usize,
+                crop_unit_x * s.frame_crop_right_offset as usize,
+                crop_unit_y * s.frame_crop_top_offset as usize,
+                crop_unit_y * s.frame_crop_bottom_offset as usize,
+            )
+        } else {
+            (0, 0, 0, 0)
+        };
+
+        Picture {
+            luma: Plane::new(width, height, 1 << (bit_depth_luma - 1)),
+            cb: Plane::new(width_c, height_c, 1 << (bit_depth_chroma - 1)),
+            cr: Plane::new(width_c, height_c, 1 << (bit_depth_chroma - 1)),
+            chroma_format_idc: s.chroma_format_idc as u32,
+            bit_depth_luma,
+            bit_depth_chroma,
+            pic_width_in_mbs,
+            mb_info: vec![MbInfo::new(); pic_width_in_mbs * frame_height_in_mbs],
+            crop_left,
+            crop_right,
+            crop_top,
+            crop_bottom,
+            frame_num: 0,
+            poc: 0,
+            id: 0,
+            nalu_idx: 0,
+        }
+    }
+
+    /// Returns the cropped width and height of the luma plane
+    pub fn cropped_size(&self) -> (usize, usize) {
+        (
+            self.luma
+                .width
+                .saturating_sub(self.crop_left + self.crop_right),
+            self.luma
+                .height
+                .saturating_sub(self.crop_top + self.crop_bottom),
+        )
+    }
+
+    /// Writes the cropped samples of a plane. Samples above 8 bits are written as 16-bit little endian
+    fn write_plane(
+        &self,
+        out: &mut Vec<u8>,
+        plane: &Plane,
+        bit_depth: u32,
+        div_x: usize,
+        div_y: usize,
+    ) {
+        let (width, height) = self.cropped_size();
+        let x0 = self.crop_left / div_x;
+        let y0 = self.crop_top / div_y;
+        for y in y0..y0 + height / div_y {
+            for x in x0..x0 + width / div_x {
+                let v = plane.get(x as i32, y as i32);
+                if bit_depth > 8 {
+                    out.extend_from_slice(&(v as u16).to_le_bytes());
+                } else {
+                    out.push(v as u8);
+                }
+            }
+        }
+    }
+
+    /// Returns the cropped frame in planar YUV. Monochrome pictures are written
+    /// as 4:2:0 with mid-gray chroma so the output is viewable by common players
+    pub fn to_yuv(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_plane(&mut out, &self.luma, self.bit_depth_luma, 1, 1);
+
+        let (div_x, div_y) = match self.chroma_format_idc {
+            1 => (2, 2),
+            2 => (2, 1),
+            3 => (1, 1),
+            _ => (2, 2),
+        };
+
+        if self.cb.width == 0 || self.cb.height == 0 {
+            let (width, height) = self.cropped_size();
+            let num_samples = 2 * (width / 2) * (height / 2);
+            let mid = 1u16 << (self.bit_depth_chroma.min(16) - 1);
+            for _ in 0..num_samples {
+                if self.bit_depth_chroma > 8 {
+                    out.extend_from_slice(&mid.to_le_bytes());
+                } else {
+                    out.push(mid as u8);
+                }
+            }
+        } else {
+            self.write_plane(&mut out, &self.cb, self.bit_depth_chroma, div_x, div_y);
+            self.write_plane(&mut out, &self.cr, self.bit_depth_chroma, div_x, div_y);
+        }
+        out
+    }
+
+    /// Returns the hex encoded MD5 of the cropped frame, as written by `to_yuv`
+    pub fn md5_hex(&self) -> String {
+        hex::encode(md5(&self.to_yuv()))
+    }
+}
+
+/// Writes the pictures in order to a raw YUV file
+pub fn save_yuv(filename: &str, pictures: &[Picture]) {
+    let f = match File::create(filename) {
+        Ok(f) => f,
+        Err(e) => panic!("Unable to create file {} : {:?}", filename, e),
+    };
+    let mut writer = BufWriter::new(f);
+
+    for pic in pictures.iter() {
+        match writer.write_all(&pic.to_yuv()) {
+            Ok(_) => (),
+            Err(e) => panic!("Unable to write to file {} : {:?}", filename, e),
+        }
+    }
+}
@@ -0,0 +1,1084 @@
+//! Turns decoded syntax elements into pictures.
+
+use crate::common::data_structures::H264DecodedStream;
+use crate::common::data_structures::MacroBlock;
+use crate::common::data_structures::MbPartPredMode;
+use crate::common::data_structures::MbType;
+use crate::common::data_structures::NALUheader;
+use crate::common::data_structures::PicParameterSet;
+use crate::common::data_structures::SeqParameterSet;
+use crate::common::data_structures::Slice;
+use crate::common::data_structures::SliceHeader;
+use crate::common::helper::is_slice_type;
+use crate::reconstruction::deblock::deblock_picture;
+use crate::reconstruction::dpb::Dpb;
+use crate::reconstruction::inter::derive_motion;
+use crate::reconstruction::inter::predict_inter;
+use crate::reconstruction::inter::InterContext;
+use crate::reconstruction::intra::derive_intra4x4_pred_mode;
+use crate::reconstruction::intra::derive_intra8x8_pred_mode;
+use crate::reconstruction::intra::intra_16x16_pred_mode;
+use crate::reconstruction::intra::luma_4x4_blk_pos;
+use crate::reconstruction::intra::predict_intra_16x16;
+use crate::reconstruction::intra::predict_intra_4x4;
+use crate::reconstruction::intra::predict_intra_8x8;
+use crate::reconstruction::intra::predict_intra_chroma;
+use crate::reconstruction::intra::IntraContext;
+use crate::reconstruction::picture::Picture;
+use crate::reconstruction::picture::Plane;
+use crate::reconstruction::transform::chroma_dc_transform;
+use crate::reconstruction::transform::chroma_qp;
+use crate::reconstruction::transform::inverse_scan;
+use crate::reconstruction::transform::inverse_transform_4x4;
+use crate::reconstruction::transform::inverse_transform_8x8;
+use crate::reconstruction::transform::luma_dc_transform;
+use crate::reconstruction::transform::scale_4x4;
+use crate::reconstruction::transform::scale_8x8;
+use crate::reconstruction::transform::ScalingMatrices;
+use crate::reconstruction::transform::ZIGZAG_4X4;
+use crate::reconstruction::transform::ZIGZAG_8X8;
+
+/// Pictures larger than this many macroblocks are not reconstructed (level 6.2 allows 139264)
+const MAX_RECONSTRUCTED_MBS: usize = 139264;
+
+/// Slice-level values used while reconstructing macroblocks
+struct SliceContext<'a> {
+    sps: &'a SeqParameterSet,
+    pps: &'a PicParameterSet,
+    scaling: ScalingMatrices,
+    chroma_array_type: u8,
+    qp_bd_offset_y: i32,
+    qp_bd_offset_c: i32,
+    mb_width_c: usize,
+    mb_height_c: usize,
+}
+
+/// Quantization and scaling inputs for one colour component of a macroblock
+struct ComponentParams {
+    // qP, including the bit depth offset
+    qp: i32,
+    weight_scale_4x4: [i32; 16],
+    weight_scale_8x8: [i32; 64],
+    bit_depth: u32,
+    transform_bypass: bool,
+}
+
+/// Residual levels of a colour component coded like luma
+struct LumaLevels<'a> {
+    dc: &'a [i32],
+    ac: &'a [Vec<i32>],
+    level_4x4: &'a [Vec<i32>],
+    level_8x8: &'a [Vec<i32>],
+}
+
+fn get_list(lists: &[Vec<i32>], idx: usize) -> &[i32] {
+    match lists.get(idx) {
+        Some(l) => l,
+        None => &[],
+    }
+}
+
+fn component_plane(pic: &Picture, comp: usize) -> &Plane {
+    match comp {
+        0 => &pic.luma,
+        1 => &pic.cb,
+        _ => &pic.cr,
+    }
+}
+
+fn component_plane_mut(pic: &mut Picture, comp: usize) -> &mut Plane {
+    match comp {
+        0 => &mut pic.luma,
+        1 => &mut pic.cb,
+        _ => &mut pic.cr,
+    }
+}
+
+/// Section 8.5.15 -- intra residual transform-bypass decoding for horizontal and vertical prediction
+fn bypass_intra_residual(r: &mut [i32], width: usize, height: usize, hor_pred_flag: bool) {
+    if hor_pred_flag {
+        for y in 0..height {
+            for x in 1..width {
+                r[y * width + x] = r[y * width + x].saturating_add(r[y * width + x - 1]);
+            }
+        }
+    } else {
+        for y in 1..height {
+            for x in 0..width {
+                r[y * width + x] = r[y * width + x].saturating_add(r[(y - 1) * width + x]);
+            }
+        }
+    }
+}
+
+/// Section 8.5.12 -- residual of a 4x4 block from coefficients in scan order.
+/// If `dc` is set, `coeffs` only has the 15 AC coefficients
+fn residual_4x4(coeffs: &[i32], dc: Option<i64>, params: &ComponentParams) -> [i32; 16] {
+    let mut list = [0i32; 16];
+    let start = if dc.is_some() { 1 } else { 0 };
+    for (k, v) in list.iter_mut().skip(start).enumerate() {
+        *v = *coeffs.get(k).unwrap_or(&0);
+    }
+    let mut c = inverse_scan(&list, &ZIGZAG_4X4);
+    if let Some(dc) = dc {
+        c[0] = dc.clamp(i32::MIN as i64, i32::MAX as i64) as i32;
+    }
+
+    if params.transform_bypass {
+        let mut r = [0i32; 16];
+        r.copy_from_slice(&c);
+        return r;
+    }
+
+    let d = scale_4x4(&c, &params.weight_scale_4x4, params.qp, dc.is_some());
+    inverse_transform_4x4(&d)
+}
+
+/// Section 8.5.13 -- residual of an 8x8 block from coefficients in scan order
+fn residual_8x8(coeffs: &[i32], params: &ComponentParams) -> [i32; 64] {
+    let c = inverse_scan(coeffs, &ZIGZAG_8X8);
+
+    if params.transform_bypass {
+        let mut r = [0i32; 64];
+        r.copy_from_slice(&c);
+        return r;
+    }
+
+    let d = scale_8x8(&c, &params.weight_scale_8x8, params.qp);
+    inverse_transform_8x8(&d)
+}
+
+/// Section 8.5.14 -- adds the residual to the prediction and stores the result in the plane
+#[allow(clippy::too_many_arguments)]
+fn store_block(
+    plane: &mut Plane,
+    x0: usize,
+    y0: usize,
+    width: usize,
+    height: usize,
+    pred: &[i32],
+    residual: &[i32],
+    bit_depth: u32,
+) {
+    let max = (1i32 << bit_depth) - 1;
+    for y in 0..height {
+        for x in 0..width {
+            let v = pred[y * width + x].saturating_add(residual[y * width + x]);
+            plane.set(x0 + x, y0 + y, v.clamp(0, max));
+        }
+    }
+}
+
+/// Sections 8.3.1 to 8.3.3 with 8.5.1 to 8.5.3 -- prediction and residual of a component coded like luma
+fn reconstruct_luma_component(
+    pic: &mut Picture,
+    comp: usize,
+    ctx: &IntraContext,
+    mb: &MacroBlock,
+    levels: &LumaLevels,
+    params: &ComponentParams,
+) {
+    let w = pic.pic_width_in_mbs;
+    let mb_x = (ctx.curr_mb_addr % w) * 16;
+    let mb_y = (ctx.curr_mb_addr / w) * 16;
+    let bd = params.bit_depth;
+
+    match mb.mb_part_pred_mode(0) {
+        MbPartPredMode::Intra4x4 => {
+            for blk in 0..16 {
+                let mode = pic.mb_info[ctx.curr_mb_addr].intra4x4_pred_mode[blk];
+                let pred = predict_intra_4x4(pic, component_plane(pic, comp), ctx, blk, mode, bd);
+
+                let mut r = if (mb.coded_block_pattern_luma >> (blk / 4)) & 1 == 1 {
+                    residual_4x4(get_list(levels.level_4x4, blk), None, params)
+                } else {
+                    [0; 16]
+                };
+                if params.transform_bypass && mode < 2 {
+                    bypass_intra_residual(&mut r, 4, 4, mode == 1);
+                }
+
+                let (bx, by) = luma_4x4_blk_pos(blk);
+                store_block(
+                    component_plane_mut(pic, comp),
+                    mb_x + bx,
+                    mb_y + by,
+                    4,
+                    4,
+                    &pred,
+                    &r,
+                    bd,
+                );
+            }
+        }
+        MbPartPredMode::Intra8x8 => {
+            for blk8 in 0..4 {
+                let mode = pic.mb_info[ctx.curr_mb_addr].intra8x8_pred_mode[blk8];
+                let pred = predict_intra_8x8(pic, component_plane(pic, comp), ctx, blk8, mode, bd);
+
+                let mut r = if (mb.coded_block_pattern_luma >> blk8) & 1 == 1 {
+                    residual_8x8(get_list(levels.level_8x8, blk8), params)
+                } else {
+                    [0; 64]
+                };
+                if params.transform_bypass && mode < 2 {
+                    bypass_intra_residual(&mut r, 8, 8, mode == 1);
+                }
+
+                store_block(
+                    component_plane_mut(pic, comp),
+                    mb_x + (blk8 % 2) * 8,
+                    mb_y + (blk8 / 2) * 8,
+                    8,
+                    8,
+                    &pred,
+                    &r,
+                    bd,
+                );
+            }
+        }
+        MbPartPredMode::Intra16x16 => {
+            let mode = intra_16x16_pred_mode(mb.mb_type);
+            let pred = predict_intra_16x16(pic, component_plane(pic, comp), ctx, mode, bd);
+
+            // Section 8.5.10 -- the DC matrix is spatially ordered in units of 4x4 blocks
+            let c = inverse_scan(levels.dc, &ZIGZAG_4X4);
+            let dc: Vec<i64> = if params.transform_bypass {
+                c.iter().map(|&x| x as i64).collect()
+            } else {
+                luma_dc_transform(&c, &params.weight_scale_4x4, params.qp).to_vec()
+            };
+
+            let mut r = vec![0; 256];
+            for blk in 0..16 {
+                let (bx, by) = luma_4x4_blk_pos(blk);
+                let ac: &[i32] = if mb.coded_block_pattern_luma > 0 {
+                    get_list(levels.ac, blk)
+                } else {
+                    &[]
+                };
+                let blk_r = residual_4x4(ac, Some(dc[(by / 4) * 4 + bx / 4]), params);
+                for y in 0..4 {
+                    for x in 0..4 {
+                        r[(by + y) * 16 + bx + x] = blk_r[y * 4 + x];
+                    }
+                }
+            }
+            if params.transform_bypass && mode < 2 {
+                bypass_intra_residual(&mut r, 16, 16, mode == 1);
+            }
+
+            store_block(
+                component_plane_mut(pic, comp),
+                mb_x,
+                mb_y,
+                16,
+                16,
+                &pred,
+                &r,
+                bd,
+            );
+        }
+        _ => (),
+    }
+}
+
+/// Section 8.5.11 -- residual of a chroma component for ChromaArrayType 1 and 2, in raster order
+fn chroma_residual(
+    mb: &MacroBlock,
+    sc: &SliceContext,
+    i_cb_cr: usize,
+    param: &ComponentParams,
+) -> Vec<i32> {
+    let (mb_w, mb_h) = (sc.mb_width_c, sc.mb_height_c);
+    let num_blks = (mb_w / 4) * (mb_h / 4);
+    let dc_levels = get_list(&mb.chroma_dc_level, i_cb_cr);
+    let dc: Vec<i64> = if mb.coded_block_pattern_chroma == 0 {
+        vec![0; num_blks]
+    } else if param.transform_bypass {
+        // Equation 8-329 -- dcC is the inverse raster of the chroma DC levels
+        let mut dc = vec![0i64; num_blks];
+        if sc.chroma_array_type == 1 {
+            for (k, v) in dc.iter_mut().enumerate() {
+                *v = *dc_levels.get(k).unwrap_or(&0) as i64;
+            }
+        } else {
+            let order = [0, 2, 1, 5, 3, 6, 4, 7];
+            for (k, v) in dc.iter_mut().enumerate() {
+                *v = *dc_levels.get(order[k]).unwrap_or(&0) as i64;
+            }
+        }
+        dc
+    } else {
+        chroma_dc_transform(
+            dc_levels,
+            sc.chroma_array_type,
+            &param.weight_scale_4x4,
+            param.qp,
+        )
+    };
+
+    let ac_lists: &[Vec<i32>] = match mb.chroma_ac_level.get(i_cb_cr) {
+        Some(l) => l,
+        None => &[],
+    };
+
+    let mut r = vec![0; mb_w * mb_h];
+    for blk in 0..num_blks {
+        let ac: &[i32] = if mb.coded_block_pattern_chroma == 2 {
+            get_list(ac_lists, blk)
+        } else {
+            &[]
+        };
+        let blk_r = residual_4x4(ac, Some(*dc.get(blk).unwrap_or(&0)), param);
+        let (bx, by) = ((blk % 2) * 4, (blk / 2) * 4);
+        for y in 0..4 {
+            for x in 0..4 {
+                r[(by + y) * mb_w + bx + x] = blk_r[y * 4 + x];
+            }
+        }
+    }
+    r
+}
+
+/// Section 8.3.4 with 8.5.11 -- chroma prediction and residual for ChromaArrayType 1 and 2
+fn reconstruct_chroma(
+    pic: &mut Picture,
+    ctx: &IntraContext,
+    mb: &MacroBlock,
+    sc: &SliceContext,
+    params: &[ComponentParams; 2],
+) {
+    let w = pic.pic_width_in_mbs;
+    let (mb_w, mb_h) = (sc.mb_width_c, sc.mb_height_c);
+    let mb_x = (ctx.curr_mb_addr % w) * mb_w;
+    let mb_y = (ctx.curr_mb_addr / w) * mb_h;
+    let mode = mb.intra_chroma_pred_mode;
+
+    for (i_cb_cr, param) in params.iter().enumerate() {
+        let comp = i_cb_cr + 1;
+        let pred = predict_intra_chroma(
+            pic,
+            component_plane(pic, comp),
+            ctx,
+            mode,
+            mb_w,
+            mb_h,
+            param.bit_depth,
+        );
+
+        let mut r = chroma_residual(mb, sc, i_cb_cr, param);
+        // intra_chroma_pred_mode 1 is horizontal and 2 is vertical
+        if param.transform_bypass && (mode == 1 || mode == 2) {
+            bypass_intra_residual(&mut r, mb_w, mb_h, mode == 1);
+        }
+
+        store_block(
+            component_plane_mut(pic, comp),
+            mb_x,
+            mb_y,
+            mb_w,
+            mb_h,
+            &pred,
+            &r,
+            param.bit_depth,
+        );
+    }
+}
+
+/// Section 8.5 -- residual of an inter macroblock component coded like luma, in raster order
+fn inter_luma_residual(mb: &MacroBlock, levels: &LumaLevels, params: &ComponentParams) -> Vec<i32> {
+    let mut r = vec![0; 256];
+    if mb.transform_size_8x8_flag {
+        for blk8 in 0..4 {
+            if (mb.coded_block_pattern_luma >> blk8) & 1 == 0 {
+                continue;
+            }
+            let blk_r = residual_8x8(get_list(levels.level_8x8, blk8), params);
+            let (bx, by) = ((blk8 % 2) * 8, (blk8 / 2) * 8);
+            for y in 0..8 {
+                for x in 0..8 {
+                    r[(by + y) * 16 + bx + x] = blk_r[y * 8 + x];
+                }
+            }
+        }
+    } else {
+        for blk in 0..16 {
+            if (mb.coded_block_pattern_luma >> (blk / 4)) & 1 == 0 {
+                continue;
+            }
+            let blk_r = residual_4x4(get_list(levels.level_4x4, blk), None, params);
+            let (bx, by) = luma_4x4_blk_pos(blk);
+            for y in 0..4 {
+                for x in 0..4 {
+                    r[(by + y) * 16 + bx + x] = blk_r[y * 4 + x];
+                }
+            }
+        }
+    }
+    r
+}
+
+/// Returns which 4x4 luma blocks (raster order) of an inter macroblock have non-zero coefficients,
+/// used to derive the deblocking filter strength. With ChromaArrayType 3 the Cb and Cr blocks count too
+fn non_zero_coeffs(mb: &MacroBlock, levels: &[LumaLevels]) -> [bool; 16] {
+    let mut res = [false; 16];
+    let non_zero = |l: &[i32]| l.iter().any(|&c| c != 0);
+    for blk in 0..16 {
+        if (mb.coded_block_pattern_luma >> (blk / 4)) & 1 == 0 {
+            continue;
+        }
+        let (bx, by) = luma_4x4_blk_pos(blk);
+        let coded = if mb.transform_size_8x8_flag {
+            levels
+                .iter()
+                .any(|l| non_zero(get_list(l.level_8x8, blk / 4)))
+        } else {
+            levels.iter().any(|l| non_zero(get_list(l.level_4x4, blk)))
+        };
+        res[(by / 4) * 4 + bx / 4] = coded;
+    }
+    res
+}
+
+/// Section 8.3.5 -- copies the PCM samples into the picture
+fn reconstruct_pcm(pic: &mut Picture, mb_addr: usize, mb: &MacroBlock, sc: &SliceContext) {
+    let w = pic.pic_width_in_mbs;
+    let max_y = (1u32 << pic.bit_depth_luma) - 1;
+    let max_c = (1u32 << pic.bit_depth_chroma) - 1;
+
+    for i in 0..256 {
+        let v = *mb.pcm_sample_luma.get(i).unwrap_or(&0);
+        pic.luma.set(
+            (mb_addr % w) * 16 + i % 16,
+            (mb_addr / w) * 16 + i / 16,
+            v.min(max_y) as i32,
+        );
+    }
+
+    if sc.chroma_array_type != 0 {
+        let (mb_w, mb_h) = (sc.mb_width_c, sc.mb_height_c);
+        let num = mb_w * mb_h;
+        for i in 0..2 * num {
+            let v = *mb.pcm_sample_chroma.get(i).unwrap_or(&0);
+            let plane = if i < num { &mut pic.cb } else { &mut pic.cr };
+            let k = i % num;
+            plane.set(
+                (mb_addr % w) * mb_w + k % mb_w,
+                (mb_addr / w) * mb_h + k / mb_w,
+                v.min(max_c) as i32,
+            );
+        }
+    }
+}
+
+/// Quantization and scaling inputs of colour component `comp` (0 is luma) for a macroblock
+fn component_params(
+    sc: &SliceContext,
+    pic: &Picture,
+    comp: usize,
+    qp_y: i32,
+    is_intra: bool,
+    transform_bypass: bool,
+) -> ComponentParams {
+    let inter = !is_intra as usize;
+    if comp == 0 {
+        ComponentParams {
+            qp: qp_y + sc.qp_bd_offset_y,
+            weight_scale_4x4: sc.scaling.weight_scale_4x4[3 * inter],
+            weight_scale_8x8: sc.scaling.weight_scale_8x8[inter],
+            bit_depth: pic.bit_depth_luma,
+            transform_bypass,
+        }
+    } else {
+        let offset = if comp == 1 {
+            sc.pps.chroma_qp_index_offset
+        } else {
+            sc.pps.second_chroma_qp_index_offset
+        };
+        ComponentParams {
+            qp: chroma_qp(qp_y, offset, sc.qp_bd_offset_c),
+            weight_scale_4x4: sc.scaling.weight_scale_4x4[comp + 3 * inter],
+            weight_scale_8x8: sc.scaling.weight_scale_8x8[2 * comp + inter],
+            bit_depth: pic.bit_depth_chroma,
+            transform_bypass,
+        }
+    }
+}
+
+/// Residual levels of the luma, Cb and Cr components, for components coded like luma
+fn luma_like_levels(mb: &MacroBlock) -> [LumaLevels<'_>; 3] {
+    [
+        LumaLevels {
+            dc: &mb.intra_16x16_dc_level,
+            ac: &mb.intra_16x16_ac_level,
+            level_4x4: &mb.luma_level_4x4,
+            level_8x8: &mb.luma_level_8x8,
+        },
+        LumaLevels {
+            dc: &mb.cb_intra_16x16_dc_level,
+            ac: &mb.cb_intra_16x16_ac_level,
+            level_4x4: &mb.cb_level_4x4,
+            level_8x8: &mb.cb_level_8x8,
+        },
+        LumaLevels {
+            dc: &mb.cr_intra_16x16_dc_level,
+            ac: &mb.cr_intra_16x16_ac_level,
+            level_4x4: &mb.cr_level_4x4,
+            level_8x8: &mb.cr_level_8x8,
+        },
+    ]
+}
+
+/// Section 8.4 with 8.5 -- inter prediction and residual of a P or B macroblock
+fn reconstruct_inter_macroblock(
+    pic: &mut Picture,
+    inter: &InterContext,
+    mb: &MacroBlock,
+    sc: &SliceContext,
+    qp_y: i32,
+    transform_bypass: bool,
+) {
+    let addr = inter.curr_mb_addr;
+    let w = pic.pic_width_in_mbs;
+
+    derive_motion(pic, inter, mb);
+    let pred = predict_inter(pic, inter, sc.mb_width_c, sc.mb_height_c);
+
+    let levels = luma_like_levels(mb);
+    let num_luma_like = if sc.chroma_array_type == 3 { 3 } else { 1 };
+    pic.mb_info[addr].non_zero_coeffs = non_zero_coeffs(mb, &levels[..num_luma_like]);
+
+    for comp in 0..3 {
+        if comp > 0 && sc.chroma_array_type == 0 {
+            break;
+        }
+        let params = component_params(sc, pic, comp, qp_y, false, transform_bypass);
+        let (r, mb_w, mb_h) = if comp == 0 || sc.chroma_array_type == 3 {
+            (inter_luma_residual(mb, &levels[comp], &params), 16, 16)
+        } else {
+            (
+                chroma_residual(mb, sc, comp - 1, &params),
+                sc.mb_width_c,
+                sc.mb_height_c,
+            )
+        };
+        store_block(
+            component_plane_mut(pic, comp),
+            (addr % w) * mb_w,
+            (addr / w) * mb_h,
+            mb_w,
+            mb_h,
+            &pred[comp],
+            &r,
+            params.bit_depth,
+        );
+    }
+}
+
+/// Reconstructs a macroblock. Returns the QPY to use as QPY,PRED for the next macroblock
+fn reconstruct_macroblock(
+    pic: &mut Picture,
+    ctx: &IntraContext,
+    inter: &InterContext,
+    mb: &MacroBlock,
+    sc: &SliceContext,
+    qp_y_pred: i32,
+) -> i32 {
+    let addr = ctx.curr_mb_addr;
+    let is_intra = mb.is_intra_non_mut() || mb.mb_type == MbType::IPCM;
+
+    // Equation 7-37 -- mb_qp_delta is only present with residual data
+    let pred_mode = mb.mb_part_pred_mode(0);
+    let has_qp_delta = mb.mb_type != MbType::IPCM
+        && (pred_mode == MbPartPredMode::Intra16x16
+            || mb.coded_block_pattern_luma > 0
+            || mb.coded_block_pattern_chroma > 0);
+    let qp_y = if has_qp_delta {
+        (qp_y_pred + mb.mb_qp_delta + 52 + 2 * sc.qp_bd_offset_y).rem_euclid(52 + sc.qp_bd_offset_y)
+            - sc.qp_bd_offset_y
+    } else {
+        qp_y_pred
+    }
+    .clamp(-sc.qp_bd_offset_y, 51);
+
+    let transform_bypass =
+        sc.sps.qpprime_y_zero_transform_bypass_flag && qp_y + sc.qp_bd_offset_y == 0;
+
+    {
+        let info = &mut pic.mb_info[addr];
+        info.slice_num = ctx.slice_num;
+        info.mb_type = mb.mb_type;
+        info.is_intra = is_intra;
+        info.transform_size_8x8_flag = mb.transform_size_8x8_flag;
+        info.qp_y = qp_y;
+        info.intra4x4_pred_mode = [2; 16];
+        info.intra8x8_pred_mode = [2; 4];
+        info.ref_idx = [[-1; 16]; 2];
+        info.mv = [[[0; 2]; 16]; 2];
+        info.ref_pic_id = [[None; 16]; 2];
+        info.non_zero_coeffs = [false; 16];
+        info.sp_or_si_slice =
+            is_slice_type(inter.sh.slice_type, "SP") || is_slice_type(inter.sh.slice_type, "SI");
+        info.transform_bypass = transform_bypass;
+        info.disable_deblocking_filter_idc = inter.sh.disable_deblocking_filter_idc;
+        info.filter_offset_a = inter.sh.slice_alpha_c0_offset_div2 * 2;
+        info.filter_offset_b = inter.sh.slice_beta_offset_div2 * 2;
+        info.chroma_qp_index_offset = [
+            sc.pps.chroma_qp_index_offset,
+            sc.pps.second_chroma_qp_index_offset,
+        ];
+    }
+
+    if mb.mb_type == MbType::IPCM {
+        reconstruct_pcm(pic, addr, mb, sc);
+        pic.mb_info[addr].available = true;
+        return qp_y;
+    }
+
+    if !is_intra {
+        reconstruct_inter_macroblock(pic, inter, mb, sc, qp_y, transform_bypass);
+        pic.mb_info[addr].available = true;
+        return qp_y;
+    }
+
+    // Sections 8.3.1.1 and 8.3.2.1 -- derive the prediction modes in decoding order
+    if pred_mode == MbPartPredMode::Intra4x4 {
+        for blk in 0..16 {
+            let mode = derive_intra4x4_pred_mode(
+                pic,
+                ctx,
+                blk,
+                mb.prev_intra4x4_pred_mode_flag[blk],
+                mb.rem_intra4x4_pred_mode[blk],
+            );
+            pic.mb_info[addr].intra4x4_pred_mode[blk] = mode;
+        }
+    } else if pred_mode == MbPartPredMode::Intra8x8 {
+        for blk8 in 0..4 {
+            let mode = derive_intra8x8_pred_mode(
+                pic,
+                ctx,
+                blk8,
+                mb.prev_intra8x8_pred_mode_flag[blk8],
+                mb.rem_intra8x8_pred_mode[blk8],
+            );
+            pic.mb_info[addr].intra8x8_pred_mode[blk8] = mode;
+        }
+    }
+
+    let levels = luma_like_levels(mb);
+    let luma_params = component_params(sc, pic, 0, qp_y, true, transform_bypass);
+    reconstruct_luma_component(pic, 0, ctx, mb, &levels[0], &luma_params);
+
+    if sc.chroma_array_type != 0 {
+        let chroma_params =
+            [1, 2].map(|comp| component_params(sc, pic, comp, qp_y, true, transform_bypass));
+
+        if sc.chroma_array_type == 3 {
+            // Section 8.3.4.5 -- Cb and Cr use the luma prediction and residual processes
+            reconstruct_luma_component(pic, 1, ctx, mb, &levels[1], &chroma_params[0]);
+            reconstruct_luma_component(pic, 2, ctx, mb, &levels[2], &chroma_params[1]);
+        } else {
+            reconstruct_chroma(pic, ctx, mb, sc, &chroma_params);
+        }
+    }
+
+    pic.mb_info[addr].available = true;
+    qp_y
+}
+
+/// Returns a reason the slice cannot be reconstructed, if any
+fn unsupported_slice_reason(
+    slice: &Slice,
+    sps: &SeqParameterSet,
+    num_mbs: usize,
+) -> Option<&'static str> {
+    if is_slice_type(slice.sh.slice_type, "SP") || is_slice_type(slice.sh.slice_type, "SI") {
+        Some("SP and SI slices are not supported")
+    } else if slice.sh.field_pic_flag || slice.sh.mbaff_frame_flag {
+        Some("field and MBAFF coding are not supported")
+    } else if sps.separate_colour_plane_flag {
+        Some("separate colour planes are not supported")
+    } else if num_mbs > MAX_RECONSTRUCTED_MBS {
+        Some("picture size is too large")
+    } else {
+        None
+    }
+}
+
+/// Section 7.4.1.2.4 -- returns true if the slice belongs to a different primary coded picture than the previous one
+fn is_first_slice_of_new_picture(
+    prev: &(NALUheader, &Slice),
+    curr_header: &NALUheader,
+    curr: &Slice,
+) -> bool {
+    let (prev_header, prev) = prev;
+    prev.sh.frame_num != curr.sh.frame_num
+        || prev.sh.pic_parameter_set_id != curr.sh.pic_parameter_set_id
+        || prev.sh.field_pic_flag != curr.sh.field_pic_flag
+        || prev.sh.bottom_field_flag != curr.sh.bottom_field_flag
+        || (prev_header.nal_ref_idc == 0) != (curr_header.nal_ref_idc == 0)
+        || prev.sh.pic_order_cnt_lsb != curr.sh.pic_order_cnt_lsb
+        || prev.sh.delta_pic_order_cnt_bottom != curr.sh.delta_pic_order_cnt_bottom
+        || prev.sh.delta_pic_order_cnt != curr.sh.delta_pic_order_cnt
+        || (prev_header.nal_unit_type == 5) != (curr_header.nal_unit_type == 5)
+        || (curr_header.nal_unit_type == 5 && prev.sh.idr_pic_id != curr.sh.idr_pic_id)
+}
+
+/// The picture being reconstructed, with the values needed once all of its slices are decoded
+struct CurrentPicture<'a> {
+    pic: Picture,
+    sps: &'a SeqParameterSet,
+    nh: NALUheader,
+    sh: &'a SliceHeader,
+}
+
+/// Deblocks a finished picture and applies the reference picture marking. The picture is kept
+/// for output, along with its output period, if all of its slices were reconstructed
+fn finish_picture(
+    curr: Option<CurrentPicture>,
+    ok: bool,
+    dpb: &mut Dpb,
+    output_period: &mut usize,
+    pictures: &mut Vec<(usize, Picture)>,
+    silent_mode: bool,
+) {
+    let CurrentPicture {
+        mut pic,
+        sps,
+        nh,
+        sh,
+    } = match curr {
+        Some(c) => c,
+        None => return,
+    };
+
+    deblock_picture(&mut pic);
+    let (nalu_idx, frame_num) = (pic.nalu_idx, pic.frame_num);
+
+    // pictures before an IDR picture or memory_management_control_operation 5 are output first
+    if nh.nal_unit_type == 5 {
+        *output_period += 1;
+    }
+    let mut out = if ok { Some(pic.clone()) } else { None };
+    if dpb.mark_and_store(pic, sps, &nh, sh) {
+        *output_period += 1;
+        if let Some(out) = out.as_mut() {
+            out.poc = 0;
+            out.frame_num = 0;
+        }
+    }
+
+    match out {
+        Some(out) => {
+            if !silent_mode {
+                println!(
+                    "\t reconstruct_stream - Picture {} - NALU {} - frame_num {} - POC {} - reconstructed",
+                    pictures.len(),
+                    out.nalu_idx,
+                    out.frame_num,
+                    out.poc
+                );
+            }
+            pictures.push((*output_period, out));
+        }
+        None => {
+            if !silent_mode {
+                println!(
+                    "\t reconstruct_stream - NALU {} - skipping picture with frame_num {}",
+                    nalu_idx, frame_num
+                );
+            }
+        }
+    }
+}
+
+/// Reconstructs the frames of a decoded stream, returned in output order. Pictures that
+/// contain slices that cannot be reconstructed are skipped
+pub fn reconstruct_stream(ds: &H264DecodedStream, silent_mode: bool) -> Vec<Picture> {
+    let mut pictures: Vec<(usize, Picture)> = Vec::new();
+    let mut dpb = Dpb::new();
+    let mut output_period = 0;
+    let mut next_pic_id = 0;
+
+    let mut sps_idx = 0;
+    let mut subset_sps_idx = 0;
+    let mut pps_idx = 0;
+    let mut slice_idx = 0;
+
+    let mut curr_pic: Option<CurrentPicture> = None;
+    let mut curr_pic_ok = false;
+    let mut slice_num = 0;
+    let mut prev_slice: Option<(NALUheader, &Slice)> = None;
+    let mut new_access_unit = true;
+
+    for i in 0..ds.nalu_headers.len() {
+        // nothing was decoded from these, so there is nothing to reconstruct
+        if ds.undecoded_nalus.contains(&i) {
+            continue;
+        }
+
+        match ds.nalu_headers[i].nal_unit_type {
+            // a slice data partition A holds the slice data of partitions B and C too
+            1 | 2 | 5 => {
+                if slice_idx >= ds.slices.len() {
+                    break;
+                }
+                let slice = &ds.slices[slice_idx];
+                slice_idx += 1;
+
+                // redundant coded pictures are not needed for reconstruction
+                if slice.sh.redundant_pic_cnt > 0 {
+                    continue;
+                }
+
+                let new_picture = new_access_unit
+                    || match &prev_slice {
+                        Some(prev) => {
+                            is_first_slice_of_new_picture(prev, &ds.nalu_headers[i], slice)
+                        }
+                        None => true,
+                    };
+                new_access_unit = false;
+                prev_slice = Some((ds.nalu_headers[i].clone(), slice));
+
+                // we search in reverse to get the most recent; ID collision is possible with random video generation
+                let pps = ds.ppses[0..pps_idx]
+                    .iter()
+                    .rev()
+                    .find(|p| p.pic_parameter_set_id == slice.sh.pic_parameter_set_id);
+                let pps = match pps {
+                    Some(p) => p,
+                    None => {
+                        println!(
+                            "\t reconstruct_stream - NALU {} - PPS {} not found - skipping slice",
+                            i, slice.sh.pic_parameter_set_id
+                        );
+                        curr_pic_ok = false;
+                        continue;
+                    }
+                };
+                let sps = if pps.is_subset_pps {
+                    ds.subset_spses[0..subset_sps_idx]
+                        .iter()
+                        .rev()
+                        .map(|s| &s.sps)
+                        .find(|s| s.seq_parameter_set_id == pps.seq_parameter_set_id)
+                } else {
+                    ds.spses[0..sps_idx]
+                        .iter()
+                        .rev()
+                        .find(|s| s.seq_parameter_set_id == pps.seq_parameter_set_id)
+                };
+                let sps = match sps {
+                    Some(s) => s,
+                    None => {
+                        println!(
+                            "\t reconstruct_stream - NALU {} - SPS {} not found - skipping slice",
+                            i, pps.seq_parameter_set_id
+                        );
+                        curr_pic_ok = false;
+                        continue;
+                    }
+                };
+
+                let num_mbs = (sps.pic_width_in_mbs_minus1 as usize + 1)
+                    * (sps.pic_height_in_map_units_minus1 as usize + 1)
+                    * (2 - sps.frame_mbs_only_flag as usize);
+
+                if new_picture {
+                    finish_picture(
+                        curr_pic.take(),
+                        curr_pic_ok,
+                        &mut dpb,
+                        &mut output_period,
+                        &mut pictures,
+                        silent_mode,
+                    );
+                    slice_num = 0;
+                    curr_pic_ok = true;
+                    // the reason is reported below, so avoid allocating the picture
+                    if num_mbs <= MAX_RECONSTRUCTED_MBS {
+                        let nh = &ds.nalu_headers[i];
+
+                        // frames inferred from a frame_num gap are left as mid-gray
+                        let mut gap_template = Picture::new(sps);
+                        gap_template.id = next_pic_id;
+                        dpb.fill_frame_num_gap(sps, nh, &slice.sh, &gap_template);
+
+                        let mut pic = gap_template;
+                        pic.id = next_pic_id + 1;
+                        next_pic_id += 2;
+                        pic.frame_num = slice.sh.frame_num;
+                        pic.nalu_idx = i;
+                        pic.poc = dpb.decode_poc(sps, nh, &slice.sh);
+                        curr_pic = Some(CurrentPicture {
+                            pic,
+                            sps,
+                            nh: nh.clone(),
+                            sh: &slice.sh,
+                        });
+                    }
+                } else {
+                    slice_num += 1;
+                }
+
+                if let Some(reason) = unsupported_slice_reason(slice, sps, num_mbs) {
+                    if !silent_mode && curr_pic_ok {
+                        println!("\t reconstruct_stream - NALU {} - {}", i, reason);
+                    }
+                    curr_pic_ok = false;
+                }
+
+                if !curr_pic_ok {
+                    continue;
+                }
+
+                let pic = match curr_pic.as_mut() {
+                    Some(c) => &mut c.pic,
+                    None => continue,
+                };
+
+                let chroma_array_type = if sps.separate_colour_plane_flag {
+                    0
+                } else {
+                    sps.chroma_format_idc
+                };
+                let (mb_width_c, mb_height_c) = match chroma_array_type {
+                    1 => (8, 8),
+                    2 => (8, 16),
+                    3 => (16, 16),
+                    _ => (0, 0),
+                };
+                let sc = SliceContext {
+                    sps,
+                    pps,
+                    scaling: ScalingMatrices::new(sps, pps),
+                    chroma_array_type,
+                    qp_bd_offset_y: 6 * sps.bit_depth_luma_minus8 as i32,
+                    qp_bd_offset_c: 6 * sps.bit_depth_chroma_minus8 as i32,
+                    mb_width_c,
+                    mb_height_c,
+                };
+
+                let ref_lists = dpb.ref_pic_lists(sps, &slice.sh, pic.poc);
+
+                let mut qp_y_pred = slice.sh.slice_qp_y;
+                for mb in slice.sd.macroblock_vec.iter() {
+                    if mb.mb_addr >= pic.mb_info.len() {
+                        continue;
+                    }
+                    let ctx = IntraContext {
+                        curr_mb_addr: mb.mb_addr,
+                        slice_num,
+                        constrained_intra_pred_flag: pps.constrained_intra_pred_flag,
+                    };
+                    let inter = InterContext {
+                        curr_mb_addr: mb.mb_addr,
+                        slice_num,
+                        sps,
+                        pps,
+                        sh: &slice.sh,
+                        dpb: &dpb,
+                        ref_lists: &ref_lists,
+                        curr_poc: pic.poc,
+                    };
+                    qp_y_pred = reconstruct_macroblock(pic, &ctx, &inter, mb, &sc, qp_y_pred);
+                }
+            }
+            19..=21 => {
+                // auxiliary coded pictures and slices of other layers or views are not reconstructed
+                slice_idx += 1;
+            }
+            7 => {
+                sps_idx += 1;
+                new_access_unit = true;
+            }
+            8 => {
+                if pps_idx < ds.ppses.len() {
+                    pps_idx += 1;
+                }
+                new_access_unit = true;
+            }
+            15 => {
+                subset_sps_idx += 1;
+                new_access_unit = true;
+            }
+            6 | 9..=11 | 13 | 16..=18 => {
+                new_access_unit = true;
+            }
+            _ => (),
+        }
+    }
+    finish_picture(
+        curr_pic.take(),
+        curr_pic_ok,
+        &mut dpb,
+        &mut output_period,
+        &mut pictures,
+        silent_mode,
+    );
+
+    // Annex C.4 -- pictures are output in increasing POC order between IDR pictures
+    pictures.sort_by_key(|(period, pic)| (*period, pic.poc));
+    pictures.into_iter().map(|(_, pic)| pic).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::decoder::decode_bitstream_from_bytes;
+
+    #[test]
+    fn test_reconstruct_intra_stream() {
+        // A 64x48 High profile IDR picture in two slices that mixes Intra_4x4, Intra_8x8 and Intra_16x16
+        // macroblocks with random prediction modes, coefficients, mb_qp_delta and chroma QP offsets.
+        // The stream was written by hand outside of h26forge and the expected MD5 of its decoded
+        // samples comes from an independent model of Sections 8.3 and 8.5 written from the spec
+        let original = include_bytes!("../../input_vids/SPS_PPS_I_INTRA_RESIDUAL.264");
+        let Ok(ds) = decode_bitstream_from_bytes(original, false, false, false, false) else {
+            panic!("failed to decode SPS_PPS_I_INTRA_RESIDUAL.264");
+        };
+
+        let pics = reconstruct_stream(&ds, true);
+        assert_eq!(pics.len(), 1);
+        assert_eq!(pics[0].md5_hex(), "c506caf22ed5f8a619358a6b961a1ed8");
+    }
+
+    #[test]
+    fn test_reconstruct_inter_stream() {
+        // Twelve 80x64 pictures (I, P and B, two slices each) with up to three active references per
+        // list. The stream covers every P and B partition shape, P_Skip, B_Skip and spatial and temporal
+        // direct, explicit and implicit weighted prediction, sliding window and MMCO 1 to 4 and 6
+        // marking, long-term references, reference list modification, and the deblocking filter with
+        // per-slice offsets. Like the intra stream, it was written by hand and the MD5s come from an
+        // independent model of Sections 8.2 to 8.7
+        let original = include_bytes!("../../input_vids/SPS_PPS_I_P_B_MULTIREF.264");
+        let Ok(ds) = decode_bitstream_from_bytes(original, false, false, false, false) else {
+            panic!("failed to decode SPS_PPS_I_P_B_MULTIREF.264");
+        };
+
+        // in output order
+        let expected = [
+            (0, "59df04866878acb3e7e7972a6793211a"),
+            (4, "fc9f9fc842a1135097a3c93daeecca3d"),
+            (8, "8d4b1224817a2be14cb0aa0a4f8c8860"),
+            (12, "3d6d4e51d1ef20656245a4abc5b6eaae"),
+            (16, "001e6deef3757d221a3f2a4cae2a829d"),
+            (24, "c7382f3f096856067b3b166d48ecc165"),
+            (28, "b8d3217d1c2d8d9c2189318c5e60a8b7"),
+            (32, "241d5284617155a5ec8668d5ad7a9e47"),
+            (36, "c5df3bec1ded63f032a95856b0271f27"),
+            (40, "069d3ea0421690851be8168d8f6b4a05"),
+            (44, "3c9cd11f7c2d4250ce6a79b8acff6e1f"),
+            (48, "47bab35756f4c0cae76c9bf995e35bab"),
+        ];
+        let pics = reconstruct_stream(&ds, true);
+        assert_eq!(pics.len(), expected.len());
+        for (pic, (poc, md5)) in pics.iter().zip(expected) {
+            assert_eq!(pic.poc, poc);
+            assert_eq!(pic.md5_hex(), md5, "picture with POC {}", poc);
+        }
+    }
+}
@@ -0,0 +1,546 @@
+//! Scaling and inverse transform processes (Section 8.5).
+
+use crate::common::data_structures::PicParameterSet;
+use crate::common::data_structures::SeqParameterSet;
+use crate::common::helper::clip3;
+
+/// Table 8-13 -- 4x4 zig-zag scan, mapping scan index to raster index
+pub const ZIGZAG_4X4: [usize; 16] = [0, 1, 4, 8, 5, 2, 3, 6, 9, 12, 13, 10, 7, 11, 14, 15];
+
+/// Table 8-14 -- 8x8 zig-zag scan, mapping scan index to raster index
+pub const ZIGZAG_8X8: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27,
+    20, 13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58,
+    59, 52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+
+/// Table 7-3 -- Default_4x4_Intra in zig-zag order
+const DEFAULT_4X4_INTRA: [i32; 16] = [6, 13, 13, 20, 20, 20, 28, 28, 28, 28, 32, 32, 32, 37, 37, 42];
+
+/// Table 7-3 -- Default_4x4_Inter in zig-zag order
+const DEFAULT_4X4_INTER: [i32; 16] = [
+    10, 14, 14, 20, 20, 20, 24, 24, 24, 24, 27, 27, 27, 30, 30, 34,
+];
+
+/// Table 7-4 -- Default_8x8_Intra in zig-zag order
+const DEFAULT_8X8_INTRA: [i32; 64] = [
+    6, 10, 10, 13, 11, 13, 16, 16, 16, 16, 18, 18, 18, 18, 18, 23, 23, 23, 23, 23, 23, 25, 25, 25,
+    25, 25, 25, 25, 27, 27, 27, 27, 27, 27, 27, 27, 29, 29, 29, 29, 29, 29, 29, 31, 31, 31, 31, 31,
+    31, 33, 33, 33, 33, 33, 36, 36, 36, 36, 38, 38, 38, 40, 40, 42,
+];
+
+/// Table 7-4 -- Default_8x8_Inter in zig-zag order
+const DEFAULT_8X8_INTER: [i32; 64] = [
+    9, 13, 13, 15, 13, 15, 17, 17, 17, 17, 19, 19, 19, 19, 19, 21, 21, 21, 21, 21, 21, 22, 22, 22,
+    22, 22, 22, 22, 24, 24, 24, 24, 24, 24, 24, 24, 25, 25, 25, 25, 25, 25, 25, 27, 27, 27, 27, 27,
+    27, 28, 28, 28, 28, 28, 30, 30, 30, 30, 32, 32, 32, 33, 33, 35,
+];
+
+/// Equation 8-315 -- values of v used in normAdjust4x4
+const NORM_ADJUST_4X4: [[i32; 3]; 6] = [
+    [10, 16, 13],
+    [11, 18, 14],
+    [13, 20, 16],
+    [14, 23, 18],
+    [16, 25, 20],
+    [18, 29, 23],
+];
+
+/// Equation 8-318 -- values of v used in normAdjust8x8
+const NORM_ADJUST_8X8: [[i32; 6]; 6] = [
+    [20, 18, 32, 19, 25, 24],
+    [22, 19, 35, 21, 28, 26],
+    [26, 23, 42, 24, 33, 31],
+    [28, 25, 45, 26, 35, 33],
+    [32, 28, 51, 30, 40, 38],
+    [36, 32, 58, 34, 46, 43],
+];
+
+/// Table 8-15 -- QPc for qPI values of 30 to 51
+const QPC_TABLE: [i32; 22] = [
+    29, 30, 31, 32, 32, 33, 34, 34, 35, 35, 36, 36, 37, 37, 37, 38, 38, 38, 39, 39, 39, 39,
+];
+
+/// Conforming streams keep coefficients within 16 bits; random streams do not, so
+/// scaled values are saturated to keep the transforms from overflowing
+const MAX_SCALED_COEFF: i64 = i32::MAX as i64;
+
+/// Section 8.5.8 -- returns QP'c for a given QPy and chroma offset
+pub fn chroma_qp(qp_y: i32, chroma_qp_index_offset: i32, qp_bd_offset_c: i32) -> i32 {
+    let qp_i = clip3(-qp_bd_offset_c, 51, qp_y + chroma_qp_index_offset);
+    let qp_c = if qp_i < 30 {
+        qp_i
+    } else {
+        QPC_TABLE[(qp_i - 30) as usize]
+    };
+    qp_c + qp_bd_offset_c
+}
+
+/// Weight scale matrices in raster order, derived from the SPS and PPS scaling lists
+#[derive(Debug, Clone)]
+pub struct ScalingMatrices {
+    // Indexed by Table 7-2: Intra Y, Intra Cb, Intra Cr, Inter Y, Inter Cb, Inter Cr
+    pub weight_scale_4x4: [[i32; 16]; 6],
+    // Indexed by Table 7-2 minus 6: Intra Y, Inter Y, Intra Cb, Inter Cb, Intra Cr, Inter Cr
+    pub weight_scale_8x8: [[i32; 64]; 6],
+}
+
+impl ScalingMatrices {
+    /// Flat_4x4_16 and Flat_8x8_16
+    pub fn flat() -> ScalingMatrices {
+        ScalingMatrices {
+            weight_scale_4x4: [[16; 16]; 6],
+            weight_scale_8x8: [[16; 64]; 6],
+        }
+    }
+
+    /// Follows the scaling list semantics in 7.4.2.1.1 and 7.4.2.2
+    pub fn new(s: &SeqParameterSet, p: &PicParameterSet) -> ScalingMatrices {
+        let seq = if s.seq_scaling_matrix_present_flag {
+            ScalingMatrices::from_lists(
+                &s.seq_scaling_list_present_flag,
+                &s.scaling_list_4x4,
+                &s.use_default_scaling_matrix_4x4,
+                &s.scaling_list_8x8,
+                &s.use_default_scaling_matrix_8x8,
+                None,
+            )
+        } else {
+            ScalingMatrices::flat()
+        };
+
+        if p.pic_scaling_matrix_present_flag {
+            // fall-back rule B only applies when the SPS carried scaling lists
+            let fallback = if s.seq_scaling_matrix_present_flag {
+                Some(&seq)
+            } else {
+                None
+            };
+            ScalingMatrices::from_lists(
+                &p.pic_scaling_list_present_flag,
+                &p.scaling_list_4x4,
+                &p.use_default_scaling_matrix_4x4,
+                &p.scaling_list_8x8,
+                &p.use_default_scaling_matrix_8x8,
+                fallback,
+            )
+        } else {
+            seq
+        }
+    }
+
+    /// Applies Table 7-2 fall-back rule A (no fallback) or B (fallback to sequence-level lists)
+    fn from_lists(
+        present: &[bool],
+        lists_4x4: &[Vec<i32>],
+        use_default_4x4: &[bool],
+        lists_8x8: &[Vec<i32>],
+        use_default_8x8: &[bool],
+        fallback: Option<&ScalingMatrices>,
+    ) -> ScalingMatrices {
+        let mut res = ScalingMatrices::flat();
+
+        for i in 0..6 {
+            let is_present = *present.get(i).unwrap_or(&false);
+            let list = lists_4x4.get(i);
+            let use_default = *use_default_4x4.get(i).unwrap_or(&false);
+            let default = if i < 3 {
+                &DEFAULT_4X4_INTRA
+            } else {
+                &DEFAULT_4X4_INTER
+            };
+
+            if is_present && !use_default && list.is_some_and(|l| l.len() == 16) {
+                let list = list.unwrap();
+                for k in 0..16 {
+                    res.weight_scale_4x4[i][ZIGZAG_4X4[k]] = list[k];
+                }
+            } else if is_present {
+                for k in 0..16 {
+                    res.weight_scale_4x4[i][ZIGZAG_4X4[k]] = default[k];
+                }
+            } else if i == 0 || i == 3 {
+                match fallback {
+                    Some(f) => res.weight_scale_4x4[i] = f.weight_scale_4x4[i],
+                    None => {
+                        for k in 0..16 {
+                            res.weight_scale_4x4[i][ZIGZAG_4X4[k]] = default[k];
+                        }
+                    }
+                }
+            } else {
+                res.weight_scale_4x4[i] = res.weight_scale_4x4[i - 1];
+            }
+        }
+
+        for i in 0..6 {
+            let is_present = *present.get(i + 6).unwrap_or(&false);
+            let list = lists_8x8.get(i + 6);
+            let use_default = *use_default_8x8.get(i + 6).unwrap_or(&false);
+            let default = match i % 2 {
+                0 => &DEFAULT_8X8_INTRA,
+                _ => &DEFAULT_8X8_INTER,
+            };
+
+            if is_present && !use_default && list.is_some_and(|l| l.len() == 64) {
+                let list = list.unwrap();
+                for k in 0..64 {
+                    res.weight_scale_8x8[i][ZIGZAG_8X8[k]] = list[k];
+                }
+            } else if is_present {
+                for k in 0..64 {
+                    res.weight_scale_8x8[i][ZIGZAG_8X8[k]] = default[k];
+                }
+            } else if i < 2 {
+                match fallback {
+                    Some(f) => res.weight_scale_8x8[i] = f.weight_scale_8x8[i],
+                    None => {
+                        for k in 0..64 {
+                            res.weight_scale_8x8[i][ZIGZAG_8X8[k]] = default[k];
+                        }
+                    }
+                }
+            } else {
+                res.weight_scale_8x8[i] = res.weight_scale_8x8[i - 2];
+            }
+        }
+
+        res
+    }
+}
+
+/// Equation 8-315 -- normAdjust4x4(m, i, j)
+fn norm_adjust_4x4(m: usize, i: usize, j: usize) -> i32 {
+    let (i2, j2) = (i % 2, j % 2);
+    if i2 == 0 && j2 == 0 {
+        NORM_ADJUST_4X4[m][0]
+    } else if i2 == 1 && j2 == 1 {
+        NORM_ADJUST_4X4[m][1]
+    } else {
+        NORM_ADJUST_4X4[m][2]
+    }
+}
+
+/// Equation 8-318 -- normAdjust8x8(m, i, j)
+fn norm_adjust_8x8(m: usize, i: usize, j: usize) -> i32 {
+    let (i2, j2, i4, j4) = (i % 2, j % 2, i % 4, j % 4);
+    if i4 == 0 && j4 == 0 {
+        NORM_ADJUST_8X8[m][0]
+    } else if i2 == 1 && j2 == 1 {
+        NORM_ADJUST_8X8[m][1]
+    } else if i4 == 2 && j4 == 2 {
+        NORM_ADJUST_8X8[m][2]
+    } else if (i4 == 0 && j2 == 1) || (i2 == 1 && j4 == 0) {
+        NORM_ADJUST_8X8[m][3]
+    } else if (i4 == 0 && j4 == 2) || (i4 == 2 && j4 == 0) {
+        NORM_ADJUST_8X8[m][4]
+    } else {
+        NORM_ADJUST_8X8[m][5]
+    }
+}
+
+/// Equation 8-316 -- LevelScale4x4(m, 0, 0), used by the DC transforms
+fn level_scale_4x4_dc(weight_scale: &[i32; 16], m: usize) -> i64 {
+    (weight_scale[0] * norm_adjust_4x4(m, 0, 0)) as i64
+}
+
+fn saturate(x: i64) -> i64 {
+    x.clamp(-MAX_SCALED_COEFF, MAX_SCALED_COEFF)
+}
+
+/// Inverse scans a list of coefficients into a raster-ordered block.
+/// Missing coefficients are treated as zero
+pub fn inverse_scan(coeffs: &[i32], scan: &[usize]) -> Vec<i32> {
+    let mut res = vec![0; scan.len()];
+    for (k, &pos) in scan.iter().enumerate() {
+        res[pos] = *coeffs.get(k).unwrap_or(&0);
+    }
+    res
+}
+
+/// Section 8.5.12.1 -- scaling of a raster-ordered 4x4 block.
+/// If `has_dc` is set, the DC coefficient was already scaled by a DC transform
+pub fn scale_4x4(c: &[i32], weight_scale: &[i32; 16], qp: i32, has_dc: bool) -> [i64; 16] {
+    let mut d = [0i64; 16];
+    let qp = qp.max(0);
+    let m = (qp % 6) as usize;
+    let shift = qp / 6;
+
+    for idx in 0..16 {
+        let (i, j) = (idx / 4, idx % 4);
+        if idx == 0 && has_dc {
+            d[0] = c[0] as i64;
+            continue;
+        }
+        let ls = (weight_scale[idx] * norm_adjust_4x4(m, i, j)) as i64;
+        let prod = c[idx] as i64 * ls;
+        d[idx] = if shift >= 4 {
+            saturate(prod) << (shift - 4)
+        } else {
+            (prod + (1i64 << (3 - shift))) >> (4 - shift)
+        };
+        d[idx] = saturate(d[idx]);
+    }
+    d
+}
+
+/// Section 8.5.13.1 -- scaling of a raster-ordered 8x8 block
+pub fn scale_8x8(c: &[i32], weight_scale: &[i32; 64], qp: i32) -> [i64; 64] {
+    let mut d = [0i64; 64];
+    let qp = qp.max(0);
+    let m = (qp % 6) as usize;
+    let shift = qp / 6;
+
+    for idx in 0..64 {
+        let (i, j) = (idx / 8, idx % 8);
+        let ls = (weight_scale[idx] * norm_adjust_8x8(m, i, j)) as i64;
+        let prod = c[idx] as i64 * ls;
+        d[idx] = if shift >= 6 {
+            saturate(prod) << (shift - 6)
+        } else {
+            (prod + (1i64 << (5 - shift))) >> (6 - shift)
+        };
+        d[idx] = saturate(d[idx]);
+    }
+    d
+}
+
+/// Section 8.5.12.2 -- 4x4 inverse integer transform, returns the residual r
+pub fn inverse_transform_4x4(d: &[i64; 16]) -> [i32; 16] {
+    let mut f = [0i64; 16];
+    let mut r = [0i32; 16];
+
+    // rows
+    for i in 0..4 {
+        let row = &d[i * 4..i * 4 + 4];
+        let e0 = row[0] + row[2];
+        let e1 = row[0] - row[2];
+        let e2 = (row[1] >> 1) - row[3];
+        let e3 = row[1] + (row[3] >> 1);
+        f[i * 4] = e0 + e3;
+        f[i * 4 + 1] = e1 + e2;
+        f[i * 4 + 2] = e1 - e2;
+        f[i * 4 + 3] = e0 - e3;
+    }
+
+    // columns
+    for j in 0..4 {
+        let g0 = f[j] + f[8 + j];
+        let g1 = f[j] - f[8 + j];
+        let g2 = (f[4 + j] >> 1) - f[12 + j];
+        let g3 = f[4 + j] + (f[12 + j] >> 1);
+        let h = [g0 + g3, g1 + g2, g1 - g2, g0 - g3];
+        for i in 0..4 {
+            r[i * 4 + j] = saturate((h[i] + 32) >> 6) as i32;
+        }
+    }
+    r
+}
+
+/// One dimensional 8-point inverse transform used for both rows and columns
+fn inverse_transform_8(d: [i64; 8]) -> [i64; 8] {
+    let a0 = d[0] + d[4];
+    let a4 = d[0] - d[4];
+    let a2 = (d[2] >> 1) - d[6];
+    let a6 = d[2] + (d[6] >> 1);
+
+    let b0 = a0 + a6;
+    let b2 = a4 + a2;
+    let b4 = a4 - a2;
+    let b6 = a0 - a6;
+
+    let a1 = -d[3] + d[5] - d[7] - (d[7] >> 1);
+    let a3 = d[1] + d[7] - d[3] - (d[3] >> 1);
+    let a5 = -d[1] + d[7] + d[5] + (d[5] >> 1);
+    let a7 = d[3] + d[5] + d[1] + (d[1] >> 1);
+
+    let b1 = a1 + (a7 >> 2);
+    let b7 = a7 - (a1 >> 2);
+    let b3 = a3 + (a5 >> 2);
+    let b5 = (a3 >> 2) - a5;
+
+    [
+        b0 + b7,
+        b2 + b5,
+        b4 + b3,
+        b6 + b1,
+        b6 - b1,
+        b4 - b3,
+        b2 - b5,
+        b0 - b7,
+    ]
+}
+
+/// Section 8.5.13.2 -- 8x8 inverse integer transform, returns the residual r
+pub fn inverse_transform_8x8(d: &[i64; 64]) -> [i32; 64] {
+    let mut g = [0i64; 64];
+    let mut r = [0i32; 64];
+
+    for i in 0..8 {
+        let mut row = [0i64; 8];
+        row.copy_from_slice(&d[i * 8..i * 8 + 8]);
+        let out = inverse_transform_8(row);
+        g[i * 8..i * 8 + 8].copy_from_slice(&out);
+    }
+
+    for j in 0..8 {
+        let mut col = [0i64; 8];
+        for i in 0..8 {
+            col[i] = g[i * 8 + j];
+        }
+        let out = inverse_transform_8(col);
+        for i in 0..8 {
+            r[i * 8 + j] = saturate((out[i] + 32) >> 6) as i32;
+        }
+    }
+    r
+}
+
+/// Section 8.5.10 -- Intra_16x16 DC transform and scaling. Input and output are raster ordered
+pub fn luma_dc_transform(c: &[i32], weight_scale: &[i32; 16], qp: i32) -> [i64; 16] {
+    let mut f = [0i64; 16];
+    let mut tmp = [0i64; 16];
+    let mut dc = [0i64; 16];
+
+    // rows
+    for i in 0..4 {
+        let r = &c[i * 4..i * 4 + 4];
+        let (r0, r1, r2, r3) = (r[0] as i64, r[1] as i64, r[2] as i64, r[3] as i64);
+        tmp[i * 4] = r0 + r1 + r2 + r3;
+        tmp[i * 4 + 1] = r0 + r1 - r2 - r3;
+        tmp[i * 4 + 2] = r0 - r1 - r2 + r3;
+        tmp[i * 4 + 3] = r0 - r1 + r2 - r3;
+    }
+    // columns
+    for j in 0..4 {
+        let (c0, c1, c2, c3) = (tmp[j], tmp[4 + j], tmp[8 + j], tmp[12 + j]);
+        f[j] = c0 + c1 + c2 + c3;
+        f[4 + j] = c0 + c1 - c2 - c3;
+        f[8 + j] = c0 - c1 - c2 + c3;
+        f[12 + j] = c0 - c1 + c2 - c3;
+    }
+
+    let qp = qp.max(0);
+    let ls = level_scale_4x4_dc(weight_scale, (qp % 6) as usize);
+    let shift = qp / 6;
+    for k in 0..16 {
+        let prod = saturate(f[k] * ls);
+        dc[k] = if qp >= 36 {
+            prod << (shift - 6)
+        } else {
+            (prod + (1i64 << (5 - shift))) >> (6 - shift)
+        };
+        dc[k] = saturate(dc[k]);
+    }
+    dc
+}
+
+/// Section 8.5.11 -- chroma DC transform and scaling for ChromaArrayType 1 and 2.
+/// Input is in the order of the parsed chroma DC levels, output is raster ordered
+/// with a width of 2 blocks
+pub fn chroma_dc_transform(
+    c: &[i32],
+    chroma_array_type: u8,
+    weight_scale: &[i32; 16],
+    qp_c: i32,
+) -> Vec<i64> {
+    let get = |k: usize| *c.get(k).unwrap_or(&0) as i64;
+
+    if chroma_array_type == 1 {
+        // equation 8-328 with a 2x2 block
+        let (c0, c1, c2, c3) = (get(0), get(1), get(2), get(3));
+        let f = [c0 + c1 + c2 + c3, c0 - c1 + c2 - c3, c0 + c1 - c2 - c3, c0 - c1 - c2 + c3];
+
+        let qp_c = qp_c.max(0);
+        let ls = level_scale_4x4_dc(weight_scale, (qp_c % 6) as usize);
+        f.iter()
+            .map(|&x| saturate((saturate(x * ls) << (qp_c / 6)) >> 5))
+            .collect()
+    } else {
+        // equation 8-330, a 4x2 (rows x columns) matrix of DC values
+        let m = [
+            [get(0), get(2)],
+            [get(1), get(5)],
+            [get(3), get(6)],
+            [get(4), get(7)],
+        ];
+        let a: [[i64; 4]; 4] = [[1, 1, 1, 1], [1, 1, -1, -1], [1, -1, -1, 1], [1, -1, 1, -1]];
+
+        let mut f = [[0i64; 2]; 4];
+        for i in 0..4 {
+            // left multiply by A, then right multiply by [[1, 1], [1, -1]]
+            let mut t = [0i64; 2];
+            for (k, row) in m.iter().enumerate() {
+                t[0] += a[i][k] * row[0];
+                t[1] += a[i][k] * row[1];
+            }
+            f[i] = [t[0] + t[1], t[0] - t[1]];
+        }
+
+        // equation 8-331
+        let qp_dc = (qp_c + 3).max(0);
+        let ls = level_scale_4x4_dc(weight_scale, (qp_dc % 6) as usize);
+        let shift = qp_dc / 6;
+        let mut res = Vec::new();
+        for row in f.iter() {
+            for &x in row.iter() {
+                let prod = saturate(x * ls);
+                res.push(saturate(if qp_dc >= 36 {
+                    prod << (shift - 6)
+                } else {
+                    (prod + (1i64 << (5 - shift))) >> (6 - shift)
+                }));
+            }
+        }
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inverse_transform_4x4_dc_only() {
+        let mut d = [0i64; 16];
+        d[0] = 64;
+        let r = inverse_transform_4x4(&d);
+        assert_eq!(r, [1; 16]);
+    }
+
+    #[test]
+    fn test_inverse_transform_8x8_dc_only() {
+        let mut d = [0i64; 64];
+        d[0] = 128;
+        let r = inverse_transform_8x8(&d);
+        assert_eq!(r, [2; 64]);
+    }
+
+    #[test]
+    fn test_scale_4x4_flat() {
+        let mut c = vec![0; 16];
+        c[0] = 1;
+        c[5] = 2;
+        let d = scale_4x4(&c, &[16; 16], 28, false);
+        // qP = 28: m = 4, qP/6 = 4, so d = c * 16 * v
+        assert_eq!(d[0], 16 * 16);
+        assert_eq!(d[5], 2 * 16 * 25);
+    }
+
+    #[test]
+    fn test_chroma_qp_table() {
+        assert_eq!(chroma_qp(20, 0, 0), 20);
+        assert_eq!(chroma_qp(51, 0, 0), 39);
+        assert_eq!(chroma_qp(51, 12, 0), 39);
+        assert_eq!(chroma_qp(30, 0, 12), 41);
+    }
+
+    #[test]
+    fn test_inverse_scan_short_list() {
+        let res = inverse_scan(&[5, 6, 7], &ZIGZAG_4X4);
+        assert_eq!(res[0], 5);
+        assert_eq!(res[1], 6);
+        assert_eq!(res[4], 7);
+        assert_eq!(res[15], 0);
+    }
+}
@@ -317,6 +317,7 @@ pub async fn stream(
                             property_small_video,
                             print_silent,
                             include_undefined_nalus,
+                            false, // no slice data partitioning
                             &rconfig,
                             &mut film_state,
                         );
@@ -0,0 +1,486 @@
+//! Spec-compliant generation: Annex A level limits and the range restrictions
+//! that keep randomly generated streams decodable under a chosen profile and level.
+
+use crate::common::data_structures::HRDParameters;
+use crate::common::data_structures::SeqParameterSet;
+use crate::vidgen::generate_configurations::RandomBoolRange;
+use crate::vidgen::generate_configurations::RandomI32Range;
+use crate::vidgen::generate_configurations::RandomU32Enum;
+use crate::vidgen::generate_configurations::RandomU32Range;
+use crate::vidgen::generate_configurations::RandomizeConfig;
+use std::cmp;
+
+/// Profiles supported by the spec-compliant generation mode
+pub const COMPLIANT_PROFILES: [u32; 3] = [66, 77, 100];
+
+/// Levels supported by the spec-compliant generation mode; 9 selects Level 1b
+pub const COMPLIANT_LEVELS: [u32; 20] = [
+    9, 10, 11, 12, 13, 20, 21, 22, 30, 31, 32, 40, 41, 42, 50, 51, 52, 60, 61, 62,
+];
+
+/// Level limits from Table A-1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelLimits {
+    pub max_mbps: u32,                // MaxMBPS; macroblocks per second
+    pub max_fs: u32,                  // MaxFS; macroblocks
+    pub max_dpb_mbs: u32,             // MaxDpbMbs; macroblocks
+    pub max_br: u32,                  // MaxBR; in units of cpbBrVclFactor or cpbBrNalFactor bits/s
+    pub max_cpb: u32,                 // MaxCPB; in units of cpbBrVclFactor or cpbBrNalFactor bits
+    pub max_vmv_r: u32,               // MaxVmvR; vertical MV range in luma frame samples
+    pub max_mvs_per_2mb: Option<u32>, // MaxMvsPer2Mb; unconstrained below level 3
+}
+
+/// Returns the Table A-1 limits for a level_idc. Level 1b is signalled either with
+/// level_idc 11 and constraint_set3_flag (Baseline, Main, and Extended) or with level_idc 9
+pub fn level_limits(level_idc: u8, constraint_set3_flag: bool) -> Option<LevelLimits> {
+    let (max_mbps, max_fs, max_dpb_mbs, max_br, max_cpb, max_vmv_r, max_mvs_per_2mb) =
+        match level_idc {
+            10 => (1485, 99, 396, 64, 175, 64, None),
+            9 => (1485, 99, 396, 128, 350, 64, None),
+            11 if constraint_set3_flag => (1485, 99, 396, 128, 350, 64, None),
+            11 => (3000, 396, 900, 192, 500, 128, None),
+            12 => (6000, 396, 2376, 384, 1000, 128, None),
+            13 => (11880, 396, 2376, 768, 2000, 128, None),
+            20 => (11880, 396, 2376, 2000, 2000, 128, None),
+            21 => (19800, 792, 4752, 4000, 4000, 256, None),
+            22 => (20250, 1620, 8100, 4000, 4000, 256, None),
+            30 => (40500, 1620, 8100, 10000, 10000, 256, Some(32)),
+            31 => (108000, 3600, 18000, 14000, 14000, 512, Some(16)),
+            32 => (216000, 5120, 20480, 20000, 20000, 512, Some(16)),
+            40 => (245760, 8192, 32768, 20000, 25000, 512, Some(16)),
+            41 => (245760, 8192, 32768, 50000, 62500, 512, Some(16)),
+            42 => (522240, 8704, 34816, 50000, 62500, 512, Some(16)),
+            50 => (589824, 22080, 110400, 135000, 135000, 512, Some(16)),
+            51 => (983040, 36864, 184320, 240000, 240000, 512, Some(16)),
+            52 => (2073600, 36864, 184320, 240000, 240000, 512, Some(16)),
+            60 => (4177920, 139264, 696320, 240000, 240000, 8192, Some(16)),
+            61 => (8355840, 139264, 696320, 480000, 480000, 8192, Some(16)),
+            62 => (16711680, 139264, 696320, 800000, 800000, 8192, Some(16)),
+            _ => return None,
+        };
+
+    Some(LevelLimits {
+        max_mbps,
+        max_fs,
+        max_dpb_mbs,
+        max_br,
+        max_cpb,
+        max_vmv_r,
+        max_mvs_per_2mb,
+    })
+}
+
+/// Returns (cpbBrVclFactor, cpbBrNalFactor) from Table A-2
+pub fn cpb_br_factors(profile_idc: u8) -> (u64, u64) {
+    match profile_idc {
+        100 => (1250, 1500),
+        110 => (3000, 3600),
+        122 | 244 | 44 => (4000, 4800),
+        _ => (1000, 1200),
+    }
+}
+
+/// Clamps the bit rate and CPB size of the first schedule to the level maximums
+fn limit_hrd_parameters(hrd: &mut HRDParameters, max_bit_rate: u64, max_cpb_size: u64) {
+    // we keep a single schedule so that the SchedSelIdx ordering constraints of E.2.2 hold
+    hrd.cpb_cnt_minus1 = 0;
+    hrd.bit_rate_value_minus1.truncate(1);
+    hrd.cpb_size_values_minus1.truncate(1);
+    hrd.cbr_flag.truncate(1);
+
+    // BitRate = (bit_rate_value_minus1 + 1) * 2^(6 + bit_rate_scale) - Equation E-37
+    if max_bit_rate >> (6 + hrd.bit_rate_scale) == 0 {
+        hrd.bit_rate_scale = 0;
+    }
+    let max_bit_rate_value = max_bit_rate >> (6 + hrd.bit_rate_scale);
+    if let Some(x) = hrd.bit_rate_value_minus1.first_mut() {
+        *x = cmp::min(*x as u64, max_bit_rate_value - 1) as u32;
+    }
+
+    // CpbSize = (cpb_size_value_minus1 + 1) * 2^(4 + cpb_size_scale) - Equation E-38
+    if max_cpb_size >> (4 + hrd.cpb_size_scale) == 0 {
+        hrd.cpb_size_scale = 0;
+    }
+    let max_cpb_size_value = max_cpb_size >> (4 + hrd.cpb_size_scale);
+    if let Some(x) = hrd.cpb_size_values_minus1.first_mut() {
+        *x = cmp::min(*x as u64, max_cpb_size_value - 1) as u32;
+    }
+}
+
+/// Adjusts already sampled VUI parameters to follow the limits of the SPS level:
+/// the picture rate stays under MaxMBPS (A.3.1 a), the HRD stays under MaxBR and
+/// MaxCPB (A.3.1 j), and the DPB size follows the E.2.1 bitstream restrictions
+pub fn apply_level_limits_to_vui(sps: &mut SeqParameterSet) {
+    let limits = match level_limits(sps.level_idc, sps.constraint_set3_flag) {
+        Some(x) => x,
+        None => return,
+    };
+    let pic_size_in_mbs =
+        (sps.pic_width_in_mbs_minus1 as u64 + 1) * (sps.pic_height_in_map_units_minus1 as u64 + 1);
+    let vui = &mut sps.vui_parameters;
+
+    if vui.timing_info_present_flag {
+        // a frame lasts 2 * num_units_in_tick / time_scale seconds
+        vui.time_scale = cmp::max(vui.time_scale, 1);
+        let min_num_units_in_tick =
+            (vui.time_scale as u64 * pic_size_in_mbs).div_ceil(2 * limits.max_mbps as u64);
+        if min_num_units_in_tick > u32::MAX as u64 {
+            vui.num_units_in_tick = u32::MAX;
+            vui.time_scale =
+                (2 * limits.max_mbps as u64 * u32::MAX as u64 / pic_size_in_mbs) as u32;
+        } else {
+            vui.num_units_in_tick = cmp::max(
+                vui.num_units_in_tick,
+                cmp::max(min_num_units_in_tick as u32, 1),
+            );
+        }
+    }
+
+    let (vcl_factor, nal_factor) = cpb_br_factors(sps.profile_idc);
+    if vui.nal_hrd_parameters_present_flag {
+        limit_hrd_parameters(
+            &mut vui.nal_hrd_parameters,
+            nal_factor * limits.max_br as u64,
+            nal_factor * limits.max_cpb as u64,
+        );
+    }
+    if vui.vcl_hrd_parameters_present_flag {
+        limit_hrd_parameters(
+            &mut vui.vcl_hrd_parameters,
+            vcl_factor * limits.max_br as u64,
+            vcl_factor * limits.max_cpb as u64,
+        );
+    }
+
+    if vui.bitstream_restriction_flag {
+        // max_dec_frame_buffering is between max_num_ref_frames and MaxDpbFrames (A.3.1 h)
+        let max_dpb_frames = cmp::min(limits.max_dpb_mbs as u64 / pic_size_in_mbs, 16) as u32;
+        vui.max_dec_frame_buffering = cmp::max(
+            sps.max_num_ref_frames,
+            cmp::min(vui.max_dec_frame_buffering, max_dpb_frames),
+        );
+        vui.max_num_reorder_frames =
+            cmp::min(vui.max_num_reorder_frames, vui.max_dec_frame_buffering);
+        // vertical motion vectors stay within [-MaxVmvR, MaxVmvR - 0.25] (A.3.1 d)
+        vui.log2_max_mv_length_vertical = cmp::min(
+            vui.log2_max_mv_length_vertical,
+            (4 * limits.max_vmv_r).ilog2(),
+        );
+    }
+}
+
+/// Keeps the values of a range that fall within [min, max], falling back to [min, max],
+/// along with the distribution of the range
+fn restrict_u32(range: RandomU32Range, min: u32, max: u32) -> RandomU32Range {
+    let mut res = if range.max < min || range.min > max {
+        RandomU32Range::new(min, max)
+    } else {
+        RandomU32Range::new(cmp::max(range.min, min), cmp::min(range.max, max))
+    };
+    res.distribution = range.distribution;
+    res
+}
+
+/// Keeps the values of a range that fall within [min, max], falling back to [min, max],
+/// along with the distribution of the range
+fn restrict_i32(range: RandomI32Range, min: i32, max: i32) -> RandomI32Range {
+    let mut res = if range.max < min || range.min > max {
+        RandomI32Range::new(min, max)
+    } else {
+        RandomI32Range::new(cmp::max(range.min, min), cmp::min(range.max, max))
+    };
+    res.distribution = range.distribution;
+    res
+}
+
+/// Keeps the enum values found in allowed and their weights, falling back to allowed
+fn restrict_enum(values: &RandomU32Enum, allowed: &[u32]) -> RandomU32Enum {
+    let weighted = values.weights.len() == values.values.len();
+    let mut res = RandomU32Enum::new(Vec::new());
+    for (i, x) in values.values.iter().enumerate() {
+        if allowed.contains(x) {
+            res.values.push(*x);
+            if weighted {
+                res.weights.push(values.weights[i]);
+            }
+        }
+    }
+    if res.values.is_empty() {
+        RandomU32Enum::new(allowed.to_vec())
+    } else {
+        res
+    }
+}
+
+/// Narrows the ranges of rconfig so that random_video only produces streams that
+/// conform to Annex A for the given profile and level.
+///
+/// Level 1b is selected with level_idc 9 for every profile. Together with the
+/// ignore_ipcm and ignore_edge_intra_pred generation flags, the resulting streams
+/// have one slice per picture, zero motion vectors, and small residuals so that
+/// reconstruction stays within the limits of 8.5.12 and MinCR.
+///
+/// Returns an error naming the supported values for an unsupported profile or level.
+pub fn spec_compliant_config(
+    rconfig: &RandomizeConfig,
+    profile_idc: u32,
+    level_idc: u32,
+) -> Result<RandomizeConfig, String> {
+    if !COMPLIANT_PROFILES.contains(&profile_idc) {
+        return Err(format!(
+            "spec-compliant generation supports profile_idc {:?}, got {}",
+            COMPLIANT_PROFILES, profile_idc
+        ));
+    }
+    if !COMPLIANT_LEVELS.contains(&level_idc) {
+        return Err(format!(
+            "spec-compliant generation supports level_idc {:?}, got {}",
+            COMPLIANT_LEVELS, level_idc
+        ));
+    }
+
+    // Level 1b is level_idc 11 with constraint_set3_flag outside of the High profiles (7.4.2.1.1)
+    let (level_idc, level_1b_flag) = match (profile_idc, level_idc) {
+        (100, 9) => (9, false),
+        (_, 9) => (11, true),
+        (_, x) => (x, false),
+    };
+    let limits = level_limits(level_idc as u8, level_1b_flag).unwrap();
+    let baseline = profile_idc == 66;
+    let high = profile_idc == 100;
+
+    let mut res = rconfig.clone();
+
+    // NALUs: only parameter sets and slices of the base specification
+    res.random_video_config.enable_extensions = RandomBoolRange::never();
+    let nalu = &mut res.random_nalu_range;
+    nalu.forbidden_zero_bit = RandomU32Range::new(0, 0);
+    nalu.nal_unit_type = restrict_enum(&nalu.nal_unit_type, &[1, 5, 7, 8]);
+    nalu.nal_unit_slice_type = restrict_enum(&nalu.nal_unit_slice_type, &[1, 5]);
+    nalu.bias_undefined_nalu = RandomBoolRange::never();
+    nalu.bias_conforming_nalu_order = RandomBoolRange::always();
+
+    // SPS
+    let sps = &mut res.random_sps_range;
+    sps.profile_idc = RandomU32Enum::new(vec![profile_idc]);
+    sps.profile_idc_extension = RandomU32Enum::new(vec![profile_idc]);
+    sps.constraint_set0_flag = RandomBoolRange::never();
+    sps.constraint_set1_flag = RandomBoolRange::never();
+    sps.constraint_set2_flag = RandomBoolRange::never();
+    sps.constraint_set3_flag = if level_1b_flag {
+        RandomBoolRange::always()
+    } else {
+        RandomBoolRange::never()
+    };
+    sps.constraint_set4_flag = RandomBoolRange::never();
+    sps.constraint_set5_flag = RandomBoolRange::never();
+    sps.reserved_zero_2bits = RandomU32Range::new(0, 0);
+    sps.level_idc = RandomU32Enum::new(vec![level_idc]);
+    sps.seq_parameter_set_id = restrict_u32(sps.seq_parameter_set_id, 0, 31);
+    sps.chroma_format_idc = RandomU32Range::new(1, 1);
+    sps.separate_colour_plane_flag = RandomBoolRange::never();
+    sps.bit_depth_luma_minus8 = RandomU32Range::new(0, 0);
+    sps.bit_depth_chroma_minus8 = RandomU32Range::new(0, 0);
+    sps.bias_same_bit_depth = RandomBoolRange::always();
+    sps.qpprime_y_zero_transform_bypass_flag = RandomBoolRange::never();
+    sps.seq_scaling_matrix_present_flag = RandomBoolRange::never();
+    // MaxFrameNum stays above the 16 possible reference frames
+    sps.log2_max_frame_num_minus4 = restrict_u32(sps.log2_max_frame_num_minus4, 1, 12);
+    sps.pic_order_cnt_type = RandomU32Range::new(0, 0);
+    // a large MaxPicOrderCntLsb keeps PicOrderCntMsb unambiguous between reference pictures
+    sps.log2_max_pic_order_cnt_lsb_minus4 =
+        restrict_u32(sps.log2_max_pic_order_cnt_lsb_minus4, 4, 12);
+    sps.gaps_in_frame_num_value_allowed_flag = RandomBoolRange::never();
+    sps.frame_mbs_only_flag = RandomBoolRange::always();
+    sps.mb_adaptive_frame_field_flag = RandomBoolRange::never();
+    sps.direct_8x8_inference_flag = RandomBoolRange::always();
+    sps.frame_cropping_flag = RandomBoolRange::never();
+    // each dimension is at most Sqrt(MaxFS * 8) macroblocks (A.3.1 f and g)
+    let max_dimension = (8f64 * limits.max_fs as f64).sqrt() as u32;
+    sps.pic_width_in_mbs_minus1 = restrict_u32(sps.pic_width_in_mbs_minus1, 0, max_dimension - 1);
+    sps.pic_height_in_map_units_minus1 =
+        restrict_u32(sps.pic_height_in_map_units_minus1, 0, max_dimension - 1);
+    sps.bias_level_limits = RandomBoolRange::always();
+
+    // VUI
+    let vui = &mut sps.random_vui_range;
+    vui.aspect_ratio_idc = restrict_u32(vui.aspect_ratio_idc, 0, 16);
+    vui.video_format = restrict_u32(vui.video_format, 0, 5);
+    vui.colour_primaries = restrict_u32(vui.colour_primaries, 4, 12);
+    vui.transfer_characteristics = restrict_u32(vui.transfer_characteristics, 4, 18);
+    vui.matrix_coefficients = restrict_u32(vui.matrix_coefficients, 4, 10);
+    vui.chroma_sample_loc_type_top_field = restrict_u32(vui.chroma_sample_loc_type_top_field, 0, 5);
+    vui.chroma_sample_loc_type_bottom_field =
+        restrict_u32(vui.chroma_sample_loc_type_bottom_field, 0, 5);
+    vui.num_units_in_tick = restrict_u32(vui.num_units_in_tick, 1, u32::MAX);
+    vui.time_scale = restrict_u32(vui.time_scale, 1, u32::MAX);
+    // HRD parameters and pic_struct_present_flag require buffering period and picture timing SEI
+    vui.nal_hrd_parameters_present_flag = RandomBoolRange::never();
+    vui.vcl_hrd_parameters_present_flag = RandomBoolRange::never();
+    vui.pic_struct_present_flag = RandomBoolRange::never();
+    vui.max_bytes_per_pic_denom = restrict_u32(vui.max_bytes_per_pic_denom, 0, 16);
+    vui.max_bits_per_mb_denom = restrict_u32(vui.max_bits_per_mb_denom, 0, 16);
+    vui.log2_max_mv_length_horizontal = restrict_u32(vui.log2_max_mv_length_horizontal, 0, 15);
+    vui.log2_max_mv_length_vertical = restrict_u32(vui.log2_max_mv_length_vertical, 0, 15);
+
+    // PPS
+    let pps = &mut res.random_pps_range;
+    pps.pic_parameter_set_id = restrict_u32(pps.pic_parameter_set_id, 0, 255);
+    if baseline {
+        pps.entropy_coding_mode_flag = RandomBoolRange::never();
+    }
+    pps.num_slice_groups_minus1 = RandomU32Range::new(0, 0);
+    pps.bias_ignore_slice_groups = RandomBoolRange::always();
+    pps.num_ref_idx_l0_default_active_minus1 = RandomU32Range::new(0, 0);
+    pps.num_ref_idx_l1_default_active_minus1 = RandomU32Range::new(0, 0);
+    pps.weighted_pred_flag = RandomBoolRange::never();
+    pps.weighted_bipred_idc = RandomU32Range::new(0, 0);
+    pps.pic_init_qp_minus26 = restrict_i32(pps.pic_init_qp_minus26, -12, 0);
+    pps.pic_init_qs_minus26 = restrict_i32(pps.pic_init_qs_minus26, -26, 25);
+    pps.chroma_qp_index_offset = restrict_i32(pps.chroma_qp_index_offset, -12, 12);
+    // intra macroblocks of P and B slices predict from inter neighbours
+    pps.constrained_intra_pred_flag = RandomBoolRange::never();
+    pps.redundant_pic_cnt_present_flag = RandomBoolRange::never();
+    if !high {
+        pps.include_more_data = RandomBoolRange::never();
+        pps.transform_8x8_mode_flag = RandomBoolRange::never();
+    }
+    pps.pic_scaling_matrix_present_flag = RandomBoolRange::never();
+    pps.second_chroma_qp_index_offset = restrict_i32(pps.second_chroma_qp_index_offset, -12, 12);
+
+    // Slice header
+    let sh = &mut res.random_slice_header_range;
+    sh.slice_type = if baseline {
+        restrict_enum(&sh.slice_type, &[0, 2, 5, 7])
+    } else {
+        restrict_enum(&sh.slice_type, &[0, 1, 2, 5, 6, 7])
+    };
+    sh.field_pic_flag = RandomBoolRange::never();
+    // temporal direct may point to a picture outside of RefPicList0
+    sh.direct_spatial_mv_pred_flag = RandomBoolRange::always();
+    sh.num_ref_idx_active_override_flag = RandomBoolRange::never();
+    sh.ref_pic_list_modification_flag_l0 = RandomBoolRange::never();
+    sh.ref_pic_list_modification_flag_l1 = RandomBoolRange::never();
+    sh.no_output_of_prior_pics_flag = RandomBoolRange::never();
+    sh.long_term_reference_flag = RandomBoolRange::never();
+    sh.adaptive_ref_pic_marking_mode_flag = RandomBoolRange::never();
+    sh.cabac_init_idc = restrict_u32(sh.cabac_init_idc, 0, 2);
+    sh.slice_qp_delta.min = sh.slice_qp_delta.min.clamp(-4, 4);
+    sh.slice_qp_delta.max = sh.slice_qp_delta.max.clamp(sh.slice_qp_delta.min, 4);
+    sh.disable_deblocking_filter_idc = restrict_u32(sh.disable_deblocking_filter_idc, 0, 2);
+    sh.slice_alpha_c0_offset_div2 = restrict_i32(sh.slice_alpha_c0_offset_div2, -6, 6);
+    sh.slice_beta_offset_div2 = restrict_i32(sh.slice_beta_offset_div2, -6, 6);
+    sh.bias_i_slice = RandomBoolRange::always();
+    sh.bias_idr_zero_frame_num = RandomBoolRange::always();
+    sh.bias_zero_first_mb_in_slice = RandomBoolRange::always();
+    sh.bias_slice_qp_y_top_bound = RandomBoolRange::always();
+    sh.bias_slice_qp_y_bottom_bound = RandomBoolRange::always();
+    sh.bias_conforming_frame_num_poc = RandomBoolRange::always();
+
+    // Macroblocks
+    let mb = &mut res.random_mb_range;
+    mb.mb_i_type = restrict_enum(&mb.mb_i_type, &(0..25).collect::<Vec<u32>>());
+    mb.mb_p_type = restrict_enum(
+        &mb.mb_p_type,
+        &(0..30).filter(|x| *x != 4).collect::<Vec<u32>>(),
+    );
+    mb.mb_b_type = restrict_enum(&mb.mb_b_type, &(0..48).collect::<Vec<u32>>());
+    // at most one motion vector per 8x8 partition keeps within MaxMvsPer2Mb and MinLumaBiPredSize
+    if limits.max_mvs_per_2mb.is_some() {
+        mb.sub_mb_type_p = restrict_enum(&mb.sub_mb_type_p, &[0]);
+        mb.sub_mb_type_b = restrict_enum(&mb.sub_mb_type_b, &[0, 1, 2, 3]);
+    } else {
+        mb.sub_mb_type_b = restrict_enum(&mb.sub_mb_type_b, &(0..13).collect::<Vec<u32>>());
+    }
+    mb.ref_idx_l0 = RandomU32Range::new(0, 0);
+    mb.ref_idx_l1 = RandomU32Range::new(0, 0);
+    mb.bias_zero_mb_ref_idx_l0 = RandomBoolRange::always();
+    mb.bias_zero_mb_ref_idx_l1 = RandomBoolRange::always();
+    mb.mvd_l0 = RandomI32Range::new(0, 0);
+    mb.mvd_l1 = RandomI32Range::new(0, 0);
+    mb.mb_skip_run = RandomU32Range::new(0, 0);
+    mb.mb_qp_delta = RandomI32Range::new(0, 0);
+    mb.total_coeff = restrict_u32(mb.total_coeff, 0, 4);
+    mb.level_prefix = restrict_u32(mb.level_prefix, 0, 1);
+    mb.level_suffix = restrict_u32(mb.level_suffix, 0, 1);
+    mb.coeff_abs_level_minus1 = restrict_u32(mb.coeff_abs_level_minus1, 0, 1);
+    mb.last_significant_coeff_flag = RandomBoolRange::new(0, 1, 1);
+    mb.bias_b_p_no_residue = RandomBoolRange::always();
+
+    Ok(res)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::round_trip;
+    use crate::vidgen::film::FilmState;
+    use crate::vidgen::vidgen::random_video;
+
+    #[test]
+    fn test_spec_compliant_generation() {
+        for (seed, profile_idc, level_idc) in [(20, 66, 10), (21, 77, 20), (22, 100, 9)] {
+            let mut rconfig =
+                spec_compliant_config(&RandomizeConfig::new(), profile_idc, level_idc).unwrap();
+            rconfig.random_video_config.num_nalus = RandomU32Range::new(4, 10);
+            let mut film = FilmState::setup_film_from_seed(seed);
+            let mut ds = random_video(
+                false, true, true, false, false, true, false, false, &rconfig, &mut film,
+            );
+
+            assert_eq!(ds.nalu_headers[0].nal_unit_type, 7);
+            for sps in ds.spses.iter() {
+                assert_eq!(sps.profile_idc as u32, profile_idc);
+                let limits = level_limits(sps.level_idc, sps.constraint_set3_flag).unwrap();
+                let frame_size_in_mbs =
+                    (sps.pic_width_in_mbs_minus1 + 1) * (sps.pic_height_in_map_units_minus1 + 1);
+                assert!(frame_size_in_mbs <= limits.max_fs);
+                assert!(sps.max_num_ref_frames * frame_size_in_mbs <= limits.max_dpb_mbs);
+            }
+
+            let mut sps_idx = 0;
+            let mut slice_idx = 0;
+            let mut after_sps = false;
+            for nh in ds.nalu_headers.iter() {
+                match nh.nal_unit_type {
+                    7 => {
+                        sps_idx += 1;
+                        after_sps = true;
+                    }
+                    1 | 5 => {
+                        let sh = &ds.slices[slice_idx].sh;
+                        let sps = &ds.spses[sps_idx - 1];
+                        if after_sps {
+                            assert_eq!(nh.nal_unit_type, 5);
+                        }
+                        if nh.nal_unit_type == 5 {
+                            assert_eq!(sh.frame_num, 0);
+                            assert_eq!(sh.pic_order_cnt_lsb, 0);
+                        } else {
+                            assert!(nh.nal_ref_idc > 0 || slice_idx > 0);
+                            assert!(sh.frame_num < 1 << (sps.log2_max_frame_num_minus4 + 4));
+                        }
+                        if profile_idc == 66 {
+                            assert_ne!(sh.slice_type % 5, 1);
+                        }
+                        after_sps = false;
+                        slice_idx += 1;
+                    }
+                    8 => (),
+                    t => panic!("unexpected nal_unit_type {}", t),
+                }
+            }
+
+            let decoded = round_trip(&mut ds);
+            assert_eq!(decoded.nalu_headers.len(), ds.nalu_headers.len());
+            assert_eq!(decoded.slices.len(), ds.slices.len());
+        }
+
+        // level_idc 265 would otherwise wrap to Level 1b
+        for (profile_idc, level_idc) in [(88, 30), (77, 14), (77, 265)] {
+            assert!(
+                spec_compliant_config(&RandomizeConfig::new(), profile_idc, level_idc).is_err()
+            );
+        }
+    }
+}
@@ -0,0 +1,705 @@
+//! Layering of partial configuration files over the default random ranges.
+//!
+//! Each file only needs the fields it changes. Files are merged in order, so
+//! later files override earlier ones. Objects merge field by field, while
+//! arrays and values replace whatever a lower layer set.
+
+use crate::common::data_structures::KNOWN_UUIDS;
+use crate::vidgen::generate_configurations::RandomizeConfig;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+
+/// Origin reported for fields no configuration file has set
+const DEFAULT_LAYER: &str = "(defaults)";
+
+/// Range of the unregistered user data SEI that indexes the known UUIDs and then the schemas
+const USER_DATA_UNREGISTERED_RANGE: &str = "random_sei_range.random_user_data_unregistered_range";
+
+/// A problem in a configuration file, located by the file and field path
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub file: String,
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}: {}", self.file, self.message)
+        } else {
+            write!(f, "{}: {}: {}", self.file, self.path, self.message)
+        }
+    }
+}
+
+/// Merge the configuration files in order over the default ranges.
+///
+/// Returns the merged configuration along with every problem found. The
+/// configuration is None if a file could not be read or the merged result
+/// does not deserialize. Unknown fields and fields of the wrong type are
+/// reported and then ignored.
+pub fn layer_config_files(filenames: &[String]) -> (Option<RandomizeConfig>, Vec<ConfigError>) {
+    let mut errors = Vec::new();
+    let mut origin: HashMap<String, String> = HashMap::new();
+    let mut merged = match serde_json::to_value(RandomizeConfig::new()) {
+        Ok(Value::Object(fields)) => fields,
+        _ => panic!("Unable to serialize the default configuration"),
+    };
+
+    let mut usable = true;
+    for filename in filenames {
+        let error = |message: String| ConfigError {
+            file: filename.clone(),
+            path: String::new(),
+            message,
+        };
+        let contents = match fs::read_to_string(filename) {
+            Ok(x) => x,
+            Err(y) => {
+                errors.push(error(format!("couldn't open: {}", y)));
+                usable = false;
+                continue;
+            }
+        };
+        match serde_json::from_str::<Value>(&contents) {
+            Ok(Value::Object(fields)) => {
+                merge_layer(&mut merged, fields, filename, "", &mut origin, &mut errors)
+            }
+            Ok(_) => {
+                errors.push(error("expected a JSON object at the top level".to_string()));
+                usable = false;
+            }
+            Err(y) => {
+                errors.push(error(format!("invalid JSON: {}", y)));
+                usable = false;
+            }
+        }
+    }
+
+    if !usable {
+        return (None, errors);
+    }
+
+    derive_user_data_uuid_max(filenames, &mut merged, &origin);
+    let merged = Value::Object(merged);
+    let config: RandomizeConfig = match serde_json::from_value(merged.clone()) {
+        Ok(x) => x,
+        Err(y) => {
+            errors.push(ConfigError {
+                file: filenames.join(", "),
+                path: String::new(),
+                message: format!("merged configuration is invalid: {}", y),
+            });
+            return (None, errors);
+        }
+    };
+
+    check_ranges(filenames, &merged, "", &origin, &mut errors);
+    check_dependencies(&config, &origin, &mut errors);
+    if !check_slice_overrides(&merged, &origin, &mut errors) {
+        return (None, errors);
+    }
+
+    (Some(config), errors)
+}
+
+/// Layer partial configurations, such as those of slice overrides, over a full one
+pub fn layer_overrides(
+    config: &RandomizeConfig,
+    layers: &[&Value],
+) -> Result<RandomizeConfig, String> {
+    let mut res = config.clone();
+    for layer in layers {
+        if let Value::Object(fields) = layer {
+            // problems were reported when the configuration was loaded
+            res = layer_partial(&res, fields, "", "").0?;
+        }
+    }
+    Ok(res)
+}
+
+/// Layer the fields of a partial JSON object over any serializable value, such as a
+/// configuration section or a syntax structure. Unknown fields and fields of the wrong
+/// type are reported with the file and path they came from, and otherwise left as they were
+pub fn layer_partial<T: Serialize + DeserializeOwned>(
+    value: &T,
+    layer: &Map<String, Value>,
+    filename: &str,
+    path: &str,
+) -> (Result<T, String>, Vec<ConfigError>) {
+    let mut merged = match serde_json::to_value(value) {
+        Ok(Value::Object(fields)) => fields,
+        _ => panic!("Unable to serialize {}", path),
+    };
+    let mut errors = Vec::new();
+    merge_layer(
+        &mut merged,
+        layer.clone(),
+        filename,
+        path,
+        &mut HashMap::new(),
+        &mut errors,
+    );
+    (
+        serde_json::from_value(Value::Object(merged)).map_err(|e| e.to_string()),
+        errors,
+    )
+}
+
+/// Fields absent from the serialized defaults that a range may still set
+fn is_optional_field(fields: &Map<String, Value>, key: &str) -> bool {
+    match key {
+        "distribution" => fields.contains_key("min"),
+        "weights" => fields.contains_key("values"),
+        _ => false,
+    }
+}
+
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(x) if x.is_f64() => "a decimal number",
+        Value::Number(_) => "an integer",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+fn field_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+/// Recursively merge the fields of one layer, recording which file set each value
+fn merge_layer(
+    merged: &mut Map<String, Value>,
+    layer: Map<String, Value>,
+    filename: &str,
+    path: &str,
+    origin: &mut HashMap<String, String>,
+    errors: &mut Vec<ConfigError>,
+) {
+    for (key, value) in layer {
+        let path = field_path(path, &key);
+        let error = |message: String| ConfigError {
+            file: filename.to_string(),
+            path: path.clone(),
+            message,
+        };
+
+        match merged.get_mut(&key) {
+            None => {
+                if is_optional_field(merged, &key) {
+                    origin.insert(path.clone(), filename.to_string());
+                    merged.insert(key, value);
+                } else {
+                    errors.push(error("unknown field".to_string()));
+                }
+            }
+            Some(Value::Object(existing)) => match value {
+                Value::Object(fields) => {
+                    merge_layer(existing, fields, filename, &path, origin, errors)
+                }
+                _ => errors.push(error(format!("expected an object, found {}", kind(&value)))),
+            },
+            Some(existing) => {
+                // unset optional values take whatever the layer has
+                if existing.is_null() || kind(existing) == kind(&value) {
+                    origin.insert(path.clone(), filename.to_string());
+                    *existing = value;
+                } else {
+                    errors.push(error(format!(
+                        "expected {}, found {}",
+                        kind(existing),
+                        kind(&value)
+                    )));
+                }
+            }
+        }
+    }
+}
+
+/// The file that last set a field, or any of its parents if an array replaced them
+fn origin_of(origin: &HashMap<String, String>, path: &str) -> String {
+    let mut path = path;
+    loop {
+        if let Some(x) = origin.get(path) {
+            return x.clone();
+        }
+        match path.rfind(['.', '[']) {
+            Some(idx) => path = &path[..idx],
+            None => return DEFAULT_LAYER.to_string(),
+        }
+    }
+}
+
+/// Cover every schema with the UUID index range when a file replaced the schemas after
+/// the range's max was last set, so adding a schema doesn't need the max bumped by hand
+fn derive_user_data_uuid_max(
+    filenames: &[String],
+    merged: &mut Map<String, Value>,
+    origin: &HashMap<String, String>,
+) {
+    let schemas_origin = origin_of(origin, &field_path(USER_DATA_UNREGISTERED_RANGE, "schemas"));
+    let max_origin = origin_of(
+        origin,
+        &field_path(USER_DATA_UNREGISTERED_RANGE, "uuid_iso_iec_11578.max"),
+    );
+    let last_set = |file: &String| filenames.iter().rposition(|x| x == file);
+    if last_set(&schemas_origin) <= last_set(&max_origin) {
+        return;
+    }
+
+    let range = merged
+        .get_mut("random_sei_range")
+        .and_then(|x| x.get_mut("random_user_data_unregistered_range"))
+        .and_then(Value::as_object_mut);
+    if let Some(range) = range {
+        let num_schemas = match range.get("schemas") {
+            Some(Value::Array(x)) => x.len() as u32,
+            _ => return,
+        };
+        if let Some(Value::Object(uuid)) = range.get_mut("uuid_iso_iec_11578") {
+            uuid.insert(
+                "max".to_string(),
+                Value::from((KNOWN_UUIDS + num_schemas).saturating_sub(1)),
+            );
+        }
+    }
+}
+
+/// Check every range and enum in the merged configuration
+fn check_ranges(
+    filenames: &[String],
+    value: &Value,
+    path: &str,
+    origin: &HashMap<String, String>,
+    errors: &mut Vec<ConfigError>,
+) {
+    match value {
+        Value::Object(fields) => {
+            if let (Some(min), Some(max)) = (
+                fields.get("min").and_then(Value::as_i64),
+                fields.get("max").and_then(Value::as_i64),
+            ) {
+                if min > max {
+                    // blame whichever bound a file set last
+                    let min_origin = origin_of(origin, &field_path(path, "min"));
+                    let max_origin = origin_of(origin, &field_path(path, "max"));
+                    let last_set = |file: &String| filenames.iter().rposition(|x| x == file);
+                    errors.push(ConfigError {
+                        file: if last_set(&min_origin) > last_set(&max_origin) {
+                            min_origin
+                        } else {
+                            max_origin
+                        },
+                        path: path.to_string(),
+                        message: format!("min {} is greater than max {}", min, max),
+                    });
+                }
+            }
+
+            // RandomU32Enum; user data schemas also have values but other fields too
+            if let Some(Value::Array(values)) = fields.get("values") {
+                if fields.keys().all(|k| k == "values" || k == "weights") {
+                    let weights: Vec<u64> = match fields.get("weights") {
+                        Some(Value::Array(x)) => x.iter().filter_map(Value::as_u64).collect(),
+                        _ => Vec::new(),
+                    };
+                    let error = |message: String| ConfigError {
+                        file: origin_of(origin, &field_path(path, "values")),
+                        path: path.to_string(),
+                        message,
+                    };
+                    if values.is_empty() {
+                        errors.push(error("no values to sample from".to_string()));
+                    } else if !weights.is_empty() && weights.len() != values.len() {
+                        errors.push(error(format!(
+                            "{} weights for {} values",
+                            weights.len(),
+                            values.len()
+                        )));
+                    } else if !weights.is_empty() && weights.iter().all(|x| *x == 0) {
+                        errors.push(error("all weights are 0".to_string()));
+                    }
+                }
+            }
+
+            for (key, value) in fields {
+                check_ranges(filenames, value, &field_path(path, key), origin, errors);
+            }
+        }
+        Value::Array(values) => {
+            for (idx, value) in values.iter().enumerate() {
+                check_ranges(
+                    filenames,
+                    value,
+                    &format!("{}[{}]", path, idx),
+                    origin,
+                    errors,
+                );
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Check that the partial configuration of each slice override layers cleanly over the
+/// rest. Returns false if an override would not produce a usable configuration
+fn check_slice_overrides(
+    merged: &Value,
+    origin: &HashMap<String, String>,
+    errors: &mut Vec<ConfigError>,
+) -> bool {
+    let slice_overrides = match merged.get("random_slice_overrides") {
+        Some(Value::Array(x)) => x,
+        _ => return true,
+    };
+
+    let mut usable = true;
+    for (idx, slice_override) in slice_overrides.iter().enumerate() {
+        let path = format!("random_slice_overrides[{}]", idx);
+        let file = origin_of(origin, &path);
+        // serde ignores unknown fields, so a misspelled criterion would match every slice
+        if let Value::Object(fields) = slice_override {
+            for key in fields.keys() {
+                if ![
+                    "nalu_indices",
+                    "first_slices",
+                    "idr",
+                    "slice_types",
+                    "config",
+                ]
+                .contains(&key.as_str())
+                {
+                    errors.push(ConfigError {
+                        file: file.clone(),
+                        path: field_path(&path, key),
+                        message: "unknown field".to_string(),
+                    });
+                }
+            }
+        }
+
+        let path = field_path(&path, "config");
+        let layer = match slice_override.get("config") {
+            Some(Value::Object(fields)) => fields.clone(),
+            x => {
+                errors.push(ConfigError {
+                    file,
+                    path,
+                    message: format!("expected an object, found {}", x.map_or("nothing", kind)),
+                });
+                usable = false;
+                continue;
+            }
+        };
+        if layer.contains_key("random_slice_overrides") {
+            errors.push(ConfigError {
+                file: file.clone(),
+                path: field_path(&path, "random_slice_overrides"),
+                message: "slice overrides cannot be nested".to_string(),
+            });
+        }
+
+        let mut layered = match merged {
+            Value::Object(fields) => fields.clone(),
+            _ => continue,
+        };
+        merge_layer(
+            &mut layered,
+            layer,
+            &file,
+            &path,
+            &mut HashMap::new(),
+            errors,
+        );
+        if let Err(y) = serde_json::from_value::<RandomizeConfig>(Value::Object(layered)) {
+            errors.push(ConfigError {
+                file,
+                path,
+                message: format!("layered configuration is invalid: {}", y),
+            });
+            usable = false;
+        }
+    }
+    usable
+}
+
+/// Check ranges whose combination can never produce the requested syntax
+fn check_dependencies(
+    config: &RandomizeConfig,
+    origin: &HashMap<String, String>,
+    errors: &mut Vec<ConfigError>,
+) {
+    let sps_ranges = [
+        ("random_sps_range", &config.random_sps_range),
+        (
+            "random_subset_sps_range.random_sps_range",
+            &config.random_subset_sps_range.random_sps_range,
+        ),
+    ];
+    for (path, sps) in sps_ranges {
+        // E.2.1: max_num_reorder_frames shall not exceed max_dec_frame_buffering, which
+        // is drawn from [0, max_num_ref_frames] when it uses its dependency
+        let vui = &sps.random_vui_range;
+        let (max_dpb, dpb_path) = if vui.max_dec_frame_buffering.use_dependency {
+            (
+                sps.max_num_ref_frames.max,
+                field_path(path, "max_num_ref_frames"),
+            )
+        } else {
+            (
+                vui.max_dec_frame_buffering.max,
+                field_path(path, "random_vui_range.max_dec_frame_buffering"),
+            )
+        };
+        if vui.max_num_reorder_frames.min > max_dpb {
+            let reorder_path = field_path(path, "random_vui_range.max_num_reorder_frames");
+            errors.push(ConfigError {
+                file: origin_of(origin, &format!("{}.min", reorder_path)),
+                path: reorder_path,
+                message: format!(
+                    "min {} always exceeds {}.max {}",
+                    vui.max_num_reorder_frames.min, dpb_path, max_dpb
+                ),
+            });
+        }
+    }
+
+    // a B picture needs an anchor picture after the IDR picture, so a GOP of at least 3
+    let gop = &config.random_gop_range;
+    if gop.num_b_frames.min > 0 && gop.gop_length.max < 3 {
+        errors.push(ConfigError {
+            file: origin_of(origin, "random_gop_range.num_b_frames.min"),
+            path: "random_gop_range.num_b_frames".to_string(),
+            message: format!(
+                "min {} requires B pictures but random_gop_range.gop_length.max {} leaves no room for them",
+                gop.num_b_frames.min, gop.gop_length.max
+            ),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vidgen::generate_configurations::load_config;
+    use crate::vidgen::generate_configurations::Distribution;
+
+    fn write_layer(name: &str, contents: &str) -> String {
+        let filename = std::env::temp_dir()
+            .join(format!("h26forge_{}_{}.json", std::process::id(), name))
+            .to_string_lossy()
+            .to_string();
+        fs::write(&filename, contents).unwrap();
+        filename
+    }
+
+    #[test]
+    fn test_bundled_configs_validate() {
+        for f in [
+            "config/default.json",
+            "config/chrome.json",
+            "config/narrower.json",
+        ] {
+            let (config, errors) = layer_config_files(&[f.to_string()]);
+            assert!(config.is_some(), "{} did not load", f);
+            assert!(errors.is_empty(), "{}: {:?}", f, errors);
+        }
+    }
+
+    #[test]
+    fn test_layered_partial_configs() {
+        let base = write_layer(
+            "base",
+            r#"{"random_video_config": {"num_nalus": {"min": 7, "max": 9}},
+                "random_sps_range": {"profile_idc": {"values": [66, 77], "weights": [1, 3]}}}"#,
+        );
+        let overlay = write_layer(
+            "overlay",
+            r#"{"random_video_config": {"num_nalus": {"max": 12, "distribution": "log_uniform"}}}"#,
+        );
+
+        let (config, errors) = layer_config_files(&[base.clone(), overlay.clone()]);
+        assert!(errors.is_empty(), "{:?}", errors);
+        let config = config.unwrap();
+        let num_nalus = config.random_video_config.num_nalus;
+        assert_eq!((num_nalus.min, num_nalus.max), (7, 12));
+        assert_eq!(num_nalus.distribution, Distribution::LogUniform);
+        assert_eq!(config.random_sps_range.profile_idc.values, vec![66, 77]);
+        assert_eq!(config.random_sps_range.profile_idc.weights, vec![1, 3]);
+        // untouched fields keep their defaults
+        let default = RandomizeConfig::new();
+        assert_eq!(
+            config.random_pps_range.pic_parameter_set_id.max,
+            default.random_pps_range.pic_parameter_set_id.max
+        );
+
+        fs::remove_file(base).unwrap();
+        fs::remove_file(overlay).unwrap();
+    }
+
+    #[test]
+    fn test_config_errors_name_file_and_field() {
+        let base = write_layer(
+            "errors_base",
+            r#"{"random_gop_range": {"num_b_frames": {"min": 1, "max": 2}},
+                "random_sps_range": {"profile_idc": {"values": [66, 77], "weights": [1]},
+                                     "random_vui_range": {"max_num_reorder_frames": {"min": 20, "max": 30},
+                                                          "max_dec_frame_buffering": {"max": 16}}}}"#,
+        );
+        let overlay = write_layer(
+            "errors_overlay",
+            r#"{"random_video_config": {"num_nalu": {"min": 1, "max": 2}, "num_nalus": {"max": "10"}},
+                "random_gop_range": {"gop_length": {"min": 2, "max": 2}, "num_gops": {"min": 5}}}"#,
+        );
+
+        let (config, errors) = layer_config_files(&[base.clone(), overlay.clone()]);
+        assert!(config.is_some());
+        let has_error = |file: &str, path: &str, message: &str| {
+            errors
+                .iter()
+                .any(|e| e.file == file && e.path == path && e.message.contains(message))
+        };
+        assert!(has_error(
+            &overlay,
+            "random_video_config.num_nalu",
+            "unknown field"
+        ));
+        assert!(has_error(
+            &overlay,
+            "random_video_config.num_nalus.max",
+            "expected an integer, found a string"
+        ));
+        assert!(has_error(
+            &overlay,
+            "random_gop_range.num_gops",
+            "min 5 is greater than max 3"
+        ));
+        assert!(has_error(
+            &base,
+            "random_sps_range.profile_idc",
+            "1 weights for 2 values"
+        ));
+        assert!(has_error(
+            &base,
+            "random_sps_range.random_vui_range.max_num_reorder_frames",
+            "always exceeds"
+        ));
+        assert!(has_error(
+            &base,
+            "random_gop_range.num_b_frames",
+            "leaves no room"
+        ));
+        assert_eq!(errors.len(), 6, "{:?}", errors);
+        // generation refuses the files that validate-config reports
+        assert_eq!(
+            load_config(&[base.clone(), overlay.clone()]).unwrap_err(),
+            errors
+        );
+
+        fs::remove_file(base).unwrap();
+        fs::remove_file(overlay).unwrap();
+    }
+
+    #[test]
+    fn test_slice_override_errors() {
+        let overrides = write_layer(
+            "overrides",
+            r#"{"random_slice_overrides": [
+                {"idr": true, "config": {"random_mb_range": {"mb_skip_flag": {"min": 1, "max": 0}}}},
+                {"slice_type": [0], "config": {"random_slice_header_range": {"slice_qp": {}}}},
+                {"first_slices": 2, "config": {"random_slice_header_range": {"slice_qp_delta": {"max": 1.5}}}}
+            ]}"#,
+        );
+
+        let (config, errors) = layer_config_files(std::slice::from_ref(&overrides));
+        assert_eq!(config.unwrap().random_slice_overrides.len(), 3);
+        let paths: Vec<&str> = errors.iter().map(|x| x.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "random_slice_overrides[0].config.random_mb_range.mb_skip_flag",
+                "random_slice_overrides[1].slice_type",
+                "random_slice_overrides[1].config.random_slice_header_range.slice_qp",
+                "random_slice_overrides[2].config.random_slice_header_range.slice_qp_delta.max",
+            ]
+        );
+        assert!(errors.iter().all(|x| x.file == overrides));
+
+        fs::remove_file(overrides).unwrap();
+    }
+
+    #[test]
+    fn test_user_data_schemas_derive_uuid_max() {
+        let schema = r#"{"name": "test", "uuid_iso_iec_11578": [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15], "fields": []}"#;
+        let schemas = write_layer(
+            "schemas",
+            &format!(
+                r#"{{"random_sei_range": {{"random_user_data_unregistered_range": {{"schemas": [{0}, {0}, {0}]}}}}}}"#,
+                schema
+            ),
+        );
+        let pinned = write_layer(
+            "schemas_pinned",
+            &format!(
+                r#"{{"random_sei_range": {{"random_user_data_unregistered_range": {{"uuid_iso_iec_11578": {{"min": 3, "max": 3}}, "schemas": [{0}, {0}, {0}]}}}}}}"#,
+                schema
+            ),
+        );
+        let uuid_range = |files: &[String]| {
+            let (config, errors) = layer_config_files(files);
+            assert!(errors.is_empty(), "{:?}", errors);
+            let range = config
+                .unwrap()
+                .random_sei_range
+                .random_user_data_unregistered_range
+                .uuid_iso_iec_11578;
+            (range.min, range.max)
+        };
+
+        // the max follows the schemas replaced after it, even over a full config
+        assert_eq!(
+            uuid_range(std::slice::from_ref(&schemas)),
+            (0, KNOWN_UUIDS + 2)
+        );
+        assert_eq!(
+            uuid_range(&["config/default.json".to_string(), schemas.clone()]),
+            (0, KNOWN_UUIDS + 2)
+        );
+        // but not over a max set along with or after the schemas
+        assert_eq!(uuid_range(std::slice::from_ref(&pinned)), (3, 3));
+        assert_eq!(
+            uuid_range(&[schemas.clone(), "config/default.json".to_string()]),
+            (0, KNOWN_UUIDS + 1)
+        );
+
+        fs::remove_file(schemas).unwrap();
+        fs::remove_file(pinned).unwrap();
+    }
+
+    #[test]
+    fn test_unreadable_config() {
+        let broken = write_layer("broken", r#"{"random_video_config": "#);
+        let missing = "h26forge_missing_config.json".to_string();
+
+        let (config, errors) = layer_config_files(&[broken.clone(), missing.clone()]);
+        assert!(config.is_none());
+        assert_eq!(errors.len(), 2);
+        assert!(errors[0].file == broken && errors[0].message.starts_with("invalid JSON"));
+        assert!(errors[1].file == missing && errors[1].message.starts_with("couldn't open"));
+
+        fs::remove_file(broken).unwrap();
+    }
+}
@@ -1,11 +1,14 @@
 //! Data Structures containing ranges for random value generation.
 
 use crate::common::data_structures::KNOWN_UUIDS;
+use crate::common::data_structures::UUID_MISB_MICROSECTIME;
+use crate::common::data_structures::UUID_X264;
+use crate::vidgen::config_layers;
+use crate::vidgen::config_layers::ConfigError;
 use crate::vidgen::film::FilmState;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::prelude::*;
-use std::io::BufReader;
 
 /// Maintains ranges for bool types
 ///
@@ -32,6 +35,98 @@ impl RandomBoolRange {
             threshold: threshold,
         }
     }
+    /// A range that always samples true
+    pub fn always() -> RandomBoolRange {
+        RandomBoolRange::new(1, 1, 1)
+    }
+    /// A range that always samples false
+    pub fn never() -> RandomBoolRange {
+        RandomBoolRange::new(0, 0, 2)
+    }
+}
+
+/// How a value is drawn from the [min, max] of a range. Configs without a
+/// distribution sample uniformly, like before.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Distribution {
+    #[default]
+    Uniform,
+    /// Half of the time, pick one of min, min+1, max-1, max, 0, +-1, or +-2^k and
+    /// +-(2^k - 1) within the range; otherwise sample uniformly
+    Boundary,
+    /// Pick the bit length of the distance from the bound nearest to 0 uniformly,
+    /// so small and large magnitudes are equally likely
+    LogUniform,
+}
+
+impl Distribution {
+    pub fn is_uniform(&self) -> bool {
+        *self == Distribution::Uniform
+    }
+
+    /// Values of interest within [min, max], sorted and without duplicates
+    fn boundary_values(min: i64, max: i64) -> Vec<i64> {
+        let mut res = vec![min, min + 1, max - 1, max, -1, 0, 1];
+        for k in 1..=32 {
+            let p = 1i64 << k;
+            res.extend([p - 1, p, -(p - 1), -p]);
+        }
+        res.retain(|x| min <= *x && *x <= max);
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    /// A distance in [0, span] whose bit length is uniformly distributed
+    fn log_uniform_offset(span: u64, film: &mut FilmState) -> u64 {
+        let bit_length = 64 - span.leading_zeros();
+        match film.read_film_u32(0, bit_length) {
+            0 => 0,
+            x => {
+                let low = 1u64 << (x - 1);
+                let high = std::cmp::min((1u64 << x) - 1, span);
+                low + film.read_film_u32(0, (high - low) as u32) as u64
+            }
+        }
+    }
+
+    /// Sample from [min, max] for everything but the uniform distribution
+    fn sample_i64(&self, min: i64, max: i64, film: &mut FilmState) -> i64 {
+        if min >= max {
+            return min;
+        }
+        match self {
+            Distribution::Boundary if film.read_film_u32(0, 1) == 0 => {
+                let values = Distribution::boundary_values(min, max);
+                values[film.read_film_u32(0, values.len() as u32 - 1) as usize]
+            }
+            Distribution::LogUniform => {
+                let anchor = 0.clamp(min, max);
+                // ranges that cross 0 pick a side first
+                if anchor > min && (anchor == max || film.read_film_u32(0, 1) == 0) {
+                    anchor - Distribution::log_uniform_offset((anchor - min) as u64, film) as i64
+                } else {
+                    anchor + Distribution::log_uniform_offset((max - anchor) as u64, film) as i64
+                }
+            }
+            _ => min + film.read_film_u32(0, (max - min) as u32) as i64,
+        }
+    }
+
+    pub fn sample_u32(&self, min: u32, max: u32, film: &mut FilmState) -> u32 {
+        match self {
+            Distribution::Uniform => film.read_film_u32(min, max),
+            _ => self.sample_i64(min as i64, max as i64, film) as u32,
+        }
+    }
+
+    pub fn sample_i32(&self, min: i32, max: i32, film: &mut FilmState) -> i32 {
+        match self {
+            Distribution::Uniform => film.read_film_i32(min, max),
+            _ => self.sample_i64(min as i64, max as i64, film) as i32,
+        }
+    }
 }
 
 /// Maintains ranges for i32 type
@@ -39,14 +134,20 @@ impl RandomBoolRange {
 pub struct RandomI32Range {
     pub min: i32,
     pub max: i32,
+    #[serde(default, skip_serializing_if = "Distribution::is_uniform")]
+    pub distribution: Distribution,
 }
 
 impl RandomI32Range {
     pub fn sample(&self, film: &mut FilmState) -> i32 {
-        film.read_film_i32(self.min, self.max)
+        self.distribution.sample_i32(self.min, self.max, film)
     }
     pub fn new(min: i32, max: i32) -> RandomI32Range {
-        RandomI32Range { min: min, max: max }
+        RandomI32Range {
+            min,
+            max,
+            distribution: Distribution::Uniform,
+        }
     }
 }
 
@@ -56,24 +157,28 @@ pub struct RandomDependentI32Range {
     pub min: i32,
     pub max: i32,
     pub use_dependency: bool,
+    #[serde(default, skip_serializing_if = "Distribution::is_uniform")]
+    pub distribution: Distribution,
 }
 
 impl RandomDependentI32Range {
     pub fn sample(&self, dependent_min: i32, dependent_max: i32, film: &mut FilmState) -> i32 {
         if self.use_dependency {
-            film.read_film_i32(dependent_min, dependent_max)
+            self.distribution
+                .sample_i32(dependent_min, dependent_max, film)
         } else {
-            film.read_film_i32(self.min, self.max)
+            self.distribution.sample_i32(self.min, self.max, film)
         }
     }
     pub fn non_dependent_sample(&self, film: &mut FilmState) -> i32 {
-        film.read_film_i32(self.min, self.max)
+        self.distribution.sample_i32(self.min, self.max, film)
     }
     pub fn new(min: i32, max: i32, use_dependency: bool) -> RandomDependentI32Range {
         RandomDependentI32Range {
             min: min,
             max: max,
             use_dependency: use_dependency,
+            distribution: Distribution::Uniform,
         }
     }
 }
@@ -83,23 +188,29 @@ impl RandomDependentI32Range {
 pub struct RandomU32Range {
     pub min: u32,
     pub max: u32,
+    #[serde(default, skip_serializing_if = "Distribution::is_uniform")]
+    pub distribution: Distribution,
 }
 
 impl RandomU32Range {
     pub fn sample(&self, film: &mut FilmState) -> u32 {
-        film.read_film_u32(self.min, self.max)
+        self.distribution.sample_u32(self.min, self.max, film)
     }
 
     pub fn sample_custom_max(&self, custom_max: u32, film: &mut FilmState) -> u32 {
         if custom_max < self.max as u32 {
-            film.read_film_u32(0, custom_max)
+            self.distribution.sample_u32(0, custom_max, film)
         } else {
             self.sample(film)
         }
     }
 
     pub fn new(min: u32, max: u32) -> RandomU32Range {
-        RandomU32Range { min: min, max: max }
+        RandomU32Range {
+            min,
+            max,
+            distribution: Distribution::Uniform,
+        }
     }
 }
 
@@ -109,14 +220,17 @@ pub struct RandomDependentU32Range {
     pub min: u32,
     pub max: u32,
     pub use_dependency: bool,
+    #[serde(default, skip_serializing_if = "Distribution::is_uniform")]
+    pub distribution: Distribution,
 }
 
 impl RandomDependentU32Range {
     pub fn sample(&self, dependent_min: u32, dependent_max: u32, film: &mut FilmState) -> u32 {
         if self.use_dependency {
-            film.read_film_u32(dependent_min, dependent_max)
+            self.distribution
+                .sample_u32(dependent_min, dependent_max, film)
         } else {
-            film.read_film_u32(self.min, self.max)
+            self.distribution.sample_u32(self.min, self.max, film)
         }
     }
 
@@ -125,18 +239,35 @@ impl RandomDependentU32Range {
             min: min,
             max: max,
             use_dependency: use_dependency,
+            distribution: Distribution::Uniform,
         }
     }
 }
 
-/// Samples from an enum
+/// Samples from an enum, uniformly or by weight
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RandomU32Enum {
     pub values: Vec<u32>,
+    // one relative weight per value; when empty, every value is equally likely
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<u32>,
 }
 
 impl RandomU32Enum {
     pub fn sample(&self, film: &mut FilmState) -> u32 {
+        let total_weight: u64 = self.weights.iter().map(|x| *x as u64).sum();
+        if self.weights.len() == self.values.len() && total_weight > 0 {
+            // the weights of values up to and including the chosen one exceed the draw
+            let mut draw = film
+                .read_film_u32(0, std::cmp::min(total_weight - 1, u32::MAX as u64) as u32)
+                as u64;
+            for (value, weight) in self.values.iter().zip(self.weights.iter()) {
+                if draw < *weight as u64 {
+                    return *value;
+                }
+                draw -= *weight as u64;
+            }
+        }
         if self.values.len() > 0 {
             let idx = film.read_film_u32(0, self.values.len() as u32 - 1);
             self.values[idx as usize]
@@ -148,6 +279,7 @@ impl RandomU32Enum {
     pub fn new(values: Vec<u32>) -> RandomU32Enum {
         RandomU32Enum {
             values: values.clone(),
+            weights: Vec::new(),
         }
     }
 }
@@ -488,6 +620,8 @@ pub struct RandomSliceHeaderRange {
     pub bias_zero_first_mb_in_slice: RandomBoolRange, // if True, first_mb_in_slice is 0, else sampled
     pub bias_slice_qp_y_top_bound: RandomBoolRange,   // if True, slice_qp_y <= 51
     pub bias_slice_qp_y_bottom_bound: RandomBoolRange, // if True, slice_qp_y >= 0
+    #[serde(default = "RandomBoolRange::never")]
+    pub bias_conforming_frame_num_poc: RandomBoolRange, // if True, frame_num, POC, and idr_pic_id follow decoding order
 }
 
 impl RandomSliceHeaderRange {
@@ -557,6 +691,7 @@ impl RandomSliceHeaderRange {
             bias_zero_first_mb_in_slice: RandomBoolRange::new(0, 50, 1), // 49 out of 50 times, first_mb_in_slice is 0
             bias_slice_qp_y_top_bound: RandomBoolRange::new(0, 100, 1), // 99 out of 100 times, slice_qp_y <= 51
             bias_slice_qp_y_bottom_bound: RandomBoolRange::new(0, 100, 1), // 99 out of 100 times, slice_qp_y >= 0
+            bias_conforming_frame_num_poc: RandomBoolRange::never(),
         }
     }
 }
@@ -567,6 +702,157 @@ impl Default for RandomSliceHeaderRange {
     }
 }
 
+/// Slice data partition syntax elements
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSliceDataPartitionRange {
+    pub slice_id: RandomU32Range,          //ue(v)
+    pub colour_plane_id: RandomU32Range,   //u(2)
+    pub redundant_pic_cnt: RandomU32Range, //ue(v)
+    // biases in video generation
+    pub bias_partitioned_slice: RandomBoolRange, // if True, a non-IDR slice is split into partitions A, B, and C
+    pub bias_same_as_partition_a: RandomBoolRange, // if True, partitions B and C copy their values from partition A, else sampled
+}
+
+impl RandomSliceDataPartitionRange {
+    pub fn new() -> RandomSliceDataPartitionRange {
+        RandomSliceDataPartitionRange {
+            slice_id: RandomU32Range::new(0, 255),
+            colour_plane_id: RandomU32Range::new(0, 3), // u(2) --  only read if SPS allows it
+            redundant_pic_cnt: RandomU32Range::new(0, 127), // [0, 127]
+            bias_partitioned_slice: RandomBoolRange::new(0, 1, 1), // 50%
+            bias_same_as_partition_a: RandomBoolRange::new(0, 9, 1), // 9 out of 10 times, copy from partition A
+        }
+    }
+}
+
+impl Default for RandomSliceDataPartitionRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Slice header and macroblock syntax elements of the scalable extension (Annex G)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSliceSVCExtensionRange {
+    // slice_header_in_scalable_extension
+    pub base_pred_weight_table_flag: RandomBoolRange,
+    pub store_ref_base_pic_flag: RandomBoolRange,
+    // dec_ref_base_pic_marking
+    pub adaptive_ref_base_pic_marking_mode_flag: RandomBoolRange,
+    pub num_modifications: RandomU32Range, // number of base marking operations to include
+    pub memory_management_base_control_operation: RandomU32Range, //ue(v)
+    pub difference_of_base_pic_nums_minus1: RandomU32Range, //ue(v)
+    pub long_term_base_pic_num: RandomU32Range, //ue(v)
+    //
+    pub ref_layer_dq_id: RandomU32Range, //ue(v)
+    pub disable_inter_layer_deblocking_filter_idc: RandomU32Range, //ue(v)
+    pub inter_layer_slice_alpha_c0_offset_div2: RandomI32Range, //se(v)
+    pub inter_layer_slice_beta_offset_div2: RandomI32Range, //se(v)
+    pub constrained_intra_resampling_flag: RandomBoolRange,
+    pub ref_layer_chroma_phase_x_plus1_flag: RandomBoolRange,
+    pub ref_layer_chroma_phase_y_plus1: RandomU32Range, //u(2)
+    pub scaled_ref_layer_left_offset: RandomI32Range,   //se(v)
+    pub scaled_ref_layer_top_offset: RandomI32Range,    //se(v)
+    pub scaled_ref_layer_right_offset: RandomI32Range,  //se(v)
+    pub scaled_ref_layer_bottom_offset: RandomI32Range, //se(v)
+    pub slice_skip_flag: RandomBoolRange,
+    pub num_mbs_in_slice_minus1: RandomU32Range, //ue(v)
+    pub adaptive_base_mode_flag: RandomBoolRange,
+    pub default_base_mode_flag: RandomBoolRange,
+    pub adaptive_motion_prediction_flag: RandomBoolRange,
+    pub default_motion_prediction_flag: RandomBoolRange,
+    pub adaptive_residual_prediction_flag: RandomBoolRange,
+    pub default_residual_prediction_flag: RandomBoolRange,
+    pub tcoeff_level_prediction_flag: RandomBoolRange,
+    pub scan_idx_start: RandomU32Range, //u(4)
+    pub scan_idx_end: RandomU32Range,   //u(4)
+    // macroblock_layer_in_scalable_extension
+    pub base_mode_flag: RandomBoolRange,
+    pub motion_prediction_flag_l0: RandomBoolRange,
+    pub motion_prediction_flag_l1: RandomBoolRange,
+    pub residual_prediction_flag: RandomBoolRange,
+}
+
+impl RandomSliceSVCExtensionRange {
+    pub fn new() -> RandomSliceSVCExtensionRange {
+        RandomSliceSVCExtensionRange {
+            base_pred_weight_table_flag: RandomBoolRange::new(0, 1, 1),
+            store_ref_base_pic_flag: RandomBoolRange::new(0, 1, 1),
+            adaptive_ref_base_pic_marking_mode_flag: RandomBoolRange::new(0, 1, 1),
+            num_modifications: RandomU32Range::new(1, 10), // 0 modifications leads to underflow
+            memory_management_base_control_operation: RandomU32Range::new(1, 2), // 0 is the stop condition, so we add that in manually later
+            difference_of_base_pic_nums_minus1: RandomU32Range::new(0, 100),
+            long_term_base_pic_num: RandomU32Range::new(0, 100),
+            ref_layer_dq_id: RandomU32Range::new(0, 127), // (dependency_id << 4) + quality_id
+            disable_inter_layer_deblocking_filter_idc: RandomU32Range::new(0, 6),
+            inter_layer_slice_alpha_c0_offset_div2: RandomI32Range::new(-10, 10), // [-6, 6]
+            inter_layer_slice_beta_offset_div2: RandomI32Range::new(-10, 10),     // [-6, 6]
+            constrained_intra_resampling_flag: RandomBoolRange::new(0, 1, 1),
+            ref_layer_chroma_phase_x_plus1_flag: RandomBoolRange::new(0, 1, 1),
+            ref_layer_chroma_phase_y_plus1: RandomU32Range::new(0, 2), // [0, 2]
+            scaled_ref_layer_left_offset: RandomI32Range::new(-100, 100),
+            scaled_ref_layer_top_offset: RandomI32Range::new(-100, 100),
+            scaled_ref_layer_right_offset: RandomI32Range::new(-100, 100),
+            scaled_ref_layer_bottom_offset: RandomI32Range::new(-100, 100),
+            slice_skip_flag: RandomBoolRange::new(0, 9, 9), // 1 out of 10 times, skip the slice data
+            num_mbs_in_slice_minus1: RandomU32Range::new(0, 100),
+            adaptive_base_mode_flag: RandomBoolRange::new(0, 1, 1),
+            default_base_mode_flag: RandomBoolRange::new(0, 1, 1),
+            adaptive_motion_prediction_flag: RandomBoolRange::new(0, 1, 1),
+            default_motion_prediction_flag: RandomBoolRange::new(0, 1, 1),
+            adaptive_residual_prediction_flag: RandomBoolRange::new(0, 1, 1),
+            default_residual_prediction_flag: RandomBoolRange::new(0, 1, 1),
+            tcoeff_level_prediction_flag: RandomBoolRange::new(0, 1, 1),
+            scan_idx_start: RandomU32Range::new(0, 0), // [0, 15] -- generated residuals assume the full scan range
+            scan_idx_end: RandomU32Range::new(15, 15), // [0, 15] -- generated residuals assume the full scan range
+            base_mode_flag: RandomBoolRange::new(0, 1, 1),
+            motion_prediction_flag_l0: RandomBoolRange::new(0, 1, 1),
+            motion_prediction_flag_l1: RandomBoolRange::new(0, 1, 1),
+            residual_prediction_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomSliceSVCExtensionRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Slice header syntax elements of the 3D-AVC extension (Annex J)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSlice3DAVCExtensionRange {
+    pub pre_slice_header_src: RandomU32Range,        //u(2)
+    pub pre_ref_lists_src: RandomU32Range,           //u(2)
+    pub pre_pred_weight_table_src: RandomU32Range,   //u(2)
+    pub pre_dec_ref_pic_marking_src: RandomU32Range, //u(2)
+    pub depth_weighted_pred_flag: RandomBoolRange,
+    pub dmvp_flag: RandomBoolRange,
+    pub slice_vsp_flag: RandomBoolRange,
+    pub dps_id: RandomU32Range, //ue(v)
+}
+
+impl RandomSlice3DAVCExtensionRange {
+    pub fn new() -> RandomSlice3DAVCExtensionRange {
+        RandomSlice3DAVCExtensionRange {
+            pre_slice_header_src: RandomU32Range::new(0, 3),
+            pre_ref_lists_src: RandomU32Range::new(0, 3),
+            pre_pred_weight_table_src: RandomU32Range::new(0, 3),
+            pre_dec_ref_pic_marking_src: RandomU32Range::new(0, 3),
+            depth_weighted_pred_flag: RandomBoolRange::new(0, 1, 1),
+            dmvp_flag: RandomBoolRange::new(0, 1, 1),
+            slice_vsp_flag: RandomBoolRange::never(), // texture view VSP macroblock syntax is not supported
+            dps_id: RandomU32Range::new(0, 63),
+        }
+    }
+}
+
+impl Default for RandomSlice3DAVCExtensionRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// HDR syntax elements
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct RandomHRDRange {
@@ -754,6 +1040,8 @@ pub struct RandomSPSRange {
     pub random_vui_range: RandomVUIRange,
     // Bias
     pub bias_same_bit_depth: RandomBoolRange, // if true, use the same bit depth
+    #[serde(default = "RandomBoolRange::never")]
+    pub bias_level_limits: RandomBoolRange, // if true, keep the frame size and VUI within the Table A-1 limits
 }
 
 impl RandomSPSRange {
@@ -761,7 +1049,7 @@ impl RandomSPSRange {
         RandomSPSRange {
             profile_idc: RandomU32Enum::new(vec![77, 66, 100]), // Main, Baseline, and High profiles
             profile_idc_extension: RandomU32Enum::new(vec![
-                77, 66, 100, 88, 118, 128, 44, 110, 122, 144, 244,
+                77, 66, 100, 88, 118, 128, 44, 110, 122, 144, 244, 135, 138, 139,
             ]), // 88 and onwards is enabled for extensions
             constraint_set0_flag: RandomBoolRange::new(0, 1, 1),
             constraint_set1_flag: RandomBoolRange::new(0, 1, 1),
@@ -805,6 +1093,7 @@ impl RandomSPSRange {
             vui_parameters_present_flag: RandomBoolRange::new(0, 1, 1),
             random_vui_range: RandomVUIRange::new(),
             bias_same_bit_depth: RandomBoolRange::new(0, 50, 1), // Most of the time use the same bit depth
+            bias_level_limits: RandomBoolRange::never(),
         }
     }
 }
@@ -815,6 +1104,36 @@ impl Default for RandomSPSRange {
     }
 }
 
+/// SPS Extension syntax elements
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSPSExtensionRange {
+    pub aux_format_idc: RandomU32Range,             // ue(v)
+    pub bit_depth_aux_minus8: RandomU32Range,       // ue(v)
+    pub alpha_incr_flag: RandomBoolRange,           // u(1)
+    pub alpha_opaque_value: RandomU32Range,         // u(v)
+    pub alpha_transparent_value: RandomU32Range,    // u(v)
+    pub additional_extension_flag: RandomBoolRange, // u(1)
+}
+
+impl RandomSPSExtensionRange {
+    pub fn new() -> RandomSPSExtensionRange {
+        RandomSPSExtensionRange {
+            aux_format_idc: RandomU32Range::new(0, 3), // 0 means no auxiliary coded pictures
+            bit_depth_aux_minus8: RandomU32Range::new(0, 4),
+            alpha_incr_flag: RandomBoolRange::new(0, 1, 1),
+            alpha_opaque_value: RandomU32Range::new(0, 8191), // bit_depth_aux_minus8 + 9 bits
+            alpha_transparent_value: RandomU32Range::new(0, 8191), // bit_depth_aux_minus8 + 9 bits
+            additional_extension_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomSPSExtensionRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Subset SPS syntax elements
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RandomSubsetSPSRange {
@@ -1005,11 +1324,66 @@ impl Default for RandomVUIMVCParametersRange {
 
 /// SPS MVCD Extension syntax elements
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
-pub struct RandomSPSMVCDExtensionRange {}
+#[serde(default)]
+pub struct RandomSPSMVCDExtensionRange {
+    pub num_views_minus1: RandomU32Range,
+    pub view_id: RandomU32Range,
+    pub depth_view_present_flag: RandomBoolRange,
+    pub texture_view_present_flag: RandomBoolRange,
+    pub num_anchor_refs_l0: RandomU32Range,
+    pub anchor_ref_l0: RandomU32Range,
+    pub num_anchor_refs_l1: RandomU32Range,
+    pub anchor_ref_l1: RandomU32Range,
+    pub num_non_anchor_refs_l0: RandomU32Range,
+    pub non_anchor_ref_l0: RandomU32Range,
+    pub num_non_anchor_refs_l1: RandomU32Range,
+    pub non_anchor_ref_l1: RandomU32Range,
+    pub num_level_values_signalled_minus1: RandomU32Range,
+    pub level_idc: RandomU32Range,
+    pub num_applicable_ops_minus1: RandomU32Range,
+    pub applicable_op_temporal_id: RandomU32Range,
+    pub applicable_op_num_target_views_minus1: RandomU32Range,
+    pub applicable_op_target_view_id: RandomU32Range,
+    pub applicable_op_depth_flag: RandomBoolRange,
+    pub applicable_op_texture_flag: RandomBoolRange,
+    pub applicable_op_num_texture_views_minus1: RandomU32Range,
+    pub applicable_op_num_depth_views: RandomU32Range,
+    pub mvcd_vui_parameters_present_flag: RandomBoolRange,
+    pub random_mvcd_vui_range: RandomVUIMVCDParametersRange,
+    pub texture_vui_parameters_present_flag: RandomBoolRange,
+    pub random_texture_vui_range: RandomVUIMVCParametersRange,
+}
 
 impl RandomSPSMVCDExtensionRange {
     pub fn new() -> RandomSPSMVCDExtensionRange {
-        RandomSPSMVCDExtensionRange {}
+        RandomSPSMVCDExtensionRange {
+            num_views_minus1: RandomU32Range::new(0, 15), //[0, 1023]
+            view_id: RandomU32Range::new(0, 1023),
+            depth_view_present_flag: RandomBoolRange::new(0, 1, 1),
+            texture_view_present_flag: RandomBoolRange::new(0, 1, 1),
+            num_anchor_refs_l0: RandomU32Range::new(0, 15),
+            anchor_ref_l0: RandomU32Range::new(0, 1023),
+            num_anchor_refs_l1: RandomU32Range::new(0, 15),
+            anchor_ref_l1: RandomU32Range::new(0, 1023),
+            num_non_anchor_refs_l0: RandomU32Range::new(0, 15),
+            non_anchor_ref_l0: RandomU32Range::new(0, 1023),
+            num_non_anchor_refs_l1: RandomU32Range::new(0, 15),
+            non_anchor_ref_l1: RandomU32Range::new(0, 1023),
+            num_level_values_signalled_minus1: RandomU32Range::new(0, 7), //[0, 63]
+            level_idc: RandomU32Range::new(0, 255),
+            num_applicable_ops_minus1: RandomU32Range::new(0, 7), //[0, 1023]
+            applicable_op_temporal_id: RandomU32Range::new(0, 7), // u(3)
+            applicable_op_num_target_views_minus1: RandomU32Range::new(0, 15),
+            applicable_op_target_view_id: RandomU32Range::new(0, 1023),
+            applicable_op_depth_flag: RandomBoolRange::new(0, 1, 1),
+            applicable_op_texture_flag: RandomBoolRange::new(0, 1, 1),
+            applicable_op_num_texture_views_minus1: RandomU32Range::new(0, 15),
+            applicable_op_num_depth_views: RandomU32Range::new(0, 15),
+            mvcd_vui_parameters_present_flag: RandomBoolRange::new(0, 1, 1),
+            random_mvcd_vui_range: RandomVUIMVCDParametersRange::new(),
+            texture_vui_parameters_present_flag: RandomBoolRange::new(0, 0, 2), // the MVC VUI ranges are large
+            random_texture_vui_range: RandomVUIMVCParametersRange::new(),
+        }
     }
 }
 
@@ -1019,13 +1393,131 @@ impl Default for RandomSPSMVCDExtensionRange {
     }
 }
 
+/// VUI MVCD Extension syntax elements
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomVUIMVCDParametersRange {
+    pub vui_mvcd_num_ops_minus1: RandomU32Range,
+    pub vui_mvcd_temporal_id: RandomU32Range, // u(3)
+    pub vui_mvcd_num_target_output_views_minus1: RandomU32Range,
+    pub vui_mvcd_view_id: RandomU32Range,
+    pub vui_mvcd_depth_flag: RandomBoolRange,
+    pub vui_mvcd_texture_flag: RandomBoolRange,
+    pub vui_mvcd_timing_info_present_flag: RandomBoolRange,
+    pub vui_mvcd_num_units_in_tick: RandomU32Range, // u(32)
+    pub vui_mvcd_time_scale: RandomU32Range,        // u(32)
+    pub vui_mvcd_fixed_frame_rate_flag: RandomBoolRange,
+    pub vui_mvcd_nal_hrd_parameters_present_flag: RandomBoolRange,
+    pub vui_mvcd_nal_hrd_parameters: RandomHRDRange,
+    pub vui_mvcd_vcl_hrd_parameters_present_flag: RandomBoolRange,
+    pub vui_mvcd_vcl_hrd_parameters: RandomHRDRange,
+    pub vui_mvcd_low_delay_hrd_flag: RandomBoolRange,
+    pub vui_mvcd_pic_struct_present_flag: RandomBoolRange,
+}
+
+impl RandomVUIMVCDParametersRange {
+    pub fn new() -> RandomVUIMVCDParametersRange {
+        RandomVUIMVCDParametersRange {
+            vui_mvcd_num_ops_minus1: RandomU32Range::new(0, 15),
+            vui_mvcd_temporal_id: RandomU32Range::new(0, 7), // u(3)
+            vui_mvcd_num_target_output_views_minus1: RandomU32Range::new(0, 15),
+            vui_mvcd_view_id: RandomU32Range::new(0, 1023),
+            vui_mvcd_depth_flag: RandomBoolRange::new(0, 1, 1),
+            vui_mvcd_texture_flag: RandomBoolRange::new(0, 1, 1),
+            vui_mvcd_timing_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            vui_mvcd_num_units_in_tick: RandomU32Range::new(0, u32::MAX), // u(32)
+            vui_mvcd_time_scale: RandomU32Range::new(0, u32::MAX),        // u(32)
+            vui_mvcd_fixed_frame_rate_flag: RandomBoolRange::new(0, 1, 1),
+            vui_mvcd_nal_hrd_parameters_present_flag: RandomBoolRange::new(0, 1, 1),
+            vui_mvcd_nal_hrd_parameters: RandomHRDRange::new(),
+            vui_mvcd_vcl_hrd_parameters_present_flag: RandomBoolRange::new(0, 1, 1),
+            vui_mvcd_vcl_hrd_parameters: RandomHRDRange::new(),
+            vui_mvcd_low_delay_hrd_flag: RandomBoolRange::new(0, 1, 1),
+            vui_mvcd_pic_struct_present_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomVUIMVCDParametersRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// SPS 3D-AVC Extension syntax elements
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
-pub struct RandomSPS3DAVCExtensionRange {}
+#[serde(default)]
+pub struct RandomSPS3DAVCExtensionRange {
+    pub three_dv_acquisition_idc: RandomU32Range,
+    pub view_id_3dv: RandomU32Range,
+    pub random_depth_ranges_range: RandomDepthRangesRange,
+    pub random_vsp_param_range: RandomVSPParamRange,
+    pub reduced_resolution_flag: RandomBoolRange,
+    pub depth_pic_width_in_mbs_minus1: RandomU32Range,
+    pub depth_pic_height_in_map_units_minus1: RandomU32Range,
+    pub depth_hor_mult_minus1: RandomU32Range,
+    pub depth_ver_mult_minus1: RandomU32Range,
+    pub depth_hor_rsh: RandomU32Range,
+    pub depth_ver_rsh: RandomU32Range,
+    pub depth_frame_cropping_flag: RandomBoolRange,
+    pub depth_frame_crop_left_offset: RandomU32Range,
+    pub depth_frame_crop_right_offset: RandomU32Range,
+    pub depth_frame_crop_top_offset: RandomU32Range,
+    pub depth_frame_crop_bottom_offset: RandomU32Range,
+    pub grid_pos_num_views: RandomU32Range,
+    pub grid_pos_view_id: RandomU32Range,
+    pub grid_pos_x: RandomI32Range,
+    pub grid_pos_y: RandomI32Range,
+    pub slice_header_prediction_flag: RandomBoolRange,
+    pub seq_view_synthesis_flag: RandomBoolRange,
+    pub alc_sps_enable_flag: RandomBoolRange,
+    pub enable_rle_skip_flag: RandomBoolRange,
+    pub num_anchor_refs_l0: RandomU32Range,
+    pub anchor_ref_l0: RandomU32Range,
+    pub num_anchor_refs_l1: RandomU32Range,
+    pub anchor_ref_l1: RandomU32Range,
+    pub num_non_anchor_refs_l0: RandomU32Range,
+    pub non_anchor_ref_l0: RandomU32Range,
+    pub num_non_anchor_refs_l1: RandomU32Range,
+    pub non_anchor_ref_l1: RandomU32Range,
+}
 
 impl RandomSPS3DAVCExtensionRange {
     pub fn new() -> RandomSPS3DAVCExtensionRange {
-        RandomSPS3DAVCExtensionRange {}
+        RandomSPS3DAVCExtensionRange {
+            three_dv_acquisition_idc: RandomU32Range::new(0, 2), //[0, 2]
+            view_id_3dv: RandomU32Range::new(0, 1023),
+            random_depth_ranges_range: RandomDepthRangesRange::new(),
+            random_vsp_param_range: RandomVSPParamRange::new(),
+            reduced_resolution_flag: RandomBoolRange::new(0, 1, 1),
+            depth_pic_width_in_mbs_minus1: RandomU32Range::new(0, 255),
+            depth_pic_height_in_map_units_minus1: RandomU32Range::new(0, 255),
+            depth_hor_mult_minus1: RandomU32Range::new(0, 15),
+            depth_ver_mult_minus1: RandomU32Range::new(0, 15),
+            depth_hor_rsh: RandomU32Range::new(0, 15),
+            depth_ver_rsh: RandomU32Range::new(0, 15),
+            depth_frame_cropping_flag: RandomBoolRange::new(0, 1, 1),
+            depth_frame_crop_left_offset: RandomU32Range::new(0, 255),
+            depth_frame_crop_right_offset: RandomU32Range::new(0, 255),
+            depth_frame_crop_top_offset: RandomU32Range::new(0, 255),
+            depth_frame_crop_bottom_offset: RandomU32Range::new(0, 255),
+            grid_pos_num_views: RandomU32Range::new(0, 15), //[0, 1024]
+            grid_pos_view_id: RandomU32Range::new(0, 1023),
+            grid_pos_x: RandomI32Range::new(-1000, 1000),
+            grid_pos_y: RandomI32Range::new(-1000, 1000),
+            slice_header_prediction_flag: RandomBoolRange::new(0, 1, 1),
+            seq_view_synthesis_flag: RandomBoolRange::new(0, 1, 1),
+            // texture view ALC and RLE skip macroblock syntax is not supported
+            alc_sps_enable_flag: RandomBoolRange::never(),
+            enable_rle_skip_flag: RandomBoolRange::never(),
+            num_anchor_refs_l0: RandomU32Range::new(0, 15),
+            anchor_ref_l0: RandomU32Range::new(0, 1023),
+            num_anchor_refs_l1: RandomU32Range::new(0, 15),
+            anchor_ref_l1: RandomU32Range::new(0, 1023),
+            num_non_anchor_refs_l0: RandomU32Range::new(0, 15),
+            non_anchor_ref_l0: RandomU32Range::new(0, 1023),
+            num_non_anchor_refs_l1: RandomU32Range::new(0, 15),
+            non_anchor_ref_l1: RandomU32Range::new(0, 1023),
+        }
     }
 }
 
@@ -1035,29 +1527,322 @@ impl Default for RandomSPS3DAVCExtensionRange {
     }
 }
 
+/// Depth ranges and 3DV acquisition element syntax elements
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomDepthRangesRange {
+    pub z_near_flag: RandomBoolRange,
+    pub z_far_flag: RandomBoolRange,
+    // 3dv_acquisition_element
+    pub element_equal_flag: RandomBoolRange,
+    pub mantissa_len_minus1: RandomU32Range, // u(5)
+    pub sign: RandomBoolRange,
+    pub exponent: RandomU32Range, // u(7)
+    pub mantissa: RandomU32Range, // u(v)
+    pub skip_flag: RandomBoolRange,
+    pub exponent_skip_flag: RandomBoolRange,
+    pub mantissa_diff: RandomI32Range, // se(v)
+}
+
+impl RandomDepthRangesRange {
+    pub fn new() -> RandomDepthRangesRange {
+        RandomDepthRangesRange {
+            z_near_flag: RandomBoolRange::new(0, 1, 1),
+            z_far_flag: RandomBoolRange::new(0, 1, 1),
+            element_equal_flag: RandomBoolRange::new(0, 1, 1),
+            mantissa_len_minus1: RandomU32Range::new(0, 31), // u(5)
+            sign: RandomBoolRange::new(0, 1, 1),
+            exponent: RandomU32Range::new(0, 127),      // u(7)
+            mantissa: RandomU32Range::new(0, u32::MAX), // bounded by mantissa_len_minus1
+            skip_flag: RandomBoolRange::new(0, 1, 1),
+            exponent_skip_flag: RandomBoolRange::new(0, 1, 1),
+            mantissa_diff: RandomI32Range::new(-1000, 1000),
+        }
+    }
+}
+
+impl Default for RandomDepthRangesRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// View synthesis prediction parameter syntax elements
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomVSPParamRange {
+    pub disparity_diff_wji: RandomU32Range,
+    pub disparity_diff_oji: RandomU32Range,
+    pub disparity_diff_wij: RandomU32Range,
+    pub disparity_diff_oij: RandomU32Range,
+}
+
+impl RandomVSPParamRange {
+    pub fn new() -> RandomVSPParamRange {
+        RandomVSPParamRange {
+            disparity_diff_wji: RandomU32Range::new(0, 1023),
+            disparity_diff_oji: RandomU32Range::new(0, 1023),
+            disparity_diff_wij: RandomU32Range::new(0, 1023),
+            disparity_diff_oij: RandomU32Range::new(0, 1023),
+        }
+    }
+}
+
+impl Default for RandomVSPParamRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Depth parameter set syntax elements
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomDepthParameterSetRange {
+    pub depth_parameter_set_id: RandomU32Range,
+    pub pred_direction: RandomU32Range,
+    pub ref_dps_id0: RandomU32Range,
+    pub ref_dps_id1: RandomU32Range,
+    pub pred_weight0: RandomU32Range, // u(6)
+    pub num_depth_views_minus1: RandomU32Range,
+    pub random_depth_ranges_range: RandomDepthRangesRange,
+    pub vsp_param_flag: RandomBoolRange,
+    pub random_vsp_param_range: RandomVSPParamRange,
+    pub depth_param_additional_extension_flag: RandomBoolRange,
+    pub nonlinear_depth_representation_num: RandomU32Range,
+    pub nonlinear_depth_representation_model: RandomU32Range,
+    pub num_depth_param_additional_extension_data_flag: RandomU32Range,
+    pub depth_param_additional_extension_data_flag: RandomBoolRange,
+}
+
+impl RandomDepthParameterSetRange {
+    pub fn new() -> RandomDepthParameterSetRange {
+        RandomDepthParameterSetRange {
+            depth_parameter_set_id: RandomU32Range::new(1, 63), //[1, 63]
+            pred_direction: RandomU32Range::new(0, 2),          //[0, 2]
+            ref_dps_id0: RandomU32Range::new(1, 63),
+            ref_dps_id1: RandomU32Range::new(1, 63),
+            pred_weight0: RandomU32Range::new(0, 63), // u(6)
+            num_depth_views_minus1: RandomU32Range::new(0, 15),
+            random_depth_ranges_range: RandomDepthRangesRange::new(),
+            vsp_param_flag: RandomBoolRange::new(0, 1, 1),
+            random_vsp_param_range: RandomVSPParamRange::new(),
+            depth_param_additional_extension_flag: RandomBoolRange::new(0, 0, 2),
+            nonlinear_depth_representation_num: RandomU32Range::new(0, 15),
+            nonlinear_depth_representation_model: RandomU32Range::new(0, 1023),
+            num_depth_param_additional_extension_data_flag: RandomU32Range::new(0, 16),
+            depth_param_additional_extension_data_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomDepthParameterSetRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// SEI syntax elements
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RandomSEIRange {
     pub num_seis: RandomU32Range,
     pub payload_type: RandomU32Enum,
+    #[serde(default)]
+    pub random_payload_size_range: RandomSEIPayloadSizeRange,
     pub random_buffering_period_range: RandomSEIBufferingPeriodRange, // Type 0
     pub random_pic_timing_range: RandomSEIPicTimingRange,             // Type 1
+    #[serde(default)]
+    pub random_pan_scan_rect_range: RandomSEIPanScanRectRange, // Type 2
+    #[serde(default)]
+    pub random_filler_payload_range: RandomSEIFillerPayloadRange, // Type 3
+    #[serde(default)]
+    pub random_user_data_registered_itu_t_t35_range: RandomSEIUserDataRegisteredITUTT35Range, // Type 4
     pub random_user_data_unregistered_range: RandomSEIUserDataUnregisteredRange, // Type 5
-    pub random_recovery_point_range: RandomSEIRecoveryPointRange,     // Type 6
+    pub random_recovery_point_range: RandomSEIRecoveryPointRange,                // Type 6
+    #[serde(default)]
+    pub random_dec_ref_pic_marking_repetition_range: RandomSEIDecRefPicMarkingRepetitionRange, // Type 7
+    #[serde(default)]
+    pub random_spare_pic_range: RandomSEISparePicRange, // Type 8
+    #[serde(default)]
+    pub random_scene_info_range: RandomSEISceneInfoRange, // Type 9
+    #[serde(default)]
+    pub random_sub_seq_info_range: RandomSEISubSeqInfoRange, // Type 10
+    #[serde(default)]
+    pub random_sub_seq_layer_char_range: RandomSEISubSeqLayerCharacteristicsRange, // Type 11
+    #[serde(default)]
+    pub random_sub_seq_char_range: RandomSEISubSeqCharacteristicsRange, // Type 12
+    #[serde(default)]
+    pub random_frame_freeze_refinement_range: RandomSEIFrameFreezeRefinementRange, // Types 13 to 17
     pub random_film_grain_char_range: RandomSEIFilmGrainCharacteristicsRange, // Type 19
+    #[serde(default)]
+    pub random_tone_mapping_info_range: RandomSEIToneMappingInfoRange, // Type 23
+    #[serde(default)]
+    pub random_scalability_info_range: RandomSEIScalabilityInfoRange, // Type 24
+    #[serde(default)]
+    pub random_sub_pic_scalable_layer_range: RandomSEISubPicScalableLayerRange, // Type 25
+    #[serde(default)]
+    pub random_non_required_layer_rep_range: RandomSEINonRequiredLayerRepRange, // Type 26
+    #[serde(default)]
+    pub random_priority_layer_info_range: RandomSEIPriorityLayerInfoRange, // Type 27
+    #[serde(default)]
+    pub random_layers_not_present_range: RandomSEILayersNotPresentRange, // Type 28
+    #[serde(default)]
+    pub random_layer_dependency_change_range: RandomSEILayerDependencyChangeRange, // Type 29
+    #[serde(default)]
+    pub random_scalable_nesting_range: RandomSEIScalableNestingRange, // Type 30
+    #[serde(default)]
+    pub random_base_layer_temporal_hrd_range: RandomSEITemporalHRDRange, // Type 31
+    #[serde(default)]
+    pub random_quality_layer_integrity_check_range: RandomSEIQualityLayerIntegrityCheckRange, // Type 32
+    #[serde(default)]
+    pub random_redundant_pic_property_range: RandomSEIRedundantPicPropertyRange, // Type 33
+    #[serde(default)]
+    pub random_tl0_dep_rep_index_range: RandomSEITl0DepRepIndexRange, // Type 34
+    #[serde(default)]
+    pub random_tl_switching_point_range: RandomSEITlSwitchingPointRange, // Type 35
+    #[serde(default)]
+    pub random_parallel_decoding_info_range: RandomSEIParallelDecodingInfoRange, // Type 36
+    #[serde(default)]
+    pub random_mvc_scalable_nesting_range: RandomSEIMVCScalableNestingRange, // Type 37
+    #[serde(default)]
+    pub random_view_scalability_info_range: RandomSEIViewScalabilityInfoRange, // Type 38
+    #[serde(default)]
+    pub random_multiview_scene_info_range: RandomSEIMultiviewSceneInfoRange, // Type 39
+    #[serde(default)]
+    pub random_multiview_acquisition_info_range: RandomSEIMultiviewAcquisitionInfoRange, // Type 40
+    #[serde(default)]
+    pub random_non_required_view_component_range: RandomSEINonRequiredViewComponentRange, // Type 41
+    #[serde(default)]
+    pub random_view_dependency_change_range: RandomSEIViewDependencyChangeRange, // Type 42
+    #[serde(default)]
+    pub random_operation_points_not_present_range: RandomSEIOperationPointsNotPresentRange, // Type 43
+    #[serde(default)]
+    pub random_base_view_temporal_hrd_range: RandomSEITemporalHRDRange, // Type 44
+    #[serde(default)]
+    pub random_frame_packing_range: RandomSEIFramePackingRange, // Type 45
+    #[serde(default)]
+    pub random_multiview_view_position_range: RandomSEIMultiviewViewPositionRange, // Type 46
+    #[serde(default)]
+    pub random_display_orientation_range: RandomSEIDisplayOrientationRange, // Type 47
+    #[serde(default)]
+    pub random_mvcd_scalable_nesting_range: RandomSEIMVCScalableNestingRange, // Type 48
+    #[serde(default)]
+    pub random_mvcd_view_scalability_info_range: RandomSEIViewScalabilityInfoRange, // Type 49
+    #[serde(default)]
+    pub random_depth_representation_info_range: RandomSEIDepthRepresentationInfoRange, // Type 50
+    #[serde(default)]
+    pub random_three_dimensional_reference_displays_info_range:
+        RandomSEIThreeDimensionalReferenceDisplaysInfoRange, // Type 51
+    #[serde(default)]
+    pub random_depth_timing_range: RandomSEIDepthTimingRange, // Type 52
+    #[serde(default)]
+    pub random_depth_sampling_info_range: RandomSEIDepthSamplingInfoRange, // Type 53
+    #[serde(default)]
+    pub random_constrained_depth_parameter_set_identifier_range:
+        RandomSEIConstrainedDepthParameterSetIdentifierRange, // Type 54
+    #[serde(default)]
+    pub random_mastering_display_colour_volume_range: RandomSEIMasteringDisplayColourVolumeRange, // Type 137
+    #[serde(default)]
+    pub random_colour_remapping_info_range: RandomSEIColourRemappingInfoRange, // Type 142
+    #[serde(default)]
+    pub random_content_light_level_info_range: RandomSEIContentLightLevelInfoRange, // Type 144
+    #[serde(default)]
+    pub random_alternative_transfer_characteristics_range:
+        RandomSEIAlternativeTransferCharacteristicsRange, // Type 147
+    #[serde(default)]
+    pub random_content_colour_volume_range: RandomSEIContentColourVolumeRange, // Type 149
+    #[serde(default)]
+    pub random_equirectangular_projection_range: RandomSEIEquirectangularProjectionRange, // Type 150
+    #[serde(default)]
+    pub random_cubemap_projection_range: RandomSEICubemapProjectionRange, // Type 151
+    #[serde(default)]
+    pub random_sphere_rotation_range: RandomSEISphereRotationRange, // Type 154
+    #[serde(default)]
+    pub random_regionwise_packing_range: RandomSEIRegionwisePackingRange, // Type 155
+    #[serde(default)]
+    pub random_omni_viewport_range: RandomSEIOmniViewportRange, // Type 156
+    #[serde(default)]
+    pub random_alternative_depth_info_range: RandomSEIAlternativeDepthInfoRange, // Type 181
 }
 
 impl RandomSEIRange {
     pub fn new() -> RandomSEIRange {
         RandomSEIRange {
             num_seis: RandomU32Range::new(1, 1),
-            // Buffering period, pic timing, unregistered user data, recovery point
-            payload_type: RandomU32Enum::new(vec![0, 1, 5, 6]),
+            // Buffering period, pic timing, pan-scan rect, filler payload, registered and
+            // unregistered user data, recovery point, the Annex D.2.9 to D.2.19 messages, tone
+            // mapping, the SVC, MVC and MVCD messages, frame packing, display orientation, the HDR
+            // and colour metadata, and the omnidirectional video messages. Parallel decoding
+            // information (36) and view dependency change (42) refer to a subset SPS, so they are
+            // left out by default
+            payload_type: RandomU32Enum::new(vec![
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 23, 24, 25, 26, 27,
+                28, 29, 30, 31, 32, 33, 34, 35, 37, 38, 39, 40, 41, 43, 44, 45, 46, 47, 48, 49, 50,
+                51, 52, 53, 54, 137, 142, 144, 147, 149, 150, 151, 154, 155, 156, 181,
+            ]),
+            random_payload_size_range: RandomSEIPayloadSizeRange::new(),
             random_buffering_period_range: RandomSEIBufferingPeriodRange::new(),
             random_pic_timing_range: RandomSEIPicTimingRange::new(),
+            random_pan_scan_rect_range: RandomSEIPanScanRectRange::new(),
+            random_filler_payload_range: RandomSEIFillerPayloadRange::new(),
+            random_user_data_registered_itu_t_t35_range:
+                RandomSEIUserDataRegisteredITUTT35Range::new(),
             random_user_data_unregistered_range: RandomSEIUserDataUnregisteredRange::new(),
             random_recovery_point_range: RandomSEIRecoveryPointRange::new(),
+            random_dec_ref_pic_marking_repetition_range:
+                RandomSEIDecRefPicMarkingRepetitionRange::new(),
+            random_spare_pic_range: RandomSEISparePicRange::new(),
+            random_scene_info_range: RandomSEISceneInfoRange::new(),
+            random_sub_seq_info_range: RandomSEISubSeqInfoRange::new(),
+            random_sub_seq_layer_char_range: RandomSEISubSeqLayerCharacteristicsRange::new(),
+            random_sub_seq_char_range: RandomSEISubSeqCharacteristicsRange::new(),
+            random_frame_freeze_refinement_range: RandomSEIFrameFreezeRefinementRange::new(),
             random_film_grain_char_range: RandomSEIFilmGrainCharacteristicsRange::new(),
+            random_tone_mapping_info_range: RandomSEIToneMappingInfoRange::new(),
+            random_scalability_info_range: RandomSEIScalabilityInfoRange::new(),
+            random_sub_pic_scalable_layer_range: RandomSEISubPicScalableLayerRange::new(),
+            random_non_required_layer_rep_range: RandomSEINonRequiredLayerRepRange::new(),
+            random_priority_layer_info_range: RandomSEIPriorityLayerInfoRange::new(),
+            random_layers_not_present_range: RandomSEILayersNotPresentRange::new(),
+            random_layer_dependency_change_range: RandomSEILayerDependencyChangeRange::new(),
+            random_scalable_nesting_range: RandomSEIScalableNestingRange::new(),
+            random_base_layer_temporal_hrd_range: RandomSEITemporalHRDRange::new(),
+            random_quality_layer_integrity_check_range:
+                RandomSEIQualityLayerIntegrityCheckRange::new(),
+            random_redundant_pic_property_range: RandomSEIRedundantPicPropertyRange::new(),
+            random_tl0_dep_rep_index_range: RandomSEITl0DepRepIndexRange::new(),
+            random_tl_switching_point_range: RandomSEITlSwitchingPointRange::new(),
+            random_parallel_decoding_info_range: RandomSEIParallelDecodingInfoRange::new(),
+            random_mvc_scalable_nesting_range: RandomSEIMVCScalableNestingRange::new(),
+            random_view_scalability_info_range: RandomSEIViewScalabilityInfoRange::new(),
+            random_multiview_scene_info_range: RandomSEIMultiviewSceneInfoRange::new(),
+            random_multiview_acquisition_info_range: RandomSEIMultiviewAcquisitionInfoRange::new(),
+            random_non_required_view_component_range: RandomSEINonRequiredViewComponentRange::new(),
+            random_view_dependency_change_range: RandomSEIViewDependencyChangeRange::new(),
+            random_operation_points_not_present_range: RandomSEIOperationPointsNotPresentRange::new(
+            ),
+            random_base_view_temporal_hrd_range: RandomSEITemporalHRDRange::new(),
+            random_frame_packing_range: RandomSEIFramePackingRange::new(),
+            random_multiview_view_position_range: RandomSEIMultiviewViewPositionRange::new(),
+            random_display_orientation_range: RandomSEIDisplayOrientationRange::new(),
+            random_mvcd_scalable_nesting_range: RandomSEIMVCScalableNestingRange::new(),
+            random_mvcd_view_scalability_info_range: RandomSEIViewScalabilityInfoRange::new(),
+            random_depth_representation_info_range: RandomSEIDepthRepresentationInfoRange::new(),
+            random_three_dimensional_reference_displays_info_range:
+                RandomSEIThreeDimensionalReferenceDisplaysInfoRange::new(),
+            random_depth_timing_range: RandomSEIDepthTimingRange::new(),
+            random_depth_sampling_info_range: RandomSEIDepthSamplingInfoRange::new(),
+            random_constrained_depth_parameter_set_identifier_range:
+                RandomSEIConstrainedDepthParameterSetIdentifierRange::new(),
+            random_mastering_display_colour_volume_range:
+                RandomSEIMasteringDisplayColourVolumeRange::new(),
+            random_colour_remapping_info_range: RandomSEIColourRemappingInfoRange::new(),
+            random_content_light_level_info_range: RandomSEIContentLightLevelInfoRange::new(),
+            random_alternative_transfer_characteristics_range:
+                RandomSEIAlternativeTransferCharacteristicsRange::new(),
+            random_content_colour_volume_range: RandomSEIContentColourVolumeRange::new(),
+            random_equirectangular_projection_range: RandomSEIEquirectangularProjectionRange::new(),
+            random_cubemap_projection_range: RandomSEICubemapProjectionRange::new(),
+            random_sphere_rotation_range: RandomSEISphereRotationRange::new(),
+            random_regionwise_packing_range: RandomSEIRegionwisePackingRange::new(),
+            random_omni_viewport_range: RandomSEIOmniViewportRange::new(),
+            random_alternative_depth_info_range: RandomSEIAlternativeDepthInfoRange::new(),
         }
     }
 }
@@ -1068,6 +1853,30 @@ impl Default for RandomSEIRange {
     }
 }
 
+/// payload_size of each sei_message() (Section 7.3.2.3.1)
+///
+/// Mismatches are disabled by default so that generated SEIs decode cleanly
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIPayloadSizeRange {
+    pub payload_size_mismatch: RandomBoolRange, // declare a payload_size that differs from the payload length
+    pub payload_size_delta: RandomI32Range,     // added to the payload length when mismatched
+}
+
+impl RandomSEIPayloadSizeRange {
+    pub fn new() -> RandomSEIPayloadSizeRange {
+        RandomSEIPayloadSizeRange {
+            payload_size_mismatch: RandomBoolRange::new(0, 0, 2),
+            payload_size_delta: RandomI32Range::new(-16, 16),
+        }
+    }
+}
+
+impl Default for RandomSEIPayloadSizeRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// SEI Type 0 -- Buffering Period (Annex D.2.2)
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct RandomSEIBufferingPeriodRange {
@@ -1140,6 +1949,111 @@ impl RandomSEIPicTimingRange {
     }
 }
 
+/// SEI Type 2 -- Pan-scan Rectangle (Annex D.2.4)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIPanScanRectRange {
+    pub pan_scan_rect_id: RandomU32Range, // ue(v)
+    pub pan_scan_rect_cancel_flag: RandomBoolRange,
+    pub pan_scan_cnt_minus1: RandomU32Range, // ue(v) ; [0, 2]
+    pub pan_scan_rect_offset: RandomI32Range, // se(v) ; used for left, right, top and bottom
+    pub pan_scan_rect_repetition_period: RandomU32Range, // ue(v) ; [0, 16384]
+}
+
+impl RandomSEIPanScanRectRange {
+    pub fn new() -> RandomSEIPanScanRectRange {
+        RandomSEIPanScanRectRange {
+            pan_scan_rect_id: RandomU32Range::new(0, 255),
+            pan_scan_rect_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            pan_scan_cnt_minus1: RandomU32Range::new(0, 2),
+            pan_scan_rect_offset: RandomI32Range::new(-4096, 4096),
+            pan_scan_rect_repetition_period: RandomU32Range::new(0, 16384),
+        }
+    }
+}
+
+impl Default for RandomSEIPanScanRectRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 3 -- Filler Payload (Annex D.2.5)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIFillerPayloadRange {
+    pub payload_length: RandomU32Range, // number of 0xFF bytes to write
+}
+
+impl RandomSEIFillerPayloadRange {
+    pub fn new() -> RandomSEIFillerPayloadRange {
+        RandomSEIFillerPayloadRange {
+            payload_length: RandomU32Range::new(1, 100),
+        }
+    }
+}
+
+impl Default for RandomSEIFillerPayloadRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 4 -- User Data Registered by Rec. ITU-T T.35 (Annex D.2.6)
+///
+/// Either ATSC A/53 closed captions or an arbitrary provider payload. The
+/// malformed knobs break individual parts of otherwise valid caption data.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIUserDataRegisteredITUTT35Range {
+    pub atsc_cc_data: RandomBoolRange, // emit ATSC GA94 cc_data() instead of an arbitrary payload
+    pub country_code_extension_flag: RandomBoolRange, // use the 0xFF country code with an extension byte
+    pub itu_t_t35_country_code: RandomU32Range,       // u(8)
+    pub itu_t_t35_country_code_extension_byte: RandomU32Range, // u(8)
+    pub itu_t_t35_provider_code: RandomU32Range,      // u(16)
+    pub itu_t_t35_payload_length: RandomU32Range,     // used to determine how many bytes to sample
+    pub process_em_data_flag: RandomBoolRange,
+    pub process_cc_data_flag: RandomBoolRange,
+    pub additional_data_flag: RandomBoolRange,
+    pub cc_count: RandomU32Range, // u(5)
+    pub em_data: RandomU32Range,  // u(8)
+    pub cc_valid: RandomBoolRange,
+    pub cc_type: RandomU32Range,             // u(2)
+    pub cc_data: RandomU32Range,             // u(8); CEA-608 bytes carry an odd parity bit on top
+    pub cc_count_mismatch: RandomBoolRange,  // cc_count disagrees with the number of triplets
+    pub bad_cc_marker_bits: RandomBoolRange, // a triplet's marker bits are not all ones
+    pub bad_marker_bits: RandomBoolRange,    // the trailing marker_bits are not 0xFF
+    pub bad_cea608_parity: RandomBoolRange,  // CEA-608 bytes have even parity
+}
+
+impl RandomSEIUserDataRegisteredITUTT35Range {
+    pub fn new() -> RandomSEIUserDataRegisteredITUTT35Range {
+        RandomSEIUserDataRegisteredITUTT35Range {
+            atsc_cc_data: RandomBoolRange::new(0, 3, 1),
+            country_code_extension_flag: RandomBoolRange::new(0, 9, 9),
+            itu_t_t35_country_code: RandomU32Range::new(0, 254),
+            itu_t_t35_country_code_extension_byte: RandomU32Range::new(0, 255),
+            itu_t_t35_provider_code: RandomU32Range::new(0, 65535),
+            itu_t_t35_payload_length: RandomU32Range::new(0, 32),
+            process_em_data_flag: RandomBoolRange::new(0, 1, 1),
+            process_cc_data_flag: RandomBoolRange::new(0, 3, 1),
+            additional_data_flag: RandomBoolRange::new(0, 9, 9),
+            cc_count: RandomU32Range::new(0, 31),
+            em_data: RandomU32Range::new(0, 255),
+            cc_valid: RandomBoolRange::new(0, 3, 1),
+            cc_type: RandomU32Range::new(0, 3),
+            cc_data: RandomU32Range::new(0, 255),
+            cc_count_mismatch: RandomBoolRange::new(0, 9, 9),
+            bad_cc_marker_bits: RandomBoolRange::new(0, 9, 9),
+            bad_marker_bits: RandomBoolRange::new(0, 9, 9),
+            bad_cea608_parity: RandomBoolRange::new(0, 9, 9),
+        }
+    }
+}
+
+impl Default for RandomSEIUserDataRegisteredITUTT35Range {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// SEI Type 5 -- Unregistered data with UUID APPLE1
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct RandomSEIUnregisteredDataApple1Range {
@@ -1194,24 +2108,121 @@ impl Default for RandomSEIUnregisteredDataApple2Range {
     }
 }
 
+/// How a field of a user data schema is written to the payload
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub enum UserDataFieldType {
+    U8,     // u(8)
+    U16,    // u(16), big-endian
+    U32,    // u(32), big-endian
+    String, // ASCII followed by a null terminator
+    Ascii,  // ASCII without a terminator
+    Bytes,  // random bytes
+}
+
+/// A field of a user data schema
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserDataFieldSchema {
+    pub name: String,
+    pub field_type: UserDataFieldType,
+    #[serde(default)]
+    pub value: Option<RandomU32Range>, // integer fields; the whole range of the type if unset
+    #[serde(default)]
+    pub values: Vec<String>, // string fields pick one of these, or random ASCII if empty
+    #[serde(default)]
+    pub length: Option<RandomU32Range>, // length of random strings and bytes; [0, 32] if unset
+}
+
+impl UserDataFieldSchema {
+    pub fn new(name: &str, field_type: UserDataFieldType) -> UserDataFieldSchema {
+        UserDataFieldSchema {
+            name: name.to_string(),
+            field_type,
+            value: None,
+            values: Vec::new(),
+            length: None,
+        }
+    }
+}
+
+/// Layout of a user_data_unregistered payload keyed by its UUID. Vendors are
+/// added by appending schemas to the configuration file
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserDataUnregisteredSchema {
+    pub name: String,
+    pub uuid_iso_iec_11578: [u8; 16],
+    pub fields: Vec<UserDataFieldSchema>,
+}
+
+impl UserDataUnregisteredSchema {
+    /// x264 writes its version and encoding options as a null-terminated string
+    pub fn x264() -> UserDataUnregisteredSchema {
+        let mut version = UserDataFieldSchema::new("version", UserDataFieldType::String);
+        version.values = vec![
+            "x264 - core 164 r3095 baf4e36 - H.264/MPEG-4 AVC codec - Copyleft 2003-2022 - http://www.videolan.org/x264.html - options: cabac=1 ref=3 deblock=1:0:0 analyse=0x3:0x113 me=hex subme=7 psy=1 psy_rd=1.00:0.00 mixed_ref=1 me_range=16 chroma_me=1 trellis=1 8x8dct=1 cqm=0 deadzone=21,11 fast_pskip=1 chroma_qp_offset=-2 threads=6 lookahead_threads=1 sliced_threads=0 nr=0 decimate=1 interlaced=0 bluray_compat=0 constrained_intra=0 bframes=3 b_pyramid=2 b_adapt=1 b_bias=0 direct=1 weightb=1 open_gop=0 weightp=2 keyint=250 keyint_min=25 scenecut=40 intra_refresh=0 rc_lookahead=40 rc=crf mbtree=1 crf=23.0 qcomp=0.60 qpmin=0 qpmax=69 qpstep=4 ip_ratio=1.40 aq=1:1.00".to_string(),
+            "x264 - core 148 r2795 aaa9aa8 - H.264/MPEG-4 AVC codec - Copyleft 2003-2017 - http://www.videolan.org/x264.html - options: cabac=0 ref=1 deblock=0:0:0 analyse=0:0 me=dia subme=0 psy=1 psy_rd=1.00:0.00 mixed_ref=0 me_range=16 chroma_me=1 trellis=0 8x8dct=0 cqm=0 deadzone=21,11 fast_pskip=1 chroma_qp_offset=0 threads=1 lookahead_threads=1 sliced_threads=0 nr=0 decimate=1 interlaced=0 bluray_compat=0 constrained_intra=0 bframes=0 weightp=0 keyint=250 keyint_min=25 scenecut=0 intra_refresh=0 rc=crf mbtree=0 crf=23.0 qcomp=0.60 qpmin=0 qpmax=69 qpstep=4 ip_ratio=1.40 aq=0".to_string(),
+        ];
+
+        UserDataUnregisteredSchema {
+            name: "x264".to_string(),
+            uuid_iso_iec_11578: UUID_X264,
+            fields: vec![version],
+        }
+    }
+
+    /// MISB ST 0604 time stamp status followed by a 64-bit microsecond time
+    /// stamp, with 0xFF written after every two bytes to avoid start code emulation
+    pub fn misb_precision_time_stamp() -> UserDataUnregisteredSchema {
+        let mut fields = vec![UserDataFieldSchema::new(
+            "time_stamp_status",
+            UserDataFieldType::U8,
+        )];
+        for i in 0..4 {
+            fields.push(UserDataFieldSchema::new(
+                &format!("time_stamp_{}", i),
+                UserDataFieldType::U16,
+            ));
+            if i < 3 {
+                let mut start_code_emulation_byte =
+                    UserDataFieldSchema::new("start_code_emulation_byte", UserDataFieldType::U8);
+                start_code_emulation_byte.value = Some(RandomU32Range::new(0xff, 0xff));
+                fields.push(start_code_emulation_byte);
+            }
+        }
+
+        UserDataUnregisteredSchema {
+            name: "MISB ST 0604 precision time stamp".to_string(),
+            uuid_iso_iec_11578: UUID_MISB_MICROSECTIME,
+            fields,
+        }
+    }
+}
+
 /// SEI Type 5 -- User Data Unregistered (Annex D.2.7)
 ///
-/// Currently only using known UUID parameters
-#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+/// uuid_iso_iec_11578 indexes the known UUIDs first and then the schemas
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RandomSEIUserDataUnregisteredRange {
     pub uuid_iso_iec_11578: RandomU32Range, // used to sample previously chosen generated SPSes
     pub user_data_apple1: RandomSEIUnregisteredDataApple1Range,
     pub user_data_apple2: RandomSEIUnregisteredDataApple2Range,
     pub user_data_payload_length: RandomU32Range, // used to determine how many bytes to sample
+    #[serde(default)]
+    pub schemas: Vec<UserDataUnregisteredSchema>,
 }
 
 impl RandomSEIUserDataUnregisteredRange {
     pub fn new() -> RandomSEIUserDataUnregisteredRange {
+        let schemas = vec![
+            UserDataUnregisteredSchema::x264(),
+            UserDataUnregisteredSchema::misb_precision_time_stamp(),
+        ];
+
         RandomSEIUserDataUnregisteredRange {
-            uuid_iso_iec_11578: RandomU32Range::new(0, KNOWN_UUIDS - 1),
+            uuid_iso_iec_11578: RandomU32Range::new(0, KNOWN_UUIDS + schemas.len() as u32 - 1),
             user_data_apple1: RandomSEIUnregisteredDataApple1Range::new(),
             user_data_apple2: RandomSEIUnregisteredDataApple2Range::new(),
             user_data_payload_length: RandomU32Range::new(10, 100),
+            schemas,
         }
     }
 }
@@ -1248,34 +2259,267 @@ impl Default for RandomSEIRecoveryPointRange {
     }
 }
 
-/// SEI Type 19 -- Film Grain Characteristics (Annex D.2.21)
+/// SEI Type 7 -- Decoded Reference Picture Marking Repetition (Annex D.2.9)
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
-pub struct RandomSEIFilmGrainCharacteristicsRange {
-    pub film_grain_characteristics_cancel_flag: RandomBoolRange,
-    pub film_grain_model_id: RandomU32Range, // u(2); look up table, only [0, 1] allowed
-    pub separate_colour_description_present_flag: RandomBoolRange, // If 0, default to VUI values, described in E.2.1
-    pub film_grain_bit_depth_luma_minus8: RandomU32Range,          // u(3) ; [0, 7]
-    pub film_grain_bit_depth_chroma_minus8: RandomU32Range,        // u(3) ; [0, 7]
-    pub film_grain_full_range_flag: RandomBoolRange,
-    pub film_grain_colour_primaries: RandomU32Range, // u(8) ; lookup into table E-3; [1, 22]; other values reserved
-    pub film_grain_transfer_characteristics: RandomU32Range, // u(8) ; lookup into table E-4; [1, 18]; other values reserved
-    pub film_grain_matrix_coefficients: RandomU32Range, // u(8) ; lookup into table E-5; [0, 14]; other values reserved
-    pub blending_mode_id: RandomU32Range,               // u(2)
-    pub log2_scale_factor: RandomU32Range,              // u(4)
-    pub comp_model_present_flag: RandomBoolRange,
-    pub num_intensity_intervals_minus1: RandomU32Range, // u(8)
-    pub num_model_values_minus1: RandomU32Range,        // u(3) [0, 5]
-    pub intensity_interval_lower_bound: RandomU32Range, // u(8)
-    pub intensity_interval_upper_bound: RandomU32Range, // u(8)
-    pub comp_model_value: RandomI32Range, // se(v) ; bit length is film_grain_bit_depth_luma_minus8
-    pub film_grain_characteristics_repetition_period: RandomU32Range, // ue(v) ; [0, 16384]
-}
-
-impl RandomSEIFilmGrainCharacteristicsRange {
-    pub fn new() -> RandomSEIFilmGrainCharacteristicsRange {
-        RandomSEIFilmGrainCharacteristicsRange {
-            film_grain_characteristics_cancel_flag: RandomBoolRange::new(0, 1, 1),
-            film_grain_model_id: RandomU32Range::new(0, 3), // u(2); look up table, only [0, 1] allowed
+pub struct RandomSEIDecRefPicMarkingRepetitionRange {
+    pub original_idr_flag: RandomBoolRange,
+    pub original_frame_num: RandomU32Range, // ue(v)
+    pub original_field_pic_flag: RandomBoolRange,
+    pub original_bottom_field_flag: RandomBoolRange,
+    pub no_output_of_prior_pics_flag: RandomBoolRange,
+    pub long_term_reference_flag: RandomBoolRange,
+    pub adaptive_ref_pic_marking_mode_flag: RandomBoolRange,
+    pub number_of_mem_ops: RandomU32Range, // number of memory operations to include
+    pub memory_management_control_operation: RandomU32Range,
+    pub difference_of_pic_nums_minus1: RandomU32Range,
+    pub long_term_pic_num: RandomU32Range,
+    pub long_term_frame_idx: RandomU32Range,
+    pub max_long_term_frame_idx_plus1: RandomU32Range,
+}
+
+impl RandomSEIDecRefPicMarkingRepetitionRange {
+    pub fn new() -> RandomSEIDecRefPicMarkingRepetitionRange {
+        RandomSEIDecRefPicMarkingRepetitionRange {
+            original_idr_flag: RandomBoolRange::new(0, 1, 1),
+            original_frame_num: RandomU32Range::new(0, 255),
+            original_field_pic_flag: RandomBoolRange::new(0, 1, 1),
+            original_bottom_field_flag: RandomBoolRange::new(0, 1, 1),
+            no_output_of_prior_pics_flag: RandomBoolRange::new(0, 1, 1),
+            long_term_reference_flag: RandomBoolRange::new(0, 1, 1),
+            adaptive_ref_pic_marking_mode_flag: RandomBoolRange::new(0, 1, 1),
+            number_of_mem_ops: RandomU32Range::new(0, 10),
+            memory_management_control_operation: RandomU32Range::new(1, 6), // 0 is an exit operation, which we defer
+            difference_of_pic_nums_minus1: RandomU32Range::new(0, 100),
+            long_term_pic_num: RandomU32Range::new(0, 100),
+            long_term_frame_idx: RandomU32Range::new(0, 100),
+            max_long_term_frame_idx_plus1: RandomU32Range::new(0, 100),
+        }
+    }
+}
+
+impl Default for RandomSEIDecRefPicMarkingRepetitionRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 8 -- Spare Picture (Annex D.2.10)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEISparePicRange {
+    pub target_frame_num: RandomU32Range, // ue(v)
+    pub spare_field_flag: RandomBoolRange,
+    pub target_bottom_field_flag: RandomBoolRange,
+    pub num_spare_pics_minus1: RandomU32Range, // ue(v)
+    pub delta_spare_frame_num: RandomU32Range, // ue(v)
+    pub spare_bottom_field_flag: RandomBoolRange,
+    pub spare_area_idc: RandomU32Range, // ue(v) ; [0, 2]
+    pub spare_unit_flag: RandomBoolRange,
+    pub zero_run_length: RandomU32Range, // ue(v) ; sampled until PicSizeInMapUnits is covered
+}
+
+impl RandomSEISparePicRange {
+    pub fn new() -> RandomSEISparePicRange {
+        RandomSEISparePicRange {
+            target_frame_num: RandomU32Range::new(0, 255),
+            spare_field_flag: RandomBoolRange::new(0, 1, 1),
+            target_bottom_field_flag: RandomBoolRange::new(0, 1, 1),
+            num_spare_pics_minus1: RandomU32Range::new(0, 4),
+            delta_spare_frame_num: RandomU32Range::new(0, 16),
+            spare_bottom_field_flag: RandomBoolRange::new(0, 1, 1),
+            spare_area_idc: RandomU32Range::new(0, 2),
+            spare_unit_flag: RandomBoolRange::new(0, 1, 1),
+            zero_run_length: RandomU32Range::new(0, 100),
+        }
+    }
+}
+
+impl Default for RandomSEISparePicRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 9 -- Scene Information (Annex D.2.11)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEISceneInfoRange {
+    pub scene_info_present_flag: RandomBoolRange,
+    pub scene_id: RandomU32Range,              // ue(v) ; [0, 2^32 - 2]
+    pub scene_transition_type: RandomU32Range, // ue(v) ; [0, 6]
+    pub second_scene_id: RandomU32Range,       // ue(v)
+}
+
+impl RandomSEISceneInfoRange {
+    pub fn new() -> RandomSEISceneInfoRange {
+        RandomSEISceneInfoRange {
+            scene_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            scene_id: RandomU32Range::new(0, 255),
+            scene_transition_type: RandomU32Range::new(0, 6),
+            second_scene_id: RandomU32Range::new(0, 255),
+        }
+    }
+}
+
+impl Default for RandomSEISceneInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 10 -- Sub-sequence Information (Annex D.2.12)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEISubSeqInfoRange {
+    pub sub_seq_layer_num: RandomU32Range, // ue(v) ; [0, 255]
+    pub sub_seq_id: RandomU32Range,        // ue(v) ; [0, 65535]
+    pub first_ref_pic_flag: RandomBoolRange,
+    pub leading_non_ref_pic_flag: RandomBoolRange,
+    pub last_pic_flag: RandomBoolRange,
+    pub sub_seq_frame_num_flag: RandomBoolRange,
+    pub sub_seq_frame_num: RandomU32Range, // ue(v)
+}
+
+impl RandomSEISubSeqInfoRange {
+    pub fn new() -> RandomSEISubSeqInfoRange {
+        RandomSEISubSeqInfoRange {
+            sub_seq_layer_num: RandomU32Range::new(0, 255),
+            sub_seq_id: RandomU32Range::new(0, 65535),
+            first_ref_pic_flag: RandomBoolRange::new(0, 1, 1),
+            leading_non_ref_pic_flag: RandomBoolRange::new(0, 1, 1),
+            last_pic_flag: RandomBoolRange::new(0, 1, 1),
+            sub_seq_frame_num_flag: RandomBoolRange::new(0, 1, 1),
+            sub_seq_frame_num: RandomU32Range::new(0, 255),
+        }
+    }
+}
+
+impl Default for RandomSEISubSeqInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 11 -- Sub-sequence Layer Characteristics (Annex D.2.13)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEISubSeqLayerCharacteristicsRange {
+    pub num_sub_seq_layers_minus1: RandomU32Range, // ue(v) ; [0, 255]
+    pub accurate_statistics_flag: RandomBoolRange,
+    pub average_bit_rate: RandomU32Range,   // u(16)
+    pub average_frame_rate: RandomU32Range, // u(16)
+}
+
+impl RandomSEISubSeqLayerCharacteristicsRange {
+    pub fn new() -> RandomSEISubSeqLayerCharacteristicsRange {
+        RandomSEISubSeqLayerCharacteristicsRange {
+            num_sub_seq_layers_minus1: RandomU32Range::new(0, 7),
+            accurate_statistics_flag: RandomBoolRange::new(0, 1, 1),
+            average_bit_rate: RandomU32Range::new(0, 65535),
+            average_frame_rate: RandomU32Range::new(0, 65535),
+        }
+    }
+}
+
+impl Default for RandomSEISubSeqLayerCharacteristicsRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 12 -- Sub-sequence Characteristics (Annex D.2.14)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEISubSeqCharacteristicsRange {
+    pub sub_seq_layer_num: RandomU32Range, // ue(v) ; [0, 255]
+    pub sub_seq_id: RandomU32Range,        // ue(v) ; [0, 65535]
+    pub duration_flag: RandomBoolRange,
+    pub sub_seq_duration: RandomU32Range, // u(32)
+    pub average_rate_flag: RandomBoolRange,
+    pub accurate_statistics_flag: RandomBoolRange,
+    pub average_bit_rate: RandomU32Range,       // u(16)
+    pub average_frame_rate: RandomU32Range,     // u(16)
+    pub num_referenced_subseqs: RandomU32Range, // ue(v)
+    pub ref_sub_seq_layer_num: RandomU32Range,  // ue(v)
+    pub ref_sub_seq_id: RandomU32Range,         // ue(v)
+    pub ref_sub_seq_direction: RandomBoolRange,
+}
+
+impl RandomSEISubSeqCharacteristicsRange {
+    pub fn new() -> RandomSEISubSeqCharacteristicsRange {
+        RandomSEISubSeqCharacteristicsRange {
+            sub_seq_layer_num: RandomU32Range::new(0, 255),
+            sub_seq_id: RandomU32Range::new(0, 65535),
+            duration_flag: RandomBoolRange::new(0, 1, 1),
+            sub_seq_duration: RandomU32Range::new(0, u32::MAX),
+            average_rate_flag: RandomBoolRange::new(0, 1, 1),
+            accurate_statistics_flag: RandomBoolRange::new(0, 1, 1),
+            average_bit_rate: RandomU32Range::new(0, 65535),
+            average_frame_rate: RandomU32Range::new(0, 65535),
+            num_referenced_subseqs: RandomU32Range::new(0, 8),
+            ref_sub_seq_layer_num: RandomU32Range::new(0, 255),
+            ref_sub_seq_id: RandomU32Range::new(0, 65535),
+            ref_sub_seq_direction: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomSEISubSeqCharacteristicsRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Types 13 to 17 -- Full-frame Freeze, Freeze Release, Snapshot and
+/// Progressive Refinement Segment Start/End (Annex D.2.15 to D.2.19)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIFrameFreezeRefinementRange {
+    pub full_frame_freeze_repetition_period: RandomU32Range, // ue(v) ; [0, 16384]
+    pub snapshot_id: RandomU32Range,                         // ue(v)
+    pub progressive_refinement_id: RandomU32Range,           // ue(v)
+    pub num_refinement_steps_minus1: RandomU32Range,         // ue(v)
+}
+
+impl RandomSEIFrameFreezeRefinementRange {
+    pub fn new() -> RandomSEIFrameFreezeRefinementRange {
+        RandomSEIFrameFreezeRefinementRange {
+            full_frame_freeze_repetition_period: RandomU32Range::new(0, 16384),
+            snapshot_id: RandomU32Range::new(0, 255),
+            progressive_refinement_id: RandomU32Range::new(0, 255),
+            num_refinement_steps_minus1: RandomU32Range::new(0, 255),
+        }
+    }
+}
+
+impl Default for RandomSEIFrameFreezeRefinementRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 19 -- Film Grain Characteristics (Annex D.2.21)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIFilmGrainCharacteristicsRange {
+    pub film_grain_characteristics_cancel_flag: RandomBoolRange,
+    pub film_grain_model_id: RandomU32Range, // u(2); look up table, only [0, 1] allowed
+    pub separate_colour_description_present_flag: RandomBoolRange, // If 0, default to VUI values, described in E.2.1
+    pub film_grain_bit_depth_luma_minus8: RandomU32Range,          // u(3) ; [0, 7]
+    pub film_grain_bit_depth_chroma_minus8: RandomU32Range,        // u(3) ; [0, 7]
+    pub film_grain_full_range_flag: RandomBoolRange,
+    pub film_grain_colour_primaries: RandomU32Range, // u(8) ; lookup into table E-3; [1, 22]; other values reserved
+    pub film_grain_transfer_characteristics: RandomU32Range, // u(8) ; lookup into table E-4; [1, 18]; other values reserved
+    pub film_grain_matrix_coefficients: RandomU32Range, // u(8) ; lookup into table E-5; [0, 14]; other values reserved
+    pub blending_mode_id: RandomU32Range,               // u(2)
+    pub log2_scale_factor: RandomU32Range,              // u(4)
+    pub comp_model_present_flag: RandomBoolRange,
+    pub num_intensity_intervals_minus1: RandomU32Range, // u(8)
+    pub num_model_values_minus1: RandomU32Range,        // u(3) [0, 5]
+    pub intensity_interval_lower_bound: RandomU32Range, // u(8)
+    pub intensity_interval_upper_bound: RandomU32Range, // u(8)
+    pub comp_model_value: RandomI32Range, // se(v) ; bit length is film_grain_bit_depth_luma_minus8
+    pub film_grain_characteristics_repetition_period: RandomU32Range, // ue(v) ; [0, 16384]
+}
+
+impl RandomSEIFilmGrainCharacteristicsRange {
+    pub fn new() -> RandomSEIFilmGrainCharacteristicsRange {
+        RandomSEIFilmGrainCharacteristicsRange {
+            film_grain_characteristics_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            film_grain_model_id: RandomU32Range::new(0, 3), // u(2); look up table, only [0, 1] allowed
             separate_colour_description_present_flag: RandomBoolRange::new(0, 1, 1),
             film_grain_bit_depth_luma_minus8: RandomU32Range::new(0, 7), // u(3) ; [0, 7]
             film_grain_bit_depth_chroma_minus8: RandomU32Range::new(0, 7), // u(3) ; [0, 7]
@@ -1296,7 +2540,1459 @@ impl RandomSEIFilmGrainCharacteristicsRange {
     }
 }
 
-impl Default for RandomSEIFilmGrainCharacteristicsRange {
+impl Default for RandomSEIFilmGrainCharacteristicsRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 23 -- Tone Mapping Information (Annex D.2.25)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIToneMappingInfoRange {
+    pub tone_map_id: RandomU32Range, // ue(v) ; [0, 2^32 - 2]
+    pub tone_map_cancel_flag: RandomBoolRange,
+    pub tone_map_repetition_period: RandomU32Range, // ue(v) ; [0, 16384]
+    pub coded_data_bit_depth: RandomU32Range,       // u(8) ; [8, 14] allowed
+    pub target_bit_depth: RandomU32Range, // u(8) ; [1, 16] allowed; model 2 writes 2^target_bit_depth values
+    pub tone_map_model_id: RandomU32Range, // ue(v) ; [0, 4] allowed, others reserved
+    pub min_value: RandomU32Range,        // u(32)
+    pub max_value: RandomU32Range,        // u(32)
+    pub sigmoid_midpoint: RandomU32Range, // u(32)
+    pub sigmoid_width: RandomU32Range,    // u(32)
+    pub start_of_coded_interval: RandomU32Range, // u(v) ; bit length is ((coded_data_bit_depth + 7) >> 3) << 3
+    pub num_pivots: RandomU32Range,              // u(16)
+    pub coded_pivot_value: RandomU32Range,       // u(v)
+    pub target_pivot_value: RandomU32Range,      // u(v)
+    pub camera_iso_speed_idc: RandomU32Range,    // u(8) ; 255 is Extended_ISO
+    pub camera_iso_speed_value: RandomU32Range,  // u(32)
+    pub exposure_index_idc: RandomU32Range,      // u(8) ; 255 is Extended_ISO
+    pub exposure_index_value: RandomU32Range,    // u(32)
+    pub exposure_compensation_value_sign_flag: RandomBoolRange,
+    pub exposure_compensation_value_numerator: RandomU32Range, // u(16)
+    pub exposure_compensation_value_denom_idc: RandomU32Range, // u(16)
+    pub ref_screen_luminance_white: RandomU32Range,            // u(32)
+    pub extended_range_white_level: RandomU32Range,            // u(32)
+    pub nominal_black_level_luma_code_value: RandomU32Range,   // u(16)
+    pub nominal_white_level_luma_code_value: RandomU32Range,   // u(16)
+    pub extended_white_level_luma_code_value: RandomU32Range,  // u(16)
+}
+
+impl RandomSEIToneMappingInfoRange {
+    pub fn new() -> RandomSEIToneMappingInfoRange {
+        RandomSEIToneMappingInfoRange {
+            tone_map_id: RandomU32Range::new(0, 255),
+            tone_map_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            tone_map_repetition_period: RandomU32Range::new(0, 16384),
+            coded_data_bit_depth: RandomU32Range::new(8, 14),
+            target_bit_depth: RandomU32Range::new(1, 10),
+            tone_map_model_id: RandomU32Range::new(0, 4),
+            min_value: RandomU32Range::new(0, u32::MAX),
+            max_value: RandomU32Range::new(0, u32::MAX),
+            sigmoid_midpoint: RandomU32Range::new(0, u32::MAX),
+            sigmoid_width: RandomU32Range::new(0, u32::MAX),
+            start_of_coded_interval: RandomU32Range::new(0, 65535),
+            num_pivots: RandomU32Range::new(0, 64),
+            coded_pivot_value: RandomU32Range::new(0, 65535),
+            target_pivot_value: RandomU32Range::new(0, 65535),
+            camera_iso_speed_idc: RandomU32Range::new(0, 255),
+            camera_iso_speed_value: RandomU32Range::new(0, u32::MAX),
+            exposure_index_idc: RandomU32Range::new(0, 255),
+            exposure_index_value: RandomU32Range::new(0, u32::MAX),
+            exposure_compensation_value_sign_flag: RandomBoolRange::new(0, 1, 1),
+            exposure_compensation_value_numerator: RandomU32Range::new(0, 65535),
+            exposure_compensation_value_denom_idc: RandomU32Range::new(0, 65535),
+            ref_screen_luminance_white: RandomU32Range::new(0, u32::MAX),
+            extended_range_white_level: RandomU32Range::new(0, u32::MAX),
+            nominal_black_level_luma_code_value: RandomU32Range::new(0, 65535),
+            nominal_white_level_luma_code_value: RandomU32Range::new(0, 65535),
+            extended_white_level_luma_code_value: RandomU32Range::new(0, 65535),
+        }
+    }
+}
+
+impl Default for RandomSEIToneMappingInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 24 -- Scalability Information (Annex G.13.2.1)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIScalabilityInfoRange {
+    pub temporal_id_nesting_flag: RandomBoolRange,
+    pub priority_layer_info_present_flag: RandomBoolRange,
+    pub priority_id_setting_flag: RandomBoolRange,
+    pub num_layers_minus1: RandomU32Range, // ue(v) ; [0, 2047]
+    pub layer_id: RandomU32Range,          // ue(v) ; [0, 2047]
+    pub priority_id: RandomU32Range,       // u(6)
+    pub discardable_flag: RandomBoolRange,
+    pub dependency_id: RandomU32Range, // u(3)
+    pub quality_id: RandomU32Range,    // u(4)
+    pub temporal_id: RandomU32Range,   // u(3)
+    pub sub_pic_layer_flag: RandomBoolRange,
+    pub sub_region_layer_flag: RandomBoolRange,
+    pub iroi_division_info_present_flag: RandomBoolRange,
+    pub profile_level_info_present_flag: RandomBoolRange,
+    pub bitrate_info_present_flag: RandomBoolRange,
+    pub frm_rate_info_present_flag: RandomBoolRange,
+    pub frm_size_info_present_flag: RandomBoolRange,
+    pub layer_dependency_info_present_flag: RandomBoolRange,
+    pub parameter_sets_info_present_flag: RandomBoolRange,
+    pub bitstream_restriction_info_present_flag: RandomBoolRange,
+    pub exact_inter_layer_pred_flag: RandomBoolRange,
+    pub exact_sample_value_match_flag: RandomBoolRange,
+    pub layer_conversion_flag: RandomBoolRange,
+    pub layer_output_flag: RandomBoolRange,
+    pub profile_level_idc: RandomU32Range, // u(24) ; layer, rewriting and priority layer values
+    pub bitrate: RandomU32Range,           // u(16) ; average, maximum and calculation window values
+    pub constant_frm_rate_idc: RandomU32Range, // u(2) ; [0, 2] allowed
+    pub avg_frm_rate: RandomU32Range,      // u(16)
+    pub frm_size_in_mbs_minus1: RandomU32Range, // ue(v) ; width and height
+    pub base_region_layer_id: RandomU32Range, // ue(v)
+    pub dynamic_rect_flag: RandomBoolRange,
+    pub region_offset: RandomU32Range, // u(16) ; horizontal and vertical
+    pub region_size: RandomU32Range,   // u(16) ; width and height
+    pub roi_id: RandomU32Range,        // ue(v)
+    pub iroi_grid_flag: RandomBoolRange,
+    pub grid_size_in_mbs_minus1: RandomU32Range, // ue(v) ; width and height
+    pub num_rois_minus1: RandomU32Range,         // ue(v)
+    pub roi_mb_value: RandomU32Range, // ue(v) ; first_mb_in_roi and the ROI width and height
+    pub num_directly_dependent_layers: RandomU32Range, // ue(v)
+    pub layer_id_delta: RandomU32Range, // ue(v) ; dependent and source layer deltas
+    pub num_parameter_sets: RandomU32Range, // ue(v) ; SPS, subset SPS and PPS counts
+    pub parameter_set_id_delta: RandomU32Range, // ue(v)
+    pub motion_vectors_over_pic_boundaries_flag: RandomBoolRange,
+    pub max_bytes_per_pic_denom: RandomU32Range, // ue(v) ; [0, 16]
+    pub max_bits_per_mb_denom: RandomU32Range,   // ue(v) ; [0, 16]
+    pub log2_max_mv_length: RandomU32Range,      // ue(v) ; horizontal and vertical, [0, 16]
+    pub max_num_reorder_frames: RandomU32Range,  // ue(v)
+    pub max_dec_frame_buffering: RandomU32Range, // ue(v)
+    pub conversion_type_idc: RandomU32Range,     // ue(v) ; [0, 2] allowed
+    pub rewriting_info_flag: RandomBoolRange,
+    pub pr_num_dids_minus1: RandomU32Range, // ue(v)
+    pub pr_dependency_id: RandomU32Range,   // u(3)
+    pub pr_num_minus1: RandomU32Range,      // ue(v)
+    pub pr_id: RandomU32Range,              // ue(v)
+    pub priority_id_setting_uri_length: RandomU32Range, // number of non-null URI bytes
+}
+
+impl RandomSEIScalabilityInfoRange {
+    pub fn new() -> RandomSEIScalabilityInfoRange {
+        RandomSEIScalabilityInfoRange {
+            temporal_id_nesting_flag: RandomBoolRange::new(0, 1, 1),
+            priority_layer_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            priority_id_setting_flag: RandomBoolRange::new(0, 1, 1),
+            num_layers_minus1: RandomU32Range::new(0, 3),
+            layer_id: RandomU32Range::new(0, 2047),
+            priority_id: RandomU32Range::new(0, 63),
+            discardable_flag: RandomBoolRange::new(0, 1, 1),
+            dependency_id: RandomU32Range::new(0, 7),
+            quality_id: RandomU32Range::new(0, 15),
+            temporal_id: RandomU32Range::new(0, 7),
+            sub_pic_layer_flag: RandomBoolRange::new(0, 1, 1),
+            sub_region_layer_flag: RandomBoolRange::new(0, 1, 1),
+            iroi_division_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            profile_level_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            bitrate_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            frm_rate_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            frm_size_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            layer_dependency_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            parameter_sets_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            bitstream_restriction_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            exact_inter_layer_pred_flag: RandomBoolRange::new(0, 1, 1),
+            exact_sample_value_match_flag: RandomBoolRange::new(0, 1, 1),
+            layer_conversion_flag: RandomBoolRange::new(0, 1, 1),
+            layer_output_flag: RandomBoolRange::new(0, 1, 1),
+            profile_level_idc: RandomU32Range::new(0, 16777215),
+            bitrate: RandomU32Range::new(0, 65535),
+            constant_frm_rate_idc: RandomU32Range::new(0, 2),
+            avg_frm_rate: RandomU32Range::new(0, 65535),
+            frm_size_in_mbs_minus1: RandomU32Range::new(0, 511),
+            base_region_layer_id: RandomU32Range::new(0, 2047),
+            dynamic_rect_flag: RandomBoolRange::new(0, 1, 1),
+            region_offset: RandomU32Range::new(0, 65535),
+            region_size: RandomU32Range::new(0, 65535),
+            roi_id: RandomU32Range::new(0, 255),
+            iroi_grid_flag: RandomBoolRange::new(0, 1, 1),
+            grid_size_in_mbs_minus1: RandomU32Range::new(0, 511),
+            num_rois_minus1: RandomU32Range::new(0, 7),
+            roi_mb_value: RandomU32Range::new(0, 8191),
+            num_directly_dependent_layers: RandomU32Range::new(0, 4),
+            layer_id_delta: RandomU32Range::new(0, 2047),
+            num_parameter_sets: RandomU32Range::new(0, 4),
+            parameter_set_id_delta: RandomU32Range::new(0, 31),
+            motion_vectors_over_pic_boundaries_flag: RandomBoolRange::new(0, 1, 1),
+            max_bytes_per_pic_denom: RandomU32Range::new(0, 16),
+            max_bits_per_mb_denom: RandomU32Range::new(0, 16),
+            log2_max_mv_length: RandomU32Range::new(0, 16),
+            max_num_reorder_frames: RandomU32Range::new(0, 16),
+            max_dec_frame_buffering: RandomU32Range::new(0, 16),
+            conversion_type_idc: RandomU32Range::new(0, 2),
+            rewriting_info_flag: RandomBoolRange::new(0, 1, 1),
+            pr_num_dids_minus1: RandomU32Range::new(0, 7),
+            pr_dependency_id: RandomU32Range::new(0, 7),
+            pr_num_minus1: RandomU32Range::new(0, 3),
+            pr_id: RandomU32Range::new(0, 63),
+            priority_id_setting_uri_length: RandomU32Range::new(0, 32),
+        }
+    }
+}
+
+impl Default for RandomSEIScalabilityInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 25 -- Sub-picture Scalable Layer (Annex G.13.2.2)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEISubPicScalableLayerRange {
+    pub layer_id: RandomU32Range, // ue(v)
+}
+
+impl RandomSEISubPicScalableLayerRange {
+    pub fn new() -> RandomSEISubPicScalableLayerRange {
+        RandomSEISubPicScalableLayerRange {
+            layer_id: RandomU32Range::new(0, 2047),
+        }
+    }
+}
+
+impl Default for RandomSEISubPicScalableLayerRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 26 -- Non-required Layer Representation (Annex G.13.2.3)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEINonRequiredLayerRepRange {
+    pub num_info_entries_minus1: RandomU32Range, // ue(v)
+    pub entry_dependency_id: RandomU32Range,     // u(3)
+    pub num_non_required_layer_reps_minus1: RandomU32Range, // ue(v)
+    pub non_required_layer_rep_dependency_id: RandomU32Range, // u(3)
+    pub non_required_layer_rep_quality_id: RandomU32Range, // u(4)
+}
+
+impl RandomSEINonRequiredLayerRepRange {
+    pub fn new() -> RandomSEINonRequiredLayerRepRange {
+        RandomSEINonRequiredLayerRepRange {
+            num_info_entries_minus1: RandomU32Range::new(0, 7),
+            entry_dependency_id: RandomU32Range::new(0, 7),
+            num_non_required_layer_reps_minus1: RandomU32Range::new(0, 7),
+            non_required_layer_rep_dependency_id: RandomU32Range::new(0, 7),
+            non_required_layer_rep_quality_id: RandomU32Range::new(0, 15),
+        }
+    }
+}
+
+impl Default for RandomSEINonRequiredLayerRepRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 27 -- Priority Layer Information (Annex G.13.2.4)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIPriorityLayerInfoRange {
+    pub pr_dependency_id: RandomU32Range, // u(3)
+    pub num_priority_ids: RandomU32Range, // u(4)
+    pub alt_priority_id: RandomU32Range,  // u(6)
+}
+
+impl RandomSEIPriorityLayerInfoRange {
+    pub fn new() -> RandomSEIPriorityLayerInfoRange {
+        RandomSEIPriorityLayerInfoRange {
+            pr_dependency_id: RandomU32Range::new(0, 7),
+            num_priority_ids: RandomU32Range::new(0, 15),
+            alt_priority_id: RandomU32Range::new(0, 63),
+        }
+    }
+}
+
+impl Default for RandomSEIPriorityLayerInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 28 -- Layers Not Present (Annex G.13.2.5)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEILayersNotPresentRange {
+    pub num_layers: RandomU32Range, // ue(v) ; [0, 2047]
+    pub layer_id: RandomU32Range,   // ue(v)
+}
+
+impl RandomSEILayersNotPresentRange {
+    pub fn new() -> RandomSEILayersNotPresentRange {
+        RandomSEILayersNotPresentRange {
+            num_layers: RandomU32Range::new(0, 8),
+            layer_id: RandomU32Range::new(0, 2047),
+        }
+    }
+}
+
+impl Default for RandomSEILayersNotPresentRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 29 -- Layer Dependency Change (Annex G.13.2.6)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEILayerDependencyChangeRange {
+    pub num_layers_minus1: RandomU32Range, // ue(v) ; [0, 2047]
+    pub layer_id: RandomU32Range,          // ue(v)
+    pub layer_dependency_info_present_flag: RandomBoolRange,
+    pub num_directly_dependent_layers: RandomU32Range, // ue(v)
+    pub layer_id_delta_minus1: RandomU32Range,         // ue(v) ; dependent and source layer deltas
+}
+
+impl RandomSEILayerDependencyChangeRange {
+    pub fn new() -> RandomSEILayerDependencyChangeRange {
+        RandomSEILayerDependencyChangeRange {
+            num_layers_minus1: RandomU32Range::new(0, 7),
+            layer_id: RandomU32Range::new(0, 2047),
+            layer_dependency_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            num_directly_dependent_layers: RandomU32Range::new(0, 4),
+            layer_id_delta_minus1: RandomU32Range::new(0, 2047),
+        }
+    }
+}
+
+impl Default for RandomSEILayerDependencyChangeRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 30 -- Scalable Nesting (Annex G.13.2.7)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RandomSEIScalableNestingRange {
+    pub all_layer_representations_in_au_flag: RandomBoolRange,
+    pub num_layer_representations_minus1: RandomU32Range, // ue(v) ; [0, 2047]
+    pub sei_dependency_id: RandomU32Range,                // u(3)
+    pub sei_quality_id: RandomU32Range,                   // u(4)
+    pub sei_temporal_id: RandomU32Range,                  // u(3)
+    pub num_nested_seis: RandomU32Range,                  // number of nested sei_message()s
+    pub nested_payload_type: RandomU32Enum, // nesting types here are cut off after MAX_SEI_NESTING_DEPTH
+}
+
+impl RandomSEIScalableNestingRange {
+    pub fn new() -> RandomSEIScalableNestingRange {
+        RandomSEIScalableNestingRange {
+            all_layer_representations_in_au_flag: RandomBoolRange::new(0, 1, 1),
+            num_layer_representations_minus1: RandomU32Range::new(0, 7),
+            sei_dependency_id: RandomU32Range::new(0, 7),
+            sei_quality_id: RandomU32Range::new(0, 15),
+            sei_temporal_id: RandomU32Range::new(0, 7),
+            num_nested_seis: RandomU32Range::new(1, 2),
+            nested_payload_type: RandomU32Enum::new(vec![0, 1, 5, 6, 23, 45, 47]),
+        }
+    }
+}
+
+impl Default for RandomSEIScalableNestingRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Types 31 and 44 -- Base Layer and Base View Temporal HRD (Annex G.13.2.8 and H.13.2.9)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEITemporalHRDRange {
+    pub num_of_temporal_layers_minus1: RandomU32Range, // ue(v) ; [0, 7]
+    pub sei_temporal_id: RandomU32Range,               // u(3)
+    pub sei_timing_info_present_flag: RandomBoolRange,
+    pub sei_num_units_in_tick: RandomU32Range, // u(32)
+    pub sei_time_scale: RandomU32Range,        // u(32)
+    pub sei_fixed_frame_rate_flag: RandomBoolRange,
+    pub sei_nal_hrd_parameters_present_flag: RandomBoolRange,
+    pub sei_vcl_hrd_parameters_present_flag: RandomBoolRange,
+    pub random_hrd_range: RandomHRDRange,
+    pub sei_low_delay_hrd_flag: RandomBoolRange,
+    pub sei_pic_struct_present_flag: RandomBoolRange,
+}
+
+impl RandomSEITemporalHRDRange {
+    pub fn new() -> RandomSEITemporalHRDRange {
+        RandomSEITemporalHRDRange {
+            num_of_temporal_layers_minus1: RandomU32Range::new(0, 7),
+            sei_temporal_id: RandomU32Range::new(0, 7),
+            sei_timing_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            sei_num_units_in_tick: RandomU32Range::new(1, u32::MAX),
+            sei_time_scale: RandomU32Range::new(1, u32::MAX),
+            sei_fixed_frame_rate_flag: RandomBoolRange::new(0, 1, 1),
+            sei_nal_hrd_parameters_present_flag: RandomBoolRange::new(0, 1, 1),
+            sei_vcl_hrd_parameters_present_flag: RandomBoolRange::new(0, 1, 1),
+            random_hrd_range: RandomHRDRange::new(),
+            sei_low_delay_hrd_flag: RandomBoolRange::new(0, 1, 1),
+            sei_pic_struct_present_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomSEITemporalHRDRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 32 -- Quality Layer Integrity Check (Annex G.13.2.9)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIQualityLayerIntegrityCheckRange {
+    pub num_info_entries_minus1: RandomU32Range, // ue(v)
+    pub entry_dependency_id: RandomU32Range,     // u(3)
+    pub quality_layer_crc: RandomU32Range,       // u(16)
+}
+
+impl RandomSEIQualityLayerIntegrityCheckRange {
+    pub fn new() -> RandomSEIQualityLayerIntegrityCheckRange {
+        RandomSEIQualityLayerIntegrityCheckRange {
+            num_info_entries_minus1: RandomU32Range::new(0, 7),
+            entry_dependency_id: RandomU32Range::new(0, 7),
+            quality_layer_crc: RandomU32Range::new(0, 65535),
+        }
+    }
+}
+
+impl Default for RandomSEIQualityLayerIntegrityCheckRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 33 -- Redundant Picture Property (Annex G.13.2.10)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIRedundantPicPropertyRange {
+    pub num_dids_minus1: RandomU32Range,           // ue(v)
+    pub dependency_id: RandomU32Range,             // u(3)
+    pub num_qids_minus1: RandomU32Range,           // ue(v)
+    pub quality_id: RandomU32Range,                // u(4)
+    pub num_redundant_pics_minus1: RandomU32Range, // ue(v)
+    pub redundant_pic_cnt_minus1: RandomU32Range,  // ue(v) ; [0, 126]
+    pub pic_match_flag: RandomBoolRange,
+    pub mb_type_match_flag: RandomBoolRange,
+    pub motion_match_flag: RandomBoolRange,
+    pub residual_match_flag: RandomBoolRange,
+    pub intra_samples_match_flag: RandomBoolRange,
+}
+
+impl RandomSEIRedundantPicPropertyRange {
+    pub fn new() -> RandomSEIRedundantPicPropertyRange {
+        RandomSEIRedundantPicPropertyRange {
+            num_dids_minus1: RandomU32Range::new(0, 3),
+            dependency_id: RandomU32Range::new(0, 7),
+            num_qids_minus1: RandomU32Range::new(0, 3),
+            quality_id: RandomU32Range::new(0, 15),
+            num_redundant_pics_minus1: RandomU32Range::new(0, 3),
+            redundant_pic_cnt_minus1: RandomU32Range::new(0, 126),
+            pic_match_flag: RandomBoolRange::new(0, 1, 1),
+            mb_type_match_flag: RandomBoolRange::new(0, 1, 1),
+            motion_match_flag: RandomBoolRange::new(0, 1, 1),
+            residual_match_flag: RandomBoolRange::new(0, 1, 1),
+            intra_samples_match_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomSEIRedundantPicPropertyRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 34 -- Temporal Level Zero Dependency Representation Index (Annex G.13.2.11)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEITl0DepRepIndexRange {
+    pub tl0_dep_rep_idx: RandomU32Range,      // u(8)
+    pub effective_idr_pic_id: RandomU32Range, // u(16)
+}
+
+impl RandomSEITl0DepRepIndexRange {
+    pub fn new() -> RandomSEITl0DepRepIndexRange {
+        RandomSEITl0DepRepIndexRange {
+            tl0_dep_rep_idx: RandomU32Range::new(0, 255),
+            effective_idr_pic_id: RandomU32Range::new(0, 65535),
+        }
+    }
+}
+
+impl Default for RandomSEITl0DepRepIndexRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 35 -- Temporal Level Switching Point (Annex G.13.2.12)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEITlSwitchingPointRange {
+    pub delta_frame_num: RandomI32Range, // se(v)
+}
+
+impl RandomSEITlSwitchingPointRange {
+    pub fn new() -> RandomSEITlSwitchingPointRange {
+        RandomSEITlSwitchingPointRange {
+            delta_frame_num: RandomI32Range::new(-1024, 1024),
+        }
+    }
+}
+
+impl Default for RandomSEITlSwitchingPointRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 36 -- Parallel Decoding Information (Annex H.13.2.1)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIParallelDecodingInfoRange {
+    pub anchor_pic_flag: RandomBoolRange, // picks the anchor or non-anchor delays
+    pub pdi_init_delay_minus2: RandomU32Range, // ue(v) ; anchor and non-anchor, l0 and l1
+}
+
+impl RandomSEIParallelDecodingInfoRange {
+    pub fn new() -> RandomSEIParallelDecodingInfoRange {
+        RandomSEIParallelDecodingInfoRange {
+            anchor_pic_flag: RandomBoolRange::new(0, 1, 1),
+            pdi_init_delay_minus2: RandomU32Range::new(0, 1023),
+        }
+    }
+}
+
+impl Default for RandomSEIParallelDecodingInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Types 37 and 48 -- MVC and MVCD Scalable Nesting (Annex H.13.2.2 and I.13.2.2)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RandomSEIMVCScalableNestingRange {
+    pub operation_point_flag: RandomBoolRange,
+    pub all_view_components_in_au_flag: RandomBoolRange,
+    pub num_view_components_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub sei_view_id: RandomU32Range,                // u(10)
+    pub sei_view_applicability_flag: RandomBoolRange, // MVCD only
+    pub sei_op_texture_only_flag: RandomBoolRange,  // MVCD only
+    pub num_view_components_op_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub sei_op_view_id: RandomU32Range,             // u(10)
+    pub sei_op_depth_flag: RandomBoolRange,         // MVCD only
+    pub sei_op_texture_flag: RandomBoolRange,       // MVCD only
+    pub sei_op_temporal_id: RandomU32Range,         // u(3)
+    pub num_nested_seis: RandomU32Range,            // number of nested sei_message()s
+    pub nested_payload_type: RandomU32Enum, // nesting types here are cut off after MAX_SEI_NESTING_DEPTH
+}
+
+impl RandomSEIMVCScalableNestingRange {
+    pub fn new() -> RandomSEIMVCScalableNestingRange {
+        RandomSEIMVCScalableNestingRange {
+            operation_point_flag: RandomBoolRange::new(0, 1, 1),
+            all_view_components_in_au_flag: RandomBoolRange::new(0, 1, 1),
+            num_view_components_minus1: RandomU32Range::new(0, 7),
+            sei_view_id: RandomU32Range::new(0, 1023),
+            sei_view_applicability_flag: RandomBoolRange::new(0, 1, 1),
+            sei_op_texture_only_flag: RandomBoolRange::new(0, 1, 1),
+            num_view_components_op_minus1: RandomU32Range::new(0, 7),
+            sei_op_view_id: RandomU32Range::new(0, 1023),
+            sei_op_depth_flag: RandomBoolRange::new(0, 1, 1),
+            sei_op_texture_flag: RandomBoolRange::new(0, 1, 1),
+            sei_op_temporal_id: RandomU32Range::new(0, 7),
+            num_nested_seis: RandomU32Range::new(1, 2),
+            nested_payload_type: RandomU32Enum::new(vec![0, 1, 5, 6, 23, 45, 47]),
+        }
+    }
+}
+
+impl Default for RandomSEIMVCScalableNestingRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Types 38 and 49 -- View and MVCD View Scalability Information (Annex H.13.2.3 and I.13.2.1)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIViewScalabilityInfoRange {
+    pub num_operation_points_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub operation_point_id: RandomU32Range,          // ue(v) ; [0, 65535]
+    pub priority_id: RandomU32Range,                 // u(5)
+    pub temporal_id: RandomU32Range,                 // u(3)
+    pub num_target_output_views_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub view_id: RandomU32Range,                     // ue(v)
+    pub view_info_depth_view_present_flag: RandomBoolRange, // MVCD only
+    pub mvcd_depth_view_flag: RandomBoolRange,       // MVCD only
+    pub view_info_texture_view_present_flag: RandomBoolRange, // MVCD only
+    pub mvcd_texture_view_flag: RandomBoolRange,     // MVCD only
+    pub profile_level_info_present_flag: RandomBoolRange,
+    pub bitrate_info_present_flag: RandomBoolRange,
+    pub frm_rate_info_present_flag: RandomBoolRange,
+    pub view_dependency_info_present_flag: RandomBoolRange,
+    pub parameter_sets_info_present_flag: RandomBoolRange,
+    pub bitstream_restriction_info_present_flag: RandomBoolRange,
+    pub op_profile_level_idc: RandomU32Range,  // u(24)
+    pub bitrate: RandomU32Range, // u(16) ; average, maximum and calculation window values
+    pub constant_frm_rate_idc: RandomU32Range, // u(2) ; [0, 2] allowed
+    pub avg_frm_rate: RandomU32Range, // u(16)
+    pub num_directly_dependent_views: RandomU32Range, // ue(v) ; [0, 16]
+    pub directly_dependent_view_id: RandomU32Range, // ue(v)
+    pub src_op_id: RandomU32Range, // ue(v) ; view dependency and parameter sets source
+    pub num_parameter_sets: RandomU32Range, // ue(v) ; SPS, subset SPS and PPS counts
+    pub parameter_set_id_delta: RandomU32Range, // ue(v)
+    pub motion_vectors_over_pic_boundaries_flag: RandomBoolRange,
+    pub max_bytes_per_pic_denom: RandomU32Range, // ue(v) ; [0, 16]
+    pub max_bits_per_mb_denom: RandomU32Range,   // ue(v) ; [0, 16]
+    pub log2_max_mv_length: RandomU32Range,      // ue(v) ; horizontal and vertical, [0, 16]
+    pub max_num_reorder_frames: RandomU32Range,  // ue(v)
+    pub max_dec_frame_buffering: RandomU32Range, // ue(v)
+}
+
+impl RandomSEIViewScalabilityInfoRange {
+    pub fn new() -> RandomSEIViewScalabilityInfoRange {
+        RandomSEIViewScalabilityInfoRange {
+            num_operation_points_minus1: RandomU32Range::new(0, 3),
+            operation_point_id: RandomU32Range::new(0, 1023),
+            priority_id: RandomU32Range::new(0, 31),
+            temporal_id: RandomU32Range::new(0, 7),
+            num_target_output_views_minus1: RandomU32Range::new(0, 3),
+            view_id: RandomU32Range::new(0, 1023),
+            view_info_depth_view_present_flag: RandomBoolRange::new(0, 1, 1),
+            mvcd_depth_view_flag: RandomBoolRange::new(0, 1, 1),
+            view_info_texture_view_present_flag: RandomBoolRange::new(0, 1, 1),
+            mvcd_texture_view_flag: RandomBoolRange::new(0, 1, 1),
+            profile_level_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            bitrate_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            frm_rate_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            view_dependency_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            parameter_sets_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            bitstream_restriction_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            op_profile_level_idc: RandomU32Range::new(0, 16777215),
+            bitrate: RandomU32Range::new(0, 65535),
+            constant_frm_rate_idc: RandomU32Range::new(0, 2),
+            avg_frm_rate: RandomU32Range::new(0, 65535),
+            num_directly_dependent_views: RandomU32Range::new(0, 4),
+            directly_dependent_view_id: RandomU32Range::new(0, 1023),
+            src_op_id: RandomU32Range::new(0, 1023),
+            num_parameter_sets: RandomU32Range::new(0, 4),
+            parameter_set_id_delta: RandomU32Range::new(0, 31),
+            motion_vectors_over_pic_boundaries_flag: RandomBoolRange::new(0, 1, 1),
+            max_bytes_per_pic_denom: RandomU32Range::new(0, 16),
+            max_bits_per_mb_denom: RandomU32Range::new(0, 16),
+            log2_max_mv_length: RandomU32Range::new(0, 16),
+            max_num_reorder_frames: RandomU32Range::new(0, 16),
+            max_dec_frame_buffering: RandomU32Range::new(0, 16),
+        }
+    }
+}
+
+impl Default for RandomSEIViewScalabilityInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 39 -- Multiview Scene Information (Annex H.13.2.4)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIMultiviewSceneInfoRange {
+    pub max_disparity: RandomU32Range, // ue(v) ; [0, 1023]
+}
+
+impl RandomSEIMultiviewSceneInfoRange {
+    pub fn new() -> RandomSEIMultiviewSceneInfoRange {
+        RandomSEIMultiviewSceneInfoRange {
+            max_disparity: RandomU32Range::new(0, 1023),
+        }
+    }
+}
+
+impl Default for RandomSEIMultiviewSceneInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sign, exponent and mantissa of the camera and display parameters in
+/// Annex H.13.2.5, I.13.2.4 and I.13.2.6
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIFloatValueRange {
+    pub prec: RandomU32Range, // ue(v) ; [0, 31]
+    pub sign: RandomBoolRange,
+    pub exponent: RandomU32Range, // u(6) ; [0, 62] allowed
+    pub mantissa: RandomU32Range, // u(v) ; two samples are masked to the derived length
+}
+
+impl RandomSEIFloatValueRange {
+    pub fn new() -> RandomSEIFloatValueRange {
+        RandomSEIFloatValueRange {
+            prec: RandomU32Range::new(0, 31),
+            sign: RandomBoolRange::new(0, 1, 1),
+            exponent: RandomU32Range::new(0, 62),
+            mantissa: RandomU32Range::new(0, u32::MAX),
+        }
+    }
+}
+
+impl Default for RandomSEIFloatValueRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 40 -- Multiview Acquisition Information (Annex H.13.2.5)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIMultiviewAcquisitionInfoRange {
+    pub num_views_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub intrinsic_param_flag: RandomBoolRange,
+    pub extrinsic_param_flag: RandomBoolRange,
+    pub intrinsic_params_equal_flag: RandomBoolRange,
+    pub random_float_value_range: RandomSEIFloatValueRange,
+}
+
+impl RandomSEIMultiviewAcquisitionInfoRange {
+    pub fn new() -> RandomSEIMultiviewAcquisitionInfoRange {
+        RandomSEIMultiviewAcquisitionInfoRange {
+            num_views_minus1: RandomU32Range::new(0, 3),
+            intrinsic_param_flag: RandomBoolRange::new(0, 1, 1),
+            extrinsic_param_flag: RandomBoolRange::new(0, 1, 1),
+            intrinsic_params_equal_flag: RandomBoolRange::new(0, 1, 1),
+            random_float_value_range: RandomSEIFloatValueRange::new(),
+        }
+    }
+}
+
+impl Default for RandomSEIMultiviewAcquisitionInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 41 -- Non-required View Component (Annex H.13.2.6)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEINonRequiredViewComponentRange {
+    pub num_info_entries_minus1: RandomU32Range, // ue(v)
+    pub view_order_index: RandomU32Range,        // ue(v)
+    pub num_non_required_view_components_minus1: RandomU32Range, // ue(v)
+    pub index_delta_minus1: RandomU32Range,      // ue(v)
+}
+
+impl RandomSEINonRequiredViewComponentRange {
+    pub fn new() -> RandomSEINonRequiredViewComponentRange {
+        RandomSEINonRequiredViewComponentRange {
+            num_info_entries_minus1: RandomU32Range::new(0, 7),
+            view_order_index: RandomU32Range::new(0, 1023),
+            num_non_required_view_components_minus1: RandomU32Range::new(0, 7),
+            index_delta_minus1: RandomU32Range::new(0, 1023),
+        }
+    }
+}
+
+impl Default for RandomSEINonRequiredViewComponentRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 42 -- View Dependency Change (Annex H.13.2.7)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIViewDependencyChangeRange {
+    pub anchor_update_flag: RandomBoolRange,
+    pub non_anchor_update_flag: RandomBoolRange,
+    pub ref_flag: RandomBoolRange, // anchor and non-anchor, l0 and l1
+}
+
+impl RandomSEIViewDependencyChangeRange {
+    pub fn new() -> RandomSEIViewDependencyChangeRange {
+        RandomSEIViewDependencyChangeRange {
+            anchor_update_flag: RandomBoolRange::new(0, 1, 1),
+            non_anchor_update_flag: RandomBoolRange::new(0, 1, 1),
+            ref_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomSEIViewDependencyChangeRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 43 -- Operation Point Not Present (Annex H.13.2.8)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIOperationPointsNotPresentRange {
+    pub num_operation_points: RandomU32Range, // ue(v) ; [0, 1024]
+    pub operation_point_not_present_id: RandomU32Range, // ue(v) ; [0, 65535]
+}
+
+impl RandomSEIOperationPointsNotPresentRange {
+    pub fn new() -> RandomSEIOperationPointsNotPresentRange {
+        RandomSEIOperationPointsNotPresentRange {
+            num_operation_points: RandomU32Range::new(0, 8),
+            operation_point_not_present_id: RandomU32Range::new(0, 65535),
+        }
+    }
+}
+
+impl Default for RandomSEIOperationPointsNotPresentRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 45 -- Frame Packing Arrangement (Annex D.2.26)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIFramePackingRange {
+    pub frame_packing_arrangement_id: RandomU32Range, // ue(v) ; [0, 2^32 - 2]
+    pub frame_packing_arrangement_cancel_flag: RandomBoolRange,
+    pub frame_packing_arrangement_type: RandomU32Range, // u(7) ; lookup into table D-8
+    pub quincunx_sampling_flag: RandomBoolRange,
+    pub content_interpretation_type: RandomU32Range, // u(6) ; [0, 2] allowed, others reserved
+    pub spatial_flipping_flag: RandomBoolRange,
+    pub frame0_flipped_flag: RandomBoolRange,
+    pub field_views_flag: RandomBoolRange,
+    pub current_frame_is_frame0_flag: RandomBoolRange,
+    pub frame0_self_contained_flag: RandomBoolRange,
+    pub frame1_self_contained_flag: RandomBoolRange,
+    pub grid_position: RandomU32Range, // u(4) ; frame0 and frame1, x and y
+    pub frame_packing_arrangement_reserved_byte: RandomU32Range, // u(8) ; must be 0
+    pub frame_packing_arrangement_repetition_period: RandomU32Range, // ue(v) ; [0, 16384]
+    pub frame_packing_arrangement_extension_flag: RandomBoolRange, // must be 0
+}
+
+impl RandomSEIFramePackingRange {
+    pub fn new() -> RandomSEIFramePackingRange {
+        RandomSEIFramePackingRange {
+            frame_packing_arrangement_id: RandomU32Range::new(0, 255),
+            frame_packing_arrangement_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            frame_packing_arrangement_type: RandomU32Range::new(0, 7),
+            quincunx_sampling_flag: RandomBoolRange::new(0, 1, 1),
+            content_interpretation_type: RandomU32Range::new(0, 2),
+            spatial_flipping_flag: RandomBoolRange::new(0, 1, 1),
+            frame0_flipped_flag: RandomBoolRange::new(0, 1, 1),
+            field_views_flag: RandomBoolRange::new(0, 1, 1),
+            current_frame_is_frame0_flag: RandomBoolRange::new(0, 1, 1),
+            frame0_self_contained_flag: RandomBoolRange::new(0, 1, 1),
+            frame1_self_contained_flag: RandomBoolRange::new(0, 1, 1),
+            grid_position: RandomU32Range::new(0, 15),
+            frame_packing_arrangement_reserved_byte: RandomU32Range::new(0, 0),
+            frame_packing_arrangement_repetition_period: RandomU32Range::new(0, 16384),
+            frame_packing_arrangement_extension_flag: RandomBoolRange::new(0, 0, 2),
+        }
+    }
+}
+
+impl Default for RandomSEIFramePackingRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 46 -- Multiview View Position (Annex H.13.2.10)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIMultiviewViewPositionRange {
+    pub num_views_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub view_position: RandomU32Range,    // ue(v) ; [0, 1023]
+    pub multiview_view_position_extension_flag: RandomBoolRange, // must be 0
+}
+
+impl RandomSEIMultiviewViewPositionRange {
+    pub fn new() -> RandomSEIMultiviewViewPositionRange {
+        RandomSEIMultiviewViewPositionRange {
+            num_views_minus1: RandomU32Range::new(0, 7),
+            view_position: RandomU32Range::new(0, 1023),
+            multiview_view_position_extension_flag: RandomBoolRange::new(0, 0, 2),
+        }
+    }
+}
+
+impl Default for RandomSEIMultiviewViewPositionRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 47 -- Display Orientation (Annex D.2.27)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIDisplayOrientationRange {
+    pub display_orientation_cancel_flag: RandomBoolRange,
+    pub hor_flip: RandomBoolRange,
+    pub ver_flip: RandomBoolRange,
+    pub anticlockwise_rotation: RandomU32Range, // u(16) ; in units of 2^-16 * 360 degrees
+    pub display_orientation_repetition_period: RandomU32Range, // ue(v) ; [0, 16384]
+    pub display_orientation_extension_flag: RandomBoolRange, // must be 0
+}
+
+impl RandomSEIDisplayOrientationRange {
+    pub fn new() -> RandomSEIDisplayOrientationRange {
+        RandomSEIDisplayOrientationRange {
+            display_orientation_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            hor_flip: RandomBoolRange::new(0, 1, 1),
+            ver_flip: RandomBoolRange::new(0, 1, 1),
+            anticlockwise_rotation: RandomU32Range::new(0, 65535),
+            display_orientation_repetition_period: RandomU32Range::new(0, 16384),
+            display_orientation_extension_flag: RandomBoolRange::new(0, 0, 2),
+        }
+    }
+}
+
+impl Default for RandomSEIDisplayOrientationRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Depth representation SEI element of Annex I.13.2.3, also used by the
+/// alternative depth information SEI
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIDepthRepresentationElementRange {
+    pub da_sign_flag: RandomBoolRange,
+    pub da_exponent: RandomU32Range, // u(7) ; [0, 126] allowed
+    pub da_mantissa_len_minus1: RandomU32Range, // u(5)
+    pub da_mantissa: RandomU32Range, // u(v) ; masked to da_mantissa_len_minus1 + 1 bits
+}
+
+impl RandomSEIDepthRepresentationElementRange {
+    pub fn new() -> RandomSEIDepthRepresentationElementRange {
+        RandomSEIDepthRepresentationElementRange {
+            da_sign_flag: RandomBoolRange::new(0, 1, 1),
+            da_exponent: RandomU32Range::new(0, 126),
+            da_mantissa_len_minus1: RandomU32Range::new(0, 31),
+            da_mantissa: RandomU32Range::new(0, u32::MAX),
+        }
+    }
+}
+
+impl Default for RandomSEIDepthRepresentationElementRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 50 -- Depth Representation Information (Annex I.13.2.3)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIDepthRepresentationInfoRange {
+    pub all_views_equal_flag: RandomBoolRange,
+    pub num_views_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub z_near_flag: RandomBoolRange,
+    pub z_far_flag: RandomBoolRange,
+    pub z_axis_equal_flag: RandomBoolRange,
+    pub common_z_axis_reference_view: RandomU32Range, // ue(v) ; [0, 1023]
+    pub d_min_flag: RandomBoolRange,
+    pub d_max_flag: RandomBoolRange,
+    pub depth_representation_type: RandomU32Range, // ue(v) ; [0, 3] allowed
+    pub depth_info_view_id: RandomU32Range,        // ue(v) ; [0, 1023]
+    pub z_axis_reference_view: RandomU32Range,     // ue(v) ; [0, 1023]
+    pub disparity_reference_view: RandomU32Range,  // ue(v) ; [0, 1023]
+    pub random_depth_representation_element_range: RandomSEIDepthRepresentationElementRange,
+    pub depth_nonlinear_representation_num_minus1: RandomU32Range, // ue(v) ; [0, 62]
+    pub depth_nonlinear_representation_model: RandomU32Range,      // ue(v) ; [0, 65535]
+}
+
+impl RandomSEIDepthRepresentationInfoRange {
+    pub fn new() -> RandomSEIDepthRepresentationInfoRange {
+        RandomSEIDepthRepresentationInfoRange {
+            all_views_equal_flag: RandomBoolRange::new(0, 1, 1),
+            num_views_minus1: RandomU32Range::new(0, 3),
+            z_near_flag: RandomBoolRange::new(0, 1, 1),
+            z_far_flag: RandomBoolRange::new(0, 1, 1),
+            z_axis_equal_flag: RandomBoolRange::new(0, 1, 1),
+            common_z_axis_reference_view: RandomU32Range::new(0, 1023),
+            d_min_flag: RandomBoolRange::new(0, 1, 1),
+            d_max_flag: RandomBoolRange::new(0, 1, 1),
+            depth_representation_type: RandomU32Range::new(0, 3),
+            depth_info_view_id: RandomU32Range::new(0, 1023),
+            z_axis_reference_view: RandomU32Range::new(0, 1023),
+            disparity_reference_view: RandomU32Range::new(0, 1023),
+            random_depth_representation_element_range:
+                RandomSEIDepthRepresentationElementRange::new(),
+            depth_nonlinear_representation_num_minus1: RandomU32Range::new(0, 7),
+            depth_nonlinear_representation_model: RandomU32Range::new(0, 65535),
+        }
+    }
+}
+
+impl Default for RandomSEIDepthRepresentationInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 51 -- 3D Reference Displays Information (Annex I.13.2.4)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIThreeDimensionalReferenceDisplaysInfoRange {
+    pub prec: RandomU32Range, // ue(v) ; [0, 31], baseline, display width and viewing distance
+    pub ref_viewing_distance_flag: RandomBoolRange,
+    pub num_ref_displays_minus1: RandomU32Range, // ue(v) ; [0, 31]
+    pub exponent: RandomU32Range,                // u(6) ; [0, 62] allowed
+    pub mantissa: RandomU32Range, // u(v) ; two samples are masked to the derived length
+    pub additional_shift_present_flag: RandomBoolRange,
+    pub num_sample_shift_plus512: RandomU32Range, // u(10) ; [0, 1023]
+    pub three_dimensional_reference_displays_extension_flag: RandomBoolRange, // must be 0
+}
+
+impl RandomSEIThreeDimensionalReferenceDisplaysInfoRange {
+    pub fn new() -> RandomSEIThreeDimensionalReferenceDisplaysInfoRange {
+        RandomSEIThreeDimensionalReferenceDisplaysInfoRange {
+            prec: RandomU32Range::new(0, 31),
+            ref_viewing_distance_flag: RandomBoolRange::new(0, 1, 1),
+            num_ref_displays_minus1: RandomU32Range::new(0, 3),
+            exponent: RandomU32Range::new(0, 62),
+            mantissa: RandomU32Range::new(0, u32::MAX),
+            additional_shift_present_flag: RandomBoolRange::new(0, 1, 1),
+            num_sample_shift_plus512: RandomU32Range::new(0, 1023),
+            three_dimensional_reference_displays_extension_flag: RandomBoolRange::new(0, 0, 2),
+        }
+    }
+}
+
+impl Default for RandomSEIThreeDimensionalReferenceDisplaysInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 52 -- Depth Timing (Annex I.13.2.5)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIDepthTimingRange {
+    pub per_view_depth_timing_flag: RandomBoolRange, // needs an MVCD subset SPS
+    pub offset_len_minus1: RandomU32Range,           // u(5)
+    pub depth_disp_delay_offset_fp: RandomU32Range,  // u(v) ; masked to offset_len_minus1 + 1 bits
+    pub depth_disp_delay_offset_dp: RandomU32Range,  // u(6)
+}
+
+impl RandomSEIDepthTimingRange {
+    pub fn new() -> RandomSEIDepthTimingRange {
+        RandomSEIDepthTimingRange {
+            per_view_depth_timing_flag: RandomBoolRange::new(0, 1, 1),
+            offset_len_minus1: RandomU32Range::new(0, 31),
+            depth_disp_delay_offset_fp: RandomU32Range::new(0, u32::MAX),
+            depth_disp_delay_offset_dp: RandomU32Range::new(0, 63),
+        }
+    }
+}
+
+impl Default for RandomSEIDepthTimingRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 53 -- Depth Sampling Information (Annex I.13.2.7)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIDepthSamplingInfoRange {
+    pub dttsr_mul: RandomU32Range, // u(16) ; x and y
+    pub dttsr_dp: RandomU32Range,  // u(4) ; x and y
+    pub per_view_depth_grid_pos_flag: RandomBoolRange,
+    pub num_video_plus_depth_views_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub depth_grid_view_id: RandomU32Range,                // ue(v) ; [0, 1023]
+    pub depth_grid_pos_fp: RandomU32Range,                 // u(20) ; x and y
+    pub depth_grid_pos_dp: RandomU32Range,                 // u(4) ; x and y
+    pub depth_grid_pos_sign_flag: RandomBoolRange,         // x and y
+}
+
+impl RandomSEIDepthSamplingInfoRange {
+    pub fn new() -> RandomSEIDepthSamplingInfoRange {
+        RandomSEIDepthSamplingInfoRange {
+            dttsr_mul: RandomU32Range::new(0, 65535),
+            dttsr_dp: RandomU32Range::new(0, 15),
+            per_view_depth_grid_pos_flag: RandomBoolRange::new(0, 1, 1),
+            num_video_plus_depth_views_minus1: RandomU32Range::new(0, 3),
+            depth_grid_view_id: RandomU32Range::new(0, 1023),
+            depth_grid_pos_fp: RandomU32Range::new(0, 1048575),
+            depth_grid_pos_dp: RandomU32Range::new(0, 15),
+            depth_grid_pos_sign_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomSEIDepthSamplingInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 54 -- Constrained Depth Parameter Set Identifier (Annex J.13.2.1)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIConstrainedDepthParameterSetIdentifierRange {
+    pub max_dps_id: RandomU32Range,      // ue(v) ; [0, 63]
+    pub max_dps_id_diff: RandomU32Range, // ue(v) ; [0, 63]
+}
+
+impl RandomSEIConstrainedDepthParameterSetIdentifierRange {
+    pub fn new() -> RandomSEIConstrainedDepthParameterSetIdentifierRange {
+        RandomSEIConstrainedDepthParameterSetIdentifierRange {
+            max_dps_id: RandomU32Range::new(0, 63),
+            max_dps_id_diff: RandomU32Range::new(0, 63),
+        }
+    }
+}
+
+impl Default for RandomSEIConstrainedDepthParameterSetIdentifierRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 137 -- Mastering Display Colour Volume (Annex D.2.29)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIMasteringDisplayColourVolumeRange {
+    pub display_primaries_x: RandomU32Range, // u(16) ; [0, 50000] allowed
+    pub display_primaries_y: RandomU32Range, // u(16) ; [0, 50000] allowed
+    pub white_point_x: RandomU32Range,       // u(16) ; [0, 50000] allowed
+    pub white_point_y: RandomU32Range,       // u(16) ; [0, 50000] allowed
+    pub max_display_mastering_luminance: RandomU32Range, // u(32)
+    pub min_display_mastering_luminance: RandomU32Range, // u(32)
+}
+
+impl RandomSEIMasteringDisplayColourVolumeRange {
+    pub fn new() -> RandomSEIMasteringDisplayColourVolumeRange {
+        RandomSEIMasteringDisplayColourVolumeRange {
+            display_primaries_x: RandomU32Range::new(0, 65535),
+            display_primaries_y: RandomU32Range::new(0, 65535),
+            white_point_x: RandomU32Range::new(0, 65535),
+            white_point_y: RandomU32Range::new(0, 65535),
+            max_display_mastering_luminance: RandomU32Range::new(0, u32::MAX),
+            min_display_mastering_luminance: RandomU32Range::new(0, u32::MAX),
+        }
+    }
+}
+
+impl Default for RandomSEIMasteringDisplayColourVolumeRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 142 -- Colour Remapping Information (Annex D.2.30)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIColourRemappingInfoRange {
+    pub colour_remap_id: RandomU32Range, // ue(v) ; [0, 2^32 - 2]
+    pub colour_remap_cancel_flag: RandomBoolRange,
+    pub colour_remap_repetition_period: RandomU32Range, // ue(v) ; [0, 16384]
+    pub colour_remap_video_signal_info_present_flag: RandomBoolRange,
+    pub colour_remap_full_range_flag: RandomBoolRange,
+    pub colour_remap_primaries: RandomU32Range, // u(8) ; lookup into table E-3
+    pub colour_remap_transfer_function: RandomU32Range, // u(8) ; lookup into table E-4
+    pub colour_remap_matrix_coefficients: RandomU32Range, // u(8) ; lookup into table E-5
+    pub colour_remap_input_bit_depth: RandomU32Range, // u(8) ; [8, 16] allowed
+    pub colour_remap_output_bit_depth: RandomU32Range, // u(8) ; [8, 16] allowed
+    pub lut_num_val_minus1: RandomU32Range,     // u(8) ; pre and post LUT sizes
+    pub lut_value: RandomU32Range,              // u(v) ; bit length is ((bit_depth + 7) >> 3) << 3
+    pub colour_remap_matrix_present_flag: RandomBoolRange,
+    pub log2_matrix_denom: RandomU32Range,   // u(4) ; [0, 15]
+    pub colour_remap_coeffs: RandomI32Range, // se(v) ; [-2^15, 2^15 - 1] allowed
+}
+
+impl RandomSEIColourRemappingInfoRange {
+    pub fn new() -> RandomSEIColourRemappingInfoRange {
+        RandomSEIColourRemappingInfoRange {
+            colour_remap_id: RandomU32Range::new(0, 255),
+            colour_remap_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            colour_remap_repetition_period: RandomU32Range::new(0, 16384),
+            colour_remap_video_signal_info_present_flag: RandomBoolRange::new(0, 1, 1),
+            colour_remap_full_range_flag: RandomBoolRange::new(0, 1, 1),
+            colour_remap_primaries: RandomU32Range::new(0, 255),
+            colour_remap_transfer_function: RandomU32Range::new(0, 255),
+            colour_remap_matrix_coefficients: RandomU32Range::new(0, 255),
+            colour_remap_input_bit_depth: RandomU32Range::new(8, 16),
+            colour_remap_output_bit_depth: RandomU32Range::new(8, 16),
+            lut_num_val_minus1: RandomU32Range::new(0, 32),
+            lut_value: RandomU32Range::new(0, 65535),
+            colour_remap_matrix_present_flag: RandomBoolRange::new(0, 1, 1),
+            log2_matrix_denom: RandomU32Range::new(0, 15),
+            colour_remap_coeffs: RandomI32Range::new(-32768, 32767),
+        }
+    }
+}
+
+impl Default for RandomSEIColourRemappingInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 144 -- Content Light Level Information (Annex D.2.31)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIContentLightLevelInfoRange {
+    pub max_content_light_level: RandomU32Range,     // u(16)
+    pub max_pic_average_light_level: RandomU32Range, // u(16)
+}
+
+impl RandomSEIContentLightLevelInfoRange {
+    pub fn new() -> RandomSEIContentLightLevelInfoRange {
+        RandomSEIContentLightLevelInfoRange {
+            max_content_light_level: RandomU32Range::new(0, 65535),
+            max_pic_average_light_level: RandomU32Range::new(0, 65535),
+        }
+    }
+}
+
+impl Default for RandomSEIContentLightLevelInfoRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 147 -- Alternative Transfer Characteristics (Annex D.2.32)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIAlternativeTransferCharacteristicsRange {
+    pub preferred_transfer_characteristics: RandomU32Range, // u(8) ; lookup into table E-4
+}
+
+impl RandomSEIAlternativeTransferCharacteristicsRange {
+    pub fn new() -> RandomSEIAlternativeTransferCharacteristicsRange {
+        RandomSEIAlternativeTransferCharacteristicsRange {
+            preferred_transfer_characteristics: RandomU32Range::new(0, 255),
+        }
+    }
+}
+
+impl Default for RandomSEIAlternativeTransferCharacteristicsRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 149 -- Content Colour Volume (Annex D.2.33)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIContentColourVolumeRange {
+    pub ccv_cancel_flag: RandomBoolRange,
+    pub ccv_persistence_flag: RandomBoolRange,
+    pub ccv_primaries_present_flag: RandomBoolRange,
+    pub ccv_min_luminance_value_present_flag: RandomBoolRange,
+    pub ccv_max_luminance_value_present_flag: RandomBoolRange,
+    pub ccv_avg_luminance_value_present_flag: RandomBoolRange,
+    pub ccv_reserved_zero_2bits: RandomU32Range, // u(2) ; must be 0
+    pub ccv_primaries: RandomI32Range,           // i(32) ; [-5000000, 5000000] allowed
+    pub ccv_luminance_value: RandomU32Range,     // u(32)
+}
+
+impl RandomSEIContentColourVolumeRange {
+    pub fn new() -> RandomSEIContentColourVolumeRange {
+        RandomSEIContentColourVolumeRange {
+            ccv_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            ccv_persistence_flag: RandomBoolRange::new(0, 1, 1),
+            ccv_primaries_present_flag: RandomBoolRange::new(0, 1, 1),
+            ccv_min_luminance_value_present_flag: RandomBoolRange::new(0, 1, 1),
+            ccv_max_luminance_value_present_flag: RandomBoolRange::new(0, 1, 1),
+            ccv_avg_luminance_value_present_flag: RandomBoolRange::new(0, 1, 1),
+            ccv_reserved_zero_2bits: RandomU32Range::new(0, 0),
+            ccv_primaries: RandomI32Range::new(-5000000, 5000000),
+            ccv_luminance_value: RandomU32Range::new(0, u32::MAX),
+        }
+    }
+}
+
+impl Default for RandomSEIContentColourVolumeRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 150 -- Equirectangular Projection (Annex D.2.35.1)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIEquirectangularProjectionRange {
+    pub erp_cancel_flag: RandomBoolRange,
+    pub erp_persistence_flag: RandomBoolRange,
+    pub erp_padding_flag: RandomBoolRange,
+    pub erp_reserved_zero_2bits: RandomU32Range, // u(2) ; must be 0
+    pub gb_erp_type: RandomU32Range,             // u(3) ; [0, 3] allowed, others reserved
+    pub left_gb_erp_width: RandomU32Range,       // u(8)
+    pub right_gb_erp_width: RandomU32Range,      // u(8)
+}
+
+impl RandomSEIEquirectangularProjectionRange {
+    pub fn new() -> RandomSEIEquirectangularProjectionRange {
+        RandomSEIEquirectangularProjectionRange {
+            erp_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            erp_persistence_flag: RandomBoolRange::new(0, 1, 1),
+            erp_padding_flag: RandomBoolRange::new(0, 1, 1),
+            erp_reserved_zero_2bits: RandomU32Range::new(0, 0),
+            gb_erp_type: RandomU32Range::new(0, 7),
+            left_gb_erp_width: RandomU32Range::new(0, 255),
+            right_gb_erp_width: RandomU32Range::new(0, 255),
+        }
+    }
+}
+
+impl Default for RandomSEIEquirectangularProjectionRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 151 -- Cubemap Projection (Annex D.2.35.2)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEICubemapProjectionRange {
+    pub cmp_cancel_flag: RandomBoolRange,
+    pub cmp_persistence_flag: RandomBoolRange,
+}
+
+impl RandomSEICubemapProjectionRange {
+    pub fn new() -> RandomSEICubemapProjectionRange {
+        RandomSEICubemapProjectionRange {
+            cmp_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            cmp_persistence_flag: RandomBoolRange::new(0, 1, 1),
+        }
+    }
+}
+
+impl Default for RandomSEICubemapProjectionRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 154 -- Sphere Rotation (Annex D.2.35.3)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEISphereRotationRange {
+    pub sphere_rotation_cancel_flag: RandomBoolRange,
+    pub sphere_rotation_persistence_flag: RandomBoolRange,
+    pub sphere_rotation_reserved_zero_6bits: RandomU32Range, // u(6) ; must be 0
+    pub yaw_rotation: RandomI32Range, // i(32) ; [-180 * 2^16, 180 * 2^16 - 1] allowed
+    pub pitch_rotation: RandomI32Range, // i(32) ; [-90 * 2^16, 90 * 2^16] allowed
+    pub roll_rotation: RandomI32Range, // i(32) ; [-180 * 2^16, 180 * 2^16 - 1] allowed
+}
+
+impl RandomSEISphereRotationRange {
+    pub fn new() -> RandomSEISphereRotationRange {
+        RandomSEISphereRotationRange {
+            sphere_rotation_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            sphere_rotation_persistence_flag: RandomBoolRange::new(0, 1, 1),
+            sphere_rotation_reserved_zero_6bits: RandomU32Range::new(0, 0),
+            yaw_rotation: RandomI32Range::new(-11796480, 11796479),
+            pitch_rotation: RandomI32Range::new(-5898240, 5898240),
+            roll_rotation: RandomI32Range::new(-11796480, 11796479),
+        }
+    }
+}
+
+impl Default for RandomSEISphereRotationRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 155 -- Region-wise Packing (Annex D.2.35.4)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIRegionwisePackingRange {
+    pub rwp_cancel_flag: RandomBoolRange,
+    pub rwp_persistence_flag: RandomBoolRange,
+    pub constituent_picture_matching_flag: RandomBoolRange,
+    pub rwp_reserved_zero_5bits: RandomU32Range, // u(5) ; must be 0
+    pub num_packed_regions: RandomU32Range,      // u(8) ; must be greater than 0
+    pub proj_picture_width: RandomU32Range,      // u(32)
+    pub proj_picture_height: RandomU32Range,     // u(32)
+    pub packed_picture_width: RandomU32Range,    // u(16)
+    pub packed_picture_height: RandomU32Range,   // u(16)
+    pub rwp_reserved_zero_4bits: RandomU32Range, // u(4) ; must be 0
+    pub rwp_transform_type: RandomU32Range,      // u(3) ; [0, 7] are all defined
+    pub rwp_guard_band_flag: RandomBoolRange,
+    pub proj_region_dimension: RandomU32Range, // u(32) ; width, height, top and left
+    pub packed_region_dimension: RandomU32Range, // u(16) ; width, height, top and left
+    pub rwp_guard_band_size: RandomU32Range,   // u(8) ; left, right, top and bottom
+    pub rwp_guard_band_not_used_for_pred_flag: RandomBoolRange,
+    pub rwp_guard_band_type: RandomU32Range, // u(3) ; [0, 3] allowed, others reserved
+    pub rwp_guard_band_reserved_zero_3bits: RandomU32Range, // u(3) ; must be 0
+}
+
+impl RandomSEIRegionwisePackingRange {
+    pub fn new() -> RandomSEIRegionwisePackingRange {
+        RandomSEIRegionwisePackingRange {
+            rwp_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            rwp_persistence_flag: RandomBoolRange::new(0, 1, 1),
+            constituent_picture_matching_flag: RandomBoolRange::new(0, 1, 1),
+            rwp_reserved_zero_5bits: RandomU32Range::new(0, 0),
+            num_packed_regions: RandomU32Range::new(1, 16),
+            proj_picture_width: RandomU32Range::new(0, 8192),
+            proj_picture_height: RandomU32Range::new(0, 8192),
+            packed_picture_width: RandomU32Range::new(0, 8192),
+            packed_picture_height: RandomU32Range::new(0, 8192),
+            rwp_reserved_zero_4bits: RandomU32Range::new(0, 0),
+            rwp_transform_type: RandomU32Range::new(0, 7),
+            rwp_guard_band_flag: RandomBoolRange::new(0, 1, 1),
+            proj_region_dimension: RandomU32Range::new(0, 8192),
+            packed_region_dimension: RandomU32Range::new(0, 8192),
+            rwp_guard_band_size: RandomU32Range::new(0, 255),
+            rwp_guard_band_not_used_for_pred_flag: RandomBoolRange::new(0, 1, 1),
+            rwp_guard_band_type: RandomU32Range::new(0, 7),
+            rwp_guard_band_reserved_zero_3bits: RandomU32Range::new(0, 0),
+        }
+    }
+}
+
+impl Default for RandomSEIRegionwisePackingRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 156 -- Omnidirectional Viewport (Annex D.2.35.5)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIOmniViewportRange {
+    pub omni_viewport_id: RandomU32Range, // u(10)
+    pub omni_viewport_cancel_flag: RandomBoolRange,
+    pub omni_viewport_persistence_flag: RandomBoolRange,
+    pub omni_viewport_cnt_minus1: RandomU32Range, // u(4)
+    pub omni_viewport_azimuth_centre: RandomI32Range, // i(32) ; [-180 * 2^16, 180 * 2^16 - 1] allowed
+    pub omni_viewport_elevation_centre: RandomI32Range, // i(32) ; [-90 * 2^16, 90 * 2^16] allowed
+    pub omni_viewport_tilt_centre: RandomI32Range, // i(32) ; [-180 * 2^16, 180 * 2^16 - 1] allowed
+    pub omni_viewport_hor_range: RandomU32Range,   // u(32) ; [1, 360 * 2^16] allowed
+    pub omni_viewport_ver_range: RandomU32Range,   // u(32) ; [1, 180 * 2^16] allowed
+}
+
+impl RandomSEIOmniViewportRange {
+    pub fn new() -> RandomSEIOmniViewportRange {
+        RandomSEIOmniViewportRange {
+            omni_viewport_id: RandomU32Range::new(0, 1023),
+            omni_viewport_cancel_flag: RandomBoolRange::new(0, 1, 1),
+            omni_viewport_persistence_flag: RandomBoolRange::new(0, 1, 1),
+            omni_viewport_cnt_minus1: RandomU32Range::new(0, 15),
+            omni_viewport_azimuth_centre: RandomI32Range::new(-11796480, 11796479),
+            omni_viewport_elevation_centre: RandomI32Range::new(-5898240, 5898240),
+            omni_viewport_tilt_centre: RandomI32Range::new(-11796480, 11796479),
+            omni_viewport_hor_range: RandomU32Range::new(1, 23592960),
+            omni_viewport_ver_range: RandomU32Range::new(1, 11796480),
+        }
+    }
+}
+
+impl Default for RandomSEIOmniViewportRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// SEI Type 181 -- Alternative Depth Information (Annex I.13.2.6)
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomSEIAlternativeDepthInfoRange {
+    pub depth_type: RandomU32Range, // ue(v) ; only 0 is specified
+    pub num_constituent_views_gvd_minus1: RandomU32Range, // ue(v) ; [0, 1023]
+    pub depth_present_gvd_flag: RandomBoolRange,
+    pub z_gvd_flag: RandomBoolRange,
+    pub intrinsic_param_gvd_flag: RandomBoolRange,
+    pub rotation_gvd_flag: RandomBoolRange,
+    pub translation_gvd_flag: RandomBoolRange,
+    pub random_depth_representation_element_range: RandomSEIDepthRepresentationElementRange,
+    pub random_float_value_range: RandomSEIFloatValueRange,
+}
+
+impl RandomSEIAlternativeDepthInfoRange {
+    pub fn new() -> RandomSEIAlternativeDepthInfoRange {
+        RandomSEIAlternativeDepthInfoRange {
+            depth_type: RandomU32Range::new(0, 0),
+            num_constituent_views_gvd_minus1: RandomU32Range::new(0, 3),
+            depth_present_gvd_flag: RandomBoolRange::new(0, 1, 1),
+            z_gvd_flag: RandomBoolRange::new(0, 1, 1),
+            intrinsic_param_gvd_flag: RandomBoolRange::new(0, 1, 1),
+            rotation_gvd_flag: RandomBoolRange::new(0, 1, 1),
+            translation_gvd_flag: RandomBoolRange::new(0, 1, 1),
+            random_depth_representation_element_range:
+                RandomSEIDepthRepresentationElementRange::new(),
+            random_float_value_range: RandomSEIFloatValueRange::new(),
+        }
+    }
+}
+
+impl Default for RandomSEIAlternativeDepthInfoRange {
     fn default() -> Self {
         Self::new()
     }
@@ -1500,8 +4196,10 @@ pub struct RandomNALUHeader {
     pub bias_idr_nalu: RandomBoolRange, // when True, will make a slice NALU into an IDR NALU
     pub bias_slice_nalu: RandomBoolRange, // when True, make a new slice instead of other NALU types
     pub bias_undefined_nalu: RandomBoolRange, // when True, will sample an undefined NALU value
+    #[serde(default = "RandomBoolRange::never")]
+    pub bias_conforming_nalu_order: RandomBoolRange, // when True, keep the NALU order and nal_ref_idc of 7.4.1.2
     // Extra
-    pub filler_data_nalu_length: RandomU32Range, // According to the spec, filler data is not parsed so we fill it with random length bytes
+    pub filler_data_nalu_length: RandomU32Range, // number of ff_byte in filler data
     pub undefined_nalu_length: RandomU32Range, // For undefined NALUs, we throw random length number of bytes
 }
 
@@ -1522,9 +4220,13 @@ impl RandomNALUHeader {
                 10, // End of Sequence
                 11, // End of Stream
                 12, // Filler data
+                13, // SPS extension
                 14, // Prefix NALU
                 15, // Subset SPS
+                16, // Depth parameter set
+                19, // Auxiliary slice
                 20, // Slice extension
+                21, // 3D-AVC slice extension
             ]),
             nal_unit_undefined_type: RandomU32Enum::new(vec![
                 17, // Reserved
@@ -1549,6 +4251,7 @@ impl RandomNALUHeader {
             bias_idr_nalu: RandomBoolRange::new(0, 1, 1), // 50%
             bias_slice_nalu: RandomBoolRange::new(0, 9, 1), // 90% chance of Slice NALU
             bias_undefined_nalu: RandomBoolRange::new(0, 9, 9), // 10% chance of Slice NALU
+            bias_conforming_nalu_order: RandomBoolRange::never(),
             filler_data_nalu_length: RandomU32Range::new(1, 128),
             undefined_nalu_length: RandomU32Range::new(1, 128),
         }
@@ -1617,6 +4320,115 @@ impl Default for RandomAccessUnitDelim {
     }
 }
 
+/// Filler Data syntax elements
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomFillerDataRange {
+    pub non_ff_byte: RandomBoolRange, // sampled per byte; when True, write a byte other than 0xFF
+    pub missing_rbsp_trailing_bits: RandomBoolRange, // when True, drop rbsp_trailing_bits()
+}
+
+impl RandomFillerDataRange {
+    pub fn new() -> RandomFillerDataRange {
+        RandomFillerDataRange {
+            non_ff_byte: RandomBoolRange::new(0, 9, 9), // 10%
+            missing_rbsp_trailing_bits: RandomBoolRange::new(0, 9, 9), // 10%
+        }
+    }
+}
+
+impl Default for RandomFillerDataRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// End of Sequence and End of Stream NALUs have no syntax elements, so these
+/// ranges control the junk bytes we append to them
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomEndOfSeqStreamRange {
+    pub end_of_seq_trailing_payload: RandomBoolRange, // when True, append bytes to End of Sequence NALUs
+    pub end_of_stream_trailing_payload: RandomBoolRange, // when True, append bytes to End of Stream NALUs
+    pub trailing_payload_length: RandomU32Range,
+}
+
+impl RandomEndOfSeqStreamRange {
+    pub fn new() -> RandomEndOfSeqStreamRange {
+        RandomEndOfSeqStreamRange {
+            end_of_seq_trailing_payload: RandomBoolRange::new(0, 9, 9), // 10%
+            end_of_stream_trailing_payload: RandomBoolRange::new(0, 9, 9), // 10%
+            trailing_payload_length: RandomU32Range::new(1, 64),
+        }
+    }
+}
+
+impl Default for RandomEndOfSeqStreamRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Group of pictures structure used when generating a coherent multi-frame sequence
+#[derive(Debug, Serialize, Deserialize, Clone, Copy)]
+pub struct RandomGOPRange {
+    pub enable_gop_structure: RandomBoolRange, // when True, generate IDR-started GOPs instead of independently sampled NALUs
+    pub num_gops: RandomU32Range,              // number of GOPs in the video
+    pub gop_length: RandomU32Range, // number of pictures in a GOP, including the IDR picture
+    pub num_b_frames: RandomU32Range, // B pictures between two anchor pictures; 0 gives IPPP, 2 gives IBBP
+    pub inject_out_of_range_reference: RandomBoolRange, // when True, one slice moves a picture missing from the DPB into RefPicList0
+    pub inject_mmco_missing_picture: RandomBoolRange, // when True, one picture marks a picture missing from the DPB as unused
+}
+
+impl RandomGOPRange {
+    pub fn new() -> RandomGOPRange {
+        RandomGOPRange {
+            enable_gop_structure: RandomBoolRange::never(),
+            num_gops: RandomU32Range::new(1, 3),
+            gop_length: RandomU32Range::new(4, 16),
+            num_b_frames: RandomU32Range::new(0, 3),
+            inject_out_of_range_reference: RandomBoolRange::new(0, 3, 3), // 25%
+            inject_mmco_missing_picture: RandomBoolRange::new(0, 3, 3),   // 25%
+        }
+    }
+}
+
+impl Default for RandomGOPRange {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Overrides part of the configuration for the slices that match every set criterion.
+/// When several overrides match a slice, later ones are layered over earlier ones
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RandomSliceOverride {
+    #[serde(default)]
+    pub nalu_indices: Vec<u32>, // positions of the slice NALU in the video; empty matches any
+    #[serde(default)]
+    pub first_slices: Option<u32>, // if set, only matches the first N slices of the video
+    #[serde(default)]
+    pub idr: Option<bool>, // if set, only matches IDR (true) or non-IDR (false) slices
+    #[serde(default)]
+    pub slice_types: Vec<u32>, // slice_type values, compared modulo 5; empty matches any
+    pub config: serde_json::Value, // partial configuration, e.g. {"random_mb_range": {...}}
+}
+
+impl RandomSliceOverride {
+    pub fn matches(
+        &self,
+        nalu_idx: usize,
+        slice_idx: usize,
+        idr: bool,
+        slice_type: Option<u8>,
+    ) -> bool {
+        (self.nalu_indices.is_empty() || self.nalu_indices.contains(&(nalu_idx as u32)))
+            && self.first_slices.is_none_or(|x| (slice_idx as u32) < x)
+            && self.idr.is_none_or(|x| x == idr)
+            && (self.slice_types.is_empty()
+                || slice_type
+                    .is_some_and(|x| self.slice_types.iter().any(|y| *y % 5 == x as u32 % 5)))
+    }
+}
+
 /// Overall random video properties
 #[derive(Debug, Serialize, Deserialize, Clone, Copy)]
 pub struct RandomizeVideo {
@@ -1647,30 +4459,57 @@ impl Default for RandomizeVideo {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RandomizeConfig {
     pub random_video_config: RandomizeVideo,
+    #[serde(default)]
+    pub random_gop_range: RandomGOPRange,
     pub random_nalu_range: RandomNALUHeader,
     pub random_access_unit_delim_range: RandomAccessUnitDelim,
+    #[serde(default)]
+    pub random_filler_data_range: RandomFillerDataRange,
+    #[serde(default)]
+    pub random_end_of_seq_stream_range: RandomEndOfSeqStreamRange,
     pub random_sps_range: RandomSPSRange,
+    #[serde(default)]
+    pub random_sps_extension_range: RandomSPSExtensionRange,
     pub random_subset_sps_range: RandomSubsetSPSRange,
     pub random_prefix_nalu_range: RandomPrefixNALU,
     pub random_pps_range: RandomPPSRange,
     pub random_sei_range: RandomSEIRange,
     pub random_slice_header_range: RandomSliceHeaderRange,
+    #[serde(default)]
+    pub random_slice_data_partition_range: RandomSliceDataPartitionRange,
+    #[serde(default)]
+    pub random_slice_svc_extension_range: RandomSliceSVCExtensionRange,
+    #[serde(default)]
+    pub random_slice_3davc_extension_range: RandomSlice3DAVCExtensionRange,
+    #[serde(default)]
+    pub random_depth_parameter_set_range: RandomDepthParameterSetRange,
     pub random_mb_range: RandomMBRange,
+    #[serde(default)]
+    pub random_slice_overrides: Vec<RandomSliceOverride>,
 }
 
 impl RandomizeConfig {
     pub fn new() -> RandomizeConfig {
         RandomizeConfig {
             random_video_config: RandomizeVideo::new(),
+            random_gop_range: RandomGOPRange::new(),
             random_nalu_range: RandomNALUHeader::new(),
             random_access_unit_delim_range: RandomAccessUnitDelim::new(),
+            random_filler_data_range: RandomFillerDataRange::new(),
+            random_end_of_seq_stream_range: RandomEndOfSeqStreamRange::new(),
             random_prefix_nalu_range: RandomPrefixNALU::new(),
             random_sps_range: RandomSPSRange::new(),
+            random_sps_extension_range: RandomSPSExtensionRange::new(),
             random_subset_sps_range: RandomSubsetSPSRange::new(),
             random_pps_range: RandomPPSRange::new(),
             random_sei_range: RandomSEIRange::new(),
             random_slice_header_range: RandomSliceHeaderRange::new(),
+            random_slice_data_partition_range: RandomSliceDataPartitionRange::new(),
+            random_slice_svc_extension_range: RandomSliceSVCExtensionRange::new(),
+            random_slice_3davc_extension_range: RandomSlice3DAVCExtensionRange::new(),
+            random_depth_parameter_set_range: RandomDepthParameterSetRange::new(),
             random_mb_range: RandomMBRange::new(),
+            random_slice_overrides: Vec::new(),
         }
     }
 }
@@ -1697,19 +4536,74 @@ pub fn save_config() {
     };
 }
 
-/// Load new random ranges for the syntax elements
-pub fn load_config(filename: &str) -> RandomizeConfig {
-    let json_file = match File::open(filename) {
-        Err(_) => panic!("couldn't open {}", filename),
-        Ok(file) => file,
-    };
+/// Load random ranges for the syntax elements, layering each file over the defaults
+/// and the files before it. Files only need to contain the fields they change.
+///
+/// Fails with every problem validate-config would report, each naming its file and field
+pub fn load_config(filenames: &[String]) -> Result<RandomizeConfig, Vec<ConfigError>> {
+    match config_layers::layer_config_files(filenames) {
+        (Some(x), errors) if errors.is_empty() => Ok(x),
+        (_, errors) => Err(errors),
+    }
+}
 
-    let reader = BufReader::new(json_file);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let res: RandomizeConfig = match serde_json::from_reader(reader) {
-        Ok(x) => x, // copy over the new result
-        Err(y) => panic!("Error reading modified H264DecodedStream: {:?}", y),
-    };
+    #[test]
+    fn test_distribution_config_compatibility() {
+        // ranges without a distribution are uniform and serialize as before
+        let range: RandomU32Range = serde_json::from_str(r#"{"min": 0, "max": 10}"#).unwrap();
+        assert_eq!(range.distribution, Distribution::Uniform);
+        assert_eq!(
+            serde_json::to_string(&range).unwrap(),
+            r#"{"min":0,"max":10}"#
+        );
+        assert_eq!(
+            serde_json::to_string(&RandomU32Enum::new(vec![1, 2])).unwrap(),
+            r#"{"values":[1,2]}"#
+        );
+
+        let range: RandomDependentI32Range = serde_json::from_str(
+            r#"{"min": -5, "max": 5, "use_dependency": false, "distribution": "log_uniform"}"#,
+        )
+        .unwrap();
+        assert_eq!(range.distribution, Distribution::LogUniform);
+        let values: RandomU32Enum =
+            serde_json::from_str(r#"{"values": [1, 2, 3], "weights": [0, 0, 1]}"#).unwrap();
+        assert_eq!(values.weights, vec![0, 0, 1]);
+    }
+
+    #[test]
+    fn test_distribution_sampling() {
+        let mut film = FilmState::setup_film_from_seed(22);
 
-    res
+        let mut range = RandomU32Range::new(0, u32::MAX);
+        range.distribution = Distribution::Boundary;
+        let boundary_values = Distribution::boundary_values(0, u32::MAX as i64);
+        let hits = (0..1000)
+            .filter(|_| boundary_values.contains(&(range.sample(&mut film) as i64)))
+            .count();
+        assert!(hits > 400, "only {} boundary values", hits);
+
+        range.distribution = Distribution::LogUniform;
+        let small = (0..1000)
+            .filter(|_| range.sample(&mut film) < 1 << 16)
+            .count();
+        assert!(small > 400, "only {} values below 2^16", small);
+
+        let mut range = RandomI32Range::new(-100, 50);
+        range.distribution = Distribution::LogUniform;
+        let samples: Vec<i32> = (0..1000).map(|_| range.sample(&mut film)).collect();
+        assert!(samples.iter().all(|x| -100 <= *x && *x <= 50));
+        assert!(samples.iter().any(|x| *x < 0) && samples.iter().any(|x| *x > 0));
+
+        let mut values = RandomU32Enum::new(vec![1, 2, 3]);
+        values.weights = vec![0, 0, 1];
+        assert!((0..100).all(|_| values.sample(&mut film) == 3));
+        // weights that do not match the values fall back to uniform sampling
+        values.weights = vec![1];
+        assert!((0..100).any(|_| values.sample(&mut film) != 3));
+    }
 }
@@ -0,0 +1,494 @@
+//! GOP-aware video generation: a sequence of IDR-started groups of pictures whose
+//! frame_num, POC, and reference lists follow a model of the decoded picture buffer.
+
+use crate::common::data_structures::H264DecodedStream;
+use crate::common::data_structures::MacroBlock;
+use crate::common::data_structures::NALUheader;
+use crate::common::data_structures::PicParameterSet;
+use crate::common::data_structures::SeqParameterSet;
+use crate::common::data_structures::Slice;
+use crate::common::data_structures::NALU;
+use crate::vidgen::film::FilmState;
+use crate::vidgen::generate_configurations::RandomBoolRange;
+use crate::vidgen::generate_configurations::RandomDependentU32Range;
+use crate::vidgen::generate_configurations::RandomU32Enum;
+use crate::vidgen::generate_configurations::RandomU32Range;
+use crate::vidgen::generate_configurations::RandomizeConfig;
+use crate::vidgen::parameter_sets::random_pps;
+use crate::vidgen::parameter_sets::random_sps;
+use crate::vidgen::slice::random_slice;
+use crate::vidgen::slice_overrides::SliceConfigs;
+use std::cmp;
+
+/// A picture of a GOP, listed in decoding order
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlannedPicture {
+    pub slice_type: u8,     // 0 for P, 1 for B, and 2 for I
+    pub reference: bool,    // anchor pictures are used for reference, B pictures are not
+    pub display_index: u32, // position in output order from the start of the GOP
+}
+
+/// Lays out a GOP of gop_length pictures: an IDR picture followed by anchor P pictures
+/// that are num_b_frames apart, with the B pictures between two anchors decoded after
+/// the later anchor. The last picture of the GOP is always an anchor.
+pub fn plan_gop(gop_length: u32, num_b_frames: u32) -> Vec<PlannedPicture> {
+    let mut res = vec![PlannedPicture {
+        slice_type: 2,
+        reference: true,
+        display_index: 0,
+    }];
+
+    let mut prev_anchor = 0;
+    for display_index in 1..gop_length {
+        if display_index % (num_b_frames + 1) == 0 || display_index == gop_length - 1 {
+            res.push(PlannedPicture {
+                slice_type: 0,
+                reference: true,
+                display_index,
+            });
+            for b_index in (prev_anchor + 1)..display_index {
+                res.push(PlannedPicture {
+                    slice_type: 1,
+                    reference: false,
+                    display_index: b_index,
+                });
+            }
+            prev_anchor = display_index;
+        }
+    }
+
+    res
+}
+
+/// Models the short-term reference frames of the DPB under sliding window marking (8.2.5.3).
+/// Frames are kept in decoding order, so with no gaps in frame_num the i-th most recent
+/// frame has PicNum CurrPicNum - 1 - i.
+#[derive(Debug, Clone)]
+pub struct DpbModel {
+    pub max_num_ref_frames: usize,
+    pub short_term_frame_nums: Vec<u32>,
+}
+
+impl DpbModel {
+    pub fn new(max_num_ref_frames: u32) -> DpbModel {
+        DpbModel {
+            max_num_ref_frames: cmp::max(max_num_ref_frames, 1) as usize,
+            short_term_frame_nums: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.short_term_frame_nums.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.short_term_frame_nums.is_empty()
+    }
+
+    /// An IDR picture marks all reference pictures as unused (8.2.5.1)
+    pub fn clear(&mut self) {
+        self.short_term_frame_nums.clear();
+    }
+
+    /// Removes the oldest short-term frame, as done by the sliding window or by an
+    /// explicit memory_management_control_operation 1
+    pub fn remove_oldest(&mut self) {
+        if !self.short_term_frame_nums.is_empty() {
+            self.short_term_frame_nums.remove(0);
+        }
+    }
+
+    /// Marks the current frame as a short-term reference, applying the sliding window
+    /// when adaptive marking is not used
+    pub fn mark_short_term(&mut self, frame_num: u32, sliding_window: bool) {
+        if sliding_window && self.short_term_frame_nums.len() >= self.max_num_ref_frames {
+            self.remove_oldest();
+        }
+        self.short_term_frame_nums.push(frame_num);
+    }
+}
+
+/// Picks one of the eligible picture indices when the injection is sampled
+fn random_injection_picture(
+    eligible: &[usize],
+    inject: RandomBoolRange,
+    film: &mut FilmState,
+) -> Option<usize> {
+    if inject.sample(film) && !eligible.is_empty() {
+        Some(eligible[film.read_film_u32(0, eligible.len() as u32 - 1) as usize])
+    } else {
+        None
+    }
+}
+
+/// Restricts a randomly generated SPS to frame pictures with POC type 0 so that the
+/// GOP structure determines frame_num, POC, and the reference picture lists
+fn gop_sequence_parameters(sps: &mut SeqParameterSet) {
+    sps.separate_colour_plane_flag = false;
+    sps.log2_max_frame_num_minus4 = cmp::min(sps.log2_max_frame_num_minus4, 12);
+    sps.pic_order_cnt_type = 0;
+    // leaves room for the POC distance between anchor pictures
+    sps.log2_max_pic_order_cnt_lsb_minus4 = sps.log2_max_pic_order_cnt_lsb_minus4.clamp(4, 12);
+    // MaxFrameNum is at least 16, so frame_num values in the DPB never repeat
+    sps.max_num_ref_frames = sps.max_num_ref_frames.clamp(1, 15);
+    sps.gaps_in_frame_num_value_allowed_flag = false;
+    sps.frame_mbs_only_flag = true;
+    sps.mb_adaptive_frame_field_flag = false;
+}
+
+/// Keeps every picture in a single slice group without redundant pictures
+fn gop_picture_parameters(pps: &mut PicParameterSet) {
+    pps.num_slice_groups_minus1 = 0;
+    pps.redundant_pic_cnt_present_flag = false;
+}
+
+/// Generate a video of IDR-started GOPs with configurable IPPP or IBBP patterns. Each
+/// picture is a single slice whose frame_num, POC, num_ref_idx_active, and ref_idx values
+/// follow a model DPB. Up to two violations may be injected into an otherwise consistent
+/// sequence: a reference list modification to a picture missing from the DPB, and a
+/// memory_management_control_operation on a missing picture.
+#[allow(clippy::too_many_arguments)]
+pub fn random_gop_video(
+    ignore_intra_pred: bool,
+    ignore_edge_intra_pred: bool,
+    ignore_ipcm: bool,
+    empty_slice_data: bool,
+    small_video: bool,
+    silent_mode: bool,
+    rconfig: &RandomizeConfig,
+    film: &mut FilmState,
+) -> H264DecodedStream {
+    let mut ds = H264DecodedStream::new();
+    let mut generated_nalu_type_str = String::new();
+
+    // the parameter sets
+    ds.nalu_elements.push(NALU::new());
+    ds.nalu_headers.push(NALUheader::new());
+    ds.nalu_headers[0].nal_unit_type = 7;
+    ds.nalu_headers[0].nal_ref_idc =
+        cmp::max(rconfig.random_nalu_range.nal_ref_idc.sample(film) as u8, 1);
+    if !silent_mode {
+        println!("\t random_gop_video - NALU 0 - Generating Sequence Parameter Set");
+    }
+    let mut sps = SeqParameterSet::new();
+    random_sps(
+        &mut sps,
+        false,
+        &rconfig.random_sps_range,
+        small_video,
+        silent_mode,
+        film,
+    );
+    gop_sequence_parameters(&mut sps);
+    ds.spses.push(sps.clone());
+    generated_nalu_type_str += "SPS(7);";
+
+    ds.nalu_elements.push(NALU::new());
+    ds.nalu_headers.push(NALUheader::new());
+    ds.nalu_headers[1].nal_unit_type = 8;
+    ds.nalu_headers[1].nal_ref_idc = ds.nalu_headers[0].nal_ref_idc;
+    if !silent_mode {
+        println!("\t random_gop_video - NALU 1 - Generating Picture Parameter Set");
+    }
+    ds.ppses.push(PicParameterSet::new());
+    random_pps(0, &sps, rconfig.random_pps_range, &mut ds, film);
+    gop_picture_parameters(&mut ds.ppses[0]);
+    let pps = ds.ppses[0].clone();
+    generated_nalu_type_str += "PPS(8);";
+
+    // the GOP layout
+    let num_gops = cmp::max(rconfig.random_gop_range.num_gops.sample(film), 1);
+    let num_b_frames = if sps.profile_idc == 66 {
+        0 // the Baseline profile has no B slices
+    } else {
+        rconfig.random_gop_range.num_b_frames.sample(film)
+    };
+    let gops: Vec<Vec<PlannedPicture>> = (0..num_gops)
+        .map(|_| {
+            let gop_length = cmp::max(rconfig.random_gop_range.gop_length.sample(film), 1);
+            plan_gop(gop_length, num_b_frames)
+        })
+        .collect();
+
+    // each violation is injected into one randomly chosen eligible picture
+    let pictures: Vec<(bool, PlannedPicture)> = gops
+        .iter()
+        .flat_map(|gop| gop.iter().enumerate().map(|(i, x)| (i == 0, *x)))
+        .collect();
+    let inter_pictures: Vec<usize> = (0..pictures.len())
+        .filter(|&i| pictures[i].1.slice_type != 2)
+        .collect();
+    let non_idr_reference_pictures: Vec<usize> = (0..pictures.len())
+        .filter(|&i| !pictures[i].0 && pictures[i].1.reference)
+        .collect();
+    let out_of_range_reference_idx = random_injection_picture(
+        &inter_pictures,
+        rconfig.random_gop_range.inject_out_of_range_reference,
+        film,
+    );
+    let mmco_missing_picture_idx = random_injection_picture(
+        &non_idr_reference_pictures,
+        rconfig.random_gop_range.inject_mmco_missing_picture,
+        film,
+    );
+
+    let mut slice_configs = SliceConfigs::new(rconfig);
+    let max_frame_num = 1u32 << (sps.log2_max_frame_num_minus4 + 4);
+    let max_pic_order_cnt_lsb = 1u32 << (sps.log2_max_pic_order_cnt_lsb_minus4 + 4);
+    let macroblock_amount =
+        ((sps.pic_width_in_mbs_minus1 + 1) * (sps.pic_height_in_map_units_minus1 + 1)) as usize;
+
+    let mut dpb = DpbModel::new(sps.max_num_ref_frames);
+    let mut prev_ref_frame_num = 0;
+    let mut prev_idr_pic_id = None;
+    let mut picture_idx = 0;
+    let mut slice_idx = 0;
+
+    for (gop_idx, gop) in gops.iter().enumerate() {
+        for (i, picture) in gop.iter().enumerate() {
+            let nalu_idx = ds.nalu_headers.len();
+            let idr = i == 0;
+
+            ds.nalu_elements.push(NALU::new());
+            ds.nalu_headers.push(NALUheader::new());
+            ds.nalu_headers[nalu_idx].nal_unit_type = if idr { 5 } else { 1 };
+            ds.nalu_headers[nalu_idx].nal_ref_idc = if picture.reference {
+                cmp::max(rconfig.random_nalu_range.nal_ref_idc.sample(film) as u8, 1)
+            } else {
+                0
+            };
+
+            // every frame in the DPB is in both lists of a B slice
+            let num_ref_idx_active = if dpb.is_empty() { 1 } else { dpb.len() as u32 };
+            // the structure of each slice header comes from the GOP, everything else is sampled
+            let mut gop_rconfig = slice_configs
+                .matching_config(nalu_idx, slice_idx, idr, Some(picture.slice_type))
+                .clone();
+            let sh_range = &mut gop_rconfig.random_slice_header_range;
+            sh_range.bias_i_slice = RandomBoolRange::never();
+            sh_range.bias_zero_first_mb_in_slice = RandomBoolRange::always();
+            sh_range.bias_conforming_frame_num_poc = RandomBoolRange::never();
+            sh_range.field_pic_flag = RandomBoolRange::never();
+            sh_range.redundant_pic_cnt = RandomU32Range::new(0, 0);
+            sh_range.num_ref_idx_active_override_flag = RandomBoolRange::always();
+            sh_range.ref_pic_list_modification_flag_l0 = RandomBoolRange::never();
+            sh_range.ref_pic_list_modification_flag_l1 = RandomBoolRange::never();
+            sh_range.no_output_of_prior_pics_flag = RandomBoolRange::never();
+            sh_range.long_term_reference_flag = RandomBoolRange::never();
+            sh_range.adaptive_ref_pic_marking_mode_flag = RandomBoolRange::never();
+            sh_range.slice_type = RandomU32Enum::new(vec![
+                picture.slice_type as u32,
+                picture.slice_type as u32 + 5,
+            ]);
+            sh_range.num_ref_idx_l0_active_minus1 =
+                RandomDependentU32Range::new(num_ref_idx_active - 1, num_ref_idx_active - 1, false);
+            sh_range.num_ref_idx_l1_active_minus1 =
+                RandomDependentU32Range::new(num_ref_idx_active - 1, num_ref_idx_active - 1, false);
+            gop_rconfig.random_mb_range.ref_idx_l0 = RandomU32Range::new(0, num_ref_idx_active - 1);
+            gop_rconfig.random_mb_range.ref_idx_l1 = RandomU32Range::new(0, num_ref_idx_active - 1);
+
+            ds.slices.push(Slice::new());
+            ds.slices[slice_idx].sd.macroblock_vec = vec![MacroBlock::new(); macroblock_amount];
+            let randomize_header = true;
+            random_slice(
+                nalu_idx,
+                slice_idx,
+                &pps,
+                &sps,
+                ignore_intra_pred,
+                ignore_edge_intra_pred,
+                ignore_ipcm,
+                empty_slice_data,
+                randomize_header,
+                silent_mode,
+                &gop_rconfig,
+                &mut ds,
+                film,
+            );
+
+            // frame_num advances after each reference picture (7.4.3)
+            let frame_num = if idr {
+                0
+            } else {
+                (prev_ref_frame_num + 1) % max_frame_num
+            };
+            let sh = &mut ds.slices[slice_idx].sh;
+            sh.frame_num = frame_num;
+            sh.pic_order_cnt_lsb = (2 * picture.display_index) % max_pic_order_cnt_lsb;
+            sh.delta_pic_order_cnt_bottom = 0;
+            if idr {
+                // consecutive IDR pictures use different idr_pic_id values
+                if prev_idr_pic_id == Some(sh.idr_pic_id) {
+                    sh.idr_pic_id = (sh.idr_pic_id + 1) % 65536;
+                }
+                prev_idr_pic_id = Some(sh.idr_pic_id);
+            } else {
+                prev_idr_pic_id = None;
+            }
+
+            // the most recent picture missing from the DPB has PicNum CurrPicNum - (len + 1)
+            let missing_pic_num_diff = dpb.len() as u32;
+
+            if out_of_range_reference_idx == Some(picture_idx) {
+                if !silent_mode {
+                    println!(
+                        "\t random_gop_video - NALU {} - Injecting a reference to a picture missing from the DPB",
+                        nalu_idx
+                    );
+                }
+                sh.ref_pic_list_modification_flag_l0 = true;
+                sh.modification_of_pic_nums_idc_l0 = vec![0, 3];
+                sh.abs_diff_pic_num_minus1_l0 = vec![missing_pic_num_diff];
+                sh.long_term_pic_num_l0 = vec![0];
+            }
+
+            let mut sliding_window = true;
+            if mmco_missing_picture_idx == Some(picture_idx) {
+                if !silent_mode {
+                    println!(
+                        "\t random_gop_video - NALU {} - Injecting a memory management control operation on a picture missing from the DPB",
+                        nalu_idx
+                    );
+                }
+                sh.adaptive_ref_pic_marking_mode_flag = true;
+                sh.memory_management_control_operation = vec![1];
+                sh.difference_of_pic_nums_minus1 = vec![missing_pic_num_diff];
+                // without the sliding window, a full DPB explicitly drops its oldest frame
+                if dpb.len() >= dpb.max_num_ref_frames {
+                    sh.memory_management_control_operation.push(1);
+                    sh.difference_of_pic_nums_minus1
+                        .push(missing_pic_num_diff - 1);
+                    dpb.remove_oldest();
+                }
+                let num_ops = sh.memory_management_control_operation.len();
+                sh.long_term_pic_num = vec![0; num_ops];
+                sh.long_term_frame_idx = vec![0; num_ops];
+                sh.max_long_term_frame_idx_plus1 = vec![0; num_ops];
+                sh.memory_management_control_operation.push(0);
+                sliding_window = false;
+            }
+
+            if !silent_mode {
+                println!(
+                    "\t random_gop_video - NALU {} - GOP {} {} picture with frame_num {} and POC {}",
+                    nalu_idx,
+                    gop_idx,
+                    match picture.slice_type {
+                        0 => "P",
+                        1 => "B",
+                        _ => "IDR",
+                    },
+                    frame_num,
+                    2 * picture.display_index
+                );
+            }
+
+            if idr {
+                dpb.clear();
+            }
+            if picture.reference {
+                dpb.mark_short_term(frame_num, sliding_window);
+                prev_ref_frame_num = frame_num;
+            }
+
+            generated_nalu_type_str += match (idr, picture.slice_type) {
+                (true, _) => "IDR Slice(5);",
+                (false, 1) => "Non-IDR B Slice(1);",
+                (false, _) => "Non-IDR P Slice(1);",
+            };
+            picture_idx += 1;
+            slice_idx += 1;
+        }
+    }
+
+    if !silent_mode {
+        println!("\t Generated Sequence: {}", generated_nalu_type_str);
+    }
+
+    ds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::main_profile_cavlc_config;
+    use crate::common::test_support::round_trip;
+    use crate::vidgen::vidgen::random_video;
+
+    #[test]
+    fn test_gop_generation_follows_dpb() {
+        let mut rconfig = main_profile_cavlc_config();
+        rconfig.random_gop_range.enable_gop_structure = RandomBoolRange::always();
+        rconfig.random_gop_range.num_gops = RandomU32Range::new(2, 2);
+        rconfig.random_gop_range.gop_length = RandomU32Range::new(7, 7);
+        rconfig.random_gop_range.num_b_frames = RandomU32Range::new(2, 2);
+        rconfig.random_gop_range.inject_out_of_range_reference = RandomBoolRange::never();
+        rconfig.random_gop_range.inject_mmco_missing_picture = RandomBoolRange::never();
+
+        let mut film = FilmState::setup_film_from_seed(23);
+        let mut ds = random_video(
+            false, false, false, false, true, true, false, false, &rconfig, &mut film,
+        );
+
+        // IDR P3 B1 B2 P6 B4 B5 in decoding order, twice
+        let nal_unit_types: Vec<u8> = ds.nalu_headers.iter().map(|x| x.nal_unit_type).collect();
+        assert_eq!(
+            nal_unit_types,
+            [7, 8, 5, 1, 1, 1, 1, 1, 1, 5, 1, 1, 1, 1, 1, 1]
+        );
+        let max_num_ref_frames = ds.spses[0].max_num_ref_frames;
+        for (i, slice) in ds.slices.iter().enumerate() {
+            let gop_idx = i % 7;
+            assert_eq!(slice.sh.slice_type % 5, [2, 0, 1, 1, 0, 1, 1][gop_idx]);
+            assert_eq!(slice.sh.frame_num, [0, 1, 2, 2, 2, 3, 3][gop_idx]);
+            assert_eq!(slice.sh.pic_order_cnt_lsb, [0, 6, 2, 4, 12, 8, 10][gop_idx]);
+            assert_eq!(
+                ds.nalu_headers[i + 2].nal_ref_idc == 0,
+                slice.sh.slice_type % 5 == 1
+            );
+            if gop_idx > 0 {
+                let refs_decoded = [0, 1, 2, 2, 2, 3, 3][gop_idx];
+                assert_eq!(
+                    slice.sh.num_ref_idx_l0_active_minus1 + 1,
+                    std::cmp::min(refs_decoded, max_num_ref_frames)
+                );
+            }
+            assert!(!slice.sh.ref_pic_list_modification_flag_l0);
+            assert!(!slice.sh.adaptive_ref_pic_marking_mode_flag);
+        }
+
+        let decoded = round_trip(&mut ds);
+        for (a, b) in decoded.slices.iter().zip(ds.slices.iter()) {
+            assert_eq!(a.sh.frame_num, b.sh.frame_num);
+            assert_eq!(a.sh.pic_order_cnt_lsb, b.sh.pic_order_cnt_lsb);
+        }
+
+        // each violation is injected exactly once
+        rconfig.random_gop_range.inject_out_of_range_reference = RandomBoolRange::always();
+        rconfig.random_gop_range.inject_mmco_missing_picture = RandomBoolRange::always();
+        let mut film = FilmState::setup_film_from_seed(24);
+        let mut ds = random_video(
+            false, false, false, true, true, true, false, false, &rconfig, &mut film,
+        );
+        let modified: Vec<_> = ds
+            .slices
+            .iter()
+            .filter(|x| x.sh.ref_pic_list_modification_flag_l0)
+            .collect();
+        assert_eq!(modified.len(), 1);
+        assert_eq!(
+            modified[0].sh.abs_diff_pic_num_minus1_l0[0],
+            modified[0].sh.num_ref_idx_l0_active_minus1 + 1
+        );
+        let marked: Vec<_> = ds
+            .slices
+            .iter()
+            .filter(|x| x.sh.adaptive_ref_pic_marking_mode_flag)
+            .collect();
+        assert_eq!(marked.len(), 1);
+        assert_eq!(marked[0].sh.memory_management_control_operation[0], 1);
+
+        let decoded = round_trip(&mut ds);
+        assert_eq!(decoded.slices.len(), 14);
+    }
+}
@@ -436,7 +436,7 @@ fn randomize_coeff_token(
         } else {
             // n_c == -2 so bounded from 0 to 8
             total_coeff =
-                (rconfig.total_coeff.sample(film) as usize) % (cmp::min(5, max_num_ceoff));
+                (rconfig.total_coeff.sample(film) as usize) % (cmp::min(8, max_num_ceoff) + 1);
         }
     } else {
         total_coeff = (rconfig.total_coeff.sample(film) as usize) % max_num_ceoff;
@@ -1,12 +1,17 @@
 //! H.264 video generation.
 
+pub mod compliance;
+pub mod config_layers;
 pub mod film;
 pub mod generate_configurations;
+pub mod gop;
 pub mod macroblock;
 pub mod modify_video;
 pub mod nalu;
 pub mod parameter_sets;
 pub mod sei;
 pub mod slice;
+pub mod slice_overrides;
 pub mod syntax_to_video;
+pub mod template;
 pub mod vidgen;
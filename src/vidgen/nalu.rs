@@ -3,6 +3,8 @@
 use crate::common::data_structures::H264DecodedStream;
 use crate::vidgen::film::FilmState;
 use crate::vidgen::generate_configurations::RandomAccessUnitDelim;
+use crate::vidgen::generate_configurations::RandomEndOfSeqStreamRange;
+use crate::vidgen::generate_configurations::RandomFillerDataRange;
 use crate::vidgen::generate_configurations::RandomNALUHeader;
 use crate::vidgen::generate_configurations::RandomNALUHeader3DAVCExtension;
 use crate::vidgen::generate_configurations::RandomNALUHeaderMVCExtension;
@@ -274,3 +276,52 @@ pub fn random_access_unit_delimiter(
 ) {
     ds.auds[aud_idx].primary_pic_type = rconfig.primary_pic_type.sample(film) as u8;
 }
+
+/// Generate random Filler Data (NALU Type 12)
+pub fn random_filler_data(
+    filler_data_idx: usize,
+    filler_data_length: u32,
+    rconfig: RandomFillerDataRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    for _ in 0..filler_data_length {
+        let ff_byte = if rconfig.non_ff_byte.sample(film) {
+            film.read_film_u32(0, 0xfe) as u8
+        } else {
+            0xff
+        };
+        ds.filler_data[filler_data_idx].ff_byte.push(ff_byte);
+    }
+
+    ds.filler_data[filler_data_idx].rbsp_trailing_bits =
+        !rconfig.missing_rbsp_trailing_bits.sample(film);
+    if !ds.filler_data[filler_data_idx].rbsp_trailing_bits {
+        // without the trailing bits, a final 0x80 would be read back as rbsp_trailing_bits
+        // and a final 0x00 as trailing_zero_8bits
+        if let Some(last) = ds.filler_data[filler_data_idx].ff_byte.last_mut() {
+            if *last == 0x80 || *last == 0x00 {
+                *last = 0xff;
+            }
+        }
+    }
+}
+
+/// Generate the junk bytes that follow the header of an End of Sequence (NALU Type 10)
+/// or End of Stream (NALU Type 11)
+pub fn random_end_of_seq_stream_payload(
+    rconfig: RandomEndOfSeqStreamRange,
+    film: &mut FilmState,
+) -> Vec<u8> {
+    let length = rconfig.trailing_payload_length.sample(film);
+    let mut payload = film.read_film_bytes(length);
+
+    // a final 0x00 would be read back as trailing_zero_8bits
+    if let Some(last) = payload.last_mut() {
+        if *last == 0x00 {
+            *last = 0x80;
+        }
+    }
+
+    payload
+}
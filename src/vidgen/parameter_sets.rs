@@ -1,15 +1,31 @@
 //! Parameter Set (SPS, PPS, VUI, extensions) syntax element randomization.
 
+use crate::common::data_structures::AVC3DSPSExtension;
+use crate::common::data_structures::AcquisitionElement3DV;
+use crate::common::data_structures::DepthRanges;
 use crate::common::data_structures::H264DecodedStream;
 use crate::common::data_structures::HRDParameters;
+use crate::common::data_structures::MVCDSPSExtension;
+use crate::common::data_structures::MVCDVUIParameters;
+use crate::common::data_structures::MVCVUIParameters;
+use crate::common::data_structures::SPSExtension;
 use crate::common::data_structures::SeqParameterSet;
+use crate::common::data_structures::VSPParam;
 use crate::common::data_structures::VUIParameters;
+use crate::vidgen::compliance::apply_level_limits_to_vui;
 use crate::vidgen::film::FilmState;
+use crate::vidgen::generate_configurations::RandomDepthParameterSetRange;
+use crate::vidgen::generate_configurations::RandomDepthRangesRange;
 use crate::vidgen::generate_configurations::RandomHRDRange;
 use crate::vidgen::generate_configurations::RandomPPSRange;
+use crate::vidgen::generate_configurations::RandomSPS3DAVCExtensionRange;
+use crate::vidgen::generate_configurations::RandomSPSExtensionRange;
+use crate::vidgen::generate_configurations::RandomSPSMVCDExtensionRange;
 use crate::vidgen::generate_configurations::RandomSPSMVCExtensionRange;
 use crate::vidgen::generate_configurations::RandomSPSRange;
 use crate::vidgen::generate_configurations::RandomSubsetSPSRange;
+use crate::vidgen::generate_configurations::RandomVSPParamRange;
+use crate::vidgen::generate_configurations::RandomVUIMVCDParametersRange;
 use crate::vidgen::generate_configurations::RandomVUIMVCParametersRange;
 use crate::vidgen::generate_configurations::RandomVUIRange;
 use std::cmp;
@@ -137,6 +153,7 @@ fn random_pic_size_and_max_num_ref_frames(
     small_video: bool,
     silent_mode: bool,
     max_fs_and_dpb_mbs: (u32, u32),
+    respect_level_limits: bool,
     rconfig: &RandomSPSRange,
     film: &mut FilmState,
 ) {
@@ -202,6 +219,19 @@ fn random_pic_size_and_max_num_ref_frames(
         }
     }
 
+    // A.3.1 b) and A.3.2 a): PicWidthInMbs * FrameHeightInMbs <= MaxFS and
+    // neither dimension is larger than Sqrt(MaxFS * 8)
+    if respect_level_limits {
+        let max_dimension = (8f64 * max_fs as f64).sqrt() as u32;
+        let width = cmp::min(sps.pic_width_in_mbs_minus1 + 1, max_dimension);
+        let height = cmp::min(
+            cmp::min(sps.pic_height_in_map_units_minus1 + 1, max_dimension),
+            max_fs / width,
+        );
+        sps.pic_width_in_mbs_minus1 = width - 1;
+        sps.pic_height_in_map_units_minus1 = height - 1;
+    }
+
     let pic_size = (sps.pic_width_in_mbs_minus1 + 1) * (sps.pic_height_in_map_units_minus1 + 1);
 
     // now we set the max number of reference frames based on how many times the frame size can go into the rest of the buffer
@@ -459,11 +489,13 @@ pub fn random_sps(
         rconfig.gaps_in_frame_num_value_allowed_flag.sample(film);
     //sps.pic_width_in_mbs_minus1 = rconfig.pic_width_in_mbs_minus1.sample(film);
     //sps.pic_height_in_map_units_minus1 = rconfig.pic_height_in_map_units_minus1.sample(film);
+    let respect_level_limits = rconfig.bias_level_limits.sample(film);
     random_pic_size_and_max_num_ref_frames(
         sps,
         small_video,
         silent_mode,
         max_fs_and_dpb_mbs,
+        respect_level_limits,
         rconfig,
         film,
     );
@@ -547,6 +579,9 @@ pub fn random_sps(
     if sps.vui_parameters_present_flag {
         sps.vui_parameters = VUIParameters::new();
         random_vui(sps, rconfig.random_vui_range, film);
+        if respect_level_limits {
+            apply_level_limits_to_vui(sps);
+        }
     }
 }
 
@@ -688,6 +723,36 @@ pub fn random_pps(
     }
 }
 
+/// Generate a random SPS Extension for the SPS with seq_parameter_set_id
+pub fn random_sps_extension(
+    seq_parameter_set_id: u32,
+    rconfig: &RandomSPSExtensionRange,
+    film: &mut FilmState,
+) -> SPSExtension {
+    let mut res = SPSExtension::new();
+
+    res.seq_parameter_set_id = seq_parameter_set_id;
+    res.aux_format_idc = rconfig.aux_format_idc.sample(film);
+    if res.aux_format_idc != 0 {
+        res.bit_depth_aux_minus8 = rconfig.bit_depth_aux_minus8.sample(film);
+        res.alpha_incr_flag = rconfig.alpha_incr_flag.sample(film);
+
+        // alpha values are u(v) with bit_depth_aux_minus8 + 9 bits
+        let max_alpha_value = 1u32
+            .checked_shl(res.bit_depth_aux_minus8 + 9)
+            .map_or(u32::MAX, |x| x - 1);
+        res.alpha_opaque_value = rconfig
+            .alpha_opaque_value
+            .sample_custom_max(max_alpha_value, film);
+        res.alpha_transparent_value = rconfig
+            .alpha_transparent_value
+            .sample_custom_max(max_alpha_value, film);
+    }
+    res.additional_extension_flag = rconfig.additional_extension_flag.sample(film);
+
+    res
+}
+
 /// Generate a random Subset SPS
 pub fn random_subset_sps(
     subset_sps_idx: usize,
@@ -707,7 +772,7 @@ pub fn random_subset_sps(
         film,
     );
 
-    // TODO: SVC/MVC/3DAVC extensions
+    // TODO: SVC extensions
     if ds.subset_spses[subset_sps_idx].sps.profile_idc == 83
         || ds.subset_spses[subset_sps_idx].sps.profile_idc == 86
     {
@@ -728,20 +793,26 @@ pub fn random_subset_sps(
             rconfig.mvc_vui_parameters_present_flag.sample(film);
 
         if ds.subset_spses[subset_sps_idx].mvc_vui_parameters_present_flag {
-            random_vui_mvc_parameters(subset_sps_idx, rconfig.random_mvc_vui_range, ds, film);
-            // specified in Annex H
+            ds.subset_spses[subset_sps_idx].mvc_vui =
+                random_vui_mvc_parameters(rconfig.random_mvc_vui_range, film); // specified in Annex H
         }
     } else if ds.subset_spses[subset_sps_idx].sps.profile_idc == 138
         || ds.subset_spses[subset_sps_idx].sps.profile_idc == 135
     {
-        //ds.subset_spses[subset_sps_idx].bit_equal_to_one = rconfig.bit_equal_to_one.sample(film);
-        //random_sps_mvcd_extension(&ds.subset_spses[subset_sps_idx].sps_mvcd); // specified in Annex I
-        println!("TODO: random_sps_mvcd_extension");
+        ds.subset_spses[subset_sps_idx].bit_equal_to_one =
+            rconfig.bit_equal_to_one.sample(film) as u8;
+        ds.subset_spses[subset_sps_idx].sps_mvcd =
+            random_sps_mvcd_extension(&rconfig.random_sps_mvcd_range, film); // specified in Annex I
     } else if ds.subset_spses[subset_sps_idx].sps.profile_idc == 139 {
-        //ds.subset_spses[subset_sps_idx].bit_equal_to_one = rconfig.bit_equal_to_one.sample(film);
-        //random_sps_mvcd_extension(&ds.subset_spses[subset_sps_idx].sps_mvcd); // specified in Annex I
-        //random_sps_3davc_extension(&ds.subset_spses[subset_sps_idx].sps_3davc); // specified in Annex J
-        println!("TODO: random_sps_mvcd_extension and random_sps_3davc_extension");
+        ds.subset_spses[subset_sps_idx].bit_equal_to_one =
+            rconfig.bit_equal_to_one.sample(film) as u8;
+        ds.subset_spses[subset_sps_idx].sps_mvcd =
+            random_sps_mvcd_extension(&rconfig.random_sps_mvcd_range, film); // specified in Annex I
+        ds.subset_spses[subset_sps_idx].sps_3davc = random_sps_3davc_extension(
+            &ds.subset_spses[subset_sps_idx].sps_mvcd,
+            &rconfig.random_sps_3davc_range,
+            film,
+        ); // specified in Annex J
     }
 
     let num_additional_extension2_flag = rconfig.num_additional_extension2_flag.sample(film);
@@ -971,153 +1042,563 @@ fn random_sps_mvc_extension(
     }
 }
 
+/// Generate a random SPS MVCD Extension
+fn random_sps_mvcd_extension(
+    rconfig: &RandomSPSMVCDExtensionRange,
+    film: &mut FilmState,
+) -> MVCDSPSExtension {
+    let mut ext = MVCDSPSExtension::new();
+
+    ext.num_views_minus1 = rconfig.num_views_minus1.sample(film);
+
+    for i in 0..=(ext.num_views_minus1 as usize) {
+        ext.view_id.push(rconfig.view_id.sample(film));
+        ext.depth_view_present_flag
+            .push(rconfig.depth_view_present_flag.sample(film));
+        if ext.depth_view_present_flag[i] {
+            ext.depth_view_id.push(ext.view_id[i]);
+            ext.num_depth_views += 1;
+        }
+        ext.texture_view_present_flag
+            .push(rconfig.texture_view_present_flag.sample(film));
+    }
+
+    // 0th index is skipped
+    ext.num_anchor_refs_l0.push(0);
+    ext.num_anchor_refs_l1.push(0);
+    ext.anchor_ref_l0.push(Vec::new());
+    ext.anchor_ref_l1.push(Vec::new());
+    for i in 1..=(ext.num_views_minus1 as usize) {
+        ext.num_anchor_refs_l0.push(0);
+        ext.num_anchor_refs_l1.push(0);
+        ext.anchor_ref_l0.push(Vec::new());
+        ext.anchor_ref_l1.push(Vec::new());
+        if ext.depth_view_present_flag[i] {
+            ext.num_anchor_refs_l0[i] = rconfig.num_anchor_refs_l0.sample(film);
+            for _ in 0..ext.num_anchor_refs_l0[i] {
+                ext.anchor_ref_l0[i].push(rconfig.anchor_ref_l0.sample(film));
+            }
+            ext.num_anchor_refs_l1[i] = rconfig.num_anchor_refs_l1.sample(film);
+            for _ in 0..ext.num_anchor_refs_l1[i] {
+                ext.anchor_ref_l1[i].push(rconfig.anchor_ref_l1.sample(film));
+            }
+        }
+    }
+
+    // 0th index is skipped
+    ext.num_non_anchor_refs_l0.push(0);
+    ext.num_non_anchor_refs_l1.push(0);
+    ext.non_anchor_ref_l0.push(Vec::new());
+    ext.non_anchor_ref_l1.push(Vec::new());
+    for i in 1..=(ext.num_views_minus1 as usize) {
+        ext.num_non_anchor_refs_l0.push(0);
+        ext.num_non_anchor_refs_l1.push(0);
+        ext.non_anchor_ref_l0.push(Vec::new());
+        ext.non_anchor_ref_l1.push(Vec::new());
+        if ext.depth_view_present_flag[i] {
+            ext.num_non_anchor_refs_l0[i] = rconfig.num_non_anchor_refs_l0.sample(film);
+            for _ in 0..ext.num_non_anchor_refs_l0[i] {
+                ext.non_anchor_ref_l0[i].push(rconfig.non_anchor_ref_l0.sample(film));
+            }
+            ext.num_non_anchor_refs_l1[i] = rconfig.num_non_anchor_refs_l1.sample(film);
+            for _ in 0..ext.num_non_anchor_refs_l1[i] {
+                ext.non_anchor_ref_l1[i].push(rconfig.non_anchor_ref_l1.sample(film));
+            }
+        }
+    }
+
+    ext.num_level_values_signalled_minus1 = rconfig.num_level_values_signalled_minus1.sample(film);
+    for i in 0..=(ext.num_level_values_signalled_minus1 as usize) {
+        ext.level_idc.push(rconfig.level_idc.sample(film) as u8);
+        ext.num_applicable_ops_minus1
+            .push(rconfig.num_applicable_ops_minus1.sample(film));
+        ext.applicable_op_temporal_id.push(Vec::new());
+        ext.applicable_op_num_target_views_minus1.push(Vec::new());
+        ext.applicable_op_target_view_id.push(Vec::new());
+        ext.applicable_op_depth_flag.push(Vec::new());
+        ext.applicable_op_texture_flag.push(Vec::new());
+        ext.applicable_op_num_texture_views_minus1.push(Vec::new());
+        ext.applicable_op_num_depth_views.push(Vec::new());
+        for j in 0..=(ext.num_applicable_ops_minus1[i] as usize) {
+            ext.applicable_op_temporal_id[i]
+                .push(rconfig.applicable_op_temporal_id.sample(film) as u8);
+            ext.applicable_op_num_target_views_minus1[i]
+                .push(rconfig.applicable_op_num_target_views_minus1.sample(film));
+            ext.applicable_op_target_view_id[i].push(Vec::new());
+            ext.applicable_op_depth_flag[i].push(Vec::new());
+            ext.applicable_op_texture_flag[i].push(Vec::new());
+            for _ in 0..=ext.applicable_op_num_target_views_minus1[i][j] {
+                ext.applicable_op_target_view_id[i][j]
+                    .push(rconfig.applicable_op_target_view_id.sample(film));
+                ext.applicable_op_depth_flag[i][j]
+                    .push(rconfig.applicable_op_depth_flag.sample(film));
+                ext.applicable_op_texture_flag[i][j]
+                    .push(rconfig.applicable_op_texture_flag.sample(film));
+            }
+            ext.applicable_op_num_texture_views_minus1[i]
+                .push(rconfig.applicable_op_num_texture_views_minus1.sample(film));
+            ext.applicable_op_num_depth_views[i]
+                .push(rconfig.applicable_op_num_depth_views.sample(film));
+        }
+    }
+
+    ext.mvcd_vui_parameters_present_flag = rconfig.mvcd_vui_parameters_present_flag.sample(film);
+    if ext.mvcd_vui_parameters_present_flag {
+        ext.mvcd_vui_parameters = random_vui_mvcd_parameters(&rconfig.random_mvcd_vui_range, film);
+    }
+    ext.texture_vui_parameters_present_flag =
+        rconfig.texture_vui_parameters_present_flag.sample(film);
+    if ext.texture_vui_parameters_present_flag {
+        ext.mvc_vui_parameters_extension =
+            random_vui_mvc_parameters(rconfig.random_texture_vui_range, film);
+    }
+
+    ext
+}
+
+/// Generate a random SPS MVCD VUI Extension
+fn random_vui_mvcd_parameters(
+    rconfig: &RandomVUIMVCDParametersRange,
+    film: &mut FilmState,
+) -> MVCDVUIParameters {
+    let mut vui = MVCDVUIParameters::new();
+
+    vui.vui_mvcd_num_ops_minus1 = rconfig.vui_mvcd_num_ops_minus1.sample(film);
+
+    for i in 0..=(vui.vui_mvcd_num_ops_minus1 as usize) {
+        vui.vui_mvcd_temporal_id
+            .push(rconfig.vui_mvcd_temporal_id.sample(film) as u8);
+        vui.vui_mvcd_num_target_output_views_minus1
+            .push(rconfig.vui_mvcd_num_target_output_views_minus1.sample(film));
+
+        vui.vui_mvcd_view_id.push(Vec::new());
+        vui.vui_mvcd_depth_flag.push(Vec::new());
+        vui.vui_mvcd_texture_flag.push(Vec::new());
+        for _ in 0..=vui.vui_mvcd_num_target_output_views_minus1[i] {
+            vui.vui_mvcd_view_id[i].push(rconfig.vui_mvcd_view_id.sample(film));
+            vui.vui_mvcd_depth_flag[i].push(rconfig.vui_mvcd_depth_flag.sample(film));
+            vui.vui_mvcd_texture_flag[i].push(rconfig.vui_mvcd_texture_flag.sample(film));
+        }
+
+        vui.vui_mvcd_timing_info_present_flag
+            .push(rconfig.vui_mvcd_timing_info_present_flag.sample(film));
+        if vui.vui_mvcd_timing_info_present_flag[i] {
+            vui.vui_mvcd_num_units_in_tick
+                .push(rconfig.vui_mvcd_num_units_in_tick.sample(film));
+            vui.vui_mvcd_time_scale
+                .push(rconfig.vui_mvcd_time_scale.sample(film));
+            vui.vui_mvcd_fixed_frame_rate_flag
+                .push(rconfig.vui_mvcd_fixed_frame_rate_flag.sample(film));
+        } else {
+            vui.vui_mvcd_num_units_in_tick.push(0);
+            vui.vui_mvcd_time_scale.push(0);
+            vui.vui_mvcd_fixed_frame_rate_flag.push(false);
+        }
+
+        vui.vui_mvcd_nal_hrd_parameters_present_flag.push(
+            rconfig
+                .vui_mvcd_nal_hrd_parameters_present_flag
+                .sample(film),
+        );
+        if vui.vui_mvcd_nal_hrd_parameters_present_flag[i] {
+            vui.vui_mvcd_nal_hrd_parameters.push(random_hrd_parameters(
+                rconfig.vui_mvcd_nal_hrd_parameters,
+                film,
+            ));
+        } else {
+            // push empty HRD_parameters
+            vui.vui_mvcd_nal_hrd_parameters.push(HRDParameters::new());
+        }
+
+        vui.vui_mvcd_vcl_hrd_parameters_present_flag.push(
+            rconfig
+                .vui_mvcd_vcl_hrd_parameters_present_flag
+                .sample(film),
+        );
+        if vui.vui_mvcd_vcl_hrd_parameters_present_flag[i] {
+            vui.vui_mvcd_vcl_hrd_parameters.push(random_hrd_parameters(
+                rconfig.vui_mvcd_vcl_hrd_parameters,
+                film,
+            ));
+        } else {
+            // push empty HRD_parameters
+            vui.vui_mvcd_vcl_hrd_parameters.push(HRDParameters::new());
+        }
+
+        if vui.vui_mvcd_nal_hrd_parameters_present_flag[i]
+            || vui.vui_mvcd_vcl_hrd_parameters_present_flag[i]
+        {
+            vui.vui_mvcd_low_delay_hrd_flag
+                .push(rconfig.vui_mvcd_low_delay_hrd_flag.sample(film));
+        } else {
+            vui.vui_mvcd_low_delay_hrd_flag.push(false);
+        }
+
+        vui.vui_mvcd_pic_struct_present_flag
+            .push(rconfig.vui_mvcd_pic_struct_present_flag.sample(film));
+    }
+
+    vui
+}
+
+/// Generate a random SPS 3D-AVC Extension
+fn random_sps_3davc_extension(
+    mvcd: &MVCDSPSExtension,
+    rconfig: &RandomSPS3DAVCExtensionRange,
+    film: &mut FilmState,
+) -> AVC3DSPSExtension {
+    let mut ext = AVC3DSPSExtension::new();
+
+    if mvcd.num_depth_views > 0 {
+        ext.three_dv_acquisition_idc = rconfig.three_dv_acquisition_idc.sample(film);
+        for _ in 0..mvcd.num_depth_views {
+            ext.view_id_3dv.push(rconfig.view_id_3dv.sample(film));
+        }
+
+        if ext.three_dv_acquisition_idc > 0 {
+            ext.depth_ranges = random_depth_ranges(
+                mvcd.num_depth_views,
+                2,
+                &rconfig.random_depth_ranges_range,
+                film,
+            );
+            ext.vsp_param =
+                random_vsp_param(mvcd.num_depth_views, &rconfig.random_vsp_param_range, film);
+        }
+
+        ext.reduced_resolution_flag = rconfig.reduced_resolution_flag.sample(film);
+        if ext.reduced_resolution_flag {
+            ext.depth_pic_width_in_mbs_minus1 = rconfig.depth_pic_width_in_mbs_minus1.sample(film);
+            ext.depth_pic_height_in_map_units_minus1 =
+                rconfig.depth_pic_height_in_map_units_minus1.sample(film);
+            ext.depth_hor_mult_minus1 = rconfig.depth_hor_mult_minus1.sample(film);
+            ext.depth_ver_mult_minus1 = rconfig.depth_ver_mult_minus1.sample(film);
+            ext.depth_hor_rsh = rconfig.depth_hor_rsh.sample(film);
+            ext.depth_ver_rsh = rconfig.depth_ver_rsh.sample(film);
+        }
+
+        ext.depth_frame_cropping_flag = rconfig.depth_frame_cropping_flag.sample(film);
+        if ext.depth_frame_cropping_flag {
+            ext.depth_frame_crop_left_offset = rconfig.depth_frame_crop_left_offset.sample(film);
+            ext.depth_frame_crop_right_offset = rconfig.depth_frame_crop_right_offset.sample(film);
+            ext.depth_frame_crop_top_offset = rconfig.depth_frame_crop_top_offset.sample(film);
+            ext.depth_frame_crop_bottom_offset =
+                rconfig.depth_frame_crop_bottom_offset.sample(film);
+        }
+
+        ext.grid_pos_num_views = rconfig.grid_pos_num_views.sample(film);
+        for _ in 0..ext.grid_pos_num_views {
+            ext.grid_pos_view_id
+                .push(rconfig.grid_pos_view_id.sample(film));
+            ext.grid_pos_x.push(rconfig.grid_pos_x.sample(film));
+            ext.grid_pos_y.push(rconfig.grid_pos_y.sample(film));
+        }
+
+        ext.slice_header_prediction_flag = rconfig.slice_header_prediction_flag.sample(film);
+        ext.seq_view_synthesis_flag = rconfig.seq_view_synthesis_flag.sample(film);
+    }
+
+    ext.alc_sps_enable_flag = rconfig.alc_sps_enable_flag.sample(film);
+    ext.enable_rle_skip_flag = rconfig.enable_rle_skip_flag.sample(film);
+
+    if !mvcd.all_views_paired_flag() {
+        // 0th index is skipped
+        ext.num_anchor_refs_l0.push(0);
+        ext.num_anchor_refs_l1.push(0);
+        ext.anchor_ref_l0.push(Vec::new());
+        ext.anchor_ref_l1.push(Vec::new());
+        for i in 1..=(mvcd.num_views_minus1 as usize) {
+            ext.num_anchor_refs_l0.push(0);
+            ext.num_anchor_refs_l1.push(0);
+            ext.anchor_ref_l0.push(Vec::new());
+            ext.anchor_ref_l1.push(Vec::new());
+            if mvcd.texture_view_present_flag[i] {
+                ext.num_anchor_refs_l0[i] = rconfig.num_anchor_refs_l0.sample(film);
+                for _ in 0..ext.num_anchor_refs_l0[i] {
+                    ext.anchor_ref_l0[i].push(rconfig.anchor_ref_l0.sample(film));
+                }
+                ext.num_anchor_refs_l1[i] = rconfig.num_anchor_refs_l1.sample(film);
+                for _ in 0..ext.num_anchor_refs_l1[i] {
+                    ext.anchor_ref_l1[i].push(rconfig.anchor_ref_l1.sample(film));
+                }
+            }
+        }
+
+        // 0th index is skipped
+        ext.num_non_anchor_refs_l0.push(0);
+        ext.num_non_anchor_refs_l1.push(0);
+        ext.non_anchor_ref_l0.push(Vec::new());
+        ext.non_anchor_ref_l1.push(Vec::new());
+        for i in 1..=(mvcd.num_views_minus1 as usize) {
+            ext.num_non_anchor_refs_l0.push(0);
+            ext.num_non_anchor_refs_l1.push(0);
+            ext.non_anchor_ref_l0.push(Vec::new());
+            ext.non_anchor_ref_l1.push(Vec::new());
+            if mvcd.texture_view_present_flag[i] {
+                ext.num_non_anchor_refs_l0[i] = rconfig.num_non_anchor_refs_l0.sample(film);
+                for _ in 0..ext.num_non_anchor_refs_l0[i] {
+                    ext.non_anchor_ref_l0[i].push(rconfig.non_anchor_ref_l0.sample(film));
+                }
+                ext.num_non_anchor_refs_l1[i] = rconfig.num_non_anchor_refs_l1.sample(film);
+                for _ in 0..ext.num_non_anchor_refs_l1[i] {
+                    ext.non_anchor_ref_l1[i].push(rconfig.non_anchor_ref_l1.sample(film));
+                }
+            }
+        }
+    }
+
+    ext
+}
+
+/// Generate a random Depth Parameter Set
+pub fn random_depth_parameter_set(
+    dps_idx: usize,
+    rconfig: &RandomDepthParameterSetRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    let dps = &mut ds.dpses[dps_idx];
+
+    dps.depth_parameter_set_id = rconfig.depth_parameter_set_id.sample(film);
+    dps.pred_direction = rconfig.pred_direction.sample(film);
+
+    if dps.pred_direction == 0 || dps.pred_direction == 1 {
+        dps.ref_dps_id0 = rconfig.ref_dps_id0.sample(film);
+    }
+    if dps.pred_direction == 0 {
+        dps.ref_dps_id1 = rconfig.ref_dps_id1.sample(film);
+        dps.pred_weight0 = rconfig.pred_weight0.sample(film) as u8;
+    }
+
+    dps.num_depth_views_minus1 = rconfig.num_depth_views_minus1.sample(film);
+    dps.depth_ranges = random_depth_ranges(
+        dps.num_depth_views_minus1 + 1,
+        dps.pred_direction,
+        &rconfig.random_depth_ranges_range,
+        film,
+    );
+
+    dps.vsp_param_flag = rconfig.vsp_param_flag.sample(film);
+    if dps.vsp_param_flag {
+        dps.vsp_param = random_vsp_param(
+            dps.num_depth_views_minus1 + 1,
+            &rconfig.random_vsp_param_range,
+            film,
+        );
+    }
+
+    dps.depth_param_additional_extension_flag =
+        rconfig.depth_param_additional_extension_flag.sample(film);
+
+    dps.nonlinear_depth_representation_num =
+        rconfig.nonlinear_depth_representation_num.sample(film);
+    for _ in 1..=dps.nonlinear_depth_representation_num {
+        dps.nonlinear_depth_representation_model
+            .push(rconfig.nonlinear_depth_representation_model.sample(film));
+    }
+
+    if dps.depth_param_additional_extension_flag {
+        let num_data_flags = rconfig
+            .num_depth_param_additional_extension_data_flag
+            .sample(film);
+        for _ in 0..num_data_flags {
+            dps.depth_param_additional_extension_data_flag.push(
+                rconfig
+                    .depth_param_additional_extension_data_flag
+                    .sample(film),
+            );
+        }
+    }
+}
+
+/// Generate random depth ranges
+fn random_depth_ranges(
+    num_views: u32,
+    pred_direction: u32,
+    rconfig: &RandomDepthRangesRange,
+    film: &mut FilmState,
+) -> DepthRanges {
+    let mut dr = DepthRanges::new();
+
+    dr.z_near_flag = rconfig.z_near_flag.sample(film);
+    dr.z_far_flag = rconfig.z_far_flag.sample(film);
+
+    if dr.z_near_flag {
+        dr.z_near = random_3dv_acquisition_element(num_views, pred_direction, 7, rconfig, film);
+    }
+    if dr.z_far_flag {
+        dr.z_far = random_3dv_acquisition_element(num_views, pred_direction, 7, rconfig, film);
+    }
+
+    dr
+}
+
+/// Generate a random 3DV acquisition element
+fn random_3dv_acquisition_element(
+    num_views: u32,
+    pred_direction: u32,
+    exp_len: u32,
+    rconfig: &RandomDepthRangesRange,
+    film: &mut FilmState,
+) -> AcquisitionElement3DV {
+    let mut ae = AcquisitionElement3DV::new();
+
+    if num_views > 1 {
+        ae.element_equal_flag = rconfig.element_equal_flag.sample(film);
+    }
+
+    // the exponents and mantissas are u(v) so must fit in their bit lengths
+    let max_exponent = ((1u64 << exp_len) - 1) as u32;
+
+    for i in 0..(ae.num_values(num_views) as usize) {
+        if pred_direction == 2 && i == 0 {
+            ae.mantissa_len_minus1 = rconfig.mantissa_len_minus1.sample(film) as u8;
+        }
+
+        if pred_direction == 2 {
+            let max_mantissa = ((1u64 << (ae.mantissa_len_minus1 as u64 + 1)) - 1) as u32;
+            ae.sign0.push(rconfig.sign.sample(film));
+            ae.exponent0
+                .push(rconfig.exponent.sample_custom_max(max_exponent, film));
+            ae.mantissa0
+                .push(rconfig.mantissa.sample_custom_max(max_mantissa, film));
+            ae.skip_flag.push(false);
+            ae.sign1.push(false);
+            ae.exponent_skip_flag.push(false);
+            ae.exponent1.push(0);
+            ae.mantissa_diff.push(0);
+        } else {
+            ae.sign0.push(false);
+            ae.exponent0.push(0);
+            ae.mantissa0.push(0);
+            ae.skip_flag.push(rconfig.skip_flag.sample(film));
+            if ae.skip_flag[i] {
+                ae.sign1.push(false);
+                ae.exponent_skip_flag.push(false);
+                ae.exponent1.push(0);
+                ae.mantissa_diff.push(0);
+            } else {
+                ae.sign1.push(rconfig.sign.sample(film));
+                ae.exponent_skip_flag
+                    .push(rconfig.exponent_skip_flag.sample(film));
+                if ae.exponent_skip_flag[i] {
+                    ae.exponent1.push(0);
+                } else {
+                    ae.exponent1
+                        .push(rconfig.exponent.sample_custom_max(max_exponent, film));
+                }
+                ae.mantissa_diff.push(rconfig.mantissa_diff.sample(film));
+            }
+        }
+    }
+
+    ae
+}
+
+/// Generate random view synthesis prediction parameters
+fn random_vsp_param(
+    num_views: u32,
+    rconfig: &RandomVSPParamRange,
+    film: &mut FilmState,
+) -> VSPParam {
+    let mut vsp = VSPParam::new();
+
+    for i in 0..(num_views as usize) {
+        vsp.disparity_diff_wji.push(Vec::new());
+        vsp.disparity_diff_oji.push(Vec::new());
+        vsp.disparity_diff_wij.push(Vec::new());
+        vsp.disparity_diff_oij.push(Vec::new());
+        for _ in 0..i {
+            vsp.disparity_diff_wji[i].push(rconfig.disparity_diff_wji.sample(film));
+            vsp.disparity_diff_oji[i].push(rconfig.disparity_diff_oji.sample(film));
+            vsp.disparity_diff_wij[i].push(rconfig.disparity_diff_wij.sample(film));
+            vsp.disparity_diff_oij[i].push(rconfig.disparity_diff_oij.sample(film));
+        }
+    }
+
+    vsp
+}
+
 /// Generate a random SPS MVC VUI Extension
 fn random_vui_mvc_parameters(
-    subset_sps_idx: usize,
     rconfig: RandomVUIMVCParametersRange,
-    ds: &mut H264DecodedStream,
     film: &mut FilmState,
-) {
-    ds.subset_spses[subset_sps_idx]
-        .mvc_vui
-        .vui_mvc_num_ops_minus1 = rconfig.vui_mvc_num_ops_minus1.sample(film);
+) -> MVCVUIParameters {
+    let mut vui = MVCVUIParameters::new();
 
-    for i in 0..=(ds.subset_spses[subset_sps_idx]
-        .mvc_vui
-        .vui_mvc_num_ops_minus1 as usize)
-    {
-        ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_temporal_id
+    vui.vui_mvc_num_ops_minus1 = rconfig.vui_mvc_num_ops_minus1.sample(film);
+
+    for i in 0..=(vui.vui_mvc_num_ops_minus1 as usize) {
+        vui.vui_mvc_temporal_id
             .push(rconfig.vui_mvc_temporal_id.sample(film) as u8);
 
-        ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_num_target_output_views_minus1
+        vui.vui_mvc_num_target_output_views_minus1
             .push(rconfig.vui_mvc_num_target_output_views_minus1.sample(film));
 
-        ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_view_id
-            .push(Vec::new());
-        for _ in 0..=(ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_num_target_output_views_minus1[i] as usize)
-        {
-            ds.subset_spses[subset_sps_idx].mvc_vui.vui_mvc_view_id[i]
-                .push(rconfig.vui_mvc_view_id.sample(film));
+        vui.vui_mvc_view_id.push(Vec::new());
+        for _ in 0..=(vui.vui_mvc_num_target_output_views_minus1[i] as usize) {
+            vui.vui_mvc_view_id[i].push(rconfig.vui_mvc_view_id.sample(film));
         }
 
-        ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_timing_info_present_flag
+        vui.vui_mvc_timing_info_present_flag
             .push(rconfig.vui_mvc_timing_info_present_flag.sample(film));
 
-        if ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_timing_info_present_flag[i]
-        {
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_num_units_in_tick
+        if vui.vui_mvc_timing_info_present_flag[i] {
+            vui.vui_mvc_num_units_in_tick
                 .push(rconfig.vui_mvc_num_units_in_tick.sample(film));
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_time_scale
+            vui.vui_mvc_time_scale
                 .push(rconfig.vui_mvc_time_scale.sample(film));
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_fixed_frame_rate_flag
+            vui.vui_mvc_fixed_frame_rate_flag
                 .push(rconfig.vui_mvc_fixed_frame_rate_flag.sample(film));
         } else {
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_num_units_in_tick
-                .push(0);
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_time_scale
-                .push(0);
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_fixed_frame_rate_flag
-                .push(false);
+            vui.vui_mvc_num_units_in_tick.push(0);
+            vui.vui_mvc_time_scale.push(0);
+            vui.vui_mvc_fixed_frame_rate_flag.push(false);
         }
 
-        ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_nal_hrd_parameters_present_flag
+        vui.vui_mvc_nal_hrd_parameters_present_flag
             .push(rconfig.vui_mvc_nal_hrd_parameters_present_flag.sample(film));
-        if ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_nal_hrd_parameters_present_flag[i]
-        {
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_nal_hrd_parameters
-                .push(random_hrd_parameters(
-                    rconfig.vui_mvc_nal_hrd_parameters,
-                    film,
-                ));
+        if vui.vui_mvc_nal_hrd_parameters_present_flag[i] {
+            vui.vui_mvc_nal_hrd_parameters.push(random_hrd_parameters(
+                rconfig.vui_mvc_nal_hrd_parameters,
+                film,
+            ));
         } else {
             // push empty HRD_parameters
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_nal_hrd_parameters
-                .push(HRDParameters::new());
+            vui.vui_mvc_nal_hrd_parameters.push(HRDParameters::new());
         }
 
-        ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_vcl_hrd_parameters_present_flag
+        vui.vui_mvc_vcl_hrd_parameters_present_flag
             .push(rconfig.vui_mvc_vcl_hrd_parameters_present_flag.sample(film));
-        if ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_vcl_hrd_parameters_present_flag[i]
-        {
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_vcl_hrd_parameters
-                .push(random_hrd_parameters(
-                    rconfig.vui_mvc_vcl_hrd_parameters,
-                    film,
-                ));
+        if vui.vui_mvc_vcl_hrd_parameters_present_flag[i] {
+            vui.vui_mvc_vcl_hrd_parameters.push(random_hrd_parameters(
+                rconfig.vui_mvc_vcl_hrd_parameters,
+                film,
+            ));
         } else {
             // push empty HRD_parameters
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_vcl_hrd_parameters
-                .push(HRDParameters::new());
+            vui.vui_mvc_vcl_hrd_parameters.push(HRDParameters::new());
         }
 
-        if ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_nal_hrd_parameters_present_flag[i]
-            || ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_vcl_hrd_parameters_present_flag[i]
+        if vui.vui_mvc_nal_hrd_parameters_present_flag[i]
+            || vui.vui_mvc_vcl_hrd_parameters_present_flag[i]
         {
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_low_delay_hrd_flag
+            vui.vui_mvc_low_delay_hrd_flag
                 .push(rconfig.vui_mvc_low_delay_hrd_flag.sample(film));
         } else {
             // push false
-            ds.subset_spses[subset_sps_idx]
-                .mvc_vui
-                .vui_mvc_low_delay_hrd_flag
-                .push(false);
+            vui.vui_mvc_low_delay_hrd_flag.push(false);
         }
 
-        ds.subset_spses[subset_sps_idx]
-            .mvc_vui
-            .vui_mvc_pic_struct_present_flag
+        vui.vui_mvc_pic_struct_present_flag
             .push(rconfig.vui_mvc_pic_struct_present_flag.sample(film));
     }
+
+    vui
 }
 
 /// Generate a random HRD parameters
-fn random_hrd_parameters(rconfig: RandomHRDRange, film: &mut FilmState) -> HRDParameters {
+pub fn random_hrd_parameters(rconfig: RandomHRDRange, film: &mut FilmState) -> HRDParameters {
     let mut hrd_param = HRDParameters::new();
 
     hrd_param.cpb_cnt_minus1 = rconfig.cpb_cnt_minus1.sample(film);
@@ -1,22 +1,148 @@
 //! SEI syntax element randomization.
 
 use crate::common::data_structures::H264DecodedStream;
+use crate::common::data_structures::HRDParameters;
+use crate::common::data_structures::SEIAlternativeDepthInfo;
+use crate::common::data_structures::SEIAlternativeTransferCharacteristics;
 use crate::common::data_structures::SEIBufferingPeriod;
+use crate::common::data_structures::SEIColourRemappingInfo;
+use crate::common::data_structures::SEIConstrainedDepthParameterSetIdentifier;
+use crate::common::data_structures::SEIContentColourVolume;
+use crate::common::data_structures::SEIContentLightLevelInfo;
+use crate::common::data_structures::SEICubemapProjection;
+use crate::common::data_structures::SEIDecRefPicMarkingRepetition;
+use crate::common::data_structures::SEIDepthGridPosition;
+use crate::common::data_structures::SEIDepthRepresentationElement;
+use crate::common::data_structures::SEIDepthRepresentationInfo;
+use crate::common::data_structures::SEIDepthSamplingInfo;
+use crate::common::data_structures::SEIDepthTiming;
+use crate::common::data_structures::SEIDepthTimingOffset;
+use crate::common::data_structures::SEIDisplayOrientation;
+use crate::common::data_structures::SEIEquirectangularProjection;
+use crate::common::data_structures::SEIFillerPayload;
 use crate::common::data_structures::SEIFilmGrainCharacteristics;
+use crate::common::data_structures::SEIFloatValue;
+use crate::common::data_structures::SEIFramePacking;
+use crate::common::data_structures::SEIFullFrameFreeze;
+use crate::common::data_structures::SEIFullFrameSnapshot;
+use crate::common::data_structures::SEILayerDependencyChange;
+use crate::common::data_structures::SEILayersNotPresent;
+use crate::common::data_structures::SEIMVCScalableNesting;
+use crate::common::data_structures::SEIMasteringDisplayColourVolume;
+use crate::common::data_structures::SEIMultiviewAcquisitionInfo;
+use crate::common::data_structures::SEIMultiviewSceneInfo;
+use crate::common::data_structures::SEIMultiviewViewPosition;
+use crate::common::data_structures::SEINalu;
+use crate::common::data_structures::SEINonRequiredLayerRep;
+use crate::common::data_structures::SEINonRequiredViewComponent;
+use crate::common::data_structures::SEIOmniViewport;
+use crate::common::data_structures::SEIOperationPointsNotPresent;
+use crate::common::data_structures::SEIPanScanRect;
+use crate::common::data_structures::SEIParallelDecodingInfo;
 use crate::common::data_structures::SEIPayload;
 use crate::common::data_structures::SEIPicTiming;
+use crate::common::data_structures::SEIPriorityLayerInfo;
+use crate::common::data_structures::SEIProgressiveRefinementSegmentEnd;
+use crate::common::data_structures::SEIProgressiveRefinementSegmentStart;
+use crate::common::data_structures::SEIQualityLayerIntegrityCheck;
 use crate::common::data_structures::SEIRecoveryPoint;
+use crate::common::data_structures::SEIRedundantPicProperty;
+use crate::common::data_structures::SEIRegionwisePacking;
+use crate::common::data_structures::SEIScalabilityInfo;
+use crate::common::data_structures::SEIScalableNesting;
+use crate::common::data_structures::SEISceneInfo;
+use crate::common::data_structures::SEISparePic;
+use crate::common::data_structures::SEISphereRotation;
+use crate::common::data_structures::SEISubPicScalableLayer;
+use crate::common::data_structures::SEISubSeqCharacteristics;
+use crate::common::data_structures::SEISubSeqInfo;
+use crate::common::data_structures::SEISubSeqLayerCharacteristics;
+use crate::common::data_structures::SEITemporalHRD;
+use crate::common::data_structures::SEIThreeDimensionalReferenceDisplaysInfo;
+use crate::common::data_structures::SEITl0DepRepIndex;
+use crate::common::data_structures::SEITlSwitchingPoint;
+use crate::common::data_structures::SEIToneMappingInfo;
+use crate::common::data_structures::SEIUserDataRegisteredITUTT35;
 use crate::common::data_structures::SEIUserDataUnregistered;
+use crate::common::data_structures::SEIViewDependencyChange;
+use crate::common::data_structures::SEIViewScalabilityInfo;
+use crate::common::data_structures::SeqParameterSet;
+use crate::common::data_structures::ATSC_USER_DATA_TYPE_CC_DATA;
+use crate::common::data_structures::ATSC_USER_IDENTIFIER_GA94;
+use crate::common::data_structures::ITU_T_T35_COUNTRY_CODE_US;
+use crate::common::data_structures::ITU_T_T35_PROVIDER_CODE_ATSC;
+use crate::common::data_structures::KNOWN_UUIDS;
 use crate::common::data_structures::UUID_APPLE1;
 use crate::common::data_structures::UUID_APPLE2;
 use crate::common::data_structures::UUID_APPLE3;
+use crate::common::helper::sei_mantissa_len;
 use crate::vidgen::film::FilmState;
+use crate::vidgen::generate_configurations::RandomBoolRange;
+use crate::vidgen::generate_configurations::RandomSEIAlternativeDepthInfoRange;
+use crate::vidgen::generate_configurations::RandomSEIAlternativeTransferCharacteristicsRange;
 use crate::vidgen::generate_configurations::RandomSEIBufferingPeriodRange;
+use crate::vidgen::generate_configurations::RandomSEIColourRemappingInfoRange;
+use crate::vidgen::generate_configurations::RandomSEIConstrainedDepthParameterSetIdentifierRange;
+use crate::vidgen::generate_configurations::RandomSEIContentColourVolumeRange;
+use crate::vidgen::generate_configurations::RandomSEIContentLightLevelInfoRange;
+use crate::vidgen::generate_configurations::RandomSEICubemapProjectionRange;
+use crate::vidgen::generate_configurations::RandomSEIDecRefPicMarkingRepetitionRange;
+use crate::vidgen::generate_configurations::RandomSEIDepthRepresentationElementRange;
+use crate::vidgen::generate_configurations::RandomSEIDepthRepresentationInfoRange;
+use crate::vidgen::generate_configurations::RandomSEIDepthSamplingInfoRange;
+use crate::vidgen::generate_configurations::RandomSEIDepthTimingRange;
+use crate::vidgen::generate_configurations::RandomSEIDisplayOrientationRange;
+use crate::vidgen::generate_configurations::RandomSEIEquirectangularProjectionRange;
+use crate::vidgen::generate_configurations::RandomSEIFillerPayloadRange;
 use crate::vidgen::generate_configurations::RandomSEIFilmGrainCharacteristicsRange;
+use crate::vidgen::generate_configurations::RandomSEIFloatValueRange;
+use crate::vidgen::generate_configurations::RandomSEIFrameFreezeRefinementRange;
+use crate::vidgen::generate_configurations::RandomSEIFramePackingRange;
+use crate::vidgen::generate_configurations::RandomSEILayerDependencyChangeRange;
+use crate::vidgen::generate_configurations::RandomSEILayersNotPresentRange;
+use crate::vidgen::generate_configurations::RandomSEIMasteringDisplayColourVolumeRange;
+use crate::vidgen::generate_configurations::RandomSEIMultiviewAcquisitionInfoRange;
+use crate::vidgen::generate_configurations::RandomSEIMultiviewSceneInfoRange;
+use crate::vidgen::generate_configurations::RandomSEIMultiviewViewPositionRange;
+use crate::vidgen::generate_configurations::RandomSEINonRequiredLayerRepRange;
+use crate::vidgen::generate_configurations::RandomSEINonRequiredViewComponentRange;
+use crate::vidgen::generate_configurations::RandomSEIOmniViewportRange;
+use crate::vidgen::generate_configurations::RandomSEIOperationPointsNotPresentRange;
+use crate::vidgen::generate_configurations::RandomSEIPanScanRectRange;
+use crate::vidgen::generate_configurations::RandomSEIParallelDecodingInfoRange;
 use crate::vidgen::generate_configurations::RandomSEIPicTimingRange;
+use crate::vidgen::generate_configurations::RandomSEIPriorityLayerInfoRange;
+use crate::vidgen::generate_configurations::RandomSEIQualityLayerIntegrityCheckRange;
 use crate::vidgen::generate_configurations::RandomSEIRange;
 use crate::vidgen::generate_configurations::RandomSEIRecoveryPointRange;
+use crate::vidgen::generate_configurations::RandomSEIRedundantPicPropertyRange;
+use crate::vidgen::generate_configurations::RandomSEIRegionwisePackingRange;
+use crate::vidgen::generate_configurations::RandomSEIScalabilityInfoRange;
+use crate::vidgen::generate_configurations::RandomSEISceneInfoRange;
+use crate::vidgen::generate_configurations::RandomSEISparePicRange;
+use crate::vidgen::generate_configurations::RandomSEISphereRotationRange;
+use crate::vidgen::generate_configurations::RandomSEISubPicScalableLayerRange;
+use crate::vidgen::generate_configurations::RandomSEISubSeqCharacteristicsRange;
+use crate::vidgen::generate_configurations::RandomSEISubSeqInfoRange;
+use crate::vidgen::generate_configurations::RandomSEISubSeqLayerCharacteristicsRange;
+use crate::vidgen::generate_configurations::RandomSEITemporalHRDRange;
+use crate::vidgen::generate_configurations::RandomSEIThreeDimensionalReferenceDisplaysInfoRange;
+use crate::vidgen::generate_configurations::RandomSEITl0DepRepIndexRange;
+use crate::vidgen::generate_configurations::RandomSEITlSwitchingPointRange;
+use crate::vidgen::generate_configurations::RandomSEIToneMappingInfoRange;
+use crate::vidgen::generate_configurations::RandomSEIUserDataRegisteredITUTT35Range;
 use crate::vidgen::generate_configurations::RandomSEIUserDataUnregisteredRange;
+use crate::vidgen::generate_configurations::RandomSEIViewDependencyChangeRange;
+use crate::vidgen::generate_configurations::RandomSEIViewScalabilityInfoRange;
+use crate::vidgen::generate_configurations::RandomU32Enum;
+use crate::vidgen::generate_configurations::RandomU32Range;
+use crate::vidgen::generate_configurations::UserDataFieldType;
+use crate::vidgen::generate_configurations::UserDataUnregisteredSchema;
+use crate::vidgen::parameter_sets::random_hrd_parameters;
+
+/// Scalable nesting SEIs may themselves carry nesting SEIs, so we bound how
+/// deep the generated messages can go
+const MAX_SEI_NESTING_DEPTH: usize = 2;
 
 /// Generate a random SEI NALU
 pub fn random_sei(
@@ -29,7 +155,7 @@ pub fn random_sei(
     for _ in 0..num_seis {
         let payload_type = rconfig.payload_type.sample(film);
         ds.seis[sei_idx].payload_type.push(payload_type);
-        let sei_payload = random_sei_payload(payload_type, rconfig, ds, film);
+        let sei_payload = random_sei_payload(payload_type, rconfig, ds, film, 0);
         ds.seis[sei_idx].payload.push(sei_payload);
         ds.seis[sei_idx].payload_size.push(0); // the encoder will properly put the size
     }
@@ -41,6 +167,7 @@ fn random_sei_payload(
     rconfig: &RandomSEIRange,
     ds: &mut H264DecodedStream,
     film: &mut FilmState,
+    nesting_depth: usize,
 ) -> SEIPayload {
     let mut sei_payload = SEIPayload::new();
     match payload_type {
@@ -52,218 +179,319 @@ fn random_sei_payload(
 
         1 => {
             sei_payload.pic_timing = random_pic_timing(rconfig.random_pic_timing_range, ds, film);
-        } /*
+        }
         2 => {
-        // pan scan rect
-        random_pan_scan_rect();
-        },
+            // pan scan rect
+            sei_payload.pan_scan_rect =
+                random_pan_scan_rect(rconfig.random_pan_scan_rect_range, film);
+        }
         3 => {
-        // filler payload
-        random_filler_payload();
-        },
+            // filler payload
+            sei_payload.filler_payload =
+                random_filler_payload(rconfig.random_filler_payload_range, film);
+        }
         4 => {
-        // user data registered ITU T T35
-        random_user_data_registered_itu_t_t35();
-        }, */
+            sei_payload.registered_user_data = random_user_data_registered_itu_t_t35(
+                rconfig.random_user_data_registered_itu_t_t35_range,
+                film,
+            );
+        }
         5 => {
             sei_payload.unregistered_user_data =
-                random_user_data_unregistered(rconfig.random_user_data_unregistered_range, film);
+                random_user_data_unregistered(&rconfig.random_user_data_unregistered_range, film);
         }
         6 => {
             // recovery point
             sei_payload.recovery_point =
                 random_recovery_point(rconfig.random_recovery_point_range, film);
-        } /*
+        }
         7 => {
-        // pic marking repetition
-        random_ref_pic_marking_repetition();
-        },
+            // pic marking repetition
+            sei_payload.dec_ref_pic_marking_repetition = random_ref_pic_marking_repetition(
+                rconfig.random_dec_ref_pic_marking_repetition_range,
+                ds,
+                film,
+            );
+        }
         8 => {
-        // spare pic
-        random_spare_pic();
-        },
+            // spare pic
+            sei_payload.spare_pic = random_spare_pic(rconfig.random_spare_pic_range, ds, film);
+        }
         9 => {
-        // scene info
-        random_scene_info();
-        },
+            // scene info
+            sei_payload.scene_info = random_scene_info(rconfig.random_scene_info_range, film);
+        }
         10 => {
-        // sub seq info
-        random_sub_seq_info();
-        },
+            // sub seq info
+            sei_payload.sub_seq_info = random_sub_seq_info(rconfig.random_sub_seq_info_range, film);
+        }
         11 => {
-        // sub seq layer characteristics
-        random_sub_seq_layer_characteristics();
-        },
+            // sub seq layer characteristics
+            sei_payload.sub_seq_layer_characteristics =
+                random_sub_seq_layer_characteristics(rconfig.random_sub_seq_layer_char_range, film);
+        }
         12 => {
-        // sub seq characteristics
-        random_sub_seq_characteristics();
-        },
+            // sub seq characteristics
+            sei_payload.sub_seq_characteristics =
+                random_sub_seq_characteristics(rconfig.random_sub_seq_char_range, film);
+        }
         13 => {
-        // full_frame_freeze
-        random_full_frame_freeze();
-        },
+            // full_frame_freeze
+            sei_payload.full_frame_freeze =
+                random_full_frame_freeze(rconfig.random_frame_freeze_refinement_range, film);
+        }
         14 => {
-        random_full_frame_freeze_release();
-        },
+            // full_frame_freeze_release has an empty payload
+        }
         15 => {
-        random_full_frame_snapshot();
-        },
+            sei_payload.full_frame_snapshot =
+                random_full_frame_snapshot(rconfig.random_frame_freeze_refinement_range, film);
+        }
         16 => {
-        random_progressive_refinement_segment_start();
-        },
+            sei_payload.progressive_refinement_segment_start =
+                random_progressive_refinement_segment_start(
+                    rconfig.random_frame_freeze_refinement_range,
+                    film,
+                );
+        }
         17 => {
-        random_progressive_refinement_segment_end();
-        },
+            sei_payload.progressive_refinement_segment_end =
+                random_progressive_refinement_segment_end(
+                    rconfig.random_frame_freeze_refinement_range,
+                    film,
+                );
+        } /*
         18 => {
         random_motion_constrained_slice_group_set();
         }, */
         19 => {
             sei_payload.film_grain_characteristics =
                 random_film_grain_characteristics(rconfig.random_film_grain_char_range, film);
-        } /*
-        20 => {
-        random_deblocking_filter_display_preference();
-        },
-        21 => {
-        random_stereo_video_info();
-        },
-        22 => {
-        random_post_filter_hint();
-        },
+        }
         23 => {
-        random_tone_mapping_info();
-        },
+            sei_payload.tone_mapping_info =
+                random_tone_mapping_info(rconfig.random_tone_mapping_info_range, film);
+        }
         24 => {
-        random_scalability_info(); // specified in Annex G
-        },
+            sei_payload.scalability_info =
+                random_scalability_info(rconfig.random_scalability_info_range, film);
+        }
         25 => {
-        random_sub_pic_scalable_layer(); // specified in Annex G
-        },
+            sei_payload.sub_pic_scalable_layer =
+                random_sub_pic_scalable_layer(rconfig.random_sub_pic_scalable_layer_range, film);
+        }
         26 => {
-        random_non_required_layer_rep(); // specified in Annex G
-        },
+            sei_payload.non_required_layer_rep =
+                random_non_required_layer_rep(rconfig.random_non_required_layer_rep_range, film);
+        }
         27 => {
-        random_priority_layer_info(); // specified in Annex G
-        },
+            sei_payload.priority_layer_info =
+                random_priority_layer_info(rconfig.random_priority_layer_info_range, film);
+        }
         28 => {
-        random_layers_not_present(); // specified in Annex G
-        },
+            sei_payload.layers_not_present =
+                random_layers_not_present(rconfig.random_layers_not_present_range, film);
+        }
         29 => {
-        random_layer_dependency_change(); // specified in Annex G
-        },
+            sei_payload.layer_dependency_change =
+                random_layer_dependency_change(rconfig.random_layer_dependency_change_range, film);
+        }
         30 => {
-        random_scalable_nesting(); // specified in Annex G
-        },
+            sei_payload.scalable_nesting =
+                random_scalable_nesting(nesting_depth, rconfig, ds, film);
+        }
         31 => {
-        random_base_layer_temporal_hrd(); // specified in Annex G
-        },
+            sei_payload.base_layer_temporal_hrd =
+                random_temporal_hrd(rconfig.random_base_layer_temporal_hrd_range, film);
+        }
         32 => {
-        random_quality_layer_integrity_check(); // specified in Annex G
-        },
+            sei_payload.quality_layer_integrity_check = random_quality_layer_integrity_check(
+                rconfig.random_quality_layer_integrity_check_range,
+                film,
+            );
+        }
         33 => {
-        random_redundant_pic_property(); // specified in Annex G
-        },
+            sei_payload.redundant_pic_property =
+                random_redundant_pic_property(rconfig.random_redundant_pic_property_range, film);
+        }
         34 => {
-        random_tl0_dep_rep_index(); // specified in Annex G
-        },
+            sei_payload.tl0_dep_rep_index =
+                random_tl0_dep_rep_index(rconfig.random_tl0_dep_rep_index_range, film);
+        }
         35 => {
-        random_tl_switching_point(); // specified in Annex G
-        },
+            sei_payload.tl_switching_point =
+                random_tl_switching_point(rconfig.random_tl_switching_point_range, film);
+        }
         36 => {
-        random_parallel_decoding_info(); // specified in Annex H
-        },
+            sei_payload.parallel_decoding_info = random_parallel_decoding_info(
+                rconfig.random_parallel_decoding_info_range,
+                ds,
+                film,
+            );
+        }
         37 => {
-        random_mvc_scalable_nesting(); // specified in Annex H
-        },
+            sei_payload.mvc_scalable_nesting =
+                random_mvc_scalable_nesting(false, nesting_depth, rconfig, ds, film);
+        }
         38 => {
-        random_view_scalability_info(); // specified in Annex H
-        },
+            sei_payload.view_scalability_info = random_view_scalability_info(
+                false,
+                rconfig.random_view_scalability_info_range,
+                film,
+            );
+        }
         39 => {
-        random_multiview_scene_info(); // specified in Annex H
-        },
+            sei_payload.multiview_scene_info =
+                random_multiview_scene_info(rconfig.random_multiview_scene_info_range, film);
+        }
         40 => {
-        random_multiview_acquisition_info(); // specified in Annex H
-        },
+            sei_payload.multiview_acquisition_info = random_multiview_acquisition_info(
+                rconfig.random_multiview_acquisition_info_range,
+                film,
+            );
+        }
         41 => {
-        random_non_required_view_component(); // specified in Annex H
-        },
+            sei_payload.non_required_view_component = random_non_required_view_component(
+                rconfig.random_non_required_view_component_range,
+                film,
+            );
+        }
         42 => {
-        random_view_dependency_change(); // specified in Annex H
-        },
+            sei_payload.view_dependency_change = random_view_dependency_change(
+                rconfig.random_view_dependency_change_range,
+                ds,
+                film,
+            );
+        }
         43 => {
-        random_operation_points_not_present(); // specified in Annex H
-        },
+            sei_payload.operation_points_not_present = random_operation_points_not_present(
+                rconfig.random_operation_points_not_present_range,
+                film,
+            );
+        }
         44 => {
-        random_base_view_temporal_hrd(); // specified in Annex H
-        },
+            sei_payload.base_view_temporal_hrd =
+                random_temporal_hrd(rconfig.random_base_view_temporal_hrd_range, film);
+        }
         45 => {
-        random_frame_packing_arrangement();
-        },
+            sei_payload.frame_packing =
+                random_frame_packing_arrangement(rconfig.random_frame_packing_range, film);
+        }
         46 => {
-        random_multiview_view_position(); // specified in Annex H
-        },
+            sei_payload.multiview_view_position =
+                random_multiview_view_position(rconfig.random_multiview_view_position_range, film);
+        }
         47 => {
-        random_display_orientation();
-        },
+            sei_payload.display_orientation =
+                random_display_orientation(rconfig.random_display_orientation_range, film);
+        }
         48 => {
-        random_mvcd_scalable_nesting(); // specified in Annex I
+            sei_payload.mvcd_scalable_nesting =
+                random_mvc_scalable_nesting(true, nesting_depth, rconfig, ds, film);
         }
         49 => {
-        random_mvcd_view_scalability_info(); // specified in Annex I
-        },
+            sei_payload.mvcd_view_scalability_info = random_view_scalability_info(
+                true,
+                rconfig.random_mvcd_view_scalability_info_range,
+                film,
+            );
+        }
         50 => {
-        random_depth_representation_info(); // specified in Annex I
-        },
+            sei_payload.depth_representation_info = random_depth_representation_info(
+                rconfig.random_depth_representation_info_range,
+                film,
+            );
+        }
         51 => {
-        random_three_dimensional_reference_displays_info(); // specified in Annex I
-        },
+            sei_payload.three_dimensional_reference_displays_info =
+                random_three_dimensional_reference_displays_info(
+                    rconfig.random_three_dimensional_reference_displays_info_range,
+                    film,
+                );
+        }
         52 => {
-        random_depth_timing(); // specified in Annex I
-        },
+            sei_payload.depth_timing =
+                random_depth_timing(rconfig.random_depth_timing_range, ds, film);
+        }
         53 => {
-        random_depth_sampling_info(); // specified in Annex I
-        },
+            sei_payload.depth_sampling_info =
+                random_depth_sampling_info(rconfig.random_depth_sampling_info_range, film);
+        }
         54 => {
-        random_constrained_depth_parameter_set_identifier(); // specified in Annex J
-        },
-        56 => {
-        random_green_metadata(); // specified in ISO/IEC 23001-11
-        },
+            sei_payload.constrained_depth_parameter_set_identifier =
+                random_constrained_depth_parameter_set_identifier(
+                    rconfig.random_constrained_depth_parameter_set_identifier_range,
+                    film,
+                );
+        }
         137 => {
-        random_mastering_display_colour_volume();
-        },
+            sei_payload.mastering_display_colour_volume = random_mastering_display_colour_volume(
+                rconfig.random_mastering_display_colour_volume_range,
+                film,
+            );
+        }
         142 => {
-        random_colour_remapping_info();
-        },
+            sei_payload.colour_remapping_info =
+                random_colour_remapping_info(rconfig.random_colour_remapping_info_range, film);
+        }
         144 => {
-        random_content_light_level_info();
-        },
+            sei_payload.content_light_level_info = random_content_light_level_info(
+                rconfig.random_content_light_level_info_range,
+                film,
+            );
+        }
         147 => {
-        random_alternative_transfer_characteristics();
-        },
-        148 => {
-        random_ambient_viewing_environment();
-        },
+            sei_payload.alternative_transfer_characteristics =
+                random_alternative_transfer_characteristics(
+                    rconfig.random_alternative_transfer_characteristics_range,
+                    film,
+                );
+        }
         149 => {
-        random_content_colour_volume();
-        },
+            sei_payload.content_colour_volume =
+                random_content_colour_volume(rconfig.random_content_colour_volume_range, film);
+        }
         150 => {
-        random_equirectangular_projection();
-        },
+            sei_payload.equirectangular_projection = random_equirectangular_projection(
+                rconfig.random_equirectangular_projection_range,
+                film,
+            );
+        }
         151 => {
-        random_cubemap_projection();
-        },
+            sei_payload.cubemap_projection =
+                random_cubemap_projection(rconfig.random_cubemap_projection_range, film);
+        }
         154 => {
-        random_sphere_rotation();
-        },
+            sei_payload.sphere_rotation =
+                random_sphere_rotation(rconfig.random_sphere_rotation_range, film);
+        }
         155 => {
-        random_regionwise_packing();
-        },
+            sei_payload.regionwise_packing =
+                random_regionwise_packing(rconfig.random_regionwise_packing_range, film);
+        }
         156 => {
-        random_omni_viewport();
-        },
+            sei_payload.omni_viewport =
+                random_omni_viewport(rconfig.random_omni_viewport_range, film);
+        }
         181 => {
-        random_alternative_depth_info(); // specified in Annex I
+            sei_payload.alternative_depth_info =
+                random_alternative_depth_info(rconfig.random_alternative_depth_info_range, film);
+        }
+        /*
+        20 => {
+        random_deblocking_filter_display_preference();
+        },
+        21 => {
+        random_stereo_video_info();
+        },
+        22 => {
+        random_post_filter_hint();
+        },
+        56 => {
+        random_green_metadata(); // specified in ISO/IEC 23001-11
+        },
+        148 => {
+        random_ambient_viewing_environment();
         },
         200 => {
         random_sei_manifest();
@@ -283,6 +511,17 @@ fn random_sei_payload(
         }
     }
 
+    if rconfig
+        .random_payload_size_range
+        .payload_size_mismatch
+        .sample(film)
+    {
+        sei_payload.payload_size_delta = rconfig
+            .random_payload_size_range
+            .payload_size_delta
+            .sample(film);
+    }
+
     sei_payload
 }
 
@@ -561,14 +800,159 @@ fn random_pic_timing(
     pt
 }
 
+/// Generate an SEI Payload of type 2 - Pan-scan rectangle
+fn random_pan_scan_rect(
+    rconfig: RandomSEIPanScanRectRange,
+    film: &mut FilmState,
+) -> SEIPanScanRect {
+    let mut psr = SEIPanScanRect::new();
+
+    psr.pan_scan_rect_id = rconfig.pan_scan_rect_id.sample(film);
+    psr.pan_scan_rect_cancel_flag = rconfig.pan_scan_rect_cancel_flag.sample(film);
+    if !psr.pan_scan_rect_cancel_flag {
+        psr.pan_scan_cnt_minus1 = rconfig.pan_scan_cnt_minus1.sample(film);
+        for _ in 0..=psr.pan_scan_cnt_minus1 {
+            psr.pan_scan_rect_left_offset
+                .push(rconfig.pan_scan_rect_offset.sample(film));
+            psr.pan_scan_rect_right_offset
+                .push(rconfig.pan_scan_rect_offset.sample(film));
+            psr.pan_scan_rect_top_offset
+                .push(rconfig.pan_scan_rect_offset.sample(film));
+            psr.pan_scan_rect_bottom_offset
+                .push(rconfig.pan_scan_rect_offset.sample(film));
+        }
+        psr.pan_scan_rect_repetition_period = rconfig.pan_scan_rect_repetition_period.sample(film);
+    }
+
+    psr
+}
+
+/// Generate an SEI Payload of type 3 - Filler payload
+fn random_filler_payload(
+    rconfig: RandomSEIFillerPayloadRange,
+    film: &mut FilmState,
+) -> SEIFillerPayload {
+    let mut fp = SEIFillerPayload::new();
+
+    let length = rconfig.payload_length.sample(film);
+    fp.ff_byte = vec![0xff; length as usize];
+
+    fp
+}
+
+/// Set bit 7 of a CEA-608 byte to give it odd parity, or even parity if `even` is set
+fn cea608_parity(byte: u32, even: bool) -> u8 {
+    let b = (byte & 0x7f) as u8;
+    let odd_parity_bit = if b.count_ones() & 1 == 0 { 0x80 } else { 0 };
+    if even {
+        b | (odd_parity_bit ^ 0x80)
+    } else {
+        b | odd_parity_bit
+    }
+}
+
+/// Generate an SEI Payload of type 4 - Registered user data
+fn random_user_data_registered_itu_t_t35(
+    rconfig: RandomSEIUserDataRegisteredITUTT35Range,
+    film: &mut FilmState,
+) -> SEIUserDataRegisteredITUTT35 {
+    let mut t35 = SEIUserDataRegisteredITUTT35::new();
+
+    if rconfig.atsc_cc_data.sample(film) {
+        t35.itu_t_t35_country_code = ITU_T_T35_COUNTRY_CODE_US;
+        t35.itu_t_t35_provider_code = ITU_T_T35_PROVIDER_CODE_ATSC;
+        t35.atsc_user_identifier = ATSC_USER_IDENTIFIER_GA94;
+        t35.atsc_user_data_type_code = ATSC_USER_DATA_TYPE_CC_DATA;
+
+        let cc = &mut t35.cc_data;
+        cc.process_em_data_flag = rconfig.process_em_data_flag.sample(film);
+        cc.process_cc_data_flag = rconfig.process_cc_data_flag.sample(film);
+        cc.additional_data_flag = rconfig.additional_data_flag.sample(film);
+        let num_triplets = rconfig.cc_count.sample_custom_max(31, film);
+        cc.cc_count = if rconfig.cc_count_mismatch.sample(film) {
+            rconfig.cc_count.sample_custom_max(31, film) as u8
+        } else {
+            num_triplets as u8
+        };
+        cc.em_data = rconfig.em_data.sample_custom_max(255, film) as u8;
+
+        for _ in 0..num_triplets {
+            if rconfig.bad_cc_marker_bits.sample(film) {
+                cc.cc_marker_bits.push(film.read_film_u32(0, 30) as u8);
+            } else {
+                cc.cc_marker_bits.push(0x1f);
+            }
+            cc.cc_valid.push(rconfig.cc_valid.sample(film));
+            let cc_type = rconfig.cc_type.sample_custom_max(3, film) as u8;
+            cc.cc_type.push(cc_type);
+            let cc_data_1 = rconfig.cc_data.sample_custom_max(255, film);
+            let cc_data_2 = rconfig.cc_data.sample_custom_max(255, film);
+            if cc_type < 2 {
+                // CEA-608 byte pairs are 7-bit characters with an odd parity bit
+                let even = rconfig.bad_cea608_parity.sample(film);
+                cc.cc_data_1.push(cea608_parity(cc_data_1, even));
+                cc.cc_data_2.push(cea608_parity(cc_data_2, even));
+            } else {
+                cc.cc_data_1.push(cc_data_1 as u8);
+                cc.cc_data_2.push(cc_data_2 as u8);
+            }
+        }
+
+        if rconfig.bad_marker_bits.sample(film) {
+            cc.marker_bits = film.read_film_u32(0, 254) as u8;
+        } else {
+            cc.marker_bits = 0xff;
+        }
+
+        // ATSC_reserved_user_data follows the cc_data()
+        if cc.additional_data_flag {
+            let length = rconfig.itu_t_t35_payload_length.sample(film);
+            for _ in 0..length {
+                t35.itu_t_t35_payload_byte
+                    .push(film.read_film_u32(0, 255) as u8);
+            }
+        }
+    } else {
+        if rconfig.country_code_extension_flag.sample(film) {
+            t35.itu_t_t35_country_code = 0xff;
+            t35.itu_t_t35_country_code_extension_byte = rconfig
+                .itu_t_t35_country_code_extension_byte
+                .sample_custom_max(255, film)
+                as u8;
+        } else {
+            t35.itu_t_t35_country_code =
+                rconfig.itu_t_t35_country_code.sample_custom_max(254, film) as u8;
+        }
+        t35.itu_t_t35_provider_code = rconfig
+            .itu_t_t35_provider_code
+            .sample_custom_max(65535, film) as u16;
+
+        let length = rconfig.itu_t_t35_payload_length.sample(film);
+        for _ in 0..length {
+            t35.itu_t_t35_payload_byte
+                .push(film.read_film_u32(0, 255) as u8);
+        }
+    }
+
+    t35
+}
+
 /// Generate an SEI Payload of type 5 - Unregistered user data
 fn random_user_data_unregistered(
-    rconfig: RandomSEIUserDataUnregisteredRange,
+    rconfig: &RandomSEIUserDataUnregisteredRange,
     film: &mut FilmState,
 ) -> SEIUserDataUnregistered {
     let mut udu = SEIUserDataUnregistered::new();
 
-    udu.uuid_iso_iec_11578 = match rconfig.uuid_iso_iec_11578.sample(film) {
+    let uuid_idx = rconfig.uuid_iso_iec_11578.sample(film);
+    if uuid_idx >= KNOWN_UUIDS && ((uuid_idx - KNOWN_UUIDS) as usize) < rconfig.schemas.len() {
+        let schema = &rconfig.schemas[(uuid_idx - KNOWN_UUIDS) as usize];
+        udu.uuid_iso_iec_11578 = schema.uuid_iso_iec_11578;
+        udu.user_data_payload_byte = random_user_data_schema_payload(schema, film);
+        return udu;
+    }
+
+    udu.uuid_iso_iec_11578 = match uuid_idx {
         0 => UUID_APPLE1,
         1 => UUID_APPLE2,
         2 => UUID_APPLE3,
@@ -621,6 +1005,58 @@ fn random_user_data_unregistered(
     udu
 }
 
+/// Generate the user_data_payload_byte following a user data schema
+fn random_user_data_schema_payload(
+    schema: &UserDataUnregisteredSchema,
+    film: &mut FilmState,
+) -> Vec<u8> {
+    let mut payload = Vec::new();
+
+    for field in schema.fields.iter() {
+        match field.field_type {
+            UserDataFieldType::U8 | UserDataFieldType::U16 | UserDataFieldType::U32 => {
+                let num_bytes = match field.field_type {
+                    UserDataFieldType::U8 => 1,
+                    UserDataFieldType::U16 => 2,
+                    _ => 4,
+                };
+                let value = match field.value {
+                    Some(r) => r.sample(film),
+                    None => film.read_film_u32(0, (u64::pow(2, 8 * num_bytes) - 1) as u32),
+                };
+                payload.extend(&value.to_be_bytes()[4 - num_bytes as usize..]);
+            }
+            UserDataFieldType::String | UserDataFieldType::Ascii => {
+                if !field.values.is_empty() {
+                    let idx = film.read_film_u32(0, field.values.len() as u32 - 1);
+                    payload.extend(field.values[idx as usize].as_bytes());
+                } else {
+                    let length = field
+                        .length
+                        .unwrap_or(RandomU32Range::new(0, 32))
+                        .sample(film);
+                    for _ in 0..length {
+                        // printable ASCII
+                        payload.push(film.read_film_u32(0x20, 0x7e) as u8);
+                    }
+                }
+                if field.field_type == UserDataFieldType::String {
+                    payload.push(0);
+                }
+            }
+            UserDataFieldType::Bytes => {
+                let length = field
+                    .length
+                    .unwrap_or(RandomU32Range::new(0, 32))
+                    .sample(film);
+                payload.extend(&film.read_film_bytes(length));
+            }
+        }
+    }
+
+    payload
+}
+
 /// Generate an SEI Payload of type 6 - Recovery point
 fn random_recovery_point(
     rconfig: RandomSEIRecoveryPointRange,
@@ -636,45 +1072,304 @@ fn random_recovery_point(
     rp
 }
 
-/// Generate an SEI Payload of type 19 - Film Grain Characteristics
-fn random_film_grain_characteristics(
-    rconfig: RandomSEIFilmGrainCharacteristicsRange,
+/// Generate an SEI Payload of type 7 - Decoded reference picture marking repetition
+fn random_ref_pic_marking_repetition(
+    rconfig: RandomSEIDecRefPicMarkingRepetitionRange,
+    ds: &mut H264DecodedStream,
     film: &mut FilmState,
-) -> SEIFilmGrainCharacteristics {
-    let mut fgc = SEIFilmGrainCharacteristics::new();
-
-    fgc.film_grain_characteristics_cancel_flag =
-        rconfig.film_grain_characteristics_cancel_flag.sample(film);
+) -> SEIDecRefPicMarkingRepetition {
+    let mut rpmr = SEIDecRefPicMarkingRepetition::new();
 
-    if !fgc.film_grain_characteristics_cancel_flag {
-        fgc.film_grain_model_id = rconfig.film_grain_model_id.sample(film) as u8;
-        fgc.separate_colour_description_present_flag = rconfig
-            .separate_colour_description_present_flag
-            .sample(film);
+    // the decoder and encoder use the last SPS for this message
+    let frame_mbs_only_flag = match ds.spses.last() {
+        Some(sps) => sps.frame_mbs_only_flag,
+        None => SeqParameterSet::new().frame_mbs_only_flag,
+    };
 
-        if fgc.separate_colour_description_present_flag {
-            fgc.film_grain_bit_depth_luma_minus8 =
-                rconfig.film_grain_bit_depth_luma_minus8.sample(film) as u8;
-            fgc.film_grain_bit_depth_chroma_minus8 =
-                rconfig.film_grain_bit_depth_chroma_minus8.sample(film) as u8;
-            fgc.film_grain_full_range_flag = rconfig.film_grain_full_range_flag.sample(film);
-            fgc.film_grain_colour_primaries =
-                rconfig.film_grain_colour_primaries.sample(film) as u8;
-            fgc.film_grain_transfer_characteristics =
-                rconfig.film_grain_transfer_characteristics.sample(film) as u8;
-            fgc.film_grain_matrix_coefficients =
-                rconfig.film_grain_matrix_coefficients.sample(film) as u8;
+    rpmr.original_idr_flag = rconfig.original_idr_flag.sample(film);
+    rpmr.original_frame_num = rconfig.original_frame_num.sample(film);
+    if !frame_mbs_only_flag {
+        rpmr.original_field_pic_flag = rconfig.original_field_pic_flag.sample(film);
+        if rpmr.original_field_pic_flag {
+            rpmr.original_bottom_field_flag = rconfig.original_bottom_field_flag.sample(film);
         }
-        fgc.blending_mode_id = rconfig.blending_mode_id.sample(film) as u8;
-        fgc.log2_scale_factor = rconfig.log2_scale_factor.sample(film) as u8;
+    }
 
-        for _ in 0..3 {
-            fgc.comp_model_present_flag
-                .push(rconfig.comp_model_present_flag.sample(film));
+    if rpmr.original_idr_flag {
+        rpmr.no_output_of_prior_pics_flag = rconfig.no_output_of_prior_pics_flag.sample(film);
+        rpmr.long_term_reference_flag = rconfig.long_term_reference_flag.sample(film);
+    } else {
+        rpmr.adaptive_ref_pic_marking_mode_flag =
+            rconfig.adaptive_ref_pic_marking_mode_flag.sample(film);
+        if rpmr.adaptive_ref_pic_marking_mode_flag {
+            let number_of_mem_ops = rconfig.number_of_mem_ops.sample(film);
+            for _ in 0..number_of_mem_ops {
+                let mmco = rconfig.memory_management_control_operation.sample(film);
+                rpmr.memory_management_control_operation.push(mmco);
+                if mmco == 1 || mmco == 3 {
+                    rpmr.difference_of_pic_nums_minus1
+                        .push(rconfig.difference_of_pic_nums_minus1.sample(film));
+                } else {
+                    rpmr.difference_of_pic_nums_minus1.push(0);
+                }
+                if mmco == 2 {
+                    rpmr.long_term_pic_num
+                        .push(rconfig.long_term_pic_num.sample(film));
+                } else {
+                    rpmr.long_term_pic_num.push(0);
+                }
+                if mmco == 3 || mmco == 6 {
+                    rpmr.long_term_frame_idx
+                        .push(rconfig.long_term_frame_idx.sample(film));
+                } else {
+                    rpmr.long_term_frame_idx.push(0);
+                }
+                if mmco == 4 {
+                    rpmr.max_long_term_frame_idx_plus1
+                        .push(rconfig.max_long_term_frame_idx_plus1.sample(film));
+                } else {
+                    rpmr.max_long_term_frame_idx_plus1.push(0);
+                }
+            }
+            // end the list with the exit operation
+            rpmr.memory_management_control_operation.push(0);
+            rpmr.difference_of_pic_nums_minus1.push(0);
+            rpmr.long_term_pic_num.push(0);
+            rpmr.long_term_frame_idx.push(0);
+            rpmr.max_long_term_frame_idx_plus1.push(0);
         }
+    }
 
-        for c in 0..3 {
-            if fgc.comp_model_present_flag[c] {
+    rpmr
+}
+
+/// Generate an SEI Payload of type 8 - Spare picture
+fn random_spare_pic(
+    rconfig: RandomSEISparePicRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) -> SEISparePic {
+    let mut sp = SEISparePic::new();
+
+    // PicSizeInMapUnits of the last SPS, which the decoder and encoder also use
+    let pic_size_in_map_units: u64 = match ds.spses.last() {
+        Some(sps) => {
+            (sps.pic_width_in_mbs_minus1 as u64 + 1)
+                * (sps.pic_height_in_map_units_minus1 as u64 + 1)
+        }
+        None => 1,
+    };
+
+    sp.target_frame_num = rconfig.target_frame_num.sample(film);
+    sp.spare_field_flag = rconfig.spare_field_flag.sample(film);
+    if sp.spare_field_flag {
+        sp.target_bottom_field_flag = rconfig.target_bottom_field_flag.sample(film);
+    }
+    sp.num_spare_pics_minus1 = rconfig.num_spare_pics_minus1.sample(film);
+
+    for i in 0..=(sp.num_spare_pics_minus1 as usize) {
+        sp.delta_spare_frame_num
+            .push(rconfig.delta_spare_frame_num.sample(film));
+        if sp.spare_field_flag {
+            sp.spare_bottom_field_flag
+                .push(rconfig.spare_bottom_field_flag.sample(film));
+        } else {
+            sp.spare_bottom_field_flag.push(false);
+        }
+        sp.spare_area_idc.push(rconfig.spare_area_idc.sample(film));
+
+        sp.spare_unit_flag.push(Vec::new());
+        sp.zero_run_length.push(Vec::new());
+        if sp.spare_area_idc[i] == 1 {
+            for _ in 0..pic_size_in_map_units {
+                sp.spare_unit_flag[i].push(rconfig.spare_unit_flag.sample(film));
+            }
+        } else if sp.spare_area_idc[i] == 2 {
+            let mut map_unit_cnt: u64 = 0;
+            while map_unit_cnt < pic_size_in_map_units {
+                let zero_run_length = rconfig.zero_run_length.sample(film);
+                sp.zero_run_length[i].push(zero_run_length);
+                map_unit_cnt += zero_run_length as u64 + 1;
+            }
+        }
+    }
+
+    sp
+}
+
+/// Generate an SEI Payload of type 9 - Scene information
+fn random_scene_info(rconfig: RandomSEISceneInfoRange, film: &mut FilmState) -> SEISceneInfo {
+    let mut si = SEISceneInfo::new();
+
+    si.scene_info_present_flag = rconfig.scene_info_present_flag.sample(film);
+    if si.scene_info_present_flag {
+        si.scene_id = rconfig.scene_id.sample(film);
+        si.scene_transition_type = rconfig.scene_transition_type.sample(film);
+        if si.scene_transition_type > 3 {
+            si.second_scene_id = rconfig.second_scene_id.sample(film);
+        }
+    }
+
+    si
+}
+
+/// Generate an SEI Payload of type 10 - Sub-sequence information
+fn random_sub_seq_info(rconfig: RandomSEISubSeqInfoRange, film: &mut FilmState) -> SEISubSeqInfo {
+    let mut ssi = SEISubSeqInfo::new();
+
+    ssi.sub_seq_layer_num = rconfig.sub_seq_layer_num.sample(film);
+    ssi.sub_seq_id = rconfig.sub_seq_id.sample(film);
+    ssi.first_ref_pic_flag = rconfig.first_ref_pic_flag.sample(film);
+    ssi.leading_non_ref_pic_flag = rconfig.leading_non_ref_pic_flag.sample(film);
+    ssi.last_pic_flag = rconfig.last_pic_flag.sample(film);
+    ssi.sub_seq_frame_num_flag = rconfig.sub_seq_frame_num_flag.sample(film);
+    if ssi.sub_seq_frame_num_flag {
+        ssi.sub_seq_frame_num = rconfig.sub_seq_frame_num.sample(film);
+    }
+
+    ssi
+}
+
+/// Generate an SEI Payload of type 11 - Sub-sequence layer characteristics
+fn random_sub_seq_layer_characteristics(
+    rconfig: RandomSEISubSeqLayerCharacteristicsRange,
+    film: &mut FilmState,
+) -> SEISubSeqLayerCharacteristics {
+    let mut sslc = SEISubSeqLayerCharacteristics::new();
+
+    sslc.num_sub_seq_layers_minus1 = rconfig.num_sub_seq_layers_minus1.sample(film);
+    for _ in 0..=sslc.num_sub_seq_layers_minus1 {
+        sslc.accurate_statistics_flag
+            .push(rconfig.accurate_statistics_flag.sample(film));
+        sslc.average_bit_rate
+            .push(rconfig.average_bit_rate.sample(film));
+        sslc.average_frame_rate
+            .push(rconfig.average_frame_rate.sample(film));
+    }
+
+    sslc
+}
+
+/// Generate an SEI Payload of type 12 - Sub-sequence characteristics
+fn random_sub_seq_characteristics(
+    rconfig: RandomSEISubSeqCharacteristicsRange,
+    film: &mut FilmState,
+) -> SEISubSeqCharacteristics {
+    let mut ssc = SEISubSeqCharacteristics::new();
+
+    ssc.sub_seq_layer_num = rconfig.sub_seq_layer_num.sample(film);
+    ssc.sub_seq_id = rconfig.sub_seq_id.sample(film);
+    ssc.duration_flag = rconfig.duration_flag.sample(film);
+    if ssc.duration_flag {
+        ssc.sub_seq_duration = rconfig.sub_seq_duration.sample(film);
+    }
+    ssc.average_rate_flag = rconfig.average_rate_flag.sample(film);
+    if ssc.average_rate_flag {
+        ssc.accurate_statistics_flag = rconfig.accurate_statistics_flag.sample(film);
+        ssc.average_bit_rate = rconfig.average_bit_rate.sample(film);
+        ssc.average_frame_rate = rconfig.average_frame_rate.sample(film);
+    }
+    ssc.num_referenced_subseqs = rconfig.num_referenced_subseqs.sample(film);
+    for _ in 0..ssc.num_referenced_subseqs {
+        ssc.ref_sub_seq_layer_num
+            .push(rconfig.ref_sub_seq_layer_num.sample(film));
+        ssc.ref_sub_seq_id.push(rconfig.ref_sub_seq_id.sample(film));
+        ssc.ref_sub_seq_direction
+            .push(rconfig.ref_sub_seq_direction.sample(film));
+    }
+
+    ssc
+}
+
+/// Generate an SEI Payload of type 13 - Full-frame freeze
+fn random_full_frame_freeze(
+    rconfig: RandomSEIFrameFreezeRefinementRange,
+    film: &mut FilmState,
+) -> SEIFullFrameFreeze {
+    let mut fff = SEIFullFrameFreeze::new();
+
+    fff.full_frame_freeze_repetition_period =
+        rconfig.full_frame_freeze_repetition_period.sample(film);
+
+    fff
+}
+
+/// Generate an SEI Payload of type 15 - Full-frame snapshot
+fn random_full_frame_snapshot(
+    rconfig: RandomSEIFrameFreezeRefinementRange,
+    film: &mut FilmState,
+) -> SEIFullFrameSnapshot {
+    let mut ffs = SEIFullFrameSnapshot::new();
+
+    ffs.snapshot_id = rconfig.snapshot_id.sample(film);
+
+    ffs
+}
+
+/// Generate an SEI Payload of type 16 - Progressive refinement segment start
+fn random_progressive_refinement_segment_start(
+    rconfig: RandomSEIFrameFreezeRefinementRange,
+    film: &mut FilmState,
+) -> SEIProgressiveRefinementSegmentStart {
+    let mut prss = SEIProgressiveRefinementSegmentStart::new();
+
+    prss.progressive_refinement_id = rconfig.progressive_refinement_id.sample(film);
+    prss.num_refinement_steps_minus1 = rconfig.num_refinement_steps_minus1.sample(film);
+
+    prss
+}
+
+/// Generate an SEI Payload of type 17 - Progressive refinement segment end
+fn random_progressive_refinement_segment_end(
+    rconfig: RandomSEIFrameFreezeRefinementRange,
+    film: &mut FilmState,
+) -> SEIProgressiveRefinementSegmentEnd {
+    let mut prse = SEIProgressiveRefinementSegmentEnd::new();
+
+    prse.progressive_refinement_id = rconfig.progressive_refinement_id.sample(film);
+
+    prse
+}
+
+/// Generate an SEI Payload of type 19 - Film Grain Characteristics
+fn random_film_grain_characteristics(
+    rconfig: RandomSEIFilmGrainCharacteristicsRange,
+    film: &mut FilmState,
+) -> SEIFilmGrainCharacteristics {
+    let mut fgc = SEIFilmGrainCharacteristics::new();
+
+    fgc.film_grain_characteristics_cancel_flag =
+        rconfig.film_grain_characteristics_cancel_flag.sample(film);
+
+    if !fgc.film_grain_characteristics_cancel_flag {
+        fgc.film_grain_model_id = rconfig.film_grain_model_id.sample(film) as u8;
+        fgc.separate_colour_description_present_flag = rconfig
+            .separate_colour_description_present_flag
+            .sample(film);
+
+        if fgc.separate_colour_description_present_flag {
+            fgc.film_grain_bit_depth_luma_minus8 =
+                rconfig.film_grain_bit_depth_luma_minus8.sample(film) as u8;
+            fgc.film_grain_bit_depth_chroma_minus8 =
+                rconfig.film_grain_bit_depth_chroma_minus8.sample(film) as u8;
+            fgc.film_grain_full_range_flag = rconfig.film_grain_full_range_flag.sample(film);
+            fgc.film_grain_colour_primaries =
+                rconfig.film_grain_colour_primaries.sample(film) as u8;
+            fgc.film_grain_transfer_characteristics =
+                rconfig.film_grain_transfer_characteristics.sample(film) as u8;
+            fgc.film_grain_matrix_coefficients =
+                rconfig.film_grain_matrix_coefficients.sample(film) as u8;
+        }
+        fgc.blending_mode_id = rconfig.blending_mode_id.sample(film) as u8;
+        fgc.log2_scale_factor = rconfig.log2_scale_factor.sample(film) as u8;
+
+        for _ in 0..3 {
+            fgc.comp_model_present_flag
+                .push(rconfig.comp_model_present_flag.sample(film));
+        }
+
+        for c in 0..3 {
+            if fgc.comp_model_present_flag[c] {
                 fgc.num_intensity_intervals_minus1
                     .push(rconfig.num_intensity_intervals_minus1.sample(film) as u8);
                 fgc.num_model_values_minus1
@@ -708,3 +1403,2046 @@ fn random_film_grain_characteristics(
     }
     fgc
 }
+
+/// Returns the largest value that fits in the u(v) elements sized from `bit_depth`
+/// as ((bit_depth + 7) >> 3) << 3
+fn max_value_for_bit_depth(bit_depth: u8) -> u32 {
+    let bits = ((bit_depth as u32 + 7) >> 3) << 3;
+    if bits >= 32 {
+        u32::MAX
+    } else {
+        (1 << bits) - 1
+    }
+}
+
+/// Generate an SEI Payload of type 23 - Tone mapping information
+fn random_tone_mapping_info(
+    rconfig: RandomSEIToneMappingInfoRange,
+    film: &mut FilmState,
+) -> SEIToneMappingInfo {
+    let mut tmi = SEIToneMappingInfo::new();
+
+    tmi.tone_map_id = rconfig.tone_map_id.sample(film);
+    tmi.tone_map_cancel_flag = rconfig.tone_map_cancel_flag.sample(film);
+    if !tmi.tone_map_cancel_flag {
+        tmi.tone_map_repetition_period = rconfig.tone_map_repetition_period.sample(film);
+        tmi.coded_data_bit_depth = rconfig.coded_data_bit_depth.sample(film) as u8;
+        tmi.target_bit_depth = rconfig.target_bit_depth.sample(film) as u8;
+        tmi.tone_map_model_id = rconfig.tone_map_model_id.sample(film);
+
+        let coded_max = max_value_for_bit_depth(tmi.coded_data_bit_depth);
+        let target_max = max_value_for_bit_depth(tmi.target_bit_depth);
+        match tmi.tone_map_model_id {
+            0 => {
+                tmi.min_value = rconfig.min_value.sample(film);
+                tmi.max_value = rconfig.max_value.sample(film);
+            }
+            1 => {
+                tmi.sigmoid_midpoint = rconfig.sigmoid_midpoint.sample(film);
+                tmi.sigmoid_width = rconfig.sigmoid_width.sample(film);
+            }
+            2 => {
+                let num_intervals = 1u64
+                    .checked_shl(tmi.target_bit_depth as u32)
+                    .unwrap_or(u64::MAX);
+                for _ in 0..num_intervals {
+                    tmi.start_of_coded_interval.push(
+                        rconfig
+                            .start_of_coded_interval
+                            .sample_custom_max(coded_max, film),
+                    );
+                }
+            }
+            3 => {
+                tmi.num_pivots = rconfig.num_pivots.sample_custom_max(65535, film);
+                for _ in 0..tmi.num_pivots {
+                    tmi.coded_pivot_value
+                        .push(rconfig.coded_pivot_value.sample_custom_max(coded_max, film));
+                    tmi.target_pivot_value.push(
+                        rconfig
+                            .target_pivot_value
+                            .sample_custom_max(target_max, film),
+                    );
+                }
+            }
+            4 => {
+                tmi.camera_iso_speed_idc = rconfig.camera_iso_speed_idc.sample(film) as u8;
+                if tmi.camera_iso_speed_idc == 255 {
+                    tmi.camera_iso_speed_value = rconfig.camera_iso_speed_value.sample(film);
+                }
+                tmi.exposure_index_idc = rconfig.exposure_index_idc.sample(film) as u8;
+                if tmi.exposure_index_idc == 255 {
+                    tmi.exposure_index_value = rconfig.exposure_index_value.sample(film);
+                }
+                tmi.exposure_compensation_value_sign_flag =
+                    rconfig.exposure_compensation_value_sign_flag.sample(film);
+                tmi.exposure_compensation_value_numerator = rconfig
+                    .exposure_compensation_value_numerator
+                    .sample_custom_max(65535, film);
+                tmi.exposure_compensation_value_denom_idc = rconfig
+                    .exposure_compensation_value_denom_idc
+                    .sample_custom_max(65535, film);
+                tmi.ref_screen_luminance_white = rconfig.ref_screen_luminance_white.sample(film);
+                tmi.extended_range_white_level = rconfig.extended_range_white_level.sample(film);
+                tmi.nominal_black_level_luma_code_value = rconfig
+                    .nominal_black_level_luma_code_value
+                    .sample_custom_max(65535, film);
+                tmi.nominal_white_level_luma_code_value = rconfig
+                    .nominal_white_level_luma_code_value
+                    .sample_custom_max(65535, film);
+                tmi.extended_white_level_luma_code_value = rconfig
+                    .extended_white_level_luma_code_value
+                    .sample_custom_max(65535, film);
+            }
+            _ => (),
+        }
+    }
+
+    tmi
+}
+
+/// Generate an SEI Payload of type 137 - Mastering display colour volume
+fn random_mastering_display_colour_volume(
+    rconfig: RandomSEIMasteringDisplayColourVolumeRange,
+    film: &mut FilmState,
+) -> SEIMasteringDisplayColourVolume {
+    let mut mdcv = SEIMasteringDisplayColourVolume::new();
+
+    for c in 0..3 {
+        mdcv.display_primaries_x[c] = rconfig.display_primaries_x.sample_custom_max(65535, film);
+        mdcv.display_primaries_y[c] = rconfig.display_primaries_y.sample_custom_max(65535, film);
+    }
+    mdcv.white_point_x = rconfig.white_point_x.sample_custom_max(65535, film);
+    mdcv.white_point_y = rconfig.white_point_y.sample_custom_max(65535, film);
+    mdcv.max_display_mastering_luminance = rconfig.max_display_mastering_luminance.sample(film);
+    mdcv.min_display_mastering_luminance = rconfig.min_display_mastering_luminance.sample(film);
+
+    mdcv
+}
+
+/// Generate the pre or post look-up table of a colour remapping SEI
+fn random_colour_remap_lut(
+    rconfig: RandomSEIColourRemappingInfoRange,
+    coded_max: u32,
+    target_max: u32,
+    film: &mut FilmState,
+) -> (u8, Vec<u32>, Vec<u32>) {
+    let num_val_minus1 = rconfig.lut_num_val_minus1.sample_custom_max(255, film) as u8;
+    let mut coded_value = Vec::new();
+    let mut target_value = Vec::new();
+    if num_val_minus1 > 0 {
+        for _ in 0..=num_val_minus1 {
+            coded_value.push(rconfig.lut_value.sample_custom_max(coded_max, film));
+            target_value.push(rconfig.lut_value.sample_custom_max(target_max, film));
+        }
+    }
+
+    (num_val_minus1, coded_value, target_value)
+}
+
+/// Generate an SEI Payload of type 142 - Colour remapping information
+fn random_colour_remapping_info(
+    rconfig: RandomSEIColourRemappingInfoRange,
+    film: &mut FilmState,
+) -> SEIColourRemappingInfo {
+    let mut cri = SEIColourRemappingInfo::new();
+
+    cri.colour_remap_id = rconfig.colour_remap_id.sample(film);
+    cri.colour_remap_cancel_flag = rconfig.colour_remap_cancel_flag.sample(film);
+    if !cri.colour_remap_cancel_flag {
+        cri.colour_remap_repetition_period = rconfig.colour_remap_repetition_period.sample(film);
+        cri.colour_remap_video_signal_info_present_flag = rconfig
+            .colour_remap_video_signal_info_present_flag
+            .sample(film);
+        if cri.colour_remap_video_signal_info_present_flag {
+            cri.colour_remap_full_range_flag = rconfig.colour_remap_full_range_flag.sample(film);
+            cri.colour_remap_primaries = rconfig.colour_remap_primaries.sample(film) as u8;
+            cri.colour_remap_transfer_function =
+                rconfig.colour_remap_transfer_function.sample(film) as u8;
+            cri.colour_remap_matrix_coefficients =
+                rconfig.colour_remap_matrix_coefficients.sample(film) as u8;
+        }
+        cri.colour_remap_input_bit_depth = rconfig.colour_remap_input_bit_depth.sample(film) as u8;
+        cri.colour_remap_output_bit_depth =
+            rconfig.colour_remap_output_bit_depth.sample(film) as u8;
+
+        let input_max = max_value_for_bit_depth(cri.colour_remap_input_bit_depth);
+        let output_max = max_value_for_bit_depth(cri.colour_remap_output_bit_depth);
+        for c in 0..3 {
+            let (num_val_minus1, coded_value, target_value) =
+                random_colour_remap_lut(rconfig, input_max, output_max, film);
+            cri.pre_lut_num_val_minus1[c] = num_val_minus1;
+            cri.pre_lut_coded_value.push(coded_value);
+            cri.pre_lut_target_value.push(target_value);
+        }
+
+        cri.colour_remap_matrix_present_flag =
+            rconfig.colour_remap_matrix_present_flag.sample(film);
+        if cri.colour_remap_matrix_present_flag {
+            cri.log2_matrix_denom = rconfig.log2_matrix_denom.sample_custom_max(15, film) as u8;
+            for c in 0..3 {
+                for i in 0..3 {
+                    cri.colour_remap_coeffs[c][i] = rconfig.colour_remap_coeffs.sample(film);
+                }
+            }
+        }
+
+        for c in 0..3 {
+            let (num_val_minus1, coded_value, target_value) =
+                random_colour_remap_lut(rconfig, output_max, output_max, film);
+            cri.post_lut_num_val_minus1[c] = num_val_minus1;
+            cri.post_lut_coded_value.push(coded_value);
+            cri.post_lut_target_value.push(target_value);
+        }
+    }
+
+    cri
+}
+
+/// Generate an SEI Payload of type 144 - Content light level information
+fn random_content_light_level_info(
+    rconfig: RandomSEIContentLightLevelInfoRange,
+    film: &mut FilmState,
+) -> SEIContentLightLevelInfo {
+    let mut clli = SEIContentLightLevelInfo::new();
+
+    clli.max_content_light_level = rconfig
+        .max_content_light_level
+        .sample_custom_max(65535, film);
+    clli.max_pic_average_light_level = rconfig
+        .max_pic_average_light_level
+        .sample_custom_max(65535, film);
+
+    clli
+}
+
+/// Generate an SEI Payload of type 147 - Alternative transfer characteristics
+fn random_alternative_transfer_characteristics(
+    rconfig: RandomSEIAlternativeTransferCharacteristicsRange,
+    film: &mut FilmState,
+) -> SEIAlternativeTransferCharacteristics {
+    let mut atc = SEIAlternativeTransferCharacteristics::new();
+
+    atc.preferred_transfer_characteristics =
+        rconfig.preferred_transfer_characteristics.sample(film) as u8;
+
+    atc
+}
+
+/// Generate an SEI Payload of type 149 - Content colour volume
+fn random_content_colour_volume(
+    rconfig: RandomSEIContentColourVolumeRange,
+    film: &mut FilmState,
+) -> SEIContentColourVolume {
+    let mut ccv = SEIContentColourVolume::new();
+
+    ccv.ccv_cancel_flag = rconfig.ccv_cancel_flag.sample(film);
+    if !ccv.ccv_cancel_flag {
+        ccv.ccv_persistence_flag = rconfig.ccv_persistence_flag.sample(film);
+        ccv.ccv_primaries_present_flag = rconfig.ccv_primaries_present_flag.sample(film);
+        ccv.ccv_min_luminance_value_present_flag =
+            rconfig.ccv_min_luminance_value_present_flag.sample(film);
+        ccv.ccv_max_luminance_value_present_flag =
+            rconfig.ccv_max_luminance_value_present_flag.sample(film);
+        ccv.ccv_avg_luminance_value_present_flag =
+            rconfig.ccv_avg_luminance_value_present_flag.sample(film);
+        ccv.ccv_reserved_zero_2bits =
+            rconfig.ccv_reserved_zero_2bits.sample_custom_max(3, film) as u8;
+        if ccv.ccv_primaries_present_flag {
+            for c in 0..3 {
+                ccv.ccv_primaries_x[c] = rconfig.ccv_primaries.sample(film);
+                ccv.ccv_primaries_y[c] = rconfig.ccv_primaries.sample(film);
+            }
+        }
+        if ccv.ccv_min_luminance_value_present_flag {
+            ccv.ccv_min_luminance_value = rconfig.ccv_luminance_value.sample(film);
+        }
+        if ccv.ccv_max_luminance_value_present_flag {
+            ccv.ccv_max_luminance_value = rconfig.ccv_luminance_value.sample(film);
+        }
+        if ccv.ccv_avg_luminance_value_present_flag {
+            ccv.ccv_avg_luminance_value = rconfig.ccv_luminance_value.sample(film);
+        }
+    }
+
+    ccv
+}
+
+/// Generate an SEI Payload of type 150 - Equirectangular projection
+fn random_equirectangular_projection(
+    rconfig: RandomSEIEquirectangularProjectionRange,
+    film: &mut FilmState,
+) -> SEIEquirectangularProjection {
+    let mut erp = SEIEquirectangularProjection::new();
+
+    erp.erp_cancel_flag = rconfig.erp_cancel_flag.sample(film);
+    if !erp.erp_cancel_flag {
+        erp.erp_persistence_flag = rconfig.erp_persistence_flag.sample(film);
+        erp.erp_padding_flag = rconfig.erp_padding_flag.sample(film);
+        erp.erp_reserved_zero_2bits =
+            rconfig.erp_reserved_zero_2bits.sample_custom_max(3, film) as u8;
+        if erp.erp_padding_flag {
+            erp.gb_erp_type = rconfig.gb_erp_type.sample_custom_max(7, film) as u8;
+            erp.left_gb_erp_width = rconfig.left_gb_erp_width.sample(film) as u8;
+            erp.right_gb_erp_width = rconfig.right_gb_erp_width.sample(film) as u8;
+        }
+    }
+
+    erp
+}
+
+/// Generate an SEI Payload of type 151 - Cubemap projection
+fn random_cubemap_projection(
+    rconfig: RandomSEICubemapProjectionRange,
+    film: &mut FilmState,
+) -> SEICubemapProjection {
+    let mut cmp = SEICubemapProjection::new();
+
+    cmp.cmp_cancel_flag = rconfig.cmp_cancel_flag.sample(film);
+    if !cmp.cmp_cancel_flag {
+        cmp.cmp_persistence_flag = rconfig.cmp_persistence_flag.sample(film);
+    }
+
+    cmp
+}
+
+/// Generate an SEI Payload of type 154 - Sphere rotation
+fn random_sphere_rotation(
+    rconfig: RandomSEISphereRotationRange,
+    film: &mut FilmState,
+) -> SEISphereRotation {
+    let mut sr = SEISphereRotation::new();
+
+    sr.sphere_rotation_cancel_flag = rconfig.sphere_rotation_cancel_flag.sample(film);
+    if !sr.sphere_rotation_cancel_flag {
+        sr.sphere_rotation_persistence_flag = rconfig.sphere_rotation_persistence_flag.sample(film);
+        sr.sphere_rotation_reserved_zero_6bits = rconfig
+            .sphere_rotation_reserved_zero_6bits
+            .sample_custom_max(63, film) as u8;
+        sr.yaw_rotation = rconfig.yaw_rotation.sample(film);
+        sr.pitch_rotation = rconfig.pitch_rotation.sample(film);
+        sr.roll_rotation = rconfig.roll_rotation.sample(film);
+    }
+
+    sr
+}
+
+/// Generate an SEI Payload of type 155 - Region-wise packing
+fn random_regionwise_packing(
+    rconfig: RandomSEIRegionwisePackingRange,
+    film: &mut FilmState,
+) -> SEIRegionwisePacking {
+    let mut rwp = SEIRegionwisePacking::new();
+
+    rwp.rwp_cancel_flag = rconfig.rwp_cancel_flag.sample(film);
+    if !rwp.rwp_cancel_flag {
+        rwp.rwp_persistence_flag = rconfig.rwp_persistence_flag.sample(film);
+        rwp.constituent_picture_matching_flag =
+            rconfig.constituent_picture_matching_flag.sample(film);
+        rwp.rwp_reserved_zero_5bits =
+            rconfig.rwp_reserved_zero_5bits.sample_custom_max(31, film) as u8;
+        rwp.num_packed_regions = rconfig.num_packed_regions.sample_custom_max(255, film) as u8;
+        rwp.proj_picture_width = rconfig.proj_picture_width.sample(film);
+        rwp.proj_picture_height = rconfig.proj_picture_height.sample(film);
+        rwp.packed_picture_width = rconfig.packed_picture_width.sample_custom_max(65535, film);
+        rwp.packed_picture_height = rconfig.packed_picture_height.sample_custom_max(65535, film);
+
+        for _ in 0..rwp.num_packed_regions {
+            rwp.rwp_reserved_zero_4bits
+                .push(rconfig.rwp_reserved_zero_4bits.sample_custom_max(15, film) as u8);
+            rwp.rwp_transform_type
+                .push(rconfig.rwp_transform_type.sample_custom_max(7, film) as u8);
+            let guard_band_flag = rconfig.rwp_guard_band_flag.sample(film);
+            rwp.rwp_guard_band_flag.push(guard_band_flag);
+            rwp.proj_region_width
+                .push(rconfig.proj_region_dimension.sample(film));
+            rwp.proj_region_height
+                .push(rconfig.proj_region_dimension.sample(film));
+            rwp.proj_region_top
+                .push(rconfig.proj_region_dimension.sample(film));
+            rwp.proj_region_left
+                .push(rconfig.proj_region_dimension.sample(film));
+            rwp.packed_region_width.push(
+                rconfig
+                    .packed_region_dimension
+                    .sample_custom_max(65535, film),
+            );
+            rwp.packed_region_height.push(
+                rconfig
+                    .packed_region_dimension
+                    .sample_custom_max(65535, film),
+            );
+            rwp.packed_region_top.push(
+                rconfig
+                    .packed_region_dimension
+                    .sample_custom_max(65535, film),
+            );
+            rwp.packed_region_left.push(
+                rconfig
+                    .packed_region_dimension
+                    .sample_custom_max(65535, film),
+            );
+            if guard_band_flag {
+                rwp.rwp_left_guard_band_width
+                    .push(rconfig.rwp_guard_band_size.sample(film) as u8);
+                rwp.rwp_right_guard_band_width
+                    .push(rconfig.rwp_guard_band_size.sample(film) as u8);
+                rwp.rwp_top_guard_band_height
+                    .push(rconfig.rwp_guard_band_size.sample(film) as u8);
+                rwp.rwp_bottom_guard_band_height
+                    .push(rconfig.rwp_guard_band_size.sample(film) as u8);
+                rwp.rwp_guard_band_not_used_for_pred_flag
+                    .push(rconfig.rwp_guard_band_not_used_for_pred_flag.sample(film));
+                let mut guard_band_type = [0; 4];
+                for t in guard_band_type.iter_mut() {
+                    *t = rconfig.rwp_guard_band_type.sample_custom_max(7, film) as u8;
+                }
+                rwp.rwp_guard_band_type.push(guard_band_type);
+                rwp.rwp_guard_band_reserved_zero_3bits.push(
+                    rconfig
+                        .rwp_guard_band_reserved_zero_3bits
+                        .sample_custom_max(7, film) as u8,
+                );
+            } else {
+                rwp.rwp_left_guard_band_width.push(0);
+                rwp.rwp_right_guard_band_width.push(0);
+                rwp.rwp_top_guard_band_height.push(0);
+                rwp.rwp_bottom_guard_band_height.push(0);
+                rwp.rwp_guard_band_not_used_for_pred_flag.push(false);
+                rwp.rwp_guard_band_type.push([0; 4]);
+                rwp.rwp_guard_band_reserved_zero_3bits.push(0);
+            }
+        }
+    }
+
+    rwp
+}
+
+/// Generate an SEI Payload of type 156 - Omnidirectional viewport
+fn random_omni_viewport(
+    rconfig: RandomSEIOmniViewportRange,
+    film: &mut FilmState,
+) -> SEIOmniViewport {
+    let mut ov = SEIOmniViewport::new();
+
+    ov.omni_viewport_id = rconfig.omni_viewport_id.sample_custom_max(1023, film);
+    ov.omni_viewport_cancel_flag = rconfig.omni_viewport_cancel_flag.sample(film);
+    if !ov.omni_viewport_cancel_flag {
+        ov.omni_viewport_persistence_flag = rconfig.omni_viewport_persistence_flag.sample(film);
+        ov.omni_viewport_cnt_minus1 =
+            rconfig.omni_viewport_cnt_minus1.sample_custom_max(15, film) as u8;
+        for _ in 0..=ov.omni_viewport_cnt_minus1 {
+            ov.omni_viewport_azimuth_centre
+                .push(rconfig.omni_viewport_azimuth_centre.sample(film));
+            ov.omni_viewport_elevation_centre
+                .push(rconfig.omni_viewport_elevation_centre.sample(film));
+            ov.omni_viewport_tilt_centre
+                .push(rconfig.omni_viewport_tilt_centre.sample(film));
+            ov.omni_viewport_hor_range
+                .push(rconfig.omni_viewport_hor_range.sample(film));
+            ov.omni_viewport_ver_range
+                .push(rconfig.omni_viewport_ver_range.sample(film));
+        }
+    }
+
+    ov
+}
+
+/// Generate an SEI Payload of type 181 - Alternative depth information
+fn random_alternative_depth_info(
+    rconfig: RandomSEIAlternativeDepthInfoRange,
+    film: &mut FilmState,
+) -> SEIAlternativeDepthInfo {
+    let mut adi = SEIAlternativeDepthInfo::new();
+    let dre = rconfig.random_depth_representation_element_range;
+    let fv = rconfig.random_float_value_range;
+
+    adi.depth_type = rconfig.depth_type.sample(film);
+    if adi.depth_type != 0 {
+        return adi;
+    }
+
+    adi.num_constituent_views_gvd_minus1 = rconfig.num_constituent_views_gvd_minus1.sample(film);
+    adi.depth_present_gvd_flag = rconfig.depth_present_gvd_flag.sample(film);
+    adi.z_gvd_flag = rconfig.z_gvd_flag.sample(film);
+    adi.intrinsic_param_gvd_flag = rconfig.intrinsic_param_gvd_flag.sample(film);
+    adi.rotation_gvd_flag = rconfig.rotation_gvd_flag.sample(film);
+    adi.translation_gvd_flag = rconfig.translation_gvd_flag.sample(film);
+
+    // the constituent views plus the generated view
+    let num_views = adi.num_constituent_views_gvd_minus1 as u64 + 2;
+    if adi.z_gvd_flag {
+        for _ in 0..num_views {
+            adi.gvd_z_near
+                .push(random_depth_representation_sei_element(dre, film));
+            adi.gvd_z_far
+                .push(random_depth_representation_sei_element(dre, film));
+        }
+    }
+    if adi.intrinsic_param_gvd_flag {
+        adi.prec_gvd_focal_length = fv.prec.sample(film);
+        adi.prec_gvd_principal_point = fv.prec.sample(film);
+    }
+    if adi.rotation_gvd_flag {
+        adi.prec_gvd_rotation_param = fv.prec.sample(film);
+    }
+    if adi.translation_gvd_flag {
+        adi.prec_gvd_translation_param = fv.prec.sample(film);
+    }
+    if !adi.intrinsic_param_gvd_flag && !adi.rotation_gvd_flag && !adi.translation_gvd_flag {
+        return adi;
+    }
+
+    // absent camera parameters are stored as zero, as the decoder does
+    for _ in 0..num_views {
+        if adi.intrinsic_param_gvd_flag {
+            adi.gvd_focal_length_x.push(random_sei_float_value(
+                adi.prec_gvd_focal_length,
+                fv,
+                film,
+            ));
+            adi.gvd_focal_length_y.push(random_sei_float_value(
+                adi.prec_gvd_focal_length,
+                fv,
+                film,
+            ));
+            adi.gvd_principal_point_x.push(random_sei_float_value(
+                adi.prec_gvd_principal_point,
+                fv,
+                film,
+            ));
+            adi.gvd_principal_point_y.push(random_sei_float_value(
+                adi.prec_gvd_principal_point,
+                fv,
+                film,
+            ));
+        } else {
+            adi.gvd_focal_length_x.push(SEIFloatValue::default());
+            adi.gvd_focal_length_y.push(SEIFloatValue::default());
+            adi.gvd_principal_point_x.push(SEIFloatValue::default());
+            adi.gvd_principal_point_y.push(SEIFloatValue::default());
+        }
+        let mut r = [[SEIFloatValue::default(); 3]; 3];
+        if adi.rotation_gvd_flag {
+            for row in r.iter_mut() {
+                for value in row.iter_mut() {
+                    *value = random_sei_float_value(adi.prec_gvd_rotation_param, fv, film);
+                }
+            }
+        }
+        adi.gvd_r.push(r);
+        adi.gvd_t_x.push(if adi.translation_gvd_flag {
+            random_sei_float_value(adi.prec_gvd_translation_param, fv, film)
+        } else {
+            SEIFloatValue::default()
+        });
+    }
+
+    adi
+}
+
+/// Generate the sei_message()s carried by a nesting SEI. Nesting SEIs may be
+/// nested in turn, so the recursion stops at MAX_SEI_NESTING_DEPTH
+fn random_nested_sei(
+    num_nested_seis: RandomU32Range,
+    nested_payload_type: &RandomU32Enum,
+    nesting_depth: usize,
+    rconfig: &RandomSEIRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) -> SEINalu {
+    let mut nested_sei = SEINalu::new();
+
+    if nesting_depth >= MAX_SEI_NESTING_DEPTH {
+        return nested_sei;
+    }
+
+    let num_seis = num_nested_seis.sample(film);
+    for _ in 0..num_seis {
+        let payload_type = nested_payload_type.sample(film);
+        nested_sei.payload_type.push(payload_type);
+        let sei_payload = random_sei_payload(payload_type, rconfig, ds, film, nesting_depth + 1);
+        nested_sei.payload.push(sei_payload);
+        nested_sei.payload_size.push(0); // the encoder will properly put the size
+    }
+
+    nested_sei
+}
+
+/// Generate an SEI Payload of type 24 - Scalability information
+fn random_scalability_info(
+    rconfig: RandomSEIScalabilityInfoRange,
+    film: &mut FilmState,
+) -> SEIScalabilityInfo {
+    let mut si = SEIScalabilityInfo::new();
+
+    si.temporal_id_nesting_flag = rconfig.temporal_id_nesting_flag.sample(film);
+    si.priority_layer_info_present_flag = rconfig.priority_layer_info_present_flag.sample(film);
+    si.priority_id_setting_flag = rconfig.priority_id_setting_flag.sample(film);
+    si.num_layers_minus1 = rconfig.num_layers_minus1.sample(film);
+
+    for i in 0..=si.num_layers_minus1 as usize {
+        si.layer_id.push(rconfig.layer_id.sample(film));
+        si.priority_id
+            .push(rconfig.priority_id.sample_custom_max(63, film) as u8);
+        si.discardable_flag
+            .push(rconfig.discardable_flag.sample(film));
+        si.dependency_id
+            .push(rconfig.dependency_id.sample_custom_max(7, film) as u8);
+        si.quality_id
+            .push(rconfig.quality_id.sample_custom_max(15, film) as u8);
+        si.temporal_id
+            .push(rconfig.temporal_id.sample_custom_max(7, film) as u8);
+        si.sub_pic_layer_flag
+            .push(rconfig.sub_pic_layer_flag.sample(film));
+        si.sub_region_layer_flag
+            .push(rconfig.sub_region_layer_flag.sample(film));
+        si.iroi_division_info_present_flag
+            .push(rconfig.iroi_division_info_present_flag.sample(film));
+        si.profile_level_info_present_flag
+            .push(rconfig.profile_level_info_present_flag.sample(film));
+        si.bitrate_info_present_flag
+            .push(rconfig.bitrate_info_present_flag.sample(film));
+        si.frm_rate_info_present_flag
+            .push(rconfig.frm_rate_info_present_flag.sample(film));
+        si.frm_size_info_present_flag
+            .push(rconfig.frm_size_info_present_flag.sample(film));
+        si.layer_dependency_info_present_flag
+            .push(rconfig.layer_dependency_info_present_flag.sample(film));
+        si.parameter_sets_info_present_flag
+            .push(rconfig.parameter_sets_info_present_flag.sample(film));
+        si.bitstream_restriction_info_present_flag
+            .push(rconfig.bitstream_restriction_info_present_flag.sample(film));
+        si.exact_inter_layer_pred_flag
+            .push(rconfig.exact_inter_layer_pred_flag.sample(film));
+        if si.sub_pic_layer_flag[i] || si.iroi_division_info_present_flag[i] {
+            si.exact_sample_value_match_flag
+                .push(rconfig.exact_sample_value_match_flag.sample(film));
+        } else {
+            si.exact_sample_value_match_flag.push(false);
+        }
+        si.layer_conversion_flag
+            .push(rconfig.layer_conversion_flag.sample(film));
+        si.layer_output_flag
+            .push(rconfig.layer_output_flag.sample(film));
+
+        if si.profile_level_info_present_flag[i] {
+            si.layer_profile_level_idc
+                .push(rconfig.profile_level_idc.sample_custom_max(16777215, film));
+        } else {
+            si.layer_profile_level_idc.push(0);
+        }
+
+        if si.bitrate_info_present_flag[i] {
+            si.avg_bitrate
+                .push(rconfig.bitrate.sample_custom_max(65535, film));
+            si.max_bitrate_layer
+                .push(rconfig.bitrate.sample_custom_max(65535, film));
+            si.max_bitrate_layer_representation
+                .push(rconfig.bitrate.sample_custom_max(65535, film));
+            si.max_bitrate_calc_window
+                .push(rconfig.bitrate.sample_custom_max(65535, film));
+        } else {
+            si.avg_bitrate.push(0);
+            si.max_bitrate_layer.push(0);
+            si.max_bitrate_layer_representation.push(0);
+            si.max_bitrate_calc_window.push(0);
+        }
+
+        if si.frm_rate_info_present_flag[i] {
+            si.constant_frm_rate_idc
+                .push(rconfig.constant_frm_rate_idc.sample_custom_max(3, film) as u8);
+            si.avg_frm_rate
+                .push(rconfig.avg_frm_rate.sample_custom_max(65535, film));
+        } else {
+            si.constant_frm_rate_idc.push(0);
+            si.avg_frm_rate.push(0);
+        }
+
+        if si.frm_size_info_present_flag[i] || si.iroi_division_info_present_flag[i] {
+            si.frm_width_in_mbs_minus1
+                .push(rconfig.frm_size_in_mbs_minus1.sample(film));
+            si.frm_height_in_mbs_minus1
+                .push(rconfig.frm_size_in_mbs_minus1.sample(film));
+        } else {
+            si.frm_width_in_mbs_minus1.push(0);
+            si.frm_height_in_mbs_minus1.push(0);
+        }
+
+        si.base_region_layer_id.push(0);
+        si.dynamic_rect_flag.push(false);
+        si.horizontal_offset.push(0);
+        si.vertical_offset.push(0);
+        si.region_width.push(0);
+        si.region_height.push(0);
+        if si.sub_region_layer_flag[i] {
+            si.base_region_layer_id[i] = rconfig.base_region_layer_id.sample(film);
+            si.dynamic_rect_flag[i] = rconfig.dynamic_rect_flag.sample(film);
+            if !si.dynamic_rect_flag[i] {
+                si.horizontal_offset[i] = rconfig.region_offset.sample_custom_max(65535, film);
+                si.vertical_offset[i] = rconfig.region_offset.sample_custom_max(65535, film);
+                si.region_width[i] = rconfig.region_size.sample_custom_max(65535, film);
+                si.region_height[i] = rconfig.region_size.sample_custom_max(65535, film);
+            }
+        }
+
+        if si.sub_pic_layer_flag[i] {
+            si.roi_id.push(rconfig.roi_id.sample(film));
+        } else {
+            si.roi_id.push(0);
+        }
+
+        si.iroi_grid_flag.push(false);
+        si.grid_width_in_mbs_minus1.push(0);
+        si.grid_height_in_mbs_minus1.push(0);
+        si.num_rois_minus1.push(0);
+        si.first_mb_in_roi.push(Vec::new());
+        si.roi_width_in_mbs_minus1.push(Vec::new());
+        si.roi_height_in_mbs_minus1.push(Vec::new());
+        if si.iroi_division_info_present_flag[i] {
+            si.iroi_grid_flag[i] = rconfig.iroi_grid_flag.sample(film);
+            if si.iroi_grid_flag[i] {
+                si.grid_width_in_mbs_minus1[i] = rconfig.grid_size_in_mbs_minus1.sample(film);
+                si.grid_height_in_mbs_minus1[i] = rconfig.grid_size_in_mbs_minus1.sample(film);
+            } else {
+                si.num_rois_minus1[i] = rconfig.num_rois_minus1.sample(film);
+                for _ in 0..=si.num_rois_minus1[i] {
+                    si.first_mb_in_roi[i].push(rconfig.roi_mb_value.sample(film));
+                    si.roi_width_in_mbs_minus1[i].push(rconfig.roi_mb_value.sample(film));
+                    si.roi_height_in_mbs_minus1[i].push(rconfig.roi_mb_value.sample(film));
+                }
+            }
+        }
+
+        si.num_directly_dependent_layers.push(0);
+        si.directly_dependent_layer_id_delta_minus1.push(Vec::new());
+        si.layer_dependency_info_src_layer_id_delta.push(0);
+        if si.layer_dependency_info_present_flag[i] {
+            si.num_directly_dependent_layers[i] =
+                rconfig.num_directly_dependent_layers.sample(film);
+            for _ in 0..si.num_directly_dependent_layers[i] {
+                si.directly_dependent_layer_id_delta_minus1[i]
+                    .push(rconfig.layer_id_delta.sample(film));
+            }
+        } else {
+            si.layer_dependency_info_src_layer_id_delta[i] = rconfig.layer_id_delta.sample(film);
+        }
+
+        si.num_seq_parameter_sets.push(0);
+        si.seq_parameter_set_id_delta.push(Vec::new());
+        si.num_subset_seq_parameter_sets.push(0);
+        si.subset_seq_parameter_set_id_delta.push(Vec::new());
+        si.num_pic_parameter_sets_minus1.push(0);
+        si.pic_parameter_set_id_delta.push(Vec::new());
+        si.parameter_sets_info_src_layer_id_delta.push(0);
+        if si.parameter_sets_info_present_flag[i] {
+            si.num_seq_parameter_sets[i] = rconfig.num_parameter_sets.sample(film);
+            for _ in 0..si.num_seq_parameter_sets[i] {
+                si.seq_parameter_set_id_delta[i].push(rconfig.parameter_set_id_delta.sample(film));
+            }
+            si.num_subset_seq_parameter_sets[i] = rconfig.num_parameter_sets.sample(film);
+            for _ in 0..si.num_subset_seq_parameter_sets[i] {
+                si.subset_seq_parameter_set_id_delta[i]
+                    .push(rconfig.parameter_set_id_delta.sample(film));
+            }
+            si.num_pic_parameter_sets_minus1[i] = rconfig.num_parameter_sets.sample(film);
+            for _ in 0..=si.num_pic_parameter_sets_minus1[i] {
+                si.pic_parameter_set_id_delta[i].push(rconfig.parameter_set_id_delta.sample(film));
+            }
+        } else {
+            si.parameter_sets_info_src_layer_id_delta[i] = rconfig.layer_id_delta.sample(film);
+        }
+
+        if si.bitstream_restriction_info_present_flag[i] {
+            si.motion_vectors_over_pic_boundaries_flag
+                .push(rconfig.motion_vectors_over_pic_boundaries_flag.sample(film));
+            si.max_bytes_per_pic_denom
+                .push(rconfig.max_bytes_per_pic_denom.sample(film));
+            si.max_bits_per_mb_denom
+                .push(rconfig.max_bits_per_mb_denom.sample(film));
+            si.log2_max_mv_length_horizontal
+                .push(rconfig.log2_max_mv_length.sample(film));
+            si.log2_max_mv_length_vertical
+                .push(rconfig.log2_max_mv_length.sample(film));
+            si.max_num_reorder_frames
+                .push(rconfig.max_num_reorder_frames.sample(film));
+            si.max_dec_frame_buffering
+                .push(rconfig.max_dec_frame_buffering.sample(film));
+        } else {
+            si.motion_vectors_over_pic_boundaries_flag.push(false);
+            si.max_bytes_per_pic_denom.push(0);
+            si.max_bits_per_mb_denom.push(0);
+            si.log2_max_mv_length_horizontal.push(0);
+            si.log2_max_mv_length_vertical.push(0);
+            si.max_num_reorder_frames.push(0);
+            si.max_dec_frame_buffering.push(0);
+        }
+
+        si.conversion_type_idc.push(0);
+        si.rewriting_info_flag.push([false; 2]);
+        si.rewriting_profile_level_idc.push([0; 2]);
+        si.rewriting_avg_bitrate.push([0; 2]);
+        si.rewriting_max_bitrate.push([0; 2]);
+        if si.layer_conversion_flag[i] {
+            si.conversion_type_idc[i] = rconfig.conversion_type_idc.sample(film);
+            for j in 0..2 {
+                si.rewriting_info_flag[i][j] = rconfig.rewriting_info_flag.sample(film);
+                if si.rewriting_info_flag[i][j] {
+                    si.rewriting_profile_level_idc[i][j] =
+                        rconfig.profile_level_idc.sample_custom_max(16777215, film);
+                    si.rewriting_avg_bitrate[i][j] = rconfig.bitrate.sample_custom_max(65535, film);
+                    si.rewriting_max_bitrate[i][j] = rconfig.bitrate.sample_custom_max(65535, film);
+                }
+            }
+        }
+    }
+
+    if si.priority_layer_info_present_flag {
+        si.pr_num_dids_minus1 = rconfig.pr_num_dids_minus1.sample(film);
+        for i in 0..=si.pr_num_dids_minus1 as usize {
+            si.pr_dependency_id
+                .push(rconfig.pr_dependency_id.sample_custom_max(7, film) as u8);
+            si.pr_num_minus1.push(rconfig.pr_num_minus1.sample(film));
+            si.pr_id.push(Vec::new());
+            si.pr_profile_level_idc.push(Vec::new());
+            si.pr_avg_bitrate.push(Vec::new());
+            si.pr_max_bitrate.push(Vec::new());
+            for _ in 0..=si.pr_num_minus1[i] {
+                si.pr_id[i].push(rconfig.pr_id.sample(film));
+                si.pr_profile_level_idc[i]
+                    .push(rconfig.profile_level_idc.sample_custom_max(16777215, film));
+                si.pr_avg_bitrate[i].push(rconfig.bitrate.sample_custom_max(65535, film));
+                si.pr_max_bitrate[i].push(rconfig.bitrate.sample_custom_max(65535, film));
+            }
+        }
+    }
+
+    if si.priority_id_setting_flag {
+        // the URI is null-terminated, so only sample non-null bytes
+        let uri_length = rconfig.priority_id_setting_uri_length.sample(film);
+        for _ in 0..uri_length {
+            si.priority_id_setting_uri
+                .push(film.read_film_u32(1, 255) as u8);
+        }
+    }
+
+    si
+}
+
+/// Generate an SEI Payload of type 25 - Sub-picture scalable layer
+fn random_sub_pic_scalable_layer(
+    rconfig: RandomSEISubPicScalableLayerRange,
+    film: &mut FilmState,
+) -> SEISubPicScalableLayer {
+    let mut spsl = SEISubPicScalableLayer::new();
+
+    spsl.layer_id = rconfig.layer_id.sample(film);
+
+    spsl
+}
+
+/// Generate an SEI Payload of type 26 - Non-required layer representation
+fn random_non_required_layer_rep(
+    rconfig: RandomSEINonRequiredLayerRepRange,
+    film: &mut FilmState,
+) -> SEINonRequiredLayerRep {
+    let mut nrlr = SEINonRequiredLayerRep::new();
+
+    nrlr.num_info_entries_minus1 = rconfig.num_info_entries_minus1.sample(film);
+    for i in 0..=nrlr.num_info_entries_minus1 as usize {
+        nrlr.entry_dependency_id
+            .push(rconfig.entry_dependency_id.sample_custom_max(7, film) as u8);
+        nrlr.num_non_required_layer_reps_minus1
+            .push(rconfig.num_non_required_layer_reps_minus1.sample(film));
+        nrlr.non_required_layer_rep_dependency_id.push(Vec::new());
+        nrlr.non_required_layer_rep_quality_id.push(Vec::new());
+        for _ in 0..=nrlr.num_non_required_layer_reps_minus1[i] {
+            nrlr.non_required_layer_rep_dependency_id[i].push(
+                rconfig
+                    .non_required_layer_rep_dependency_id
+                    .sample_custom_max(7, film) as u8,
+            );
+            nrlr.non_required_layer_rep_quality_id[i].push(
+                rconfig
+                    .non_required_layer_rep_quality_id
+                    .sample_custom_max(15, film) as u8,
+            );
+        }
+    }
+
+    nrlr
+}
+
+/// Generate an SEI Payload of type 27 - Priority layer information
+fn random_priority_layer_info(
+    rconfig: RandomSEIPriorityLayerInfoRange,
+    film: &mut FilmState,
+) -> SEIPriorityLayerInfo {
+    let mut pli = SEIPriorityLayerInfo::new();
+
+    pli.pr_dependency_id = rconfig.pr_dependency_id.sample_custom_max(7, film) as u8;
+    pli.num_priority_ids = rconfig.num_priority_ids.sample_custom_max(15, film) as u8;
+    for _ in 0..pli.num_priority_ids {
+        pli.alt_priority_id
+            .push(rconfig.alt_priority_id.sample_custom_max(63, film) as u8);
+    }
+
+    pli
+}
+
+/// Generate an SEI Payload of type 28 - Layers not present
+fn random_layers_not_present(
+    rconfig: RandomSEILayersNotPresentRange,
+    film: &mut FilmState,
+) -> SEILayersNotPresent {
+    let mut lnp = SEILayersNotPresent::new();
+
+    lnp.num_layers = rconfig.num_layers.sample(film);
+    for _ in 0..lnp.num_layers {
+        lnp.layer_id.push(rconfig.layer_id.sample(film));
+    }
+
+    lnp
+}
+
+/// Generate an SEI Payload of type 29 - Layer dependency change
+fn random_layer_dependency_change(
+    rconfig: RandomSEILayerDependencyChangeRange,
+    film: &mut FilmState,
+) -> SEILayerDependencyChange {
+    let mut ldc = SEILayerDependencyChange::new();
+
+    ldc.num_layers_minus1 = rconfig.num_layers_minus1.sample(film);
+    for i in 0..=ldc.num_layers_minus1 as usize {
+        ldc.layer_id.push(rconfig.layer_id.sample(film));
+        ldc.layer_dependency_info_present_flag
+            .push(rconfig.layer_dependency_info_present_flag.sample(film));
+        ldc.num_directly_dependent_layers.push(0);
+        ldc.directly_dependent_layer_id_delta_minus1
+            .push(Vec::new());
+        ldc.layer_dependency_info_src_layer_id_delta_minus1.push(0);
+        if ldc.layer_dependency_info_present_flag[i] {
+            ldc.num_directly_dependent_layers[i] =
+                rconfig.num_directly_dependent_layers.sample(film);
+            for _ in 0..ldc.num_directly_dependent_layers[i] {
+                ldc.directly_dependent_layer_id_delta_minus1[i]
+                    .push(rconfig.layer_id_delta_minus1.sample(film));
+            }
+        } else {
+            ldc.layer_dependency_info_src_layer_id_delta_minus1[i] =
+                rconfig.layer_id_delta_minus1.sample(film);
+        }
+    }
+
+    ldc
+}
+
+/// Generate an SEI Payload of type 30 - Scalable nesting
+fn random_scalable_nesting(
+    nesting_depth: usize,
+    rconfig: &RandomSEIRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) -> SEIScalableNesting {
+    let nconfig = &rconfig.random_scalable_nesting_range;
+    let mut sn = SEIScalableNesting::new();
+
+    sn.all_layer_representations_in_au_flag =
+        nconfig.all_layer_representations_in_au_flag.sample(film);
+    if !sn.all_layer_representations_in_au_flag {
+        sn.num_layer_representations_minus1 = nconfig.num_layer_representations_minus1.sample(film);
+        for _ in 0..=sn.num_layer_representations_minus1 {
+            sn.sei_dependency_id
+                .push(nconfig.sei_dependency_id.sample_custom_max(7, film) as u8);
+            sn.sei_quality_id
+                .push(nconfig.sei_quality_id.sample_custom_max(15, film) as u8);
+        }
+        sn.sei_temporal_id = nconfig.sei_temporal_id.sample_custom_max(7, film) as u8;
+    }
+    sn.nested_sei = random_nested_sei(
+        nconfig.num_nested_seis,
+        &nconfig.nested_payload_type,
+        nesting_depth,
+        rconfig,
+        ds,
+        film,
+    );
+
+    sn
+}
+
+/// Generate an SEI Payload of type 31 or 44 - Base layer or base view temporal HRD
+fn random_temporal_hrd(rconfig: RandomSEITemporalHRDRange, film: &mut FilmState) -> SEITemporalHRD {
+    let mut th = SEITemporalHRD::new();
+
+    th.num_of_temporal_layers_minus1 = rconfig.num_of_temporal_layers_minus1.sample(film);
+    for i in 0..=th.num_of_temporal_layers_minus1 as usize {
+        th.sei_temporal_id
+            .push(rconfig.sei_temporal_id.sample_custom_max(7, film) as u8);
+        th.sei_timing_info_present_flag
+            .push(rconfig.sei_timing_info_present_flag.sample(film));
+        if th.sei_timing_info_present_flag[i] {
+            th.sei_num_units_in_tick
+                .push(rconfig.sei_num_units_in_tick.sample(film));
+            th.sei_time_scale.push(rconfig.sei_time_scale.sample(film));
+            th.sei_fixed_frame_rate_flag
+                .push(rconfig.sei_fixed_frame_rate_flag.sample(film));
+        } else {
+            th.sei_num_units_in_tick.push(0);
+            th.sei_time_scale.push(0);
+            th.sei_fixed_frame_rate_flag.push(false);
+        }
+        th.sei_nal_hrd_parameters_present_flag
+            .push(rconfig.sei_nal_hrd_parameters_present_flag.sample(film));
+        if th.sei_nal_hrd_parameters_present_flag[i] {
+            th.sei_nal_hrd_parameters
+                .push(random_hrd_parameters(rconfig.random_hrd_range, film));
+        } else {
+            th.sei_nal_hrd_parameters.push(HRDParameters::new());
+        }
+        th.sei_vcl_hrd_parameters_present_flag
+            .push(rconfig.sei_vcl_hrd_parameters_present_flag.sample(film));
+        if th.sei_vcl_hrd_parameters_present_flag[i] {
+            th.sei_vcl_hrd_parameters
+                .push(random_hrd_parameters(rconfig.random_hrd_range, film));
+        } else {
+            th.sei_vcl_hrd_parameters.push(HRDParameters::new());
+        }
+        if th.sei_nal_hrd_parameters_present_flag[i] || th.sei_vcl_hrd_parameters_present_flag[i] {
+            th.sei_low_delay_hrd_flag
+                .push(rconfig.sei_low_delay_hrd_flag.sample(film));
+        } else {
+            th.sei_low_delay_hrd_flag.push(false);
+        }
+        th.sei_pic_struct_present_flag
+            .push(rconfig.sei_pic_struct_present_flag.sample(film));
+    }
+
+    th
+}
+
+/// Generate an SEI Payload of type 32 - Quality layer integrity check
+fn random_quality_layer_integrity_check(
+    rconfig: RandomSEIQualityLayerIntegrityCheckRange,
+    film: &mut FilmState,
+) -> SEIQualityLayerIntegrityCheck {
+    let mut qlic = SEIQualityLayerIntegrityCheck::new();
+
+    qlic.num_info_entries_minus1 = rconfig.num_info_entries_minus1.sample(film);
+    for _ in 0..=qlic.num_info_entries_minus1 {
+        qlic.entry_dependency_id
+            .push(rconfig.entry_dependency_id.sample_custom_max(7, film) as u8);
+        qlic.quality_layer_crc
+            .push(rconfig.quality_layer_crc.sample_custom_max(65535, film));
+    }
+
+    qlic
+}
+
+/// Generate an SEI Payload of type 33 - Redundant picture property
+fn random_redundant_pic_property(
+    rconfig: RandomSEIRedundantPicPropertyRange,
+    film: &mut FilmState,
+) -> SEIRedundantPicProperty {
+    let mut rpp = SEIRedundantPicProperty::new();
+
+    rpp.num_dids_minus1 = rconfig.num_dids_minus1.sample(film);
+    for i in 0..=rpp.num_dids_minus1 as usize {
+        rpp.dependency_id
+            .push(rconfig.dependency_id.sample_custom_max(7, film) as u8);
+        rpp.num_qids_minus1
+            .push(rconfig.num_qids_minus1.sample(film));
+        rpp.quality_id.push(Vec::new());
+        rpp.num_redundant_pics_minus1.push(Vec::new());
+        rpp.redundant_pic_cnt_minus1.push(Vec::new());
+        rpp.pic_match_flag.push(Vec::new());
+        rpp.mb_type_match_flag.push(Vec::new());
+        rpp.motion_match_flag.push(Vec::new());
+        rpp.residual_match_flag.push(Vec::new());
+        rpp.intra_samples_match_flag.push(Vec::new());
+        for j in 0..=rpp.num_qids_minus1[i] as usize {
+            rpp.quality_id[i].push(rconfig.quality_id.sample_custom_max(15, film) as u8);
+            rpp.num_redundant_pics_minus1[i].push(rconfig.num_redundant_pics_minus1.sample(film));
+            rpp.redundant_pic_cnt_minus1[i].push(Vec::new());
+            rpp.pic_match_flag[i].push(Vec::new());
+            rpp.mb_type_match_flag[i].push(Vec::new());
+            rpp.motion_match_flag[i].push(Vec::new());
+            rpp.residual_match_flag[i].push(Vec::new());
+            rpp.intra_samples_match_flag[i].push(Vec::new());
+            for _ in 0..=rpp.num_redundant_pics_minus1[i][j] {
+                rpp.redundant_pic_cnt_minus1[i][j]
+                    .push(rconfig.redundant_pic_cnt_minus1.sample(film));
+                let pic_match_flag = rconfig.pic_match_flag.sample(film);
+                rpp.pic_match_flag[i][j].push(pic_match_flag);
+                if !pic_match_flag {
+                    rpp.mb_type_match_flag[i][j].push(rconfig.mb_type_match_flag.sample(film));
+                    rpp.motion_match_flag[i][j].push(rconfig.motion_match_flag.sample(film));
+                    rpp.residual_match_flag[i][j].push(rconfig.residual_match_flag.sample(film));
+                    rpp.intra_samples_match_flag[i][j]
+                        .push(rconfig.intra_samples_match_flag.sample(film));
+                } else {
+                    rpp.mb_type_match_flag[i][j].push(false);
+                    rpp.motion_match_flag[i][j].push(false);
+                    rpp.residual_match_flag[i][j].push(false);
+                    rpp.intra_samples_match_flag[i][j].push(false);
+                }
+            }
+        }
+    }
+
+    rpp
+}
+
+/// Generate an SEI Payload of type 34 - Temporal level zero dependency representation index
+fn random_tl0_dep_rep_index(
+    rconfig: RandomSEITl0DepRepIndexRange,
+    film: &mut FilmState,
+) -> SEITl0DepRepIndex {
+    let mut tdri = SEITl0DepRepIndex::new();
+
+    tdri.tl0_dep_rep_idx = rconfig.tl0_dep_rep_idx.sample_custom_max(255, film);
+    tdri.effective_idr_pic_id = rconfig.effective_idr_pic_id.sample_custom_max(65535, film);
+
+    tdri
+}
+
+/// Generate an SEI Payload of type 35 - Temporal level switching point
+fn random_tl_switching_point(
+    rconfig: RandomSEITlSwitchingPointRange,
+    film: &mut FilmState,
+) -> SEITlSwitchingPoint {
+    let mut tsp = SEITlSwitchingPoint::new();
+
+    tsp.delta_frame_num = rconfig.delta_frame_num.sample(film);
+
+    tsp
+}
+
+/// Generate an SEI Payload of type 36 - Parallel decoding information
+fn random_parallel_decoding_info(
+    rconfig: RandomSEIParallelDecodingInfoRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) -> SEIParallelDecodingInfo {
+    let mut pdi = SEIParallelDecodingInfo::new();
+
+    // the number of delays comes from the inter-view references of the last subset SPS
+    let [num_anchor_refs_l0, num_anchor_refs_l1, num_non_anchor_refs_l0, num_non_anchor_refs_l1] =
+        match ds.subset_spses.last() {
+            Some(subset_sps) => {
+                pdi.seq_parameter_set_id = subset_sps.sps.seq_parameter_set_id;
+                subset_sps.inter_view_ref_counts()
+            }
+            None => Default::default(),
+        };
+
+    // the decoder infers this from how many delays fit in the payload
+    pdi.anchor_pic_flag = rconfig.anchor_pic_flag.sample(film);
+    if pdi.anchor_pic_flag {
+        (
+            pdi.pdi_init_delay_anchor_minus2_l0,
+            pdi.pdi_init_delay_anchor_minus2_l1,
+        ) = random_pdi_init_delays(
+            &num_anchor_refs_l0,
+            &num_anchor_refs_l1,
+            rconfig.pdi_init_delay_minus2,
+            film,
+        );
+    } else {
+        (
+            pdi.pdi_init_delay_non_anchor_minus2_l0,
+            pdi.pdi_init_delay_non_anchor_minus2_l1,
+        ) = random_pdi_init_delays(
+            &num_non_anchor_refs_l0,
+            &num_non_anchor_refs_l1,
+            rconfig.pdi_init_delay_minus2,
+            film,
+        );
+    }
+
+    pdi
+}
+
+/// Generate the pdi_init_delay_*_minus2_l0 and _l1 values of views 1 and up
+fn random_pdi_init_delays(
+    num_refs_l0: &[u32],
+    num_refs_l1: &[u32],
+    pdi_init_delay_minus2: RandomU32Range,
+    film: &mut FilmState,
+) -> (Vec<Vec<u32>>, Vec<Vec<u32>>) {
+    let mut l0 = vec![Vec::new()];
+    let mut l1 = vec![Vec::new()];
+
+    for i in 1..num_refs_l0.len().min(num_refs_l1.len()) {
+        l0.push(
+            (0..num_refs_l0[i])
+                .map(|_| pdi_init_delay_minus2.sample(film))
+                .collect(),
+        );
+        l1.push(
+            (0..num_refs_l1[i])
+                .map(|_| pdi_init_delay_minus2.sample(film))
+                .collect(),
+        );
+    }
+
+    (l0, l1)
+}
+
+/// Generate an SEI Payload of type 37 or 48 - MVC or MVCD scalable nesting
+fn random_mvc_scalable_nesting(
+    mvcd: bool,
+    nesting_depth: usize,
+    rconfig: &RandomSEIRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) -> SEIMVCScalableNesting {
+    let nconfig = if mvcd {
+        &rconfig.random_mvcd_scalable_nesting_range
+    } else {
+        &rconfig.random_mvc_scalable_nesting_range
+    };
+    let mut msn = SEIMVCScalableNesting::new();
+
+    msn.operation_point_flag = nconfig.operation_point_flag.sample(film);
+    if !msn.operation_point_flag {
+        msn.all_view_components_in_au_flag = nconfig.all_view_components_in_au_flag.sample(film);
+        if !msn.all_view_components_in_au_flag {
+            msn.num_view_components_minus1 = nconfig.num_view_components_minus1.sample(film);
+            for _ in 0..=msn.num_view_components_minus1 {
+                msn.sei_view_id
+                    .push(nconfig.sei_view_id.sample_custom_max(1023, film));
+                if mvcd {
+                    msn.sei_view_applicability_flag
+                        .push(nconfig.sei_view_applicability_flag.sample(film));
+                }
+            }
+        }
+    } else {
+        if mvcd {
+            msn.sei_op_texture_only_flag = nconfig.sei_op_texture_only_flag.sample(film);
+        }
+        msn.num_view_components_op_minus1 = nconfig.num_view_components_op_minus1.sample(film);
+        for _ in 0..=msn.num_view_components_op_minus1 {
+            msn.sei_op_view_id
+                .push(nconfig.sei_op_view_id.sample_custom_max(1023, film));
+            if mvcd && !msn.sei_op_texture_only_flag {
+                msn.sei_op_depth_flag
+                    .push(nconfig.sei_op_depth_flag.sample(film));
+                msn.sei_op_texture_flag
+                    .push(nconfig.sei_op_texture_flag.sample(film));
+            }
+        }
+        msn.sei_op_temporal_id = nconfig.sei_op_temporal_id.sample_custom_max(7, film) as u8;
+    }
+    msn.nested_sei = random_nested_sei(
+        nconfig.num_nested_seis,
+        &nconfig.nested_payload_type,
+        nesting_depth,
+        rconfig,
+        ds,
+        film,
+    );
+
+    msn
+}
+
+/// Generate an SEI Payload of type 38 or 49 - View or MVCD view scalability information
+fn random_view_scalability_info(
+    mvcd: bool,
+    rconfig: RandomSEIViewScalabilityInfoRange,
+    film: &mut FilmState,
+) -> SEIViewScalabilityInfo {
+    let mut vsi = SEIViewScalabilityInfo::new();
+
+    vsi.num_operation_points_minus1 = rconfig.num_operation_points_minus1.sample(film);
+    for i in 0..=vsi.num_operation_points_minus1 as usize {
+        vsi.operation_point_id
+            .push(rconfig.operation_point_id.sample(film));
+        vsi.priority_id
+            .push(rconfig.priority_id.sample_custom_max(31, film) as u8);
+        vsi.temporal_id
+            .push(rconfig.temporal_id.sample_custom_max(7, film) as u8);
+        vsi.num_target_output_views_minus1
+            .push(rconfig.num_target_output_views_minus1.sample(film));
+        vsi.view_id.push(Vec::new());
+        vsi.view_info_depth_view_present_flag.push(Vec::new());
+        vsi.mvcd_depth_view_flag.push(Vec::new());
+        vsi.view_info_texture_view_present_flag.push(Vec::new());
+        vsi.mvcd_texture_view_flag.push(Vec::new());
+        for j in 0..=vsi.num_target_output_views_minus1[i] as usize {
+            vsi.view_id[i].push(rconfig.view_id.sample(film));
+            if mvcd {
+                vsi.view_info_depth_view_present_flag[i]
+                    .push(rconfig.view_info_depth_view_present_flag.sample(film));
+                if vsi.view_info_depth_view_present_flag[i][j] {
+                    vsi.mvcd_depth_view_flag[i].push(rconfig.mvcd_depth_view_flag.sample(film));
+                } else {
+                    vsi.mvcd_depth_view_flag[i].push(false);
+                }
+                vsi.view_info_texture_view_present_flag[i]
+                    .push(rconfig.view_info_texture_view_present_flag.sample(film));
+                if vsi.view_info_texture_view_present_flag[i][j] {
+                    vsi.mvcd_texture_view_flag[i].push(rconfig.mvcd_texture_view_flag.sample(film));
+                } else {
+                    vsi.mvcd_texture_view_flag[i].push(false);
+                }
+            }
+        }
+        vsi.profile_level_info_present_flag
+            .push(rconfig.profile_level_info_present_flag.sample(film));
+        vsi.bitrate_info_present_flag
+            .push(rconfig.bitrate_info_present_flag.sample(film));
+        vsi.frm_rate_info_present_flag
+            .push(rconfig.frm_rate_info_present_flag.sample(film));
+        // only signalled for operation points with a single target output view
+        if vsi.num_target_output_views_minus1[i] == 0 {
+            vsi.view_dependency_info_present_flag
+                .push(rconfig.view_dependency_info_present_flag.sample(film));
+        } else {
+            vsi.view_dependency_info_present_flag.push(false);
+        }
+        vsi.parameter_sets_info_present_flag
+            .push(rconfig.parameter_sets_info_present_flag.sample(film));
+        vsi.bitstream_restriction_info_present_flag
+            .push(rconfig.bitstream_restriction_info_present_flag.sample(film));
+
+        if vsi.profile_level_info_present_flag[i] {
+            vsi.op_profile_level_idc.push(
+                rconfig
+                    .op_profile_level_idc
+                    .sample_custom_max(16777215, film),
+            );
+        } else {
+            vsi.op_profile_level_idc.push(0);
+        }
+
+        if vsi.bitrate_info_present_flag[i] {
+            vsi.avg_bitrate
+                .push(rconfig.bitrate.sample_custom_max(65535, film));
+            vsi.max_bitrate
+                .push(rconfig.bitrate.sample_custom_max(65535, film));
+            vsi.max_bitrate_calc_window
+                .push(rconfig.bitrate.sample_custom_max(65535, film));
+        } else {
+            vsi.avg_bitrate.push(0);
+            vsi.max_bitrate.push(0);
+            vsi.max_bitrate_calc_window.push(0);
+        }
+
+        if vsi.frm_rate_info_present_flag[i] {
+            vsi.constant_frm_rate_idc
+                .push(rconfig.constant_frm_rate_idc.sample_custom_max(3, film) as u8);
+            vsi.avg_frm_rate
+                .push(rconfig.avg_frm_rate.sample_custom_max(65535, film));
+        } else {
+            vsi.constant_frm_rate_idc.push(0);
+            vsi.avg_frm_rate.push(0);
+        }
+
+        vsi.num_directly_dependent_views.push(0);
+        vsi.directly_dependent_view_id.push(Vec::new());
+        vsi.view_dependency_info_src_op_id.push(0);
+        if vsi.view_dependency_info_present_flag[i] {
+            vsi.num_directly_dependent_views[i] = rconfig.num_directly_dependent_views.sample(film);
+            for _ in 0..vsi.num_directly_dependent_views[i] {
+                vsi.directly_dependent_view_id[i]
+                    .push(rconfig.directly_dependent_view_id.sample(film));
+            }
+        } else {
+            vsi.view_dependency_info_src_op_id[i] = rconfig.src_op_id.sample(film);
+        }
+
+        vsi.num_seq_parameter_sets.push(0);
+        vsi.seq_parameter_set_id_delta.push(Vec::new());
+        vsi.num_subset_seq_parameter_sets.push(0);
+        vsi.subset_seq_parameter_set_id_delta.push(Vec::new());
+        vsi.num_pic_parameter_sets_minus1.push(0);
+        vsi.pic_parameter_set_id_delta.push(Vec::new());
+        vsi.parameter_sets_info_src_op_id.push(0);
+        if vsi.parameter_sets_info_present_flag[i] {
+            vsi.num_seq_parameter_sets[i] = rconfig.num_parameter_sets.sample(film);
+            for _ in 0..vsi.num_seq_parameter_sets[i] {
+                vsi.seq_parameter_set_id_delta[i].push(rconfig.parameter_set_id_delta.sample(film));
+            }
+            vsi.num_subset_seq_parameter_sets[i] = rconfig.num_parameter_sets.sample(film);
+            for _ in 0..vsi.num_subset_seq_parameter_sets[i] {
+                vsi.subset_seq_parameter_set_id_delta[i]
+                    .push(rconfig.parameter_set_id_delta.sample(film));
+            }
+            vsi.num_pic_parameter_sets_minus1[i] = rconfig.num_parameter_sets.sample(film);
+            for _ in 0..=vsi.num_pic_parameter_sets_minus1[i] {
+                vsi.pic_parameter_set_id_delta[i].push(rconfig.parameter_set_id_delta.sample(film));
+            }
+        } else {
+            vsi.parameter_sets_info_src_op_id[i] = rconfig.src_op_id.sample(film);
+        }
+
+        if vsi.bitstream_restriction_info_present_flag[i] {
+            vsi.motion_vectors_over_pic_boundaries_flag
+                .push(rconfig.motion_vectors_over_pic_boundaries_flag.sample(film));
+            vsi.max_bytes_per_pic_denom
+                .push(rconfig.max_bytes_per_pic_denom.sample(film));
+            vsi.max_bits_per_mb_denom
+                .push(rconfig.max_bits_per_mb_denom.sample(film));
+            vsi.log2_max_mv_length_horizontal
+                .push(rconfig.log2_max_mv_length.sample(film));
+            vsi.log2_max_mv_length_vertical
+                .push(rconfig.log2_max_mv_length.sample(film));
+            vsi.max_num_reorder_frames
+                .push(rconfig.max_num_reorder_frames.sample(film));
+            vsi.max_dec_frame_buffering
+                .push(rconfig.max_dec_frame_buffering.sample(film));
+        } else {
+            vsi.motion_vectors_over_pic_boundaries_flag.push(false);
+            vsi.max_bytes_per_pic_denom.push(0);
+            vsi.max_bits_per_mb_denom.push(0);
+            vsi.log2_max_mv_length_horizontal.push(0);
+            vsi.log2_max_mv_length_vertical.push(0);
+            vsi.max_num_reorder_frames.push(0);
+            vsi.max_dec_frame_buffering.push(0);
+        }
+    }
+
+    vsi
+}
+
+/// Generate an SEI Payload of type 39 - Multiview scene information
+fn random_multiview_scene_info(
+    rconfig: RandomSEIMultiviewSceneInfoRange,
+    film: &mut FilmState,
+) -> SEIMultiviewSceneInfo {
+    let mut msi = SEIMultiviewSceneInfo::new();
+
+    msi.max_disparity = rconfig.max_disparity.sample(film);
+
+    msi
+}
+
+/// Sample a mantissa of len bits, which may be longer than 32
+fn random_sei_mantissa(len: u32, mantissa: RandomU32Range, film: &mut FilmState) -> u64 {
+    let value = ((mantissa.sample(film) as u64) << 32) | mantissa.sample(film) as u64;
+    if len >= 64 {
+        value
+    } else {
+        value & ((1 << len) - 1)
+    }
+}
+
+/// Generate the sign, exponent and mantissa of a camera parameter
+fn random_sei_float_value(
+    prec: u32,
+    rconfig: RandomSEIFloatValueRange,
+    film: &mut FilmState,
+) -> SEIFloatValue {
+    let sign = rconfig.sign.sample(film);
+    let exponent = rconfig.exponent.sample(film) as u8;
+
+    SEIFloatValue {
+        sign,
+        exponent,
+        mantissa: random_sei_mantissa(sei_mantissa_len(exponent, prec), rconfig.mantissa, film),
+    }
+}
+
+/// Generate an SEI Payload of type 40 - Multiview acquisition information
+fn random_multiview_acquisition_info(
+    rconfig: RandomSEIMultiviewAcquisitionInfoRange,
+    film: &mut FilmState,
+) -> SEIMultiviewAcquisitionInfo {
+    let mut mai = SEIMultiviewAcquisitionInfo::new();
+    let fv = rconfig.random_float_value_range;
+
+    mai.num_views_minus1 = rconfig.num_views_minus1.sample(film);
+    mai.intrinsic_param_flag = rconfig.intrinsic_param_flag.sample(film);
+    mai.extrinsic_param_flag = rconfig.extrinsic_param_flag.sample(film);
+
+    if mai.intrinsic_param_flag {
+        mai.intrinsic_params_equal_flag = rconfig.intrinsic_params_equal_flag.sample(film);
+        mai.prec_focal_length = fv.prec.sample(film);
+        mai.prec_principal_point = fv.prec.sample(film);
+        mai.prec_skew_factor = fv.prec.sample(film);
+
+        let num_intrinsic_params = if mai.intrinsic_params_equal_flag {
+            0
+        } else {
+            mai.num_views_minus1
+        };
+        for _ in 0..=num_intrinsic_params {
+            mai.focal_length_x
+                .push(random_sei_float_value(mai.prec_focal_length, fv, film));
+            mai.focal_length_y
+                .push(random_sei_float_value(mai.prec_focal_length, fv, film));
+            mai.principal_point_x
+                .push(random_sei_float_value(mai.prec_principal_point, fv, film));
+            mai.principal_point_y
+                .push(random_sei_float_value(mai.prec_principal_point, fv, film));
+            mai.skew_factor
+                .push(random_sei_float_value(mai.prec_skew_factor, fv, film));
+        }
+    }
+
+    if mai.extrinsic_param_flag {
+        mai.prec_rotation_param = fv.prec.sample(film);
+        mai.prec_translation_param = fv.prec.sample(film);
+
+        for _ in 0..=mai.num_views_minus1 {
+            let mut r = [[SEIFloatValue::default(); 3]; 3];
+            let mut t = [SEIFloatValue::default(); 3];
+            for (row, t_value) in r.iter_mut().zip(t.iter_mut()) {
+                for value in row.iter_mut() {
+                    *value = random_sei_float_value(mai.prec_rotation_param, fv, film);
+                }
+                *t_value = random_sei_float_value(mai.prec_translation_param, fv, film);
+            }
+            mai.r.push(r);
+            mai.t.push(t);
+        }
+    }
+
+    mai
+}
+
+/// Generate an SEI Payload of type 41 - Non-required view component
+fn random_non_required_view_component(
+    rconfig: RandomSEINonRequiredViewComponentRange,
+    film: &mut FilmState,
+) -> SEINonRequiredViewComponent {
+    let mut nrvc = SEINonRequiredViewComponent::new();
+
+    nrvc.num_info_entries_minus1 = rconfig.num_info_entries_minus1.sample(film);
+    for i in 0..=nrvc.num_info_entries_minus1 as usize {
+        nrvc.view_order_index
+            .push(rconfig.view_order_index.sample(film));
+        nrvc.num_non_required_view_components_minus1
+            .push(rconfig.num_non_required_view_components_minus1.sample(film));
+        nrvc.index_delta_minus1.push(Vec::new());
+        for _ in 0..=nrvc.num_non_required_view_components_minus1[i] {
+            nrvc.index_delta_minus1[i].push(rconfig.index_delta_minus1.sample(film));
+        }
+    }
+
+    nrvc
+}
+
+/// Generate an SEI Payload of type 42 - View dependency change
+fn random_view_dependency_change(
+    rconfig: RandomSEIViewDependencyChangeRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) -> SEIViewDependencyChange {
+    let mut vdc = SEIViewDependencyChange::new();
+
+    // the number of flags comes from the inter-view references of the last subset SPS
+    let [num_anchor_refs_l0, num_anchor_refs_l1, num_non_anchor_refs_l0, num_non_anchor_refs_l1] =
+        match ds.subset_spses.last() {
+            Some(subset_sps) => {
+                vdc.seq_parameter_set_id = subset_sps.sps.seq_parameter_set_id;
+                subset_sps.inter_view_ref_counts()
+            }
+            None => Default::default(),
+        };
+
+    vdc.anchor_update_flag = rconfig.anchor_update_flag.sample(film);
+    vdc.non_anchor_update_flag = rconfig.non_anchor_update_flag.sample(film);
+    if vdc.anchor_update_flag {
+        (vdc.anchor_ref_l0_flag, vdc.anchor_ref_l1_flag) = random_view_dependency_flags(
+            &num_anchor_refs_l0,
+            &num_anchor_refs_l1,
+            rconfig.ref_flag,
+            film,
+        );
+    }
+    if vdc.non_anchor_update_flag {
+        (vdc.non_anchor_ref_l0_flag, vdc.non_anchor_ref_l1_flag) = random_view_dependency_flags(
+            &num_non_anchor_refs_l0,
+            &num_non_anchor_refs_l1,
+            rconfig.ref_flag,
+            film,
+        );
+    }
+
+    vdc
+}
+
+/// Generate the *_ref_l0_flag and _l1_flag values of views 1 and up
+fn random_view_dependency_flags(
+    num_refs_l0: &[u32],
+    num_refs_l1: &[u32],
+    ref_flag: RandomBoolRange,
+    film: &mut FilmState,
+) -> (Vec<Vec<bool>>, Vec<Vec<bool>>) {
+    let mut l0 = vec![Vec::new()];
+    let mut l1 = vec![Vec::new()];
+
+    for i in 1..num_refs_l0.len().min(num_refs_l1.len()) {
+        l0.push((0..num_refs_l0[i]).map(|_| ref_flag.sample(film)).collect());
+        l1.push((0..num_refs_l1[i]).map(|_| ref_flag.sample(film)).collect());
+    }
+
+    (l0, l1)
+}
+
+/// Generate an SEI Payload of type 43 - Operation point not present
+fn random_operation_points_not_present(
+    rconfig: RandomSEIOperationPointsNotPresentRange,
+    film: &mut FilmState,
+) -> SEIOperationPointsNotPresent {
+    let mut opnp = SEIOperationPointsNotPresent::new();
+
+    opnp.num_operation_points = rconfig.num_operation_points.sample(film);
+    for _ in 0..opnp.num_operation_points {
+        opnp.operation_point_not_present_id
+            .push(rconfig.operation_point_not_present_id.sample(film));
+    }
+
+    opnp
+}
+
+/// Generate an SEI Payload of type 46 - Multiview view position
+fn random_multiview_view_position(
+    rconfig: RandomSEIMultiviewViewPositionRange,
+    film: &mut FilmState,
+) -> SEIMultiviewViewPosition {
+    let mut mvp = SEIMultiviewViewPosition::new();
+
+    mvp.num_views_minus1 = rconfig.num_views_minus1.sample(film);
+    for _ in 0..=mvp.num_views_minus1 {
+        mvp.view_position.push(rconfig.view_position.sample(film));
+    }
+    mvp.multiview_view_position_extension_flag =
+        rconfig.multiview_view_position_extension_flag.sample(film);
+
+    mvp
+}
+
+/// Generate a depth representation SEI element
+fn random_depth_representation_sei_element(
+    rconfig: RandomSEIDepthRepresentationElementRange,
+    film: &mut FilmState,
+) -> SEIDepthRepresentationElement {
+    let da_sign_flag = rconfig.da_sign_flag.sample(film);
+    let da_exponent = rconfig.da_exponent.sample(film) as u8;
+    let da_mantissa_len_minus1 = rconfig.da_mantissa_len_minus1.sample(film) as u8;
+
+    SEIDepthRepresentationElement {
+        da_sign_flag,
+        da_exponent,
+        da_mantissa_len_minus1,
+        da_mantissa: random_sei_mantissa(
+            da_mantissa_len_minus1 as u32 + 1,
+            rconfig.da_mantissa,
+            film,
+        ) as u32,
+    }
+}
+
+/// Generate an SEI Payload of type 50 - Depth representation information
+fn random_depth_representation_info(
+    rconfig: RandomSEIDepthRepresentationInfoRange,
+    film: &mut FilmState,
+) -> SEIDepthRepresentationInfo {
+    let mut dri = SEIDepthRepresentationInfo::new();
+    let dre = rconfig.random_depth_representation_element_range;
+
+    dri.all_views_equal_flag = rconfig.all_views_equal_flag.sample(film);
+    if !dri.all_views_equal_flag {
+        dri.num_views_minus1 = rconfig.num_views_minus1.sample(film);
+    }
+    dri.z_near_flag = rconfig.z_near_flag.sample(film);
+    dri.z_far_flag = rconfig.z_far_flag.sample(film);
+    if dri.z_near_flag || dri.z_far_flag {
+        dri.z_axis_equal_flag = rconfig.z_axis_equal_flag.sample(film);
+        if dri.z_axis_equal_flag {
+            dri.common_z_axis_reference_view = rconfig.common_z_axis_reference_view.sample(film);
+        }
+    }
+    dri.d_min_flag = rconfig.d_min_flag.sample(film);
+    dri.d_max_flag = rconfig.d_max_flag.sample(film);
+    dri.depth_representation_type = rconfig.depth_representation_type.sample(film);
+
+    // absent values are stored as zero, as the decoder does
+    let num_views = if dri.all_views_equal_flag {
+        1
+    } else {
+        dri.num_views_minus1 as u64 + 1
+    };
+    for _ in 0..num_views {
+        dri.depth_info_view_id
+            .push(rconfig.depth_info_view_id.sample(film));
+        dri.z_axis_reference_view.push(
+            if (dri.z_near_flag || dri.z_far_flag) && !dri.z_axis_equal_flag {
+                rconfig.z_axis_reference_view.sample(film)
+            } else {
+                0
+            },
+        );
+        dri.disparity_reference_view
+            .push(if dri.d_min_flag || dri.d_max_flag {
+                rconfig.disparity_reference_view.sample(film)
+            } else {
+                0
+            });
+        dri.z_near.push(if dri.z_near_flag {
+            random_depth_representation_sei_element(dre, film)
+        } else {
+            SEIDepthRepresentationElement::default()
+        });
+        dri.z_far.push(if dri.z_far_flag {
+            random_depth_representation_sei_element(dre, film)
+        } else {
+            SEIDepthRepresentationElement::default()
+        });
+        dri.d_min.push(if dri.d_min_flag {
+            random_depth_representation_sei_element(dre, film)
+        } else {
+            SEIDepthRepresentationElement::default()
+        });
+        dri.d_max.push(if dri.d_max_flag {
+            random_depth_representation_sei_element(dre, film)
+        } else {
+            SEIDepthRepresentationElement::default()
+        });
+    }
+
+    if dri.depth_representation_type == 3 {
+        dri.depth_nonlinear_representation_num_minus1 = rconfig
+            .depth_nonlinear_representation_num_minus1
+            .sample(film);
+        for _ in 0..=dri.depth_nonlinear_representation_num_minus1 {
+            dri.depth_nonlinear_representation_model
+                .push(rconfig.depth_nonlinear_representation_model.sample(film));
+        }
+    }
+
+    dri
+}
+
+/// Generate an SEI Payload of type 51 - 3D reference displays information
+fn random_three_dimensional_reference_displays_info(
+    rconfig: RandomSEIThreeDimensionalReferenceDisplaysInfoRange,
+    film: &mut FilmState,
+) -> SEIThreeDimensionalReferenceDisplaysInfo {
+    let mut trdi = SEIThreeDimensionalReferenceDisplaysInfo::new();
+
+    trdi.prec_ref_baseline = rconfig.prec.sample(film);
+    trdi.prec_ref_display_width = rconfig.prec.sample(film);
+    trdi.ref_viewing_distance_flag = rconfig.ref_viewing_distance_flag.sample(film);
+    if trdi.ref_viewing_distance_flag {
+        trdi.prec_ref_viewing_dist = rconfig.prec.sample(film);
+    }
+    trdi.num_ref_displays_minus1 = rconfig.num_ref_displays_minus1.sample(film);
+
+    // absent values are stored as zero, as the decoder does
+    for i in 0..=trdi.num_ref_displays_minus1 as usize {
+        trdi.exponent_ref_baseline
+            .push(rconfig.exponent.sample(film) as u8);
+        trdi.mantissa_ref_baseline.push(random_sei_mantissa(
+            sei_mantissa_len(trdi.exponent_ref_baseline[i], trdi.prec_ref_baseline),
+            rconfig.mantissa,
+            film,
+        ));
+        trdi.exponent_ref_display_width
+            .push(rconfig.exponent.sample(film) as u8);
+        trdi.mantissa_ref_display_width.push(random_sei_mantissa(
+            sei_mantissa_len(
+                trdi.exponent_ref_display_width[i],
+                trdi.prec_ref_display_width,
+            ),
+            rconfig.mantissa,
+            film,
+        ));
+        if trdi.ref_viewing_distance_flag {
+            trdi.exponent_ref_viewing_distance
+                .push(rconfig.exponent.sample(film) as u8);
+            trdi.mantissa_ref_viewing_distance.push(random_sei_mantissa(
+                sei_mantissa_len(
+                    trdi.exponent_ref_viewing_distance[i],
+                    trdi.prec_ref_viewing_dist,
+                ),
+                rconfig.mantissa,
+                film,
+            ));
+        } else {
+            trdi.exponent_ref_viewing_distance.push(0);
+            trdi.mantissa_ref_viewing_distance.push(0);
+        }
+        trdi.additional_shift_present_flag
+            .push(rconfig.additional_shift_present_flag.sample(film));
+        trdi.num_sample_shift_plus512
+            .push(if trdi.additional_shift_present_flag[i] {
+                rconfig.num_sample_shift_plus512.sample(film)
+            } else {
+                0
+            });
+    }
+    trdi.three_dimensional_reference_displays_extension_flag = rconfig
+        .three_dimensional_reference_displays_extension_flag
+        .sample(film);
+
+    trdi
+}
+
+/// Generate a depth timing offset
+fn random_depth_timing_offset(
+    rconfig: RandomSEIDepthTimingRange,
+    film: &mut FilmState,
+) -> SEIDepthTimingOffset {
+    let offset_len_minus1 = rconfig.offset_len_minus1.sample(film) as u8;
+
+    SEIDepthTimingOffset {
+        offset_len_minus1,
+        depth_disp_delay_offset_fp: random_sei_mantissa(
+            offset_len_minus1 as u32 + 1,
+            rconfig.depth_disp_delay_offset_fp,
+            film,
+        ) as u32,
+        depth_disp_delay_offset_dp: rconfig.depth_disp_delay_offset_dp.sample(film) as u8,
+    }
+}
+
+/// Generate an SEI Payload of type 52 - Depth timing
+fn random_depth_timing(
+    rconfig: RandomSEIDepthTimingRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) -> SEIDepthTiming {
+    let mut dt = SEIDepthTiming::new();
+
+    // NumDepthViews comes from the last MVCD subset SPS, so per-view offsets
+    // are only generated when there is one
+    let num_depth_views = ds
+        .subset_spses
+        .iter()
+        .rev()
+        .find(|x| x.is_mvcd())
+        .map(|x| x.sps_mvcd.num_depth_views);
+
+    dt.per_view_depth_timing_flag =
+        num_depth_views.is_some() && rconfig.per_view_depth_timing_flag.sample(film);
+    let num_offsets = match num_depth_views {
+        Some(x) if dt.per_view_depth_timing_flag => x,
+        _ => 1,
+    };
+    for _ in 0..num_offsets {
+        dt.depth_timing_offset
+            .push(random_depth_timing_offset(rconfig, film));
+    }
+
+    dt
+}
+
+/// Generate a depth grid position
+fn random_depth_grid_position(
+    rconfig: RandomSEIDepthSamplingInfoRange,
+    film: &mut FilmState,
+) -> SEIDepthGridPosition {
+    SEIDepthGridPosition {
+        depth_grid_pos_x_fp: rconfig.depth_grid_pos_fp.sample(film),
+        depth_grid_pos_x_dp: rconfig.depth_grid_pos_dp.sample(film) as u8,
+        depth_grid_pos_x_sign_flag: rconfig.depth_grid_pos_sign_flag.sample(film),
+        depth_grid_pos_y_fp: rconfig.depth_grid_pos_fp.sample(film),
+        depth_grid_pos_y_dp: rconfig.depth_grid_pos_dp.sample(film) as u8,
+        depth_grid_pos_y_sign_flag: rconfig.depth_grid_pos_sign_flag.sample(film),
+    }
+}
+
+/// Generate an SEI Payload of type 53 - Depth sampling information
+fn random_depth_sampling_info(
+    rconfig: RandomSEIDepthSamplingInfoRange,
+    film: &mut FilmState,
+) -> SEIDepthSamplingInfo {
+    let mut dsi = SEIDepthSamplingInfo::new();
+
+    dsi.dttsr_x_mul = rconfig.dttsr_mul.sample(film);
+    dsi.dttsr_x_dp = rconfig.dttsr_dp.sample(film) as u8;
+    dsi.dttsr_y_mul = rconfig.dttsr_mul.sample(film);
+    dsi.dttsr_y_dp = rconfig.dttsr_dp.sample(film) as u8;
+    dsi.per_view_depth_grid_pos_flag = rconfig.per_view_depth_grid_pos_flag.sample(film);
+
+    if dsi.per_view_depth_grid_pos_flag {
+        dsi.num_video_plus_depth_views_minus1 =
+            rconfig.num_video_plus_depth_views_minus1.sample(film);
+        for _ in 0..=dsi.num_video_plus_depth_views_minus1 {
+            dsi.depth_grid_view_id
+                .push(rconfig.depth_grid_view_id.sample(film));
+            dsi.depth_grid_position
+                .push(random_depth_grid_position(rconfig, film));
+        }
+    } else {
+        dsi.depth_grid_position
+            .push(random_depth_grid_position(rconfig, film));
+    }
+
+    dsi
+}
+
+/// Generate an SEI Payload of type 54 - Constrained depth parameter set identifier
+fn random_constrained_depth_parameter_set_identifier(
+    rconfig: RandomSEIConstrainedDepthParameterSetIdentifierRange,
+    film: &mut FilmState,
+) -> SEIConstrainedDepthParameterSetIdentifier {
+    let mut cdpsi = SEIConstrainedDepthParameterSetIdentifier::new();
+
+    cdpsi.max_dps_id = rconfig.max_dps_id.sample(film);
+    cdpsi.max_dps_id_diff = rconfig.max_dps_id_diff.sample(film);
+
+    cdpsi
+}
+
+/// Generate an SEI Payload of type 45 - Frame packing arrangement
+fn random_frame_packing_arrangement(
+    rconfig: RandomSEIFramePackingRange,
+    film: &mut FilmState,
+) -> SEIFramePacking {
+    let mut fp = SEIFramePacking::new();
+
+    fp.frame_packing_arrangement_id = rconfig.frame_packing_arrangement_id.sample(film);
+    fp.frame_packing_arrangement_cancel_flag =
+        rconfig.frame_packing_arrangement_cancel_flag.sample(film);
+    if !fp.frame_packing_arrangement_cancel_flag {
+        fp.frame_packing_arrangement_type = rconfig
+            .frame_packing_arrangement_type
+            .sample_custom_max(127, film) as u8;
+        fp.quincunx_sampling_flag = rconfig.quincunx_sampling_flag.sample(film);
+        fp.content_interpretation_type = rconfig
+            .content_interpretation_type
+            .sample_custom_max(63, film) as u8;
+        fp.spatial_flipping_flag = rconfig.spatial_flipping_flag.sample(film);
+        fp.frame0_flipped_flag = rconfig.frame0_flipped_flag.sample(film);
+        fp.field_views_flag = rconfig.field_views_flag.sample(film);
+        fp.current_frame_is_frame0_flag = rconfig.current_frame_is_frame0_flag.sample(film);
+        fp.frame0_self_contained_flag = rconfig.frame0_self_contained_flag.sample(film);
+        fp.frame1_self_contained_flag = rconfig.frame1_self_contained_flag.sample(film);
+        if fp.grid_positions_present() {
+            fp.frame0_grid_position_x = rconfig.grid_position.sample_custom_max(15, film) as u8;
+            fp.frame0_grid_position_y = rconfig.grid_position.sample_custom_max(15, film) as u8;
+            fp.frame1_grid_position_x = rconfig.grid_position.sample_custom_max(15, film) as u8;
+            fp.frame1_grid_position_y = rconfig.grid_position.sample_custom_max(15, film) as u8;
+        }
+        fp.frame_packing_arrangement_reserved_byte = rconfig
+            .frame_packing_arrangement_reserved_byte
+            .sample_custom_max(255, film)
+            as u8;
+        fp.frame_packing_arrangement_repetition_period = rconfig
+            .frame_packing_arrangement_repetition_period
+            .sample(film);
+    }
+    fp.frame_packing_arrangement_extension_flag = rconfig
+        .frame_packing_arrangement_extension_flag
+        .sample(film);
+
+    fp
+}
+
+/// Generate an SEI Payload of type 47 - Display orientation
+fn random_display_orientation(
+    rconfig: RandomSEIDisplayOrientationRange,
+    film: &mut FilmState,
+) -> SEIDisplayOrientation {
+    let mut dor = SEIDisplayOrientation::new();
+
+    dor.display_orientation_cancel_flag = rconfig.display_orientation_cancel_flag.sample(film);
+    if !dor.display_orientation_cancel_flag {
+        dor.hor_flip = rconfig.hor_flip.sample(film);
+        dor.ver_flip = rconfig.ver_flip.sample(film);
+        dor.anticlockwise_rotation = rconfig
+            .anticlockwise_rotation
+            .sample_custom_max(65535, film);
+        dor.display_orientation_repetition_period =
+            rconfig.display_orientation_repetition_period.sample(film);
+        dor.display_orientation_extension_flag =
+            rconfig.display_orientation_extension_flag.sample(film);
+    }
+
+    dor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::data_structures::UUID_MISB_MICROSECTIME;
+    use crate::common::data_structures::UUID_X264;
+    use crate::common::test_support::round_trip;
+    use crate::common::test_support::stream_with_empty_sei;
+
+    #[test]
+    fn test_sei_user_data_unregistered_schemas() {
+        let mut ds = stream_with_empty_sei();
+
+        // only sample the built-in schemas
+        let mut rconfig = RandomSEIRange::new();
+        rconfig.num_seis = RandomU32Range::new(1, 1);
+        rconfig.payload_type = RandomU32Enum::new(vec![5]);
+        rconfig
+            .random_user_data_unregistered_range
+            .uuid_iso_iec_11578 = RandomU32Range::new(3, 4);
+        let mut film = FilmState::setup_film_from_seed(18);
+        for _ in 0..8 {
+            random_sei(0, &rconfig, &mut ds, &mut film);
+        }
+
+        for p in ds.seis[0].payload.iter() {
+            let udu = &p.unregistered_user_data;
+            if udu.uuid_iso_iec_11578 == UUID_X264 {
+                assert!(udu.user_data_payload_byte.starts_with(b"x264 - core "));
+                assert_eq!(udu.user_data_payload_byte.last(), Some(&0));
+            } else {
+                assert_eq!(udu.uuid_iso_iec_11578, UUID_MISB_MICROSECTIME);
+                assert_eq!(udu.user_data_payload_byte.len(), 12);
+                for i in [3, 6, 9] {
+                    assert_eq!(udu.user_data_payload_byte[i], 0xff);
+                }
+            }
+        }
+
+        let decoded = round_trip(&mut ds);
+
+        assert_eq!(decoded.seis.len(), 1);
+        assert_eq!(decoded.seis[0].payload_type, vec![5; 8]);
+        for (a, b) in decoded.seis[0]
+            .payload
+            .iter()
+            .zip(ds.seis[0].payload.iter())
+        {
+            assert_eq!(
+                a.unregistered_user_data.uuid_iso_iec_11578,
+                b.unregistered_user_data.uuid_iso_iec_11578
+            );
+            assert_eq!(
+                a.unregistered_user_data.user_data_payload_byte,
+                b.unregistered_user_data.user_data_payload_byte
+            );
+        }
+
+        // a vendor schema described in the configuration file
+        let schema: UserDataUnregisteredSchema = serde_json::from_str(
+            r#"{
+                "name": "vendor",
+                "uuid_iso_iec_11578": [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16],
+                "fields": [
+                    {"name": "version", "field_type": "U16", "value": {"min": 4660, "max": 4660}},
+                    {"name": "tag", "field_type": "Ascii", "values": ["abc"]},
+                    {"name": "reserved", "field_type": "Bytes", "length": {"min": 2, "max": 2}}
+                ]
+            }"#,
+        )
+        .unwrap();
+        rconfig.random_user_data_unregistered_range.schemas = vec![schema];
+        rconfig
+            .random_user_data_unregistered_range
+            .uuid_iso_iec_11578 = RandomU32Range::new(3, 3);
+        random_sei(0, &rconfig, &mut ds, &mut film);
+
+        let udu = &ds.seis[0].payload.last().unwrap().unregistered_user_data;
+        assert_eq!(
+            udu.uuid_iso_iec_11578,
+            [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]
+        );
+        assert_eq!(udu.user_data_payload_byte.len(), 7);
+        assert_eq!(
+            udu.user_data_payload_byte[..5],
+            [0x12, 0x34, b'a', b'b', b'c']
+        );
+    }
+}
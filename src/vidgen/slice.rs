@@ -6,12 +6,19 @@ use crate::common::data_structures::MbPartPredMode;
 use crate::common::data_structures::MbType;
 use crate::common::data_structures::PicParameterSet;
 use crate::common::data_structures::SeqParameterSet;
+use crate::common::data_structures::SliceData;
+use crate::common::data_structures::SliceDataPartition;
+use crate::common::data_structures::SliceHeader3DAVCExtension;
+use crate::common::data_structures::SliceHeaderSVCExtension;
 use crate::common::data_structures::SubMbType;
+use crate::common::data_structures::SubsetSPS;
 use crate::common::data_structures::VideoParameters;
 use crate::common::helper::is_slice_type;
 use crate::vidgen::film::FilmState;
 use crate::vidgen::generate_configurations::RandomMBRange;
+use crate::vidgen::generate_configurations::RandomSliceDataPartitionRange;
 use crate::vidgen::generate_configurations::RandomSliceHeaderRange;
+use crate::vidgen::generate_configurations::RandomSliceSVCExtensionRange;
 use crate::vidgen::generate_configurations::RandomizeConfig;
 use crate::vidgen::macroblock::random_b_mbtype;
 use crate::vidgen::macroblock::random_i_mbtype;
@@ -37,6 +44,7 @@ fn random_slice_data(
     ignore_ipcm: bool,
     empty_slice_data: bool,
     rconfig: &RandomMBRange,
+    svc_rconfig: &RandomSliceSVCExtensionRange,
     ds: &mut H264DecodedStream,
     film: &mut FilmState,
 ) {
@@ -146,7 +154,29 @@ fn random_slice_data(
                 ignore_intra_pred_flag = x_d == 0 || y_d == 0;
             }
 
-            if is_slice_type(ds.slices[slice_idx].sh.slice_type, "I") {
+            // base_mode_flag - G.7.3.6
+            let svc_extension_flag = ds.slices[slice_idx].sh.svc_extension_flag;
+            let in_crop_window = ds.slices[slice_idx]
+                .sh
+                .in_crop_window(ds.slices[slice_idx].sd.macroblock_vec[i].mb_addr, vp);
+            if svc_extension_flag {
+                ds.slices[slice_idx].sd.macroblock_vec[i].base_mode_flag = if in_crop_window
+                    && ds.slices[slice_idx]
+                        .sh
+                        .svc_extension
+                        .adaptive_base_mode_flag
+                {
+                    svc_rconfig.base_mode_flag.sample(film)
+                } else {
+                    in_crop_window && ds.slices[slice_idx].sh.svc_extension.default_base_mode_flag
+                };
+            }
+            let base_mode_flag = ds.slices[slice_idx].sd.macroblock_vec[i].base_mode_flag;
+
+            if base_mode_flag {
+                // the macroblock type is inferred from the reference layer
+                ds.slices[slice_idx].sd.macroblock_vec[i].mb_type = MbType::INONE;
+            } else if is_slice_type(ds.slices[slice_idx].sh.slice_type, "I") {
                 if empty_slice_data {
                     ds.slices[slice_idx].sd.macroblock_vec[i].mb_type = MbType::INxN;
                 } else {
@@ -190,13 +220,32 @@ fn random_slice_data(
                 ds.slices[slice_idx].sd.macroblock_vec[i].no_sub_mb_part_size_less_than_8x8_flag =
                     true;
 
-                if ds.slices[slice_idx].sd.macroblock_vec[i].mb_type != MbType::INxN
+                if base_mode_flag {
+                    // prediction data is inferred from the reference layer
+                } else if ds.slices[slice_idx].sd.macroblock_vec[i].mb_type != MbType::INxN
                     && ds.slices[slice_idx].sd.macroblock_vec[i].mb_part_pred_mode(0)
                         != MbPartPredMode::Intra16x16
                     && ds.slices[slice_idx].sd.macroblock_vec[i].num_mb_part() == 4
                 {
                     // sub_mb_pred
                     randomize_sub_mb_pred(slice_idx, i, rconfig, ds, film);
+                    if svc_extension_flag {
+                        let pred_modes: Vec<MbPartPredMode> = (0..4)
+                            .map(|mb_part_idx| {
+                                ds.slices[slice_idx].sd.macroblock_vec[i]
+                                    .sub_mb_part_pred_mode(mb_part_idx)
+                            })
+                            .collect();
+                        random_motion_prediction_flags(
+                            slice_idx,
+                            i,
+                            &pred_modes,
+                            in_crop_window,
+                            svc_rconfig,
+                            ds,
+                            film,
+                        );
+                    }
                     for mb_part_idx in 0..4 {
                         if ds.slices[slice_idx].sd.macroblock_vec[i].sub_mb_type[mb_part_idx]
                             != SubMbType::BDirect8x8
@@ -233,10 +282,63 @@ fn random_slice_data(
                         ds,
                         film,
                     );
+
+                    let mppm = ds.slices[slice_idx].sd.macroblock_vec[i].mb_part_pred_mode(0);
+                    if svc_extension_flag
+                        && !ds.slices[slice_idx].sd.macroblock_vec[i].is_intra_non_mut()
+                        && mppm != MbPartPredMode::Direct
+                    {
+                        let pred_modes: Vec<MbPartPredMode> =
+                            (0..ds.slices[slice_idx].sd.macroblock_vec[i].num_mb_part())
+                                .map(|mb_part_idx| {
+                                    ds.slices[slice_idx].sd.macroblock_vec[i]
+                                        .mb_part_pred_mode(mb_part_idx)
+                                })
+                                .collect();
+                        random_motion_prediction_flags(
+                            slice_idx,
+                            i,
+                            &pred_modes,
+                            in_crop_window,
+                            svc_rconfig,
+                            ds,
+                            film,
+                        );
+                    }
+                }
+
+                // residual_prediction_flag - G.7.3.6
+                if svc_extension_flag {
+                    ds.slices[slice_idx].sd.macroblock_vec[i].residual_prediction_flag = if ds
+                        .slices[slice_idx]
+                        .sh
+                        .svc_extension
+                        .adaptive_residual_prediction_flag
+                        && !is_slice_type(ds.slices[slice_idx].sh.slice_type, "I")
+                        && in_crop_window
+                        && (base_mode_flag
+                            || !ds.slices[slice_idx].sd.macroblock_vec[i].is_intra_non_mut())
+                    {
+                        svc_rconfig.residual_prediction_flag.sample(film)
+                    } else {
+                        in_crop_window
+                            && !is_slice_type(ds.slices[slice_idx].sh.slice_type, "I")
+                            && ds.slices[slice_idx]
+                                .sh
+                                .svc_extension
+                                .default_residual_prediction_flag
+                    };
                 }
 
-                if ds.slices[slice_idx].sd.macroblock_vec[i].mb_part_pred_mode(0)
-                    != MbPartPredMode::Intra16x16
+                // an empty scan range means no residual syntax elements are present
+                let empty_scan_range = svc_extension_flag
+                    && ds.slices[slice_idx].sh.svc_extension.scan_idx_end
+                        < ds.slices[slice_idx].sh.svc_extension.scan_idx_start;
+
+                if !empty_scan_range
+                    && (base_mode_flag
+                        || ds.slices[slice_idx].sd.macroblock_vec[i].mb_part_pred_mode(0)
+                            != MbPartPredMode::Intra16x16)
                 {
                     // Bias B and P types to not have as much residue data
                     if is_slice_type(ds.slices[slice_idx].sh.slice_type, "B")
@@ -259,7 +361,7 @@ fn random_slice_data(
                     }
 
                     // for these chroma array types let's zero out the chroma part
-                    if sps.chroma_format_idc == 0 || sps.chroma_format_idc == 3 {
+                    if vp.chroma_array_type == 0 || vp.chroma_array_type == 3 {
                         // zero out the top two bits
                         ds.slices[slice_idx].sd.macroblock_vec[i].coded_block_pattern &= 0xF;
                     }
@@ -271,12 +373,13 @@ fn random_slice_data(
 
                     if ds.slices[slice_idx].sd.macroblock_vec[i].coded_block_pattern_luma > 0
                         && pps.transform_8x8_mode_flag
-                        && ds.slices[slice_idx].sd.macroblock_vec[i].mb_type != MbType::INxN
-                        && ds.slices[slice_idx].sd.macroblock_vec[i]
-                            .no_sub_mb_part_size_less_than_8x8_flag
-                        && (ds.slices[slice_idx].sd.macroblock_vec[i].mb_type
-                            != MbType::BDirect16x16
-                            || sps.direct_8x8_inference_flag)
+                        && (base_mode_flag
+                            || (ds.slices[slice_idx].sd.macroblock_vec[i].mb_type != MbType::INxN
+                                && ds.slices[slice_idx].sd.macroblock_vec[i]
+                                    .no_sub_mb_part_size_less_than_8x8_flag
+                                && (ds.slices[slice_idx].sd.macroblock_vec[i].mb_type
+                                    != MbType::BDirect16x16
+                                    || sps.direct_8x8_inference_flag)))
                     {
                         ds.slices[slice_idx].sd.macroblock_vec[i].transform_size_8x8_flag =
                             rconfig.transform_size_8x8_flag.sample(film);
@@ -285,10 +388,11 @@ fn random_slice_data(
                 // call this in case we're the appropriate type Intra16x16 type
                 ds.slices[slice_idx].sd.macroblock_vec[i].set_cbp_chroma_and_luma();
 
-                if ds.slices[slice_idx].sd.macroblock_vec[i].coded_block_pattern_luma > 0
-                    || ds.slices[slice_idx].sd.macroblock_vec[i].coded_block_pattern_chroma > 0
-                    || ds.slices[slice_idx].sd.macroblock_vec[i].mb_part_pred_mode(0)
-                        == MbPartPredMode::Intra16x16
+                if !empty_scan_range
+                    && (ds.slices[slice_idx].sd.macroblock_vec[i].coded_block_pattern_luma > 0
+                        || ds.slices[slice_idx].sd.macroblock_vec[i].coded_block_pattern_chroma > 0
+                        || ds.slices[slice_idx].sd.macroblock_vec[i].mb_part_pred_mode(0)
+                            == MbPartPredMode::Intra16x16)
                 {
                     ds.slices[slice_idx].sd.macroblock_vec[i].mb_qp_delta =
                         rconfig.mb_qp_delta.sample(film);
@@ -331,6 +435,51 @@ fn random_slice_data(
     ds.slices[slice_idx].sd.end_of_slice_flag[last_mb_idx] = true;
 }
 
+/// Generate motion_prediction_flag_l0 and motion_prediction_flag_l1 following G.7.3.6.1 and G.7.3.6.2
+///
+/// pred_modes holds the prediction mode of each macroblock or sub-macroblock partition
+fn random_motion_prediction_flags(
+    slice_idx: usize,
+    mb_idx: usize,
+    pred_modes: &[MbPartPredMode],
+    in_crop_window: bool,
+    rconfig: &RandomSliceSVCExtensionRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    if in_crop_window
+        && ds.slices[slice_idx]
+            .sh
+            .svc_extension
+            .adaptive_motion_prediction_flag
+    {
+        for (mb_part_idx, pred_mode) in pred_modes.iter().enumerate() {
+            if *pred_mode != MbPartPredMode::PredL1 && *pred_mode != MbPartPredMode::Direct {
+                ds.slices[slice_idx].sd.macroblock_vec[mb_idx].motion_prediction_flag_l0
+                    [mb_part_idx] = rconfig.motion_prediction_flag_l0.sample(film);
+            }
+        }
+        for (mb_part_idx, pred_mode) in pred_modes.iter().enumerate() {
+            if *pred_mode != MbPartPredMode::PredL0 && *pred_mode != MbPartPredMode::Direct {
+                ds.slices[slice_idx].sd.macroblock_vec[mb_idx].motion_prediction_flag_l1
+                    [mb_part_idx] = rconfig.motion_prediction_flag_l1.sample(film);
+            }
+        }
+    } else {
+        let inferred = in_crop_window
+            && ds.slices[slice_idx]
+                .sh
+                .svc_extension
+                .default_motion_prediction_flag;
+        for mb_part_idx in 0..pred_modes.len() {
+            ds.slices[slice_idx].sd.macroblock_vec[mb_idx].motion_prediction_flag_l0[mb_part_idx] =
+                inferred;
+            ds.slices[slice_idx].sd.macroblock_vec[mb_idx].motion_prediction_flag_l1[mb_part_idx] =
+                inferred;
+        }
+    }
+}
+
 fn random_ref_pic_list_modification(
     slice_idx: usize,
     rconfig: &RandomSliceHeaderRange,
@@ -757,6 +906,73 @@ fn random_dec_ref_pic_marking(
     }
 }
 
+/// Overwrite frame_num, pic_order_cnt_lsb, and idr_pic_id to follow the decoding order
+/// progression of 7.4.3 and 8.2.1.1, assuming one frame slice per picture, no gaps in
+/// frame_num, and no memory management control operations
+fn conforming_frame_num_and_poc(
+    nalu_idx: usize,
+    slice_idx: usize,
+    sps: &SeqParameterSet,
+    vp: &VideoParameters,
+    ds: &mut H264DecodedStream,
+) {
+    let max_frame_num = 1u64 << (sps.log2_max_frame_num_minus4 + 4).min(32);
+    let max_pic_order_cnt_lsb = 1u64 << (sps.log2_max_pic_order_cnt_lsb_minus4 as u32 + 4).min(32);
+
+    // the NALU header of the previous picture
+    let prev_nalu_header = match slice_idx {
+        0 => None,
+        _ => (0..nalu_idx)
+            .rev()
+            .map(|i| &ds.nalu_headers[i])
+            .find(|nh| [1, 2, 5].contains(&nh.nal_unit_type)),
+    };
+    let prev = prev_nalu_header.map(|nh| {
+        (
+            nh.nal_unit_type,
+            nh.nal_ref_idc,
+            ds.slices[slice_idx - 1].sh.frame_num,
+            ds.slices[slice_idx - 1].sh.pic_order_cnt_lsb,
+            ds.slices[slice_idx - 1].sh.idr_pic_id,
+        )
+    });
+
+    ds.slices[slice_idx].sh.delta_pic_order_cnt_bottom = 0;
+    if vp.idr_pic_flag {
+        ds.slices[slice_idx].sh.frame_num = 0;
+        ds.slices[slice_idx].sh.pic_order_cnt_lsb = 0;
+        // consecutive IDR access units have different idr_pic_id values
+        if let Some((5, _, _, _, prev_idr_pic_id)) = prev {
+            if prev_idr_pic_id == ds.slices[slice_idx].sh.idr_pic_id {
+                ds.slices[slice_idx].sh.idr_pic_id = (prev_idr_pic_id + 1) % 65536;
+            }
+        }
+    } else if let Some((_, prev_nal_ref_idc, prev_frame_num, prev_poc_lsb, _)) = prev {
+        // PrevRefFrameNum only advances after reference pictures
+        ds.slices[slice_idx].sh.frame_num = if prev_nal_ref_idc != 0 {
+            ((prev_frame_num as u64 + 1) % max_frame_num) as u32
+        } else {
+            prev_frame_num
+        };
+        // output order follows decoding order; like most encoders we only use even values
+        ds.slices[slice_idx].sh.pic_order_cnt_lsb =
+            ((prev_poc_lsb as u64 + 2) % max_pic_order_cnt_lsb) as u32;
+    }
+}
+
+/// Sample a slice_type, biasing the first slice and IDR slices towards I slices
+pub fn random_slice_type(
+    first_or_idr: bool,
+    rconfig: &RandomSliceHeaderRange,
+    film: &mut FilmState,
+) -> u8 {
+    if first_or_idr && rconfig.bias_i_slice.sample(film) {
+        2 // I slice
+    } else {
+        rconfig.slice_type.sample(film) as u8
+    }
+}
+
 /// Generate random slice header syntax elements.
 ///
 pub fn random_slice_header(
@@ -772,15 +988,17 @@ pub fn random_slice_header(
 ) {
     // NOTE: we sample first_mb_in_slice after determining field/frame slice
 
-    // bias the first slice towards an I slice or IDR NALU types to be I slices
-    if slice_idx == 0 || vp.idr_pic_flag {
-        if rconfig.bias_i_slice.sample(film) {
-            ds.slices[slice_idx].sh.slice_type = 2; // I slice
-        } else {
-            ds.slices[slice_idx].sh.slice_type = rconfig.slice_type.sample(film) as u8;
+    ds.slices[slice_idx].sh.slice_type =
+        random_slice_type(slice_idx == 0 || vp.idr_pic_flag, rconfig, film);
+    // scalable extension slices may only be EP, EB, or EI slices (G.7.4.3)
+    let svc_slice = ds.nalu_headers[nalu_idx].nal_unit_type == 20
+        && ds.nalu_headers[nalu_idx].svc_extension_flag;
+    if svc_slice {
+        if is_slice_type(ds.slices[slice_idx].sh.slice_type, "SP") {
+            ds.slices[slice_idx].sh.slice_type -= 3;
+        } else if is_slice_type(ds.slices[slice_idx].sh.slice_type, "SI") {
+            ds.slices[slice_idx].sh.slice_type -= 2;
         }
-    } else {
-        ds.slices[slice_idx].sh.slice_type = rconfig.slice_type.sample(film) as u8;
     }
     ds.slices[slice_idx].sh.pic_parameter_set_id = pps.pic_parameter_set_id;
 
@@ -881,6 +1099,10 @@ pub fn random_slice_header(
         }
     }
 
+    if rconfig.bias_conforming_frame_num_poc.sample(film) {
+        conforming_frame_num_and_poc(nalu_idx, slice_idx, sps, vp, ds);
+    }
+
     if pps.redundant_pic_cnt_present_flag {
         ds.slices[slice_idx].sh.redundant_pic_cnt = rconfig.redundant_pic_cnt.sample(film);
     }
@@ -917,7 +1139,7 @@ pub fn random_slice_header(
         }
     }
 
-    if ds.nalu_headers[nalu_idx].nal_unit_type == 20
+    if (ds.nalu_headers[nalu_idx].nal_unit_type == 20 && !svc_slice)
         || ds.nalu_headers[nalu_idx].nal_unit_type == 21
     {
         random_ref_pic_list_mvc_modification(slice_idx, rconfig, ds, film);
@@ -1084,6 +1306,14 @@ pub fn random_slice(
     }
 
     let mut vp = VideoParameters::new(&ds.nalu_headers[nalu_idx], pps, sps);
+    if ds.nalu_headers[nalu_idx].nal_unit_type == 19 {
+        if let Err(e) = vp.set_auxiliary_picture(&ds.sps_extensions, sps.seq_parameter_set_id) {
+            println!(
+                "\t [WARNING] {} - generating with the primary picture parameters",
+                e.message
+            );
+        }
+    }
     if randomize_header {
         random_slice_header(
             nalu_idx,
@@ -1096,9 +1326,9 @@ pub fn random_slice(
             ds,
             film,
         );
-        // for neighbor processing
-        vp.mbaff_frame_flag = ds.slices[slice_idx].sh.mbaff_frame_flag;
     }
+    // for neighbor processing
+    vp.mbaff_frame_flag = ds.slices[slice_idx].sh.mbaff_frame_flag;
     random_slice_data(
         slice_idx,
         pps,
@@ -1109,11 +1339,59 @@ pub fn random_slice(
         ignore_ipcm,
         empty_slice_data,
         &rconfig.random_mb_range,
+        &rconfig.random_slice_svc_extension_range,
         ds,
         film,
     );
 }
 
+/// Generate random slice data partition syntax elements for NALU types 2, 3, and 4.
+///
+/// slice_idx : the slice carried by partition A
+/// sdp_a : partition A of the slice, used by partitions B and C
+#[allow(clippy::too_many_arguments)]
+pub fn random_slice_data_partition(
+    nalu_idx: usize,
+    slice_idx: usize,
+    sdp_a: Option<&SliceDataPartition>,
+    pps: &PicParameterSet,
+    sps: &SeqParameterSet,
+    rconfig: &RandomSliceDataPartitionRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    let mut sdp = SliceDataPartition::new();
+
+    match sdp_a {
+        Some(sdp_a)
+            if ds.nalu_headers[nalu_idx].nal_unit_type != 2
+                && rconfig.bias_same_as_partition_a.sample(film) =>
+        {
+            sdp.slice_id = sdp_a.slice_id;
+            if sps.separate_colour_plane_flag {
+                sdp.colour_plane_id = ds.slices[slice_idx].sh.colour_plane_id;
+            }
+            if pps.redundant_pic_cnt_present_flag {
+                sdp.redundant_pic_cnt = ds.slices[slice_idx].sh.redundant_pic_cnt;
+            }
+        }
+        _ => {
+            sdp.slice_id = rconfig.slice_id.sample(film);
+            // colour_plane_id and redundant_pic_cnt are only in partitions B and C
+            if ds.nalu_headers[nalu_idx].nal_unit_type != 2 {
+                if sps.separate_colour_plane_flag {
+                    sdp.colour_plane_id = rconfig.colour_plane_id.sample(film) as u8;
+                }
+                if pps.redundant_pic_cnt_present_flag {
+                    sdp.redundant_pic_cnt = rconfig.redundant_pic_cnt.sample(film);
+                }
+            }
+        }
+    }
+
+    ds.slice_partitions.push(sdp);
+}
+
 /// Generate random slice layer extension syntax elements.
 pub fn random_slice_layer_extension(
     nalu_idx: usize,
@@ -1130,19 +1408,78 @@ pub fn random_slice_layer_extension(
     ds: &mut H264DecodedStream,
     film: &mut FilmState,
 ) {
-    // TODO: this is not a valid video because slice headers and slice data
-    //      are different in SVC, MVC, and 3D
-
-    //if ds.nalu_headers[nalu_idx].svc_extension_flag {
-    //    random_slice_header_in_scalable_extension();
-    //    let slice_skip_flag = false;
-    //    if slice_skip_flag {
-    //        random_slice_data_in_scalable_extension();
-    //    }
-    //} else if ds.nalu_headers[nalu_idx].avc_3d_extension_flag {
-    //    random_slice_header_in_3davc_extension();
-    //    random_slice_data_in_3davc_extension();
-    //} else {
+    if ds.nalu_headers[nalu_idx].svc_extension_flag {
+        let cur_subset_sps = &ds.subset_spses[subset_sps_idx].clone();
+        let cur_pps = &ds.ppses[subset_pps_idx].clone();
+
+        let mut vp = VideoParameters::new(&ds.nalu_headers[nalu_idx], cur_pps, &cur_subset_sps.sps);
+        if randomize_header {
+            random_slice_header_in_scalable_extension(
+                nalu_idx,
+                slice_idx,
+                cur_pps,
+                cur_subset_sps,
+                &vp,
+                silent_mode,
+                rconfig,
+                ds,
+                film,
+            );
+        }
+        // for neighbor processing
+        vp.mbaff_frame_flag = ds.slices[slice_idx].sh.mbaff_frame_flag;
+        random_slice_data_in_scalable_extension(
+            slice_idx,
+            cur_pps,
+            cur_subset_sps,
+            &vp,
+            ignore_intra_pred,
+            ignore_edge_intra_pred,
+            ignore_ipcm,
+            empty_slice_data,
+            rconfig,
+            ds,
+            film,
+        );
+        return;
+    }
+
+    if ds.nalu_headers[nalu_idx].avc_3d_extension_flag {
+        let cur_subset_sps = &ds.subset_spses[subset_sps_idx].clone();
+        let cur_pps = &ds.ppses[subset_pps_idx].clone();
+
+        let mut vp = VideoParameters::new(&ds.nalu_headers[nalu_idx], cur_pps, &cur_subset_sps.sps);
+        if randomize_header {
+            random_slice_header_in_3davc_extension(
+                nalu_idx,
+                slice_idx,
+                cur_pps,
+                cur_subset_sps,
+                &vp,
+                silent_mode,
+                rconfig,
+                ds,
+                film,
+            );
+        }
+        // for neighbor processing
+        vp.mbaff_frame_flag = ds.slices[slice_idx].sh.mbaff_frame_flag;
+        random_slice_data_in_3davc_extension(
+            slice_idx,
+            cur_pps,
+            cur_subset_sps,
+            &vp,
+            ignore_intra_pred,
+            ignore_edge_intra_pred,
+            ignore_ipcm,
+            empty_slice_data,
+            rconfig,
+            ds,
+            film,
+        );
+        return;
+    }
+
     let cur_sps = &ds.subset_spses[subset_sps_idx].sps.clone();
     let cur_pps = &ds.ppses[subset_pps_idx].clone();
     random_slice(
@@ -1160,29 +1497,374 @@ pub fn random_slice_layer_extension(
         ds,
         film,
     );
-    //}
 }
 
-#[allow(dead_code)]
-fn random_slice_header_in_scalable_extension() {
-    // TODO: Annex G
-    println!("random_slice_header_in_scalable_extension - not yet supported");
+/// Generate a random dec_ref_base_pic_marking as a part of a scalable extension slice header
+fn random_dec_ref_base_pic_marking(
+    slice_idx: usize,
+    rconfig: &RandomSliceSVCExtensionRange,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    let svc_ext = &mut ds.slices[slice_idx].sh.svc_extension;
+    svc_ext.adaptive_ref_base_pic_marking_mode_flag =
+        rconfig.adaptive_ref_base_pic_marking_mode_flag.sample(film);
+    if svc_ext.adaptive_ref_base_pic_marking_mode_flag {
+        let num_modifications = rconfig.num_modifications.sample(film) as usize;
+        if num_modifications > 0 {
+            for i in 0..num_modifications - 1 {
+                svc_ext.memory_management_base_control_operation.push(
+                    rconfig
+                        .memory_management_base_control_operation
+                        .sample(film),
+                );
+
+                if svc_ext.memory_management_base_control_operation[i] == 1 {
+                    svc_ext
+                        .difference_of_base_pic_nums_minus1
+                        .push(rconfig.difference_of_base_pic_nums_minus1.sample(film));
+                } else {
+                    svc_ext.difference_of_base_pic_nums_minus1.push(0);
+                }
+
+                if svc_ext.memory_management_base_control_operation[i] == 2 {
+                    svc_ext
+                        .long_term_base_pic_num
+                        .push(rconfig.long_term_base_pic_num.sample(film));
+                } else {
+                    svc_ext.long_term_base_pic_num.push(0);
+                }
+            }
+        }
+        // stop condition
+        svc_ext.memory_management_base_control_operation.push(0);
+        svc_ext.difference_of_base_pic_nums_minus1.push(0);
+        svc_ext.long_term_base_pic_num.push(0);
+    }
 }
 
-#[allow(dead_code)]
-fn random_slice_data_in_scalable_extension() {
-    // TODO: Annex G
-    println!("random_slice_data_in_scalable_extension - not yet supported");
+/// Generate a random slice header in scalable extension following G.7.3.3.4
+///
+/// The syntax elements shared with Section 7.3.3 are generated by random_slice_header
+#[allow(clippy::too_many_arguments)]
+fn random_slice_header_in_scalable_extension(
+    nalu_idx: usize,
+    slice_idx: usize,
+    pps: &PicParameterSet,
+    subset_sps: &SubsetSPS,
+    vp: &VideoParameters,
+    silent_mode: bool,
+    rconfig: &RandomizeConfig,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    random_slice_header(
+        nalu_idx,
+        slice_idx,
+        pps,
+        &subset_sps.sps,
+        vp,
+        silent_mode,
+        &rconfig.random_slice_header_range,
+        ds,
+        film,
+    );
+
+    let svc_rconfig = &rconfig.random_slice_svc_extension_range;
+
+    let nh = ds.nalu_headers[nalu_idx].clone();
+
+    let sh = &mut ds.slices[slice_idx].sh;
+    sh.svc_extension_flag = true;
+    sh.svc_extension = SliceHeaderSVCExtension::new();
+    sh.svc_extension.no_inter_layer_pred_flag = nh.svc_extension.no_inter_layer_pred_flag;
+    sh.svc_extension.quality_id = nh.svc_extension.quality_id;
+
+    if sh.svc_extension.quality_id == 0 {
+        if ((pps.weighted_pred_flag && is_slice_type(sh.slice_type, "P"))
+            || (pps.weighted_bipred_idc == 1 && is_slice_type(sh.slice_type, "B")))
+            && !sh.svc_extension.no_inter_layer_pred_flag
+        {
+            sh.svc_extension.base_pred_weight_table_flag =
+                svc_rconfig.base_pred_weight_table_flag.sample(film);
+        }
+
+        if nh.nal_ref_idc != 0 && !subset_sps.sps_svc.slice_header_restriction_flag {
+            sh.svc_extension.store_ref_base_pic_flag =
+                svc_rconfig.store_ref_base_pic_flag.sample(film);
+            if (nh.svc_extension.use_ref_base_pic_flag || sh.svc_extension.store_ref_base_pic_flag)
+                && !nh.svc_extension.idr_flag
+            {
+                random_dec_ref_base_pic_marking(slice_idx, svc_rconfig, ds, film);
+            }
+        }
+    } else {
+        // reference list syntax elements are inferred from the base quality layer,
+        // so keep the PPS defaults that a decoder falls back to
+        sh.direct_spatial_mv_pred_flag = false;
+        sh.num_ref_idx_active_override_flag = false;
+        sh.num_ref_idx_l0_active_minus1 = pps.num_ref_idx_l0_default_active_minus1;
+        sh.num_ref_idx_l1_active_minus1 = pps.num_ref_idx_l1_default_active_minus1;
+    }
+
+    let sh = &mut ds.slices[slice_idx].sh;
+    if !sh.svc_extension.no_inter_layer_pred_flag && sh.svc_extension.quality_id == 0 {
+        sh.svc_extension.ref_layer_dq_id = svc_rconfig.ref_layer_dq_id.sample(film);
+
+        if subset_sps
+            .sps_svc
+            .inter_layer_deblocking_filter_control_present_flag
+        {
+            sh.svc_extension.disable_inter_layer_deblocking_filter_idc = svc_rconfig
+                .disable_inter_layer_deblocking_filter_idc
+                .sample(film);
+            if sh.svc_extension.disable_inter_layer_deblocking_filter_idc != 1 {
+                sh.svc_extension.inter_layer_slice_alpha_c0_offset_div2 = svc_rconfig
+                    .inter_layer_slice_alpha_c0_offset_div2
+                    .sample(film);
+                sh.svc_extension.inter_layer_slice_beta_offset_div2 =
+                    svc_rconfig.inter_layer_slice_beta_offset_div2.sample(film);
+            }
+        }
+
+        sh.svc_extension.constrained_intra_resampling_flag =
+            svc_rconfig.constrained_intra_resampling_flag.sample(film);
+
+        if subset_sps.sps_svc.extended_spatial_scalability_idc == 2 {
+            if vp.chroma_array_type > 0 {
+                sh.svc_extension.ref_layer_chroma_phase_x_plus1_flag =
+                    svc_rconfig.ref_layer_chroma_phase_x_plus1_flag.sample(film);
+                sh.svc_extension.ref_layer_chroma_phase_y_plus1 =
+                    svc_rconfig.ref_layer_chroma_phase_y_plus1.sample(film) as u8;
+            }
+            sh.svc_extension.scaled_ref_layer_left_offset =
+                svc_rconfig.scaled_ref_layer_left_offset.sample(film);
+            sh.svc_extension.scaled_ref_layer_top_offset =
+                svc_rconfig.scaled_ref_layer_top_offset.sample(film);
+            sh.svc_extension.scaled_ref_layer_right_offset =
+                svc_rconfig.scaled_ref_layer_right_offset.sample(film);
+            sh.svc_extension.scaled_ref_layer_bottom_offset =
+                svc_rconfig.scaled_ref_layer_bottom_offset.sample(film);
+        }
+    }
+
+    if !sh.svc_extension.no_inter_layer_pred_flag {
+        sh.svc_extension.slice_skip_flag = svc_rconfig.slice_skip_flag.sample(film);
+
+        if sh.svc_extension.slice_skip_flag {
+            sh.svc_extension.num_mbs_in_slice_minus1 =
+                svc_rconfig.num_mbs_in_slice_minus1.sample(film);
+        } else {
+            sh.svc_extension.adaptive_base_mode_flag =
+                svc_rconfig.adaptive_base_mode_flag.sample(film);
+            if !sh.svc_extension.adaptive_base_mode_flag {
+                sh.svc_extension.default_base_mode_flag =
+                    svc_rconfig.default_base_mode_flag.sample(film);
+            }
+            if !sh.svc_extension.default_base_mode_flag {
+                sh.svc_extension.adaptive_motion_prediction_flag =
+                    svc_rconfig.adaptive_motion_prediction_flag.sample(film);
+                if !sh.svc_extension.adaptive_motion_prediction_flag {
+                    sh.svc_extension.default_motion_prediction_flag =
+                        svc_rconfig.default_motion_prediction_flag.sample(film);
+                }
+            }
+            sh.svc_extension.adaptive_residual_prediction_flag =
+                svc_rconfig.adaptive_residual_prediction_flag.sample(film);
+            if !sh.svc_extension.adaptive_residual_prediction_flag {
+                sh.svc_extension.default_residual_prediction_flag =
+                    svc_rconfig.default_residual_prediction_flag.sample(film);
+            }
+        }
+
+        if subset_sps.sps_svc.adaptive_tcoeff_level_prediction_flag {
+            sh.svc_extension.tcoeff_level_prediction_flag =
+                svc_rconfig.tcoeff_level_prediction_flag.sample(film);
+        }
+    }
+
+    if !subset_sps.sps_svc.slice_header_restriction_flag && !sh.svc_extension.slice_skip_flag {
+        sh.svc_extension.scan_idx_start = svc_rconfig.scan_idx_start.sample(film) as u8;
+        sh.svc_extension.scan_idx_end = svc_rconfig.scan_idx_end.sample(film) as u8;
+    }
+
+    sh.svc_extension.set_scaled_ref_layer_offsets(subset_sps);
 }
 
-#[allow(dead_code)]
-fn random_slice_header_in_3davc_extension() {
-    // TODO: Annex J
-    println!("random_slice_header_in_3davc_extension - not yet supported");
+/// Generate random slice data in scalable extension following G.7.3.4.1
+///
+/// The scalable extension syntax elements are generated per macroblock by random_slice_data
+#[allow(clippy::too_many_arguments)]
+fn random_slice_data_in_scalable_extension(
+    slice_idx: usize,
+    pps: &PicParameterSet,
+    subset_sps: &SubsetSPS,
+    vp: &VideoParameters,
+    ignore_intra_pred: bool,
+    ignore_edge_intra_pred: bool,
+    ignore_ipcm: bool,
+    empty_slice_data: bool,
+    rconfig: &RandomizeConfig,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    // skipped slices carry no slice data
+    if ds.slices[slice_idx].sh.svc_extension.slice_skip_flag {
+        ds.slices[slice_idx].sd = SliceData::new();
+        return;
+    }
+
+    random_slice_data(
+        slice_idx,
+        pps,
+        &subset_sps.sps,
+        vp,
+        ignore_intra_pred,
+        ignore_edge_intra_pred,
+        ignore_ipcm,
+        empty_slice_data,
+        &rconfig.random_mb_range,
+        &rconfig.random_slice_svc_extension_range,
+        ds,
+        film,
+    );
+}
+
+/// Generate a random slice header in 3D-AVC extension following J.7.3.3.4
+///
+/// The syntax elements shared with Section 7.3.3 are generated by random_slice_header. When
+/// slice_header_prediction_flag is set, the elements that are predicted from another view
+/// component are reset to the defaults a decoder falls back to.
+#[allow(clippy::too_many_arguments)]
+fn random_slice_header_in_3davc_extension(
+    nalu_idx: usize,
+    slice_idx: usize,
+    pps: &PicParameterSet,
+    subset_sps: &SubsetSPS,
+    vp: &VideoParameters,
+    silent_mode: bool,
+    rconfig: &RandomizeConfig,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    random_slice_header(
+        nalu_idx,
+        slice_idx,
+        pps,
+        &subset_sps.sps,
+        vp,
+        silent_mode,
+        &rconfig.random_slice_header_range,
+        ds,
+        film,
+    );
+
+    let avc_3d_rconfig = &rconfig.random_slice_3davc_extension_range;
+    let sps_3davc = &subset_sps.sps_3davc;
+
+    let sh = &mut ds.slices[slice_idx].sh;
+    sh.avc_3d_extension_flag = true;
+    sh.avc_3d_extension = SliceHeader3DAVCExtension::new();
+
+    if sps_3davc.slice_header_prediction_flag {
+        sh.avc_3d_extension.pre_slice_header_src =
+            avc_3d_rconfig.pre_slice_header_src.sample(film) as u8;
+
+        sh.colour_plane_id = 0;
+        sh.frame_num = 0;
+        sh.field_pic_flag = false;
+        sh.bottom_field_flag = false;
+        sh.idr_pic_id = 0;
+        sh.pic_order_cnt_lsb = 0;
+        sh.delta_pic_order_cnt_bottom = 0;
+        sh.delta_pic_order_cnt = Vec::new();
+        sh.redundant_pic_cnt = 0;
+        sh.direct_spatial_mv_pred_flag = false;
+        sh.cabac_init_idc = 0;
+        sh.sp_for_switch_flag = false;
+        sh.slice_qs_delta = 0;
+        sh.disable_deblocking_filter_idc = 0;
+        sh.slice_alpha_c0_offset_div2 = 0;
+        sh.slice_beta_offset_div2 = 0;
+        sh.slice_group_change_cycle = 0;
+        sh.mbaff_frame_flag = subset_sps.sps.mb_adaptive_frame_field_flag;
+
+        if is_slice_type(sh.slice_type, "P")
+            || is_slice_type(sh.slice_type, "SP")
+            || is_slice_type(sh.slice_type, "B")
+        {
+            sh.avc_3d_extension.pre_ref_lists_src =
+                avc_3d_rconfig.pre_ref_lists_src.sample(film) as u8;
+        }
+        if sh.avc_3d_extension.pre_ref_lists_src != 0 {
+            // the reference lists are predicted, so keep the PPS defaults
+            sh.num_ref_idx_active_override_flag = false;
+            sh.num_ref_idx_l0_active_minus1 = pps.num_ref_idx_l0_default_active_minus1;
+            sh.num_ref_idx_l1_active_minus1 = pps.num_ref_idx_l1_default_active_minus1;
+        }
+
+        if (pps.weighted_pred_flag
+            && (is_slice_type(sh.slice_type, "P") || is_slice_type(sh.slice_type, "SP")))
+            || (pps.weighted_bipred_idc == 1 && is_slice_type(sh.slice_type, "B"))
+        {
+            sh.avc_3d_extension.pre_pred_weight_table_src =
+                avc_3d_rconfig.pre_pred_weight_table_src.sample(film) as u8;
+        }
+
+        if ds.nalu_headers[nalu_idx].nal_ref_idc != 0 {
+            sh.avc_3d_extension.pre_dec_ref_pic_marking_src =
+                avc_3d_rconfig.pre_dec_ref_pic_marking_src.sample(film) as u8;
+        }
+    } else {
+        if !is_slice_type(sh.slice_type, "I") && !is_slice_type(sh.slice_type, "SI") {
+            if vp.depth_flag {
+                sh.avc_3d_extension.depth_weighted_pred_flag =
+                    avc_3d_rconfig.depth_weighted_pred_flag.sample(film);
+            } else {
+                sh.avc_3d_extension.dmvp_flag = avc_3d_rconfig.dmvp_flag.sample(film);
+                if sps_3davc.seq_view_synthesis_flag {
+                    sh.avc_3d_extension.slice_vsp_flag = avc_3d_rconfig.slice_vsp_flag.sample(film);
+                }
+            }
+        }
+
+        if sps_3davc.three_dv_acquisition_idc != 1
+            && (sh.avc_3d_extension.depth_weighted_pred_flag || sh.avc_3d_extension.dmvp_flag)
+        {
+            sh.avc_3d_extension.dps_id = avc_3d_rconfig.dps_id.sample(film);
+        }
+    }
 }
 
-#[allow(dead_code)]
-fn random_slice_data_in_3davc_extension() {
-    // TODO: Annex J
-    println!("random_slice_data_in_3davc_extension - not yet supported");
+/// Generate random slice data in 3D-AVC extension following J.7.3.4.1
+///
+/// Only the macroblock syntax shared with 7.3.4 is generated
+#[allow(clippy::too_many_arguments)]
+fn random_slice_data_in_3davc_extension(
+    slice_idx: usize,
+    pps: &PicParameterSet,
+    subset_sps: &SubsetSPS,
+    vp: &VideoParameters,
+    ignore_intra_pred: bool,
+    ignore_edge_intra_pred: bool,
+    ignore_ipcm: bool,
+    empty_slice_data: bool,
+    rconfig: &RandomizeConfig,
+    ds: &mut H264DecodedStream,
+    film: &mut FilmState,
+) {
+    random_slice_data(
+        slice_idx,
+        pps,
+        &subset_sps.sps,
+        vp,
+        ignore_intra_pred,
+        ignore_edge_intra_pred,
+        ignore_ipcm,
+        empty_slice_data,
+        &rconfig.random_mb_range,
+        &rconfig.random_slice_svc_extension_range,
+        ds,
+        film,
+    );
 }
@@ -0,0 +1,184 @@
+//! Per-slice overrides of the random ranges.
+//!
+//! Overrides match slices by NALU position, slice index, IDR-ness and slice
+//! type, and layer a partial configuration over the one used for the rest of
+//! the video, e.g. a clean first IDR slice followed by aggressive P slices.
+
+use crate::common::data_structures::NALUheader;
+use crate::vidgen::config_layers::layer_overrides;
+use crate::vidgen::film::FilmState;
+use crate::vidgen::generate_configurations::RandomBoolRange;
+use crate::vidgen::generate_configurations::RandomU32Enum;
+use crate::vidgen::generate_configurations::RandomizeConfig;
+use crate::vidgen::slice::random_slice_type;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+/// Resolves the configuration of each slice, caching one configuration per set
+/// of matching overrides
+pub struct SliceConfigs<'a> {
+    rconfig: &'a RandomizeConfig,
+    resolved: HashMap<Vec<usize>, RandomizeConfig>,
+}
+
+impl<'a> SliceConfigs<'a> {
+    pub fn new(rconfig: &'a RandomizeConfig) -> SliceConfigs<'a> {
+        SliceConfigs {
+            rconfig,
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// The configuration with every override matching the slice layered over it. Overrides
+    /// on slice types only match if the slice type is known
+    pub fn matching_config(
+        &mut self,
+        nalu_idx: usize,
+        slice_idx: usize,
+        idr: bool,
+        slice_type: Option<u8>,
+    ) -> &RandomizeConfig {
+        let matching: Vec<usize> = (0..self.rconfig.random_slice_overrides.len())
+            .filter(|&i| {
+                self.rconfig.random_slice_overrides[i].matches(nalu_idx, slice_idx, idr, slice_type)
+            })
+            .collect();
+        if matching.is_empty() {
+            return self.rconfig;
+        }
+
+        let rconfig = self.rconfig;
+        self.resolved
+            .entry(matching)
+            .or_insert_with_key(|matching| {
+                let layers: Vec<&serde_json::Value> = matching
+                    .iter()
+                    .map(|&i| &rconfig.random_slice_overrides[i].config)
+                    .collect();
+                match layer_overrides(rconfig, &layers) {
+                    Ok(x) => x,
+                    Err(y) => panic!("Unable to apply slice overrides {:?}: {}", matching, y),
+                }
+            })
+    }
+
+    /// The configuration for the slice in the NALU at nalu_idx. If an override depends on
+    /// the slice type, the slice type is sampled first and fixed in the returned configuration
+    pub fn slice_config(
+        &mut self,
+        nalu_idx: usize,
+        slice_idx: usize,
+        nh: &NALUheader,
+        film: &mut FilmState,
+    ) -> Cow<'_, RandomizeConfig> {
+        let idr = nh.idr_pic_flag();
+        if self
+            .rconfig
+            .random_slice_overrides
+            .iter()
+            .all(|x| x.slice_types.is_empty())
+        {
+            return Cow::Borrowed(self.matching_config(nalu_idx, slice_idx, idr, None));
+        }
+
+        let slice_type = random_slice_type(
+            slice_idx == 0 || idr,
+            &self
+                .matching_config(nalu_idx, slice_idx, idr, None)
+                .random_slice_header_range,
+            film,
+        );
+        let mut res = self
+            .matching_config(nalu_idx, slice_idx, idr, Some(slice_type))
+            .clone();
+        res.random_slice_header_range.slice_type = RandomU32Enum::new(vec![slice_type as u32]);
+        res.random_slice_header_range.bias_i_slice = RandomBoolRange::never();
+        Cow::Owned(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::main_profile_cavlc_config;
+    use crate::common::test_support::round_trip;
+    use crate::vidgen::generate_configurations::RandomI32Range;
+    use crate::vidgen::generate_configurations::RandomSliceOverride;
+    use crate::vidgen::generate_configurations::RandomU32Range;
+    use crate::vidgen::vidgen::random_video;
+
+    #[test]
+    fn test_slice_overrides() {
+        let slice_qp_delta = |x: i32| serde_json::json!({"random_slice_header_range": {"slice_qp_delta": {"min": x, "max": x}}});
+        let mut rconfig = main_profile_cavlc_config();
+        rconfig.random_pps_range.pic_init_qp_minus26 = RandomI32Range::new(0, 0);
+        rconfig.random_slice_header_range.slice_qp_delta.min = -20;
+        rconfig.random_slice_header_range.slice_qp_delta.max = -10;
+        rconfig.random_gop_range.enable_gop_structure = RandomBoolRange::always();
+        rconfig.random_gop_range.num_gops = RandomU32Range::new(1, 1);
+        rconfig.random_gop_range.gop_length = RandomU32Range::new(7, 7);
+        rconfig.random_gop_range.num_b_frames = RandomU32Range::new(2, 2);
+        rconfig.random_gop_range.inject_out_of_range_reference = RandomBoolRange::never();
+        rconfig.random_gop_range.inject_mmco_missing_picture = RandomBoolRange::never();
+        // a clean IDR slice, then P slices with a different QP, except for the first one
+        rconfig.random_slice_overrides = vec![
+            serde_json::from_value::<RandomSliceOverride>(
+                serde_json::json!({"idr": true, "config": slice_qp_delta(0)}),
+            )
+            .unwrap(),
+            serde_json::from_value(
+                serde_json::json!({"slice_types": [0], "config": slice_qp_delta(5)}),
+            )
+            .unwrap(),
+            serde_json::from_value(
+                serde_json::json!({"nalu_indices": [3], "config": slice_qp_delta(-5)}),
+            )
+            .unwrap(),
+        ];
+
+        // IDR P3 B1 B2 P6 B4 B5 in decoding order
+        let mut film = FilmState::setup_film_from_seed(25);
+        let mut ds = random_video(
+            false, false, false, true, true, true, false, false, &rconfig, &mut film,
+        );
+        let qp_deltas: Vec<i32> = ds.slices.iter().map(|x| x.sh.slice_qp_delta).collect();
+        assert_eq!(qp_deltas[..2], [0, -5]);
+        assert!(qp_deltas[2..4].iter().all(|x| (-20..=-10).contains(x)));
+        assert_eq!(qp_deltas[4], 5);
+        assert!(qp_deltas[5..].iter().all(|x| (-20..=-10).contains(x)));
+
+        let decoded = round_trip(&mut ds);
+        assert_eq!(decoded.slices.len(), 7);
+
+        // without a GOP structure the slice type is sampled before the override applies
+        rconfig.random_gop_range.enable_gop_structure = RandomBoolRange::never();
+        rconfig.random_video_config.num_nalus = RandomU32Range::new(12, 12);
+        rconfig.random_nalu_range.nal_unit_type = RandomU32Enum::new(vec![1]);
+        rconfig.random_slice_header_range.slice_type = RandomU32Enum::new(vec![0, 1, 2]);
+        // the first slice is layered with both overrides, the later one wins
+        rconfig.random_slice_overrides = vec![
+            serde_json::from_value(
+                serde_json::json!({"slice_types": [7], "config": slice_qp_delta(3)}),
+            )
+            .unwrap(),
+            serde_json::from_value(
+                serde_json::json!({"first_slices": 1, "config": slice_qp_delta(0)}),
+            )
+            .unwrap(),
+        ];
+        let mut film = FilmState::setup_film_from_seed(26);
+        let ds = random_video(
+            false, false, false, true, true, true, false, false, &rconfig, &mut film,
+        );
+        assert_eq!(ds.slices[0].sh.slice_qp_delta, 0);
+        for slice in ds.slices[1..].iter() {
+            if slice.sh.slice_type % 5 == 2 {
+                assert_eq!(slice.sh.slice_qp_delta, 3);
+            } else {
+                assert!((-20..=-10).contains(&slice.sh.slice_qp_delta));
+            }
+        }
+        assert!(ds.slices[1..].iter().any(|x| x.sh.slice_type % 5 == 2));
+        assert!(ds.slices[1..].iter().any(|x| x.sh.slice_type % 5 != 2));
+    }
+}
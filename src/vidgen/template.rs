@@ -0,0 +1,315 @@
+//! Template-driven generation.
+//!
+//! A template is a partial H264DecodedStream, such as the syntax elements of a
+//! known proof of concept. Its nalu_headers list sets the number and order of
+//! NALUs, and every field it pins is kept while the rest of the video is
+//! sampled from the random ranges. The other lists are matched to generated
+//! elements by index, e.g. the second entry of spses pins the second SPS.
+
+use crate::vidgen::config_layers::layer_partial;
+use crate::vidgen::generate_configurations::RandomBoolRange;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::fs;
+
+/// The lists of a H264DecodedStream that a template can pin
+const PINNABLE_LISTS: [&str; 12] = [
+    "nalu_headers",
+    "spses",
+    "subset_spses",
+    "sps_extensions",
+    "ppses",
+    "prefix_nalus",
+    "slices",
+    "seis",
+    "auds",
+    "filler_data",
+    "dpses",
+    "slice_partitions",
+];
+
+/// Ranges that sample the same syntax element as a pinned field of another name
+const ALIASES: [(&str, &str); 4] = [
+    ("nal_unit_type", "nal_unit_slice_type"),
+    ("nal_unit_type", "nal_unit_extension_type"),
+    ("nal_unit_type", "nal_unit_undefined_type"),
+    ("profile_idc", "profile_idc_extension"),
+];
+
+pub struct VideoTemplate {
+    filename: String,
+    lists: Map<String, Value>,
+}
+
+/// Read a template, warning about the parts that cannot be pinned
+pub fn load_template(filename: &str) -> VideoTemplate {
+    let contents = match fs::read_to_string(filename) {
+        Err(_) => panic!("couldn't open {}", filename),
+        Ok(x) => x,
+    };
+    let lists = match serde_json::from_str(&contents) {
+        Ok(Value::Object(x)) => x,
+        Ok(_) => panic!(
+            "Error reading template {}: expected a JSON object",
+            filename
+        ),
+        Err(y) => panic!("Error reading template {}: {:?}", filename, y),
+    };
+
+    for (key, value) in lists.iter() {
+        if !PINNABLE_LISTS.contains(&key.as_str()) {
+            println!(
+                "[WARNING] {}: {}: cannot be pinned by a template - ignoring",
+                filename, key
+            );
+        } else if !value.is_array() {
+            println!(
+                "[WARNING] {}: {}: expected an array - ignoring",
+                filename, key
+            );
+        }
+    }
+
+    let template = VideoTemplate {
+        filename: filename.to_string(),
+        lists,
+    };
+    if template.num_nalus() == 0 {
+        panic!(
+            "Template {} has no nalu_headers to set the NALU order",
+            filename
+        );
+    }
+    template
+}
+
+impl VideoTemplate {
+    /// The number of NALUs of videos generated from the template
+    pub fn num_nalus(&self) -> usize {
+        self.lists
+            .get("nalu_headers")
+            .and_then(Value::as_array)
+            .map_or(0, Vec::len)
+    }
+
+    /// The fields pinned for the element at idx of a list
+    pub fn pinned(&self, list: &str, idx: usize) -> Option<&Map<String, Value>> {
+        self.lists.get(list)?.as_array()?.get(idx)?.as_object()
+    }
+
+    /// The slice header fields pinned for the slice at slice_idx
+    pub fn pinned_slice_header(&self, slice_idx: usize) -> Option<&Map<String, Value>> {
+        self.pinned("slices", slice_idx)?.get("sh")?.as_object()
+    }
+
+    /// Narrow each range of a configuration section that samples a pinned field to the
+    /// pinned value, so the syntax elements that depend on it are sampled to agree with it
+    pub fn narrow<T: Serialize + DeserializeOwned + Clone>(
+        &self,
+        section: &T,
+        pinned: Option<&Map<String, Value>>,
+    ) -> T {
+        let pinned = match pinned {
+            Some(x) => x,
+            None => return section.clone(),
+        };
+        let ranges = match serde_json::to_value(section) {
+            Ok(Value::Object(x)) => x,
+            _ => return section.clone(),
+        };
+
+        let mut narrowed_ranges = Map::new();
+        let aliases = ALIASES
+            .iter()
+            .filter(|(pinned_key, _)| pinned.contains_key(*pinned_key))
+            .map(|(pinned_key, key)| (*key, &pinned[*pinned_key]));
+        for (key, value) in pinned.iter().map(|(k, v)| (k.as_str(), v)).chain(aliases) {
+            let range = match ranges.get(key) {
+                Some(Value::Object(x)) => x,
+                _ => continue,
+            };
+            let narrowed = if range.contains_key("threshold") {
+                match value {
+                    Value::Bool(true) => serde_json::to_value(RandomBoolRange::always()),
+                    Value::Bool(false) => serde_json::to_value(RandomBoolRange::never()),
+                    _ => continue,
+                }
+                .unwrap()
+            } else if range.contains_key("values") {
+                json!({ "values": [value], "weights": [] })
+            } else if range.contains_key("min") {
+                let mut x = json!({ "min": value, "max": value, "distribution": "uniform" });
+                if range.contains_key("use_dependency") {
+                    x["use_dependency"] = Value::Bool(false);
+                }
+                x
+            } else {
+                continue;
+            };
+            narrowed_ranges.insert(key.to_string(), narrowed);
+        }
+        // a pinned slice type is never replaced by the I slice bias
+        if pinned.contains_key("slice_type") && ranges.contains_key("bias_i_slice") {
+            narrowed_ranges.insert(
+                "bias_i_slice".to_string(),
+                serde_json::to_value(RandomBoolRange::never()).unwrap(),
+            );
+        }
+
+        // values that don't fit a range, like a negative value for an unsigned one, are
+        // left to be pinned after sampling
+        let mut res = section.clone();
+        for (key, narrowed) in narrowed_ranges {
+            let layer = Map::from_iter([(key, narrowed)]);
+            if let (Ok(x), _) = layer_partial(&res, &layer, &self.filename, "") {
+                res = x;
+            }
+        }
+        res
+    }
+
+    /// Overwrite the fields of a generated element with those pinned for the element at idx
+    /// of a list
+    pub fn pin<T: Serialize + DeserializeOwned>(&self, list: &str, idx: usize, value: &mut T) {
+        if let Some(pinned) = self.pinned(list, idx) {
+            self.pin_fields(&format!("{}[{}]", list, idx), pinned, value);
+        }
+    }
+
+    /// Pin the fields of the most recently generated element of a list
+    pub fn pin_last<T: Serialize + DeserializeOwned>(&self, list: &str, elements: &mut [T]) {
+        if let Some(last) = elements.len().checked_sub(1) {
+            self.pin(list, last, &mut elements[last]);
+        }
+    }
+
+    /// Overwrite fields of value with the pinned ones. Returns true if any field changed
+    pub fn pin_fields<T: Serialize + DeserializeOwned>(
+        &self,
+        path: &str,
+        pinned: &Map<String, Value>,
+        value: &mut T,
+    ) -> bool {
+        let (res, errors) = layer_partial(value, pinned, &self.filename, path);
+        for e in errors {
+            println!("[WARNING] {} - not pinned", e);
+        }
+        match res {
+            Ok(x) => {
+                let changed = serde_json::to_value(&x).ok() != serde_json::to_value(&*value).ok();
+                *value = x;
+                changed
+            }
+            Err(y) => {
+                println!(
+                    "[WARNING] {}: {}: unable to pin fields - {}",
+                    self.filename, path, y
+                );
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_support::round_trip;
+    use crate::vidgen::film::FilmState;
+    use crate::vidgen::generate_configurations::RandomizeConfig;
+    use crate::vidgen::vidgen::random_video_from_template;
+
+    #[test]
+    fn test_template_generation() {
+        let template = serde_json::json!({
+            "nalu_headers": [
+                {"nal_unit_type": 7},
+                {"nal_unit_type": 8},
+                {"nal_unit_type": 5, "nal_ref_idc": 3},
+                {"nal_unit_type": 1},
+                {"nal_unit_type": 8},
+                {"nal_unit_type": 1, "nal_ref_idc": 0},
+            ],
+            "spses": [{
+                "profile_idc": 77,
+                "pic_order_cnt_type": 2,
+                "frame_cropping_flag": false,
+                "vui_parameters_present_flag": false,
+            }],
+            "ppses": [
+                {"entropy_coding_mode_flag": false, "pic_init_qp_minus26": 0},
+                {"entropy_coding_mode_flag": false, "pic_init_qp_minus26": -3},
+            ],
+            "slices": [
+                {"sh": {"slice_type": 7, "slice_qp_delta": 2}},
+                {"sh": {"slice_type": 5, "slice_qp_delta": -4}},
+                {"sh": {"slice_qp_delta": -10}},
+            ],
+        });
+        let filename = std::env::temp_dir()
+            .join(format!("h26forge_{}_template.json", std::process::id()))
+            .to_string_lossy()
+            .to_string();
+        std::fs::write(&filename, template.to_string()).unwrap();
+        let template = load_template(&filename);
+
+        let rconfig = RandomizeConfig::new();
+        let mut film = FilmState::setup_film_from_seed(27);
+        let mut ds = random_video_from_template(
+            &template, false, false, false, true, true, true, false, false, &rconfig, &mut film,
+        )
+        .unwrap();
+        let nalu_types: Vec<u8> = ds.nalu_headers.iter().map(|x| x.nal_unit_type).collect();
+        assert_eq!(nalu_types, [7, 8, 5, 1, 8, 1]);
+        assert_eq!(ds.nalu_headers[2].nal_ref_idc, 3);
+        assert_eq!(ds.nalu_headers[5].nal_ref_idc, 0);
+        assert_eq!(ds.spses[0].profile_idc, 77);
+        assert_eq!(ds.spses[0].pic_order_cnt_type, 2);
+        assert_eq!(ds.ppses[1].pic_init_qp_minus26, -3);
+        assert_eq!(
+            (ds.slices[0].sh.slice_type, ds.slices[0].sh.slice_qp_delta),
+            (7, 2)
+        );
+        assert_eq!(
+            (ds.slices[1].sh.slice_type, ds.slices[1].sh.slice_qp_delta),
+            (5, -4)
+        );
+        // derived variables follow the pinned values
+        assert_eq!(ds.slices[0].sh.slice_qp_y, 28);
+        assert_eq!(ds.slices[2].sh.slice_qp_y, 13);
+
+        let decoded = round_trip(&mut ds);
+        let nalu_types: Vec<u8> = decoded
+            .nalu_headers
+            .iter()
+            .map(|x| x.nal_unit_type)
+            .collect();
+        assert_eq!(nalu_types, [7, 8, 5, 1, 8, 1]);
+        let qp_deltas: Vec<i32> = decoded.slices.iter().map(|x| x.sh.slice_qp_delta).collect();
+        assert_eq!(qp_deltas, [2, -4, -10]);
+        assert_eq!(decoded.slices[1].sh.slice_type, 5);
+
+        // a pinned slice must come after the parameter sets it refers to
+        std::fs::write(&filename, r#"{"nalu_headers": [{"nal_unit_type": 5}]}"#).unwrap();
+        let template = load_template(&filename);
+        let res = random_video_from_template(
+            &template,
+            false,
+            false,
+            false,
+            true,
+            true,
+            true,
+            false,
+            false,
+            &rconfig,
+            &mut FilmState::setup_film_from_seed(27),
+        );
+        let Err(e) = res else {
+            panic!("expected an error for a slice before its parameter sets");
+        };
+        assert!(e.starts_with("template NALU 0 has nal_unit_type 5"));
+        std::fs::remove_file(&filename).unwrap();
+    }
+}
@@ -1,6 +1,8 @@
 //! Random video generation entry point.
 
 use crate::common::data_structures::AccessUnitDelim;
+use crate::common::data_structures::DepthParameterSet;
+use crate::common::data_structures::FillerData;
 use crate::common::data_structures::H264DecodedStream;
 use crate::common::data_structures::MacroBlock;
 use crate::common::data_structures::NALUheader;
@@ -9,19 +11,146 @@ use crate::common::data_structures::PrefixNALU;
 use crate::common::data_structures::SEINalu;
 use crate::common::data_structures::SeqParameterSet;
 use crate::common::data_structures::Slice;
+use crate::common::data_structures::SliceData;
 use crate::common::data_structures::SubsetSPS;
 use crate::common::data_structures::NALU;
 use crate::vidgen::film::FilmState;
 use crate::vidgen::generate_configurations::RandomizeConfig;
+use crate::vidgen::gop::random_gop_video;
 use crate::vidgen::nalu::random_access_unit_delimiter;
+use crate::vidgen::nalu::random_end_of_seq_stream_payload;
+use crate::vidgen::nalu::random_filler_data;
 use crate::vidgen::nalu::random_nalu_header;
 use crate::vidgen::nalu::random_prefix_nalu;
+use crate::vidgen::parameter_sets::random_depth_parameter_set;
 use crate::vidgen::parameter_sets::random_pps;
 use crate::vidgen::parameter_sets::random_sps;
+use crate::vidgen::parameter_sets::random_sps_extension;
 use crate::vidgen::parameter_sets::random_subset_sps;
 use crate::vidgen::sei::random_sei;
 use crate::vidgen::slice::random_slice;
+use crate::vidgen::slice::random_slice_data_partition;
 use crate::vidgen::slice::random_slice_layer_extension;
+use crate::vidgen::slice_overrides::SliceConfigs;
+use crate::vidgen::template::VideoTemplate;
+use std::borrow::Cow;
+use std::cmp;
+
+/// Adjust the sampled NALU type and nal_ref_idc to follow 7.4.1.2: parameter sets
+/// are followed by a picture, the first picture after an SPS is an IDR picture, and
+/// nal_ref_idc is non-zero for parameter sets and IDR pictures and zero for SEI and
+/// the other non-VCL NALUs of Table 7-1
+fn conforming_nalu_order(nalu_idx: usize, number_nalus: usize, ds: &mut H264DecodedStream) {
+    let nh = &mut ds.nalu_headers[nalu_idx];
+
+    // leave room for the PPS and picture that follow an SPS, and the picture that follows a PPS
+    let trailing_sps = nh.nal_unit_type == 7 && nalu_idx > 0 && nalu_idx + 2 >= number_nalus;
+    let trailing_pps = nh.nal_unit_type == 8 && nalu_idx + 1 >= number_nalus;
+    if trailing_sps || trailing_pps {
+        nh.nal_unit_type = 1;
+    }
+
+    if nh.nal_unit_type == 1
+        && (0..nalu_idx)
+            .rev()
+            .map(|i| ds.nalu_headers[i].nal_unit_type)
+            .find(|x| [1, 2, 5, 7].contains(x))
+            == Some(7)
+    {
+        ds.nalu_headers[nalu_idx].nal_unit_type = 5;
+    }
+
+    let nh = &mut ds.nalu_headers[nalu_idx];
+    match nh.nal_unit_type {
+        5 | 7 | 8 | 13 | 15 => nh.nal_ref_idc = cmp::max(nh.nal_ref_idc, 1),
+        6 | 9..=12 => nh.nal_ref_idc = 0,
+        _ => (),
+    }
+}
+
+/// Whether the parameter sets that a NALU type refers to have been generated. Without a
+/// template, the NALU type is adjusted so this always holds
+fn references_exist(
+    nalu_idx: usize,
+    sps_idx: usize,
+    subset_sps_idx: usize,
+    pps_idx: usize,
+    slice_idx: usize,
+    ds: &H264DecodedStream,
+) -> bool {
+    match ds.nalu_headers[nalu_idx].nal_unit_type {
+        1 | 2 | 5 => sps_idx > 0 && pps_idx > 0 && !ds.ppses[pps_idx - 1].is_subset_pps,
+        // auxiliary coded pictures also need the SPS extension of their SPS
+        19 => {
+            sps_idx > 0
+                && pps_idx > 0
+                && !ds.ppses[pps_idx - 1].is_subset_pps
+                && ds
+                    .sps_extensions
+                    .iter()
+                    .any(|x| x.seq_parameter_set_id == ds.spses[sps_idx - 1].seq_parameter_set_id)
+        }
+        3 | 4 => slice_idx > 0 && !ds.slice_partitions.is_empty(),
+        8 => match ds.nalu_headers[nalu_idx - 1].nal_unit_type {
+            15 => subset_sps_idx > 0,
+            _ => sps_idx > 0,
+        },
+        13 => sps_idx > 0,
+        20 | 21 => subset_sps_idx > 0 && ds.ppses.iter().any(|x| x.is_subset_pps),
+        _ => true,
+    }
+}
+
+/// Pin the slice header fields that a template sets, keeping the variables derived in
+/// 7-25 and 7-30 in step unless they are pinned too. Returns true if the header changed,
+/// in which case the slice data is cleared to be generated again
+fn pin_slice_header(
+    template: &VideoTemplate,
+    slice_idx: usize,
+    pps: &PicParameterSet,
+    sps: &SeqParameterSet,
+    ds: &mut H264DecodedStream,
+) -> bool {
+    let pinned = match template.pinned_slice_header(slice_idx) {
+        Some(x) => x,
+        None => return false,
+    };
+    let sh = &mut ds.slices[slice_idx].sh;
+    if !template.pin_fields(&format!("slices[{}].sh", slice_idx), pinned, sh) {
+        return false;
+    }
+
+    if !pinned.contains_key("mbaff_frame_flag") {
+        sh.mbaff_frame_flag = sps.mb_adaptive_frame_field_flag && !sh.field_pic_flag;
+    }
+    if !pinned.contains_key("slice_qp_y") {
+        sh.slice_qp_y = 26 + sh.slice_qp_delta + pps.pic_init_qp_minus26;
+    }
+    if !pinned.contains_key("qp_y_prev") {
+        sh.qp_y_prev = sh.slice_qp_y;
+    }
+
+    // for amount of macroblocks, assume frames only
+    let macroblock_amount =
+        ((sps.pic_width_in_mbs_minus1 + 1) * (sps.pic_height_in_map_units_minus1 + 1)) as usize;
+    ds.slices[slice_idx].sd = SliceData::new();
+    ds.slices[slice_idx].sd.macroblock_vec = vec![MacroBlock::new(); macroblock_amount];
+    true
+}
+
+/// Narrow the slice header ranges of a slice configuration to the fields a template pins
+fn narrow_slice_config(
+    template: Option<&VideoTemplate>,
+    slice_idx: usize,
+    slice_rconfig: &mut Cow<'_, RandomizeConfig>,
+) {
+    if let Some(pinned) = template.and_then(|x| x.pinned_slice_header(slice_idx)) {
+        let narrowed = template
+            .unwrap()
+            .narrow(&slice_rconfig.random_slice_header_range, Some(pinned));
+        slice_rconfig.to_mut().random_slice_header_range = narrowed;
+    }
+}
 
 /// Generate a random video
 pub fn random_video(
@@ -32,10 +161,91 @@ pub fn random_video(
     small_video: bool,
     silent_mode: bool,
     undefined_nalus: bool,
+    data_partitioning: bool,
     rconfig: &RandomizeConfig,
     film: &mut FilmState,
 ) -> H264DecodedStream {
-    let number_nalus = rconfig.random_video_config.num_nalus.sample(film) as usize;
+    generate_video(
+        None,
+        ignore_intra_pred,
+        ignore_edge_intra_pred,
+        ignore_ipcm,
+        empty_slice_data,
+        small_video,
+        silent_mode,
+        undefined_nalus,
+        data_partitioning,
+        rconfig,
+        film,
+    )
+    .expect("generation only fails for a template")
+}
+
+/// Generate a random video that keeps the NALU order and every field pinned by a template.
+///
+/// Returns an error if a pinned NALU refers to parameter sets or a slice that
+/// come later in the template.
+#[allow(clippy::too_many_arguments)]
+pub fn random_video_from_template(
+    template: &VideoTemplate,
+    ignore_intra_pred: bool,
+    ignore_edge_intra_pred: bool,
+    ignore_ipcm: bool,
+    empty_slice_data: bool,
+    small_video: bool,
+    silent_mode: bool,
+    undefined_nalus: bool,
+    data_partitioning: bool,
+    rconfig: &RandomizeConfig,
+    film: &mut FilmState,
+) -> Result<H264DecodedStream, String> {
+    generate_video(
+        Some(template),
+        ignore_intra_pred,
+        ignore_edge_intra_pred,
+        ignore_ipcm,
+        empty_slice_data,
+        small_video,
+        silent_mode,
+        undefined_nalus,
+        data_partitioning,
+        rconfig,
+        film,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn generate_video(
+    template: Option<&VideoTemplate>,
+    ignore_intra_pred: bool,
+    ignore_edge_intra_pred: bool,
+    ignore_ipcm: bool,
+    empty_slice_data: bool,
+    small_video: bool,
+    silent_mode: bool,
+    undefined_nalus: bool,
+    data_partitioning: bool,
+    rconfig: &RandomizeConfig,
+    film: &mut FilmState,
+) -> Result<H264DecodedStream, String> {
+    // a template sets its own NALU order, so it doesn't follow a GOP structure
+    if template.is_none() && rconfig.random_gop_range.enable_gop_structure.sample(film) {
+        return Ok(random_gop_video(
+            ignore_intra_pred,
+            ignore_edge_intra_pred,
+            ignore_ipcm,
+            empty_slice_data,
+            small_video,
+            silent_mode,
+            rconfig,
+            film,
+        ));
+    }
+
+    let number_nalus = match template {
+        Some(x) => x.num_nalus(),
+        None => rconfig.random_video_config.num_nalus.sample(film) as usize,
+    };
     let enable_extensions = rconfig.random_video_config.enable_extensions.sample(film);
 
     let mut ds = H264DecodedStream::new();
@@ -46,20 +256,31 @@ pub fn random_video(
     let mut pps_idx = 0;
     let mut sei_idx = 0;
     let mut slice_idx = 0;
+    let mut slice_partition_a_idx = 0;
     let mut aud_idx = 0;
+    let mut filler_data_idx = 0;
+    let mut dps_idx = 0;
 
     let mut generated_nalu_type_str = String::new();
+    let mut slice_configs = SliceConfigs::new(rconfig);
 
     for nalu_idx in 0..number_nalus {
         ds.nalu_elements.push(NALU::new());
         ds.nalu_headers.push(NALUheader::new());
         let param_sets_exist = pps_idx > 0 && sps_idx > 0;
+        let nalu_range = match template {
+            Some(x) => Cow::Owned(x.narrow(
+                &rconfig.random_nalu_range,
+                x.pinned("nalu_headers", nalu_idx),
+            )),
+            None => Cow::Borrowed(&rconfig.random_nalu_range),
+        };
         random_nalu_header(
             nalu_idx,
             param_sets_exist,
             enable_extensions,
             undefined_nalus,
-            &rconfig.random_nalu_range,
+            &nalu_range,
             &mut ds,
             film,
         );
@@ -79,14 +300,75 @@ pub fn random_video(
             ds.nalu_headers[nalu_idx].nal_unit_type = 5;
         }
 
+        // slice data partition A is followed by partitions B and C of the same slice,
+        // which share its nal_ref_idc
+        if nalu_idx > 0 && ds.nalu_headers[nalu_idx - 1].nal_unit_type == 2 {
+            ds.nalu_headers[nalu_idx].nal_unit_type = 3;
+            ds.nalu_headers[nalu_idx].nal_ref_idc = ds.nalu_headers[nalu_idx - 1].nal_ref_idc;
+        } else if nalu_idx > 1
+            && ds.nalu_headers[nalu_idx - 1].nal_unit_type == 3
+            && ds.nalu_headers[nalu_idx - 2].nal_unit_type == 2
+        {
+            ds.nalu_headers[nalu_idx].nal_unit_type = 4;
+            ds.nalu_headers[nalu_idx].nal_ref_idc = ds.nalu_headers[nalu_idx - 1].nal_ref_idc;
+        } else if data_partitioning
+            && ds.nalu_headers[nalu_idx].nal_unit_type == 1
+            && !ds.ppses[pps_idx - 1].entropy_coding_mode_flag // partitioning is only allowed with CAVLC
+            && !ds.ppses[pps_idx - 1].is_subset_pps
+            && rconfig
+                .random_slice_data_partition_range
+                .bias_partitioned_slice
+                .sample(film)
+        {
+            ds.nalu_headers[nalu_idx].nal_unit_type = 2;
+        }
+
         // if we have a coded slice extension without a subsetSPS then we'll create a new subsetSPS
         // TODO: create an unreferenced coded slice extension
-        if ds.nalu_headers[nalu_idx].nal_unit_type == 20 && subset_sps_idx == 0 {
+        if (ds.nalu_headers[nalu_idx].nal_unit_type == 20
+            || ds.nalu_headers[nalu_idx].nal_unit_type == 21)
+            && subset_sps_idx == 0
+        {
             ds.nalu_headers[nalu_idx].nal_unit_type = 15;
         }
 
+        // auxiliary coded pictures use the SPS extension of the most recent SPS; if there is
+        // none that signals auxiliary pictures then we'll create a new SPS extension
+        if ds.nalu_headers[nalu_idx].nal_unit_type == 19 {
+            let aux_pictures_signalled = pps_idx > 0
+                && !ds.ppses[pps_idx - 1].is_subset_pps
+                && ds
+                    .sps_extensions
+                    .iter()
+                    .rev()
+                    .find(|x| x.seq_parameter_set_id == ds.spses[sps_idx - 1].seq_parameter_set_id)
+                    .is_some_and(|x| x.aux_format_idc != 0);
+            if !aux_pictures_signalled {
+                ds.nalu_headers[nalu_idx].nal_unit_type = 13;
+            }
+        }
+
+        if rconfig
+            .random_nalu_range
+            .bias_conforming_nalu_order
+            .sample(film)
+        {
+            conforming_nalu_order(nalu_idx, number_nalus, &mut ds);
+        }
+
+        if let Some(template) = template {
+            // pinned header fields take precedence over the adjustments above
+            template.pin("nalu_headers", nalu_idx, &mut ds.nalu_headers[nalu_idx]);
+            if !references_exist(nalu_idx, sps_idx, subset_sps_idx, pps_idx, slice_idx, &ds) {
+                return Err(format!(
+                    "template NALU {} has nal_unit_type {}, but the parameter sets or slice it refers to come later in the template",
+                    nalu_idx, ds.nalu_headers[nalu_idx].nal_unit_type
+                ));
+            }
+        }
+
         match ds.nalu_headers[nalu_idx].nal_unit_type {
-            1 | 5 => {
+            1 | 2 | 5 => {
                 // slices
                 if !silent_mode {
                     println!(
@@ -94,6 +376,7 @@ pub fn random_video(
                         nalu_idx,
                         match ds.nalu_headers[nalu_idx].nal_unit_type {
                             1 => "a non-IDR picture",
+                            2 => "a non-IDR picture, data partition A",
                             _ => "an IDR picture",
                         }
                     );
@@ -115,29 +398,92 @@ pub fn random_video(
                     cur_sps = ds.spses[sps_idx - 1].clone();
                 }
 
-                let randomize_header = true;
-                random_slice(
+                let mut slice_rconfig = slice_configs.slice_config(
                     nalu_idx,
                     slice_idx,
-                    cur_pps,
-                    &cur_sps,
-                    ignore_intra_pred,
-                    ignore_edge_intra_pred,
-                    ignore_ipcm,
-                    empty_slice_data,
-                    randomize_header,
-                    silent_mode,
-                    rconfig,
-                    &mut ds,
+                    &ds.nalu_headers[nalu_idx],
                     film,
                 );
+                narrow_slice_config(template, slice_idx, &mut slice_rconfig);
+                let mut randomize_header = true;
+                loop {
+                    random_slice(
+                        nalu_idx,
+                        slice_idx,
+                        cur_pps,
+                        &cur_sps,
+                        ignore_intra_pred,
+                        ignore_edge_intra_pred,
+                        ignore_ipcm,
+                        empty_slice_data,
+                        randomize_header,
+                        silent_mode,
+                        &slice_rconfig,
+                        &mut ds,
+                        film,
+                    );
+                    // regenerate the slice data once to match the pinned header
+                    if !randomize_header
+                        || !template.is_some_and(|x| {
+                            pin_slice_header(x, slice_idx, cur_pps, &cur_sps, &mut ds)
+                        })
+                    {
+                        break;
+                    }
+                    randomize_header = false;
+                }
                 if ds.nalu_headers[nalu_idx].nal_unit_type == 1 {
                     generated_nalu_type_str += "Non-IDR Slice(1);";
+                } else if ds.nalu_headers[nalu_idx].nal_unit_type == 2 {
+                    slice_partition_a_idx = ds.slice_partitions.len();
+                    random_slice_data_partition(
+                        nalu_idx,
+                        slice_idx,
+                        None,
+                        cur_pps,
+                        &cur_sps,
+                        &rconfig.random_slice_data_partition_range,
+                        &mut ds,
+                        film,
+                    );
+                    generated_nalu_type_str += "Slice Data Partition A(2);";
                 } else {
                     generated_nalu_type_str += "IDR Slice(5);";
                 }
                 slice_idx += 1;
             }
+            3 | 4 => {
+                if !silent_mode {
+                    println!(
+                        "\t random_video - NALU {} - Generating Coded slice data partition {}",
+                        nalu_idx,
+                        match ds.nalu_headers[nalu_idx].nal_unit_type {
+                            3 => "B",
+                            _ => "C",
+                        }
+                    );
+                }
+                // no parameter sets can be generated between partitions of a slice
+                let cur_pps = &ds.ppses[pps_idx - 1].clone();
+                let cur_sps = ds.spses[sps_idx - 1].clone();
+                let sdp_a = ds.slice_partitions[slice_partition_a_idx].clone();
+
+                random_slice_data_partition(
+                    nalu_idx,
+                    slice_idx - 1,
+                    Some(&sdp_a),
+                    cur_pps,
+                    &cur_sps,
+                    &rconfig.random_slice_data_partition_range,
+                    &mut ds,
+                    film,
+                );
+                if ds.nalu_headers[nalu_idx].nal_unit_type == 3 {
+                    generated_nalu_type_str += "Slice Data Partition B(3);";
+                } else {
+                    generated_nalu_type_str += "Slice Data Partition C(4);";
+                }
+            }
             6 => {
                 if !silent_mode {
                     println!(
@@ -159,10 +505,16 @@ pub fn random_video(
                     );
                 }
                 ds.spses.push(SeqParameterSet::new());
+                let sps_range = match template {
+                    Some(x) => {
+                        Cow::Owned(x.narrow(&rconfig.random_sps_range, x.pinned("spses", sps_idx)))
+                    }
+                    None => Cow::Borrowed(&rconfig.random_sps_range),
+                };
                 random_sps(
                     &mut ds.spses[sps_idx],
                     enable_extensions,
-                    &rconfig.random_sps_range,
+                    &sps_range,
                     small_video,
                     silent_mode,
                     film,
@@ -190,7 +542,11 @@ pub fn random_video(
                     cur_sps = ds.spses[sps_idx - 1].clone();
                 }
 
-                random_pps(pps_idx, &cur_sps, rconfig.random_pps_range, &mut ds, film);
+                let pps_range = match template {
+                    Some(x) => x.narrow(&rconfig.random_pps_range, x.pinned("ppses", pps_idx)),
+                    None => rconfig.random_pps_range,
+                };
+                random_pps(pps_idx, &cur_sps, pps_range, &mut ds, film);
                 generated_nalu_type_str += "PPS(8);";
                 pps_idx += 1;
             }
@@ -217,14 +573,41 @@ pub fn random_video(
                 if !silent_mode {
                     println!("\t random_video - NALU {} - End of Sequence", nalu_idx);
                 }
+                if rconfig
+                    .random_end_of_seq_stream_range
+                    .end_of_seq_trailing_payload
+                    .sample(film)
+                {
+                    // nothing should follow the header, so this is junk
+                    ds.nalu_elements[nalu_idx].content.push(0); // the first byte is the header, which is ignored
+                    ds.nalu_elements[nalu_idx]
+                        .content
+                        .extend(random_end_of_seq_stream_payload(
+                            rconfig.random_end_of_seq_stream_range,
+                            film,
+                        ));
+                }
                 generated_nalu_type_str += "EndOfSequence(10);";
-                // TODO: nothing to do here, but could throw junk in the future
             }
             11 => {
                 // End of Stream - signals
                 if !silent_mode {
                     println!("\t random_video - NALU {} - End of Stream", nalu_idx);
                 }
+                if rconfig
+                    .random_end_of_seq_stream_range
+                    .end_of_stream_trailing_payload
+                    .sample(film)
+                {
+                    // nothing should follow the header, so this is junk
+                    ds.nalu_elements[nalu_idx].content.push(0); // the first byte is the header, which is ignored
+                    ds.nalu_elements[nalu_idx]
+                        .content
+                        .extend(random_end_of_seq_stream_payload(
+                            rconfig.random_end_of_seq_stream_range,
+                            film,
+                        ));
+                }
                 generated_nalu_type_str += "EndOfStream(11);";
             }
             12 => {
@@ -240,13 +623,33 @@ pub fn random_video(
                         nalu_idx, filler_data_length
                     );
                 }
-                ds.nalu_elements[nalu_idx].content.push(0); // the first byte is the header, which is ignored
-                ds.nalu_elements[nalu_idx]
-                    .content
-                    .extend(film.read_film_bytes(filler_data_length)); // the rest is random bytes of random length
+                ds.filler_data.push(FillerData::new());
+                random_filler_data(
+                    filler_data_idx,
+                    filler_data_length,
+                    rconfig.random_filler_data_range,
+                    &mut ds,
+                    film,
+                );
+                filler_data_idx += 1;
 
                 generated_nalu_type_str += "FillerData(12);";
             }
+            13 => {
+                // SPS extension
+                if !silent_mode {
+                    println!(
+                        "\t random_video - NALU {} - Generating Sequence Parameter Set Extension",
+                        nalu_idx
+                    );
+                }
+                ds.sps_extensions.push(random_sps_extension(
+                    ds.spses[sps_idx - 1].seq_parameter_set_id,
+                    &rconfig.random_sps_extension_range,
+                    film,
+                ));
+                generated_nalu_type_str += "SPSExtension(13);";
+            }
             14 => {
                 // Prefix NALU
                 if !silent_mode {
@@ -287,15 +690,92 @@ pub fn random_video(
                 generated_nalu_type_str += "SubsetSPS(15);";
                 subset_sps_idx += 1;
             }
-            20 => {
-                // Coded slice extension
+            16 => {
+                // Depth parameter set
+                if !silent_mode {
+                    println!(
+                        "\t random_video - NALU {} - Generating Depth Parameter Set",
+                        nalu_idx
+                    );
+                }
+                ds.dpses.push(DepthParameterSet::new());
+                random_depth_parameter_set(
+                    dps_idx,
+                    &rconfig.random_depth_parameter_set_range,
+                    &mut ds,
+                    film,
+                );
+                generated_nalu_type_str += "DPS(16);";
+                dps_idx += 1;
+            }
+            19 => {
+                // auxiliary coded picture slices share the parameter sets of the primary coded picture
                 if !silent_mode {
                     println!(
-                        "\t random_video - NALU {} - Generating Coded Slice Extension",
+                        "\t random_video - NALU {} - Generating Coded slice of an auxiliary coded picture",
                         nalu_idx
                     );
                 }
                 ds.slices.push(Slice::new());
+                // for amount of macroblocks, assume frames only
+                let macroblock_amount = ((ds.spses[sps_idx - 1].pic_width_in_mbs_minus1 + 1)
+                    * (ds.spses[sps_idx - 1].pic_height_in_map_units_minus1 + 1))
+                    as usize;
+                ds.slices[slice_idx].sd.macroblock_vec = vec![MacroBlock::new(); macroblock_amount];
+
+                let cur_pps = &ds.ppses[pps_idx - 1].clone();
+                let cur_sps = ds.spses[sps_idx - 1].clone();
+
+                let mut slice_rconfig = slice_configs.slice_config(
+                    nalu_idx,
+                    slice_idx,
+                    &ds.nalu_headers[nalu_idx],
+                    film,
+                );
+                narrow_slice_config(template, slice_idx, &mut slice_rconfig);
+                let mut randomize_header = true;
+                loop {
+                    random_slice(
+                        nalu_idx,
+                        slice_idx,
+                        cur_pps,
+                        &cur_sps,
+                        ignore_intra_pred,
+                        ignore_edge_intra_pred,
+                        ignore_ipcm,
+                        empty_slice_data,
+                        randomize_header,
+                        silent_mode,
+                        &slice_rconfig,
+                        &mut ds,
+                        film,
+                    );
+                    // regenerate the slice data once to match the pinned header
+                    if !randomize_header
+                        || !template.is_some_and(|x| {
+                            pin_slice_header(x, slice_idx, cur_pps, &cur_sps, &mut ds)
+                        })
+                    {
+                        break;
+                    }
+                    randomize_header = false;
+                }
+                generated_nalu_type_str += "AuxSlice(19);";
+                slice_idx += 1;
+            }
+            20 | 21 => {
+                // Coded slice extension
+                if !silent_mode {
+                    if ds.nalu_headers[nalu_idx].nal_unit_type == 20 {
+                        println!(
+                            "\t random_video - NALU {} - Generating Coded Slice Extension",
+                            nalu_idx
+                        );
+                    } else {
+                        println!("\t random_video - NALU {} - Generating Coded Slice Extension for a depth view or 3D-AVC texture view", nalu_idx);
+                    }
+                }
+                ds.slices.push(Slice::new());
 
                 // Get the most recent PPS associated with a subset SPS, which
                 // should also be the most recent subset SPS
@@ -318,23 +798,47 @@ pub fn random_video(
                         + 1)) as usize;
                 ds.slices[slice_idx].sd.macroblock_vec = vec![MacroBlock::new(); macroblock_amount];
                 // use the most recent PPS and SPS
-                let randomize_header = true;
-                random_slice_layer_extension(
+                let mut slice_rconfig = slice_configs.slice_config(
                     nalu_idx,
                     slice_idx,
-                    subset_pps_idx,
-                    subset_sps_idx - 1,
-                    ignore_intra_pred,
-                    ignore_edge_intra_pred,
-                    ignore_ipcm,
-                    empty_slice_data,
-                    randomize_header,
-                    silent_mode,
-                    &rconfig,
-                    &mut ds,
+                    &ds.nalu_headers[nalu_idx],
                     film,
                 );
-                generated_nalu_type_str += "CodedSliceExt(20);";
+                narrow_slice_config(template, slice_idx, &mut slice_rconfig);
+                let cur_pps = ds.ppses[subset_pps_idx].clone();
+                let cur_sps = ds.subset_spses[subset_sps_idx - 1].sps.clone();
+                let mut randomize_header = true;
+                loop {
+                    random_slice_layer_extension(
+                        nalu_idx,
+                        slice_idx,
+                        subset_pps_idx,
+                        subset_sps_idx - 1,
+                        ignore_intra_pred,
+                        ignore_edge_intra_pred,
+                        ignore_ipcm,
+                        empty_slice_data,
+                        randomize_header,
+                        silent_mode,
+                        &slice_rconfig,
+                        &mut ds,
+                        film,
+                    );
+                    // regenerate the slice data once to match the pinned header
+                    if !randomize_header
+                        || !template.is_some_and(|x| {
+                            pin_slice_header(x, slice_idx, &cur_pps, &cur_sps, &mut ds)
+                        })
+                    {
+                        break;
+                    }
+                    randomize_header = false;
+                }
+                if ds.nalu_headers[nalu_idx].nal_unit_type == 20 {
+                    generated_nalu_type_str += "CodedSliceExt(20);";
+                } else {
+                    generated_nalu_type_str += "CodedSlice3DExt(21);";
+                }
                 slice_idx += 1;
             }
             0 | 17 | 18 | 22..=31 => {
@@ -355,10 +859,27 @@ pub fn random_video(
                 ds.nalu_headers[nalu_idx].nal_unit_type
             ),
         }
+
+        // slice headers are pinned as their slices are generated
+        if let Some(template) = template {
+            match ds.nalu_headers[nalu_idx].nal_unit_type {
+                2..=4 => template.pin_last("slice_partitions", &mut ds.slice_partitions),
+                6 => template.pin_last("seis", &mut ds.seis),
+                7 => template.pin_last("spses", &mut ds.spses),
+                8 => template.pin_last("ppses", &mut ds.ppses),
+                9 => template.pin_last("auds", &mut ds.auds),
+                12 => template.pin_last("filler_data", &mut ds.filler_data),
+                13 => template.pin_last("sps_extensions", &mut ds.sps_extensions),
+                14 => template.pin_last("prefix_nalus", &mut ds.prefix_nalus),
+                15 => template.pin_last("subset_spses", &mut ds.subset_spses),
+                16 => template.pin_last("dpses", &mut ds.dpses),
+                _ => (),
+            }
+        }
     }
     if !silent_mode {
         println!("\t Generated Sequence: {}", generated_nalu_type_str);
     }
 
-    ds
+    Ok(ds)
 }